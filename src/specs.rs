@@ -8,7 +8,117 @@
 //! constants, structures, and layout defined in the individual specifications.
 //! Not runtime implementation or operating-system adaptation is provided.
 
+pub mod bits;
+pub mod checksum;
 pub mod int;
+pub mod uuid;
+pub mod utf16;
+pub mod varint;
 
+pub mod acpi;
+pub mod arp;
+pub mod asn1;
+pub mod avi;
+pub mod bittorrent;
+pub mod bmp;
+pub mod bson;
+pub mod bzip2;
+pub mod cab;
+pub mod cbfs;
+pub mod cbor;
+pub mod can;
+pub mod cfbf;
+pub mod coap;
+pub mod codepage;
+pub mod dbus;
+pub mod dds;
+pub mod dhcp;
+pub mod dhcpv6;
+pub mod dns;
+pub mod ebml;
+pub mod edid;
+pub mod ethernet;
+pub mod exif;
+pub mod flac;
+pub mod geneve;
+pub mod gif;
+pub mod gitpack;
+pub mod gre;
+pub mod gvariant;
+pub mod hci;
+pub mod hid;
+pub mod http2;
+pub mod icc;
+pub mod icmp;
+pub mod icmpv6;
+pub mod ico;
+pub mod id3v2;
+pub mod ieee80211;
+pub mod ifd;
+pub mod igmp;
+pub mod ipv4;
+pub mod ipv6;
+pub mod isobmff;
+pub mod jpeg;
+pub mod ktx;
+pub mod lz4;
+pub mod lzma;
+pub mod microcode;
+pub mod midi;
+pub mod mld;
+pub mod modbus;
+pub mod mp3;
+pub mod mpegts;
+pub mod mpls;
+pub mod mqtt;
 pub mod msdosmz;
+pub mod msgpack;
+pub mod netlink;
+pub mod ntp;
+pub mod ogg;
+pub mod pcap;
+pub mod pcapng;
+pub mod pci;
+pub mod pcirom;
 pub mod pecoff;
+pub mod png;
+pub mod ppp;
+pub mod pppoe;
+pub mod protobuf;
+pub mod punycode;
+pub mod qoi;
+pub mod quic;
+pub mod radiotap;
+pub mod radius;
+pub mod rfc4648;
+pub mod riff;
+pub mod rtcp;
+pub mod rtp;
+pub mod sctp;
+pub mod sevenzip;
+pub mod sfnt;
+pub mod smbios;
+pub mod sqlite;
+pub mod ssh;
+pub mod stp;
+pub mod tcp;
+pub mod terminfo;
+pub mod tftp;
+pub mod thrift;
+pub mod tiff;
+pub mod tls;
+pub mod tpm2;
+pub mod udp;
+pub mod uefifv;
+pub mod uefipath;
+pub mod uefivar;
+pub mod usb;
+pub mod usbip;
+pub mod vlan;
+pub mod vxlan;
+pub mod wav;
+pub mod webp;
+pub mod websocket;
+pub mod wim;
+pub mod wireguard;
+pub mod x509;
@@ -0,0 +1,2964 @@
+//! Advanced Configuration and Power Interface Tables
+//!
+//! ACPI describes the platform to the operating system via a set of system
+//! description tables in memory. Firmware provides the physical address of
+//! the Root System Description Pointer (`Rsdp`), which in turn points to the
+//! RSDT (32-bit entries) and, since ACPI 2.0, the XSDT (64-bit entries). Both
+//! are arrays of physical addresses of further tables.
+//!
+//! Every table, but the FACS, starts with the common `SdtHeader`. Its
+//! signature identifies the table, and its length covers the entire table
+//! including the header. All bytes of a table sum to zero modulo 256 (see
+//! `checksum_valid()`).
+//!
+//! Several tables consist of a fixed part followed by a sequence of variable
+//! sized structures. Each starts with its type and length, which are 8-bit
+//! wide in the MADT and SRAT, and 16-bit wide in the DMAR. These can be
+//! walked with `Structures`.
+//!
+//! All multi-byte integers are encoded as little-endian. Since the
+//! structures are not naturally aligned in memory, they are packed.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::checksum;
+use crate::specs::int;
+
+/// RSDP Signature
+///
+/// The initial 8 bytes of the RSDP ("RSD PTR ").
+pub const SIGNATURE_RSDP: [u8; 8] = *b"RSD PTR ";
+
+pub const SIGNATURE_RSDT: [u8; 4] = *b"RSDT";
+pub const SIGNATURE_XSDT: [u8; 4] = *b"XSDT";
+pub const SIGNATURE_FADT: [u8; 4] = *b"FACP";
+pub const SIGNATURE_FACS: [u8; 4] = *b"FACS";
+pub const SIGNATURE_DSDT: [u8; 4] = *b"DSDT";
+pub const SIGNATURE_SSDT: [u8; 4] = *b"SSDT";
+pub const SIGNATURE_MADT: [u8; 4] = *b"APIC";
+pub const SIGNATURE_MCFG: [u8; 4] = *b"MCFG";
+pub const SIGNATURE_HPET: [u8; 4] = *b"HPET";
+pub const SIGNATURE_SRAT: [u8; 4] = *b"SRAT";
+pub const SIGNATURE_DMAR: [u8; 4] = *b"DMAR";
+
+pub const RSDP_SIZE_V1: usize = 20;
+pub const RSDP_SIZE_V2: usize = 36;
+pub const RSDP_REVISION_V1: u8 = 0;
+pub const RSDP_REVISION_V2: u8 = 2;
+
+pub const ADDRESS_SPACE_SYSTEM_MEMORY: u8 = 0x00;
+pub const ADDRESS_SPACE_SYSTEM_IO: u8 = 0x01;
+pub const ADDRESS_SPACE_PCI_CONFIG: u8 = 0x02;
+pub const ADDRESS_SPACE_EMBEDDED_CONTROLLER: u8 = 0x03;
+pub const ADDRESS_SPACE_SMBUS: u8 = 0x04;
+pub const ADDRESS_SPACE_SYSTEM_CMOS: u8 = 0x05;
+pub const ADDRESS_SPACE_PCI_BAR_TARGET: u8 = 0x06;
+pub const ADDRESS_SPACE_IPMI: u8 = 0x07;
+pub const ADDRESS_SPACE_GENERAL_PURPOSE_IO: u8 = 0x08;
+pub const ADDRESS_SPACE_GENERIC_SERIAL_BUS: u8 = 0x09;
+pub const ADDRESS_SPACE_PLATFORM_COMMUNICATIONS_CHANNEL: u8 = 0x0a;
+pub const ADDRESS_SPACE_PLATFORM_RUNTIME_MECHANISM: u8 = 0x0b;
+pub const ADDRESS_SPACE_FUNCTIONAL_FIXED_HARDWARE: u8 = 0x7f;
+
+pub const ACCESS_SIZE_UNDEFINED: u8 = 0;
+pub const ACCESS_SIZE_BYTE: u8 = 1;
+pub const ACCESS_SIZE_WORD: u8 = 2;
+pub const ACCESS_SIZE_DWORD: u8 = 3;
+pub const ACCESS_SIZE_QWORD: u8 = 4;
+
+pub const PM_PROFILE_UNSPECIFIED: u8 = 0;
+pub const PM_PROFILE_DESKTOP: u8 = 1;
+pub const PM_PROFILE_MOBILE: u8 = 2;
+pub const PM_PROFILE_WORKSTATION: u8 = 3;
+pub const PM_PROFILE_ENTERPRISE_SERVER: u8 = 4;
+pub const PM_PROFILE_SOHO_SERVER: u8 = 5;
+pub const PM_PROFILE_APPLIANCE_PC: u8 = 6;
+pub const PM_PROFILE_PERFORMANCE_SERVER: u8 = 7;
+pub const PM_PROFILE_TABLET: u8 = 8;
+
+pub const IAPC_BOOT_ARCH_LEGACY_DEVICES: u16 = 0x0001;
+pub const IAPC_BOOT_ARCH_8042: u16 = 0x0002;
+pub const IAPC_BOOT_ARCH_VGA_NOT_PRESENT: u16 = 0x0004;
+pub const IAPC_BOOT_ARCH_MSI_NOT_SUPPORTED: u16 = 0x0008;
+pub const IAPC_BOOT_ARCH_PCIE_ASPM_CONTROLS: u16 = 0x0010;
+pub const IAPC_BOOT_ARCH_CMOS_RTC_NOT_PRESENT: u16 = 0x0020;
+
+pub const ARM_BOOT_ARCH_PSCI_COMPLIANT: u16 = 0x0001;
+pub const ARM_BOOT_ARCH_PSCI_USE_HVC: u16 = 0x0002;
+
+pub const FADT_WBINVD: u32 = 0x0000_0001;
+pub const FADT_WBINVD_FLUSH: u32 = 0x0000_0002;
+pub const FADT_PROC_C1: u32 = 0x0000_0004;
+pub const FADT_P_LVL2_UP: u32 = 0x0000_0008;
+pub const FADT_PWR_BUTTON: u32 = 0x0000_0010;
+pub const FADT_SLP_BUTTON: u32 = 0x0000_0020;
+pub const FADT_FIX_RTC: u32 = 0x0000_0040;
+pub const FADT_RTC_S4: u32 = 0x0000_0080;
+pub const FADT_TMR_VAL_EXT: u32 = 0x0000_0100;
+pub const FADT_DCK_CAP: u32 = 0x0000_0200;
+pub const FADT_RESET_REG_SUP: u32 = 0x0000_0400;
+pub const FADT_SEALED_CASE: u32 = 0x0000_0800;
+pub const FADT_HEADLESS: u32 = 0x0000_1000;
+pub const FADT_CPU_SW_SLP: u32 = 0x0000_2000;
+pub const FADT_PCI_EXP_WAK: u32 = 0x0000_4000;
+pub const FADT_USE_PLATFORM_CLOCK: u32 = 0x0000_8000;
+pub const FADT_S4_RTC_STS_VALID: u32 = 0x0001_0000;
+pub const FADT_REMOTE_POWER_ON_CAPABLE: u32 = 0x0002_0000;
+pub const FADT_FORCE_APIC_CLUSTER_MODEL: u32 = 0x0004_0000;
+pub const FADT_FORCE_APIC_PHYSICAL_DESTINATION_MODE: u32 = 0x0008_0000;
+pub const FADT_HW_REDUCED_ACPI: u32 = 0x0010_0000;
+pub const FADT_LOW_POWER_S0_IDLE_CAPABLE: u32 = 0x0020_0000;
+
+pub const MADT_PCAT_COMPAT: u32 = 0x0000_0001;
+
+pub const MADT_LOCAL_APIC: u8 = 0x00;
+pub const MADT_IO_APIC: u8 = 0x01;
+pub const MADT_INTERRUPT_SOURCE_OVERRIDE: u8 = 0x02;
+pub const MADT_NMI_SOURCE: u8 = 0x03;
+pub const MADT_LOCAL_APIC_NMI: u8 = 0x04;
+pub const MADT_LOCAL_APIC_ADDRESS_OVERRIDE: u8 = 0x05;
+pub const MADT_IO_SAPIC: u8 = 0x06;
+pub const MADT_LOCAL_SAPIC: u8 = 0x07;
+pub const MADT_PLATFORM_INTERRUPT_SOURCE: u8 = 0x08;
+pub const MADT_LOCAL_X2APIC: u8 = 0x09;
+pub const MADT_LOCAL_X2APIC_NMI: u8 = 0x0a;
+pub const MADT_GICC: u8 = 0x0b;
+pub const MADT_GICD: u8 = 0x0c;
+pub const MADT_GIC_MSI_FRAME: u8 = 0x0d;
+pub const MADT_GICR: u8 = 0x0e;
+pub const MADT_GIC_ITS: u8 = 0x0f;
+pub const MADT_MULTIPROCESSOR_WAKEUP: u8 = 0x10;
+pub const MADT_CORE_PIC: u8 = 0x11;
+pub const MADT_LIO_PIC: u8 = 0x12;
+pub const MADT_HT_PIC: u8 = 0x13;
+pub const MADT_EIO_PIC: u8 = 0x14;
+pub const MADT_MSI_PIC: u8 = 0x15;
+pub const MADT_BIO_PIC: u8 = 0x16;
+pub const MADT_LPC_PIC: u8 = 0x17;
+pub const MADT_RINTC: u8 = 0x18;
+pub const MADT_IMSIC: u8 = 0x19;
+pub const MADT_APLIC: u8 = 0x1a;
+pub const MADT_PLIC: u8 = 0x1b;
+/* reserved: 0x1c..0x7f */
+/* OEM: 0x80..0xff */
+
+pub const LOCAL_APIC_ENABLED: u32 = 0x0000_0001;
+pub const LOCAL_APIC_ONLINE_CAPABLE: u32 = 0x0000_0002;
+
+pub const MPS_INTI_POLARITY_MASK: u16 = 0x0003;
+pub const MPS_INTI_POLARITY_CONFORMS: u16 = 0x0000;
+pub const MPS_INTI_POLARITY_ACTIVE_HIGH: u16 = 0x0001;
+/* reserved: 0x0002 */
+pub const MPS_INTI_POLARITY_ACTIVE_LOW: u16 = 0x0003;
+pub const MPS_INTI_TRIGGER_MASK: u16 = 0x000c;
+pub const MPS_INTI_TRIGGER_CONFORMS: u16 = 0x0000;
+pub const MPS_INTI_TRIGGER_EDGE: u16 = 0x0004;
+/* reserved: 0x0008 */
+pub const MPS_INTI_TRIGGER_LEVEL: u16 = 0x000c;
+
+pub const GICC_ENABLED: u32 = 0x0000_0001;
+pub const GICC_PERFORMANCE_INTERRUPT_MODE: u32 = 0x0000_0002;
+pub const GICC_VGIC_MAINTENANCE_INTERRUPT_MODE: u32 = 0x0000_0004;
+pub const GICC_ONLINE_CAPABLE: u32 = 0x0000_0008;
+
+pub const GIC_VERSION_UNSPECIFIED: u8 = 0;
+pub const GIC_VERSION_V1: u8 = 1;
+pub const GIC_VERSION_V2: u8 = 2;
+pub const GIC_VERSION_V3: u8 = 3;
+pub const GIC_VERSION_V4: u8 = 4;
+
+pub const SRAT_LOCAL_APIC_AFFINITY: u8 = 0;
+pub const SRAT_MEMORY_AFFINITY: u8 = 1;
+pub const SRAT_X2APIC_AFFINITY: u8 = 2;
+pub const SRAT_GICC_AFFINITY: u8 = 3;
+pub const SRAT_GIC_ITS_AFFINITY: u8 = 4;
+pub const SRAT_GENERIC_INITIATOR_AFFINITY: u8 = 5;
+pub const SRAT_GENERIC_PORT_AFFINITY: u8 = 6;
+
+pub const SRAT_ENABLED: u32 = 0x0000_0001;
+pub const SRAT_HOT_PLUGGABLE: u32 = 0x0000_0002;
+pub const SRAT_NON_VOLATILE: u32 = 0x0000_0004;
+
+pub const MCFG_ALLOCATION_OFFSET: usize = 44;
+
+pub const DMAR_FLAG_INTR_REMAP: u8 = 0x01;
+pub const DMAR_FLAG_X2APIC_OPT_OUT: u8 = 0x02;
+pub const DMAR_FLAG_DMA_CTRL_PLATFORM_OPT_IN: u8 = 0x04;
+
+pub const DMAR_DRHD: u16 = 0;
+pub const DMAR_RMRR: u16 = 1;
+pub const DMAR_ATSR: u16 = 2;
+pub const DMAR_RHSA: u16 = 3;
+pub const DMAR_ANDD: u16 = 4;
+pub const DMAR_SATC: u16 = 5;
+pub const DMAR_SIDP: u16 = 6;
+
+pub const DRHD_INCLUDE_PCI_ALL: u8 = 0x01;
+pub const ATSR_ALL_PORTS: u8 = 0x01;
+
+pub const SCOPE_PCI_ENDPOINT: u8 = 0x01;
+pub const SCOPE_PCI_SUB_HIERARCHY: u8 = 0x02;
+pub const SCOPE_IOAPIC: u8 = 0x03;
+pub const SCOPE_HPET: u8 = 0x04;
+pub const SCOPE_ACPI_NAMESPACE_DEVICE: u8 = 0x05;
+
+
+/// Root System Description Pointer
+///
+/// The RSDP of revision 0 (ACPI 1.0), located by firmware-specific means,
+/// e.g., the EFI configuration table or a scan of the BIOS area.
+#[repr(C, packed)]
+pub struct Rsdp {
+    /// The static signature. This must match `SIGNATURE_RSDP`.
+    pub signature: [u8; 8],
+
+    /// Checksum of the first 20 bytes (see `checksum::sum8()`).
+    pub checksum: u8,
+
+    /// OEM identification.
+    pub oem_id: [u8; 6],
+
+    /// Revision of the structure. 0 for ACPI 1.0, 2 for later versions.
+    pub revision: u8,
+
+    /// Physical address of the RSDT.
+    pub rsdt_address: int::u32le,
+}
+
+/// Extended Root System Description Pointer
+///
+/// The RSDP of revision 2 (ACPI 2.0 and later), extending `Rsdp`.
+#[repr(C, packed)]
+pub struct Rsdp2 {
+    /// Fields of revision 0.
+    pub rsdp: Rsdp,
+
+    /// Size of the structure in bytes.
+    pub length: int::u32le,
+
+    /// Physical address of the XSDT.
+    pub xsdt_address: int::u64le,
+
+    /// Checksum of the entire structure.
+    pub extended_checksum: u8,
+
+    /// Reserved.
+    pub reserved: [u8; 3],
+}
+
+/// System Description Table Header
+///
+/// The common header of all system description tables, except the FACS.
+#[repr(C, packed)]
+pub struct SdtHeader {
+    /// Table signature (`SIGNATURE_*`).
+    pub signature: [u8; 4],
+
+    /// Size of the table in bytes, including the header.
+    pub length: int::u32le,
+
+    /// Revision of the table format.
+    pub revision: u8,
+
+    /// Checksum of the entire table (see `checksum_valid()`).
+    pub checksum: u8,
+
+    /// OEM identification.
+    pub oem_id: [u8; 6],
+
+    /// OEM identification of the table.
+    pub oem_table_id: [u8; 8],
+
+    /// OEM revision of the table.
+    pub oem_revision: int::u32le,
+
+    /// Vendor id of the utility that created the table.
+    pub creator_id: [u8; 4],
+
+    /// Revision of the utility that created the table.
+    pub creator_revision: int::u32le,
+}
+
+/// Generic Address Structure
+#[repr(C, packed)]
+pub struct GenericAddress {
+    /// Address space (`ADDRESS_SPACE_*`).
+    pub address_space_id: u8,
+
+    /// Size of the register in bits.
+    pub register_bit_width: u8,
+
+    /// Offset of the register in bits.
+    pub register_bit_offset: u8,
+
+    /// Access size (`ACCESS_SIZE_*`).
+    pub access_size: u8,
+
+    /// Address of the register in the address space.
+    pub address: int::u64le,
+}
+
+/// Fixed ACPI Description Table
+///
+/// The FADT of ACPI 6. Tables of earlier revisions are shorter, and can be
+/// imported with `Fadt::from_table()`.
+#[repr(C, packed)]
+pub struct Fadt {
+    /// Table header.
+    pub header: SdtHeader,
+
+    /// Physical address of the FACS.
+    pub firmware_ctrl: int::u32le,
+
+    /// Physical address of the DSDT.
+    pub dsdt: int::u32le,
+
+    /// Reserved, formerly the interrupt model.
+    pub reserved0: u8,
+
+    /// Preferred power management profile (`PM_PROFILE_*`).
+    pub preferred_pm_profile: u8,
+
+    /// System vector of the SCI interrupt.
+    pub sci_int: int::u16le,
+
+    /// I/O port of the SMI command port.
+    pub smi_cmd: int::u32le,
+
+    /// Value to write to `smi_cmd` to enable ACPI.
+    pub acpi_enable: u8,
+
+    /// Value to write to `smi_cmd` to disable ACPI.
+    pub acpi_disable: u8,
+
+    /// Value to write to `smi_cmd` to enter S4BIOS.
+    pub s4bios_req: u8,
+
+    /// Value to write to `smi_cmd` to assume processor performance state control.
+    pub pstate_cnt: u8,
+
+    /// I/O port of the PM1a event register block.
+    pub pm1a_evt_blk: int::u32le,
+
+    /// I/O port of the PM1b event register block.
+    pub pm1b_evt_blk: int::u32le,
+
+    /// I/O port of the PM1a control register block.
+    pub pm1a_cnt_blk: int::u32le,
+
+    /// I/O port of the PM1b control register block.
+    pub pm1b_cnt_blk: int::u32le,
+
+    /// I/O port of the PM2 control register block.
+    pub pm2_cnt_blk: int::u32le,
+
+    /// I/O port of the power management timer.
+    pub pm_tmr_blk: int::u32le,
+
+    /// I/O port of the GPE0 register block.
+    pub gpe0_blk: int::u32le,
+
+    /// I/O port of the GPE1 register block.
+    pub gpe1_blk: int::u32le,
+
+    /// Size of the PM1 event register blocks.
+    pub pm1_evt_len: u8,
+
+    /// Size of the PM1 control register blocks.
+    pub pm1_cnt_len: u8,
+
+    /// Size of the PM2 control register block.
+    pub pm2_cnt_len: u8,
+
+    /// Size of the power management timer.
+    pub pm_tmr_len: u8,
+
+    /// Size of the GPE0 register block.
+    pub gpe0_blk_len: u8,
+
+    /// Size of the GPE1 register block.
+    pub gpe1_blk_len: u8,
+
+    /// First GPE number of the GPE1 register block.
+    pub gpe1_base: u8,
+
+    /// Value to write to `smi_cmd` to indicate support for C-state changes.
+    pub cst_cnt: u8,
+
+    /// Worst-case latency to enter and exit C2, in microseconds.
+    pub p_lvl2_lat: int::u16le,
+
+    /// Worst-case latency to enter and exit C3, in microseconds.
+    pub p_lvl3_lat: int::u16le,
+
+    /// Obsolete.
+    pub flush_size: int::u16le,
+
+    /// Obsolete.
+    pub flush_stride: int::u16le,
+
+    /// Offset of the duty cycle setting in the processor control register.
+    pub duty_offset: u8,
+
+    /// Width of the duty cycle setting in the processor control register.
+    pub duty_width: u8,
+
+    /// RTC CMOS index of the day-of-month alarm.
+    pub day_alrm: u8,
+
+    /// RTC CMOS index of the month-of-year alarm.
+    pub mon_alrm: u8,
+
+    /// RTC CMOS index of the century.
+    pub century: u8,
+
+    /// IA-PC boot architecture flags (`IAPC_BOOT_ARCH_*`).
+    pub iapc_boot_arch: int::u16le,
+
+    /// Reserved.
+    pub reserved1: u8,
+
+    /// Fixed feature flags (`FADT_*`).
+    pub flags: int::u32le,
+
+    /// Reset register.
+    pub reset_reg: GenericAddress,
+
+    /// Value to write to `reset_reg` to reset the system.
+    pub reset_value: u8,
+
+    /// ARM boot architecture flags (`ARM_BOOT_ARCH_*`).
+    pub arm_boot_arch: int::u16le,
+
+    /// Minor version of the table.
+    pub fadt_minor_version: u8,
+
+    /// Extended physical address of the FACS.
+    pub x_firmware_ctrl: int::u64le,
+
+    /// Extended physical address of the DSDT.
+    pub x_dsdt: int::u64le,
+
+    /// Extended PM1a event register block.
+    pub x_pm1a_evt_blk: GenericAddress,
+
+    /// Extended PM1b event register block.
+    pub x_pm1b_evt_blk: GenericAddress,
+
+    /// Extended PM1a control register block.
+    pub x_pm1a_cnt_blk: GenericAddress,
+
+    /// Extended PM1b control register block.
+    pub x_pm1b_cnt_blk: GenericAddress,
+
+    /// Extended PM2 control register block.
+    pub x_pm2_cnt_blk: GenericAddress,
+
+    /// Extended power management timer.
+    pub x_pm_tmr_blk: GenericAddress,
+
+    /// Extended GPE0 register block.
+    pub x_gpe0_blk: GenericAddress,
+
+    /// Extended GPE1 register block.
+    pub x_gpe1_blk: GenericAddress,
+
+    /// Sleep control register of hardware-reduced systems.
+    pub sleep_control_reg: GenericAddress,
+
+    /// Sleep status register of hardware-reduced systems.
+    pub sleep_status_reg: GenericAddress,
+
+    /// Identity of the hypervisor vendor.
+    pub hypervisor_vendor_identity: int::u64le,
+}
+
+/// Multiple APIC Description Table
+///
+/// The fixed part of the MADT, followed by interrupt controller structures
+/// (see `Structures`).
+#[repr(C, packed)]
+pub struct Madt {
+    /// Table header.
+    pub header: SdtHeader,
+
+    /// Physical address of the local APIC of each processor.
+    pub local_apic_address: int::u32le,
+
+    /// MADT flags (`MADT_PCAT_COMPAT`).
+    pub flags: int::u32le,
+}
+
+/// Processor Local APIC Structure
+#[repr(C, packed)]
+pub struct MadtLocalApic {
+    /// Structure type (`MADT_*`).
+    pub ics_type: u8,
+
+    /// Size of the structure in bytes.
+    pub length: u8,
+
+    /// Processor UID.
+    pub acpi_processor_uid: u8,
+
+    /// Local APIC id.
+    pub apic_id: u8,
+
+    /// Flags (`LOCAL_APIC_*`).
+    pub flags: int::u32le,
+}
+
+/// I/O APIC Structure
+#[repr(C, packed)]
+pub struct MadtIoApic {
+    /// Structure type (`MADT_*`).
+    pub ics_type: u8,
+
+    /// Size of the structure in bytes.
+    pub length: u8,
+
+    /// I/O APIC id.
+    pub io_apic_id: u8,
+
+    /// Reserved.
+    pub reserved: u8,
+
+    /// Physical address of the I/O APIC.
+    pub io_apic_address: int::u32le,
+
+    /// First global system interrupt of the I/O APIC.
+    pub gsi_base: int::u32le,
+}
+
+/// Interrupt Source Override Structure
+#[repr(C, packed)]
+pub struct MadtInterruptSourceOverride {
+    /// Structure type (`MADT_*`).
+    pub ics_type: u8,
+
+    /// Size of the structure in bytes.
+    pub length: u8,
+
+    /// Bus of the source, 0 for ISA.
+    pub bus: u8,
+
+    /// Bus-relative interrupt source.
+    pub source: u8,
+
+    /// Global system interrupt of the source.
+    pub gsi: int::u32le,
+
+    /// MPS INTI flags (`MPS_INTI_*`).
+    pub flags: int::u16le,
+}
+
+/// Non-maskable Interrupt Source Structure
+#[repr(C, packed)]
+pub struct MadtNmiSource {
+    /// Structure type (`MADT_*`).
+    pub ics_type: u8,
+
+    /// Size of the structure in bytes.
+    pub length: u8,
+
+    /// MPS INTI flags (`MPS_INTI_*`).
+    pub flags: int::u16le,
+
+    /// Global system interrupt of the NMI.
+    pub gsi: int::u32le,
+}
+
+/// Local APIC NMI Structure
+#[repr(C, packed)]
+pub struct MadtLocalApicNmi {
+    /// Structure type (`MADT_*`).
+    pub ics_type: u8,
+
+    /// Size of the structure in bytes.
+    pub length: u8,
+
+    /// Processor UID, or `0xff` for all processors.
+    pub acpi_processor_uid: u8,
+
+    /// MPS INTI flags (`MPS_INTI_*`).
+    pub flags: int::u16le,
+
+    /// Local APIC interrupt input the NMI is connected to.
+    pub lint: u8,
+}
+
+/// Local APIC Address Override Structure
+#[repr(C, packed)]
+pub struct MadtLocalApicAddressOverride {
+    /// Structure type (`MADT_*`).
+    pub ics_type: u8,
+
+    /// Size of the structure in bytes.
+    pub length: u8,
+
+    /// Reserved.
+    pub reserved: int::u16le,
+
+    /// Physical address of the local APIC of each processor.
+    pub local_apic_address: int::u64le,
+}
+
+/// I/O SAPIC Structure
+#[repr(C, packed)]
+pub struct MadtIoSapic {
+    /// Structure type (`MADT_*`).
+    pub ics_type: u8,
+
+    /// Size of the structure in bytes.
+    pub length: u8,
+
+    /// I/O SAPIC id.
+    pub io_apic_id: u8,
+
+    /// Reserved.
+    pub reserved: u8,
+
+    /// First global system interrupt of the I/O SAPIC.
+    pub gsi_base: int::u32le,
+
+    /// Physical address of the I/O SAPIC.
+    pub io_sapic_address: int::u64le,
+}
+
+/// Local SAPIC Structure
+///
+/// Followed by the NUL-terminated processor UID string.
+#[repr(C, packed)]
+pub struct MadtLocalSapic {
+    /// Structure type (`MADT_*`).
+    pub ics_type: u8,
+
+    /// Size of the structure in bytes.
+    pub length: u8,
+
+    /// Processor id.
+    pub acpi_processor_id: u8,
+
+    /// Local SAPIC id.
+    pub local_sapic_id: u8,
+
+    /// Local SAPIC eid.
+    pub local_sapic_eid: u8,
+
+    /// Reserved.
+    pub reserved: [u8; 3],
+
+    /// Flags (`LOCAL_APIC_*`).
+    pub flags: int::u32le,
+
+    /// Processor UID value.
+    pub acpi_processor_uid: int::u32le,
+}
+
+/// Platform Interrupt Source Structure
+#[repr(C, packed)]
+pub struct MadtPlatformInterruptSource {
+    /// Structure type (`MADT_*`).
+    pub ics_type: u8,
+
+    /// Size of the structure in bytes.
+    pub length: u8,
+
+    /// MPS INTI flags (`MPS_INTI_*`).
+    pub flags: int::u16le,
+
+    /// Interrupt type: 1 PMI, 2 INIT, 3 corrected platform error.
+    pub interrupt_type: u8,
+
+    /// Processor id of the destination.
+    pub processor_id: u8,
+
+    /// Processor eid of the destination.
+    pub processor_eid: u8,
+
+    /// I/O SAPIC vector.
+    pub io_sapic_vector: u8,
+
+    /// Global system interrupt.
+    pub gsi: int::u32le,
+
+    /// Platform interrupt source flags.
+    pub platform_interrupt_source_flags: int::u32le,
+}
+
+/// Processor Local x2APIC Structure
+#[repr(C, packed)]
+pub struct MadtLocalX2Apic {
+    /// Structure type (`MADT_*`).
+    pub ics_type: u8,
+
+    /// Size of the structure in bytes.
+    pub length: u8,
+
+    /// Reserved.
+    pub reserved: int::u16le,
+
+    /// Local x2APIC id.
+    pub x2apic_id: int::u32le,
+
+    /// Flags (`LOCAL_APIC_*`).
+    pub flags: int::u32le,
+
+    /// Processor UID.
+    pub acpi_processor_uid: int::u32le,
+}
+
+/// Local x2APIC NMI Structure
+#[repr(C, packed)]
+pub struct MadtLocalX2ApicNmi {
+    /// Structure type (`MADT_*`).
+    pub ics_type: u8,
+
+    /// Size of the structure in bytes.
+    pub length: u8,
+
+    /// MPS INTI flags (`MPS_INTI_*`).
+    pub flags: int::u16le,
+
+    /// Processor UID, or `0xffffffff` for all processors.
+    pub acpi_processor_uid: int::u32le,
+
+    /// Local x2APIC interrupt input the NMI is connected to.
+    pub lint: u8,
+
+    /// Reserved.
+    pub reserved: [u8; 3],
+}
+
+/// GIC CPU Interface Structure
+///
+/// The GICC of ACPI 6.3. ACPI 6.5 appends the 16-bit TRBE interrupt,
+/// extending the structure to 82 bytes.
+#[repr(C, packed)]
+pub struct MadtGicc {
+    /// Structure type (`MADT_*`).
+    pub ics_type: u8,
+
+    /// Size of the structure in bytes.
+    pub length: u8,
+
+    /// Reserved.
+    pub reserved: int::u16le,
+
+    /// GIC CPU interface number.
+    pub cpu_interface_number: int::u32le,
+
+    /// Processor UID.
+    pub acpi_processor_uid: int::u32le,
+
+    /// Flags (`GICC_*`).
+    pub flags: int::u32le,
+
+    /// Version of the ARM parking protocol.
+    pub parking_protocol_version: int::u32le,
+
+    /// GSIV of the performance monitoring interrupt.
+    pub performance_interrupt_gsiv: int::u32le,
+
+    /// Physical address of the parking protocol mailbox.
+    pub parked_address: int::u64le,
+
+    /// Physical address of the CPU interface.
+    pub physical_base_address: int::u64le,
+
+    /// Physical address of the virtual CPU interface.
+    pub gicv: int::u64le,
+
+    /// Physical address of the virtual interface control block.
+    pub gich: int::u64le,
+
+    /// GSIV of the virtual GIC maintenance interrupt.
+    pub vgic_maintenance_interrupt: int::u32le,
+
+    /// Physical address of the redistributor.
+    pub gicr_base_address: int::u64le,
+
+    /// MPIDR of the processor.
+    pub mpidr: int::u64le,
+
+    /// Power efficiency class of the processor.
+    pub processor_power_efficiency_class: u8,
+
+    /// Reserved.
+    pub reserved1: u8,
+
+    /// GSIV of the statistical profiling extension overflow interrupt.
+    pub spe_overflow_interrupt: int::u16le,
+}
+
+/// GIC Distributor Structure
+#[repr(C, packed)]
+pub struct MadtGicd {
+    /// Structure type (`MADT_*`).
+    pub ics_type: u8,
+
+    /// Size of the structure in bytes.
+    pub length: u8,
+
+    /// Reserved.
+    pub reserved: int::u16le,
+
+    /// GIC id.
+    pub gic_id: int::u32le,
+
+    /// Physical address of the distributor.
+    pub physical_base_address: int::u64le,
+
+    /// Reserved, must be zero.
+    pub system_vector_base: int::u32le,
+
+    /// GIC version (`GIC_VERSION_*`).
+    pub gic_version: u8,
+
+    /// Reserved.
+    pub reserved1: [u8; 3],
+}
+
+/// GIC MSI Frame Structure
+#[repr(C, packed)]
+pub struct MadtGicMsiFrame {
+    /// Structure type (`MADT_*`).
+    pub ics_type: u8,
+
+    /// Size of the structure in bytes.
+    pub length: u8,
+
+    /// Reserved.
+    pub reserved: int::u16le,
+
+    /// GIC MSI frame id.
+    pub gic_msi_frame_id: int::u32le,
+
+    /// Physical address of the MSI frame.
+    pub physical_base_address: int::u64le,
+
+    /// Flags.
+    pub flags: int::u32le,
+
+    /// Number of SPIs, if selected by flags.
+    pub spi_count: int::u16le,
+
+    /// First SPI, if selected by flags.
+    pub spi_base: int::u16le,
+}
+
+/// GIC Redistributor Structure
+#[repr(C, packed)]
+pub struct MadtGicr {
+    /// Structure type (`MADT_*`).
+    pub ics_type: u8,
+
+    /// Size of the structure in bytes.
+    pub length: u8,
+
+    /// Reserved.
+    pub reserved: int::u16le,
+
+    /// Physical address of the redistributor discovery range.
+    pub discovery_range_base_address: int::u64le,
+
+    /// Size of the redistributor discovery range.
+    pub discovery_range_length: int::u32le,
+}
+
+/// GIC Interrupt Translation Service Structure
+#[repr(C, packed)]
+pub struct MadtGicIts {
+    /// Structure type (`MADT_*`).
+    pub ics_type: u8,
+
+    /// Size of the structure in bytes.
+    pub length: u8,
+
+    /// Reserved.
+    pub reserved: int::u16le,
+
+    /// GIC ITS id.
+    pub gic_its_id: int::u32le,
+
+    /// Physical address of the ITS.
+    pub physical_base_address: int::u64le,
+
+    /// Reserved.
+    pub reserved1: int::u32le,
+}
+
+/// Multiprocessor Wakeup Structure
+#[repr(C, packed)]
+pub struct MadtMultiprocessorWakeup {
+    /// Structure type (`MADT_*`).
+    pub ics_type: u8,
+
+    /// Size of the structure in bytes.
+    pub length: u8,
+
+    /// Version of the mailbox.
+    pub mailbox_version: int::u16le,
+
+    /// Reserved.
+    pub reserved: int::u32le,
+
+    /// Physical address of the mailbox.
+    pub mailbox_address: int::u64le,
+}
+
+/// LoongArch Core Programmable Interrupt Controller Structure
+#[repr(C, packed)]
+pub struct MadtCorePic {
+    /// Structure type (`MADT_*`).
+    pub ics_type: u8,
+
+    /// Size of the structure in bytes.
+    pub length: u8,
+
+    /// Structure version.
+    pub version: u8,
+
+    /// Processor UID.
+    pub acpi_processor_id: int::u32le,
+
+    /// Physical processor id.
+    pub physical_processor_id: int::u32le,
+
+    /// Flags (`LOCAL_APIC_*`).
+    pub flags: int::u32le,
+}
+
+/// LoongArch Legacy I/O Programmable Interrupt Controller Structure
+#[repr(C, packed)]
+pub struct MadtLioPic {
+    /// Structure type (`MADT_*`).
+    pub ics_type: u8,
+
+    /// Size of the structure in bytes.
+    pub length: u8,
+
+    /// Structure version.
+    pub version: u8,
+
+    /// Physical address of the registers.
+    pub base_address: int::u64le,
+
+    /// Size of the registers.
+    pub size: int::u16le,
+
+    /// Parent vectors of the two outputs.
+    pub cascade_vector: [u8; 2],
+
+    /// Mapping of inputs to outputs.
+    pub cascade_vector_mapping: int::u64le,
+}
+
+/// LoongArch HyperTransport Programmable Interrupt Controller Structure
+#[repr(C, packed)]
+pub struct MadtHtPic {
+    /// Structure type (`MADT_*`).
+    pub ics_type: u8,
+
+    /// Size of the structure in bytes.
+    pub length: u8,
+
+    /// Structure version.
+    pub version: u8,
+
+    /// Physical address of the registers.
+    pub base_address: int::u64le,
+
+    /// Size of the registers.
+    pub size: int::u16le,
+
+    /// Parent vectors of the outputs.
+    pub cascade_vector: [u8; 8],
+}
+
+/// LoongArch Extended I/O Programmable Interrupt Controller Structure
+#[repr(C, packed)]
+pub struct MadtEioPic {
+    /// Structure type (`MADT_*`).
+    pub ics_type: u8,
+
+    /// Size of the structure in bytes.
+    pub length: u8,
+
+    /// Structure version.
+    pub version: u8,
+
+    /// Parent vector of the output.
+    pub cascade_vector: u8,
+
+    /// Node id.
+    pub node: u8,
+
+    /// Map of nodes to route interrupts to.
+    pub node_map: int::u64le,
+}
+
+/// LoongArch MSI Programmable Interrupt Controller Structure
+#[repr(C, packed)]
+pub struct MadtMsiPic {
+    /// Structure type (`MADT_*`).
+    pub ics_type: u8,
+
+    /// Size of the structure in bytes.
+    pub length: u8,
+
+    /// Structure version.
+    pub version: u8,
+
+    /// Physical address of the message register.
+    pub message_address: int::u64le,
+
+    /// First vector.
+    pub start: int::u32le,
+
+    /// Number of vectors.
+    pub count: int::u32le,
+}
+
+/// LoongArch Bridge I/O Programmable Interrupt Controller Structure
+#[repr(C, packed)]
+pub struct MadtBioPic {
+    /// Structure type (`MADT_*`).
+    pub ics_type: u8,
+
+    /// Size of the structure in bytes.
+    pub length: u8,
+
+    /// Structure version.
+    pub version: u8,
+
+    /// Physical address of the registers.
+    pub address: int::u64le,
+
+    /// Size of the registers.
+    pub size: int::u16le,
+
+    /// Hardware id.
+    pub id: int::u16le,
+
+    /// First global system interrupt.
+    pub gsi_base: int::u16le,
+}
+
+/// LoongArch LPC Programmable Interrupt Controller Structure
+#[repr(C, packed)]
+pub struct MadtLpcPic {
+    /// Structure type (`MADT_*`).
+    pub ics_type: u8,
+
+    /// Size of the structure in bytes.
+    pub length: u8,
+
+    /// Structure version.
+    pub version: u8,
+
+    /// Physical address of the registers.
+    pub address: int::u64le,
+
+    /// Size of the registers.
+    pub size: int::u16le,
+
+    /// Parent vector of the output.
+    pub cascade_vector: int::u16le,
+}
+
+/// RISC-V Hart Local Interrupt Controller Structure
+#[repr(C, packed)]
+pub struct MadtRintc {
+    /// Structure type (`MADT_*`).
+    pub ics_type: u8,
+
+    /// Size of the structure in bytes.
+    pub length: u8,
+
+    /// Structure version.
+    pub version: u8,
+
+    /// Reserved.
+    pub reserved: u8,
+
+    /// Flags (`LOCAL_APIC_*`).
+    pub flags: int::u32le,
+
+    /// Hart id.
+    pub hart_id: int::u64le,
+
+    /// Processor UID.
+    pub acpi_processor_uid: int::u32le,
+
+    /// Id of the external interrupt controller.
+    pub ext_intc_id: int::u32le,
+
+    /// Physical address of the IMSIC interrupt files.
+    pub imsic_base_address: int::u64le,
+
+    /// Size of the IMSIC interrupt files.
+    pub imsic_size: int::u32le,
+}
+
+/// RISC-V Incoming MSI Controller Structure
+#[repr(C, packed)]
+pub struct MadtImsic {
+    /// Structure type (`MADT_*`).
+    pub ics_type: u8,
+
+    /// Size of the structure in bytes.
+    pub length: u8,
+
+    /// Structure version.
+    pub version: u8,
+
+    /// Reserved.
+    pub reserved: u8,
+
+    /// Flags.
+    pub flags: int::u32le,
+
+    /// Number of interrupt identities of supervisor files.
+    pub num_ids: int::u16le,
+
+    /// Number of interrupt identities of guest files.
+    pub num_guest_ids: int::u16le,
+
+    /// Number of guest index bits in MSI addresses.
+    pub guest_index_bits: u8,
+
+    /// Number of hart index bits in MSI addresses.
+    pub hart_index_bits: u8,
+
+    /// Number of group index bits in MSI addresses.
+    pub group_index_bits: u8,
+
+    /// Position of the group index bits in MSI addresses.
+    pub group_index_shift: u8,
+}
+
+/// RISC-V Advanced Platform-Level Interrupt Controller Structure
+#[repr(C, packed)]
+pub struct MadtAplic {
+    /// Structure type (`MADT_*`).
+    pub ics_type: u8,
+
+    /// Size of the structure in bytes.
+    pub length: u8,
+
+    /// Structure version.
+    pub version: u8,
+
+    /// APLIC id.
+    pub aplic_id: u8,
+
+    /// Flags.
+    pub flags: int::u32le,
+
+    /// Hardware id.
+    pub hardware_id: [u8; 8],
+
+    /// Number of interrupt delivery controls.
+    pub num_idcs: int::u16le,
+
+    /// Number of interrupt sources.
+    pub num_sources: int::u16le,
+
+    /// First global system interrupt.
+    pub gsi_base: int::u32le,
+
+    /// Physical address of the registers.
+    pub base_address: int::u64le,
+
+    /// Size of the registers.
+    pub size: int::u32le,
+}
+
+/// RISC-V Platform-Level Interrupt Controller Structure
+#[repr(C, packed)]
+pub struct MadtPlic {
+    /// Structure type (`MADT_*`).
+    pub ics_type: u8,
+
+    /// Size of the structure in bytes.
+    pub length: u8,
+
+    /// Structure version.
+    pub version: u8,
+
+    /// PLIC id.
+    pub plic_id: u8,
+
+    /// Hardware id.
+    pub hardware_id: [u8; 8],
+
+    /// Number of interrupt sources.
+    pub num_irqs: int::u16le,
+
+    /// Maximum interrupt priority.
+    pub max_priority: int::u16le,
+
+    /// Flags.
+    pub flags: int::u32le,
+
+    /// Size of the registers.
+    pub size: int::u32le,
+
+    /// Physical address of the registers.
+    pub base_address: int::u64le,
+
+    /// First global system interrupt.
+    pub gsi_base: int::u32le,
+}
+
+/// PCI Express Memory-mapped Configuration Table
+///
+/// The fixed part of the MCFG, followed by allocation entries (see
+/// `McfgAllocation`).
+#[repr(C, packed)]
+pub struct Mcfg {
+    /// Table header.
+    pub header: SdtHeader,
+
+    /// Reserved.
+    pub reserved: [u8; 8],
+}
+
+/// Configuration Space Base Address Allocation
+#[repr(C, packed)]
+pub struct McfgAllocation {
+    /// Physical address of the enhanced configuration space.
+    pub base_address: int::u64le,
+
+    /// PCI segment group number.
+    pub pci_segment_group: int::u16le,
+
+    /// First bus number decoded.
+    pub start_bus: u8,
+
+    /// Last bus number decoded.
+    pub end_bus: u8,
+
+    /// Reserved.
+    pub reserved: int::u32le,
+}
+
+/// High Precision Event Timer Table
+#[repr(C, packed)]
+pub struct Hpet {
+    /// Table header.
+    pub header: SdtHeader,
+
+    /// Hardware id of the event timer block.
+    pub event_timer_block_id: int::u32le,
+
+    /// Base address of the event timer block.
+    pub base_address: GenericAddress,
+
+    /// Sequence number of the timer block.
+    pub hpet_number: u8,
+
+    /// Minimum clock ticks in periodic mode, without lost interrupts.
+    pub minimum_tick: int::u16le,
+
+    /// Page protection and OEM attributes.
+    pub page_protection: u8,
+}
+
+/// System Resource Affinity Table
+///
+/// The fixed part of the SRAT, followed by affinity structures (see
+/// `Structures`).
+#[repr(C, packed)]
+pub struct Srat {
+    /// Table header.
+    pub header: SdtHeader,
+
+    /// Reserved, must be 1.
+    pub table_revision: int::u32le,
+
+    /// Reserved.
+    pub reserved: [u8; 8],
+}
+
+/// Processor Local APIC/SAPIC Affinity Structure
+#[repr(C, packed)]
+pub struct SratLocalApicAffinity {
+    /// Structure type (`SRAT_*`).
+    pub srat_type: u8,
+
+    /// Size of the structure in bytes.
+    pub length: u8,
+
+    /// Bits 0 to 7 of the proximity domain.
+    pub proximity_domain_low: u8,
+
+    /// Local APIC id.
+    pub apic_id: u8,
+
+    /// Flags (`SRAT_ENABLED`).
+    pub flags: int::u32le,
+
+    /// Local SAPIC eid.
+    pub local_sapic_eid: u8,
+
+    /// Bits 8 to 31 of the proximity domain.
+    pub proximity_domain_high: [u8; 3],
+
+    /// Clock domain.
+    pub clock_domain: int::u32le,
+}
+
+/// Memory Affinity Structure
+#[repr(C, packed)]
+pub struct SratMemoryAffinity {
+    /// Structure type (`SRAT_*`).
+    pub srat_type: u8,
+
+    /// Size of the structure in bytes.
+    pub length: u8,
+
+    /// Proximity domain.
+    pub proximity_domain: int::u32le,
+
+    /// Reserved.
+    pub reserved: int::u16le,
+
+    /// Physical address of the memory range.
+    pub base_address: int::u64le,
+
+    /// Size of the memory range.
+    pub range_length: int::u64le,
+
+    /// Reserved.
+    pub reserved1: int::u32le,
+
+    /// Flags (`SRAT_*`).
+    pub flags: int::u32le,
+
+    /// Reserved.
+    pub reserved2: int::u64le,
+}
+
+/// Processor Local x2APIC Affinity Structure
+#[repr(C, packed)]
+pub struct SratX2ApicAffinity {
+    /// Structure type (`SRAT_*`).
+    pub srat_type: u8,
+
+    /// Size of the structure in bytes.
+    pub length: u8,
+
+    /// Reserved.
+    pub reserved: int::u16le,
+
+    /// Proximity domain.
+    pub proximity_domain: int::u32le,
+
+    /// Local x2APIC id.
+    pub x2apic_id: int::u32le,
+
+    /// Flags (`SRAT_ENABLED`).
+    pub flags: int::u32le,
+
+    /// Clock domain.
+    pub clock_domain: int::u32le,
+
+    /// Reserved.
+    pub reserved1: int::u32le,
+}
+
+/// GICC Affinity Structure
+#[repr(C, packed)]
+pub struct SratGiccAffinity {
+    /// Structure type (`SRAT_*`).
+    pub srat_type: u8,
+
+    /// Size of the structure in bytes.
+    pub length: u8,
+
+    /// Proximity domain.
+    pub proximity_domain: int::u32le,
+
+    /// Processor UID.
+    pub acpi_processor_uid: int::u32le,
+
+    /// Flags (`SRAT_ENABLED`).
+    pub flags: int::u32le,
+
+    /// Clock domain.
+    pub clock_domain: int::u32le,
+}
+
+/// GIC ITS Affinity Structure
+#[repr(C, packed)]
+pub struct SratGicItsAffinity {
+    /// Structure type (`SRAT_*`).
+    pub srat_type: u8,
+
+    /// Size of the structure in bytes.
+    pub length: u8,
+
+    /// Proximity domain.
+    pub proximity_domain: int::u32le,
+
+    /// Reserved.
+    pub reserved: int::u16le,
+
+    /// GIC ITS id.
+    pub its_id: int::u32le,
+}
+
+/// Generic Initiator and Generic Port Affinity Structure
+#[repr(C, packed)]
+pub struct SratGenericAffinity {
+    /// Structure type (`SRAT_*`).
+    pub srat_type: u8,
+
+    /// Size of the structure in bytes.
+    pub length: u8,
+
+    /// Reserved.
+    pub reserved: u8,
+
+    /// Device handle type: 0 ACPI, 1 PCI.
+    pub device_handle_type: u8,
+
+    /// Proximity domain.
+    pub proximity_domain: int::u32le,
+
+    /// Device handle.
+    pub device_handle: [u8; 16],
+
+    /// Flags (`SRAT_ENABLED`).
+    pub flags: int::u32le,
+
+    /// Reserved.
+    pub reserved1: int::u32le,
+}
+
+/// DMA Remapping Table
+///
+/// The fixed part of the DMAR, followed by remapping structures (see
+/// `Structures::new_wide()`).
+#[repr(C, packed)]
+pub struct Dmar {
+    /// Table header.
+    pub header: SdtHeader,
+
+    /// Maximum DMA physical address width, minus one.
+    pub host_address_width: u8,
+
+    /// Flags (`DMAR_FLAG_*`).
+    pub flags: u8,
+
+    /// Reserved.
+    pub reserved: [u8; 10],
+}
+
+/// DMA Remapping Hardware Unit Definition Structure
+///
+/// Followed by device scopes (see `DeviceScope`).
+#[repr(C, packed)]
+pub struct DmarDrhd {
+    /// Structure type (`DMAR_*`).
+    pub dmar_type: int::u16le,
+
+    /// Size of the structure in bytes, including device scopes.
+    pub length: int::u16le,
+
+    /// Flags (`DRHD_INCLUDE_PCI_ALL`).
+    pub flags: u8,
+
+    /// Size of the register set, as exponent of 4 KiB pages.
+    pub size: u8,
+
+    /// PCI segment number.
+    pub segment: int::u16le,
+
+    /// Physical address of the remapping registers.
+    pub register_base_address: int::u64le,
+}
+
+/// Reserved Memory Region Reporting Structure
+///
+/// Followed by device scopes (see `DeviceScope`).
+#[repr(C, packed)]
+pub struct DmarRmrr {
+    /// Structure type (`DMAR_*`).
+    pub dmar_type: int::u16le,
+
+    /// Size of the structure in bytes, including device scopes.
+    pub length: int::u16le,
+
+    /// Reserved.
+    pub reserved: int::u16le,
+
+    /// PCI segment number.
+    pub segment: int::u16le,
+
+    /// Physical address of the reserved memory region.
+    pub base_address: int::u64le,
+
+    /// Last address of the reserved memory region.
+    pub limit_address: int::u64le,
+}
+
+/// Root Port ATS Capability Reporting Structure
+///
+/// Followed by device scopes (see `DeviceScope`).
+#[repr(C, packed)]
+pub struct DmarAtsr {
+    /// Structure type (`DMAR_*`).
+    pub dmar_type: int::u16le,
+
+    /// Size of the structure in bytes, including device scopes.
+    pub length: int::u16le,
+
+    /// Flags (`ATSR_ALL_PORTS`).
+    pub flags: u8,
+
+    /// Reserved.
+    pub reserved: u8,
+
+    /// PCI segment number.
+    pub segment: int::u16le,
+}
+
+/// Remapping Hardware Static Affinity Structure
+#[repr(C, packed)]
+pub struct DmarRhsa {
+    /// Structure type (`DMAR_*`).
+    pub dmar_type: int::u16le,
+
+    /// Size of the structure in bytes, including device scopes.
+    pub length: int::u16le,
+
+    /// Reserved.
+    pub reserved: int::u32le,
+
+    /// Physical address of the remapping registers.
+    pub register_base_address: int::u64le,
+
+    /// Proximity domain.
+    pub proximity_domain: int::u32le,
+}
+
+/// ACPI Name-space Device Declaration Structure
+///
+/// Followed by the NUL-terminated ACPI object name of the device.
+#[repr(C, packed)]
+pub struct DmarAndd {
+    /// Structure type (`DMAR_*`).
+    pub dmar_type: int::u16le,
+
+    /// Size of the structure in bytes, including device scopes.
+    pub length: int::u16le,
+
+    /// Reserved.
+    pub reserved: [u8; 3],
+
+    /// Enumeration id of the device in device scopes.
+    pub acpi_device_number: u8,
+}
+
+/// SoC Integrated Address Translation Cache Reporting Structure
+///
+/// Followed by device scopes (see `DeviceScope`).
+#[repr(C, packed)]
+pub struct DmarSatc {
+    /// Structure type (`DMAR_*`).
+    pub dmar_type: int::u16le,
+
+    /// Size of the structure in bytes, including device scopes.
+    pub length: int::u16le,
+
+    /// Flags.
+    pub flags: u8,
+
+    /// Reserved.
+    pub reserved: u8,
+
+    /// PCI segment number.
+    pub segment: int::u16le,
+}
+
+/// SoC Integrated Device Property Reporting Structure
+///
+/// Followed by device scopes (see `DeviceScope`).
+#[repr(C, packed)]
+pub struct DmarSidp {
+    /// Structure type (`DMAR_*`).
+    pub dmar_type: int::u16le,
+
+    /// Size of the structure in bytes, including device scopes.
+    pub length: int::u16le,
+
+    /// Reserved.
+    pub reserved: int::u16le,
+
+    /// PCI segment number.
+    pub segment: int::u16le,
+}
+
+/// DMAR Device Scope
+///
+/// Followed by the path from the start bus to the device, as pairs of
+/// device and function numbers.
+#[repr(C, packed)]
+pub struct DeviceScope {
+    /// Device scope type (`SCOPE_*`).
+    pub scope_type: u8,
+
+    /// Size of the device scope in bytes, including the path.
+    pub length: u8,
+
+    /// Flags.
+    pub flags: u8,
+
+    /// Reserved.
+    pub reserved: u8,
+
+    /// I/O APIC id, HPET number, or ACPI device number.
+    pub enumeration_id: u8,
+
+    /// Bus number of the start of the path.
+    pub start_bus: u8,
+}
+
+/// Variable Sized Structure
+///
+/// A reference to a single variable sized structure of a table, as yielded
+/// by `Structures`. The data covers the entire structure, including its type
+/// and length.
+#[derive(Clone, Copy, Debug)]
+pub struct Structure<'data> {
+    /// Type of the structure.
+    pub structure_type: u16,
+
+    /// Data of the structure, including type and length.
+    pub data: &'data [u8],
+}
+
+/// Structure Iterator
+///
+/// Iterate a sequence of variable sized structures, as found in the MADT,
+/// SRAT, DMAR, and DMAR device scopes. The iterator stops at the first
+/// structure with an invalid length, and the remaining data can be retrieved
+/// via `Structures::remainder()`.
+#[derive(Clone, Debug)]
+pub struct Structures<'data> {
+    data: &'data [u8],
+    wide: bool,
+}
+
+/// Table Entry Iterator
+///
+/// Iterate the physical addresses of the tables listed in the RSDT or XSDT.
+#[derive(Clone, Debug)]
+pub struct Entries<'data> {
+    data: &'data [u8],
+    entry_size: usize,
+}
+
+
+impl Rsdp {
+    /// Import an RSDP from a byte slice
+    ///
+    /// Create a new RSDP from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 20]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 20], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Check whether the checksum is valid
+    ///
+    /// Verify the signature and checksum of an RSDP of any revision. For
+    /// revision 2 and later, the length and extended checksum are verified as
+    /// well. The data can exceed the RSDP.
+    pub fn is_valid(data: &[u8]) -> bool {
+        let Some(v) = data.get(..RSDP_SIZE_V1) else {
+            return false;
+        };
+        let rsdp = Self::from_bytes(v.try_into().unwrap());
+        if rsdp.signature != SIGNATURE_RSDP || checksum::sum8(v) != 0 {
+            return false;
+        }
+        if rsdp.revision < RSDP_REVISION_V2 {
+            return true;
+        }
+
+        let Some(v) = data.get(..RSDP_SIZE_V2) else {
+            return false;
+        };
+        let length = int::ForeignEndian::to_native(
+            Rsdp2::from_bytes(v.try_into().unwrap()).length,
+        ) as usize;
+        match data.get(..length) {
+            Some(v) if length >= RSDP_SIZE_V2 => checksum::sum8(v) == 0,
+            _ => false,
+        }
+    }
+}
+
+impl Rsdp2 {
+    /// Import an RSDP from a byte slice
+    ///
+    /// Create a new RSDP from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 36]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 36], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl SdtHeader {
+    /// Import a table header from a byte slice
+    ///
+    /// Create a new table header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 36]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 36], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl GenericAddress {
+    /// Import a generic address structure from a byte slice
+    ///
+    /// Create a new generic address structure from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 12]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 12], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl Fadt {
+    /// Import an FADT from a byte slice
+    ///
+    /// Create a new FADT from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 276]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 276], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Import an FADT of any revision
+    ///
+    /// Create a new FADT from a table of any revision. Tables shorter than
+    /// the FADT of ACPI 6 are extended with zeroes, and trailing data is
+    /// ignored. No byte-order conversions are applied.
+    pub fn from_table(data: &[u8]) -> Self {
+        let mut v = [0u8; 276];
+        let n = core::cmp::min(data.len(), v.len());
+
+        v[..n].copy_from_slice(&data[..n]);
+        Self::from_bytes(&v)
+    }
+}
+
+impl Madt {
+    /// Import an MADT header from a byte slice
+    ///
+    /// Create a new MADT header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 44]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 44], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl MadtLocalApic {
+    /// Import a structure from a byte slice
+    ///
+    /// Create a new structure from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 8]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 8], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl MadtIoApic {
+    /// Import a structure from a byte slice
+    ///
+    /// Create a new structure from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 12]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 12], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl MadtInterruptSourceOverride {
+    /// Import a structure from a byte slice
+    ///
+    /// Create a new structure from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 10]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 10], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl MadtNmiSource {
+    /// Import a structure from a byte slice
+    ///
+    /// Create a new structure from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 8]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 8], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl MadtLocalApicNmi {
+    /// Import a structure from a byte slice
+    ///
+    /// Create a new structure from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 6]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 6], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl MadtLocalApicAddressOverride {
+    /// Import a structure from a byte slice
+    ///
+    /// Create a new structure from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 12]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 12], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl MadtIoSapic {
+    /// Import a structure from a byte slice
+    ///
+    /// Create a new structure from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 16]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 16], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl MadtLocalSapic {
+    /// Import a structure from a byte slice
+    ///
+    /// Create a new structure from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 16]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 16], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl MadtPlatformInterruptSource {
+    /// Import a structure from a byte slice
+    ///
+    /// Create a new structure from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 16]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 16], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl MadtLocalX2Apic {
+    /// Import a structure from a byte slice
+    ///
+    /// Create a new structure from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 16]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 16], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl MadtLocalX2ApicNmi {
+    /// Import a structure from a byte slice
+    ///
+    /// Create a new structure from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 12]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 12], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl MadtGicc {
+    /// Import a structure from a byte slice
+    ///
+    /// Create a new structure from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 80]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 80], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl MadtGicd {
+    /// Import a structure from a byte slice
+    ///
+    /// Create a new structure from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 24]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 24], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl MadtGicMsiFrame {
+    /// Import a structure from a byte slice
+    ///
+    /// Create a new structure from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 24]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 24], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl MadtGicr {
+    /// Import a structure from a byte slice
+    ///
+    /// Create a new structure from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 16]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 16], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl MadtGicIts {
+    /// Import a structure from a byte slice
+    ///
+    /// Create a new structure from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 20]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 20], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl MadtMultiprocessorWakeup {
+    /// Import a structure from a byte slice
+    ///
+    /// Create a new structure from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 16]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 16], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl MadtCorePic {
+    /// Import a structure from a byte slice
+    ///
+    /// Create a new structure from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 15]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 15], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl MadtLioPic {
+    /// Import a structure from a byte slice
+    ///
+    /// Create a new structure from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 23]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 23], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl MadtHtPic {
+    /// Import a structure from a byte slice
+    ///
+    /// Create a new structure from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 21]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 21], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl MadtEioPic {
+    /// Import a structure from a byte slice
+    ///
+    /// Create a new structure from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 13]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 13], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl MadtMsiPic {
+    /// Import a structure from a byte slice
+    ///
+    /// Create a new structure from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 19]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 19], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl MadtBioPic {
+    /// Import a structure from a byte slice
+    ///
+    /// Create a new structure from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 17]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 17], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl MadtLpcPic {
+    /// Import a structure from a byte slice
+    ///
+    /// Create a new structure from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 15]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 15], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl MadtRintc {
+    /// Import a structure from a byte slice
+    ///
+    /// Create a new structure from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 36]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 36], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl MadtImsic {
+    /// Import a structure from a byte slice
+    ///
+    /// Create a new structure from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 16]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 16], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl MadtAplic {
+    /// Import a structure from a byte slice
+    ///
+    /// Create a new structure from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 36]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 36], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl MadtPlic {
+    /// Import a structure from a byte slice
+    ///
+    /// Create a new structure from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 36]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 36], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl Mcfg {
+    /// Import a MCFG header from a byte slice
+    ///
+    /// Create a new MCFG header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 44]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 44], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl McfgAllocation {
+    /// Import an MCFG allocation from a byte slice
+    ///
+    /// Create a new MCFG allocation from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 16]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 16], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl Hpet {
+    /// Import an HPET from a byte slice
+    ///
+    /// Create a new HPET from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 56]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 56], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl Srat {
+    /// Import an SRAT header from a byte slice
+    ///
+    /// Create a new SRAT header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 48]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 48], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl SratLocalApicAffinity {
+    /// Import a structure from a byte slice
+    ///
+    /// Create a new structure from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 16]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 16], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl SratMemoryAffinity {
+    /// Import a structure from a byte slice
+    ///
+    /// Create a new structure from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 40]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 40], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl SratX2ApicAffinity {
+    /// Import a structure from a byte slice
+    ///
+    /// Create a new structure from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 24]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 24], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl SratGiccAffinity {
+    /// Import a structure from a byte slice
+    ///
+    /// Create a new structure from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 18]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 18], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl SratGicItsAffinity {
+    /// Import a structure from a byte slice
+    ///
+    /// Create a new structure from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 12]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 12], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl SratGenericAffinity {
+    /// Import a structure from a byte slice
+    ///
+    /// Create a new structure from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 32]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 32], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl Dmar {
+    /// Import a DMAR header from a byte slice
+    ///
+    /// Create a new DMAR header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 48]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 48], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl DmarDrhd {
+    /// Import a structure from a byte slice
+    ///
+    /// Create a new structure from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 16]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 16], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl DmarRmrr {
+    /// Import a structure from a byte slice
+    ///
+    /// Create a new structure from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 24]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 24], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl DmarAtsr {
+    /// Import a structure from a byte slice
+    ///
+    /// Create a new structure from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 8]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 8], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl DmarRhsa {
+    /// Import a structure from a byte slice
+    ///
+    /// Create a new structure from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 20]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 20], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl DmarAndd {
+    /// Import a structure from a byte slice
+    ///
+    /// Create a new structure from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 8]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 8], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl DmarSatc {
+    /// Import a structure from a byte slice
+    ///
+    /// Create a new structure from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 8]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 8], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl DmarSidp {
+    /// Import a structure from a byte slice
+    ///
+    /// Create a new structure from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 8]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 8], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl DeviceScope {
+    /// Import a device scope from a byte slice
+    ///
+    /// Create a new device scope from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 6]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 6], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl<'data> Structures<'data> {
+    /// Create structure iterator
+    ///
+    /// Create a new iterator over structures with 8-bit type and length
+    /// fields, as used by the MADT, SRAT, and DMAR device scopes. The data
+    /// must start at the first structure.
+    pub fn new(data: &'data [u8]) -> Self {
+        Self {
+            data,
+            wide: false,
+        }
+    }
+
+    /// Create wide structure iterator
+    ///
+    /// Create a new iterator over structures with 16-bit type and length
+    /// fields, as used by the DMAR.
+    pub fn new_wide(data: &'data [u8]) -> Self {
+        Self {
+            data,
+            wide: true,
+        }
+    }
+
+    /// Return remaining data
+    ///
+    /// Return the data that has not been consumed by the iterator.
+    pub fn remainder(&self) -> &'data [u8] {
+        self.data
+    }
+}
+
+impl<'data> Iterator for Structures<'data> {
+    type Item = Structure<'data>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (structure_type, length, min) = if self.wide {
+            let v = self.data.get(..4)?;
+            (
+                u16::from_le_bytes([v[0], v[1]]),
+                u16::from_le_bytes([v[2], v[3]]) as usize,
+                4,
+            )
+        } else {
+            let v = self.data.get(..2)?;
+            (v[0] as u16, v[1] as usize, 2)
+        };
+
+        if length < min || length > self.data.len() {
+            return None;
+        }
+
+        let (data, rest) = self.data.split_at(length);
+        self.data = rest;
+        Some(Structure {
+            structure_type,
+            data,
+        })
+    }
+}
+
+impl<'data> Entries<'data> {
+    /// Create RSDT entry iterator
+    ///
+    /// Create a new iterator over the 32-bit entries of an RSDT. The data
+    /// must be the entire table, including its header.
+    pub fn new(table: &'data [u8]) -> Self {
+        Self {
+            data: table.get(size_of::<SdtHeader>()..).unwrap_or(&[]),
+            entry_size: 4,
+        }
+    }
+
+    /// Create XSDT entry iterator
+    ///
+    /// Create a new iterator over the 64-bit entries of an XSDT. The data
+    /// must be the entire table, including its header.
+    pub fn new_wide(table: &'data [u8]) -> Self {
+        Self {
+            data: table.get(size_of::<SdtHeader>()..).unwrap_or(&[]),
+            entry_size: 8,
+        }
+    }
+}
+
+impl<'data> Iterator for Entries<'data> {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let v = self.data.get(..self.entry_size)?;
+        self.data = &self.data[self.entry_size..];
+
+        let mut buf = [0u8; 8];
+        buf[..v.len()].copy_from_slice(v);
+        Some(u64::from_le_bytes(buf))
+    }
+}
+
+/// Check table checksum
+///
+/// Return whether all bytes of the given table sum to zero modulo 256. The
+/// data must cover exactly the table (see `table()`).
+pub fn checksum_valid(table: &[u8]) -> bool {
+    checksum::sum8(table) == 0
+}
+
+/// Split off a table
+///
+/// Parse the header of the table at the start of `data`, and return it
+/// together with the data of the entire table, as given by its length. Any
+/// trailing data is ignored. `None` is returned if the data is truncated, or
+/// the length is smaller than the header. The checksum is not verified.
+pub fn table(data: &[u8]) -> Option<(SdtHeader, &[u8])> {
+    let header = SdtHeader::from_bytes(data.get(..36)?.try_into().ok()?);
+    let length = int::ForeignEndian::to_native(header.length) as usize;
+    if length < size_of::<SdtHeader>() {
+        return None;
+    }
+
+    Some((header, data.get(..length)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::specs::int::ForeignEndian;
+
+    // Minimal RSDP of revision 2, with valid checksums.
+    const RSDP: [u8; 36] = [
+        0x52, 0x53, 0x44, 0x20, 0x50, 0x54, 0x52, 0x20,
+        0xb1, 0x4f, 0x45, 0x4d, 0x49, 0x44, 0x20, 0x02,
+        0x00, 0x40, 0xe1, 0x7f, 0x24, 0x00, 0x00, 0x00,
+        0xe8, 0x40, 0xe1, 0x7f, 0x00, 0x00, 0x00, 0x00,
+        0x54, 0x00, 0x00, 0x00,
+    ];
+
+    // Minimal MADT with a local APIC, an I/O APIC, an interrupt source
+    // override, and a local APIC NMI.
+    const MADT: [u8; 80] = [
+        0x41, 0x50, 0x49, 0x43, 0x50, 0x00, 0x00, 0x00,
+        0x05, 0xc6, 0x4f, 0x45, 0x4d, 0x49, 0x44, 0x20,
+        0x4f, 0x45, 0x4d, 0x54, 0x41, 0x42, 0x4c, 0x45,
+        0x01, 0x00, 0x00, 0x00, 0x41, 0x43, 0x50, 0x49,
+        0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0xe0, 0xfe,
+        0x01, 0x00, 0x00, 0x00, 0x00, 0x08, 0x00, 0x00,
+        0x01, 0x00, 0x00, 0x00, 0x01, 0x0c, 0x02, 0x00,
+        0x00, 0x00, 0xc0, 0xfe, 0x00, 0x00, 0x00, 0x00,
+        0x02, 0x0a, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x04, 0x06, 0xff, 0x05, 0x00, 0x01,
+    ];
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<Rsdp>(), 20);
+        assert_eq!(size_of::<Rsdp2>(), 36);
+        assert_eq!(size_of::<SdtHeader>(), 36);
+        assert_eq!(size_of::<GenericAddress>(), 12);
+        assert_eq!(size_of::<Fadt>(), 276);
+        assert_eq!(size_of::<Madt>(), 44);
+        assert_eq!(size_of::<MadtLocalApic>(), 8);
+        assert_eq!(size_of::<MadtIoApic>(), 12);
+        assert_eq!(size_of::<MadtInterruptSourceOverride>(), 10);
+        assert_eq!(size_of::<MadtNmiSource>(), 8);
+        assert_eq!(size_of::<MadtLocalApicNmi>(), 6);
+        assert_eq!(size_of::<MadtLocalApicAddressOverride>(), 12);
+        assert_eq!(size_of::<MadtIoSapic>(), 16);
+        assert_eq!(size_of::<MadtLocalSapic>(), 16);
+        assert_eq!(size_of::<MadtPlatformInterruptSource>(), 16);
+        assert_eq!(size_of::<MadtLocalX2Apic>(), 16);
+        assert_eq!(size_of::<MadtLocalX2ApicNmi>(), 12);
+        assert_eq!(size_of::<MadtGicc>(), 80);
+        assert_eq!(size_of::<MadtGicd>(), 24);
+        assert_eq!(size_of::<MadtGicMsiFrame>(), 24);
+        assert_eq!(size_of::<MadtGicr>(), 16);
+        assert_eq!(size_of::<MadtGicIts>(), 20);
+        assert_eq!(size_of::<MadtMultiprocessorWakeup>(), 16);
+        assert_eq!(size_of::<MadtCorePic>(), 15);
+        assert_eq!(size_of::<MadtLioPic>(), 23);
+        assert_eq!(size_of::<MadtHtPic>(), 21);
+        assert_eq!(size_of::<MadtEioPic>(), 13);
+        assert_eq!(size_of::<MadtMsiPic>(), 19);
+        assert_eq!(size_of::<MadtBioPic>(), 17);
+        assert_eq!(size_of::<MadtLpcPic>(), 15);
+        assert_eq!(size_of::<MadtRintc>(), 36);
+        assert_eq!(size_of::<MadtImsic>(), 16);
+        assert_eq!(size_of::<MadtAplic>(), 36);
+        assert_eq!(size_of::<MadtPlic>(), 36);
+        assert_eq!(size_of::<Mcfg>(), 44);
+        assert_eq!(size_of::<McfgAllocation>(), 16);
+        assert_eq!(size_of::<Hpet>(), 56);
+        assert_eq!(size_of::<Srat>(), 48);
+        assert_eq!(size_of::<SratLocalApicAffinity>(), 16);
+        assert_eq!(size_of::<SratMemoryAffinity>(), 40);
+        assert_eq!(size_of::<SratX2ApicAffinity>(), 24);
+        assert_eq!(size_of::<SratGiccAffinity>(), 18);
+        assert_eq!(size_of::<SratGicItsAffinity>(), 12);
+        assert_eq!(size_of::<SratGenericAffinity>(), 32);
+        assert_eq!(size_of::<Dmar>(), 48);
+        assert_eq!(size_of::<DmarDrhd>(), 16);
+        assert_eq!(size_of::<DmarRmrr>(), 24);
+        assert_eq!(size_of::<DmarAtsr>(), 8);
+        assert_eq!(size_of::<DmarRhsa>(), 20);
+        assert_eq!(size_of::<DmarAndd>(), 8);
+        assert_eq!(size_of::<DmarSatc>(), 8);
+        assert_eq!(size_of::<DmarSidp>(), 8);
+        assert_eq!(size_of::<DeviceScope>(), 6);
+        assert_eq!(align_of::<Fadt>(), 1);
+        assert_eq!(align_of::<MadtGicc>(), 1);
+    }
+
+    // Verify both RSDP checksums, and reject corrupted data.
+    #[test]
+    fn verify_rsdp() {
+        assert!(Rsdp::is_valid(&RSDP));
+        assert!(!Rsdp::is_valid(&RSDP[..20]));
+
+        let rsdp = Rsdp2::from_bytes(&RSDP);
+        assert_eq!(rsdp.rsdp.revision, RSDP_REVISION_V2);
+        assert_eq!(rsdp.rsdp.rsdt_address.to_native(), 0x7fe14000);
+        assert_eq!(rsdp.xsdt_address.to_native(), 0x7fe140e8);
+
+        let mut v = RSDP;
+        v[21] ^= 0x01;
+        assert!(!Rsdp::is_valid(&v));
+
+        // A revision 0 RSDP only covers the first checksum.
+        let mut v = RSDP;
+        v[15] = RSDP_REVISION_V1;
+        v[8] = v[8].wrapping_add(RSDP_REVISION_V2);
+        v[32] = 0;
+        assert!(Rsdp::is_valid(&v));
+    }
+
+    // Split the MADT, verify its checksum, and walk all structures.
+    #[test]
+    fn verify_madt() {
+        let mut data = std::vec::Vec::from(&MADT[..]);
+        data.extend_from_slice(&[0xff; 4]);
+
+        let (header, table) = table(&data).unwrap();
+        assert_eq!(header.signature, SIGNATURE_MADT);
+        assert_eq!(table.len(), MADT.len());
+        assert!(checksum_valid(table));
+        assert!(!checksum_valid(&data));
+
+        let madt = Madt::from_bytes(table[..44].try_into().unwrap());
+        assert_eq!(madt.local_apic_address.to_native(), 0xfee00000);
+        assert_eq!(madt.flags.to_native(), MADT_PCAT_COMPAT);
+
+        let mut it = Structures::new(&table[44..]);
+
+        let s = it.next().unwrap();
+        assert_eq!(s.structure_type, MADT_LOCAL_APIC as u16);
+        let v = MadtLocalApic::from_bytes(s.data.try_into().unwrap());
+        assert_eq!(v.flags.to_native(), LOCAL_APIC_ENABLED);
+
+        let s = it.next().unwrap();
+        assert_eq!(s.structure_type, MADT_IO_APIC as u16);
+        let v = MadtIoApic::from_bytes(s.data.try_into().unwrap());
+        assert_eq!(v.io_apic_id, 2);
+        assert_eq!(v.io_apic_address.to_native(), 0xfec00000);
+
+        let s = it.next().unwrap();
+        assert_eq!(s.structure_type, MADT_INTERRUPT_SOURCE_OVERRIDE as u16);
+        let v = MadtInterruptSourceOverride::from_bytes(s.data.try_into().unwrap());
+        assert_eq!(v.gsi.to_native(), 2);
+
+        let s = it.next().unwrap();
+        assert_eq!(s.structure_type, MADT_LOCAL_APIC_NMI as u16);
+        let v = MadtLocalApicNmi::from_bytes(s.data.try_into().unwrap());
+        assert_eq!(v.acpi_processor_uid, 0xff);
+        assert_eq!(v.flags.to_native(), MPS_INTI_POLARITY_CONFORMS | MPS_INTI_TRIGGER_EDGE | 1);
+        assert_eq!(v.lint, 1);
+
+        assert!(it.next().is_none());
+        assert!(it.remainder().is_empty());
+
+        // A truncated structure stops the iteration.
+        let mut it = Structures::new(&table[44..50]);
+        assert!(it.next().is_none());
+        assert_eq!(it.remainder().len(), 6);
+    }
+
+    // Walk the 32-bit and 64-bit entries of system description tables.
+    #[test]
+    fn verify_entries() {
+        let mut data = [0u8; 52];
+        data[36..40].copy_from_slice(&0x1000u32.to_le_bytes());
+        data[40..44].copy_from_slice(&0x2000u32.to_le_bytes());
+        data[44..52].copy_from_slice(&0x1_0000_3000u64.to_le_bytes());
+
+        let v: std::vec::Vec<u64> = Entries::new(&data).collect();
+        assert_eq!(v, [0x1000, 0x2000, 0x3000, 0x1]);
+
+        let v: std::vec::Vec<u64> = Entries::new_wide(&data[..52]).collect();
+        assert_eq!(v, [0x2000_0000_1000, 0x1_0000_3000]);
+    }
+}
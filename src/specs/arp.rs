@@ -0,0 +1,260 @@
+//! Address Resolution Protocol
+//!
+//! ARP maps protocol addresses to hardware addresses on a link. Packets
+//! start with a fixed `Header`, which names the hardware and protocol types
+//! and the length of their addresses. It is followed by the sender hardware
+//! address, the sender protocol address, the target hardware address, and
+//! the target protocol address.
+//!
+//! Since address lengths vary, `Packet` provides access to the addresses of
+//! arbitrary packets. `EthernetIpv4` describes the common case of IPv4 over
+//! Ethernet directly. RARP and InARP use the same format with their own
+//! operations.
+//!
+//! All multi-byte integers are encoded as big-endian.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::{ethernet, int};
+
+pub const HEADER_SIZE: usize = 8;
+pub const ETHERNET_IPV4_SIZE: usize = 28;
+
+pub const HARDWARE_ETHERNET: u16 = 1;
+pub const HARDWARE_IEEE802: u16 = 6;
+pub const HARDWARE_FRAME_RELAY: u16 = 15;
+pub const HARDWARE_ATM: u16 = 16;
+pub const HARDWARE_HDLC: u16 = 17;
+pub const HARDWARE_FIBRE_CHANNEL: u16 = 18;
+pub const HARDWARE_SERIAL: u16 = 20;
+pub const HARDWARE_INFINIBAND: u16 = 32;
+
+pub const PROTOCOL_IPV4: u16 = ethernet::ETHERTYPE_IPV4;
+
+pub const OP_REQUEST: u16 = 1;
+pub const OP_REPLY: u16 = 2;
+pub const OP_RARP_REQUEST: u16 = 3;
+pub const OP_RARP_REPLY: u16 = 4;
+pub const OP_INARP_REQUEST: u16 = 8;
+pub const OP_INARP_REPLY: u16 = 9;
+pub const OP_NAK: u16 = 10;
+
+/// ARP Header
+#[repr(C)]
+pub struct Header {
+    /// Hardware type (`HARDWARE_*`).
+    pub hardware_type: int::u16be,
+
+    /// Protocol type, an EtherType (`PROTOCOL_*`).
+    pub protocol_type: int::u16be,
+
+    /// Length of hardware addresses in bytes.
+    pub hardware_length: u8,
+
+    /// Length of protocol addresses in bytes.
+    pub protocol_length: u8,
+
+    /// Operation (`OP_*`).
+    pub operation: int::u16be,
+}
+
+/// ARP Packet for IPv4 over Ethernet
+#[repr(C)]
+pub struct EthernetIpv4 {
+    /// Header with `HARDWARE_ETHERNET` and `PROTOCOL_IPV4`, and address
+    /// lengths of 6 and 4.
+    pub header: Header,
+
+    /// Sender hardware address.
+    pub sender_mac: ethernet::Mac,
+
+    /// Sender protocol address.
+    pub sender_ip: [u8; 4],
+
+    /// Target hardware address, ignored in requests.
+    pub target_mac: ethernet::Mac,
+
+    /// Target protocol address.
+    pub target_ip: [u8; 4],
+}
+
+/// ARP Packet Reference
+///
+/// A reference to an ARP packet of arbitrary address lengths, split into its
+/// addresses.
+#[derive(Clone, Copy, Debug)]
+pub struct Packet<'data> {
+    /// The entire packet, including the header and trailing data.
+    pub data: &'data [u8],
+
+    /// Sender hardware address.
+    pub sender_hardware: &'data [u8],
+
+    /// Sender protocol address.
+    pub sender_protocol: &'data [u8],
+
+    /// Target hardware address.
+    pub target_hardware: &'data [u8],
+
+    /// Target protocol address.
+    pub target_protocol: &'data [u8],
+}
+
+impl Header {
+    /// Import a header from a byte slice
+    ///
+    /// Create a new header from data copied from a byte slice. No byte-order
+    /// conversions are applied.
+    pub fn from_bytes(data: &[u8; 8]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 8], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Return packet size
+    ///
+    /// Return the size of the packet including the header, as given by the
+    /// address lengths.
+    pub fn packet_size(&self) -> usize {
+        HEADER_SIZE + 2 * (self.hardware_length as usize + self.protocol_length as usize)
+    }
+
+    /// Check for IPv4 over Ethernet
+    ///
+    /// Return whether the header describes IPv4 over Ethernet, and thus
+    /// starts an `EthernetIpv4` packet.
+    pub fn is_ethernet_ipv4(&self) -> bool {
+        int::ForeignEndian::to_native(self.hardware_type) == HARDWARE_ETHERNET
+            && int::ForeignEndian::to_native(self.protocol_type) == PROTOCOL_IPV4
+            && self.hardware_length == 6
+            && self.protocol_length == 4
+    }
+}
+
+impl EthernetIpv4 {
+    /// Import a packet from a byte slice
+    ///
+    /// Create a new packet from data copied from a byte slice. No byte-order
+    /// conversions are applied.
+    pub fn from_bytes(data: &[u8; 28]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 28], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl<'data> Packet<'data> {
+    /// Parse a packet
+    ///
+    /// Split the packet at the start of `data` into its addresses. Return
+    /// `None` if the data is too short for the address lengths of the
+    /// header. Any trailing data (e.g., Ethernet padding) is ignored.
+    pub fn parse(data: &'data [u8]) -> Option<Self> {
+        if data.len() < HEADER_SIZE {
+            return None;
+        }
+
+        let h = data[4] as usize;
+        let p = data[5] as usize;
+        if data.len() < HEADER_SIZE + 2 * (h + p) {
+            return None;
+        }
+
+        let o = HEADER_SIZE;
+        Some(Self {
+            data,
+            sender_hardware: &data[o..o + h],
+            sender_protocol: &data[o + h..o + h + p],
+            target_hardware: &data[o + h + p..o + 2 * h + p],
+            target_protocol: &data[o + 2 * h + p..o + 2 * (h + p)],
+        })
+    }
+
+    /// Return the packet header
+    pub fn header(&self) -> Header {
+        Header::from_bytes(self.data[..HEADER_SIZE].try_into().unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A request for the MAC of 192.168.1.1, padded to the Ethernet minimum.
+    const REQUEST: [u8; 46] = [
+        0x00, 0x01, 0x08, 0x00, 0x06, 0x04, 0x00, 0x01,
+        0x02, 0x00, 0x00, 0x00, 0x00, 0x01, 0xc0, 0xa8,
+        0x01, 0x64, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0xc0, 0xa8, 0x01, 0x01, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<Header>(), HEADER_SIZE);
+        assert_eq!(align_of::<Header>(), 2);
+        assert_eq!(size_of::<EthernetIpv4>(), ETHERNET_IPV4_SIZE);
+        assert_eq!(align_of::<EthernetIpv4>(), 2);
+    }
+
+    // Parse a request via the fixed-size layout.
+    #[test]
+    fn verify_ethernet_ipv4() {
+        let p = EthernetIpv4::from_bytes(REQUEST[..28].try_into().unwrap());
+        assert!(p.header.is_ethernet_ipv4());
+        assert_eq!(p.header.packet_size(), ETHERNET_IPV4_SIZE);
+        assert_eq!(int::ForeignEndian::to_native(p.header.operation), OP_REQUEST);
+        assert_eq!(p.sender_mac.to_native(), 0x0200_0000_0001);
+        assert_eq!(p.sender_ip, [192, 168, 1, 100]);
+        assert_eq!(p.target_mac, ethernet::Mac::default());
+        assert_eq!(p.target_ip, [192, 168, 1, 1]);
+    }
+
+    // Parse packets of arbitrary address lengths.
+    #[test]
+    fn verify_packet() {
+        let p = Packet::parse(&REQUEST).unwrap();
+        assert!(p.header().is_ethernet_ipv4());
+        assert_eq!(p.sender_hardware, &REQUEST[8..14]);
+        assert_eq!(p.sender_protocol, &[192, 168, 1, 100]);
+        assert_eq!(p.target_hardware, &[0; 6]);
+        assert_eq!(p.target_protocol, &[192, 168, 1, 1]);
+
+        // An InARP request over a link with 2-byte hardware addresses.
+        let data = [
+            0x00, 0x0f, 0x08, 0x00, 0x02, 0x04, 0x00, 0x08,
+            0x04, 0x51, 0x0a, 0x00, 0x00, 0x01, 0x04, 0x61,
+            0x00, 0x00, 0x00, 0x00,
+        ];
+        let p = Packet::parse(&data).unwrap();
+        let h = p.header();
+        assert!(!h.is_ethernet_ipv4());
+        assert_eq!(int::ForeignEndian::to_native(h.hardware_type), HARDWARE_FRAME_RELAY);
+        assert_eq!(int::ForeignEndian::to_native(h.operation), OP_INARP_REQUEST);
+        assert_eq!(p.sender_hardware, &[0x04, 0x51]);
+        assert_eq!(p.sender_protocol, &[10, 0, 0, 1]);
+        assert_eq!(p.target_hardware, &[0x04, 0x61]);
+        assert_eq!(p.target_protocol, &[0, 0, 0, 0]);
+
+        assert!(Packet::parse(&data[..19]).is_none());
+        assert!(Packet::parse(&data[..7]).is_none());
+    }
+}
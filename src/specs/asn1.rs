@@ -0,0 +1,430 @@
+//! Abstract Syntax Notation One
+//!
+//! The Basic Encoding Rules (BER, ITU-T X.690) encode each value as
+//! identifier octets, length octets, and contents octets. The identifier
+//! carries the class (`CLASS_*`), a flag for constructed encodings, whose
+//! contents are a sequence of nested encodings, and the tag number (see
+//! `Identifier`). Tag numbers above 30 use the high-tag-number form, with
+//! the number following as VLQ (see `varint::vlq_decode()`).
+//!
+//! The length is either a single byte for lengths up to 127 (short form),
+//! the number of length octets with the high bit set, followed by the
+//! length (long form), or `LENGTH_INDEFINITE` (see `decode_length()`). The
+//! contents of indefinite-length encodings are terminated by an
+//! end-of-contents marker (`END_OF_CONTENTS`), and only allowed for
+//! constructed encodings.
+//!
+//! The Distinguished Encoding Rules (DER) restrict BER such that each value
+//! has exactly one encoding. Among others, lengths must use the minimal
+//! form, and high tag numbers must not have leading zero groups (see
+//! `Tlv::parse_der()`).
+//!
+//! All multi-byte integers are encoded as big-endian.
+
+use crate::specs::varint;
+
+pub const CLASS_SHIFT: u32 = 6;
+pub const CONSTRUCTED: u8 = 0x20;
+pub const TAG_MASK: u8 = 0x1f;
+
+pub const CLASS_UNIVERSAL: u8 = 0;
+pub const CLASS_APPLICATION: u8 = 1;
+pub const CLASS_CONTEXT: u8 = 2;
+pub const CLASS_PRIVATE: u8 = 3;
+
+/// High-tag-number Form Marker
+pub const TAG_HIGH: u8 = 0x1f;
+
+pub const LENGTH_LONG: u8 = 0x80;
+pub const LENGTH_INDEFINITE: u8 = 0x80;
+/* reserved: 0xff */
+
+/// Maximum Number of Long Form Length Octets
+///
+/// Lengths are limited to `usize` here, so larger lengths are rejected.
+pub const LENGTH_OCTETS_MAX: usize = size_of::<usize>();
+
+/// End-of-contents Marker
+pub const END_OF_CONTENTS: [u8; 2] = [0x00, 0x00];
+
+pub const TAG_END_OF_CONTENTS: u32 = 0;
+pub const TAG_BOOLEAN: u32 = 1;
+pub const TAG_INTEGER: u32 = 2;
+pub const TAG_BIT_STRING: u32 = 3;
+pub const TAG_OCTET_STRING: u32 = 4;
+pub const TAG_NULL: u32 = 5;
+pub const TAG_OBJECT_IDENTIFIER: u32 = 6;
+pub const TAG_OBJECT_DESCRIPTOR: u32 = 7;
+pub const TAG_EXTERNAL: u32 = 8;
+pub const TAG_REAL: u32 = 9;
+pub const TAG_ENUMERATED: u32 = 10;
+pub const TAG_EMBEDDED_PDV: u32 = 11;
+pub const TAG_UTF8_STRING: u32 = 12;
+pub const TAG_RELATIVE_OID: u32 = 13;
+pub const TAG_TIME: u32 = 14;
+/* reserved: 15 */
+pub const TAG_SEQUENCE: u32 = 16;
+pub const TAG_SET: u32 = 17;
+pub const TAG_NUMERIC_STRING: u32 = 18;
+pub const TAG_PRINTABLE_STRING: u32 = 19;
+pub const TAG_T61_STRING: u32 = 20;
+pub const TAG_VIDEOTEX_STRING: u32 = 21;
+pub const TAG_IA5_STRING: u32 = 22;
+pub const TAG_UTC_TIME: u32 = 23;
+pub const TAG_GENERALIZED_TIME: u32 = 24;
+pub const TAG_GRAPHIC_STRING: u32 = 25;
+pub const TAG_VISIBLE_STRING: u32 = 26;
+pub const TAG_GENERAL_STRING: u32 = 27;
+pub const TAG_UNIVERSAL_STRING: u32 = 28;
+pub const TAG_CHARACTER_STRING: u32 = 29;
+pub const TAG_BMP_STRING: u32 = 30;
+pub const TAG_DATE: u32 = 31;
+pub const TAG_TIME_OF_DAY: u32 = 32;
+pub const TAG_DATE_TIME: u32 = 33;
+pub const TAG_DURATION: u32 = 34;
+pub const TAG_OID_IRI: u32 = 35;
+pub const TAG_RELATIVE_OID_IRI: u32 = 36;
+
+pub const BOOLEAN_FALSE: u8 = 0x00;
+pub const BOOLEAN_TRUE_DER: u8 = 0xff;
+
+/// Identifier
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Identifier {
+    /// Tag class (`CLASS_*`).
+    pub class: u8,
+
+    /// Whether the contents are a sequence of nested encodings.
+    pub constructed: bool,
+
+    /// Tag number (`TAG_*` for the universal class).
+    pub tag: u32,
+}
+
+/// Tag-Length-Value Reference
+#[derive(Clone, Copy, Debug)]
+pub struct Tlv<'data> {
+    /// Identifier.
+    pub identifier: Identifier,
+
+    /// Contents, excluding the end-of-contents marker of indefinite-length
+    /// encodings.
+    pub contents: &'data [u8],
+
+    /// Whether the length was indefinite.
+    pub indefinite: bool,
+
+    /// Size of the entire encoding in bytes.
+    pub size: usize,
+}
+
+/// TLV Iterator
+///
+/// Iterate over consecutive BER encodings, such as the contents of a
+/// constructed encoding. Iteration stops at the first invalid or truncated
+/// encoding, and at end-of-contents markers.
+#[derive(Clone, Debug)]
+pub struct Tlvs<'data> {
+    data: &'data [u8],
+}
+
+impl Identifier {
+    /// Decode identifier octets
+    ///
+    /// Decode the identifier at the start of `data`. Return the identifier
+    /// and the number of bytes consumed, or `None` if the data is truncated,
+    /// or the tag number does not fit in 32 bits.
+    pub fn decode(data: &[u8]) -> Option<(Self, usize)> {
+        let first = *data.first()?;
+        let class = first >> CLASS_SHIFT;
+        let constructed = first & CONSTRUCTED != 0;
+
+        let (tag, n) = match first & TAG_MASK {
+            TAG_HIGH => {
+                let (v, n) = varint::vlq_decode(&data[1..])?;
+                (u32::try_from(v).ok()?, 1 + n)
+            },
+            v => (v as u32, 1),
+        };
+
+        Some((Self { class, constructed, tag }, n))
+    }
+
+    /// Encode identifier octets
+    ///
+    /// Encode the identifier, using the low-tag-number form for tag numbers
+    /// up to 30. Return the number of bytes written, or `None` if the buffer
+    /// is too small.
+    pub fn encode(&self, out: &mut [u8]) -> Option<usize> {
+        let mut first = (self.class << CLASS_SHIFT) & 0xc0;
+        if self.constructed {
+            first |= CONSTRUCTED;
+        }
+
+        if self.tag < TAG_HIGH as u32 {
+            *out.first_mut()? = first | self.tag as u8;
+            return Some(1);
+        }
+
+        *out.first_mut()? = first | TAG_HIGH;
+        Some(1 + varint::vlq_encode(self.tag as u64, &mut out[1..])?)
+    }
+
+    /// Check for universal tags
+    pub fn is_universal(&self, tag: u32) -> bool {
+        self.class == CLASS_UNIVERSAL && self.tag == tag
+    }
+}
+
+impl<'data> Tlv<'data> {
+    /// Parse a BER encoding
+    ///
+    /// Parse the encoding at the start of `data`. The end of
+    /// indefinite-length encodings is found by skipping nested encodings.
+    /// Return `None` if the encoding is truncated or invalid.
+    pub fn parse(data: &'data [u8]) -> Option<Self> {
+        let (identifier, n) = Identifier::decode(data)?;
+        let (length, m) = decode_length(&data[n..])?;
+        let start = n + m;
+
+        match length {
+            Some(length) => {
+                let end = start.checked_add(length)?;
+                Some(Self {
+                    identifier,
+                    contents: data.get(start..end)?,
+                    indefinite: false,
+                    size: end,
+                })
+            },
+            None if identifier.constructed => {
+                // Skip nested encodings iteratively, tracking the nesting
+                // depth of indefinite-length encodings.
+                let mut depth = 0;
+                let mut end = start;
+                loop {
+                    let rest = &data[end..];
+                    if rest.starts_with(&END_OF_CONTENTS) {
+                        if depth == 0 {
+                            break;
+                        }
+                        depth -= 1;
+                        end += END_OF_CONTENTS.len();
+                        continue;
+                    }
+
+                    let (v, n) = Identifier::decode(rest)?;
+                    let (l, m) = decode_length(&rest[n..])?;
+                    end += n + m;
+                    match l {
+                        Some(l) if l <= data.len() - end => end += l,
+                        Some(_) => return None,
+                        None if v.constructed => depth += 1,
+                        None => return None,
+                    }
+                }
+                Some(Self {
+                    identifier,
+                    contents: &data[start..end],
+                    indefinite: true,
+                    size: end + END_OF_CONTENTS.len(),
+                })
+            },
+            None => None,
+        }
+    }
+
+    /// Parse a DER encoding
+    ///
+    /// Parse the encoding at the start of `data` like `parse()`, but
+    /// additionally reject indefinite lengths, non-minimal lengths, and
+    /// non-minimal tag numbers. Nested encodings are not verified.
+    pub fn parse_der(data: &'data [u8]) -> Option<Self> {
+        let (identifier, n) = Identifier::decode(data)?;
+        let (length, m) = decode_length(&data[n..])?;
+        let length = length?;
+
+        if n > 1 && (identifier.tag < TAG_HIGH as u32 || data[1] == 0x80) {
+            return None;
+        }
+        if m != length_size(length) {
+            return None;
+        }
+
+        let end = (n + m).checked_add(length)?;
+        Some(Self { identifier, contents: data.get(n + m..end)?, indefinite: false, size: end })
+    }
+
+    /// Return nested encodings
+    ///
+    /// Return an iterator over the nested encodings of constructed
+    /// encodings.
+    pub fn children(&self) -> Option<Tlvs<'data>> {
+        match self.identifier.constructed {
+            true => Some(Tlvs::new(self.contents)),
+            false => None,
+        }
+    }
+}
+
+impl<'data> Tlvs<'data> {
+    /// Create TLV iterator
+    ///
+    /// Create a new iterator over the consecutive encodings in `data`.
+    pub fn new(data: &'data [u8]) -> Self {
+        Self { data }
+    }
+
+    /// Return remaining data
+    ///
+    /// Return the data that has not been consumed by the iterator.
+    pub fn remainder(&self) -> &'data [u8] {
+        self.data
+    }
+}
+
+impl<'data> Iterator for Tlvs<'data> {
+    type Item = Tlv<'data>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.data.starts_with(&END_OF_CONTENTS) {
+            return None;
+        }
+
+        let v = Tlv::parse(self.data)?;
+        self.data = &self.data[v.size..];
+        Some(v)
+    }
+}
+
+/// Decode length octets
+///
+/// Decode the length at the start of `data`. Return the length, or `None`
+/// for indefinite lengths, and the number of bytes consumed. Return `None`
+/// if the data is truncated, the length is reserved, or it does not fit in
+/// `usize`.
+pub fn decode_length(data: &[u8]) -> Option<(Option<usize>, usize)> {
+    let first = *data.first()?;
+    if first == LENGTH_INDEFINITE {
+        return Some((None, 1));
+    }
+    if first & LENGTH_LONG == 0 {
+        return Some((Some(first as usize), 1));
+    }
+
+    let n = (first & !LENGTH_LONG) as usize;
+    if n == 0x7f || n > LENGTH_OCTETS_MAX {
+        return None;
+    }
+
+    let v = data.get(1..1 + n)?;
+    Some((Some(v.iter().fold(0, |acc, b| (acc << 8) | *b as usize)), 1 + n))
+}
+
+/// Return length size
+///
+/// Return the number of length octets of the minimal definite encoding of
+/// `length`, as required by DER.
+pub fn length_size(length: usize) -> usize {
+    match length {
+        0..=0x7f => 1,
+        _ => 1 + (usize::BITS - length.leading_zeros()).div_ceil(8) as usize,
+    }
+}
+
+/// Encode length octets
+///
+/// Encode `length` using the minimal definite form. Return the number of
+/// bytes written, or `None` if the buffer is too small.
+pub fn encode_length(length: usize, out: &mut [u8]) -> Option<usize> {
+    let n = length_size(length);
+    let out = out.get_mut(..n)?;
+
+    if n == 1 {
+        out[0] = length as u8;
+    } else {
+        out[0] = LENGTH_LONG | (n - 1) as u8;
+        out[1..].copy_from_slice(&length.to_be_bytes()[size_of::<usize>() + 1 - n..]);
+    }
+    Some(n)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // DER of `SEQUENCE { INTEGER 5, [1] IMPLICIT OCTET STRING "ab",
+    // [APPLICATION 33] NULL }`.
+    const SEQUENCE: [u8; 12] = [
+        0x30, 0x0a, 0x02, 0x01, 0x05, 0x81, 0x02, 0x61,
+        0x62, 0x5f, 0x21, 0x00,
+    ];
+
+    // BER of an indefinite-length constructed OCTET STRING with 2 nested
+    // segments, including a nested indefinite-length encoding.
+    const INDEFINITE: [u8; 16] = [
+        0x24, 0x80, 0x04, 0x01, 0x61, 0x24, 0x80, 0x04,
+        0x02, 0x62, 0x63, 0x00, 0x00, 0x00, 0x00, 0xff,
+    ];
+
+    // Decode and encode identifiers and lengths.
+    #[test]
+    fn verify_octets() {
+        let id = Identifier { class: CLASS_APPLICATION, constructed: false, tag: 33 };
+        assert_eq!(Identifier::decode(&SEQUENCE[9..]), Some((id, 2)));
+        let id = Identifier { class: CLASS_CONTEXT, constructed: true, tag: 0x4000 };
+        let mut buf = [0; 4];
+        assert_eq!(id.encode(&mut buf), Some(4));
+        assert_eq!(buf, [0xbf, 0x81, 0x80, 0x00]);
+        assert_eq!(Identifier::decode(&buf), Some((id, 4)));
+        assert!(Identifier::decode(&SEQUENCE).unwrap().0.is_universal(TAG_SEQUENCE));
+
+        assert_eq!(decode_length(&[0x7f]), Some((Some(127), 1)));
+        assert_eq!(decode_length(&[0x82, 0x01, 0x00]), Some((Some(256), 3)));
+        assert_eq!(decode_length(&[0x80]), Some((None, 1)));
+        assert_eq!(decode_length(&[0xff]), None);
+        assert_eq!(decode_length(&[0x82, 0x01]), None);
+
+        assert_eq!(length_size(0x7f), 1);
+        assert_eq!(length_size(0x80), 2);
+        assert_eq!(length_size(0x10000), 4);
+        let mut buf = [0; 4];
+        assert_eq!(encode_length(0x1234, &mut buf), Some(3));
+        assert_eq!(buf[..3], [0x82, 0x12, 0x34]);
+        assert_eq!(encode_length(0x1234, &mut buf[..2]), None);
+    }
+
+    // Parse definite and indefinite-length encodings.
+    #[test]
+    fn verify_tlvs() {
+        let v = Tlv::parse_der(&SEQUENCE).unwrap();
+        assert_eq!((v.size, v.indefinite), (12, false));
+        let mut c = v.children().unwrap();
+        let e = c.next().unwrap();
+        assert!(e.identifier.is_universal(TAG_INTEGER));
+        assert_eq!(e.contents, [0x05]);
+        let e = c.next().unwrap();
+        assert_eq!((e.identifier.class, e.identifier.tag), (CLASS_CONTEXT, 1));
+        assert_eq!(e.contents, b"ab");
+        let e = c.next().unwrap();
+        assert_eq!((e.identifier.tag, e.size), (33, 3));
+        assert!(c.next().is_none());
+        assert!(e.children().is_none());
+
+        let v = Tlv::parse(&INDEFINITE).unwrap();
+        assert_eq!((v.size, v.indefinite, v.contents.len()), (15, true, 11));
+        let mut c = v.children().unwrap();
+        assert_eq!(c.next().unwrap().contents, b"a");
+        let e = c.next().unwrap();
+        assert!(e.indefinite);
+        assert_eq!(e.children().unwrap().next().unwrap().contents, b"bc");
+        assert!(c.next().is_none());
+
+        assert!(Tlv::parse(&INDEFINITE[..14]).is_none());
+        assert!(Tlv::parse_der(&INDEFINITE).is_none());
+        assert!(Tlv::parse(&[0x04, 0x80, 0x00, 0x00]).is_none());
+        assert!(Tlv::parse_der(&[0x04, 0x81, 0x01, 0x61]).is_none());
+        assert!(Tlv::parse(&[0x04, 0x81, 0x01, 0x61]).is_some());
+        assert!(Tlv::parse_der(&[0x1f, 0x05, 0x00]).is_none());
+    }
+}
@@ -0,0 +1,511 @@
+//! Audio Video Interleave
+//!
+//! AVI files are RIFF containers (see `riff`) of form type `AVI `. The file
+//! starts with a `hdrl` list, which carries the `MainHeader` (`avih`) and one
+//! `strl` list per stream. Each `strl` list holds the `StreamHeader`
+//! (`strh`) and the stream format (`strf`), which is a `bmp::InfoHeader`
+//! for video streams and a `wav::Format` for audio streams. The `movi` list
+//! follows with the stream data, and the legacy `idx1` index concludes the
+//! file.
+//!
+//! Data chunks in `movi` are identified by a 2-digit decimal stream number
+//! followed by a 2-character type code, e.g., `00dc` for compressed video of
+//! stream 0 (see `chunk_id()`).
+//!
+//! The OpenDML extensions lift the 1 GiB limit of the format. Additional
+//! RIFF chunks of form type `AVIX` follow the first one, and two-tier
+//! indices replace `idx1`: a `SuperIndex` (`indx`) per stream in its `strl`
+//! list references `StandardIndex` chunks (`ix##`) spread over the file.
+//! The `odml` list in `hdrl` carries the total number of frames in a `dmlh`
+//! chunk.
+//!
+//! All multi-byte integers are encoded as little-endian.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::int;
+
+pub const FORM: [u8; 4] = *b"AVI ";
+pub const FORM_EXTENDED: [u8; 4] = *b"AVIX";
+
+pub const LIST_HDRL: [u8; 4] = *b"hdrl";
+pub const LIST_STRL: [u8; 4] = *b"strl";
+pub const LIST_MOVI: [u8; 4] = *b"movi";
+pub const LIST_ODML: [u8; 4] = *b"odml";
+pub const LIST_REC: [u8; 4] = *b"rec ";
+
+pub const CHUNK_AVIH: [u8; 4] = *b"avih";
+pub const CHUNK_STRH: [u8; 4] = *b"strh";
+pub const CHUNK_STRF: [u8; 4] = *b"strf";
+pub const CHUNK_STRD: [u8; 4] = *b"strd";
+pub const CHUNK_STRN: [u8; 4] = *b"strn";
+pub const CHUNK_IDX1: [u8; 4] = *b"idx1";
+pub const CHUNK_INDX: [u8; 4] = *b"indx";
+pub const CHUNK_DMLH: [u8; 4] = *b"dmlh";
+pub const CHUNK_JUNK: [u8; 4] = *b"JUNK";
+
+pub const STREAM_VIDEO: [u8; 4] = *b"vids";
+pub const STREAM_AUDIO: [u8; 4] = *b"auds";
+pub const STREAM_MIDI: [u8; 4] = *b"mids";
+pub const STREAM_TEXT: [u8; 4] = *b"txts";
+
+pub const DATA_UNCOMPRESSED_VIDEO: [u8; 2] = *b"db";
+pub const DATA_COMPRESSED_VIDEO: [u8; 2] = *b"dc";
+pub const DATA_PALETTE_CHANGE: [u8; 2] = *b"pc";
+pub const DATA_AUDIO: [u8; 2] = *b"wb";
+pub const DATA_TEXT: [u8; 2] = *b"tx";
+
+pub const AVIF_HAS_INDEX: u32 = 0x0000_0010;
+pub const AVIF_MUST_USE_INDEX: u32 = 0x0000_0020;
+pub const AVIF_IS_INTERLEAVED: u32 = 0x0000_0100;
+pub const AVIF_TRUST_CK_TYPE: u32 = 0x0000_0800;
+pub const AVIF_WAS_CAPTURE_FILE: u32 = 0x0001_0000;
+pub const AVIF_COPYRIGHTED: u32 = 0x0002_0000;
+
+pub const AVISF_DISABLED: u32 = 0x0000_0001;
+pub const AVISF_VIDEO_PALCHANGES: u32 = 0x0001_0000;
+
+pub const AVIIF_LIST: u32 = 0x0000_0001;
+pub const AVIIF_KEYFRAME: u32 = 0x0000_0010;
+pub const AVIIF_NO_TIME: u32 = 0x0000_0100;
+
+pub const INDEX_OF_INDEXES: u8 = 0x00;
+pub const INDEX_OF_CHUNKS: u8 = 0x01;
+pub const INDEX_IS_DATA: u8 = 0x80;
+
+pub const INDEX_SUB_DEFAULT: u8 = 0x00;
+pub const INDEX_SUB_2FIELD: u8 = 0x01;
+
+/// Non-keyframe Flag
+///
+/// Set in the size of a `StandardIndexEntry` if the chunk is not a key
+/// frame.
+pub const INDEX_DELTA_FRAME: u32 = 0x8000_0000;
+
+/// Main Header
+///
+/// The payload of the `avih` chunk.
+#[repr(C)]
+pub struct MainHeader {
+    /// Duration of a frame in microseconds.
+    pub micro_sec_per_frame: int::u32le,
+
+    /// Approximate maximum data rate.
+    pub max_bytes_per_sec: int::u32le,
+
+    /// Alignment of data in bytes.
+    pub padding_granularity: int::u32le,
+
+    /// File flags (`AVIF_*`).
+    pub flags: int::u32le,
+
+    /// Number of frames in the first RIFF chunk.
+    pub total_frames: int::u32le,
+
+    /// Initial frame for interleaved files.
+    pub initial_frames: int::u32le,
+
+    /// Number of streams.
+    pub streams: int::u32le,
+
+    /// Suggested buffer size for reading the file.
+    pub suggested_buffer_size: int::u32le,
+
+    /// Width of the video in pixels.
+    pub width: int::u32le,
+
+    /// Height of the video in pixels.
+    pub height: int::u32le,
+
+    /// Reserved, must be 0.
+    pub reserved: [int::u32le; 4],
+}
+
+/// Stream Header
+///
+/// The payload of an `strh` chunk.
+#[repr(C)]
+pub struct StreamHeader {
+    /// Stream type (`STREAM_*`).
+    pub fcc_type: [u8; 4],
+
+    /// Preferred codec handler.
+    pub fcc_handler: [u8; 4],
+
+    /// Stream flags (`AVISF_*`).
+    pub flags: int::u32le,
+
+    /// Priority of the stream.
+    pub priority: int::u16le,
+
+    /// Language tag.
+    pub language: int::u16le,
+
+    /// Initial frames of interleaved files.
+    pub initial_frames: int::u32le,
+
+    /// Time scale; `rate / scale` is the sample rate.
+    pub scale: int::u32le,
+
+    /// Rate; `rate / scale` is the sample rate.
+    pub rate: int::u32le,
+
+    /// Start time in units of `scale / rate`.
+    pub start: int::u32le,
+
+    /// Length in units of `scale / rate`.
+    pub length: int::u32le,
+
+    /// Suggested buffer size for reading the stream.
+    pub suggested_buffer_size: int::u32le,
+
+    /// Quality indicator between 0 and 10000, or -1.
+    pub quality: int::u32le,
+
+    /// Size of a sample, or 0 if the size varies.
+    pub sample_size: int::u32le,
+
+    /// Destination rectangle as left, top, right, and bottom.
+    pub frame: [int::i16le; 4],
+}
+
+/// Legacy Index Entry
+///
+/// An entry of the `idx1` chunk.
+#[repr(C)]
+pub struct Idx1Entry {
+    /// Identifier of the referenced chunk.
+    pub chunk_id: [u8; 4],
+
+    /// Index flags (`AVIIF_*`).
+    pub flags: int::u32le,
+
+    /// Offset of the chunk header, relative to the `movi` list type (or, in
+    /// some writers, relative to the start of the file).
+    pub offset: int::u32le,
+
+    /// Size of the chunk payload.
+    pub size: int::u32le,
+}
+
+/// OpenDML Extended Header
+///
+/// The start of the `dmlh` chunk. Writers pad the chunk to 248 bytes.
+#[repr(C)]
+pub struct ExtendedHeader {
+    /// Number of frames in the entire file.
+    pub total_frames: int::u32le,
+}
+
+/// OpenDML Super Index Header
+///
+/// The start of an `indx` chunk, followed by `entries_in_use` entries of
+/// `SuperIndexEntry`.
+#[repr(C)]
+pub struct SuperIndex {
+    /// Size of an entry in 32-bit units, must be 4.
+    pub longs_per_entry: int::u16le,
+
+    /// Index sub-type (`INDEX_SUB_*`).
+    pub index_sub_type: u8,
+
+    /// Index type, must be `INDEX_OF_INDEXES`.
+    pub index_type: u8,
+
+    /// Number of valid entries.
+    pub entries_in_use: int::u32le,
+
+    /// Identifier of the indexed chunks, e.g., `00dc`.
+    pub chunk_id: [u8; 4],
+
+    /// Reserved, must be 0.
+    pub reserved: [int::u32le; 3],
+}
+
+/// OpenDML Super Index Entry
+#[repr(C)]
+pub struct SuperIndexEntry {
+    /// Absolute file offset of the `ix##` chunk.
+    pub offset: int::u64le,
+
+    /// Size of the `ix##` chunk in bytes.
+    pub size: int::u32le,
+
+    /// Duration covered by the `ix##` chunk, in units of the stream.
+    pub duration: int::u32le,
+}
+
+/// OpenDML Standard Index Header
+///
+/// The start of an `ix##` chunk, followed by `entries_in_use` entries of
+/// `StandardIndexEntry`. Since the base offset is not naturally aligned,
+/// this structure is packed.
+#[repr(C, packed)]
+pub struct StandardIndex {
+    /// Size of an entry in 32-bit units, must be 2.
+    pub longs_per_entry: int::u16le,
+
+    /// Index sub-type, must be `INDEX_SUB_DEFAULT`.
+    pub index_sub_type: u8,
+
+    /// Index type, must be `INDEX_OF_CHUNKS`.
+    pub index_type: u8,
+
+    /// Number of valid entries.
+    pub entries_in_use: int::u32le,
+
+    /// Identifier of the indexed chunks, e.g., `00dc`.
+    pub chunk_id: [u8; 4],
+
+    /// Base for the offsets of all entries.
+    pub base_offset: int::u64le,
+
+    /// Reserved, must be 0.
+    pub reserved: int::u32le,
+}
+
+/// OpenDML Standard Index Entry
+#[repr(C)]
+pub struct StandardIndexEntry {
+    /// Offset of the chunk payload, relative to the base offset.
+    pub offset: int::u32le,
+
+    /// Size of the chunk payload, with `INDEX_DELTA_FRAME` set for
+    /// non-keyframes.
+    pub size: int::u32le,
+}
+
+impl MainHeader {
+    /// Import a main header from a byte slice
+    ///
+    /// Create a new main header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 56]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 56], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl StreamHeader {
+    /// Import a stream header from a byte slice
+    ///
+    /// Create a new stream header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 56]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 56], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl Idx1Entry {
+    /// Import an index entry from a byte slice
+    ///
+    /// Create a new index entry from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 16]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 16], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl SuperIndex {
+    /// Import a super index header from a byte slice
+    ///
+    /// Create a new super index header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 24]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 24], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl StandardIndex {
+    /// Import a standard index header from a byte slice
+    ///
+    /// Create a new standard index header from data copied from a byte
+    /// slice. No byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 24]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 24], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl StandardIndexEntry {
+    /// Check for key frames
+    pub fn is_keyframe(&self) -> bool {
+        int::ForeignEndian::to_native(self.size) & INDEX_DELTA_FRAME == 0
+    }
+
+    /// Return payload size
+    ///
+    /// Return the size of the chunk payload with the flag stripped.
+    pub fn payload_size(&self) -> u32 {
+        int::ForeignEndian::to_native(self.size) & !INDEX_DELTA_FRAME
+    }
+}
+
+/// Create Data Chunk Identifier
+///
+/// Return the identifier of data chunks of the given stream and type code
+/// (`DATA_*`). `None` is returned if the stream number exceeds 99.
+pub fn chunk_id(stream: u8, kind: &[u8; 2]) -> Option<[u8; 4]> {
+    if stream > 99 {
+        return None;
+    }
+
+    Some([b'0' + stream / 10, b'0' + stream % 10, kind[0], kind[1]])
+}
+
+/// Parse Data Chunk Identifier
+///
+/// Split a data chunk identifier into stream number and type code. `None`
+/// is returned if it does not start with two decimal digits.
+pub fn parse_chunk_id(id: &[u8; 4]) -> Option<(u8, [u8; 2])> {
+    if !id[0].is_ascii_digit() || !id[1].is_ascii_digit() {
+        return None;
+    }
+
+    Some(((id[0] - b'0') * 10 + (id[1] - b'0'), [id[2], id[3]]))
+}
+
+/// Create Standard Index Identifier
+///
+/// Return the identifier of `ix##` chunks of the given stream. `None` is
+/// returned if the stream number exceeds 99.
+pub fn index_id(stream: u8) -> Option<[u8; 4]> {
+    let v = chunk_id(stream, b"ix")?;
+    Some([v[2], v[3], v[0], v[1]])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::specs::riff;
+
+    // A file with a `hdrl` list carrying a main header for 25fps 320x240
+    // video with a single stream.
+    fn file() -> [u8; 88] {
+        let mut f = [0u8; 88];
+
+        f[0..4].copy_from_slice(&riff::ID_RIFF);
+        f[4..8].copy_from_slice(&80u32.to_le_bytes());
+        f[8..12].copy_from_slice(&FORM);
+        f[12..16].copy_from_slice(&riff::ID_LIST);
+        f[16..20].copy_from_slice(&68u32.to_le_bytes());
+        f[20..24].copy_from_slice(&LIST_HDRL);
+        f[24..28].copy_from_slice(&CHUNK_AVIH);
+        f[28..32].copy_from_slice(&56u32.to_le_bytes());
+        f[32..36].copy_from_slice(&40000u32.to_le_bytes());
+        f[44..48].copy_from_slice(&AVIF_HAS_INDEX.to_le_bytes());
+        f[48..52].copy_from_slice(&250u32.to_le_bytes());
+        f[56..60].copy_from_slice(&1u32.to_le_bytes());
+        f[64..68].copy_from_slice(&320u32.to_le_bytes());
+        f[68..72].copy_from_slice(&240u32.to_le_bytes());
+
+        f
+    }
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<MainHeader>(), 56);
+        assert_eq!(size_of::<StreamHeader>(), 56);
+        assert_eq!(size_of::<Idx1Entry>(), 16);
+        assert_eq!(size_of::<SuperIndex>(), 24);
+        assert_eq!(size_of::<SuperIndexEntry>(), 16);
+        assert_eq!(align_of::<SuperIndexEntry>(), 8);
+        assert_eq!(size_of::<StandardIndex>(), 24);
+        assert_eq!(align_of::<StandardIndex>(), 1);
+        assert_eq!(size_of::<StandardIndexEntry>(), 8);
+    }
+
+    // Walk the header list of a file.
+    #[test]
+    fn verify_header() {
+        let f = file();
+        let (form, mut chunks) = riff::parse(&f).unwrap();
+        assert_eq!(form, FORM);
+
+        let (list, mut sub) = chunks.next().unwrap().list().unwrap();
+        assert_eq!(list, LIST_HDRL);
+
+        let c = sub.next().unwrap();
+        assert_eq!(c.id, CHUNK_AVIH);
+        let h = MainHeader::from_bytes(c.data.try_into().unwrap());
+        assert_eq!(int::ForeignEndian::to_native(h.micro_sec_per_frame), 40000);
+        assert_eq!(int::ForeignEndian::to_native(h.flags), AVIF_HAS_INDEX);
+        assert_eq!(int::ForeignEndian::to_native(h.total_frames), 250);
+        assert_eq!(int::ForeignEndian::to_native(h.streams), 1);
+        assert_eq!(int::ForeignEndian::to_native(h.width), 320);
+        assert_eq!(int::ForeignEndian::to_native(h.height), 240);
+    }
+
+    // Verify chunk identifiers and index entries.
+    #[test]
+    fn verify_index() {
+        assert_eq!(chunk_id(0, &DATA_COMPRESSED_VIDEO), Some(*b"00dc"));
+        assert_eq!(chunk_id(12, &DATA_AUDIO), Some(*b"12wb"));
+        assert_eq!(chunk_id(100, &DATA_AUDIO), None);
+        assert_eq!(parse_chunk_id(b"01wb"), Some((1, DATA_AUDIO)));
+        assert_eq!(parse_chunk_id(b"ix00"), None);
+        assert_eq!(index_id(3), Some(*b"ix03"));
+
+        let e = StandardIndexEntry {
+            offset: 0.into(),
+            size: (INDEX_DELTA_FRAME | 1234).into(),
+        };
+        assert!(!e.is_keyframe());
+        assert_eq!(e.payload_size(), 1234);
+
+        let mut data = [0u8; 24];
+        data[0..2].copy_from_slice(&2u16.to_le_bytes());
+        data[3] = INDEX_OF_CHUNKS;
+        data[4..8].copy_from_slice(&7u32.to_le_bytes());
+        data[8..12].copy_from_slice(b"00dc");
+        data[12..20].copy_from_slice(&0x1_0000_0000u64.to_le_bytes());
+
+        let i = StandardIndex::from_bytes(&data);
+        assert_eq!(i.index_type, INDEX_OF_CHUNKS);
+        assert_eq!(int::ForeignEndian::to_native(i.entries_in_use), 7);
+        assert_eq!(int::ForeignEndian::to_native(i.base_offset), 0x1_0000_0000);
+    }
+}
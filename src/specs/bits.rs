@@ -0,0 +1,173 @@
+//! Bit-level Access
+//!
+//! This module provides helpers to access data at the granularity of single
+//! bits. Many formats (in particular compression formats and media streams)
+//! pack fields tightly without any regard for byte boundaries. The helpers in
+//! this module allow walking such data without copying it.
+//!
+//! All helpers in this module use MSB-first bit ordering. That is, the first
+//! bit of a byte slice is the most significant bit of its first byte, and
+//! multi-bit values are assembled with their most significant bit first. This
+//! matches the order used by most specifications that pack data at the
+//! bit-level (e.g., bzip2, MPEG, FLAC).
+
+/// Bit Reader
+///
+/// A cursor over a byte slice that allows reading values of arbitrary bit
+/// width (up to 64 bits) at any bit offset. The reader never modifies the
+/// underlying data and can be freely repositioned.
+///
+/// Positions are always given in bits relative to the start of the slice.
+#[derive(Clone, Copy, Debug)]
+pub struct Reader<'data> {
+    data: &'data [u8],
+    position: usize,
+}
+
+impl<'data> Reader<'data> {
+    /// Create a new bit reader
+    ///
+    /// Create a new bit reader for the given byte slice, positioned at its
+    /// first bit.
+    pub fn new(data: &'data [u8]) -> Self {
+        Self {
+            data,
+            position: 0,
+        }
+    }
+
+    /// Return underlying data
+    ///
+    /// Return the byte slice this reader operates on.
+    pub fn data(&self) -> &'data [u8] {
+        self.data
+    }
+
+    /// Return current position
+    ///
+    /// Return the offset of the next bit to read, relative to the start of
+    /// the underlying data.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Return number of remaining bits
+    ///
+    /// Return the number of bits that can still be read from the current
+    /// position until the end of the underlying data is reached.
+    pub fn remaining(&self) -> usize {
+        (self.data.len() * 8).saturating_sub(self.position)
+    }
+
+    /// Reposition the reader
+    ///
+    /// Move the reader to the given bit offset. Offsets beyond the end of the
+    /// data are allowed, but any further read will fail.
+    pub fn seek(&mut self, position: usize) {
+        self.position = position;
+    }
+
+    /// Skip bits
+    ///
+    /// Advance the reader by `n` bits without reading them.
+    pub fn skip(&mut self, n: usize) {
+        self.position = self.position.saturating_add(n);
+    }
+
+    /// Align to byte boundary
+    ///
+    /// Advance the reader to the next byte boundary. If the reader is already
+    /// positioned on a byte boundary, this is a no-op.
+    pub fn align(&mut self) {
+        self.position = (self.position + 7) & !7;
+    }
+
+    /// Peek at bits
+    ///
+    /// Read the next `n` bits and return them as an integer, with the first
+    /// bit read being the most significant bit of the value. The position of
+    /// the reader is not modified.
+    ///
+    /// `None` is returned if less than `n` bits remain. `n` must not exceed
+    /// 64.
+    pub fn peek(&self, n: u32) -> Option<u64> {
+        assert!(n <= 64);
+
+        if (n as usize) > self.remaining() {
+            return None;
+        }
+
+        let mut v: u64 = 0;
+        let mut pos = self.position;
+        let mut left = n;
+
+        while left > 0 {
+            let byte = self.data[pos / 8];
+            let offset = (pos % 8) as u32;
+            let avail = 8 - offset;
+            let take = core::cmp::min(avail, left);
+            let bits = (byte >> (avail - take)) & ((1u16 << take) - 1) as u8;
+
+            v = (v << take) | bits as u64;
+            pos += take as usize;
+            left -= take;
+        }
+
+        Some(v)
+    }
+
+    /// Read bits
+    ///
+    /// Read the next `n` bits and advance the reader. See `peek()` for
+    /// details.
+    pub fn read(&mut self, n: u32) -> Option<u64> {
+        let v = self.peek(n)?;
+        self.position += n as usize;
+        Some(v)
+    }
+
+    /// Read a single bit
+    ///
+    /// Read the next bit and advance the reader by one.
+    pub fn read_bit(&mut self) -> Option<bool> {
+        self.read(1).map(|v| v != 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Verify reading values at unaligned positions, including values that
+    // span multiple bytes.
+    #[test]
+    fn verify_reader() {
+        let data = [0b1010_1100, 0b0101_0011, 0xff];
+        let mut r = Reader::new(&data);
+
+        assert_eq!(r.remaining(), 24);
+        assert_eq!(r.read_bit(), Some(true));
+        assert_eq!(r.read_bit(), Some(false));
+        assert_eq!(r.read(4), Some(0b1011));
+        assert_eq!(r.position(), 6);
+        assert_eq!(r.read(6), Some(0b00_0101));
+        assert_eq!(r.peek(12), Some(0b0011_1111_1111));
+        assert_eq!(r.read(13), None);
+        assert_eq!(r.read(12), Some(0b0011_1111_1111));
+        assert_eq!(r.remaining(), 0);
+        assert_eq!(r.read(0), Some(0));
+        assert_eq!(r.read(1), None);
+
+        r.seek(3);
+        r.align();
+        assert_eq!(r.position(), 8);
+        r.align();
+        assert_eq!(r.position(), 8);
+        assert_eq!(r.read(16), Some(0x53ff));
+
+        let data = [0x01, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd, 0xef, 0x80];
+        let mut r = Reader::new(&data);
+        r.seek(1);
+        assert_eq!(r.read(64), Some(0x02468acf13579bdf));
+    }
+}
@@ -0,0 +1,445 @@
+//! BitTorrent Bencode and Peer Wire Protocol
+//!
+//! BitTorrent (BEP 3) encodes metainfo files, tracker responses, and DHT
+//! messages with bencode. A bencoded value is one of:
+//!
+//!  * Integer: `INTEGER`, the decimal value, and `END` (e.g., `i42e`).
+//!    Leading zeros and negative zero are invalid.
+//!  * Byte String: The decimal length, `SEPARATOR`, and the raw bytes
+//!    (e.g., `4:spam`).
+//!  * List: `LIST`, the encoded elements, and `END`.
+//!  * Dictionary: `DICT`, alternating byte string keys and values, and
+//!    `END`. Keys are sorted as raw strings.
+//!
+//! Bencode is scanned as a stream of tokens (see `Token`), where lists and
+//! dictionaries are delimited by their start token and `Token::End`. The
+//! info hash identifying a torrent is the SHA-1 hash of the exact encoding
+//! of the `info` dictionary of the metainfo file (see `find_key()`).
+//!
+//! Peers exchange a 68-byte handshake (see `Handshake`), followed by
+//! messages prefixed with their length. A message consists of a message id
+//! (`MESSAGE_*`) and its payload, while a length of 0 denotes a keep-alive
+//! message without id. All multi-byte integers are encoded as big-endian.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::int;
+
+pub const INTEGER: u8 = b'i';
+pub const LIST: u8 = b'l';
+pub const DICT: u8 = b'd';
+pub const END: u8 = b'e';
+pub const SEPARATOR: u8 = b':';
+
+/// Maximum Nesting Depth
+///
+/// Nesting depth of lists and dictionaries supported when scanning values.
+pub const DEPTH_MAX: usize = 64;
+
+/// Protocol Name
+///
+/// The protocol name carried by handshakes ("BitTorrent protocol").
+pub const PROTOCOL: [u8; 19] = *b"BitTorrent protocol";
+
+pub const HANDSHAKE_SIZE: usize = 68;
+pub const HASH_SIZE: usize = 20;
+pub const PEER_ID_SIZE: usize = 20;
+
+pub const RESERVED_EXTENSION_BYTE: usize = 5;
+pub const RESERVED_EXTENSION_MASK: u8 = 0x10;
+pub const RESERVED_FAST_BYTE: usize = 7;
+pub const RESERVED_FAST_MASK: u8 = 0x04;
+pub const RESERVED_DHT_BYTE: usize = 7;
+pub const RESERVED_DHT_MASK: u8 = 0x01;
+
+pub const MESSAGE_CHOKE: u8 = 0;
+pub const MESSAGE_UNCHOKE: u8 = 1;
+pub const MESSAGE_INTERESTED: u8 = 2;
+pub const MESSAGE_NOT_INTERESTED: u8 = 3;
+pub const MESSAGE_HAVE: u8 = 4;
+pub const MESSAGE_BITFIELD: u8 = 5;
+pub const MESSAGE_REQUEST: u8 = 6;
+pub const MESSAGE_PIECE: u8 = 7;
+pub const MESSAGE_CANCEL: u8 = 8;
+pub const MESSAGE_PORT: u8 = 9;
+pub const MESSAGE_SUGGEST_PIECE: u8 = 13;
+pub const MESSAGE_HAVE_ALL: u8 = 14;
+pub const MESSAGE_HAVE_NONE: u8 = 15;
+pub const MESSAGE_REJECT_REQUEST: u8 = 16;
+pub const MESSAGE_ALLOWED_FAST: u8 = 17;
+pub const MESSAGE_EXTENDED: u8 = 20;
+
+/// Common Block Size
+///
+/// The size of blocks requested by common clients. Larger requests are
+/// commonly rejected.
+pub const BLOCK_SIZE: u32 = 16384;
+
+/// Bencode Token
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Token<'data> {
+    Integer(i64),
+    String(&'data [u8]),
+    List,
+    Dict,
+    End,
+}
+
+/// Token Iterator
+///
+/// Iterate over the tokens of bencoded data. Iteration stops at the first
+/// truncated or invalid token.
+#[derive(Clone, Debug)]
+pub struct Tokens<'data> {
+    data: &'data [u8],
+}
+
+/// Handshake
+#[repr(C)]
+pub struct Handshake {
+    /// Length of the protocol name. This must match the length of
+    /// `PROTOCOL`.
+    pub protocol_length: u8,
+
+    /// Protocol name. This must match `PROTOCOL`.
+    pub protocol: [u8; 19],
+
+    /// Reserved bits, used to signal support for extensions
+    /// (`RESERVED_*`).
+    pub reserved: [u8; 8],
+
+    /// Info hash of the torrent.
+    pub info_hash: [u8; 20],
+
+    /// Peer id of the sender.
+    pub peer_id: [u8; 20],
+}
+
+/// Block Request
+///
+/// The payload of `MESSAGE_REQUEST`, `MESSAGE_CANCEL`, and
+/// `MESSAGE_REJECT_REQUEST`. The payload of `MESSAGE_PIECE` starts with the
+/// same fields, without `length`, followed by the block data.
+#[repr(C)]
+pub struct Request {
+    /// Index of the piece.
+    pub index: int::u32be,
+
+    /// Offset of the block within the piece.
+    pub begin: int::u32be,
+
+    /// Size of the block in bytes.
+    pub length: int::u32be,
+}
+
+/// Peer Message Reference
+#[derive(Clone, Copy, Debug)]
+pub struct Message<'data> {
+    /// Message id (`MESSAGE_*`), or `None` for keep-alive messages.
+    pub id: Option<u8>,
+
+    /// Message payload.
+    pub payload: &'data [u8],
+
+    /// Size of the message in bytes, including the length prefix.
+    pub size: usize,
+}
+
+// Parse a decimal number without leading zeros.
+fn decimal(data: &[u8]) -> Option<u64> {
+    if data.is_empty() || (data.len() > 1 && data[0] == b'0') {
+        return None;
+    }
+
+    let mut v: u64 = 0;
+    for c in data {
+        if !c.is_ascii_digit() {
+            return None;
+        }
+        v = v.checked_mul(10)?.checked_add((c - b'0') as u64)?;
+    }
+    Some(v)
+}
+
+impl<'data> Token<'data> {
+    /// Parse bencode token
+    ///
+    /// Parse the token at the start of `data`. Return the token and the
+    /// number of bytes consumed, or `None` if the token is truncated or
+    /// invalid.
+    pub fn parse(data: &'data [u8]) -> Option<(Self, usize)> {
+        match *data.first()? {
+            LIST => Some((Token::List, 1)),
+            DICT => Some((Token::Dict, 1)),
+            END => Some((Token::End, 1)),
+            INTEGER => {
+                let end = data.iter().position(|v| *v == END)?;
+                let (negative, digits) = match data[1..end].split_first() {
+                    Some((b'-', rest)) => (true, rest),
+                    _ => (false, &data[1..end]),
+                };
+                let v = decimal(digits)?;
+                let v = match negative {
+                    true if v == 0 => return None,
+                    true => 0i64.checked_sub_unsigned(v)?,
+                    false => i64::try_from(v).ok()?,
+                };
+                Some((Token::Integer(v), end + 1))
+            },
+            _ => {
+                let sep = data.iter().position(|v| *v == SEPARATOR)?;
+                let length = usize::try_from(decimal(&data[..sep])?).ok()?;
+                let end = (sep + 1).checked_add(length)?;
+                Some((Token::String(data.get(sep + 1..end)?), end))
+            },
+        }
+    }
+}
+
+impl<'data> Tokens<'data> {
+    /// Create token iterator
+    ///
+    /// Create a new iterator over the tokens of the bencoded data `data`.
+    pub fn new(data: &'data [u8]) -> Self {
+        Self { data }
+    }
+
+    /// Return remaining data
+    ///
+    /// Return the data that has not been consumed by the iterator.
+    pub fn remainder(&self) -> &'data [u8] {
+        self.data
+    }
+}
+
+impl<'data> Iterator for Tokens<'data> {
+    type Item = Token<'data>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (v, n) = Token::parse(self.data)?;
+        self.data = &self.data[n..];
+        Some(v)
+    }
+}
+
+/// Return value size
+///
+/// Return the size of the complete bencoded value at the start of `data`.
+/// Return `None` if the value is truncated or invalid, a dictionary key is
+/// not a byte string, or the value is nested deeper than `DEPTH_MAX`.
+pub fn value_size(data: &[u8]) -> Option<usize> {
+    // State of each open container.
+    const IN_LIST: u8 = 0;
+    const IN_DICT_KEY: u8 = 1;
+    const IN_DICT_VALUE: u8 = 2;
+
+    let mut stack = [IN_LIST; DEPTH_MAX];
+    let mut depth = 0;
+    let mut o = 0;
+
+    loop {
+        let (t, n) = Token::parse(&data[o..])?;
+        o += n;
+
+        match t {
+            Token::End => {
+                if depth == 0 || stack[depth - 1] == IN_DICT_VALUE {
+                    return None;
+                }
+                depth -= 1;
+            },
+            _ if depth > 0 && stack[depth - 1] == IN_DICT_KEY => {
+                if !matches!(t, Token::String(_)) {
+                    return None;
+                }
+                stack[depth - 1] = IN_DICT_VALUE;
+                continue;
+            },
+            Token::List | Token::Dict => {
+                *stack.get_mut(depth)? = if t == Token::Dict { IN_DICT_KEY } else { IN_LIST };
+                depth += 1;
+                continue;
+            },
+            _ => {},
+        }
+
+        // A complete value was consumed.
+        if depth == 0 {
+            return Some(o);
+        }
+        if stack[depth - 1] == IN_DICT_VALUE {
+            stack[depth - 1] = IN_DICT_KEY;
+        }
+    }
+}
+
+/// Find dictionary entry
+///
+/// Return the encoding of the value of the entry with the given key in the
+/// bencoded dictionary `data`, or `None` if there is none, or the
+/// dictionary is invalid.
+pub fn find_key<'data>(data: &'data [u8], key: &[u8]) -> Option<&'data [u8]> {
+    if *data.first()? != DICT {
+        return None;
+    }
+
+    let mut o = 1;
+    loop {
+        let (k, n) = Token::parse(&data[o..])?;
+        let k = match k {
+            Token::String(v) => v,
+            _ => return None,
+        };
+        o += n;
+
+        let m = value_size(&data[o..])?;
+        if k == key {
+            return Some(&data[o..o + m]);
+        }
+        o += m;
+    }
+}
+
+impl Handshake {
+    /// Import a handshake from a byte slice
+    ///
+    /// Create a new handshake from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 68]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 68], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Check protocol name
+    pub fn is_valid(&self) -> bool {
+        self.protocol_length as usize == PROTOCOL.len() && self.protocol == PROTOCOL
+    }
+}
+
+impl Request {
+    /// Import a request from a byte slice
+    ///
+    /// Create a new request from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 12]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 12], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl<'data> Message<'data> {
+    /// Parse peer message
+    ///
+    /// Parse the length-prefixed message at the start of `data`. Return
+    /// `None` if the message is truncated.
+    pub fn parse(data: &'data [u8]) -> Option<Self> {
+        let length = u32::from_be_bytes(data.get(..4)?.try_into().ok()?) as usize;
+        let end = 4usize.checked_add(length)?;
+        let v = data.get(4..end)?;
+
+        match v.split_first() {
+            Some((id, payload)) => Some(Self { id: Some(*id), payload, size: end }),
+            None => Some(Self { id: None, payload: v, size: end }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::specs::int::ForeignEndian;
+
+    // Minimal metainfo file with an `announce` URL and an `info`
+    // dictionary.
+    const METAINFO: &[u8] = b"d8:announce3:url4:infod6:lengthi42e4:name1:a5:filesl\
+        le0:e5:extrai-7eee";
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<Handshake>(), 68);
+        assert_eq!(align_of::<Handshake>(), 1);
+        assert_eq!(size_of::<Request>(), 12);
+        assert_eq!(align_of::<Request>(), 4);
+    }
+
+    // Scan tokens and values.
+    #[test]
+    fn verify_bencode() {
+        let mut t = Tokens::new(b"li42e4:spamde0:i-1ee");
+        assert_eq!(t.next(), Some(Token::List));
+        assert_eq!(t.next(), Some(Token::Integer(42)));
+        assert_eq!(t.next(), Some(Token::String(b"spam")));
+        assert_eq!(t.next(), Some(Token::Dict));
+        assert_eq!(t.next(), Some(Token::End));
+        assert_eq!(t.next(), Some(Token::String(b"")));
+        assert_eq!(t.next(), Some(Token::Integer(-1)));
+        assert_eq!(t.next(), Some(Token::End));
+        assert_eq!(t.next(), None);
+        assert!(t.remainder().is_empty());
+
+        for v in [&b"i-0e"[..], b"i03e", b"ie", b"i1", b"01:a", b"2:a", b"-1:a"] {
+            assert_eq!(Token::parse(v), None);
+        }
+
+        assert_eq!(value_size(METAINFO), Some(METAINFO.len()));
+        assert_eq!(value_size(b"i1ei2e"), Some(3));
+        assert_eq!(value_size(b"di1e1:ae"), None);
+        assert_eq!(value_size(b"d1:ae"), None);
+        assert_eq!(value_size(b"l"), None);
+        assert_eq!(value_size(&[LIST; DEPTH_MAX + 1]), None);
+
+        let info = find_key(METAINFO, b"info").unwrap();
+        assert_eq!(info.len(), 47);
+        assert_eq!(find_key(info, b"length"), Some(&b"i42e"[..]));
+        assert_eq!(find_key(info, b"extra"), Some(&b"i-7e"[..]));
+        assert_eq!(find_key(METAINFO, b"name"), None);
+    }
+
+    // Parse a handshake and peer messages.
+    #[test]
+    fn verify_peer_wire() {
+        let mut data = [0; 68];
+        data[0] = 19;
+        data[1..20].copy_from_slice(&PROTOCOL);
+        data[25] = RESERVED_EXTENSION_MASK;
+        data[28..48].fill(0xaa);
+        let h = Handshake::from_bytes(&data);
+        assert!(h.is_valid());
+        assert_ne!(h.reserved[RESERVED_EXTENSION_BYTE] & RESERVED_EXTENSION_MASK, 0);
+        assert_eq!(h.info_hash, [0xaa; 20]);
+
+        let data = [
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0d,
+            0x06, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x40,
+            0x00, 0x00, 0x00, 0x40, 0x00,
+        ];
+        let m = Message::parse(&data).unwrap();
+        assert_eq!((m.id, m.size), (None, 4));
+        let m = Message::parse(&data[4..]).unwrap();
+        assert_eq!((m.id, m.size), (Some(MESSAGE_REQUEST), 17));
+        let r = Request::from_bytes(m.payload.try_into().unwrap());
+        assert_eq!(r.index.to_native(), 1);
+        assert_eq!(r.begin.to_native(), BLOCK_SIZE);
+        assert_eq!(r.length.to_native(), BLOCK_SIZE);
+        assert!(Message::parse(&data[4..20]).is_none());
+    }
+}
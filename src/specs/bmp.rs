@@ -0,0 +1,377 @@
+//! Windows Bitmap Format
+//!
+//! The Windows Bitmap format (BMP) stores device-independent bitmaps (DIB).
+//! Due to its simplicity, it is commonly used for firmware splash screens and
+//! boot logos. A BMP file starts with a `FileHeader`, followed by one of
+//! several DIB headers, an optional color table, and the pixel array.
+//!
+//! The DIB header exists in multiple revisions, each extending the previous
+//! one. The revision is identified by the size of the header, stored in its
+//! first field:
+//!
+//!  * `CoreHeader` (12 bytes): The original OS/2 header with 16-bit
+//!    dimensions.
+//!  * `InfoHeader` (40 bytes): The Windows 3.x header, which is by far the
+//!    most common one.
+//!  * `V4Header` (108 bytes): Adds color masks and color space information.
+//!  * `V5Header` (124 bytes): Adds rendering intent and ICC profile data.
+//!
+//! The pixel array consists of rows, each padded to a multiple of 4 bytes
+//! (see `row_stride()`). Rows are stored bottom-up, unless the height is
+//! negative, in which case the image is stored top-down.
+//!
+//! Color masks for `COMPRESSION_BITFIELDS` are either part of the DIB header
+//! (version 4 and later), or follow an `InfoHeader` directly.
+//!
+//! All multi-byte integers are encoded as little-endian.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::int;
+
+/// Magic Signature
+///
+/// The initial 2 bytes of a BMP file ("BM").
+pub const MAGIC: [u8; 2] = [0x42, 0x4d];
+
+pub const CORE_HEADER_SIZE: u32 = 12;
+pub const INFO_HEADER_SIZE: u32 = 40;
+pub const V2_HEADER_SIZE: u32 = 52;
+pub const V3_HEADER_SIZE: u32 = 56;
+pub const OS22X_HEADER_SIZE: u32 = 64;
+pub const V4_HEADER_SIZE: u32 = 108;
+pub const V5_HEADER_SIZE: u32 = 124;
+
+pub const COMPRESSION_RGB: u32 = 0;
+pub const COMPRESSION_RLE8: u32 = 1;
+pub const COMPRESSION_RLE4: u32 = 2;
+pub const COMPRESSION_BITFIELDS: u32 = 3;
+pub const COMPRESSION_JPEG: u32 = 4;
+pub const COMPRESSION_PNG: u32 = 5;
+pub const COMPRESSION_ALPHABITFIELDS: u32 = 6;
+pub const COMPRESSION_CMYK: u32 = 11;
+pub const COMPRESSION_CMYKRLE8: u32 = 12;
+pub const COMPRESSION_CMYKRLE4: u32 = 13;
+
+pub const CS_CALIBRATED_RGB: u32 = 0x0000_0000;
+pub const CS_SRGB: u32 = 0x7352_4742;
+pub const CS_WINDOWS_COLOR_SPACE: u32 = 0x5769_6e20;
+pub const CS_PROFILE_LINKED: u32 = 0x4c49_4e4b;
+pub const CS_PROFILE_EMBEDDED: u32 = 0x4d42_4544;
+
+pub const INTENT_BUSINESS: u32 = 0x0000_0001;
+pub const INTENT_GRAPHICS: u32 = 0x0000_0002;
+pub const INTENT_IMAGES: u32 = 0x0000_0004;
+pub const INTENT_ABS_COLORIMETRIC: u32 = 0x0000_0008;
+
+/// File Header
+///
+/// The static header at offset 0 of a BMP file (BITMAPFILEHEADER). Since its
+/// 32-bit fields are not naturally aligned, this structure is packed.
+#[repr(C, packed)]
+pub struct FileHeader {
+    /// The static signature identifying the format. This must match `MAGIC`.
+    pub bf_type: [u8; 2],
+
+    /// Size of the file in bytes.
+    pub bf_size: int::u32le,
+
+    /// Reserved, must be 0.
+    pub bf_reserved1: int::u16le,
+
+    /// Reserved, must be 0.
+    pub bf_reserved2: int::u16le,
+
+    /// Offset of the pixel array relative to the start of the file.
+    pub bf_off_bits: int::u32le,
+}
+
+/// Core Header
+///
+/// The OS/2 DIB header (BITMAPCOREHEADER). Its color table consists of
+/// `Rgb` entries rather than `RgbQuad` entries.
+#[repr(C)]
+pub struct CoreHeader {
+    /// Size of this header, must be `CORE_HEADER_SIZE`.
+    pub bc_size: int::u32le,
+
+    /// Width of the image in pixels.
+    pub bc_width: int::u16le,
+
+    /// Height of the image in pixels.
+    pub bc_height: int::u16le,
+
+    /// Number of planes, must be 1.
+    pub bc_planes: int::u16le,
+
+    /// Number of bits per pixel.
+    pub bc_bit_count: int::u16le,
+}
+
+/// Info Header
+///
+/// The Windows DIB header (BITMAPINFOHEADER).
+#[repr(C)]
+pub struct InfoHeader {
+    /// Size of the DIB header, `INFO_HEADER_SIZE` or bigger for later
+    /// revisions.
+    pub bi_size: int::u32le,
+
+    /// Width of the image in pixels.
+    pub bi_width: int::i32le,
+
+    /// Height of the image in pixels. If negative, the image is stored
+    /// top-down.
+    pub bi_height: int::i32le,
+
+    /// Number of planes, must be 1.
+    pub bi_planes: int::u16le,
+
+    /// Number of bits per pixel (1, 4, 8, 16, 24, or 32; 0 for JPEG and PNG).
+    pub bi_bit_count: int::u16le,
+
+    /// Compression type (`COMPRESSION_*`).
+    pub bi_compression: int::u32le,
+
+    /// Size of the pixel array in bytes. Can be 0 for `COMPRESSION_RGB`.
+    pub bi_size_image: int::u32le,
+
+    /// Horizontal resolution in pixels per meter.
+    pub bi_x_pels_per_meter: int::i32le,
+
+    /// Vertical resolution in pixels per meter.
+    pub bi_y_pels_per_meter: int::i32le,
+
+    /// Number of color table entries, or 0 for the maximum of the bit depth.
+    pub bi_clr_used: int::u32le,
+
+    /// Number of important colors, or 0 if all are important.
+    pub bi_clr_important: int::u32le,
+}
+
+/// CIE XYZ Coordinate
+///
+/// The coordinates of a color endpoint in the CIE XYZ space. Values are
+/// fixed-point numbers with 2 integer and 30 fractional bits.
+#[repr(C)]
+pub struct CieXyz {
+    pub x: int::i32le,
+    pub y: int::i32le,
+    pub z: int::i32le,
+}
+
+/// Version 4 Header
+///
+/// The DIB header extended with color masks and color space information
+/// (BITMAPV4HEADER).
+#[repr(C)]
+pub struct V4Header {
+    /// The base header, with `bi_size` set to `V4_HEADER_SIZE` or bigger.
+    pub info: InfoHeader,
+
+    /// Color mask of the red component.
+    pub red_mask: int::u32le,
+
+    /// Color mask of the green component.
+    pub green_mask: int::u32le,
+
+    /// Color mask of the blue component.
+    pub blue_mask: int::u32le,
+
+    /// Color mask of the alpha component.
+    pub alpha_mask: int::u32le,
+
+    /// Color space type (`CS_*`).
+    pub cs_type: int::u32le,
+
+    /// Red, green, and blue endpoints for `CS_CALIBRATED_RGB`.
+    pub endpoints: [CieXyz; 3],
+
+    /// Red gamma for `CS_CALIBRATED_RGB`, as unsigned 16.16 fixed-point.
+    pub gamma_red: int::u32le,
+
+    /// Green gamma for `CS_CALIBRATED_RGB`, as unsigned 16.16 fixed-point.
+    pub gamma_green: int::u32le,
+
+    /// Blue gamma for `CS_CALIBRATED_RGB`, as unsigned 16.16 fixed-point.
+    pub gamma_blue: int::u32le,
+}
+
+/// Version 5 Header
+///
+/// The DIB header extended with rendering intent and profile information
+/// (BITMAPV5HEADER).
+#[repr(C)]
+pub struct V5Header {
+    /// The version 4 header, with `bi_size` set to `V5_HEADER_SIZE`.
+    pub v4: V4Header,
+
+    /// Rendering intent (`INTENT_*`).
+    pub intent: int::u32le,
+
+    /// Offset of the profile data relative to the start of this header, for
+    /// `CS_PROFILE_LINKED` and `CS_PROFILE_EMBEDDED`.
+    pub profile_data: int::u32le,
+
+    /// Size of the profile data in bytes.
+    pub profile_size: int::u32le,
+
+    /// Reserved, must be 0.
+    pub reserved: int::u32le,
+}
+
+/// Color Table Entry
+///
+/// A color table entry following `InfoHeader` and later revisions (RGBQUAD).
+#[repr(C)]
+pub struct RgbQuad {
+    pub blue: u8,
+    pub green: u8,
+    pub red: u8,
+    pub reserved: u8,
+}
+
+/// Core Color Table Entry
+///
+/// A color table entry following `CoreHeader` (RGBTRIPLE).
+#[repr(C)]
+pub struct Rgb {
+    pub blue: u8,
+    pub green: u8,
+    pub red: u8,
+}
+
+impl FileHeader {
+    /// Import a file header from a byte slice
+    ///
+    /// Create a new file header from a byte slice, copying the data over.
+    /// The data is copied verbatim without any conversion.
+    pub fn from_bytes(data: &[u8; 14]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 14], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl InfoHeader {
+    /// Import an info header from a byte slice
+    ///
+    /// Create a new info header from a byte slice, copying the data over.
+    /// The data is copied verbatim without any conversion.
+    pub fn from_bytes(data: &[u8; 40]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 40], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Check row order
+    ///
+    /// Return whether the pixel array is stored top-down (i.e., the height is
+    /// negative).
+    pub fn is_top_down(&self) -> bool {
+        int::ForeignEndian::to_native(self.bi_height) < 0
+    }
+
+    /// Return row stride
+    ///
+    /// Return the size of a single row of the pixel array in bytes, including
+    /// its padding. See `row_stride()`.
+    pub fn row_stride(&self) -> Option<usize> {
+        let width = int::ForeignEndian::to_native(self.bi_width);
+        let bits = int::ForeignEndian::to_native(self.bi_bit_count);
+
+        if width < 0 {
+            return None;
+        }
+
+        row_stride(width as u32, bits)
+    }
+}
+
+/// Calculate Row Stride
+///
+/// Return the size of a single row of the pixel array in bytes. Each row is
+/// padded to a multiple of 4 bytes, hence the stride is
+/// `((width * bit_count + 31) / 32) * 4`. `None` is returned on overflow.
+pub fn row_stride(width: u32, bit_count: u16) -> Option<usize> {
+    let bits = (width as usize).checked_mul(bit_count as usize)?;
+    Some((bits.checked_add(31)? / 32) * 4)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<FileHeader>(), 14);
+        assert_eq!(align_of::<FileHeader>(), 1);
+        assert_eq!(size_of::<CoreHeader>(), CORE_HEADER_SIZE as usize);
+        assert_eq!(size_of::<InfoHeader>(), INFO_HEADER_SIZE as usize);
+        assert_eq!(size_of::<CieXyz>(), 12);
+        assert_eq!(size_of::<V4Header>(), V4_HEADER_SIZE as usize);
+        assert_eq!(size_of::<V5Header>(), V5_HEADER_SIZE as usize);
+        assert_eq!(align_of::<V5Header>(), 4);
+        assert_eq!(size_of::<RgbQuad>(), 4);
+        assert_eq!(size_of::<Rgb>(), 3);
+    }
+
+    // Decode the headers of a 2x2 24-bit top-down bitmap.
+    #[test]
+    fn verify_headers() {
+        let data: [u8; 54] = [
+            0x42, 0x4d, 0x46, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x36, 0x00, 0x00, 0x00, 0x28, 0x00,
+            0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0xfe, 0xff,
+            0xff, 0xff, 0x01, 0x00, 0x18, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x10, 0x00, 0x00, 0x00, 0x13, 0x0b,
+            0x00, 0x00, 0x13, 0x0b, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ];
+
+        let f = FileHeader::from_bytes(data[..14].try_into().unwrap());
+        assert_eq!(f.bf_type, MAGIC);
+        assert_eq!(int::ForeignEndian::to_native(f.bf_size), 70);
+        assert_eq!(int::ForeignEndian::to_native(f.bf_off_bits), 54);
+
+        let i = InfoHeader::from_bytes(data[14..].try_into().unwrap());
+        assert_eq!(int::ForeignEndian::to_native(i.bi_size), INFO_HEADER_SIZE);
+        assert_eq!(int::ForeignEndian::to_native(i.bi_width), 2);
+        assert_eq!(int::ForeignEndian::to_native(i.bi_height), -2);
+        assert_eq!(int::ForeignEndian::to_native(i.bi_bit_count), 24);
+        assert_eq!(int::ForeignEndian::to_native(i.bi_compression), COMPRESSION_RGB);
+        assert_eq!(int::ForeignEndian::to_native(i.bi_x_pels_per_meter), 2835);
+        assert!(i.is_top_down());
+        assert_eq!(i.row_stride(), Some(8));
+    }
+
+    // Verify the row padding rule for different bit depths.
+    #[test]
+    fn verify_row_stride() {
+        assert_eq!(row_stride(0, 24), Some(0));
+        assert_eq!(row_stride(1, 1), Some(4));
+        assert_eq!(row_stride(32, 1), Some(4));
+        assert_eq!(row_stride(33, 1), Some(8));
+        assert_eq!(row_stride(3, 4), Some(4));
+        assert_eq!(row_stride(5, 8), Some(8));
+        assert_eq!(row_stride(3, 24), Some(12));
+        assert_eq!(row_stride(3, 32), Some(12));
+    }
+}
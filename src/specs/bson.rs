@@ -0,0 +1,420 @@
+//! Binary JSON
+//!
+//! BSON documents start with their total size as 32-bit integer, including
+//! the size itself, followed by a list of elements, and terminated by a 0
+//! byte (see `split()`). Each element consists of a type byte (`TYPE_*`),
+//! the 0-terminated element name, and the value (see `Elements`). Arrays
+//! are encoded as documents, with the decimal indices as names.
+//!
+//! Most value types have a fixed size (see `value_size()`). Strings, as well
+//! as JavaScript code and symbols, are prefixed with their size as 32-bit
+//! integer, including the 0-terminator. Binary data is prefixed with its
+//! size, excluding the subtype (see `BinaryHeader`). Embedded documents and
+//! arrays, as well as code with scope, carry their total size themselves.
+//!
+//! All multi-byte integers are encoded as little-endian, with the exception
+//! of the timestamp and counter of object identifiers, which are encoded as
+//! big-endian.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::int;
+
+/// Minimum Document Size
+pub const DOCUMENT_SIZE_MIN: usize = 5;
+
+pub const OBJECT_ID_SIZE: usize = 12;
+pub const BINARY_HEADER_SIZE: usize = 5;
+pub const TIMESTAMP_SIZE: usize = 8;
+pub const DECIMAL128_SIZE: usize = 16;
+
+pub const TYPE_DOUBLE: u8 = 0x01;
+pub const TYPE_STRING: u8 = 0x02;
+pub const TYPE_DOCUMENT: u8 = 0x03;
+pub const TYPE_ARRAY: u8 = 0x04;
+pub const TYPE_BINARY: u8 = 0x05;
+pub const TYPE_UNDEFINED: u8 = 0x06;
+pub const TYPE_OBJECT_ID: u8 = 0x07;
+pub const TYPE_BOOLEAN: u8 = 0x08;
+pub const TYPE_DATETIME: u8 = 0x09;
+pub const TYPE_NULL: u8 = 0x0a;
+pub const TYPE_REGEX: u8 = 0x0b;
+pub const TYPE_DB_POINTER: u8 = 0x0c;
+pub const TYPE_JAVASCRIPT: u8 = 0x0d;
+pub const TYPE_SYMBOL: u8 = 0x0e;
+pub const TYPE_JAVASCRIPT_SCOPE: u8 = 0x0f;
+pub const TYPE_INT32: u8 = 0x10;
+pub const TYPE_TIMESTAMP: u8 = 0x11;
+pub const TYPE_INT64: u8 = 0x12;
+pub const TYPE_DECIMAL128: u8 = 0x13;
+pub const TYPE_MAX_KEY: u8 = 0x7f;
+pub const TYPE_MIN_KEY: u8 = 0xff;
+
+pub const SUBTYPE_GENERIC: u8 = 0x00;
+pub const SUBTYPE_FUNCTION: u8 = 0x01;
+pub const SUBTYPE_BINARY_OLD: u8 = 0x02;
+pub const SUBTYPE_UUID_OLD: u8 = 0x03;
+pub const SUBTYPE_UUID: u8 = 0x04;
+pub const SUBTYPE_MD5: u8 = 0x05;
+pub const SUBTYPE_ENCRYPTED: u8 = 0x06;
+pub const SUBTYPE_COLUMN: u8 = 0x07;
+pub const SUBTYPE_SENSITIVE: u8 = 0x08;
+pub const SUBTYPE_VECTOR: u8 = 0x09;
+pub const SUBTYPE_USER_MIN: u8 = 0x80;
+
+pub const BOOLEAN_FALSE: u8 = 0x00;
+pub const BOOLEAN_TRUE: u8 = 0x01;
+
+/// Object Identifier
+#[repr(C)]
+pub struct ObjectId {
+    /// Creation time in seconds since the Unix epoch.
+    pub timestamp: int::u32be,
+
+    /// Random value unique to the machine and process.
+    pub random: [u8; 5],
+
+    /// Incrementing counter, initialized to a random value.
+    pub counter: int::u24be,
+}
+
+/// Binary Data Header
+///
+/// Since the size of the header is not a multiple of the alignment of
+/// `length`, this structure is packed.
+#[repr(C, packed)]
+pub struct BinaryHeader {
+    /// Size of the binary data, excluding the subtype.
+    pub length: int::u32le,
+
+    /// Binary subtype (`SUBTYPE_*`).
+    pub subtype: u8,
+}
+
+/// Replication Timestamp
+#[repr(C)]
+pub struct Timestamp {
+    /// Incrementing ordinal for operations within a given second.
+    pub increment: int::u32le,
+
+    /// Seconds since the Unix epoch.
+    pub seconds: int::u32le,
+}
+
+/// Element Reference
+#[derive(Clone, Copy, Debug)]
+pub struct Element<'data> {
+    /// Element type (`TYPE_*`).
+    pub element_type: u8,
+
+    /// Element name, without the 0-terminator.
+    pub name: &'data [u8],
+
+    /// Encoded value.
+    pub value: &'data [u8],
+}
+
+/// Element Iterator
+///
+/// Iterate over the elements of a document, given the element list without
+/// the document size and terminator (see `split()`). Iteration stops at the
+/// first truncated element, or an element of unknown type.
+#[derive(Clone, Debug)]
+pub struct Elements<'data> {
+    data: &'data [u8],
+}
+
+impl ObjectId {
+    /// Import an object identifier from a byte slice
+    ///
+    /// Create a new object identifier from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 12]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 12], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl BinaryHeader {
+    /// Import a header from a byte slice
+    ///
+    /// Create a new header from data copied from a byte slice. No byte-order
+    /// conversions are applied.
+    pub fn from_bytes(data: &[u8; 5]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 5], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl Timestamp {
+    /// Import a timestamp from a byte slice
+    ///
+    /// Create a new timestamp from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 8]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 8], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl<'data> Element<'data> {
+    /// Return string value
+    ///
+    /// Return the content of a string, JavaScript code, or symbol value,
+    /// without the size and 0-terminator.
+    pub fn string(&self) -> Option<&'data [u8]> {
+        match self.element_type {
+            TYPE_STRING | TYPE_JAVASCRIPT | TYPE_SYMBOL => string(self.value),
+            _ => None,
+        }
+    }
+
+    /// Return binary value
+    ///
+    /// Return the subtype and content of a binary value, or `None` if the
+    /// value is truncated.
+    pub fn binary(&self) -> Option<(u8, &'data [u8])> {
+        match self.element_type {
+            TYPE_BINARY => Some((*self.value.get(4)?, self.value.get(BINARY_HEADER_SIZE..)?)),
+            _ => None,
+        }
+    }
+
+    /// Return elements of embedded documents
+    ///
+    /// Return an iterator over the elements of an embedded document or
+    /// array value.
+    pub fn elements(&self) -> Option<Elements<'data>> {
+        match self.element_type {
+            TYPE_DOCUMENT | TYPE_ARRAY => Some(Elements::new(split(self.value)?.0)),
+            _ => None,
+        }
+    }
+}
+
+impl<'data> Elements<'data> {
+    /// Create element iterator
+    ///
+    /// Create a new iterator over the element list `data`.
+    pub fn new(data: &'data [u8]) -> Self {
+        Self { data }
+    }
+
+    /// Return remaining data
+    ///
+    /// Return the data that has not been consumed by the iterator.
+    pub fn remainder(&self) -> &'data [u8] {
+        self.data
+    }
+}
+
+impl<'data> Iterator for Elements<'data> {
+    type Item = Element<'data>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (element_type, rest) = self.data.split_first()?;
+        let n = rest.iter().position(|v| *v == 0)?;
+        let name = &rest[..n];
+        let rest = &rest[n + 1..];
+        let size = value_size(*element_type, rest)?;
+
+        let value = &rest[..size];
+        self.data = &rest[size..];
+        Some(Element { element_type: *element_type, name, value })
+    }
+}
+
+// Read the 32-bit size prefix at the start of `data`.
+fn size(data: &[u8]) -> Option<usize> {
+    let v = i32::from_le_bytes(data.get(..4)?.try_into().ok()?);
+    usize::try_from(v).ok()
+}
+
+/// Split document
+///
+/// Split the document at the start of `data` into its element list, without
+/// the document size and terminator, and the data following the document.
+/// Return `None` if the document is truncated, its size is invalid, or it is
+/// not terminated.
+pub fn split(data: &[u8]) -> Option<(&[u8], &[u8])> {
+    let n = size(data)?;
+    if n < DOCUMENT_SIZE_MIN || n > data.len() || data[n - 1] != 0 {
+        return None;
+    }
+
+    Some((&data[4..n - 1], &data[n..]))
+}
+
+/// Decode string
+///
+/// Return the content of the size-prefixed string at the start of `data`,
+/// without the size and 0-terminator.
+pub fn string(data: &[u8]) -> Option<&[u8]> {
+    let n = size(data)?;
+    match n.checked_add(4).and_then(|v| data.get(4..v)) {
+        Some([v @ .., 0]) => Some(v),
+        _ => None,
+    }
+}
+
+/// Return value size
+///
+/// Return the size of the value of the given element type at the start of
+/// `data`. Return `None` if the type is unknown, or the value is
+/// truncated.
+pub fn value_size(element_type: u8, data: &[u8]) -> Option<usize> {
+    // Size of the 0-terminated string at the start of `data`.
+    let cstring = |data: &[u8]| data.iter().position(|v| *v == 0).map(|v| v + 1);
+
+    let n = match element_type {
+        TYPE_UNDEFINED | TYPE_NULL | TYPE_MIN_KEY | TYPE_MAX_KEY => 0,
+        TYPE_BOOLEAN => 1,
+        TYPE_INT32 => 4,
+        TYPE_DOUBLE | TYPE_DATETIME | TYPE_INT64 => 8,
+        TYPE_TIMESTAMP => TIMESTAMP_SIZE,
+        TYPE_OBJECT_ID => OBJECT_ID_SIZE,
+        TYPE_DECIMAL128 => DECIMAL128_SIZE,
+        TYPE_STRING | TYPE_JAVASCRIPT | TYPE_SYMBOL => 4 + string(data)?.len() + 1,
+        TYPE_DB_POINTER => 4 + string(data)?.len() + 1 + OBJECT_ID_SIZE,
+        TYPE_BINARY => BINARY_HEADER_SIZE + size(data)?,
+        TYPE_DOCUMENT | TYPE_ARRAY | TYPE_JAVASCRIPT_SCOPE => size(data)?,
+        TYPE_REGEX => {
+            let pattern = cstring(data)?;
+            pattern + cstring(&data[pattern..])?
+        },
+        _ => return None,
+    };
+
+    if n > data.len() {
+        return None;
+    }
+    Some(n)
+}
+
+/// Find element
+///
+/// Return the first element with the given name in the element list
+/// `data`.
+pub fn find_element<'data>(data: &'data [u8], name: &[u8]) -> Option<Element<'data>> {
+    Elements::new(data).find(|v| v.name == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::specs::int::ForeignEndian;
+
+    // Document `{"hello": "world"}` from the specification.
+    const HELLO: [u8; 22] = [
+        0x16, 0x00, 0x00, 0x00, 0x02, 0x68, 0x65, 0x6c,
+        0x6c, 0x6f, 0x00, 0x06, 0x00, 0x00, 0x00, 0x77,
+        0x6f, 0x72, 0x6c, 0x64, 0x00, 0x00,
+    ];
+
+    // Document `{"BSON": ["awesome", 5.05, 1986]}` from the specification.
+    const AWESOME: [u8; 49] = [
+        0x31, 0x00, 0x00, 0x00, 0x04, 0x42, 0x53, 0x4f,
+        0x4e, 0x00, 0x26, 0x00, 0x00, 0x00, 0x02, 0x30,
+        0x00, 0x08, 0x00, 0x00, 0x00, 0x61, 0x77, 0x65,
+        0x73, 0x6f, 0x6d, 0x65, 0x00, 0x01, 0x31, 0x00,
+        0x33, 0x33, 0x33, 0x33, 0x33, 0x33, 0x14, 0x40,
+        0x10, 0x32, 0x00, 0xc2, 0x07, 0x00, 0x00, 0x00,
+        0x00,
+    ];
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<ObjectId>(), OBJECT_ID_SIZE);
+        assert_eq!(align_of::<ObjectId>(), 4);
+        assert_eq!(size_of::<BinaryHeader>(), BINARY_HEADER_SIZE);
+        assert_eq!(align_of::<BinaryHeader>(), 1);
+        assert_eq!(size_of::<Timestamp>(), TIMESTAMP_SIZE);
+        assert_eq!(align_of::<Timestamp>(), 4);
+    }
+
+    // Iterate the elements of the documents of the specification.
+    #[test]
+    fn verify_documents() {
+        let (elements, rest) = split(&HELLO).unwrap();
+        assert!(rest.is_empty());
+        let e = find_element(elements, b"hello").unwrap();
+        assert_eq!(e.element_type, TYPE_STRING);
+        assert_eq!(e.string(), Some(&b"world"[..]));
+        assert_eq!(split(&HELLO[..21]), None);
+
+        let (elements, _) = split(&AWESOME).unwrap();
+        let mut e = Elements::new(elements);
+        let v = e.next().unwrap();
+        assert_eq!((v.element_type, v.name), (TYPE_ARRAY, &b"BSON"[..]));
+        assert!(e.next().is_none());
+
+        let mut array = v.elements().unwrap();
+        let v = array.next().unwrap();
+        assert_eq!((v.name, v.string()), (&b"0"[..], Some(&b"awesome"[..])));
+        let v = array.next().unwrap();
+        assert_eq!((v.element_type, v.name), (TYPE_DOUBLE, &b"1"[..]));
+        assert_eq!(f64::from_le_bytes(v.value.try_into().unwrap()), 5.05);
+        let v = array.next().unwrap();
+        assert_eq!((v.element_type, v.value), (TYPE_INT32, &1986i32.to_le_bytes()[..]));
+        assert!(array.next().is_none());
+        assert!(array.remainder().is_empty());
+    }
+
+    // Decode fixed-size values.
+    #[test]
+    fn verify_values() {
+        let id = ObjectId::from_bytes(&[
+            0x65, 0x53, 0xf1, 0x00, 0x01, 0x02, 0x03, 0x04,
+            0x05, 0x00, 0x00, 0x2a,
+        ]);
+        assert_eq!(id.timestamp.to_native(), 1_700_000_000);
+        assert_eq!(id.random, [1, 2, 3, 4, 5]);
+        assert_eq!(id.counter.to_native(), 42);
+
+        let data = [0x03, 0x00, 0x00, 0x00, SUBTYPE_MD5, 0xaa, 0xbb, 0xcc];
+        let h = BinaryHeader::from_bytes(data[..5].try_into().unwrap());
+        assert_eq!({ h.length }.to_native(), 3);
+        assert_eq!(value_size(TYPE_BINARY, &data), Some(8));
+        assert_eq!(value_size(TYPE_BINARY, &data[..7]), None);
+
+        let e = Element { element_type: TYPE_BINARY, name: b"", value: &data };
+        assert_eq!(e.binary(), Some((SUBTYPE_MD5, &[0xaa, 0xbb, 0xcc][..])));
+        let e = Element { element_type: TYPE_BINARY, name: b"", value: &data[..4] };
+        assert!(e.binary().is_none());
+
+        let t = Timestamp::from_bytes(&[0x01, 0x00, 0x00, 0x00, 0x00, 0xf1, 0x53, 0x65]);
+        assert_eq!(t.increment.to_native(), 1);
+        assert_eq!(t.seconds.to_native(), 1_700_000_000);
+
+        assert_eq!(value_size(TYPE_REGEX, b"a*\0i\0rest"), Some(5));
+        assert_eq!(value_size(TYPE_NULL, &[]), Some(0));
+        assert_eq!(value_size(TYPE_STRING, &[0x02, 0x00, 0x00, 0x00, 0x61, 0x62]), None);
+        assert_eq!(value_size(0x20, &data), None);
+    }
+}
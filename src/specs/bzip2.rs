@@ -0,0 +1,303 @@
+//! bzip2 Stream Framing
+//!
+//! The bzip2 format compresses data using the Burrows-Wheeler transform
+//! followed by move-to-front and Huffman coding. Data is split into blocks of
+//! up to 900k bytes, each compressed independently. This module only covers
+//! the framing of a bzip2 stream, not the compression itself.
+//!
+//! A bzip2 stream starts with a 4-byte header: the signature "BZh" followed
+//! by an ASCII digit '1' to '9', which specifies the block size in units of
+//! 100k bytes. What follows is a sequence of compressed blocks, terminated by
+//! a stream footer.
+//!
+//! Apart from the stream header, nothing in a bzip2 stream is byte aligned.
+//! Blocks and the footer are packed at the bit-level without any padding
+//! between them. Only the end of the stream is padded to the next byte
+//! boundary. All values are stored MSB-first.
+//!
+//! Every block starts with a 48-bit magic number (the BCD representation of
+//! pi), followed by the 32-bit CRC of the uncompressed block data. The stream
+//! footer starts with a different 48-bit magic number (the BCD representation
+//! of the square root of pi), followed by a 32-bit CRC combining all block
+//! CRCs of the stream.
+//!
+//! Since the block magic is not escaped in any way, the same bit-sequence can
+//! occur in compressed data. Tooling that locates blocks by scanning for
+//! markers (e.g., to decompress blocks in parallel) must verify candidates by
+//! decoding them.
+//!
+//! Multiple bzip2 streams can be concatenated and are then considered a single
+//! stream by most decoders.
+
+use crate::specs::{bits, checksum};
+
+/// Stream Signature
+///
+/// The initial 3 bytes of every bzip2 stream are set to "BZh". The 'h'
+/// denotes Huffman coding, which replaced arithmetic coding used in the
+/// predecessor format.
+pub const MAGIC: [u8; 3] = [0x42, 0x5a, 0x68];
+
+/// Minimum Block Size Level
+///
+/// The lowest valid level byte of the stream header (ASCII '1').
+pub const LEVEL_MIN: u8 = 0x31;
+
+/// Maximum Block Size Level
+///
+/// The highest valid level byte of the stream header (ASCII '9').
+pub const LEVEL_MAX: u8 = 0x39;
+
+/// Block Size Unit
+///
+/// The level of a stream header specifies the maximum uncompressed block size
+/// in units of this size.
+pub const BLOCK_SIZE_UNIT: usize = 100_000;
+
+/// Block Header Magic
+///
+/// The 48-bit magic number that starts every compressed block. It is the BCD
+/// encoding of the first 12 digits of pi.
+pub const BLOCK_MAGIC: u64 = 0x3141_5926_5359;
+
+/// Stream Footer Magic
+///
+/// The 48-bit magic number that starts the stream footer. It is the BCD
+/// encoding of the first 12 digits of the square root of pi.
+pub const FOOTER_MAGIC: u64 = 0x1772_4538_5090;
+
+/// Size of the Magic Numbers in Bits
+pub const MAGIC_BITS: u32 = 48;
+
+/// Size of the Block and Stream CRCs in Bits
+pub const CRC_BITS: u32 = 32;
+
+/// Size of the Block Origin Pointer in Bits
+pub const ORIG_PTR_BITS: u32 = 24;
+
+/// Stream Header
+///
+/// The static 4-byte header at the start of every bzip2 stream.
+#[repr(C)]
+pub struct Header {
+    /// The static signature identifying the format. This must match `MAGIC`.
+    pub magic: [u8; 3],
+
+    /// The block size level as ASCII digit in the range `LEVEL_MIN` to
+    /// `LEVEL_MAX`.
+    pub level: u8,
+}
+
+/// Block Header
+///
+/// The bit-packed header that follows the block magic of every compressed
+/// block. It is not byte-aligned and thus needs to be read via a bit reader.
+pub struct BlockHeader {
+    /// The CRC of the uncompressed data of this block.
+    pub crc: u32,
+
+    /// Whether the block was randomised. This is deprecated and no longer
+    /// produced by any encoder, but decoders are still expected to support
+    /// it.
+    pub randomised: bool,
+
+    /// The starting pointer into the BWT-transformed block (24-bit).
+    pub orig_ptr: u32,
+}
+
+/// Stream Marker
+///
+/// The types of markers that can be located in a bzip2 stream via their
+/// magic numbers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Marker {
+    /// A compressed block header, identified by `BLOCK_MAGIC`.
+    Block,
+    /// The stream footer, identified by `FOOTER_MAGIC`.
+    Footer,
+}
+
+impl Header {
+    /// Import a header from a byte slice
+    ///
+    /// Create a new header structure from a byte slice, copying the data over.
+    pub fn from_bytes(data: &[u8; 4]) -> Self {
+        Self {
+            magic: [data[0], data[1], data[2]],
+            level: data[3],
+        }
+    }
+
+    /// Convert to byte array
+    ///
+    /// Return the byte representation of this header.
+    pub fn to_bytes(&self) -> [u8; 4] {
+        [self.magic[0], self.magic[1], self.magic[2], self.level]
+    }
+
+    /// Return maximum block size
+    ///
+    /// Return the maximum uncompressed block size as specified by the level
+    /// of this header, or `None` if the level is invalid.
+    pub fn block_size(&self) -> Option<usize> {
+        block_size(self.level)
+    }
+}
+
+impl BlockHeader {
+    /// Read a block header
+    ///
+    /// Read a block header from a bit reader, positioned directly after the
+    /// block magic. Returns `None` if the data is truncated.
+    pub fn read(reader: &mut bits::Reader) -> Option<Self> {
+        let crc = reader.read(CRC_BITS)? as u32;
+        let randomised = reader.read_bit()?;
+        let orig_ptr = reader.read(ORIG_PTR_BITS)? as u32;
+
+        Some(Self {
+            crc,
+            randomised,
+            orig_ptr,
+        })
+    }
+}
+
+/// Calculate Block Size
+///
+/// Return the maximum uncompressed block size for the given level byte of a
+/// stream header, or `None` if the level is invalid.
+pub fn block_size(level: u8) -> Option<usize> {
+    if (LEVEL_MIN..=LEVEL_MAX).contains(&level) {
+        Some((level - LEVEL_MIN + 1) as usize * BLOCK_SIZE_UNIT)
+    } else {
+        None
+    }
+}
+
+/// Update Block CRC
+///
+/// Continue a block CRC calculation with more data. bzip2 uses a CRC-32 with
+/// the polynomial `0x04c11db7` in non-reflected form (MSB-first). The initial
+/// value is `0xffffffff` and the final value is inverted. This function
+/// expects and returns the raw intermediate value (i.e., without the final
+/// inversion).
+pub fn crc32_update(crc: u32, data: &[u8]) -> u32 {
+    checksum::crc32_msb_update(crc, data)
+}
+
+/// Calculate Block CRC
+///
+/// Calculate the CRC of the uncompressed data of a block, as stored in the
+/// block header.
+pub fn crc32(data: &[u8]) -> u32 {
+    !crc32_update(0xffff_ffff, data)
+}
+
+/// Combine Block CRCs
+///
+/// The stream CRC in the footer is calculated by rotating the current stream
+/// CRC left by one bit and then XOR'ing the next block CRC. The initial stream
+/// CRC is 0. This function performs a single step of this calculation.
+pub fn combine_crc(stream_crc: u32, block_crc: u32) -> u32 {
+    stream_crc.rotate_left(1) ^ block_crc
+}
+
+/// Locate Next Marker
+///
+/// Scan the bit reader for the next block or footer magic, starting at its
+/// current position. Any bit offset is considered, since markers are not
+/// aligned. On success, the type of marker and the bit offset of its start
+/// are returned, and the reader is positioned directly after the magic. On
+/// failure, the reader is positioned at the end of the data.
+///
+/// Note that compressed data can contain the magic sequences by chance, so
+/// any match must be considered a candidate only.
+pub fn find_marker(reader: &mut bits::Reader) -> Option<(Marker, usize)> {
+    let mask = (1u64 << MAGIC_BITS) - 1;
+    let start = reader.position();
+    let mut window = match reader.read(MAGIC_BITS) {
+        Some(v) => v,
+        None => {
+            reader.seek(start + reader.remaining());
+            return None;
+        },
+    };
+
+    loop {
+        if window == BLOCK_MAGIC {
+            return Some((Marker::Block, reader.position() - MAGIC_BITS as usize));
+        } else if window == FOOTER_MAGIC {
+            return Some((Marker::Footer, reader.position() - MAGIC_BITS as usize));
+        }
+
+        window = ((window << 1) | reader.read(1)?) & mask;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The string "hello world\n" compressed with bzip2 at level 9.
+    const HELLO: [u8; 52] = [
+        0x42, 0x5a, 0x68, 0x39, 0x31, 0x41, 0x59, 0x26,
+        0x53, 0x59, 0x4e, 0xec, 0xe8, 0x36, 0x00, 0x00,
+        0x02, 0x51, 0x80, 0x00, 0x10, 0x40, 0x00, 0x06,
+        0x44, 0x90, 0x80, 0x20, 0x00, 0x31, 0x06, 0x4c,
+        0x41, 0x01, 0xa7, 0xa9, 0xa5, 0x80, 0xbb, 0x94,
+        0x31, 0xf8, 0xbb, 0x92, 0x29, 0xc2, 0x84, 0x82,
+        0x77, 0x67, 0x41, 0xb0,
+    ];
+
+    // Verify the stream header helpers and level validation.
+    #[test]
+    fn verify_header() {
+        let h = Header::from_bytes((&HELLO[..4]).try_into().unwrap());
+
+        assert_eq!(h.magic, MAGIC);
+        assert_eq!(h.block_size(), Some(900_000));
+        assert_eq!(h.to_bytes(), HELLO[..4]);
+
+        assert_eq!(block_size(b'1'), Some(100_000));
+        assert_eq!(block_size(b'0'), None);
+        assert_eq!(block_size(b'a'), None);
+    }
+
+    // Verify the CRC against the standard check value and the combination
+    // step.
+    #[test]
+    fn verify_crc() {
+        assert_eq!(crc32(b"123456789"), 0xfc891918);
+        assert_eq!(crc32(b"hello world\n"), 0x4eece836);
+        assert_eq!(combine_crc(0, 0x4eece836), 0x4eece836);
+        assert_eq!(combine_crc(0x80000001, 0), 0x00000003);
+    }
+
+    // Walk a real stream and verify both markers are found and the CRCs
+    // match.
+    #[test]
+    fn verify_markers() {
+        let mut r = bits::Reader::new(&HELLO);
+
+        r.seek(32);
+        assert_eq!(find_marker(&mut r), Some((Marker::Block, 32)));
+        let b = BlockHeader::read(&mut r).unwrap();
+        assert_eq!(b.crc, crc32(b"hello world\n"));
+        assert!(!b.randomised);
+
+        let (m, _) = find_marker(&mut r).unwrap();
+        assert_eq!(m, Marker::Footer);
+        let crc = r.read(CRC_BITS).unwrap() as u32;
+        assert_eq!(crc, combine_crc(0, b.crc));
+
+        r.align();
+        assert_eq!(r.remaining(), 0);
+        assert_eq!(find_marker(&mut r), None);
+
+        // Verify unaligned markers are found.
+        let data = [0x01, 0x8a, 0x0a, 0xc9, 0x32, 0x9a, 0xc8];
+        let mut r = bits::Reader::new(&data);
+        assert_eq!(find_marker(&mut r), Some((Marker::Block, 5)));
+        assert_eq!(r.position(), 53);
+    }
+}
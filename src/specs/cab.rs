@@ -0,0 +1,383 @@
+//! Microsoft Cabinet Format
+//!
+//! The Microsoft Cabinet format (CAB) is an archive format used by Windows
+//! installers, driver packages, and Windows Update. A cabinet stores a set of
+//! files, grouped into folders. Each folder is a single compressed stream
+//! which can span multiple files, and files can span multiple folders.
+//! Additionally, a set of cabinets can form a chain, with folders continuing
+//! in the next cabinet.
+//!
+//! A cabinet starts with a `Header` (CFHEADER), optionally followed by the
+//! sizes of per-structure reserve areas, the reserve area of the header, and
+//! the names of the previous and next cabinets in the chain. Then follows an
+//! array of `Folder` structures (CFFOLDER), each optionally followed by a
+//! reserve area. At the offset given by `Header::coff_files` an array of
+//! `File` structures (CFFILE) is located, each followed by the
+//! NUL-terminated name of the file. Finally, each folder points to a chain of
+//! `Data` blocks (CFDATA), each followed by an optional reserve area and the
+//! compressed data.
+//!
+//! The sizes of the reserve areas are specified in a `Reserve` structure
+//! directly following the header, if `HEADER_RESERVE_PRESENT` is set. They
+//! are usually used to store signatures.
+//!
+//! All multi-byte integers are encoded as little-endian.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::int;
+
+/// Magic Signature
+///
+/// The initial 4 bytes of every cabinet file ("MSCF").
+pub const MAGIC: [u8; 4] = [0x4d, 0x53, 0x43, 0x46];
+
+pub const VERSION_MAJOR: u8 = 1;
+pub const VERSION_MINOR: u8 = 3;
+
+pub const HEADER_PREV_CABINET: u16 = 0x0001;
+pub const HEADER_NEXT_CABINET: u16 = 0x0002;
+pub const HEADER_RESERVE_PRESENT: u16 = 0x0004;
+
+/// Maximum Size of the Header Reserve Area
+pub const RESERVE_HEADER_MAX: usize = 60_000;
+
+pub const COMPRESS_MASK_TYPE: u16 = 0x000f;
+pub const COMPRESS_TYPE_NONE: u16 = 0x0000;
+pub const COMPRESS_TYPE_MSZIP: u16 = 0x0001;
+pub const COMPRESS_TYPE_QUANTUM: u16 = 0x0002;
+pub const COMPRESS_TYPE_LZX: u16 = 0x0003;
+pub const COMPRESS_MASK_QUANTUM_LEVEL: u16 = 0x00f0;
+pub const COMPRESS_SHIFT_QUANTUM_LEVEL: u32 = 4;
+pub const COMPRESS_MASK_QUANTUM_MEMORY: u16 = 0x1f00;
+pub const COMPRESS_SHIFT_QUANTUM_MEMORY: u32 = 8;
+pub const COMPRESS_MASK_LZX_WINDOW: u16 = 0x1f00;
+pub const COMPRESS_SHIFT_LZX_WINDOW: u32 = 8;
+
+pub const FOLDER_CONTINUED_FROM_PREV: u16 = 0xfffd;
+pub const FOLDER_CONTINUED_TO_NEXT: u16 = 0xfffe;
+pub const FOLDER_CONTINUED_PREV_AND_NEXT: u16 = 0xffff;
+
+pub const ATTRIBUTE_READ_ONLY: u16 = 0x0001;
+pub const ATTRIBUTE_HIDDEN: u16 = 0x0002;
+pub const ATTRIBUTE_SYSTEM: u16 = 0x0004;
+pub const ATTRIBUTE_ARCHIVE: u16 = 0x0020;
+pub const ATTRIBUTE_EXECUTE: u16 = 0x0040;
+pub const ATTRIBUTE_NAME_IS_UTF: u16 = 0x0080;
+
+/// Maximum Uncompressed Size of a Data Block
+pub const DATA_MAX: usize = 0x8000;
+
+/// Cabinet Header
+///
+/// The static header at offset 0 of every cabinet (CFHEADER).
+#[repr(C)]
+pub struct Header {
+    /// The static signature identifying the format. This must match `MAGIC`.
+    pub signature: [u8; 4],
+
+    /// Reserved, must be 0.
+    pub reserved1: int::u32le,
+
+    /// Total size of the cabinet file in bytes.
+    pub cb_cabinet: int::u32le,
+
+    /// Reserved, must be 0.
+    pub reserved2: int::u32le,
+
+    /// Absolute file offset of the first `File` entry.
+    pub coff_files: int::u32le,
+
+    /// Reserved, must be 0.
+    pub reserved3: int::u32le,
+
+    /// Minor version of the format, usually `VERSION_MINOR`.
+    pub version_minor: u8,
+
+    /// Major version of the format, usually `VERSION_MAJOR`.
+    pub version_major: u8,
+
+    /// Number of `Folder` entries in this cabinet.
+    pub c_folders: int::u16le,
+
+    /// Number of `File` entries in this cabinet.
+    pub c_files: int::u16le,
+
+    /// Header flags (`HEADER_*`), describing which optional fields follow.
+    pub flags: int::u16le,
+
+    /// Identifier shared by all cabinets of a chain.
+    pub set_id: int::u16le,
+
+    /// Index of this cabinet in its chain, starting at 0.
+    pub i_cabinet: int::u16le,
+}
+
+/// Reserve Area Sizes
+///
+/// This structure directly follows `Header` if `HEADER_RESERVE_PRESENT` is
+/// set. It specifies the size of the reserve areas trailing the header, each
+/// folder entry, and each data block.
+#[repr(C)]
+pub struct Reserve {
+    /// Size of the reserve area trailing this structure.
+    pub cb_cfheader: int::u16le,
+
+    /// Size of the reserve area trailing each `Folder` entry.
+    pub cb_cffolder: u8,
+
+    /// Size of the reserve area trailing each `Data` block header.
+    pub cb_cfdata: u8,
+}
+
+/// Folder Entry
+///
+/// Describes a single folder (CFFOLDER), which is a compressed stream of the
+/// concatenated content of a set of files.
+#[repr(C)]
+pub struct Folder {
+    /// Absolute file offset of the first `Data` block of this folder.
+    pub coff_cab_start: int::u32le,
+
+    /// Number of `Data` blocks of this folder in this cabinet.
+    pub c_cfdata: int::u16le,
+
+    /// Compression type and parameters (`COMPRESS_*`).
+    pub type_compress: int::u16le,
+}
+
+/// File Entry
+///
+/// Describes a single file (CFFILE). The entry is directly followed by the
+/// NUL-terminated name of the file. The name is encoded in UTF-8 if
+/// `ATTRIBUTE_NAME_IS_UTF` is set, otherwise its encoding is unspecified
+/// (usually the active OEM code page).
+#[repr(C)]
+pub struct File {
+    /// Uncompressed size of the file in bytes.
+    pub cb_file: int::u32le,
+
+    /// Uncompressed offset of the file in its folder.
+    pub uoff_folder_start: int::u32le,
+
+    /// Index of the folder containing this file, or one of the
+    /// `FOLDER_CONTINUED_*` markers.
+    pub i_folder: int::u16le,
+
+    /// Modification date in MS-DOS format.
+    pub date: int::u16le,
+
+    /// Modification time in MS-DOS format.
+    pub time: int::u16le,
+
+    /// File attributes (`ATTRIBUTE_*`).
+    pub attribs: int::u16le,
+}
+
+/// Data Block
+///
+/// The header of a single compressed block (CFDATA) of a folder. It is
+/// followed by the reserve area (if any), and then the compressed data.
+#[repr(C)]
+pub struct Data {
+    /// Checksum of this block, or 0 if not calculated. See `data_checksum()`.
+    pub csum: int::u32le,
+
+    /// Size of the compressed data following this header and its reserve
+    /// area.
+    pub cb_data: int::u16le,
+
+    /// Size of the uncompressed data of this block. This is 0 if the block
+    /// continues in the next cabinet.
+    pub cb_uncomp: int::u16le,
+}
+
+impl Header {
+    /// Import a header from a byte slice
+    ///
+    /// Create a new header structure from a byte slice, copying the data over.
+    /// The data is copied verbatim without any conversion.
+    pub fn from_bytes(data: &[u8; 36]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 36], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Convert to byte slice
+    ///
+    /// Return a byte-slice reference to the header. No byte-order conversions
+    /// are applied.
+    pub fn as_bytes(&self) -> &[u8; 36] {
+        assert!(align_of::<[u8; 36]>() <= align_of::<Self>());
+        assert!(size_of::<[u8; 36]>() == size_of::<Self>());
+
+        unsafe {
+            core::mem::transmute::<&Self, &[u8; 36]>(self)
+        }
+    }
+
+    /// Check whether a reserve structure follows
+    pub fn has_reserve(&self) -> bool {
+        int::ForeignEndian::to_native(self.flags) & HEADER_RESERVE_PRESENT != 0
+    }
+}
+
+impl Reserve {
+    /// Import reserve sizes from a byte slice
+    ///
+    /// Create a new reserve structure from a byte slice, copying the data
+    /// over. No byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 4]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 4], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl Folder {
+    /// Return compression type
+    ///
+    /// Return the compression type (`COMPRESS_TYPE_*`) of this folder.
+    pub fn compression_type(&self) -> u16 {
+        int::ForeignEndian::to_native(self.type_compress) & COMPRESS_MASK_TYPE
+    }
+}
+
+/// Calculate Checksum
+///
+/// Calculate the checksum used for data blocks over `data`, continuing from
+/// `seed`. The checksum XOR's all 32-bit little-endian words of the data.
+/// Note that the trailing 1 to 3 bytes (if any) are combined in reverse
+/// order, with the last byte being the least significant one. This deviates
+/// from a zero-padded little-endian word, and must be replicated exactly.
+pub fn checksum(data: &[u8], seed: u32) -> u32 {
+    let mut csum = seed;
+    let mut chunks = data.chunks_exact(4);
+
+    for c in &mut chunks {
+        csum ^= u32::from_le_bytes([c[0], c[1], c[2], c[3]]);
+    }
+
+    let tail = chunks.remainder().iter().fold(0u32, |acc, b| (acc << 8) | *b as u32);
+    csum ^ tail
+}
+
+/// Calculate Data Block Checksum
+///
+/// Calculate the value of `Data::csum` for a data block. `header` must contain
+/// the bytes of the block header following `csum` (i.e., `cb_data` and
+/// `cb_uncomp`) plus the reserve area of the block. `data` is the compressed
+/// data of the block. The data is checksummed first, and the result is used
+/// as seed for the header.
+pub fn data_checksum(header: &[u8], data: &[u8]) -> u32 {
+    checksum(header, checksum(data, 0))
+}
+
+/// Decode MS-DOS Date
+///
+/// Split an MS-DOS date into year, month (1-12), and day (1-31).
+pub fn dos_date(date: u16) -> (u16, u8, u8) {
+    (1980 + (date >> 9), ((date >> 5) & 0x0f) as u8, (date & 0x1f) as u8)
+}
+
+/// Decode MS-DOS Time
+///
+/// Split an MS-DOS time into hours, minutes, and seconds. The seconds are
+/// stored with a granularity of 2 seconds.
+pub fn dos_time(time: u16) -> (u8, u8, u8) {
+    ((time >> 11) as u8, ((time >> 5) & 0x3f) as u8, ((time & 0x1f) * 2) as u8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<Header>(), 36);
+        assert_eq!(align_of::<Header>(), 4);
+        assert_eq!(size_of::<Reserve>(), 4);
+        assert_eq!(align_of::<Reserve>(), 2);
+        assert_eq!(size_of::<Folder>(), 8);
+        assert_eq!(align_of::<Folder>(), 4);
+        assert_eq!(size_of::<File>(), 16);
+        assert_eq!(align_of::<File>(), 4);
+        assert_eq!(size_of::<Data>(), 8);
+        assert_eq!(align_of::<Data>(), 4);
+    }
+
+    // Verify header decoding of a single-cabinet header with reserve
+    // present.
+    #[test]
+    fn verify_header() {
+        let data: [u8; 36] = [
+            0x4d, 0x53, 0x43, 0x46, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x2c, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x03, 0x01, 0x01, 0x00, 0x02, 0x00, 0x04, 0x00,
+            0x34, 0x12, 0x00, 0x00,
+        ];
+        let h = Header::from_bytes(&data);
+
+        assert_eq!(h.signature, MAGIC);
+        assert_eq!(int::ForeignEndian::to_native(h.cb_cabinet), 0x1000);
+        assert_eq!(int::ForeignEndian::to_native(h.coff_files), 0x2c);
+        assert_eq!(h.version_major, VERSION_MAJOR);
+        assert_eq!(h.version_minor, VERSION_MINOR);
+        assert_eq!(int::ForeignEndian::to_native(h.c_folders), 1);
+        assert_eq!(int::ForeignEndian::to_native(h.c_files), 2);
+        assert_eq!(int::ForeignEndian::to_native(h.set_id), 0x1234);
+        assert!(h.has_reserve());
+        assert_eq!(h.as_bytes(), &data);
+
+        let r = Reserve::from_bytes(&[0x10, 0x00, 0x04, 0x00]);
+        assert_eq!(int::ForeignEndian::to_native(r.cb_cfheader), 16);
+        assert_eq!(r.cb_cffolder, 4);
+        assert_eq!(r.cb_cfdata, 0);
+    }
+
+    // Verify the checksum, including its reversed handling of trailing
+    // bytes.
+    #[test]
+    fn verify_checksum() {
+        assert_eq!(checksum(&[], 0), 0);
+        assert_eq!(checksum(&[0x01, 0x02, 0x03, 0x04], 0), 0x04030201);
+        assert_eq!(checksum(&[0x01, 0x02, 0x03, 0x04], 0x04030201), 0);
+        assert_eq!(checksum(&[0x01, 0x02, 0x03], 0), 0x00010203);
+        assert_eq!(checksum(&[0x01, 0x02, 0x03, 0x04, 0x05], 0), 0x04030204);
+
+        let csum = data_checksum(&[0x03, 0x00, 0x03, 0x00], &[0x01, 0x02, 0x03]);
+        assert_eq!(csum, 0x00030003 ^ 0x00010203);
+    }
+
+    // Verify the compression type and date/time helpers.
+    #[test]
+    fn verify_helpers() {
+        let f = Folder {
+            coff_cab_start: 0.into(),
+            c_cfdata: 1.into(),
+            type_compress: (COMPRESS_TYPE_LZX | (21 << COMPRESS_SHIFT_LZX_WINDOW)).into(),
+        };
+        assert_eq!(f.compression_type(), COMPRESS_TYPE_LZX);
+
+        assert_eq!(dos_date(0x5a21), (2025, 1, 1));
+        assert_eq!(dos_time(0x6b3d), (13, 25, 58));
+    }
+}
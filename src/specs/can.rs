@@ -0,0 +1,278 @@
+//! Controller Area Network
+//!
+//! CAN frames carry an 11-bit (standard) or 29-bit (extended) identifier and
+//! up to 8 bytes of data. CAN-FD frames extend the data to up to 64 bytes,
+//! with the data length code (DLC) mapping to lengths of 12 to 64 bytes in
+//! steps (see `fd_dlc_to_length()`).
+//!
+//! The structures follow the layout used by SocketCAN, which stores the
+//! identifier in a 32-bit field together with the `ID_FLAG_*` bits, and
+//! exchanges `Frame` and `FdFrame` through raw CAN sockets. The size of the
+//! socket read tells them apart (`MTU` and `FD_MTU`). Error frames
+//! (`ID_FLAG_ERROR`) carry the error class (`ERROR_*`) in the identifier,
+//! and details in the data.
+//!
+//! Unlike most formats, all multi-byte integers are encoded in native byte
+//! order.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+/// Size of Classic Frames
+pub const MTU: usize = 16;
+
+/// Size of CAN-FD Frames
+pub const FD_MTU: usize = 72;
+
+pub const DATA_MAX: usize = 8;
+pub const FD_DATA_MAX: usize = 64;
+
+pub const ID_FLAG_EXTENDED: u32 = 0x80000000;
+pub const ID_FLAG_REMOTE: u32 = 0x40000000;
+pub const ID_FLAG_ERROR: u32 = 0x20000000;
+
+pub const ID_STANDARD_MASK: u32 = 0x000007ff;
+pub const ID_EXTENDED_MASK: u32 = 0x1fffffff;
+pub const ID_ERROR_MASK: u32 = 0x1fffffff;
+
+pub const FD_FLAG_BRS: u8 = 0x01;
+pub const FD_FLAG_ESI: u8 = 0x02;
+pub const FD_FLAG_FDF: u8 = 0x04;
+
+pub const ERROR_TX_TIMEOUT: u32 = 0x00000001;
+pub const ERROR_LOST_ARBITRATION: u32 = 0x00000002;
+pub const ERROR_CONTROLLER: u32 = 0x00000004;
+pub const ERROR_PROTOCOL: u32 = 0x00000008;
+pub const ERROR_TRANSCEIVER: u32 = 0x00000010;
+pub const ERROR_ACK: u32 = 0x00000020;
+pub const ERROR_BUS_OFF: u32 = 0x00000040;
+pub const ERROR_BUS_ERROR: u32 = 0x00000080;
+pub const ERROR_RESTARTED: u32 = 0x00000100;
+pub const ERROR_COUNTERS: u32 = 0x00000200;
+
+/// Classic CAN Frame
+///
+/// The data is 8-byte aligned, matching SocketCAN, so the structure is
+/// aligned accordingly.
+#[repr(C, align(8))]
+pub struct Frame {
+    /// Identifier combined with `ID_FLAG_*`.
+    pub can_id: u32,
+
+    /// Number of data bytes, at most `DATA_MAX`.
+    pub len: u8,
+
+    /// Reserved, must be 0.
+    pub pad: u8,
+
+    /// Reserved, must be 0.
+    pub res0: u8,
+
+    /// Raw DLC of 9 to 15 for frames with 8 data bytes, otherwise 0.
+    pub len8_dlc: u8,
+
+    /// Frame data, of which `len` bytes are used.
+    pub data: [u8; 8],
+}
+
+/// CAN-FD Frame
+///
+/// The data is 8-byte aligned, matching SocketCAN, so the structure is
+/// aligned accordingly.
+#[repr(C, align(8))]
+pub struct FdFrame {
+    /// Identifier combined with `ID_FLAG_*`. Remote frames do not exist in
+    /// CAN-FD.
+    pub can_id: u32,
+
+    /// Number of data bytes, a valid CAN-FD length of at most
+    /// `FD_DATA_MAX`.
+    pub len: u8,
+
+    /// Frame flags (`FD_FLAG_*`).
+    pub flags: u8,
+
+    /// Reserved, must be 0.
+    pub res0: u8,
+
+    /// Reserved, must be 0.
+    pub res1: u8,
+
+    /// Frame data, of which `len` bytes are used.
+    pub data: [u8; 64],
+}
+
+impl Frame {
+    /// Import a frame from a byte slice
+    ///
+    /// Create a new frame from data copied from a byte slice. No byte-order
+    /// conversions are applied.
+    pub fn from_bytes(data: &[u8; 16]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 16], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Return identifier
+    ///
+    /// Return the identifier without flags (see `id()`).
+    pub fn id(&self) -> u32 {
+        id(self.can_id)
+    }
+
+    /// Return frame data
+    ///
+    /// Return the used part of the frame data, or `None` if the length is
+    /// invalid.
+    pub fn payload(&self) -> Option<&[u8]> {
+        self.data.get(..self.len as usize)
+    }
+}
+
+impl FdFrame {
+    /// Import a frame from a byte slice
+    ///
+    /// Create a new frame from data copied from a byte slice. No byte-order
+    /// conversions are applied.
+    pub fn from_bytes(data: &[u8; 72]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 72], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Return identifier
+    ///
+    /// Return the identifier without flags (see `id()`).
+    pub fn id(&self) -> u32 {
+        id(self.can_id)
+    }
+
+    /// Return frame data
+    ///
+    /// Return the used part of the frame data, or `None` if the length is
+    /// invalid.
+    pub fn payload(&self) -> Option<&[u8]> {
+        self.data.get(..self.len as usize)
+    }
+}
+
+/// Return identifier
+///
+/// Return the identifier of a raw identifier field without flags, masked to
+/// 29 bits for extended and error frames, and to 11 bits otherwise.
+pub fn id(can_id: u32) -> u32 {
+    if can_id & (ID_FLAG_EXTENDED | ID_FLAG_ERROR) != 0 {
+        can_id & ID_EXTENDED_MASK
+    } else {
+        can_id & ID_STANDARD_MASK
+    }
+}
+
+/// Convert CAN-FD DLC to length
+///
+/// Return the number of data bytes encoded by a 4-bit CAN-FD data length
+/// code. Codes up to 8 map to themselves. Only the lower 4 bits are used.
+pub fn fd_dlc_to_length(dlc: u8) -> usize {
+    match dlc & 0x0f {
+        v @ 0..=8 => v as usize,
+        9 => 12,
+        10 => 16,
+        11 => 20,
+        12 => 24,
+        13 => 32,
+        14 => 48,
+        _ => 64,
+    }
+}
+
+/// Convert length to CAN-FD DLC
+///
+/// Return the smallest CAN-FD data length code that covers `length` bytes,
+/// or `None` if it exceeds `FD_DATA_MAX`. The data must be padded to the
+/// length of the returned code.
+pub fn fd_length_to_dlc(length: usize) -> Option<u8> {
+    match length {
+        0..=8 => Some(length as u8),
+        9..=12 => Some(9),
+        13..=16 => Some(10),
+        17..=20 => Some(11),
+        21..=24 => Some(12),
+        25..=32 => Some(13),
+        33..=48 => Some(14),
+        49..=64 => Some(15),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<Frame>(), MTU);
+        assert_eq!(align_of::<Frame>(), 8);
+        assert_eq!(size_of::<FdFrame>(), FD_MTU);
+        assert_eq!(align_of::<FdFrame>(), 8);
+    }
+
+    // Decode standard, extended, and error frames in native byte order.
+    #[test]
+    fn verify_frame() {
+        let mut data = [0u8; 16];
+        data[..4].copy_from_slice(&0x123u32.to_ne_bytes());
+        data[4] = 3;
+        data[8..11].copy_from_slice(&[0xde, 0xad, 0xbe]);
+        let f = Frame::from_bytes(&data);
+        assert_eq!(f.id(), 0x123);
+        assert_eq!(f.can_id & ID_FLAG_EXTENDED, 0);
+        assert_eq!(f.payload(), Some(&[0xde, 0xad, 0xbe][..]));
+
+        data[..4].copy_from_slice(&(0x18daf110 | ID_FLAG_EXTENDED | ID_FLAG_REMOTE).to_ne_bytes());
+        data[4] = 9;
+        let f = Frame::from_bytes(&data);
+        assert_eq!(f.id(), 0x18daf110);
+        assert_ne!(f.can_id & ID_FLAG_REMOTE, 0);
+        assert!(f.payload().is_none());
+
+        assert_eq!(id(ID_FLAG_ERROR | ERROR_BUS_OFF), ERROR_BUS_OFF);
+        assert_eq!(id(0x18daf110), 0x110);
+    }
+
+    // Decode a CAN-FD frame and convert all data length codes.
+    #[test]
+    fn verify_fd() {
+        let mut data = [0u8; 72];
+        data[..4].copy_from_slice(&0x7e8u32.to_ne_bytes());
+        data[4] = 12;
+        data[5] = FD_FLAG_BRS;
+        data[8..20].copy_from_slice(b"hello, world");
+        let f = FdFrame::from_bytes(&data);
+        assert_eq!(f.id(), 0x7e8);
+        assert_eq!(f.flags & FD_FLAG_BRS, FD_FLAG_BRS);
+        assert_eq!(f.payload(), Some(&b"hello, world"[..]));
+
+        for dlc in 0..16 {
+            assert_eq!(fd_length_to_dlc(fd_dlc_to_length(dlc)), Some(dlc));
+        }
+        assert_eq!(fd_dlc_to_length(13), 32);
+        assert_eq!(fd_length_to_dlc(33), Some(14));
+        assert_eq!(fd_length_to_dlc(65), None);
+    }
+}
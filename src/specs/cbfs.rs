@@ -0,0 +1,761 @@
+//! coreboot File System and Flash Map
+//!
+//! The coreboot File System (CBFS) is a simple archive format for the
+//! firmware components of a coreboot ROM image. A CBFS region is a sequence
+//! of files, each starting with a `FileHeader` at an offset aligned to
+//! `ALIGNMENT` bytes. The header is followed by the file name, optional
+//! attributes (`AttributeHeader`), and the file data. Empty space is covered
+//! by files of `TYPE_NULL`. Legacy images additionally describe the region
+//! with a `MasterHeader`.
+//!
+//! Modern images locate their regions via a flash map (FMAP): a `FmapHeader`
+//! stored anywhere in the image, followed by `FmapArea` descriptors naming
+//! the regions of the image. The CBFS of the primary firmware is stored in
+//! the area `AREA_COREBOOT`.
+//!
+//! All multi-byte integers of CBFS are encoded as big-endian, while all
+//! multi-byte integers of the flash map are encoded as little-endian. Since
+//! the flash map structures are not naturally aligned, they are packed.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::int;
+
+/// Master Header Magic
+///
+/// The magic value of the master header ("ORBC").
+pub const HEADER_MAGIC: u32 = 0x4f52_4243;
+
+pub const HEADER_VERSION1: u32 = 0x3131_3131;
+pub const HEADER_VERSION2: u32 = 0x3131_3132;
+
+/// File Magic
+///
+/// The magic value of every file header ("LARCHIVE").
+pub const FILE_MAGIC: [u8; 8] = *b"LARCHIVE";
+
+/// File Alignment
+///
+/// The alignment of files in a CBFS region, in bytes.
+pub const ALIGNMENT: usize = 64;
+
+pub const ARCHITECTURE_UNKNOWN: u32 = 0xffff_ffff;
+pub const ARCHITECTURE_X86: u32 = 0x0000_0001;
+pub const ARCHITECTURE_ARM: u32 = 0x0000_0010;
+pub const ARCHITECTURE_AARCH64: u32 = 0x0000_aa64;
+pub const ARCHITECTURE_MIPS: u32 = 0x0000_0100;
+pub const ARCHITECTURE_RISCV: u32 = 0xc001_d0de;
+pub const ARCHITECTURE_PPC64: u32 = 0x4075_70ff;
+
+pub const TYPE_DELETED: u32 = 0x0000_0000;
+pub const TYPE_BOOTBLOCK: u32 = 0x0000_0001;
+pub const TYPE_CBFSHEADER: u32 = 0x0000_0002;
+pub const TYPE_LEGACY_STAGE: u32 = 0x0000_0010;
+pub const TYPE_STAGE: u32 = 0x0000_0011;
+pub const TYPE_SELF: u32 = 0x0000_0020;
+pub const TYPE_FIT_PAYLOAD: u32 = 0x0000_0021;
+pub const TYPE_OPTIONROM: u32 = 0x0000_0030;
+pub const TYPE_BOOTSPLASH: u32 = 0x0000_0040;
+pub const TYPE_RAW: u32 = 0x0000_0050;
+pub const TYPE_VSA: u32 = 0x0000_0051;
+pub const TYPE_MBI: u32 = 0x0000_0052;
+pub const TYPE_MICROCODE: u32 = 0x0000_0053;
+pub const TYPE_INTEL_FIT: u32 = 0x0000_0054;
+pub const TYPE_FSP: u32 = 0x0000_0060;
+pub const TYPE_MRC: u32 = 0x0000_0061;
+pub const TYPE_MMA: u32 = 0x0000_0062;
+pub const TYPE_EFI: u32 = 0x0000_0063;
+pub const TYPE_STRUCT: u32 = 0x0000_0070;
+pub const TYPE_CMOS_DEFAULT: u32 = 0x0000_00aa;
+pub const TYPE_SPD: u32 = 0x0000_00ab;
+pub const TYPE_MRC_CACHE: u32 = 0x0000_00ac;
+pub const TYPE_CMOS_LAYOUT: u32 = 0x0000_01aa;
+pub const TYPE_NULL: u32 = 0xffff_ffff;
+
+pub const ATTRIBUTE_UNUSED: u32 = 0x0000_0000;
+pub const ATTRIBUTE_UNUSED2: u32 = 0xffff_ffff;
+pub const ATTRIBUTE_COMPRESSION: u32 = 0x4243_5a4c;
+pub const ATTRIBUTE_HASH: u32 = 0x6873_6148;
+pub const ATTRIBUTE_POSITION: u32 = 0x4243_5350;
+pub const ATTRIBUTE_ALIGNMENT: u32 = 0x4243_4c41;
+pub const ATTRIBUTE_IBB: u32 = 0x3249_4242;
+pub const ATTRIBUTE_PADDING: u32 = 0x4744_4150;
+pub const ATTRIBUTE_STAGEHEADER: u32 = 0x5374_6748;
+
+pub const COMPRESSION_NONE: u32 = 0;
+pub const COMPRESSION_LZMA: u32 = 1;
+pub const COMPRESSION_LZ4: u32 = 2;
+
+/// Master Header File Name
+pub const NAME_MASTER_HEADER: &[u8] = b"cbfs master header";
+
+/// Flash Map Signature
+///
+/// The signature of every flash map header ("__FMAP__").
+pub const FMAP_SIGNATURE: [u8; 8] = *b"__FMAP__";
+
+pub const FMAP_VER_MAJOR: u8 = 1;
+pub const FMAP_NAME_SIZE: usize = 32;
+
+pub const FMAP_AREA_STATIC: u16 = 0x0001;
+pub const FMAP_AREA_COMPRESSED: u16 = 0x0002;
+pub const FMAP_AREA_RO: u16 = 0x0004;
+pub const FMAP_AREA_PRESERVE: u16 = 0x0008;
+
+pub const AREA_FMAP: &[u8] = b"FMAP";
+pub const AREA_COREBOOT: &[u8] = b"COREBOOT";
+pub const AREA_FW_MAIN_A: &[u8] = b"FW_MAIN_A";
+pub const AREA_FW_MAIN_B: &[u8] = b"FW_MAIN_B";
+
+/// CBFS Master Header
+///
+/// The legacy header describing the CBFS of a ROM image, stored in a file of
+/// type `TYPE_CBFSHEADER`. A 32-bit pointer to it (relative to the end of
+/// the image, or absolute on x86) is stored in the last 4 bytes of the ROM.
+#[repr(C)]
+pub struct MasterHeader {
+    /// The static magic value. This must match `HEADER_MAGIC`.
+    pub magic: int::u32be,
+
+    /// Version of the format (`HEADER_VERSION*`).
+    pub version: int::u32be,
+
+    /// Size of the ROM image in bytes.
+    pub romsize: int::u32be,
+
+    /// Size of the bootblock at the end of the ROM image in bytes.
+    pub bootblocksize: int::u32be,
+
+    /// Alignment of files in bytes, usually `ALIGNMENT`.
+    pub align: int::u32be,
+
+    /// Offset of the first file from the start of the ROM image.
+    pub offset: int::u32be,
+
+    /// Architecture of the image (`ARCHITECTURE_*`).
+    pub architecture: int::u32be,
+
+    /// Padding.
+    pub pad: int::u32be,
+}
+
+/// CBFS File Header
+///
+/// The header of every file. It is followed by the NUL-terminated file name,
+/// optional attributes at `attributes_offset`, and the file data at
+/// `offset`, both relative to the start of the header.
+#[repr(C)]
+pub struct FileHeader {
+    /// The static magic value. This must match `FILE_MAGIC`.
+    pub magic: [u8; 8],
+
+    /// Size of the file data in bytes.
+    pub len: int::u32be,
+
+    /// File type (`TYPE_*`).
+    pub file_type: int::u32be,
+
+    /// Offset of the first attribute, or 0 if there are none.
+    pub attributes_offset: int::u32be,
+
+    /// Offset of the file data.
+    pub offset: int::u32be,
+}
+
+/// File Attribute Header
+///
+/// The header of every file attribute. The length includes the header, and
+/// is a multiple of 4.
+#[repr(C)]
+pub struct AttributeHeader {
+    /// Attribute tag (`ATTRIBUTE_*`).
+    pub tag: int::u32be,
+
+    /// Size of the attribute in bytes, including the header.
+    pub len: int::u32be,
+}
+
+/// Compression Attribute (`ATTRIBUTE_COMPRESSION`)
+#[repr(C)]
+pub struct CompressionAttribute {
+    /// Attribute header.
+    pub header: AttributeHeader,
+
+    /// Compression algorithm of the file data (`COMPRESSION_*`).
+    pub compression: int::u32be,
+
+    /// Size of the file data after decompression.
+    pub decompressed_size: int::u32be,
+}
+
+/// Stage Header Attribute (`ATTRIBUTE_STAGEHEADER`)
+///
+/// Since attributes are only aligned to 4 bytes, this structure is
+/// packed.
+#[repr(C, packed)]
+pub struct StageHeaderAttribute {
+    /// Attribute header.
+    pub header: AttributeHeader,
+
+    /// Load address of the stage.
+    pub loadaddr: int::u64be,
+
+    /// Size of the stage in memory, including BSS.
+    pub memlen: int::u32be,
+
+    /// Offset of the entry point from the load address.
+    pub entry_offset: int::u32be,
+}
+
+/// Flash Map Header
+///
+/// The header of a flash map, followed by `nareas` area descriptors.
+#[repr(C, packed)]
+pub struct FmapHeader {
+    /// The static signature. This must match `FMAP_SIGNATURE`.
+    pub signature: [u8; 8],
+
+    /// Major version of the format (`FMAP_VER_MAJOR`).
+    pub ver_major: u8,
+
+    /// Minor version of the format.
+    pub ver_minor: u8,
+
+    /// Physical address of the flash image in memory.
+    pub base: int::u64le,
+
+    /// Size of the flash image in bytes.
+    pub size: int::u32le,
+
+    /// NUL-padded name of the flash map.
+    pub name: [u8; 32],
+
+    /// Number of areas following the header.
+    pub nareas: int::u16le,
+}
+
+/// Flash Map Area
+#[repr(C, packed)]
+pub struct FmapArea {
+    /// Offset of the area from the start of the flash image.
+    pub offset: int::u32le,
+
+    /// Size of the area in bytes.
+    pub size: int::u32le,
+
+    /// NUL-padded name of the area (e.g., `AREA_COREBOOT`).
+    pub name: [u8; 32],
+
+    /// Area flags (`FMAP_AREA_*`).
+    pub flags: int::u16le,
+}
+
+/// File Reference
+///
+/// A reference to a single file of a CBFS region, as yielded by `Files`.
+#[derive(Clone, Copy, Debug)]
+pub struct File<'data> {
+    /// File type (`TYPE_*`).
+    pub file_type: u32,
+
+    /// File name, without the NUL terminator.
+    pub name: &'data [u8],
+
+    /// File attributes (see `Attributes`), or empty if there are none.
+    pub attributes: &'data [u8],
+
+    /// Offset of the file data from the start of the header.
+    pub offset: usize,
+
+    /// File data.
+    pub data: &'data [u8],
+}
+
+/// File Iterator
+///
+/// Iterate the files of a CBFS region. The iterator stops at the first
+/// offset without a valid file header, and the remaining data can be
+/// retrieved via `Files::remainder()`.
+#[derive(Clone, Debug)]
+pub struct Files<'data> {
+    data: &'data [u8],
+}
+
+/// Attribute Reference
+///
+/// A reference to a single file attribute. The data covers the entire
+/// attribute, including its header.
+#[derive(Clone, Copy, Debug)]
+pub struct Attribute<'data> {
+    /// Attribute tag (`ATTRIBUTE_*`).
+    pub tag: u32,
+
+    /// Data of the attribute, including the header.
+    pub data: &'data [u8],
+}
+
+/// Attribute Iterator
+///
+/// Iterate the attributes of a file. The iterator stops at the first unused
+/// or malformed attribute.
+#[derive(Clone, Debug)]
+pub struct Attributes<'data> {
+    data: &'data [u8],
+}
+
+/// Flash Map Reference
+///
+/// A parsed flash map, referencing its area descriptors.
+#[derive(Clone, Copy, Debug)]
+pub struct Fmap<'data> {
+    /// Physical address of the flash image in memory.
+    pub base: u64,
+
+    /// Size of the flash image in bytes.
+    pub size: u32,
+
+    /// Name of the flash map, without NUL padding.
+    pub name: &'data [u8],
+
+    /// Area descriptors, each of `size_of::<FmapArea>()` bytes.
+    pub areas: &'data [u8],
+}
+
+impl MasterHeader {
+    /// Import a master header from a byte slice
+    ///
+    /// Create a new master header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 32]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 32], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl FileHeader {
+    /// Import a file header from a byte slice
+    ///
+    /// Create a new file header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 24]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 24], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl AttributeHeader {
+    /// Import an attribute header from a byte slice
+    ///
+    /// Create a new attribute header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 8]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 8], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl CompressionAttribute {
+    /// Import an attribute from a byte slice
+    ///
+    /// Create a new attribute from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 16]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 16], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl StageHeaderAttribute {
+    /// Import an attribute from a byte slice
+    ///
+    /// Create a new attribute from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 24]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 24], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl FmapHeader {
+    /// Import an FMAP header from a byte slice
+    ///
+    /// Create a new FMAP header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 56]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 56], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl FmapArea {
+    /// Import an FMAP area from a byte slice
+    ///
+    /// Create a new FMAP area from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 42]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 42], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl<'data> File<'data> {
+    /// Parse a file
+    ///
+    /// Parse the file at the start of `data`. Any trailing data is ignored.
+    /// `None` is returned if the magic does not match, the data is
+    /// truncated, or the offsets are inconsistent.
+    pub fn parse(data: &'data [u8]) -> Option<Self> {
+        let h = FileHeader::from_bytes(data.get(..24)?.try_into().ok()?);
+        if h.magic != FILE_MAGIC {
+            return None;
+        }
+
+        let len = int::ForeignEndian::to_native(h.len) as usize;
+        let offset = int::ForeignEndian::to_native(h.offset) as usize;
+        let attributes_offset = match int::ForeignEndian::to_native(h.attributes_offset) {
+            0 => offset,
+            v => v as usize,
+        };
+        if attributes_offset < size_of::<FileHeader>() || attributes_offset > offset {
+            return None;
+        }
+
+        let name = data.get(24..attributes_offset)?;
+        let end = name.iter().position(|v| *v == 0).unwrap_or(name.len());
+
+        Some(Self {
+            file_type: int::ForeignEndian::to_native(h.file_type),
+            name: &name[..end],
+            attributes: data.get(attributes_offset..offset)?,
+            offset,
+            data: data.get(offset..offset.checked_add(len)?)?,
+        })
+    }
+
+    /// Return total size
+    ///
+    /// Return the size of the file in bytes, from the start of the header to
+    /// the end of the file data, without trailing alignment.
+    pub fn size(&self) -> usize {
+        self.offset + self.data.len()
+    }
+
+    /// Iterate attributes
+    ///
+    /// Create an iterator over the attributes of the file.
+    pub fn attributes(&self) -> Attributes<'data> {
+        Attributes::new(self.attributes)
+    }
+
+    /// Find attribute
+    ///
+    /// Return the first attribute with the given tag, if any.
+    pub fn attribute(&self, tag: u32) -> Option<Attribute<'data>> {
+        self.attributes().find(|v| v.tag == tag)
+    }
+}
+
+impl<'data> Files<'data> {
+    /// Create file iterator
+    ///
+    /// Create a new iterator over the files of a CBFS region. The data must
+    /// start at the first file, and alignment is relative to its start.
+    pub fn new(data: &'data [u8]) -> Self {
+        Self {
+            data,
+        }
+    }
+
+    /// Return remaining data
+    ///
+    /// Return the data that has not been consumed by the iterator.
+    pub fn remainder(&self) -> &'data [u8] {
+        self.data
+    }
+}
+
+impl<'data> Iterator for Files<'data> {
+    type Item = File<'data>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let v = File::parse(self.data)?;
+        let next = v.size().next_multiple_of(ALIGNMENT);
+        self.data = self.data.get(next..).unwrap_or(&[]);
+        Some(v)
+    }
+}
+
+impl<'data> Attributes<'data> {
+    /// Create attribute iterator
+    ///
+    /// Create a new iterator over a sequence of file attributes.
+    pub fn new(data: &'data [u8]) -> Self {
+        Self {
+            data,
+        }
+    }
+}
+
+impl<'data> Iterator for Attributes<'data> {
+    type Item = Attribute<'data>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let h = AttributeHeader::from_bytes(self.data.get(..8)?.try_into().ok()?);
+        let tag = int::ForeignEndian::to_native(h.tag);
+        let len = int::ForeignEndian::to_native(h.len) as usize;
+        if tag == ATTRIBUTE_UNUSED || tag == ATTRIBUTE_UNUSED2 || len < 8 {
+            return None;
+        }
+
+        let data = self.data.get(..len)?;
+        self.data = &self.data[len..];
+        Some(Attribute {
+            tag,
+            data,
+        })
+    }
+}
+
+impl<'data> Fmap<'data> {
+    /// Parse a flash map
+    ///
+    /// Parse the flash map at the start of `data`. `None` is returned if the
+    /// signature or major version do not match, or the data is truncated.
+    pub fn parse(data: &'data [u8]) -> Option<Self> {
+        let h = FmapHeader::from_bytes(data.get(..56)?.try_into().ok()?);
+        if h.signature != FMAP_SIGNATURE || h.ver_major != FMAP_VER_MAJOR {
+            return None;
+        }
+
+        let n = int::ForeignEndian::to_native(h.nareas) as usize;
+        let areas = data.get(56..56 + n * size_of::<FmapArea>())?;
+        Some(Self {
+            base: int::ForeignEndian::to_native(h.base),
+            size: int::ForeignEndian::to_native(h.size),
+            name: fmap_name(&data[22..54]),
+            areas,
+        })
+    }
+
+    /// Find a flash map
+    ///
+    /// Search `data` for a valid flash map and return its offset and the
+    /// parsed flash map. The signature is searched at every offset.
+    pub fn find(data: &'data [u8]) -> Option<(usize, Self)> {
+        (0..data.len())
+            .filter(|i| data[*i..].starts_with(&FMAP_SIGNATURE))
+            .find_map(|i| Some((i, Self::parse(&data[i..])?)))
+    }
+
+    /// Return number of areas
+    pub fn count(&self) -> usize {
+        self.areas.len() / size_of::<FmapArea>()
+    }
+
+    /// Return area
+    ///
+    /// Return the area descriptor with the given index, or `None` if out of
+    /// bounds.
+    pub fn area(&self, index: usize) -> Option<FmapArea> {
+        let n = size_of::<FmapArea>();
+        let v = self.areas.get(index * n..(index + 1) * n)?;
+        Some(FmapArea::from_bytes(v.try_into().ok()?))
+    }
+
+    /// Find area
+    ///
+    /// Return the first area descriptor with the given name, if any.
+    pub fn find_area(&self, name: &[u8]) -> Option<FmapArea> {
+        (0..self.count())
+            .filter_map(|i| self.area(i))
+            .find(|v| fmap_name(&v.name) == name)
+    }
+}
+
+/// Strip flash map name
+///
+/// Return a flash map or area name without its NUL padding.
+pub fn fmap_name(name: &[u8]) -> &[u8] {
+    let end = name.iter().position(|v| *v == 0).unwrap_or(name.len());
+    &name[..end]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::specs::int::ForeignEndian;
+
+    // CBFS region with a raw file "config" of an uncompressed attribute, and
+    // a NULL file covering the remaining space.
+    const REGION: [u8; 128] = [
+        0x4c, 0x41, 0x52, 0x43, 0x48, 0x49, 0x56, 0x45,
+        0x00, 0x00, 0x00, 0x05, 0x00, 0x00, 0x00, 0x50,
+        0x00, 0x00, 0x00, 0x20, 0x00, 0x00, 0x00, 0x30,
+        0x63, 0x6f, 0x6e, 0x66, 0x69, 0x67, 0x00, 0x00,
+        0x42, 0x43, 0x5a, 0x4c, 0x00, 0x00, 0x00, 0x10,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x05,
+        0x68, 0x65, 0x6c, 0x6c, 0x6f, 0xff, 0xff, 0xff,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0x4c, 0x41, 0x52, 0x43, 0x48, 0x49, 0x56, 0x45,
+        0x00, 0x00, 0x00, 0x20, 0xff, 0xff, 0xff, 0xff,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x20,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    ];
+
+    // Master header of a 64 KiB x86 image.
+    const MASTER_HEADER: [u8; 32] = [
+        0x4f, 0x52, 0x42, 0x43, 0x31, 0x31, 0x31, 0x32,
+        0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x08, 0x00,
+        0x00, 0x00, 0x00, 0x40, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00,
+    ];
+
+    // Flash map "FLASH" of a 16 MiB image with a single area.
+    const FMAP: [u8; 98] = [
+        0x5f, 0x5f, 0x46, 0x4d, 0x41, 0x50, 0x5f, 0x5f,
+        0x01, 0x01, 0x00, 0x00, 0x00, 0xff, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x46, 0x4c,
+        0x41, 0x53, 0x48, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00,
+        0x00, 0x00, 0x20, 0x00, 0x00, 0x00, 0xe0, 0x00,
+        0x43, 0x4f, 0x52, 0x45, 0x42, 0x4f, 0x4f, 0x54,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00,
+    ];
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<MasterHeader>(), 32);
+        assert_eq!(size_of::<FileHeader>(), 24);
+        assert_eq!(size_of::<AttributeHeader>(), 8);
+        assert_eq!(size_of::<CompressionAttribute>(), 16);
+        assert_eq!(size_of::<StageHeaderAttribute>(), 24);
+        assert_eq!(align_of::<StageHeaderAttribute>(), 1);
+        assert_eq!(size_of::<FmapHeader>(), 56);
+        assert_eq!(align_of::<FmapHeader>(), 1);
+        assert_eq!(size_of::<FmapArea>(), 42);
+        assert_eq!(align_of::<FmapArea>(), 1);
+    }
+
+    // Walk the files of a CBFS region and decode their attributes.
+    #[test]
+    fn verify_files() {
+        let mut files = Files::new(&REGION);
+
+        let f = files.next().unwrap();
+        assert_eq!(f.file_type, TYPE_RAW);
+        assert_eq!(f.name, b"config");
+        assert_eq!(f.data, b"hello");
+        assert_eq!(f.size(), 53);
+        assert_eq!(f.attributes().count(), 1);
+        let a = f.attribute(ATTRIBUTE_COMPRESSION).unwrap();
+        let v = CompressionAttribute::from_bytes(a.data.try_into().unwrap());
+        assert_eq!(v.compression.to_native(), COMPRESSION_NONE);
+        assert_eq!(v.decompressed_size.to_native(), 5);
+        assert!(f.attribute(ATTRIBUTE_HASH).is_none());
+
+        let f = files.next().unwrap();
+        assert_eq!(f.file_type, TYPE_NULL);
+        assert!(f.name.is_empty());
+        assert!(f.attributes.is_empty());
+        assert_eq!(f.data.len(), 32);
+
+        assert!(files.next().is_none());
+        assert!(files.remainder().is_empty());
+
+        // Offsets beyond the data are rejected.
+        let mut v: [u8; 64] = REGION[..64].try_into().unwrap();
+        v[20..24].copy_from_slice(&[0x00, 0x00, 0x10, 0x00]);
+        assert!(File::parse(&v).is_none());
+
+        let h = MasterHeader::from_bytes(&MASTER_HEADER);
+        assert_eq!(h.magic.to_native(), HEADER_MAGIC);
+        assert_eq!(h.version.to_native(), HEADER_VERSION2);
+        assert_eq!(h.align.to_native() as usize, ALIGNMENT);
+        assert_eq!(h.architecture.to_native(), ARCHITECTURE_X86);
+    }
+
+    // Find a flash map in an image and look up its areas.
+    #[test]
+    fn verify_fmap() {
+        let mut image = [0xffu8; 256];
+        image[100..100 + FMAP.len()].copy_from_slice(&FMAP);
+
+        let (offset, fmap) = Fmap::find(&image).unwrap();
+        assert_eq!(offset, 100);
+        assert_eq!(fmap.base, 0xff000000);
+        assert_eq!(fmap.size, 0x1000000);
+        assert_eq!(fmap.name, b"FLASH");
+        assert_eq!(fmap.count(), 1);
+
+        let a = fmap.find_area(AREA_COREBOOT).unwrap();
+        assert_eq!(a.offset.to_native(), 0x200000);
+        assert_eq!(a.size.to_native(), 0xe00000);
+        assert!(fmap.find_area(AREA_FMAP).is_none());
+        assert!(fmap.area(1).is_none());
+
+        assert!(Fmap::find(&image[..150]).is_none());
+    }
+}
@@ -0,0 +1,293 @@
+//! Concise Binary Object Representation
+//!
+//! CBOR (RFC 8949) encodes each data item as an initial byte, carrying the
+//! major type (`MAJOR_*`) in the upper 3 bits and additional information
+//! (`INFO_*`) in the lower 5 bits. The additional information either is the
+//! argument itself, or gives the size of the argument that follows (see
+//! `argument_size()`). Depending on the major type, the argument is an
+//! integer value, the length of a string, the number of array elements or
+//! map pairs, a tag number, or a simple value or float.
+//!
+//! Strings, arrays, and maps can have an indefinite length. Such items are
+//! terminated with a break stop code (`BREAK`) and strings consist of
+//! definite-length chunks of the same major type.
+//!
+//! Tagged items (`MAJOR_TAG`) wrap a single data item. The well-known tags
+//! include date/time strings (`TAG_DATETIME_STRING`, text string as per RFC
+//! 3339), epoch-based date/time (`TAG_DATETIME_EPOCH`, integer or float
+//! seconds), and bignums (`TAG_BIGNUM_*`, byte string of the big-endian
+//! magnitude, where negative bignums encode `-1 - n`).
+//!
+//! All multi-byte integers are encoded as big-endian.
+
+pub const MAJOR_SHIFT: u32 = 5;
+pub const INFO_MASK: u8 = 0x1f;
+
+pub const MAJOR_UNSIGNED: u8 = 0;
+pub const MAJOR_NEGATIVE: u8 = 1;
+pub const MAJOR_BYTES: u8 = 2;
+pub const MAJOR_TEXT: u8 = 3;
+pub const MAJOR_ARRAY: u8 = 4;
+pub const MAJOR_MAP: u8 = 5;
+pub const MAJOR_TAG: u8 = 6;
+pub const MAJOR_SIMPLE: u8 = 7;
+
+/// Maximum Immediate Argument
+pub const INFO_IMMEDIATE_MAX: u8 = 23;
+
+pub const INFO_UINT8: u8 = 24;
+pub const INFO_UINT16: u8 = 25;
+pub const INFO_UINT32: u8 = 26;
+pub const INFO_UINT64: u8 = 27;
+/* reserved: 28..=30 */
+pub const INFO_INDEFINITE: u8 = 31;
+
+/// Break Stop Code
+pub const BREAK: u8 = 0xff;
+
+pub const SIMPLE_FALSE: u8 = 20;
+pub const SIMPLE_TRUE: u8 = 21;
+pub const SIMPLE_NULL: u8 = 22;
+pub const SIMPLE_UNDEFINED: u8 = 23;
+
+/// Minimum Simple Value Encoded With Extra Byte
+pub const SIMPLE_EXTENDED_MIN: u8 = 32;
+
+pub const SIMPLE_FLOAT16: u8 = INFO_UINT16;
+pub const SIMPLE_FLOAT32: u8 = INFO_UINT32;
+pub const SIMPLE_FLOAT64: u8 = INFO_UINT64;
+
+pub const TAG_DATETIME_STRING: u64 = 0;
+pub const TAG_DATETIME_EPOCH: u64 = 1;
+pub const TAG_BIGNUM_POSITIVE: u64 = 2;
+pub const TAG_BIGNUM_NEGATIVE: u64 = 3;
+pub const TAG_DECIMAL_FRACTION: u64 = 4;
+pub const TAG_BIGFLOAT: u64 = 5;
+pub const TAG_COSE_ENCRYPT0: u64 = 16;
+pub const TAG_COSE_MAC0: u64 = 17;
+pub const TAG_COSE_SIGN1: u64 = 18;
+pub const TAG_EXPECTED_BASE64URL: u64 = 21;
+pub const TAG_EXPECTED_BASE64: u64 = 22;
+pub const TAG_EXPECTED_BASE16: u64 = 23;
+pub const TAG_ENCODED_CBOR: u64 = 24;
+pub const TAG_URI: u64 = 32;
+pub const TAG_BASE64URL: u64 = 33;
+pub const TAG_BASE64: u64 = 34;
+pub const TAG_MIME: u64 = 36;
+pub const TAG_CWT: u64 = 61;
+pub const TAG_COSE_ENCRYPT: u64 = 96;
+pub const TAG_COSE_MAC: u64 = 97;
+pub const TAG_COSE_SIGN: u64 = 98;
+pub const TAG_DATE_EPOCH: u64 = 100;
+pub const TAG_DATE_STRING: u64 = 1004;
+pub const TAG_SELF_DESCRIBED: u64 = 55799;
+
+/// Self-described CBOR Magic
+pub const MAGIC: [u8; 3] = [0xd9, 0xd9, 0xf7];
+
+/// Data Item Header
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Header {
+    /// Major type (`MAJOR_*`).
+    pub major: u8,
+
+    /// Additional information (`INFO_*`).
+    pub info: u8,
+
+    /// Argument, or 0 for indefinite lengths and the break stop code. For
+    /// floats, this carries the raw bits.
+    pub argument: u64,
+
+    /// Size of the header in bytes, including the initial byte and the
+    /// argument.
+    pub size: usize,
+}
+
+impl Header {
+    /// Parse a data item header
+    ///
+    /// Parse the initial byte and argument at the start of `data`. Return
+    /// `None` if the data is truncated, the additional information is
+    /// reserved, or an indefinite length is used with a major type that does
+    /// not support it. The content need not be present.
+    pub fn parse(data: &[u8]) -> Option<Self> {
+        let initial = *data.first()?;
+        let major = initial >> MAJOR_SHIFT;
+        let info = initial & INFO_MASK;
+        let n = argument_size(info)?;
+
+        if info == INFO_INDEFINITE && !matches!(major, MAJOR_BYTES..=MAJOR_MAP | MAJOR_SIMPLE) {
+            return None;
+        }
+
+        let argument = match info {
+            0..=INFO_IMMEDIATE_MAX => info as u64,
+            INFO_INDEFINITE => 0,
+            _ => {
+                let v = data.get(1..1 + n)?;
+                v.iter().fold(0, |acc, b| (acc << 8) | *b as u64)
+            },
+        };
+
+        Some(Self { major, info, argument, size: 1 + n })
+    }
+
+    /// Check for indefinite length
+    pub fn is_indefinite(&self) -> bool {
+        self.info == INFO_INDEFINITE && self.major != MAJOR_SIMPLE
+    }
+
+    /// Check for break stop code
+    pub fn is_break(&self) -> bool {
+        self.info == INFO_INDEFINITE && self.major == MAJOR_SIMPLE
+    }
+
+    /// Return content size
+    ///
+    /// Return the number of bytes following the header that belong to this
+    /// data item itself. This is the length of definite-length strings, and
+    /// 0 for all other items, whose content, if any, follows as separate data
+    /// items.
+    pub fn content_size(&self) -> Option<usize> {
+        match self.major {
+            MAJOR_BYTES | MAJOR_TEXT if !self.is_indefinite() => {
+                usize::try_from(self.argument).ok()
+            },
+            _ => Some(0),
+        }
+    }
+}
+
+/// Return argument size
+///
+/// Return the number of bytes of the argument that follows the initial byte
+/// with the given additional information, or `None` if the additional
+/// information is reserved.
+pub fn argument_size(info: u8) -> Option<usize> {
+    match info {
+        0..=INFO_IMMEDIATE_MAX => Some(0),
+        INFO_UINT8 => Some(1),
+        INFO_UINT16 => Some(2),
+        INFO_UINT32 => Some(4),
+        INFO_UINT64 => Some(8),
+        INFO_INDEFINITE => Some(0),
+        _ => None,
+    }
+}
+
+/// Select additional information
+///
+/// Return the additional information of the preferred (shortest) encoding
+/// of the argument `v`.
+pub fn info_for(v: u64) -> u8 {
+    match v {
+        0..=0x17 => v as u8,
+        0x18..=0xff => INFO_UINT8,
+        0x100..=0xffff => INFO_UINT16,
+        0x10000..=0xffff_ffff => INFO_UINT32,
+        _ => INFO_UINT64,
+    }
+}
+
+/// Encode data item header
+///
+/// Encode the initial byte and argument of a data item with the given major
+/// type, using the preferred encoding. Return the number of bytes written,
+/// or `None` if the buffer is too small.
+pub fn encode_header(major: u8, v: u64, out: &mut [u8]) -> Option<usize> {
+    let info = info_for(v);
+    let n = argument_size(info)?;
+    let out = out.get_mut(..1 + n)?;
+
+    out[0] = (major << MAJOR_SHIFT) | info;
+    out[1..].copy_from_slice(&v.to_be_bytes()[8 - n..]);
+    Some(1 + n)
+}
+
+/// Decode half-precision float
+///
+/// Convert the raw bits of an IEEE 754 half-precision float, as used by
+/// `SIMPLE_FLOAT16`, to a double-precision float.
+pub fn float16(bits: u16) -> f64 {
+    let exponent = ((bits >> 10) & 0x1f) as i32;
+    let mantissa = (bits & 0x3ff) as f64;
+    // Build powers of two from their bits, as `powi()` requires `std`.
+    let pow2 = |e: i32| f64::from_bits(((e + 1023) as u64) << 52);
+    let v = match exponent {
+        0 => mantissa * pow2(-24),
+        0x1f if mantissa == 0.0 => f64::INFINITY,
+        0x1f => f64::NAN,
+        _ => (mantissa + 1024.0) * pow2(exponent - 25),
+    };
+
+    if bits & 0x8000 != 0 { -v } else { v }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Tag 1(1363896240) from the examples of RFC 8949.
+    const EPOCH: [u8; 6] = [
+        0xc1, 0x1a, 0x51, 0x4b, 0x67, 0xb0,
+    ];
+
+    // Tag 2(h'010000000000000000') from the examples of RFC 8949.
+    const BIGNUM: [u8; 11] = [
+        0xc2, 0x49, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00,
+    ];
+
+    // Decode headers of all major types.
+    #[test]
+    fn verify_headers() {
+        let h = Header::parse(&EPOCH).unwrap();
+        assert_eq!((h.major, h.argument, h.size), (MAJOR_TAG, TAG_DATETIME_EPOCH, 1));
+        let h = Header::parse(&EPOCH[1..]).unwrap();
+        assert_eq!((h.major, h.argument, h.size), (MAJOR_UNSIGNED, 1363896240, 5));
+
+        let h = Header::parse(&BIGNUM).unwrap();
+        assert_eq!((h.major, h.argument), (MAJOR_TAG, TAG_BIGNUM_POSITIVE));
+        let h = Header::parse(&BIGNUM[1..]).unwrap();
+        assert_eq!((h.major, h.size, h.content_size()), (MAJOR_BYTES, 1, Some(9)));
+
+        let h = Header::parse(&[0x38, 0x63]).unwrap();
+        assert_eq!((h.major, h.argument, h.size), (MAJOR_NEGATIVE, 99, 2));
+        let h = Header::parse(&[0x9f]).unwrap();
+        assert_eq!((h.major, h.is_indefinite(), h.content_size()), (MAJOR_ARRAY, true, Some(0)));
+        let h = Header::parse(&[BREAK]).unwrap();
+        assert!(h.is_break() && !h.is_indefinite());
+        let h = Header::parse(&[0xf5]).unwrap();
+        assert_eq!((h.major, h.argument), (MAJOR_SIMPLE, SIMPLE_TRUE as u64));
+        let h = Header::parse(&[0xf9, 0x3c, 0x00]).unwrap();
+        assert_eq!((h.info, h.argument), (SIMPLE_FLOAT16, 0x3c00));
+        assert_eq!(Header::parse(&MAGIC).unwrap().argument, TAG_SELF_DESCRIBED);
+
+        assert_eq!(Header::parse(&[0x1c]), None);
+        assert_eq!(Header::parse(&[0x1f]), None);
+        assert_eq!(Header::parse(&[0xdf]), None);
+        assert_eq!(Header::parse(&[0x1b, 0x00]), None);
+    }
+
+    // Encode arguments and decode half-precision floats.
+    #[test]
+    fn verify_arguments() {
+        let mut buf = [0; 9];
+        assert_eq!(encode_header(MAJOR_UNSIGNED, 23, &mut buf), Some(1));
+        assert_eq!(buf[0], 0x17);
+        assert_eq!(encode_header(MAJOR_UNSIGNED, 1363896240, &mut buf), Some(5));
+        assert_eq!(buf[..5], EPOCH[1..]);
+        assert_eq!(encode_header(MAJOR_NEGATIVE, 999, &mut buf), Some(3));
+        assert_eq!(buf[..3], [0x39, 0x03, 0xe7]);
+        assert_eq!(encode_header(MAJOR_TAG, u64::MAX, &mut buf), Some(9));
+        assert_eq!(buf, [0xdb, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff]);
+        assert_eq!(encode_header(MAJOR_ARRAY, 256, &mut buf[..2]), None);
+
+        assert_eq!(float16(0x3c00), 1.0);
+        assert_eq!(float16(0xc400), -4.0);
+        assert_eq!(float16(0x7bff), 65504.0);
+        assert_eq!(float16(0x0001), 5.960464477539063e-8);
+        assert_eq!(float16(0x7c00), f64::INFINITY);
+        assert!(float16(0x7e00).is_nan());
+    }
+}
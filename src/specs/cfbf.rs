@@ -0,0 +1,416 @@
+//! Compound File Binary Format
+//!
+//! The Compound File Binary Format (CFBF, also known as OLE Structured
+//! Storage or OLE2) implements a simple file system inside a single file. It
+//! is the container format of legacy Microsoft Office documents, MSI
+//! installer packages, Outlook messages, thumbnail caches, and many more.
+//!
+//! The file is split into sectors of equal size (512 bytes for version 3,
+//! 4096 bytes for version 4). The first sector holds the `Header`, and all
+//! further sectors are numbered starting at 0 for the sector directly
+//! following the header. Hence, sector `N` is located at file offset
+//! `(N + 1) * sector_size`.
+//!
+//! Sectors are chained via the File Allocation Table (FAT), which is an array
+//! of 32-bit sector numbers stored in FAT sectors. Each entry either points to
+//! the next sector of a chain, or contains one of the special markers
+//! `ENDOFCHAIN`, `FREESECT`, `FATSECT`, or `DIFSECT`. The locations of the FAT
+//! sectors themselves are stored in the Double-Indirect FAT (DIFAT). The first
+//! 109 DIFAT entries are stored in the header, further entries are stored in a
+//! chain of DIFAT sectors, where the last entry of each DIFAT sector points to
+//! the next DIFAT sector.
+//!
+//! Small streams (below `Header::mini_stream_cutoff_size`) are stored in the
+//! mini stream, which is itself a regular stream owned by the root directory
+//! entry. The mini stream is split into mini sectors (usually 64 bytes),
+//! chained via the mini FAT.
+//!
+//! The directory is a stream of 128-byte `DirectoryEntry` structures. Entries
+//! of a single storage form a red-black tree, with the child of a storage
+//! pointing to the root of its tree. Entry 0 is always the root storage.
+//!
+//! All multi-byte integers are encoded as little-endian. Names are stored as
+//! UTF-16LE.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::int;
+
+/// Magic Signature
+///
+/// The initial 8 bytes of every compound file.
+pub const MAGIC: [u8; 8] = [0xd0, 0xcf, 0x11, 0xe0, 0xa1, 0xb1, 0x1a, 0xe1];
+
+pub const VERSION_MINOR: u16 = 0x003e;
+pub const VERSION_MAJOR_3: u16 = 0x0003;
+pub const VERSION_MAJOR_4: u16 = 0x0004;
+
+/// Byte Order Mark
+///
+/// The value of `Header::byte_order`. It marks the file as little-endian and
+/// is the only valid value.
+pub const BYTE_ORDER: u16 = 0xfffe;
+
+pub const SECTOR_SHIFT_3: u16 = 9;
+pub const SECTOR_SHIFT_4: u16 = 12;
+pub const MINI_SECTOR_SHIFT: u16 = 6;
+
+/// Mini Stream Cutoff Size
+///
+/// Streams smaller than this size are stored in the mini stream. This is the
+/// only valid value of `Header::mini_stream_cutoff_size`.
+pub const MINI_STREAM_CUTOFF_SIZE: u32 = 4096;
+
+/// Number of DIFAT Entries in the Header
+pub const HEADER_DIFAT_ENTRIES: usize = 109;
+
+/// Maximum Regular Sector Number
+pub const MAXREGSECT: u32 = 0xffff_fffa;
+/* reserved: 0xfffffffb */
+/// DIFAT sector marker in the FAT.
+pub const DIFSECT: u32 = 0xffff_fffc;
+/// FAT sector marker in the FAT.
+pub const FATSECT: u32 = 0xffff_fffd;
+/// End of a sector chain.
+pub const ENDOFCHAIN: u32 = 0xffff_fffe;
+/// Unallocated sector.
+pub const FREESECT: u32 = 0xffff_ffff;
+
+/// Maximum Regular Directory Entry Number
+pub const MAXREGSID: u32 = 0xffff_fffa;
+/// Empty directory entry reference.
+pub const NOSTREAM: u32 = 0xffff_ffff;
+
+pub const OBJECT_TYPE_UNKNOWN: u8 = 0x00;
+pub const OBJECT_TYPE_STORAGE: u8 = 0x01;
+pub const OBJECT_TYPE_STREAM: u8 = 0x02;
+pub const OBJECT_TYPE_ROOT_STORAGE: u8 = 0x05;
+
+pub const COLOR_RED: u8 = 0x00;
+pub const COLOR_BLACK: u8 = 0x01;
+
+/// Maximum Name Length
+///
+/// Names of directory entries are limited to 31 UTF-16 code units plus a
+/// terminating NUL.
+pub const NAME_MAX: usize = 32;
+
+/// Size of a Directory Entry
+pub const DIRECTORY_ENTRY_SIZE: usize = 128;
+
+/// File Header
+///
+/// The header located in the first sector of every compound file. For
+/// version 4 files, the remainder of the first 4096-byte sector is zeroed.
+#[repr(C)]
+pub struct Header {
+    /// The static signature identifying the format. This must match `MAGIC`.
+    pub signature: [u8; 8],
+
+    /// Reserved, must be zero.
+    pub clsid: [u8; 16],
+
+    /// Minor version, should be `VERSION_MINOR`.
+    pub minor_version: int::u16le,
+
+    /// Major version, either `VERSION_MAJOR_3` or `VERSION_MAJOR_4`.
+    pub major_version: int::u16le,
+
+    /// Byte order mark, must be `BYTE_ORDER`.
+    pub byte_order: int::u16le,
+
+    /// Sector size as power of 2 (`SECTOR_SHIFT_3` or `SECTOR_SHIFT_4`,
+    /// depending on the major version).
+    pub sector_shift: int::u16le,
+
+    /// Mini sector size as power of 2, must be `MINI_SECTOR_SHIFT`.
+    pub mini_sector_shift: int::u16le,
+
+    /// Reserved, must be zero.
+    pub reserved: [u8; 6],
+
+    /// Number of directory sectors. Must be 0 for version 3 files.
+    pub num_directory_sectors: int::u32le,
+
+    /// Number of FAT sectors.
+    pub num_fat_sectors: int::u32le,
+
+    /// First sector of the directory chain.
+    pub first_directory_sector: int::u32le,
+
+    /// Transaction signature, usually 0.
+    pub transaction_signature: int::u32le,
+
+    /// Mini stream cutoff size, must be `MINI_STREAM_CUTOFF_SIZE`.
+    pub mini_stream_cutoff_size: int::u32le,
+
+    /// First sector of the mini FAT chain, or `ENDOFCHAIN`.
+    pub first_mini_fat_sector: int::u32le,
+
+    /// Number of mini FAT sectors.
+    pub num_mini_fat_sectors: int::u32le,
+
+    /// First DIFAT sector, or `ENDOFCHAIN` if all DIFAT entries fit into the
+    /// header.
+    pub first_difat_sector: int::u32le,
+
+    /// Number of DIFAT sectors.
+    pub num_difat_sectors: int::u32le,
+
+    /// The first 109 DIFAT entries. Unused entries are set to `FREESECT`.
+    pub difat: [int::u32le; HEADER_DIFAT_ENTRIES],
+}
+
+/// File Time
+///
+/// A Windows FILETIME, which is a 64-bit count of 100ns intervals since
+/// 1601-01-01 UTC, split into two 32-bit halves. The split keeps the value at
+/// 4-byte alignment, which is required by the directory entry layout.
+#[repr(C)]
+pub struct FileTime {
+    /// The lower 32 bits of the value.
+    pub low: int::u32le,
+
+    /// The upper 32 bits of the value.
+    pub high: int::u32le,
+}
+
+/// Directory Entry
+///
+/// Describes a single storage or stream object.
+#[repr(C)]
+pub struct DirectoryEntry {
+    /// Name of the object in UTF-16LE, NUL-terminated.
+    pub name: [int::u16le; NAME_MAX],
+
+    /// Length of the name in bytes, including the terminating NUL.
+    pub name_length: int::u16le,
+
+    /// Type of the object (`OBJECT_TYPE_*`).
+    pub object_type: u8,
+
+    /// Color of this node in the red-black tree (`COLOR_*`).
+    pub color_flag: u8,
+
+    /// Left sibling in the red-black tree, or `NOSTREAM`.
+    pub left_sibling_id: int::u32le,
+
+    /// Right sibling in the red-black tree, or `NOSTREAM`.
+    pub right_sibling_id: int::u32le,
+
+    /// Root of the red-black tree of the children of a storage, or
+    /// `NOSTREAM`.
+    pub child_id: int::u32le,
+
+    /// Class ID of a storage, zero for streams.
+    pub clsid: [u8; 16],
+
+    /// User-defined state bits of a storage.
+    pub state_bits: int::u32le,
+
+    /// Creation time, or 0.
+    pub creation_time: FileTime,
+
+    /// Modification time, or 0.
+    pub modified_time: FileTime,
+
+    /// First sector of the stream data (or the mini stream for the root
+    /// entry). Sectors refer to the mini stream if the size is below the
+    /// cutoff.
+    pub starting_sector: int::u32le,
+
+    /// Size of the stream in bytes. For version 3 files, only the lower 32
+    /// bits are to be used.
+    pub stream_size: int::u64le,
+}
+
+impl Header {
+    /// Import a header from a byte slice
+    ///
+    /// Create a new header structure from a byte slice, copying the data over.
+    /// The data is copied verbatim without any conversion.
+    pub fn from_bytes(data: &[u8; 512]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 512], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Return sector size
+    ///
+    /// Return the sector size in bytes, as specified by `sector_shift`.
+    /// `None` is returned if the shift is out of range.
+    pub fn sector_size(&self) -> Option<usize> {
+        1usize.checked_shl(int::ForeignEndian::to_native(self.sector_shift) as u32)
+    }
+
+    /// Return mini sector size
+    ///
+    /// Return the mini sector size in bytes, as specified by
+    /// `mini_sector_shift`. `None` is returned if the shift is out of range.
+    pub fn mini_sector_size(&self) -> Option<usize> {
+        1usize.checked_shl(int::ForeignEndian::to_native(self.mini_sector_shift) as u32)
+    }
+
+    /// Calculate sector offset
+    ///
+    /// Return the absolute file offset of the given regular sector. `None` is
+    /// returned if the sector is not a regular sector, or on overflow.
+    pub fn sector_offset(&self, sector: u32) -> Option<u64> {
+        if sector > MAXREGSECT {
+            return None;
+        }
+
+        (sector as u64 + 1).checked_mul(self.sector_size()? as u64)
+    }
+}
+
+impl FileTime {
+    /// Return native value
+    ///
+    /// Combine both halves into the native 64-bit value.
+    pub fn to_native(&self) -> u64 {
+        int::ForeignEndian::to_native(self.low) as u64
+            | (int::ForeignEndian::to_native(self.high) as u64) << 32
+    }
+}
+
+impl DirectoryEntry {
+    /// Import a directory entry from a byte slice
+    ///
+    /// Create a new directory entry from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 128]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 128], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Return name
+    ///
+    /// Return the UTF-16 code units of the name of this entry, excluding the
+    /// terminating NUL. `None` is returned if `name_length` is invalid. The
+    /// name can be decoded via `utf16::WideStr`.
+    pub fn name(&self) -> Option<&[int::u16le]> {
+        let len = int::ForeignEndian::to_native(self.name_length) as usize;
+
+        if len == 0 {
+            Some(&self.name[..0])
+        } else if len & 1 != 0 || len > NAME_MAX * 2 {
+            None
+        } else {
+            Some(&self.name[..len / 2 - 1])
+        }
+    }
+}
+
+/// Return FAT Entries per Sector
+///
+/// Return the number of 32-bit entries that fit into a single FAT, mini FAT,
+/// or DIFAT sector of the given size. Note that DIFAT sectors use their last
+/// entry to chain to the next DIFAT sector.
+pub fn entries_per_sector(sector_size: usize) -> usize {
+    sector_size / 4
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<Header>(), 512);
+        assert_eq!(align_of::<Header>(), 4);
+        assert_eq!(size_of::<DirectoryEntry>(), DIRECTORY_ENTRY_SIZE);
+        assert_eq!(align_of::<DirectoryEntry>(), 8);
+        assert_eq!(size_of::<FileTime>(), 8);
+        assert_eq!(align_of::<FileTime>(), 4);
+    }
+
+    // Verify field offsets of the header and the sector helpers.
+    #[test]
+    fn verify_header() {
+        let mut data = [0u8; 512];
+        data[..8].copy_from_slice(&MAGIC);
+        data[24] = 0x3e;
+        data[26] = 0x03;
+        data[28..30].copy_from_slice(&[0xfe, 0xff]);
+        data[30] = 9;
+        data[32] = 6;
+        data[44] = 1;
+        data[48] = 1;
+        data[56..60].copy_from_slice(&MINI_STREAM_CUTOFF_SIZE.to_le_bytes());
+        data[60..64].copy_from_slice(&ENDOFCHAIN.to_le_bytes());
+        data[68..72].copy_from_slice(&ENDOFCHAIN.to_le_bytes());
+        for e in data[80..].iter_mut() {
+            *e = 0xff;
+        }
+
+        let h = Header::from_bytes(&data);
+
+        assert_eq!(h.signature, MAGIC);
+        assert_eq!(int::ForeignEndian::to_native(h.minor_version), VERSION_MINOR);
+        assert_eq!(int::ForeignEndian::to_native(h.major_version), VERSION_MAJOR_3);
+        assert_eq!(int::ForeignEndian::to_native(h.byte_order), BYTE_ORDER);
+        assert_eq!(int::ForeignEndian::to_native(h.num_fat_sectors), 1);
+        assert_eq!(int::ForeignEndian::to_native(h.first_directory_sector), 1);
+        assert_eq!(int::ForeignEndian::to_native(h.first_mini_fat_sector), ENDOFCHAIN);
+        assert_eq!(int::ForeignEndian::to_native(h.difat[0]), 0);
+        assert_eq!(int::ForeignEndian::to_native(h.difat[1]), FREESECT);
+
+        assert_eq!(h.sector_size(), Some(512));
+        assert_eq!(h.mini_sector_size(), Some(64));
+        assert_eq!(h.sector_offset(0), Some(512));
+        assert_eq!(h.sector_offset(3), Some(2048));
+        assert_eq!(h.sector_offset(ENDOFCHAIN), None);
+        assert_eq!(entries_per_sector(512), 128);
+    }
+
+    // Verify decoding of a root directory entry.
+    #[test]
+    fn verify_directory_entry() {
+        let mut data = [0u8; 128];
+        for (i, c) in "Root Entry".encode_utf16().enumerate() {
+            data[i * 2..i * 2 + 2].copy_from_slice(&c.to_le_bytes());
+        }
+        data[64] = 22;
+        data[66] = OBJECT_TYPE_ROOT_STORAGE;
+        data[67] = COLOR_BLACK;
+        data[68..80].copy_from_slice(&[0xff; 12]);
+        data[108..116].copy_from_slice(&0x01d0_0000_0000_0000u64.to_le_bytes());
+        data[116..120].copy_from_slice(&3u32.to_le_bytes());
+        data[120..128].copy_from_slice(&0x40u64.to_le_bytes());
+
+        let e = DirectoryEntry::from_bytes(&data);
+
+        let name: std::vec::Vec<u16> = e.name().unwrap()
+            .iter()
+            .map(|c| int::ForeignEndian::to_native(*c))
+            .collect();
+        assert_eq!(std::string::String::from_utf16(&name).unwrap(), "Root Entry");
+        assert_eq!(e.object_type, OBJECT_TYPE_ROOT_STORAGE);
+        assert_eq!(e.color_flag, COLOR_BLACK);
+        assert_eq!(int::ForeignEndian::to_native(e.left_sibling_id), NOSTREAM);
+        assert_eq!(int::ForeignEndian::to_native(e.child_id), NOSTREAM);
+        assert_eq!(e.creation_time.to_native(), 0);
+        assert_eq!(e.modified_time.to_native(), 0x01d0_0000_0000_0000);
+        assert_eq!(int::ForeignEndian::to_native(e.starting_sector), 3);
+        assert_eq!(int::ForeignEndian::to_native(e.stream_size), 0x40);
+    }
+}
@@ -0,0 +1,315 @@
+//! Checksum Algorithms
+//!
+//! This module provides implementations of checksum algorithms that are
+//! shared by multiple specifications. Checksums that are specific to a single
+//! format are kept in the module of that format instead.
+//!
+//! All implementations operate on byte slices and support incremental
+//! calculation, so data can be fed in multiple chunks.
+
+/// Update CRC-32
+///
+/// Continue a CRC-32 calculation with more data. This is the CRC-32 as used
+/// by Ethernet, ZIP, PNG, and many others (also known as CRC-32/ISO-HDLC).
+/// It uses the reflected polynomial `0xedb88320` (LSB-first), an initial
+/// value of `0xffffffff`, and an inverted final value.
+///
+/// This function expects and returns the raw intermediate value (i.e.,
+/// without the final inversion). Start with `0xffffffff` and invert the final
+/// value, or use `crc32()` if all data is available at once.
+pub fn crc32_update(mut crc: u32, data: &[u8]) -> u32 {
+    for b in data {
+        crc ^= *b as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    crc
+}
+
+/// Calculate CRC-32
+///
+/// Calculate the CRC-32 of the given data. See `crc32_update()` for details.
+pub fn crc32(data: &[u8]) -> u32 {
+    !crc32_update(0xffff_ffff, data)
+}
+
+/// Update MSB-first CRC-32
+///
+/// Continue a CRC-32 calculation with more data, using the polynomial
+/// `0x04c11db7` in non-reflected form (MSB-first). This is the CRC-32 as
+/// used by bzip2, MPEG-2, Ogg, and others. These differ only in their initial
+/// and final values, which are left to the caller:
+///
+///  * bzip2 (CRC-32/BZIP2): Start with `0xffffffff` and invert the final
+///    value.
+///  * MPEG-2 (CRC-32/MPEG-2): Start with `0xffffffff`, no final inversion.
+///  * Ogg: Start with `0`, no final inversion.
+///
+/// This function expects and returns the raw intermediate value.
+pub fn crc32_msb_update(mut crc: u32, data: &[u8]) -> u32 {
+    for b in data {
+        crc ^= (*b as u32) << 24;
+        for _ in 0..8 {
+            crc = if crc & 0x8000_0000 != 0 {
+                (crc << 1) ^ 0x04c1_1db7
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+/// Update CRC-32C
+///
+/// Continue a CRC-32C calculation with more data. This is the Castagnoli
+/// CRC-32 as used by SCTP, iSCSI, ext4, and others. It uses the reflected
+/// polynomial `0x82f63b78` (LSB-first), an initial value of `0xffffffff`, and
+/// an inverted final value.
+///
+/// This function expects and returns the raw intermediate value. Start with
+/// `0xffffffff` and invert the final value, or use `crc32c()` if all data is
+/// available at once.
+pub fn crc32c_update(mut crc: u32, data: &[u8]) -> u32 {
+    for b in data {
+        crc ^= *b as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0x82f6_3b78
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    crc
+}
+
+/// Calculate CRC-32C
+///
+/// Calculate the CRC-32C of the given data. See `crc32c_update()` for
+/// details.
+pub fn crc32c(data: &[u8]) -> u32 {
+    !crc32c_update(0xffff_ffff, data)
+}
+
+/// Update CRC-16
+///
+/// Continue a CRC-16 calculation with more data, using the reflected
+/// polynomial `0xa001` (LSB-first). This is the CRC-16 as used by Modbus,
+/// USB, and others. These differ only in their initial and final values,
+/// which are left to the caller:
+///
+///  * ARC (CRC-16/ARC): Start with `0`, no final inversion.
+///  * Modbus (CRC-16/MODBUS): Start with `0xffff`, no final inversion.
+///  * USB (CRC-16/USB): Start with `0xffff` and invert the final value.
+///
+/// This function expects and returns the raw intermediate value.
+pub fn crc16_update(mut crc: u16, data: &[u8]) -> u16 {
+    for b in data {
+        crc ^= *b as u16;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xa001
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    crc
+}
+
+/// Update 8-bit Sum
+///
+/// Continue an 8-bit sum calculation with more data. This is the checksum
+/// used by ACPI, SMBIOS, EDID, PCI option ROMs, and others: a checksum byte
+/// is chosen such that all bytes of the structure sum to zero modulo 256.
+pub fn sum8_update(sum: u8, data: &[u8]) -> u8 {
+    data.iter().fold(sum, |acc, b| acc.wrapping_add(*b))
+}
+
+/// Calculate 8-bit Sum
+///
+/// Calculate the 8-bit sum of the given data. See `sum8_update()` for
+/// details. Data with a correct checksum byte yields `0`.
+pub fn sum8(data: &[u8]) -> u8 {
+    sum8_update(0, data)
+}
+
+/// Update Internet Checksum
+///
+/// Continue an Internet checksum calculation (RFC 1071) with more data. This
+/// is the 16-bit one's-complement sum of all 16-bit big-endian words, as used
+/// by IPv4, ICMP, TCP, UDP, and others. An odd trailing byte is padded with
+/// a zero byte, so all chunks but the last must have an even length.
+///
+/// This function expects and returns the raw intermediate sum (i.e., without
+/// the final inversion). Start with `0` and pass the final value to
+/// `internet_finish()`, or use `internet()` if all data is available at once.
+pub fn internet_update(mut sum: u32, data: &[u8]) -> u32 {
+    let mut words = data.chunks_exact(2);
+    for w in &mut words {
+        sum += u16::from_be_bytes([w[0], w[1]]) as u32;
+        sum = (sum & 0xffff) + (sum >> 16);
+    }
+    if let [b] = words.remainder() {
+        sum += (*b as u32) << 8;
+        sum = (sum & 0xffff) + (sum >> 16);
+    }
+    sum
+}
+
+/// Finish Internet Checksum
+///
+/// Fold the raw intermediate sum of `internet_update()` into 16 bits and
+/// invert it, yielding the value to store in the checksum field.
+pub fn internet_finish(sum: u32) -> u16 {
+    let sum = (sum & 0xffff) + (sum >> 16);
+    let sum = (sum & 0xffff) + (sum >> 16);
+    !(sum as u16)
+}
+
+/// Calculate Internet Checksum
+///
+/// Calculate the Internet checksum of the given data. See
+/// `internet_update()` for details. Data that includes a correct checksum
+/// yields `0`.
+pub fn internet(data: &[u8]) -> u16 {
+    internet_finish(internet_update(0, data))
+}
+
+/// Adjust Internet Checksum
+///
+/// Update the value of a checksum field after a single 16-bit word of the
+/// covered data changed from `old` to `new`, without recalculating the
+/// checksum over all data. This uses equation 3 of RFC 1624, which, unlike
+/// earlier methods, never yields `0xffff` for data that sums to zero.
+pub fn internet_adjust(checksum: u16, old: u16, new: u16) -> u16 {
+    internet_finish(!checksum as u32 + !old as u32 + new as u32)
+}
+
+/// Replace Data of Internet Checksum
+///
+/// Update the value of a checksum field after the covered data `old` was
+/// replaced with `new`, as in `internet_adjust()`. Both slices must start at
+/// an even offset of the covered data. An odd trailing byte is treated like
+/// in `internet_update()`. Both slices must have the same length, otherwise
+/// this panics.
+pub fn internet_replace(checksum: u16, old: &[u8], new: &[u8]) -> u16 {
+    assert_eq!(old.len(), new.len());
+
+    let mut sum = internet_update(!checksum as u32, new);
+    for w in old.chunks(2) {
+        let v = u16::from_be_bytes([w[0], *w.get(1).unwrap_or(&0)]);
+        sum += !v as u32;
+        sum = (sum & 0xffff) + (sum >> 16);
+    }
+    internet_finish(sum)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Verify the CRC-32 against its standard check value, and verify
+    // incremental updates yield the same result.
+    #[test]
+    fn verify_crc32() {
+        assert_eq!(crc32(b""), 0x00000000);
+        assert_eq!(crc32(b"123456789"), 0xcbf43926);
+
+        let crc = crc32_update(0xffff_ffff, b"1234");
+        let crc = crc32_update(crc, b"56789");
+        assert_eq!(!crc, 0xcbf43926);
+    }
+
+    // Verify the MSB-first CRC-32 against the check values of its common
+    // parameterizations.
+    #[test]
+    fn verify_crc32_msb() {
+        assert_eq!(!crc32_msb_update(0xffff_ffff, b"123456789"), 0xfc891918);
+        assert_eq!(crc32_msb_update(0xffff_ffff, b"123456789"), 0x0376e6e7);
+        assert_eq!(crc32_msb_update(0, b"123456789"), 0x89a1897f);
+
+        let crc = crc32_msb_update(0, b"1234");
+        let crc = crc32_msb_update(crc, b"56789");
+        assert_eq!(crc, 0x89a1897f);
+    }
+
+    // Verify the CRC-32C against its standard check value, and verify
+    // incremental updates yield the same result.
+    #[test]
+    fn verify_crc32c() {
+        assert_eq!(crc32c(b""), 0x00000000);
+        assert_eq!(crc32c(b"123456789"), 0xe3069283);
+
+        let crc = crc32c_update(0xffff_ffff, b"12345");
+        let crc = crc32c_update(crc, b"6789");
+        assert_eq!(!crc, 0xe3069283);
+    }
+
+    // Verify the CRC-16 against the check values of its common
+    // parameterizations.
+    #[test]
+    fn verify_crc16() {
+        assert_eq!(crc16_update(0, b"123456789"), 0xbb3d);
+        assert_eq!(crc16_update(0xffff, b"123456789"), 0x4b37);
+        assert_eq!(!crc16_update(0xffff, b"123456789"), 0xb4c8);
+
+        let crc = crc16_update(0xffff, b"1234");
+        let crc = crc16_update(crc, b"56789");
+        assert_eq!(crc, 0x4b37);
+    }
+
+    // Verify the 8-bit sum wraps, and a complementing checksum byte makes
+    // the total sum zero.
+    #[test]
+    fn verify_sum8() {
+        assert_eq!(sum8(b""), 0);
+        assert_eq!(sum8(&[0x80, 0x80, 0x01]), 0x01);
+
+        let data = [0x12, 0x34, 0x56];
+        let check = 0u8.wrapping_sub(sum8(&data));
+        assert_eq!(sum8_update(sum8(&data), &[check]), 0);
+    }
+
+    // Verify the Internet checksum against the RFC 1071 example, including
+    // odd lengths and incremental updates.
+    #[test]
+    fn verify_internet() {
+        let data = [0x00, 0x01, 0xf2, 0x03, 0xf4, 0xf5, 0xf6, 0xf7];
+        assert_eq!(internet_update(0, &data), 0xddf2);
+        assert_eq!(internet(&data), 0x220d);
+        assert_eq!(internet(b""), 0xffff);
+        assert_eq!(internet(&[0x01]), 0xfeff);
+
+        let sum = internet_update(0, &data[..4]);
+        let sum = internet_update(sum, &data[4..]);
+        assert_eq!(internet_finish(sum), 0x220d);
+
+        let sum = internet_update(0, &data);
+        assert_eq!(internet_finish(internet_update(sum, &[0x22, 0x0d])), 0);
+    }
+
+    // Verify incremental checksum updates against recalculation, including
+    // the RFC 1624 example.
+    #[test]
+    fn verify_internet_adjust() {
+        assert_eq!(internet_adjust(0xdd2f, 0x5555, 0x3285), 0x0000);
+
+        let mut data = [0x00, 0x01, 0xf2, 0x03, 0xf4, 0xf5, 0xf6, 0xf7];
+        let c = internet(&data);
+        data[2..4].copy_from_slice(&[0x12, 0x34]);
+        assert_eq!(internet_adjust(c, 0xf203, 0x1234), internet(&data));
+
+        let c = internet(&data);
+        let old = data;
+        data[4..7].copy_from_slice(&[0xab, 0xcd, 0xef]);
+        assert_eq!(internet_replace(c, &old[4..7], &data[4..7]), internet(&data));
+        assert_eq!(internet_replace(c, &old[4..8], &data[4..8]), internet(&data));
+    }
+}
@@ -0,0 +1,458 @@
+//! Constrained Application Protocol
+//!
+//! CoAP messages start with a 4-byte `Header` carrying the version, the
+//! message type, the token length, the code, and a message identifier. The
+//! header is followed by the token, a sequence of options (see `Options`),
+//! and an optional payload, which is preceded by `PAYLOAD_MARKER`. See
+//! `Message` to locate them.
+//!
+//! Codes are split into a 3-bit class and a 5-bit detail, written as
+//! `class.detail` (e.g., `2.05` for `CODE_CONTENT`, see `code()`). Class 0
+//! are requests, class 2 success responses, class 4 client errors, and class
+//! 5 server errors.
+//!
+//! Options are sorted by option number. Each option header encodes the
+//! delta to the previous option number and the length of the value in one
+//! nibble each. The nibble values 13 and 14 are followed by an 8-bit or
+//! 16-bit extended value, offset by 13 or 269, respectively. The nibble
+//! value 15 is reserved for the payload marker.
+//!
+//! All multi-byte integers are encoded as big-endian.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::int;
+
+pub const HEADER_SIZE: usize = 4;
+pub const TOKEN_MAX: usize = 8;
+pub const VERSION: u8 = 1;
+
+/// Marker Preceding the Payload
+pub const PAYLOAD_MARKER: u8 = 0xff;
+
+pub const VERSION_MASK: u8 = 0xc0;
+pub const VERSION_SHIFT: u32 = 6;
+pub const TYPE_MASK: u8 = 0x30;
+pub const TYPE_SHIFT: u32 = 4;
+pub const TOKEN_LENGTH_MASK: u8 = 0x0f;
+
+pub const TYPE_CONFIRMABLE: u8 = 0;
+pub const TYPE_NON_CONFIRMABLE: u8 = 1;
+pub const TYPE_ACKNOWLEDGEMENT: u8 = 2;
+pub const TYPE_RESET: u8 = 3;
+
+pub const CODE_CLASS_SHIFT: u32 = 5;
+pub const CODE_DETAIL_MASK: u8 = 0x1f;
+
+pub const CODE_EMPTY: u8 = code(0, 0);
+pub const CODE_GET: u8 = code(0, 1);
+pub const CODE_POST: u8 = code(0, 2);
+pub const CODE_PUT: u8 = code(0, 3);
+pub const CODE_DELETE: u8 = code(0, 4);
+pub const CODE_FETCH: u8 = code(0, 5);
+pub const CODE_PATCH: u8 = code(0, 6);
+pub const CODE_IPATCH: u8 = code(0, 7);
+
+pub const CODE_CREATED: u8 = code(2, 1);
+pub const CODE_DELETED: u8 = code(2, 2);
+pub const CODE_VALID: u8 = code(2, 3);
+pub const CODE_CHANGED: u8 = code(2, 4);
+pub const CODE_CONTENT: u8 = code(2, 5);
+pub const CODE_CONTINUE: u8 = code(2, 31);
+
+pub const CODE_BAD_REQUEST: u8 = code(4, 0);
+pub const CODE_UNAUTHORIZED: u8 = code(4, 1);
+pub const CODE_BAD_OPTION: u8 = code(4, 2);
+pub const CODE_FORBIDDEN: u8 = code(4, 3);
+pub const CODE_NOT_FOUND: u8 = code(4, 4);
+pub const CODE_METHOD_NOT_ALLOWED: u8 = code(4, 5);
+pub const CODE_NOT_ACCEPTABLE: u8 = code(4, 6);
+pub const CODE_REQUEST_ENTITY_INCOMPLETE: u8 = code(4, 8);
+pub const CODE_CONFLICT: u8 = code(4, 9);
+pub const CODE_PRECONDITION_FAILED: u8 = code(4, 12);
+pub const CODE_REQUEST_ENTITY_TOO_LARGE: u8 = code(4, 13);
+pub const CODE_UNSUPPORTED_CONTENT_FORMAT: u8 = code(4, 15);
+
+pub const CODE_INTERNAL_SERVER_ERROR: u8 = code(5, 0);
+pub const CODE_NOT_IMPLEMENTED: u8 = code(5, 1);
+pub const CODE_BAD_GATEWAY: u8 = code(5, 2);
+pub const CODE_SERVICE_UNAVAILABLE: u8 = code(5, 3);
+pub const CODE_GATEWAY_TIMEOUT: u8 = code(5, 4);
+pub const CODE_PROXYING_NOT_SUPPORTED: u8 = code(5, 5);
+
+pub const OPTION_IF_MATCH: u16 = 1;
+pub const OPTION_URI_HOST: u16 = 3;
+pub const OPTION_ETAG: u16 = 4;
+pub const OPTION_IF_NONE_MATCH: u16 = 5;
+pub const OPTION_OBSERVE: u16 = 6;
+pub const OPTION_URI_PORT: u16 = 7;
+pub const OPTION_LOCATION_PATH: u16 = 8;
+pub const OPTION_URI_PATH: u16 = 11;
+pub const OPTION_CONTENT_FORMAT: u16 = 12;
+pub const OPTION_MAX_AGE: u16 = 14;
+pub const OPTION_URI_QUERY: u16 = 15;
+pub const OPTION_ACCEPT: u16 = 17;
+pub const OPTION_LOCATION_QUERY: u16 = 20;
+pub const OPTION_BLOCK2: u16 = 23;
+pub const OPTION_BLOCK1: u16 = 27;
+pub const OPTION_SIZE2: u16 = 28;
+pub const OPTION_PROXY_URI: u16 = 35;
+pub const OPTION_PROXY_SCHEME: u16 = 39;
+pub const OPTION_SIZE1: u16 = 60;
+pub const OPTION_NO_RESPONSE: u16 = 258;
+
+pub const CONTENT_FORMAT_TEXT_PLAIN: u16 = 0;
+pub const CONTENT_FORMAT_LINK_FORMAT: u16 = 40;
+pub const CONTENT_FORMAT_XML: u16 = 41;
+pub const CONTENT_FORMAT_OCTET_STREAM: u16 = 42;
+pub const CONTENT_FORMAT_EXI: u16 = 47;
+pub const CONTENT_FORMAT_JSON: u16 = 50;
+pub const CONTENT_FORMAT_CBOR: u16 = 60;
+
+pub const NIBBLE_EXTENDED_8: u8 = 13;
+pub const NIBBLE_EXTENDED_16: u8 = 14;
+pub const NIBBLE_RESERVED: u8 = 15;
+pub const EXTENDED_8_OFFSET: u32 = 13;
+pub const EXTENDED_16_OFFSET: u32 = 269;
+
+/// CoAP Header
+#[repr(C)]
+pub struct Header {
+    /// Version (2 bits), message type (2 bits), and token length (4 bits).
+    pub flags: u8,
+
+    /// Code, with the class in the upper 3 bits and the detail in the lower
+    /// 5 bits.
+    pub code: u8,
+
+    /// Message identifier, to detect duplicates and match acknowledgements.
+    pub message_id: int::u16be,
+}
+
+/// Message Reference
+///
+/// A CoAP message with its token, options, and payload located.
+#[derive(Clone, Copy, Debug)]
+pub struct Message<'data> {
+    /// Entire message, including the header.
+    pub data: &'data [u8],
+
+    /// Token, of at most `TOKEN_MAX` bytes.
+    pub token: &'data [u8],
+
+    /// Encoded options (see `Options`).
+    pub options: &'data [u8],
+
+    /// Payload, excluding the payload marker.
+    pub payload: &'data [u8],
+}
+
+/// Option Iterator
+///
+/// An iterator over the options of a message, yielding the option number
+/// and value. Iteration stops at the end of the data, at the payload marker,
+/// or at the first truncated or malformed option.
+#[derive(Clone, Debug)]
+pub struct Options<'data> {
+    data: &'data [u8],
+    number: u32,
+}
+
+impl Header {
+    /// Import a header from a byte slice
+    ///
+    /// Create a new header from data copied from a byte slice. No byte-order
+    /// conversions are applied.
+    pub fn from_bytes(data: &[u8; 4]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 4], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Return version
+    pub fn version(&self) -> u8 {
+        (self.flags & VERSION_MASK) >> VERSION_SHIFT
+    }
+
+    /// Return message type
+    pub fn message_type(&self) -> u8 {
+        (self.flags & TYPE_MASK) >> TYPE_SHIFT
+    }
+
+    /// Return token length
+    pub fn token_length(&self) -> usize {
+        (self.flags & TOKEN_LENGTH_MASK) as usize
+    }
+
+    /// Return code class
+    pub fn code_class(&self) -> u8 {
+        self.code >> CODE_CLASS_SHIFT
+    }
+
+    /// Return code detail
+    pub fn code_detail(&self) -> u8 {
+        self.code & CODE_DETAIL_MASK
+    }
+}
+
+impl<'data> Message<'data> {
+    /// Parse a message
+    ///
+    /// Parse a CoAP message and locate its parts. Return `None` if the
+    /// message is truncated, the token length is invalid, an option is
+    /// malformed, or the payload marker is followed by an empty payload.
+    pub fn parse(data: &'data [u8]) -> Option<Self> {
+        let h = Header::from_bytes(data.get(..HEADER_SIZE)?.try_into().unwrap());
+        let n = h.token_length();
+        if n > TOKEN_MAX {
+            return None;
+        }
+
+        let token = data.get(HEADER_SIZE..HEADER_SIZE + n)?;
+        let rest = &data[HEADER_SIZE + n..];
+
+        let mut options = Options::new(rest);
+        for _ in options.by_ref() {}
+        let end = rest.len() - options.remainder().len();
+
+        let payload = match options.remainder() {
+            [] => &[][..],
+            [PAYLOAD_MARKER, v @ ..] if !v.is_empty() => v,
+            _ => return None,
+        };
+
+        Some(Self {
+            data,
+            token,
+            options: &rest[..end],
+            payload,
+        })
+    }
+
+    /// Return the message header
+    pub fn header(&self) -> Header {
+        Header::from_bytes(self.data[..HEADER_SIZE].try_into().unwrap())
+    }
+}
+
+impl<'data> Options<'data> {
+    /// Create option iterator
+    ///
+    /// Create a new iterator over the options in `data`, which must start
+    /// after the token.
+    pub fn new(data: &'data [u8]) -> Self {
+        Self {
+            data,
+            number: 0,
+        }
+    }
+
+    /// Return remaining data
+    ///
+    /// Return the data that has not been consumed by the iterator, starting
+    /// with the payload marker if the iteration reached it.
+    pub fn remainder(&self) -> &'data [u8] {
+        self.data
+    }
+}
+
+impl<'data> Iterator for Options<'data> {
+    type Item = (u16, &'data [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let first = *self.data.first()?;
+        if first == PAYLOAD_MARKER {
+            return None;
+        }
+
+        let mut o = 1;
+        let delta = nibble_at(self.data, &mut o, first >> 4)?;
+        let n = nibble_at(self.data, &mut o, first & 0x0f)? as usize;
+        let number = self.number + delta;
+        if number > u16::MAX as u32 {
+            return None;
+        }
+
+        let v = self.data.get(o..o + n)?;
+        self.data = &self.data[o + n..];
+        self.number = number;
+        Some((number as u16, v))
+    }
+}
+
+// Decode the option delta or length of the given nibble, reading its
+// extended value at `o` and advancing `o` past it.
+fn nibble_at(data: &[u8], o: &mut usize, nibble: u8) -> Option<u32> {
+    match nibble {
+        NIBBLE_EXTENDED_8 => {
+            let v = *data.get(*o)? as u32;
+            *o += 1;
+            Some(v + EXTENDED_8_OFFSET)
+        },
+        NIBBLE_EXTENDED_16 => {
+            let v = u16::from_be_bytes([*data.get(*o)?, *data.get(*o + 1)?]) as u32;
+            *o += 2;
+            Some(v + EXTENDED_16_OFFSET)
+        },
+        NIBBLE_RESERVED => None,
+        v => Some(v as u32),
+    }
+}
+
+/// Encode code
+///
+/// Return the code of the given class and detail, written as
+/// `class.detail`.
+pub const fn code(class: u8, detail: u8) -> u8 {
+    (class << CODE_CLASS_SHIFT) | (detail & CODE_DETAIL_MASK)
+}
+
+/// Check for critical options
+///
+/// Return whether an endpoint must reject a message with the given option
+/// if it does not recognize it.
+pub fn is_critical(number: u16) -> bool {
+    number & 0x01 != 0
+}
+
+/// Check for unsafe options
+///
+/// Return whether a proxy must not forward the given option if it does not
+/// recognize it.
+pub fn is_unsafe(number: u16) -> bool {
+    number & 0x02 != 0
+}
+
+/// Check for options excluded from the cache key
+pub fn is_no_cache_key(number: u16) -> bool {
+    number & 0x1e == 0x1c
+}
+
+/// Encode option header
+///
+/// Encode the header of an option with the given delta to the previous
+/// option number and value length, using the shortest extended forms.
+/// Return the number of bytes written, or `None` if the buffer is too small
+/// or a value exceeds the extended forms.
+pub fn encode_option_header(delta: u32, length: u32, out: &mut [u8]) -> Option<usize> {
+    // Split a value into its nibble and extended bytes.
+    fn split(v: u32) -> Option<(u8, [u8; 2], usize)> {
+        if v < EXTENDED_8_OFFSET {
+            Some((v as u8, [0; 2], 0))
+        } else if v < EXTENDED_16_OFFSET {
+            Some((NIBBLE_EXTENDED_8, [(v - EXTENDED_8_OFFSET) as u8, 0], 1))
+        } else {
+            let e = u16::try_from(v - EXTENDED_16_OFFSET).ok()?;
+            Some((NIBBLE_EXTENDED_16, e.to_be_bytes(), 2))
+        }
+    }
+
+    let (dn, de, dl) = split(delta)?;
+    let (ln, le, ll) = split(length)?;
+    let out = out.get_mut(..1 + dl + ll)?;
+    out[0] = (dn << 4) | ln;
+    out[1..1 + dl].copy_from_slice(&de[..dl]);
+    out[1 + dl..].copy_from_slice(&le[..ll]);
+    Some(1 + dl + ll)
+}
+
+/// Find option
+///
+/// Return the value of the first option with the given number in `data`.
+pub fn find_option(data: &[u8], number: u16) -> Option<&[u8]> {
+    Options::new(data).find(|v| v.0 == number).map(|v| v.1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A confirmable GET request for "/temperature" with a 1-byte token.
+    const REQUEST: [u8; 17] = [
+        0x41, 0x01, 0x7d, 0x34, 0x71, 0xbb, 0x74, 0x65,
+        0x6d, 0x70, 0x65, 0x72, 0x61, 0x74, 0x75, 0x72,
+        0x65,
+    ];
+
+    // The piggybacked 2.05 response, with options using both extended
+    // delta forms, and a payload.
+    const RESPONSE: [u8; 17] = [
+        0x61, 0x45, 0x7d, 0x34, 0x71, 0xc0, 0xd1, 0x03,
+        0x02, 0xe0, 0x02, 0xbf, 0xff, 0x32, 0x32, 0x2e,
+        0x35,
+    ];
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<Header>(), HEADER_SIZE);
+        assert_eq!(align_of::<Header>(), 2);
+    }
+
+    // Parse a request and its response.
+    #[test]
+    fn verify_message() {
+        let m = Message::parse(&REQUEST).unwrap();
+        let h = m.header();
+        assert_eq!(h.version(), VERSION);
+        assert_eq!(h.message_type(), TYPE_CONFIRMABLE);
+        assert_eq!(h.code, CODE_GET);
+        assert_eq!(int::ForeignEndian::to_native(h.message_id), 0x7d34);
+        assert_eq!(m.token, &[0x71]);
+        assert_eq!(find_option(m.options, OPTION_URI_PATH), Some(&b"temperature"[..]));
+        assert!(m.payload.is_empty());
+
+        let m = Message::parse(&RESPONSE).unwrap();
+        let h = m.header();
+        assert_eq!(h.message_type(), TYPE_ACKNOWLEDGEMENT);
+        assert_eq!((h.code_class(), h.code_detail()), (2, 5));
+        assert_eq!(h.code, CODE_CONTENT);
+        let mut opts = Options::new(m.options);
+        assert_eq!(opts.next(), Some((OPTION_CONTENT_FORMAT, &[][..])));
+        assert_eq!(opts.next(), Some((OPTION_SIZE2, &[0x02][..])));
+        assert_eq!(opts.next(), Some((1000, &[][..])));
+        assert!(opts.next().is_none());
+        assert!(opts.remainder().is_empty());
+        assert_eq!(m.payload, b"22.5");
+
+        assert!(Message::parse(&RESPONSE[..13]).is_none());
+        assert!(Message::parse(&REQUEST[..16]).is_none());
+        let mut data = REQUEST;
+        data[0] = 0x49;
+        assert!(Message::parse(&data).is_none());
+    }
+
+    // Encode option headers with extended forms and check option classes.
+    #[test]
+    fn verify_option_header() {
+        let mut buf = [0; 5];
+        assert_eq!(encode_option_header(11, 11, &mut buf), Some(1));
+        assert_eq!(buf[..1], [0xbb]);
+        assert_eq!(encode_option_header(16, 1, &mut buf), Some(2));
+        assert_eq!(buf[..2], [0xd1, 0x03]);
+        assert_eq!(encode_option_header(972, 300, &mut buf), Some(5));
+        assert_eq!(buf, [0xee, 0x02, 0xbf, 0x00, 0x1f]);
+        assert_eq!(encode_option_header(65805, 0, &mut buf), None);
+        assert_eq!(encode_option_header(13, 0, &mut buf[..1]), None);
+
+        assert!(Options::new(&[0xf0]).next().is_none());
+        assert!(Options::new(&[0x0f]).next().is_none());
+
+        assert!(is_critical(OPTION_URI_PATH));
+        assert!(!is_critical(OPTION_CONTENT_FORMAT));
+        assert!(is_unsafe(OPTION_URI_HOST));
+        assert!(!is_unsafe(OPTION_ETAG));
+        assert!(is_no_cache_key(OPTION_SIZE1));
+        assert!(!is_no_cache_key(OPTION_MAX_AGE));
+    }
+}
@@ -0,0 +1,149 @@
+//! OEM Code Pages
+//!
+//! MS-DOS and its descendants encode text in 8-bit OEM code pages, which
+//! extend ASCII with 128 national characters, box-drawing characters, and
+//! symbols. Code page 437 is the original IBM PC character set, while code
+//! page 850 replaces some of its graphics with additional Latin-1 letters.
+//! FAT short names, MS-DOS era strings, and ZIP entries without the UTF-8
+//! flag are commonly encoded in one of these.
+//!
+//! The tables map the upper half, `0x80` to `0xff`, of a code page to
+//! Unicode. The lower half is identical to ASCII. Some software renders the
+//! ASCII control range as graphics instead, which is a matter of display and
+//! not part of the conversion.
+
+/// Code Page Table
+///
+/// Unicode code points of the bytes `0x80` to `0xff` of a code page.
+pub type Table = [char; 128];
+
+/// Code Page 437 (OEM United States)
+pub const CP437: Table = [
+    '\u{00c7}', '\u{00fc}', '\u{00e9}', '\u{00e2}', '\u{00e4}', '\u{00e0}', '\u{00e5}', '\u{00e7}',
+    '\u{00ea}', '\u{00eb}', '\u{00e8}', '\u{00ef}', '\u{00ee}', '\u{00ec}', '\u{00c4}', '\u{00c5}',
+    '\u{00c9}', '\u{00e6}', '\u{00c6}', '\u{00f4}', '\u{00f6}', '\u{00f2}', '\u{00fb}', '\u{00f9}',
+    '\u{00ff}', '\u{00d6}', '\u{00dc}', '\u{00a2}', '\u{00a3}', '\u{00a5}', '\u{20a7}', '\u{0192}',
+    '\u{00e1}', '\u{00ed}', '\u{00f3}', '\u{00fa}', '\u{00f1}', '\u{00d1}', '\u{00aa}', '\u{00ba}',
+    '\u{00bf}', '\u{2310}', '\u{00ac}', '\u{00bd}', '\u{00bc}', '\u{00a1}', '\u{00ab}', '\u{00bb}',
+    '\u{2591}', '\u{2592}', '\u{2593}', '\u{2502}', '\u{2524}', '\u{2561}', '\u{2562}', '\u{2556}',
+    '\u{2555}', '\u{2563}', '\u{2551}', '\u{2557}', '\u{255d}', '\u{255c}', '\u{255b}', '\u{2510}',
+    '\u{2514}', '\u{2534}', '\u{252c}', '\u{251c}', '\u{2500}', '\u{253c}', '\u{255e}', '\u{255f}',
+    '\u{255a}', '\u{2554}', '\u{2569}', '\u{2566}', '\u{2560}', '\u{2550}', '\u{256c}', '\u{2567}',
+    '\u{2568}', '\u{2564}', '\u{2565}', '\u{2559}', '\u{2558}', '\u{2552}', '\u{2553}', '\u{256b}',
+    '\u{256a}', '\u{2518}', '\u{250c}', '\u{2588}', '\u{2584}', '\u{258c}', '\u{2590}', '\u{2580}',
+    '\u{03b1}', '\u{00df}', '\u{0393}', '\u{03c0}', '\u{03a3}', '\u{03c3}', '\u{00b5}', '\u{03c4}',
+    '\u{03a6}', '\u{0398}', '\u{03a9}', '\u{03b4}', '\u{221e}', '\u{03c6}', '\u{03b5}', '\u{2229}',
+    '\u{2261}', '\u{00b1}', '\u{2265}', '\u{2264}', '\u{2320}', '\u{2321}', '\u{00f7}', '\u{2248}',
+    '\u{00b0}', '\u{2219}', '\u{00b7}', '\u{221a}', '\u{207f}', '\u{00b2}', '\u{25a0}', '\u{00a0}',
+];
+
+/// Code Page 850 (OEM Multilingual Latin 1)
+pub const CP850: Table = [
+    '\u{00c7}', '\u{00fc}', '\u{00e9}', '\u{00e2}', '\u{00e4}', '\u{00e0}', '\u{00e5}', '\u{00e7}',
+    '\u{00ea}', '\u{00eb}', '\u{00e8}', '\u{00ef}', '\u{00ee}', '\u{00ec}', '\u{00c4}', '\u{00c5}',
+    '\u{00c9}', '\u{00e6}', '\u{00c6}', '\u{00f4}', '\u{00f6}', '\u{00f2}', '\u{00fb}', '\u{00f9}',
+    '\u{00ff}', '\u{00d6}', '\u{00dc}', '\u{00f8}', '\u{00a3}', '\u{00d8}', '\u{00d7}', '\u{0192}',
+    '\u{00e1}', '\u{00ed}', '\u{00f3}', '\u{00fa}', '\u{00f1}', '\u{00d1}', '\u{00aa}', '\u{00ba}',
+    '\u{00bf}', '\u{00ae}', '\u{00ac}', '\u{00bd}', '\u{00bc}', '\u{00a1}', '\u{00ab}', '\u{00bb}',
+    '\u{2591}', '\u{2592}', '\u{2593}', '\u{2502}', '\u{2524}', '\u{00c1}', '\u{00c2}', '\u{00c0}',
+    '\u{00a9}', '\u{2563}', '\u{2551}', '\u{2557}', '\u{255d}', '\u{00a2}', '\u{00a5}', '\u{2510}',
+    '\u{2514}', '\u{2534}', '\u{252c}', '\u{251c}', '\u{2500}', '\u{253c}', '\u{00e3}', '\u{00c3}',
+    '\u{255a}', '\u{2554}', '\u{2569}', '\u{2566}', '\u{2560}', '\u{2550}', '\u{256c}', '\u{00a4}',
+    '\u{00f0}', '\u{00d0}', '\u{00ca}', '\u{00cb}', '\u{00c8}', '\u{0131}', '\u{00cd}', '\u{00ce}',
+    '\u{00cf}', '\u{2518}', '\u{250c}', '\u{2588}', '\u{2584}', '\u{00a6}', '\u{00cc}', '\u{2580}',
+    '\u{00d3}', '\u{00df}', '\u{00d4}', '\u{00d2}', '\u{00f5}', '\u{00d5}', '\u{00b5}', '\u{00fe}',
+    '\u{00de}', '\u{00da}', '\u{00db}', '\u{00d9}', '\u{00fd}', '\u{00dd}', '\u{00af}', '\u{00b4}',
+    '\u{00ad}', '\u{00b1}', '\u{2017}', '\u{00be}', '\u{00b6}', '\u{00a7}', '\u{00f7}', '\u{00b8}',
+    '\u{00b0}', '\u{00a8}', '\u{00b7}', '\u{00b9}', '\u{00b3}', '\u{00b2}', '\u{25a0}', '\u{00a0}',
+];
+
+/// Decode byte
+///
+/// Return the code point of the byte `v` in the code page `table`.
+pub fn decode(table: &Table, v: u8) -> char {
+    match v {
+        0x00..=0x7f => v as char,
+        _ => table[(v - 0x80) as usize],
+    }
+}
+
+/// Encode code point
+///
+/// Return the byte of the code point `c` in the code page `table`, or
+/// `None` if the code page cannot represent it.
+pub fn encode(table: &Table, c: char) -> Option<u8> {
+    if c.is_ascii() {
+        Some(c as u8)
+    } else {
+        table.iter().position(|v| *v == c).map(|v| v as u8 + 0x80)
+    }
+}
+
+/// Decode string
+///
+/// Decode the string `data` in the code page `table` to UTF-8. Return the
+/// number of bytes written, or `None` if the buffer is too small.
+pub fn decode_str(table: &Table, data: &[u8], out: &mut [u8]) -> Option<usize> {
+    let mut o = 0;
+
+    for v in data {
+        let c = decode(table, *v);
+        let n = c.len_utf8();
+        c.encode_utf8(out.get_mut(o..o + n)?);
+        o += n;
+    }
+
+    Some(o)
+}
+
+/// Encode string
+///
+/// Encode the string `data` in the code page `table`. Return the number of
+/// bytes written, or `None` if a code point cannot be represented, or the
+/// buffer is too small.
+pub fn encode_str(table: &Table, data: &str, out: &mut [u8]) -> Option<usize> {
+    let mut o = 0;
+
+    for c in data.chars() {
+        *out.get_mut(o)? = encode(table, c)?;
+        o += 1;
+    }
+
+    Some(o)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Verify that the tables are bijective and disjoint from ASCII.
+    #[test]
+    fn verify_tables() {
+        for table in [&CP437, &CP850] {
+            for v in 0..=255u8 {
+                assert_eq!(encode(table, decode(table, v)), Some(v));
+            }
+        }
+    }
+
+    // Convert strings in both directions.
+    #[test]
+    fn verify_strings() {
+        let mut buf = [0; 16];
+
+        assert_eq!(decode_str(&CP437, b"\x9aber\xe1", &mut buf), Some(7));
+        assert_eq!(&buf[..7], "Überß".as_bytes());
+        assert_eq!(encode_str(&CP437, "Überß", &mut buf), Some(5));
+        assert_eq!(&buf[..5], b"\x9aber\xe1");
+        assert_eq!(decode(&CP437, 0xc9), '╔');
+        assert_eq!(decode(&CP850, 0xc9), '╔');
+        assert_eq!(decode(&CP437, 0x9b), '¢');
+        assert_eq!(decode(&CP850, 0x9b), 'ø');
+
+        assert_eq!(encode_str(&CP437, "Ø", &mut buf), None);
+        assert_eq!(encode_str(&CP850, "Ø", &mut buf), Some(1));
+        assert_eq!(buf[0], 0x9d);
+        assert_eq!(encode_str(&CP850, "€", &mut buf), None);
+        assert_eq!(decode_str(&CP437, b"\x9a", &mut buf[..1]), None);
+    }
+}
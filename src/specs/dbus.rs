@@ -0,0 +1,495 @@
+//! D-Bus Wire Format
+//!
+//! D-Bus messages start with a 16-byte `Header`, whose first byte selects
+//! the byte order of the entire message (`ENDIAN_*`). The header ends with
+//! the length of an array of header fields, which follows the header. Each
+//! header field is a struct of a field code (`FIELD_*`) and a variant,
+//! which carries the signature of its value, followed by the value (see
+//! `Fields`). The array is padded to a multiple of 8 bytes, and followed by
+//! the message body, whose signature is given in `FIELD_SIGNATURE`.
+//!
+//! All values are marshalled at an offset that is a multiple of the
+//! alignment of their type (see `alignment()`), relative to the start of the
+//! message, with padding bytes set to 0. Types are encoded as signatures of
+//! ASCII type codes (`TYPE_*`), where containers combine the single complete
+//! types that follow them (see `single_type_length()`). Strings and object
+//! paths carry a 32-bit length, signatures an 8-bit length, and all of them
+//! are followed by a 0-terminator (see `read_basic()`).
+//!
+//! Multi-byte integers are encoded in the byte order given in the header.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::int;
+
+pub const HEADER_SIZE: usize = 16;
+pub const VERSION: u8 = 1;
+
+/// Maximum Size of Messages
+pub const MESSAGE_MAX: usize = 1 << 27;
+
+/// Maximum Size of Arrays
+pub const ARRAY_MAX: usize = 1 << 26;
+
+/// Maximum Size of Signatures
+pub const SIGNATURE_MAX: usize = 255;
+
+/// Maximum Size of Names
+pub const NAME_MAX: usize = 255;
+
+/// Maximum Nesting Depth of Arrays and Structs Each
+pub const NESTING_MAX: usize = 32;
+
+pub const ENDIAN_LITTLE: u8 = b'l';
+pub const ENDIAN_BIG: u8 = b'B';
+
+/* reserved: 0 */
+pub const MESSAGE_METHOD_CALL: u8 = 1;
+pub const MESSAGE_METHOD_RETURN: u8 = 2;
+pub const MESSAGE_ERROR: u8 = 3;
+pub const MESSAGE_SIGNAL: u8 = 4;
+
+pub const FLAG_NO_REPLY_EXPECTED: u8 = 0x01;
+pub const FLAG_NO_AUTO_START: u8 = 0x02;
+pub const FLAG_ALLOW_INTERACTIVE_AUTHORIZATION: u8 = 0x04;
+
+/* reserved: 0 */
+pub const FIELD_PATH: u8 = 1;
+pub const FIELD_INTERFACE: u8 = 2;
+pub const FIELD_MEMBER: u8 = 3;
+pub const FIELD_ERROR_NAME: u8 = 4;
+pub const FIELD_REPLY_SERIAL: u8 = 5;
+pub const FIELD_DESTINATION: u8 = 6;
+pub const FIELD_SENDER: u8 = 7;
+pub const FIELD_SIGNATURE: u8 = 8;
+pub const FIELD_UNIX_FDS: u8 = 9;
+
+pub const TYPE_BYTE: u8 = b'y';
+pub const TYPE_BOOLEAN: u8 = b'b';
+pub const TYPE_INT16: u8 = b'n';
+pub const TYPE_UINT16: u8 = b'q';
+pub const TYPE_INT32: u8 = b'i';
+pub const TYPE_UINT32: u8 = b'u';
+pub const TYPE_INT64: u8 = b'x';
+pub const TYPE_UINT64: u8 = b't';
+pub const TYPE_DOUBLE: u8 = b'd';
+pub const TYPE_STRING: u8 = b's';
+pub const TYPE_OBJECT_PATH: u8 = b'o';
+pub const TYPE_SIGNATURE: u8 = b'g';
+pub const TYPE_UNIX_FD: u8 = b'h';
+pub const TYPE_ARRAY: u8 = b'a';
+pub const TYPE_VARIANT: u8 = b'v';
+pub const TYPE_STRUCT_BEGIN: u8 = b'(';
+pub const TYPE_STRUCT_END: u8 = b')';
+pub const TYPE_DICT_ENTRY_BEGIN: u8 = b'{';
+pub const TYPE_DICT_ENTRY_END: u8 = b'}';
+
+/// Message Header
+#[repr(C)]
+pub struct Header {
+    /// Byte order of the message (`ENDIAN_*`).
+    pub endian: u8,
+
+    /// Message type (`MESSAGE_*`).
+    pub message_type: u8,
+
+    /// Message flags (`FLAG_*`).
+    pub flags: u8,
+
+    /// Protocol version, `VERSION`.
+    pub version: u8,
+
+    /// Size of the body in bytes.
+    pub body_length: int::u32re,
+
+    /// Serial number of the message, which must not be 0.
+    pub serial: int::u32re,
+
+    /// Size of the header field array in bytes, without trailing padding.
+    pub fields_length: int::u32re,
+}
+
+/// Header Field Iterator
+///
+/// Iterate over the header fields of a message, yielding the field code,
+/// the type code of its value, and the value as returned by `read_basic()`.
+/// Iteration stops at the first field whose value is not of a single basic
+/// type, since no standard header field uses other types, and at the first
+/// field that exceeds the data.
+#[derive(Clone, Debug)]
+pub struct Fields<'data> {
+    data: &'data [u8],
+    endian: int::Endianness,
+    offset: usize,
+}
+
+impl Header {
+    /// Import a header from a byte slice
+    ///
+    /// Create a new header from data copied from a byte slice. No byte-order
+    /// conversions are applied.
+    pub fn from_bytes(data: &[u8; 16]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 16], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Return byte order
+    ///
+    /// Return the byte order of the message, or `None` if invalid.
+    pub fn endianness(&self) -> Option<int::Endianness> {
+        endianness(self.endian)
+    }
+
+    /// Return message size
+    ///
+    /// Return the total size of the message, including the header, the
+    /// padded header field array, and the body. Return `None` if it exceeds
+    /// `MESSAGE_MAX`.
+    pub fn message_size(&self, endian: int::Endianness) -> Option<usize> {
+        let fields = self.fields_length.to_native(endian) as usize;
+        let body = self.body_length.to_native(endian) as usize;
+        let n = HEADER_SIZE + fields.next_multiple_of(8) + body;
+        if fields > ARRAY_MAX || n > MESSAGE_MAX {
+            None
+        } else {
+            Some(n)
+        }
+    }
+}
+
+impl<'data> Fields<'data> {
+    /// Create header field iterator
+    ///
+    /// Create a new iterator over the header fields of the message in
+    /// `data`, which must start with the `Header`, encoded in the given byte
+    /// order.
+    pub fn new(data: &'data [u8], endian: int::Endianness) -> Self {
+        let end = data
+            .get(12..16)
+            .map(|v| int::u32re::from_raw(u32::from_ne_bytes([v[0], v[1], v[2], v[3]])))
+            .map(|v| HEADER_SIZE.saturating_add(v.to_native(endian) as usize))
+            .unwrap_or(0);
+
+        Self {
+            data: &data[..end.min(data.len())],
+            endian,
+            offset: HEADER_SIZE,
+        }
+    }
+
+    /// Return remaining data
+    ///
+    /// Return the header field data that has not been consumed by the
+    /// iterator.
+    pub fn remainder(&self) -> &'data [u8] {
+        self.data.get(self.offset..).unwrap_or(&[])
+    }
+}
+
+impl<'data> Iterator for Fields<'data> {
+    type Item = (u8, u8, &'data [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let o = self.offset.next_multiple_of(8);
+        let code = *self.data.get(o)?;
+        let (signature, o) = read_basic(self.data, o + 1, TYPE_SIGNATURE, self.endian)?;
+        let r = match signature {
+            [t] => read_basic(self.data, o, *t, self.endian).map(|(v, o)| (*t, v, o)),
+            _ => None,
+        };
+
+        match r {
+            Some((t, v, o)) => {
+                self.offset = o;
+                Some((code, t, v))
+            },
+            None => {
+                self.offset = self.data.len();
+                None
+            },
+        }
+    }
+}
+
+/// Return byte order
+///
+/// Return the byte order selected by the first byte of a message, or `None`
+/// if invalid.
+pub fn endianness(v: u8) -> Option<int::Endianness> {
+    match v {
+        ENDIAN_LITTLE => Some(int::Endianness::Little),
+        ENDIAN_BIG => Some(int::Endianness::Big),
+        _ => None,
+    }
+}
+
+/// Return type alignment
+///
+/// Return the alignment of values of the type with the given type code, or
+/// `None` for invalid type codes. Structs and dict entries are identified by
+/// their opening parenthesis or brace. The alignment of arrays is the one of
+/// their 32-bit length, and the elements are aligned to their own type.
+pub fn alignment(type_code: u8) -> Option<usize> {
+    match type_code {
+        TYPE_BYTE | TYPE_SIGNATURE | TYPE_VARIANT => Some(1),
+        TYPE_INT16 | TYPE_UINT16 => Some(2),
+        TYPE_BOOLEAN | TYPE_INT32 | TYPE_UINT32 | TYPE_UNIX_FD => Some(4),
+        TYPE_STRING | TYPE_OBJECT_PATH | TYPE_ARRAY => Some(4),
+        TYPE_INT64 | TYPE_UINT64 | TYPE_DOUBLE => Some(8),
+        TYPE_STRUCT_BEGIN | TYPE_DICT_ENTRY_BEGIN => Some(8),
+        _ => None,
+    }
+}
+
+/// Return fixed type size
+///
+/// Return the size of values of the basic type with the given type code, or
+/// `None` if the type is not a fixed-size basic type.
+pub fn fixed_size(type_code: u8) -> Option<usize> {
+    match type_code {
+        TYPE_BYTE => Some(1),
+        TYPE_INT16 | TYPE_UINT16 => Some(2),
+        TYPE_BOOLEAN | TYPE_INT32 | TYPE_UINT32 | TYPE_UNIX_FD => Some(4),
+        TYPE_INT64 | TYPE_UINT64 | TYPE_DOUBLE => Some(8),
+        _ => None,
+    }
+}
+
+/// Align offset
+///
+/// Return `offset` rounded up to the alignment of the type with the given
+/// type code, or `None` for invalid type codes.
+pub fn align(offset: usize, type_code: u8) -> Option<usize> {
+    Some(offset.next_multiple_of(alignment(type_code)?))
+}
+
+/// Read basic value
+///
+/// Read a value of the basic type with the given type code at `offset` of
+/// `data`, after skipping the alignment padding, which must be 0. `data`
+/// must start at the start of the message, or another 8-byte aligned
+/// position. Return the value and the offset following it.
+///
+/// Fixed-size values are returned as their raw bytes. Strings, object paths,
+/// and signatures are returned without their length and 0-terminator.
+/// Return `None` for other types, if the data is truncated, or the value is
+/// not 0-terminated.
+pub fn read_basic(
+    data: &[u8],
+    offset: usize,
+    type_code: u8,
+    endian: int::Endianness,
+) -> Option<(&[u8], usize)> {
+    let start = align(offset, type_code)?;
+    if data.get(offset..start)?.iter().any(|v| *v != 0) {
+        return None;
+    }
+
+    let (length, start) = match type_code {
+        TYPE_STRING | TYPE_OBJECT_PATH => {
+            let v = data.get(start..start + 4)?;
+            let v = int::u32re::from_raw(u32::from_ne_bytes([v[0], v[1], v[2], v[3]]));
+            (v.to_native(endian) as usize, start + 4)
+        },
+        TYPE_SIGNATURE => (*data.get(start)? as usize, start + 1),
+        _ => {
+            let n = fixed_size(type_code)?;
+            return Some((data.get(start..start + n)?, start + n));
+        },
+    };
+
+    let end = start.checked_add(length)?;
+    if *data.get(end)? != 0 {
+        return None;
+    }
+    Some((&data[start..end], end + 1))
+}
+
+/// Return length of single complete type
+///
+/// Return the length of the single complete type at the start of
+/// `signature`, or `None` if it does not start with a valid single complete
+/// type. Dict entries must be array elements, and must have a basic key
+/// type.
+pub fn single_type_length(signature: &[u8]) -> Option<usize> {
+    // Parse a single type, tracking the array and struct nesting depth.
+    fn parse(s: &[u8], arrays: usize, structs: usize, dict: bool) -> Option<usize> {
+        if arrays > NESTING_MAX || structs > NESTING_MAX {
+            return None;
+        }
+
+        match *s.first()? {
+            TYPE_ARRAY => Some(1 + parse(&s[1..], arrays + 1, structs, true)?),
+            TYPE_STRUCT_BEGIN => {
+                let mut n = 1;
+                while *s.get(n)? != TYPE_STRUCT_END {
+                    n += parse(&s[n..], arrays, structs + 1, false)?;
+                }
+                if n == 1 { None } else { Some(n + 1) }
+            },
+            TYPE_DICT_ENTRY_BEGIN if dict => {
+                let key = *s.get(1)?;
+                if fixed_size(key).is_none()
+                    && !matches!(key, TYPE_STRING | TYPE_OBJECT_PATH | TYPE_SIGNATURE)
+                {
+                    return None;
+                }
+                let n = 2 + parse(&s[2..], arrays, structs + 1, false)?;
+                if *s.get(n)? == TYPE_DICT_ENTRY_END { Some(n + 1) } else { None }
+            },
+            TYPE_VARIANT | TYPE_STRING | TYPE_OBJECT_PATH | TYPE_SIGNATURE => Some(1),
+            v => fixed_size(v).map(|_| 1),
+        }
+    }
+
+    if signature.len() > SIGNATURE_MAX {
+        return None;
+    }
+    parse(signature, 0, 0, false)
+}
+
+/// Find header field
+///
+/// Return the type code and value of the first header field with the given
+/// code of the message in `data`.
+pub fn find_field(data: &[u8], endian: int::Endianness, code: u8) -> Option<(u8, &[u8])> {
+    Fields::new(data, endian).find(|v| v.0 == code).map(|v| (v.1, v.2))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<Header>(), HEADER_SIZE);
+        assert_eq!(align_of::<Header>(), 4);
+    }
+
+    // Little-endian `Hello` method call to the message bus.
+    const HELLO: [u8; 128] = [
+        0x6c, 0x01, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00,
+        0x01, 0x00, 0x00, 0x00, 0x6d, 0x00, 0x00, 0x00,
+        0x01, 0x01, 0x6f, 0x00, 0x15, 0x00, 0x00, 0x00,
+        0x2f, 0x6f, 0x72, 0x67, 0x2f, 0x66, 0x72, 0x65,
+        0x65, 0x64, 0x65, 0x73, 0x6b, 0x74, 0x6f, 0x70,
+        0x2f, 0x44, 0x42, 0x75, 0x73, 0x00, 0x00, 0x00,
+        0x03, 0x01, 0x73, 0x00, 0x05, 0x00, 0x00, 0x00,
+        0x48, 0x65, 0x6c, 0x6c, 0x6f, 0x00, 0x00, 0x00,
+        0x02, 0x01, 0x73, 0x00, 0x14, 0x00, 0x00, 0x00,
+        0x6f, 0x72, 0x67, 0x2e, 0x66, 0x72, 0x65, 0x65,
+        0x64, 0x65, 0x73, 0x6b, 0x74, 0x6f, 0x70, 0x2e,
+        0x44, 0x42, 0x75, 0x73, 0x00, 0x00, 0x00, 0x00,
+        0x06, 0x01, 0x73, 0x00, 0x14, 0x00, 0x00, 0x00,
+        0x6f, 0x72, 0x67, 0x2e, 0x66, 0x72, 0x65, 0x65,
+        0x64, 0x65, 0x73, 0x6b, 0x74, 0x6f, 0x70, 0x2e,
+        0x44, 0x42, 0x75, 0x73, 0x00, 0x00, 0x00, 0x00,
+    ];
+
+    // Big-endian reply to `Hello`, with the unique name as body.
+    const REPLY: [u8; 90] = [
+        0x42, 0x02, 0x01, 0x01, 0x00, 0x00, 0x00, 0x0a,
+        0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x3d,
+        0x05, 0x01, 0x75, 0x00, 0x00, 0x00, 0x00, 0x01,
+        0x06, 0x01, 0x73, 0x00, 0x00, 0x00, 0x00, 0x05,
+        0x3a, 0x31, 0x2e, 0x34, 0x32, 0x00, 0x00, 0x00,
+        0x08, 0x01, 0x67, 0x00, 0x01, 0x73, 0x00, 0x00,
+        0x07, 0x01, 0x73, 0x00, 0x00, 0x00, 0x00, 0x14,
+        0x6f, 0x72, 0x67, 0x2e, 0x66, 0x72, 0x65, 0x65,
+        0x64, 0x65, 0x73, 0x6b, 0x74, 0x6f, 0x70, 0x2e,
+        0x44, 0x42, 0x75, 0x73, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x05, 0x3a, 0x31, 0x2e, 0x34,
+        0x32, 0x00,
+    ];
+
+    // Decode the headers and header fields of messages in both byte orders.
+    #[test]
+    fn verify_message() {
+        let h = Header::from_bytes(HELLO[..16].try_into().unwrap());
+        let e = h.endianness().unwrap();
+        assert_eq!(e, int::Endianness::Little);
+        assert_eq!(h.message_type, MESSAGE_METHOD_CALL);
+        assert_eq!(h.version, VERSION);
+        assert_eq!(h.serial.to_native(e), 1);
+        assert_eq!(h.message_size(e), Some(HELLO.len()));
+
+        let mut f = Fields::new(&HELLO, e);
+        assert_eq!(f.next(), Some((FIELD_PATH, TYPE_OBJECT_PATH, &b"/org/freedesktop/DBus"[..])));
+        assert_eq!(f.next(), Some((FIELD_MEMBER, TYPE_STRING, &b"Hello"[..])));
+        assert_eq!(f.next(), Some((FIELD_INTERFACE, TYPE_STRING, &b"org.freedesktop.DBus"[..])));
+        assert_eq!(f.next(), Some((FIELD_DESTINATION, TYPE_STRING, &b"org.freedesktop.DBus"[..])));
+        assert_eq!(f.next(), None);
+        assert!(f.remainder().is_empty());
+
+        let h = Header::from_bytes(REPLY[..16].try_into().unwrap());
+        let e = h.endianness().unwrap();
+        assert_eq!(e, int::Endianness::Big);
+        assert_eq!(h.message_type, MESSAGE_METHOD_RETURN);
+        assert_eq!(h.flags, FLAG_NO_REPLY_EXPECTED);
+        assert_eq!(h.message_size(e), Some(REPLY.len()));
+
+        assert_eq!(
+            find_field(&REPLY, e, FIELD_REPLY_SERIAL),
+            Some((TYPE_UINT32, &[0, 0, 0, 1][..])),
+        );
+        assert_eq!(find_field(&REPLY, e, FIELD_SIGNATURE), Some((TYPE_SIGNATURE, &b"s"[..])));
+        assert_eq!(find_field(&REPLY, e, FIELD_PATH), None);
+
+        let body = h.message_size(e).unwrap() - h.body_length.to_native(e) as usize;
+        let v = Some((&b":1.42"[..], REPLY.len()));
+        assert_eq!(read_basic(&REPLY, body, TYPE_STRING, e), v);
+        assert_eq!(read_basic(&REPLY, body - 1, TYPE_STRING, e), v);
+        assert_eq!(read_basic(&REPLY[..89], body, TYPE_STRING, e), None);
+
+        let mut data = REPLY;
+        data[body - 1] = 0x01;
+        assert_eq!(read_basic(&data, body - 3, TYPE_STRING, e), None);
+        data[89] = 0x01;
+        assert_eq!(read_basic(&data, body, TYPE_STRING, e), None);
+    }
+
+    // Verify alignment rules and signature parsing.
+    #[test]
+    fn verify_signature() {
+        assert_eq!(alignment(TYPE_BYTE), Some(1));
+        assert_eq!(alignment(TYPE_UINT16), Some(2));
+        assert_eq!(alignment(TYPE_STRING), Some(4));
+        assert_eq!(alignment(TYPE_DOUBLE), Some(8));
+        assert_eq!(alignment(TYPE_STRUCT_BEGIN), Some(8));
+        assert_eq!(alignment(b'z'), None);
+        assert_eq!(align(13, TYPE_UINT32), Some(16));
+        assert_eq!(align(13, TYPE_SIGNATURE), Some(13));
+        assert_eq!(fixed_size(TYPE_BOOLEAN), Some(4));
+        assert_eq!(fixed_size(TYPE_STRING), None);
+
+        assert_eq!(single_type_length(b"s"), Some(1));
+        assert_eq!(single_type_length(b"a{sv}i"), Some(5));
+        assert_eq!(single_type_length(b"(ia(sv))u"), Some(8));
+        assert_eq!(single_type_length(b"aai"), Some(3));
+        assert_eq!(single_type_length(b"()"), None);
+        assert_eq!(single_type_length(b"{sv}"), None);
+        assert_eq!(single_type_length(b"a{vs}"), None);
+        assert_eq!(single_type_length(b"a{sii}"), None);
+        assert_eq!(single_type_length(b"(i"), None);
+
+        let mut s = [TYPE_ARRAY; 34];
+        s[32] = TYPE_INT32;
+        assert_eq!(single_type_length(&s), Some(33));
+        s[32] = TYPE_ARRAY;
+        s[33] = TYPE_INT32;
+        assert_eq!(single_type_length(&s), None);
+        assert_eq!(single_type_length(b""), None);
+    }
+}
@@ -0,0 +1,426 @@
+//! DirectDraw Surface
+//!
+//! DDS files store textures, including mipmaps, cube maps, volume textures,
+//! and texture arrays, in a layout ready for upload to the GPU. A file
+//! starts with `MAGIC`, followed by the 124-byte `Header`, which embeds the
+//! `PixelFormat`. If the pixel format carries the four-character code
+//! `FOURCC_DX10`, the `HeaderDx10` extension follows, describing the
+//! format as a DXGI format (`DXGI_FORMAT_*`). The surface data follows the
+//! headers, with all mipmaps of each array element (or cube face) in order.
+//!
+//! Block-compressed formats (BC1 to BC7, also known as DXT1 to DXT5 for
+//! BC1 to BC3) store 4x4 pixel blocks of 8 or 16 bytes each (see
+//! `block_size()` and `compressed_size()`).
+//!
+//! All multi-byte integers are encoded as little-endian.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::int;
+
+/// File Magic
+///
+/// The initial 4 bytes of every file ("DDS ").
+pub const MAGIC: [u8; 4] = [0x44, 0x44, 0x53, 0x20];
+
+/// Size of the Header
+pub const HEADER_SIZE: u32 = 124;
+
+/// Size of the Pixel Format
+pub const PIXEL_FORMAT_SIZE: u32 = 32;
+
+pub const DDSD_CAPS: u32 = 0x0000_0001;
+pub const DDSD_HEIGHT: u32 = 0x0000_0002;
+pub const DDSD_WIDTH: u32 = 0x0000_0004;
+pub const DDSD_PITCH: u32 = 0x0000_0008;
+pub const DDSD_PIXELFORMAT: u32 = 0x0000_1000;
+pub const DDSD_MIPMAPCOUNT: u32 = 0x0002_0000;
+pub const DDSD_LINEARSIZE: u32 = 0x0008_0000;
+pub const DDSD_DEPTH: u32 = 0x0080_0000;
+
+pub const DDPF_ALPHAPIXELS: u32 = 0x0000_0001;
+pub const DDPF_ALPHA: u32 = 0x0000_0002;
+pub const DDPF_FOURCC: u32 = 0x0000_0004;
+pub const DDPF_RGB: u32 = 0x0000_0040;
+pub const DDPF_YUV: u32 = 0x0000_0200;
+pub const DDPF_LUMINANCE: u32 = 0x0002_0000;
+
+pub const DDSCAPS_COMPLEX: u32 = 0x0000_0008;
+pub const DDSCAPS_TEXTURE: u32 = 0x0000_1000;
+pub const DDSCAPS_MIPMAP: u32 = 0x0040_0000;
+
+pub const DDSCAPS2_CUBEMAP: u32 = 0x0000_0200;
+pub const DDSCAPS2_CUBEMAP_POSITIVEX: u32 = 0x0000_0400;
+pub const DDSCAPS2_CUBEMAP_NEGATIVEX: u32 = 0x0000_0800;
+pub const DDSCAPS2_CUBEMAP_POSITIVEY: u32 = 0x0000_1000;
+pub const DDSCAPS2_CUBEMAP_NEGATIVEY: u32 = 0x0000_2000;
+pub const DDSCAPS2_CUBEMAP_POSITIVEZ: u32 = 0x0000_4000;
+pub const DDSCAPS2_CUBEMAP_NEGATIVEZ: u32 = 0x0000_8000;
+pub const DDSCAPS2_CUBEMAP_ALLFACES: u32 = 0x0000_fc00;
+pub const DDSCAPS2_VOLUME: u32 = 0x0020_0000;
+
+pub const FOURCC_DXT1: [u8; 4] = *b"DXT1";
+pub const FOURCC_DXT2: [u8; 4] = *b"DXT2";
+pub const FOURCC_DXT3: [u8; 4] = *b"DXT3";
+pub const FOURCC_DXT4: [u8; 4] = *b"DXT4";
+pub const FOURCC_DXT5: [u8; 4] = *b"DXT5";
+pub const FOURCC_ATI1: [u8; 4] = *b"ATI1";
+pub const FOURCC_ATI2: [u8; 4] = *b"ATI2";
+pub const FOURCC_BC4U: [u8; 4] = *b"BC4U";
+pub const FOURCC_BC4S: [u8; 4] = *b"BC4S";
+pub const FOURCC_BC5U: [u8; 4] = *b"BC5U";
+pub const FOURCC_BC5S: [u8; 4] = *b"BC5S";
+pub const FOURCC_DX10: [u8; 4] = *b"DX10";
+
+pub const DIMENSION_TEXTURE1D: u32 = 2;
+pub const DIMENSION_TEXTURE2D: u32 = 3;
+pub const DIMENSION_TEXTURE3D: u32 = 4;
+
+pub const MISC_TEXTURECUBE: u32 = 0x4;
+
+pub const ALPHA_MODE_UNKNOWN: u32 = 0;
+pub const ALPHA_MODE_STRAIGHT: u32 = 1;
+pub const ALPHA_MODE_PREMULTIPLIED: u32 = 2;
+pub const ALPHA_MODE_OPAQUE: u32 = 3;
+pub const ALPHA_MODE_CUSTOM: u32 = 4;
+pub const ALPHA_MODE_MASK: u32 = 0x7;
+
+pub const DXGI_FORMAT_UNKNOWN: u32 = 0;
+pub const DXGI_FORMAT_R32G32B32A32_FLOAT: u32 = 2;
+pub const DXGI_FORMAT_R32G32B32_FLOAT: u32 = 6;
+pub const DXGI_FORMAT_R16G16B16A16_FLOAT: u32 = 10;
+pub const DXGI_FORMAT_R16G16B16A16_UNORM: u32 = 11;
+pub const DXGI_FORMAT_R32G32_FLOAT: u32 = 16;
+pub const DXGI_FORMAT_R10G10B10A2_UNORM: u32 = 24;
+pub const DXGI_FORMAT_R11G11B10_FLOAT: u32 = 26;
+pub const DXGI_FORMAT_R8G8B8A8_UNORM: u32 = 28;
+pub const DXGI_FORMAT_R8G8B8A8_UNORM_SRGB: u32 = 29;
+pub const DXGI_FORMAT_R16G16_FLOAT: u32 = 34;
+pub const DXGI_FORMAT_R32_FLOAT: u32 = 41;
+pub const DXGI_FORMAT_R8G8_UNORM: u32 = 49;
+pub const DXGI_FORMAT_R16_FLOAT: u32 = 54;
+pub const DXGI_FORMAT_R16_UNORM: u32 = 56;
+pub const DXGI_FORMAT_R8_UNORM: u32 = 61;
+pub const DXGI_FORMAT_A8_UNORM: u32 = 65;
+pub const DXGI_FORMAT_R9G9B9E5_SHAREDEXP: u32 = 67;
+pub const DXGI_FORMAT_BC1_TYPELESS: u32 = 70;
+pub const DXGI_FORMAT_BC1_UNORM: u32 = 71;
+pub const DXGI_FORMAT_BC1_UNORM_SRGB: u32 = 72;
+pub const DXGI_FORMAT_BC2_TYPELESS: u32 = 73;
+pub const DXGI_FORMAT_BC2_UNORM: u32 = 74;
+pub const DXGI_FORMAT_BC2_UNORM_SRGB: u32 = 75;
+pub const DXGI_FORMAT_BC3_TYPELESS: u32 = 76;
+pub const DXGI_FORMAT_BC3_UNORM: u32 = 77;
+pub const DXGI_FORMAT_BC3_UNORM_SRGB: u32 = 78;
+pub const DXGI_FORMAT_BC4_TYPELESS: u32 = 79;
+pub const DXGI_FORMAT_BC4_UNORM: u32 = 80;
+pub const DXGI_FORMAT_BC4_SNORM: u32 = 81;
+pub const DXGI_FORMAT_BC5_TYPELESS: u32 = 82;
+pub const DXGI_FORMAT_BC5_UNORM: u32 = 83;
+pub const DXGI_FORMAT_BC5_SNORM: u32 = 84;
+pub const DXGI_FORMAT_B5G6R5_UNORM: u32 = 85;
+pub const DXGI_FORMAT_B5G5R5A1_UNORM: u32 = 86;
+pub const DXGI_FORMAT_B8G8R8A8_UNORM: u32 = 87;
+pub const DXGI_FORMAT_B8G8R8X8_UNORM: u32 = 88;
+pub const DXGI_FORMAT_B8G8R8A8_UNORM_SRGB: u32 = 91;
+pub const DXGI_FORMAT_B8G8R8X8_UNORM_SRGB: u32 = 93;
+pub const DXGI_FORMAT_BC6H_TYPELESS: u32 = 94;
+pub const DXGI_FORMAT_BC6H_UF16: u32 = 95;
+pub const DXGI_FORMAT_BC6H_SF16: u32 = 96;
+pub const DXGI_FORMAT_BC7_TYPELESS: u32 = 97;
+pub const DXGI_FORMAT_BC7_UNORM: u32 = 98;
+pub const DXGI_FORMAT_BC7_UNORM_SRGB: u32 = 99;
+
+/// Pixel Format
+#[repr(C)]
+pub struct PixelFormat {
+    /// Size of the structure, must be `PIXEL_FORMAT_SIZE`.
+    pub size: int::u32le,
+
+    /// Pixel format flags (`DDPF_*`).
+    pub flags: int::u32le,
+
+    /// Four-character code of compressed formats (`FOURCC_*`), valid if
+    /// `DDPF_FOURCC` is set.
+    pub four_cc: [u8; 4],
+
+    /// Number of bits per pixel of uncompressed formats.
+    pub rgb_bit_count: int::u32le,
+
+    /// Channel masks of uncompressed formats.
+    pub r_bit_mask: int::u32le,
+    pub g_bit_mask: int::u32le,
+    pub b_bit_mask: int::u32le,
+    pub a_bit_mask: int::u32le,
+}
+
+/// File Header
+///
+/// The header following `MAGIC`.
+#[repr(C)]
+pub struct Header {
+    /// Size of the structure, must be `HEADER_SIZE`.
+    pub size: int::u32le,
+
+    /// Flags of valid fields (`DDSD_*`).
+    pub flags: int::u32le,
+
+    /// Surface height and width in pixels.
+    pub height: int::u32le,
+    pub width: int::u32le,
+
+    /// Bytes per row of uncompressed formats, or the total size of the top
+    /// level of compressed formats.
+    pub pitch_or_linear_size: int::u32le,
+
+    /// Depth of volume textures.
+    pub depth: int::u32le,
+
+    /// Number of mipmap levels.
+    pub mip_map_count: int::u32le,
+
+    pub reserved1: [int::u32le; 11],
+
+    /// Pixel format.
+    pub pixel_format: PixelFormat,
+
+    /// Surface capabilities (`DDSCAPS_*`, `DDSCAPS2_*`).
+    pub caps: int::u32le,
+    pub caps2: int::u32le,
+    pub caps3: int::u32le,
+    pub caps4: int::u32le,
+
+    pub reserved2: int::u32le,
+}
+
+/// DX10 Header Extension
+///
+/// The extension following the header if the pixel format carries
+/// `FOURCC_DX10`.
+#[repr(C)]
+pub struct HeaderDx10 {
+    /// Surface format (`DXGI_FORMAT_*`).
+    pub dxgi_format: int::u32le,
+
+    /// Resource dimension (`DIMENSION_*`).
+    pub resource_dimension: int::u32le,
+
+    /// Miscellaneous flags (`MISC_*`).
+    pub misc_flag: int::u32le,
+
+    /// Number of array elements, or of cubes for cube maps.
+    pub array_size: int::u32le,
+
+    /// Alpha mode (`ALPHA_MODE_*`) in the low bits.
+    pub misc_flags2: int::u32le,
+}
+
+impl PixelFormat {
+    /// Import a pixel format from a byte slice
+    ///
+    /// Create a new pixel format from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 32]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 32], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Return four-character code
+    ///
+    /// Return the four-character code, or `None` if `DDPF_FOURCC` is not
+    /// set.
+    pub fn four_cc(&self) -> Option<[u8; 4]> {
+        match int::ForeignEndian::to_native(self.flags) & DDPF_FOURCC {
+            0 => None,
+            _ => Some(self.four_cc),
+        }
+    }
+}
+
+impl Header {
+    /// Import a file header from a byte slice
+    ///
+    /// Create a new file header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 124]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 124], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Check for the DX10 extension
+    pub fn has_dx10(&self) -> bool {
+        self.pixel_format.four_cc() == Some(FOURCC_DX10)
+    }
+
+    /// Return mipmap count
+    ///
+    /// Return the number of mipmap levels, which is 1 if `DDSD_MIPMAPCOUNT`
+    /// is not set, or the count is 0.
+    pub fn mip_levels(&self) -> u32 {
+        let flags = int::ForeignEndian::to_native(self.flags);
+        let count = int::ForeignEndian::to_native(self.mip_map_count);
+        match flags & DDSD_MIPMAPCOUNT {
+            0 => 1,
+            _ => core::cmp::max(count, 1),
+        }
+    }
+}
+
+impl HeaderDx10 {
+    /// Import a DX10 header from a byte slice
+    ///
+    /// Create a new DX10 header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 20]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 20], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+/// Return Block Size of DXGI Formats
+///
+/// Return the size in bytes of a 4x4 block of a block-compressed DXGI
+/// format, or `None` if the format is not block-compressed.
+pub fn block_size(dxgi_format: u32) -> Option<usize> {
+    match dxgi_format {
+        DXGI_FORMAT_BC1_TYPELESS..=DXGI_FORMAT_BC1_UNORM_SRGB => Some(8),
+        DXGI_FORMAT_BC4_TYPELESS..=DXGI_FORMAT_BC4_SNORM => Some(8),
+        DXGI_FORMAT_BC2_TYPELESS..=DXGI_FORMAT_BC3_UNORM_SRGB => Some(16),
+        DXGI_FORMAT_BC5_TYPELESS..=DXGI_FORMAT_BC5_SNORM => Some(16),
+        DXGI_FORMAT_BC6H_TYPELESS..=DXGI_FORMAT_BC7_UNORM_SRGB => Some(16),
+        _ => None,
+    }
+}
+
+/// Return Block Size of Four-character Codes
+///
+/// Return the size in bytes of a 4x4 block of a block-compressed legacy
+/// format, or `None` if the code is not a known block-compressed format.
+pub fn four_cc_block_size(four_cc: &[u8; 4]) -> Option<usize> {
+    match *four_cc {
+        FOURCC_DXT1 | FOURCC_ATI1 | FOURCC_BC4U | FOURCC_BC4S => Some(8),
+        FOURCC_DXT2 | FOURCC_DXT3 | FOURCC_DXT4 | FOURCC_DXT5 => Some(16),
+        FOURCC_ATI2 | FOURCC_BC5U | FOURCC_BC5S => Some(16),
+        _ => None,
+    }
+}
+
+/// Calculate Compressed Surface Size
+///
+/// Return the size in bytes of a block-compressed surface with the given
+/// dimensions in pixels. Partial blocks are rounded up.
+pub fn compressed_size(width: u32, height: u32, block_size: usize) -> usize {
+    let w = core::cmp::max(1, (width as usize + 3) >> 2);
+    let h = core::cmp::max(1, (height as usize + 3) >> 2);
+    w * h * block_size
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The start of a 256x256 DXT1 texture with a full mipmap chain.
+    const FILE: [u8; 128] = [
+        0x44, 0x44, 0x53, 0x20, 0x7c, 0x00, 0x00, 0x00,
+        0x07, 0x10, 0x0a, 0x00, 0x00, 0x01, 0x00, 0x00,
+        0x00, 0x01, 0x00, 0x00, 0x00, 0x80, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x09, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x20, 0x00, 0x00, 0x00,
+        0x04, 0x00, 0x00, 0x00, 0x44, 0x58, 0x54, 0x31,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x08, 0x10, 0x40, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<PixelFormat>(), PIXEL_FORMAT_SIZE as usize);
+        assert_eq!(align_of::<PixelFormat>(), 4);
+        assert_eq!(size_of::<Header>(), HEADER_SIZE as usize);
+        assert_eq!(align_of::<Header>(), 4);
+        assert_eq!(size_of::<HeaderDx10>(), 20);
+    }
+
+    // Parse the header of a legacy compressed texture.
+    #[test]
+    fn verify_header() {
+        assert_eq!(FILE[..4], MAGIC);
+
+        let h = Header::from_bytes(FILE[4..].try_into().unwrap());
+        assert_eq!(int::ForeignEndian::to_native(h.size), HEADER_SIZE);
+        assert_eq!(int::ForeignEndian::to_native(h.width), 256);
+        assert_eq!(int::ForeignEndian::to_native(h.height), 256);
+        assert_eq!(h.mip_levels(), 9);
+        assert!(!h.has_dx10());
+
+        let four_cc = h.pixel_format.four_cc().unwrap();
+        assert_eq!(four_cc, FOURCC_DXT1);
+        let block = four_cc_block_size(&four_cc).unwrap();
+        assert_eq!(
+            compressed_size(256, 256, block),
+            int::ForeignEndian::to_native(h.pitch_or_linear_size) as usize,
+        );
+
+        let caps = int::ForeignEndian::to_native(h.caps);
+        assert_eq!(caps & DDSCAPS_MIPMAP, DDSCAPS_MIPMAP);
+        assert_eq!(int::ForeignEndian::to_native(h.caps2) & DDSCAPS2_CUBEMAP, 0);
+    }
+
+    // Verify the DX10 extension and block sizes.
+    #[test]
+    fn verify_dx10() {
+        let h = HeaderDx10::from_bytes(&[
+            0x62, 0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00,
+            0x04, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00,
+            0x01, 0x00, 0x00, 0x00,
+        ]);
+        let format = int::ForeignEndian::to_native(h.dxgi_format);
+        assert_eq!(format, DXGI_FORMAT_BC7_UNORM);
+        assert_eq!(int::ForeignEndian::to_native(h.resource_dimension), DIMENSION_TEXTURE2D);
+        assert_eq!(int::ForeignEndian::to_native(h.misc_flag), MISC_TEXTURECUBE);
+        assert_eq!(int::ForeignEndian::to_native(h.misc_flags2) & ALPHA_MODE_MASK, ALPHA_MODE_STRAIGHT);
+
+        assert_eq!(block_size(format), Some(16));
+        assert_eq!(block_size(DXGI_FORMAT_BC1_UNORM_SRGB), Some(8));
+        assert_eq!(block_size(DXGI_FORMAT_BC4_SNORM), Some(8));
+        assert!(block_size(DXGI_FORMAT_R8G8B8A8_UNORM).is_none());
+        assert!(four_cc_block_size(&FOURCC_DX10).is_none());
+
+        assert_eq!(compressed_size(1, 1, 16), 16);
+        assert_eq!(compressed_size(5, 3, 8), 16);
+        assert_eq!(compressed_size(0, 0, 8), 8);
+    }
+}
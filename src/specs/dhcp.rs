@@ -0,0 +1,360 @@
+//! Dynamic Host Configuration Protocol
+//!
+//! DHCP extends the Bootstrap Protocol (BOOTP). Both use the same 236-byte
+//! fixed `Header`, followed by the `MAGIC` cookie and a sequence of options
+//! (see `Options`). Options are encoded as TLVs with 1-byte code and 1-byte
+//! length, except for `OPTION_PAD` and `OPTION_END`, which consist of the
+//! code only. Options that do not fit can be placed in the `file` and
+//! `sname` fields of the header, if announced via `OPTION_OVERLOAD`.
+//! Options exceeding 255 bytes are split into multiple instances with the
+//! same code, which must be concatenated by the receiver.
+//!
+//! Every DHCP message carries `OPTION_MESSAGE_TYPE`, which distinguishes the
+//! individual messages (`MESSAGE_*`). Messages without it are plain BOOTP
+//! messages. Clients send from `udp::PORT_DHCP_CLIENT` to
+//! `udp::PORT_DHCP_SERVER`.
+//!
+//! All multi-byte integers are encoded as big-endian.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::int;
+
+pub const HEADER_SIZE: usize = 236;
+pub const MAGIC: [u8; 4] = [0x63, 0x82, 0x53, 0x63];
+
+/// Minimum Message Size
+///
+/// The minimum size of messages every client and server must accept,
+/// excluding the IP and UDP headers.
+pub const MESSAGE_MIN: usize = 300;
+
+pub const OP_BOOTREQUEST: u8 = 1;
+pub const OP_BOOTREPLY: u8 = 2;
+
+pub const HTYPE_ETHERNET: u8 = 1;
+pub const HTYPE_IEEE802: u8 = 6;
+pub const HTYPE_INFINIBAND: u8 = 32;
+
+pub const FLAG_BROADCAST: u16 = 0x8000;
+
+pub const OPTION_PAD: u8 = 0;
+pub const OPTION_SUBNET_MASK: u8 = 1;
+pub const OPTION_TIME_OFFSET: u8 = 2;
+pub const OPTION_ROUTER: u8 = 3;
+pub const OPTION_TIME_SERVER: u8 = 4;
+pub const OPTION_NAME_SERVER: u8 = 5;
+pub const OPTION_DNS_SERVER: u8 = 6;
+pub const OPTION_LOG_SERVER: u8 = 7;
+pub const OPTION_HOST_NAME: u8 = 12;
+pub const OPTION_BOOT_FILE_SIZE: u8 = 13;
+pub const OPTION_DOMAIN_NAME: u8 = 15;
+pub const OPTION_ROOT_PATH: u8 = 17;
+pub const OPTION_EXTENSIONS_PATH: u8 = 18;
+pub const OPTION_IP_FORWARDING: u8 = 19;
+pub const OPTION_DEFAULT_TTL: u8 = 23;
+pub const OPTION_INTERFACE_MTU: u8 = 26;
+pub const OPTION_BROADCAST_ADDRESS: u8 = 28;
+pub const OPTION_STATIC_ROUTE: u8 = 33;
+pub const OPTION_NIS_DOMAIN: u8 = 40;
+pub const OPTION_NIS_SERVER: u8 = 41;
+pub const OPTION_NTP_SERVER: u8 = 42;
+pub const OPTION_VENDOR_SPECIFIC: u8 = 43;
+pub const OPTION_NETBIOS_NAME_SERVER: u8 = 44;
+pub const OPTION_REQUESTED_ADDRESS: u8 = 50;
+pub const OPTION_LEASE_TIME: u8 = 51;
+pub const OPTION_OVERLOAD: u8 = 52;
+pub const OPTION_MESSAGE_TYPE: u8 = 53;
+pub const OPTION_SERVER_IDENTIFIER: u8 = 54;
+pub const OPTION_PARAMETER_REQUEST_LIST: u8 = 55;
+pub const OPTION_MESSAGE: u8 = 56;
+pub const OPTION_MAX_MESSAGE_SIZE: u8 = 57;
+pub const OPTION_RENEWAL_TIME: u8 = 58;
+pub const OPTION_REBINDING_TIME: u8 = 59;
+pub const OPTION_VENDOR_CLASS: u8 = 60;
+pub const OPTION_CLIENT_IDENTIFIER: u8 = 61;
+pub const OPTION_TFTP_SERVER_NAME: u8 = 66;
+pub const OPTION_BOOTFILE_NAME: u8 = 67;
+pub const OPTION_USER_CLASS: u8 = 77;
+pub const OPTION_RAPID_COMMIT: u8 = 80;
+pub const OPTION_CLIENT_FQDN: u8 = 81;
+pub const OPTION_RELAY_AGENT_INFORMATION: u8 = 82;
+pub const OPTION_CLIENT_ARCHITECTURE: u8 = 93;
+pub const OPTION_CLIENT_NDI: u8 = 94;
+pub const OPTION_CLIENT_UUID: u8 = 97;
+pub const OPTION_IPV6_ONLY_PREFERRED: u8 = 108;
+pub const OPTION_CAPTIVE_PORTAL: u8 = 114;
+pub const OPTION_DOMAIN_SEARCH: u8 = 119;
+pub const OPTION_CLASSLESS_STATIC_ROUTE: u8 = 121;
+pub const OPTION_VENDOR_IDENTIFYING_CLASS: u8 = 124;
+pub const OPTION_VENDOR_IDENTIFYING_SPECIFIC: u8 = 125;
+pub const OPTION_PRIVATE_BASE: u8 = 224;
+pub const OPTION_PRIVATE_LAST: u8 = 254;
+pub const OPTION_END: u8 = 255;
+
+pub const MESSAGE_DISCOVER: u8 = 1;
+pub const MESSAGE_OFFER: u8 = 2;
+pub const MESSAGE_REQUEST: u8 = 3;
+pub const MESSAGE_DECLINE: u8 = 4;
+pub const MESSAGE_ACK: u8 = 5;
+pub const MESSAGE_NAK: u8 = 6;
+pub const MESSAGE_RELEASE: u8 = 7;
+pub const MESSAGE_INFORM: u8 = 8;
+pub const MESSAGE_FORCERENEW: u8 = 9;
+pub const MESSAGE_LEASEQUERY: u8 = 10;
+pub const MESSAGE_LEASEUNASSIGNED: u8 = 11;
+pub const MESSAGE_LEASEUNKNOWN: u8 = 12;
+pub const MESSAGE_LEASEACTIVE: u8 = 13;
+
+pub const OVERLOAD_FILE: u8 = 1;
+pub const OVERLOAD_SNAME: u8 = 2;
+pub const OVERLOAD_BOTH: u8 = 3;
+
+pub const ARCH_X86_BIOS: u16 = 0x0000;
+pub const ARCH_X86_UEFI: u16 = 0x0006;
+pub const ARCH_X64_UEFI: u16 = 0x0007;
+pub const ARCH_EBC: u16 = 0x0009;
+pub const ARCH_ARM32_UEFI: u16 = 0x000a;
+pub const ARCH_ARM64_UEFI: u16 = 0x000b;
+pub const ARCH_X64_UEFI_HTTP: u16 = 0x0010;
+pub const ARCH_ARM64_UEFI_HTTP: u16 = 0x0013;
+pub const ARCH_RISCV64_UEFI: u16 = 0x001b;
+
+/// PXE Vendor Class Prefix
+///
+/// The prefix of `OPTION_VENDOR_CLASS` sent by PXE clients, which is
+/// followed by the architecture and UNDI version (e.g.,
+/// `PXEClient:Arch:00007:UNDI:003016`).
+pub const VENDOR_CLASS_PXE: &[u8] = b"PXEClient";
+
+/// BOOTP and DHCP Header
+///
+/// The fixed part of every message, followed by the `MAGIC` cookie and the
+/// options.
+#[repr(C)]
+pub struct Header {
+    /// Message direction (`OP_*`).
+    pub op: u8,
+
+    /// Hardware address type (`HTYPE_*`).
+    pub htype: u8,
+
+    /// Hardware address length in bytes.
+    pub hlen: u8,
+
+    /// Number of relay agents that forwarded the message.
+    pub hops: u8,
+
+    /// Transaction identifier, chosen by the client.
+    pub xid: int::u32be,
+
+    /// Seconds since the client started the transaction.
+    pub secs: int::u16be,
+
+    /// Flags (`FLAG_*`).
+    pub flags: int::u16be,
+
+    /// Current address of the client, if it has one.
+    pub ciaddr: [u8; 4],
+
+    /// Address assigned to the client.
+    pub yiaddr: [u8; 4],
+
+    /// Address of the next server in the boot process (e.g., TFTP).
+    pub siaddr: [u8; 4],
+
+    /// Address of the relay agent.
+    pub giaddr: [u8; 4],
+
+    /// Hardware address of the client, padded with zeroes.
+    pub chaddr: [u8; 16],
+
+    /// Zero-terminated server host name, or overloaded options.
+    pub sname: [u8; 64],
+
+    /// Zero-terminated boot file name, or overloaded options.
+    pub file: [u8; 128],
+}
+
+/// Option Iterator
+///
+/// An iterator over the options of a message, yielding the option code and
+/// the option data. `OPTION_PAD` is skipped, and iteration stops at
+/// `OPTION_END`, at the end of the data, or at the first truncated option.
+#[derive(Clone, Debug)]
+pub struct Options<'data> {
+    data: &'data [u8],
+}
+
+impl Header {
+    /// Import a header from a byte slice
+    ///
+    /// Create a new header from data copied from a byte slice. No byte-order
+    /// conversions are applied.
+    pub fn from_bytes(data: &[u8; 236]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 236], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Return hardware address
+    ///
+    /// Return the part of `chaddr` covered by `hlen`, or `None` if `hlen`
+    /// exceeds the field.
+    pub fn hardware_address(&self) -> Option<&[u8]> {
+        self.chaddr.get(..self.hlen as usize)
+    }
+
+    /// Check for broadcast flag
+    pub fn is_broadcast(&self) -> bool {
+        int::ForeignEndian::to_native(self.flags) & FLAG_BROADCAST != 0
+    }
+}
+
+impl<'data> Options<'data> {
+    /// Create option iterator
+    ///
+    /// Create a new iterator over the options in `data`, which must start
+    /// after the `MAGIC` cookie, or at the start of an overloaded field.
+    pub fn new(data: &'data [u8]) -> Self {
+        Self {
+            data,
+        }
+    }
+
+    /// Return remaining data
+    ///
+    /// Return the data that has not been consumed by the iterator.
+    pub fn remainder(&self) -> &'data [u8] {
+        self.data
+    }
+}
+
+impl<'data> Iterator for Options<'data> {
+    type Item = (u8, &'data [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match *self.data.first()? {
+                OPTION_PAD => self.data = &self.data[1..],
+                OPTION_END => return None,
+                code => {
+                    let n = *self.data.get(1)? as usize;
+                    let v = self.data.get(2..2 + n)?;
+                    self.data = &self.data[2 + n..];
+                    return Some((code, v));
+                },
+            }
+        }
+    }
+}
+
+/// Return options of a message
+///
+/// Return the options following the `MAGIC` cookie of `message`, or `None`
+/// if the message is too short or lacks the cookie.
+pub fn options(message: &[u8]) -> Option<&[u8]> {
+    if message.get(HEADER_SIZE..HEADER_SIZE + 4)? == MAGIC {
+        Some(&message[HEADER_SIZE + 4..])
+    } else {
+        None
+    }
+}
+
+/// Find option
+///
+/// Return the data of the first option with the given code in `data`, not
+/// considering overloaded fields.
+pub fn find_option(data: &[u8], code: u8) -> Option<&[u8]> {
+    Options::new(data).find(|v| v.0 == code).map(|v| v.1)
+}
+
+/// Return message type
+///
+/// Return the value of `OPTION_MESSAGE_TYPE` in `data`, or `None` if it is
+/// missing or malformed, as is the case for BOOTP.
+pub fn message_type(data: &[u8]) -> Option<u8> {
+    match find_option(data, OPTION_MESSAGE_TYPE)? {
+        [v] => Some(*v),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A DHCPDISCOVER from a PXE client.
+    fn discover() -> [u8; 272] {
+        let mut m = [0; 272];
+        m[..8].copy_from_slice(&[0x01, 0x01, 0x06, 0x00, 0x39, 0x03, 0xf3, 0x26]);
+        m[8..12].copy_from_slice(&[0x00, 0x04, 0x80, 0x00]);
+        m[28..34].copy_from_slice(&[0x52, 0x54, 0x00, 0x12, 0x34, 0x56]);
+        m[236..272].copy_from_slice(&[
+            0x63, 0x82, 0x53, 0x63, 0x35, 0x01, 0x01, 0x37,
+            0x03, 0x01, 0x03, 0x06, 0x00, 0x5d, 0x02, 0x00,
+            0x07, 0x3c, 0x09, 0x50, 0x58, 0x45, 0x43, 0x6c,
+            0x69, 0x65, 0x6e, 0x74, 0x32, 0x04, 0x0a, 0x00,
+            0x00, 0x05, 0xff, 0x00,
+        ]);
+        m
+    }
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<Header>(), HEADER_SIZE);
+        assert_eq!(align_of::<Header>(), 4);
+    }
+
+    // Parse the header of a discover message.
+    #[test]
+    fn verify_header() {
+        let m = discover();
+        let h = Header::from_bytes(m[..236].try_into().unwrap());
+        assert_eq!(h.op, OP_BOOTREQUEST);
+        assert_eq!(h.htype, HTYPE_ETHERNET);
+        assert_eq!(int::ForeignEndian::to_native(h.xid), 0x3903f326);
+        assert_eq!(int::ForeignEndian::to_native(h.secs), 4);
+        assert!(h.is_broadcast());
+        assert_eq!(h.hardware_address(), Some(&[0x52, 0x54, 0x00, 0x12, 0x34, 0x56][..]));
+        assert_eq!(h.yiaddr, [0; 4]);
+
+        let mut v = m;
+        v[236] = 0;
+        assert!(options(&v).is_none());
+        assert!(options(&m[..239]).is_none());
+    }
+
+    // Walk the options of a discover message.
+    #[test]
+    fn verify_options() {
+        let m = discover();
+        let data = options(&m).unwrap();
+        assert_eq!(message_type(data), Some(MESSAGE_DISCOVER));
+
+        let mut opts = Options::new(data);
+        assert_eq!(opts.next(), Some((OPTION_MESSAGE_TYPE, &[MESSAGE_DISCOVER][..])));
+        assert_eq!(opts.next(), Some((OPTION_PARAMETER_REQUEST_LIST, &[1, 3, 6][..])));
+        assert_eq!(opts.next(), Some((OPTION_CLIENT_ARCHITECTURE, &ARCH_X64_UEFI.to_be_bytes()[..])));
+        assert_eq!(opts.next(), Some((OPTION_VENDOR_CLASS, VENDOR_CLASS_PXE)));
+        assert_eq!(opts.next(), Some((OPTION_REQUESTED_ADDRESS, &[10, 0, 0, 5][..])));
+        assert!(opts.next().is_none());
+        assert_eq!(opts.remainder(), &[OPTION_END, OPTION_PAD]);
+
+        assert!(find_option(data, OPTION_LEASE_TIME).is_none());
+        assert!(message_type(&[OPTION_END, 0x35, 0x01, 0x01]).is_none());
+        assert!(message_type(&[0x35, 0x02, 0x01, 0x01]).is_none());
+        assert!(Options::new(&[0x35, 0x02, 0x01]).next().is_none());
+    }
+}
@@ -0,0 +1,425 @@
+//! Dynamic Host Configuration Protocol for IPv6
+//!
+//! DHCPv6 messages between clients and servers start with a 4-byte `Header`,
+//! carrying the message type and a transaction identifier. Messages between
+//! relay agents and servers start with a 34-byte `RelayHeader` instead, and
+//! carry the relayed message in `OPTION_RELAY_MSG`. Both are followed by a
+//! sequence of options (see `Options`).
+//!
+//! Options are encoded as TLVs with 2-byte code and 2-byte length, without
+//! padding. Some options carry fixed fields followed by nested options,
+//! most notably the identity associations (`IaNa`, `IaPd`) and their
+//! addresses and prefixes (`IaAddress`, `IaPrefix`). Names are encoded as
+//! uncompressed DNS names (see `dns::Labels`).
+//!
+//! Clients send from `udp::PORT_DHCPV6_CLIENT` to `udp::PORT_DHCPV6_SERVER`,
+//! usually to the `ALL_RELAY_AGENTS_AND_SERVERS` multicast group.
+//!
+//! All multi-byte integers are encoded as big-endian.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::int;
+
+pub const HEADER_SIZE: usize = 4;
+pub const RELAY_HEADER_SIZE: usize = 34;
+pub const OPTION_HEADER_SIZE: usize = 4;
+
+pub const ALL_RELAY_AGENTS_AND_SERVERS: [u8; 16] = [
+    0xff, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x02,
+];
+pub const ALL_SERVERS: [u8; 16] = [
+    0xff, 0x05, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x03,
+];
+
+pub const MESSAGE_SOLICIT: u8 = 1;
+pub const MESSAGE_ADVERTISE: u8 = 2;
+pub const MESSAGE_REQUEST: u8 = 3;
+pub const MESSAGE_CONFIRM: u8 = 4;
+pub const MESSAGE_RENEW: u8 = 5;
+pub const MESSAGE_REBIND: u8 = 6;
+pub const MESSAGE_REPLY: u8 = 7;
+pub const MESSAGE_RELEASE: u8 = 8;
+pub const MESSAGE_DECLINE: u8 = 9;
+pub const MESSAGE_RECONFIGURE: u8 = 10;
+pub const MESSAGE_INFORMATION_REQUEST: u8 = 11;
+pub const MESSAGE_RELAY_FORW: u8 = 12;
+pub const MESSAGE_RELAY_REPL: u8 = 13;
+
+pub const OPTION_CLIENTID: u16 = 1;
+pub const OPTION_SERVERID: u16 = 2;
+pub const OPTION_IA_NA: u16 = 3;
+pub const OPTION_IA_TA: u16 = 4;
+pub const OPTION_IAADDR: u16 = 5;
+pub const OPTION_ORO: u16 = 6;
+pub const OPTION_PREFERENCE: u16 = 7;
+pub const OPTION_ELAPSED_TIME: u16 = 8;
+pub const OPTION_RELAY_MSG: u16 = 9;
+pub const OPTION_AUTH: u16 = 11;
+pub const OPTION_UNICAST: u16 = 12;
+pub const OPTION_STATUS_CODE: u16 = 13;
+pub const OPTION_RAPID_COMMIT: u16 = 14;
+pub const OPTION_USER_CLASS: u16 = 15;
+pub const OPTION_VENDOR_CLASS: u16 = 16;
+pub const OPTION_VENDOR_OPTS: u16 = 17;
+pub const OPTION_INTERFACE_ID: u16 = 18;
+pub const OPTION_RECONF_MSG: u16 = 19;
+pub const OPTION_RECONF_ACCEPT: u16 = 20;
+pub const OPTION_DNS_SERVERS: u16 = 23;
+pub const OPTION_DOMAIN_LIST: u16 = 24;
+pub const OPTION_IA_PD: u16 = 25;
+pub const OPTION_IAPREFIX: u16 = 26;
+pub const OPTION_SNTP_SERVERS: u16 = 31;
+pub const OPTION_INFORMATION_REFRESH_TIME: u16 = 32;
+pub const OPTION_CLIENT_FQDN: u16 = 39;
+pub const OPTION_NTP_SERVER: u16 = 56;
+pub const OPTION_BOOTFILE_URL: u16 = 59;
+pub const OPTION_BOOTFILE_PARAM: u16 = 60;
+pub const OPTION_CLIENT_ARCH_TYPE: u16 = 61;
+pub const OPTION_NII: u16 = 62;
+pub const OPTION_SOL_MAX_RT: u16 = 82;
+pub const OPTION_INF_MAX_RT: u16 = 83;
+
+pub const STATUS_SUCCESS: u16 = 0;
+pub const STATUS_UNSPEC_FAIL: u16 = 1;
+pub const STATUS_NO_ADDRS_AVAIL: u16 = 2;
+pub const STATUS_NO_BINDING: u16 = 3;
+pub const STATUS_NOT_ON_LINK: u16 = 4;
+pub const STATUS_USE_MULTICAST: u16 = 5;
+pub const STATUS_NO_PREFIX_AVAIL: u16 = 6;
+
+pub const DUID_LLT: u16 = 1;
+pub const DUID_EN: u16 = 2;
+pub const DUID_LL: u16 = 3;
+pub const DUID_UUID: u16 = 4;
+
+/// Infinite Lifetime
+pub const LIFETIME_INFINITY: u32 = 0xffff_ffff;
+
+/// Client/Server Message Header
+#[repr(C)]
+pub struct Header {
+    /// Message type (`MESSAGE_*`).
+    pub msg_type: u8,
+
+    /// Transaction identifier, chosen by the client.
+    pub transaction_id: int::u24be,
+}
+
+/// Relay Agent/Server Message Header
+#[repr(C)]
+pub struct RelayHeader {
+    /// Message type (`MESSAGE_RELAY_FORW` or `MESSAGE_RELAY_REPL`).
+    pub msg_type: u8,
+
+    /// Number of relay agents that forwarded the message.
+    pub hop_count: u8,
+
+    /// Address identifying the link of the client, or unspecified.
+    pub link_address: [u8; 16],
+
+    /// Address of the client or relay agent the message was received from.
+    pub peer_address: [u8; 16],
+}
+
+/// Identity Association for Non-temporary Addresses
+///
+/// The fixed fields of `OPTION_IA_NA`, followed by nested options (usually
+/// `OPTION_IAADDR` and `OPTION_STATUS_CODE`).
+#[repr(C)]
+pub struct IaNa {
+    /// Identifier of the association, chosen by the client.
+    pub iaid: int::u32be,
+
+    /// Seconds until the client should renew, relative to the current time.
+    pub t1: int::u32be,
+
+    /// Seconds until the client should rebind, relative to the current time.
+    pub t2: int::u32be,
+}
+
+/// Identity Association for Prefix Delegation
+///
+/// The fixed fields of `OPTION_IA_PD`, followed by nested options (usually
+/// `OPTION_IAPREFIX` and `OPTION_STATUS_CODE`). The layout matches `IaNa`.
+pub type IaPd = IaNa;
+
+/// IA Address
+///
+/// The fixed fields of `OPTION_IAADDR`, followed by nested options.
+#[repr(C)]
+pub struct IaAddress {
+    /// Assigned address.
+    pub address: [u8; 16],
+
+    /// Preferred lifetime in seconds, or `LIFETIME_INFINITY`.
+    pub preferred_lifetime: int::u32be,
+
+    /// Valid lifetime in seconds, or `LIFETIME_INFINITY`.
+    pub valid_lifetime: int::u32be,
+}
+
+/// IA Prefix
+///
+/// The fixed fields of `OPTION_IAPREFIX`, followed by nested options. Since
+/// the prefix is not naturally aligned, this structure is packed.
+#[repr(C, packed)]
+pub struct IaPrefix {
+    /// Preferred lifetime in seconds, or `LIFETIME_INFINITY`.
+    pub preferred_lifetime: int::u32be,
+
+    /// Valid lifetime in seconds, or `LIFETIME_INFINITY`.
+    pub valid_lifetime: int::u32be,
+
+    /// Length of the prefix in bits.
+    pub prefix_length: u8,
+
+    /// Delegated prefix, with bits beyond the prefix length set to zero.
+    pub prefix: [u8; 16],
+}
+
+/// Option Iterator
+///
+/// An iterator over the options of a message or the nested options of an
+/// option, yielding the option code and the option data. Iteration stops at
+/// the end of the data, or at the first truncated option.
+#[derive(Clone, Debug)]
+pub struct Options<'data> {
+    data: &'data [u8],
+}
+
+impl Header {
+    /// Import a header from a byte slice
+    ///
+    /// Create a new header from data copied from a byte slice. No byte-order
+    /// conversions are applied.
+    pub fn from_bytes(data: &[u8; 4]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 4], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl RelayHeader {
+    /// Import a relay header from a byte slice
+    ///
+    /// Create a new relay header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 34]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 34], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl IaNa {
+    /// Import an identity association from a byte slice
+    ///
+    /// Create a new identity association from data copied from a byte
+    /// slice. No byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 12]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 12], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl IaAddress {
+    /// Import an IA address from a byte slice
+    ///
+    /// Create a new IA address from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 24]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 24], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl IaPrefix {
+    /// Import an IA prefix from a byte slice
+    ///
+    /// Create a new IA prefix from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 25]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 25], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl<'data> Options<'data> {
+    /// Create option iterator
+    ///
+    /// Create a new iterator over the options in `data`, which must start
+    /// after the message header, or after the fixed fields of an option with
+    /// nested options.
+    pub fn new(data: &'data [u8]) -> Self {
+        Self {
+            data,
+        }
+    }
+
+    /// Return remaining data
+    ///
+    /// Return the data that has not been consumed by the iterator.
+    pub fn remainder(&self) -> &'data [u8] {
+        self.data
+    }
+}
+
+impl<'data> Iterator for Options<'data> {
+    type Item = (u16, &'data [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.data.len() < OPTION_HEADER_SIZE {
+            return None;
+        }
+
+        let code = u16::from_be_bytes([self.data[0], self.data[1]]);
+        let n = u16::from_be_bytes([self.data[2], self.data[3]]) as usize;
+        let v = self.data.get(4..4 + n)?;
+        self.data = &self.data[4 + n..];
+        Some((code, v))
+    }
+}
+
+/// Check for relay messages
+pub fn is_relay(msg_type: u8) -> bool {
+    msg_type == MESSAGE_RELAY_FORW || msg_type == MESSAGE_RELAY_REPL
+}
+
+/// Find option
+///
+/// Return the data of the first option with the given code in `data`, not
+/// considering nested options.
+pub fn find_option(data: &[u8], code: u16) -> Option<&[u8]> {
+    Options::new(data).find(|v| v.0 == code).map(|v| v.1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A reply with a client identifier and an IA_NA with a single address.
+    const REPLY: [u8; 66] = [
+        0x07, 0xa1, 0xb2, 0xc3, 0x00, 0x01, 0x00, 0x0a,
+        0x00, 0x03, 0x00, 0x01, 0x52, 0x54, 0x00, 0x12,
+        0x34, 0x56, 0x00, 0x03, 0x00, 0x28, 0x00, 0x00,
+        0x00, 0x01, 0x00, 0x00, 0x0e, 0x10, 0x00, 0x00,
+        0x15, 0x18, 0x00, 0x05, 0x00, 0x18, 0x20, 0x01,
+        0x0d, 0xb8, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x00,
+        0x1c, 0x20, 0x00, 0x00, 0x2a, 0x30, 0x00, 0x0d,
+        0x00, 0x00,
+    ];
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<Header>(), HEADER_SIZE);
+        assert_eq!(align_of::<Header>(), 1);
+        assert_eq!(size_of::<RelayHeader>(), RELAY_HEADER_SIZE);
+        assert_eq!(align_of::<RelayHeader>(), 1);
+        assert_eq!(size_of::<IaNa>(), 12);
+        assert_eq!(align_of::<IaNa>(), 4);
+        assert_eq!(size_of::<IaAddress>(), 24);
+        assert_eq!(align_of::<IaAddress>(), 4);
+        assert_eq!(size_of::<IaPrefix>(), 25);
+        assert_eq!(align_of::<IaPrefix>(), 1);
+    }
+
+    // Parse a reply and its nested IA_NA options.
+    #[test]
+    fn verify_reply() {
+        let h = Header::from_bytes(REPLY[..4].try_into().unwrap());
+        assert_eq!(h.msg_type, MESSAGE_REPLY);
+        assert_eq!(h.transaction_id.to_native(), 0xa1b2c3);
+        assert!(!is_relay(h.msg_type));
+
+        let mut opts = Options::new(&REPLY[4..]);
+        let (code, duid) = opts.next().unwrap();
+        assert_eq!(code, OPTION_CLIENTID);
+        assert_eq!(u16::from_be_bytes([duid[0], duid[1]]), DUID_LL);
+        let (code, ia) = opts.next().unwrap();
+        assert_eq!(code, OPTION_IA_NA);
+        assert!(opts.next().is_some());
+        assert!(opts.next().is_none());
+        assert!(opts.remainder().is_empty());
+
+        let v = IaNa::from_bytes(ia[..12].try_into().unwrap());
+        assert_eq!(int::ForeignEndian::to_native(v.iaid), 1);
+        assert_eq!(int::ForeignEndian::to_native(v.t1), 3600);
+        assert_eq!(int::ForeignEndian::to_native(v.t2), 5400);
+
+        let addr = find_option(&ia[12..], OPTION_IAADDR).unwrap();
+        let v = IaAddress::from_bytes(addr.try_into().unwrap());
+        assert_eq!(v.address[..4], [0x20, 0x01, 0x0d, 0xb8]);
+        assert_eq!(v.address[15], 0x10);
+        assert_eq!(int::ForeignEndian::to_native(v.preferred_lifetime), 7200);
+        assert_eq!(int::ForeignEndian::to_native(v.valid_lifetime), 10800);
+
+        let status = find_option(&REPLY[4..], OPTION_STATUS_CODE).unwrap();
+        assert!(status.is_empty());
+        assert!(Options::new(&REPLY[4..20]).nth(1).is_none());
+    }
+
+    // Parse a relay header and an IA prefix.
+    #[test]
+    fn verify_relay() {
+        let mut data = [0; 34];
+        data[0] = MESSAGE_RELAY_FORW;
+        data[2..4].copy_from_slice(&[0x20, 0x01]);
+        data[18..20].copy_from_slice(&[0xfe, 0x80]);
+        let h = RelayHeader::from_bytes(&data);
+        assert!(is_relay(h.msg_type));
+        assert_eq!(h.hop_count, 0);
+        assert_eq!(h.link_address[..2], [0x20, 0x01]);
+        assert_eq!(h.peer_address[..2], [0xfe, 0x80]);
+
+        let mut data = [0; 25];
+        data[..8].copy_from_slice(&[0xff; 8]);
+        data[8] = 56;
+        data[9..13].copy_from_slice(&[0x20, 0x01, 0x0d, 0xb8]);
+        let p = IaPrefix::from_bytes(&data);
+        assert_eq!(int::ForeignEndian::to_native(p.preferred_lifetime), LIFETIME_INFINITY);
+        assert_eq!(p.prefix_length, 56);
+        assert_eq!(p.prefix[..4], [0x20, 0x01, 0x0d, 0xb8]);
+    }
+}
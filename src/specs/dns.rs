@@ -0,0 +1,877 @@
+//! Domain Name System
+//!
+//! DNS messages start with a 12-byte `Header`, followed by four sections:
+//! questions, answers, authority records, and additional records. The number
+//! of entries in each section is given in the header. Questions consist of a
+//! name followed by a `Question`, resource records consist of a name followed
+//! by a `ResourceRecord` and the record data (RDATA).
+//!
+//! Names are encoded as a sequence of labels, each prefixed with its length
+//! in bytes, and terminated by the zero-length root label. To save space, the
+//! remainder of a name can be replaced by a 2-byte compression pointer, which
+//! has the upper 2 bits set and carries the offset of a prior occurrence of
+//! the remaining labels relative to the start of the message. Pointers can
+//! appear in names in the RDATA of the original record types (e.g., `NS`,
+//! `CNAME`, `SOA`, `MX`), but not in newer types (e.g., `SRV`). Hence, names
+//! can only be decoded with access to the entire message (see
+//! `name_decode()`). Internationalized labels are carried as ASCII, encoded
+//! as Punycode (see `punycode::encode_label()`).
+//!
+//! Over UDP, messages are limited to 512 bytes unless a larger size is
+//! announced via an `OPT` pseudo-record in the additional section (EDNS).
+//! Over TCP, each message is prefixed with its size as 16-bit integer.
+//!
+//! All multi-byte integers are encoded as big-endian.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::int;
+
+pub const HEADER_SIZE: usize = 12;
+pub const QUESTION_SIZE: usize = 4;
+pub const RESOURCE_RECORD_SIZE: usize = 10;
+pub const UDP_SIZE_MAX: usize = 512;
+
+/// Maximum Size of Encoded Names
+pub const NAME_MAX: usize = 255;
+
+/// Maximum Size of Labels
+pub const LABEL_MAX: usize = 63;
+
+pub const LABEL_MASK: u8 = 0xc0;
+pub const LABEL_NORMAL: u8 = 0x00;
+/* reserved: 0x40 (extended label types), 0x80 */
+pub const LABEL_POINTER: u8 = 0xc0;
+
+pub const POINTER_MASK: u16 = 0x3fff;
+
+pub const FLAG_QR: u16 = 0x8000;
+pub const FLAG_AA: u16 = 0x0400;
+pub const FLAG_TC: u16 = 0x0200;
+pub const FLAG_RD: u16 = 0x0100;
+pub const FLAG_RA: u16 = 0x0080;
+pub const FLAG_Z: u16 = 0x0040;
+pub const FLAG_AD: u16 = 0x0020;
+pub const FLAG_CD: u16 = 0x0010;
+
+pub const OPCODE_SHIFT: u32 = 11;
+pub const OPCODE_MASK: u16 = 0x7800;
+pub const RCODE_MASK: u16 = 0x000f;
+
+pub const OPCODE_QUERY: u8 = 0;
+pub const OPCODE_IQUERY: u8 = 1;
+pub const OPCODE_STATUS: u8 = 2;
+pub const OPCODE_NOTIFY: u8 = 4;
+pub const OPCODE_UPDATE: u8 = 5;
+pub const OPCODE_DSO: u8 = 6;
+
+pub const RCODE_NOERROR: u16 = 0;
+pub const RCODE_FORMERR: u16 = 1;
+pub const RCODE_SERVFAIL: u16 = 2;
+pub const RCODE_NXDOMAIN: u16 = 3;
+pub const RCODE_NOTIMP: u16 = 4;
+pub const RCODE_REFUSED: u16 = 5;
+pub const RCODE_YXDOMAIN: u16 = 6;
+pub const RCODE_YXRRSET: u16 = 7;
+pub const RCODE_NXRRSET: u16 = 8;
+pub const RCODE_NOTAUTH: u16 = 9;
+pub const RCODE_NOTZONE: u16 = 10;
+pub const RCODE_BADVERS: u16 = 16;
+pub const RCODE_BADCOOKIE: u16 = 23;
+
+pub const TYPE_A: u16 = 1;
+pub const TYPE_NS: u16 = 2;
+pub const TYPE_CNAME: u16 = 5;
+pub const TYPE_SOA: u16 = 6;
+pub const TYPE_PTR: u16 = 12;
+pub const TYPE_HINFO: u16 = 13;
+pub const TYPE_MX: u16 = 15;
+pub const TYPE_TXT: u16 = 16;
+pub const TYPE_AAAA: u16 = 28;
+pub const TYPE_SRV: u16 = 33;
+pub const TYPE_NAPTR: u16 = 35;
+pub const TYPE_DNAME: u16 = 39;
+pub const TYPE_OPT: u16 = 41;
+pub const TYPE_DS: u16 = 43;
+pub const TYPE_SSHFP: u16 = 44;
+pub const TYPE_RRSIG: u16 = 46;
+pub const TYPE_NSEC: u16 = 47;
+pub const TYPE_DNSKEY: u16 = 48;
+pub const TYPE_NSEC3: u16 = 50;
+pub const TYPE_NSEC3PARAM: u16 = 51;
+pub const TYPE_TLSA: u16 = 52;
+pub const TYPE_SVCB: u16 = 64;
+pub const TYPE_HTTPS: u16 = 65;
+pub const TYPE_TSIG: u16 = 250;
+pub const TYPE_IXFR: u16 = 251;
+pub const TYPE_AXFR: u16 = 252;
+pub const TYPE_ANY: u16 = 255;
+pub const TYPE_CAA: u16 = 257;
+
+pub const CLASS_IN: u16 = 1;
+pub const CLASS_CH: u16 = 3;
+pub const CLASS_HS: u16 = 4;
+pub const CLASS_NONE: u16 = 254;
+pub const CLASS_ANY: u16 = 255;
+
+/// Multicast DNS Class Flag
+///
+/// In mDNS, the top bit of the class requests a unicast response in
+/// questions, and flushes the cache in resource records.
+pub const CLASS_MDNS_FLAG: u16 = 0x8000;
+
+pub const EDNS_VERSION: u8 = 0;
+pub const EDNS_FLAG_DO: u16 = 0x8000;
+
+pub const EDNS_OPTION_NSID: u16 = 3;
+pub const EDNS_OPTION_CLIENT_SUBNET: u16 = 8;
+pub const EDNS_OPTION_EXPIRE: u16 = 9;
+pub const EDNS_OPTION_COOKIE: u16 = 10;
+pub const EDNS_OPTION_TCP_KEEPALIVE: u16 = 11;
+pub const EDNS_OPTION_PADDING: u16 = 12;
+pub const EDNS_OPTION_EXTENDED_ERROR: u16 = 15;
+
+/// Message Header
+#[repr(C)]
+pub struct Header {
+    /// Identifier, copied from queries into responses.
+    pub id: int::u16be,
+
+    /// Flags (`FLAG_*`), opcode, and response code.
+    pub flags: int::u16be,
+
+    /// Number of questions.
+    pub qdcount: int::u16be,
+
+    /// Number of answer records.
+    pub ancount: int::u16be,
+
+    /// Number of authority records.
+    pub nscount: int::u16be,
+
+    /// Number of additional records.
+    pub arcount: int::u16be,
+}
+
+/// Question
+///
+/// The fields following the name of a question.
+#[repr(C)]
+pub struct Question {
+    /// Requested record type (`TYPE_*`).
+    pub qtype: int::u16be,
+
+    /// Requested record class (`CLASS_*`).
+    pub qclass: int::u16be,
+}
+
+/// Resource Record
+///
+/// The fields following the name of a resource record, which are followed
+/// by the record data. Since the size of the record is not a multiple of the
+/// alignment of `ttl`, this structure is packed.
+///
+/// For `TYPE_OPT`, the class carries the maximum UDP payload size of the
+/// sender, and the TTL carries the upper 8 bits of the extended response
+/// code, the EDNS version, and the EDNS flags (see `edns_ttl()`).
+#[repr(C, packed)]
+pub struct ResourceRecord {
+    /// Record type (`TYPE_*`).
+    pub rr_type: int::u16be,
+
+    /// Record class (`CLASS_*`).
+    pub class: int::u16be,
+
+    /// Time in seconds the record may be cached.
+    pub ttl: int::u32be,
+
+    /// Size of the record data in bytes.
+    pub rdlength: int::u16be,
+}
+
+/// SOA Record Data
+///
+/// The fields following the primary server name and the mailbox name of the
+/// zone administrator.
+#[repr(C)]
+pub struct Soa {
+    /// Version number of the zone.
+    pub serial: int::u32be,
+
+    /// Refresh interval of secondary servers in seconds.
+    pub refresh: int::u32be,
+
+    /// Retry interval after failed refreshes in seconds.
+    pub retry: int::u32be,
+
+    /// Time in seconds after which a zone is no longer authoritative.
+    pub expire: int::u32be,
+
+    /// TTL of negative responses in seconds.
+    pub minimum: int::u32be,
+}
+
+/// MX Record Data
+///
+/// The field preceding the name of the mail exchange.
+#[repr(C)]
+pub struct Mx {
+    /// Preference, lower values are preferred.
+    pub preference: int::u16be,
+}
+
+/// SRV Record Data
+///
+/// The fields preceding the uncompressed name of the target host.
+#[repr(C)]
+pub struct Srv {
+    /// Priority, lower values are preferred.
+    pub priority: int::u16be,
+
+    /// Relative weight of targets with the same priority.
+    pub weight: int::u16be,
+
+    /// Port of the service on the target host.
+    pub port: int::u16be,
+}
+
+/// Question Reference
+#[derive(Clone, Copy, Debug)]
+pub struct QuestionRef {
+    /// Offset of the name relative to the start of the message.
+    pub name: usize,
+
+    /// Requested record type.
+    pub qtype: u16,
+
+    /// Requested record class.
+    pub qclass: u16,
+}
+
+/// Resource Record Reference
+#[derive(Clone, Copy, Debug)]
+pub struct Record<'data> {
+    /// Offset of the name relative to the start of the message.
+    pub name: usize,
+
+    /// Record type.
+    pub rr_type: u16,
+
+    /// Record class.
+    pub class: u16,
+
+    /// Time in seconds the record may be cached.
+    pub ttl: u32,
+
+    /// Offset of the record data relative to the start of the message.
+    pub rdata_offset: usize,
+
+    /// Record data.
+    pub rdata: &'data [u8],
+}
+
+/// Question Iterator
+///
+/// An iterator over the questions of a message, starting at a given offset.
+/// Iteration stops after the given number of questions, or at the first
+/// malformed one.
+#[derive(Clone, Debug)]
+pub struct Questions<'data> {
+    message: &'data [u8],
+    offset: usize,
+    count: usize,
+}
+
+/// Resource Record Iterator
+///
+/// An iterator over resource records of a message, starting at a given
+/// offset. Iteration stops after the given number of records, or at the
+/// first malformed one. Since the sections are consecutive, a single
+/// iterator can walk all records following the questions.
+#[derive(Clone, Debug)]
+pub struct Records<'data> {
+    message: &'data [u8],
+    offset: usize,
+    count: usize,
+}
+
+/// Label Iterator
+///
+/// An iterator over the labels of an uncompressed name, as produced by
+/// `name_decode()`. The root label is not yielded.
+#[derive(Clone, Debug)]
+pub struct Labels<'data> {
+    data: &'data [u8],
+}
+
+/// Character String Iterator
+///
+/// An iterator over a sequence of character strings, each prefixed with its
+/// length as a single byte, as used in the record data of `TYPE_TXT`.
+#[derive(Clone, Debug)]
+pub struct Strings<'data> {
+    data: &'data [u8],
+}
+
+/// EDNS Option Iterator
+///
+/// An iterator over the options in the record data of `TYPE_OPT`, yielding
+/// the option code (`EDNS_OPTION_*`) and the option data.
+#[derive(Clone, Debug)]
+pub struct EdnsOptions<'data> {
+    data: &'data [u8],
+}
+
+impl Header {
+    /// Import a header from a byte slice
+    ///
+    /// Create a new header from data copied from a byte slice. No byte-order
+    /// conversions are applied.
+    pub fn from_bytes(data: &[u8; 12]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 12], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Return opcode
+    pub fn opcode(&self) -> u8 {
+        ((int::ForeignEndian::to_native(self.flags) & OPCODE_MASK) >> OPCODE_SHIFT) as u8
+    }
+
+    /// Return response code
+    ///
+    /// Return the lower 4 bits of the response code. The upper bits are
+    /// carried in the `OPT` record, if present (see `edns_rcode()`).
+    pub fn rcode(&self) -> u16 {
+        int::ForeignEndian::to_native(self.flags) & RCODE_MASK
+    }
+
+    /// Check flags
+    ///
+    /// Return whether all of the given flags (`FLAG_*`) are set.
+    pub fn has_flags(&self, flags: u16) -> bool {
+        int::ForeignEndian::to_native(self.flags) & flags == flags
+    }
+}
+
+impl Question {
+    /// Import a question from a byte slice
+    ///
+    /// Create a new question from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 4]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 4], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl ResourceRecord {
+    /// Import a resource record from a byte slice
+    ///
+    /// Create a new resource record from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 10]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 10], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl Soa {
+    /// Import SOA record data from a byte slice
+    ///
+    /// Create new SOA record data from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 20]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 20], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl Mx {
+    /// Import MX record data from a byte slice
+    ///
+    /// Create new MX record data from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 2]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 2], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl Srv {
+    /// Import SRV record data from a byte slice
+    ///
+    /// Create new SRV record data from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 6]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 6], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl<'data> Questions<'data> {
+    /// Create question iterator
+    ///
+    /// Create a new iterator over `count` questions of `message`, starting
+    /// at `offset` (usually `HEADER_SIZE`).
+    pub fn new(message: &'data [u8], offset: usize, count: usize) -> Self {
+        Self {
+            message,
+            offset,
+            count,
+        }
+    }
+
+    /// Return current offset
+    ///
+    /// Return the offset of the data that has not been consumed by the
+    /// iterator, relative to the start of the message.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
+impl<'data> Iterator for Questions<'data> {
+    type Item = QuestionRef;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.count == 0 {
+            return None;
+        }
+
+        let name = self.offset;
+        let start = name + name_size(self.message, name)?;
+        let v = self.message.get(start..start + QUESTION_SIZE)?;
+
+        self.offset = start + QUESTION_SIZE;
+        self.count -= 1;
+        Some(QuestionRef {
+            name,
+            qtype: u16::from_be_bytes([v[0], v[1]]),
+            qclass: u16::from_be_bytes([v[2], v[3]]),
+        })
+    }
+}
+
+impl<'data> Records<'data> {
+    /// Create resource record iterator
+    ///
+    /// Create a new iterator over `count` resource records of `message`,
+    /// starting at `offset`.
+    pub fn new(message: &'data [u8], offset: usize, count: usize) -> Self {
+        Self {
+            message,
+            offset,
+            count,
+        }
+    }
+
+    /// Return current offset
+    ///
+    /// Return the offset of the data that has not been consumed by the
+    /// iterator, relative to the start of the message.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
+impl<'data> Iterator for Records<'data> {
+    type Item = Record<'data>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.count == 0 {
+            return None;
+        }
+
+        let name = self.offset;
+        let start = name + name_size(self.message, name)?;
+        let v = self.message.get(start..start + RESOURCE_RECORD_SIZE)?;
+        let rdata_offset = start + RESOURCE_RECORD_SIZE;
+        let rdlength = u16::from_be_bytes([v[8], v[9]]) as usize;
+        let rdata = self.message.get(rdata_offset..rdata_offset + rdlength)?;
+
+        self.offset = rdata_offset + rdlength;
+        self.count -= 1;
+        Some(Record {
+            name,
+            rr_type: u16::from_be_bytes([v[0], v[1]]),
+            class: u16::from_be_bytes([v[2], v[3]]),
+            ttl: u32::from_be_bytes([v[4], v[5], v[6], v[7]]),
+            rdata_offset,
+            rdata,
+        })
+    }
+}
+
+impl<'data> Labels<'data> {
+    /// Create label iterator
+    pub fn new(data: &'data [u8]) -> Self {
+        Self {
+            data,
+        }
+    }
+
+    /// Return remaining data
+    ///
+    /// Return the data that has not been consumed by the iterator.
+    pub fn remainder(&self) -> &'data [u8] {
+        self.data
+    }
+}
+
+impl<'data> Iterator for Labels<'data> {
+    type Item = &'data [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let n = *self.data.first()? as usize;
+        if n == 0 || n > LABEL_MAX || self.data.len() <= n {
+            return None;
+        }
+
+        let v = &self.data[1..=n];
+        self.data = &self.data[n + 1..];
+        Some(v)
+    }
+}
+
+impl<'data> Strings<'data> {
+    /// Create character string iterator
+    pub fn new(data: &'data [u8]) -> Self {
+        Self {
+            data,
+        }
+    }
+
+    /// Return remaining data
+    ///
+    /// Return the data that has not been consumed by the iterator.
+    pub fn remainder(&self) -> &'data [u8] {
+        self.data
+    }
+}
+
+impl<'data> Iterator for Strings<'data> {
+    type Item = &'data [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let n = *self.data.first()? as usize;
+        if self.data.len() <= n {
+            return None;
+        }
+
+        let v = &self.data[1..=n];
+        self.data = &self.data[n + 1..];
+        Some(v)
+    }
+}
+
+impl<'data> EdnsOptions<'data> {
+    /// Create EDNS option iterator
+    pub fn new(data: &'data [u8]) -> Self {
+        Self {
+            data,
+        }
+    }
+
+    /// Return remaining data
+    ///
+    /// Return the data that has not been consumed by the iterator.
+    pub fn remainder(&self) -> &'data [u8] {
+        self.data
+    }
+}
+
+impl<'data> Iterator for EdnsOptions<'data> {
+    type Item = (u16, &'data [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.data.len() < 4 {
+            return None;
+        }
+
+        let code = u16::from_be_bytes([self.data[0], self.data[1]]);
+        let n = u16::from_be_bytes([self.data[2], self.data[3]]) as usize;
+        if self.data.len() - 4 < n {
+            return None;
+        }
+
+        let v = &self.data[4..4 + n];
+        self.data = &self.data[4 + n..];
+        Some((code, v))
+    }
+}
+
+/// Encode compression pointer
+///
+/// Return the 2-byte compression pointer to `offset`, which must not exceed
+/// `POINTER_MASK`.
+pub fn pointer(offset: u16) -> [u8; 2] {
+    assert!(offset <= POINTER_MASK);
+    (offset | ((LABEL_POINTER as u16) << 8)).to_be_bytes()
+}
+
+/// Decode compression pointer
+///
+/// Return the offset a compression pointer starting with the bytes `hi` and
+/// `lo` points to, or `None` if `hi` does not start a pointer.
+pub fn pointer_offset(hi: u8, lo: u8) -> Option<usize> {
+    if hi & LABEL_MASK == LABEL_POINTER {
+        Some((u16::from_be_bytes([hi, lo]) & POINTER_MASK) as usize)
+    } else {
+        None
+    }
+}
+
+/// Return encoded size of name
+///
+/// Return the number of bytes the name at `offset` in `message` occupies,
+/// up to and including its root label or compression pointer. Pointers are
+/// not followed. Return `None` if the name is truncated or uses reserved
+/// label types.
+pub fn name_size(message: &[u8], offset: usize) -> Option<usize> {
+    let mut pos = offset;
+
+    loop {
+        let n = *message.get(pos)?;
+        match n & LABEL_MASK {
+            LABEL_NORMAL if n == 0 => return Some(pos + 1 - offset),
+            LABEL_NORMAL => pos += 1 + n as usize,
+            LABEL_POINTER => {
+                message.get(pos + 1)?;
+                return Some(pos + 2 - offset);
+            },
+            _ => return None,
+        }
+    }
+}
+
+/// Decode name
+///
+/// Decode the name at `offset` in `message`, following compression
+/// pointers, and write it uncompressed to the start of `out`, including the
+/// root label. Return the number of bytes written and the number of bytes
+/// the name occupies at `offset` (see `name_size()`).
+///
+/// Return `None` if the name is truncated, uses reserved label types,
+/// exceeds `NAME_MAX`, or `out` is too small. To rule out loops, pointers
+/// must point to data before the current label, as is the case for prior
+/// occurrences.
+pub fn name_decode(message: &[u8], offset: usize, out: &mut [u8]) -> Option<(usize, usize)> {
+    let mut pos = offset;
+    let mut written = 0;
+    let mut consumed = None;
+
+    loop {
+        let n = *message.get(pos)?;
+        match n & LABEL_MASK {
+            LABEL_NORMAL => {
+                let v = message.get(pos..pos + 1 + n as usize)?;
+                if written + v.len() > NAME_MAX {
+                    return None;
+                }
+                out.get_mut(written..written + v.len())?.copy_from_slice(v);
+                written += v.len();
+                pos += v.len();
+
+                if n == 0 {
+                    return Some((written, consumed.unwrap_or_else(|| pos - offset)));
+                }
+            },
+            LABEL_POINTER => {
+                let target = pointer_offset(n, *message.get(pos + 1)?)?;
+                if target >= pos {
+                    return None;
+                }
+                if consumed.is_none() {
+                    consumed = Some(pos + 2 - offset);
+                }
+                pos = target;
+            },
+            _ => return None,
+        }
+    }
+}
+
+/// Return extended response code
+///
+/// Combine the 4-bit response code of a header with the TTL of an `OPT`
+/// record into the 12-bit extended response code.
+pub fn edns_rcode(rcode: u16, ttl: u32) -> u16 {
+    (((ttl >> 24) as u16) << 4) | (rcode & RCODE_MASK)
+}
+
+/// Encode OPT record TTL
+///
+/// Return the TTL of an `OPT` record with the upper 8 bits of the extended
+/// response code `rcode`, the given EDNS version, and flags (`EDNS_FLAG_*`).
+pub fn edns_ttl(rcode: u16, version: u8, flags: u16) -> u32 {
+    (((rcode >> 4) as u32 & 0xff) << 24) | ((version as u32) << 16) | flags as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A response with an A and a compressed MX record for "example.com", and
+    // an OPT record with the DO flag.
+    const MESSAGE: [u8; 77] = [
+        0x12, 0x34, 0x81, 0x80, 0x00, 0x01, 0x00, 0x02,
+        0x00, 0x00, 0x00, 0x01, 0x07, 0x65, 0x78, 0x61,
+        0x6d, 0x70, 0x6c, 0x65, 0x03, 0x63, 0x6f, 0x6d,
+        0x00, 0x00, 0x01, 0x00, 0x01, 0xc0, 0x0c, 0x00,
+        0x01, 0x00, 0x01, 0x00, 0x00, 0x0e, 0x10, 0x00,
+        0x04, 0x5d, 0xb8, 0xd8, 0x22, 0xc0, 0x0c, 0x00,
+        0x0f, 0x00, 0x01, 0x00, 0x00, 0x0e, 0x10, 0x00,
+        0x09, 0x00, 0x0a, 0x04, 0x6d, 0x61, 0x69, 0x6c,
+        0xc0, 0x0c, 0x00, 0x00, 0x29, 0x04, 0xd0, 0x00,
+        0x00, 0x80, 0x00, 0x00, 0x00,
+    ];
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<Header>(), HEADER_SIZE);
+        assert_eq!(align_of::<Header>(), 2);
+        assert_eq!(size_of::<Question>(), QUESTION_SIZE);
+        assert_eq!(align_of::<Question>(), 2);
+        assert_eq!(size_of::<ResourceRecord>(), RESOURCE_RECORD_SIZE);
+        assert_eq!(align_of::<ResourceRecord>(), 1);
+        assert_eq!(size_of::<Soa>(), 20);
+        assert_eq!(align_of::<Soa>(), 4);
+        assert_eq!(size_of::<Mx>(), 2);
+        assert_eq!(align_of::<Mx>(), 2);
+        assert_eq!(size_of::<Srv>(), 6);
+        assert_eq!(align_of::<Srv>(), 2);
+    }
+
+    // Walk all sections of a response and decode its records.
+    #[test]
+    fn verify_message() {
+        let h = Header::from_bytes(MESSAGE[..12].try_into().unwrap());
+        assert_eq!(int::ForeignEndian::to_native(h.id), 0x1234);
+        assert_eq!(h.opcode(), OPCODE_QUERY);
+        assert_eq!(h.rcode(), RCODE_NOERROR);
+        assert!(h.has_flags(FLAG_QR | FLAG_RD | FLAG_RA));
+        assert!(!h.has_flags(FLAG_AA));
+
+        let mut questions = Questions::new(&MESSAGE, HEADER_SIZE, 1);
+        let q = questions.next().unwrap();
+        assert_eq!((q.name, q.qtype, q.qclass), (12, TYPE_A, CLASS_IN));
+        assert!(questions.next().is_none());
+        assert_eq!(questions.offset(), 29);
+
+        let mut records = Records::new(&MESSAGE, questions.offset(), 3);
+        let r = records.next().unwrap();
+        assert_eq!((r.rr_type, r.class, r.ttl), (TYPE_A, CLASS_IN, 3600));
+        assert_eq!(r.rdata, &[93, 184, 216, 34]);
+
+        let r = records.next().unwrap();
+        assert_eq!(r.rr_type, TYPE_MX);
+        let mx = Mx::from_bytes(r.rdata[..2].try_into().unwrap());
+        assert_eq!(int::ForeignEndian::to_native(mx.preference), 10);
+        let mut name = [0; NAME_MAX];
+        assert_eq!(name_decode(&MESSAGE, r.rdata_offset + 2, &mut name), Some((18, 7)));
+        let mut labels = Labels::new(&name[..18]);
+        assert_eq!(labels.next(), Some(&b"mail"[..]));
+        assert_eq!(labels.next(), Some(&b"example"[..]));
+        assert_eq!(labels.next(), Some(&b"com"[..]));
+        assert!(labels.next().is_none());
+        assert_eq!(labels.remainder(), &[0]);
+
+        let r = records.next().unwrap();
+        assert_eq!((r.name, r.rr_type, r.class), (66, TYPE_OPT, 1232));
+        assert_eq!(r.ttl, edns_ttl(0, EDNS_VERSION, EDNS_FLAG_DO));
+        assert!(r.rdata.is_empty());
+        assert!(records.next().is_none());
+        assert_eq!(records.offset(), MESSAGE.len());
+
+        let rr = ResourceRecord::from_bytes(MESSAGE[31..41].try_into().unwrap());
+        assert_eq!(int::ForeignEndian::to_native(rr.ttl), 3600);
+        assert_eq!(int::ForeignEndian::to_native(rr.rdlength), 4);
+    }
+
+    // Verify name compression and the other record data helpers.
+    #[test]
+    fn verify_names() {
+        assert_eq!(pointer(12), [0xc0, 0x0c]);
+        assert_eq!(pointer_offset(0xc1, 0x02), Some(0x102));
+        assert_eq!(pointer_offset(0x07, 0x65), None);
+
+        assert_eq!(name_size(&MESSAGE, 12), Some(13));
+        assert_eq!(name_size(&MESSAGE, 29), Some(2));
+        assert_eq!(name_size(&MESSAGE[..20], 12), None);
+
+        // Pointers to themselves or forward are rejected.
+        let mut data = MESSAGE;
+        data[29..31].copy_from_slice(&pointer(29));
+        assert!(name_decode(&data, 29, &mut [0; NAME_MAX]).is_none());
+        data[29..31].copy_from_slice(&pointer(45));
+        assert!(name_decode(&data, 29, &mut [0; NAME_MAX]).is_none());
+
+        // The output buffer must fit the entire name.
+        assert_eq!(name_decode(&MESSAGE, 29, &mut [0; NAME_MAX]), Some((13, 2)));
+        assert!(name_decode(&MESSAGE, 29, &mut [0; 12]).is_none());
+
+        let mut txt = Strings::new(b"\x05hello\x00\x03abc");
+        assert_eq!(txt.next(), Some(&b"hello"[..]));
+        assert_eq!(txt.next(), Some(&b""[..]));
+        assert_eq!(txt.next(), Some(&b"abc"[..]));
+        assert!(txt.next().is_none());
+
+        let mut opts = EdnsOptions::new(&[0x00, 0x0c, 0x00, 0x02, 0x00, 0x00, 0x00, 0x03]);
+        assert_eq!(opts.next(), Some((EDNS_OPTION_PADDING, &[0x00, 0x00][..])));
+        assert!(opts.next().is_none());
+        assert_eq!(opts.remainder().len(), 2);
+
+        assert_eq!(edns_ttl(RCODE_BADVERS, 0, 0), 0x0100_0000);
+        assert_eq!(edns_rcode(0, 0x0100_0000), RCODE_BADVERS);
+
+        let s = Srv::from_bytes(&[0x00, 0x01, 0x00, 0x05, 0x14, 0x66]);
+        assert_eq!(int::ForeignEndian::to_native(s.port), 5222);
+    }
+}
@@ -0,0 +1,279 @@
+//! Extensible Binary Meta Language
+//!
+//! EBML (RFC 8794) is a binary encoding of a tree of elements, used by
+//! Matroska (RFC 9559) and WebM. Every element starts with an element ID and
+//! an element data size, both encoded as variable-size integers (see
+//! `varint::vint_decode()`), followed by the element data. The element ID
+//! keeps its width marker, so IDs are given here in their encoded form. A
+//! data size with all value bits set means the size is unknown, which is
+//! only valid for master elements and extends the element up to the end of
+//! its parent.
+//!
+//! Every EBML document starts with an `ID_EBML` header element, carrying the
+//! document type (e.g., `DOCTYPE_MATROSKA` or `DOCTYPE_WEBM`). Matroska files
+//! follow it with a single `ID_SEGMENT` element, which holds all other top
+//! level elements.
+//!
+//! All multi-byte integers are encoded as big-endian.
+
+use crate::specs::varint;
+
+/// Maximum Width of Element IDs
+pub const ID_MAX: usize = 4;
+
+pub const ID_EBML: u32 = 0x1a45dfa3;
+pub const ID_EBML_VERSION: u32 = 0x4286;
+pub const ID_EBML_READ_VERSION: u32 = 0x42f7;
+pub const ID_EBML_MAX_ID_LENGTH: u32 = 0x42f2;
+pub const ID_EBML_MAX_SIZE_LENGTH: u32 = 0x42f3;
+pub const ID_DOC_TYPE: u32 = 0x4282;
+pub const ID_DOC_TYPE_VERSION: u32 = 0x4287;
+pub const ID_DOC_TYPE_READ_VERSION: u32 = 0x4285;
+pub const ID_CRC32: u32 = 0xbf;
+pub const ID_VOID: u32 = 0xec;
+
+pub const ID_SEGMENT: u32 = 0x18538067;
+pub const ID_SEEK_HEAD: u32 = 0x114d9b74;
+pub const ID_SEEK: u32 = 0x4dbb;
+pub const ID_SEEK_ID: u32 = 0x53ab;
+pub const ID_SEEK_POSITION: u32 = 0x53ac;
+pub const ID_INFO: u32 = 0x1549a966;
+pub const ID_TIMESTAMP_SCALE: u32 = 0x2ad7b1;
+pub const ID_DURATION: u32 = 0x4489;
+pub const ID_MUXING_APP: u32 = 0x4d80;
+pub const ID_WRITING_APP: u32 = 0x5741;
+pub const ID_TRACKS: u32 = 0x1654ae6b;
+pub const ID_TRACK_ENTRY: u32 = 0xae;
+pub const ID_TRACK_NUMBER: u32 = 0xd7;
+pub const ID_TRACK_UID: u32 = 0x73c5;
+pub const ID_TRACK_TYPE: u32 = 0x83;
+pub const ID_CODEC_ID: u32 = 0x86;
+pub const ID_CODEC_PRIVATE: u32 = 0x63a2;
+pub const ID_VIDEO: u32 = 0xe0;
+pub const ID_PIXEL_WIDTH: u32 = 0xb0;
+pub const ID_PIXEL_HEIGHT: u32 = 0xba;
+pub const ID_AUDIO: u32 = 0xe1;
+pub const ID_SAMPLING_FREQUENCY: u32 = 0xb5;
+pub const ID_CHANNELS: u32 = 0x9f;
+pub const ID_CLUSTER: u32 = 0x1f43b675;
+pub const ID_TIMESTAMP: u32 = 0xe7;
+pub const ID_SIMPLE_BLOCK: u32 = 0xa3;
+pub const ID_BLOCK_GROUP: u32 = 0xa0;
+pub const ID_BLOCK: u32 = 0xa1;
+pub const ID_CUES: u32 = 0x1c53bb6b;
+pub const ID_CHAPTERS: u32 = 0x1043a770;
+pub const ID_TAGS: u32 = 0x1254c367;
+pub const ID_ATTACHMENTS: u32 = 0x1941a469;
+
+pub const DOCTYPE_MATROSKA: &[u8] = b"matroska";
+pub const DOCTYPE_WEBM: &[u8] = b"webm";
+
+pub const TRACK_TYPE_VIDEO: u64 = 0x01;
+pub const TRACK_TYPE_AUDIO: u64 = 0x02;
+pub const TRACK_TYPE_COMPLEX: u64 = 0x03;
+pub const TRACK_TYPE_LOGO: u64 = 0x10;
+pub const TRACK_TYPE_SUBTITLE: u64 = 0x11;
+pub const TRACK_TYPE_BUTTONS: u64 = 0x12;
+pub const TRACK_TYPE_CONTROL: u64 = 0x20;
+pub const TRACK_TYPE_METADATA: u64 = 0x21;
+
+pub const BLOCK_KEYFRAME: u8 = 0x80;
+pub const BLOCK_INVISIBLE: u8 = 0x08;
+pub const BLOCK_LACING_MASK: u8 = 0x06;
+pub const BLOCK_DISCARDABLE: u8 = 0x01;
+
+pub const LACING_NONE: u8 = 0x00;
+pub const LACING_XIPH: u8 = 0x02;
+pub const LACING_FIXED: u8 = 0x04;
+pub const LACING_EBML: u8 = 0x06;
+
+/// Element Reference
+///
+/// A reference to an element in a byte slice, as yielded by `Elements`.
+#[derive(Clone, Copy, Debug)]
+pub struct Element<'data> {
+    /// Element ID, including the width marker.
+    pub id: u32,
+
+    /// Whether the element has an unknown size.
+    pub unknown_size: bool,
+
+    /// Element data.
+    pub data: &'data [u8],
+}
+
+/// Element Iterator
+///
+/// An iterator over a sequence of elements, as found at the top level of a
+/// document and in the data of master elements. Elements of unknown size
+/// extend to the end of the data. Iteration stops at the end of the data,
+/// or at the first truncated or invalid element.
+#[derive(Clone, Debug)]
+pub struct Elements<'data> {
+    data: &'data [u8],
+}
+
+impl<'data> Element<'data> {
+    /// Iterate child elements
+    ///
+    /// Return an iterator over the data of a master element. Whether an
+    /// element is a master element depends on its ID, and is not checked.
+    pub fn children(&self) -> Elements<'data> {
+        Elements::new(self.data)
+    }
+
+    /// Decode unsigned integer
+    ///
+    /// Decode the data of an unsigned integer element, which has 0 to 8
+    /// bytes.
+    pub fn uint(&self) -> Option<u64> {
+        uint(self.data)
+    }
+}
+
+impl<'data> Elements<'data> {
+    /// Create element iterator
+    ///
+    /// Create a new iterator over the elements in `data`.
+    pub fn new(data: &'data [u8]) -> Self {
+        Self {
+            data,
+        }
+    }
+
+    /// Return remaining data
+    pub fn remainder(&self) -> &'data [u8] {
+        self.data
+    }
+}
+
+impl<'data> Iterator for Elements<'data> {
+    type Item = Element<'data>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (id, n) = element_id(self.data)?;
+        let (size, m) = varint::vint_decode(&self.data[n..])?;
+        let at = n + m;
+
+        let unknown_size = size == varint::vint_reserved(m);
+        let end = if unknown_size {
+            self.data.len()
+        } else {
+            at.checked_add(usize::try_from(size).ok()?)?
+        };
+
+        let element = Element {
+            id,
+            unknown_size,
+            data: self.data.get(at..end)?,
+        };
+
+        self.data = &self.data[end..];
+        Some(element)
+    }
+}
+
+/// Decode Element ID
+///
+/// Decode the element ID at the start of `data`, keeping the width marker.
+/// Return the ID and its width, or `None` if it is truncated or wider than
+/// `ID_MAX`.
+pub fn element_id(data: &[u8]) -> Option<(u32, usize)> {
+    let width = varint::vint_width(*data.first()?)?;
+    if width > ID_MAX {
+        return None;
+    }
+
+    let v = data.get(..width)?.iter().fold(0, |acc, b| (acc << 8) | *b as u32);
+    Some((v, width))
+}
+
+/// Decode Unsigned Integer
+///
+/// Decode the big-endian data of an unsigned integer element. Empty data
+/// encodes 0. `None` is returned for more than 8 bytes.
+pub fn uint(data: &[u8]) -> Option<u64> {
+    if data.len() > 8 {
+        return None;
+    }
+
+    Some(data.iter().fold(0, |acc, b| (acc << 8) | *b as u64))
+}
+
+/// Decode Block Header
+///
+/// Decode the header of a `SimpleBlock` or `Block`, and return the track
+/// number, the timestamp relative to the cluster, the flags (`BLOCK_*`),
+/// and the size of the header.
+pub fn block_header(data: &[u8]) -> Option<(u64, i16, u8, usize)> {
+    let (track, n) = varint::vint_decode(data)?;
+    let v = data.get(n..n + 3)?;
+    let timestamp = i16::from_be_bytes([v[0], v[1]]);
+    Some((track, timestamp, v[2], n + 3))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A WebM header followed by a segment of unknown size with a void.
+    const FILE: [u8; 51] = [
+        0x1a, 0x45, 0xdf, 0xa3, 0x9f, 0x42, 0x86, 0x81,
+        0x01, 0x42, 0xf7, 0x81, 0x01, 0x42, 0xf2, 0x81,
+        0x04, 0x42, 0xf3, 0x81, 0x08, 0x42, 0x82, 0x84,
+        0x77, 0x65, 0x62, 0x6d, 0x42, 0x87, 0x81, 0x02,
+        0x42, 0x85, 0x81, 0x02, 0x18, 0x53, 0x80, 0x67,
+        0x01, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0xec, 0x81, 0x00,
+    ];
+
+    // Walk the EBML header and decode its values.
+    #[test]
+    fn verify_header() {
+        let mut elements = Elements::new(&FILE);
+
+        let e = elements.next().unwrap();
+        assert_eq!(e.id, ID_EBML);
+        assert!(!e.unknown_size);
+
+        let mut doctype = None;
+        for c in e.children() {
+            match c.id {
+                ID_EBML_VERSION | ID_EBML_READ_VERSION => assert_eq!(c.uint(), Some(1)),
+                ID_EBML_MAX_ID_LENGTH => assert_eq!(c.uint(), Some(ID_MAX as u64)),
+                ID_EBML_MAX_SIZE_LENGTH => assert_eq!(c.uint(), Some(8)),
+                ID_DOC_TYPE => doctype = Some(c.data),
+                ID_DOC_TYPE_VERSION | ID_DOC_TYPE_READ_VERSION => assert_eq!(c.uint(), Some(2)),
+                _ => panic!(),
+            }
+        }
+        assert_eq!(doctype, Some(DOCTYPE_WEBM));
+
+        let e = elements.next().unwrap();
+        assert_eq!(e.id, ID_SEGMENT);
+        assert!(e.unknown_size);
+
+        let mut children = e.children();
+        let v = children.next().unwrap();
+        assert_eq!(v.id, ID_VOID);
+        assert_eq!(v.data, &[0x00]);
+        assert!(children.next().is_none());
+        assert!(elements.next().is_none());
+    }
+
+    // Verify rejection of invalid framing and decoding of block headers.
+    #[test]
+    fn verify_framing() {
+        assert!(Elements::new(&FILE[..35]).next().is_none());
+        assert!(Elements::new(&FILE[..40]).nth(1).is_none());
+        assert!(element_id(&[0x08, 0x00, 0x00, 0x00, 0x00]).is_none());
+        assert_eq!(element_id(&[0x1f, 0x43, 0xb6, 0x75]), Some((ID_CLUSTER, 4)));
+        assert_eq!(uint(&[]), Some(0));
+        assert!(uint(&[0; 9]).is_none());
+
+        let (track, timestamp, flags, n) = block_header(&[0x81, 0xff, 0xfe, 0x80]).unwrap();
+        assert_eq!((track, timestamp, n), (1, -2, 4));
+        assert_eq!(flags & BLOCK_KEYFRAME, BLOCK_KEYFRAME);
+        assert_eq!(flags & BLOCK_LACING_MASK, LACING_NONE);
+    }
+}
@@ -0,0 +1,802 @@
+//! Extended Display Identification Data
+//!
+//! Displays describe their identity and capabilities to the source via the
+//! EDID, read over the DDC channel in blocks of `BLOCK_SIZE` bytes. The
+//! base block (`BaseBlock`) starts with the static `HEADER` and carries the
+//! vendor and product identification, basic display parameters, and the
+//! supported video modes as established timings, standard timings
+//! (`StandardTiming`), and detailed timing descriptors (`DetailedTiming`).
+//! Descriptor slots not used for detailed timings carry display
+//! descriptors (`DisplayDescriptor`), like the monitor name or range
+//! limits.
+//!
+//! The base block is followed by `extension_count` extension blocks,
+//! identified by their first byte (`EXT_*`). Most common is the CEA-861
+//! extension (`CeaExtension`), carrying a collection of data blocks
+//! followed by further detailed timing descriptors. All bytes of each block
+//! sum up to 0 modulo 256 (see `checksum_valid()`).
+//!
+//! All multi-byte integers are encoded as little-endian, except for the
+//! manufacturer ID, which is encoded as big-endian.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::checksum;
+use crate::specs::int;
+
+pub const HEADER: [u8; 8] = [0x00, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x00];
+pub const BLOCK_SIZE: usize = 128;
+
+/// Year Base
+///
+/// The year that year fields are relative to.
+pub const YEAR_BASE: u16 = 1990;
+
+/// Model Year Marker
+///
+/// The week of manufacture indicating that the year is the model year.
+pub const WEEK_MODEL_YEAR: u8 = 0xff;
+
+pub const INPUT_DIGITAL: u8 = 0x80;
+pub const INPUT_DEPTH_MASK: u8 = 0x70;
+pub const INPUT_DEPTH_UNDEFINED: u8 = 0x00;
+pub const INPUT_DEPTH_6: u8 = 0x10;
+pub const INPUT_DEPTH_8: u8 = 0x20;
+pub const INPUT_DEPTH_10: u8 = 0x30;
+pub const INPUT_DEPTH_12: u8 = 0x40;
+pub const INPUT_DEPTH_14: u8 = 0x50;
+pub const INPUT_DEPTH_16: u8 = 0x60;
+pub const INPUT_INTERFACE_MASK: u8 = 0x0f;
+pub const INPUT_INTERFACE_UNDEFINED: u8 = 0x00;
+pub const INPUT_INTERFACE_DVI: u8 = 0x01;
+pub const INPUT_INTERFACE_HDMI_A: u8 = 0x02;
+pub const INPUT_INTERFACE_HDMI_B: u8 = 0x03;
+pub const INPUT_INTERFACE_MDDI: u8 = 0x04;
+pub const INPUT_INTERFACE_DISPLAYPORT: u8 = 0x05;
+
+pub const FEATURE_STANDBY: u8 = 0x80;
+pub const FEATURE_SUSPEND: u8 = 0x40;
+pub const FEATURE_ACTIVE_OFF: u8 = 0x20;
+pub const FEATURE_COLOR_MASK: u8 = 0x18;
+pub const FEATURE_SRGB: u8 = 0x04;
+pub const FEATURE_PREFERRED_NATIVE: u8 = 0x02;
+pub const FEATURE_CONTINUOUS_FREQ: u8 = 0x01;
+
+/// Unused Standard Timing
+///
+/// The value of unused standard timing slots.
+pub const STANDARD_TIMING_UNUSED: [u8; 2] = [0x01, 0x01];
+
+pub const ASPECT_16_10: u8 = 0x0;
+pub const ASPECT_4_3: u8 = 0x1;
+pub const ASPECT_5_4: u8 = 0x2;
+pub const ASPECT_16_9: u8 = 0x3;
+
+pub const DTD_INTERLACED: u8 = 0x80;
+pub const DTD_STEREO_MASK: u8 = 0x61;
+pub const DTD_SYNC_MASK: u8 = 0x18;
+pub const DTD_SYNC_ANALOG: u8 = 0x00;
+pub const DTD_SYNC_BIPOLAR_ANALOG: u8 = 0x08;
+pub const DTD_SYNC_DIGITAL_COMPOSITE: u8 = 0x10;
+pub const DTD_SYNC_DIGITAL_SEPARATE: u8 = 0x18;
+pub const DTD_VSYNC_POSITIVE: u8 = 0x04;
+pub const DTD_HSYNC_POSITIVE: u8 = 0x02;
+
+pub const DISPLAY_SERIAL: u8 = 0xff;
+pub const DISPLAY_TEXT: u8 = 0xfe;
+pub const DISPLAY_RANGE_LIMITS: u8 = 0xfd;
+pub const DISPLAY_NAME: u8 = 0xfc;
+pub const DISPLAY_COLOR_POINT: u8 = 0xfb;
+pub const DISPLAY_STANDARD_TIMINGS: u8 = 0xfa;
+pub const DISPLAY_DCM: u8 = 0xf9;
+pub const DISPLAY_CVT: u8 = 0xf8;
+pub const DISPLAY_ESTABLISHED_TIMINGS3: u8 = 0xf7;
+pub const DISPLAY_DUMMY: u8 = 0x10;
+
+pub const EXT_CEA: u8 = 0x02;
+pub const EXT_VTB: u8 = 0x10;
+pub const EXT_DI: u8 = 0x40;
+pub const EXT_LS: u8 = 0x50;
+pub const EXT_DPVL: u8 = 0x60;
+pub const EXT_DISPLAYID: u8 = 0x70;
+pub const EXT_BLOCK_MAP: u8 = 0xf0;
+pub const EXT_MANUFACTURER: u8 = 0xff;
+
+pub const CEA_UNDERSCAN: u8 = 0x80;
+pub const CEA_BASIC_AUDIO: u8 = 0x40;
+pub const CEA_YCBCR444: u8 = 0x20;
+pub const CEA_YCBCR422: u8 = 0x10;
+pub const CEA_NATIVE_COUNT_MASK: u8 = 0x0f;
+
+pub const CEA_BLOCK_TAG_SHIFT: u8 = 5;
+pub const CEA_BLOCK_LENGTH_MASK: u8 = 0x1f;
+
+pub const CEA_BLOCK_AUDIO: u8 = 0x1;
+pub const CEA_BLOCK_VIDEO: u8 = 0x2;
+pub const CEA_BLOCK_VENDOR: u8 = 0x3;
+pub const CEA_BLOCK_SPEAKER: u8 = 0x4;
+pub const CEA_BLOCK_VESA_DTC: u8 = 0x5;
+pub const CEA_BLOCK_EXTENDED: u8 = 0x7;
+
+pub const CEA_EXT_VIDEO_CAPABILITY: u8 = 0x00;
+pub const CEA_EXT_VENDOR_VIDEO: u8 = 0x01;
+pub const CEA_EXT_COLORIMETRY: u8 = 0x05;
+pub const CEA_EXT_HDR_STATIC_METADATA: u8 = 0x06;
+pub const CEA_EXT_HDR_DYNAMIC_METADATA: u8 = 0x07;
+pub const CEA_EXT_YCBCR420_VIDEO: u8 = 0x0e;
+pub const CEA_EXT_YCBCR420_CAPABILITY_MAP: u8 = 0x0f;
+pub const CEA_EXT_VENDOR_AUDIO: u8 = 0x11;
+pub const CEA_EXT_ROOM_CONFIGURATION: u8 = 0x13;
+
+pub const CEA_VIC_NATIVE: u8 = 0x80;
+pub const CEA_VIC_MASK: u8 = 0x7f;
+
+pub const OUI_HDMI: u32 = 0x000c03;
+pub const OUI_HDMI_FORUM: u32 = 0xc45dd8;
+
+/// EDID Base Block
+///
+/// The first 128-byte block of the EDID, in the layout of EDID 1.3 and 1.4.
+#[repr(C)]
+pub struct BaseBlock {
+    /// The static header. This must match `HEADER`.
+    pub header: [u8; 8],
+
+    /// Manufacturer ID as three 5-bit letters, encoded as big-endian (see
+    /// `decode_manufacturer()`).
+    pub manufacturer_id: int::u16be,
+
+    /// Manufacturer-assigned product code.
+    pub product_code: int::u16le,
+
+    /// Serial number, or 0.
+    pub serial_number: int::u32le,
+
+    /// Week of manufacture, or `WEEK_MODEL_YEAR` if `year` is the model year.
+    pub week: u8,
+
+    /// Year of manufacture, or model year, relative to `YEAR_BASE`.
+    pub year: u8,
+
+    /// EDID version, usually 1.
+    pub version: u8,
+
+    /// EDID revision.
+    pub revision: u8,
+
+    /// Video input definition (`INPUT_*`).
+    pub video_input: u8,
+
+    /// Horizontal screen size in cm, or 0.
+    pub h_size: u8,
+
+    /// Vertical screen size in cm, or 0.
+    pub v_size: u8,
+
+    /// Display gamma, as `(gamma * 100) - 100`, or 0xff if undefined.
+    pub gamma: u8,
+
+    /// Supported features (`FEATURE_*`).
+    pub features: u8,
+
+    /// Chromaticity coordinates of the color primaries and the white point.
+    pub chromaticity: [u8; 10],
+
+    /// Bit mask of supported established timings.
+    pub established_timings: [u8; 3],
+
+    /// Standard timings, or `STANDARD_TIMING_UNUSED` (see
+    /// `StandardTiming`).
+    pub standard_timings: [[u8; 2]; 8],
+
+    /// Detailed timing descriptors and display descriptors, told apart by
+    /// a pixel clock of 0.
+    pub descriptors: [[u8; 18]; 4],
+
+    /// Number of extension blocks following the base block.
+    pub extension_count: u8,
+
+    /// Checksum byte, making all bytes of the block sum up to 0.
+    pub checksum: u8,
+}
+
+/// Detailed Timing Descriptor
+///
+/// Describes a video mode. The 12-bit active, blanking, and image size
+/// values, and the 10-bit and 6-bit sync values are split into low bits
+/// and nibbles or bit pairs of shared bytes (see the accessors).
+#[repr(C)]
+pub struct DetailedTiming {
+    /// Pixel clock in units of 10 kHz. This is never 0.
+    pub pixel_clock: int::u16le,
+
+    /// Bits 7:0 of the horizontal active pixels.
+    pub h_active_lo: u8,
+
+    /// Bits 7:0 of the horizontal blanking pixels.
+    pub h_blanking_lo: u8,
+
+    /// Bits 11:8 of the horizontal active (7:4) and blanking (3:0) pixels.
+    pub h_hi: u8,
+
+    /// Bits 7:0 of the vertical active lines.
+    pub v_active_lo: u8,
+
+    /// Bits 7:0 of the vertical blanking lines.
+    pub v_blanking_lo: u8,
+
+    /// Bits 11:8 of the vertical active (7:4) and blanking (3:0) lines.
+    pub v_hi: u8,
+
+    /// Bits 7:0 of the horizontal front porch in pixels.
+    pub h_sync_offset_lo: u8,
+
+    /// Bits 7:0 of the horizontal sync pulse width in pixels.
+    pub h_sync_width_lo: u8,
+
+    /// Bits 3:0 of the vertical front porch (7:4) and sync pulse width (3:0).
+    pub v_sync_lo: u8,
+
+    /// Bits 9:8 of the horizontal front porch (7:6) and sync width (5:4), and
+    /// bits 5:4 of the vertical front porch (3:2) and sync width (1:0).
+    pub sync_hi: u8,
+
+    /// Bits 7:0 of the horizontal image size in mm.
+    pub h_image_size_lo: u8,
+
+    /// Bits 7:0 of the vertical image size in mm.
+    pub v_image_size_lo: u8,
+
+    /// Bits 11:8 of the horizontal (7:4) and vertical (3:0) image size.
+    pub image_size_hi: u8,
+
+    /// Horizontal border in pixels.
+    pub h_border: u8,
+
+    /// Vertical border in lines.
+    pub v_border: u8,
+
+    /// Interlacing, stereo, and sync flags (`DTD_*`).
+    pub flags: u8,
+}
+
+/// Display Descriptor
+///
+/// A descriptor slot that does not carry a detailed timing, as indicated by
+/// a pixel clock of 0.
+#[repr(C)]
+pub struct DisplayDescriptor {
+    /// Always 0.
+    pub zero: int::u16le,
+
+    /// Reserved.
+    pub reserved0: u8,
+
+    /// Descriptor tag (`DISPLAY_*`).
+    pub tag: u8,
+
+    /// Reserved, except for `DISPLAY_RANGE_LIMITS`.
+    pub reserved1: u8,
+
+    /// Descriptor data.
+    pub data: [u8; 13],
+}
+
+/// Standard Timing
+///
+/// A decoded standard timing, identifying a video mode by resolution and
+/// refresh rate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StandardTiming {
+    /// Horizontal active pixels.
+    pub h_active: u16,
+
+    /// Vertical active lines.
+    pub v_active: u16,
+
+    /// Refresh rate in Hz.
+    pub refresh: u8,
+}
+
+/// CEA-861 Extension Reference
+///
+/// A reference to a CEA-861 extension block (`EXT_CEA`).
+#[derive(Clone, Copy, Debug)]
+pub struct CeaExtension<'data> {
+    /// Revision of the extension block.
+    pub revision: u8,
+
+    /// Display support flags (`CEA_*`), for revision 2 and later.
+    pub flags: u8,
+
+    /// Data block collection, for revision 3 and later (see `DataBlocks`).
+    pub data_blocks: &'data [u8],
+
+    /// Detailed timing descriptors, followed by padding.
+    pub timings: &'data [u8],
+}
+
+/// CEA-861 Data Block Reference
+///
+/// A reference to a data block of the data block collection of a CEA-861
+/// extension.
+#[derive(Clone, Copy, Debug)]
+pub struct DataBlock<'data> {
+    /// Data block tag (`CEA_BLOCK_*`).
+    pub tag: u8,
+
+    /// Extended tag of `CEA_BLOCK_EXTENDED` (`CEA_EXT_*`), or 0.
+    pub extended_tag: u8,
+
+    /// Payload of the data block, excluding the header and extended tag.
+    pub data: &'data [u8],
+}
+
+/// CEA-861 Data Block Iterator
+///
+/// An iterator over a data block collection, yielding a `DataBlock` for
+/// every data block. Iteration stops at the first truncated data block.
+#[derive(Clone, Debug)]
+pub struct DataBlocks<'data> {
+    data: &'data [u8],
+}
+
+impl BaseBlock {
+    /// Import a base block from a byte slice
+    ///
+    /// Create a new base block from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 128]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 128], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Return manufacturer letters
+    ///
+    /// Decode the manufacturer ID into its three ASCII letters.
+    pub fn manufacturer(&self) -> Option<[u8; 3]> {
+        decode_manufacturer(int::ForeignEndian::to_native(self.manufacturer_id))
+    }
+
+    /// Return a detailed timing
+    ///
+    /// Return the descriptor slot with the given index (0 to 3) as detailed
+    /// timing, or `None` if it carries a display descriptor.
+    pub fn timing(&self, index: usize) -> Option<DetailedTiming> {
+        let v = self.descriptors.get(index)?;
+        if v[0] == 0 && v[1] == 0 {
+            return None;
+        }
+
+        Some(DetailedTiming::from_bytes(v))
+    }
+
+    /// Return a display descriptor
+    ///
+    /// Return the descriptor slot with the given index (0 to 3) as display
+    /// descriptor, or `None` if it carries a detailed timing.
+    pub fn display_descriptor(&self, index: usize) -> Option<DisplayDescriptor> {
+        let v = self.descriptors.get(index)?;
+        if v[0] != 0 || v[1] != 0 {
+            return None;
+        }
+
+        Some(DisplayDescriptor::from_bytes(v))
+    }
+}
+
+impl DetailedTiming {
+    /// Import a detailed timing descriptor from a byte slice
+    ///
+    /// Create a new detailed timing descriptor from data copied from a byte
+    /// slice. No byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 18]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 18], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Return pixel clock in kHz
+    pub fn pixel_clock_khz(&self) -> u32 {
+        int::ForeignEndian::to_native(self.pixel_clock) as u32 * 10
+    }
+
+    /// Return horizontal active pixels
+    pub fn h_active(&self) -> u16 {
+        (((self.h_hi >> 4) as u16) << 8) | self.h_active_lo as u16
+    }
+
+    /// Return horizontal blanking pixels
+    pub fn h_blanking(&self) -> u16 {
+        (((self.h_hi & 0x0f) as u16) << 8) | self.h_blanking_lo as u16
+    }
+
+    /// Return vertical active lines
+    pub fn v_active(&self) -> u16 {
+        (((self.v_hi >> 4) as u16) << 8) | self.v_active_lo as u16
+    }
+
+    /// Return vertical blanking lines
+    pub fn v_blanking(&self) -> u16 {
+        (((self.v_hi & 0x0f) as u16) << 8) | self.v_blanking_lo as u16
+    }
+
+    /// Return horizontal front porch in pixels
+    pub fn h_sync_offset(&self) -> u16 {
+        (((self.sync_hi >> 6) as u16) << 8) | self.h_sync_offset_lo as u16
+    }
+
+    /// Return horizontal sync pulse width in pixels
+    pub fn h_sync_width(&self) -> u16 {
+        ((((self.sync_hi >> 4) & 0x3) as u16) << 8) | self.h_sync_width_lo as u16
+    }
+
+    /// Return vertical front porch in lines
+    pub fn v_sync_offset(&self) -> u8 {
+        (((self.sync_hi >> 2) & 0x3) << 4) | (self.v_sync_lo >> 4)
+    }
+
+    /// Return vertical sync pulse width in lines
+    pub fn v_sync_width(&self) -> u8 {
+        ((self.sync_hi & 0x3) << 4) | (self.v_sync_lo & 0x0f)
+    }
+
+    /// Return horizontal image size in mm
+    pub fn h_image_size(&self) -> u16 {
+        (((self.image_size_hi >> 4) as u16) << 8) | self.h_image_size_lo as u16
+    }
+
+    /// Return vertical image size in mm
+    pub fn v_image_size(&self) -> u16 {
+        (((self.image_size_hi & 0x0f) as u16) << 8) | self.v_image_size_lo as u16
+    }
+
+    /// Check for an interlaced mode
+    pub fn is_interlaced(&self) -> bool {
+        (self.flags & DTD_INTERLACED) != 0
+    }
+}
+
+impl DisplayDescriptor {
+    /// Import a display descriptor from a byte slice
+    ///
+    /// Create a new display descriptor from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 18]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 18], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Return descriptor text
+    ///
+    /// Return the text of descriptors of type `DISPLAY_SERIAL`,
+    /// `DISPLAY_TEXT`, and `DISPLAY_NAME`, up to the terminating line feed,
+    /// and without trailing padding.
+    pub fn text(&self) -> &[u8] {
+        let v = &self.data[..];
+        let v = match v.iter().position(|c| *c == b'\n') {
+            Some(n) => &v[..n],
+            None => v,
+        };
+
+        match v.iter().rposition(|c| *c != b' ') {
+            Some(n) => &v[..=n],
+            None => &[],
+        }
+    }
+}
+
+impl StandardTiming {
+    /// Decode a standard timing
+    ///
+    /// Decode a 2-byte standard timing of an EDID of the given revision.
+    /// `ASPECT_16_10` denotes 1:1 before revision 3. `None` is returned
+    /// for `STANDARD_TIMING_UNUSED`, and other values with a zero first
+    /// byte.
+    pub fn decode(data: &[u8; 2], revision: u8) -> Option<Self> {
+        if *data == STANDARD_TIMING_UNUSED || data[0] == 0 {
+            return None;
+        }
+
+        let h_active = (data[0] as u16 + 31) * 8;
+        let v_active = match data[1] >> 6 {
+            ASPECT_16_10 if revision < 3 => h_active,
+            ASPECT_16_10 => h_active * 10 / 16,
+            ASPECT_4_3 => h_active * 3 / 4,
+            ASPECT_5_4 => h_active * 4 / 5,
+            _ => h_active * 9 / 16,
+        };
+
+        Some(Self {
+            h_active,
+            v_active,
+            refresh: (data[1] & 0x3f) + 60,
+        })
+    }
+}
+
+impl<'data> CeaExtension<'data> {
+    /// Parse a CEA-861 extension block
+    ///
+    /// Parse the extension block `block`. `None` is returned if it is not a
+    /// CEA-861 extension, or if the offset of the detailed timings is
+    /// invalid.
+    pub fn parse(block: &'data [u8; BLOCK_SIZE]) -> Option<Self> {
+        if block[0] != EXT_CEA {
+            return None;
+        }
+
+        // An offset of 0 indicates neither data blocks nor timings.
+        let offset = match block[2] as usize {
+            0 => 4,
+            v if (4..BLOCK_SIZE).contains(&v) => v,
+            _ => return None,
+        };
+        let end = if block[2] == 0 { 4 } else { BLOCK_SIZE - 1 };
+
+        Some(Self {
+            revision: block[1],
+            flags: if block[1] >= 2 { block[3] } else { 0 },
+            data_blocks: if block[1] >= 3 { &block[4..offset] } else { &[] },
+            timings: &block[offset..end],
+        })
+    }
+
+    /// Return a detailed timing
+    ///
+    /// Return the detailed timing with the given index, or `None` if out of
+    /// bounds, or if the padding is reached.
+    pub fn timing(&self, index: usize) -> Option<DetailedTiming> {
+        let v = self.timings.get(index * 18..index * 18 + 18)?;
+        if v[0] == 0 && v[1] == 0 {
+            return None;
+        }
+
+        Some(DetailedTiming::from_bytes(v.try_into().ok()?))
+    }
+}
+
+impl<'data> DataBlocks<'data> {
+    /// Create data block iterator
+    ///
+    /// Create a new iterator over the data block collection `data`.
+    pub fn new(data: &'data [u8]) -> Self {
+        Self { data }
+    }
+
+    /// Return remaining data
+    ///
+    /// Return the data that has not been consumed by the iterator.
+    pub fn remainder(&self) -> &'data [u8] {
+        self.data
+    }
+}
+
+impl<'data> Iterator for DataBlocks<'data> {
+    type Item = DataBlock<'data>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let header = *self.data.first()?;
+        let tag = header >> CEA_BLOCK_TAG_SHIFT;
+        let length = (header & CEA_BLOCK_LENGTH_MASK) as usize;
+        let data = self.data.get(1..1 + length)?;
+        self.data = &self.data[1 + length..];
+
+        if tag == CEA_BLOCK_EXTENDED {
+            let (extended_tag, data) = data.split_first()?;
+            Some(DataBlock {
+                tag,
+                extended_tag: *extended_tag,
+                data,
+            })
+        } else {
+            Some(DataBlock {
+                tag,
+                extended_tag: 0,
+                data,
+            })
+        }
+    }
+}
+
+/// Verify the checksum of a block
+///
+/// Check that all bytes of an EDID block sum up to 0 modulo 256.
+pub fn checksum_valid(block: &[u8; BLOCK_SIZE]) -> bool {
+    checksum::sum8(block) == 0
+}
+
+/// Decode a manufacturer ID
+///
+/// Decode the three 5-bit letters of a manufacturer ID, where 1 encodes
+/// `A`, into ASCII. `None` is returned if a letter is out of range.
+pub fn decode_manufacturer(id: u16) -> Option<[u8; 3]> {
+    let mut v = [0u8; 3];
+
+    for (i, c) in v.iter_mut().enumerate() {
+        let l = ((id >> (10 - i * 5)) & 0x1f) as u8;
+        if !(1..=26).contains(&l) {
+            return None;
+        }
+        *c = b'A' + l - 1;
+    }
+
+    Some(v)
+}
+
+/// Encode a manufacturer ID
+///
+/// Encode three uppercase ASCII letters as manufacturer ID. `None` is
+/// returned if a letter is out of range.
+pub fn encode_manufacturer(letters: &[u8; 3]) -> Option<u16> {
+    letters.iter().try_fold(0u16, |acc, c| {
+        if !c.is_ascii_uppercase() {
+            return None;
+        }
+        Some((acc << 5) | (c - b'A' + 1) as u16)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::specs::int::ForeignEndian;
+
+    // EDID 1.4 base block of a 1080p monitor with a detailed timing, range
+    // limits, a name, and a serial number.
+    const BASE: [u8; 128] = [
+        0x00, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x00,
+        0x10, 0xac, 0xc5, 0xa0, 0x78, 0x56, 0x34, 0x12,
+        0x01, 0x1e, 0x01, 0x04, 0xa5, 0x34, 0x20, 0x78,
+        0x3a, 0xee, 0x95, 0xa3, 0x54, 0x4c, 0x99, 0x26,
+        0x0f, 0x50, 0x54, 0x21, 0x08, 0x00, 0xd1, 0xc0,
+        0x81, 0x80, 0x01, 0x01, 0x01, 0x01, 0x01, 0x01,
+        0x01, 0x01, 0x01, 0x01, 0x01, 0x01, 0x02, 0x3a,
+        0x80, 0x18, 0x71, 0x38, 0x2d, 0x40, 0x58, 0x2c,
+        0x45, 0x00, 0xfd, 0x1e, 0x11, 0x00, 0x00, 0x1e,
+        0x00, 0x00, 0x00, 0xfd, 0x00, 0x38, 0x4c, 0x1e,
+        0x53, 0x11, 0x00, 0x0a, 0x20, 0x20, 0x20, 0x20,
+        0x20, 0x20, 0x00, 0x00, 0x00, 0xfc, 0x00, 0x54,
+        0x45, 0x53, 0x54, 0x20, 0x4d, 0x4f, 0x4e, 0x49,
+        0x54, 0x4f, 0x52, 0x0a, 0x00, 0x00, 0x00, 0xff,
+        0x00, 0x41, 0x42, 0x43, 0x31, 0x32, 0x33, 0x0a,
+        0x20, 0x20, 0x20, 0x20, 0x20, 0x20, 0x01, 0x85,
+    ];
+
+    // CEA-861 extension block with video, audio, HDMI vendor-specific, and
+    // colorimetry data blocks, and one detailed timing.
+    const CEA: [u8; 128] = [
+        0x02, 0x03, 0x16, 0xf1, 0x43, 0x90, 0x04, 0x03,
+        0x23, 0x09, 0x07, 0x07, 0x65, 0x03, 0x0c, 0x00,
+        0x10, 0x00, 0xe3, 0x05, 0x03, 0x01, 0x02, 0x3a,
+        0x80, 0x18, 0x71, 0x38, 0x2d, 0x40, 0x58, 0x2c,
+        0x45, 0x00, 0xfd, 0x1e, 0x11, 0x00, 0x00, 0x1e,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x73,
+    ];
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<BaseBlock>(), BLOCK_SIZE);
+        assert_eq!(align_of::<BaseBlock>(), 4);
+        assert_eq!(size_of::<DetailedTiming>(), 18);
+        assert_eq!(size_of::<DisplayDescriptor>(), 18);
+    }
+
+    // Import the base block and decode its identification and timings.
+    #[test]
+    fn verify_base() {
+        assert!(checksum_valid(&BASE));
+        let b = BaseBlock::from_bytes(&BASE);
+        assert_eq!(b.header, HEADER);
+        assert_eq!(b.manufacturer_id.to_native(), 0x10ac);
+        assert_eq!(&b.manufacturer().unwrap(), b"DEL");
+        assert_eq!(encode_manufacturer(b"DEL"), Some(0x10ac));
+        assert_eq!(b.product_code.to_native(), 0xa0c5);
+        assert_eq!(b.serial_number.to_native(), 0x12345678);
+        assert_eq!(b.year as u16 + YEAR_BASE, 2020);
+        assert_eq!((b.version, b.revision), (1, 4));
+        assert_eq!(b.video_input & INPUT_DEPTH_MASK, INPUT_DEPTH_8);
+        assert_eq!(b.video_input & INPUT_INTERFACE_MASK, INPUT_INTERFACE_DISPLAYPORT);
+        assert_eq!(b.extension_count, 1);
+
+        let s = StandardTiming::decode(&b.standard_timings[0], b.revision).unwrap();
+        assert_eq!((s.h_active, s.v_active, s.refresh), (1920, 1080, 60));
+        let s = StandardTiming::decode(&b.standard_timings[1], b.revision).unwrap();
+        assert_eq!((s.h_active, s.v_active, s.refresh), (1280, 1024, 60));
+        assert!(StandardTiming::decode(&b.standard_timings[2], b.revision).is_none());
+
+        let t = b.timing(0).unwrap();
+        assert!(b.display_descriptor(0).is_none());
+        assert_eq!(t.pixel_clock_khz(), 148500);
+        assert_eq!((t.h_active(), t.h_blanking()), (1920, 280));
+        assert_eq!((t.v_active(), t.v_blanking()), (1080, 45));
+        assert_eq!((t.h_sync_offset(), t.h_sync_width()), (88, 44));
+        assert_eq!((t.v_sync_offset(), t.v_sync_width()), (4, 5));
+        assert_eq!((t.h_image_size(), t.v_image_size()), (509, 286));
+        assert!(!t.is_interlaced());
+        assert_eq!(t.flags & DTD_SYNC_MASK, DTD_SYNC_DIGITAL_SEPARATE);
+
+        assert!(b.timing(1).is_none());
+        let d = b.display_descriptor(1).unwrap();
+        assert_eq!(d.tag, DISPLAY_RANGE_LIMITS);
+        let d = b.display_descriptor(2).unwrap();
+        assert_eq!((d.tag, d.text()), (DISPLAY_NAME, &b"TEST MONITOR"[..]));
+        let d = b.display_descriptor(3).unwrap();
+        assert_eq!((d.tag, d.text()), (DISPLAY_SERIAL, &b"ABC123"[..]));
+        assert!(b.timing(4).is_none());
+
+        let mut v = BASE;
+        v[20] ^= 0x01;
+        assert!(!checksum_valid(&v));
+        assert_eq!(decode_manufacturer(0x0000), None);
+        assert_eq!(encode_manufacturer(b"De1"), None);
+    }
+
+    // Parse the CEA-861 extension and its data block collection.
+    #[test]
+    fn verify_cea() {
+        assert!(checksum_valid(&CEA));
+        let c = CeaExtension::parse(&CEA).unwrap();
+        assert_eq!(c.revision, 3);
+        assert_eq!(c.flags & CEA_NATIVE_COUNT_MASK, 1);
+        assert_ne!(c.flags & CEA_BASIC_AUDIO, 0);
+
+        let blocks: std::vec::Vec<DataBlock> = DataBlocks::new(c.data_blocks).collect();
+        assert_eq!(blocks.len(), 4);
+        assert_eq!(blocks[0].tag, CEA_BLOCK_VIDEO);
+        assert_eq!(blocks[0].data[0] & CEA_VIC_NATIVE, CEA_VIC_NATIVE);
+        assert_eq!(blocks[0].data[0] & CEA_VIC_MASK, 16);
+        assert_eq!((blocks[1].tag, blocks[1].data.len()), (CEA_BLOCK_AUDIO, 3));
+        assert_eq!(blocks[2].tag, CEA_BLOCK_VENDOR);
+        let oui = u32::from_le_bytes([blocks[2].data[0], blocks[2].data[1], blocks[2].data[2], 0]);
+        assert_eq!(oui, OUI_HDMI);
+        assert_eq!(blocks[3].tag, CEA_BLOCK_EXTENDED);
+        assert_eq!(blocks[3].extended_tag, CEA_EXT_COLORIMETRY);
+        assert_eq!(blocks[3].data, &[0x03, 0x01]);
+
+        assert_eq!(c.timing(0).unwrap().h_active(), 1920);
+        assert!(c.timing(1).is_none());
+
+        assert!(CeaExtension::parse(&BASE).is_none());
+        let mut v = CEA;
+        v[2] = 0;
+        let c = CeaExtension::parse(&v).unwrap();
+        assert!(c.data_blocks.is_empty() && c.timings.is_empty());
+        assert_eq!(DataBlocks::new(&[0x43, 0x90]).count(), 0);
+    }
+}
@@ -0,0 +1,355 @@
+//! Ethernet Frames
+//!
+//! Ethernet frames start with the destination and source MAC addresses,
+//! followed by a 16-bit field that is either the EtherType of the payload
+//! (Ethernet II, values from `ETHERTYPE_MIN`), or the length of the payload
+//! (IEEE 802.3, values up to `LENGTH_MAX`). Values in between are invalid.
+//!
+//! IEEE 802.3 frames carry an IEEE 802.2 LLC header (`Llc`) at the start of
+//! their payload. If both service access points are `SAP_SNAP`, it is
+//! followed by a SNAP extension carrying an OUI and a protocol ID (`LlcSnap`).
+//! With `OUI_ENCAPSULATED`, the protocol ID is an EtherType.
+//!
+//! The payload is padded to `PAYLOAD_MIN`, and followed by the frame check
+//! sequence, which is the CRC-32 of the entire frame (see `fcs()`). Captured
+//! frames usually lack the FCS.
+//!
+//! All multi-byte integers are encoded as big-endian, except for the FCS,
+//! which is transmitted least significant byte first.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::{checksum, int};
+
+pub const HEADER_SIZE: usize = 14;
+pub const FCS_SIZE: usize = 4;
+pub const PAYLOAD_MIN: usize = 46;
+pub const PAYLOAD_MAX: usize = 1500;
+
+/// Maximum Value of Length Fields
+pub const LENGTH_MAX: u16 = 1500;
+
+/// Minimum Value of EtherType Fields
+pub const ETHERTYPE_MIN: u16 = 0x0600;
+
+pub const ETHERTYPE_IPV4: u16 = 0x0800;
+pub const ETHERTYPE_ARP: u16 = 0x0806;
+pub const ETHERTYPE_WAKE_ON_LAN: u16 = 0x0842;
+pub const ETHERTYPE_TRANSPARENT_BRIDGING: u16 = 0x6558;
+pub const ETHERTYPE_RARP: u16 = 0x8035;
+pub const ETHERTYPE_VLAN: u16 = 0x8100;
+pub const ETHERTYPE_IPV6: u16 = 0x86dd;
+pub const ETHERTYPE_FLOW_CONTROL: u16 = 0x8808;
+pub const ETHERTYPE_MPLS: u16 = 0x8847;
+pub const ETHERTYPE_MPLS_MULTICAST: u16 = 0x8848;
+pub const ETHERTYPE_PPPOE_DISCOVERY: u16 = 0x8863;
+pub const ETHERTYPE_PPPOE_SESSION: u16 = 0x8864;
+pub const ETHERTYPE_EAPOL: u16 = 0x888e;
+pub const ETHERTYPE_QINQ: u16 = 0x88a8;
+pub const ETHERTYPE_LLDP: u16 = 0x88cc;
+pub const ETHERTYPE_MACSEC: u16 = 0x88e5;
+pub const ETHERTYPE_PTP: u16 = 0x88f7;
+
+pub const SAP_NULL: u8 = 0x00;
+pub const SAP_STP: u8 = 0x42;
+pub const SAP_SNAP: u8 = 0xaa;
+pub const SAP_NETWARE: u8 = 0xe0;
+pub const SAP_GLOBAL: u8 = 0xff;
+
+pub const SAP_GROUP: u8 = 0x01;
+pub const SAP_RESPONSE: u8 = 0x01;
+
+pub const LLC_UI: u8 = 0x03;
+
+pub const OUI_ENCAPSULATED: [u8; 3] = [0x00, 0x00, 0x00];
+pub const OUI_CISCO: [u8; 3] = [0x00, 0x00, 0x0c];
+pub const OUI_BRIDGE_TUNNEL: [u8; 3] = [0x00, 0x00, 0xf8];
+
+/// MAC Address
+///
+/// A 48-bit IEEE 802 MAC address, stored in transmission order. The least
+/// significant bit of the first byte marks group addresses, the second bit
+/// marks locally administered addresses.
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+#[repr(transparent)]
+pub struct Mac {
+    raw: [u8; 6],
+}
+
+/// Ethernet Header
+#[repr(C)]
+pub struct Header {
+    /// Destination MAC address.
+    pub destination: Mac,
+
+    /// Source MAC address.
+    pub source: Mac,
+
+    /// EtherType (`ETHERTYPE_*`) or payload length (see `ether_type()` and
+    /// `length()`).
+    pub ether_type: int::u16be,
+}
+
+/// IEEE 802.2 LLC Header
+#[repr(C)]
+pub struct Llc {
+    /// Destination service access point (`SAP_*`), or'ed with `SAP_GROUP`.
+    pub dsap: u8,
+
+    /// Source service access point (`SAP_*`), or'ed with `SAP_RESPONSE`.
+    pub ssap: u8,
+
+    /// Control field, usually `LLC_UI`.
+    pub control: u8,
+}
+
+/// IEEE 802.2 LLC Header with SNAP Extension
+#[repr(C)]
+pub struct LlcSnap {
+    /// LLC header, with both service access points set to `SAP_SNAP`.
+    pub llc: Llc,
+
+    /// Organizationally unique identifier (`OUI_*`).
+    pub oui: [u8; 3],
+
+    /// Protocol ID, an EtherType for `OUI_ENCAPSULATED`.
+    pub protocol: int::u16be,
+}
+
+impl Mac {
+    /// Broadcast Address
+    pub const BROADCAST: Self = Self { raw: [0xff; 6] };
+
+    /// Create from raw value
+    pub fn from_raw(raw: [u8; 6]) -> Self {
+        Self { raw }
+    }
+
+    /// Return raw value
+    pub fn to_raw(self) -> [u8; 6] {
+        self.raw
+    }
+
+    /// Create from native value
+    ///
+    /// Create the address from the lower 48 bits of `native`, with the most
+    /// significant byte transmitted first.
+    pub fn from_native(native: u64) -> Self {
+        Self { raw: int::u48be::from_native(native).to_raw() }
+    }
+
+    /// Return native value
+    pub fn to_native(self) -> u64 {
+        int::u48be::from_raw(self.raw).to_native()
+    }
+
+    /// Return organizationally unique identifier
+    pub fn oui(self) -> [u8; 3] {
+        [self.raw[0], self.raw[1], self.raw[2]]
+    }
+
+    /// Check for the broadcast address
+    pub fn is_broadcast(self) -> bool {
+        self == Self::BROADCAST
+    }
+
+    /// Check for group addresses
+    ///
+    /// Return whether this is a multicast or broadcast address.
+    pub fn is_multicast(self) -> bool {
+        self.raw[0] & 0x01 != 0
+    }
+
+    /// Check for locally administered addresses
+    pub fn is_local(self) -> bool {
+        self.raw[0] & 0x02 != 0
+    }
+}
+
+impl Header {
+    /// Import a header from a byte slice
+    ///
+    /// Create a new header from data copied from a byte slice. No byte-order
+    /// conversions are applied.
+    pub fn from_bytes(data: &[u8; 14]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 14], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Return EtherType
+    ///
+    /// Return the EtherType of an Ethernet II frame, or `None` if this is an
+    /// IEEE 802.3 frame or the field is invalid.
+    pub fn ether_type(&self) -> Option<u16> {
+        let v = int::ForeignEndian::to_native(self.ether_type);
+        if v >= ETHERTYPE_MIN {
+            Some(v)
+        } else {
+            None
+        }
+    }
+
+    /// Return payload length
+    ///
+    /// Return the payload length of an IEEE 802.3 frame, or `None` if this is
+    /// an Ethernet II frame or the field is invalid.
+    pub fn length(&self) -> Option<u16> {
+        let v = int::ForeignEndian::to_native(self.ether_type);
+        if v <= LENGTH_MAX {
+            Some(v)
+        } else {
+            None
+        }
+    }
+}
+
+impl Llc {
+    /// Import an LLC header from a byte slice
+    ///
+    /// Create a new LLC header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 3]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 3], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Check for a SNAP extension
+    pub fn is_snap(&self) -> bool {
+        self.dsap == SAP_SNAP && self.ssap == SAP_SNAP
+    }
+}
+
+impl LlcSnap {
+    /// Import an LLC/SNAP header from a byte slice
+    ///
+    /// Create a new LLC/SNAP header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 8]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 8], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+/// Calculate Frame Check Sequence
+///
+/// Calculate the FCS of a frame, starting at the destination address and
+/// including any padding.
+pub fn fcs(frame: &[u8]) -> u32 {
+    checksum::crc32(frame)
+}
+
+/// Verify Frame Check Sequence
+///
+/// Verify the FCS at the end of `data`, which must contain the entire frame.
+/// Return `false` if the data is too short to carry an FCS.
+pub fn verify_fcs(data: &[u8]) -> bool {
+    if data.len() < FCS_SIZE {
+        return false;
+    }
+
+    let (frame, v) = data.split_at(data.len() - FCS_SIZE);
+    fcs(frame) == u32::from_le_bytes(v.try_into().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // An IEEE 802.3 frame header with LLC/SNAP, as used by CDP.
+    const SNAP: [u8; 22] = [
+        0x01, 0x00, 0x0c, 0xcc, 0xcc, 0xcc, 0x00, 0x1b,
+        0x21, 0x3c, 0x9d, 0xf0, 0x00, 0x20, 0xaa, 0xaa,
+        0x03, 0x00, 0x00, 0x0c, 0x20, 0x00,
+    ];
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<Mac>(), 6);
+        assert_eq!(align_of::<Mac>(), 1);
+        assert_eq!(size_of::<Header>(), HEADER_SIZE);
+        assert_eq!(align_of::<Header>(), 2);
+        assert_eq!(size_of::<Llc>(), 3);
+        assert_eq!(align_of::<Llc>(), 1);
+        assert_eq!(size_of::<LlcSnap>(), 8);
+        assert_eq!(align_of::<LlcSnap>(), 2);
+    }
+
+    // Parse an IEEE 802.3 header and distinguish it from Ethernet II.
+    #[test]
+    fn verify_header() {
+        let h = Header::from_bytes(SNAP[..14].try_into().unwrap());
+        assert!(h.destination.is_multicast());
+        assert!(!h.destination.is_broadcast());
+        assert_eq!(h.destination.oui(), [0x01, 0x00, 0x0c]);
+        assert_eq!(h.source.to_native(), 0x001b_213c_9df0);
+        assert!(!h.source.is_multicast());
+        assert!(!h.source.is_local());
+        assert!(h.ether_type().is_none());
+        assert_eq!(h.length(), Some(32));
+
+        let l = LlcSnap::from_bytes(SNAP[14..22].try_into().unwrap());
+        assert!(l.llc.is_snap());
+        assert_eq!(l.llc.control, LLC_UI);
+        assert_eq!(l.oui, OUI_CISCO);
+        assert_eq!(int::ForeignEndian::to_native(l.protocol), 0x2000);
+
+        let mut data = SNAP;
+        data[12..14].copy_from_slice(&ETHERTYPE_IPV6.to_be_bytes());
+        let h = Header::from_bytes(data[..14].try_into().unwrap());
+        assert_eq!(h.ether_type(), Some(ETHERTYPE_IPV6));
+        assert!(h.length().is_none());
+
+        data[12..14].copy_from_slice(&[0x05, 0xff]);
+        let h = Header::from_bytes(data[..14].try_into().unwrap());
+        assert!(h.ether_type().is_none());
+        assert!(h.length().is_none());
+    }
+
+    // Verify MAC conversions and the frame check sequence of a padded frame.
+    #[test]
+    fn verify_fcs_mac() {
+        assert_eq!(Mac::from_native(0xffff_ffff_ffff), Mac::BROADCAST);
+        assert_eq!(Mac::from_native(0x0200_0000_0001).to_raw(), [2, 0, 0, 0, 0, 1]);
+        assert!(Mac::from_raw([2, 0, 0, 0, 0, 1]).is_local());
+
+        let mut frame = [0; HEADER_SIZE + PAYLOAD_MIN + FCS_SIZE];
+        frame[..6].copy_from_slice(&Mac::BROADCAST.to_raw());
+        frame[6..12].copy_from_slice(&[0x02, 0x00, 0x00, 0x00, 0x00, 0x01]);
+        frame[12..14].copy_from_slice(&ETHERTYPE_ARP.to_be_bytes());
+
+        let v = fcs(&frame[..60]);
+        assert_eq!(v, 0x544ab5de);
+        frame[60..].copy_from_slice(&v.to_le_bytes());
+        assert!(verify_fcs(&frame));
+
+        frame[20] ^= 0x01;
+        assert!(!verify_fcs(&frame));
+        assert!(!verify_fcs(&frame[..3]));
+    }
+}
@@ -0,0 +1,299 @@
+//! Exchangeable Image File Format
+//!
+//! Exif stores camera and capture metadata in TIFF structures (see `tiff`).
+//! In JPEG files, the TIFF structure is embedded in an `APP1` segment after
+//! the `jpeg::EXIF_IDENTIFIER`, and all offsets are relative to the start of
+//! the TIFF header. Other containers, like PNG (`eXIf`) and WebP (`EXIF`),
+//! embed the TIFF structure directly.
+//!
+//! The first IFD (IFD0) describes the main image using the baseline TIFF
+//! tags. The Exif-specific tags live in separate IFDs, referenced from IFD0
+//! by pointer tags of type `LONG` (or `IFD`), whose value is the offset of
+//! the sub-IFD:
+//!
+//!  * `tiff::TAG_EXIF_IFD`: The Exif IFD with capture parameters (`TAG_*`).
+//!  * `tiff::TAG_GPS_IFD`: The GPS IFD with location data (`GPS_*`).
+//!  * `TAG_INTEROP_IFD`: Found in the Exif IFD, the interoperability IFD
+//!    (`INTEROP_*`).
+//!
+//! The second IFD (IFD1), if present, describes an embedded thumbnail,
+//! typically a JPEG referenced by `TAG_JPEG_INTERCHANGE_FORMAT`.
+//!
+//! Rational values are stored as pairs of 32-bit integers, numerator first.
+//! Like all integers, they follow the byte order of the TIFF header.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::{int, tiff};
+
+pub const TAG_JPEG_INTERCHANGE_FORMAT: u16 = 0x0201;
+pub const TAG_JPEG_INTERCHANGE_FORMAT_LENGTH: u16 = 0x0202;
+
+pub const TAG_EXPOSURE_TIME: u16 = 0x829a;
+pub const TAG_F_NUMBER: u16 = 0x829d;
+pub const TAG_EXPOSURE_PROGRAM: u16 = 0x8822;
+pub const TAG_SPECTRAL_SENSITIVITY: u16 = 0x8824;
+pub const TAG_PHOTOGRAPHIC_SENSITIVITY: u16 = 0x8827;
+pub const TAG_OECF: u16 = 0x8828;
+pub const TAG_SENSITIVITY_TYPE: u16 = 0x8830;
+pub const TAG_EXIF_VERSION: u16 = 0x9000;
+pub const TAG_DATE_TIME_ORIGINAL: u16 = 0x9003;
+pub const TAG_DATE_TIME_DIGITIZED: u16 = 0x9004;
+pub const TAG_OFFSET_TIME: u16 = 0x9010;
+pub const TAG_OFFSET_TIME_ORIGINAL: u16 = 0x9011;
+pub const TAG_OFFSET_TIME_DIGITIZED: u16 = 0x9012;
+pub const TAG_COMPONENTS_CONFIGURATION: u16 = 0x9101;
+pub const TAG_COMPRESSED_BITS_PER_PIXEL: u16 = 0x9102;
+pub const TAG_SHUTTER_SPEED_VALUE: u16 = 0x9201;
+pub const TAG_APERTURE_VALUE: u16 = 0x9202;
+pub const TAG_BRIGHTNESS_VALUE: u16 = 0x9203;
+pub const TAG_EXPOSURE_BIAS_VALUE: u16 = 0x9204;
+pub const TAG_MAX_APERTURE_VALUE: u16 = 0x9205;
+pub const TAG_SUBJECT_DISTANCE: u16 = 0x9206;
+pub const TAG_METERING_MODE: u16 = 0x9207;
+pub const TAG_LIGHT_SOURCE: u16 = 0x9208;
+pub const TAG_FLASH: u16 = 0x9209;
+pub const TAG_FOCAL_LENGTH: u16 = 0x920a;
+pub const TAG_SUBJECT_AREA: u16 = 0x9214;
+pub const TAG_MAKER_NOTE: u16 = 0x927c;
+pub const TAG_USER_COMMENT: u16 = 0x9286;
+pub const TAG_SUB_SEC_TIME: u16 = 0x9290;
+pub const TAG_SUB_SEC_TIME_ORIGINAL: u16 = 0x9291;
+pub const TAG_SUB_SEC_TIME_DIGITIZED: u16 = 0x9292;
+pub const TAG_FLASHPIX_VERSION: u16 = 0xa000;
+pub const TAG_COLOR_SPACE: u16 = 0xa001;
+pub const TAG_PIXEL_X_DIMENSION: u16 = 0xa002;
+pub const TAG_PIXEL_Y_DIMENSION: u16 = 0xa003;
+pub const TAG_RELATED_SOUND_FILE: u16 = 0xa004;
+pub const TAG_INTEROP_IFD: u16 = 0xa005;
+pub const TAG_FLASH_ENERGY: u16 = 0xa20b;
+pub const TAG_FOCAL_PLANE_X_RESOLUTION: u16 = 0xa20e;
+pub const TAG_FOCAL_PLANE_Y_RESOLUTION: u16 = 0xa20f;
+pub const TAG_FOCAL_PLANE_RESOLUTION_UNIT: u16 = 0xa210;
+pub const TAG_SUBJECT_LOCATION: u16 = 0xa214;
+pub const TAG_EXPOSURE_INDEX: u16 = 0xa215;
+pub const TAG_SENSING_METHOD: u16 = 0xa217;
+pub const TAG_FILE_SOURCE: u16 = 0xa300;
+pub const TAG_SCENE_TYPE: u16 = 0xa301;
+pub const TAG_CFA_PATTERN: u16 = 0xa302;
+pub const TAG_CUSTOM_RENDERED: u16 = 0xa401;
+pub const TAG_EXPOSURE_MODE: u16 = 0xa402;
+pub const TAG_WHITE_BALANCE: u16 = 0xa403;
+pub const TAG_DIGITAL_ZOOM_RATIO: u16 = 0xa404;
+pub const TAG_FOCAL_LENGTH_IN_35MM_FILM: u16 = 0xa405;
+pub const TAG_SCENE_CAPTURE_TYPE: u16 = 0xa406;
+pub const TAG_GAIN_CONTROL: u16 = 0xa407;
+pub const TAG_CONTRAST: u16 = 0xa408;
+pub const TAG_SATURATION: u16 = 0xa409;
+pub const TAG_SHARPNESS: u16 = 0xa40a;
+pub const TAG_SUBJECT_DISTANCE_RANGE: u16 = 0xa40c;
+pub const TAG_IMAGE_UNIQUE_ID: u16 = 0xa420;
+pub const TAG_CAMERA_OWNER_NAME: u16 = 0xa430;
+pub const TAG_BODY_SERIAL_NUMBER: u16 = 0xa431;
+pub const TAG_LENS_SPECIFICATION: u16 = 0xa432;
+pub const TAG_LENS_MAKE: u16 = 0xa433;
+pub const TAG_LENS_MODEL: u16 = 0xa434;
+pub const TAG_LENS_SERIAL_NUMBER: u16 = 0xa435;
+
+pub const GPS_VERSION_ID: u16 = 0x0000;
+pub const GPS_LATITUDE_REF: u16 = 0x0001;
+pub const GPS_LATITUDE: u16 = 0x0002;
+pub const GPS_LONGITUDE_REF: u16 = 0x0003;
+pub const GPS_LONGITUDE: u16 = 0x0004;
+pub const GPS_ALTITUDE_REF: u16 = 0x0005;
+pub const GPS_ALTITUDE: u16 = 0x0006;
+pub const GPS_TIME_STAMP: u16 = 0x0007;
+pub const GPS_SATELLITES: u16 = 0x0008;
+pub const GPS_STATUS: u16 = 0x0009;
+pub const GPS_MEASURE_MODE: u16 = 0x000a;
+pub const GPS_DOP: u16 = 0x000b;
+pub const GPS_SPEED_REF: u16 = 0x000c;
+pub const GPS_SPEED: u16 = 0x000d;
+pub const GPS_TRACK_REF: u16 = 0x000e;
+pub const GPS_TRACK: u16 = 0x000f;
+pub const GPS_IMG_DIRECTION_REF: u16 = 0x0010;
+pub const GPS_IMG_DIRECTION: u16 = 0x0011;
+pub const GPS_MAP_DATUM: u16 = 0x0012;
+pub const GPS_DEST_LATITUDE_REF: u16 = 0x0013;
+pub const GPS_DEST_LATITUDE: u16 = 0x0014;
+pub const GPS_DEST_LONGITUDE_REF: u16 = 0x0015;
+pub const GPS_DEST_LONGITUDE: u16 = 0x0016;
+pub const GPS_DEST_BEARING_REF: u16 = 0x0017;
+pub const GPS_DEST_BEARING: u16 = 0x0018;
+pub const GPS_DEST_DISTANCE_REF: u16 = 0x0019;
+pub const GPS_DEST_DISTANCE: u16 = 0x001a;
+pub const GPS_PROCESSING_METHOD: u16 = 0x001b;
+pub const GPS_AREA_INFORMATION: u16 = 0x001c;
+pub const GPS_DATE_STAMP: u16 = 0x001d;
+pub const GPS_DIFFERENTIAL: u16 = 0x001e;
+pub const GPS_H_POSITIONING_ERROR: u16 = 0x001f;
+
+pub const INTEROP_INDEX: u16 = 0x0001;
+pub const INTEROP_VERSION: u16 = 0x0002;
+
+pub const COLOR_SPACE_SRGB: u16 = 1;
+pub const COLOR_SPACE_UNCALIBRATED: u16 = 0xffff;
+
+/// Unsigned Rational
+///
+/// The representation of `tiff::TYPE_RATIONAL` values.
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct Rational {
+    pub numerator: int::u32re,
+    pub denominator: int::u32re,
+}
+
+/// Signed Rational
+///
+/// The representation of `tiff::TYPE_SRATIONAL` values.
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+pub struct SRational {
+    pub numerator: int::i32re,
+    pub denominator: int::i32re,
+}
+
+impl Rational {
+    /// Import a rational from a byte slice
+    ///
+    /// Create a new rational from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 8]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 8], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Create rational from native values
+    pub fn from_native(numerator: u32, denominator: u32, endian: int::Endianness) -> Self {
+        Self {
+            numerator: int::u32re::from_native(numerator, endian),
+            denominator: int::u32re::from_native(denominator, endian),
+        }
+    }
+
+    /// Return native values
+    ///
+    /// Return the rational as `(numerator, denominator)`.
+    pub fn to_native(self, endian: int::Endianness) -> (u32, u32) {
+        (self.numerator.to_native(endian), self.denominator.to_native(endian))
+    }
+}
+
+impl SRational {
+    /// Import a rational from a byte slice
+    ///
+    /// Create a new rational from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 8]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 8], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Create rational from native values
+    pub fn from_native(numerator: i32, denominator: i32, endian: int::Endianness) -> Self {
+        Self {
+            numerator: int::i32re::from_native(numerator, endian),
+            denominator: int::i32re::from_native(denominator, endian),
+        }
+    }
+
+    /// Return native values
+    ///
+    /// Return the rational as `(numerator, denominator)`.
+    pub fn to_native(self, endian: int::Endianness) -> (i32, i32) {
+        (self.numerator.to_native(endian), self.denominator.to_native(endian))
+    }
+}
+
+/// Check for IFD Pointer Tags
+///
+/// Return whether the tag of an IFD entry references a sub-IFD defined by
+/// Exif.
+pub fn is_ifd_pointer(tag: u16) -> bool {
+    matches!(tag, tiff::TAG_EXIF_IFD | tiff::TAG_GPS_IFD | TAG_INTEROP_IFD)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::specs::jpeg;
+
+    // The payload of an Exif `APP1` segment with an IFD0 pointing to an Exif
+    // IFD, which carries an exposure time of 1/250s.
+    const APP1: [u8; 58] = [
+        0x45, 0x78, 0x69, 0x66, 0x00, 0x00, 0x49, 0x49,
+        0x2a, 0x00, 0x08, 0x00, 0x00, 0x00, 0x01, 0x00,
+        0x69, 0x87, 0x04, 0x00, 0x01, 0x00, 0x00, 0x00,
+        0x1a, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x01, 0x00, 0x9a, 0x82, 0x05, 0x00, 0x01, 0x00,
+        0x00, 0x00, 0x2c, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0xfa, 0x00,
+        0x00, 0x00,
+    ];
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<Rational>(), 8);
+        assert_eq!(align_of::<Rational>(), 4);
+        assert_eq!(size_of::<SRational>(), 8);
+    }
+
+    // Follow the Exif IFD pointer and decode a rational.
+    #[test]
+    fn verify_exif() {
+        assert_eq!(APP1[..6], jpeg::EXIF_IDENTIFIER);
+        let t = &APP1[6..];
+
+        let h = tiff::Header::from_bytes(t[..8].try_into().unwrap());
+        let e = h.endianness().unwrap();
+        let ifd0 = h.ifd_offset.to_native(e) as usize;
+
+        let p = tiff::IfdEntry::from_bytes(t[ifd0 + 2..ifd0 + 14].try_into().unwrap());
+        assert!(is_ifd_pointer(p.tag.to_native(e)));
+        assert_eq!(p.field_type.to_native(e), tiff::TYPE_LONG);
+        let sub = int::u32re::from_raw(u32::from_ne_bytes(p.value)).to_native(e) as usize;
+
+        let x = tiff::IfdEntry::from_bytes(t[sub + 2..sub + 14].try_into().unwrap());
+        assert_eq!(x.tag.to_native(e), TAG_EXPOSURE_TIME);
+        let off = x.value_offset(e).unwrap() as usize;
+
+        let r = Rational::from_bytes(t[off..off + 8].try_into().unwrap());
+        assert_eq!(r.to_native(e), (1, 250));
+    }
+
+    // Verify rationals in both byte orders.
+    #[test]
+    fn verify_rational() {
+        let r = SRational::from_native(-1, 3, int::Endianness::Big);
+        assert_eq!(r.to_native(int::Endianness::Big), (-1, 3));
+
+        let r = SRational::from_bytes(&[0xff, 0xff, 0xff, 0xfe, 0x00, 0x00, 0x00, 0x03]);
+        assert_eq!(r.to_native(int::Endianness::Big), (-2, 3));
+
+        let r = Rational::from_native(72, 1, int::Endianness::Little);
+        assert_eq!(r.to_native(int::Endianness::Little), (72, 1));
+        assert_eq!(r.to_native(int::Endianness::Big), (72 << 24, 1 << 24));
+
+        assert!(!is_ifd_pointer(tiff::TAG_IMAGE_WIDTH));
+    }
+}
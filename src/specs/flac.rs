@@ -0,0 +1,516 @@
+//! Free Lossless Audio Codec
+//!
+//! A native FLAC stream starts with the `MAGIC` marker, followed by a
+//! sequence of metadata blocks and then the audio frames. Each metadata
+//! block starts with a 4-byte `BlockHeader`, carrying the block type, a flag
+//! marking the last metadata block, and the 24-bit size of the block
+//! payload. The first block must be `BLOCK_STREAMINFO`.
+//!
+//! `StreamInfo` packs the sample rate, channel count, bits per sample, and
+//! the 36-bit total sample count into a single 64-bit field, for which
+//! accessors are provided. Vorbis comments reuse the format of the Vorbis
+//! codec, hence unlike everything else in FLAC, their integers are encoded
+//! as little-endian.
+//!
+//! Unless noted otherwise, all multi-byte integers are encoded as
+//! big-endian.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::int;
+
+/// Stream Marker
+///
+/// The initial 4 bytes of every native FLAC stream ("fLaC").
+pub const MAGIC: [u8; 4] = [0x66, 0x4c, 0x61, 0x43];
+
+/// Frame Sync Code
+///
+/// The leading 14 bits of every audio frame header, left-aligned in 16 bits.
+/// The following bit is reserved, the last one selects variable block sizes.
+pub const FRAME_SYNC: u16 = 0xfff8;
+
+pub const BLOCK_STREAMINFO: u8 = 0;
+pub const BLOCK_PADDING: u8 = 1;
+pub const BLOCK_APPLICATION: u8 = 2;
+pub const BLOCK_SEEKTABLE: u8 = 3;
+pub const BLOCK_VORBIS_COMMENT: u8 = 4;
+pub const BLOCK_CUESHEET: u8 = 5;
+pub const BLOCK_PICTURE: u8 = 6;
+pub const BLOCK_INVALID: u8 = 127;
+
+pub const BLOCK_LAST: u8 = 0x80;
+pub const BLOCK_TYPE_MASK: u8 = 0x7f;
+
+/// Size of the StreamInfo Block
+pub const STREAMINFO_SIZE: usize = 34;
+
+/// Placeholder Seek Point
+///
+/// The sample number of unused seek points.
+pub const SEEKPOINT_PLACEHOLDER: u64 = 0xffff_ffff_ffff_ffff;
+
+pub const PICTURE_OTHER: u32 = 0;
+pub const PICTURE_FILE_ICON: u32 = 1;
+pub const PICTURE_OTHER_FILE_ICON: u32 = 2;
+pub const PICTURE_FRONT_COVER: u32 = 3;
+pub const PICTURE_BACK_COVER: u32 = 4;
+pub const PICTURE_LEAFLET: u32 = 5;
+pub const PICTURE_MEDIA: u32 = 6;
+pub const PICTURE_LEAD_ARTIST: u32 = 7;
+pub const PICTURE_ARTIST: u32 = 8;
+pub const PICTURE_CONDUCTOR: u32 = 9;
+pub const PICTURE_BAND: u32 = 10;
+pub const PICTURE_COMPOSER: u32 = 11;
+pub const PICTURE_LYRICIST: u32 = 12;
+pub const PICTURE_RECORDING_LOCATION: u32 = 13;
+pub const PICTURE_DURING_RECORDING: u32 = 14;
+pub const PICTURE_DURING_PERFORMANCE: u32 = 15;
+pub const PICTURE_SCREEN_CAPTURE: u32 = 16;
+pub const PICTURE_BRIGHT_FISH: u32 = 17;
+pub const PICTURE_ILLUSTRATION: u32 = 18;
+pub const PICTURE_BAND_LOGO: u32 = 19;
+pub const PICTURE_PUBLISHER_LOGO: u32 = 20;
+
+/// Metadata Block Header
+#[repr(C)]
+pub struct BlockHeader {
+    /// Block type (`BLOCK_*`) in the low 7 bits, and `BLOCK_LAST` if this
+    /// is the last metadata block.
+    pub flags: u8,
+
+    /// Size of the block payload in bytes.
+    pub length: int::u24be,
+}
+
+/// Stream Information
+///
+/// The payload of a `BLOCK_STREAMINFO` block. Since the packed fields are
+/// not naturally aligned, this structure is packed.
+#[repr(C, packed)]
+pub struct StreamInfo {
+    /// Minimum block size in samples.
+    pub min_block_size: int::u16be,
+
+    /// Maximum block size in samples.
+    pub max_block_size: int::u16be,
+
+    /// Minimum frame size in bytes, or 0 if unknown.
+    pub min_frame_size: int::u24be,
+
+    /// Maximum frame size in bytes, or 0 if unknown.
+    pub max_frame_size: int::u24be,
+
+    /// Sample rate (20 bits), channels minus one (3 bits), bits per sample
+    /// minus one (5 bits), and total samples (36 bits), starting at the most
+    /// significant bit.
+    pub packed: int::u64be,
+
+    /// MD5 of the unencoded audio data.
+    pub md5: [u8; 16],
+}
+
+/// Seek Point
+///
+/// An entry of a `BLOCK_SEEKTABLE` block. Since it is 18 bytes in size,
+/// entries are not naturally aligned and the structure is packed.
+#[repr(C, packed)]
+pub struct SeekPoint {
+    /// Sample number of the first sample of the target frame, or
+    /// `SEEKPOINT_PLACEHOLDER`.
+    pub sample: int::u64be,
+
+    /// Offset of the target frame from the first frame.
+    pub offset: int::u64be,
+
+    /// Number of samples in the target frame.
+    pub samples: int::u16be,
+}
+
+/// Picture Reference
+///
+/// The decoded payload of a `BLOCK_PICTURE` block.
+#[derive(Clone, Copy, Debug)]
+pub struct Picture<'data> {
+    /// Picture type (`PICTURE_*`).
+    pub picture_type: u32,
+
+    /// MIME type in ASCII.
+    pub mime: &'data [u8],
+
+    /// Description in UTF-8.
+    pub description: &'data [u8],
+
+    /// Width in pixels.
+    pub width: u32,
+
+    /// Height in pixels.
+    pub height: u32,
+
+    /// Color depth in bits per pixel.
+    pub depth: u32,
+
+    /// Number of palette colors, or 0.
+    pub colors: u32,
+
+    /// Picture data.
+    pub data: &'data [u8],
+}
+
+/// Metadata Block Reference
+///
+/// A reference to a metadata block in a byte slice, as yielded by `Blocks`.
+#[derive(Clone, Copy, Debug)]
+pub struct Block<'data> {
+    /// Block type (`BLOCK_*`).
+    pub block_type: u8,
+
+    /// Whether this is the last metadata block.
+    pub last: bool,
+
+    /// Block payload.
+    pub data: &'data [u8],
+}
+
+/// Metadata Block Iterator
+///
+/// An iterator over the metadata blocks of a stream. It must be created on
+/// the data following the stream marker. Iteration stops after the last
+/// block, or at the first truncated block. Use `Blocks::remainder()` to get
+/// the data following the yielded blocks, which are the audio frames after
+/// the last block.
+#[derive(Clone, Debug)]
+pub struct Blocks<'data> {
+    data: &'data [u8],
+    done: bool,
+}
+
+/// Vorbis Comment Iterator
+///
+/// An iterator over the comments of a Vorbis comment block, yielding each
+/// `NAME=value` comment as raw UTF-8.
+#[derive(Clone, Debug)]
+pub struct Comments<'data> {
+    data: &'data [u8],
+    count: u32,
+}
+
+impl BlockHeader {
+    /// Import a block header from a byte slice
+    ///
+    /// Create a new block header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 4]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 4], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Return block type
+    pub fn block_type(&self) -> u8 {
+        self.flags & BLOCK_TYPE_MASK
+    }
+
+    /// Check for the last metadata block
+    pub fn is_last(&self) -> bool {
+        self.flags & BLOCK_LAST != 0
+    }
+}
+
+impl StreamInfo {
+    /// Import stream information from a byte slice
+    ///
+    /// Create a new stream information structure from data copied from a
+    /// byte slice. No byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 34]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 34], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Return sample rate in Hz
+    pub fn sample_rate(&self) -> u32 {
+        (int::ForeignEndian::to_native(self.packed) >> 44) as u32
+    }
+
+    /// Return number of channels
+    pub fn channels(&self) -> u8 {
+        ((int::ForeignEndian::to_native(self.packed) >> 41) & 0x7) as u8 + 1
+    }
+
+    /// Return bits per sample
+    pub fn bits_per_sample(&self) -> u8 {
+        ((int::ForeignEndian::to_native(self.packed) >> 36) & 0x1f) as u8 + 1
+    }
+
+    /// Return total number of samples
+    ///
+    /// Return the number of samples per channel in the stream, or 0 if
+    /// unknown.
+    pub fn total_samples(&self) -> u64 {
+        int::ForeignEndian::to_native(self.packed) & 0xf_ffff_ffff
+    }
+}
+
+impl SeekPoint {
+    /// Import a seek point from a byte slice
+    ///
+    /// Create a new seek point from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 18]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 18], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Check for placeholders
+    pub fn is_placeholder(&self) -> bool {
+        int::ForeignEndian::to_native(self.sample) == SEEKPOINT_PLACEHOLDER
+    }
+}
+
+// Read a big-endian 32-bit value at the start of `data` and advance it.
+fn read_u32be(data: &mut &[u8]) -> Option<u32> {
+    let v = u32::from_be_bytes(data.get(..4)?.try_into().unwrap());
+    *data = &data[4..];
+    Some(v)
+}
+
+// Read a little-endian 32-bit value at the start of `data` and advance it.
+fn read_u32le(data: &mut &[u8]) -> Option<u32> {
+    let v = u32::from_le_bytes(data.get(..4)?.try_into().unwrap());
+    *data = &data[4..];
+    Some(v)
+}
+
+// Read a slice of `n` bytes at the start of `data` and advance it.
+fn read_slice<'data>(data: &mut &'data [u8], n: u32) -> Option<&'data [u8]> {
+    let v = data.get(..n as usize)?;
+    *data = &data[n as usize..];
+    Some(v)
+}
+
+impl<'data> Picture<'data> {
+    /// Parse a picture block
+    ///
+    /// Decode the payload of a `BLOCK_PICTURE` block. `None` is returned if
+    /// the payload is truncated.
+    pub fn parse(mut data: &'data [u8]) -> Option<Self> {
+        let picture_type = read_u32be(&mut data)?;
+        let n = read_u32be(&mut data)?;
+        let mime = read_slice(&mut data, n)?;
+        let n = read_u32be(&mut data)?;
+        let description = read_slice(&mut data, n)?;
+        let width = read_u32be(&mut data)?;
+        let height = read_u32be(&mut data)?;
+        let depth = read_u32be(&mut data)?;
+        let colors = read_u32be(&mut data)?;
+        let n = read_u32be(&mut data)?;
+        let data = read_slice(&mut data, n)?;
+
+        Some(Self {
+            picture_type,
+            mime,
+            description,
+            width,
+            height,
+            depth,
+            colors,
+            data,
+        })
+    }
+}
+
+impl<'data> Blocks<'data> {
+    /// Create metadata block iterator
+    ///
+    /// Create a new iterator over the metadata blocks in `data`, which must
+    /// start directly after the stream marker.
+    pub fn new(data: &'data [u8]) -> Self {
+        Self {
+            data,
+            done: false,
+        }
+    }
+
+    /// Return remaining data
+    ///
+    /// Return the data following the last block yielded so far.
+    pub fn remainder(&self) -> &'data [u8] {
+        self.data
+    }
+}
+
+impl<'data> Iterator for Blocks<'data> {
+    type Item = Block<'data>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done || self.data.len() < 4 {
+            return None;
+        }
+
+        let h = BlockHeader::from_bytes(self.data[..4].try_into().unwrap());
+        let length = h.length.to_native() as usize;
+        if self.data.len() - 4 < length {
+            return None;
+        }
+
+        let block = Block {
+            block_type: h.block_type(),
+            last: h.is_last(),
+            data: &self.data[4..4 + length],
+        };
+
+        self.done = block.last;
+        self.data = &self.data[4 + length..];
+        Some(block)
+    }
+}
+
+impl<'data> Comments<'data> {
+    /// Parse a Vorbis comment block
+    ///
+    /// Decode the header of a Vorbis comment block and return the vendor
+    /// string and an iterator over the comments. `None` is returned if the
+    /// header is truncated.
+    pub fn parse(mut data: &'data [u8]) -> Option<(&'data [u8], Self)> {
+        let n = read_u32le(&mut data)?;
+        let vendor = read_slice(&mut data, n)?;
+        let count = read_u32le(&mut data)?;
+
+        Some((vendor, Self { data, count }))
+    }
+}
+
+impl<'data> Iterator for Comments<'data> {
+    type Item = &'data [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.count == 0 {
+            return None;
+        }
+
+        let mut data = self.data;
+        let n = read_u32le(&mut data)?;
+        let v = read_slice(&mut data, n)?;
+
+        self.data = data;
+        self.count -= 1;
+        Some(v)
+    }
+}
+
+/// Split a Vorbis Comment
+///
+/// Split a comment into its name and value at the first `=`. Names are
+/// case-insensitive ASCII. `None` is returned if there is no `=`.
+pub fn split_comment(comment: &[u8]) -> Option<(&[u8], &[u8])> {
+    let i = comment.iter().position(|v| *v == b'=')?;
+    Some((&comment[..i], &comment[i + 1..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A stream with STREAMINFO for 44.1kHz 16-bit stereo audio of 1234567
+    // samples, and a Vorbis comment block with a title.
+    const STREAM: [u8; 71] = [
+        0x66, 0x4c, 0x61, 0x43, 0x00, 0x00, 0x00, 0x22,
+        0x10, 0x00, 0x10, 0x00, 0x00, 0x00, 0x10, 0x00,
+        0x20, 0x00, 0x0a, 0xc4, 0x42, 0xf0, 0x00, 0x12,
+        0xd6, 0x87, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x84, 0x00, 0x00, 0x19, 0x04, 0x00,
+        0x00, 0x00, 0x74, 0x65, 0x73, 0x74, 0x01, 0x00,
+        0x00, 0x00, 0x09, 0x00, 0x00, 0x00, 0x54, 0x49,
+        0x54, 0x4c, 0x45, 0x3d, 0x61, 0x62, 0x63,
+    ];
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<BlockHeader>(), 4);
+        assert_eq!(size_of::<StreamInfo>(), STREAMINFO_SIZE);
+        assert_eq!(align_of::<StreamInfo>(), 1);
+        assert_eq!(size_of::<SeekPoint>(), 18);
+        assert_eq!(align_of::<SeekPoint>(), 1);
+    }
+
+    // Walk the metadata blocks of a stream.
+    #[test]
+    fn verify_blocks() {
+        assert_eq!(STREAM[..4], MAGIC);
+        let mut blocks = Blocks::new(&STREAM[4..]);
+
+        let b = blocks.next().unwrap();
+        assert_eq!(b.block_type, BLOCK_STREAMINFO);
+        assert!(!b.last);
+        let s = StreamInfo::from_bytes(b.data.try_into().unwrap());
+        assert_eq!(int::ForeignEndian::to_native(s.min_block_size), 4096);
+        assert_eq!(s.min_frame_size.to_native(), 0x10);
+        assert_eq!(s.max_frame_size.to_native(), 0x2000);
+        assert_eq!(s.sample_rate(), 44100);
+        assert_eq!(s.channels(), 2);
+        assert_eq!(s.bits_per_sample(), 16);
+        assert_eq!(s.total_samples(), 1234567);
+
+        let b = blocks.next().unwrap();
+        assert_eq!(b.block_type, BLOCK_VORBIS_COMMENT);
+        assert!(b.last);
+        let (vendor, mut comments) = Comments::parse(b.data).unwrap();
+        assert_eq!(vendor, b"test");
+        let c = comments.next().unwrap();
+        assert_eq!(split_comment(c), Some((&b"TITLE"[..], &b"abc"[..])));
+        assert!(comments.next().is_none());
+
+        assert!(blocks.next().is_none());
+        assert!(blocks.remainder().is_empty());
+    }
+
+    // Decode a picture block and a seek point.
+    #[test]
+    fn verify_picture() {
+        let mut data = [0u8; 45];
+        data[0..4].copy_from_slice(&PICTURE_FRONT_COVER.to_be_bytes());
+        data[4..8].copy_from_slice(&9u32.to_be_bytes());
+        data[8..17].copy_from_slice(b"image/png");
+        data[21..25].copy_from_slice(&300u32.to_be_bytes());
+        data[25..29].copy_from_slice(&200u32.to_be_bytes());
+        data[29..33].copy_from_slice(&24u32.to_be_bytes());
+        data[37..41].copy_from_slice(&4u32.to_be_bytes());
+
+        let p = Picture::parse(&data).unwrap();
+        assert_eq!(p.picture_type, PICTURE_FRONT_COVER);
+        assert_eq!(p.mime, b"image/png");
+        assert!(p.description.is_empty());
+        assert_eq!((p.width, p.height, p.depth, p.colors), (300, 200, 24, 0));
+        assert_eq!(p.data.len(), 4);
+        assert!(Picture::parse(&data[..44]).is_none());
+
+        let s = SeekPoint::from_bytes(&[0xff; 18]);
+        assert!(s.is_placeholder());
+    }
+}
@@ -0,0 +1,275 @@
+//! Generic Network Virtualization Encapsulation
+//!
+//! Geneve tunnels packets over UDP (`udp::PORT_GENEVE`). Each datagram
+//! carries an 8-byte `Header` with a 24-bit virtual network identifier (VNI)
+//! and the EtherType of the payload, followed by variable-length options
+//! (see `Options`), followed by the payload. Ethernet payloads use
+//! `ethernet::ETHERTYPE_TRANSPARENT_BRIDGING`.
+//!
+//! Options are TLVs with a 16-bit class, which defines the namespace of the
+//! 8-bit type, and a 5-bit length in 4-byte units. The top bit of the type
+//! marks critical options, which tunnel endpoints must drop the packet for
+//! if they do not understand them.
+//!
+//! All multi-byte integers are encoded as big-endian.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::int;
+
+pub const HEADER_SIZE: usize = 8;
+pub const OPTION_HEADER_SIZE: usize = 4;
+pub const VERSION: u8 = 0;
+
+/// Maximum Size of All Options
+pub const OPTIONS_MAX: usize = 252;
+
+/// Maximum Size of Single Option Data
+pub const OPTION_DATA_MAX: usize = 124;
+
+pub const VERSION_MASK: u8 = 0xc0;
+pub const OPTIONS_LENGTH_MASK: u8 = 0x3f;
+
+pub const FLAG_O: u8 = 0x80;
+pub const FLAG_C: u8 = 0x40;
+
+pub const OPTION_TYPE_CRITICAL: u8 = 0x80;
+pub const OPTION_LENGTH_MASK: u8 = 0x1f;
+
+/* reserved: 0x0000..0x00ff (IETF standards) */
+pub const CLASS_LINUX: u16 = 0x0100;
+pub const CLASS_OPEN_VSWITCH: u16 = 0x0101;
+pub const CLASS_OVN: u16 = 0x0102;
+pub const CLASS_INT: u16 = 0x0103;
+pub const CLASS_VMWARE: u16 = 0x0104;
+pub const CLASS_EXPERIMENTAL: u16 = 0xfff0;
+
+/// Geneve Header
+#[repr(C)]
+pub struct Header {
+    /// Version (2 bits) and size of the options in 4-byte units (6 bits).
+    pub version_length: u8,
+
+    /// Flags (`FLAG_*`).
+    pub flags: u8,
+
+    /// EtherType of the payload (`ethernet::ETHERTYPE_*`).
+    pub protocol_type: int::u16be,
+
+    /// Virtual network identifier.
+    pub vni: int::u24be,
+
+    /// Reserved, must be 0.
+    pub reserved: u8,
+}
+
+/// Option Header
+#[repr(C)]
+pub struct OptionHeader {
+    /// Option class (`CLASS_*`).
+    pub option_class: int::u16be,
+
+    /// Option type, with `OPTION_TYPE_CRITICAL` marking critical options.
+    pub option_type: u8,
+
+    /// Reserved bits (3 bits) and size of the option data in 4-byte units
+    /// (5 bits), excluding the header.
+    pub length: u8,
+}
+
+/// Option Reference
+#[derive(Clone, Copy, Debug)]
+pub struct GeneveOption<'data> {
+    /// Option class.
+    pub option_class: u16,
+
+    /// Option type, including the critical bit.
+    pub option_type: u8,
+
+    /// Option data, excluding the header.
+    pub data: &'data [u8],
+}
+
+/// Option Iterator
+///
+/// An iterator over the options of a Geneve header. Iteration stops at the
+/// end of the data, or at the first truncated option.
+#[derive(Clone, Debug)]
+pub struct Options<'data> {
+    data: &'data [u8],
+}
+
+impl Header {
+    /// Import a header from a byte slice
+    ///
+    /// Create a new header from data copied from a byte slice. No byte-order
+    /// conversions are applied.
+    pub fn from_bytes(data: &[u8; 8]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 8], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Return version
+    pub fn version(&self) -> u8 {
+        self.version_length >> 6
+    }
+
+    /// Return size of options
+    ///
+    /// Return the size of the options following the header in bytes.
+    pub fn options_size(&self) -> usize {
+        (self.version_length & OPTIONS_LENGTH_MASK) as usize * 4
+    }
+
+    /// Return VNI
+    pub fn vni(&self) -> u32 {
+        self.vni.to_native()
+    }
+
+    /// Check for OAM packets
+    pub fn is_oam(&self) -> bool {
+        self.flags & FLAG_O != 0
+    }
+
+    /// Check for critical options
+    ///
+    /// Return whether the sender marked the packet as carrying critical
+    /// options.
+    pub fn has_critical(&self) -> bool {
+        self.flags & FLAG_C != 0
+    }
+}
+
+impl OptionHeader {
+    /// Import an option header from a byte slice
+    ///
+    /// Create a new option header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 4]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 4], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl<'data> GeneveOption<'data> {
+    /// Check for critical options
+    pub fn is_critical(&self) -> bool {
+        self.option_type & OPTION_TYPE_CRITICAL != 0
+    }
+}
+
+impl<'data> Options<'data> {
+    /// Create option iterator
+    ///
+    /// Create a new iterator over the options in `data`, which should be
+    /// limited to the options size given in the header.
+    pub fn new(data: &'data [u8]) -> Self {
+        Self {
+            data,
+        }
+    }
+
+    /// Return remaining data
+    ///
+    /// Return the data that has not been consumed by the iterator.
+    pub fn remainder(&self) -> &'data [u8] {
+        self.data
+    }
+}
+
+impl<'data> Iterator for Options<'data> {
+    type Item = GeneveOption<'data>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let header = self.data.get(..OPTION_HEADER_SIZE)?;
+        let n = (header[3] & OPTION_LENGTH_MASK) as usize * 4;
+        let data = self.data.get(OPTION_HEADER_SIZE..OPTION_HEADER_SIZE + n)?;
+
+        self.data = &self.data[OPTION_HEADER_SIZE + n..];
+        Some(GeneveOption {
+            option_class: u16::from_be_bytes([header[0], header[1]]),
+            option_type: header[2],
+            data,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A header with 12 bytes of options, one critical, carrying Ethernet.
+    const HEADER: [u8; 20] = [
+        0x03, 0x40, 0x65, 0x58, 0x00, 0x04, 0xd2, 0x00,
+        0x01, 0x02, 0x80, 0x01, 0x00, 0x00, 0x00, 0x05,
+        0x01, 0x01, 0x01, 0x00,
+    ];
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<Header>(), HEADER_SIZE);
+        assert_eq!(align_of::<Header>(), 2);
+        assert_eq!(size_of::<OptionHeader>(), OPTION_HEADER_SIZE);
+        assert_eq!(align_of::<OptionHeader>(), 2);
+    }
+
+    // Parse the base header.
+    #[test]
+    fn verify_header() {
+        let h = Header::from_bytes(HEADER[..8].try_into().unwrap());
+        assert_eq!(h.version(), VERSION);
+        assert_eq!(h.options_size(), 12);
+        assert_eq!(int::ForeignEndian::to_native(h.protocol_type), 0x6558);
+        assert_eq!(h.vni(), 1234);
+        assert!(!h.is_oam());
+        assert!(h.has_critical());
+
+        let h = Header::from_bytes(&[0x7f, 0x80, 0x08, 0x00, 0xff, 0xff, 0xff, 0x00]);
+        assert_eq!(h.version(), 1);
+        assert_eq!(h.options_size(), OPTIONS_MAX);
+        assert_eq!(h.vni(), 0xffffff);
+        assert!(h.is_oam());
+    }
+
+    // Walk the options following the header.
+    #[test]
+    fn verify_options() {
+        let mut opts = Options::new(&HEADER[8..]);
+        let o = opts.next().unwrap();
+        assert_eq!(o.option_class, CLASS_OVN);
+        assert_eq!(o.option_type, 0x80);
+        assert!(o.is_critical());
+        assert_eq!(o.data, &[0x00, 0x00, 0x00, 0x05]);
+
+        let o = opts.next().unwrap();
+        assert_eq!(o.option_class, CLASS_OPEN_VSWITCH);
+        assert!(!o.is_critical());
+        assert!(o.data.is_empty());
+        assert!(opts.next().is_none());
+        assert!(opts.remainder().is_empty());
+
+        let h = OptionHeader::from_bytes(HEADER[8..12].try_into().unwrap());
+        assert_eq!(h.length & OPTION_LENGTH_MASK, 1);
+        assert!(Options::new(&HEADER[8..15]).next().is_none());
+        assert_eq!(OPTION_LENGTH_MASK as usize * 4, OPTION_DATA_MAX);
+    }
+}
@@ -0,0 +1,450 @@
+//! Graphics Interchange Format
+//!
+//! The Graphics Interchange Format (GIF) is a palette-based raster image
+//! format with LZW compression. It exists in two versions: GIF87a, and
+//! GIF89a, which adds extension blocks (used for animation, transparency, and
+//! comments).
+//!
+//! A GIF file starts with a 6-byte `Header`, followed by the
+//! `LogicalScreenDescriptor` and an optional global color table. Then
+//! follows a sequence of blocks, each identified by its first byte:
+//!
+//!  * `IMAGE_SEPARATOR`: An `ImageDescriptor`, optionally followed by a local
+//!    color table, the LZW minimum code size, and the image data as a
+//!    sequence of sub-blocks.
+//!  * `EXTENSION_INTRODUCER`: An extension, identified by its label byte,
+//!    followed by a sequence of sub-blocks.
+//!  * `TRAILER`: The end of the file.
+//!
+//! Data of variable length is split into sub-blocks. Each sub-block starts
+//! with its size in bytes (1 to 255), followed by the data. A sub-block of
+//! size 0 terminates the sequence.
+//!
+//! Several fields pack multiple values into a single byte. Accessors are
+//! provided for the individual values. Color table sizes are encoded as
+//! exponent `n`, meaning the table has `2^(n+1)` entries of 3 bytes each.
+//!
+//! All multi-byte integers are encoded as little-endian. Since the
+//! structures are not naturally aligned in the stream, they are packed.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::int;
+
+/// Magic Signature
+///
+/// The initial 3 bytes of every GIF file ("GIF").
+pub const MAGIC: [u8; 3] = [0x47, 0x49, 0x46];
+
+pub const VERSION_87A: [u8; 3] = *b"87a";
+pub const VERSION_89A: [u8; 3] = *b"89a";
+
+pub const IMAGE_SEPARATOR: u8 = 0x2c;
+pub const EXTENSION_INTRODUCER: u8 = 0x21;
+pub const TRAILER: u8 = 0x3b;
+
+pub const LABEL_PLAIN_TEXT: u8 = 0x01;
+pub const LABEL_GRAPHIC_CONTROL: u8 = 0xf9;
+pub const LABEL_COMMENT: u8 = 0xfe;
+pub const LABEL_APPLICATION: u8 = 0xff;
+
+pub const SCREEN_GLOBAL_COLOR_TABLE: u8 = 0x80;
+pub const SCREEN_COLOR_RESOLUTION_MASK: u8 = 0x70;
+pub const SCREEN_COLOR_RESOLUTION_SHIFT: u32 = 4;
+pub const SCREEN_SORT: u8 = 0x08;
+pub const SCREEN_GLOBAL_COLOR_TABLE_SIZE_MASK: u8 = 0x07;
+
+pub const IMAGE_LOCAL_COLOR_TABLE: u8 = 0x80;
+pub const IMAGE_INTERLACE: u8 = 0x40;
+pub const IMAGE_SORT: u8 = 0x20;
+/* reserved: 0x10, 0x08 */
+pub const IMAGE_LOCAL_COLOR_TABLE_SIZE_MASK: u8 = 0x07;
+
+/* reserved: 0x80, 0x40, 0x20 */
+pub const GCE_DISPOSAL_MASK: u8 = 0x1c;
+pub const GCE_DISPOSAL_SHIFT: u32 = 2;
+pub const GCE_USER_INPUT: u8 = 0x02;
+pub const GCE_TRANSPARENT_COLOR: u8 = 0x01;
+
+pub const DISPOSAL_UNSPECIFIED: u8 = 0;
+pub const DISPOSAL_NONE: u8 = 1;
+pub const DISPOSAL_RESTORE_BACKGROUND: u8 = 2;
+pub const DISPOSAL_RESTORE_PREVIOUS: u8 = 3;
+
+/// NETSCAPE2.0 Application Identifier
+///
+/// The identifier and authentication code of the application extension that
+/// carries the loop count of animations. Its data consists of a single
+/// sub-block with the byte `0x01` followed by the 16-bit loop count (0 means
+/// infinite).
+pub const APPLICATION_NETSCAPE: [u8; 11] = *b"NETSCAPE2.0";
+
+/// File Header
+#[repr(C)]
+pub struct Header {
+    /// The static signature identifying the format. This must match `MAGIC`.
+    pub signature: [u8; 3],
+
+    /// The format version (`VERSION_87A` or `VERSION_89A`).
+    pub version: [u8; 3],
+}
+
+/// Logical Screen Descriptor
+///
+/// Describes the canvas all images are rendered onto. It directly follows
+/// the header.
+#[repr(C, packed)]
+pub struct LogicalScreenDescriptor {
+    /// Width of the canvas in pixels.
+    pub width: int::u16le,
+
+    /// Height of the canvas in pixels.
+    pub height: int::u16le,
+
+    /// Packed fields (`SCREEN_*`).
+    pub packed: u8,
+
+    /// Index of the background color in the global color table.
+    pub background_color_index: u8,
+
+    /// Pixel aspect ratio `n`, meaning `(n + 15) / 64`, or 0 if not given.
+    pub pixel_aspect_ratio: u8,
+}
+
+/// Image Descriptor
+///
+/// Describes a single image rendered onto the canvas. This includes the
+/// leading `IMAGE_SEPARATOR`.
+#[repr(C, packed)]
+pub struct ImageDescriptor {
+    /// Image separator, must be `IMAGE_SEPARATOR`.
+    pub separator: u8,
+
+    /// Horizontal position of the image on the canvas.
+    pub left: int::u16le,
+
+    /// Vertical position of the image on the canvas.
+    pub top: int::u16le,
+
+    /// Width of the image in pixels.
+    pub width: int::u16le,
+
+    /// Height of the image in pixels.
+    pub height: int::u16le,
+
+    /// Packed fields (`IMAGE_*`).
+    pub packed: u8,
+}
+
+/// Graphic Control Extension
+///
+/// Controls transparency and animation timing of the next image. This
+/// includes the extension introducer and label, as well as the terminating
+/// empty sub-block.
+#[repr(C, packed)]
+pub struct GraphicControlExtension {
+    /// Extension introducer, must be `EXTENSION_INTRODUCER`.
+    pub introducer: u8,
+
+    /// Extension label, must be `LABEL_GRAPHIC_CONTROL`.
+    pub label: u8,
+
+    /// Block size, must be 4.
+    pub block_size: u8,
+
+    /// Packed fields (`GCE_*`).
+    pub packed: u8,
+
+    /// Delay after rendering the image in 1/100 seconds.
+    pub delay_time: int::u16le,
+
+    /// Index of the transparent color, if `GCE_TRANSPARENT_COLOR` is set.
+    pub transparent_color_index: u8,
+
+    /// Block terminator, must be 0.
+    pub terminator: u8,
+}
+
+/// Application Extension
+///
+/// The header of an application extension, including the extension
+/// introducer and label. It is followed by a sequence of sub-blocks with the
+/// application data.
+#[repr(C)]
+pub struct ApplicationExtension {
+    /// Extension introducer, must be `EXTENSION_INTRODUCER`.
+    pub introducer: u8,
+
+    /// Extension label, must be `LABEL_APPLICATION`.
+    pub label: u8,
+
+    /// Block size, must be 11.
+    pub block_size: u8,
+
+    /// Application identifier.
+    pub identifier: [u8; 8],
+
+    /// Application authentication code.
+    pub authentication_code: [u8; 3],
+}
+
+/// Sub-block Iterator
+///
+/// An iterator over a sequence of sub-blocks, yielding the data of each
+/// sub-block. Iteration stops at the terminating empty sub-block or if the
+/// data is truncated. Use `SubBlocks::remainder()` to get the data following
+/// the terminator.
+#[derive(Clone, Debug)]
+pub struct SubBlocks<'data> {
+    data: &'data [u8],
+    done: bool,
+}
+
+impl LogicalScreenDescriptor {
+    /// Import a logical screen descriptor from a byte slice
+    ///
+    /// Create a new descriptor from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 7]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 7], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Return size of the global color table
+    ///
+    /// Return the size of the global color table in bytes, or 0 if there is
+    /// none.
+    pub fn global_color_table_size(&self) -> usize {
+        if self.packed & SCREEN_GLOBAL_COLOR_TABLE != 0 {
+            color_table_size(self.packed & SCREEN_GLOBAL_COLOR_TABLE_SIZE_MASK)
+        } else {
+            0
+        }
+    }
+
+    /// Return color resolution
+    ///
+    /// Return the number of bits per primary color of the original image.
+    pub fn color_resolution(&self) -> u8 {
+        ((self.packed & SCREEN_COLOR_RESOLUTION_MASK) >> SCREEN_COLOR_RESOLUTION_SHIFT) + 1
+    }
+}
+
+impl ImageDescriptor {
+    /// Import an image descriptor from a byte slice
+    ///
+    /// Create a new descriptor from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 10]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 10], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Return size of the local color table
+    ///
+    /// Return the size of the local color table in bytes, or 0 if there is
+    /// none.
+    pub fn local_color_table_size(&self) -> usize {
+        if self.packed & IMAGE_LOCAL_COLOR_TABLE != 0 {
+            color_table_size(self.packed & IMAGE_LOCAL_COLOR_TABLE_SIZE_MASK)
+        } else {
+            0
+        }
+    }
+
+    /// Check whether the image is interlaced
+    pub fn is_interlaced(&self) -> bool {
+        self.packed & IMAGE_INTERLACE != 0
+    }
+}
+
+impl GraphicControlExtension {
+    /// Import a graphic control extension from a byte slice
+    ///
+    /// Create a new extension from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 8]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 8], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Return disposal method
+    ///
+    /// Return the disposal method (`DISPOSAL_*`) of the next image.
+    pub fn disposal_method(&self) -> u8 {
+        (self.packed & GCE_DISPOSAL_MASK) >> GCE_DISPOSAL_SHIFT
+    }
+
+    /// Return transparent color index
+    ///
+    /// Return the index of the transparent color, or `None` if transparency
+    /// is not enabled.
+    pub fn transparent_color(&self) -> Option<u8> {
+        if self.packed & GCE_TRANSPARENT_COLOR != 0 {
+            Some(self.transparent_color_index)
+        } else {
+            None
+        }
+    }
+}
+
+impl<'data> SubBlocks<'data> {
+    /// Create sub-block iterator
+    ///
+    /// Create a new iterator over the sequence of sub-blocks at the start of
+    /// `data`.
+    pub fn new(data: &'data [u8]) -> Self {
+        Self {
+            data,
+            done: false,
+        }
+    }
+
+    /// Return remaining data
+    ///
+    /// Return the data following the last sub-block yielded so far. Once the
+    /// iterator reached the terminator, this is the data following the
+    /// terminator.
+    pub fn remainder(&self) -> &'data [u8] {
+        self.data
+    }
+
+    /// Check for complete termination
+    ///
+    /// Return whether the iterator reached the terminating empty sub-block.
+    /// If iteration ended without this being set, the data was truncated.
+    pub fn is_terminated(&self) -> bool {
+        self.done
+    }
+}
+
+impl<'data> Iterator for SubBlocks<'data> {
+    type Item = &'data [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let size = *self.data.first()? as usize;
+        if size == 0 {
+            self.data = &self.data[1..];
+            self.done = true;
+            return None;
+        }
+
+        if self.data.len() <= size {
+            return None;
+        }
+
+        let block = &self.data[1..1 + size];
+        self.data = &self.data[1 + size..];
+        Some(block)
+    }
+}
+
+/// Calculate Color Table Size
+///
+/// Return the size of a color table in bytes for the given size exponent, as
+/// stored in the packed fields.
+pub fn color_table_size(exponent: u8) -> usize {
+    3 << ((exponent & 0x07) + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A 1x1 transparent image, a common tracking pixel.
+    const PIXEL: [u8; 43] = [
+        0x47, 0x49, 0x46, 0x38, 0x39, 0x61, 0x01, 0x00,
+        0x01, 0x00, 0x80, 0x00, 0x00, 0xff, 0xff, 0xff,
+        0x00, 0x00, 0x00, 0x21, 0xf9, 0x04, 0x01, 0x00,
+        0x00, 0x00, 0x00, 0x2c, 0x00, 0x00, 0x00, 0x00,
+        0x01, 0x00, 0x01, 0x00, 0x00, 0x02, 0x02, 0x44,
+        0x01, 0x00, 0x3b,
+    ];
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<Header>(), 6);
+        assert_eq!(size_of::<LogicalScreenDescriptor>(), 7);
+        assert_eq!(align_of::<LogicalScreenDescriptor>(), 1);
+        assert_eq!(size_of::<ImageDescriptor>(), 10);
+        assert_eq!(align_of::<ImageDescriptor>(), 1);
+        assert_eq!(size_of::<GraphicControlExtension>(), 8);
+        assert_eq!(align_of::<GraphicControlExtension>(), 1);
+        assert_eq!(size_of::<ApplicationExtension>(), 14);
+    }
+
+    // Walk all blocks of a real image.
+    #[test]
+    fn verify_blocks() {
+        assert_eq!(PIXEL[..3], MAGIC);
+        assert_eq!(PIXEL[3..6], VERSION_89A);
+
+        let s = LogicalScreenDescriptor::from_bytes(PIXEL[6..13].try_into().unwrap());
+        assert_eq!(int::ForeignEndian::to_native(s.width), 1);
+        assert_eq!(int::ForeignEndian::to_native(s.height), 1);
+        assert_eq!(s.color_resolution(), 1);
+        assert_eq!(s.global_color_table_size(), 6);
+
+        let g = GraphicControlExtension::from_bytes(PIXEL[19..27].try_into().unwrap());
+        assert_eq!(g.introducer, EXTENSION_INTRODUCER);
+        assert_eq!(g.label, LABEL_GRAPHIC_CONTROL);
+        assert_eq!(g.disposal_method(), DISPOSAL_UNSPECIFIED);
+        assert_eq!(g.transparent_color(), Some(0));
+        assert_eq!(int::ForeignEndian::to_native(g.delay_time), 0);
+
+        let i = ImageDescriptor::from_bytes(PIXEL[27..37].try_into().unwrap());
+        assert_eq!(i.separator, IMAGE_SEPARATOR);
+        assert_eq!(int::ForeignEndian::to_native(i.width), 1);
+        assert_eq!(i.local_color_table_size(), 0);
+        assert!(!i.is_interlaced());
+
+        // Skip the LZW minimum code size and walk the image data.
+        let mut blocks = SubBlocks::new(&PIXEL[38..]);
+        assert_eq!(blocks.next(), Some(&[0x44, 0x01][..]));
+        assert_eq!(blocks.next(), None);
+        assert!(blocks.is_terminated());
+        assert_eq!(blocks.remainder(), &[TRAILER]);
+    }
+
+    // Verify sub-block iteration stops on truncated data.
+    #[test]
+    fn verify_sub_blocks() {
+        let mut blocks = SubBlocks::new(&[0x01, 0xaa, 0x03, 0xbb]);
+        assert_eq!(blocks.next(), Some(&[0xaa][..]));
+        assert_eq!(blocks.next(), None);
+        assert!(!blocks.is_terminated());
+
+        assert_eq!(color_table_size(0), 6);
+        assert_eq!(color_table_size(7), 768);
+    }
+}
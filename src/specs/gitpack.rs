@@ -0,0 +1,627 @@
+//! Git Packfiles and Pack Indices
+//!
+//! Git stores objects in packfiles, which start with a 12-byte header,
+//! followed by the object entries, and end with the hash of all preceding
+//! data. Each entry starts with an object header, which encodes the object
+//! type (`OBJ_*`) and the inflated size in a varint (see `ObjectHeader`),
+//! followed by the zlib-compressed object data.
+//!
+//! Deltified entries add a reference to their base object between header
+//! and data. `OBJ_OFS_DELTA` entries encode the distance to the base entry
+//! in the same pack (see `ofs_decode()`), `OBJ_REF_DELTA` entries the name
+//! of the base object. The inflated data of deltas starts with the sizes of
+//! the base and result object as LEB128 varints (see `delta_sizes()`),
+//! followed by instructions to copy ranges of the base, or insert literal
+//! data (see `Instruction`).
+//!
+//! A pack index (version 2) allows looking up entries by object name. It
+//! starts with an 8-byte header and a fanout table with the cumulative
+//! number of objects per first byte of their name, followed by the sorted
+//! object names, the CRC-32 of each packed entry, and their offsets in the
+//! pack. Offsets that do not fit 31 bits refer to a table of 64-bit offsets
+//! (see `Index`). The index ends with the hash of the pack and its own
+//! hash.
+//!
+//! Object names are SHA-1 hashes (`HASH_SIZE`), unless a repository uses
+//! SHA-256 (`HASH_SHA256_SIZE`). All multi-byte integers are encoded as
+//! big-endian.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::int;
+use crate::specs::varint;
+
+/// Pack Signature
+///
+/// The initial 4 bytes of every packfile ("PACK").
+pub const PACK_MAGIC: [u8; 4] = [0x50, 0x41, 0x43, 0x4b];
+
+pub const PACK_VERSION_2: u32 = 2;
+pub const PACK_VERSION_3: u32 = 3;
+
+/// Index Signature
+///
+/// The initial 4 bytes of pack indices of version 2 and later ("\377tOc").
+pub const INDEX_MAGIC: [u8; 4] = [0xff, 0x74, 0x4f, 0x63];
+
+pub const INDEX_VERSION_2: u32 = 2;
+
+pub const HASH_SIZE: usize = 20;
+pub const HASH_SHA256_SIZE: usize = 32;
+
+pub const OBJ_COMMIT: u8 = 1;
+pub const OBJ_TREE: u8 = 2;
+pub const OBJ_BLOB: u8 = 3;
+pub const OBJ_TAG: u8 = 4;
+/* reserved: 5 */
+pub const OBJ_OFS_DELTA: u8 = 6;
+pub const OBJ_REF_DELTA: u8 = 7;
+
+pub const FANOUT_COUNT: usize = 256;
+
+/// Flag of Offsets into the 64-bit Offset Table
+pub const OFFSET_LARGE: u32 = 0x8000_0000;
+
+pub const DELTA_COPY: u8 = 0x80;
+pub const DELTA_COPY_OFFSET_MASK: u8 = 0x0f;
+pub const DELTA_COPY_SIZE_MASK: u8 = 0x70;
+
+/// Copy Size of Delta Instructions without Size Bytes
+pub const DELTA_COPY_SIZE_DEFAULT: u32 = 0x10000;
+
+/// Maximum Size of Delta Insert Instructions
+pub const DELTA_INSERT_MAX: usize = 0x7f;
+
+/// Pack Header
+#[repr(C)]
+pub struct PackHeader {
+    /// The static signature identifying the format. This must match
+    /// `PACK_MAGIC`.
+    pub magic: [u8; 4],
+
+    /// Version of the pack format (`PACK_VERSION_*`).
+    pub version: int::u32be,
+
+    /// Number of objects in the pack.
+    pub count: int::u32be,
+}
+
+/// Index Header
+#[repr(C)]
+pub struct IndexHeader {
+    /// The static signature identifying the format. This must match
+    /// `INDEX_MAGIC`.
+    pub magic: [u8; 4],
+
+    /// Version of the index format (`INDEX_VERSION_*`).
+    pub version: int::u32be,
+}
+
+/// Object Entry Header
+#[derive(Clone, Copy, Debug)]
+pub struct ObjectHeader {
+    /// Object type (`OBJ_*`).
+    pub object_type: u8,
+
+    /// Size of the inflated object data, or delta data, in bytes.
+    pub size: u64,
+
+    /// Size of the header in bytes.
+    pub header_size: usize,
+}
+
+/// Delta Instruction
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Instruction<'data> {
+    /// Copy `size` bytes at `offset` of the base object.
+    Copy { offset: u32, size: u32 },
+    /// Insert literal data.
+    Insert(&'data [u8]),
+}
+
+/// Delta Instruction Iterator
+///
+/// Iterate over the instructions of delta data, following the sizes.
+/// Iteration stops at the first truncated or invalid instruction.
+#[derive(Clone, Debug)]
+pub struct Instructions<'data> {
+    data: &'data [u8],
+}
+
+/// Pack Index Reference
+///
+/// A parsed pack index of version 2. All tables are referenced in place.
+#[derive(Clone, Copy, Debug)]
+pub struct Index<'data> {
+    /// Size of object names in bytes.
+    pub hash_size: usize,
+
+    /// Fanout table, as 256 big-endian 32-bit integers.
+    pub fanout: &'data [u8],
+
+    /// Sorted object names.
+    pub names: &'data [u8],
+
+    /// CRC-32 of packed entries, as big-endian 32-bit integers.
+    pub crcs: &'data [u8],
+
+    /// Offsets of packed entries, as big-endian 32-bit integers.
+    pub offsets: &'data [u8],
+
+    /// 64-bit offsets of packed entries, as big-endian 64-bit integers.
+    pub large_offsets: &'data [u8],
+
+    /// Hash of the pack.
+    pub pack_hash: &'data [u8],
+
+    /// Hash of the index, excluding this field.
+    pub index_hash: &'data [u8],
+}
+
+// Read a big-endian 32-bit integer at the given index.
+fn be32(data: &[u8], i: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(data.get(i * 4..i * 4 + 4)?.try_into().ok()?))
+}
+
+impl PackHeader {
+    /// Import a header from a byte slice
+    ///
+    /// Create a new header from data copied from a byte slice. No byte-order
+    /// conversions are applied.
+    pub fn from_bytes(data: &[u8; 12]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 12], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl IndexHeader {
+    /// Import a header from a byte slice
+    ///
+    /// Create a new header from data copied from a byte slice. No byte-order
+    /// conversions are applied.
+    pub fn from_bytes(data: &[u8; 8]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 8], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl ObjectHeader {
+    /// Parse object header
+    ///
+    /// Parse the entry header at the start of `data`. The first byte carries
+    /// a continuation bit, the type in 3 bits, and the low 4 bits of the
+    /// size, followed by further groups of 7 bits of the size in
+    /// little-endian order. Return `None` if the header is truncated, the
+    /// size overflows, or the type is invalid.
+    pub fn parse(data: &[u8]) -> Option<Self> {
+        let first = *data.first()?;
+        let object_type = (first >> 4) & 0x7;
+        if object_type == 0 || object_type == 5 {
+            return None;
+        }
+
+        let mut size = (first & 0x0f) as u64;
+        let mut shift = 4;
+        let mut b = first;
+        let mut n = 1;
+        while b & 0x80 != 0 {
+            b = *data.get(n)?;
+            n += 1;
+            let v = ((b & 0x7f) as u64).checked_shl(shift)?;
+            if v >> shift != (b & 0x7f) as u64 {
+                return None;
+            }
+            size |= v;
+            shift += 7;
+        }
+
+        Some(Self { object_type, size, header_size: n })
+    }
+
+    /// Encode object header
+    ///
+    /// Encode the entry header for the given type and size. Return the number
+    /// of bytes written, or `None` if the buffer is too small.
+    pub fn encode(object_type: u8, size: u64, out: &mut [u8]) -> Option<usize> {
+        let mut v = size >> 4;
+        let mut b = (object_type << 4) | (size & 0x0f) as u8;
+        let mut n = 0;
+
+        while v > 0 {
+            *out.get_mut(n)? = b | 0x80;
+            n += 1;
+            b = (v & 0x7f) as u8;
+            v >>= 7;
+        }
+
+        *out.get_mut(n)? = b;
+        Some(n + 1)
+    }
+}
+
+/// Decode base offset
+///
+/// Decode the negative offset of the base entry of an `OBJ_OFS_DELTA` entry,
+/// relative to the start of the entry. Groups of 7 bits are in big-endian
+/// order, and each continuation adds one, so each value has exactly one
+/// encoding. Return the offset and the number of bytes consumed, or `None`
+/// if the data is truncated or the offset overflows.
+pub fn ofs_decode(data: &[u8]) -> Option<(u64, usize)> {
+    let mut b = *data.first()?;
+    let mut v = (b & 0x7f) as u64;
+    let mut n = 1;
+
+    while b & 0x80 != 0 {
+        b = *data.get(n)?;
+        n += 1;
+        v = v.checked_add(1)?;
+        if v >> 57 != 0 {
+            return None;
+        }
+        v = (v << 7) | (b & 0x7f) as u64;
+    }
+
+    Some((v, n))
+}
+
+/// Encode base offset
+///
+/// Encode the offset of the base entry of an `OBJ_OFS_DELTA` entry. Return
+/// the number of bytes written, or `None` if the buffer is too small.
+pub fn ofs_encode(mut v: u64, out: &mut [u8]) -> Option<usize> {
+    let mut buf = [0; 10];
+    let mut i = buf.len() - 1;
+
+    buf[i] = (v & 0x7f) as u8;
+    v >>= 7;
+    while v > 0 {
+        v -= 1;
+        i -= 1;
+        buf[i] = 0x80 | (v & 0x7f) as u8;
+        v >>= 7;
+    }
+
+    let n = buf.len() - i;
+    out.get_mut(..n)?.copy_from_slice(&buf[i..]);
+    Some(n)
+}
+
+/// Decode delta sizes
+///
+/// Decode the sizes of the base and result object at the start of delta
+/// data. Return both sizes and the number of bytes consumed, or `None` if
+/// the data is truncated.
+pub fn delta_sizes(data: &[u8]) -> Option<(u64, u64, usize)> {
+    let (base, n) = varint::leb128_decode(data)?;
+    let (result, m) = varint::leb128_decode(&data[n..])?;
+    Some((base, result, n + m))
+}
+
+impl<'data> Instruction<'data> {
+    /// Decode delta instruction
+    ///
+    /// Decode the instruction at the start of `data`. Copy instructions
+    /// select the present bytes of offset and size with bits of the opcode,
+    /// and leave the others zero. Return the instruction and the number of
+    /// bytes consumed, or `None` if the instruction is truncated or uses the
+    /// reserved opcode 0.
+    pub fn decode(data: &'data [u8]) -> Option<(Self, usize)> {
+        let op = *data.first()?;
+        if op == 0 {
+            return None;
+        }
+
+        if op & DELTA_COPY == 0 {
+            let end = 1 + op as usize;
+            return Some((Instruction::Insert(data.get(1..end)?), end));
+        }
+
+        let mut v: u64 = 0;
+        let mut n = 1;
+        for i in 0..7 {
+            if op & (1 << i) != 0 {
+                v |= (*data.get(n)? as u64) << (i * 8);
+                n += 1;
+            }
+        }
+
+        let offset = v as u32;
+        let size = match (v >> 32) as u32 {
+            0 => DELTA_COPY_SIZE_DEFAULT,
+            s => s,
+        };
+
+        Some((Instruction::Copy { offset, size }, n))
+    }
+
+    /// Encode delta instruction
+    ///
+    /// Encode the instruction, omitting zero bytes of offset and size. Return
+    /// the number of bytes written, or `None` if the buffer is too small, an
+    /// insert is empty or exceeds `DELTA_INSERT_MAX`, or a copy is empty or
+    /// exceeds 24 bits of size.
+    pub fn encode(&self, out: &mut [u8]) -> Option<usize> {
+        match *self {
+            Instruction::Insert(v) => {
+                if v.is_empty() || v.len() > DELTA_INSERT_MAX {
+                    return None;
+                }
+                out.get_mut(..1 + v.len())?[1..].copy_from_slice(v);
+                out[0] = v.len() as u8;
+                Some(1 + v.len())
+            },
+            Instruction::Copy { offset, size } => {
+                if size == 0 || size > 0xff_ffff {
+                    return None;
+                }
+                let size = if size == DELTA_COPY_SIZE_DEFAULT { 0 } else { size };
+                let v = offset as u64 | ((size as u64) << 32);
+                let mut op = DELTA_COPY;
+                let mut n = 1;
+                for i in 0..7 {
+                    let b = (v >> (i * 8)) as u8;
+                    if b != 0 {
+                        *out.get_mut(n)? = b;
+                        op |= 1 << i;
+                        n += 1;
+                    }
+                }
+                *out.first_mut()? = op;
+                Some(n)
+            },
+        }
+    }
+}
+
+impl<'data> Instructions<'data> {
+    /// Create delta instruction iterator
+    ///
+    /// Create a new iterator over the instructions of `data`, which must
+    /// start after the delta sizes.
+    pub fn new(data: &'data [u8]) -> Self {
+        Self { data }
+    }
+
+    /// Return remaining data
+    ///
+    /// Return the data that has not been consumed by the iterator.
+    pub fn remainder(&self) -> &'data [u8] {
+        self.data
+    }
+}
+
+impl<'data> Iterator for Instructions<'data> {
+    type Item = Instruction<'data>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (v, n) = Instruction::decode(self.data)?;
+        self.data = &self.data[n..];
+        Some(v)
+    }
+}
+
+impl<'data> Index<'data> {
+    /// Parse pack index
+    ///
+    /// Parse a pack index of version 2 with object names of `hash_size`
+    /// bytes. Return `None` if the header is invalid, the fanout table is not
+    /// monotonic, or the tables are truncated.
+    pub fn parse(data: &'data [u8], hash_size: usize) -> Option<Self> {
+        let h = IndexHeader::from_bytes(data.get(..8)?.try_into().ok()?);
+        if h.magic != INDEX_MAGIC || int::ForeignEndian::to_native(h.version) != INDEX_VERSION_2 {
+            return None;
+        }
+
+        let fanout = data.get(8..8 + FANOUT_COUNT * 4)?;
+        let mut count: u32 = 0;
+        for i in 0..FANOUT_COUNT {
+            let v = be32(fanout, i)?;
+            if v < count {
+                return None;
+            }
+            count = v;
+        }
+
+        let n = count as usize;
+        let (names, rest) = data[8 + fanout.len()..].split_at_checked(n.checked_mul(hash_size)?)?;
+        let (crcs, rest) = rest.split_at_checked(n * 4)?;
+        let (offsets, rest) = rest.split_at_checked(n * 4)?;
+        let tail = hash_size.checked_mul(2)?;
+        let large = rest.len().checked_sub(tail)?;
+        if !large.is_multiple_of(8) {
+            return None;
+        }
+        let (large_offsets, rest) = rest.split_at(large);
+        let (pack_hash, index_hash) = rest.split_at(hash_size);
+
+        Some(Self {
+            hash_size,
+            fanout,
+            names,
+            crcs,
+            offsets,
+            large_offsets,
+            pack_hash,
+            index_hash,
+        })
+    }
+
+    /// Return number of objects
+    pub fn count(&self) -> usize {
+        self.crcs.len() / 4
+    }
+
+    /// Return object name
+    ///
+    /// Return the name of the object at index `i`.
+    pub fn name(&self, i: usize) -> Option<&'data [u8]> {
+        self.names.get(i * self.hash_size..(i + 1) * self.hash_size)
+    }
+
+    /// Return CRC-32 of packed entry
+    ///
+    /// Return the CRC-32 (see `checksum::crc32()`) of the packed entry of the
+    /// object at index `i`, including its header.
+    pub fn crc32(&self, i: usize) -> Option<u32> {
+        be32(self.crcs, i)
+    }
+
+    /// Return offset of packed entry
+    ///
+    /// Return the offset in the pack of the entry of the object at index `i`,
+    /// resolving large offsets.
+    pub fn offset(&self, i: usize) -> Option<u64> {
+        let v = be32(self.offsets, i)?;
+        if v & OFFSET_LARGE == 0 {
+            return Some(v as u64);
+        }
+
+        let j = (v & !OFFSET_LARGE) as usize;
+        let b = self.large_offsets.get(j * 8..j * 8 + 8)?;
+        Some(u64::from_be_bytes(b.try_into().ok()?))
+    }
+
+    /// Find object
+    ///
+    /// Return the index of the object with the given name, using the fanout
+    /// table to narrow the search.
+    pub fn find(&self, name: &[u8]) -> Option<usize> {
+        let first = *name.first()? as usize;
+        let start = match first {
+            0 => 0,
+            _ => be32(self.fanout, first - 1)? as usize,
+        };
+        let end = (be32(self.fanout, first)? as usize).min(self.count());
+
+        let (mut lo, mut hi) = (start, end);
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            match self.name(mid)?.cmp(name) {
+                core::cmp::Ordering::Less => lo = mid + 1,
+                core::cmp::Ordering::Greater => hi = mid,
+                core::cmp::Ordering::Equal => return Some(mid),
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::specs::checksum;
+    use crate::specs::int::ForeignEndian;
+
+    // Pack generated by `git pack-objects` with the single blob "hello\n".
+    const PACK: [u8; 47] = [
+        0x50, 0x41, 0x43, 0x4b, 0x00, 0x00, 0x00, 0x02,
+        0x00, 0x00, 0x00, 0x01, 0x36, 0x78, 0x9c, 0xcb,
+        0x48, 0xcd, 0xc9, 0xc9, 0xe7, 0x02, 0x00, 0x08,
+        0x4b, 0x02, 0x1f, 0xde, 0x04, 0x12, 0x40, 0x1f,
+        0x4a, 0x9e, 0x5f, 0x05, 0x41, 0x1f, 0x44, 0xea,
+        0xf9, 0xc8, 0x6d, 0x46, 0x09, 0x67, 0x46,
+    ];
+
+    // Object name of the blob "hello\n".
+    const NAME: [u8; 20] = [
+        0xce, 0x01, 0x36, 0x25, 0x03, 0x0b, 0xa8, 0xdb,
+        0xa9, 0x06, 0xf7, 0x56, 0x96, 0x7f, 0x9e, 0x9c,
+        0xa3, 0x94, 0x46, 0x4a,
+    ];
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<PackHeader>(), 12);
+        assert_eq!(align_of::<PackHeader>(), 4);
+        assert_eq!(size_of::<IndexHeader>(), 8);
+        assert_eq!(align_of::<IndexHeader>(), 4);
+    }
+
+    // Parse the pack and an index of it.
+    #[test]
+    fn verify_pack() {
+        let h = PackHeader::from_bytes(PACK[..12].try_into().unwrap());
+        assert_eq!(h.magic, PACK_MAGIC);
+        assert_eq!(h.version.to_native(), PACK_VERSION_2);
+        assert_eq!(h.count.to_native(), 1);
+
+        let o = ObjectHeader::parse(&PACK[12..]).unwrap();
+        assert_eq!((o.object_type, o.size, o.header_size), (OBJ_BLOB, 6, 1));
+
+        let mut idx = std::vec::Vec::new();
+        idx.extend_from_slice(&INDEX_MAGIC);
+        idx.extend_from_slice(&INDEX_VERSION_2.to_be_bytes());
+        for i in 0..FANOUT_COUNT {
+            idx.extend_from_slice(&((i >= 0xce) as u32).to_be_bytes());
+        }
+        idx.extend_from_slice(&NAME);
+        idx.extend_from_slice(&checksum::crc32(&PACK[12..27]).to_be_bytes());
+        idx.extend_from_slice(&12u32.to_be_bytes());
+        idx.extend_from_slice(&PACK[27..]);
+        idx.extend_from_slice(&[0; HASH_SIZE]);
+
+        let x = Index::parse(&idx, HASH_SIZE).unwrap();
+        assert_eq!(x.count(), 1);
+        assert_eq!(x.name(0), Some(&NAME[..]));
+        assert_eq!(x.crc32(0), Some(0x5294_1500));
+        assert_eq!(x.offset(0), Some(12));
+        assert_eq!(x.pack_hash, &PACK[27..]);
+        assert_eq!(x.find(&NAME), Some(0));
+        assert_eq!(x.find(&[0xce; 20]), None);
+        assert!(Index::parse(&idx[..idx.len() - 1], HASH_SIZE).is_none());
+    }
+
+    // Encode and decode varints and delta instructions.
+    #[test]
+    fn verify_encodings() {
+        let mut buf = [0; 16];
+
+        assert_eq!(ObjectHeader::encode(OBJ_COMMIT, 0x234, &mut buf), Some(2));
+        assert_eq!(buf[..2], [0x94, 0x23]);
+        let o = ObjectHeader::parse(&buf).unwrap();
+        assert_eq!((o.object_type, o.size, o.header_size), (OBJ_COMMIT, 0x234, 2));
+        assert!(ObjectHeader::parse(&[0xd0]).is_none());
+        assert!(ObjectHeader::parse(&[0x50]).is_none());
+
+        assert_eq!(ofs_encode(128, &mut buf), Some(2));
+        assert_eq!(buf[..2], [0x80, 0x00]);
+        assert_eq!(ofs_decode(&buf), Some((128, 2)));
+        assert_eq!(ofs_decode(&[0x7f]), Some((127, 1)));
+        assert_eq!(ofs_decode(&[0xff, 0x7f]), Some((16511, 2)));
+
+        let delta = [0x06, 0x05, 0x91, 0x01, 0x04, 0x01, 0x21];
+        assert_eq!(delta_sizes(&delta), Some((6, 5, 2)));
+        let mut i = Instructions::new(&delta[2..]);
+        let c = i.next().unwrap();
+        assert_eq!(c, Instruction::Copy { offset: 1, size: 4 });
+        assert_eq!(i.next(), Some(Instruction::Insert(b"!")));
+        assert!(i.next().is_none());
+        assert_eq!(c.encode(&mut buf), Some(3));
+        assert_eq!(buf[..3], delta[2..5]);
+        let c = Instruction::Copy { offset: 0, size: DELTA_COPY_SIZE_DEFAULT };
+        assert_eq!(c.encode(&mut buf), Some(1));
+        assert_eq!(Instruction::decode(&buf[..1]), Some((c, 1)));
+        assert_eq!(Instruction::decode(&[0x00]), None);
+        assert_eq!(Instruction::decode(&[0x02, 0x21]), None);
+    }
+}
@@ -0,0 +1,286 @@
+//! Generic Routing Encapsulation
+//!
+//! GRE packets are carried in IP (`ipv4::PROTOCOL_GRE`) and start with a
+//! 4-byte `Header`, carrying flags and the EtherType of the payload. The
+//! header is followed by optional fields, each present only if its flag is
+//! set, in this order: checksum and reserved field (`FLAG_CHECKSUM`, 4
+//! bytes), key (`FLAG_KEY`, 4 bytes), and sequence number (`FLAG_SEQUENCE`,
+//! 4 bytes). See `Packet` to locate them. The deprecated routing flag of RFC
+//! 1701 implies the checksum field as well, but is otherwise not supported.
+//!
+//! Version 1 is the enhanced GRE of PPTP, which always carries the key field
+//! split into payload length and call identifier, and adds an optional
+//! acknowledgment number after the sequence number (`FLAG_ACK`).
+//!
+//! NVGRE tunnels Ethernet frames (`ethernet::ETHERTYPE_TRANSPARENT_BRIDGING`)
+//! and uses the key field to carry a 24-bit virtual subnet identifier and an
+//! 8-bit flow identifier (see `nvgre_key()`).
+//!
+//! The checksum is the Internet checksum of the GRE header and payload,
+//! calculated with the checksum field set to zero.
+//!
+//! All multi-byte integers are encoded as big-endian.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::{checksum, int};
+
+pub const HEADER_SIZE: usize = 4;
+pub const HEADER_SIZE_MAX: usize = 16;
+pub const CHECKSUM_OFFSET: usize = 4;
+
+pub const FLAG_CHECKSUM: u16 = 0x8000;
+pub const FLAG_ROUTING: u16 = 0x4000;
+pub const FLAG_KEY: u16 = 0x2000;
+pub const FLAG_SEQUENCE: u16 = 0x1000;
+pub const FLAG_STRICT_ROUTE: u16 = 0x0800;
+pub const FLAG_ACK: u16 = 0x0080;
+pub const RECURSION_MASK: u16 = 0x0700;
+pub const VERSION_MASK: u16 = 0x0007;
+
+pub const VERSION_GRE: u8 = 0;
+pub const VERSION_PPTP: u8 = 1;
+
+/// PPP EtherType used by PPTP
+pub const PROTOCOL_PPP: u16 = 0x880b;
+
+/// GRE Header
+#[repr(C)]
+pub struct Header {
+    /// Flags (`FLAG_*`) and version.
+    pub flags: int::u16be,
+
+    /// EtherType of the payload (`ethernet::ETHERTYPE_*`).
+    pub protocol_type: int::u16be,
+}
+
+/// Packet Reference
+///
+/// A GRE packet with its optional fields located and decoded.
+#[derive(Clone, Copy, Debug)]
+pub struct Packet<'data> {
+    /// Entire packet, including the header.
+    pub data: &'data [u8],
+
+    /// Checksum, if present.
+    pub checksum: Option<u16>,
+
+    /// Key, if present.
+    pub key: Option<u32>,
+
+    /// Sequence number, if present.
+    pub sequence: Option<u32>,
+
+    /// Acknowledgment number, if present (version 1 only).
+    pub ack: Option<u32>,
+
+    /// Payload following the header and optional fields.
+    pub payload: &'data [u8],
+}
+
+impl Header {
+    /// Import a header from a byte slice
+    ///
+    /// Create a new header from data copied from a byte slice. No byte-order
+    /// conversions are applied.
+    pub fn from_bytes(data: &[u8; 4]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 4], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Return version
+    pub fn version(&self) -> u8 {
+        (int::ForeignEndian::to_native(self.flags) & VERSION_MASK) as u8
+    }
+
+    /// Check flags
+    ///
+    /// Return whether all of the given flags (`FLAG_*`) are set.
+    pub fn has_flags(&self, flags: u16) -> bool {
+        int::ForeignEndian::to_native(self.flags) & flags == flags
+    }
+
+    /// Return header size
+    ///
+    /// Return the size of the header including all optional fields
+    /// announced by the flags.
+    pub fn header_size(&self) -> usize {
+        header_size(int::ForeignEndian::to_native(self.flags))
+    }
+}
+
+impl<'data> Packet<'data> {
+    /// Parse a packet
+    ///
+    /// Parse the header of a GRE packet and decode its optional fields.
+    /// Return `None` if the packet is shorter than its header.
+    pub fn parse(data: &'data [u8]) -> Option<Self> {
+        let flags = u16::from_be_bytes([*data.first()?, *data.get(1)?]);
+        let end = header_size(flags);
+        if data.len() < end {
+            return None;
+        }
+
+        let mut o = HEADER_SIZE;
+        let mut field = |present: bool| {
+            if present {
+                o += 4;
+                Some(u32::from_be_bytes(data[o - 4..o].try_into().unwrap()))
+            } else {
+                None
+            }
+        };
+
+        let checksum = field(flags & (FLAG_CHECKSUM | FLAG_ROUTING) != 0).map(|v| (v >> 16) as u16);
+        let key = field(flags & FLAG_KEY != 0);
+        let sequence = field(flags & FLAG_SEQUENCE != 0);
+        let ack = field(is_pptp_ack(flags));
+        Some(Self {
+            data,
+            checksum,
+            key,
+            sequence,
+            ack,
+            payload: &data[end..],
+        })
+    }
+
+    /// Return the packet header
+    pub fn header(&self) -> Header {
+        Header::from_bytes(self.data[..HEADER_SIZE].try_into().unwrap())
+    }
+}
+
+// Return whether the flags announce the acknowledgment number of PPTP.
+fn is_pptp_ack(flags: u16) -> bool {
+    flags & VERSION_MASK == VERSION_PPTP as u16 && flags & FLAG_ACK != 0
+}
+
+/// Return header size
+///
+/// Return the size of a header with the given flags, including all optional
+/// fields.
+pub fn header_size(flags: u16) -> usize {
+    let mut n = HEADER_SIZE;
+    if flags & (FLAG_CHECKSUM | FLAG_ROUTING) != 0 {
+        n += 4;
+    }
+    if flags & FLAG_KEY != 0 {
+        n += 4;
+    }
+    if flags & FLAG_SEQUENCE != 0 {
+        n += 4;
+    }
+    if is_pptp_ack(flags) {
+        n += 4;
+    }
+    n
+}
+
+/// Encode NVGRE key
+///
+/// Return the key field of an NVGRE packet with the given 24-bit virtual
+/// subnet identifier and 8-bit flow identifier.
+pub fn nvgre_key(vsid: u32, flow_id: u8) -> u32 {
+    ((vsid & 0x00ff_ffff) << 8) | flow_id as u32
+}
+
+/// Return virtual subnet identifier of NVGRE key
+pub fn nvgre_vsid(key: u32) -> u32 {
+    key >> 8
+}
+
+/// Return flow identifier of NVGRE key
+pub fn nvgre_flow_id(key: u32) -> u8 {
+    key as u8
+}
+
+/// Calculate Packet Checksum
+///
+/// Calculate the checksum of an entire GRE packet, treating the checksum
+/// field as zero. `packet` must be at least `CHECKSUM_OFFSET + 4` bytes.
+pub fn packet_checksum(packet: &[u8]) -> u16 {
+    let sum = checksum::internet_update(0, &packet[..CHECKSUM_OFFSET]);
+    let sum = checksum::internet_update(sum, &packet[CHECKSUM_OFFSET + 2..]);
+    checksum::internet_finish(sum)
+}
+
+/// Verify Packet Checksum
+pub fn verify_checksum(packet: &[u8]) -> bool {
+    checksum::internet(packet) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A packet with checksum, key and sequence number, carrying "ping".
+    const PACKET: [u8; 20] = [
+        0xb0, 0x00, 0x08, 0x00, 0x68, 0xfd, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x2a, 0x00, 0x00, 0x00, 0x07,
+        0x70, 0x69, 0x6e, 0x67,
+    ];
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<Header>(), HEADER_SIZE);
+        assert_eq!(align_of::<Header>(), 2);
+    }
+
+    // Parse a packet with all optional fields and verify its checksum.
+    #[test]
+    fn verify_packet() {
+        let p = Packet::parse(&PACKET).unwrap();
+        let h = p.header();
+        assert_eq!(h.version(), VERSION_GRE);
+        assert!(h.has_flags(FLAG_CHECKSUM | FLAG_KEY | FLAG_SEQUENCE));
+        assert_eq!(h.header_size(), HEADER_SIZE_MAX);
+        assert_eq!(int::ForeignEndian::to_native(h.protocol_type), 0x0800);
+        assert_eq!(p.checksum, Some(0x68fd));
+        assert_eq!(p.key, Some(42));
+        assert_eq!(p.sequence, Some(7));
+        assert_eq!(p.ack, None);
+        assert_eq!(p.payload, b"ping");
+
+        assert_eq!(packet_checksum(&PACKET), 0x68fd);
+        assert!(verify_checksum(&PACKET));
+
+        assert!(Packet::parse(&PACKET[..15]).is_none());
+        let p = Packet::parse(&[0x00, 0x00, 0x86, 0xdd]).unwrap();
+        assert_eq!((p.checksum, p.key, p.sequence), (None, None, None));
+        assert!(p.payload.is_empty());
+    }
+
+    // Parse PPTP and NVGRE packets.
+    #[test]
+    fn verify_variants() {
+        let data = [
+            0x30, 0x81, 0x88, 0x0b, 0x00, 0x00, 0xbe, 0xef,
+            0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00, 0x02,
+        ];
+        let p = Packet::parse(&data).unwrap();
+        assert_eq!(p.header().version(), VERSION_PPTP);
+        assert_eq!(int::ForeignEndian::to_native(p.header().protocol_type), PROTOCOL_PPP);
+        assert_eq!(p.key, Some(0xbeef));
+        assert_eq!(p.sequence, Some(3));
+        assert_eq!(p.ack, Some(2));
+        assert_eq!(header_size(0x0080), HEADER_SIZE);
+
+        let key = nvgre_key(0x123456, 0x78);
+        assert_eq!(key, 0x12345678);
+        assert_eq!(nvgre_vsid(key), 0x123456);
+        assert_eq!(nvgre_flow_id(key), 0x78);
+    }
+}
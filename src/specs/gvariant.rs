@@ -0,0 +1,440 @@
+//! GVariant Serialization Format
+//!
+//! GVariant serializes values of types given as type strings, using the
+//! type codes of D-Bus (see `dbus::TYPE_*`) plus maybe types (`TYPE_MAYBE`).
+//! Unlike D-Bus, the data carries no lengths. Instead, the size of each value
+//! is given by its container, and containers with variable-size children
+//! append framing offsets, which are the end offsets of their children,
+//! relative to the start of the container.
+//!
+//! Every type has an alignment, and fixed-size types have a fixed size (see
+//! `type_info()`). Values are aligned relative to the start of the
+//! serialized data, which itself must be aligned to 8 bytes. The size of
+//! each framing offset depends on the size of the container (see
+//! `offset_size()`). Containers are laid out as follows:
+//!
+//!  * Arrays of fixed-size elements store the elements one after another.
+//!    Arrays of variable-size elements store the aligned elements, followed
+//!    by a framing offset for each element (see `Elements`).
+//!  * Maybes are empty for `Nothing`. Otherwise, they store the value, plus a
+//!    zero byte if the value is of variable size (see `maybe()`).
+//!  * Tuples and dict entries store the aligned members, followed by a
+//!    framing offset for each variable-size member other than the last, in
+//!    reverse order. Tuples of fixed-size members are fixed-size themselves,
+//!    padded to the alignment, and at least 1 byte (see `Members`).
+//!  * Variants store the value, followed by a zero byte, and the type string
+//!    of the value (see `variant()`).
+//!  * Strings, object paths, and signatures store their content followed by
+//!    a 0-terminator (see `string()`).
+//!
+//! Framing offsets are encoded as little-endian. All other multi-byte
+//! integers are encoded in the byte order of the producer, which is
+//! little-endian in common file formats like GVDB and GResource.
+
+use crate::specs::dbus;
+
+/// Maximum Nesting Depth of Containers
+pub const NESTING_MAX: usize = 128;
+
+pub const TYPE_MAYBE: u8 = b'm';
+
+/// Type Information
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TypeInfo {
+    /// Length of the type string.
+    pub length: usize,
+
+    /// Alignment of values of the type.
+    pub alignment: usize,
+
+    /// Size of values of the type, if fixed.
+    pub fixed_size: Option<usize>,
+}
+
+/// Array Element Iterator
+///
+/// Iterate over the serialized elements of an array. As required by the
+/// specification, arrays with invalid framing are treated as empty, and
+/// elements with invalid framing offsets are yielded as empty slices.
+#[derive(Clone, Debug)]
+pub struct Elements<'data> {
+    data: &'data [u8],
+    element: TypeInfo,
+    offsets: usize,
+    offset: usize,
+    index: usize,
+    count: usize,
+}
+
+/// Tuple Member Iterator
+///
+/// Iterate over the serialized members of a tuple or dict entry. As with
+/// `Elements`, members with invalid framing are yielded as empty slices.
+/// Iteration stops at the end of the member types, or at the first invalid
+/// member type.
+#[derive(Clone, Debug)]
+pub struct Members<'data> {
+    data: &'data [u8],
+    types: &'data [u8],
+    offset_size: usize,
+    offset: usize,
+    frames: usize,
+}
+
+impl<'data> Elements<'data> {
+    /// Create array element iterator
+    ///
+    /// Create a new iterator over the elements of the serialized array
+    /// `data`, whose elements have the given type.
+    pub fn new(data: &'data [u8], element: TypeInfo) -> Self {
+        let mut v = Self {
+            data,
+            element,
+            offsets: data.len(),
+            offset: 0,
+            index: 0,
+            count: 0,
+        };
+
+        match element.fixed_size {
+            Some(n) => {
+                if data.len().is_multiple_of(n) {
+                    v.count = data.len() / n;
+                }
+            },
+            None => {
+                let n = offset_size(data.len());
+                let end = read_offset(data, data.len().wrapping_sub(n), n);
+                if let Some(end) = end.filter(|v| n > 0 && *v <= data.len()) {
+                    let size = data.len() - end;
+                    if size.is_multiple_of(n) {
+                        v.offsets = end;
+                        v.count = size / n;
+                    }
+                }
+            },
+        }
+
+        v
+    }
+}
+
+impl<'data> Iterator for Elements<'data> {
+    type Item = &'data [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.count {
+            return None;
+        }
+
+        let start = self.offset.next_multiple_of(self.element.alignment);
+        let end = match self.element.fixed_size {
+            Some(n) => start + n,
+            None => {
+                let n = offset_size(self.data.len());
+                read_offset(self.data, self.offsets + self.index * n, n)?
+            },
+        };
+
+        self.index += 1;
+        self.offset = end;
+        if start <= end && end <= self.offsets {
+            Some(&self.data[start..end])
+        } else {
+            Some(&[])
+        }
+    }
+}
+
+impl<'data> Members<'data> {
+    /// Create tuple member iterator
+    ///
+    /// Create a new iterator over the members of the serialized tuple or
+    /// dict entry `data`, whose member types are given by the type strings
+    /// concatenated in `types`, without the enclosing parentheses or braces.
+    pub fn new(data: &'data [u8], types: &'data [u8]) -> Self {
+        Self {
+            data,
+            types,
+            offset_size: offset_size(data.len()),
+            offset: 0,
+            frames: 0,
+        }
+    }
+}
+
+impl<'data> Iterator for Members<'data> {
+    type Item = &'data [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let info = type_info(self.types)?;
+        self.types = &self.types[info.length..];
+
+        let limit = self.data.len().saturating_sub(self.frames * self.offset_size);
+        let start = self.offset.next_multiple_of(info.alignment);
+        let end = match info.fixed_size {
+            Some(n) => start + n,
+            None if self.types.is_empty() => limit,
+            None => {
+                self.frames += 1;
+                let at = self.data.len().wrapping_sub(self.frames * self.offset_size);
+                read_offset(self.data, at, self.offset_size).unwrap_or(usize::MAX)
+            },
+        };
+
+        self.offset = end;
+        if start <= end && end <= limit {
+            Some(&self.data[start..end])
+        } else {
+            Some(&[])
+        }
+    }
+}
+
+/// Parse type string
+///
+/// Return the type information of the single complete type at the start of
+/// `signature`, or `None` if it does not start with a valid single complete
+/// type.
+pub fn type_info(signature: &[u8]) -> Option<TypeInfo> {
+    // Parse a single type, tracking the nesting depth.
+    fn parse(s: &[u8], depth: usize) -> Option<TypeInfo> {
+        if depth > NESTING_MAX {
+            return None;
+        }
+
+        let fixed = |n: usize| Some(TypeInfo { length: 1, alignment: n, fixed_size: Some(n) });
+        let variable = |n: usize| Some(TypeInfo { length: 1, alignment: n, fixed_size: None });
+
+        match *s.first()? {
+            dbus::TYPE_BYTE | dbus::TYPE_BOOLEAN => fixed(1),
+            dbus::TYPE_INT16 | dbus::TYPE_UINT16 => fixed(2),
+            dbus::TYPE_INT32 | dbus::TYPE_UINT32 | dbus::TYPE_UNIX_FD => fixed(4),
+            dbus::TYPE_INT64 | dbus::TYPE_UINT64 | dbus::TYPE_DOUBLE => fixed(8),
+            dbus::TYPE_STRING | dbus::TYPE_OBJECT_PATH | dbus::TYPE_SIGNATURE => variable(1),
+            dbus::TYPE_VARIANT => variable(8),
+            dbus::TYPE_ARRAY | TYPE_MAYBE => {
+                let v = parse(&s[1..], depth + 1)?;
+                Some(TypeInfo { length: 1 + v.length, alignment: v.alignment, fixed_size: None })
+            },
+            dbus::TYPE_STRUCT_BEGIN | dbus::TYPE_DICT_ENTRY_BEGIN => {
+                let close = match s[0] {
+                    dbus::TYPE_STRUCT_BEGIN => dbus::TYPE_STRUCT_END,
+                    _ => dbus::TYPE_DICT_ENTRY_END,
+                };
+                let mut length = 1;
+                let mut alignment = 1;
+                let mut size = Some(0usize);
+                let mut count = 0;
+                while *s.get(length)? != close {
+                    let v = parse(&s[length..], depth + 1)?;
+                    if close == dbus::TYPE_DICT_ENTRY_END && count == 0 && v.length != 1 {
+                        return None;
+                    }
+                    length += v.length;
+                    alignment = alignment.max(v.alignment);
+                    size = match (size, v.fixed_size) {
+                        (Some(o), Some(n)) => Some(o.next_multiple_of(v.alignment) + n),
+                        _ => None,
+                    };
+                    count += 1;
+                }
+                if close == dbus::TYPE_DICT_ENTRY_END && count != 2 {
+                    return None;
+                }
+                Some(TypeInfo {
+                    length: length + 1,
+                    alignment,
+                    fixed_size: size.map(|v| v.next_multiple_of(alignment).max(1)),
+                })
+            },
+            _ => None,
+        }
+    }
+
+    parse(signature, 0)
+}
+
+/// Return framing offset size
+///
+/// Return the size of each framing offset of a serialized container of the
+/// given total size, including its framing offsets.
+pub fn offset_size(container_size: usize) -> usize {
+    match container_size {
+        0 => 0,
+        0x1..=0xff => 1,
+        0x100..=0xffff => 2,
+        0x10000..=0xffff_ffff => 4,
+        _ => 8,
+    }
+}
+
+/// Select framing offset size
+///
+/// Return the size of each framing offset to use when serializing a
+/// container whose content has the given size, and which needs `count`
+/// framing offsets. This is the smallest size for which the total size of
+/// the container maps back to it via `offset_size()`.
+pub fn select_offset_size(content_size: usize, count: usize) -> usize {
+    if count == 0 {
+        return 0;
+    }
+
+    [1, 2, 4]
+        .into_iter()
+        .find(|n| offset_size(content_size + count * n) == *n)
+        .unwrap_or(8)
+}
+
+/// Read framing offset
+///
+/// Read the little-endian framing offset of the given size at `at`.
+pub fn read_offset(data: &[u8], at: usize, size: usize) -> Option<usize> {
+    let v = data.get(at..at.checked_add(size)?)?;
+    Some(v.iter().rev().fold(0u64, |acc, b| (acc << 8) | *b as u64) as usize)
+}
+
+/// Decode maybe
+///
+/// Return the serialized value of the serialized maybe `data`, whose value
+/// has the given type, or `None` for `Nothing` or invalid framing.
+pub fn maybe(data: &[u8], value: TypeInfo) -> Option<&[u8]> {
+    match value.fixed_size {
+        Some(n) if data.len() == n => Some(data),
+        Some(_) => None,
+        None => data.split_last().map(|v| v.1),
+    }
+}
+
+/// Decode variant
+///
+/// Split the serialized variant `data` into the serialized value and its
+/// type string. Return `None` if the type string is missing, or is not a
+/// single complete type.
+pub fn variant(data: &[u8]) -> Option<(&[u8], &[u8])> {
+    let n = data.iter().rposition(|v| *v == 0)?;
+    let signature = &data[n + 1..];
+    if type_info(signature)?.length != signature.len() {
+        return None;
+    }
+    Some((&data[..n], signature))
+}
+
+/// Decode string
+///
+/// Return the content of the serialized string, object path, or signature
+/// `data`, or `None` if it is not 0-terminated, or contains other
+/// 0-bytes.
+pub fn string(data: &[u8]) -> Option<&[u8]> {
+    let (last, v) = data.split_last()?;
+    if *last != 0 || v.contains(&0) {
+        return None;
+    }
+    Some(v)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Array of strings from the specification.
+    const STRINGS: [u8; 23] = [
+        0x69, 0x00, 0x63, 0x61, 0x6e, 0x00, 0x68, 0x61,
+        0x73, 0x00, 0x73, 0x74, 0x72, 0x69, 0x6e, 0x67,
+        0x73, 0x3f, 0x00, 0x02, 0x06, 0x0a, 0x13,
+    ];
+
+    // Dictionary `a{si}` of `{"hi": -2, "bye": -1}` from the specification.
+    const DICTIONARY: [u8; 23] = [
+        0x68, 0x69, 0x00, 0x00, 0xfe, 0xff, 0xff, 0xff,
+        0x03, 0x00, 0x00, 0x00, 0x62, 0x79, 0x65, 0x00,
+        0xff, 0xff, 0xff, 0xff, 0x04, 0x09, 0x15,
+    ];
+
+    // Verify type information and framing offset sizes.
+    #[test]
+    fn verify_types() {
+        let info = |v: &[u8]| type_info(v).map(|v| (v.length, v.alignment, v.fixed_size));
+        assert_eq!(info(b"y"), Some((1, 1, Some(1))));
+        assert_eq!(info(b"b"), Some((1, 1, Some(1))));
+        assert_eq!(info(b"t"), Some((1, 8, Some(8))));
+        assert_eq!(info(b"s"), Some((1, 1, None)));
+        assert_eq!(info(b"v"), Some((1, 8, None)));
+        assert_eq!(info(b"ai"), Some((2, 4, None)));
+        assert_eq!(info(b"mt"), Some((2, 8, None)));
+        assert_eq!(info(b"()"), Some((2, 1, Some(1))));
+        assert_eq!(info(b"(yi)"), Some((4, 4, Some(8))));
+        assert_eq!(info(b"(iy)"), Some((4, 4, Some(8))));
+        assert_eq!(info(b"(yy)s"), Some((4, 1, Some(2))));
+        assert_eq!(info(b"(si)"), Some((4, 4, None)));
+        assert_eq!(info(b"a{sv}"), Some((5, 8, None)));
+        assert_eq!(info(b"{yy}"), Some((4, 1, Some(2))));
+        assert_eq!(info(b"{(y)y}"), None);
+        assert_eq!(info(b"{y}"), None);
+        assert_eq!(info(b"(y"), None);
+        assert_eq!(info(b"a"), None);
+
+        assert_eq!(offset_size(0), 0);
+        assert_eq!(offset_size(0xff), 1);
+        assert_eq!(offset_size(0x100), 2);
+        assert_eq!(offset_size(0x10000), 4);
+        assert_eq!(select_offset_size(19, 4), 1);
+        assert_eq!(select_offset_size(0xfc, 4), 2);
+        assert_eq!(select_offset_size(0xfb, 4), 1);
+        assert_eq!(select_offset_size(0xfffe, 1), 4);
+        assert_eq!(select_offset_size(10, 0), 0);
+    }
+
+    // Decode arrays, tuples, maybes, and variants of the specification.
+    #[test]
+    fn verify_containers() {
+        let s = type_info(b"s").unwrap();
+        let mut e = Elements::new(&STRINGS, s);
+        assert_eq!(e.next().and_then(string), Some(&b"i"[..]));
+        assert_eq!(e.next().and_then(string), Some(&b"can"[..]));
+        assert_eq!(e.next().and_then(string), Some(&b"has"[..]));
+        assert_eq!(e.next().and_then(string), Some(&b"strings?"[..]));
+        assert_eq!(e.next(), None);
+
+        let mut v = STRINGS;
+        v[22] = 0x18;
+        assert_eq!(Elements::new(&v, s).count(), 0);
+        v[21] = 0x01;
+        v[22] = 0x13;
+        assert_eq!(Elements::new(&v, s).nth(2), Some(&[][..]));
+        assert_eq!(Elements::new(&[], s).count(), 0);
+        assert_eq!(Elements::new(&[0x01, 0x00], type_info(b"b").unwrap()).count(), 2);
+        assert_eq!(Elements::new(&[0x01, 0x00, 0x00], type_info(b"n").unwrap()).count(), 0);
+
+        let entry = type_info(b"{si}").unwrap();
+        let mut e = Elements::new(&DICTIONARY, entry);
+        let mut m = Members::new(e.next().unwrap(), b"si");
+        assert_eq!(m.next().and_then(string), Some(&b"hi"[..]));
+        assert_eq!(m.next(), Some(&(-2i32).to_le_bytes()[..]));
+        assert_eq!(m.next(), None);
+        let mut m = Members::new(e.next().unwrap(), b"si");
+        assert_eq!(m.next().and_then(string), Some(&b"bye"[..]));
+        assert_eq!(m.next(), Some(&(-1i32).to_le_bytes()[..]));
+        assert_eq!(e.next(), None);
+
+        let tuple = [0x66, 0x6f, 0x6f, 0x00, 0xff, 0xff, 0xff, 0xff, 0x04];
+        let mut m = Members::new(&tuple, b"si");
+        assert_eq!(m.next(), Some(&b"foo\0"[..]));
+        assert_eq!(m.next(), Some(&[0xff; 4][..]));
+
+        let mut m = Members::new(&tuple[..8], b"si");
+        assert_eq!(m.next(), Some(&[][..]));
+
+        assert_eq!(maybe(b"hello world\0\0", s), Some(&b"hello world\0"[..]));
+        assert_eq!(maybe(b"", s), None);
+        assert_eq!(maybe(&[0x2a, 0x00], type_info(b"n").unwrap()), Some(&[0x2a, 0x00][..]));
+        assert_eq!(maybe(&[0x2a], type_info(b"n").unwrap()), None);
+
+        let v = [0xff, 0xff, 0xff, 0xff, 0x00, 0x69];
+        assert_eq!(variant(&v), Some((&[0xff; 4][..], &b"i"[..])));
+        assert_eq!(variant(b"\x01\x00(yy"), None);
+        assert_eq!(variant(b"\x01\x02"), None);
+        assert_eq!(string(b"a\0b\0"), None);
+        assert_eq!(string(b"ab"), None);
+    }
+}
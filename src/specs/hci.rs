@@ -0,0 +1,920 @@
+//! Bluetooth Host Controller Interface
+//!
+//! HCI packets are exchanged between a Bluetooth host and its controller.
+//! Commands (`CommandHeader`) are sent by the host, carrying a 16-bit opcode
+//! that combines a 6-bit group (`OGF_*`) and a 10-bit command (see
+//! `opcode()`). Events (`EventHeader`) are sent by the controller, carrying
+//! an event code (`EVENT_*`). LE events share `EVENT_LE_META`, and carry a
+//! subevent code (`LE_*`) as first parameter (see `le_meta()`). ACL, SCO, and
+//! ISO data packets carry a 12-bit connection handle combined with flags.
+//!
+//! On serial transports (H4) and in captures with link type
+//! `pcap::LINKTYPE_BLUETOOTH_HCI_H4`, each packet is preceded by a 1-byte
+//! packet indicator (`PACKET_*`). The packet size follows from its header
+//! (see `packet_size()`).
+//!
+//! LE advertising report events carry one or more reports (see
+//! `AdvertisingReports` and `ExtendedAdvertisingReports`), whose data is a
+//! sequence of AD structures (see `AdStructures`), each with a 1-byte length
+//! (including the type) and 1-byte type (`AD_*`).
+//!
+//! Unlike most network protocols, all multi-byte integers are encoded as
+//! little-endian, and Bluetooth device addresses are transmitted least
+//! significant byte first.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::int;
+
+pub const COMMAND_HEADER_SIZE: usize = 3;
+pub const EVENT_HEADER_SIZE: usize = 2;
+pub const ACL_HEADER_SIZE: usize = 4;
+pub const SCO_HEADER_SIZE: usize = 3;
+pub const ISO_HEADER_SIZE: usize = 4;
+pub const ADDRESS_SIZE: usize = 6;
+
+/// Maximum Size of Legacy Advertising Data
+pub const ADVERTISING_DATA_MAX: usize = 31;
+
+pub const PACKET_COMMAND: u8 = 0x01;
+pub const PACKET_ACL: u8 = 0x02;
+pub const PACKET_SCO: u8 = 0x03;
+pub const PACKET_EVENT: u8 = 0x04;
+pub const PACKET_ISO: u8 = 0x05;
+
+pub const OCF_MASK: u16 = 0x03ff;
+pub const OGF_SHIFT: u32 = 10;
+
+pub const OGF_LINK_CONTROL: u16 = 0x01;
+pub const OGF_LINK_POLICY: u16 = 0x02;
+pub const OGF_CONTROLLER: u16 = 0x03;
+pub const OGF_INFORMATIONAL: u16 = 0x04;
+pub const OGF_STATUS: u16 = 0x05;
+pub const OGF_TESTING: u16 = 0x06;
+pub const OGF_LE: u16 = 0x08;
+pub const OGF_VENDOR: u16 = 0x3f;
+
+pub const OPCODE_INQUIRY: u16 = opcode(OGF_LINK_CONTROL, 0x001);
+pub const OPCODE_CREATE_CONNECTION: u16 = opcode(OGF_LINK_CONTROL, 0x005);
+pub const OPCODE_DISCONNECT: u16 = opcode(OGF_LINK_CONTROL, 0x006);
+pub const OPCODE_REMOTE_NAME_REQUEST: u16 = opcode(OGF_LINK_CONTROL, 0x019);
+pub const OPCODE_SET_EVENT_MASK: u16 = opcode(OGF_CONTROLLER, 0x001);
+pub const OPCODE_RESET: u16 = opcode(OGF_CONTROLLER, 0x003);
+pub const OPCODE_WRITE_LOCAL_NAME: u16 = opcode(OGF_CONTROLLER, 0x013);
+pub const OPCODE_READ_LOCAL_NAME: u16 = opcode(OGF_CONTROLLER, 0x014);
+pub const OPCODE_WRITE_SCAN_ENABLE: u16 = opcode(OGF_CONTROLLER, 0x01a);
+pub const OPCODE_READ_LOCAL_VERSION: u16 = opcode(OGF_INFORMATIONAL, 0x001);
+pub const OPCODE_READ_LOCAL_COMMANDS: u16 = opcode(OGF_INFORMATIONAL, 0x002);
+pub const OPCODE_READ_LOCAL_FEATURES: u16 = opcode(OGF_INFORMATIONAL, 0x003);
+pub const OPCODE_READ_BUFFER_SIZE: u16 = opcode(OGF_INFORMATIONAL, 0x005);
+pub const OPCODE_READ_BD_ADDR: u16 = opcode(OGF_INFORMATIONAL, 0x009);
+pub const OPCODE_READ_RSSI: u16 = opcode(OGF_STATUS, 0x005);
+pub const OPCODE_LE_SET_EVENT_MASK: u16 = opcode(OGF_LE, 0x001);
+pub const OPCODE_LE_READ_BUFFER_SIZE: u16 = opcode(OGF_LE, 0x002);
+pub const OPCODE_LE_SET_RANDOM_ADDRESS: u16 = opcode(OGF_LE, 0x005);
+pub const OPCODE_LE_SET_ADVERTISING_PARAMETERS: u16 = opcode(OGF_LE, 0x006);
+pub const OPCODE_LE_SET_ADVERTISING_DATA: u16 = opcode(OGF_LE, 0x008);
+pub const OPCODE_LE_SET_SCAN_RESPONSE_DATA: u16 = opcode(OGF_LE, 0x009);
+pub const OPCODE_LE_SET_ADVERTISING_ENABLE: u16 = opcode(OGF_LE, 0x00a);
+pub const OPCODE_LE_SET_SCAN_PARAMETERS: u16 = opcode(OGF_LE, 0x00b);
+pub const OPCODE_LE_SET_SCAN_ENABLE: u16 = opcode(OGF_LE, 0x00c);
+pub const OPCODE_LE_CREATE_CONNECTION: u16 = opcode(OGF_LE, 0x00d);
+pub const OPCODE_LE_CREATE_CONNECTION_CANCEL: u16 = opcode(OGF_LE, 0x00e);
+pub const OPCODE_LE_CONNECTION_UPDATE: u16 = opcode(OGF_LE, 0x013);
+pub const OPCODE_LE_START_ENCRYPTION: u16 = opcode(OGF_LE, 0x019);
+pub const OPCODE_LE_SET_EXTENDED_ADVERTISING_PARAMETERS: u16 = opcode(OGF_LE, 0x036);
+pub const OPCODE_LE_SET_EXTENDED_ADVERTISING_DATA: u16 = opcode(OGF_LE, 0x037);
+pub const OPCODE_LE_SET_EXTENDED_ADVERTISING_ENABLE: u16 = opcode(OGF_LE, 0x039);
+pub const OPCODE_LE_SET_EXTENDED_SCAN_PARAMETERS: u16 = opcode(OGF_LE, 0x041);
+pub const OPCODE_LE_SET_EXTENDED_SCAN_ENABLE: u16 = opcode(OGF_LE, 0x042);
+pub const OPCODE_LE_EXTENDED_CREATE_CONNECTION: u16 = opcode(OGF_LE, 0x043);
+
+pub const EVENT_INQUIRY_COMPLETE: u8 = 0x01;
+pub const EVENT_INQUIRY_RESULT: u8 = 0x02;
+pub const EVENT_CONNECTION_COMPLETE: u8 = 0x03;
+pub const EVENT_CONNECTION_REQUEST: u8 = 0x04;
+pub const EVENT_DISCONNECTION_COMPLETE: u8 = 0x05;
+pub const EVENT_AUTHENTICATION_COMPLETE: u8 = 0x06;
+pub const EVENT_REMOTE_NAME_REQUEST_COMPLETE: u8 = 0x07;
+pub const EVENT_ENCRYPTION_CHANGE: u8 = 0x08;
+pub const EVENT_READ_REMOTE_VERSION_COMPLETE: u8 = 0x0c;
+pub const EVENT_COMMAND_COMPLETE: u8 = 0x0e;
+pub const EVENT_COMMAND_STATUS: u8 = 0x0f;
+pub const EVENT_HARDWARE_ERROR: u8 = 0x10;
+pub const EVENT_NUMBER_OF_COMPLETED_PACKETS: u8 = 0x13;
+pub const EVENT_ENCRYPTION_KEY_REFRESH_COMPLETE: u8 = 0x30;
+pub const EVENT_LE_META: u8 = 0x3e;
+pub const EVENT_VENDOR: u8 = 0xff;
+
+pub const LE_CONNECTION_COMPLETE: u8 = 0x01;
+pub const LE_ADVERTISING_REPORT: u8 = 0x02;
+pub const LE_CONNECTION_UPDATE_COMPLETE: u8 = 0x03;
+pub const LE_READ_REMOTE_FEATURES_COMPLETE: u8 = 0x04;
+pub const LE_LONG_TERM_KEY_REQUEST: u8 = 0x05;
+pub const LE_DATA_LENGTH_CHANGE: u8 = 0x07;
+pub const LE_ENHANCED_CONNECTION_COMPLETE: u8 = 0x0a;
+pub const LE_DIRECTED_ADVERTISING_REPORT: u8 = 0x0b;
+pub const LE_PHY_UPDATE_COMPLETE: u8 = 0x0c;
+pub const LE_EXTENDED_ADVERTISING_REPORT: u8 = 0x0d;
+pub const LE_ADVERTISING_SET_TERMINATED: u8 = 0x12;
+
+pub const STATUS_SUCCESS: u8 = 0x00;
+pub const STATUS_UNKNOWN_COMMAND: u8 = 0x01;
+pub const STATUS_UNKNOWN_CONNECTION: u8 = 0x02;
+pub const STATUS_HARDWARE_FAILURE: u8 = 0x03;
+pub const STATUS_PAGE_TIMEOUT: u8 = 0x04;
+pub const STATUS_AUTHENTICATION_FAILURE: u8 = 0x05;
+pub const STATUS_PIN_OR_KEY_MISSING: u8 = 0x06;
+pub const STATUS_MEMORY_EXCEEDED: u8 = 0x07;
+pub const STATUS_CONNECTION_TIMEOUT: u8 = 0x08;
+pub const STATUS_COMMAND_DISALLOWED: u8 = 0x0c;
+pub const STATUS_INVALID_PARAMETERS: u8 = 0x12;
+pub const STATUS_REMOTE_USER_TERMINATED: u8 = 0x13;
+pub const STATUS_LOCAL_HOST_TERMINATED: u8 = 0x16;
+pub const STATUS_UNSUPPORTED_REMOTE_FEATURE: u8 = 0x1a;
+pub const STATUS_UNSPECIFIED_ERROR: u8 = 0x1f;
+pub const STATUS_CONNECTION_FAILED_TO_ESTABLISH: u8 = 0x3e;
+
+pub const HANDLE_MASK: u16 = 0x0fff;
+pub const HANDLE_FLAGS_SHIFT: u32 = 12;
+
+pub const ACL_PB_MASK: u16 = 0x3000;
+pub const ACL_PB_FIRST_NON_FLUSHABLE: u16 = 0x0000;
+pub const ACL_PB_CONTINUING: u16 = 0x1000;
+pub const ACL_PB_FIRST_FLUSHABLE: u16 = 0x2000;
+pub const ACL_BC_MASK: u16 = 0xc000;
+
+pub const SCO_PACKET_STATUS_MASK: u16 = 0x3000;
+
+pub const ISO_PB_MASK: u16 = 0x3000;
+pub const ISO_TIMESTAMP: u16 = 0x4000;
+pub const ISO_LENGTH_MASK: u16 = 0x3fff;
+
+pub const ADDRESS_PUBLIC: u8 = 0x00;
+pub const ADDRESS_RANDOM: u8 = 0x01;
+pub const ADDRESS_PUBLIC_IDENTITY: u8 = 0x02;
+pub const ADDRESS_RANDOM_IDENTITY: u8 = 0x03;
+pub const ADDRESS_ANONYMOUS: u8 = 0xff;
+
+pub const ADVERTISING_IND: u8 = 0x00;
+pub const ADVERTISING_DIRECT_IND: u8 = 0x01;
+pub const ADVERTISING_SCAN_IND: u8 = 0x02;
+pub const ADVERTISING_NONCONN_IND: u8 = 0x03;
+pub const ADVERTISING_SCAN_RSP: u8 = 0x04;
+
+pub const EXTENDED_CONNECTABLE: u16 = 0x0001;
+pub const EXTENDED_SCANNABLE: u16 = 0x0002;
+pub const EXTENDED_DIRECTED: u16 = 0x0004;
+pub const EXTENDED_SCAN_RESPONSE: u16 = 0x0008;
+pub const EXTENDED_LEGACY: u16 = 0x0010;
+pub const EXTENDED_DATA_STATUS_MASK: u16 = 0x0060;
+pub const EXTENDED_DATA_STATUS_SHIFT: u32 = 5;
+
+pub const PHY_NONE: u8 = 0x00;
+pub const PHY_LE_1M: u8 = 0x01;
+pub const PHY_LE_2M: u8 = 0x02;
+pub const PHY_LE_CODED: u8 = 0x03;
+
+/// RSSI Value of Unavailable Measurements
+pub const RSSI_UNAVAILABLE: i8 = 127;
+
+pub const AD_FLAGS: u8 = 0x01;
+pub const AD_INCOMPLETE_UUID16: u8 = 0x02;
+pub const AD_COMPLETE_UUID16: u8 = 0x03;
+pub const AD_INCOMPLETE_UUID32: u8 = 0x04;
+pub const AD_COMPLETE_UUID32: u8 = 0x05;
+pub const AD_INCOMPLETE_UUID128: u8 = 0x06;
+pub const AD_COMPLETE_UUID128: u8 = 0x07;
+pub const AD_SHORT_NAME: u8 = 0x08;
+pub const AD_COMPLETE_NAME: u8 = 0x09;
+pub const AD_TX_POWER: u8 = 0x0a;
+pub const AD_CLASS_OF_DEVICE: u8 = 0x0d;
+pub const AD_SLAVE_CONNECTION_INTERVAL: u8 = 0x12;
+pub const AD_SERVICE_DATA_UUID16: u8 = 0x16;
+pub const AD_PUBLIC_TARGET_ADDRESS: u8 = 0x17;
+pub const AD_RANDOM_TARGET_ADDRESS: u8 = 0x18;
+pub const AD_APPEARANCE: u8 = 0x19;
+pub const AD_ADVERTISING_INTERVAL: u8 = 0x1a;
+pub const AD_SERVICE_DATA_UUID32: u8 = 0x20;
+pub const AD_SERVICE_DATA_UUID128: u8 = 0x21;
+pub const AD_URI: u8 = 0x24;
+pub const AD_MESH_MESSAGE: u8 = 0x2a;
+pub const AD_MESH_BEACON: u8 = 0x2b;
+pub const AD_MANUFACTURER_DATA: u8 = 0xff;
+
+pub const AD_FLAG_LE_LIMITED_DISCOVERABLE: u8 = 0x01;
+pub const AD_FLAG_LE_GENERAL_DISCOVERABLE: u8 = 0x02;
+pub const AD_FLAG_BR_EDR_NOT_SUPPORTED: u8 = 0x04;
+pub const AD_FLAG_SIMULTANEOUS_LE_BR_EDR: u8 = 0x08;
+
+/// Command Header
+///
+/// Since the size of the header is not a multiple of the alignment of
+/// `opcode`, this structure is packed.
+#[repr(C, packed)]
+pub struct CommandHeader {
+    /// Opcode combining group and command (see `opcode()`).
+    pub opcode: int::u16le,
+
+    /// Size of the parameters in bytes.
+    pub length: u8,
+}
+
+/// Event Header
+#[repr(C)]
+pub struct EventHeader {
+    /// Event code (`EVENT_*`).
+    pub event_code: u8,
+
+    /// Size of the parameters in bytes.
+    pub length: u8,
+}
+
+/// ACL Data Header
+#[repr(C)]
+pub struct AclHeader {
+    /// Connection handle combined with `ACL_PB_*` and broadcast flags.
+    pub handle: int::u16le,
+
+    /// Size of the data in bytes.
+    pub length: int::u16le,
+}
+
+/// SCO Data Header
+///
+/// Since the size of the header is not a multiple of the alignment of
+/// `handle`, this structure is packed.
+#[repr(C, packed)]
+pub struct ScoHeader {
+    /// Connection handle combined with the packet status flags.
+    pub handle: int::u16le,
+
+    /// Size of the data in bytes.
+    pub length: u8,
+}
+
+/// ISO Data Header
+#[repr(C)]
+pub struct IsoHeader {
+    /// Connection handle combined with `ISO_PB_*` and `ISO_TIMESTAMP`.
+    pub handle: int::u16le,
+
+    /// Size of the data in bytes, in the lower 14 bits.
+    pub length: int::u16le,
+}
+
+/// Command Complete Event Parameters
+///
+/// The parameters are followed by the return parameters of the command,
+/// which usually start with a status code.
+///
+/// Since the size of the parameters is not a multiple of the alignment of
+/// `opcode`, this structure is packed.
+#[repr(C, packed)]
+pub struct CommandComplete {
+    /// Number of commands the host may send.
+    pub num_packets: u8,
+
+    /// Opcode of the completed command.
+    pub opcode: int::u16le,
+}
+
+/// Command Status Event Parameters
+#[repr(C)]
+pub struct CommandStatus {
+    /// Status code (`STATUS_*`).
+    pub status: u8,
+
+    /// Number of commands the host may send.
+    pub num_packets: u8,
+
+    /// Opcode of the pending command.
+    pub opcode: int::u16le,
+}
+
+/// Disconnection Complete Event Parameters
+///
+/// Since `handle` is not naturally aligned, this structure is packed.
+#[repr(C, packed)]
+pub struct DisconnectionComplete {
+    /// Status code (`STATUS_*`).
+    pub status: u8,
+
+    /// Connection handle.
+    pub handle: int::u16le,
+
+    /// Reason of the disconnection (`STATUS_*`).
+    pub reason: u8,
+}
+
+/// LE Connection Complete Event Parameters
+///
+/// The parameters follow the subevent code. Since `handle` is not naturally
+/// aligned, this structure is packed.
+#[repr(C, packed)]
+pub struct LeConnectionComplete {
+    /// Status code (`STATUS_*`).
+    pub status: u8,
+
+    /// Connection handle.
+    pub handle: int::u16le,
+
+    /// Role of the local device, 0 for central and 1 for peripheral.
+    pub role: u8,
+
+    /// Address type of the peer (`ADDRESS_*`).
+    pub peer_address_type: u8,
+
+    /// Address of the peer.
+    pub peer_address: [u8; 6],
+
+    /// Connection interval in units of 1.25 milliseconds.
+    pub interval: int::u16le,
+
+    /// Peripheral latency in connection events.
+    pub latency: int::u16le,
+
+    /// Supervision timeout in units of 10 milliseconds.
+    pub supervision_timeout: int::u16le,
+
+    /// Clock accuracy of the central device.
+    pub central_clock_accuracy: u8,
+}
+
+/// Extended Advertising Report Header
+///
+/// Each report of an `LE_EXTENDED_ADVERTISING_REPORT` event starts with this
+/// header, followed by `data_length` bytes of advertising data.
+#[repr(C)]
+pub struct ExtendedAdvertisingReport {
+    /// Event type (`EXTENDED_*`).
+    pub event_type: int::u16le,
+
+    /// Address type of the advertiser (`ADDRESS_*`).
+    pub address_type: u8,
+
+    /// Address of the advertiser.
+    pub address: [u8; 6],
+
+    /// PHY of the primary advertising channel (`PHY_*`).
+    pub primary_phy: u8,
+
+    /// PHY of the secondary advertising channel (`PHY_*`).
+    pub secondary_phy: u8,
+
+    /// Advertising set identifier, or `0xff` if none.
+    pub advertising_sid: u8,
+
+    /// Transmit power in dBm, or 127 if unavailable.
+    pub tx_power: i8,
+
+    /// Signal strength in dBm, or `RSSI_UNAVAILABLE`.
+    pub rssi: i8,
+
+    /// Interval of periodic advertising in units of 1.25 milliseconds, or 0.
+    pub periodic_advertising_interval: int::u16le,
+
+    /// Address type of the target of directed advertising.
+    pub direct_address_type: u8,
+
+    /// Address of the target of directed advertising.
+    pub direct_address: [u8; 6],
+
+    /// Size of the advertising data in bytes.
+    pub data_length: u8,
+}
+
+/// LE Advertising Report Reference
+#[derive(Clone, Copy, Debug)]
+pub struct AdvertisingReport<'data> {
+    /// Event type (`ADVERTISING_*`).
+    pub event_type: u8,
+
+    /// Address type of the advertiser (`ADDRESS_*`).
+    pub address_type: u8,
+
+    /// Address of the advertiser.
+    pub address: [u8; 6],
+
+    /// Advertising data.
+    pub data: &'data [u8],
+
+    /// Signal strength in dBm, or `RSSI_UNAVAILABLE`.
+    pub rssi: i8,
+}
+
+/// LE Advertising Report Iterator
+///
+/// Iterate over the reports of an `LE_ADVERTISING_REPORT` event. Each
+/// report is encoded as a sequence of its fields, as done by common host
+/// stacks. Iteration stops at the first report that exceeds the data.
+#[derive(Clone, Debug)]
+pub struct AdvertisingReports<'data> {
+    data: &'data [u8],
+    count: u8,
+}
+
+/// LE Extended Advertising Report Iterator
+///
+/// Iterate over the reports of an `LE_EXTENDED_ADVERTISING_REPORT` event,
+/// yielding the header and advertising data of each report. Iteration stops
+/// at the first report that exceeds the data.
+#[derive(Clone, Debug)]
+pub struct ExtendedAdvertisingReports<'data> {
+    data: &'data [u8],
+    count: u8,
+}
+
+/// AD Structure Iterator
+///
+/// Iterate over the AD structures of advertising data, yielding the type
+/// and data of each structure. Iteration stops at the first structure with a
+/// length of 0, which starts the padding of the advertising data, and at the
+/// first structure that exceeds the data.
+#[derive(Clone, Debug)]
+pub struct AdStructures<'data> {
+    data: &'data [u8],
+}
+
+impl CommandHeader {
+    /// Import a header from a byte slice
+    ///
+    /// Create a new header from data copied from a byte slice. No byte-order
+    /// conversions are applied.
+    pub fn from_bytes(data: &[u8; 3]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 3], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl EventHeader {
+    /// Import a header from a byte slice
+    ///
+    /// Create a new header from data copied from a byte slice. No byte-order
+    /// conversions are applied.
+    pub fn from_bytes(data: &[u8; 2]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 2], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl AclHeader {
+    /// Import a header from a byte slice
+    ///
+    /// Create a new header from data copied from a byte slice. No byte-order
+    /// conversions are applied.
+    pub fn from_bytes(data: &[u8; 4]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 4], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl ScoHeader {
+    /// Import a header from a byte slice
+    ///
+    /// Create a new header from data copied from a byte slice. No byte-order
+    /// conversions are applied.
+    pub fn from_bytes(data: &[u8; 3]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 3], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl IsoHeader {
+    /// Import a header from a byte slice
+    ///
+    /// Create a new header from data copied from a byte slice. No byte-order
+    /// conversions are applied.
+    pub fn from_bytes(data: &[u8; 4]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 4], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl CommandComplete {
+    /// Import parameters from a byte slice
+    ///
+    /// Create new parameters from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 3]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 3], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl CommandStatus {
+    /// Import parameters from a byte slice
+    ///
+    /// Create new parameters from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 4]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 4], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl DisconnectionComplete {
+    /// Import parameters from a byte slice
+    ///
+    /// Create new parameters from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 4]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 4], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl LeConnectionComplete {
+    /// Import parameters from a byte slice
+    ///
+    /// Create new parameters from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 18]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 18], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl ExtendedAdvertisingReport {
+    /// Import a header from a byte slice
+    ///
+    /// Create a new header from data copied from a byte slice. No byte-order
+    /// conversions are applied.
+    pub fn from_bytes(data: &[u8; 24]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 24], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl<'data> AdvertisingReports<'data> {
+    /// Create advertising report iterator
+    ///
+    /// Create a new iterator over the reports of an `LE_ADVERTISING_REPORT`
+    /// event. `data` must start with the number of reports, right after the
+    /// subevent code.
+    pub fn new(data: &'data [u8]) -> Self {
+        match data.split_first() {
+            Some((count, data)) => Self { data, count: *count },
+            None => Self { data, count: 0 },
+        }
+    }
+
+    /// Return remaining data
+    ///
+    /// Return the data that has not been consumed by the iterator.
+    pub fn remainder(&self) -> &'data [u8] {
+        self.data
+    }
+}
+
+impl<'data> Iterator for AdvertisingReports<'data> {
+    type Item = AdvertisingReport<'data>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.count == 0 || self.data.len() < 9 {
+            return None;
+        }
+
+        let n = self.data[8] as usize;
+        let rssi = *self.data.get(9 + n)?;
+        let v = AdvertisingReport {
+            event_type: self.data[0],
+            address_type: self.data[1],
+            address: self.data[2..8].try_into().ok()?,
+            data: &self.data[9..9 + n],
+            rssi: rssi as i8,
+        };
+        self.data = &self.data[10 + n..];
+        self.count -= 1;
+        Some(v)
+    }
+}
+
+impl<'data> ExtendedAdvertisingReports<'data> {
+    /// Create extended advertising report iterator
+    ///
+    /// Create a new iterator over the reports of an
+    /// `LE_EXTENDED_ADVERTISING_REPORT` event. `data` must start with the
+    /// number of reports, right after the subevent code.
+    pub fn new(data: &'data [u8]) -> Self {
+        match data.split_first() {
+            Some((count, data)) => Self { data, count: *count },
+            None => Self { data, count: 0 },
+        }
+    }
+
+    /// Return remaining data
+    ///
+    /// Return the data that has not been consumed by the iterator.
+    pub fn remainder(&self) -> &'data [u8] {
+        self.data
+    }
+}
+
+impl<'data> Iterator for ExtendedAdvertisingReports<'data> {
+    type Item = (ExtendedAdvertisingReport, &'data [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.count == 0 {
+            return None;
+        }
+
+        let h = ExtendedAdvertisingReport::from_bytes(self.data.get(..24)?.try_into().ok()?);
+        let end = 24 + h.data_length as usize;
+        let v = self.data.get(24..end)?;
+        self.data = &self.data[end..];
+        self.count -= 1;
+        Some((h, v))
+    }
+}
+
+impl<'data> AdStructures<'data> {
+    /// Create AD structure iterator
+    ///
+    /// Create a new iterator over the AD structures in the advertising or
+    /// scan response data `data`.
+    pub fn new(data: &'data [u8]) -> Self {
+        Self { data }
+    }
+
+    /// Return remaining data
+    ///
+    /// Return the data that has not been consumed by the iterator.
+    pub fn remainder(&self) -> &'data [u8] {
+        self.data
+    }
+}
+
+impl<'data> Iterator for AdStructures<'data> {
+    type Item = (u8, &'data [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let n = *self.data.first()? as usize;
+        if n == 0 {
+            return None;
+        }
+
+        let v = self.data.get(2..1 + n)?;
+        let ad_type = self.data[1];
+        self.data = &self.data[1 + n..];
+        Some((ad_type, v))
+    }
+}
+
+/// Combine opcode
+///
+/// Return the opcode of the command `ocf` in the group `ogf`.
+pub const fn opcode(ogf: u16, ocf: u16) -> u16 {
+    (ogf << OGF_SHIFT) | (ocf & OCF_MASK)
+}
+
+/// Return group of opcode
+pub fn ogf(opcode: u16) -> u16 {
+    opcode >> OGF_SHIFT
+}
+
+/// Return command of opcode
+pub fn ocf(opcode: u16) -> u16 {
+    opcode & OCF_MASK
+}
+
+/// Return connection handle
+///
+/// Return the connection handle of the handle field of a data packet header
+/// without flags.
+pub fn handle(v: u16) -> u16 {
+    v & HANDLE_MASK
+}
+
+/// Return packet size
+///
+/// Return the size of the HCI packet with the given packet indicator
+/// (`PACKET_*`), whose header starts `data`. The size includes the header,
+/// but not the indicator. Return `None` if the indicator is unknown, or the
+/// data is too short for the header.
+pub fn packet_size(indicator: u8, data: &[u8]) -> Option<usize> {
+    let le16 = |o: usize| -> Option<usize> {
+        Some(u16::from_le_bytes([*data.get(o)?, *data.get(o + 1)?]) as usize)
+    };
+
+    match indicator {
+        PACKET_COMMAND => Some(COMMAND_HEADER_SIZE + *data.get(2)? as usize),
+        PACKET_ACL => Some(ACL_HEADER_SIZE + le16(2)?),
+        PACKET_SCO => Some(SCO_HEADER_SIZE + *data.get(2)? as usize),
+        PACKET_EVENT => Some(EVENT_HEADER_SIZE + *data.get(1)? as usize),
+        PACKET_ISO => Some(ISO_HEADER_SIZE + (le16(2)? & ISO_LENGTH_MASK as usize)),
+        _ => None,
+    }
+}
+
+/// Split LE meta event
+///
+/// Split the parameters of an `EVENT_LE_META` event into the subevent code
+/// (`LE_*`) and the remaining parameters.
+pub fn le_meta(data: &[u8]) -> Option<(u8, &[u8])> {
+    data.split_first().map(|(code, data)| (*code, data))
+}
+
+/// Find AD structure
+///
+/// Return the data of the first AD structure with the given type.
+pub fn find_ad(data: &[u8], ad_type: u8) -> Option<&[u8]> {
+    AdStructures::new(data).find(|v| v.0 == ad_type).map(|v| v.1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::specs::int::ForeignEndian;
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<CommandHeader>(), COMMAND_HEADER_SIZE);
+        assert_eq!(align_of::<CommandHeader>(), 1);
+        assert_eq!(size_of::<EventHeader>(), EVENT_HEADER_SIZE);
+        assert_eq!(align_of::<EventHeader>(), 1);
+        assert_eq!(size_of::<AclHeader>(), ACL_HEADER_SIZE);
+        assert_eq!(align_of::<AclHeader>(), 2);
+        assert_eq!(size_of::<ScoHeader>(), SCO_HEADER_SIZE);
+        assert_eq!(align_of::<ScoHeader>(), 1);
+        assert_eq!(size_of::<IsoHeader>(), ISO_HEADER_SIZE);
+        assert_eq!(align_of::<IsoHeader>(), 2);
+        assert_eq!(size_of::<CommandComplete>(), 3);
+        assert_eq!(align_of::<CommandComplete>(), 1);
+        assert_eq!(size_of::<CommandStatus>(), 4);
+        assert_eq!(align_of::<CommandStatus>(), 2);
+        assert_eq!(size_of::<DisconnectionComplete>(), 4);
+        assert_eq!(align_of::<DisconnectionComplete>(), 1);
+        assert_eq!(size_of::<LeConnectionComplete>(), 18);
+        assert_eq!(align_of::<LeConnectionComplete>(), 1);
+        assert_eq!(size_of::<ExtendedAdvertisingReport>(), 24);
+        assert_eq!(align_of::<ExtendedAdvertisingReport>(), 2);
+    }
+
+    // Decode command and data headers, and the command complete event.
+    #[test]
+    fn verify_packets() {
+        assert_eq!(OPCODE_RESET, 0x0c03);
+        assert_eq!(OPCODE_LE_SET_SCAN_ENABLE, 0x200c);
+        assert_eq!(ogf(OPCODE_LE_SET_SCAN_ENABLE), OGF_LE);
+        assert_eq!(ocf(OPCODE_LE_SET_SCAN_ENABLE), 0x00c);
+
+        let c = CommandHeader::from_bytes(&[0x03, 0x0c, 0x00]);
+        assert_eq!(c.opcode.to_native(), OPCODE_RESET);
+        assert_eq!(packet_size(PACKET_COMMAND, &[0x03, 0x0c, 0x00]), Some(3));
+
+        let event = [0x0e, 0x04, 0x01, 0x03, 0x0c, 0x00];
+        let e = EventHeader::from_bytes(&[event[0], event[1]]);
+        assert_eq!(e.event_code, EVENT_COMMAND_COMPLETE);
+        assert_eq!(packet_size(PACKET_EVENT, &event), Some(event.len()));
+        let p = CommandComplete::from_bytes(&[event[2], event[3], event[4]]);
+        assert_eq!(p.num_packets, 1);
+        assert_eq!(p.opcode.to_native(), OPCODE_RESET);
+        assert_eq!(event[5], STATUS_SUCCESS);
+
+        let a = AclHeader::from_bytes(&[0x40, 0x20, 0x05, 0x00]);
+        let v = a.handle.to_native();
+        assert_eq!(handle(v), 0x040);
+        assert_eq!(v & ACL_PB_MASK, ACL_PB_FIRST_FLUSHABLE);
+        assert_eq!(a.length.to_native(), 5);
+        assert_eq!(packet_size(PACKET_ACL, &[0x40, 0x20, 0x05, 0x00]), Some(9));
+        assert_eq!(packet_size(PACKET_ISO, &[0x40, 0x60, 0x05, 0xc0]), Some(9));
+        assert_eq!(packet_size(PACKET_ACL, &[0x40, 0x20, 0x05]), None);
+        assert_eq!(packet_size(0x06, &[0x00; 4]), None);
+    }
+
+    // H4 LE advertising report event with flags and complete local name.
+    const ADVERTISING_REPORT: [u8; 24] = [
+        0x04, 0x3e, 0x15, 0x02, 0x01, 0x00, 0x01, 0x66,
+        0x55, 0x44, 0x33, 0x22, 0xc1, 0x09, 0x02, 0x01,
+        0x06, 0x05, 0x09, 0x54, 0x65, 0x73, 0x74, 0xc8,
+    ];
+
+    // H4 LE extended advertising report event of a legacy advertisement.
+    const EXTENDED_ADVERTISING_REPORT: [u8; 32] = [
+        0x04, 0x3e, 0x1d, 0x0d, 0x01, 0x13, 0x00, 0x00,
+        0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x01, 0x00,
+        0xff, 0xf6, 0xc4, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x03, 0x02, 0x01, 0x06,
+    ];
+
+    // Decode legacy and extended advertising reports and their AD
+    // structures.
+    #[test]
+    fn verify_advertising() {
+        let data = &ADVERTISING_REPORT[1..];
+        assert_eq!(packet_size(ADVERTISING_REPORT[0], data), Some(data.len()));
+        assert_eq!(data[0], EVENT_LE_META);
+        let (code, params) = le_meta(&data[2..]).unwrap();
+        assert_eq!(code, LE_ADVERTISING_REPORT);
+
+        let mut r = AdvertisingReports::new(params);
+        let v = r.next().unwrap();
+        assert_eq!(v.event_type, ADVERTISING_IND);
+        assert_eq!(v.address_type, ADDRESS_RANDOM);
+        assert_eq!(v.address, [0x66, 0x55, 0x44, 0x33, 0x22, 0xc1]);
+        assert_eq!(v.rssi, -56);
+        assert!(r.next().is_none());
+        assert!(r.remainder().is_empty());
+
+        let mut ad = AdStructures::new(v.data);
+        assert_eq!(ad.next(), Some((AD_FLAGS, &[0x06][..])));
+        assert_eq!(ad.next(), Some((AD_COMPLETE_NAME, &b"Test"[..])));
+        assert_eq!(ad.next(), None);
+        assert_eq!(find_ad(v.data, AD_COMPLETE_NAME), Some(&b"Test"[..]));
+        assert_eq!(find_ad(&[0x02, 0x01, 0x06, 0x00, 0x09], AD_COMPLETE_NAME), None);
+
+        let (code, params) = le_meta(&EXTENDED_ADVERTISING_REPORT[3..]).unwrap();
+        assert_eq!(code, LE_EXTENDED_ADVERTISING_REPORT);
+        let mut r = ExtendedAdvertisingReports::new(params);
+        let (h, v) = r.next().unwrap();
+        assert_eq!(
+            h.event_type.to_native(),
+            EXTENDED_CONNECTABLE | EXTENDED_SCANNABLE | EXTENDED_LEGACY,
+        );
+        assert_eq!(h.address, [0x01, 0x02, 0x03, 0x04, 0x05, 0x06]);
+        assert_eq!(h.primary_phy, PHY_LE_1M);
+        assert_eq!(h.tx_power, -10);
+        assert_eq!(h.rssi, -60);
+        assert_eq!(v, &[0x02, 0x01, 0x06]);
+        assert!(r.next().is_none());
+
+        let mut r = AdvertisingReports::new(&ADVERTISING_REPORT[4..23]);
+        assert!(r.next().is_none());
+    }
+}
@@ -0,0 +1,537 @@
+//! USB Human Interface Device Reports
+//!
+//! HID devices describe the format of the reports they exchange with the
+//! host in a report descriptor, fetched as class descriptor
+//! `usb::DT_REPORT` of a HID interface (see `HidDescriptor`). The report
+//! descriptor is a sequence of items. Main items define the fields of the
+//! reports and group them into collections, while global and local items
+//! set the properties (usage, size, count, logical range) of the fields
+//! that follow.
+//!
+//! Short items start with a 1-byte prefix encoding the size of their data
+//! (0, 1, 2, or 4 bytes), their type, and their tag (see `PREFIX_*`). Long
+//! items start with `PREFIX_LONG`, followed by the 8-bit data size and an
+//! 8-bit tag (see `Items`). Item data is encoded as little-endian and
+//! sign-extended for signed values.
+//!
+//! Usages are 16-bit IDs qualified by a 16-bit usage page. Local usage
+//! items of 4 bytes carry both as an extended usage (see `usage()`).
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::int;
+
+pub const PREFIX_SIZE_MASK: u8 = 0x03;
+pub const PREFIX_TYPE_MASK: u8 = 0x0c;
+pub const PREFIX_TYPE_SHIFT: u8 = 2;
+pub const PREFIX_TAG_MASK: u8 = 0xf0;
+pub const PREFIX_TAG_SHIFT: u8 = 4;
+
+/// Long Item Prefix
+///
+/// The prefix of long items, followed by the data size and the tag.
+pub const PREFIX_LONG: u8 = 0xfe;
+
+pub const TYPE_MAIN: u8 = 0x0;
+pub const TYPE_GLOBAL: u8 = 0x1;
+pub const TYPE_LOCAL: u8 = 0x2;
+pub const TYPE_RESERVED: u8 = 0x3;
+
+pub const MAIN_INPUT: u8 = 0x8;
+pub const MAIN_OUTPUT: u8 = 0x9;
+pub const MAIN_COLLECTION: u8 = 0xa;
+pub const MAIN_FEATURE: u8 = 0xb;
+pub const MAIN_END_COLLECTION: u8 = 0xc;
+
+pub const GLOBAL_USAGE_PAGE: u8 = 0x0;
+pub const GLOBAL_LOGICAL_MINIMUM: u8 = 0x1;
+pub const GLOBAL_LOGICAL_MAXIMUM: u8 = 0x2;
+pub const GLOBAL_PHYSICAL_MINIMUM: u8 = 0x3;
+pub const GLOBAL_PHYSICAL_MAXIMUM: u8 = 0x4;
+pub const GLOBAL_UNIT_EXPONENT: u8 = 0x5;
+pub const GLOBAL_UNIT: u8 = 0x6;
+pub const GLOBAL_REPORT_SIZE: u8 = 0x7;
+pub const GLOBAL_REPORT_ID: u8 = 0x8;
+pub const GLOBAL_REPORT_COUNT: u8 = 0x9;
+pub const GLOBAL_PUSH: u8 = 0xa;
+pub const GLOBAL_POP: u8 = 0xb;
+
+pub const LOCAL_USAGE: u8 = 0x0;
+pub const LOCAL_USAGE_MINIMUM: u8 = 0x1;
+pub const LOCAL_USAGE_MAXIMUM: u8 = 0x2;
+pub const LOCAL_DESIGNATOR_INDEX: u8 = 0x3;
+pub const LOCAL_DESIGNATOR_MINIMUM: u8 = 0x4;
+pub const LOCAL_DESIGNATOR_MAXIMUM: u8 = 0x5;
+pub const LOCAL_STRING_INDEX: u8 = 0x7;
+pub const LOCAL_STRING_MINIMUM: u8 = 0x8;
+pub const LOCAL_STRING_MAXIMUM: u8 = 0x9;
+pub const LOCAL_DELIMITER: u8 = 0xa;
+
+pub const MAIN_CONSTANT: u32 = 0x0001;
+pub const MAIN_VARIABLE: u32 = 0x0002;
+pub const MAIN_RELATIVE: u32 = 0x0004;
+pub const MAIN_WRAP: u32 = 0x0008;
+pub const MAIN_NONLINEAR: u32 = 0x0010;
+pub const MAIN_NO_PREFERRED: u32 = 0x0020;
+pub const MAIN_NULL_STATE: u32 = 0x0040;
+pub const MAIN_VOLATILE: u32 = 0x0080;
+pub const MAIN_BUFFERED_BYTES: u32 = 0x0100;
+
+pub const COLLECTION_PHYSICAL: u32 = 0x00;
+pub const COLLECTION_APPLICATION: u32 = 0x01;
+pub const COLLECTION_LOGICAL: u32 = 0x02;
+pub const COLLECTION_REPORT: u32 = 0x03;
+pub const COLLECTION_NAMED_ARRAY: u32 = 0x04;
+pub const COLLECTION_USAGE_SWITCH: u32 = 0x05;
+pub const COLLECTION_USAGE_MODIFIER: u32 = 0x06;
+
+pub const REQ_GET_REPORT: u8 = 0x01;
+pub const REQ_GET_IDLE: u8 = 0x02;
+pub const REQ_GET_PROTOCOL: u8 = 0x03;
+pub const REQ_SET_REPORT: u8 = 0x09;
+pub const REQ_SET_IDLE: u8 = 0x0a;
+pub const REQ_SET_PROTOCOL: u8 = 0x0b;
+
+pub const REPORT_TYPE_INPUT: u8 = 0x01;
+pub const REPORT_TYPE_OUTPUT: u8 = 0x02;
+pub const REPORT_TYPE_FEATURE: u8 = 0x03;
+
+pub const PROTOCOL_BOOT: u16 = 0x0000;
+pub const PROTOCOL_REPORT: u16 = 0x0001;
+
+pub const PAGE_GENERIC_DESKTOP: u16 = 0x0001;
+pub const PAGE_SIMULATION: u16 = 0x0002;
+pub const PAGE_VR: u16 = 0x0003;
+pub const PAGE_SPORT: u16 = 0x0004;
+pub const PAGE_GAME: u16 = 0x0005;
+pub const PAGE_GENERIC_DEVICE: u16 = 0x0006;
+pub const PAGE_KEYBOARD: u16 = 0x0007;
+pub const PAGE_LED: u16 = 0x0008;
+pub const PAGE_BUTTON: u16 = 0x0009;
+pub const PAGE_ORDINAL: u16 = 0x000a;
+pub const PAGE_TELEPHONY: u16 = 0x000b;
+pub const PAGE_CONSUMER: u16 = 0x000c;
+pub const PAGE_DIGITIZER: u16 = 0x000d;
+pub const PAGE_HAPTICS: u16 = 0x000e;
+pub const PAGE_PID: u16 = 0x000f;
+pub const PAGE_UNICODE: u16 = 0x0010;
+pub const PAGE_SENSOR: u16 = 0x0020;
+pub const PAGE_BATTERY_SYSTEM: u16 = 0x0085;
+pub const PAGE_VENDOR_MIN: u16 = 0xff00;
+
+pub const GD_POINTER: u16 = 0x01;
+pub const GD_MOUSE: u16 = 0x02;
+pub const GD_JOYSTICK: u16 = 0x04;
+pub const GD_GAMEPAD: u16 = 0x05;
+pub const GD_KEYBOARD: u16 = 0x06;
+pub const GD_KEYPAD: u16 = 0x07;
+pub const GD_MULTI_AXIS: u16 = 0x08;
+pub const GD_X: u16 = 0x30;
+pub const GD_Y: u16 = 0x31;
+pub const GD_Z: u16 = 0x32;
+pub const GD_RX: u16 = 0x33;
+pub const GD_RY: u16 = 0x34;
+pub const GD_RZ: u16 = 0x35;
+pub const GD_SLIDER: u16 = 0x36;
+pub const GD_DIAL: u16 = 0x37;
+pub const GD_WHEEL: u16 = 0x38;
+pub const GD_HAT_SWITCH: u16 = 0x39;
+pub const GD_SYSTEM_CONTROL: u16 = 0x80;
+pub const GD_SYSTEM_POWER_DOWN: u16 = 0x81;
+pub const GD_SYSTEM_SLEEP: u16 = 0x82;
+pub const GD_SYSTEM_WAKE_UP: u16 = 0x83;
+
+pub const KEY_NONE: u16 = 0x00;
+pub const KEY_ERR_ROLLOVER: u16 = 0x01;
+pub const KEY_POST_FAIL: u16 = 0x02;
+pub const KEY_ERR_UNDEFINED: u16 = 0x03;
+pub const KEY_A: u16 = 0x04;
+pub const KEY_Z: u16 = 0x1d;
+pub const KEY_1: u16 = 0x1e;
+pub const KEY_9: u16 = 0x26;
+pub const KEY_0: u16 = 0x27;
+pub const KEY_ENTER: u16 = 0x28;
+pub const KEY_ESCAPE: u16 = 0x29;
+pub const KEY_BACKSPACE: u16 = 0x2a;
+pub const KEY_TAB: u16 = 0x2b;
+pub const KEY_SPACE: u16 = 0x2c;
+pub const KEY_CAPS_LOCK: u16 = 0x39;
+pub const KEY_F1: u16 = 0x3a;
+pub const KEY_F12: u16 = 0x45;
+pub const KEY_PRINT_SCREEN: u16 = 0x46;
+pub const KEY_SCROLL_LOCK: u16 = 0x47;
+pub const KEY_PAUSE: u16 = 0x48;
+pub const KEY_INSERT: u16 = 0x49;
+pub const KEY_HOME: u16 = 0x4a;
+pub const KEY_PAGE_UP: u16 = 0x4b;
+pub const KEY_DELETE: u16 = 0x4c;
+pub const KEY_END: u16 = 0x4d;
+pub const KEY_PAGE_DOWN: u16 = 0x4e;
+pub const KEY_RIGHT: u16 = 0x4f;
+pub const KEY_LEFT: u16 = 0x50;
+pub const KEY_DOWN: u16 = 0x51;
+pub const KEY_UP: u16 = 0x52;
+pub const KEY_NUM_LOCK: u16 = 0x53;
+pub const KEY_LEFT_CONTROL: u16 = 0xe0;
+pub const KEY_LEFT_SHIFT: u16 = 0xe1;
+pub const KEY_LEFT_ALT: u16 = 0xe2;
+pub const KEY_LEFT_GUI: u16 = 0xe3;
+pub const KEY_RIGHT_CONTROL: u16 = 0xe4;
+pub const KEY_RIGHT_SHIFT: u16 = 0xe5;
+pub const KEY_RIGHT_ALT: u16 = 0xe6;
+pub const KEY_RIGHT_GUI: u16 = 0xe7;
+
+pub const LED_NUM_LOCK: u16 = 0x01;
+pub const LED_CAPS_LOCK: u16 = 0x02;
+pub const LED_SCROLL_LOCK: u16 = 0x03;
+pub const LED_COMPOSE: u16 = 0x04;
+pub const LED_KANA: u16 = 0x05;
+
+pub const BUTTON_NONE: u16 = 0x00;
+pub const BUTTON_PRIMARY: u16 = 0x01;
+pub const BUTTON_SECONDARY: u16 = 0x02;
+pub const BUTTON_TERTIARY: u16 = 0x03;
+
+/// HID Descriptor (`usb::DT_HID`)
+///
+/// The class descriptor following the interface descriptor of HID
+/// interfaces. It lists the class descriptors of the interface, at least
+/// the report descriptor. Further entries of 3 bytes follow if
+/// `num_descriptors` is greater than 1.
+///
+/// Since the descriptor is not padded to its natural alignment, this
+/// structure is packed.
+#[repr(C, packed)]
+pub struct HidDescriptor {
+    /// Size of the descriptor in bytes.
+    pub length: u8,
+
+    /// Descriptor type (`usb::DT_HID`).
+    pub descriptor_type: u8,
+
+    /// HID specification release in binary-coded decimal.
+    pub bcd_hid: int::u16le,
+
+    /// Country code of localized hardware, or 0.
+    pub country_code: u8,
+
+    /// Number of class descriptors.
+    pub num_descriptors: u8,
+
+    /// Type of the first class descriptor (`usb::DT_REPORT`).
+    pub class_descriptor_type: u8,
+
+    /// Size of the first class descriptor in bytes.
+    pub class_descriptor_length: int::u16le,
+}
+
+/// Item Reference
+///
+/// A reference to an item of a report descriptor.
+#[derive(Clone, Copy, Debug)]
+pub struct Item<'data> {
+    /// Item type (`TYPE_*`), or `TYPE_RESERVED` for long items.
+    pub item_type: u8,
+
+    /// Item tag (`MAIN_*`, `GLOBAL_*`, `LOCAL_*`, or the long item tag).
+    pub tag: u8,
+
+    /// Whether this is a long item.
+    pub long: bool,
+
+    /// Item data.
+    pub data: &'data [u8],
+}
+
+/// Item Iterator
+///
+/// An iterator over the items of a report descriptor, yielding an `Item`
+/// for every item. Iteration stops at the first truncated item.
+#[derive(Clone, Debug)]
+pub struct Items<'data> {
+    data: &'data [u8],
+}
+
+impl HidDescriptor {
+    /// Import an HID descriptor from a byte slice
+    ///
+    /// Create a new HID descriptor from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 9]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 9], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl<'data> Item<'data> {
+    /// Return unsigned data
+    ///
+    /// Return the data of a short item as unsigned integer, zero-extended
+    /// to 32 bits.
+    pub fn value(&self) -> u32 {
+        self.data
+            .iter()
+            .take(4)
+            .rev()
+            .fold(0, |acc, v| (acc << 8) | (*v as u32))
+    }
+
+    /// Return signed data
+    ///
+    /// Return the data of a short item as signed integer, sign-extended to
+    /// 32 bits.
+    pub fn signed(&self) -> i32 {
+        match self.data.len() {
+            0 => 0,
+            1 => self.data[0] as i8 as i32,
+            2 => i16::from_le_bytes([self.data[0], self.data[1]]) as i32,
+            _ => self.value() as i32,
+        }
+    }
+}
+
+impl<'data> Items<'data> {
+    /// Create item iterator
+    ///
+    /// Create a new iterator over the items of the report descriptor
+    /// `data`.
+    pub fn new(data: &'data [u8]) -> Self {
+        Self { data }
+    }
+
+    /// Return remaining data
+    ///
+    /// Return the data that has not been consumed by the iterator.
+    pub fn remainder(&self) -> &'data [u8] {
+        self.data
+    }
+}
+
+impl<'data> Iterator for Items<'data> {
+    type Item = Item<'data>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let prefix = *self.data.first()?;
+
+        let (item, length) = if prefix == PREFIX_LONG {
+            let size = *self.data.get(1)? as usize;
+            let item = Item {
+                item_type: TYPE_RESERVED,
+                tag: *self.data.get(2)?,
+                long: true,
+                data: self.data.get(3..3 + size)?,
+            };
+            (item, 3 + size)
+        } else {
+            let size = match prefix & PREFIX_SIZE_MASK {
+                3 => 4,
+                v => v as usize,
+            };
+            let item = Item {
+                item_type: (prefix & PREFIX_TYPE_MASK) >> PREFIX_TYPE_SHIFT,
+                tag: (prefix & PREFIX_TAG_MASK) >> PREFIX_TAG_SHIFT,
+                long: false,
+                data: self.data.get(1..1 + size)?,
+            };
+            (item, 1 + size)
+        };
+
+        self.data = &self.data[length..];
+        Some(item)
+    }
+}
+
+/// Create an extended usage
+///
+/// Combine a usage page and a usage ID into a 32-bit extended usage, as
+/// carried by local usage items of 4 bytes.
+pub const fn usage(page: u16, id: u16) -> u32 {
+    ((page as u32) << 16) | (id as u32)
+}
+
+/// Encode a short item prefix
+///
+/// Return the prefix of a short item of the given type, tag, and data size,
+/// or `None` if the size is not one of 0, 1, 2, or 4.
+pub fn prefix(item_type: u8, tag: u8, size: usize) -> Option<u8> {
+    let size = match size {
+        0..=2 => size as u8,
+        4 => 3,
+        _ => return None,
+    };
+
+    Some(
+        ((tag << PREFIX_TAG_SHIFT) & PREFIX_TAG_MASK)
+            | ((item_type << PREFIX_TYPE_SHIFT) & PREFIX_TYPE_MASK)
+            | size,
+    )
+}
+
+/// Encode a short item
+///
+/// Encode a short item of the given type, tag, and data into `out`. Return
+/// the number of bytes written, or `None` if the data size is invalid, or
+/// the buffer is too small.
+pub fn encode_short(item_type: u8, tag: u8, data: &[u8], out: &mut [u8]) -> Option<usize> {
+    let p = prefix(item_type, tag, data.len())?;
+    *out.first_mut()? = p;
+    out.get_mut(1..1 + data.len())?.copy_from_slice(data);
+    Some(1 + data.len())
+}
+
+/// Encode an unsigned short item
+///
+/// Encode a short item with the smallest data size that holds `value`
+/// unsigned. Return the number of bytes written, or `None` if the buffer
+/// is too small.
+pub fn encode_unsigned(item_type: u8, tag: u8, value: u32, out: &mut [u8]) -> Option<usize> {
+    let v = value.to_le_bytes();
+    let size = match value {
+        0 => 0,
+        0x1..=0xff => 1,
+        0x100..=0xffff => 2,
+        _ => 4,
+    };
+
+    encode_short(item_type, tag, &v[..size], out)
+}
+
+/// Encode a signed short item
+///
+/// Encode a short item with the smallest data size that holds `value`
+/// sign-extended. Return the number of bytes written, or `None` if the
+/// buffer is too small.
+pub fn encode_signed(item_type: u8, tag: u8, value: i32, out: &mut [u8]) -> Option<usize> {
+    let v = value.to_le_bytes();
+    let size = if value == 0 {
+        0
+    } else if i8::try_from(value).is_ok() {
+        1
+    } else if i16::try_from(value).is_ok() {
+        2
+    } else {
+        4
+    };
+
+    encode_short(item_type, tag, &v[..size], out)
+}
+
+/// Encode a long item
+///
+/// Encode a long item of the given tag and data into `out`. Return the
+/// number of bytes written, or `None` if the data exceeds 255 bytes, or the
+/// buffer is too small.
+pub fn encode_long(tag: u8, data: &[u8], out: &mut [u8]) -> Option<usize> {
+    let size = u8::try_from(data.len()).ok()?;
+    out.get_mut(..3)?.copy_from_slice(&[PREFIX_LONG, size, tag]);
+    out.get_mut(3..3 + data.len())?.copy_from_slice(data);
+    Some(3 + data.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::specs::int::ForeignEndian;
+    use crate::specs::usb;
+
+    // Report descriptor of a boot mouse with 3 buttons and relative X and
+    // Y axes, as given by the HID specification.
+    const MOUSE: [u8; 50] = [
+        0x05, 0x01, 0x09, 0x02, 0xa1, 0x01, 0x09, 0x01,
+        0xa1, 0x00, 0x05, 0x09, 0x19, 0x01, 0x29, 0x03,
+        0x15, 0x00, 0x25, 0x01, 0x95, 0x03, 0x75, 0x01,
+        0x81, 0x02, 0x95, 0x01, 0x75, 0x05, 0x81, 0x01,
+        0x05, 0x01, 0x09, 0x30, 0x09, 0x31, 0x15, 0x81,
+        0x25, 0x7f, 0x75, 0x08, 0x95, 0x02, 0x81, 0x06,
+        0xc0, 0xc0,
+    ];
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<HidDescriptor>(), 9);
+    }
+
+    // Import the HID descriptor of a boot mouse.
+    #[test]
+    fn verify_descriptor() {
+        let d = HidDescriptor::from_bytes(&[0x09, 0x21, 0x11, 0x01, 0x00, 0x01, 0x22, 0x32, 0x00]);
+        assert_eq!(d.descriptor_type, usb::DT_HID);
+        assert_eq!(d.bcd_hid.to_native(), 0x0111);
+        assert_eq!(d.num_descriptors, 1);
+        assert_eq!(d.class_descriptor_type, usb::DT_REPORT);
+        assert_eq!(d.class_descriptor_length.to_native() as usize, MOUSE.len());
+    }
+
+    // Parse the mouse report descriptor, and re-encode it from its items.
+    #[test]
+    fn verify_items() {
+        let items: std::vec::Vec<Item> = Items::new(&MOUSE).collect();
+        assert_eq!(items.len(), 26);
+
+        let i = items[0];
+        assert_eq!((i.item_type, i.tag, i.long), (TYPE_GLOBAL, GLOBAL_USAGE_PAGE, false));
+        assert_eq!(i.value(), PAGE_GENERIC_DESKTOP as u32);
+        let i = items[2];
+        assert_eq!((i.item_type, i.tag), (TYPE_MAIN, MAIN_COLLECTION));
+        assert_eq!(i.value(), COLLECTION_APPLICATION);
+        let i = items[12];
+        assert_eq!((i.item_type, i.tag), (TYPE_MAIN, MAIN_INPUT));
+        assert_eq!(i.value(), MAIN_VARIABLE);
+        let i = items[19];
+        assert_eq!((i.item_type, i.tag), (TYPE_GLOBAL, GLOBAL_LOGICAL_MINIMUM));
+        assert_eq!((i.value(), i.signed()), (0x81, -127));
+        let i = items[23];
+        assert_eq!(i.value(), MAIN_VARIABLE | MAIN_RELATIVE);
+        assert_eq!((items[25].item_type, items[25].tag), (TYPE_MAIN, MAIN_END_COLLECTION));
+        assert!(items[25].data.is_empty());
+
+        let mut buf = [0u8; 64];
+        let mut n = 0;
+        for i in &items {
+            n += encode_short(i.item_type, i.tag, i.data, &mut buf[n..]).unwrap();
+        }
+        assert_eq!(&buf[..n], &MOUSE);
+    }
+
+    // Encode short and long items of various sizes.
+    #[test]
+    fn verify_encode() {
+        let mut buf = [0u8; 16];
+
+        assert_eq!(prefix(TYPE_LOCAL, LOCAL_USAGE, 4), Some(0x0b));
+        assert_eq!(prefix(TYPE_MAIN, MAIN_INPUT, 3), None);
+
+        let n = encode_unsigned(TYPE_GLOBAL, GLOBAL_USAGE_PAGE, 0xff00, &mut buf).unwrap();
+        assert_eq!(&buf[..n], &[0x06, 0x00, 0xff]);
+        let n = encode_signed(TYPE_GLOBAL, GLOBAL_LOGICAL_MINIMUM, -32768, &mut buf).unwrap();
+        assert_eq!(&buf[..n], &[0x16, 0x00, 0x80]);
+        let n = encode_signed(TYPE_GLOBAL, GLOBAL_LOGICAL_MAXIMUM, 255, &mut buf).unwrap();
+        assert_eq!(&buf[..n], &[0x26, 0xff, 0x00]);
+        let u = usage(PAGE_KEYBOARD, KEY_A);
+        let n = encode_unsigned(TYPE_LOCAL, LOCAL_USAGE, u, &mut buf).unwrap();
+        assert_eq!(&buf[..n], &[0x0b, 0x04, 0x00, 0x07, 0x00]);
+        let n = encode_unsigned(TYPE_MAIN, MAIN_END_COLLECTION, 0, &mut buf).unwrap();
+        assert_eq!(&buf[..n], &[0xc0]);
+
+        let n = encode_long(0xf0, &[0x01, 0x02], &mut buf).unwrap();
+        assert_eq!(&buf[..n], &[0xfe, 0x02, 0xf0, 0x01, 0x02]);
+        let i = Items::new(&buf[..n]).next().unwrap();
+        assert!(i.long);
+        assert_eq!((i.tag, i.data), (0xf0, &[0x01, 0x02][..]));
+
+        assert!(encode_unsigned(TYPE_LOCAL, LOCAL_USAGE, u, &mut buf[..4]).is_none());
+        assert!(Items::new(&[0x0b, 0x04, 0x00]).next().is_none());
+    }
+}
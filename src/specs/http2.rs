@@ -0,0 +1,421 @@
+//! Hypertext Transfer Protocol Version 2
+//!
+//! HTTP/2 multiplexes streams over a single connection. Clients start the
+//! connection with `PREFACE`, followed by a SETTINGS frame, and servers
+//! respond with a SETTINGS frame. Afterwards, both ends exchange frames,
+//! each starting with a 9-byte `Header` carrying the 24-bit payload length,
+//! the frame type (`FRAME_*`), flags (`FLAG_*`), and a 31-bit stream
+//! identifier. Stream 0 carries frames that apply to the connection.
+//!
+//! DATA, HEADERS and PUSH_PROMISE frames can be padded (`FLAG_PADDED`), in
+//! which case the payload starts with the size of the padding, and the
+//! padding follows the payload (see `unpad()`). Field blocks are compressed
+//! with HPACK and are not decoded here.
+//!
+//! The fixed payloads of SETTINGS (see `Settings`), PING, GOAWAY and
+//! WINDOW_UPDATE frames are provided as structures.
+//!
+//! All multi-byte integers are encoded as big-endian.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::int;
+
+pub const HEADER_SIZE: usize = 9;
+pub const SETTING_SIZE: usize = 6;
+pub const PING_SIZE: usize = 8;
+pub const GOAWAY_SIZE: usize = 8;
+pub const WINDOW_UPDATE_SIZE: usize = 4;
+
+/// Client Connection Preface
+pub const PREFACE: [u8; 24] = *b"PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n";
+
+pub const FRAME_SIZE_DEFAULT: u32 = 0x4000;
+pub const FRAME_SIZE_MAX: u32 = 0x00ff_ffff;
+pub const WINDOW_SIZE_DEFAULT: u32 = 0xffff;
+pub const WINDOW_SIZE_MAX: u32 = 0x7fff_ffff;
+pub const STREAM_ID_MASK: u32 = 0x7fff_ffff;
+
+pub const FRAME_DATA: u8 = 0x00;
+pub const FRAME_HEADERS: u8 = 0x01;
+pub const FRAME_PRIORITY: u8 = 0x02;
+pub const FRAME_RST_STREAM: u8 = 0x03;
+pub const FRAME_SETTINGS: u8 = 0x04;
+pub const FRAME_PUSH_PROMISE: u8 = 0x05;
+pub const FRAME_PING: u8 = 0x06;
+pub const FRAME_GOAWAY: u8 = 0x07;
+pub const FRAME_WINDOW_UPDATE: u8 = 0x08;
+pub const FRAME_CONTINUATION: u8 = 0x09;
+pub const FRAME_ALTSVC: u8 = 0x0a;
+pub const FRAME_ORIGIN: u8 = 0x0c;
+pub const FRAME_PRIORITY_UPDATE: u8 = 0x10;
+
+pub const FLAG_END_STREAM: u8 = 0x01;
+pub const FLAG_ACK: u8 = 0x01;
+pub const FLAG_END_HEADERS: u8 = 0x04;
+pub const FLAG_PADDED: u8 = 0x08;
+pub const FLAG_PRIORITY: u8 = 0x20;
+
+pub const SETTINGS_HEADER_TABLE_SIZE: u16 = 0x01;
+pub const SETTINGS_ENABLE_PUSH: u16 = 0x02;
+pub const SETTINGS_MAX_CONCURRENT_STREAMS: u16 = 0x03;
+pub const SETTINGS_INITIAL_WINDOW_SIZE: u16 = 0x04;
+pub const SETTINGS_MAX_FRAME_SIZE: u16 = 0x05;
+pub const SETTINGS_MAX_HEADER_LIST_SIZE: u16 = 0x06;
+pub const SETTINGS_ENABLE_CONNECT_PROTOCOL: u16 = 0x08;
+pub const SETTINGS_NO_RFC7540_PRIORITIES: u16 = 0x09;
+
+pub const ERROR_NO_ERROR: u32 = 0x00;
+pub const ERROR_PROTOCOL_ERROR: u32 = 0x01;
+pub const ERROR_INTERNAL_ERROR: u32 = 0x02;
+pub const ERROR_FLOW_CONTROL_ERROR: u32 = 0x03;
+pub const ERROR_SETTINGS_TIMEOUT: u32 = 0x04;
+pub const ERROR_STREAM_CLOSED: u32 = 0x05;
+pub const ERROR_FRAME_SIZE_ERROR: u32 = 0x06;
+pub const ERROR_REFUSED_STREAM: u32 = 0x07;
+pub const ERROR_CANCEL: u32 = 0x08;
+pub const ERROR_COMPRESSION_ERROR: u32 = 0x09;
+pub const ERROR_CONNECT_ERROR: u32 = 0x0a;
+pub const ERROR_ENHANCE_YOUR_CALM: u32 = 0x0b;
+pub const ERROR_INADEQUATE_SECURITY: u32 = 0x0c;
+pub const ERROR_HTTP_1_1_REQUIRED: u32 = 0x0d;
+
+/// Frame Header
+///
+/// Since the stream identifier is not naturally aligned, this structure is
+/// packed.
+#[repr(C, packed)]
+pub struct Header {
+    /// Size of the payload in bytes, excluding the header.
+    pub length: int::u24be,
+
+    /// Frame type (`FRAME_*`).
+    pub frame_type: u8,
+
+    /// Flags (`FLAG_*`), depending on the frame type.
+    pub flags: u8,
+
+    /// Reserved bit and stream identifier (see `stream_id()`).
+    pub stream_id: int::u32be,
+}
+
+/// SETTINGS Parameter
+///
+/// Since the value is not naturally aligned, this structure is packed.
+#[repr(C, packed)]
+pub struct Setting {
+    /// Parameter identifier (`SETTINGS_*`).
+    pub identifier: int::u16be,
+
+    /// Parameter value.
+    pub value: int::u32be,
+}
+
+/// PING Payload
+#[repr(C)]
+pub struct Ping {
+    /// Opaque data, echoed in the acknowledgment.
+    pub opaque_data: [u8; 8],
+}
+
+/// GOAWAY Payload
+///
+/// The fixed fields of a GOAWAY frame, followed by opaque debug data.
+#[repr(C)]
+pub struct GoAway {
+    /// Reserved bit and identifier of the last processed stream.
+    pub last_stream_id: int::u32be,
+
+    /// Error code (`ERROR_*`).
+    pub error_code: int::u32be,
+}
+
+/// WINDOW_UPDATE Payload
+#[repr(C)]
+pub struct WindowUpdate {
+    /// Reserved bit and window size increment.
+    pub increment: int::u32be,
+}
+
+/// Settings Iterator
+///
+/// An iterator over the parameters of a SETTINGS frame, yielding the
+/// identifier and value. Iteration stops at the end of the data, ignoring
+/// trailing bytes of truncated parameters.
+#[derive(Clone, Debug)]
+pub struct Settings<'data> {
+    data: &'data [u8],
+}
+
+impl Header {
+    /// Import a header from a byte slice
+    ///
+    /// Create a new header from data copied from a byte slice. No byte-order
+    /// conversions are applied.
+    pub fn from_bytes(data: &[u8; 9]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 9], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Create header
+    ///
+    /// Create a new header from native values. The payload length must not
+    /// exceed `FRAME_SIZE_MAX`, and the reserved bit of the stream
+    /// identifier is cleared.
+    pub fn new(length: u32, frame_type: u8, flags: u8, stream_id: u32) -> Self {
+        assert!(length <= FRAME_SIZE_MAX);
+
+        Self {
+            length: int::u24be::from_native(length),
+            frame_type,
+            flags,
+            stream_id: int::ForeignEndian::from_native(stream_id & STREAM_ID_MASK),
+        }
+    }
+
+    /// Convert to byte slice
+    ///
+    /// Return a byte-slice reference to the header. No byte-order
+    /// conversions are applied.
+    pub fn as_bytes(&self) -> &[u8; 9] {
+        assert!(align_of::<[u8; 9]>() <= align_of::<Self>());
+        assert!(size_of::<[u8; 9]>() == size_of::<Self>());
+
+        unsafe {
+            core::mem::transmute::<&Self, &[u8; 9]>(self)
+        }
+    }
+
+    /// Return payload length
+    pub fn length(&self) -> u32 {
+        self.length.to_native()
+    }
+
+    /// Return stream identifier
+    ///
+    /// Return the stream identifier with the reserved bit ignored.
+    pub fn stream_id(&self) -> u32 {
+        int::ForeignEndian::to_native(self.stream_id) & STREAM_ID_MASK
+    }
+
+    /// Check flags
+    ///
+    /// Return whether all of the given flags (`FLAG_*`) are set.
+    pub fn has_flags(&self, flags: u8) -> bool {
+        self.flags & flags == flags
+    }
+}
+
+impl Setting {
+    /// Import a parameter from a byte slice
+    ///
+    /// Create a new parameter from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 6]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 6], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl Ping {
+    /// Import a PING payload from a byte slice
+    ///
+    /// Create a new PING payload from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 8]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 8], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl GoAway {
+    /// Import a GOAWAY payload from a byte slice
+    ///
+    /// Create a new GOAWAY payload from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 8]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 8], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Return last stream identifier
+    pub fn last_stream_id(&self) -> u32 {
+        int::ForeignEndian::to_native(self.last_stream_id) & STREAM_ID_MASK
+    }
+}
+
+impl WindowUpdate {
+    /// Import a WINDOW_UPDATE payload from a byte slice
+    ///
+    /// Create a new WINDOW_UPDATE payload from data copied from a byte
+    /// slice. No byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 4]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 4], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Return window size increment
+    pub fn increment(&self) -> u32 {
+        int::ForeignEndian::to_native(self.increment) & WINDOW_SIZE_MAX
+    }
+}
+
+impl<'data> Settings<'data> {
+    /// Create settings iterator
+    ///
+    /// Create a new iterator over the parameters in the payload of a
+    /// SETTINGS frame.
+    pub fn new(data: &'data [u8]) -> Self {
+        Self {
+            data,
+        }
+    }
+
+    /// Return remaining data
+    ///
+    /// Return the data that has not been consumed by the iterator.
+    pub fn remainder(&self) -> &'data [u8] {
+        self.data
+    }
+}
+
+impl<'data> Iterator for Settings<'data> {
+    type Item = (u16, u32);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let v = Setting::from_bytes(self.data.get(..SETTING_SIZE)?.try_into().unwrap());
+        self.data = &self.data[SETTING_SIZE..];
+        Some((
+            int::ForeignEndian::to_native(v.identifier),
+            int::ForeignEndian::to_native(v.value),
+        ))
+    }
+}
+
+/// Remove padding
+///
+/// Return the payload of a padded frame without the padding length and the
+/// padding, or `None` if the padding exceeds the payload. If `FLAG_PADDED`
+/// is not set in `flags`, the payload is returned unchanged.
+pub fn unpad(flags: u8, payload: &[u8]) -> Option<&[u8]> {
+    if flags & FLAG_PADDED == 0 {
+        return Some(payload);
+    }
+
+    let n = *payload.first()? as usize;
+    (n < payload.len()).then(|| &payload[1..payload.len() - n])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A SETTINGS frame with two parameters, followed by a PING frame.
+    const FRAMES: [u8; 38] = [
+        0x00, 0x00, 0x0c, 0x04, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x03, 0x00, 0x00, 0x00, 0x64, 0x00,
+        0x04, 0x00, 0x10, 0x00, 0x00, 0x00, 0x00, 0x08,
+        0x06, 0x01, 0x00, 0x00, 0x00, 0x00, 0x01, 0x02,
+        0x03, 0x04, 0x05, 0x06, 0x07, 0x08,
+    ];
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<Header>(), HEADER_SIZE);
+        assert_eq!(align_of::<Header>(), 1);
+        assert_eq!(size_of::<Setting>(), SETTING_SIZE);
+        assert_eq!(align_of::<Setting>(), 1);
+        assert_eq!(size_of::<Ping>(), PING_SIZE);
+        assert_eq!(align_of::<Ping>(), 1);
+        assert_eq!(size_of::<GoAway>(), GOAWAY_SIZE);
+        assert_eq!(align_of::<GoAway>(), 4);
+        assert_eq!(size_of::<WindowUpdate>(), WINDOW_UPDATE_SIZE);
+        assert_eq!(align_of::<WindowUpdate>(), 4);
+    }
+
+    // Parse SETTINGS and PING frames.
+    #[test]
+    fn verify_frames() {
+        let h = Header::from_bytes(FRAMES[..9].try_into().unwrap());
+        assert_eq!(h.length(), 12);
+        assert_eq!(h.frame_type, FRAME_SETTINGS);
+        assert!(!h.has_flags(FLAG_ACK));
+        assert_eq!(h.stream_id(), 0);
+
+        let mut settings = Settings::new(&FRAMES[9..21]);
+        assert_eq!(settings.next(), Some((SETTINGS_MAX_CONCURRENT_STREAMS, 100)));
+        assert_eq!(settings.next(), Some((SETTINGS_INITIAL_WINDOW_SIZE, 0x100000)));
+        assert_eq!(settings.next(), None);
+        assert!(settings.remainder().is_empty());
+
+        let h = Header::from_bytes(FRAMES[21..30].try_into().unwrap());
+        assert_eq!(h.length() as usize, PING_SIZE);
+        assert_eq!(h.frame_type, FRAME_PING);
+        assert!(h.has_flags(FLAG_ACK));
+        let p = Ping::from_bytes(FRAMES[30..].try_into().unwrap());
+        assert_eq!(p.opaque_data, [1, 2, 3, 4, 5, 6, 7, 8]);
+
+        let h = Header::new(PING_SIZE as u32, FRAME_PING, FLAG_ACK, 0x8000_0000);
+        assert_eq!(h.as_bytes(), &FRAMES[21..30]);
+        assert!(PREFACE.starts_with(b"PRI * HTTP/2.0"));
+    }
+
+    // Parse fixed payloads and strip padding.
+    #[test]
+    fn verify_payloads() {
+        let g = GoAway::from_bytes(&[0x80, 0x00, 0x00, 0x07, 0x00, 0x00, 0x00, 0x0b]);
+        assert_eq!(g.last_stream_id(), 7);
+        assert_eq!(int::ForeignEndian::to_native(g.error_code), ERROR_ENHANCE_YOUR_CALM);
+
+        let w = WindowUpdate::from_bytes(&[0xff, 0xff, 0xff, 0xff]);
+        assert_eq!(w.increment(), WINDOW_SIZE_MAX);
+
+        let h = Header::from_bytes(&[0x00, 0x00, 0x06, 0x00, 0x09, 0x00, 0x00, 0x00, 0x01]);
+        assert!(h.has_flags(FLAG_PADDED | FLAG_END_STREAM));
+        assert_eq!(h.stream_id(), 1);
+        assert_eq!(unpad(h.flags, &[0x02, 0x68, 0x69, 0x21, 0x00, 0x00]), Some(&b"hi!"[..]));
+        assert_eq!(unpad(0, b"hi"), Some(&b"hi"[..]));
+        assert_eq!(unpad(FLAG_PADDED, &[0x02, 0x00]), None);
+        assert_eq!(unpad(FLAG_PADDED, &[]), None);
+    }
+}
@@ -0,0 +1,346 @@
+//! ICC Color Profiles
+//!
+//! ICC profiles (ICC.1, ISO 15076-1) describe the color characteristics of a
+//! device or color space, relative to a profile connection space (PCS). They
+//! are embedded in many image formats, e.g., in `iCCP` chunks of PNG files,
+//! `APP2` segments of JPEG files, or `ICCP` chunks of WebP files.
+//!
+//! A profile starts with a fixed 128-byte `Header`, followed by the tag
+//! table: a 32-bit tag count and an array of `TagEntry` records. Each entry
+//! references the tag data by offset from the start of the profile and
+//! size. Tag data starts with a 4-byte type signature, followed by 4
+//! reserved bytes and the type-specific payload. Several tags may share the
+//! same data.
+//!
+//! Signatures are 4-byte ASCII codes, padded with spaces. Non-integer values
+//! are stored as fixed-point numbers, usually `s15Fixed16Number` (signed, 16
+//! fractional bits).
+//!
+//! All multi-byte integers are encoded as big-endian.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::int;
+
+/// Size of the Header
+pub const HEADER_SIZE: usize = 128;
+
+/// Profile File Signature
+///
+/// The magic signature at offset 36 of every profile ("acsp").
+pub const MAGIC: [u8; 4] = *b"acsp";
+
+pub const CLASS_INPUT: [u8; 4] = *b"scnr";
+pub const CLASS_DISPLAY: [u8; 4] = *b"mntr";
+pub const CLASS_OUTPUT: [u8; 4] = *b"prtr";
+pub const CLASS_LINK: [u8; 4] = *b"link";
+pub const CLASS_COLOR_SPACE: [u8; 4] = *b"spac";
+pub const CLASS_ABSTRACT: [u8; 4] = *b"abst";
+pub const CLASS_NAMED_COLOR: [u8; 4] = *b"nmcl";
+
+pub const SPACE_XYZ: [u8; 4] = *b"XYZ ";
+pub const SPACE_LAB: [u8; 4] = *b"Lab ";
+pub const SPACE_LUV: [u8; 4] = *b"Luv ";
+pub const SPACE_YCBCR: [u8; 4] = *b"YCbr";
+pub const SPACE_YXY: [u8; 4] = *b"Yxy ";
+pub const SPACE_RGB: [u8; 4] = *b"RGB ";
+pub const SPACE_GRAY: [u8; 4] = *b"GRAY";
+pub const SPACE_HSV: [u8; 4] = *b"HSV ";
+pub const SPACE_HLS: [u8; 4] = *b"HLS ";
+pub const SPACE_CMYK: [u8; 4] = *b"CMYK";
+pub const SPACE_CMY: [u8; 4] = *b"CMY ";
+
+pub const PLATFORM_APPLE: [u8; 4] = *b"APPL";
+pub const PLATFORM_MICROSOFT: [u8; 4] = *b"MSFT";
+pub const PLATFORM_SGI: [u8; 4] = *b"SGI ";
+pub const PLATFORM_SUN: [u8; 4] = *b"SUNW";
+
+pub const FLAG_EMBEDDED: u32 = 0x0000_0001;
+pub const FLAG_NOT_INDEPENDENT: u32 = 0x0000_0002;
+
+pub const INTENT_PERCEPTUAL: u32 = 0;
+pub const INTENT_RELATIVE_COLORIMETRIC: u32 = 1;
+pub const INTENT_SATURATION: u32 = 2;
+pub const INTENT_ABSOLUTE_COLORIMETRIC: u32 = 3;
+
+pub const TAG_A_TO_B0: [u8; 4] = *b"A2B0";
+pub const TAG_A_TO_B1: [u8; 4] = *b"A2B1";
+pub const TAG_A_TO_B2: [u8; 4] = *b"A2B2";
+pub const TAG_B_TO_A0: [u8; 4] = *b"B2A0";
+pub const TAG_B_TO_A1: [u8; 4] = *b"B2A1";
+pub const TAG_B_TO_A2: [u8; 4] = *b"B2A2";
+pub const TAG_BLUE_MATRIX_COLUMN: [u8; 4] = *b"bXYZ";
+pub const TAG_BLUE_TRC: [u8; 4] = *b"bTRC";
+pub const TAG_CHROMATIC_ADAPTATION: [u8; 4] = *b"chad";
+pub const TAG_CHROMATICITY: [u8; 4] = *b"chrm";
+pub const TAG_COPYRIGHT: [u8; 4] = *b"cprt";
+pub const TAG_DEVICE_MFG_DESC: [u8; 4] = *b"dmnd";
+pub const TAG_DEVICE_MODEL_DESC: [u8; 4] = *b"dmdd";
+pub const TAG_GAMUT: [u8; 4] = *b"gamt";
+pub const TAG_GRAY_TRC: [u8; 4] = *b"kTRC";
+pub const TAG_GREEN_MATRIX_COLUMN: [u8; 4] = *b"gXYZ";
+pub const TAG_GREEN_TRC: [u8; 4] = *b"gTRC";
+pub const TAG_LUMINANCE: [u8; 4] = *b"lumi";
+pub const TAG_MEASUREMENT: [u8; 4] = *b"meas";
+pub const TAG_MEDIA_BLACK_POINT: [u8; 4] = *b"bkpt";
+pub const TAG_MEDIA_WHITE_POINT: [u8; 4] = *b"wtpt";
+pub const TAG_PROFILE_DESCRIPTION: [u8; 4] = *b"desc";
+pub const TAG_RED_MATRIX_COLUMN: [u8; 4] = *b"rXYZ";
+pub const TAG_RED_TRC: [u8; 4] = *b"rTRC";
+pub const TAG_TECHNOLOGY: [u8; 4] = *b"tech";
+pub const TAG_VIEWING_COND_DESC: [u8; 4] = *b"vued";
+pub const TAG_VIEWING_CONDITIONS: [u8; 4] = *b"view";
+
+pub const TYPE_CURVE: [u8; 4] = *b"curv";
+pub const TYPE_PARAMETRIC_CURVE: [u8; 4] = *b"para";
+pub const TYPE_XYZ: [u8; 4] = *b"XYZ ";
+pub const TYPE_TEXT: [u8; 4] = *b"text";
+pub const TYPE_TEXT_DESCRIPTION: [u8; 4] = *b"desc";
+pub const TYPE_MULTI_LOCALIZED_UNICODE: [u8; 4] = *b"mluc";
+pub const TYPE_S15_FIXED16_ARRAY: [u8; 4] = *b"sf32";
+pub const TYPE_LUT8: [u8; 4] = *b"mft1";
+pub const TYPE_LUT16: [u8; 4] = *b"mft2";
+pub const TYPE_LUT_A_TO_B: [u8; 4] = *b"mAB ";
+pub const TYPE_LUT_B_TO_A: [u8; 4] = *b"mBA ";
+pub const TYPE_SIGNATURE: [u8; 4] = *b"sig ";
+
+/// Date and Time
+#[repr(C)]
+pub struct DateTime {
+    pub year: int::u16be,
+    pub month: int::u16be,
+    pub day: int::u16be,
+    pub hours: int::u16be,
+    pub minutes: int::u16be,
+    pub seconds: int::u16be,
+}
+
+/// CIE XYZ Tristimulus Value
+#[repr(C)]
+pub struct XyzNumber {
+    pub x: int::s15f16be,
+    pub y: int::s15f16be,
+    pub z: int::s15f16be,
+}
+
+/// Profile Header
+#[repr(C)]
+pub struct Header {
+    /// Size of the profile in bytes.
+    pub size: int::u32be,
+
+    /// Preferred CMM type signature.
+    pub cmm_type: [u8; 4],
+
+    /// Profile version. The major version is in the first byte, the minor
+    /// and bug-fix versions in the nibbles of the second byte.
+    pub version: int::u32be,
+
+    /// Device class (`CLASS_*`).
+    pub device_class: [u8; 4],
+
+    /// Data color space (`SPACE_*`).
+    pub color_space: [u8; 4],
+
+    /// Profile connection space, `SPACE_XYZ` or `SPACE_LAB`.
+    pub pcs: [u8; 4],
+
+    /// Creation date and time.
+    pub date_time: DateTime,
+
+    /// Profile file signature, must match `MAGIC`.
+    pub magic: [u8; 4],
+
+    /// Primary platform (`PLATFORM_*`), or 0.
+    pub platform: [u8; 4],
+
+    /// Profile flags (`FLAG_*`). The upper 16 bits are reserved for the CMM.
+    pub flags: int::u32be,
+
+    /// Device manufacturer signature.
+    pub manufacturer: [u8; 4],
+
+    /// Device model signature.
+    pub model: [u8; 4],
+
+    /// Device attributes.
+    pub attributes: int::u64be,
+
+    /// Rendering intent (`INTENT_*`).
+    pub rendering_intent: int::u32be,
+
+    /// Illuminant of the profile connection space, usually D50.
+    pub illuminant: XyzNumber,
+
+    /// Profile creator signature.
+    pub creator: [u8; 4],
+
+    /// MD5 of the profile with the flags, rendering intent, and profile ID
+    /// fields set to 0, or all 0 if not computed.
+    pub profile_id: [u8; 16],
+
+    /// Reserved, must be 0.
+    pub reserved: [u8; 28],
+}
+
+/// Tag Table Entry
+#[repr(C)]
+pub struct TagEntry {
+    /// Tag signature (`TAG_*`).
+    pub signature: [u8; 4],
+
+    /// Offset of the tag data from the start of the profile.
+    pub offset: int::u32be,
+
+    /// Size of the tag data in bytes.
+    pub size: int::u32be,
+}
+
+impl Header {
+    /// Import a header from a byte slice
+    ///
+    /// Create a new header from data copied from a byte slice. No byte-order
+    /// conversions are applied.
+    pub fn from_bytes(data: &[u8; 128]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 128], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Return profile version
+    ///
+    /// Return the profile version as `(major, minor, bugfix)`.
+    pub fn version(&self) -> (u8, u8, u8) {
+        let v = int::ForeignEndian::to_native(self.version).to_be_bytes();
+        (v[0], v[1] >> 4, v[1] & 0x0f)
+    }
+}
+
+impl TagEntry {
+    /// Import a tag entry from a byte slice
+    ///
+    /// Create a new tag entry from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 12]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 12], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+/// Find Tag
+///
+/// Search the tag table of the profile in `data` for the tag `signature`
+/// and return its data, including the type signature. `None` is returned if
+/// the tag is not found, or the profile is truncated.
+pub fn find_tag<'data>(data: &'data [u8], signature: &[u8; 4]) -> Option<&'data [u8]> {
+    let table = data.get(HEADER_SIZE..)?;
+    let count = u32::from_be_bytes(table.get(..4)?.try_into().unwrap()) as usize;
+
+    for i in 0..count {
+        let at = 4 + i.checked_mul(12)?;
+        let e = TagEntry::from_bytes(table.get(at..at + 12)?.try_into().unwrap());
+
+        if e.signature == *signature {
+            let offset = int::ForeignEndian::to_native(e.offset) as usize;
+            let size = int::ForeignEndian::to_native(e.size) as usize;
+            return data.get(offset..offset.checked_add(size)?);
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The D50 illuminant as `XyzNumber`.
+    const D50: [u8; 12] = [
+        0x00, 0x00, 0xf6, 0xd6, 0x00, 0x01, 0x00, 0x00,
+        0x00, 0x00, 0xd3, 0x2d,
+    ];
+
+    // Build a minimal display profile with a single white-point tag.
+    fn profile() -> [u8; 164] {
+        let mut p = [0u8; 164];
+
+        p[0..4].copy_from_slice(&164u32.to_be_bytes());
+        p[8..12].copy_from_slice(&[0x04, 0x30, 0x00, 0x00]);
+        p[12..16].copy_from_slice(&CLASS_DISPLAY);
+        p[16..20].copy_from_slice(&SPACE_RGB);
+        p[20..24].copy_from_slice(&SPACE_XYZ);
+        p[24..26].copy_from_slice(&2024u16.to_be_bytes());
+        p[36..40].copy_from_slice(&MAGIC);
+        p[68..80].copy_from_slice(&D50);
+
+        p[128..132].copy_from_slice(&1u32.to_be_bytes());
+        p[132..136].copy_from_slice(&TAG_MEDIA_WHITE_POINT);
+        p[136..140].copy_from_slice(&144u32.to_be_bytes());
+        p[140..144].copy_from_slice(&20u32.to_be_bytes());
+        p[144..148].copy_from_slice(&TYPE_XYZ);
+        p[152..164].copy_from_slice(&D50);
+
+        p
+    }
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<Header>(), HEADER_SIZE);
+        assert_eq!(align_of::<Header>(), 8);
+        assert_eq!(size_of::<DateTime>(), 12);
+        assert_eq!(size_of::<XyzNumber>(), 12);
+        assert_eq!(size_of::<TagEntry>(), 12);
+    }
+
+    // Parse the header and tag table of a profile.
+    #[test]
+    fn verify_profile() {
+        let p = profile();
+        let h = Header::from_bytes(p[..128].try_into().unwrap());
+
+        assert_eq!(int::ForeignEndian::to_native(h.size), 164);
+        assert_eq!(h.version(), (4, 3, 0));
+        assert_eq!(h.device_class, CLASS_DISPLAY);
+        assert_eq!(h.color_space, SPACE_RGB);
+        assert_eq!(h.magic, MAGIC);
+        assert_eq!(int::ForeignEndian::to_native(h.date_time.year), 2024);
+        assert_eq!(h.illuminant.y.to_bits(), 0x10000);
+
+        let t = find_tag(&p, &TAG_MEDIA_WHITE_POINT).unwrap();
+        assert_eq!(t.len(), 20);
+        assert_eq!(t[..4], TYPE_XYZ);
+        assert!(find_tag(&p, &TAG_COPYRIGHT).is_none());
+    }
+
+    // Verify fixed-point conversions of the D50 illuminant.
+    #[test]
+    fn verify_fixed() {
+        let p = profile();
+        let h = Header::from_bytes(p[..128].try_into().unwrap());
+
+        assert!((h.illuminant.x.to_f64() - 0.9642).abs() < 0.0001);
+        assert!((h.illuminant.y.to_f64() - 1.0).abs() < 0.0001);
+        assert!((h.illuminant.z.to_f64() - 0.8249).abs() < 0.0001);
+
+        assert_eq!(int::s15f16be::from_bits(-0x8000).to_f64(), -0.5);
+        assert_eq!(int::u8f8be::from_bits(0x0180).to_f64(), 1.5);
+    }
+}
@@ -0,0 +1,244 @@
+//! Internet Control Message Protocol
+//!
+//! ICMP messages are carried in IPv4 packets with `ipv4::PROTOCOL_ICMP`.
+//! Each message starts with a 4-byte `Header`, followed by 4 bytes whose
+//! meaning depends on the message type (e.g., `Echo`), and the message body.
+//! Error messages carry as much of the offending packet as fits, starting
+//! with its IPv4 header.
+//!
+//! The checksum is the Internet checksum (see `checksum::internet()`) of the
+//! entire ICMP message, calculated with the checksum field set to zero. Unlike
+//! ICMPv6, no pseudo-header is covered.
+//!
+//! All multi-byte integers are encoded as big-endian.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::{checksum, int};
+
+pub const HEADER_SIZE: usize = 4;
+pub const CHECKSUM_OFFSET: usize = 2;
+
+pub const TYPE_ECHO_REPLY: u8 = 0;
+pub const TYPE_DEST_UNREACHABLE: u8 = 3;
+pub const TYPE_SOURCE_QUENCH: u8 = 4;
+pub const TYPE_REDIRECT: u8 = 5;
+pub const TYPE_ECHO_REQUEST: u8 = 8;
+pub const TYPE_ROUTER_ADVERTISEMENT: u8 = 9;
+pub const TYPE_ROUTER_SOLICITATION: u8 = 10;
+pub const TYPE_TIME_EXCEEDED: u8 = 11;
+pub const TYPE_PARAMETER_PROBLEM: u8 = 12;
+pub const TYPE_TIMESTAMP_REQUEST: u8 = 13;
+pub const TYPE_TIMESTAMP_REPLY: u8 = 14;
+pub const TYPE_EXTENDED_ECHO_REQUEST: u8 = 42;
+pub const TYPE_EXTENDED_ECHO_REPLY: u8 = 43;
+
+pub const UNREACHABLE_NET: u8 = 0;
+pub const UNREACHABLE_HOST: u8 = 1;
+pub const UNREACHABLE_PROTOCOL: u8 = 2;
+pub const UNREACHABLE_PORT: u8 = 3;
+pub const UNREACHABLE_FRAGMENTATION_NEEDED: u8 = 4;
+pub const UNREACHABLE_SOURCE_ROUTE_FAILED: u8 = 5;
+pub const UNREACHABLE_NET_UNKNOWN: u8 = 6;
+pub const UNREACHABLE_HOST_UNKNOWN: u8 = 7;
+pub const UNREACHABLE_HOST_ISOLATED: u8 = 8;
+pub const UNREACHABLE_NET_PROHIBITED: u8 = 9;
+pub const UNREACHABLE_HOST_PROHIBITED: u8 = 10;
+pub const UNREACHABLE_NET_TOS: u8 = 11;
+pub const UNREACHABLE_HOST_TOS: u8 = 12;
+pub const UNREACHABLE_ADMIN_PROHIBITED: u8 = 13;
+pub const UNREACHABLE_PRECEDENCE_VIOLATION: u8 = 14;
+pub const UNREACHABLE_PRECEDENCE_CUTOFF: u8 = 15;
+
+pub const REDIRECT_NET: u8 = 0;
+pub const REDIRECT_HOST: u8 = 1;
+pub const REDIRECT_TOS_NET: u8 = 2;
+pub const REDIRECT_TOS_HOST: u8 = 3;
+
+pub const TIME_EXCEEDED_TTL: u8 = 0;
+pub const TIME_EXCEEDED_REASSEMBLY: u8 = 1;
+
+pub const PARAMETER_POINTER: u8 = 0;
+pub const PARAMETER_MISSING_OPTION: u8 = 1;
+pub const PARAMETER_BAD_LENGTH: u8 = 2;
+
+/// ICMP Header
+#[repr(C)]
+pub struct Header {
+    /// Message type (`TYPE_*`).
+    pub message_type: u8,
+
+    /// Message code, depending on the type (e.g., `UNREACHABLE_*`).
+    pub code: u8,
+
+    /// Checksum of the message.
+    pub checksum: int::u16be,
+}
+
+/// Echo Request and Reply
+///
+/// The 4 bytes following the header of echo, timestamp, and information
+/// messages. The rest of the message is echoed verbatim.
+#[repr(C)]
+pub struct Echo {
+    /// Identifier to match replies to requests.
+    pub identifier: int::u16be,
+
+    /// Sequence number to match replies to requests.
+    pub sequence: int::u16be,
+}
+
+/// Destination Unreachable
+///
+/// The 4 bytes following the header of destination unreachable messages.
+#[repr(C)]
+pub struct Unreachable {
+    /// Reserved, must be 0.
+    pub unused: int::u16be,
+
+    /// MTU of the next hop for `UNREACHABLE_FRAGMENTATION_NEEDED`, or 0.
+    pub next_hop_mtu: int::u16be,
+}
+
+impl Header {
+    /// Import a header from a byte slice
+    ///
+    /// Create a new header from data copied from a byte slice. No byte-order
+    /// conversions are applied.
+    pub fn from_bytes(data: &[u8; 4]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 4], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl Echo {
+    /// Import an echo message from a byte slice
+    ///
+    /// Create a new echo message from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 4]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 4], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl Unreachable {
+    /// Import a destination unreachable message from a byte slice
+    ///
+    /// Create a new destination unreachable message from data copied from a
+    /// byte slice. No byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 4]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 4], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+/// Check for error messages
+///
+/// Return whether `message_type` denotes an error message, which must not be
+/// sent in response to another error message.
+pub fn is_error(message_type: u8) -> bool {
+    matches!(
+        message_type,
+        TYPE_DEST_UNREACHABLE
+            | TYPE_SOURCE_QUENCH
+            | TYPE_REDIRECT
+            | TYPE_TIME_EXCEEDED
+            | TYPE_PARAMETER_PROBLEM
+    )
+}
+
+/// Calculate Message Checksum
+///
+/// Calculate the checksum of an entire ICMP message, treating the checksum
+/// field as zero. `message` must be at least `HEADER_SIZE` bytes.
+pub fn message_checksum(message: &[u8]) -> u16 {
+    let sum = checksum::internet_update(0, &message[..CHECKSUM_OFFSET]);
+    let sum = checksum::internet_update(sum, &message[CHECKSUM_OFFSET + 2..]);
+    checksum::internet_finish(sum)
+}
+
+/// Verify Message Checksum
+pub fn verify_checksum(message: &[u8]) -> bool {
+    checksum::internet(message) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // An echo request with 8 bytes of data.
+    const ECHO: [u8; 16] = [
+        0x08, 0x00, 0x54, 0x35, 0x12, 0x34, 0x00, 0x01,
+        0x61, 0x62, 0x63, 0x64, 0x65, 0x66, 0x67, 0x68,
+    ];
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<Header>(), HEADER_SIZE);
+        assert_eq!(align_of::<Header>(), 2);
+        assert_eq!(size_of::<Echo>(), 4);
+        assert_eq!(align_of::<Echo>(), 2);
+        assert_eq!(size_of::<Unreachable>(), 4);
+        assert_eq!(align_of::<Unreachable>(), 2);
+    }
+
+    // Parse an echo request and verify its checksum.
+    #[test]
+    fn verify_echo() {
+        let h = Header::from_bytes(ECHO[..4].try_into().unwrap());
+        assert_eq!(h.message_type, TYPE_ECHO_REQUEST);
+        assert_eq!(h.code, 0);
+        assert!(!is_error(h.message_type));
+
+        let e = Echo::from_bytes(ECHO[4..8].try_into().unwrap());
+        assert_eq!(int::ForeignEndian::to_native(e.identifier), 0x1234);
+        assert_eq!(int::ForeignEndian::to_native(e.sequence), 1);
+
+        assert_eq!(message_checksum(&ECHO), 0x5435);
+        assert!(verify_checksum(&ECHO));
+    }
+
+    // Turn the request into a reply, and parse an unreachable message.
+    #[test]
+    fn verify_reply() {
+        let mut data = ECHO;
+        data[0] = TYPE_ECHO_REPLY;
+        assert!(!verify_checksum(&data));
+        let v = message_checksum(&data);
+        data[2..4].copy_from_slice(&v.to_be_bytes());
+        assert!(verify_checksum(&data));
+        assert_eq!(v, 0x5c35);
+
+        let u = Unreachable::from_bytes(&[0x00, 0x00, 0x05, 0xdc]);
+        assert_eq!(int::ForeignEndian::to_native(u.next_hop_mtu), 1500);
+        assert!(is_error(TYPE_DEST_UNREACHABLE));
+        assert!(!is_error(TYPE_TIMESTAMP_REPLY));
+    }
+}
@@ -0,0 +1,507 @@
+//! Internet Control Message Protocol for IPv6
+//!
+//! ICMPv6 messages are carried in IPv6 packets with `ipv4::PROTOCOL_ICMPV6`.
+//! They share the 4-byte header layout of ICMP (`icmp::Header`), while
+//! message types and codes differ. Echo messages use the layout of
+//! `icmp::Echo`. Types below `TYPE_INFORMATIONAL` are error messages.
+//!
+//! Neighbor discovery (NDP) replaces ARP and ICMP router discovery. Its
+//! messages are followed by options (see `Options`), each made of a type and
+//! a length in units of 8 bytes. NDP messages must be sent with a hop limit
+//! of `NDP_HOP_LIMIT`, and receivers drop them otherwise.
+//!
+//! The checksum is the Internet checksum (see `checksum::internet()`) of the
+//! IPv6 pseudo-header (see `ipv6::PseudoHeader`) followed by the entire
+//! ICMPv6 message, calculated with the checksum field set to zero.
+//!
+//! All multi-byte integers are encoded as big-endian.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::{checksum, int, ipv4, ipv6};
+
+pub const CHECKSUM_OFFSET: usize = 2;
+
+/// Hop Limit of NDP Messages
+pub const NDP_HOP_LIMIT: u8 = 255;
+
+pub const TYPE_DEST_UNREACHABLE: u8 = 1;
+pub const TYPE_PACKET_TOO_BIG: u8 = 2;
+pub const TYPE_TIME_EXCEEDED: u8 = 3;
+pub const TYPE_PARAMETER_PROBLEM: u8 = 4;
+pub const TYPE_INFORMATIONAL: u8 = 128;
+pub const TYPE_ECHO_REQUEST: u8 = 128;
+pub const TYPE_ECHO_REPLY: u8 = 129;
+pub const TYPE_MLD_QUERY: u8 = 130;
+pub const TYPE_MLD_REPORT: u8 = 131;
+pub const TYPE_MLD_DONE: u8 = 132;
+pub const TYPE_ROUTER_SOLICITATION: u8 = 133;
+pub const TYPE_ROUTER_ADVERTISEMENT: u8 = 134;
+pub const TYPE_NEIGHBOR_SOLICITATION: u8 = 135;
+pub const TYPE_NEIGHBOR_ADVERTISEMENT: u8 = 136;
+pub const TYPE_REDIRECT: u8 = 137;
+pub const TYPE_MLDV2_REPORT: u8 = 143;
+
+pub const UNREACHABLE_NO_ROUTE: u8 = 0;
+pub const UNREACHABLE_ADMIN_PROHIBITED: u8 = 1;
+pub const UNREACHABLE_BEYOND_SCOPE: u8 = 2;
+pub const UNREACHABLE_ADDRESS: u8 = 3;
+pub const UNREACHABLE_PORT: u8 = 4;
+pub const UNREACHABLE_POLICY_FAILED: u8 = 5;
+pub const UNREACHABLE_REJECT_ROUTE: u8 = 6;
+
+pub const TIME_EXCEEDED_HOP_LIMIT: u8 = 0;
+pub const TIME_EXCEEDED_REASSEMBLY: u8 = 1;
+
+pub const PARAMETER_HEADER_FIELD: u8 = 0;
+pub const PARAMETER_NEXT_HEADER: u8 = 1;
+pub const PARAMETER_OPTION: u8 = 2;
+
+pub const RA_MANAGED: u8 = 0x80;
+pub const RA_OTHER: u8 = 0x40;
+pub const RA_HOME_AGENT: u8 = 0x20;
+pub const RA_PREFERENCE_MASK: u8 = 0x18;
+
+pub const NA_ROUTER: u32 = 0x8000_0000;
+pub const NA_SOLICITED: u32 = 0x4000_0000;
+pub const NA_OVERRIDE: u32 = 0x2000_0000;
+
+pub const OPTION_SOURCE_LINK_ADDRESS: u8 = 1;
+pub const OPTION_TARGET_LINK_ADDRESS: u8 = 2;
+pub const OPTION_PREFIX_INFORMATION: u8 = 3;
+pub const OPTION_REDIRECTED_HEADER: u8 = 4;
+pub const OPTION_MTU: u8 = 5;
+pub const OPTION_ROUTE_INFORMATION: u8 = 24;
+pub const OPTION_RDNSS: u8 = 25;
+pub const OPTION_DNSSL: u8 = 31;
+
+pub const PREFIX_ON_LINK: u8 = 0x80;
+pub const PREFIX_AUTONOMOUS: u8 = 0x40;
+
+/// Router Solicitation
+///
+/// The body following the header of `TYPE_ROUTER_SOLICITATION` messages.
+#[repr(C)]
+pub struct RouterSolicitation {
+    /// Reserved, must be 0.
+    pub reserved: int::u32be,
+}
+
+/// Router Advertisement
+///
+/// The body following the header of `TYPE_ROUTER_ADVERTISEMENT` messages.
+#[repr(C)]
+pub struct RouterAdvertisement {
+    /// Hop limit to use for outgoing packets, or 0 if unspecified.
+    pub cur_hop_limit: u8,
+
+    /// Flags (`RA_*`).
+    pub flags: u8,
+
+    /// Lifetime of the default router in seconds, or 0 if not a default
+    /// router.
+    pub router_lifetime: int::u16be,
+
+    /// Reachable time in milliseconds, or 0 if unspecified.
+    pub reachable_time: int::u32be,
+
+    /// Retransmission timer in milliseconds, or 0 if unspecified.
+    pub retrans_timer: int::u32be,
+}
+
+/// Neighbor Solicitation
+///
+/// The body following the header of `TYPE_NEIGHBOR_SOLICITATION` messages.
+#[repr(C)]
+pub struct NeighborSolicitation {
+    /// Reserved, must be 0.
+    pub reserved: int::u32be,
+
+    /// Address of the solicited neighbor.
+    pub target: [u8; 16],
+}
+
+/// Neighbor Advertisement
+///
+/// The body following the header of `TYPE_NEIGHBOR_ADVERTISEMENT` messages.
+#[repr(C)]
+pub struct NeighborAdvertisement {
+    /// Flags (`NA_*`), the remaining bits are reserved.
+    pub flags: int::u32be,
+
+    /// Address of the advertised neighbor.
+    pub target: [u8; 16],
+}
+
+/// Redirect
+///
+/// The body following the header of `TYPE_REDIRECT` messages.
+#[repr(C)]
+pub struct Redirect {
+    /// Reserved, must be 0.
+    pub reserved: int::u32be,
+
+    /// Address of the better first hop.
+    pub target: [u8; 16],
+
+    /// Address of the redirected destination.
+    pub destination: [u8; 16],
+}
+
+/// Prefix Information Option
+#[repr(C)]
+pub struct PrefixInformation {
+    /// Option type, must be `OPTION_PREFIX_INFORMATION`.
+    pub option_type: u8,
+
+    /// Option length in units of 8 bytes, must be 4.
+    pub length: u8,
+
+    /// Number of valid leading bits of the prefix.
+    pub prefix_length: u8,
+
+    /// Flags (`PREFIX_*`).
+    pub flags: u8,
+
+    /// Valid lifetime in seconds, or `0xffffffff` for infinity.
+    pub valid_lifetime: int::u32be,
+
+    /// Preferred lifetime in seconds, or `0xffffffff` for infinity.
+    pub preferred_lifetime: int::u32be,
+
+    /// Reserved, must be 0.
+    pub reserved: int::u32be,
+
+    /// The prefix, with any bits beyond the prefix length cleared.
+    pub prefix: [u8; 16],
+}
+
+/// MTU Option
+#[repr(C)]
+pub struct Mtu {
+    /// Option type, must be `OPTION_MTU`.
+    pub option_type: u8,
+
+    /// Option length in units of 8 bytes, must be 1.
+    pub length: u8,
+
+    /// Reserved, must be 0.
+    pub reserved: int::u16be,
+
+    /// MTU of the link.
+    pub mtu: int::u32be,
+}
+
+/// NDP Option Iterator
+///
+/// An iterator over the options of NDP messages, yielding the option type and
+/// the option data, excluding the type and length bytes. Iteration stops at
+/// the end of the data, or at the first option that is truncated or has a
+/// length of 0.
+#[derive(Clone, Debug)]
+pub struct Options<'data> {
+    data: &'data [u8],
+}
+
+impl RouterSolicitation {
+    /// Import a router solicitation from a byte slice
+    ///
+    /// Create a new router solicitation from data copied from a byte slice.
+    /// No byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 4]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 4], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl RouterAdvertisement {
+    /// Import a router advertisement from a byte slice
+    ///
+    /// Create a new router advertisement from data copied from a byte slice.
+    /// No byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 12]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 12], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl NeighborSolicitation {
+    /// Import a neighbor solicitation from a byte slice
+    ///
+    /// Create a new neighbor solicitation from data copied from a byte slice.
+    /// No byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 20]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 20], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl NeighborAdvertisement {
+    /// Import a neighbor advertisement from a byte slice
+    ///
+    /// Create a new neighbor advertisement from data copied from a byte
+    /// slice. No byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 20]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 20], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl Redirect {
+    /// Import a redirect from a byte slice
+    ///
+    /// Create a new redirect from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 36]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 36], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl PrefixInformation {
+    /// Import a prefix information option from a byte slice
+    ///
+    /// Create a new prefix information option from data copied from a byte
+    /// slice. No byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 32]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 32], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl Mtu {
+    /// Import an MTU option from a byte slice
+    ///
+    /// Create a new MTU option from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 8]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 8], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl<'data> Options<'data> {
+    /// Create option iterator
+    ///
+    /// Create a new iterator over the options in `data`, which must start
+    /// directly after the fixed part of an NDP message.
+    pub fn new(data: &'data [u8]) -> Self {
+        Self {
+            data,
+        }
+    }
+
+    /// Return remaining data
+    ///
+    /// Return the data that has not been consumed by the iterator.
+    pub fn remainder(&self) -> &'data [u8] {
+        self.data
+    }
+}
+
+impl<'data> Iterator for Options<'data> {
+    type Item = (u8, &'data [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.data.len() < 2 {
+            return None;
+        }
+
+        let size = self.data[1] as usize * 8;
+        if size == 0 || self.data.len() < size {
+            return None;
+        }
+
+        let option = (self.data[0], &self.data[2..size]);
+        self.data = &self.data[size..];
+        Some(option)
+    }
+}
+
+/// Check for error messages
+pub fn is_error(message_type: u8) -> bool {
+    message_type < TYPE_INFORMATIONAL
+}
+
+/// Calculate Message Checksum
+///
+/// Calculate the checksum of an entire ICMPv6 message sent from `source` to
+/// `destination`, treating the checksum field as zero. `message` must be at
+/// least `icmp::HEADER_SIZE` bytes.
+pub fn message_checksum(source: &[u8; 16], destination: &[u8; 16], message: &[u8]) -> u16 {
+    let ph = ipv6::PseudoHeader::new(*source, *destination, message.len() as u32, ipv4::PROTOCOL_ICMPV6);
+    let sum = checksum::internet_update(0, ph.as_bytes());
+    let sum = checksum::internet_update(sum, &message[..CHECKSUM_OFFSET]);
+    let sum = checksum::internet_update(sum, &message[CHECKSUM_OFFSET + 2..]);
+    checksum::internet_finish(sum)
+}
+
+/// Verify Message Checksum
+///
+/// Verify the checksum of an entire ICMPv6 message sent from `source` to
+/// `destination`.
+pub fn verify_checksum(source: &[u8; 16], destination: &[u8; 16], message: &[u8]) -> bool {
+    let ph = ipv6::PseudoHeader::new(*source, *destination, message.len() as u32, ipv4::PROTOCOL_ICMPV6);
+    let sum = checksum::internet_update(0, ph.as_bytes());
+    checksum::internet_finish(checksum::internet_update(sum, message)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::specs::icmp;
+
+    const SOURCE: [u8; 16] = [
+        0xfe, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01,
+    ];
+
+    // The solicited-node multicast address of fe80::2.
+    const DESTINATION: [u8; 16] = [
+        0xff, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x01, 0xff, 0x00, 0x00, 0x02,
+    ];
+
+    // A neighbor solicitation for fe80::2 with a source link-layer address.
+    const SOLICITATION: [u8; 32] = [
+        0x87, 0x00, 0x7a, 0x97, 0x00, 0x00, 0x00, 0x00,
+        0xfe, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02,
+        0x01, 0x01, 0x02, 0x00, 0x00, 0x00, 0x00, 0x01,
+    ];
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<RouterSolicitation>(), 4);
+        assert_eq!(align_of::<RouterSolicitation>(), 4);
+        assert_eq!(size_of::<RouterAdvertisement>(), 12);
+        assert_eq!(align_of::<RouterAdvertisement>(), 4);
+        assert_eq!(size_of::<NeighborSolicitation>(), 20);
+        assert_eq!(align_of::<NeighborSolicitation>(), 4);
+        assert_eq!(size_of::<NeighborAdvertisement>(), 20);
+        assert_eq!(align_of::<NeighborAdvertisement>(), 4);
+        assert_eq!(size_of::<Redirect>(), 36);
+        assert_eq!(align_of::<Redirect>(), 4);
+        assert_eq!(size_of::<PrefixInformation>(), 32);
+        assert_eq!(align_of::<PrefixInformation>(), 4);
+        assert_eq!(size_of::<Mtu>(), 8);
+        assert_eq!(align_of::<Mtu>(), 4);
+    }
+
+    // Parse a neighbor solicitation and verify its checksum, which covers
+    // the pseudo-header.
+    #[test]
+    fn verify_solicitation() {
+        let h = icmp::Header::from_bytes(SOLICITATION[..4].try_into().unwrap());
+        assert_eq!(h.message_type, TYPE_NEIGHBOR_SOLICITATION);
+        assert!(!is_error(h.message_type));
+
+        let ns = NeighborSolicitation::from_bytes(SOLICITATION[4..24].try_into().unwrap());
+        assert_eq!(int::ForeignEndian::to_native(ns.reserved), 0);
+        assert_eq!(ns.target[15], 2);
+
+        let mut opts = Options::new(&SOLICITATION[24..]);
+        assert_eq!(opts.next(), Some((OPTION_SOURCE_LINK_ADDRESS, &[0x02, 0, 0, 0, 0, 1][..])));
+        assert!(opts.next().is_none());
+
+        assert_eq!(message_checksum(&SOURCE, &DESTINATION, &SOLICITATION), 0x7a97);
+        assert!(verify_checksum(&SOURCE, &DESTINATION, &SOLICITATION));
+        assert!(!verify_checksum(&DESTINATION, &SOURCE, &SOLICITATION[..24]));
+        assert!(!verify_checksum(&SOURCE, &ipv6::ADDR_ALL_NODES, &SOLICITATION));
+
+        assert!(Options::new(&[OPTION_MTU, 0, 0, 0, 0, 0, 0, 0]).next().is_none());
+        assert!(Options::new(&SOLICITATION[24..31]).next().is_none());
+    }
+
+    // Parse a router advertisement body and its prefix and MTU options.
+    #[test]
+    fn verify_advertisement() {
+        let ra = RouterAdvertisement::from_bytes(&[
+            0x40, 0xc0, 0x07, 0x08, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00,
+        ]);
+        assert_eq!(ra.cur_hop_limit, 64);
+        assert_eq!(ra.flags, RA_MANAGED | RA_OTHER);
+        assert_eq!(int::ForeignEndian::to_native(ra.router_lifetime), 1800);
+
+        let opts = [
+            0x03, 0x04, 0x40, 0xc0, 0x00, 0x27, 0x8d, 0x00,
+            0x00, 0x09, 0x3a, 0x80, 0x00, 0x00, 0x00, 0x00,
+            0x20, 0x01, 0x0d, 0xb8, 0x00, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x05, 0x01, 0x00, 0x00, 0x00, 0x00, 0x05, 0xdc,
+        ];
+        let mut it = Options::new(&opts);
+        let (t, _) = it.next().unwrap();
+        assert_eq!(t, OPTION_PREFIX_INFORMATION);
+        let p = PrefixInformation::from_bytes(opts[..32].try_into().unwrap());
+        assert_eq!(p.prefix_length, 64);
+        assert_eq!(p.flags, PREFIX_ON_LINK | PREFIX_AUTONOMOUS);
+        assert_eq!(int::ForeignEndian::to_native(p.valid_lifetime), 2592000);
+        assert_eq!(int::ForeignEndian::to_native(p.preferred_lifetime), 604800);
+        assert_eq!(&p.prefix[..4], &[0x20, 0x01, 0x0d, 0xb8]);
+
+        let (t, data) = it.next().unwrap();
+        assert_eq!(t, OPTION_MTU);
+        assert_eq!(data.len(), 6);
+        let m = Mtu::from_bytes(opts[32..].try_into().unwrap());
+        assert_eq!(int::ForeignEndian::to_native(m.mtu), 1500);
+        assert!(it.next().is_none());
+
+        assert!(is_error(TYPE_PACKET_TOO_BIG));
+    }
+}
@@ -0,0 +1,280 @@
+//! Icon and Cursor Files
+//!
+//! The ICO and CUR formats store a set of images of different sizes and color
+//! depths in a single file. They share the same layout and differ only in the
+//! type field of the header, and in the meaning of two fields of each
+//! directory entry.
+//!
+//! A file starts with an `IconDir` header, followed by `count` directory
+//! entries of type `IconDirEntry`. Each entry references the image data by
+//! offset and size. The image data is either a complete PNG file, or a DIB:
+//! a `bmp::InfoHeader` followed by the color table, the XOR (color) bitmap,
+//! and the AND (transparency) mask. The DIB has no `bmp::FileHeader`, and its
+//! height is doubled to account for both bitmaps. The mask is a 1-bit bitmap
+//! with rows padded to 32 bits. Both variants are told apart by the PNG
+//! signature at the start of the image data.
+//!
+//! Width and height fields of directory entries are a single byte, with 0
+//! meaning 256. For cursors, the planes and bit-count fields carry the
+//! hotspot instead.
+//!
+//! Windows resources (`RT_GROUP_ICON` and `RT_GROUP_CURSOR`) use the same
+//! header, but their directory entries reference the images by resource ID
+//! rather than by offset. These entries are provided as `GroupIconDirEntry`.
+//!
+//! All multi-byte integers are encoded as little-endian.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::{bmp, int, png};
+
+pub const TYPE_ICON: u16 = 1;
+pub const TYPE_CURSOR: u16 = 2;
+
+/// Icon Directory
+///
+/// The header of ICO and CUR files, as well as of group icon and group
+/// cursor resources.
+#[repr(C)]
+pub struct IconDir {
+    /// Reserved, must be 0.
+    pub reserved: int::u16le,
+
+    /// File type, either `TYPE_ICON` or `TYPE_CURSOR`.
+    pub dir_type: int::u16le,
+
+    /// Number of directory entries following the header.
+    pub count: int::u16le,
+}
+
+/// Icon Directory Entry
+#[repr(C)]
+pub struct IconDirEntry {
+    /// Width in pixels, 0 means 256.
+    pub width: u8,
+
+    /// Height in pixels, 0 means 256.
+    pub height: u8,
+
+    /// Number of palette colors, or 0 if no palette is used.
+    pub color_count: u8,
+
+    /// Reserved, should be 0.
+    pub reserved: u8,
+
+    /// Color planes for icons, horizontal hotspot for cursors.
+    pub planes: int::u16le,
+
+    /// Bits per pixel for icons, vertical hotspot for cursors.
+    pub bit_count: int::u16le,
+
+    /// Size of the image data in bytes.
+    pub bytes_in_res: int::u32le,
+
+    /// Offset of the image data from the start of the file.
+    pub image_offset: int::u32le,
+}
+
+/// Group Icon Directory Entry
+///
+/// The directory entry used by group icon and group cursor resources. Since
+/// the structure is 14 bytes in size, entries are not naturally aligned and
+/// the structure is packed.
+#[repr(C, packed)]
+pub struct GroupIconDirEntry {
+    /// Width in pixels, 0 means 256.
+    pub width: u8,
+
+    /// Height in pixels, 0 means 256.
+    pub height: u8,
+
+    /// Number of palette colors, or 0 if no palette is used.
+    pub color_count: u8,
+
+    /// Reserved, should be 0.
+    pub reserved: u8,
+
+    /// Color planes for icons, horizontal hotspot for cursors.
+    pub planes: int::u16le,
+
+    /// Bits per pixel for icons, vertical hotspot for cursors.
+    pub bit_count: int::u16le,
+
+    /// Size of the image data in bytes.
+    pub bytes_in_res: int::u32le,
+
+    /// Resource ID of the `RT_ICON` or `RT_CURSOR` resource.
+    pub id: int::u16le,
+}
+
+impl IconDir {
+    /// Import a directory header from a byte slice
+    ///
+    /// Create a new directory header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 6]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 6], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl IconDirEntry {
+    /// Import a directory entry from a byte slice
+    ///
+    /// Create a new directory entry from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 16]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 16], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Return width in pixels
+    pub fn width(&self) -> u32 {
+        dimension(self.width)
+    }
+
+    /// Return height in pixels
+    pub fn height(&self) -> u32 {
+        dimension(self.height)
+    }
+
+    /// Return cursor hotspot
+    ///
+    /// Return the hotspot as `(x, y)`. This is only meaningful for entries of
+    /// files of type `TYPE_CURSOR`.
+    pub fn hotspot(&self) -> (u16, u16) {
+        (
+            int::ForeignEndian::to_native(self.planes),
+            int::ForeignEndian::to_native(self.bit_count),
+        )
+    }
+}
+
+impl GroupIconDirEntry {
+    /// Import a directory entry from a byte slice
+    ///
+    /// Create a new directory entry from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 14]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 14], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Return width in pixels
+    pub fn width(&self) -> u32 {
+        dimension(self.width)
+    }
+
+    /// Return height in pixels
+    pub fn height(&self) -> u32 {
+        dimension(self.height)
+    }
+}
+
+/// Decode Dimension
+///
+/// Decode a width or height field of a directory entry, where 0 means 256.
+pub fn dimension(v: u8) -> u32 {
+    if v == 0 { 256 } else { v as u32 }
+}
+
+/// Check for PNG Image Data
+///
+/// Return whether the image data of an entry is a PNG file. Otherwise, it is
+/// a DIB starting with a `bmp::InfoHeader`.
+pub fn is_png(data: &[u8]) -> bool {
+    data.starts_with(&png::MAGIC)
+}
+
+/// Calculate DIB Image Height
+///
+/// Return the height of the image as stored in the `bi_height` field of the
+/// `bmp::InfoHeader` of an entry, which covers the XOR bitmap and the AND
+/// mask, or `None` on overflow.
+pub fn dib_height(height: u32) -> Option<u32> {
+    height.checked_mul(2)
+}
+
+/// Calculate Mask Size
+///
+/// Return the size in bytes of the AND mask of a DIB entry with the given
+/// dimensions, or `None` on overflow.
+pub fn mask_size(width: u32, height: u32) -> Option<usize> {
+    bmp::row_stride(width, 1)?.checked_mul(height as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A cursor file header with a single 32x32 entry and a hotspot at (3, 5).
+    const CURSOR: [u8; 22] = [
+        0x00, 0x00, 0x02, 0x00, 0x01, 0x00, 0x20, 0x20,
+        0x00, 0x00, 0x03, 0x00, 0x05, 0x00, 0x30, 0x01,
+        0x00, 0x00, 0x16, 0x00, 0x00, 0x00,
+    ];
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<IconDir>(), 6);
+        assert_eq!(size_of::<IconDirEntry>(), 16);
+        assert_eq!(align_of::<IconDirEntry>(), 4);
+        assert_eq!(size_of::<GroupIconDirEntry>(), 14);
+        assert_eq!(align_of::<GroupIconDirEntry>(), 1);
+    }
+
+    // Parse a cursor directory.
+    #[test]
+    fn verify_cursor() {
+        let d = IconDir::from_bytes(CURSOR[..6].try_into().unwrap());
+        assert_eq!(int::ForeignEndian::to_native(d.dir_type), TYPE_CURSOR);
+        assert_eq!(int::ForeignEndian::to_native(d.count), 1);
+
+        let e = IconDirEntry::from_bytes(CURSOR[6..].try_into().unwrap());
+        assert_eq!((e.width(), e.height()), (32, 32));
+        assert_eq!(e.hotspot(), (3, 5));
+        assert_eq!(int::ForeignEndian::to_native(e.bytes_in_res), 0x130);
+        assert_eq!(int::ForeignEndian::to_native(e.image_offset), 22);
+    }
+
+    // Verify the quirks of entry fields and image data.
+    #[test]
+    fn verify_helpers() {
+        assert_eq!(dimension(0), 256);
+        assert_eq!(dimension(16), 16);
+
+        assert!(is_png(&png::MAGIC));
+        assert!(!is_png(&[0x28, 0x00, 0x00, 0x00]));
+
+        assert_eq!(dib_height(32), Some(64));
+        assert!(dib_height(u32::MAX / 2 + 1).is_none());
+        assert_eq!(mask_size(32, 32), Some(128));
+        assert_eq!(mask_size(16, 16), Some(64));
+    }
+}
@@ -0,0 +1,392 @@
+//! ID3v2 Tags
+//!
+//! ID3v2 tags carry metadata of audio files, most commonly at the start of
+//! MP3 files. A tag starts with a 10-byte `Header`, optionally followed by
+//! an extended header, followed by a sequence of frames, and optionally
+//! padding or a 10-byte footer. Each frame has a frame header with a frame
+//! ID, the frame size, and flags, followed by the frame data.
+//!
+//! Sizes in the tag header are encoded as synchsafe integers, which use only
+//! the low 7 bits of each byte, so they can never contain a false MPEG frame
+//! sync. ID3v2.4 uses synchsafe integers for frame sizes as well, while
+//! ID3v2.3 uses plain 32-bit integers for frame sizes. ID3v2.2 uses 3-byte
+//! frame IDs and 24-bit frame sizes instead.
+//!
+//! If the unsynchronisation flag is set, a 0x00 byte was inserted after
+//! every 0xff byte that is followed by a byte with its top 3 bits set or by
+//! 0x00. This must be reverted via `resynchronise()` before parsing.
+//!
+//! All multi-byte integers are encoded as big-endian.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::int;
+
+/// Tag Identifier
+///
+/// The initial 3 bytes of every tag header ("ID3").
+pub const MAGIC: [u8; 3] = [0x49, 0x44, 0x33];
+
+/// Footer Identifier
+///
+/// The initial 3 bytes of every tag footer ("3DI").
+pub const MAGIC_FOOTER: [u8; 3] = [0x33, 0x44, 0x49];
+
+/// Size of Headers and Footers
+pub const HEADER_SIZE: usize = 10;
+
+/// Size of Frame Headers
+///
+/// The size of ID3v2.3 and ID3v2.4 frame headers.
+pub const FRAME_HEADER_SIZE: usize = 10;
+
+/// Size of ID3v2.2 Frame Headers
+pub const FRAME_HEADER_V22_SIZE: usize = 6;
+
+pub const VERSION_2: u8 = 2;
+pub const VERSION_3: u8 = 3;
+pub const VERSION_4: u8 = 4;
+
+pub const FLAG_UNSYNCHRONISATION: u8 = 0x80;
+pub const FLAG_EXTENDED_HEADER: u8 = 0x40;
+pub const FLAG_EXPERIMENTAL: u8 = 0x20;
+pub const FLAG_FOOTER: u8 = 0x10;
+
+pub const FRAME_V3_TAG_ALTER: u16 = 0x8000;
+pub const FRAME_V3_FILE_ALTER: u16 = 0x4000;
+pub const FRAME_V3_READ_ONLY: u16 = 0x2000;
+pub const FRAME_V3_COMPRESSION: u16 = 0x0080;
+pub const FRAME_V3_ENCRYPTION: u16 = 0x0040;
+pub const FRAME_V3_GROUPING: u16 = 0x0020;
+
+pub const FRAME_V4_TAG_ALTER: u16 = 0x4000;
+pub const FRAME_V4_FILE_ALTER: u16 = 0x2000;
+pub const FRAME_V4_READ_ONLY: u16 = 0x1000;
+pub const FRAME_V4_GROUPING: u16 = 0x0040;
+pub const FRAME_V4_COMPRESSION: u16 = 0x0008;
+pub const FRAME_V4_ENCRYPTION: u16 = 0x0004;
+pub const FRAME_V4_UNSYNCHRONISATION: u16 = 0x0002;
+pub const FRAME_V4_DATA_LENGTH: u16 = 0x0001;
+
+pub const ENCODING_LATIN1: u8 = 0x00;
+pub const ENCODING_UTF16: u8 = 0x01;
+pub const ENCODING_UTF16BE: u8 = 0x02;
+pub const ENCODING_UTF8: u8 = 0x03;
+
+pub const FRAME_APIC: [u8; 4] = *b"APIC";
+pub const FRAME_COMM: [u8; 4] = *b"COMM";
+pub const FRAME_GEOB: [u8; 4] = *b"GEOB";
+pub const FRAME_PRIV: [u8; 4] = *b"PRIV";
+pub const FRAME_TALB: [u8; 4] = *b"TALB";
+pub const FRAME_TCON: [u8; 4] = *b"TCON";
+pub const FRAME_TDRC: [u8; 4] = *b"TDRC";
+pub const FRAME_TIT2: [u8; 4] = *b"TIT2";
+pub const FRAME_TLEN: [u8; 4] = *b"TLEN";
+pub const FRAME_TPE1: [u8; 4] = *b"TPE1";
+pub const FRAME_TPE2: [u8; 4] = *b"TPE2";
+pub const FRAME_TPOS: [u8; 4] = *b"TPOS";
+pub const FRAME_TRCK: [u8; 4] = *b"TRCK";
+pub const FRAME_TXXX: [u8; 4] = *b"TXXX";
+pub const FRAME_TYER: [u8; 4] = *b"TYER";
+pub const FRAME_USLT: [u8; 4] = *b"USLT";
+pub const FRAME_WXXX: [u8; 4] = *b"WXXX";
+
+/// Synchsafe Integer
+///
+/// A 28-bit unsigned integer encoded in 4 bytes, with 7 bits per byte and
+/// the most significant bit of each byte cleared. The bytes are stored in
+/// big-endian order.
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+#[repr(transparent)]
+pub struct Synchsafe {
+    raw: [u8; 4],
+}
+
+/// Tag Header
+///
+/// This is also the layout of the tag footer, which uses `MAGIC_FOOTER`
+/// instead.
+#[repr(C)]
+pub struct Header {
+    /// Tag identifier, must match `MAGIC`.
+    pub magic: [u8; 3],
+
+    /// Major version (`VERSION_*`).
+    pub version: u8,
+
+    /// Revision number.
+    pub revision: u8,
+
+    /// Tag flags (`FLAG_*`).
+    pub flags: u8,
+
+    /// Size of the tag, excluding the header and footer.
+    pub size: Synchsafe,
+}
+
+/// Frame Header
+///
+/// The frame header of ID3v2.3 and ID3v2.4. The encoding of the size
+/// depends on the version, see `size()`.
+#[repr(C)]
+pub struct FrameHeader {
+    /// Frame ID, made of uppercase letters and digits.
+    pub id: [u8; 4],
+
+    /// Size of the frame data, excluding the frame header.
+    pub size: [u8; 4],
+
+    /// Frame flags (`FRAME_V3_*` or `FRAME_V4_*`).
+    pub flags: int::u16be,
+}
+
+/// ID3v2.2 Frame Header
+#[repr(C)]
+pub struct FrameHeaderV22 {
+    /// Frame ID, made of uppercase letters and digits.
+    pub id: [u8; 3],
+
+    /// Size of the frame data, excluding the frame header.
+    pub size: int::u24be,
+}
+
+impl Synchsafe {
+    /// Maximum Value
+    pub const MAX: u32 = 0x0fff_ffff;
+
+    /// Create from raw value
+    pub fn from_raw(raw: [u8; 4]) -> Self {
+        Self { raw }
+    }
+
+    /// Return raw value
+    pub fn to_raw(self) -> [u8; 4] {
+        self.raw
+    }
+
+    /// Create from native value
+    ///
+    /// Encode `native` as synchsafe integer, or return `None` if it exceeds
+    /// `MAX`.
+    pub fn from_native(native: u32) -> Option<Self> {
+        if native > Self::MAX {
+            return None;
+        }
+
+        Some(Self {
+            raw: [
+                (native >> 21) as u8 & 0x7f,
+                (native >> 14) as u8 & 0x7f,
+                (native >> 7) as u8 & 0x7f,
+                native as u8 & 0x7f,
+            ],
+        })
+    }
+
+    /// Return native value
+    ///
+    /// Decode the synchsafe integer, or return `None` if any byte has its
+    /// most significant bit set.
+    pub fn to_native(self) -> Option<u32> {
+        if !self.is_valid() {
+            return None;
+        }
+
+        Some(self.raw.iter().fold(0, |acc, v| (acc << 7) | *v as u32))
+    }
+
+    /// Check for a valid encoding
+    pub fn is_valid(self) -> bool {
+        self.raw.iter().all(|v| v & 0x80 == 0)
+    }
+}
+
+impl Header {
+    /// Import a tag header from a byte slice
+    ///
+    /// Create a new tag header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 10]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 10], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Check for a valid header
+    ///
+    /// Return whether the identifier matches, the version is known, and the
+    /// size is a valid synchsafe integer.
+    pub fn is_valid(&self) -> bool {
+        self.magic == MAGIC
+            && (VERSION_2..=VERSION_4).contains(&self.version)
+            && self.size.is_valid()
+    }
+
+    /// Return total size
+    ///
+    /// Return the size of the entire tag, including header and footer, or
+    /// `None` if the size is invalid.
+    pub fn total_size(&self) -> Option<usize> {
+        let footer = self.version >= VERSION_4 && self.flags & FLAG_FOOTER != 0;
+        let size = self.size.to_native()? as usize;
+        Some(HEADER_SIZE + size + if footer { HEADER_SIZE } else { 0 })
+    }
+}
+
+impl FrameHeader {
+    /// Import a frame header from a byte slice
+    ///
+    /// Create a new frame header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 10]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 10], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Check for padding
+    ///
+    /// Padding follows the last frame, and is made of 0x00 bytes, so a
+    /// frame ID starting with 0x00 terminates the frame sequence.
+    pub fn is_padding(&self) -> bool {
+        self.id[0] == 0
+    }
+
+    /// Return frame size
+    ///
+    /// Decode the frame size according to the major version of the tag. For
+    /// ID3v2.4 the size is a synchsafe integer, and `None` is returned if
+    /// its encoding is invalid.
+    pub fn size(&self, version: u8) -> Option<u32> {
+        if version >= VERSION_4 {
+            Synchsafe::from_raw(self.size).to_native()
+        } else {
+            Some(u32::from_be_bytes(self.size))
+        }
+    }
+}
+
+impl FrameHeaderV22 {
+    /// Import a frame header from a byte slice
+    ///
+    /// Create a new frame header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 6]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 6], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+/// Revert Unsynchronisation
+///
+/// Copy `data` into `out`, dropping every 0x00 byte that follows a 0xff
+/// byte. Return the number of bytes written, or `None` if `out` is too
+/// small. `out` is never required to be larger than `data`.
+pub fn resynchronise(data: &[u8], out: &mut [u8]) -> Option<usize> {
+    let mut n = 0;
+    let mut prev = 0;
+
+    for v in data {
+        if prev == 0xff && *v == 0x00 {
+            prev = 0x00;
+            continue;
+        }
+
+        *out.get_mut(n)? = *v;
+        n += 1;
+        prev = *v;
+    }
+
+    Some(n)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // An ID3v2.4 tag with a single TIT2 frame and padding.
+    const TAG: [u8; 32] = [
+        0x49, 0x44, 0x33, 0x04, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x16, 0x54, 0x49, 0x54, 0x32, 0x00, 0x00,
+        0x00, 0x06, 0x00, 0x00, 0x03, 0x54, 0x69, 0x74,
+        0x6c, 0x65, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<Synchsafe>(), 4);
+        assert_eq!(align_of::<Synchsafe>(), 1);
+        assert_eq!(size_of::<Header>(), HEADER_SIZE);
+        assert_eq!(align_of::<Header>(), 1);
+        assert_eq!(size_of::<FrameHeader>(), FRAME_HEADER_SIZE);
+        assert_eq!(align_of::<FrameHeader>(), 2);
+        assert_eq!(size_of::<FrameHeaderV22>(), FRAME_HEADER_V22_SIZE);
+        assert_eq!(align_of::<FrameHeaderV22>(), 1);
+    }
+
+    // Verify synchsafe integer encoding and its limits.
+    #[test]
+    fn verify_synchsafe() {
+        let v = Synchsafe::from_native(257).unwrap();
+        assert_eq!(v.to_raw(), [0x00, 0x00, 0x02, 0x01]);
+        assert_eq!(v.to_native(), Some(257));
+
+        let v = Synchsafe::from_native(Synchsafe::MAX).unwrap();
+        assert_eq!(v.to_raw(), [0x7f, 0x7f, 0x7f, 0x7f]);
+        assert_eq!(v.to_native(), Some(Synchsafe::MAX));
+
+        assert!(Synchsafe::from_native(Synchsafe::MAX + 1).is_none());
+        assert!(Synchsafe::from_raw([0x00, 0x00, 0x00, 0x80]).to_native().is_none());
+
+        let mut out = [0; 6];
+        let n = resynchronise(&[0xff, 0x00, 0xe0, 0xff, 0x00, 0x00], &mut out).unwrap();
+        assert_eq!(&out[..n], [0xff, 0xe0, 0xff, 0x00]);
+        assert!(resynchronise(&[0x01, 0x02], &mut out[..1]).is_none());
+    }
+
+    // Parse a tag header and its first frame.
+    #[test]
+    fn verify_tag() {
+        let h = Header::from_bytes(TAG[..10].try_into().unwrap());
+        assert!(h.is_valid());
+        assert_eq!(h.version, VERSION_4);
+        assert_eq!(h.size.to_native(), Some(22));
+        assert_eq!(h.total_size(), Some(TAG.len()));
+
+        let f = FrameHeader::from_bytes(TAG[10..20].try_into().unwrap());
+        assert_eq!(f.id, FRAME_TIT2);
+        assert_eq!(f.size(VERSION_4), Some(6));
+        assert_eq!(f.size(VERSION_3), Some(6));
+        assert_eq!(TAG[20], ENCODING_UTF8);
+        assert_eq!(&TAG[21..26], b"Title");
+        assert!(FrameHeader::from_bytes(&[0; 10]).is_padding());
+
+        let f = FrameHeaderV22::from_bytes(&[0x54, 0x54, 0x32, 0x00, 0x01, 0x02]);
+        assert_eq!(&f.id, b"TT2");
+        assert_eq!(f.size.to_native(), 0x102);
+    }
+}
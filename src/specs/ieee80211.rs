@@ -0,0 +1,709 @@
+//! IEEE 802.11 Wireless LAN Frames
+//!
+//! 802.11 frames start with a MAC header whose layout depends on the frame
+//! type and flags given in the 16-bit frame control field (`FC_*`), followed
+//! by the frame body and the frame check sequence. Management and data frames
+//! start with a `Header` carrying 3 addresses, whose meaning depends on the
+//! `FC_TO_DS` and `FC_FROM_DS` flags (see `Header::destination()`). With both
+//! flags set, a fourth address follows (`FourAddressHeader`). QoS data frames
+//! append a 16-bit QoS control field (`QOS_*`), and, if `FC_ORDER` is set, a
+//! 32-bit HT control field. Control frames use shorter headers. See
+//! `header_size()` for the size of the header of a given frame.
+//!
+//! The body of management frames starts with fixed parameters depending on
+//! the subtype (e.g., `Beacon`, see `fixed_parameters_size()`), followed by
+//! a sequence of information elements (see `Elements`), encoded as TLVs with
+//! 1-byte identifier and 1-byte length. The body of data frames usually
+//! starts with an IEEE 802.2 LLC header with SNAP extension (see
+//! `ethernet::LlcSnap`).
+//!
+//! The FCS is the same CRC-32 as used by Ethernet (see `ethernet::fcs()`).
+//! Captured frames usually lack the FCS, and are often preceded by a
+//! Radiotap header (see `radiotap`).
+//!
+//! Unlike most network protocols, all multi-byte integers are encoded as
+//! little-endian, and bit fields are numbered starting at the least
+//! significant bit.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::int;
+
+pub const ADDRESS_SIZE: usize = 6;
+pub const HEADER_SIZE: usize = 24;
+pub const FOUR_ADDRESS_HEADER_SIZE: usize = 30;
+pub const QOS_CONTROL_SIZE: usize = 2;
+pub const HT_CONTROL_SIZE: usize = 4;
+pub const FCS_SIZE: usize = 4;
+pub const ELEMENT_HEADER_SIZE: usize = 2;
+
+pub const FC_VERSION_MASK: u16 = 0x0003;
+pub const FC_TYPE_MASK: u16 = 0x000c;
+pub const FC_TYPE_SHIFT: u32 = 2;
+pub const FC_SUBTYPE_MASK: u16 = 0x00f0;
+pub const FC_SUBTYPE_SHIFT: u32 = 4;
+pub const FC_TO_DS: u16 = 0x0100;
+pub const FC_FROM_DS: u16 = 0x0200;
+pub const FC_MORE_FRAGMENTS: u16 = 0x0400;
+pub const FC_RETRY: u16 = 0x0800;
+pub const FC_POWER_MANAGEMENT: u16 = 0x1000;
+pub const FC_MORE_DATA: u16 = 0x2000;
+pub const FC_PROTECTED: u16 = 0x4000;
+pub const FC_ORDER: u16 = 0x8000;
+
+pub const TYPE_MANAGEMENT: u8 = 0;
+pub const TYPE_CONTROL: u8 = 1;
+pub const TYPE_DATA: u8 = 2;
+pub const TYPE_EXTENSION: u8 = 3;
+
+pub const SUBTYPE_ASSOCIATION_REQUEST: u8 = 0;
+pub const SUBTYPE_ASSOCIATION_RESPONSE: u8 = 1;
+pub const SUBTYPE_REASSOCIATION_REQUEST: u8 = 2;
+pub const SUBTYPE_REASSOCIATION_RESPONSE: u8 = 3;
+pub const SUBTYPE_PROBE_REQUEST: u8 = 4;
+pub const SUBTYPE_PROBE_RESPONSE: u8 = 5;
+pub const SUBTYPE_TIMING_ADVERTISEMENT: u8 = 6;
+pub const SUBTYPE_BEACON: u8 = 8;
+pub const SUBTYPE_ATIM: u8 = 9;
+pub const SUBTYPE_DISASSOCIATION: u8 = 10;
+pub const SUBTYPE_AUTHENTICATION: u8 = 11;
+pub const SUBTYPE_DEAUTHENTICATION: u8 = 12;
+pub const SUBTYPE_ACTION: u8 = 13;
+pub const SUBTYPE_ACTION_NO_ACK: u8 = 14;
+
+pub const SUBTYPE_TRIGGER: u8 = 2;
+pub const SUBTYPE_BEAMFORMING_REPORT_POLL: u8 = 4;
+pub const SUBTYPE_NDP_ANNOUNCEMENT: u8 = 5;
+pub const SUBTYPE_CONTROL_FRAME_EXTENSION: u8 = 6;
+pub const SUBTYPE_CONTROL_WRAPPER: u8 = 7;
+pub const SUBTYPE_BLOCK_ACK_REQUEST: u8 = 8;
+pub const SUBTYPE_BLOCK_ACK: u8 = 9;
+pub const SUBTYPE_PS_POLL: u8 = 10;
+pub const SUBTYPE_RTS: u8 = 11;
+pub const SUBTYPE_CTS: u8 = 12;
+pub const SUBTYPE_ACK: u8 = 13;
+pub const SUBTYPE_CF_END: u8 = 14;
+pub const SUBTYPE_CF_END_ACK: u8 = 15;
+
+pub const SUBTYPE_DATA: u8 = 0;
+pub const SUBTYPE_NULL: u8 = 4;
+pub const SUBTYPE_QOS_DATA: u8 = 8;
+pub const SUBTYPE_QOS_NULL: u8 = 12;
+
+/// Subtype Flag of QoS Data Frames
+pub const SUBTYPE_FLAG_QOS: u8 = 0x08;
+
+/// Subtype Flag of Data Frames Without Data
+pub const SUBTYPE_FLAG_NULL: u8 = 0x04;
+
+pub const SEQUENCE_FRAGMENT_MASK: u16 = 0x000f;
+pub const SEQUENCE_NUMBER_SHIFT: u32 = 4;
+
+pub const QOS_TID_MASK: u16 = 0x000f;
+pub const QOS_EOSP: u16 = 0x0010;
+pub const QOS_ACK_POLICY_MASK: u16 = 0x0060;
+pub const QOS_ACK_POLICY_SHIFT: u32 = 5;
+pub const QOS_AMSDU_PRESENT: u16 = 0x0080;
+
+pub const CAPABILITY_ESS: u16 = 0x0001;
+pub const CAPABILITY_IBSS: u16 = 0x0002;
+pub const CAPABILITY_PRIVACY: u16 = 0x0010;
+pub const CAPABILITY_SHORT_PREAMBLE: u16 = 0x0020;
+pub const CAPABILITY_SPECTRUM_MANAGEMENT: u16 = 0x0100;
+pub const CAPABILITY_QOS: u16 = 0x0200;
+pub const CAPABILITY_SHORT_SLOT_TIME: u16 = 0x0400;
+pub const CAPABILITY_RADIO_MEASUREMENT: u16 = 0x1000;
+
+pub const AUTHENTICATION_OPEN_SYSTEM: u16 = 0;
+pub const AUTHENTICATION_SHARED_KEY: u16 = 1;
+pub const AUTHENTICATION_FAST_BSS_TRANSITION: u16 = 2;
+pub const AUTHENTICATION_SAE: u16 = 3;
+pub const AUTHENTICATION_FILS_SK: u16 = 4;
+
+pub const STATUS_SUCCESS: u16 = 0;
+pub const STATUS_UNSPECIFIED_FAILURE: u16 = 1;
+pub const STATUS_CAPABILITIES_UNSUPPORTED: u16 = 10;
+pub const STATUS_REASSOCIATION_DENIED: u16 = 11;
+pub const STATUS_ASSOCIATION_DENIED: u16 = 12;
+pub const STATUS_ALGORITHM_UNSUPPORTED: u16 = 13;
+pub const STATUS_SEQUENCE_OUT_OF_ORDER: u16 = 14;
+pub const STATUS_CHALLENGE_FAILURE: u16 = 15;
+pub const STATUS_TIMEOUT: u16 = 16;
+pub const STATUS_AP_FULL: u16 = 17;
+pub const STATUS_RATES_UNSUPPORTED: u16 = 18;
+pub const STATUS_REFUSED_TEMPORARILY: u16 = 30;
+pub const STATUS_INVALID_RSN_ELEMENT: u16 = 72;
+pub const STATUS_ANTI_CLOGGING_TOKEN_REQUIRED: u16 = 76;
+
+pub const REASON_UNSPECIFIED: u16 = 1;
+pub const REASON_PREVIOUS_AUTHENTICATION_INVALID: u16 = 2;
+pub const REASON_DEAUTHENTICATION_LEAVING: u16 = 3;
+pub const REASON_DISASSOCIATION_INACTIVITY: u16 = 4;
+pub const REASON_DISASSOCIATION_AP_BUSY: u16 = 5;
+pub const REASON_CLASS2_FRAME_NONAUTHENTICATED: u16 = 6;
+pub const REASON_CLASS3_FRAME_NONASSOCIATED: u16 = 7;
+pub const REASON_DISASSOCIATION_LEAVING: u16 = 8;
+pub const REASON_NOT_AUTHENTICATED: u16 = 9;
+pub const REASON_INVALID_ELEMENT: u16 = 13;
+pub const REASON_MIC_FAILURE: u16 = 14;
+pub const REASON_FOUR_WAY_HANDSHAKE_TIMEOUT: u16 = 15;
+pub const REASON_GROUP_KEY_HANDSHAKE_TIMEOUT: u16 = 16;
+pub const REASON_IEEE8021X_AUTHENTICATION_FAILED: u16 = 23;
+
+pub const ELEMENT_SSID: u8 = 0;
+pub const ELEMENT_SUPPORTED_RATES: u8 = 1;
+pub const ELEMENT_DSSS_PARAMETER_SET: u8 = 3;
+pub const ELEMENT_TIM: u8 = 5;
+pub const ELEMENT_COUNTRY: u8 = 7;
+pub const ELEMENT_BSS_LOAD: u8 = 11;
+pub const ELEMENT_CHALLENGE_TEXT: u8 = 16;
+pub const ELEMENT_POWER_CONSTRAINT: u8 = 32;
+pub const ELEMENT_TPC_REPORT: u8 = 35;
+pub const ELEMENT_CHANNEL_SWITCH_ANNOUNCEMENT: u8 = 37;
+pub const ELEMENT_ERP: u8 = 42;
+pub const ELEMENT_HT_CAPABILITIES: u8 = 45;
+pub const ELEMENT_RSN: u8 = 48;
+pub const ELEMENT_EXTENDED_SUPPORTED_RATES: u8 = 50;
+pub const ELEMENT_MOBILITY_DOMAIN: u8 = 54;
+pub const ELEMENT_FAST_BSS_TRANSITION: u8 = 55;
+pub const ELEMENT_HT_OPERATION: u8 = 61;
+pub const ELEMENT_RM_ENABLED_CAPABILITIES: u8 = 70;
+pub const ELEMENT_MESH_ID: u8 = 114;
+pub const ELEMENT_EXTENDED_CAPABILITIES: u8 = 127;
+pub const ELEMENT_VHT_CAPABILITIES: u8 = 191;
+pub const ELEMENT_VHT_OPERATION: u8 = 192;
+pub const ELEMENT_VENDOR_SPECIFIC: u8 = 221;
+pub const ELEMENT_EXTENSION: u8 = 255;
+
+pub const ELEMENT_EXTENSION_FILS_SESSION: u8 = 4;
+pub const ELEMENT_EXTENSION_HE_CAPABILITIES: u8 = 35;
+pub const ELEMENT_EXTENSION_HE_OPERATION: u8 = 36;
+pub const ELEMENT_EXTENSION_EHT_OPERATION: u8 = 106;
+pub const ELEMENT_EXTENSION_MULTI_LINK: u8 = 107;
+pub const ELEMENT_EXTENSION_EHT_CAPABILITIES: u8 = 108;
+
+/// MAC Header of Management and Data Frames
+#[repr(C)]
+pub struct Header {
+    /// Frame control field (`FC_*`).
+    pub frame_control: int::u16le,
+
+    /// Duration in microseconds, or association ID in PS-Poll frames.
+    pub duration: int::u16le,
+
+    /// First address, always the receiver.
+    pub address1: [u8; 6],
+
+    /// Second address, always the transmitter.
+    pub address2: [u8; 6],
+
+    /// Third address, depending on `FC_TO_DS` and `FC_FROM_DS`.
+    pub address3: [u8; 6],
+
+    /// Fragment number in the lower 4 bits, followed by the sequence number.
+    pub sequence_control: int::u16le,
+}
+
+/// MAC Header of Frames Between Distribution Systems
+///
+/// Data frames with both `FC_TO_DS` and `FC_FROM_DS` set carry a fourth
+/// address, which is the source address.
+#[repr(C)]
+pub struct FourAddressHeader {
+    /// Common header.
+    pub header: Header,
+
+    /// Fourth address, the source.
+    pub address4: [u8; 6],
+}
+
+/// Fixed Parameters of Beacon and Probe Response Frames
+///
+/// Since the size of the parameters is not a multiple of the alignment of
+/// `timestamp`, this structure is packed.
+#[repr(C, packed)]
+pub struct Beacon {
+    /// TSF timer of the transmitter in microseconds.
+    pub timestamp: int::u64le,
+
+    /// Beacon interval in time units of 1024 microseconds.
+    pub beacon_interval: int::u16le,
+
+    /// Capability information (`CAPABILITY_*`).
+    pub capability: int::u16le,
+}
+
+/// Fixed Parameters of Authentication Frames
+#[repr(C)]
+pub struct Authentication {
+    /// Authentication algorithm (`AUTHENTICATION_*`).
+    pub algorithm: int::u16le,
+
+    /// Transaction sequence number, starting at 1.
+    pub transaction: int::u16le,
+
+    /// Status code (`STATUS_*`).
+    pub status: int::u16le,
+}
+
+/// Fixed Parameters of Association Request Frames
+#[repr(C)]
+pub struct AssociationRequest {
+    /// Capability information (`CAPABILITY_*`).
+    pub capability: int::u16le,
+
+    /// Listen interval in units of beacon intervals.
+    pub listen_interval: int::u16le,
+}
+
+/// Fixed Parameters of Reassociation Request Frames
+#[repr(C)]
+pub struct ReassociationRequest {
+    /// Capability information (`CAPABILITY_*`).
+    pub capability: int::u16le,
+
+    /// Listen interval in units of beacon intervals.
+    pub listen_interval: int::u16le,
+
+    /// Address of the access point currently associated with.
+    pub current_ap: [u8; 6],
+}
+
+/// Fixed Parameters of (Re)association Response Frames
+#[repr(C)]
+pub struct AssociationResponse {
+    /// Capability information (`CAPABILITY_*`).
+    pub capability: int::u16le,
+
+    /// Status code (`STATUS_*`).
+    pub status: int::u16le,
+
+    /// Association ID, with the 2 most significant bits set.
+    pub association_id: int::u16le,
+}
+
+/// Information Element Iterator
+///
+/// Iterate over the information elements of a frame body, yielding the
+/// identifier and data of each element. Iteration stops at the first element
+/// that exceeds the data. Elements with `ELEMENT_EXTENSION` carry an
+/// `ELEMENT_EXTENSION_*` identifier in the first byte of their data.
+#[derive(Clone, Debug)]
+pub struct Elements<'data> {
+    data: &'data [u8],
+}
+
+impl Header {
+    /// Import a header from a byte slice
+    ///
+    /// Create a new header from data copied from a byte slice. No byte-order
+    /// conversions are applied.
+    pub fn from_bytes(data: &[u8; 24]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 24], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Return destination address
+    ///
+    /// Return the address of the final recipient, which is the first address
+    /// unless `FC_TO_DS` is set.
+    pub fn destination(&self) -> &[u8; 6] {
+        let fc: u16 = int::ForeignEndian::to_native(self.frame_control);
+        if fc & FC_TO_DS == 0 {
+            &self.address1
+        } else {
+            &self.address3
+        }
+    }
+
+    /// Return source address
+    ///
+    /// Return the address of the original sender, which is the second
+    /// address unless `FC_FROM_DS` is set. Return `None` if both
+    /// `FC_TO_DS` and `FC_FROM_DS` are set, in which case it is the fourth
+    /// address (see `FourAddressHeader`).
+    pub fn source(&self) -> Option<&[u8; 6]> {
+        let fc: u16 = int::ForeignEndian::to_native(self.frame_control);
+        match fc & (FC_TO_DS | FC_FROM_DS) {
+            FC_FROM_DS => Some(&self.address3),
+            0 | FC_TO_DS => Some(&self.address2),
+            _ => None,
+        }
+    }
+
+    /// Return BSSID
+    ///
+    /// Return the BSSID, or `None` if both `FC_TO_DS` and `FC_FROM_DS` are
+    /// set, in which case the frame carries no BSSID.
+    pub fn bssid(&self) -> Option<&[u8; 6]> {
+        let fc: u16 = int::ForeignEndian::to_native(self.frame_control);
+        match fc & (FC_TO_DS | FC_FROM_DS) {
+            0 => Some(&self.address3),
+            FC_FROM_DS => Some(&self.address2),
+            FC_TO_DS => Some(&self.address1),
+            _ => None,
+        }
+    }
+
+    /// Return sequence number
+    pub fn sequence_number(&self) -> u16 {
+        int::ForeignEndian::to_native(self.sequence_control) >> SEQUENCE_NUMBER_SHIFT
+    }
+
+    /// Return fragment number
+    pub fn fragment_number(&self) -> u8 {
+        let v: u16 = int::ForeignEndian::to_native(self.sequence_control);
+        (v & SEQUENCE_FRAGMENT_MASK) as u8
+    }
+}
+
+impl FourAddressHeader {
+    /// Import a header from a byte slice
+    ///
+    /// Create a new header from data copied from a byte slice. No byte-order
+    /// conversions are applied.
+    pub fn from_bytes(data: &[u8; 30]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 30], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl Beacon {
+    /// Import parameters from a byte slice
+    ///
+    /// Create new parameters from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 12]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 12], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl Authentication {
+    /// Import parameters from a byte slice
+    ///
+    /// Create new parameters from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 6]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 6], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl AssociationRequest {
+    /// Import parameters from a byte slice
+    ///
+    /// Create new parameters from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 4]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 4], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl ReassociationRequest {
+    /// Import parameters from a byte slice
+    ///
+    /// Create new parameters from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 10]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 10], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl AssociationResponse {
+    /// Import parameters from a byte slice
+    ///
+    /// Create new parameters from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 6]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 6], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl<'data> Elements<'data> {
+    /// Create element iterator
+    ///
+    /// Create a new iterator over the information elements in `data`, which
+    /// must start right after the fixed parameters of the frame body.
+    pub fn new(data: &'data [u8]) -> Self {
+        Self { data }
+    }
+
+    /// Return remaining data
+    ///
+    /// Return the data that has not been consumed by the iterator.
+    pub fn remainder(&self) -> &'data [u8] {
+        self.data
+    }
+}
+
+impl<'data> Iterator for Elements<'data> {
+    type Item = (u8, &'data [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.data.len() < ELEMENT_HEADER_SIZE {
+            return None;
+        }
+
+        let id = self.data[0];
+        let end = ELEMENT_HEADER_SIZE + self.data[1] as usize;
+        let value = self.data.get(ELEMENT_HEADER_SIZE..end)?;
+        self.data = &self.data[end..];
+        Some((id, value))
+    }
+}
+
+/// Return frame type
+///
+/// Return the type (`TYPE_*`) encoded in a frame control field.
+pub fn frame_type(frame_control: u16) -> u8 {
+    ((frame_control & FC_TYPE_MASK) >> FC_TYPE_SHIFT) as u8
+}
+
+/// Return frame subtype
+///
+/// Return the subtype (`SUBTYPE_*`) encoded in a frame control field. Its
+/// meaning depends on the frame type.
+pub fn subtype(frame_control: u16) -> u8 {
+    ((frame_control & FC_SUBTYPE_MASK) >> FC_SUBTYPE_SHIFT) as u8
+}
+
+/// Check for QoS data frames
+///
+/// Return whether a frame control field describes a data frame with a QoS
+/// control field.
+pub fn is_qos_data(frame_control: u16) -> bool {
+    frame_type(frame_control) == TYPE_DATA
+        && subtype(frame_control) & SUBTYPE_FLAG_QOS != 0
+}
+
+/// Return size of MAC header
+///
+/// Return the size of the MAC header of a frame with the given frame control
+/// field, including the QoS and HT control fields, if present. Return `None`
+/// for unknown protocol versions and frame types.
+pub fn header_size(frame_control: u16) -> Option<usize> {
+    if frame_control & FC_VERSION_MASK != 0 {
+        return None;
+    }
+
+    let ht = if frame_control & FC_ORDER != 0 { HT_CONTROL_SIZE } else { 0 };
+    match frame_type(frame_control) {
+        TYPE_MANAGEMENT => Some(HEADER_SIZE + ht),
+        TYPE_CONTROL => match subtype(frame_control) {
+            SUBTYPE_CTS | SUBTYPE_ACK => Some(10),
+            _ => Some(16),
+        },
+        TYPE_DATA => {
+            let mut n = HEADER_SIZE;
+            if frame_control & (FC_TO_DS | FC_FROM_DS) == FC_TO_DS | FC_FROM_DS {
+                n += ADDRESS_SIZE;
+            }
+            if is_qos_data(frame_control) {
+                n += QOS_CONTROL_SIZE + ht;
+            }
+            Some(n)
+        },
+        _ => None,
+    }
+}
+
+/// Return size of fixed parameters
+///
+/// Return the size of the fixed parameters that start the body of a
+/// management frame of the given subtype, and precede its information
+/// elements. Return `None` for unknown subtypes and action frames, whose
+/// body depends on the action category.
+pub fn fixed_parameters_size(subtype: u8) -> Option<usize> {
+    match subtype {
+        SUBTYPE_ASSOCIATION_REQUEST => Some(4),
+        SUBTYPE_ASSOCIATION_RESPONSE | SUBTYPE_REASSOCIATION_RESPONSE => Some(6),
+        SUBTYPE_REASSOCIATION_REQUEST => Some(10),
+        SUBTYPE_PROBE_REQUEST | SUBTYPE_ATIM => Some(0),
+        SUBTYPE_PROBE_RESPONSE | SUBTYPE_BEACON => Some(12),
+        SUBTYPE_AUTHENTICATION => Some(6),
+        SUBTYPE_DISASSOCIATION | SUBTYPE_DEAUTHENTICATION => Some(2),
+        _ => None,
+    }
+}
+
+/// Find information element
+///
+/// Return the data of the first information element with the given
+/// identifier.
+pub fn find_element(data: &[u8], id: u8) -> Option<&[u8]> {
+    Elements::new(data).find(|v| v.0 == id).map(|v| v.1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::specs::int::ForeignEndian;
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<Header>(), HEADER_SIZE);
+        assert_eq!(align_of::<Header>(), 2);
+        assert_eq!(size_of::<FourAddressHeader>(), FOUR_ADDRESS_HEADER_SIZE);
+        assert_eq!(align_of::<FourAddressHeader>(), 2);
+        assert_eq!(size_of::<Beacon>(), 12);
+        assert_eq!(align_of::<Beacon>(), 1);
+        assert_eq!(size_of::<Authentication>(), 6);
+        assert_eq!(align_of::<Authentication>(), 2);
+        assert_eq!(size_of::<AssociationRequest>(), 4);
+        assert_eq!(align_of::<AssociationRequest>(), 2);
+        assert_eq!(size_of::<ReassociationRequest>(), 10);
+        assert_eq!(align_of::<ReassociationRequest>(), 2);
+        assert_eq!(size_of::<AssociationResponse>(), 6);
+        assert_eq!(align_of::<AssociationResponse>(), 2);
+    }
+
+    // Beacon frame with SSID "test", supported rates, and DS parameters.
+    const BEACON: [u8; 47] = [
+        0x80, 0x00, 0x00, 0x00, 0xff, 0xff, 0xff, 0xff,
+        0xff, 0xff, 0x02, 0x00, 0x00, 0x00, 0x00, 0x01,
+        0x02, 0x00, 0x00, 0x00, 0x00, 0x01, 0x30, 0x12,
+        0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08,
+        0x64, 0x00, 0x11, 0x04, 0x00, 0x04, 0x74, 0x65,
+        0x73, 0x74, 0x01, 0x02, 0x82, 0x84, 0x03,
+    ];
+
+    // Decode a beacon frame with its fixed parameters and elements.
+    #[test]
+    fn verify_beacon() {
+        let h = Header::from_bytes(BEACON[..24].try_into().unwrap());
+        let fc = h.frame_control.to_native();
+        assert_eq!(frame_type(fc), TYPE_MANAGEMENT);
+        assert_eq!(subtype(fc), SUBTYPE_BEACON);
+        assert_eq!(header_size(fc), Some(HEADER_SIZE));
+        assert_eq!(h.destination(), &[0xff; 6]);
+        assert_eq!(h.source(), Some(&[0x02, 0x00, 0x00, 0x00, 0x00, 0x01]));
+        assert_eq!(h.bssid(), h.source());
+        assert_eq!(h.sequence_number(), 0x123);
+        assert_eq!(h.fragment_number(), 0);
+
+        let n = fixed_parameters_size(subtype(fc)).unwrap();
+        let b = Beacon::from_bytes(BEACON[24..24 + n].try_into().unwrap());
+        assert_eq!(b.timestamp.to_native(), 0x0807060504030201);
+        assert_eq!(b.beacon_interval.to_native(), 100);
+        assert_eq!(
+            b.capability.to_native(),
+            CAPABILITY_ESS | CAPABILITY_PRIVACY | CAPABILITY_SHORT_SLOT_TIME,
+        );
+
+        let mut e = Elements::new(&BEACON[24 + n..]);
+        assert_eq!(e.next(), Some((ELEMENT_SSID, &b"test"[..])));
+        assert_eq!(e.next(), Some((ELEMENT_SUPPORTED_RATES, &[0x82, 0x84][..])));
+        assert_eq!(e.next(), None);
+        assert_eq!(e.remainder(), &[0x03]);
+        let elements = &BEACON[24 + n..];
+        assert_eq!(find_element(elements, ELEMENT_SUPPORTED_RATES), Some(&[0x82, 0x84][..]));
+        assert_eq!(find_element(elements, ELEMENT_TIM), None);
+    }
+
+    // Verify address roles and header sizes of data and control frames.
+    #[test]
+    fn verify_header() {
+        let mut data = [0u8; 30];
+        data[..2].copy_from_slice(&[0x88, 0x02]);
+        for i in 0..4 {
+            data[4 + 6 * i..10 + 6 * i].fill(i as u8 + 1);
+        }
+        data[22..24].copy_from_slice(&[0x01, 0x00]);
+        data[24..30].fill(4);
+
+        let h = Header::from_bytes(data[..24].try_into().unwrap());
+        let fc = h.frame_control.to_native();
+        assert!(is_qos_data(fc));
+        assert_eq!(header_size(fc), Some(26));
+        assert_eq!(h.destination(), &[1; 6]);
+        assert_eq!(h.bssid(), Some(&[2; 6]));
+        assert_eq!(h.source(), Some(&[3; 6]));
+        assert_eq!(h.fragment_number(), 1);
+
+        data[1] = 0x01;
+        let h = Header::from_bytes(data[..24].try_into().unwrap());
+        assert_eq!(h.destination(), &[3; 6]);
+        assert_eq!(h.bssid(), Some(&[1; 6]));
+        assert_eq!(h.source(), Some(&[2; 6]));
+
+        data[1] = 0x83;
+        let h = FourAddressHeader::from_bytes(&data);
+        let fc = h.header.frame_control.to_native();
+        assert_eq!(header_size(fc), Some(36));
+        assert_eq!(h.header.destination(), &[3; 6]);
+        assert_eq!(h.header.bssid(), None);
+        assert_eq!(h.header.source(), None);
+        assert_eq!(h.address4, [4; 6]);
+
+        assert_eq!(header_size(0x0008), Some(HEADER_SIZE));
+        assert_eq!(header_size(0x0348), Some(FOUR_ADDRESS_HEADER_SIZE));
+        assert_eq!(header_size(0x00d4), Some(10));
+        assert_eq!(header_size(0x00b4), Some(16));
+        assert_eq!(header_size(0x8080), Some(28));
+        assert_eq!(header_size(0x0081), None);
+        assert_eq!(header_size(0x00bc), None);
+
+        let a = Authentication::from_bytes(&[0x00, 0x00, 0x02, 0x00, 0x00, 0x00]);
+        assert_eq!(a.algorithm.to_native(), AUTHENTICATION_OPEN_SYSTEM);
+        assert_eq!(a.transaction.to_native(), 2);
+        assert_eq!(a.status.to_native(), STATUS_SUCCESS);
+    }
+}
@@ -0,0 +1,498 @@
+//! Intel Flash Descriptor
+//!
+//! The flash descriptor occupies the first 4 KiB of the SPI flash of Intel
+//! platforms. It partitions the flash into regions (descriptor, BIOS, ME,
+//! GbE, and more), grants bus masters access to them, and carries the soft
+//! straps that configure the chipset and processor before firmware runs.
+//!
+//! The descriptor starts with 16 reserved bytes, followed by the 32-bit
+//! `SIGNATURE` and the `DescriptorMap`. Descriptors of ICH8 and earlier
+//! carry the signature at offset 0 instead. The descriptor map locates the
+//! remaining sections by bases given in units of 16 bytes, relative to the
+//! start of the flash:
+//!
+//!  * Component section: Flash component parameters (`FLCOMP`, `FLILL`).
+//!  * Region section: One 32-bit `FLREG` register per region, with base and
+//!    limit in units of 4 KiB (see `Region`).
+//!  * Master section: One 32-bit `FLMSTR` register per bus master, with the
+//!    read and write permissions for each region.
+//!  * PCH and processor strap sections: Soft straps as arrays of 32-bit
+//!    words.
+//!
+//! The descriptor upper map at `UPPER_MAP_OFFSET` locates the VSCC table,
+//! and the OEM section occupies the last 256 bytes of the descriptor.
+//!
+//! All multi-byte integers are encoded as little-endian.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::int;
+
+/// Descriptor Signature
+///
+/// The 32-bit signature identifying a valid flash descriptor.
+pub const SIGNATURE: u32 = 0x0ff0_a55a;
+
+pub const SIGNATURE_OFFSET: usize = 0x10;
+pub const SIGNATURE_OFFSET_LEGACY: usize = 0x00;
+pub const DESCRIPTOR_SIZE: usize = 0x1000;
+pub const UPPER_MAP_OFFSET: usize = 0xefc;
+pub const OEM_SECTION_OFFSET: usize = 0xf00;
+pub const OEM_SECTION_SIZE: usize = 0x100;
+
+/// Section Base Granularity
+///
+/// The unit of the section bases of the descriptor map, in bytes.
+pub const BASE_GRANULARITY: usize = 16;
+
+pub const FLMAP0_FCBA_MASK: u32 = 0x0000_00ff;
+pub const FLMAP0_FCBA_SHIFT: u32 = 0;
+pub const FLMAP0_NC_MASK: u32 = 0x0000_0300;
+pub const FLMAP0_NC_SHIFT: u32 = 8;
+pub const FLMAP0_FRBA_MASK: u32 = 0x00ff_0000;
+pub const FLMAP0_FRBA_SHIFT: u32 = 16;
+pub const FLMAP0_NR_MASK: u32 = 0x0700_0000;
+pub const FLMAP0_NR_SHIFT: u32 = 24;
+
+pub const FLMAP1_FMBA_MASK: u32 = 0x0000_00ff;
+pub const FLMAP1_FMBA_SHIFT: u32 = 0;
+pub const FLMAP1_NM_MASK: u32 = 0x0000_0700;
+pub const FLMAP1_NM_SHIFT: u32 = 8;
+pub const FLMAP1_FPSBA_MASK: u32 = 0x00ff_0000;
+pub const FLMAP1_FPSBA_SHIFT: u32 = 16;
+pub const FLMAP1_PSL_MASK: u32 = 0xff00_0000;
+pub const FLMAP1_PSL_SHIFT: u32 = 24;
+
+pub const FLMAP2_FMSBA_MASK: u32 = 0x0000_00ff;
+pub const FLMAP2_FMSBA_SHIFT: u32 = 0;
+pub const FLMAP2_PSL_MASK: u32 = 0x0000_ff00;
+pub const FLMAP2_PSL_SHIFT: u32 = 8;
+pub const FLMAP2_ICCRIBA_MASK: u32 = 0x00ff_0000;
+pub const FLMAP2_ICCRIBA_SHIFT: u32 = 16;
+
+pub const FLUMAP1_VTBA_MASK: u32 = 0x0000_00ff;
+pub const FLUMAP1_VTBA_SHIFT: u32 = 0;
+pub const FLUMAP1_VTL_MASK: u32 = 0x0000_ff00;
+pub const FLUMAP1_VTL_SHIFT: u32 = 8;
+
+pub const REGION_DESCRIPTOR: usize = 0;
+pub const REGION_BIOS: usize = 1;
+pub const REGION_ME: usize = 2;
+pub const REGION_GBE: usize = 3;
+pub const REGION_PLATFORM_DATA: usize = 4;
+pub const REGION_DEVICE_EXPANSION: usize = 5;
+pub const REGION_SECONDARY_BIOS: usize = 6;
+pub const REGION_MICROCODE: usize = 7;
+pub const REGION_EC: usize = 8;
+pub const REGION_DEVICE_EXPANSION2: usize = 9;
+pub const REGION_IE: usize = 10;
+pub const REGION_10GBE0: usize = 11;
+pub const REGION_10GBE1: usize = 12;
+/* reserved: 13, 14 */
+pub const REGION_PTT: usize = 15;
+pub const REGION_COUNT: usize = 16;
+
+/// Region Granularity
+///
+/// The unit of region bases and limits, in bytes.
+pub const REGION_GRANULARITY: u32 = 0x1000;
+
+pub const FLREG_BASE_MASK: u32 = 0x0000_7fff;
+pub const FLREG_BASE_SHIFT: u32 = 0;
+pub const FLREG_LIMIT_MASK: u32 = 0x7fff_0000;
+pub const FLREG_LIMIT_SHIFT: u32 = 16;
+
+/// Unused Region
+///
+/// The `FLREG` value of an unused region, with the base above the limit.
+pub const FLREG_UNUSED: u32 = 0x0000_7fff;
+
+pub const MASTER_BIOS: usize = 0;
+pub const MASTER_ME: usize = 1;
+pub const MASTER_GBE: usize = 2;
+pub const MASTER_EC: usize = 4;
+
+pub const FLMSTR_V1_REQUESTER_ID_MASK: u32 = 0x0000_ffff;
+pub const FLMSTR_V1_READ_SHIFT: u32 = 16;
+pub const FLMSTR_V1_WRITE_SHIFT: u32 = 24;
+pub const FLMSTR_V2_EXT_READ_SHIFT: u32 = 0;
+pub const FLMSTR_V2_EXT_WRITE_SHIFT: u32 = 4;
+pub const FLMSTR_V2_READ_SHIFT: u32 = 8;
+pub const FLMSTR_V2_WRITE_SHIFT: u32 = 20;
+
+pub const FLCOMP_COMP1_DENSITY_MASK: u32 = 0x0000_000f;
+pub const FLCOMP_COMP2_DENSITY_MASK: u32 = 0x0000_00f0;
+pub const FLCOMP_COMP2_DENSITY_SHIFT: u32 = 4;
+pub const FLCOMP_READ_CLOCK_MASK: u32 = 0x0007_0000;
+pub const FLCOMP_READ_CLOCK_SHIFT: u32 = 17;
+pub const FLCOMP_FAST_READ_SUPPORT: u32 = 0x0010_0000;
+pub const FLCOMP_FAST_READ_CLOCK_MASK: u32 = 0x0038_0000;
+pub const FLCOMP_FAST_READ_CLOCK_SHIFT: u32 = 21;
+pub const FLCOMP_WRITE_CLOCK_MASK: u32 = 0x01c0_0000;
+pub const FLCOMP_WRITE_CLOCK_SHIFT: u32 = 24;
+pub const FLCOMP_READ_ID_CLOCK_MASK: u32 = 0x0e00_0000;
+pub const FLCOMP_READ_ID_CLOCK_SHIFT: u32 = 27;
+pub const FLCOMP_DUAL_OUTPUT_FAST_READ: u32 = 0x4000_0000;
+
+/// Descriptor Map
+///
+/// The signature and the descriptor map registers, at `SIGNATURE_OFFSET`.
+#[repr(C)]
+pub struct DescriptorMap {
+    /// The static signature. This must match `SIGNATURE`.
+    pub signature: int::u32le,
+
+    /// Component and region section bases and counts (`FLMAP0_*`).
+    pub flmap0: int::u32le,
+
+    /// Master and PCH strap section bases and counts (`FLMAP1_*`).
+    pub flmap1: int::u32le,
+
+    /// Processor strap section base and count (`FLMAP2_*`).
+    pub flmap2: int::u32le,
+}
+
+/// Flash Region
+///
+/// A decoded `FLREG` register. Both the base and the inclusive limit are
+/// byte offsets from the start of the flash.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Region {
+    /// Offset of the first byte of the region.
+    pub base: u32,
+
+    /// Offset of the last byte of the region.
+    pub limit: u32,
+}
+
+/// Flash Descriptor Reference
+///
+/// A reference to a flash image with a valid descriptor, providing access to
+/// the sections of the descriptor.
+#[derive(Clone, Copy, Debug)]
+pub struct Descriptor<'data> {
+    /// Flash image, starting at the start of the flash.
+    pub data: &'data [u8],
+
+    /// Offset of the signature in the flash image.
+    pub offset: usize,
+
+    /// Value of `FLMAP0`.
+    pub flmap0: u32,
+
+    /// Value of `FLMAP1`.
+    pub flmap1: u32,
+
+    /// Value of `FLMAP2`.
+    pub flmap2: u32,
+}
+
+impl DescriptorMap {
+    /// Import a descriptor map from a byte slice
+    ///
+    /// Create a new descriptor map from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 16]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 16], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl Region {
+    /// Decode a region register
+    ///
+    /// Decode the value of an `FLREG` register. `None` is returned for
+    /// unused regions, which have their base above their limit.
+    pub fn decode(flreg: u32) -> Option<Self> {
+        let base = (flreg & FLREG_BASE_MASK) >> FLREG_BASE_SHIFT;
+        let limit = (flreg & FLREG_LIMIT_MASK) >> FLREG_LIMIT_SHIFT;
+        if base > limit {
+            return None;
+        }
+
+        Some(Self {
+            base: base * REGION_GRANULARITY,
+            limit: limit * REGION_GRANULARITY + (REGION_GRANULARITY - 1),
+        })
+    }
+
+    /// Encode a region register
+    ///
+    /// Encode the region as value of an `FLREG` register. The base and limit
+    /// are truncated to the region granularity.
+    pub fn encode(&self) -> u32 {
+        let base = (self.base / REGION_GRANULARITY) << FLREG_BASE_SHIFT;
+        let limit = (self.limit / REGION_GRANULARITY) << FLREG_LIMIT_SHIFT;
+        (base & FLREG_BASE_MASK) | (limit & FLREG_LIMIT_MASK)
+    }
+
+    /// Return size of the region
+    pub fn size(&self) -> u32 {
+        self.limit - self.base + 1
+    }
+}
+
+impl<'data> Descriptor<'data> {
+    /// Parse a flash descriptor
+    ///
+    /// Parse the descriptor of the flash image `data`, checking for the
+    /// signature at `SIGNATURE_OFFSET`, and then at the legacy offset.
+    /// `None` is returned if no signature is found.
+    pub fn parse(data: &'data [u8]) -> Option<Self> {
+        [SIGNATURE_OFFSET, SIGNATURE_OFFSET_LEGACY].into_iter().find_map(|offset| {
+            let v = DescriptorMap::from_bytes(data.get(offset..offset + 16)?.try_into().ok()?);
+            if int::ForeignEndian::to_native(v.signature) != SIGNATURE {
+                return None;
+            }
+
+            Some(Self {
+                data,
+                offset,
+                flmap0: int::ForeignEndian::to_native(v.flmap0),
+                flmap1: int::ForeignEndian::to_native(v.flmap1),
+                flmap2: int::ForeignEndian::to_native(v.flmap2),
+            })
+        })
+    }
+
+    // Read the 32-bit register with the given index of a section.
+    fn register(&self, base: u32, index: usize) -> Option<u32> {
+        let offset = (base as usize) * BASE_GRANULARITY + index * 4;
+        let v = self.data.get(offset..offset + 4)?;
+        Some(u32::from_le_bytes(v.try_into().ok()?))
+    }
+
+    // Return a section of the given base and number of 32-bit words.
+    fn section(&self, base: u32, length: u32) -> Option<&'data [u8]> {
+        let offset = (base as usize) * BASE_GRANULARITY;
+        self.data.get(offset..offset + (length as usize) * 4)
+    }
+
+    /// Return component section base
+    ///
+    /// Return the base of the component section in units of 16 bytes.
+    pub fn component_base(&self) -> u32 {
+        (self.flmap0 & FLMAP0_FCBA_MASK) >> FLMAP0_FCBA_SHIFT
+    }
+
+    /// Return number of flash components
+    pub fn component_count(&self) -> u32 {
+        ((self.flmap0 & FLMAP0_NC_MASK) >> FLMAP0_NC_SHIFT) + 1
+    }
+
+    /// Return region section base
+    ///
+    /// Return the base of the region section in units of 16 bytes.
+    pub fn region_base(&self) -> u32 {
+        (self.flmap0 & FLMAP0_FRBA_MASK) >> FLMAP0_FRBA_SHIFT
+    }
+
+    /// Return master section base
+    ///
+    /// Return the base of the master section in units of 16 bytes.
+    pub fn master_base(&self) -> u32 {
+        (self.flmap1 & FLMAP1_FMBA_MASK) >> FLMAP1_FMBA_SHIFT
+    }
+
+    /// Return number of masters
+    pub fn master_count(&self) -> u32 {
+        (self.flmap1 & FLMAP1_NM_MASK) >> FLMAP1_NM_SHIFT
+    }
+
+    /// Return the `FLCOMP` register
+    pub fn flcomp(&self) -> Option<u32> {
+        self.register(self.component_base(), 0)
+    }
+
+    /// Return a region register
+    ///
+    /// Return the `FLREG` register of the region with the given index
+    /// (`REGION_*`), or `None` if out of bounds.
+    pub fn flreg(&self, index: usize) -> Option<u32> {
+        if index >= REGION_COUNT {
+            return None;
+        }
+
+        self.register(self.region_base(), index)
+    }
+
+    /// Return a region
+    ///
+    /// Return the decoded region with the given index (`REGION_*`), or
+    /// `None` if it is unused or out of bounds.
+    pub fn region(&self, index: usize) -> Option<Region> {
+        Region::decode(self.flreg(index)?)
+    }
+
+    /// Return region data
+    ///
+    /// Return the contents of the region with the given index (`REGION_*`)
+    /// from the flash image, or `None` if the region is unused or exceeds
+    /// the image.
+    pub fn region_data(&self, index: usize) -> Option<&'data [u8]> {
+        let r = self.region(index)?;
+        self.data.get(r.base as usize..=r.limit as usize)
+    }
+
+    /// Return a master register
+    ///
+    /// Return the `FLMSTR` register of the master with the given index
+    /// (`MASTER_*`, which is the 0-based index of `FLMSTR1` and onwards), or
+    /// `None` if out of bounds.
+    pub fn flmstr(&self, index: usize) -> Option<u32> {
+        self.register(self.master_base(), index)
+    }
+
+    /// Return PCH soft straps
+    ///
+    /// Return the PCH strap section, an array of 32-bit words.
+    pub fn pch_straps(&self) -> Option<&'data [u8]> {
+        self.section(
+            (self.flmap1 & FLMAP1_FPSBA_MASK) >> FLMAP1_FPSBA_SHIFT,
+            (self.flmap1 & FLMAP1_PSL_MASK) >> FLMAP1_PSL_SHIFT,
+        )
+    }
+
+    /// Return processor soft straps
+    ///
+    /// Return the processor strap section, an array of 32-bit words.
+    pub fn processor_straps(&self) -> Option<&'data [u8]> {
+        self.section(
+            (self.flmap2 & FLMAP2_FMSBA_MASK) >> FLMAP2_FMSBA_SHIFT,
+            (self.flmap2 & FLMAP2_PSL_MASK) >> FLMAP2_PSL_SHIFT,
+        )
+    }
+
+    /// Return a soft strap
+    ///
+    /// Return the PCH soft strap with the given index, or `None` if out of
+    /// bounds.
+    pub fn pch_strap(&self, index: usize) -> Option<u32> {
+        let v = self.pch_straps()?.get(index * 4..index * 4 + 4)?;
+        Some(u32::from_le_bytes(v.try_into().ok()?))
+    }
+}
+
+/// Decode legacy master access
+///
+/// Decode an `FLMSTR` register of ICH8 to Ivy Bridge chipsets into the bit
+/// masks of the regions the master can read and write, respectively.
+pub fn master_access_v1(flmstr: u32) -> (u16, u16) {
+    (
+        ((flmstr >> FLMSTR_V1_READ_SHIFT) & 0xff) as u16,
+        ((flmstr >> FLMSTR_V1_WRITE_SHIFT) & 0xff) as u16,
+    )
+}
+
+/// Decode master access
+///
+/// Decode an `FLMSTR` register of Skylake and later chipsets into the bit
+/// masks of the regions the master can read and write, respectively. This
+/// includes the extended permissions of regions 12 to 15.
+pub fn master_access_v2(flmstr: u32) -> (u16, u16) {
+    let read = ((flmstr >> FLMSTR_V2_READ_SHIFT) & 0xfff)
+        | (((flmstr >> FLMSTR_V2_EXT_READ_SHIFT) & 0xf) << 12);
+    let write = ((flmstr >> FLMSTR_V2_WRITE_SHIFT) & 0xfff)
+        | (((flmstr >> FLMSTR_V2_EXT_WRITE_SHIFT) & 0xf) << 12);
+    (read as u16, write as u16)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Build a descriptor of a 16 MiB flash image with descriptor, BIOS, ME,
+    // and GbE regions.
+    fn descriptor() -> std::vec::Vec<u8> {
+        let mut v = std::vec![0xffu8; DESCRIPTOR_SIZE];
+        let mut put = |offset: usize, value: u32| {
+            v[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+        };
+
+        put(0x10, SIGNATURE);
+        put(0x14, 0x0004_0003);
+        put(0x18, 0x1210_0208);
+        put(0x1c, 0x0000_0120);
+        put(0x30, 0x0000_0005);
+        put(0x40, 0x0000_0000);
+        put(0x44, 0x0fff_0600);
+        put(0x48, 0x05ff_0003);
+        put(0x4c, 0x0002_0001);
+        put(0x50, FLREG_UNUSED);
+        put(0x80, 0x00a0_0b00);
+        put(0x84, 0x00d0_0d00);
+        put(0x100, 0x1234_5678);
+        v
+    }
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<DescriptorMap>(), 16);
+        assert_eq!(align_of::<DescriptorMap>(), 4);
+    }
+
+    // Parse the descriptor map and decode all regions.
+    #[test]
+    fn verify_regions() {
+        let data = descriptor();
+        let d = Descriptor::parse(&data).unwrap();
+        assert_eq!(d.offset, SIGNATURE_OFFSET);
+        assert_eq!(d.component_base(), 0x03);
+        assert_eq!(d.component_count(), 1);
+        assert_eq!(d.region_base(), 0x04);
+        assert_eq!(d.master_base(), 0x08);
+        assert_eq!(d.master_count(), 2);
+        assert_eq!(d.flcomp().unwrap() & FLCOMP_COMP1_DENSITY_MASK, 5);
+
+        let r = d.region(REGION_DESCRIPTOR).unwrap();
+        assert_eq!((r.base, r.limit, r.size()), (0, 0xfff, 0x1000));
+        let r = d.region(REGION_BIOS).unwrap();
+        assert_eq!((r.base, r.limit), (0x600000, 0xffffff));
+        assert_eq!(r.encode(), 0x0fff_0600);
+        let r = d.region(REGION_ME).unwrap();
+        assert_eq!((r.base, r.limit), (0x3000, 0x5fffff));
+        let r = d.region(REGION_GBE).unwrap();
+        assert_eq!((r.base, r.limit), (0x1000, 0x2fff));
+        assert_eq!(d.region(REGION_PLATFORM_DATA), None);
+        assert_eq!(d.region(REGION_COUNT), None);
+        assert_eq!(d.region_data(REGION_DESCRIPTOR).unwrap().len(), DESCRIPTOR_SIZE);
+        assert_eq!(d.region_data(REGION_BIOS), None);
+
+        let mut v = data.clone();
+        v[0x10] = 0;
+        assert!(Descriptor::parse(&v).is_none());
+    }
+
+    // Decode master permissions and soft strap sections.
+    #[test]
+    fn verify_masters() {
+        let data = descriptor();
+        let d = Descriptor::parse(&data).unwrap();
+
+        let (read, write) = master_access_v2(d.flmstr(MASTER_BIOS).unwrap());
+        assert_eq!(read, 1 << REGION_DESCRIPTOR | 1 << REGION_BIOS | 1 << REGION_GBE);
+        assert_eq!(write, 1 << REGION_BIOS | 1 << REGION_GBE);
+        let (read, write) = master_access_v2(d.flmstr(MASTER_ME).unwrap());
+        assert_eq!(read, 0b1101);
+        assert_eq!(write, 0b1101);
+
+        assert_eq!(master_access_v1(0x0a0b_0000), (0x0b, 0x0a));
+
+        assert_eq!(d.pch_straps().unwrap().len(), 0x12 * 4);
+        assert_eq!(d.pch_strap(0), Some(0x1234_5678));
+        assert_eq!(d.pch_strap(0x12), None);
+        assert_eq!(d.processor_straps().unwrap().len(), 4);
+    }
+}
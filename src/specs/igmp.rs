@@ -0,0 +1,380 @@
+//! Internet Group Management Protocol
+//!
+//! IGMP messages are carried in IPv4 (`ipv4::PROTOCOL_IGMP`) with a TTL of 1
+//! and the router-alert option. IGMPv1 and IGMPv2 messages consist of a
+//! single 8-byte `Message`. IGMPv3 extends queries with a source list (see
+//! `Query`), and adds a new report format which carries a sequence of group
+//! records (see `Report` and `GroupRecords`). The length of a query tells
+//! the versions apart: 8 bytes for IGMPv1 and IGMPv2, at least 12 bytes for
+//! IGMPv3.
+//!
+//! The checksum is the Internet checksum of the entire message, the same as
+//! for ICMP (see `icmp::message_checksum()`).
+//!
+//! MLD, the IPv6 equivalent of IGMP, uses the same group records with IPv6
+//! addresses (see `mld`).
+//!
+//! All multi-byte integers are encoded as big-endian.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::int;
+
+pub const MESSAGE_SIZE: usize = 8;
+pub const QUERY_SIZE: usize = 12;
+pub const REPORT_SIZE: usize = 8;
+pub const GROUP_RECORD_SIZE: usize = 8;
+
+pub const ALL_SYSTEMS: [u8; 4] = [224, 0, 0, 1];
+pub const ALL_ROUTERS: [u8; 4] = [224, 0, 0, 2];
+pub const ALL_V3_ROUTERS: [u8; 4] = [224, 0, 0, 22];
+
+pub const TYPE_MEMBERSHIP_QUERY: u8 = 0x11;
+pub const TYPE_V1_MEMBERSHIP_REPORT: u8 = 0x12;
+pub const TYPE_V2_MEMBERSHIP_REPORT: u8 = 0x16;
+pub const TYPE_LEAVE_GROUP: u8 = 0x17;
+pub const TYPE_V3_MEMBERSHIP_REPORT: u8 = 0x22;
+
+pub const QUERY_S: u8 = 0x08;
+pub const QUERY_QRV_MASK: u8 = 0x07;
+
+pub const RECORD_MODE_IS_INCLUDE: u8 = 1;
+pub const RECORD_MODE_IS_EXCLUDE: u8 = 2;
+pub const RECORD_CHANGE_TO_INCLUDE: u8 = 3;
+pub const RECORD_CHANGE_TO_EXCLUDE: u8 = 4;
+pub const RECORD_ALLOW_NEW_SOURCES: u8 = 5;
+pub const RECORD_BLOCK_OLD_SOURCES: u8 = 6;
+
+/// IGMPv1 and IGMPv2 Message
+///
+/// This is also the start of IGMPv3 queries.
+#[repr(C)]
+pub struct Message {
+    /// Message type (`TYPE_*`).
+    pub message_type: u8,
+
+    /// Maximum response time of queries in 1/10 seconds, 0 for IGMPv1. For
+    /// IGMPv3, a code (see `decode_code()`).
+    pub max_resp: u8,
+
+    /// Checksum of the message.
+    pub checksum: int::u16be,
+
+    /// Group address, or 0 for general queries.
+    pub group: [u8; 4],
+}
+
+/// IGMPv3 Query
+///
+/// Followed by the source addresses, each 4 bytes.
+#[repr(C)]
+pub struct Query {
+    /// Message type, must be `TYPE_MEMBERSHIP_QUERY`.
+    pub message_type: u8,
+
+    /// Maximum response code (see `decode_code()`), in 1/10 seconds.
+    pub max_resp_code: u8,
+
+    /// Checksum of the message.
+    pub checksum: int::u16be,
+
+    /// Group address, or 0 for general queries.
+    pub group: [u8; 4],
+
+    /// Reserved bits, the suppress-router-side-processing flag
+    /// (`QUERY_S`), and the querier's robustness variable (`QUERY_QRV_MASK`).
+    pub flags: u8,
+
+    /// Querier's query interval code (see `decode_code()`), in seconds.
+    pub qqic: u8,
+
+    /// Number of source addresses.
+    pub sources: int::u16be,
+}
+
+/// IGMPv3 Membership Report
+///
+/// Followed by the group records (see `GroupRecords`).
+#[repr(C)]
+pub struct Report {
+    /// Message type, must be `TYPE_V3_MEMBERSHIP_REPORT`.
+    pub message_type: u8,
+
+    /// Reserved, must be 0.
+    pub reserved0: u8,
+
+    /// Checksum of the message.
+    pub checksum: int::u16be,
+
+    /// Reserved, must be 0.
+    pub reserved1: int::u16be,
+
+    /// Number of group records.
+    pub records: int::u16be,
+}
+
+/// IGMPv3 Group Record Header
+///
+/// Followed by the source addresses and auxiliary data.
+#[repr(C)]
+pub struct GroupRecordHeader {
+    /// Record type (`RECORD_*`).
+    pub record_type: u8,
+
+    /// Size of the auxiliary data in 4-byte units.
+    pub aux_data_len: u8,
+
+    /// Number of source addresses.
+    pub sources: int::u16be,
+
+    /// Group address.
+    pub group: [u8; 4],
+}
+
+/// Group Record Reference
+///
+/// A group record of IGMPv3 (`N` is 4) or MLDv2 (`N` is 16).
+#[derive(Clone, Copy, Debug)]
+pub struct GroupRecord<'data, const N: usize> {
+    /// Record type (`RECORD_*`).
+    pub record_type: u8,
+
+    /// Group address.
+    pub group: [u8; N],
+
+    /// Source addresses, each `N` bytes.
+    pub sources: &'data [u8],
+
+    /// Auxiliary data.
+    pub aux: &'data [u8],
+}
+
+/// Group Record Iterator
+///
+/// An iterator over the group records of IGMPv3 (`N` is 4) or MLDv2 (`N` is
+/// 16) reports. Iteration stops at the end of the data, or at the first
+/// truncated record.
+#[derive(Clone, Debug)]
+pub struct GroupRecords<'data, const N: usize> {
+    data: &'data [u8],
+}
+
+impl Message {
+    /// Import a message from a byte slice
+    ///
+    /// Create a new message from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 8]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 8], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl Query {
+    /// Import a query from a byte slice
+    ///
+    /// Create a new query from data copied from a byte slice. No byte-order
+    /// conversions are applied.
+    pub fn from_bytes(data: &[u8; 12]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 12], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Return querier's robustness variable
+    pub fn qrv(&self) -> u8 {
+        self.flags & QUERY_QRV_MASK
+    }
+
+    /// Check for suppress-router-side-processing flag
+    pub fn suppress(&self) -> bool {
+        self.flags & QUERY_S != 0
+    }
+}
+
+impl Report {
+    /// Import a report from a byte slice
+    ///
+    /// Create a new report from data copied from a byte slice. No byte-order
+    /// conversions are applied.
+    pub fn from_bytes(data: &[u8; 8]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 8], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl GroupRecordHeader {
+    /// Import a group record header from a byte slice
+    ///
+    /// Create a new group record header from data copied from a byte slice.
+    /// No byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 8]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 8], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl<'data, const N: usize> GroupRecords<'data, N> {
+    /// Create group record iterator
+    ///
+    /// Create a new iterator over the group records in `data`, which must
+    /// start directly after the report header.
+    pub fn new(data: &'data [u8]) -> Self {
+        Self {
+            data,
+        }
+    }
+
+    /// Return remaining data
+    ///
+    /// Return the data that has not been consumed by the iterator.
+    pub fn remainder(&self) -> &'data [u8] {
+        self.data
+    }
+}
+
+impl<'data, const N: usize> Iterator for GroupRecords<'data, N> {
+    type Item = GroupRecord<'data, N>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let header = self.data.get(..4 + N)?;
+        let n_aux = header[1] as usize * 4;
+        let n_sources = u16::from_be_bytes([header[2], header[3]]) as usize * N;
+        let end = 4 + N + n_sources + n_aux;
+        if self.data.len() < end {
+            return None;
+        }
+
+        let v = GroupRecord {
+            record_type: header[0],
+            group: header[4..].try_into().unwrap(),
+            sources: &self.data[4 + N..4 + N + n_sources],
+            aux: &self.data[4 + N + n_sources..end],
+        };
+        self.data = &self.data[end..];
+        Some(v)
+    }
+}
+
+/// Decode 8-bit time code
+///
+/// Decode the maximum response code or the querier's query interval code of
+/// IGMPv3 and MLDv2. Values below 128 are encoded literally, others as
+/// floating-point value with 3 bits of exponent and 4 bits of mantissa.
+pub fn decode_code(code: u8) -> u32 {
+    if code < 0x80 {
+        code as u32
+    } else {
+        ((code as u32 & 0x0f) | 0x10) << (((code >> 4) & 0x07) + 3)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // An IGMPv3 report joining 239.1.2.3 for a single source, and leaving
+    // 239.4.5.6 without sources.
+    const REPORT: [u8; 32] = [
+        0x22, 0x00, 0xea, 0xeb, 0x00, 0x00, 0x00, 0x02,
+        0x01, 0x00, 0x00, 0x01, 0xef, 0x01, 0x02, 0x03,
+        0x0a, 0x00, 0x00, 0x01, 0x03, 0x00, 0x00, 0x00,
+        0xef, 0x04, 0x05, 0x06, 0x00, 0x00, 0x00, 0x00,
+    ];
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<Message>(), MESSAGE_SIZE);
+        assert_eq!(align_of::<Message>(), 2);
+        assert_eq!(size_of::<Query>(), QUERY_SIZE);
+        assert_eq!(align_of::<Query>(), 2);
+        assert_eq!(size_of::<Report>(), REPORT_SIZE);
+        assert_eq!(align_of::<Report>(), 2);
+        assert_eq!(size_of::<GroupRecordHeader>(), GROUP_RECORD_SIZE);
+        assert_eq!(align_of::<GroupRecordHeader>(), 2);
+    }
+
+    // Parse a v3 report and its group records.
+    #[test]
+    fn verify_report() {
+        let r = Report::from_bytes(REPORT[..8].try_into().unwrap());
+        assert_eq!(r.message_type, TYPE_V3_MEMBERSHIP_REPORT);
+        assert_eq!(int::ForeignEndian::to_native(r.records), 2);
+        assert!(crate::specs::icmp::verify_checksum(&REPORT));
+
+        let mut records: GroupRecords<4> = GroupRecords::new(&REPORT[8..24]);
+        let v = records.next().unwrap();
+        assert_eq!(v.record_type, RECORD_MODE_IS_INCLUDE);
+        assert_eq!(v.group, [239, 1, 2, 3]);
+        assert_eq!(v.sources, &[10, 0, 0, 1]);
+        assert!(v.aux.is_empty());
+        assert!(records.next().is_none());
+        assert_eq!(records.remainder().len(), 4);
+
+        let h = GroupRecordHeader::from_bytes(REPORT[20..28].try_into().unwrap());
+        assert_eq!(h.record_type, RECORD_CHANGE_TO_INCLUDE);
+        assert_eq!(int::ForeignEndian::to_native(h.sources), 0);
+        assert_eq!(h.group, [239, 4, 5, 6]);
+
+        // The trailing auxiliary data is part of the second record.
+        let mut data = REPORT;
+        data[21] = 1;
+        let mut records: GroupRecords<4> = GroupRecords::new(&data[8..]);
+        assert!(records.next().is_some());
+        assert_eq!(records.next().unwrap().aux, &[0; 4]);
+        assert!(records.next().is_none());
+    }
+
+    // Parse v2 and v3 queries and decode their time codes.
+    #[test]
+    fn verify_query() {
+        let m = Message::from_bytes(&[0x11, 0x64, 0xee, 0x9b, 0x00, 0x00, 0x00, 0x00]);
+        assert_eq!(m.message_type, TYPE_MEMBERSHIP_QUERY);
+        assert_eq!(m.max_resp, 100);
+        assert_eq!(m.group, [0; 4]);
+
+        let q = Query::from_bytes(&[
+            0x11, 0x8f, 0x00, 0x00, 0xef, 0x01, 0x02, 0x03,
+            0x0a, 0x7d, 0x00, 0x00,
+        ]);
+        assert_eq!(q.group, [239, 1, 2, 3]);
+        assert_eq!(q.qrv(), 2);
+        assert!(q.suppress());
+        assert_eq!(decode_code(q.max_resp_code), 0x1f << 3);
+        assert_eq!(decode_code(q.qqic), 125);
+        assert_eq!(decode_code(0xff), 0x1f << 10);
+        assert_eq!(int::ForeignEndian::to_native(q.sources), 0);
+    }
+}
@@ -1,8 +1,8 @@
-/// Integer Handling
-///
-/// This module provides abstractions over integers as well as a set of utility
-/// classes that implement integer operations. It is mostly a losely coupled
-/// set of extensions to the standard library.
+//! Integer Handling
+//!
+//! This module provides abstractions over integers as well as a set of utility
+//! classes that implement integer operations. It is mostly a losely coupled
+//! set of extensions to the standard library.
 
 /// Internal Abstraction over Primitive Integers
 ///
@@ -138,7 +138,7 @@ pub struct BigEndian<T>
 // All `BigEndian` types are clonable.
 impl<T: Copy> Clone for BigEndian<T> {
     fn clone(&self) -> BigEndian<T> {
-        BigEndian { raw: self.raw }
+        *self
     }
 }
 
@@ -158,7 +158,7 @@ unsafe impl<T> ForeignEndian<T> for BigEndian<T>
     where T: PrimInt
 {
     fn from_raw(raw: T) -> Self {
-        Self { raw: raw }
+        Self { raw }
     }
 
     fn to_raw(self) -> T {
@@ -166,11 +166,11 @@ unsafe impl<T> ForeignEndian<T> for BigEndian<T>
     }
 
     fn from_native(native: T) -> Self {
-        Self { raw: native.to_le() }
+        Self { raw: native.to_be() }
     }
 
     fn to_native(self) -> T {
-        T::from_le(self.raw)
+        T::from_be(self.raw)
     }
 }
 
@@ -240,7 +240,7 @@ pub struct LittleEndian<T>
 // All `LittleEndian` types are clonable.
 impl<T: Copy> Clone for LittleEndian<T> {
     fn clone(&self) -> LittleEndian<T> {
-        LittleEndian { raw: self.raw }
+        *self
     }
 }
 
@@ -260,7 +260,7 @@ unsafe impl<T> ForeignEndian<T> for LittleEndian<T>
     where T: PrimInt
 {
     fn from_raw(raw: T) -> Self {
-        Self { raw: raw }
+        Self { raw }
     }
 
     fn to_raw(self) -> T {
@@ -323,6 +323,310 @@ unsafe impl<T> Send for LittleEndian<T>
 {
 }
 
+/// Byte Order
+///
+/// Identifies a byte order at runtime. Some formats select their byte order
+/// in a header field, rather than having it fixed by the specification. Such
+/// formats use `RuntimeEndian` for their fields and pass the byte order along
+/// on every conversion.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum Endianness {
+    Big,
+    Little,
+}
+
+impl Endianness {
+    /// Return the native byte order
+    ///
+    /// Return the byte order of the machine this code was compiled for.
+    pub fn native() -> Self {
+        if cfg!(target_endian = "big") {
+            Endianness::Big
+        } else {
+            Endianness::Little
+        }
+    }
+}
+
+/// Runtime-endian Encoded Values
+///
+/// Base structure that represents values encoded in a byte order that is
+/// only known at runtime. It is a simple wrapping-structure with the same
+/// alignment and size requirements as the type it wraps.
+///
+/// Unlike `BigEndian` and `LittleEndian`, this type cannot implement
+/// `ForeignEndian`, since the conversion requires the byte order as
+/// additional argument. Instead, all primitive integer types provide inherent
+/// methods of the same names, which take an `Endianness` where needed.
+#[repr(transparent)]
+pub struct RuntimeEndian<T>
+    where T: Copy
+{
+    raw: T,
+}
+
+// All `RuntimeEndian` types are clonable.
+impl<T: Copy> Clone for RuntimeEndian<T> {
+    fn clone(&self) -> RuntimeEndian<T> {
+        *self
+    }
+}
+
+// All `RuntimeEndian` types are copyable.
+impl<T: Copy> Copy for RuntimeEndian<T> {}
+
+// For debugging simply print the raw values.
+impl<T: Copy + core::fmt::Debug> core::fmt::Debug for RuntimeEndian<T> {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter<'_>) -> Result<(), core::fmt::Error> {
+        fmt.debug_struct("RuntimeEndian")
+           .field("raw", &self.raw)
+           .finish()
+    }
+}
+
+// The zero value is identical in all byte orders.
+impl<T: Copy + Default> Default for RuntimeEndian<T> {
+    fn default() -> Self {
+        Self { raw: Default::default() }
+    }
+}
+
+// Equality of raw values is independent of the byte order.
+impl<T: Copy + PartialEq> PartialEq for RuntimeEndian<T> {
+    fn eq(&self, other: &Self) -> bool {
+        <T as PartialEq>::eq(&self.raw, &other.raw)
+    }
+}
+
+// Implement the conversions on all primitive integers. This cannot be a
+// generic implementation, since `PrimInt` must not leak into public
+// interfaces.
+macro_rules! impl_runtime_endian {
+    ( $t:ident ) => {
+        impl RuntimeEndian<$t> {
+            /// Create from raw value
+            ///
+            /// Take the raw value `raw` in the foreign byte order and wrap
+            /// it without any modification.
+            pub fn from_raw(raw: $t) -> Self {
+                Self { raw }
+            }
+
+            /// Return raw value
+            ///
+            /// Return the raw value in the foreign byte order without any
+            /// modification.
+            pub fn to_raw(self) -> $t {
+                self.raw
+            }
+
+            /// Create value from native representation
+            ///
+            /// Create the value from a native value, converting it to the
+            /// byte order `endian`.
+            pub fn from_native(native: $t, endian: Endianness) -> Self {
+                match endian {
+                    Endianness::Big => Self { raw: native.to_be() },
+                    Endianness::Little => Self { raw: native.to_le() },
+                }
+            }
+
+            /// Return native representation
+            ///
+            /// Return the native representation of the value, converting it
+            /// from the byte order `endian`.
+            pub fn to_native(self, endian: Endianness) -> $t {
+                match endian {
+                    Endianness::Big => $t::from_be(self.raw),
+                    Endianness::Little => $t::from_le(self.raw),
+                }
+            }
+        }
+    }
+}
+
+impl_runtime_endian!(u8);
+impl_runtime_endian!(u16);
+impl_runtime_endian!(u32);
+impl_runtime_endian!(u64);
+impl_runtime_endian!(u128);
+impl_runtime_endian!(i8);
+impl_runtime_endian!(i16);
+impl_runtime_endian!(i32);
+impl_runtime_endian!(i64);
+impl_runtime_endian!(i128);
+
+/// Odd-width Big-endian Encoded Values
+///
+/// Base structure that represents unsigned integers of `N` bytes, encoded as
+/// big-endian, where `N` is not the size of any primitive integer. The value
+/// is stored as a byte array, so the structure has an alignment of 1.
+///
+/// Conversions are provided as inherent methods for all supported widths,
+/// using the next larger primitive integer as native representation. When
+/// converting from native values, any excess high bits are discarded.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct BigEndianN<const N: usize> {
+    raw: [u8; N],
+}
+
+/// Odd-width Little-endian Encoded Values
+///
+/// Base structure that represents unsigned integers of `N` bytes, encoded as
+/// little-endian. See `BigEndianN` for details.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct LittleEndianN<const N: usize> {
+    raw: [u8; N],
+}
+
+// The zero value is all zero bytes.
+impl<const N: usize> Default for BigEndianN<N> {
+    fn default() -> Self {
+        Self { raw: [0; N] }
+    }
+}
+
+// The zero value is all zero bytes.
+impl<const N: usize> Default for LittleEndianN<N> {
+    fn default() -> Self {
+        Self { raw: [0; N] }
+    }
+}
+
+// Implement the conversions for a given width by going through the native
+// representation `$t`, which must be larger than `$n` bytes.
+macro_rules! impl_endian_n {
+    ( $n:literal, $t:ident ) => {
+        impl BigEndianN<$n> {
+            /// Create from raw value
+            pub fn from_raw(raw: [u8; $n]) -> Self {
+                Self { raw }
+            }
+
+            /// Return raw value
+            pub fn to_raw(self) -> [u8; $n] {
+                self.raw
+            }
+
+            /// Create value from native representation
+            ///
+            /// Create the value from a native value, discarding any bits
+            /// that do not fit.
+            pub fn from_native(native: $t) -> Self {
+                let v = native.to_be_bytes();
+                let mut raw = [0; $n];
+                raw.copy_from_slice(&v[v.len() - $n..]);
+                Self { raw }
+            }
+
+            /// Return native representation
+            pub fn to_native(self) -> $t {
+                let mut v = [0; core::mem::size_of::<$t>()];
+                let l = v.len();
+                v[l - $n..].copy_from_slice(&self.raw);
+                $t::from_be_bytes(v)
+            }
+        }
+
+        impl LittleEndianN<$n> {
+            /// Create from raw value
+            pub fn from_raw(raw: [u8; $n]) -> Self {
+                Self { raw }
+            }
+
+            /// Return raw value
+            pub fn to_raw(self) -> [u8; $n] {
+                self.raw
+            }
+
+            /// Create value from native representation
+            ///
+            /// Create the value from a native value, discarding any bits
+            /// that do not fit.
+            pub fn from_native(native: $t) -> Self {
+                let v = native.to_le_bytes();
+                let mut raw = [0; $n];
+                raw.copy_from_slice(&v[..$n]);
+                Self { raw }
+            }
+
+            /// Return native representation
+            pub fn to_native(self) -> $t {
+                let mut v = [0; core::mem::size_of::<$t>()];
+                v[..$n].copy_from_slice(&self.raw);
+                $t::from_le_bytes(v)
+            }
+        }
+    }
+}
+
+impl_endian_n!(3, u32);
+impl_endian_n!(5, u64);
+impl_endian_n!(6, u64);
+impl_endian_n!(7, u64);
+
+/// Fixed-point Values
+///
+/// Base structure that represents binary fixed-point numbers with `FRAC`
+/// fractional bits, stored in the integer type `T`. `T` is usually one of the
+/// foreign-endian integer types, so byte-order conversion is handled as
+/// usual. The structure has the same size and alignment as `T`.
+///
+/// Conversions are provided as inherent methods on the supported
+/// combinations. `to_bits()` and `from_bits()` convert from and to the
+/// native integer with the fixed-point bits, while `to_f64()` provides the
+/// numeric value.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct FixedPoint<T, const FRAC: u32>
+    where T: Copy
+{
+    raw: T,
+}
+
+// Implement the conversions for fixed-point numbers based on the foreign
+// integer `$f` with the native integer `$t`.
+macro_rules! impl_fixed_point {
+    ( $f:ty, $t:ident, $frac:literal ) => {
+        impl FixedPoint<$f, $frac> {
+            /// Create from raw value
+            pub fn from_raw(raw: $f) -> Self {
+                Self { raw }
+            }
+
+            /// Return raw value
+            pub fn to_raw(self) -> $f {
+                self.raw
+            }
+
+            /// Create from native fixed-point bits
+            pub fn from_bits(bits: $t) -> Self {
+                Self { raw: <$f>::from_native(bits) }
+            }
+
+            /// Return native fixed-point bits
+            pub fn to_bits(self) -> $t {
+                self.raw.to_native()
+            }
+
+            /// Return numeric value
+            pub fn to_f64(self) -> f64 {
+                self.to_bits() as f64 / (1u64 << $frac) as f64
+            }
+        }
+    }
+}
+
+impl_fixed_point!(BigEndian<u16>, u16, 8);
+impl_fixed_point!(BigEndian<u16>, u16, 15);
+impl_fixed_point!(BigEndian<i16>, i16, 8);
+impl_fixed_point!(BigEndian<i16>, i16, 14);
+impl_fixed_point!(BigEndian<i32>, i32, 16);
+impl_fixed_point!(BigEndian<u32>, u32, 16);
+impl_fixed_point!(BigEndian<u64>, u64, 32);
+
 #[allow(non_camel_case_types)]
 pub type u8be = BigEndian<u8>;
 #[allow(non_camel_case_types)]
@@ -334,6 +638,14 @@ pub type u64be = BigEndian<u64>;
 #[allow(non_camel_case_types)]
 pub type u128be = BigEndian<u128>;
 #[allow(non_camel_case_types)]
+pub type u24be = BigEndianN<3>;
+#[allow(non_camel_case_types)]
+pub type u40be = BigEndianN<5>;
+#[allow(non_camel_case_types)]
+pub type u48be = BigEndianN<6>;
+#[allow(non_camel_case_types)]
+pub type u56be = BigEndianN<7>;
+#[allow(non_camel_case_types)]
 pub type i8be = BigEndian<i8>;
 #[allow(non_camel_case_types)]
 pub type i16be = BigEndian<i16>;
@@ -355,6 +667,14 @@ pub type u64le = LittleEndian<u64>;
 #[allow(non_camel_case_types)]
 pub type u128le = LittleEndian<u128>;
 #[allow(non_camel_case_types)]
+pub type u24le = LittleEndianN<3>;
+#[allow(non_camel_case_types)]
+pub type u40le = LittleEndianN<5>;
+#[allow(non_camel_case_types)]
+pub type u48le = LittleEndianN<6>;
+#[allow(non_camel_case_types)]
+pub type u56le = LittleEndianN<7>;
+#[allow(non_camel_case_types)]
 pub type i8le = LittleEndian<i8>;
 #[allow(non_camel_case_types)]
 pub type i16le = LittleEndian<i16>;
@@ -364,3 +684,164 @@ pub type i32le = LittleEndian<i32>;
 pub type i64le = LittleEndian<i64>;
 #[allow(non_camel_case_types)]
 pub type i128le = LittleEndian<i128>;
+
+#[allow(non_camel_case_types)]
+pub type u8re = RuntimeEndian<u8>;
+#[allow(non_camel_case_types)]
+pub type u16re = RuntimeEndian<u16>;
+#[allow(non_camel_case_types)]
+pub type u32re = RuntimeEndian<u32>;
+#[allow(non_camel_case_types)]
+pub type u64re = RuntimeEndian<u64>;
+#[allow(non_camel_case_types)]
+pub type u128re = RuntimeEndian<u128>;
+#[allow(non_camel_case_types)]
+pub type i8re = RuntimeEndian<i8>;
+#[allow(non_camel_case_types)]
+pub type i16re = RuntimeEndian<i16>;
+#[allow(non_camel_case_types)]
+pub type i32re = RuntimeEndian<i32>;
+#[allow(non_camel_case_types)]
+pub type i64re = RuntimeEndian<i64>;
+#[allow(non_camel_case_types)]
+pub type i128re = RuntimeEndian<i128>;
+
+#[allow(non_camel_case_types)]
+pub type u8f8be = FixedPoint<u16be, 8>;
+#[allow(non_camel_case_types)]
+pub type u1f15be = FixedPoint<u16be, 15>;
+#[allow(non_camel_case_types)]
+pub type s7f8be = FixedPoint<i16be, 8>;
+#[allow(non_camel_case_types)]
+pub type s1f14be = FixedPoint<i16be, 14>;
+#[allow(non_camel_case_types)]
+pub type s15f16be = FixedPoint<i32be, 16>;
+#[allow(non_camel_case_types)]
+pub type u16f16be = FixedPoint<u32be, 16>;
+#[allow(non_camel_case_types)]
+pub type u32f32be = FixedPoint<u64be, 32>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Verify the memory layout of big-endian integers, and their
+    // conversions from and to native values.
+    #[test]
+    fn verify_big_endian() {
+        let v = u16be::from_native(0x0102);
+        assert_eq!(v.to_raw().to_ne_bytes(), [0x01, 0x02]);
+        assert_eq!(v.to_native(), 0x0102);
+
+        let v = u32be::from_native(0x01020304);
+        assert_eq!(v.to_raw().to_ne_bytes(), [0x01, 0x02, 0x03, 0x04]);
+        assert_eq!(v.to_native(), 0x01020304);
+
+        let v = u64be::from_native(0x0102030405060708);
+        assert_eq!(
+            v.to_raw().to_ne_bytes(),
+            [0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08],
+        );
+        assert_eq!(v.to_native(), 0x0102030405060708);
+
+        let v = u32be::from_raw(u32::from_ne_bytes([0x0a, 0x0b, 0x0c, 0x0d]));
+        assert_eq!(v.to_native(), 0x0a0b0c0d);
+        assert_eq!(i16be::from_native(-2).to_raw().to_ne_bytes(), [0xff, 0xfe]);
+    }
+
+    // Verify the memory layout of little-endian integers, as counterpart
+    // to the big-endian layout.
+    #[test]
+    fn verify_little_endian() {
+        let v = u32le::from_native(0x01020304);
+        assert_eq!(v.to_raw().to_ne_bytes(), [0x04, 0x03, 0x02, 0x01]);
+        assert_eq!(v.to_native(), 0x01020304);
+    }
+
+    // Verify runtime-endian integers in both byte orders.
+    #[test]
+    fn verify_runtime_endian() {
+        let v = u32re::from_native(0x01020304, Endianness::Big);
+        assert_eq!(v.to_raw().to_ne_bytes(), [0x01, 0x02, 0x03, 0x04]);
+        assert_eq!(v.to_native(Endianness::Big), 0x01020304);
+        assert_eq!(v.to_native(Endianness::Little), 0x04030201);
+
+        let v = u32re::from_native(0x01020304, Endianness::Little);
+        assert_eq!(v.to_raw().to_ne_bytes(), [0x04, 0x03, 0x02, 0x01]);
+        assert_eq!(v.to_native(Endianness::Little), 0x01020304);
+
+        let v = u16re::from_raw(u16::from_ne_bytes([0x12, 0x34]));
+        assert_eq!(v.to_native(Endianness::Big), 0x1234);
+        assert_eq!(v.to_native(Endianness::Little), 0x3412);
+
+        for endian in [Endianness::Big, Endianness::Little] {
+            let v = i64re::from_native(-0x0102030405060708, endian);
+            assert_eq!(v.to_native(endian), -0x0102030405060708);
+            assert_eq!(u8re::from_native(0xab, endian).to_raw(), 0xab);
+        }
+
+        let native = u16re::from_native(0x1234, Endianness::native());
+        assert_eq!(native.to_raw(), 0x1234);
+        assert_eq!(u64re::default().to_native(Endianness::Big), 0);
+    }
+
+    // Verify the layout, round-trips, and truncation of odd-width integers.
+    #[test]
+    fn verify_endian_n() {
+        assert_eq!(core::mem::size_of::<u24be>(), 3);
+        assert_eq!(core::mem::align_of::<u56le>(), 1);
+
+        let v = u24be::from_native(0x010203);
+        assert_eq!(v.to_raw(), [0x01, 0x02, 0x03]);
+        assert_eq!(v.to_native(), 0x010203);
+        let v = u24le::from_native(0x010203);
+        assert_eq!(v.to_raw(), [0x03, 0x02, 0x01]);
+        assert_eq!(v.to_native(), 0x010203);
+
+        let v = u40be::from_native(0x0102030405);
+        assert_eq!(v.to_raw(), [0x01, 0x02, 0x03, 0x04, 0x05]);
+        let v = u48le::from_native(0x010203040506);
+        assert_eq!(v.to_raw(), [0x06, 0x05, 0x04, 0x03, 0x02, 0x01]);
+        let v = u56be::from_raw([0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07]);
+        assert_eq!(v.to_native(), 0x01020304050607);
+        assert_eq!(u56le::from_native(0x01020304050607).to_native(), 0x01020304050607);
+
+        // Excess high bits are discarded.
+        assert_eq!(u24be::from_native(0xff010203).to_native(), 0x010203);
+        assert_eq!(u24le::from_native(0xff010203).to_raw(), [0x03, 0x02, 0x01]);
+        assert_eq!(u40le::from_native(u64::MAX).to_native(), 0xff_ffff_ffff);
+        assert_eq!(u48be::from_native(0x1122_3344_5566_7788).to_native(), 0x3344_5566_7788);
+        assert_eq!(u24be::default().to_native(), 0);
+    }
+
+    // Verify fixed-point bits, their integer and fraction parts, and the
+    // signed variants.
+    #[test]
+    fn verify_fixed_point() {
+        let v = u16f16be::from_bits(0x0001_8000);
+        assert_eq!(v.to_raw().to_raw().to_ne_bytes(), [0x00, 0x01, 0x80, 0x00]);
+        assert_eq!(v.to_bits(), 0x0001_8000);
+        assert_eq!((v.to_bits() >> 16, v.to_bits() & 0xffff), (1, 0x8000));
+        assert_eq!(v.to_f64(), 1.5);
+        assert_eq!(u16f16be::from_raw(v.to_raw()), v);
+
+        let v = u8f8be::from_bits(0x0340);
+        assert_eq!(v.to_raw().to_raw().to_ne_bytes(), [0x03, 0x40]);
+        assert_eq!(v.to_f64(), 3.25);
+        assert_eq!(u1f15be::from_bits(0x4000).to_f64(), 0.5);
+
+        let v = u32f32be::from_bits(0x0000_0002_4000_0000);
+        assert_eq!((v.to_bits() >> 32, v.to_bits() as u32), (2, 0x4000_0000));
+        assert_eq!(v.to_f64(), 2.25);
+
+        // Signed values are two's complement of all bits, so the integer
+        // part rounds towards negative infinity.
+        let v = s15f16be::from_bits(-0x0001_8000);
+        assert_eq!(v.to_raw().to_raw().to_ne_bytes(), [0xff, 0xfe, 0x80, 0x00]);
+        assert_eq!((v.to_bits() >> 16, v.to_bits() & 0xffff), (-2, 0x8000));
+        assert_eq!(v.to_f64(), -1.5);
+        assert_eq!(s7f8be::from_bits(-0x0080).to_f64(), -0.5);
+        assert_eq!(s1f14be::from_bits(-0x4000).to_f64(), -1.0);
+        assert_eq!(s1f14be::from_bits(0x2000).to_bits(), 0x2000);
+    }
+}
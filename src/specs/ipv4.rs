@@ -0,0 +1,405 @@
+//! Internet Protocol Version 4
+//!
+//! IPv4 packets start with a `Header` of at least `HEADER_SIZE` bytes. Its
+//! actual size is given by the internet header length (IHL) in units of 4
+//! bytes, and any space beyond the fixed header is filled with options (see
+//! `Options`). The payload follows the options, and its protocol is given by
+//! the `PROTOCOL_*` numbers, which are shared with the next-header field of
+//! IPv6.
+//!
+//! The header checksum is the Internet checksum (see `checksum::internet()`)
+//! of the header including options, calculated with the checksum field set to
+//! zero. It does not cover the payload. Upper-layer checksums (e.g., of TCP
+//! and UDP) cover a `PseudoHeader` in front of the upper-layer packet
+//! instead.
+//!
+//! All multi-byte integers are encoded as big-endian.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::{checksum, int};
+
+pub const VERSION: u8 = 4;
+
+pub const HEADER_SIZE: usize = 20;
+pub const HEADER_SIZE_MAX: usize = 60;
+pub const CHECKSUM_OFFSET: usize = 10;
+pub const PSEUDO_HEADER_SIZE: usize = 12;
+
+pub const PROTOCOL_HOPOPT: u8 = 0;
+pub const PROTOCOL_ICMP: u8 = 1;
+pub const PROTOCOL_IGMP: u8 = 2;
+pub const PROTOCOL_IPIP: u8 = 4;
+pub const PROTOCOL_TCP: u8 = 6;
+pub const PROTOCOL_UDP: u8 = 17;
+pub const PROTOCOL_IPV6: u8 = 41;
+pub const PROTOCOL_IPV6_ROUTING: u8 = 43;
+pub const PROTOCOL_IPV6_FRAGMENT: u8 = 44;
+pub const PROTOCOL_GRE: u8 = 47;
+pub const PROTOCOL_ESP: u8 = 50;
+pub const PROTOCOL_AH: u8 = 51;
+pub const PROTOCOL_ICMPV6: u8 = 58;
+pub const PROTOCOL_IPV6_NONXT: u8 = 59;
+pub const PROTOCOL_IPV6_OPTS: u8 = 60;
+pub const PROTOCOL_OSPF: u8 = 89;
+pub const PROTOCOL_PIM: u8 = 103;
+pub const PROTOCOL_VRRP: u8 = 112;
+pub const PROTOCOL_L2TP: u8 = 115;
+pub const PROTOCOL_SCTP: u8 = 132;
+pub const PROTOCOL_MOBILITY: u8 = 135;
+pub const PROTOCOL_UDPLITE: u8 = 136;
+pub const PROTOCOL_MPLS: u8 = 137;
+pub const PROTOCOL_EXPERIMENT1: u8 = 253;
+pub const PROTOCOL_EXPERIMENT2: u8 = 254;
+
+pub const DSCP_CS0: u8 = 0;
+pub const DSCP_CS1: u8 = 8;
+pub const DSCP_AF11: u8 = 10;
+pub const DSCP_AF12: u8 = 12;
+pub const DSCP_AF13: u8 = 14;
+pub const DSCP_CS2: u8 = 16;
+pub const DSCP_AF21: u8 = 18;
+pub const DSCP_AF22: u8 = 20;
+pub const DSCP_AF23: u8 = 22;
+pub const DSCP_CS3: u8 = 24;
+pub const DSCP_AF31: u8 = 26;
+pub const DSCP_AF32: u8 = 28;
+pub const DSCP_AF33: u8 = 30;
+pub const DSCP_CS4: u8 = 32;
+pub const DSCP_AF41: u8 = 34;
+pub const DSCP_AF42: u8 = 36;
+pub const DSCP_AF43: u8 = 38;
+pub const DSCP_CS5: u8 = 40;
+pub const DSCP_VOICE_ADMIT: u8 = 44;
+pub const DSCP_EF: u8 = 46;
+pub const DSCP_CS6: u8 = 48;
+pub const DSCP_CS7: u8 = 56;
+
+pub const ECN_NOT_ECT: u8 = 0;
+pub const ECN_ECT1: u8 = 1;
+pub const ECN_ECT0: u8 = 2;
+pub const ECN_CE: u8 = 3;
+
+/* reserved: 0x8000 */
+pub const FLAG_DONT_FRAGMENT: u16 = 0x4000;
+pub const FLAG_MORE_FRAGMENTS: u16 = 0x2000;
+pub const FRAGMENT_OFFSET_MASK: u16 = 0x1fff;
+
+pub const OPTION_END: u8 = 0;
+pub const OPTION_NOP: u8 = 1;
+pub const OPTION_RECORD_ROUTE: u8 = 7;
+pub const OPTION_TIMESTAMP: u8 = 68;
+pub const OPTION_SECURITY: u8 = 130;
+pub const OPTION_LSRR: u8 = 131;
+pub const OPTION_SSRR: u8 = 137;
+pub const OPTION_ROUTER_ALERT: u8 = 148;
+
+pub const OPTION_COPIED: u8 = 0x80;
+pub const OPTION_CLASS_MASK: u8 = 0x60;
+pub const OPTION_NUMBER_MASK: u8 = 0x1f;
+
+/// IPv4 Header
+#[repr(C)]
+pub struct Header {
+    /// Version in the upper nibble, must be `VERSION`, and the header length
+    /// in units of 4 bytes in the lower nibble (see `ihl()`).
+    pub version_ihl: u8,
+
+    /// DSCP in the upper 6 bits, ECN in the lower 2 bits (see `dscp()` and
+    /// `ecn()`).
+    pub tos: u8,
+
+    /// Size of the packet in bytes, including the header.
+    pub total_length: int::u16be,
+
+    /// Identification of the datagram, shared by all its fragments.
+    pub identification: int::u16be,
+
+    /// Flags (`FLAG_*`) and the fragment offset in units of 8 bytes.
+    pub flags_fragment: int::u16be,
+
+    /// Time to live.
+    pub ttl: u8,
+
+    /// Protocol of the payload (`PROTOCOL_*`).
+    pub protocol: u8,
+
+    /// Header checksum.
+    pub checksum: int::u16be,
+
+    /// Source address.
+    pub source: [u8; 4],
+
+    /// Destination address.
+    pub destination: [u8; 4],
+}
+
+/// Checksum Pseudo-header
+///
+/// The data prepended to upper-layer packets when calculating their
+/// checksums.
+#[repr(C)]
+pub struct PseudoHeader {
+    /// Source address.
+    pub source: [u8; 4],
+
+    /// Destination address.
+    pub destination: [u8; 4],
+
+    /// Reserved, must be 0.
+    pub zero: u8,
+
+    /// Upper-layer protocol (`PROTOCOL_*`).
+    pub protocol: u8,
+
+    /// Size of the upper-layer packet in bytes, including its header.
+    pub length: int::u16be,
+}
+
+/// Option Iterator
+///
+/// An iterator over the options of an IPv4 header, yielding the option type
+/// and the option data, excluding the type and length bytes. `OPTION_NOP`
+/// carries no length and yields empty data. Iteration stops at the end of
+/// the data, at `OPTION_END`, or at the first option that is truncated or
+/// has an invalid length. The same framing is used by TCP options.
+#[derive(Clone, Debug)]
+pub struct Options<'data> {
+    data: &'data [u8],
+}
+
+impl Header {
+    /// Import a header from a byte slice
+    ///
+    /// Create a new header from data copied from a byte slice. No byte-order
+    /// conversions are applied.
+    pub fn from_bytes(data: &[u8; 20]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 20], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Return IP version
+    pub fn version(&self) -> u8 {
+        self.version_ihl >> 4
+    }
+
+    /// Return internet header length
+    ///
+    /// Return the header length in units of 4 bytes. Valid headers have a
+    /// length of at least 5.
+    pub fn ihl(&self) -> u8 {
+        self.version_ihl & 0x0f
+    }
+
+    /// Return header size
+    ///
+    /// Return the size of the header including options, in bytes.
+    pub fn header_size(&self) -> usize {
+        self.ihl() as usize * 4
+    }
+
+    /// Return differentiated services code point (`DSCP_*`)
+    pub fn dscp(&self) -> u8 {
+        self.tos >> 2
+    }
+
+    /// Return explicit congestion notification (`ECN_*`)
+    pub fn ecn(&self) -> u8 {
+        self.tos & 0x03
+    }
+
+    /// Check the don't-fragment flag
+    pub fn dont_fragment(&self) -> bool {
+        int::ForeignEndian::to_native(self.flags_fragment) & FLAG_DONT_FRAGMENT != 0
+    }
+
+    /// Check the more-fragments flag
+    pub fn more_fragments(&self) -> bool {
+        int::ForeignEndian::to_native(self.flags_fragment) & FLAG_MORE_FRAGMENTS != 0
+    }
+
+    /// Return fragment offset
+    ///
+    /// Return the offset of this fragment in the datagram, in bytes.
+    pub fn fragment_offset(&self) -> usize {
+        (int::ForeignEndian::to_native(self.flags_fragment) & FRAGMENT_OFFSET_MASK) as usize * 8
+    }
+
+    /// Check for fragments
+    ///
+    /// Return whether the packet carries a fragment of a datagram, rather
+    /// than the entire datagram.
+    pub fn is_fragment(&self) -> bool {
+        self.more_fragments() || self.fragment_offset() != 0
+    }
+}
+
+impl PseudoHeader {
+    /// Create a pseudo-header from native values
+    pub fn new(source: [u8; 4], destination: [u8; 4], protocol: u8, length: u16) -> Self {
+        Self {
+            source,
+            destination,
+            zero: 0,
+            protocol,
+            length: int::ForeignEndian::from_native(length),
+        }
+    }
+
+    /// Convert to byte slice
+    ///
+    /// Return a byte-slice reference to the pseudo-header. No byte-order
+    /// conversions are applied.
+    pub fn as_bytes(&self) -> &[u8; 12] {
+        assert!(align_of::<[u8; 12]>() <= align_of::<Self>());
+        assert!(size_of::<[u8; 12]>() == size_of::<Self>());
+
+        unsafe {
+            core::mem::transmute::<&Self, &[u8; 12]>(self)
+        }
+    }
+}
+
+impl<'data> Options<'data> {
+    /// Create option iterator
+    ///
+    /// Create a new iterator over the options in `data`, which must start
+    /// directly after the fixed header.
+    pub fn new(data: &'data [u8]) -> Self {
+        Self {
+            data,
+        }
+    }
+
+    /// Return remaining data
+    ///
+    /// Return the data that has not been consumed by the iterator.
+    pub fn remainder(&self) -> &'data [u8] {
+        self.data
+    }
+}
+
+impl<'data> Iterator for Options<'data> {
+    type Item = (u8, &'data [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match *self.data.first()? {
+            OPTION_END => None,
+            OPTION_NOP => {
+                self.data = &self.data[1..];
+                Some((OPTION_NOP, &[]))
+            },
+            kind => {
+                let length = *self.data.get(1)? as usize;
+                if length < 2 || self.data.len() < length {
+                    return None;
+                }
+
+                let data = &self.data[2..length];
+                self.data = &self.data[length..];
+                Some((kind, data))
+            },
+        }
+    }
+}
+
+/// Calculate Header Checksum
+///
+/// Calculate the checksum of a header including its options, treating the
+/// checksum field as zero. `header` must be at least `HEADER_SIZE` bytes.
+pub fn header_checksum(header: &[u8]) -> u16 {
+    let sum = checksum::internet_update(0, &header[..CHECKSUM_OFFSET]);
+    let sum = checksum::internet_update(sum, &header[CHECKSUM_OFFSET + 2..]);
+    checksum::internet_finish(sum)
+}
+
+/// Verify Header Checksum
+///
+/// Verify the checksum of a header including its options.
+pub fn verify_checksum(header: &[u8]) -> bool {
+    checksum::internet(header) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A UDP packet header from 192.168.0.1 to 192.168.0.199.
+    const HEADER: [u8; 20] = [
+        0x45, 0x00, 0x00, 0x73, 0x00, 0x00, 0x40, 0x00,
+        0x40, 0x11, 0xb8, 0x61, 0xc0, 0xa8, 0x00, 0x01,
+        0xc0, 0xa8, 0x00, 0xc7,
+    ];
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<Header>(), HEADER_SIZE);
+        assert_eq!(align_of::<Header>(), 2);
+        assert_eq!(size_of::<PseudoHeader>(), PSEUDO_HEADER_SIZE);
+        assert_eq!(align_of::<PseudoHeader>(), 2);
+    }
+
+    // Parse a header and verify its checksum.
+    #[test]
+    fn verify_header() {
+        let h = Header::from_bytes(&HEADER);
+        assert_eq!(h.version(), VERSION);
+        assert_eq!(h.header_size(), HEADER_SIZE);
+        assert_eq!(h.dscp(), DSCP_CS0);
+        assert_eq!(h.ecn(), ECN_NOT_ECT);
+        assert_eq!(int::ForeignEndian::to_native(h.total_length), 115);
+        assert!(h.dont_fragment());
+        assert!(!h.more_fragments());
+        assert!(!h.is_fragment());
+        assert_eq!(h.ttl, 64);
+        assert_eq!(h.protocol, PROTOCOL_UDP);
+        assert_eq!(h.source, [192, 168, 0, 1]);
+        assert_eq!(h.destination, [192, 168, 0, 199]);
+
+        assert_eq!(header_checksum(&HEADER), 0xb861);
+        assert!(verify_checksum(&HEADER));
+
+        let mut data = HEADER;
+        data[1] = DSCP_EF << 2 | ECN_CE;
+        data[6..8].copy_from_slice(&(FLAG_MORE_FRAGMENTS | 185).to_be_bytes());
+        let h = Header::from_bytes(&data);
+        assert_eq!(h.dscp(), DSCP_EF);
+        assert_eq!(h.ecn(), ECN_CE);
+        assert!(!h.dont_fragment());
+        assert!(h.is_fragment());
+        assert_eq!(h.fragment_offset(), 1480);
+        assert!(!verify_checksum(&data));
+    }
+
+    // Walk header options, including padding and truncation.
+    #[test]
+    fn verify_options() {
+        let data = [
+            OPTION_NOP, OPTION_ROUTER_ALERT, 4, 0, 0,
+            OPTION_RECORD_ROUTE, 3, 4, OPTION_END, 0xff,
+        ];
+        let mut opts = Options::new(&data);
+        assert_eq!(opts.next(), Some((OPTION_NOP, &[][..])));
+        assert_eq!(opts.next(), Some((OPTION_ROUTER_ALERT, &[0, 0][..])));
+        assert_eq!(opts.next(), Some((OPTION_RECORD_ROUTE, &[4][..])));
+        assert!(opts.next().is_none());
+        assert_eq!(opts.remainder(), &[OPTION_END, 0xff]);
+        assert_eq!(OPTION_ROUTER_ALERT & OPTION_COPIED, OPTION_COPIED);
+        assert_eq!(OPTION_TIMESTAMP & OPTION_NUMBER_MASK, 4);
+
+        assert!(Options::new(&data[1..4]).next().is_none());
+        assert!(Options::new(&[OPTION_SECURITY, 1, 0]).next().is_none());
+    }
+}
@@ -0,0 +1,553 @@
+//! Internet Protocol Version 6
+//!
+//! IPv6 packets start with a fixed `Header`, followed by a chain of
+//! extension headers and eventually the upper-layer payload. Each header
+//! names the type of the next one in its next-header field, using the
+//! protocol numbers of IPv4 (`ipv4::PROTOCOL_*`). `Extensions` walks this
+//! chain.
+//!
+//! Extension headers start with the next-header field. Except for the
+//! fragment header, which has a fixed size, the second byte gives the size of
+//! the header in units of 8 bytes, not counting the first 8 bytes (see
+//! `extension_size()`). Hop-by-hop and destination options headers carry
+//! options (see `Options`), padded with `OPTION_PAD1` and `OPTION_PADN`.
+//!
+//! Upper-layer checksums (e.g., of ICMPv6, TCP, and UDP) cover a
+//! `PseudoHeader` in front of the upper-layer packet, since IPv6 has no
+//! header checksum of its own.
+//!
+//! All multi-byte integers are encoded as big-endian.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::{int, ipv4};
+
+pub const VERSION: u8 = 6;
+
+pub const HEADER_SIZE: usize = 40;
+pub const FRAGMENT_HEADER_SIZE: usize = 8;
+pub const PSEUDO_HEADER_SIZE: usize = 40;
+
+pub const ADDR_UNSPECIFIED: [u8; 16] = [0; 16];
+pub const ADDR_LOOPBACK: [u8; 16] = [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1];
+pub const ADDR_ALL_NODES: [u8; 16] = [0xff, 0x02, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1];
+pub const ADDR_ALL_ROUTERS: [u8; 16] = [0xff, 0x02, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2];
+
+pub const FLOW_LABEL_MASK: u32 = 0x000f_ffff;
+
+pub const OPTION_PAD1: u8 = 0x00;
+pub const OPTION_PADN: u8 = 0x01;
+pub const OPTION_ROUTER_ALERT: u8 = 0x05;
+pub const OPTION_CALIPSO: u8 = 0x07;
+pub const OPTION_JUMBO: u8 = 0xc2;
+pub const OPTION_HOME_ADDRESS: u8 = 0xc9;
+
+pub const OPTION_ACTION_MASK: u8 = 0xc0;
+pub const OPTION_ACTION_SKIP: u8 = 0x00;
+pub const OPTION_ACTION_DISCARD: u8 = 0x40;
+pub const OPTION_ACTION_DISCARD_ICMP: u8 = 0x80;
+pub const OPTION_ACTION_DISCARD_ICMP_UNICAST: u8 = 0xc0;
+pub const OPTION_MUTABLE: u8 = 0x20;
+
+pub const ROUTING_TYPE_SOURCE: u8 = 0;
+pub const ROUTING_TYPE_NIMROD: u8 = 1;
+pub const ROUTING_TYPE_MOBILITY: u8 = 2;
+pub const ROUTING_TYPE_RPL: u8 = 3;
+pub const ROUTING_TYPE_SEGMENT: u8 = 4;
+
+pub const FRAGMENT_OFFSET_SHIFT: u16 = 3;
+pub const FRAGMENT_MORE: u16 = 0x0001;
+
+/// IPv6 Header
+#[repr(C)]
+pub struct Header {
+    /// Version in the upper 4 bits, must be `VERSION`, followed by the 8-bit
+    /// traffic class and the 20-bit flow label.
+    pub version_class_flow: int::u32be,
+
+    /// Size of the payload in bytes, including extension headers.
+    pub payload_length: int::u16be,
+
+    /// Type of the next header (`ipv4::PROTOCOL_*`).
+    pub next_header: u8,
+
+    /// Hop limit.
+    pub hop_limit: u8,
+
+    /// Source address.
+    pub source: [u8; 16],
+
+    /// Destination address.
+    pub destination: [u8; 16],
+}
+
+/// Generic Extension Header
+///
+/// The common start of hop-by-hop options, routing, and destination options
+/// headers.
+#[repr(C)]
+pub struct ExtensionHeader {
+    /// Type of the next header (`ipv4::PROTOCOL_*`).
+    pub next_header: u8,
+
+    /// Size of the header in units of 8 bytes, not counting the first 8.
+    pub length: u8,
+}
+
+/// Routing Header
+#[repr(C)]
+pub struct RoutingHeader {
+    /// Type of the next header (`ipv4::PROTOCOL_*`).
+    pub next_header: u8,
+
+    /// Size of the header in units of 8 bytes, not counting the first 8.
+    pub length: u8,
+
+    /// Routing type (`ROUTING_TYPE_*`).
+    pub routing_type: u8,
+
+    /// Number of route segments remaining.
+    pub segments_left: u8,
+}
+
+/// Fragment Header
+#[repr(C)]
+pub struct FragmentHeader {
+    /// Type of the next header (`ipv4::PROTOCOL_*`).
+    pub next_header: u8,
+
+    /// Reserved, must be 0.
+    pub reserved: u8,
+
+    /// Fragment offset in units of 8 bytes in the upper 13 bits, and
+    /// `FRAGMENT_MORE`.
+    pub offset_flags: int::u16be,
+
+    /// Identification of the datagram, shared by all its fragments.
+    pub identification: int::u32be,
+}
+
+/// Checksum Pseudo-header
+///
+/// The data prepended to upper-layer packets when calculating their
+/// checksums. With a routing header, the destination is the final
+/// destination rather than the one in the fixed header.
+#[repr(C)]
+pub struct PseudoHeader {
+    /// Source address.
+    pub source: [u8; 16],
+
+    /// Final destination address.
+    pub destination: [u8; 16],
+
+    /// Size of the upper-layer packet in bytes, including its header.
+    pub length: int::u32be,
+
+    /// Reserved, must be 0.
+    pub zero: [u8; 3],
+
+    /// Upper-layer protocol (`ipv4::PROTOCOL_*`).
+    pub next_header: u8,
+}
+
+/// Extension Header Reference
+#[derive(Clone, Copy, Debug)]
+pub struct Extension<'data> {
+    /// Type of this header (`ipv4::PROTOCOL_*`).
+    pub kind: u8,
+
+    /// Type of the next header (`ipv4::PROTOCOL_*`).
+    pub next_header: u8,
+
+    /// The entire extension header, including its first two bytes.
+    pub data: &'data [u8],
+}
+
+/// Extension Header Iterator
+///
+/// An iterator over the chain of extension headers of a packet. Iteration
+/// stops at the first header that is not an extension header (see
+/// `is_extension()`), or that is truncated. Afterwards, `next_header()`
+/// returns the type of the header at the start of `remainder()`, which is
+/// usually the upper-layer protocol.
+#[derive(Clone, Debug)]
+pub struct Extensions<'data> {
+    next_header: u8,
+    data: &'data [u8],
+}
+
+/// Option Iterator
+///
+/// An iterator over the options of hop-by-hop and destination options
+/// headers, yielding the option type and the option data, excluding the type
+/// and length bytes. `OPTION_PAD1` carries no length and yields empty data.
+/// Iteration stops at the end of the data, or at the first option that is
+/// truncated.
+#[derive(Clone, Debug)]
+pub struct Options<'data> {
+    data: &'data [u8],
+}
+
+impl Header {
+    /// Import a header from a byte slice
+    ///
+    /// Create a new header from data copied from a byte slice. No byte-order
+    /// conversions are applied.
+    pub fn from_bytes(data: &[u8; 40]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 40], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Return IP version
+    pub fn version(&self) -> u8 {
+        (int::ForeignEndian::to_native(self.version_class_flow) >> 28) as u8
+    }
+
+    /// Return traffic class
+    ///
+    /// Return the traffic class, which is split into DSCP and ECN like the
+    /// type-of-service field of IPv4 (see `dscp()` and `ecn()`).
+    pub fn traffic_class(&self) -> u8 {
+        (int::ForeignEndian::to_native(self.version_class_flow) >> 20) as u8
+    }
+
+    /// Return differentiated services code point (`ipv4::DSCP_*`)
+    pub fn dscp(&self) -> u8 {
+        self.traffic_class() >> 2
+    }
+
+    /// Return explicit congestion notification (`ipv4::ECN_*`)
+    pub fn ecn(&self) -> u8 {
+        self.traffic_class() & 0x03
+    }
+
+    /// Return flow label
+    pub fn flow_label(&self) -> u32 {
+        int::ForeignEndian::to_native(self.version_class_flow) & FLOW_LABEL_MASK
+    }
+}
+
+impl ExtensionHeader {
+    /// Import an extension header from a byte slice
+    ///
+    /// Create a new extension header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 2]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 2], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl RoutingHeader {
+    /// Import a routing header from a byte slice
+    ///
+    /// Create a new routing header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 4]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 4], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl FragmentHeader {
+    /// Import a fragment header from a byte slice
+    ///
+    /// Create a new fragment header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 8]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 8], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Return fragment offset
+    ///
+    /// Return the offset of this fragment in the datagram, in bytes.
+    pub fn fragment_offset(&self) -> usize {
+        (int::ForeignEndian::to_native(self.offset_flags) >> FRAGMENT_OFFSET_SHIFT) as usize * 8
+    }
+
+    /// Check the more-fragments flag
+    pub fn more_fragments(&self) -> bool {
+        int::ForeignEndian::to_native(self.offset_flags) & FRAGMENT_MORE != 0
+    }
+}
+
+impl PseudoHeader {
+    /// Create a pseudo-header from native values
+    pub fn new(source: [u8; 16], destination: [u8; 16], length: u32, next_header: u8) -> Self {
+        Self {
+            source,
+            destination,
+            length: int::ForeignEndian::from_native(length),
+            zero: [0; 3],
+            next_header,
+        }
+    }
+
+    /// Convert to byte slice
+    ///
+    /// Return a byte-slice reference to the pseudo-header. No byte-order
+    /// conversions are applied.
+    pub fn as_bytes(&self) -> &[u8; 40] {
+        assert!(align_of::<[u8; 40]>() <= align_of::<Self>());
+        assert!(size_of::<[u8; 40]>() == size_of::<Self>());
+
+        unsafe {
+            core::mem::transmute::<&Self, &[u8; 40]>(self)
+        }
+    }
+}
+
+impl<'data> Extensions<'data> {
+    /// Create extension header iterator
+    ///
+    /// Create a new iterator over the extension headers in `data`, where
+    /// `next_header` is the type of the first header, as given by the fixed
+    /// header.
+    pub fn new(next_header: u8, data: &'data [u8]) -> Self {
+        Self {
+            next_header,
+            data,
+        }
+    }
+
+    /// Return the type of the next header
+    pub fn next_header(&self) -> u8 {
+        self.next_header
+    }
+
+    /// Return remaining data
+    ///
+    /// Return the data that has not been consumed by the iterator.
+    pub fn remainder(&self) -> &'data [u8] {
+        self.data
+    }
+}
+
+impl<'data> Iterator for Extensions<'data> {
+    type Item = Extension<'data>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if !is_extension(self.next_header) || self.data.len() < 2 {
+            return None;
+        }
+
+        let size = extension_size(self.next_header, self.data[1]);
+        if self.data.len() < size {
+            return None;
+        }
+
+        let ext = Extension {
+            kind: self.next_header,
+            next_header: self.data[0],
+            data: &self.data[..size],
+        };
+
+        self.next_header = ext.next_header;
+        self.data = &self.data[size..];
+        Some(ext)
+    }
+}
+
+impl<'data> Options<'data> {
+    /// Create option iterator
+    ///
+    /// Create a new iterator over the options in `data`, which must start
+    /// directly after the first two bytes of the extension header.
+    pub fn new(data: &'data [u8]) -> Self {
+        Self {
+            data,
+        }
+    }
+
+    /// Return remaining data
+    ///
+    /// Return the data that has not been consumed by the iterator.
+    pub fn remainder(&self) -> &'data [u8] {
+        self.data
+    }
+}
+
+impl<'data> Iterator for Options<'data> {
+    type Item = (u8, &'data [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match *self.data.first()? {
+            OPTION_PAD1 => {
+                self.data = &self.data[1..];
+                Some((OPTION_PAD1, &[]))
+            },
+            kind => {
+                let length = *self.data.get(1)? as usize;
+                if self.data.len() - 2 < length {
+                    return None;
+                }
+
+                let data = &self.data[2..2 + length];
+                self.data = &self.data[2 + length..];
+                Some((kind, data))
+            },
+        }
+    }
+}
+
+/// Check for extension headers
+///
+/// Return whether the header type `next_header` is an extension header that
+/// `Extensions` can skip. ESP is excluded, since everything following it is
+/// encrypted.
+pub fn is_extension(next_header: u8) -> bool {
+    matches!(
+        next_header,
+        ipv4::PROTOCOL_HOPOPT
+            | ipv4::PROTOCOL_IPV6_ROUTING
+            | ipv4::PROTOCOL_IPV6_FRAGMENT
+            | ipv4::PROTOCOL_IPV6_OPTS
+            | ipv4::PROTOCOL_AH
+            | ipv4::PROTOCOL_MOBILITY
+    )
+}
+
+/// Return extension header size
+///
+/// Return the size of an extension header of type `next_header` in bytes,
+/// given the value of its length field. The authentication header counts in
+/// units of 4 bytes instead, and the fragment header has a fixed size.
+pub fn extension_size(next_header: u8, length: u8) -> usize {
+    match next_header {
+        ipv4::PROTOCOL_IPV6_FRAGMENT => FRAGMENT_HEADER_SIZE,
+        ipv4::PROTOCOL_AH => (length as usize + 2) * 4,
+        _ => (length as usize + 1) * 8,
+    }
+}
+
+/// Check for multicast addresses
+pub fn is_multicast(addr: &[u8; 16]) -> bool {
+    addr[0] == 0xff
+}
+
+/// Check for link-local unicast addresses
+pub fn is_link_local(addr: &[u8; 16]) -> bool {
+    addr[0] == 0xfe && addr[1] & 0xc0 == 0x80
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A fragment of a UDP datagram to ff02::16, with a router alert.
+    const PACKET: [u8; 56] = [
+        0x6b, 0x81, 0x23, 0x45, 0x00, 0x18, 0x00, 0x01,
+        0xfe, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01,
+        0xff, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x16,
+        0x2c, 0x00, 0x05, 0x02, 0x00, 0x00, 0x01, 0x00,
+        0x11, 0x00, 0x03, 0x21, 0x12, 0x34, 0x56, 0x78,
+    ];
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<Header>(), HEADER_SIZE);
+        assert_eq!(align_of::<Header>(), 4);
+        assert_eq!(size_of::<ExtensionHeader>(), 2);
+        assert_eq!(align_of::<ExtensionHeader>(), 1);
+        assert_eq!(size_of::<RoutingHeader>(), 4);
+        assert_eq!(align_of::<RoutingHeader>(), 1);
+        assert_eq!(size_of::<FragmentHeader>(), FRAGMENT_HEADER_SIZE);
+        assert_eq!(align_of::<FragmentHeader>(), 4);
+        assert_eq!(size_of::<PseudoHeader>(), PSEUDO_HEADER_SIZE);
+        assert_eq!(align_of::<PseudoHeader>(), 4);
+    }
+
+    // Parse the fixed header and the bit packing of its first word.
+    #[test]
+    fn verify_header() {
+        let h = Header::from_bytes(PACKET[..40].try_into().unwrap());
+        assert_eq!(h.version(), VERSION);
+        assert_eq!(h.traffic_class(), 0xb8);
+        assert_eq!(h.dscp(), ipv4::DSCP_EF);
+        assert_eq!(h.ecn(), ipv4::ECN_NOT_ECT);
+        assert_eq!(h.flow_label(), 0x12345);
+        assert_eq!(int::ForeignEndian::to_native(h.payload_length), 24);
+        assert_eq!(h.next_header, ipv4::PROTOCOL_HOPOPT);
+        assert_eq!(h.hop_limit, 1);
+        assert!(is_link_local(&h.source));
+        assert!(!is_multicast(&h.source));
+        assert!(is_multicast(&h.destination));
+        assert!(!is_link_local(&ADDR_LOOPBACK));
+    }
+
+    // Walk the extension header chain and the options along it.
+    #[test]
+    fn verify_extensions() {
+        let mut exts = Extensions::new(ipv4::PROTOCOL_HOPOPT, &PACKET[40..]);
+
+        let e = exts.next().unwrap();
+        assert_eq!(e.kind, ipv4::PROTOCOL_HOPOPT);
+        assert_eq!(e.next_header, ipv4::PROTOCOL_IPV6_FRAGMENT);
+        assert_eq!(e.data.len(), 8);
+
+        let mut opts = Options::new(&e.data[2..]);
+        assert_eq!(opts.next(), Some((OPTION_ROUTER_ALERT, &[0, 0][..])));
+        assert_eq!(opts.next(), Some((OPTION_PADN, &[][..])));
+        assert!(opts.next().is_none());
+        assert_eq!(OPTION_JUMBO & OPTION_ACTION_MASK, OPTION_ACTION_DISCARD_ICMP_UNICAST);
+
+        let e = exts.next().unwrap();
+        assert_eq!(e.kind, ipv4::PROTOCOL_IPV6_FRAGMENT);
+        let f = FragmentHeader::from_bytes(e.data.try_into().unwrap());
+        assert_eq!(f.next_header, ipv4::PROTOCOL_UDP);
+        assert_eq!(f.fragment_offset(), 800);
+        assert!(f.more_fragments());
+        assert_eq!(int::ForeignEndian::to_native(f.identification), 0x12345678);
+
+        assert!(exts.next().is_none());
+        assert_eq!(exts.next_header(), ipv4::PROTOCOL_UDP);
+        assert!(exts.remainder().is_empty());
+
+        // Truncated headers stop the iteration in place.
+        let mut exts = Extensions::new(ipv4::PROTOCOL_HOPOPT, &PACKET[40..52]);
+        assert!(exts.next().is_some());
+        assert!(exts.next().is_none());
+        assert_eq!(exts.next_header(), ipv4::PROTOCOL_IPV6_FRAGMENT);
+        assert_eq!(exts.remainder().len(), 4);
+
+        assert_eq!(extension_size(ipv4::PROTOCOL_AH, 4), 24);
+        assert_eq!(extension_size(ipv4::PROTOCOL_IPV6_ROUTING, 2), 24);
+        assert!(!is_extension(ipv4::PROTOCOL_ESP));
+    }
+}
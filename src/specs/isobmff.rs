@@ -0,0 +1,888 @@
+//! ISO Base Media File Format
+//!
+//! The ISO base media file format (ISO 14496-12), the basis of MP4, MOV,
+//! 3GP, HEIF, and others, is a tree of boxes (also known as atoms). Every box
+//! starts with a `BoxHeader` with a 32-bit size and a 4-byte box type,
+//! followed by the box payload. A size of `SIZE_LARGE` means a 64-bit size
+//! follows the box type, a size of `SIZE_TO_END` means the box extends to the
+//! end of the file. Boxes of type `BOX_UUID` carry a 16-byte extended type
+//! after the header. Sizes always include the header.
+//!
+//! Many boxes are full boxes, whose payload starts with a `FullBoxHeader`
+//! carrying a version and 24 bits of flags. Version 1 of the time-related
+//! boxes uses 64-bit fields for times and durations, version 0 uses 32-bit
+//! fields. Times are in seconds since 1904-01-01 00:00 UTC.
+//!
+//! The sample tables (`stts`, `stsc`, `stsz`, `stco`, `co64`) start with a
+//! 32-bit entry count followed by an array of fixed-size entries, which can
+//! be accessed via `entries()`.
+//!
+//! All multi-byte integers are encoded as big-endian.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::int;
+
+pub const BOX_CO64: [u8; 4] = *b"co64";
+pub const BOX_DINF: [u8; 4] = *b"dinf";
+pub const BOX_EDTS: [u8; 4] = *b"edts";
+pub const BOX_FREE: [u8; 4] = *b"free";
+pub const BOX_FTYP: [u8; 4] = *b"ftyp";
+pub const BOX_HDLR: [u8; 4] = *b"hdlr";
+pub const BOX_MDAT: [u8; 4] = *b"mdat";
+pub const BOX_MDHD: [u8; 4] = *b"mdhd";
+pub const BOX_MDIA: [u8; 4] = *b"mdia";
+pub const BOX_META: [u8; 4] = *b"meta";
+pub const BOX_MINF: [u8; 4] = *b"minf";
+pub const BOX_MOOF: [u8; 4] = *b"moof";
+pub const BOX_MOOV: [u8; 4] = *b"moov";
+pub const BOX_MVHD: [u8; 4] = *b"mvhd";
+pub const BOX_SKIP: [u8; 4] = *b"skip";
+pub const BOX_STBL: [u8; 4] = *b"stbl";
+pub const BOX_STCO: [u8; 4] = *b"stco";
+pub const BOX_STSC: [u8; 4] = *b"stsc";
+pub const BOX_STSD: [u8; 4] = *b"stsd";
+pub const BOX_STSS: [u8; 4] = *b"stss";
+pub const BOX_STSZ: [u8; 4] = *b"stsz";
+pub const BOX_STTS: [u8; 4] = *b"stts";
+pub const BOX_TKHD: [u8; 4] = *b"tkhd";
+pub const BOX_TRAK: [u8; 4] = *b"trak";
+pub const BOX_UDTA: [u8; 4] = *b"udta";
+pub const BOX_UUID: [u8; 4] = *b"uuid";
+
+pub const HANDLER_HINT: [u8; 4] = *b"hint";
+pub const HANDLER_META: [u8; 4] = *b"meta";
+pub const HANDLER_SOUN: [u8; 4] = *b"soun";
+pub const HANDLER_SUBT: [u8; 4] = *b"subt";
+pub const HANDLER_TEXT: [u8; 4] = *b"text";
+pub const HANDLER_VIDE: [u8; 4] = *b"vide";
+
+pub const TKHD_ENABLED: u32 = 0x000001;
+pub const TKHD_IN_MOVIE: u32 = 0x000002;
+pub const TKHD_IN_PREVIEW: u32 = 0x000004;
+pub const TKHD_SIZE_IS_ASPECT_RATIO: u32 = 0x000008;
+
+/// Box Size Extending to the End of the File
+pub const SIZE_TO_END: u32 = 0;
+
+/// Box Size Stored as 64-bit Integer
+pub const SIZE_LARGE: u32 = 1;
+
+/// Epoch Offset
+///
+/// The number of seconds between 1904-01-01 and 1970-01-01, to convert
+/// times to Unix time.
+pub const EPOCH_OFFSET: u64 = 2_082_844_800;
+
+/// Identity Matrix
+///
+/// The transformation matrix of `mvhd` and `tkhd` as native integers. All
+/// values are 16.16 fixed-point, except for the last column, which is 2.30
+/// fixed-point.
+pub const MATRIX_IDENTITY: [i32; 9] = [
+    0x0001_0000, 0, 0,
+    0, 0x0001_0000, 0,
+    0, 0, 0x4000_0000,
+];
+
+/// Box Header
+#[repr(C)]
+pub struct BoxHeader {
+    /// Size of the box including the header, or `SIZE_TO_END` or
+    /// `SIZE_LARGE`.
+    pub size: int::u32be,
+
+    /// Box type.
+    pub kind: [u8; 4],
+}
+
+/// Full Box Header
+///
+/// The initial 4 bytes of the payload of full boxes.
+#[repr(C)]
+pub struct FullBoxHeader {
+    /// Box version.
+    pub version: u8,
+
+    /// Box-specific flags.
+    pub flags: int::u24be,
+}
+
+/// File Type Box
+///
+/// The payload of `ftyp`, followed by an array of 4-byte compatible brands
+/// up to the end of the box.
+#[repr(C)]
+pub struct FileType {
+    /// Brand of the best use of the file.
+    pub major_brand: [u8; 4],
+
+    /// Minor version of the major brand.
+    pub minor_version: int::u32be,
+}
+
+/// Movie Header Box (Version 0)
+///
+/// The payload of `mvhd` following the full box header.
+#[repr(C)]
+pub struct MovieHeaderV0 {
+    /// Creation time in seconds since 1904.
+    pub creation_time: int::u32be,
+
+    /// Modification time in seconds since 1904.
+    pub modification_time: int::u32be,
+
+    /// Number of time units per second.
+    pub timescale: int::u32be,
+
+    /// Duration of the longest track in `timescale` units.
+    pub duration: int::u32be,
+
+    /// Preferred playback rate, usually 1.0.
+    pub rate: int::s15f16be,
+
+    /// Preferred playback volume, usually 1.0.
+    pub volume: int::s7f8be,
+
+    pub reserved0: int::u16be,
+    pub reserved1: [int::u32be; 2],
+
+    /// Video transformation matrix (see `MATRIX_IDENTITY`).
+    pub matrix: [int::i32be; 9],
+
+    pub pre_defined: [int::u32be; 6],
+
+    /// Track ID to use for the next added track.
+    pub next_track_id: int::u32be,
+}
+
+/// Movie Header Box (Version 1)
+///
+/// The payload of `mvhd` following the full box header. Since the duration
+/// is not naturally aligned, this structure is packed.
+#[repr(C, packed)]
+pub struct MovieHeaderV1 {
+    /// Creation time in seconds since 1904.
+    pub creation_time: int::u64be,
+
+    /// Modification time in seconds since 1904.
+    pub modification_time: int::u64be,
+
+    /// Number of time units per second.
+    pub timescale: int::u32be,
+
+    /// Duration of the longest track in `timescale` units.
+    pub duration: int::u64be,
+
+    /// Preferred playback rate, usually 1.0.
+    pub rate: int::s15f16be,
+
+    /// Preferred playback volume, usually 1.0.
+    pub volume: int::s7f8be,
+
+    pub reserved0: int::u16be,
+    pub reserved1: [int::u32be; 2],
+
+    /// Video transformation matrix (see `MATRIX_IDENTITY`).
+    pub matrix: [int::i32be; 9],
+
+    pub pre_defined: [int::u32be; 6],
+
+    /// Track ID to use for the next added track.
+    pub next_track_id: int::u32be,
+}
+
+/// Track Header Box (Version 0)
+///
+/// The payload of `tkhd` following the full box header. The flags of the
+/// full box header carry `TKHD_*`.
+#[repr(C)]
+pub struct TrackHeaderV0 {
+    /// Creation time in seconds since 1904.
+    pub creation_time: int::u32be,
+
+    /// Modification time in seconds since 1904.
+    pub modification_time: int::u32be,
+
+    /// Track ID, never 0.
+    pub track_id: int::u32be,
+
+    pub reserved0: int::u32be,
+
+    /// Duration in movie timescale units.
+    pub duration: int::u32be,
+
+    pub reserved1: [int::u32be; 2],
+
+    /// Front-to-back ordering of video tracks.
+    pub layer: int::i16be,
+
+    /// Group of alternative tracks, or 0.
+    pub alternate_group: int::i16be,
+
+    /// Audio volume, 1.0 for audio tracks, otherwise 0.
+    pub volume: int::s7f8be,
+
+    pub reserved2: int::u16be,
+
+    /// Video transformation matrix (see `MATRIX_IDENTITY`).
+    pub matrix: [int::i32be; 9],
+
+    /// Visual presentation width.
+    pub width: int::u16f16be,
+
+    /// Visual presentation height.
+    pub height: int::u16f16be,
+}
+
+/// Track Header Box (Version 1)
+///
+/// The payload of `tkhd` following the full box header. Since the size is
+/// not a multiple of the alignment of its 64-bit fields, this structure is
+/// packed.
+#[repr(C, packed)]
+pub struct TrackHeaderV1 {
+    /// Creation time in seconds since 1904.
+    pub creation_time: int::u64be,
+
+    /// Modification time in seconds since 1904.
+    pub modification_time: int::u64be,
+
+    /// Track ID, never 0.
+    pub track_id: int::u32be,
+
+    pub reserved0: int::u32be,
+
+    /// Duration in movie timescale units.
+    pub duration: int::u64be,
+
+    pub reserved1: [int::u32be; 2],
+
+    /// Front-to-back ordering of video tracks.
+    pub layer: int::i16be,
+
+    /// Group of alternative tracks, or 0.
+    pub alternate_group: int::i16be,
+
+    /// Audio volume, 1.0 for audio tracks, otherwise 0.
+    pub volume: int::s7f8be,
+
+    pub reserved2: int::u16be,
+
+    /// Video transformation matrix (see `MATRIX_IDENTITY`).
+    pub matrix: [int::i32be; 9],
+
+    /// Visual presentation width.
+    pub width: int::u16f16be,
+
+    /// Visual presentation height.
+    pub height: int::u16f16be,
+}
+
+/// Media Header Box (Version 0)
+///
+/// The payload of `mdhd` following the full box header.
+#[repr(C)]
+pub struct MediaHeaderV0 {
+    /// Creation time in seconds since 1904.
+    pub creation_time: int::u32be,
+
+    /// Modification time in seconds since 1904.
+    pub modification_time: int::u32be,
+
+    /// Number of time units per second.
+    pub timescale: int::u32be,
+
+    /// Duration in `timescale` units.
+    pub duration: int::u32be,
+
+    /// Packed ISO-639-2/T language code (see `language()`).
+    pub language: int::u16be,
+
+    pub pre_defined: int::u16be,
+}
+
+/// Media Header Box (Version 1)
+///
+/// The payload of `mdhd` following the full box header. Since the duration
+/// is not naturally aligned, this structure is packed.
+#[repr(C, packed)]
+pub struct MediaHeaderV1 {
+    /// Creation time in seconds since 1904.
+    pub creation_time: int::u64be,
+
+    /// Modification time in seconds since 1904.
+    pub modification_time: int::u64be,
+
+    /// Number of time units per second.
+    pub timescale: int::u32be,
+
+    /// Duration in `timescale` units.
+    pub duration: int::u64be,
+
+    /// Packed ISO-639-2/T language code (see `language()`).
+    pub language: int::u16be,
+
+    pub pre_defined: int::u16be,
+}
+
+/// Handler Reference Box
+///
+/// The payload of `hdlr` following the full box header, followed by a
+/// zero-terminated UTF-8 name.
+#[repr(C)]
+pub struct HandlerHeader {
+    pub pre_defined: int::u32be,
+
+    /// Handler type (`HANDLER_*`).
+    pub handler_type: [u8; 4],
+
+    pub reserved: [int::u32be; 3],
+}
+
+/// Sample Entry
+///
+/// The common start of each sample entry in `stsd`, following the box
+/// header of the entry. The box type of the entry is the codec (e.g.,
+/// `avc1` or `mp4a`).
+#[repr(C)]
+pub struct SampleEntry {
+    pub reserved: [u8; 6],
+
+    /// Index into the data references of `dinf`, starting at 1.
+    pub data_reference_index: int::u16be,
+}
+
+/// Time-to-Sample Entry
+///
+/// An entry of the `stts` table.
+#[repr(C)]
+pub struct TimeToSampleEntry {
+    /// Number of consecutive samples with this duration.
+    pub sample_count: int::u32be,
+
+    /// Sample duration in media timescale units.
+    pub sample_delta: int::u32be,
+}
+
+/// Sample-to-Chunk Entry
+///
+/// An entry of the `stsc` table. Each entry applies to all chunks from
+/// `first_chunk` up to the first chunk of the next entry.
+#[repr(C)]
+pub struct SampleToChunkEntry {
+    /// Index of the first chunk of this run, starting at 1.
+    pub first_chunk: int::u32be,
+
+    /// Number of samples in each chunk.
+    pub samples_per_chunk: int::u32be,
+
+    /// Index of the sample entry in `stsd`, starting at 1.
+    pub sample_description_index: int::u32be,
+}
+
+/// Sample Size Box
+///
+/// The payload of `stsz` following the full box header. If `sample_size`
+/// is 0, a table of `sample_count` 32-bit sample sizes follows.
+#[repr(C)]
+pub struct SampleSizeHeader {
+    /// Size of all samples, or 0 if sizes differ.
+    pub sample_size: int::u32be,
+
+    /// Number of samples.
+    pub sample_count: int::u32be,
+}
+
+/// Box Reference
+///
+/// A reference to a box in a byte slice, as yielded by `Boxes`.
+#[derive(Clone, Copy, Debug)]
+pub struct BoxRef<'data> {
+    /// Box type.
+    pub kind: [u8; 4],
+
+    /// Extended type of `BOX_UUID` boxes.
+    pub usertype: Option<[u8; 16]>,
+
+    /// Box payload, excluding all headers.
+    pub data: &'data [u8],
+}
+
+/// Box Iterator
+///
+/// An iterator over a sequence of boxes, as found at the top level of a
+/// file and in the payload of container boxes. Iteration stops at the end
+/// of the data, or at the first truncated or invalid box.
+#[derive(Clone, Debug)]
+pub struct Boxes<'data> {
+    data: &'data [u8],
+}
+
+impl BoxHeader {
+    /// Import a box header from a byte slice
+    ///
+    /// Create a new box header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 8]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 8], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl FullBoxHeader {
+    /// Import a full box header from a byte slice
+    ///
+    /// Create a new full box header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 4]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 4], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl FileType {
+    /// Import a file type header from a byte slice
+    ///
+    /// Create a new file type header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 8]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 8], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl MovieHeaderV0 {
+    /// Import a movie header from a byte slice
+    ///
+    /// Create a new movie header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 96]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 96], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl MovieHeaderV1 {
+    /// Import a movie header from a byte slice
+    ///
+    /// Create a new movie header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 108]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 108], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl TrackHeaderV0 {
+    /// Import a track header from a byte slice
+    ///
+    /// Create a new track header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 80]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 80], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl TrackHeaderV1 {
+    /// Import a track header from a byte slice
+    ///
+    /// Create a new track header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 92]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 92], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl MediaHeaderV0 {
+    /// Import a media header from a byte slice
+    ///
+    /// Create a new media header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 20]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 20], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl MediaHeaderV1 {
+    /// Import a media header from a byte slice
+    ///
+    /// Create a new media header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 32]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 32], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl HandlerHeader {
+    /// Import a handler header from a byte slice
+    ///
+    /// Create a new handler header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 20]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 20], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl SampleEntry {
+    /// Import a sample entry from a byte slice
+    ///
+    /// Create a new sample entry from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 8]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 8], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl TimeToSampleEntry {
+    /// Import a time-to-sample entry from a byte slice
+    ///
+    /// Create a new time-to-sample entry from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 8]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 8], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl SampleToChunkEntry {
+    /// Import a sample-to-chunk entry from a byte slice
+    ///
+    /// Create a new sample-to-chunk entry from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 12]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 12], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl SampleSizeHeader {
+    /// Import a sample size header from a byte slice
+    ///
+    /// Create a new sample size header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 8]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 8], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl<'data> BoxRef<'data> {
+    /// Split full box header
+    ///
+    /// Return the version, the flags, and the remaining payload of a full
+    /// box, or `None` if the payload is too short.
+    pub fn full(&self) -> Option<(u8, u32, &'data [u8])> {
+        let h = FullBoxHeader::from_bytes(self.data.get(..4)?.try_into().unwrap());
+        Some((h.version, h.flags.to_native(), &self.data[4..]))
+    }
+
+    /// Iterate child boxes
+    ///
+    /// Return an iterator over the payload of a container box. Whether a box
+    /// is a container depends on its type, and is not checked.
+    pub fn children(&self) -> Boxes<'data> {
+        Boxes::new(self.data)
+    }
+}
+
+impl<'data> Boxes<'data> {
+    /// Create box iterator
+    ///
+    /// Create a new iterator over the boxes in `data`.
+    pub fn new(data: &'data [u8]) -> Self {
+        Self {
+            data,
+        }
+    }
+
+    /// Return remaining data
+    pub fn remainder(&self) -> &'data [u8] {
+        self.data
+    }
+}
+
+impl<'data> Iterator for Boxes<'data> {
+    type Item = BoxRef<'data>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let h = BoxHeader::from_bytes(self.data.get(..8)?.try_into().unwrap());
+
+        let (mut at, size) = match int::ForeignEndian::to_native(h.size) {
+            SIZE_TO_END => (8, self.data.len()),
+            SIZE_LARGE => {
+                let v = self.data.get(8..16)?;
+                (16, usize::try_from(u64::from_be_bytes(v.try_into().unwrap())).ok()?)
+            },
+            v => (8, v as usize),
+        };
+
+        let usertype = if h.kind == BOX_UUID {
+            let v = self.data.get(at..at + 16)?;
+            at += 16;
+            Some(v.try_into().unwrap())
+        } else {
+            None
+        };
+
+        if size < at || size > self.data.len() {
+            return None;
+        }
+
+        let item = BoxRef {
+            kind: h.kind,
+            usertype,
+            data: &self.data[at..size],
+        };
+
+        self.data = &self.data[size..];
+        Some(item)
+    }
+}
+
+/// Access Table Entries
+///
+/// Split a sample table payload, following the full box header, into the
+/// entry count and the entries of `entry_size` bytes each. `None` is
+/// returned if the table is truncated.
+pub fn entries(data: &[u8], entry_size: usize) -> Option<(usize, &[u8])> {
+    let count = u32::from_be_bytes(data.get(..4)?.try_into().unwrap()) as usize;
+    let size = count.checked_mul(entry_size)?;
+    Some((count, data.get(4..4_usize.checked_add(size)?)?))
+}
+
+/// Decode Language Code
+///
+/// Decode the packed ISO-639-2/T language code of `mdhd` into 3 lowercase
+/// ASCII letters. Each letter is stored in 5 bits as offset from 0x60.
+pub fn language(packed: u16) -> [u8; 3] {
+    [
+        ((packed >> 10) & 0x1f) as u8 + 0x60,
+        ((packed >> 5) & 0x1f) as u8 + 0x60,
+        (packed & 0x1f) as u8 + 0x60,
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A file type box followed by a movie box with a movie header.
+    const FILE: [u8; 140] = [
+        0x00, 0x00, 0x00, 0x18, 0x66, 0x74, 0x79, 0x70,
+        0x69, 0x73, 0x6f, 0x6d, 0x00, 0x00, 0x02, 0x00,
+        0x69, 0x73, 0x6f, 0x6d, 0x6d, 0x70, 0x34, 0x31,
+        0x00, 0x00, 0x00, 0x74, 0x6d, 0x6f, 0x6f, 0x76,
+        0x00, 0x00, 0x00, 0x6c, 0x6d, 0x76, 0x68, 0x64,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x03, 0xe8,
+        0x00, 0x00, 0x27, 0x10, 0x00, 0x01, 0x00, 0x00,
+        0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x40, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x02,
+    ];
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<BoxHeader>(), 8);
+        assert_eq!(align_of::<BoxHeader>(), 4);
+        assert_eq!(size_of::<FullBoxHeader>(), 4);
+        assert_eq!(size_of::<FileType>(), 8);
+        assert_eq!(size_of::<MovieHeaderV0>(), 96);
+        assert_eq!(align_of::<MovieHeaderV0>(), 4);
+        assert_eq!(size_of::<MovieHeaderV1>(), 108);
+        assert_eq!(size_of::<TrackHeaderV0>(), 80);
+        assert_eq!(size_of::<TrackHeaderV1>(), 92);
+        assert_eq!(size_of::<MediaHeaderV0>(), 20);
+        assert_eq!(size_of::<MediaHeaderV1>(), 32);
+        assert_eq!(size_of::<HandlerHeader>(), 20);
+        assert_eq!(size_of::<SampleEntry>(), 8);
+        assert_eq!(size_of::<TimeToSampleEntry>(), 8);
+        assert_eq!(size_of::<SampleToChunkEntry>(), 12);
+        assert_eq!(size_of::<SampleSizeHeader>(), 8);
+    }
+
+    // Walk a file and decode its movie header.
+    #[test]
+    fn verify_boxes() {
+        let mut boxes = Boxes::new(&FILE);
+
+        let b = boxes.next().unwrap();
+        assert_eq!(b.kind, BOX_FTYP);
+        let ftyp = FileType::from_bytes(b.data[..8].try_into().unwrap());
+        assert_eq!(&ftyp.major_brand, b"isom");
+        assert_eq!(int::ForeignEndian::to_native(ftyp.minor_version), 0x200);
+        assert_eq!(&b.data[8..], b"isommp41");
+
+        let b = boxes.next().unwrap();
+        assert_eq!(b.kind, BOX_MOOV);
+        assert!(boxes.next().is_none());
+
+        let mut children = b.children();
+        let b = children.next().unwrap();
+        assert_eq!(b.kind, BOX_MVHD);
+        assert!(children.next().is_none());
+
+        let (version, flags, data) = b.full().unwrap();
+        assert_eq!((version, flags), (0, 0));
+        let h = MovieHeaderV0::from_bytes(data.try_into().unwrap());
+        assert_eq!(int::ForeignEndian::to_native(h.timescale), 1000);
+        assert_eq!(int::ForeignEndian::to_native(h.duration), 10000);
+        assert_eq!(h.rate.to_f64(), 1.0);
+        assert_eq!(h.volume.to_f64(), 1.0);
+        for (m, v) in h.matrix.iter().zip(MATRIX_IDENTITY.iter()) {
+            assert_eq!(int::ForeignEndian::to_native(*m), *v);
+        }
+        assert_eq!(int::ForeignEndian::to_native(h.next_track_id), 2);
+
+        assert!(Boxes::new(&FILE[..23]).next().is_none());
+    }
+
+    // Verify large and uuid boxes, sample tables, and language codes.
+    #[test]
+    fn verify_framing() {
+        let mut data = [0u8; 42];
+        data[..4].copy_from_slice(&[0, 0, 0, 1]);
+        data[4..8].copy_from_slice(&BOX_FREE);
+        data[8..16].copy_from_slice(&18u64.to_be_bytes());
+        data[18..22].copy_from_slice(&[0, 0, 0, 0]);
+        data[22..26].copy_from_slice(&BOX_UUID);
+        data[26..30].copy_from_slice(&[0xaa; 4]);
+
+        let mut boxes = Boxes::new(&data);
+        let b = boxes.next().unwrap();
+        assert_eq!(b.kind, BOX_FREE);
+        assert_eq!(b.data.len(), 2);
+        let b = boxes.next().unwrap();
+        assert_eq!(b.kind, BOX_UUID);
+        assert_eq!(&b.usertype.unwrap()[..4], &[0xaa; 4]);
+        assert!(b.data.is_empty());
+        assert!(boxes.next().is_none());
+
+        let stts = [0, 0, 0, 1, 0, 0, 0, 5, 0, 0, 0x04, 0x00];
+        let (count, v) = entries(&stts, size_of::<TimeToSampleEntry>()).unwrap();
+        assert_eq!(count, 1);
+        let e = TimeToSampleEntry::from_bytes(v.try_into().unwrap());
+        assert_eq!(int::ForeignEndian::to_native(e.sample_count), 5);
+        assert_eq!(int::ForeignEndian::to_native(e.sample_delta), 1024);
+        assert!(entries(&stts[..11], 8).is_none());
+
+        assert_eq!(&language(0x55c4), b"und");
+    }
+}
@@ -0,0 +1,464 @@
+//! JPEG Markers and JFIF Headers
+//!
+//! A JPEG file (ITU T.81) is a sequence of segments, each introduced by a
+//! 2-byte marker: `0xff` followed by a marker code. Any number of `0xff` fill
+//! bytes may precede a marker. Most markers are followed by a 16-bit length,
+//! which includes the length field itself but not the marker, and the
+//! segment payload. The standalone markers `SOI`, `EOI`, `TEM`, and `RST0` to
+//! `RST7` carry no length and no payload.
+//!
+//! A file starts with `SOI` and ends with `EOI`. The scan header `SOS` is
+//! followed by entropy-coded data without any length. Within this data, a
+//! `0xff` byte is followed by a stuffed `0x00`, or by one of the `RSTn`
+//! markers. Any other marker ends the entropy-coded data.
+//!
+//! The application segments `APP0` to `APP15` carry metadata, identified by a
+//! zero-terminated string at the start of their payload. JFIF uses `APP0`
+//! with the `JFIF` identifier, Exif uses `APP1` with the `Exif` identifier
+//! followed by a TIFF structure.
+//!
+//! All multi-byte integers are encoded as big-endian.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::int;
+
+/// Marker Prefix
+///
+/// Every marker starts with this byte, followed by the marker code.
+pub const MARKER_PREFIX: u8 = 0xff;
+
+pub const MARKER_TEM: u8 = 0x01;
+pub const MARKER_SOF0: u8 = 0xc0;
+pub const MARKER_SOF1: u8 = 0xc1;
+pub const MARKER_SOF2: u8 = 0xc2;
+pub const MARKER_SOF3: u8 = 0xc3;
+pub const MARKER_DHT: u8 = 0xc4;
+pub const MARKER_SOF5: u8 = 0xc5;
+pub const MARKER_SOF6: u8 = 0xc6;
+pub const MARKER_SOF7: u8 = 0xc7;
+pub const MARKER_JPG: u8 = 0xc8;
+pub const MARKER_SOF9: u8 = 0xc9;
+pub const MARKER_SOF10: u8 = 0xca;
+pub const MARKER_SOF11: u8 = 0xcb;
+pub const MARKER_DAC: u8 = 0xcc;
+pub const MARKER_SOF13: u8 = 0xcd;
+pub const MARKER_SOF14: u8 = 0xce;
+pub const MARKER_SOF15: u8 = 0xcf;
+pub const MARKER_RST0: u8 = 0xd0;
+pub const MARKER_RST7: u8 = 0xd7;
+pub const MARKER_SOI: u8 = 0xd8;
+pub const MARKER_EOI: u8 = 0xd9;
+pub const MARKER_SOS: u8 = 0xda;
+pub const MARKER_DQT: u8 = 0xdb;
+pub const MARKER_DNL: u8 = 0xdc;
+pub const MARKER_DRI: u8 = 0xdd;
+pub const MARKER_DHP: u8 = 0xde;
+pub const MARKER_EXP: u8 = 0xdf;
+pub const MARKER_APP0: u8 = 0xe0;
+pub const MARKER_APP1: u8 = 0xe1;
+pub const MARKER_APP2: u8 = 0xe2;
+pub const MARKER_APP14: u8 = 0xee;
+pub const MARKER_APP15: u8 = 0xef;
+pub const MARKER_COM: u8 = 0xfe;
+
+/// JFIF Identifier
+///
+/// The identifier at the start of a JFIF `APP0` segment.
+pub const JFIF_IDENTIFIER: [u8; 5] = *b"JFIF\0";
+
+/// JFIF Extension Identifier
+///
+/// The identifier at the start of a JFIF extension `APP0` segment, followed
+/// by one of the `JFXX_*` extension codes and the thumbnail.
+pub const JFXX_IDENTIFIER: [u8; 5] = *b"JFXX\0";
+
+pub const JFXX_THUMBNAIL_JPEG: u8 = 0x10;
+pub const JFXX_THUMBNAIL_PALETTE: u8 = 0x11;
+pub const JFXX_THUMBNAIL_RGB: u8 = 0x13;
+
+/// Exif Identifier
+///
+/// The identifier at the start of an Exif `APP1` segment. It is followed by
+/// a TIFF header.
+pub const EXIF_IDENTIFIER: [u8; 6] = *b"Exif\0\0";
+
+/// XMP Identifier
+///
+/// The identifier at the start of an XMP `APP1` segment.
+pub const XMP_IDENTIFIER: [u8; 29] = *b"http://ns.adobe.com/xap/1.0/\0";
+
+/// ICC Profile Identifier
+///
+/// The identifier at the start of an `APP2` segment carrying a chunk of an
+/// ICC profile. It is followed by the 1-based chunk index and the total
+/// number of chunks, each as a single byte.
+pub const ICC_IDENTIFIER: [u8; 12] = *b"ICC_PROFILE\0";
+
+pub const JFIF_UNITS_NONE: u8 = 0;
+pub const JFIF_UNITS_DPI: u8 = 1;
+pub const JFIF_UNITS_DPCM: u8 = 2;
+
+/// JFIF Header
+///
+/// The payload of a JFIF `APP0` segment. It is followed by an uncompressed
+/// RGB thumbnail of `x_thumbnail * y_thumbnail` pixels.
+#[repr(C)]
+pub struct JfifHeader {
+    /// Identifier, must match `JFIF_IDENTIFIER`.
+    pub identifier: [u8; 5],
+
+    /// Major version, must be 1.
+    pub version_major: u8,
+
+    /// Minor version, usually 1 or 2.
+    pub version_minor: u8,
+
+    /// Unit of the pixel density (`JFIF_UNITS_*`).
+    pub units: u8,
+
+    /// Horizontal pixel density.
+    pub x_density: int::u16be,
+
+    /// Vertical pixel density.
+    pub y_density: int::u16be,
+
+    /// Width of the thumbnail in pixels.
+    pub x_thumbnail: u8,
+
+    /// Height of the thumbnail in pixels.
+    pub y_thumbnail: u8,
+}
+
+/// Frame Header
+///
+/// The payload of all `SOFn` segments. It is followed by one
+/// `FrameComponent` for each component.
+#[repr(C, packed)]
+pub struct FrameHeader {
+    /// Sample precision in bits.
+    pub precision: u8,
+
+    /// Number of lines, or 0 if defined by a later `DNL` segment.
+    pub height: int::u16be,
+
+    /// Number of samples per line.
+    pub width: int::u16be,
+
+    /// Number of components in the frame.
+    pub components: u8,
+}
+
+/// Frame Component
+#[repr(C)]
+pub struct FrameComponent {
+    /// Component identifier.
+    pub id: u8,
+
+    /// Horizontal sampling factor in the high nibble, vertical sampling
+    /// factor in the low nibble.
+    pub sampling: u8,
+
+    /// Quantization table selector.
+    pub quantization_table: u8,
+}
+
+/// Scan Component
+///
+/// A component of a scan, following the component count at the start of an
+/// `SOS` segment. The components are followed by three bytes: the start and
+/// end of the spectral selection, and the successive approximation bits.
+#[repr(C)]
+pub struct ScanComponent {
+    /// Component identifier, matching a `FrameComponent`.
+    pub id: u8,
+
+    /// DC entropy table selector in the high nibble, AC entropy table
+    /// selector in the low nibble.
+    pub tables: u8,
+}
+
+/// Segment Reference
+///
+/// A reference to a segment in a byte slice, as yielded by `Segments`.
+#[derive(Clone, Copy, Debug)]
+pub struct Segment<'data> {
+    /// Marker code, without the prefix.
+    pub marker: u8,
+
+    /// Segment payload, excluding the length field. This is empty for
+    /// standalone markers.
+    pub data: &'data [u8],
+}
+
+/// Segment Iterator
+///
+/// An iterator over the segments of a JPEG file, starting with `SOI`.
+/// Iteration stops at `EOI`, after the first `SOS` segment, or at the first
+/// segment that is malformed or truncated. Use `Segments::remainder()` to
+/// get the data following the last segment, which is the entropy-coded data
+/// after `SOS`.
+#[derive(Clone, Debug)]
+pub struct Segments<'data> {
+    data: &'data [u8],
+    done: bool,
+}
+
+impl JfifHeader {
+    /// Import a JFIF header from a byte slice
+    ///
+    /// Create a new JFIF header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 14]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 14], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl FrameHeader {
+    /// Import a frame header from a byte slice
+    ///
+    /// Create a new frame header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 6]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 6], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl FrameComponent {
+    /// Return horizontal sampling factor
+    pub fn horizontal_sampling(&self) -> u8 {
+        self.sampling >> 4
+    }
+
+    /// Return vertical sampling factor
+    pub fn vertical_sampling(&self) -> u8 {
+        self.sampling & 0x0f
+    }
+}
+
+impl<'data> Segments<'data> {
+    /// Create segment iterator
+    ///
+    /// Create a new iterator over the segments in `data`, which must start
+    /// with the `SOI` marker.
+    pub fn new(data: &'data [u8]) -> Self {
+        Self {
+            data,
+            done: false,
+        }
+    }
+
+    /// Return remaining data
+    ///
+    /// Return the data following the last segment yielded so far.
+    pub fn remainder(&self) -> &'data [u8] {
+        self.data
+    }
+}
+
+impl<'data> Iterator for Segments<'data> {
+    type Item = Segment<'data>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        // Require a prefix, and skip any fill bytes following it.
+        if self.data.first() != Some(&MARKER_PREFIX) {
+            return None;
+        }
+        let mut pos = 0;
+        while self.data.get(pos + 1) == Some(&MARKER_PREFIX) {
+            pos += 1;
+        }
+        let marker = *self.data.get(pos + 1)?;
+        pos += 2;
+
+        if is_standalone(marker) {
+            self.done = marker == MARKER_EOI;
+            self.data = &self.data[pos..];
+            return Some(Segment { marker, data: &[] });
+        }
+
+        let length = u16::from_be_bytes(self.data.get(pos..pos + 2)?.try_into().unwrap());
+        if length < 2 || self.data.len() - pos < length as usize {
+            return None;
+        }
+
+        let end = pos + length as usize;
+        let segment = Segment {
+            marker,
+            data: &self.data[pos + 2..end],
+        };
+
+        self.done = marker == MARKER_SOS;
+        self.data = &self.data[end..];
+        Some(segment)
+    }
+}
+
+/// Check for standalone markers
+///
+/// Return whether the marker is a standalone marker without length and
+/// payload.
+pub fn is_standalone(marker: u8) -> bool {
+    matches!(marker, MARKER_TEM | MARKER_RST0..=MARKER_RST7 | MARKER_SOI | MARKER_EOI)
+}
+
+/// Check for start-of-frame markers
+///
+/// Return whether the marker is one of the `SOFn` markers, which all carry a
+/// `FrameHeader`. `DHT`, `JPG`, and `DAC` share the range but are excluded.
+pub fn is_sof(marker: u8) -> bool {
+    matches!(marker, MARKER_SOF0..=MARKER_SOF15)
+        && !matches!(marker, MARKER_DHT | MARKER_JPG | MARKER_DAC)
+}
+
+/// Check for application markers
+pub fn is_app(marker: u8) -> bool {
+    matches!(marker, MARKER_APP0..=MARKER_APP15)
+}
+
+/// Find end of entropy-coded data
+///
+/// Scan entropy-coded data for the next marker that is neither a stuffed
+/// zero byte, a fill byte, nor a restart marker. Return the offset of its
+/// prefix, or `None` if no such marker is found.
+pub fn find_marker(data: &[u8]) -> Option<usize> {
+    let mut i = 0;
+
+    while i + 1 < data.len() {
+        if data[i] == MARKER_PREFIX {
+            match data[i + 1] {
+                0x00 | MARKER_RST0..=MARKER_RST7 => i += 2,
+                MARKER_PREFIX => i += 1,
+                _ => return Some(i),
+            }
+        } else {
+            i += 1;
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A minimal marker sequence with JFIF header, a baseline frame of a
+    // single 16x8 component, a scan, and entropy-coded data containing a
+    // stuffed zero and a restart marker.
+    const IMAGE: [u8; 57] = [
+        0xff, 0xd8, 0xff, 0xe0, 0x00, 0x10, 0x4a, 0x46,
+        0x49, 0x46, 0x00, 0x01, 0x02, 0x01, 0x00, 0x48,
+        0x00, 0x48, 0x00, 0x00, 0xff, 0xfe, 0x00, 0x04,
+        0x68, 0x69, 0xff, 0xff, 0xc0, 0x00, 0x0b, 0x08,
+        0x00, 0x08, 0x00, 0x10, 0x01, 0x01, 0x11, 0x00,
+        0xff, 0xda, 0x00, 0x08, 0x01, 0x01, 0x00, 0x00,
+        0x3f, 0x00, 0x12, 0xff, 0x00, 0xff, 0xd0, 0xff,
+        0xd9,
+    ];
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<JfifHeader>(), 14);
+        assert_eq!(align_of::<JfifHeader>(), 2);
+        assert_eq!(size_of::<FrameHeader>(), 6);
+        assert_eq!(align_of::<FrameHeader>(), 1);
+        assert_eq!(size_of::<FrameComponent>(), 3);
+        assert_eq!(size_of::<ScanComponent>(), 2);
+    }
+
+    // Walk the segments of an image and parse the headers.
+    #[test]
+    fn verify_segments() {
+        let mut segments = Segments::new(&IMAGE);
+
+        let s = segments.next().unwrap();
+        assert_eq!(s.marker, MARKER_SOI);
+        assert!(s.data.is_empty());
+
+        let s = segments.next().unwrap();
+        assert_eq!(s.marker, MARKER_APP0);
+        let h = JfifHeader::from_bytes(s.data.try_into().unwrap());
+        assert_eq!(h.identifier, JFIF_IDENTIFIER);
+        assert_eq!((h.version_major, h.version_minor), (1, 2));
+        assert_eq!(h.units, JFIF_UNITS_DPI);
+        assert_eq!(int::ForeignEndian::to_native(h.x_density), 72);
+        assert_eq!(int::ForeignEndian::to_native(h.y_density), 72);
+
+        let s = segments.next().unwrap();
+        assert_eq!(s.marker, MARKER_COM);
+        assert_eq!(s.data, b"hi");
+
+        // Fill bytes precede this marker.
+        let s = segments.next().unwrap();
+        assert!(is_sof(s.marker));
+        let f = FrameHeader::from_bytes(s.data[..6].try_into().unwrap());
+        assert_eq!(f.precision, 8);
+        assert_eq!(int::ForeignEndian::to_native(f.height), 8);
+        assert_eq!(int::ForeignEndian::to_native(f.width), 16);
+        assert_eq!(f.components, 1);
+        assert_eq!(s.data[7], 0x11);
+
+        let s = segments.next().unwrap();
+        assert_eq!(s.marker, MARKER_SOS);
+        assert!(segments.next().is_none());
+
+        let rest = segments.remainder();
+        let end = find_marker(rest).unwrap();
+        assert_eq!(end, 5);
+
+        let mut segments = Segments::new(&rest[end..]);
+        assert_eq!(segments.next().unwrap().marker, MARKER_EOI);
+        assert!(segments.next().is_none());
+
+        // Fill bytes are skipped, but garbage before a marker is rejected.
+        let s = Segments::new(&[0xff, 0xff, 0xff, MARKER_SOI]).next().unwrap();
+        assert_eq!(s.marker, MARKER_SOI);
+        assert!(Segments::new(&[0x00, 0xff, MARKER_SOI]).next().is_none());
+        assert!(Segments::new(&[0xff, 0x00, 0xff, MARKER_SOI]).next().is_none());
+    }
+
+    // Verify marker classification.
+    #[test]
+    fn verify_markers() {
+        assert!(is_standalone(MARKER_SOI));
+        assert!(is_standalone(MARKER_RST0 + 3));
+        assert!(!is_standalone(MARKER_SOS));
+
+        assert!(is_sof(MARKER_SOF0));
+        assert!(is_sof(MARKER_SOF2));
+        assert!(is_sof(MARKER_SOF15));
+        assert!(!is_sof(MARKER_DHT));
+        assert!(!is_sof(MARKER_DAC));
+
+        assert!(is_app(MARKER_APP1));
+        assert!(!is_app(MARKER_COM));
+
+        assert!(Segments::new(&IMAGE[..10]).nth(1).is_none());
+    }
+}
@@ -0,0 +1,677 @@
+//! Khronos Texture Containers
+//!
+//! KTX files store textures ready for upload to OpenGL and Vulkan, including
+//! all mipmap levels, array layers and cube-map faces. Two incompatible
+//! generations exist, distinguished by their 12-byte identifier.
+//!
+//! A KTX 1 file starts with `Header`, followed by `bytes_of_key_value_data`
+//! bytes of key/value pairs (see `KeyValues`), followed by the mipmap levels.
+//! Each level is prefixed with its 32-bit image size and padded to 4 bytes.
+//! The file is encoded in the byte order of the machine that wrote it, which
+//! is recorded in the endianness field of the header.
+//!
+//! A KTX 2 file starts with `Header2`, followed by one `LevelIndex` per mipmap
+//! level (starting with the base level), the data format descriptor (see
+//! `DfdBlocks`), the key/value data, the optional supercompression global
+//! data, and eventually the mipmap levels (stored smallest first). All parts
+//! are located via byte offsets from the start of the file.
+//!
+//! KTX 1 uses the byte order of its endianness field. All multi-byte integers
+//! of KTX 2 are encoded as little-endian.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::int;
+
+pub const MAGIC: [u8; 12] = [0xab, 0x4b, 0x54, 0x58, 0x20, 0x31, 0x31, 0xbb, 0x0d, 0x0a, 0x1a, 0x0a];
+pub const MAGIC2: [u8; 12] = [0xab, 0x4b, 0x54, 0x58, 0x20, 0x32, 0x30, 0xbb, 0x0d, 0x0a, 0x1a, 0x0a];
+
+/// Endianness Marker
+///
+/// The value of the endianness field of KTX 1 headers, written in the byte
+/// order of the file.
+pub const ENDIANNESS: u32 = 0x04030201;
+
+pub const HEADER_SIZE: usize = 64;
+pub const HEADER2_SIZE: usize = 80;
+pub const LEVEL_INDEX_SIZE: usize = 24;
+
+pub const GL_UNSIGNED_BYTE: u32 = 0x1401;
+pub const GL_FLOAT: u32 = 0x1406;
+pub const GL_HALF_FLOAT: u32 = 0x140b;
+pub const GL_RGB: u32 = 0x1907;
+pub const GL_RGBA: u32 = 0x1908;
+pub const GL_RGB8: u32 = 0x8051;
+pub const GL_RGBA8: u32 = 0x8058;
+pub const GL_SRGB8_ALPHA8: u32 = 0x8c43;
+
+pub const VK_FORMAT_UNDEFINED: u32 = 0;
+pub const VK_FORMAT_R8G8B8A8_UNORM: u32 = 37;
+pub const VK_FORMAT_R8G8B8A8_SRGB: u32 = 43;
+pub const VK_FORMAT_R16G16B16A16_SFLOAT: u32 = 97;
+pub const VK_FORMAT_R32G32B32A32_SFLOAT: u32 = 109;
+pub const VK_FORMAT_BC1_RGB_UNORM_BLOCK: u32 = 131;
+pub const VK_FORMAT_BC3_UNORM_BLOCK: u32 = 137;
+pub const VK_FORMAT_BC7_UNORM_BLOCK: u32 = 145;
+pub const VK_FORMAT_BC7_SRGB_BLOCK: u32 = 146;
+pub const VK_FORMAT_ASTC_4X4_UNORM_BLOCK: u32 = 157;
+
+pub const SUPERCOMPRESSION_NONE: u32 = 0;
+pub const SUPERCOMPRESSION_BASIS_LZ: u32 = 1;
+pub const SUPERCOMPRESSION_ZSTD: u32 = 2;
+pub const SUPERCOMPRESSION_ZLIB: u32 = 3;
+
+pub const KEY_ORIENTATION: &[u8] = b"KTXorientation";
+pub const KEY_WRITER: &[u8] = b"KTXwriter";
+pub const KEY_SWIZZLE: &[u8] = b"KTXswizzle";
+pub const KEY_GL_FORMAT: &[u8] = b"KTXglFormat";
+
+/// Size of the Data Format Descriptor Block Header
+pub const DFD_BLOCK_HEADER_SIZE: usize = 8;
+
+/// Size of the Basic Data Format Descriptor Block
+///
+/// This is the size of the basic descriptor block without its samples, but
+/// including its block header.
+pub const DFD_BASIC_SIZE: usize = 24;
+
+/// Size of Basic Data Format Descriptor Samples
+pub const DFD_SAMPLE_SIZE: usize = 16;
+
+pub const DFD_VENDOR_KHRONOS: u32 = 0;
+pub const DFD_TYPE_BASIC: u16 = 0;
+pub const DFD_VERSION_1_3: u16 = 2;
+
+pub const DFD_MODEL_UNSPECIFIED: u8 = 0;
+pub const DFD_MODEL_RGBSDA: u8 = 1;
+pub const DFD_MODEL_YUVSDA: u8 = 2;
+pub const DFD_MODEL_BC1A: u8 = 128;
+pub const DFD_MODEL_BC2: u8 = 129;
+pub const DFD_MODEL_BC3: u8 = 130;
+pub const DFD_MODEL_BC4: u8 = 131;
+pub const DFD_MODEL_BC5: u8 = 132;
+pub const DFD_MODEL_BC6H: u8 = 133;
+pub const DFD_MODEL_BC7: u8 = 134;
+pub const DFD_MODEL_ETC1: u8 = 160;
+pub const DFD_MODEL_ETC2: u8 = 161;
+pub const DFD_MODEL_ASTC: u8 = 162;
+pub const DFD_MODEL_ETC1S: u8 = 163;
+pub const DFD_MODEL_UASTC: u8 = 166;
+
+pub const DFD_PRIMARIES_UNSPECIFIED: u8 = 0;
+pub const DFD_PRIMARIES_BT709: u8 = 1;
+pub const DFD_PRIMARIES_BT601_EBU: u8 = 2;
+pub const DFD_PRIMARIES_BT601_SMPTE: u8 = 3;
+pub const DFD_PRIMARIES_BT2020: u8 = 4;
+pub const DFD_PRIMARIES_DISPLAYP3: u8 = 10;
+
+pub const DFD_TRANSFER_UNSPECIFIED: u8 = 0;
+pub const DFD_TRANSFER_LINEAR: u8 = 1;
+pub const DFD_TRANSFER_SRGB: u8 = 2;
+
+pub const DFD_FLAG_ALPHA_PREMULTIPLIED: u8 = 0x01;
+
+pub const DFD_CHANNEL_MASK: u8 = 0x0f;
+pub const DFD_CHANNEL_LINEAR: u8 = 0x10;
+pub const DFD_CHANNEL_EXPONENT: u8 = 0x20;
+pub const DFD_CHANNEL_SIGNED: u8 = 0x40;
+pub const DFD_CHANNEL_FLOAT: u8 = 0x80;
+
+pub const DFD_CHANNEL_RED: u8 = 0;
+pub const DFD_CHANNEL_GREEN: u8 = 1;
+pub const DFD_CHANNEL_BLUE: u8 = 2;
+pub const DFD_CHANNEL_STENCIL: u8 = 13;
+pub const DFD_CHANNEL_DEPTH: u8 = 14;
+pub const DFD_CHANNEL_ALPHA: u8 = 15;
+
+/// KTX 1 Header
+#[repr(C)]
+pub struct Header {
+    /// File identifier, must be `MAGIC`.
+    pub identifier: [u8; 12],
+
+    /// Endianness marker, must be `ENDIANNESS` (see `endianness()`).
+    pub endianness: int::u32re,
+
+    /// OpenGL data type, or 0 for compressed textures.
+    pub gl_type: int::u32re,
+
+    /// Size of the data type in bytes, used for byte-swapping.
+    pub gl_type_size: int::u32re,
+
+    /// OpenGL pixel format, or 0 for compressed textures.
+    pub gl_format: int::u32re,
+
+    /// OpenGL internal format.
+    pub gl_internal_format: int::u32re,
+
+    /// OpenGL base internal format.
+    pub gl_base_internal_format: int::u32re,
+
+    /// Width in pixels.
+    pub pixel_width: int::u32re,
+
+    /// Height in pixels, or 0 for 1D textures.
+    pub pixel_height: int::u32re,
+
+    /// Depth in pixels, or 0 for 1D and 2D textures.
+    pub pixel_depth: int::u32re,
+
+    /// Number of array elements, or 0 if not an array texture.
+    pub number_of_array_elements: int::u32re,
+
+    /// Number of cube-map faces, either 1 or 6.
+    pub number_of_faces: int::u32re,
+
+    /// Number of mipmap levels, or 0 to request generation.
+    pub number_of_mipmap_levels: int::u32re,
+
+    /// Size of the key/value data in bytes.
+    pub bytes_of_key_value_data: int::u32re,
+}
+
+/// KTX 2 Header
+#[repr(C)]
+pub struct Header2 {
+    /// File identifier, must be `MAGIC2`.
+    pub identifier: [u8; 12],
+
+    /// Vulkan format (`VK_FORMAT_*`), or `VK_FORMAT_UNDEFINED` if described by
+    /// the data format descriptor only.
+    pub vk_format: int::u32le,
+
+    /// Size of the data type in bytes, used for byte-swapping.
+    pub type_size: int::u32le,
+
+    /// Width in pixels.
+    pub pixel_width: int::u32le,
+
+    /// Height in pixels, or 0 for 1D textures.
+    pub pixel_height: int::u32le,
+
+    /// Depth in pixels, or 0 for 1D and 2D textures.
+    pub pixel_depth: int::u32le,
+
+    /// Number of array layers, or 0 if not an array texture.
+    pub layer_count: int::u32le,
+
+    /// Number of cube-map faces, either 1 or 6.
+    pub face_count: int::u32le,
+
+    /// Number of mipmap levels, or 0 to request generation.
+    pub level_count: int::u32le,
+
+    /// Supercompression scheme (`SUPERCOMPRESSION_*`).
+    pub supercompression_scheme: int::u32le,
+
+    /// Offset of the data format descriptor.
+    pub dfd_byte_offset: int::u32le,
+
+    /// Size of the data format descriptor in bytes.
+    pub dfd_byte_length: int::u32le,
+
+    /// Offset of the key/value data, or 0 if there is none.
+    pub kvd_byte_offset: int::u32le,
+
+    /// Size of the key/value data in bytes.
+    pub kvd_byte_length: int::u32le,
+
+    /// Offset of the supercompression global data, or 0 if there is none.
+    pub sgd_byte_offset: int::u64le,
+
+    /// Size of the supercompression global data in bytes.
+    pub sgd_byte_length: int::u64le,
+}
+
+/// KTX 2 Level Index Entry
+#[repr(C)]
+pub struct LevelIndex {
+    /// Offset of the mipmap level.
+    pub byte_offset: int::u64le,
+
+    /// Size of the mipmap level in bytes, as stored.
+    pub byte_length: int::u64le,
+
+    /// Size of the mipmap level in bytes, after supercompression is undone.
+    pub uncompressed_byte_length: int::u64le,
+}
+
+/// Basic Data Format Descriptor
+///
+/// The payload of a `DFD_TYPE_BASIC` descriptor block, following the block
+/// header. It is followed by the samples (see `DfdSample`).
+#[repr(C)]
+pub struct DfdBasic {
+    /// Color model (`DFD_MODEL_*`).
+    pub color_model: u8,
+
+    /// Color primaries (`DFD_PRIMARIES_*`).
+    pub color_primaries: u8,
+
+    /// Transfer function (`DFD_TRANSFER_*`).
+    pub transfer_function: u8,
+
+    /// Flags (`DFD_FLAG_*`).
+    pub flags: u8,
+
+    /// Texel block dimensions, each minus 1.
+    pub texel_block_dimension: [u8; 4],
+
+    /// Bytes per plane, for up to 8 planes.
+    pub bytes_plane: [u8; 8],
+}
+
+/// Basic Data Format Descriptor Sample
+#[repr(C)]
+pub struct DfdSample {
+    /// Offset of the sample in bits.
+    pub bit_offset: int::u16le,
+
+    /// Length of the sample in bits, minus 1.
+    pub bit_length: u8,
+
+    /// Channel type in the lower nibble (`DFD_CHANNEL_MASK`), qualifiers in
+    /// the upper nibble.
+    pub channel_type: u8,
+
+    /// Position of the sample in the texel block, per dimension.
+    pub sample_position: [u8; 4],
+
+    /// Value representing the lower bound of the channel.
+    pub sample_lower: int::u32le,
+
+    /// Value representing the upper bound of the channel.
+    pub sample_upper: int::u32le,
+}
+
+/// Key/Value Iterator
+///
+/// An iterator over the key/value pairs of KTX 1 and KTX 2 files. Each pair is
+/// prefixed with its 32-bit size and padded to 4 bytes. The key is terminated
+/// by a NUL byte, which is not part of the key yielded by the iterator. The
+/// value is yielded verbatim, including any NUL terminator. Iteration stops at
+/// the end of the data, or at the first pair that is truncated or lacks a key
+/// terminator.
+#[derive(Clone, Debug)]
+pub struct KeyValues<'data> {
+    data: &'data [u8],
+    endian: int::Endianness,
+}
+
+/// Data Format Descriptor Block
+#[derive(Clone, Copy, Debug)]
+pub struct DfdBlock<'data> {
+    /// Vendor ID (`DFD_VENDOR_*`).
+    pub vendor_id: u32,
+
+    /// Descriptor type (`DFD_TYPE_*`).
+    pub descriptor_type: u16,
+
+    /// Version of the descriptor type (`DFD_VERSION_*`).
+    pub version: u16,
+
+    /// Payload of the block, following the block header.
+    pub data: &'data [u8],
+}
+
+/// Data Format Descriptor Block Iterator
+///
+/// An iterator over the descriptor blocks of a KTX 2 data format descriptor.
+/// Each block header packs a 17-bit vendor ID and a 15-bit descriptor type,
+/// followed by a 16-bit version and the 16-bit block size including the
+/// header. Iteration stops at the end of the data, or at the first block that
+/// is truncated or has an invalid size.
+#[derive(Clone, Debug)]
+pub struct DfdBlocks<'data> {
+    data: &'data [u8],
+}
+
+impl Header {
+    /// Import a header from a byte slice
+    ///
+    /// Create a new header from data copied from a byte slice. No byte-order
+    /// conversions are applied.
+    pub fn from_bytes(data: &[u8; 64]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 64], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Return byte order
+    ///
+    /// Return the byte order of the file, or `None` if the header carries an
+    /// invalid endianness marker.
+    pub fn endianness(&self) -> Option<int::Endianness> {
+        if self.endianness.to_native(int::Endianness::Little) == ENDIANNESS {
+            Some(int::Endianness::Little)
+        } else if self.endianness.to_native(int::Endianness::Big) == ENDIANNESS {
+            Some(int::Endianness::Big)
+        } else {
+            None
+        }
+    }
+}
+
+impl Header2 {
+    /// Import a header from a byte slice
+    ///
+    /// Create a new header from data copied from a byte slice. No byte-order
+    /// conversions are applied.
+    pub fn from_bytes(data: &[u8; 80]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 80], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Return number of level index entries
+    ///
+    /// Return the number of `LevelIndex` entries following the header. A
+    /// level count of 0 still stores the base level.
+    pub fn levels(&self) -> u32 {
+        core::cmp::max(int::ForeignEndian::to_native(self.level_count), 1)
+    }
+}
+
+impl LevelIndex {
+    /// Import a level index entry from a byte slice
+    ///
+    /// Create a new level index entry from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 24]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 24], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl DfdBasic {
+    /// Import a basic descriptor from a byte slice
+    ///
+    /// Create a new basic descriptor from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 16]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 16], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl DfdSample {
+    /// Import a sample from a byte slice
+    ///
+    /// Create a new sample from data copied from a byte slice. No byte-order
+    /// conversions are applied.
+    pub fn from_bytes(data: &[u8; 16]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 16], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl<'data> KeyValues<'data> {
+    /// Create key/value iterator
+    ///
+    /// Create a new iterator over the key/value data in `data`, encoded in
+    /// the byte order `endian`.
+    pub fn new(data: &'data [u8], endian: int::Endianness) -> Self {
+        Self {
+            data,
+            endian,
+        }
+    }
+
+    /// Return remaining data
+    ///
+    /// Return the data that has not been consumed by the iterator.
+    pub fn remainder(&self) -> &'data [u8] {
+        self.data
+    }
+}
+
+impl<'data> Iterator for KeyValues<'data> {
+    type Item = (&'data [u8], &'data [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.data.len() < 4 {
+            return None;
+        }
+
+        let raw = u32::from_ne_bytes(self.data[..4].try_into().unwrap());
+        let size = int::u32re::from_raw(raw).to_native(self.endian) as usize;
+        if self.data.len() - 4 < size {
+            return None;
+        }
+
+        let pair = &self.data[4..4 + size];
+        let nul = pair.iter().position(|v| *v == 0)?;
+
+        let end = core::cmp::min(4 + ((size + 3) & !3), self.data.len());
+        self.data = &self.data[end..];
+        Some((&pair[..nul], &pair[nul + 1..]))
+    }
+}
+
+impl<'data> DfdBlocks<'data> {
+    /// Create descriptor block iterator
+    ///
+    /// Create a new iterator over the descriptor blocks in `data`, which must
+    /// start directly after the 32-bit total size of the data format
+    /// descriptor.
+    pub fn new(data: &'data [u8]) -> Self {
+        Self {
+            data,
+        }
+    }
+
+    /// Return remaining data
+    ///
+    /// Return the data that has not been consumed by the iterator.
+    pub fn remainder(&self) -> &'data [u8] {
+        self.data
+    }
+}
+
+impl<'data> Iterator for DfdBlocks<'data> {
+    type Item = DfdBlock<'data>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.data.len() < DFD_BLOCK_HEADER_SIZE {
+            return None;
+        }
+
+        let vendor_type = u32::from_le_bytes(self.data[..4].try_into().unwrap());
+        let version = u16::from_le_bytes(self.data[4..6].try_into().unwrap());
+        let size = u16::from_le_bytes(self.data[6..8].try_into().unwrap()) as usize;
+        if size < DFD_BLOCK_HEADER_SIZE || self.data.len() < size {
+            return None;
+        }
+
+        let block = DfdBlock {
+            vendor_id: vendor_type & 0x1ffff,
+            descriptor_type: (vendor_type >> 17) as u16,
+            version,
+            data: &self.data[DFD_BLOCK_HEADER_SIZE..size],
+        };
+
+        self.data = &self.data[size..];
+        Some(block)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A big-endian 16x8 RGBA8 texture header with an orientation key.
+    const FILE: [u8; 88] = [
+        0xab, 0x4b, 0x54, 0x58, 0x20, 0x31, 0x31, 0xbb,
+        0x0d, 0x0a, 0x1a, 0x0a, 0x04, 0x03, 0x02, 0x01,
+        0x00, 0x00, 0x14, 0x01, 0x00, 0x00, 0x00, 0x01,
+        0x00, 0x00, 0x19, 0x08, 0x00, 0x00, 0x80, 0x58,
+        0x00, 0x00, 0x19, 0x08, 0x00, 0x00, 0x00, 0x10,
+        0x00, 0x00, 0x00, 0x08, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01,
+        0x00, 0x00, 0x00, 0x05, 0x00, 0x00, 0x00, 0x18,
+        0x00, 0x00, 0x00, 0x12, 0x4b, 0x54, 0x58, 0x6f,
+        0x72, 0x69, 0x65, 0x6e, 0x74, 0x61, 0x74, 0x69,
+        0x6f, 0x6e, 0x00, 0x72, 0x64, 0x00, 0x00, 0x00,
+    ];
+
+    // A KTX 2 header of a 4x4 RGBA8 texture, followed by its level index.
+    const FILE2: [u8; 104] = [
+        0xab, 0x4b, 0x54, 0x58, 0x20, 0x32, 0x30, 0xbb,
+        0x0d, 0x0a, 0x1a, 0x0a, 0x25, 0x00, 0x00, 0x00,
+        0x01, 0x00, 0x00, 0x00, 0x04, 0x00, 0x00, 0x00,
+        0x04, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00,
+        0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x68, 0x00, 0x00, 0x00, 0x5c, 0x00, 0x00, 0x00,
+        0xc4, 0x00, 0x00, 0x00, 0x18, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0xdc, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x40, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x40, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+
+    // The data format descriptor of the KTX 2 texture.
+    const DFD: [u8; 92] = [
+        0x5c, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x02, 0x00, 0x58, 0x00, 0x01, 0x01, 0x01, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x04, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x07, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0xff, 0x00, 0x00, 0x00, 0x08, 0x00, 0x07, 0x01,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0xff, 0x00, 0x00, 0x00, 0x10, 0x00, 0x07, 0x02,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0xff, 0x00, 0x00, 0x00, 0x18, 0x00, 0x07, 0x0f,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0xff, 0x00, 0x00, 0x00,
+    ];
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<Header>(), HEADER_SIZE);
+        assert_eq!(align_of::<Header>(), 4);
+        assert_eq!(size_of::<Header2>(), HEADER2_SIZE);
+        assert_eq!(align_of::<Header2>(), 8);
+        assert_eq!(size_of::<LevelIndex>(), LEVEL_INDEX_SIZE);
+        assert_eq!(align_of::<LevelIndex>(), 8);
+        assert_eq!(size_of::<DfdBasic>(), DFD_BASIC_SIZE - DFD_BLOCK_HEADER_SIZE);
+        assert_eq!(align_of::<DfdBasic>(), 1);
+        assert_eq!(size_of::<DfdSample>(), DFD_SAMPLE_SIZE);
+        assert_eq!(align_of::<DfdSample>(), 4);
+    }
+
+    // Parse a big-endian KTX 1 header and its key/value data.
+    #[test]
+    fn verify_ktx1() {
+        let h = Header::from_bytes(FILE[..64].try_into().unwrap());
+        assert_eq!(h.identifier, MAGIC);
+        let e = h.endianness().unwrap();
+        assert_eq!(e, int::Endianness::Big);
+        assert_eq!(h.gl_type.to_native(e), GL_UNSIGNED_BYTE);
+        assert_eq!(h.gl_format.to_native(e), GL_RGBA);
+        assert_eq!(h.gl_internal_format.to_native(e), GL_RGBA8);
+        assert_eq!(h.pixel_width.to_native(e), 16);
+        assert_eq!(h.pixel_height.to_native(e), 8);
+        assert_eq!(h.number_of_faces.to_native(e), 1);
+        assert_eq!(h.number_of_mipmap_levels.to_native(e), 5);
+
+        let n = h.bytes_of_key_value_data.to_native(e) as usize;
+        let mut kv = KeyValues::new(&FILE[64..64 + n], e);
+        assert_eq!(kv.next(), Some((KEY_ORIENTATION, &b"rd\0"[..])));
+        assert!(kv.next().is_none());
+        assert!(kv.remainder().is_empty());
+
+        // Byte-swapped markers are detected, anything else is rejected.
+        let mut data = FILE;
+        data[12..16].copy_from_slice(&[0x01, 0x02, 0x03, 0x04]);
+        let h = Header::from_bytes(data[..64].try_into().unwrap());
+        assert_eq!(h.endianness(), Some(int::Endianness::Little));
+        data[12..16].copy_from_slice(&[0x01, 0x02, 0x04, 0x03]);
+        let h = Header::from_bytes(data[..64].try_into().unwrap());
+        assert!(h.endianness().is_none());
+    }
+
+    // Parse a KTX 2 header, its level index, and its data format descriptor.
+    #[test]
+    fn verify_ktx2() {
+        let h = Header2::from_bytes(FILE2[..80].try_into().unwrap());
+        assert_eq!(h.identifier, MAGIC2);
+        assert_eq!(int::ForeignEndian::to_native(h.vk_format), VK_FORMAT_R8G8B8A8_UNORM);
+        assert_eq!(int::ForeignEndian::to_native(h.supercompression_scheme), SUPERCOMPRESSION_NONE);
+        assert_eq!(h.levels(), 1);
+        assert_eq!(int::ForeignEndian::to_native(h.dfd_byte_offset), 104);
+        assert_eq!(int::ForeignEndian::to_native(h.dfd_byte_length), 92);
+        assert_eq!(int::ForeignEndian::to_native(h.sgd_byte_length), 0);
+
+        let l = LevelIndex::from_bytes(FILE2[80..104].try_into().unwrap());
+        assert_eq!(int::ForeignEndian::to_native(l.byte_offset), 220);
+        assert_eq!(int::ForeignEndian::to_native(l.byte_length), 64);
+        assert_eq!(int::ForeignEndian::to_native(l.uncompressed_byte_length), 64);
+
+        let total = u32::from_le_bytes(DFD[..4].try_into().unwrap()) as usize;
+        assert_eq!(total, DFD.len());
+
+        let mut blocks = DfdBlocks::new(&DFD[4..total]);
+        let b = blocks.next().unwrap();
+        assert_eq!(b.vendor_id, DFD_VENDOR_KHRONOS);
+        assert_eq!(b.descriptor_type, DFD_TYPE_BASIC);
+        assert_eq!(b.version, DFD_VERSION_1_3);
+        assert_eq!(b.data.len(), DFD_BASIC_SIZE - DFD_BLOCK_HEADER_SIZE + 4 * DFD_SAMPLE_SIZE);
+        assert!(blocks.next().is_none());
+
+        let d = DfdBasic::from_bytes(b.data[..16].try_into().unwrap());
+        assert_eq!(d.color_model, DFD_MODEL_RGBSDA);
+        assert_eq!(d.transfer_function, DFD_TRANSFER_LINEAR);
+        assert_eq!(d.bytes_plane[0], 4);
+
+        let s = DfdSample::from_bytes(b.data[64..80].try_into().unwrap());
+        assert_eq!(int::ForeignEndian::to_native(s.bit_offset), 24);
+        assert_eq!(s.bit_length + 1, 8);
+        assert_eq!(s.channel_type & DFD_CHANNEL_MASK, DFD_CHANNEL_ALPHA);
+        assert_eq!(int::ForeignEndian::to_native(s.sample_upper), 255);
+
+        // Blocks that claim to be smaller than their header are rejected.
+        let mut data = DFD;
+        data[10] = 0x04;
+        assert!(DfdBlocks::new(&data[4..]).next().is_none());
+    }
+}
@@ -0,0 +1,421 @@
+//! LZ4 Frame Format
+//!
+//! LZ4 is a fast LZ77-style compression algorithm. The raw compressed blocks
+//! carry no metadata, hence the LZ4 frame format defines a container that
+//! wraps a sequence of blocks and adds sizes, flags, and optional checksums.
+//! This module covers the frame format only, not the block compression.
+//!
+//! A frame starts with a 4-byte magic number, followed by a frame descriptor
+//! of variable size. The descriptor starts with the `FLG` and `BD` bytes,
+//! which describe which optional fields follow and how blocks are encoded.
+//! The descriptor is terminated by a header checksum. What follows is a
+//! sequence of data blocks, each prefixed by its 4-byte size. A block size of
+//! 0 marks the end of the frame, optionally followed by a checksum of the
+//! entire uncompressed content.
+//!
+//! Skippable frames share the same magic number space and allow embedding
+//! user-defined data in a stream of LZ4 frames. Decoders skip them entirely.
+//!
+//! All checksums are based on the 32-bit xxHash function with a seed of 0.
+//! All multi-byte integers are encoded as little-endian.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::int;
+
+/// Frame Magic
+///
+/// The magic number at the start of every LZ4 frame.
+pub const MAGIC: u32 = 0x184d_2204;
+
+/// Legacy Frame Magic
+///
+/// The magic number of the deprecated legacy frame format, which consists of
+/// blocks of 8MiB without any descriptor or checksums.
+pub const MAGIC_LEGACY: u32 = 0x184c_2102;
+
+/// Skippable Frame Magic Range
+///
+/// Skippable frames use any magic number in the range `0x184d2a50` up to
+/// `0x184d2a5f`. The lower 4 bits are free for application use.
+pub const MAGIC_SKIPPABLE_MIN: u32 = 0x184d_2a50;
+pub const MAGIC_SKIPPABLE_MAX: u32 = 0x184d_2a5f;
+pub const MAGIC_SKIPPABLE_MASK: u32 = 0xffff_fff0;
+
+/// Frame Format Version
+///
+/// The only defined version number of the frame format, stored in the upper
+/// 2 bits of `FLG`.
+pub const VERSION: u8 = 0x01;
+
+pub const FLG_VERSION_MASK: u8 = 0xc0;
+pub const FLG_VERSION_SHIFT: u32 = 6;
+pub const FLG_BLOCK_INDEPENDENCE: u8 = 0x20;
+pub const FLG_BLOCK_CHECKSUM: u8 = 0x10;
+pub const FLG_CONTENT_SIZE: u8 = 0x08;
+pub const FLG_CONTENT_CHECKSUM: u8 = 0x04;
+/* reserved: 0x02 */
+pub const FLG_DICT_ID: u8 = 0x01;
+pub const FLG_RESERVED_MASK: u8 = 0x02;
+
+/* reserved: 0x80 */
+pub const BD_BLOCK_MAX_SIZE_MASK: u8 = 0x70;
+pub const BD_BLOCK_MAX_SIZE_SHIFT: u32 = 4;
+/* reserved: 0x08, 0x04, 0x02, 0x01 */
+pub const BD_RESERVED_MASK: u8 = 0x8f;
+
+pub const BLOCK_MAX_SIZE_64KIB: u8 = 4;
+pub const BLOCK_MAX_SIZE_256KIB: u8 = 5;
+pub const BLOCK_MAX_SIZE_1MIB: u8 = 6;
+pub const BLOCK_MAX_SIZE_4MIB: u8 = 7;
+
+/// Uncompressed Block Flag
+///
+/// If the highest bit of a block size is set, the block data is stored
+/// uncompressed and must be copied verbatim.
+pub const BLOCK_UNCOMPRESSED: u32 = 0x8000_0000;
+pub const BLOCK_SIZE_MASK: u32 = 0x7fff_ffff;
+
+/// End Mark
+///
+/// A block size of 0 terminates the block sequence of a frame.
+pub const END_MARK: u32 = 0x0000_0000;
+
+/// Size of Checksums
+///
+/// Block checksums, header checksums (before truncation), and content
+/// checksums are all 32-bit xxHash values.
+pub const CHECKSUM_SIZE: usize = 4;
+
+/// Skippable Frame Header
+///
+/// The 8-byte header of a skippable frame. It is followed by `size` bytes of
+/// user data.
+#[repr(C)]
+pub struct SkippableHeader {
+    /// The magic number in the range `MAGIC_SKIPPABLE_MIN` to
+    /// `MAGIC_SKIPPABLE_MAX`.
+    pub magic: int::u32le,
+
+    /// The size of the user data following this header.
+    pub size: int::u32le,
+}
+
+/// Frame Descriptor
+///
+/// The decoded representation of a frame descriptor. Since the descriptor is
+/// of variable size, it cannot be mapped as a structure directly. Instead,
+/// `Descriptor::parse()` decodes it into this representation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Descriptor {
+    /// The `FLG` byte.
+    pub flg: u8,
+
+    /// The `BD` byte.
+    pub bd: u8,
+
+    /// The uncompressed size of the content, if present.
+    pub content_size: Option<u64>,
+
+    /// The dictionary ID, if present.
+    pub dict_id: Option<u32>,
+
+    /// The header checksum, which is the second byte of the xxHash of the
+    /// descriptor (excluding the magic and the checksum itself).
+    pub hc: u8,
+}
+
+/// Calculate 32-bit xxHash
+///
+/// Calculate the 32-bit variant of the xxHash algorithm over the given data
+/// with the given seed. LZ4 always uses a seed of 0.
+pub fn xxh32(data: &[u8], seed: u32) -> u32 {
+    const P1: u32 = 0x9e37_79b1;
+    const P2: u32 = 0x85eb_ca77;
+    const P3: u32 = 0xc2b2_ae3d;
+    const P4: u32 = 0x27d4_eb2f;
+    const P5: u32 = 0x1656_67b1;
+
+    fn round(acc: u32, v: u32) -> u32 {
+        acc.wrapping_add(v.wrapping_mul(P2)).rotate_left(13).wrapping_mul(P1)
+    }
+
+    fn word(b: &[u8]) -> u32 {
+        u32::from_le_bytes([b[0], b[1], b[2], b[3]])
+    }
+
+    let mut rest = data;
+    let mut h: u32;
+
+    if data.len() >= 16 {
+        let mut v = [
+            seed.wrapping_add(P1).wrapping_add(P2),
+            seed.wrapping_add(P2),
+            seed,
+            seed.wrapping_sub(P1),
+        ];
+
+        while rest.len() >= 16 {
+            for (i, acc) in v.iter_mut().enumerate() {
+                *acc = round(*acc, word(&rest[i * 4..]));
+            }
+            rest = &rest[16..];
+        }
+
+        h = v[0].rotate_left(1)
+            .wrapping_add(v[1].rotate_left(7))
+            .wrapping_add(v[2].rotate_left(12))
+            .wrapping_add(v[3].rotate_left(18));
+    } else {
+        h = seed.wrapping_add(P5);
+    }
+
+    h = h.wrapping_add(data.len() as u32);
+
+    while rest.len() >= 4 {
+        h = h.wrapping_add(word(rest).wrapping_mul(P3)).rotate_left(17).wrapping_mul(P4);
+        rest = &rest[4..];
+    }
+
+    for b in rest {
+        h = h.wrapping_add((*b as u32).wrapping_mul(P5)).rotate_left(11).wrapping_mul(P1);
+    }
+
+    h ^= h >> 15;
+    h = h.wrapping_mul(P2);
+    h ^= h >> 13;
+    h = h.wrapping_mul(P3);
+    h ^= h >> 16;
+    h
+}
+
+/// Calculate Header Checksum
+///
+/// Calculate the header checksum of a frame descriptor. `data` must contain
+/// the descriptor starting at `FLG`, up to but excluding the header checksum
+/// byte.
+pub fn header_checksum(data: &[u8]) -> u8 {
+    (xxh32(data, 0) >> 8) as u8
+}
+
+/// Check for Skippable Frames
+///
+/// Return whether the given frame magic denotes a skippable frame.
+pub fn is_skippable(magic: u32) -> bool {
+    magic & MAGIC_SKIPPABLE_MASK == MAGIC_SKIPPABLE_MIN
+}
+
+/// Calculate Maximum Block Size
+///
+/// Return the maximum block size in bytes for a block maximum size code as
+/// stored in `BD`, or `None` if the code is invalid.
+pub fn block_max_size(code: u8) -> Option<usize> {
+    match code {
+        BLOCK_MAX_SIZE_64KIB..=BLOCK_MAX_SIZE_4MIB => Some(1 << (8 + 2 * code as usize)),
+        _ => None,
+    }
+}
+
+/// Decode Block Size
+///
+/// Split a raw block size field into the size of the block data, and whether
+/// the block is stored uncompressed.
+pub fn block_size(raw: u32) -> (usize, bool) {
+    ((raw & BLOCK_SIZE_MASK) as usize, raw & BLOCK_UNCOMPRESSED != 0)
+}
+
+impl SkippableHeader {
+    /// Import a skippable frame header from a byte slice
+    ///
+    /// Create a new header structure from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 8]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 8], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl Descriptor {
+    /// Return format version
+    ///
+    /// Return the version as encoded in `FLG`. This must match `VERSION`.
+    pub fn version(&self) -> u8 {
+        (self.flg & FLG_VERSION_MASK) >> FLG_VERSION_SHIFT
+    }
+
+    /// Return maximum block size
+    ///
+    /// Return the maximum size of uncompressed blocks as encoded in `BD`, or
+    /// `None` if invalid.
+    pub fn block_max_size(&self) -> Option<usize> {
+        block_max_size((self.bd & BD_BLOCK_MAX_SIZE_MASK) >> BD_BLOCK_MAX_SIZE_SHIFT)
+    }
+
+    /// Return whether blocks carry checksums
+    pub fn has_block_checksum(&self) -> bool {
+        self.flg & FLG_BLOCK_CHECKSUM != 0
+    }
+
+    /// Return whether the frame carries a content checksum
+    pub fn has_content_checksum(&self) -> bool {
+        self.flg & FLG_CONTENT_CHECKSUM != 0
+    }
+
+    /// Calculate descriptor size
+    ///
+    /// Return the size of a frame descriptor with the given `FLG` byte,
+    /// including the header checksum, but excluding the magic number.
+    pub fn size_for(flg: u8) -> usize {
+        let mut n = 3;
+        if flg & FLG_CONTENT_SIZE != 0 {
+            n += 8;
+        }
+        if flg & FLG_DICT_ID != 0 {
+            n += 4;
+        }
+        n
+    }
+
+    /// Parse a frame descriptor
+    ///
+    /// Parse a frame descriptor from the start of `data`, which must point
+    /// directly after the frame magic. On success, the descriptor and its
+    /// size in bytes are returned. `None` is returned if the data is
+    /// truncated, the version is unknown, reserved bits are set, or the
+    /// header checksum does not match.
+    pub fn parse(data: &[u8]) -> Option<(Self, usize)> {
+        let flg = *data.first()?;
+        let size = Self::size_for(flg);
+
+        if data.len() < size {
+            return None;
+        }
+
+        let bd = data[1];
+        let mut off = 2;
+
+        let content_size = if flg & FLG_CONTENT_SIZE != 0 {
+            let v = u64::from_le_bytes(data[off..off + 8].try_into().unwrap());
+            off += 8;
+            Some(v)
+        } else {
+            None
+        };
+
+        let dict_id = if flg & FLG_DICT_ID != 0 {
+            let v = u32::from_le_bytes(data[off..off + 4].try_into().unwrap());
+            off += 4;
+            Some(v)
+        } else {
+            None
+        };
+
+        let d = Self {
+            flg,
+            bd,
+            content_size,
+            dict_id,
+            hc: data[off],
+        };
+
+        if d.version() != VERSION
+            || flg & FLG_RESERVED_MASK != 0
+            || bd & BD_RESERVED_MASK != 0
+            || d.hc != header_checksum(&data[..off])
+        {
+            return None;
+        }
+
+        Some((d, size))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::specs::int::ForeignEndian;
+
+    // An empty frame with 64KiB blocks, independent blocks, and a content
+    // checksum.
+    const EMPTY: [u8; 15] = [
+        0x04, 0x22, 0x4d, 0x18, 0x64, 0x40, 0xa7, 0x00,
+        0x00, 0x00, 0x00, 0x05, 0x5d, 0xcc, 0x02,
+    ];
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<SkippableHeader>(), 8);
+        assert_eq!(align_of::<SkippableHeader>(), 4);
+    }
+
+    // Verify xxHash against known reference values.
+    #[test]
+    fn verify_xxh32() {
+        assert_eq!(xxh32(b"", 0), 0x02cc5d05);
+        assert_eq!(xxh32(b"abc", 0), 0x32d153ff);
+        assert_eq!(
+            xxh32(b"Nobody inspects the spammish repetition", 0),
+            0xe2293b2f,
+        );
+    }
+
+    // Decode an empty frame and verify all its fields.
+    #[test]
+    fn verify_frame() {
+        assert_eq!(u32::from_le_bytes(EMPTY[..4].try_into().unwrap()), MAGIC);
+
+        let (d, n) = Descriptor::parse(&EMPTY[4..]).unwrap();
+        assert_eq!(n, 3);
+        assert_eq!(d.version(), VERSION);
+        assert_eq!(d.block_max_size(), Some(64 * 1024));
+        assert!(d.flg & FLG_BLOCK_INDEPENDENCE != 0);
+        assert!(!d.has_block_checksum());
+        assert!(d.has_content_checksum());
+        assert_eq!(d.content_size, None);
+        assert_eq!(d.dict_id, None);
+
+        let end = u32::from_le_bytes(EMPTY[7..11].try_into().unwrap());
+        assert_eq!(end, END_MARK);
+
+        let csum = u32::from_le_bytes(EMPTY[11..15].try_into().unwrap());
+        assert_eq!(csum, xxh32(b"", 0));
+
+        // A corrupted header checksum must be rejected.
+        let mut bad = EMPTY;
+        bad[6] ^= 0x01;
+        assert_eq!(Descriptor::parse(&bad[4..]), None);
+        assert_eq!(Descriptor::parse(&EMPTY[4..6]), None);
+    }
+
+    // Verify the block and skippable-frame helpers.
+    #[test]
+    fn verify_helpers() {
+        assert_eq!(block_size(0x8000_0010), (16, true));
+        assert_eq!(block_size(0x0000_0010), (16, false));
+
+        assert_eq!(block_max_size(BLOCK_MAX_SIZE_4MIB), Some(4 * 1024 * 1024));
+        assert_eq!(block_max_size(3), None);
+
+        assert!(is_skippable(0x184d2a50));
+        assert!(is_skippable(0x184d2a5f));
+        assert!(!is_skippable(MAGIC));
+
+        let s = SkippableHeader::from_bytes(
+            &[0x5a, 0x2a, 0x4d, 0x18, 0x04, 0x00, 0x00, 0x00],
+        );
+        assert!(is_skippable(s.magic.to_native()));
+        assert_eq!(s.size.to_native(), 4);
+    }
+}
@@ -0,0 +1,233 @@
+//! LZMA Properties and Legacy Header
+//!
+//! LZMA (Lempel-Ziv-Markov chain Algorithm) is the compression algorithm
+//! developed for the 7z format. Its raw streams are embedded in many
+//! containers, each with their own way of describing the decoder parameters.
+//! This module covers the parameter encoding shared by all of them, as well
+//! as the 13-byte header of the legacy `.lzma` format ("LZMA_alone").
+//!
+//! The LZMA decoder is parameterized by three values, packed into a single
+//! properties byte:
+//!
+//!  * `lc`: The number of high bits of the previous byte used as literal
+//!    context (0 to 8).
+//!  * `lp`: The number of low bits of the position used as literal position
+//!    state (0 to 4).
+//!  * `pb`: The number of low bits of the position used as position state
+//!    (0 to 4).
+//!
+//! The properties byte is calculated as `(pb * 5 + lp) * 9 + lc`. Hence, any
+//! value below 225 is a valid properties byte. The most common value is
+//! `0x5d`, which is the default of `lc=3`, `lp=0`, `pb=2`.
+//!
+//! The legacy header is the properties byte, followed by the dictionary size
+//! and the uncompressed size of the stream. The uncompressed size can be
+//! unknown, in which case the stream must be terminated by an end marker.
+//! Since the header carries no signature, detection is heuristic. Firmware
+//! containers commonly embed streams prefixed with this header.
+//!
+//! All multi-byte integers are encoded as little-endian.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::int;
+
+/// Size of the Legacy Header
+pub const HEADER_SIZE: usize = 13;
+
+pub const LC_MAX: u8 = 8;
+pub const LP_MAX: u8 = 4;
+pub const PB_MAX: u8 = 4;
+
+/// Maximum Properties Byte
+///
+/// The highest valid properties byte, for `lc=8`, `lp=4`, `pb=4`.
+pub const PROPERTIES_MAX: u8 = 224;
+
+/// Default Properties Byte
+pub const PROPERTIES_DEFAULT: u8 = 0x5d;
+
+/// Minimum Dictionary Size
+///
+/// Decoders treat any dictionary size below this as this size.
+pub const DICT_SIZE_MIN: u32 = 4096;
+
+/// Unknown Uncompressed Size
+///
+/// Marks the uncompressed size of a legacy header as unknown. The stream
+/// must then be terminated by an end marker.
+pub const SIZE_UNKNOWN: u64 = 0xffff_ffff_ffff_ffff;
+
+/// Legacy Header
+///
+/// The 13-byte header of the legacy `.lzma` format. Since the dictionary
+/// size is not naturally aligned, this structure is packed.
+#[repr(C, packed)]
+pub struct Header {
+    /// The properties byte, see `Properties`.
+    pub properties: u8,
+
+    /// Dictionary size in bytes.
+    pub dict_size: int::u32le,
+
+    /// Uncompressed size in bytes, or `SIZE_UNKNOWN`.
+    pub uncompressed_size: int::u64le,
+}
+
+/// Decoder Properties
+///
+/// The decoded representation of an LZMA properties byte.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Properties {
+    /// Number of literal context bits.
+    pub lc: u8,
+
+    /// Number of literal position bits.
+    pub lp: u8,
+
+    /// Number of position bits.
+    pub pb: u8,
+}
+
+impl Properties {
+    /// Decode properties byte
+    ///
+    /// Split a properties byte into its components. `None` is returned if the
+    /// byte exceeds `PROPERTIES_MAX`.
+    pub fn from_byte(v: u8) -> Option<Self> {
+        if v > PROPERTIES_MAX {
+            return None;
+        }
+
+        Some(Self {
+            lc: v % 9,
+            lp: (v / 9) % 5,
+            pb: v / 45,
+        })
+    }
+
+    /// Encode properties byte
+    ///
+    /// Pack the properties into a properties byte. `None` is returned if any
+    /// component is out of range.
+    pub fn to_byte(&self) -> Option<u8> {
+        if self.lc > LC_MAX || self.lp > LP_MAX || self.pb > PB_MAX {
+            return None;
+        }
+
+        Some((self.pb * 5 + self.lp) * 9 + self.lc)
+    }
+}
+
+impl Default for Properties {
+    fn default() -> Self {
+        Self {
+            lc: 3,
+            lp: 0,
+            pb: 2,
+        }
+    }
+}
+
+impl Header {
+    /// Import a header from a byte slice
+    ///
+    /// Create a new header structure from a byte slice, copying the data over.
+    /// The data is copied verbatim without any conversion.
+    pub fn from_bytes(data: &[u8; 13]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 13], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Convert to byte slice
+    ///
+    /// Return a byte-slice reference to the header. No byte-order conversions
+    /// are applied.
+    pub fn as_bytes(&self) -> &[u8; 13] {
+        assert!(align_of::<[u8; 13]>() <= align_of::<Self>());
+        assert!(size_of::<[u8; 13]>() == size_of::<Self>());
+
+        unsafe {
+            core::mem::transmute::<&Self, &[u8; 13]>(self)
+        }
+    }
+
+    /// Return uncompressed size
+    ///
+    /// Return the uncompressed size of the stream, or `None` if it is
+    /// unknown.
+    pub fn size(&self) -> Option<u64> {
+        match int::ForeignEndian::to_native(self.uncompressed_size) {
+            SIZE_UNKNOWN => None,
+            v => Some(v),
+        }
+    }
+
+    /// Return effective dictionary size
+    ///
+    /// Return the dictionary size a decoder has to use, which is the stored
+    /// size clamped to `DICT_SIZE_MIN`.
+    pub fn effective_dict_size(&self) -> u32 {
+        core::cmp::max(int::ForeignEndian::to_native(self.dict_size), DICT_SIZE_MIN)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The string "hello" compressed with `lzma` at its default level.
+    const HELLO: [u8; 28] = [
+        0x5d, 0x00, 0x00, 0x80, 0x00, 0xff, 0xff, 0xff,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0x00, 0x34, 0x19,
+        0x49, 0xee, 0x8e, 0x68, 0x21, 0xff, 0xff, 0xff,
+        0xb9, 0xe0, 0x00, 0x00,
+    ];
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<Header>(), HEADER_SIZE);
+        assert_eq!(align_of::<Header>(), 1);
+    }
+
+    // Decode the header of a stream produced by the reference tools.
+    #[test]
+    fn verify_header() {
+        let h = Header::from_bytes((&HELLO[..13]).try_into().unwrap());
+
+        assert_eq!(Properties::from_byte(h.properties), Some(Properties::default()));
+        assert_eq!(int::ForeignEndian::to_native(h.dict_size), 8 * 1024 * 1024);
+        assert_eq!(h.effective_dict_size(), 8 * 1024 * 1024);
+        assert_eq!(h.size(), None);
+        assert_eq!(h.as_bytes(), &HELLO[..13]);
+    }
+
+    // Verify all properties bytes survive a round-trip.
+    #[test]
+    fn verify_properties() {
+        assert_eq!(Properties::default().to_byte(), Some(PROPERTIES_DEFAULT));
+
+        for v in 0..=PROPERTIES_MAX {
+            assert_eq!(Properties::from_byte(v).unwrap().to_byte(), Some(v));
+        }
+
+        assert_eq!(Properties::from_byte(PROPERTIES_MAX + 1), None);
+        assert_eq!(Properties { lc: 9, lp: 0, pb: 0 }.to_byte(), None);
+        assert_eq!(
+            Properties::from_byte(PROPERTIES_MAX),
+            Some(Properties { lc: 8, lp: 4, pb: 4 }),
+        );
+    }
+}
@@ -0,0 +1,893 @@
+//! Microcode Updates
+//!
+//! Processors of Intel and AMD can be patched at runtime by loading a
+//! microcode update. Intel updates start with an `IntelHeader`, followed
+//! by the encrypted update data, and an optional extended signature table
+//! (`IntelExtendedHeader`) listing additional processors the update applies
+//! to (`IntelExtendedSignature`). Update files simply concatenate updates
+//! (see `IntelUpdates`). All 32-bit words of an update sum up to 0, and so
+//! do all words of the extended signature table. Every extended signature
+//! carries the checksum the update would have with this signature in the
+//! header (see `IntelUpdate::checksum_valid()`).
+//!
+//! AMD updates are distributed as containers, starting with `AMD_MAGIC`,
+//! followed by sections with an `AmdSectionHeader` each (see
+//! `AmdContainer`). The first section is the equivalence table
+//! (`AmdEquivalenceEntry`), mapping processor signatures to equivalence
+//! IDs. All further sections are patches, starting with an
+//! `AmdPatchHeader` that names the equivalence ID it applies to. Older
+//! patches carry a 32-bit sum of the patch data, while newer patches are
+//! verified by the processor itself.
+//!
+//! All multi-byte integers are encoded as little-endian. The structures are
+//! naturally aligned.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::int;
+
+pub const INTEL_HEADER_VERSION: u32 = 1;
+pub const INTEL_HEADER_SIZE: usize = 48;
+pub const INTEL_EXTENDED_HEADER_SIZE: usize = 20;
+pub const INTEL_EXTENDED_SIGNATURE_SIZE: usize = 12;
+pub const INTEL_DEFAULT_DATA_SIZE: usize = 2000;
+pub const INTEL_DEFAULT_TOTAL_SIZE: usize = 2048;
+
+/// Intel Platform ID Register
+///
+/// The model-specific register reporting the platform ID of the processor
+/// in `INTEL_PLATFORM_ID_MASK << INTEL_PLATFORM_ID_SHIFT`. An update applies
+/// to a platform if bit `1 << platform_id` is set in its processor flags.
+pub const INTEL_MSR_PLATFORM_ID: u32 = 0x17;
+pub const INTEL_PLATFORM_ID_SHIFT: u32 = 50;
+pub const INTEL_PLATFORM_ID_MASK: u64 = 0x7;
+
+/// AMD Container Magic
+///
+/// The static magic at the start of every AMD container, "DMA\0" read as
+/// little-endian 32-bit integer.
+pub const AMD_MAGIC: u32 = 0x00414d44;
+
+pub const AMD_SECTION_EQUIVALENCE: u32 = 0x00000000;
+pub const AMD_SECTION_PATCH: u32 = 0x00000001;
+
+/// Intel Update Header
+///
+/// The header of an Intel microcode update, followed by the update data and
+/// an optional extended signature table.
+#[repr(C)]
+pub struct IntelHeader {
+    /// Version of the header format (`INTEL_HEADER_VERSION`).
+    pub header_version: int::u32le,
+
+    /// Revision of the update.
+    pub update_revision: int::u32le,
+
+    /// Release date as BCD, formatted as `0xMMDDYYYY`.
+    pub date: int::u32le,
+
+    /// Processor signature as reported by CPUID leaf 1 in EAX.
+    pub processor_signature: int::u32le,
+
+    /// Checksum, chosen such that all 32-bit words of the update sum up to
+    /// 0.
+    pub checksum: int::u32le,
+
+    /// Revision of the loader required to apply the update.
+    pub loader_revision: int::u32le,
+
+    /// Bit mask of the supported platform IDs.
+    pub processor_flags: int::u32le,
+
+    /// Size of the update data in bytes, or 0 for
+    /// `INTEL_DEFAULT_DATA_SIZE`.
+    pub data_size: int::u32le,
+
+    /// Size of the update in bytes, including the header and the extended
+    /// signature table, or 0 for `INTEL_DEFAULT_TOTAL_SIZE`.
+    pub total_size: int::u32le,
+
+    /// Reserved, must be 0.
+    pub reserved: [u8; 12],
+}
+
+/// Intel Extended Signature Table Header
+///
+/// The header of the extended signature table, placed after the update
+/// data, and followed by `signature_count` extended signatures.
+#[repr(C)]
+pub struct IntelExtendedHeader {
+    /// Number of extended signatures.
+    pub signature_count: int::u32le,
+
+    /// Checksum, chosen such that all 32-bit words of the extended signature
+    /// table sum up to 0.
+    pub checksum: int::u32le,
+
+    /// Reserved, must be 0.
+    pub reserved: [u8; 12],
+}
+
+/// Intel Extended Signature
+///
+/// An additional processor the update applies to.
+#[repr(C)]
+pub struct IntelExtendedSignature {
+    /// Processor signature as reported by CPUID leaf 1 in EAX.
+    pub processor_signature: int::u32le,
+
+    /// Bit mask of the supported platform IDs.
+    pub processor_flags: int::u32le,
+
+    /// Checksum the update would have with this signature and these flags in
+    /// the header.
+    pub checksum: int::u32le,
+}
+
+/// AMD Section Header
+///
+/// The header of every section of a container, followed by the section
+/// data.
+#[repr(C)]
+pub struct AmdSectionHeader {
+    /// Type of the section (`AMD_SECTION_*`).
+    pub section_type: int::u32le,
+
+    /// Size of the section data in bytes.
+    pub size: int::u32le,
+}
+
+/// AMD Equivalence Table Entry
+///
+/// Maps a processor signature to the equivalence ID used by patches. The
+/// table is terminated by an entry with an `installed_cpu` of 0.
+#[repr(C)]
+pub struct AmdEquivalenceEntry {
+    /// Processor signature as reported by CPUID leaf 1 in EAX.
+    pub installed_cpu: int::u32le,
+
+    /// Mask of fixed errata.
+    pub fixed_errata_mask: int::u32le,
+
+    /// Comparison value of fixed errata.
+    pub fixed_errata_compare: int::u32le,
+
+    /// Equivalence ID matched against `AmdPatchHeader::processor_rev_id`.
+    pub equivalence_id: int::u16le,
+
+    /// Reserved, must be 0.
+    pub reserved: int::u16le,
+}
+
+/// AMD Patch Header
+///
+/// The header of a patch section, followed by the patch data.
+#[repr(C)]
+pub struct AmdPatchHeader {
+    /// Release date as BCD, formatted as `0xMMDDYYYY`.
+    pub data_code: int::u32le,
+
+    /// Revision of the patch.
+    pub patch_id: int::u32le,
+
+    /// Format of the patch data.
+    pub mc_patch_data_id: int::u16le,
+
+    /// Length of the patch data (legacy).
+    pub mc_patch_data_len: u8,
+
+    /// Initialization flag.
+    pub init_flag: u8,
+
+    /// Checksum of the patch data, or 0 if the patch is verified by the
+    /// processor (see `amd_checksum()`).
+    pub mc_patch_data_checksum: int::u32le,
+
+    /// PCI ID of the northbridge, or 0 for any.
+    pub nb_dev_id: int::u32le,
+
+    /// PCI ID of the southbridge, or 0 for any.
+    pub sb_dev_id: int::u32le,
+
+    /// Equivalence ID of the supported processors.
+    pub processor_rev_id: int::u16le,
+
+    /// Revision of the northbridge.
+    pub nb_rev_id: u8,
+
+    /// Revision of the southbridge.
+    pub sb_rev_id: u8,
+
+    /// Revision of the BIOS API.
+    pub bios_api_rev: u8,
+
+    /// Reserved, must be 0.
+    pub reserved: [u8; 3],
+
+    /// Match registers.
+    pub match_reg: [int::u32le; 8],
+}
+
+/// Intel Update Reference
+///
+/// A reference to a single Intel microcode update.
+#[derive(Clone, Copy, Debug)]
+pub struct IntelUpdate<'data> {
+    /// Revision of the update.
+    pub revision: u32,
+
+    /// Release date as BCD, formatted as `0xMMDDYYYY`.
+    pub date: u32,
+
+    /// Processor signature of the header.
+    pub processor_signature: u32,
+
+    /// Processor flags of the header.
+    pub processor_flags: u32,
+
+    /// Checksum of the header.
+    pub checksum: u32,
+
+    /// Update data.
+    pub data: &'data [u8],
+
+    /// Extended signature table, or empty if none.
+    pub extended: &'data [u8],
+
+    /// Entire update, including the header.
+    pub update: &'data [u8],
+}
+
+/// Intel Update Iterator
+///
+/// An iterator over the concatenated Intel microcode updates of an update
+/// file, yielding an `IntelUpdate` for every update. Iteration stops at the
+/// first invalid update.
+#[derive(Clone, Debug)]
+pub struct IntelUpdates<'data> {
+    data: &'data [u8],
+}
+
+/// Intel Extended Signature Iterator
+///
+/// An iterator over the extended signatures of an extended signature table,
+/// yielding an `IntelExtendedSignature` for every entry.
+#[derive(Clone, Debug)]
+pub struct IntelExtendedSignatures<'data> {
+    data: &'data [u8],
+}
+
+/// AMD Container Reference
+///
+/// A reference to an AMD microcode container, split into its equivalence
+/// table and its patch sections.
+#[derive(Clone, Copy, Debug)]
+pub struct AmdContainer<'data> {
+    /// Data of the equivalence table section.
+    pub equivalence: &'data [u8],
+
+    /// Sections following the equivalence table (see `AmdSections`).
+    pub sections: &'data [u8],
+}
+
+/// AMD Equivalence Table Iterator
+///
+/// An iterator over the entries of an equivalence table, yielding an
+/// `AmdEquivalenceEntry` for every entry up to the terminating entry.
+#[derive(Clone, Debug)]
+pub struct AmdEquivalences<'data> {
+    data: &'data [u8],
+}
+
+/// AMD Section Reference
+///
+/// A reference to a section of an AMD container.
+#[derive(Clone, Copy, Debug)]
+pub struct AmdSection<'data> {
+    /// Type of the section (`AMD_SECTION_*`).
+    pub section_type: u32,
+
+    /// Section data.
+    pub data: &'data [u8],
+}
+
+/// AMD Section Iterator
+///
+/// An iterator over the sections of an AMD container, yielding an
+/// `AmdSection` for every section. Iteration stops at the first truncated
+/// section.
+#[derive(Clone, Debug)]
+pub struct AmdSections<'data> {
+    data: &'data [u8],
+}
+
+/// AMD Patch Reference
+///
+/// A reference to a patch of an AMD container.
+#[derive(Clone, Copy, Debug)]
+pub struct AmdPatch<'data> {
+    /// Revision of the patch.
+    pub patch_id: u32,
+
+    /// Release date as BCD, formatted as `0xMMDDYYYY`.
+    pub date: u32,
+
+    /// Equivalence ID of the supported processors.
+    pub equivalence_id: u16,
+
+    /// Checksum of the patch data, or 0 if none.
+    pub checksum: u32,
+
+    /// Patch data following the header.
+    pub data: &'data [u8],
+}
+
+impl IntelHeader {
+    /// Import an Intel update header from a byte slice
+    ///
+    /// Create a new Intel update header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 48]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 48], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl IntelExtendedHeader {
+    /// Import an extended signature table header from a byte slice
+    ///
+    /// Create a new extended signature table header from data copied from a byte
+    /// slice. No byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 20]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 20], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl IntelExtendedSignature {
+    /// Import an extended signature from a byte slice
+    ///
+    /// Create a new extended signature from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 12]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 12], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl AmdSectionHeader {
+    /// Import a section header from a byte slice
+    ///
+    /// Create a new section header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 8]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 8], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl AmdEquivalenceEntry {
+    /// Import an equivalence entry from a byte slice
+    ///
+    /// Create a new equivalence entry from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 16]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 16], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl AmdPatchHeader {
+    /// Import a patch header from a byte slice
+    ///
+    /// Create a new patch header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 64]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 64], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl<'data> IntelUpdate<'data> {
+    /// Parse an Intel update
+    ///
+    /// Parse the Intel microcode update at the start of `data`. Trailing
+    /// data beyond the total size is ignored. `None` is returned for unknown
+    /// header versions, invalid sizes, or if the update is truncated.
+    pub fn parse(data: &'data [u8]) -> Option<Self> {
+        let header = IntelHeader::from_bytes(data.get(..INTEL_HEADER_SIZE)?.try_into().ok()?);
+        if int::ForeignEndian::to_native(header.header_version) != INTEL_HEADER_VERSION {
+            return None;
+        }
+
+        let data_size = match int::ForeignEndian::to_native(header.data_size) as usize {
+            0 => INTEL_DEFAULT_DATA_SIZE,
+            v => v,
+        };
+        let total_size = match int::ForeignEndian::to_native(header.total_size) as usize {
+            0 => INTEL_DEFAULT_TOTAL_SIZE,
+            v => v,
+        };
+        if data_size % 4 != 0 || total_size % 4 != 0 {
+            return None;
+        }
+
+        let end = INTEL_HEADER_SIZE.checked_add(data_size)?;
+        let update = data.get(..total_size)?;
+        let extended = update.get(end..)?;
+        if !extended.is_empty() {
+            let ext = IntelExtendedHeader::from_bytes(
+                extended.get(..INTEL_EXTENDED_HEADER_SIZE)?.try_into().ok()?,
+            );
+            let n = int::ForeignEndian::to_native(ext.signature_count) as usize;
+            let size = n
+                .checked_mul(INTEL_EXTENDED_SIGNATURE_SIZE)?
+                .checked_add(INTEL_EXTENDED_HEADER_SIZE)?;
+            if size > extended.len() {
+                return None;
+            }
+        }
+
+        Some(Self {
+            revision: int::ForeignEndian::to_native(header.update_revision),
+            date: int::ForeignEndian::to_native(header.date),
+            processor_signature: int::ForeignEndian::to_native(header.processor_signature),
+            processor_flags: int::ForeignEndian::to_native(header.processor_flags),
+            checksum: int::ForeignEndian::to_native(header.checksum),
+            data: &update[INTEL_HEADER_SIZE..end],
+            extended,
+            update,
+        })
+    }
+
+    /// Return the extended signatures
+    ///
+    /// Return an iterator over the extended signatures of the update, which
+    /// is empty if the update has no extended signature table.
+    pub fn extended_signatures(&self) -> IntelExtendedSignatures<'data> {
+        let n = self.extended.get(..4).map_or(0, |v| {
+            u32::from_le_bytes(v.try_into().unwrap()) as usize
+        });
+        let end = INTEL_EXTENDED_HEADER_SIZE + n * INTEL_EXTENDED_SIGNATURE_SIZE;
+        let data = self.extended.get(INTEL_EXTENDED_HEADER_SIZE..end).unwrap_or(&[]);
+        IntelExtendedSignatures::new(data)
+    }
+
+    /// Verify the checksums
+    ///
+    /// Verify that all 32-bit words of the update sum up to 0, that all
+    /// words of the extended signature table sum up to 0, and that the
+    /// checksum of every extended signature matches the checksum of the
+    /// update with its signature and flags swapped in.
+    pub fn checksum_valid(&self) -> bool {
+        if checksum(self.update) != 0 || checksum(self.extended) != 0 {
+            return false;
+        }
+
+        let base = self
+            .checksum
+            .wrapping_add(self.processor_signature)
+            .wrapping_add(self.processor_flags);
+        self.extended_signatures().all(|v| {
+            int::ForeignEndian::to_native(v.checksum)
+                .wrapping_add(int::ForeignEndian::to_native(v.processor_signature))
+                .wrapping_add(int::ForeignEndian::to_native(v.processor_flags))
+                == base
+        })
+    }
+
+    /// Check whether the update applies to a processor
+    ///
+    /// Check whether the header or any extended signature matches the
+    /// processor signature (CPUID leaf 1 in EAX) and platform ID (see
+    /// `INTEL_MSR_PLATFORM_ID`) of a processor. Processor flags of 0 match
+    /// any platform.
+    pub fn matches(&self, signature: u32, platform_id: u8) -> bool {
+        let Some(bit) = 1u32.checked_shl(platform_id as u32) else {
+            return false;
+        };
+        let check = |s: u32, f: u32| s == signature && (f == 0 || (f & bit) != 0);
+
+        check(self.processor_signature, self.processor_flags)
+            || self.extended_signatures().any(|v| {
+                check(
+                    int::ForeignEndian::to_native(v.processor_signature),
+                    int::ForeignEndian::to_native(v.processor_flags),
+                )
+            })
+    }
+}
+
+impl<'data> IntelUpdates<'data> {
+    /// Create Intel update iterator
+    ///
+    /// Create a new iterator over the concatenated Intel microcode updates
+    /// in `data`.
+    pub fn new(data: &'data [u8]) -> Self {
+        Self { data }
+    }
+
+    /// Return remaining data
+    ///
+    /// Return the data that has not been consumed by the iterator.
+    pub fn remainder(&self) -> &'data [u8] {
+        self.data
+    }
+}
+
+impl<'data> Iterator for IntelUpdates<'data> {
+    type Item = IntelUpdate<'data>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let v = IntelUpdate::parse(self.data)?;
+        self.data = &self.data[v.update.len()..];
+        Some(v)
+    }
+}
+
+impl<'data> IntelExtendedSignatures<'data> {
+    /// Create extended signature iterator
+    ///
+    /// Create a new iterator over the extended signatures in `data`,
+    /// following the extended signature table header.
+    pub fn new(data: &'data [u8]) -> Self {
+        Self { data }
+    }
+}
+
+impl<'data> Iterator for IntelExtendedSignatures<'data> {
+    type Item = IntelExtendedSignature;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let v = self.data.get(..INTEL_EXTENDED_SIGNATURE_SIZE)?;
+        self.data = &self.data[INTEL_EXTENDED_SIGNATURE_SIZE..];
+        Some(IntelExtendedSignature::from_bytes(v.try_into().unwrap()))
+    }
+}
+
+impl<'data> AmdContainer<'data> {
+    /// Parse an AMD container
+    ///
+    /// Parse the AMD microcode container in `data`. `None` is returned if
+    /// the magic is invalid, the equivalence table is missing, or the data
+    /// is truncated.
+    pub fn parse(data: &'data [u8]) -> Option<Self> {
+        if u32::from_le_bytes(data.get(..4)?.try_into().ok()?) != AMD_MAGIC {
+            return None;
+        }
+
+        let mut sections = AmdSections::new(&data[4..]);
+        let equivalence = sections.next()?;
+        if equivalence.section_type != AMD_SECTION_EQUIVALENCE {
+            return None;
+        }
+
+        Some(Self {
+            equivalence: equivalence.data,
+            sections: sections.remainder(),
+        })
+    }
+
+    /// Return the equivalence table
+    pub fn equivalences(&self) -> AmdEquivalences<'data> {
+        AmdEquivalences::new(self.equivalence)
+    }
+
+    /// Return the patch sections
+    ///
+    /// Return an iterator over all sections following the equivalence
+    /// table. Sections of other types than `AMD_SECTION_PATCH` are yielded
+    /// as well.
+    pub fn patches(&self) -> AmdSections<'data> {
+        AmdSections::new(self.sections)
+    }
+
+    /// Look up an equivalence ID
+    ///
+    /// Return the equivalence ID of the processor with the given signature
+    /// (CPUID leaf 1 in EAX), or `None` if the container does not list it.
+    pub fn equivalence_id(&self, signature: u32) -> Option<u16> {
+        self.equivalences()
+            .find(|v| int::ForeignEndian::to_native(v.installed_cpu) == signature)
+            .map(|v| int::ForeignEndian::to_native(v.equivalence_id))
+    }
+}
+
+impl<'data> AmdEquivalences<'data> {
+    /// Create equivalence table iterator
+    ///
+    /// Create a new iterator over the entries of the equivalence table in
+    /// `data`.
+    pub fn new(data: &'data [u8]) -> Self {
+        Self { data }
+    }
+}
+
+impl<'data> Iterator for AmdEquivalences<'data> {
+    type Item = AmdEquivalenceEntry;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let n = size_of::<AmdEquivalenceEntry>();
+        let v = AmdEquivalenceEntry::from_bytes(self.data.get(..n)?.try_into().unwrap());
+        if int::ForeignEndian::to_native(v.installed_cpu) == 0 {
+            self.data = &[];
+            return None;
+        }
+
+        self.data = &self.data[n..];
+        Some(v)
+    }
+}
+
+impl<'data> AmdSections<'data> {
+    /// Create section iterator
+    ///
+    /// Create a new iterator over the sections in `data`, following the
+    /// container magic.
+    pub fn new(data: &'data [u8]) -> Self {
+        Self { data }
+    }
+
+    /// Return remaining data
+    ///
+    /// Return the data that has not been consumed by the iterator.
+    pub fn remainder(&self) -> &'data [u8] {
+        self.data
+    }
+}
+
+impl<'data> Iterator for AmdSections<'data> {
+    type Item = AmdSection<'data>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let n = size_of::<AmdSectionHeader>();
+        let header = AmdSectionHeader::from_bytes(self.data.get(..n)?.try_into().unwrap());
+        let size = int::ForeignEndian::to_native(header.size) as usize;
+        let data = self.data.get(n..n.checked_add(size)?)?;
+
+        self.data = &self.data[n + size..];
+        Some(AmdSection {
+            section_type: int::ForeignEndian::to_native(header.section_type),
+            data,
+        })
+    }
+}
+
+impl<'data> AmdPatch<'data> {
+    /// Parse an AMD patch
+    ///
+    /// Parse the data of a patch section. `None` is returned if the data is
+    /// too short to hold the patch header.
+    pub fn parse(data: &'data [u8]) -> Option<Self> {
+        let n = size_of::<AmdPatchHeader>();
+        let header = AmdPatchHeader::from_bytes(data.get(..n)?.try_into().ok()?);
+
+        Some(Self {
+            patch_id: int::ForeignEndian::to_native(header.patch_id),
+            date: int::ForeignEndian::to_native(header.data_code),
+            equivalence_id: int::ForeignEndian::to_native(header.processor_rev_id),
+            checksum: int::ForeignEndian::to_native(header.mc_patch_data_checksum),
+            data: &data[n..],
+        })
+    }
+
+    /// Verify the checksum
+    ///
+    /// Verify that the 32-bit sum of the patch data matches the checksum of
+    /// the header. Patches without checksum always pass.
+    pub fn checksum_valid(&self) -> bool {
+        self.checksum == 0 || checksum(self.data) == self.checksum
+    }
+}
+
+/// Calculate a microcode checksum
+///
+/// Calculate the wrapping sum of all little-endian 32-bit words of `data`,
+/// as used by both Intel updates and AMD patches. Trailing bytes that do
+/// not form a full word are ignored.
+pub fn checksum(data: &[u8]) -> u32 {
+    data.chunks_exact(4)
+        .map(|v| u32::from_le_bytes(v.try_into().unwrap()))
+        .fold(0, u32::wrapping_add)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::specs::int::ForeignEndian;
+
+    // Append a little-endian 32-bit value to `out`.
+    fn push_u32(out: &mut std::vec::Vec<u8>, v: u32) {
+        out.extend_from_slice(&v.to_le_bytes());
+    }
+
+    // Build an Intel update with 16 bytes of data, and an extended
+    // signature table with 2 signatures.
+    fn intel_update() -> std::vec::Vec<u8> {
+        let mut v = std::vec::Vec::new();
+        for w in [1, 0x2f, 0x10142023, 0x000906ea, 0, 1, 0x22, 16, 108, 0, 0, 0] {
+            push_u32(&mut v, w);
+        }
+        for w in [0x11111111, 0x22222222, 0x33333333, 0x44444444] {
+            push_u32(&mut v, w);
+        }
+        let sum = checksum(&v).wrapping_neg();
+        v[16..20].copy_from_slice(&sum.to_le_bytes());
+
+        let base = sum.wrapping_add(0x000906ea).wrapping_add(0x22);
+        let mut ext = std::vec::Vec::new();
+        for w in [2, 0, 0, 0, 0] {
+            push_u32(&mut ext, w);
+        }
+        for (s, f) in [(0x000906eb, 0x02), (0x000906ec, 0x20)] {
+            push_u32(&mut ext, s);
+            push_u32(&mut ext, f);
+            push_u32(&mut ext, base.wrapping_sub(s).wrapping_sub(f));
+        }
+        let sum = checksum(&ext).wrapping_neg();
+        ext[4..8].copy_from_slice(&sum.to_le_bytes());
+
+        v.extend_from_slice(&ext);
+        v
+    }
+
+    // Build an AMD container with one processor and one patch.
+    fn amd_container() -> std::vec::Vec<u8> {
+        let mut v = std::vec::Vec::new();
+        push_u32(&mut v, AMD_MAGIC);
+        push_u32(&mut v, AMD_SECTION_EQUIVALENCE);
+        push_u32(&mut v, 32);
+        for w in [0x00a20f12, 0, 0, 0x0000a212, 0, 0, 0, 0] {
+            push_u32(&mut v, w);
+        }
+
+        push_u32(&mut v, AMD_SECTION_PATCH);
+        push_u32(&mut v, 72);
+        let data = [0x01020304u32, 0x10203040];
+        let sum = data.iter().fold(0, |a: u32, v| a.wrapping_add(*v));
+        for w in [0x07262022, 0x0a201210, 0x8004, sum, 0, 0, 0xa212] {
+            push_u32(&mut v, w);
+        }
+        v.extend_from_slice(&[0; 36]);
+        for w in data {
+            push_u32(&mut v, w);
+        }
+        v
+    }
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<IntelHeader>(), INTEL_HEADER_SIZE);
+        assert_eq!(size_of::<IntelExtendedHeader>(), INTEL_EXTENDED_HEADER_SIZE);
+        assert_eq!(size_of::<IntelExtendedSignature>(), INTEL_EXTENDED_SIGNATURE_SIZE);
+        assert_eq!(size_of::<AmdSectionHeader>(), 8);
+        assert_eq!(size_of::<AmdEquivalenceEntry>(), 16);
+        assert_eq!(size_of::<AmdPatchHeader>(), 64);
+    }
+
+    // Parse an Intel update, verify its checksums, and match processors.
+    #[test]
+    fn verify_intel() {
+        let data = intel_update();
+        let h = IntelHeader::from_bytes(data[..INTEL_HEADER_SIZE].try_into().unwrap());
+        assert_eq!(h.total_size.to_native(), 108);
+
+        let u = IntelUpdate::parse(&data).unwrap();
+        assert_eq!((u.revision, u.date), (0x2f, 0x10142023));
+        assert_eq!((u.data.len(), u.extended.len(), u.update.len()), (16, 44, 108));
+        assert!(u.checksum_valid());
+
+        let s: std::vec::Vec<_> = u.extended_signatures()
+            .map(|v| (v.processor_signature.to_native(), v.processor_flags.to_native()))
+            .collect();
+        assert_eq!(s, [(0x000906eb, 0x02), (0x000906ec, 0x20)]);
+
+        assert!(u.matches(0x000906ea, 1) && u.matches(0x000906ea, 5));
+        assert!(!u.matches(0x000906ea, 2));
+        assert!(u.matches(0x000906eb, 1) && !u.matches(0x000906eb, 5));
+        assert!(u.matches(0x000906ec, 5) && !u.matches(0x000906ed, 5));
+
+        // Concatenated updates followed by padding.
+        let mut file = data.clone();
+        file.extend_from_slice(&data);
+        file.extend_from_slice(&[0; 4]);
+        let mut it = IntelUpdates::new(&file);
+        assert_eq!(it.by_ref().count(), 2);
+        assert_eq!(it.remainder(), &[0; 4]);
+
+        // Any modification breaks the checksums.
+        for offset in [8, 60, 88] {
+            let mut v = data.clone();
+            v[offset] ^= 0x01;
+            assert!(!IntelUpdate::parse(&v).unwrap().checksum_valid());
+        }
+
+        assert!(IntelUpdate::parse(&data[..107]).is_none());
+        let mut v = data.clone();
+        v[0] = 2;
+        assert!(IntelUpdate::parse(&v).is_none());
+        v[0] = 1;
+        v[64] = 3;
+        assert!(IntelUpdate::parse(&v).is_none());
+    }
+
+    // Parse an AMD container, look up the processor, and verify the patch.
+    #[test]
+    fn verify_amd() {
+        let data = amd_container();
+        let c = AmdContainer::parse(&data).unwrap();
+        assert_eq!(c.equivalences().count(), 1);
+        assert_eq!(c.equivalence_id(0x00a20f12), Some(0xa212));
+        assert_eq!(c.equivalence_id(0x00a20f10), None);
+
+        let mut p = c.patches();
+        let s = p.next().unwrap();
+        assert_eq!((s.section_type, s.data.len()), (AMD_SECTION_PATCH, 72));
+        assert!(p.next().is_none());
+        assert!(p.remainder().is_empty());
+
+        let h = AmdPatchHeader::from_bytes(s.data[..64].try_into().unwrap());
+        assert_eq!(h.mc_patch_data_id.to_native(), 0x8004);
+        let v = AmdPatch::parse(s.data).unwrap();
+        assert_eq!((v.patch_id, v.date, v.equivalence_id), (0x0a201210, 0x07262022, 0xa212));
+        assert_eq!(v.data.len(), 8);
+        assert!(v.checksum_valid());
+
+        let mut d = data.clone();
+        *d.last_mut().unwrap() ^= 0x80;
+        let c = AmdContainer::parse(&d).unwrap();
+        let v = AmdPatch::parse(c.patches().next().unwrap().data).unwrap();
+        assert!(!v.checksum_valid());
+
+        d[0] = b'X';
+        assert!(AmdContainer::parse(&d).is_none());
+        assert!(AmdContainer::parse(&data[..20]).is_none());
+    }
+}
@@ -0,0 +1,273 @@
+//! Standard MIDI Files
+//!
+//! A Standard MIDI File is a sequence of chunks, each made of a 4-byte
+//! chunk type and a 32-bit length, similar to RIFF but big-endian and without
+//! padding. The file starts with an `MThd` chunk carrying the `Header`,
+//! followed by one `MTrk` chunk per track. Chunks of unknown type must be
+//! skipped.
+//!
+//! A track is a sequence of events, each preceded by a delta time in ticks,
+//! encoded as VLQ (see `varint::vlq_decode()`) of at most `DELTA_MAX_SIZE`
+//! bytes. Events are MIDI channel messages, system exclusive messages
+//! (`STATUS_SYSEX`, `STATUS_SYSEX_ESCAPE`), or meta events (`STATUS_META`).
+//! The status byte of channel messages may be omitted if it matches the
+//! previous one ("running status"), which can be detected since data bytes
+//! never have the most significant bit set. System exclusive messages and
+//! meta events carry a VLQ length, and meta events a type before it. Every
+//! track ends with a `META_END_OF_TRACK` event.
+//!
+//! All multi-byte integers are encoded as big-endian.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::{int, varint};
+
+pub const CHUNK_HEADER: [u8; 4] = *b"MThd";
+pub const CHUNK_TRACK: [u8; 4] = *b"MTrk";
+
+/// Size of the Header Chunk Payload
+pub const HEADER_SIZE: usize = 6;
+
+/// Maximum Size of Delta Times
+///
+/// Delta times and lengths are limited to 28 bits, and thus 4 bytes.
+pub const DELTA_MAX_SIZE: usize = 4;
+
+pub const FORMAT_SINGLE: u16 = 0;
+pub const FORMAT_SIMULTANEOUS: u16 = 1;
+pub const FORMAT_SEQUENTIAL: u16 = 2;
+
+pub const STATUS_NOTE_OFF: u8 = 0x80;
+pub const STATUS_NOTE_ON: u8 = 0x90;
+pub const STATUS_POLY_PRESSURE: u8 = 0xa0;
+pub const STATUS_CONTROL_CHANGE: u8 = 0xb0;
+pub const STATUS_PROGRAM_CHANGE: u8 = 0xc0;
+pub const STATUS_CHANNEL_PRESSURE: u8 = 0xd0;
+pub const STATUS_PITCH_BEND: u8 = 0xe0;
+pub const STATUS_SYSEX: u8 = 0xf0;
+pub const STATUS_TIME_CODE: u8 = 0xf1;
+pub const STATUS_SONG_POSITION: u8 = 0xf2;
+pub const STATUS_SONG_SELECT: u8 = 0xf3;
+pub const STATUS_TUNE_REQUEST: u8 = 0xf6;
+pub const STATUS_SYSEX_ESCAPE: u8 = 0xf7;
+pub const STATUS_CLOCK: u8 = 0xf8;
+pub const STATUS_START: u8 = 0xfa;
+pub const STATUS_CONTINUE: u8 = 0xfb;
+pub const STATUS_STOP: u8 = 0xfc;
+pub const STATUS_ACTIVE_SENSING: u8 = 0xfe;
+pub const STATUS_META: u8 = 0xff;
+
+pub const STATUS_TYPE_MASK: u8 = 0xf0;
+pub const STATUS_CHANNEL_MASK: u8 = 0x0f;
+
+pub const META_SEQUENCE_NUMBER: u8 = 0x00;
+pub const META_TEXT: u8 = 0x01;
+pub const META_COPYRIGHT: u8 = 0x02;
+pub const META_TRACK_NAME: u8 = 0x03;
+pub const META_INSTRUMENT_NAME: u8 = 0x04;
+pub const META_LYRIC: u8 = 0x05;
+pub const META_MARKER: u8 = 0x06;
+pub const META_CUE_POINT: u8 = 0x07;
+pub const META_CHANNEL_PREFIX: u8 = 0x20;
+pub const META_END_OF_TRACK: u8 = 0x2f;
+pub const META_TEMPO: u8 = 0x51;
+pub const META_SMPTE_OFFSET: u8 = 0x54;
+pub const META_TIME_SIGNATURE: u8 = 0x58;
+pub const META_KEY_SIGNATURE: u8 = 0x59;
+pub const META_SEQUENCER_SPECIFIC: u8 = 0x7f;
+
+/// Chunk Header
+#[repr(C)]
+pub struct ChunkHeader {
+    /// Chunk type, e.g., `CHUNK_HEADER` or `CHUNK_TRACK`.
+    pub id: [u8; 4],
+
+    /// Size of the payload in bytes.
+    pub length: int::u32be,
+}
+
+/// Header Chunk
+///
+/// The payload of the `MThd` chunk. Future versions may extend it, so the
+/// chunk length might exceed `HEADER_SIZE`.
+#[repr(C)]
+pub struct Header {
+    /// File format (`FORMAT_*`).
+    pub format: int::u16be,
+
+    /// Number of tracks.
+    pub tracks: int::u16be,
+
+    /// Time division (see `ticks_per_quarter()` and `smpte()`).
+    pub division: int::u16be,
+}
+
+impl ChunkHeader {
+    /// Import a chunk header from a byte slice
+    ///
+    /// Create a new chunk header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 8]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 8], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl Header {
+    /// Import a header from a byte slice
+    ///
+    /// Create a new header from data copied from a byte slice. No byte-order
+    /// conversions are applied.
+    pub fn from_bytes(data: &[u8; 6]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 6], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Return ticks per quarter note
+    ///
+    /// Return the number of ticks per quarter note, or `None` if the
+    /// division is SMPTE-based.
+    pub fn ticks_per_quarter(&self) -> Option<u16> {
+        let v = int::ForeignEndian::to_native(self.division);
+        match v & 0x8000 {
+            0 => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Return SMPTE division
+    ///
+    /// Return the number of frames per second (24, 25, 29 for 30 drop-frame,
+    /// or 30) and the ticks per frame, or `None` if the division is based on
+    /// quarter notes.
+    pub fn smpte(&self) -> Option<(u8, u8)> {
+        let v = int::ForeignEndian::to_native(self.division);
+        match v & 0x8000 {
+            0 => None,
+            _ => Some((0u8.wrapping_sub((v >> 8) as u8), v as u8)),
+        }
+    }
+}
+
+/// Return Data Size of Channel Messages
+///
+/// Return the number of data bytes following the status byte of a channel
+/// message, or `None` if `status` is not a channel message.
+pub fn data_size(status: u8) -> Option<usize> {
+    match status & STATUS_TYPE_MASK {
+        STATUS_PROGRAM_CHANGE | STATUS_CHANNEL_PRESSURE => Some(1),
+        STATUS_NOTE_OFF..=STATUS_PITCH_BEND => Some(2),
+        _ => None,
+    }
+}
+
+/// Decode Delta Time
+///
+/// Decode a delta time or length at the start of `data`, limited to
+/// `DELTA_MAX_SIZE` bytes.
+pub fn delta_decode(data: &[u8]) -> Option<(u32, usize)> {
+    let n = core::cmp::min(data.len(), DELTA_MAX_SIZE);
+    let (v, n) = varint::vlq_decode(&data[..n])?;
+    Some((v as u32, n))
+}
+
+/// Decode Tempo
+///
+/// Decode the payload of a `META_TEMPO` event, the length of a quarter note
+/// in microseconds.
+pub fn tempo(data: &[u8; 3]) -> u32 {
+    (data[0] as u32) << 16 | (data[1] as u32) << 8 | data[2] as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A single-track file with a tempo event, a note, and the end of track.
+    const FILE: [u8; 41] = [
+        0x4d, 0x54, 0x68, 0x64, 0x00, 0x00, 0x00, 0x06,
+        0x00, 0x00, 0x00, 0x01, 0x01, 0xe0, 0x4d, 0x54,
+        0x72, 0x6b, 0x00, 0x00, 0x00, 0x13, 0x00, 0xff,
+        0x51, 0x03, 0x07, 0xa1, 0x20, 0x00, 0x90, 0x3c,
+        0x40, 0x83, 0x60, 0x3c, 0x00, 0x00, 0xff, 0x2f,
+        0x00,
+    ];
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<ChunkHeader>(), 8);
+        assert_eq!(align_of::<ChunkHeader>(), 4);
+        assert_eq!(size_of::<Header>(), HEADER_SIZE);
+        assert_eq!(align_of::<Header>(), 2);
+    }
+
+    // Parse the header and walk the events of a track.
+    #[test]
+    fn verify_file() {
+        let c = ChunkHeader::from_bytes(FILE[..8].try_into().unwrap());
+        assert_eq!(c.id, CHUNK_HEADER);
+        assert_eq!(int::ForeignEndian::to_native(c.length), 6);
+
+        let h = Header::from_bytes(FILE[8..14].try_into().unwrap());
+        assert_eq!(int::ForeignEndian::to_native(h.format), FORMAT_SINGLE);
+        assert_eq!(int::ForeignEndian::to_native(h.tracks), 1);
+        assert_eq!(h.ticks_per_quarter(), Some(480));
+        assert!(h.smpte().is_none());
+
+        let c = ChunkHeader::from_bytes(FILE[14..22].try_into().unwrap());
+        assert_eq!(c.id, CHUNK_TRACK);
+        let track = &FILE[22..22 + int::ForeignEndian::to_native(c.length) as usize];
+
+        // Tempo meta event of 500000us per quarter note.
+        assert_eq!(delta_decode(track), Some((0, 1)));
+        assert_eq!(&track[1..4], &[STATUS_META, META_TEMPO, 3]);
+        assert_eq!(tempo(track[4..7].try_into().unwrap()), 500000);
+
+        // Note on, followed by a note off via running status.
+        assert_eq!(delta_decode(&track[7..]), Some((0, 1)));
+        assert_eq!(track[8] & STATUS_TYPE_MASK, STATUS_NOTE_ON);
+        assert_eq!(data_size(track[8]), Some(2));
+        assert_eq!(delta_decode(&track[11..]), Some((480, 2)));
+        assert!(track[13] & 0x80 == 0);
+
+        assert_eq!(delta_decode(&track[15..]), Some((0, 1)));
+        assert_eq!(&track[16..], &[STATUS_META, META_END_OF_TRACK, 0]);
+    }
+
+    // Verify time divisions, message sizes, and delta time limits.
+    #[test]
+    fn verify_events() {
+        let h = Header::from_bytes(&[0x00, 0x01, 0x00, 0x02, 0xe7, 0x28]);
+        assert_eq!(h.smpte(), Some((25, 40)));
+        assert!(h.ticks_per_quarter().is_none());
+        let h = Header::from_bytes(&[0x00, 0x00, 0x00, 0x01, 0x80, 0x28]);
+        assert_eq!(h.smpte(), Some((128, 40)));
+
+        assert_eq!(data_size(STATUS_PROGRAM_CHANGE | 0x09), Some(1));
+        assert_eq!(data_size(STATUS_PITCH_BEND), Some(2));
+        assert!(data_size(STATUS_SYSEX).is_none());
+        assert!(data_size(0x40).is_none());
+
+        assert_eq!(delta_decode(&[0xff, 0xff, 0xff, 0x7f]), Some((0x0fff_ffff, 4)));
+        assert!(delta_decode(&[0xff, 0xff, 0xff, 0xff, 0x7f]).is_none());
+    }
+}
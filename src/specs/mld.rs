@@ -0,0 +1,282 @@
+//! Multicast Listener Discovery
+//!
+//! MLD is the IPv6 equivalent of IGMP (see `igmp`). Its messages are ICMPv6
+//! messages (`icmpv6::TYPE_MLD_*`), sent with a hop limit of 1 and the
+//! router-alert hop-by-hop option. The structures in this module describe
+//! the body following the ICMPv6 header (see `icmp::Header`).
+//!
+//! MLDv1 queries, reports, and done messages share a single layout (see
+//! `Message`). MLDv2 extends queries with a source list (see `Query`), and
+//! adds a new report format (`icmpv6::TYPE_MLDV2_REPORT`) which carries a
+//! sequence of group records (see `Report` and `GroupRecords`). The length of
+//! a query tells the versions apart: 24 bytes for MLDv1, at least 28 bytes
+//! for MLDv2, including the ICMPv6 header.
+//!
+//! All multi-byte integers are encoded as big-endian.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::{igmp, int};
+
+pub const MESSAGE_SIZE: usize = 20;
+pub const QUERY_SIZE: usize = 24;
+pub const REPORT_SIZE: usize = 4;
+pub const GROUP_RECORD_SIZE: usize = 20;
+
+pub const ALL_NODES: [u8; 16] = [
+    0xff, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01,
+];
+pub const ALL_ROUTERS: [u8; 16] = [
+    0xff, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02,
+];
+pub const ALL_MLDV2_ROUTERS: [u8; 16] = [
+    0xff, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x16,
+];
+
+/// MLDv1 Message Body
+///
+/// The body of MLDv1 queries, reports, and done messages. This is also the
+/// start of the body of MLDv2 queries.
+#[repr(C)]
+pub struct Message {
+    /// Maximum response delay of queries in milliseconds. For MLDv2, a code
+    /// (see `decode_response_code()`).
+    pub max_response: int::u16be,
+
+    /// Reserved, must be 0.
+    pub reserved: int::u16be,
+
+    /// Multicast address, or unspecified for general queries.
+    pub group: [u8; 16],
+}
+
+/// MLDv2 Query Body
+///
+/// Followed by the source addresses, each 16 bytes.
+#[repr(C)]
+pub struct Query {
+    /// Maximum response code (see `decode_response_code()`), in
+    /// milliseconds.
+    pub max_response_code: int::u16be,
+
+    /// Reserved, must be 0.
+    pub reserved: int::u16be,
+
+    /// Multicast address, or unspecified for general queries.
+    pub group: [u8; 16],
+
+    /// Reserved bits, the suppress-router-side-processing flag
+    /// (`igmp::QUERY_S`), and the querier's robustness variable
+    /// (`igmp::QUERY_QRV_MASK`).
+    pub flags: u8,
+
+    /// Querier's query interval code (see `igmp::decode_code()`), in
+    /// seconds.
+    pub qqic: u8,
+
+    /// Number of source addresses.
+    pub sources: int::u16be,
+}
+
+/// MLDv2 Report Body
+///
+/// Followed by the group records (see `GroupRecords`).
+#[repr(C)]
+pub struct Report {
+    /// Reserved, must be 0.
+    pub reserved: int::u16be,
+
+    /// Number of group records.
+    pub records: int::u16be,
+}
+
+/// MLDv2 Group Record Header
+///
+/// Followed by the source addresses and auxiliary data.
+#[repr(C)]
+pub struct GroupRecordHeader {
+    /// Record type (`igmp::RECORD_*`).
+    pub record_type: u8,
+
+    /// Size of the auxiliary data in 4-byte units.
+    pub aux_data_len: u8,
+
+    /// Number of source addresses.
+    pub sources: int::u16be,
+
+    /// Multicast address.
+    pub group: [u8; 16],
+}
+
+/// MLDv2 Group Record Reference
+pub type GroupRecord<'data> = igmp::GroupRecord<'data, 16>;
+
+/// MLDv2 Group Record Iterator
+pub type GroupRecords<'data> = igmp::GroupRecords<'data, 16>;
+
+impl Message {
+    /// Import a message body from a byte slice
+    ///
+    /// Create a new message body from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 20]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 20], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl Query {
+    /// Import a query body from a byte slice
+    ///
+    /// Create a new query body from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 24]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 24], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Return querier's robustness variable
+    pub fn qrv(&self) -> u8 {
+        self.flags & igmp::QUERY_QRV_MASK
+    }
+
+    /// Check for suppress-router-side-processing flag
+    pub fn suppress(&self) -> bool {
+        self.flags & igmp::QUERY_S != 0
+    }
+}
+
+impl Report {
+    /// Import a report body from a byte slice
+    ///
+    /// Create a new report body from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 4]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 4], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl GroupRecordHeader {
+    /// Import a group record header from a byte slice
+    ///
+    /// Create a new group record header from data copied from a byte slice.
+    /// No byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 20]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 20], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+/// Decode 16-bit maximum response code
+///
+/// Decode the maximum response code of MLDv2 queries. Values below 32768 are
+/// encoded literally, others as floating-point value with 3 bits of
+/// exponent and 12 bits of mantissa.
+pub fn decode_response_code(code: u16) -> u32 {
+    if code < 0x8000 {
+        code as u32
+    } else {
+        ((code as u32 & 0x0fff) | 0x1000) << (((code >> 12) & 0x07) + 3)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // An MLDv2 report body with a single exclude record without sources,
+    // joining ff02::fb.
+    const REPORT: [u8; 24] = [
+        0x00, 0x00, 0x00, 0x01, 0x04, 0x00, 0x00, 0x00,
+        0xff, 0x02, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xfb,
+    ];
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<Message>(), MESSAGE_SIZE);
+        assert_eq!(align_of::<Message>(), 2);
+        assert_eq!(size_of::<Query>(), QUERY_SIZE);
+        assert_eq!(align_of::<Query>(), 2);
+        assert_eq!(size_of::<Report>(), REPORT_SIZE);
+        assert_eq!(align_of::<Report>(), 2);
+        assert_eq!(size_of::<GroupRecordHeader>(), GROUP_RECORD_SIZE);
+        assert_eq!(align_of::<GroupRecordHeader>(), 2);
+    }
+
+    // Parse an MLDv2 report body and its group record.
+    #[test]
+    fn verify_report() {
+        let r = Report::from_bytes(REPORT[..4].try_into().unwrap());
+        assert_eq!(int::ForeignEndian::to_native(r.records), 1);
+
+        let h = GroupRecordHeader::from_bytes(REPORT[4..].try_into().unwrap());
+        assert_eq!(h.record_type, igmp::RECORD_CHANGE_TO_EXCLUDE);
+        assert_eq!(h.group[15], 0xfb);
+
+        let mut records = GroupRecords::new(&REPORT[4..]);
+        let v: GroupRecord = records.next().unwrap();
+        assert_eq!(v.record_type, igmp::RECORD_CHANGE_TO_EXCLUDE);
+        assert_eq!(v.group, h.group);
+        assert!(v.sources.is_empty());
+        assert!(records.next().is_none());
+        assert!(GroupRecords::new(&REPORT[4..23]).next().is_none());
+    }
+
+    // Parse MLDv1 and MLDv2 query bodies and decode their codes.
+    #[test]
+    fn verify_query() {
+        let mut data = [0; 24];
+        data[..2].copy_from_slice(&[0x27, 0x10]);
+        let m = Message::from_bytes(data[..20].try_into().unwrap());
+        assert_eq!(int::ForeignEndian::to_native(m.max_response), 10000);
+        assert_eq!(m.group, [0; 16]);
+
+        data[..2].copy_from_slice(&[0x80, 0x01]);
+        data[20] = 0x02;
+        data[21] = 125;
+        let q = Query::from_bytes(&data);
+        assert_eq!(decode_response_code(int::ForeignEndian::to_native(q.max_response_code)), 0x1001 << 3);
+        assert_eq!(decode_response_code(10000), 10000);
+        assert_eq!(q.qrv(), 2);
+        assert!(!q.suppress());
+        assert_eq!(igmp::decode_code(q.qqic), 125);
+    }
+}
@@ -0,0 +1,445 @@
+//! Modbus
+//!
+//! Modbus requests and responses are protocol data units (PDUs) of up to
+//! `PDU_MAX` bytes, starting with a function code (`FUNCTION_*`), followed
+//! by function-specific data. Responses to failed requests carry the
+//! function code with `FUNCTION_EXCEPTION_FLAG` set, followed by an
+//! exception code (`EXCEPTION_*`).
+//!
+//! Over serial lines (RTU), each PDU is prefixed with the server address and
+//! followed by a CRC-16 of the address and PDU (see `decode_rtu()`). Over
+//! TCP, each PDU is prefixed with an `MbapHeader` carrying a transaction
+//! identifier, the length of the remaining data, and a unit identifier in
+//! place of the server address (see `decode_tcp()`).
+//!
+//! Coil and discrete-input data is packed into bytes, with the first item in
+//! the least-significant bit of the first byte. Register data is a sequence
+//! of 16-bit registers.
+//!
+//! All multi-byte integers are encoded as big-endian, except for the CRC of
+//! RTU frames, which is little-endian.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::{checksum, int};
+
+pub const TCP_PORT: u16 = 502;
+
+pub const MBAP_HEADER_SIZE: usize = 7;
+pub const CRC_SIZE: usize = 2;
+
+/// Maximum PDU Size
+pub const PDU_MAX: usize = 253;
+
+/// Maximum RTU Frame Size
+pub const RTU_FRAME_MAX: usize = 256;
+
+/// Maximum TCP Frame Size
+pub const TCP_FRAME_MAX: usize = 260;
+
+/// Protocol Identifier of Modbus over TCP
+pub const PROTOCOL_ID: u16 = 0;
+
+pub const ADDRESS_BROADCAST: u8 = 0;
+pub const ADDRESS_MAX: u8 = 247;
+
+pub const FUNCTION_READ_COILS: u8 = 0x01;
+pub const FUNCTION_READ_DISCRETE_INPUTS: u8 = 0x02;
+pub const FUNCTION_READ_HOLDING_REGISTERS: u8 = 0x03;
+pub const FUNCTION_READ_INPUT_REGISTERS: u8 = 0x04;
+pub const FUNCTION_WRITE_SINGLE_COIL: u8 = 0x05;
+pub const FUNCTION_WRITE_SINGLE_REGISTER: u8 = 0x06;
+pub const FUNCTION_READ_EXCEPTION_STATUS: u8 = 0x07;
+pub const FUNCTION_DIAGNOSTICS: u8 = 0x08;
+pub const FUNCTION_GET_COMM_EVENT_COUNTER: u8 = 0x0b;
+pub const FUNCTION_GET_COMM_EVENT_LOG: u8 = 0x0c;
+pub const FUNCTION_WRITE_MULTIPLE_COILS: u8 = 0x0f;
+pub const FUNCTION_WRITE_MULTIPLE_REGISTERS: u8 = 0x10;
+pub const FUNCTION_REPORT_SERVER_ID: u8 = 0x11;
+pub const FUNCTION_READ_FILE_RECORD: u8 = 0x14;
+pub const FUNCTION_WRITE_FILE_RECORD: u8 = 0x15;
+pub const FUNCTION_MASK_WRITE_REGISTER: u8 = 0x16;
+pub const FUNCTION_READ_WRITE_MULTIPLE_REGISTERS: u8 = 0x17;
+pub const FUNCTION_READ_FIFO_QUEUE: u8 = 0x18;
+pub const FUNCTION_ENCAPSULATED_INTERFACE: u8 = 0x2b;
+
+pub const FUNCTION_EXCEPTION_FLAG: u8 = 0x80;
+
+pub const EXCEPTION_ILLEGAL_FUNCTION: u8 = 0x01;
+pub const EXCEPTION_ILLEGAL_DATA_ADDRESS: u8 = 0x02;
+pub const EXCEPTION_ILLEGAL_DATA_VALUE: u8 = 0x03;
+pub const EXCEPTION_SERVER_DEVICE_FAILURE: u8 = 0x04;
+pub const EXCEPTION_ACKNOWLEDGE: u8 = 0x05;
+pub const EXCEPTION_SERVER_DEVICE_BUSY: u8 = 0x06;
+pub const EXCEPTION_MEMORY_PARITY_ERROR: u8 = 0x08;
+pub const EXCEPTION_GATEWAY_PATH_UNAVAILABLE: u8 = 0x0a;
+pub const EXCEPTION_GATEWAY_TARGET_FAILED: u8 = 0x0b;
+
+pub const COIL_ON: u16 = 0xff00;
+pub const COIL_OFF: u16 = 0x0000;
+
+pub const READ_BITS_MAX: u16 = 2000;
+pub const READ_REGISTERS_MAX: u16 = 125;
+pub const WRITE_BITS_MAX: u16 = 1968;
+pub const WRITE_REGISTERS_MAX: u16 = 123;
+
+/// Modbus Application Protocol Header
+///
+/// Since the size of the header is not a multiple of the alignment of
+/// `length`, this structure is packed.
+#[repr(C, packed)]
+pub struct MbapHeader {
+    /// Transaction identifier, echoed in the response.
+    pub transaction_id: int::u16be,
+
+    /// Protocol identifier, must be `PROTOCOL_ID`.
+    pub protocol_id: int::u16be,
+
+    /// Number of bytes following this field, including the unit identifier.
+    pub length: int::u16be,
+
+    /// Unit identifier, the server address behind gateways to serial lines.
+    pub unit_id: u8,
+}
+
+/// Range Request PDU
+///
+/// The layout of requests of all read functions for coils, discrete inputs,
+/// and registers, as well as of responses to write-multiple functions.
+///
+/// Since `address` is not naturally aligned, this structure is packed.
+#[repr(C, packed)]
+pub struct RangeRequest {
+    /// Function code (`FUNCTION_*`).
+    pub function: u8,
+
+    /// Address of the first item.
+    pub address: int::u16be,
+
+    /// Number of items.
+    pub quantity: int::u16be,
+}
+
+/// Write Single PDU
+///
+/// The layout of requests and responses of `FUNCTION_WRITE_SINGLE_COIL` and
+/// `FUNCTION_WRITE_SINGLE_REGISTER`. Responses echo the request.
+///
+/// Since `address` is not naturally aligned, this structure is packed.
+#[repr(C, packed)]
+pub struct WriteSingle {
+    /// Function code (`FUNCTION_*`).
+    pub function: u8,
+
+    /// Address of the item.
+    pub address: int::u16be,
+
+    /// Register value, or `COIL_ON` and `COIL_OFF` for coils.
+    pub value: int::u16be,
+}
+
+/// Write Multiple Request PDU Header
+///
+/// The layout of requests of `FUNCTION_WRITE_MULTIPLE_COILS` and
+/// `FUNCTION_WRITE_MULTIPLE_REGISTERS`, followed by `byte_count` bytes of
+/// data.
+///
+/// Since `address` is not naturally aligned, this structure is packed.
+#[repr(C, packed)]
+pub struct WriteMultipleRequest {
+    /// Function code (`FUNCTION_*`).
+    pub function: u8,
+
+    /// Address of the first item.
+    pub address: int::u16be,
+
+    /// Number of items.
+    pub quantity: int::u16be,
+
+    /// Number of data bytes following this header.
+    pub byte_count: u8,
+}
+
+/// Mask Write Register PDU
+///
+/// The layout of requests and responses of `FUNCTION_MASK_WRITE_REGISTER`.
+/// The new register value is `(value & and_mask) | (or_mask & !and_mask)`.
+///
+/// Since `address` is not naturally aligned, this structure is packed.
+#[repr(C, packed)]
+pub struct MaskWrite {
+    /// Function code, must be `FUNCTION_MASK_WRITE_REGISTER`.
+    pub function: u8,
+
+    /// Address of the register.
+    pub address: int::u16be,
+
+    /// Mask of bits to keep.
+    pub and_mask: int::u16be,
+
+    /// Bits to set among the bits not kept.
+    pub or_mask: int::u16be,
+}
+
+/// Read Response PDU Header
+///
+/// The layout of responses of all read functions for coils, discrete
+/// inputs, and registers, followed by `byte_count` bytes of data.
+#[repr(C)]
+pub struct ReadResponse {
+    /// Function code (`FUNCTION_*`).
+    pub function: u8,
+
+    /// Number of data bytes following this header.
+    pub byte_count: u8,
+}
+
+/// Exception Response PDU
+#[repr(C)]
+pub struct Exception {
+    /// Function code of the request, combined with
+    /// `FUNCTION_EXCEPTION_FLAG`.
+    pub function: u8,
+
+    /// Exception code (`EXCEPTION_*`).
+    pub code: u8,
+}
+
+impl MbapHeader {
+    /// Import a header from a byte slice
+    ///
+    /// Create a new header from data copied from a byte slice. No byte-order
+    /// conversions are applied.
+    pub fn from_bytes(data: &[u8; 7]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 7], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl RangeRequest {
+    /// Import a range request from a byte slice
+    ///
+    /// Create a new range request from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 5]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 5], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl WriteSingle {
+    /// Import a write-single PDU from a byte slice
+    ///
+    /// Create a new write-single PDU from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 5]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 5], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl WriteMultipleRequest {
+    /// Import a write-multiple request from a byte slice
+    ///
+    /// Create a new write-multiple request from data copied from a byte
+    /// slice. No byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 6]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 6], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl MaskWrite {
+    /// Import a mask-write PDU from a byte slice
+    ///
+    /// Create a new mask-write PDU from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 7]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 7], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+/// Check for exception responses
+pub fn is_exception(function: u8) -> bool {
+    function & FUNCTION_EXCEPTION_FLAG != 0
+}
+
+/// Return byte count of bit data
+///
+/// Return the number of bytes needed to pack `quantity` coils or discrete
+/// inputs.
+pub fn bit_byte_count(quantity: u16) -> usize {
+    (quantity as usize).div_ceil(8)
+}
+
+/// Calculate RTU CRC
+///
+/// Calculate the CRC-16/MODBUS of the given data, which is the server
+/// address followed by the PDU. It is transmitted in little-endian order.
+pub fn rtu_crc(data: &[u8]) -> u16 {
+    checksum::crc16_update(0xffff, data)
+}
+
+/// Decode RTU frame
+///
+/// Verify the CRC of an RTU frame and return the server address and the
+/// PDU, or `None` if the frame is truncated or the CRC does not match.
+pub fn decode_rtu(frame: &[u8]) -> Option<(u8, &[u8])> {
+    if frame.len() < 2 + CRC_SIZE {
+        return None;
+    }
+
+    let (data, crc) = frame.split_at(frame.len() - CRC_SIZE);
+    if rtu_crc(data) != u16::from_le_bytes([crc[0], crc[1]]) {
+        return None;
+    }
+
+    Some((data[0], &data[1..]))
+}
+
+/// Decode TCP frame
+///
+/// Split a TCP frame into its header and PDU, limited to the length given
+/// by the header. Return `None` if the frame is truncated, the protocol
+/// identifier is invalid, or no PDU is present.
+pub fn decode_tcp(frame: &[u8]) -> Option<(MbapHeader, &[u8])> {
+    let h = MbapHeader::from_bytes(frame.get(..MBAP_HEADER_SIZE)?.try_into().unwrap());
+    if int::ForeignEndian::to_native(h.protocol_id) != PROTOCOL_ID {
+        return None;
+    }
+
+    let n = int::ForeignEndian::to_native(h.length) as usize;
+    if n < 2 {
+        return None;
+    }
+
+    let v = frame.get(MBAP_HEADER_SIZE..MBAP_HEADER_SIZE + n - 1)?;
+    Some((h, v))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A read-holding-registers request over RTU.
+    const RTU_REQUEST: [u8; 8] = [
+        0x11, 0x03, 0x00, 0x6b, 0x00, 0x03, 0x76, 0x87,
+    ];
+
+    // The response to the request, without CRC.
+    const RTU_RESPONSE: [u8; 10] = [
+        0x11, 0x03, 0x06, 0xae, 0x41, 0x56, 0x52, 0x43,
+        0x40, 0x49,
+    ];
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<MbapHeader>(), MBAP_HEADER_SIZE);
+        assert_eq!(align_of::<MbapHeader>(), 1);
+        assert_eq!(size_of::<RangeRequest>(), 5);
+        assert_eq!(align_of::<RangeRequest>(), 1);
+        assert_eq!(size_of::<WriteSingle>(), 5);
+        assert_eq!(align_of::<WriteSingle>(), 1);
+        assert_eq!(size_of::<WriteMultipleRequest>(), 6);
+        assert_eq!(align_of::<WriteMultipleRequest>(), 1);
+        assert_eq!(size_of::<MaskWrite>(), 7);
+        assert_eq!(align_of::<MaskWrite>(), 1);
+        assert_eq!(size_of::<ReadResponse>(), 2);
+        assert_eq!(align_of::<ReadResponse>(), 1);
+        assert_eq!(size_of::<Exception>(), 2);
+        assert_eq!(align_of::<Exception>(), 1);
+    }
+
+    // Verify the CRC of RTU frames and decode their PDUs.
+    #[test]
+    fn verify_rtu() {
+        let (address, pdu) = decode_rtu(&RTU_REQUEST).unwrap();
+        assert_eq!(address, 0x11);
+        let r = RangeRequest::from_bytes(pdu.try_into().unwrap());
+        assert_eq!(r.function, FUNCTION_READ_HOLDING_REGISTERS);
+        assert_eq!(int::ForeignEndian::to_native(r.address), 0x006b);
+        assert_eq!(int::ForeignEndian::to_native(r.quantity), 3);
+
+        let mut data = [0; 12];
+        data[..10].copy_from_slice(&RTU_RESPONSE);
+        data[10..].copy_from_slice(&rtu_crc(&RTU_RESPONSE).to_le_bytes());
+        assert_eq!(data[10..], [0xad, 0x00]);
+        let (_, pdu) = decode_rtu(&data).unwrap();
+        assert_eq!(pdu[0], FUNCTION_READ_HOLDING_REGISTERS);
+        assert_eq!(pdu[1], 6);
+        assert_eq!(u16::from_be_bytes([pdu[2], pdu[3]]), 0xae41);
+
+        data[5] ^= 0x01;
+        assert!(decode_rtu(&data).is_none());
+        assert!(decode_rtu(&RTU_REQUEST[..3]).is_none());
+    }
+
+    // Decode TCP frames, including an exception response.
+    #[test]
+    fn verify_tcp() {
+        let frame = [
+            0x00, 0x01, 0x00, 0x00, 0x00, 0x08, 0x01, 0x0f,
+            0x00, 0x13, 0x00, 0x0a, 0x02, 0xcd, 0x01, 0xff,
+        ];
+        let (h, pdu) = decode_tcp(&frame).unwrap();
+        assert_eq!(int::ForeignEndian::to_native(h.transaction_id), 1);
+        assert_eq!(h.unit_id, 1);
+        assert_eq!(pdu.len(), 7);
+        let w = WriteMultipleRequest::from_bytes(pdu[..6].try_into().unwrap());
+        assert_eq!(w.function, FUNCTION_WRITE_MULTIPLE_COILS);
+        assert_eq!(int::ForeignEndian::to_native(w.quantity), 10);
+        assert_eq!(w.byte_count as usize, bit_byte_count(10));
+
+        let frame = [0x00, 0x02, 0x00, 0x00, 0x00, 0x03, 0x01, 0x83, 0x02];
+        let (_, pdu) = decode_tcp(&frame).unwrap();
+        assert!(is_exception(pdu[0]));
+        assert_eq!(pdu[0] & !FUNCTION_EXCEPTION_FLAG, FUNCTION_READ_HOLDING_REGISTERS);
+        assert_eq!(pdu[1], EXCEPTION_ILLEGAL_DATA_ADDRESS);
+
+        assert!(decode_tcp(&frame[..8]).is_none());
+        let mut data = frame;
+        data[3] = 0x01;
+        assert!(decode_tcp(&data).is_none());
+    }
+}
@@ -0,0 +1,325 @@
+//! MPEG Audio Frames
+//!
+//! MPEG-1 and MPEG-2 audio streams (ISO 11172-3, ISO 13818-3), including the
+//! unofficial MPEG-2.5 extension, are a sequence of self-contained frames
+//! without any container. Each frame starts with a 32-bit header, carrying
+//! an 11-bit sync word, followed by an optional 16-bit CRC and the audio
+//! data. The header encodes all parameters needed to compute the size of the
+//! frame, so streams can be traversed without decoding.
+//!
+//! Bitrate and sample rate are encoded as indices into tables, which depend
+//! on version and layer. Layer I frames are made of 4-byte slots, all other
+//! layers use 1-byte slots. The padding bit adds one slot to a frame.
+//!
+//! MP3 files commonly carry an `id3v2` tag at the start and an ID3v1 tag in
+//! the last 128 bytes. Encoders of variable-bitrate streams place a `Xing`
+//! or `Info` header (or a `VBRI` header) in the data of the first frame,
+//! carrying the frame count for duration estimates.
+//!
+//! All multi-byte integers are encoded as big-endian.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::int;
+
+/// Sync Word
+///
+/// The leading 11 bits of every frame header, left-aligned in 16 bits.
+pub const SYNC: u16 = 0xffe0;
+
+pub const VERSION_2_5: u8 = 0b00;
+/* reserved: 0b01 */
+pub const VERSION_2: u8 = 0b10;
+pub const VERSION_1: u8 = 0b11;
+
+/* reserved: 0b00 */
+pub const LAYER_3: u8 = 0b01;
+pub const LAYER_2: u8 = 0b10;
+pub const LAYER_1: u8 = 0b11;
+
+pub const CHANNEL_STEREO: u8 = 0b00;
+pub const CHANNEL_JOINT_STEREO: u8 = 0b01;
+pub const CHANNEL_DUAL: u8 = 0b10;
+pub const CHANNEL_MONO: u8 = 0b11;
+
+pub const EMPHASIS_NONE: u8 = 0b00;
+pub const EMPHASIS_50_15: u8 = 0b01;
+/* reserved: 0b10 */
+pub const EMPHASIS_CCITT_J17: u8 = 0b11;
+
+/// Free-format Bitrate Index
+pub const BITRATE_FREE: u8 = 0;
+
+/// Invalid Bitrate Index
+pub const BITRATE_INVALID: u8 = 15;
+
+pub const TAG_XING: [u8; 4] = *b"Xing";
+pub const TAG_INFO: [u8; 4] = *b"Info";
+pub const TAG_VBRI: [u8; 4] = *b"VBRI";
+
+/// ID3v1 Tag Marker
+///
+/// The initial 3 bytes of an ID3v1 tag, located in the last 128 bytes of a
+/// file.
+pub const ID3V1_MAGIC: [u8; 3] = *b"TAG";
+
+/// Size of ID3v1 Tags
+pub const ID3V1_SIZE: usize = 128;
+
+/// Bitrates in kbit/s
+///
+/// Indexed by the bitrate index, for MPEG-1 layers I, II, III, followed by
+/// MPEG-2/2.5 layer I, and MPEG-2/2.5 layers II and III.
+pub const BITRATES: [[u16; 15]; 5] = [
+    [0, 32, 64, 96, 128, 160, 192, 224, 256, 288, 320, 352, 384, 416, 448],
+    [0, 32, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320, 384],
+    [0, 32, 40, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320],
+    [0, 32, 48, 56, 64, 80, 96, 112, 128, 144, 160, 176, 192, 224, 256],
+    [0, 8, 16, 24, 32, 40, 48, 56, 64, 80, 96, 112, 128, 144, 160],
+];
+
+/// Sample Rates in Hz
+///
+/// Indexed by the sample-rate index, for MPEG-1, MPEG-2, and MPEG-2.5.
+pub const SAMPLE_RATES: [[u32; 3]; 3] = [
+    [44100, 48000, 32000],
+    [22050, 24000, 16000],
+    [11025, 12000, 8000],
+];
+
+/// Frame Header
+#[repr(C)]
+pub struct FrameHeader {
+    /// Sync word (11 bits), version (2 bits), layer (2 bits), protection
+    /// absent (1 bit), bitrate index (4 bits), sample-rate index (2 bits),
+    /// padding (1 bit), private (1 bit), channel mode (2 bits), mode
+    /// extension (2 bits), copyright (1 bit), original (1 bit), and emphasis
+    /// (2 bits), starting at the most significant bit.
+    pub raw: int::u32be,
+}
+
+impl FrameHeader {
+    /// Import a frame header from a byte slice
+    ///
+    /// Create a new frame header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 4]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 4], *data);
+            uninit.assume_init()
+        }
+    }
+
+    fn bits(&self, shift: u32, width: u32) -> u8 {
+        ((int::ForeignEndian::to_native(self.raw) >> shift) & ((1 << width) - 1)) as u8
+    }
+
+    /// Check the sync word
+    pub fn has_sync(&self) -> bool {
+        (int::ForeignEndian::to_native(self.raw) >> 21) == 0x7ff
+    }
+
+    /// Return version (`VERSION_*`)
+    pub fn version(&self) -> u8 {
+        self.bits(19, 2)
+    }
+
+    /// Return layer (`LAYER_*`)
+    pub fn layer(&self) -> u8 {
+        self.bits(17, 2)
+    }
+
+    /// Check whether a CRC follows the header
+    pub fn has_crc(&self) -> bool {
+        self.bits(16, 1) == 0
+    }
+
+    /// Return bitrate index
+    pub fn bitrate_index(&self) -> u8 {
+        self.bits(12, 4)
+    }
+
+    /// Return sample-rate index
+    pub fn sample_rate_index(&self) -> u8 {
+        self.bits(10, 2)
+    }
+
+    /// Check for a padding slot
+    pub fn has_padding(&self) -> bool {
+        self.bits(9, 1) != 0
+    }
+
+    /// Return channel mode (`CHANNEL_*`)
+    pub fn channel_mode(&self) -> u8 {
+        self.bits(6, 2)
+    }
+
+    /// Return mode extension
+    pub fn mode_extension(&self) -> u8 {
+        self.bits(4, 2)
+    }
+
+    /// Return emphasis (`EMPHASIS_*`)
+    pub fn emphasis(&self) -> u8 {
+        self.bits(0, 2)
+    }
+
+    /// Check for a valid header
+    ///
+    /// Return whether the sync word matches and no reserved or invalid
+    /// values are used. Free-format bitrates are considered valid.
+    pub fn is_valid(&self) -> bool {
+        self.has_sync()
+            && self.version() != 0b01
+            && self.layer() != 0b00
+            && self.bitrate_index() != BITRATE_INVALID
+            && self.sample_rate_index() != 0b11
+    }
+
+    /// Return bitrate
+    ///
+    /// Return the bitrate in kbit/s, or `None` if the header is invalid or
+    /// uses a free-format bitrate.
+    pub fn bitrate(&self) -> Option<u32> {
+        if !self.is_valid() || self.bitrate_index() == BITRATE_FREE {
+            return None;
+        }
+
+        let table = match (self.version(), self.layer()) {
+            (VERSION_1, LAYER_1) => 0,
+            (VERSION_1, LAYER_2) => 1,
+            (VERSION_1, _) => 2,
+            (_, LAYER_1) => 3,
+            (_, _) => 4,
+        };
+
+        Some(BITRATES[table][self.bitrate_index() as usize] as u32)
+    }
+
+    /// Return sample rate
+    ///
+    /// Return the sample rate in Hz, or `None` if the header is invalid.
+    pub fn sample_rate(&self) -> Option<u32> {
+        if !self.is_valid() {
+            return None;
+        }
+
+        let table = match self.version() {
+            VERSION_1 => 0,
+            VERSION_2 => 1,
+            _ => 2,
+        };
+
+        Some(SAMPLE_RATES[table][self.sample_rate_index() as usize])
+    }
+
+    /// Return samples per frame
+    pub fn samples_per_frame(&self) -> u32 {
+        match (self.version(), self.layer()) {
+            (_, LAYER_1) => 384,
+            (VERSION_1, _) | (_, LAYER_2) => 1152,
+            (_, _) => 576,
+        }
+    }
+
+    /// Return frame size
+    ///
+    /// Return the size of the entire frame in bytes, including the header,
+    /// or `None` if the header is invalid or uses a free-format bitrate.
+    pub fn frame_size(&self) -> Option<usize> {
+        let bitrate = self.bitrate()? as usize * 1000;
+        let rate = self.sample_rate()? as usize;
+        let padding = self.has_padding() as usize;
+
+        if self.layer() == LAYER_1 {
+            Some((12 * bitrate / rate + padding) * 4)
+        } else {
+            let bytes = self.samples_per_frame() as usize / 8;
+            Some(bytes * bitrate / rate + padding)
+        }
+    }
+
+    /// Return offset of the side information end
+    ///
+    /// Return the offset of the `Xing`/`Info` tag in the first frame of a
+    /// layer III stream, which follows the header, the CRC, and the side
+    /// information.
+    pub fn xing_offset(&self) -> usize {
+        let side = match (self.version() == VERSION_1, self.channel_mode() == CHANNEL_MONO) {
+            (true, false) => 32,
+            (true, true) => 17,
+            (false, false) => 17,
+            (false, true) => 9,
+        };
+
+        4 + if self.has_crc() { 2 } else { 0 } + side
+    }
+}
+
+/// Find Frame Sync
+///
+/// Return the offset of the next valid frame header in `data`.
+pub fn find_frame(data: &[u8]) -> Option<usize> {
+    data.windows(4).position(|v| {
+        v[0] == 0xff && FrameHeader::from_bytes(v.try_into().unwrap()).is_valid()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<FrameHeader>(), 4);
+        assert_eq!(align_of::<FrameHeader>(), 4);
+    }
+
+    // Decode the header of a common 128kbit/s joint-stereo frame.
+    #[test]
+    fn verify_header() {
+        let h = FrameHeader::from_bytes(&[0xff, 0xfb, 0x90, 0x64]);
+        assert!(h.is_valid());
+        assert_eq!(h.version(), VERSION_1);
+        assert_eq!(h.layer(), LAYER_3);
+        assert!(!h.has_crc());
+        assert_eq!(h.bitrate(), Some(128));
+        assert_eq!(h.sample_rate(), Some(44100));
+        assert!(!h.has_padding());
+        assert_eq!(h.channel_mode(), CHANNEL_JOINT_STEREO);
+        assert_eq!(h.samples_per_frame(), 1152);
+        assert_eq!(h.frame_size(), Some(417));
+        assert_eq!(h.xing_offset(), 36);
+    }
+
+    // Verify frame sizes of other versions and layers.
+    #[test]
+    fn verify_sizes() {
+        // MPEG-2 layer III, 64kbit/s, 22.05kHz, padded, mono.
+        let h = FrameHeader::from_bytes(&[0xff, 0xf3, 0x82, 0xc4]);
+        assert_eq!(h.version(), VERSION_2);
+        assert_eq!(h.bitrate(), Some(64));
+        assert_eq!(h.sample_rate(), Some(22050));
+        assert_eq!(h.samples_per_frame(), 576);
+        assert_eq!(h.frame_size(), Some(209));
+        assert_eq!(h.xing_offset(), 13);
+
+        // MPEG-1 layer I, 384kbit/s, 48kHz.
+        let h = FrameHeader::from_bytes(&[0xff, 0xff, 0xc4, 0x00]);
+        assert_eq!(h.frame_size(), Some(384));
+
+        assert!(!FrameHeader::from_bytes(&[0xff, 0xfb, 0xf0, 0x00]).is_valid());
+        assert!(!FrameHeader::from_bytes(&[0xff, 0xe9, 0x90, 0x00]).is_valid());
+        assert_eq!(find_frame(&[0x00, 0xff, 0x00, 0xff, 0xfb, 0x90, 0x64]), Some(3));
+    }
+}
@@ -0,0 +1,673 @@
+//! MPEG Transport Stream
+//!
+//! The MPEG-2 transport stream (ISO 13818-1) is a sequence of fixed-size
+//! 188-byte packets, each starting with the sync byte `SYNC` and a 4-byte
+//! `PacketHeader`. Each packet belongs to the stream identified by its
+//! 13-bit PID. Depending on the adaptation field control, the header is
+//! followed by an adaptation field, a payload, or both. The adaptation field
+//! carries the program clock reference (PCR) and stuffing.
+//!
+//! Payloads carry either PSI sections or PES packets. PSI sections (`PAT`,
+//! `PMT`, ...) start with a pointer field in the first packet of a section,
+//! followed by a `SectionHeader`, the table data, and an MPEG-2 CRC-32 (see
+//! `checksum::crc32_msb_update()`). The PAT on PID `PID_PAT` maps program
+//! numbers to the PIDs of their PMTs, and each PMT lists the elementary
+//! streams of a program.
+//!
+//! PES packets start with a `PesHeader` and, for most stream IDs, a
+//! `PesOptionalHeader` carrying the 33-bit presentation and decoding
+//! timestamps in a 5-byte encoding with marker bits (see
+//! `timestamp_decode()`). Timestamps use a 90kHz clock, the PCR a 27MHz
+//! clock.
+//!
+//! All multi-byte integers are encoded as big-endian.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::{checksum, int};
+
+/// Packet Size
+pub const PACKET_SIZE: usize = 188;
+
+/// Sync Byte
+pub const SYNC: u8 = 0x47;
+
+pub const PID_PAT: u16 = 0x0000;
+pub const PID_CAT: u16 = 0x0001;
+pub const PID_TSDT: u16 = 0x0002;
+pub const PID_NIT: u16 = 0x0010;
+pub const PID_SDT: u16 = 0x0011;
+pub const PID_EIT: u16 = 0x0012;
+pub const PID_NULL: u16 = 0x1fff;
+
+/// PID Mask
+pub const PID_MASK: u16 = 0x1fff;
+
+pub const ADAPTATION_PAYLOAD: u8 = 0b01;
+pub const ADAPTATION_FIELD: u8 = 0b10;
+pub const ADAPTATION_BOTH: u8 = 0b11;
+
+pub const AF_DISCONTINUITY: u8 = 0x80;
+pub const AF_RANDOM_ACCESS: u8 = 0x40;
+pub const AF_ES_PRIORITY: u8 = 0x20;
+pub const AF_PCR: u8 = 0x10;
+pub const AF_OPCR: u8 = 0x08;
+pub const AF_SPLICING_POINT: u8 = 0x04;
+pub const AF_PRIVATE_DATA: u8 = 0x02;
+pub const AF_EXTENSION: u8 = 0x01;
+
+pub const TABLE_PAT: u8 = 0x00;
+pub const TABLE_CAT: u8 = 0x01;
+pub const TABLE_PMT: u8 = 0x02;
+pub const TABLE_NIT: u8 = 0x40;
+pub const TABLE_SDT: u8 = 0x42;
+pub const TABLE_EIT: u8 = 0x4e;
+pub const TABLE_STUFFING: u8 = 0xff;
+
+pub const STREAM_TYPE_MPEG1_VIDEO: u8 = 0x01;
+pub const STREAM_TYPE_MPEG2_VIDEO: u8 = 0x02;
+pub const STREAM_TYPE_MPEG1_AUDIO: u8 = 0x03;
+pub const STREAM_TYPE_MPEG2_AUDIO: u8 = 0x04;
+pub const STREAM_TYPE_PRIVATE_SECTIONS: u8 = 0x05;
+pub const STREAM_TYPE_PRIVATE_PES: u8 = 0x06;
+pub const STREAM_TYPE_AAC_ADTS: u8 = 0x0f;
+pub const STREAM_TYPE_MPEG4_VIDEO: u8 = 0x10;
+pub const STREAM_TYPE_AAC_LATM: u8 = 0x11;
+pub const STREAM_TYPE_H264: u8 = 0x1b;
+pub const STREAM_TYPE_H265: u8 = 0x24;
+pub const STREAM_TYPE_AC3: u8 = 0x81;
+
+pub const STREAM_ID_PROGRAM_STREAM_MAP: u8 = 0xbc;
+pub const STREAM_ID_PRIVATE_1: u8 = 0xbd;
+pub const STREAM_ID_PADDING: u8 = 0xbe;
+pub const STREAM_ID_PRIVATE_2: u8 = 0xbf;
+pub const STREAM_ID_AUDIO: u8 = 0xc0;
+pub const STREAM_ID_VIDEO: u8 = 0xe0;
+pub const STREAM_ID_ECM: u8 = 0xf0;
+pub const STREAM_ID_EMM: u8 = 0xf1;
+pub const STREAM_ID_DSMCC: u8 = 0xf2;
+pub const STREAM_ID_H222_E: u8 = 0xf8;
+pub const STREAM_ID_PROGRAM_STREAM_DIRECTORY: u8 = 0xff;
+
+pub const PTS_DTS_NONE: u8 = 0b00;
+/* forbidden: 0b01 */
+pub const PTS_DTS_PTS: u8 = 0b10;
+pub const PTS_DTS_BOTH: u8 = 0b11;
+
+pub const TIMESTAMP_PTS_ONLY: u8 = 0b0010;
+pub const TIMESTAMP_PTS: u8 = 0b0011;
+pub const TIMESTAMP_DTS: u8 = 0b0001;
+
+/// PES Start Code Prefix
+pub const PES_START_CODE: [u8; 3] = [0x00, 0x00, 0x01];
+
+/// Timestamp Clock Rate
+pub const CLOCK_TIMESTAMP: u64 = 90_000;
+
+/// PCR Clock Rate
+pub const CLOCK_PCR: u64 = 27_000_000;
+
+/// Packet Header
+#[repr(C)]
+pub struct PacketHeader {
+    /// Sync byte (8 bits), transport error indicator (1 bit), payload unit
+    /// start indicator (1 bit), transport priority (1 bit), PID (13 bits),
+    /// scrambling control (2 bits), adaptation field control (2 bits), and
+    /// continuity counter (4 bits), starting at the most significant bit.
+    pub raw: int::u32be,
+}
+
+/// PSI Section Header
+///
+/// The header of long-form PSI sections, such as the PAT and PMT. Since
+/// the section length is not naturally aligned, this structure is packed.
+#[repr(C, packed)]
+pub struct SectionHeader {
+    /// Table ID (`TABLE_*`).
+    pub table_id: u8,
+
+    /// Section syntax indicator (1 bit), private indicator (1 bit),
+    /// reserved (2 bits), and section length (12 bits). The length counts
+    /// the bytes following this field, including the CRC.
+    pub length: int::u16be,
+
+    /// Table ID extension, the transport stream ID for the PAT and the
+    /// program number for PMTs.
+    pub id: int::u16be,
+
+    /// Reserved (2 bits), version number (5 bits), and current/next
+    /// indicator (1 bit).
+    pub version: u8,
+
+    /// Number of this section.
+    pub section_number: u8,
+
+    /// Number of the last section of the table.
+    pub last_section_number: u8,
+}
+
+/// PAT Entry
+#[repr(C)]
+pub struct PatEntry {
+    /// Program number, or 0 for the network PID.
+    pub program_number: int::u16be,
+
+    /// Reserved (3 bits) and PID of the PMT (13 bits).
+    pub pid: int::u16be,
+}
+
+/// PMT Header
+///
+/// The fixed part of the PMT following the section header, followed by the
+/// program descriptors.
+#[repr(C)]
+pub struct PmtHeader {
+    /// Reserved (3 bits) and PID carrying the PCR (13 bits).
+    pub pcr_pid: int::u16be,
+
+    /// Reserved (4 bits) and length of the program descriptors (12 bits).
+    pub info_length: int::u16be,
+}
+
+/// PMT Stream Entry
+///
+/// An elementary stream of a PMT, followed by its descriptors. Since the PID
+/// is not naturally aligned, this structure is packed.
+#[repr(C, packed)]
+pub struct PmtEntry {
+    /// Stream type (`STREAM_TYPE_*`).
+    pub stream_type: u8,
+
+    /// Reserved (3 bits) and PID of the elementary stream (13 bits).
+    pub pid: int::u16be,
+
+    /// Reserved (4 bits) and length of the stream descriptors (12 bits).
+    pub info_length: int::u16be,
+}
+
+/// PES Header
+#[repr(C)]
+pub struct PesHeader {
+    /// Start code prefix, must match `PES_START_CODE`.
+    pub start_code: [u8; 3],
+
+    /// Stream ID (`STREAM_ID_*`).
+    pub stream_id: u8,
+
+    /// Number of bytes following this field, or 0 for unbounded video
+    /// streams.
+    pub length: int::u16be,
+}
+
+/// PES Optional Header
+///
+/// The optional header following the PES header of all streams except
+/// padding, private stream 2, and a few others. It is followed by
+/// `header_length` bytes of optional fields, starting with the timestamps.
+#[repr(C)]
+pub struct PesOptionalHeader {
+    /// Marker `0b10` (2 bits), scrambling control (2 bits), priority,
+    /// data alignment, copyright, and original (1 bit each).
+    pub flags1: u8,
+
+    /// PTS/DTS indicator (2 bits, `PTS_DTS_*`), ESCR, ES rate, DSM trick
+    /// mode, additional copy info, CRC, and extension flags (1 bit each).
+    pub flags2: u8,
+
+    /// Length of the optional fields.
+    pub header_length: u8,
+}
+
+/// Packet Reference
+///
+/// A reference to a packet in a byte slice, as returned by `Packet::parse()`.
+#[derive(Clone, Copy, Debug)]
+pub struct Packet<'data> {
+    /// The entire packet, including the header.
+    pub data: &'data [u8],
+
+    /// The adaptation field, excluding its length byte.
+    pub adaptation: Option<&'data [u8]>,
+
+    /// The payload.
+    pub payload: &'data [u8],
+}
+
+impl PacketHeader {
+    /// Import a packet header from a byte slice
+    ///
+    /// Create a new packet header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 4]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 4], *data);
+            uninit.assume_init()
+        }
+    }
+
+    fn bits(&self, shift: u32, width: u32) -> u32 {
+        (int::ForeignEndian::to_native(self.raw) >> shift) & ((1 << width) - 1)
+    }
+
+    /// Return sync byte
+    pub fn sync(&self) -> u8 {
+        self.bits(24, 8) as u8
+    }
+
+    /// Check transport error indicator
+    pub fn has_error(&self) -> bool {
+        self.bits(23, 1) != 0
+    }
+
+    /// Check payload unit start indicator
+    ///
+    /// If set, a PES packet starts in the payload, or a pointer field
+    /// precedes the PSI sections in the payload.
+    pub fn is_unit_start(&self) -> bool {
+        self.bits(22, 1) != 0
+    }
+
+    /// Check transport priority
+    pub fn has_priority(&self) -> bool {
+        self.bits(21, 1) != 0
+    }
+
+    /// Return PID
+    pub fn pid(&self) -> u16 {
+        self.bits(8, 13) as u16
+    }
+
+    /// Return scrambling control
+    pub fn scrambling(&self) -> u8 {
+        self.bits(6, 2) as u8
+    }
+
+    /// Return adaptation field control (`ADAPTATION_*`)
+    pub fn adaptation(&self) -> u8 {
+        self.bits(4, 2) as u8
+    }
+
+    /// Return continuity counter
+    pub fn continuity(&self) -> u8 {
+        self.bits(0, 4) as u8
+    }
+}
+
+impl SectionHeader {
+    /// Import a section header from a byte slice
+    ///
+    /// Create a new section header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 8]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 8], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Return section length
+    ///
+    /// Return the number of bytes following the length field, including the
+    /// CRC.
+    pub fn section_length(&self) -> usize {
+        (int::ForeignEndian::to_native(self.length) & 0x0fff) as usize
+    }
+
+    /// Return version number
+    pub fn version_number(&self) -> u8 {
+        (self.version >> 1) & 0x1f
+    }
+
+    /// Check current/next indicator
+    ///
+    /// Return whether the table is currently applicable, rather than the
+    /// next table to become valid.
+    pub fn is_current(&self) -> bool {
+        self.version & 0x01 != 0
+    }
+}
+
+impl PatEntry {
+    /// Import a PAT entry from a byte slice
+    ///
+    /// Create a new PAT entry from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 4]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 4], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl PmtHeader {
+    /// Import a PMT header from a byte slice
+    ///
+    /// Create a new PMT header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 4]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 4], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl PmtEntry {
+    /// Import a PMT stream entry from a byte slice
+    ///
+    /// Create a new PMT stream entry from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 5]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 5], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl PesHeader {
+    /// Import a PES header from a byte slice
+    ///
+    /// Create a new PES header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 6]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 6], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Check for an optional header
+    ///
+    /// Return whether a `PesOptionalHeader` follows, based on the stream ID.
+    pub fn has_optional_header(&self) -> bool {
+        !matches!(
+            self.stream_id,
+            STREAM_ID_PROGRAM_STREAM_MAP
+                | STREAM_ID_PADDING
+                | STREAM_ID_PRIVATE_2
+                | STREAM_ID_ECM
+                | STREAM_ID_EMM
+                | STREAM_ID_DSMCC
+                | STREAM_ID_H222_E
+                | STREAM_ID_PROGRAM_STREAM_DIRECTORY
+        )
+    }
+}
+
+impl PesOptionalHeader {
+    /// Import a PES optional header from a byte slice
+    ///
+    /// Create a new PES optional header from data copied from a byte slice.
+    /// No byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 3]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 3], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Return PTS/DTS indicator (`PTS_DTS_*`)
+    pub fn pts_dts(&self) -> u8 {
+        self.flags2 >> 6
+    }
+}
+
+impl<'data> Packet<'data> {
+    /// Parse a packet
+    ///
+    /// Parse the packet at the start of `data`. `None` is returned if the
+    /// sync byte does not match, the packet is truncated, or the adaptation
+    /// field exceeds the packet.
+    pub fn parse(data: &'data [u8]) -> Option<Self> {
+        let data = data.get(..PACKET_SIZE)?;
+        let h = PacketHeader::from_bytes(data[..4].try_into().unwrap());
+        if h.sync() != SYNC {
+            return None;
+        }
+
+        let mut at = 4;
+        let adaptation = if h.adaptation() & ADAPTATION_FIELD != 0 {
+            let length = data[4] as usize;
+            let v = data.get(5..5 + length)?;
+            at = 5 + length;
+            Some(v)
+        } else {
+            None
+        };
+
+        let payload = if h.adaptation() & ADAPTATION_PAYLOAD != 0 {
+            &data[at..]
+        } else {
+            &data[PACKET_SIZE..]
+        };
+
+        Some(Self {
+            data,
+            adaptation,
+            payload,
+        })
+    }
+
+    /// Return packet header
+    pub fn header(&self) -> PacketHeader {
+        PacketHeader::from_bytes(self.data[..4].try_into().unwrap())
+    }
+
+    /// Return PCR
+    ///
+    /// Return the program clock reference of the adaptation field in 27MHz
+    /// units, if present.
+    pub fn pcr(&self) -> Option<u64> {
+        let v = self.adaptation?;
+        if v.first()? & AF_PCR == 0 {
+            return None;
+        }
+
+        Some(pcr_decode(v.get(1..7)?.try_into().unwrap()))
+    }
+}
+
+/// Decode PCR
+///
+/// Decode a 6-byte program clock reference, made of a 33-bit base in 90kHz
+/// units, 6 reserved bits, and a 9-bit extension in 27MHz units. Return the
+/// value in 27MHz units.
+pub fn pcr_decode(data: &[u8; 6]) -> u64 {
+    let v = data.iter().fold(0u64, |acc, b| (acc << 8) | *b as u64);
+    (v >> 15) * 300 + (v & 0x1ff)
+}
+
+/// Encode PCR
+///
+/// Encode a program clock reference in 27MHz units, with the reserved bits
+/// set.
+pub fn pcr_encode(pcr: u64) -> [u8; 6] {
+    let base = (pcr / 300) & 0x1_ffff_ffff;
+    let v = (base << 15) | (0x3f << 9) | (pcr % 300);
+    let b = v.to_be_bytes();
+    [b[2], b[3], b[4], b[5], b[6], b[7]]
+}
+
+/// Decode Timestamp
+///
+/// Decode a 5-byte PTS or DTS, made of a 4-bit prefix (`TIMESTAMP_*`) and
+/// the 33-bit timestamp split into 3 parts, each followed by a marker bit.
+/// Return the prefix and the timestamp, or `None` if a marker bit is not
+/// set.
+pub fn timestamp_decode(data: &[u8; 5]) -> Option<(u8, u64)> {
+    if data[0] & 0x01 == 0 || data[2] & 0x01 == 0 || data[4] & 0x01 == 0 {
+        return None;
+    }
+
+    let v = (((data[0] >> 1) & 0x07) as u64) << 30
+        | (data[1] as u64) << 22
+        | ((data[2] >> 1) as u64) << 15
+        | (data[3] as u64) << 7
+        | (data[4] >> 1) as u64;
+    Some((data[0] >> 4, v))
+}
+
+/// Encode Timestamp
+///
+/// Encode a 33-bit PTS or DTS with the given 4-bit prefix (`TIMESTAMP_*`).
+/// Higher bits of the timestamp are discarded.
+pub fn timestamp_encode(prefix: u8, v: u64) -> [u8; 5] {
+    [
+        (prefix << 4) | (((v >> 30) & 0x07) as u8) << 1 | 0x01,
+        (v >> 22) as u8,
+        (((v >> 15) & 0x7f) as u8) << 1 | 0x01,
+        (v >> 7) as u8,
+        ((v & 0x7f) as u8) << 1 | 0x01,
+    ]
+}
+
+/// Verify Section CRC
+///
+/// Verify the MPEG-2 CRC-32 of a complete PSI section, including the
+/// section header and the trailing CRC.
+pub fn verify_section(section: &[u8]) -> bool {
+    checksum::crc32_msb_update(0xffff_ffff, section) == 0
+}
+
+/// Calculate Section CRC
+///
+/// Calculate the MPEG-2 CRC-32 of a PSI section, excluding the trailing
+/// CRC.
+pub fn section_crc(section: &[u8]) -> u32 {
+    checksum::crc32_msb_update(0xffff_ffff, section)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The start of a packet with a PAT of program 1 with its PMT on PID
+    // 0x1000, padded to the packet size with 0xff.
+    const PAT: [u8; 21] = [
+        0x47, 0x40, 0x00, 0x10, 0x00, 0x00, 0xb0, 0x0d,
+        0x00, 0x01, 0xc1, 0x00, 0x00, 0x00, 0x01, 0xf0,
+        0x00, 0x2a, 0xb1, 0x04, 0xb2,
+    ];
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<PacketHeader>(), 4);
+        assert_eq!(align_of::<PacketHeader>(), 4);
+        assert_eq!(size_of::<SectionHeader>(), 8);
+        assert_eq!(align_of::<SectionHeader>(), 1);
+        assert_eq!(size_of::<PatEntry>(), 4);
+        assert_eq!(size_of::<PmtHeader>(), 4);
+        assert_eq!(size_of::<PmtEntry>(), 5);
+        assert_eq!(size_of::<PesHeader>(), 6);
+        assert_eq!(align_of::<PesHeader>(), 2);
+        assert_eq!(size_of::<PesOptionalHeader>(), 3);
+    }
+
+    // Parse a packet carrying a PAT and verify its CRC.
+    #[test]
+    fn verify_pat() {
+        let mut data = [0xff; PACKET_SIZE];
+        data[..PAT.len()].copy_from_slice(&PAT);
+
+        let p = Packet::parse(&data).unwrap();
+        let h = p.header();
+        assert!(h.is_unit_start());
+        assert_eq!(h.pid(), PID_PAT);
+        assert_eq!(h.adaptation(), ADAPTATION_PAYLOAD);
+        assert_eq!(h.continuity(), 0);
+        assert!(p.adaptation.is_none());
+        assert_eq!(p.payload.len(), PACKET_SIZE - 4);
+
+        let pointer = p.payload[0] as usize;
+        let section = &p.payload[1 + pointer..];
+        let s = SectionHeader::from_bytes(section[..8].try_into().unwrap());
+        assert_eq!(s.table_id, TABLE_PAT);
+        assert_eq!(s.section_length(), 13);
+        assert_eq!(int::ForeignEndian::to_native(s.id), 1);
+        assert_eq!(s.version_number(), 0);
+        assert!(s.is_current());
+
+        let section = &section[..3 + s.section_length()];
+        assert!(verify_section(section));
+        assert_eq!(section_crc(&section[..12]), 0x2ab104b2);
+
+        let e = PatEntry::from_bytes(section[8..12].try_into().unwrap());
+        assert_eq!(int::ForeignEndian::to_native(e.program_number), 1);
+        assert_eq!(int::ForeignEndian::to_native(e.pid) & PID_MASK, 0x1000);
+
+        data[0] = 0x48;
+        assert!(Packet::parse(&data).is_none());
+        assert!(Packet::parse(&PAT).is_none());
+    }
+
+    // Verify PCR and PTS encodings.
+    #[test]
+    fn verify_timestamps() {
+        let mut data = [0xff; PACKET_SIZE];
+        data[..12].copy_from_slice(&[
+            0x47, 0x01, 0x00, 0x30, 0x07, 0x50, 0x00, 0x00,
+            0xaf, 0xc8, 0x7e, 0x05,
+        ]);
+
+        let p = Packet::parse(&data).unwrap();
+        assert_eq!(p.header().pid(), 0x100);
+        assert_eq!(p.adaptation.unwrap().len(), 7);
+        assert_eq!(p.payload.len(), PACKET_SIZE - 12);
+        assert_eq!(p.pcr(), Some(90000 * 300 + 5));
+        assert_eq!(pcr_encode(90000 * 300 + 5), [0x00, 0x00, 0xaf, 0xc8, 0x7e, 0x05]);
+
+        let v = [0x29, 0x8d, 0x15, 0xcf, 0x13];
+        assert_eq!(timestamp_decode(&v), Some((TIMESTAMP_PTS_ONLY, 0x1_2345_6789)));
+        assert_eq!(timestamp_encode(TIMESTAMP_PTS_ONLY, 0x1_2345_6789), v);
+        assert_eq!(timestamp_encode(TIMESTAMP_PTS_ONLY, 90000), [0x21, 0x00, 0x05, 0xbf, 0x21]);
+        assert!(timestamp_decode(&[0x20, 0x00, 0x05, 0xbf, 0x21]).is_none());
+
+        let h = PesHeader::from_bytes(&[0x00, 0x00, 0x01, 0xe0, 0x00, 0x00]);
+        assert_eq!(h.start_code, PES_START_CODE);
+        assert!(h.has_optional_header());
+        let o = PesOptionalHeader::from_bytes(&[0x80, 0x80, 0x05]);
+        assert_eq!(o.pts_dts(), PTS_DTS_PTS);
+    }
+}
@@ -0,0 +1,237 @@
+//! Multiprotocol Label Switching
+//!
+//! MPLS packets carry a stack of 4-byte label stack entries in front of the
+//! payload, following the Ethernet header (`ethernet::ETHERTYPE_MPLS`) or
+//! other encapsulations. Each entry carries a 20-bit label, a 3-bit traffic
+//! class (TC), the bottom-of-stack flag (S), and an 8-bit TTL. The payload
+//! directly follows the entry with the bottom-of-stack flag set (see
+//! `Stack`). Its protocol is not signaled, but implied by the label or the
+//! first nibble of the payload.
+//!
+//! Labels below 16 are reserved for special purposes (`LABEL_*`).
+//!
+//! All multi-byte integers are encoded as big-endian.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::int;
+
+pub const ENTRY_SIZE: usize = 4;
+
+pub const LABEL_IPV4_EXPLICIT_NULL: u32 = 0;
+pub const LABEL_ROUTER_ALERT: u32 = 1;
+pub const LABEL_IPV6_EXPLICIT_NULL: u32 = 2;
+pub const LABEL_IMPLICIT_NULL: u32 = 3;
+pub const LABEL_ENTROPY_LABEL_INDICATOR: u32 = 7;
+pub const LABEL_GAL: u32 = 13;
+pub const LABEL_OAM_ALERT: u32 = 14;
+pub const LABEL_EXTENSION: u32 = 15;
+pub const LABEL_RESERVED_MAX: u32 = 15;
+pub const LABEL_MAX: u32 = 0x000f_ffff;
+
+pub const ENTRY_LABEL_SHIFT: u32 = 12;
+pub const ENTRY_TC_SHIFT: u32 = 9;
+pub const ENTRY_TC_MASK: u32 = 0x0000_0e00;
+pub const ENTRY_S: u32 = 0x0000_0100;
+pub const ENTRY_TTL_MASK: u32 = 0x0000_00ff;
+
+/// Label Stack Entry
+#[repr(C)]
+pub struct Entry {
+    /// Label, traffic class, bottom-of-stack flag, and TTL (see `label()`,
+    /// `tc()`, `bottom()`, and `ttl()`).
+    pub entry: int::u32be,
+}
+
+/// Label Stack Iterator
+///
+/// An iterator over a label stack, yielding entries up to and including the
+/// first one with the bottom-of-stack flag set. Afterwards, `remainder()`
+/// starts at the payload. Iteration also stops at the end of the data, in
+/// which case `is_complete()` tells truncated stacks apart.
+#[derive(Clone, Debug)]
+pub struct Stack<'data> {
+    data: &'data [u8],
+    complete: bool,
+}
+
+impl Entry {
+    /// Import an entry from a byte slice
+    ///
+    /// Create a new entry from data copied from a byte slice. No byte-order
+    /// conversions are applied.
+    pub fn from_bytes(data: &[u8; 4]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 4], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Create an entry from native values
+    pub fn from_native(label: u32, tc: u8, bottom: bool, ttl: u8) -> Self {
+        Self {
+            entry: int::ForeignEndian::from_native(entry(label, tc, bottom, ttl)),
+        }
+    }
+
+    /// Return label
+    pub fn label(&self) -> u32 {
+        int::ForeignEndian::to_native(self.entry) >> ENTRY_LABEL_SHIFT
+    }
+
+    /// Return traffic class
+    pub fn tc(&self) -> u8 {
+        ((int::ForeignEndian::to_native(self.entry) & ENTRY_TC_MASK) >> ENTRY_TC_SHIFT) as u8
+    }
+
+    /// Return bottom-of-stack flag
+    pub fn bottom(&self) -> bool {
+        int::ForeignEndian::to_native(self.entry) & ENTRY_S != 0
+    }
+
+    /// Return time to live
+    pub fn ttl(&self) -> u8 {
+        (int::ForeignEndian::to_native(self.entry) & ENTRY_TTL_MASK) as u8
+    }
+}
+
+impl<'data> Stack<'data> {
+    /// Create label stack iterator
+    ///
+    /// Create a new iterator over the label stack at the start of `data`.
+    pub fn new(data: &'data [u8]) -> Self {
+        Self {
+            data,
+            complete: false,
+        }
+    }
+
+    /// Return remaining data
+    ///
+    /// Return the data that has not been consumed by the iterator.
+    pub fn remainder(&self) -> &'data [u8] {
+        self.data
+    }
+
+    /// Check for the bottom of the stack
+    ///
+    /// Return whether the iterator yielded the entry with the
+    /// bottom-of-stack flag set.
+    pub fn is_complete(&self) -> bool {
+        self.complete
+    }
+}
+
+impl<'data> Iterator for Stack<'data> {
+    type Item = Entry;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.complete || self.data.len() < ENTRY_SIZE {
+            return None;
+        }
+
+        let entry = Entry::from_bytes(self.data[..ENTRY_SIZE].try_into().unwrap());
+        self.data = &self.data[ENTRY_SIZE..];
+        self.complete = entry.bottom();
+        Some(entry)
+    }
+}
+
+/// Encode label stack entry
+///
+/// Combine the label, the traffic class, the bottom-of-stack flag, and the
+/// TTL. Excess bits of `label` and `tc` are discarded.
+pub fn entry(label: u32, tc: u8, bottom: bool, ttl: u8) -> u32 {
+    ((label & LABEL_MAX) << ENTRY_LABEL_SHIFT)
+        | ((tc as u32 & 0x7) << ENTRY_TC_SHIFT)
+        | if bottom { ENTRY_S } else { 0 }
+        | ttl as u32
+}
+
+/// Return payload of a labeled packet
+///
+/// Skip the label stack at the start of `data` and return the payload, or
+/// `None` if the stack lacks its bottom entry.
+pub fn payload(data: &[u8]) -> Option<&[u8]> {
+    let mut stack = Stack::new(data);
+    for _ in stack.by_ref() {}
+    if stack.is_complete() {
+        Some(stack.remainder())
+    } else {
+        None
+    }
+}
+
+/// Check for reserved labels
+pub fn is_reserved(label: u32) -> bool {
+    label <= LABEL_RESERVED_MAX
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A two-entry stack with an IPv4 payload.
+    const PACKET: [u8; 12] = [
+        0x00, 0x3e, 0x80, 0x40, 0x00, 0x06, 0x41, 0x3f,
+        0x45, 0x00, 0x00, 0x14,
+    ];
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<Entry>(), ENTRY_SIZE);
+        assert_eq!(align_of::<Entry>(), 4);
+    }
+
+    // Walk a label stack and decode its entries.
+    #[test]
+    fn verify_stack() {
+        let mut stack = Stack::new(&PACKET);
+        let e = stack.next().unwrap();
+        assert_eq!(e.label(), 1000);
+        assert_eq!(e.tc(), 0);
+        assert!(!e.bottom());
+        assert_eq!(e.ttl(), 64);
+        assert!(!stack.is_complete());
+
+        let e = stack.next().unwrap();
+        assert_eq!(e.label(), 100);
+        assert_eq!(e.tc(), 0);
+        assert!(e.bottom());
+        assert_eq!(e.ttl(), 63);
+        assert!(stack.next().is_none());
+        assert!(stack.is_complete());
+        assert_eq!(stack.remainder()[0] >> 4, 4);
+
+        assert_eq!(payload(&PACKET), Some(&PACKET[8..]));
+        assert_eq!(payload(&PACKET[..7]), None);
+        assert_eq!(Stack::new(&PACKET[..7]).count(), 1);
+    }
+
+    // Encode entries and classify labels.
+    #[test]
+    fn verify_entry() {
+        assert_eq!(entry(1000, 0, false, 64), 0x003e_8040);
+        assert_eq!(entry(100, 0, true, 63).to_be_bytes(), PACKET[4..8]);
+        assert_eq!(entry(LABEL_MAX + 1, 0xff, false, 0), ENTRY_TC_MASK);
+
+        let e = Entry::from_native(LABEL_IPV6_EXPLICIT_NULL, 5, true, 255);
+        assert_eq!(e.label(), LABEL_IPV6_EXPLICIT_NULL);
+        assert_eq!(e.tc(), 5);
+        assert!(e.bottom());
+        assert_eq!(e.ttl(), 255);
+
+        assert!(is_reserved(LABEL_GAL));
+        assert!(!is_reserved(16));
+    }
+}
@@ -0,0 +1,635 @@
+//! Message Queuing Telemetry Transport
+//!
+//! MQTT control packets start with a fixed header (see `FixedHeader`),
+//! carrying the packet type and type-specific flags in the first byte,
+//! followed by the remaining length of the packet as variable-length
+//! integer (see `decode_length()`). The remaining data is a type-specific
+//! variable header, followed by a payload.
+//!
+//! Strings and binary data are prefixed with a 2-byte length. Version 5.0
+//! (`LEVEL_5`) adds properties to most variable headers (see `Properties`),
+//! prefixed with their total length as variable-length integer, and replaces
+//! the return codes of version 3.1.1 (`LEVEL_3_1_1`) with reason codes
+//! (`REASON_*`). The protocol level is negotiated by CONNECT, and needed to
+//! parse all later packets.
+//!
+//! All multi-byte integers are encoded as big-endian.
+
+use crate::specs::varint;
+
+/// Maximum Size of Remaining Lengths
+pub const LENGTH_SIZE_MAX: usize = 4;
+
+/// Maximum Remaining Length
+pub const LENGTH_MAX: usize = 268_435_455;
+
+pub const PROTOCOL_NAME: [u8; 4] = *b"MQTT";
+pub const LEVEL_3_1_1: u8 = 4;
+pub const LEVEL_5: u8 = 5;
+
+pub const TYPE_SHIFT: u32 = 4;
+pub const FLAGS_MASK: u8 = 0x0f;
+
+pub const TYPE_CONNECT: u8 = 1;
+pub const TYPE_CONNACK: u8 = 2;
+pub const TYPE_PUBLISH: u8 = 3;
+pub const TYPE_PUBACK: u8 = 4;
+pub const TYPE_PUBREC: u8 = 5;
+pub const TYPE_PUBREL: u8 = 6;
+pub const TYPE_PUBCOMP: u8 = 7;
+pub const TYPE_SUBSCRIBE: u8 = 8;
+pub const TYPE_SUBACK: u8 = 9;
+pub const TYPE_UNSUBSCRIBE: u8 = 10;
+pub const TYPE_UNSUBACK: u8 = 11;
+pub const TYPE_PINGREQ: u8 = 12;
+pub const TYPE_PINGRESP: u8 = 13;
+pub const TYPE_DISCONNECT: u8 = 14;
+pub const TYPE_AUTH: u8 = 15;
+
+/// Fixed Header Flags of PUBREL, SUBSCRIBE, and UNSUBSCRIBE
+pub const FLAGS_REQUIRED: u8 = 0x02;
+
+pub const PUBLISH_FLAG_DUP: u8 = 0x08;
+pub const PUBLISH_QOS_MASK: u8 = 0x06;
+pub const PUBLISH_QOS_SHIFT: u32 = 1;
+pub const PUBLISH_FLAG_RETAIN: u8 = 0x01;
+
+pub const CONNECT_FLAG_USERNAME: u8 = 0x80;
+pub const CONNECT_FLAG_PASSWORD: u8 = 0x40;
+pub const CONNECT_FLAG_WILL_RETAIN: u8 = 0x20;
+pub const CONNECT_WILL_QOS_MASK: u8 = 0x18;
+pub const CONNECT_WILL_QOS_SHIFT: u32 = 3;
+pub const CONNECT_FLAG_WILL: u8 = 0x04;
+pub const CONNECT_FLAG_CLEAN_START: u8 = 0x02;
+/* reserved: 0x01 */
+
+pub const CONNACK_FLAG_SESSION_PRESENT: u8 = 0x01;
+
+pub const CONNACK_ACCEPTED: u8 = 0x00;
+pub const CONNACK_UNACCEPTABLE_PROTOCOL_VERSION: u8 = 0x01;
+pub const CONNACK_IDENTIFIER_REJECTED: u8 = 0x02;
+pub const CONNACK_SERVER_UNAVAILABLE: u8 = 0x03;
+pub const CONNACK_BAD_USERNAME_OR_PASSWORD: u8 = 0x04;
+pub const CONNACK_NOT_AUTHORIZED: u8 = 0x05;
+
+pub const SUBSCRIBE_QOS_MASK: u8 = 0x03;
+pub const SUBSCRIBE_FLAG_NO_LOCAL: u8 = 0x04;
+pub const SUBSCRIBE_FLAG_RETAIN_AS_PUBLISHED: u8 = 0x08;
+pub const SUBSCRIBE_RETAIN_HANDLING_MASK: u8 = 0x30;
+pub const SUBSCRIBE_RETAIN_HANDLING_SHIFT: u32 = 4;
+
+/// Return Code of Failed Subscriptions in Version 3.1.1
+pub const SUBACK_FAILURE: u8 = 0x80;
+
+pub const REASON_SUCCESS: u8 = 0x00;
+pub const REASON_GRANTED_QOS_1: u8 = 0x01;
+pub const REASON_GRANTED_QOS_2: u8 = 0x02;
+pub const REASON_DISCONNECT_WITH_WILL: u8 = 0x04;
+pub const REASON_NO_MATCHING_SUBSCRIBERS: u8 = 0x10;
+pub const REASON_NO_SUBSCRIPTION_EXISTED: u8 = 0x11;
+pub const REASON_CONTINUE_AUTHENTICATION: u8 = 0x18;
+pub const REASON_REAUTHENTICATE: u8 = 0x19;
+pub const REASON_UNSPECIFIED_ERROR: u8 = 0x80;
+pub const REASON_MALFORMED_PACKET: u8 = 0x81;
+pub const REASON_PROTOCOL_ERROR: u8 = 0x82;
+pub const REASON_IMPLEMENTATION_SPECIFIC_ERROR: u8 = 0x83;
+pub const REASON_UNSUPPORTED_PROTOCOL_VERSION: u8 = 0x84;
+pub const REASON_CLIENT_IDENTIFIER_NOT_VALID: u8 = 0x85;
+pub const REASON_BAD_USERNAME_OR_PASSWORD: u8 = 0x86;
+pub const REASON_NOT_AUTHORIZED: u8 = 0x87;
+pub const REASON_SERVER_UNAVAILABLE: u8 = 0x88;
+pub const REASON_SERVER_BUSY: u8 = 0x89;
+pub const REASON_BANNED: u8 = 0x8a;
+pub const REASON_SERVER_SHUTTING_DOWN: u8 = 0x8b;
+pub const REASON_BAD_AUTHENTICATION_METHOD: u8 = 0x8c;
+pub const REASON_KEEP_ALIVE_TIMEOUT: u8 = 0x8d;
+pub const REASON_SESSION_TAKEN_OVER: u8 = 0x8e;
+pub const REASON_TOPIC_FILTER_INVALID: u8 = 0x8f;
+pub const REASON_TOPIC_NAME_INVALID: u8 = 0x90;
+pub const REASON_PACKET_IDENTIFIER_IN_USE: u8 = 0x91;
+pub const REASON_PACKET_IDENTIFIER_NOT_FOUND: u8 = 0x92;
+pub const REASON_RECEIVE_MAXIMUM_EXCEEDED: u8 = 0x93;
+pub const REASON_TOPIC_ALIAS_INVALID: u8 = 0x94;
+pub const REASON_PACKET_TOO_LARGE: u8 = 0x95;
+pub const REASON_MESSAGE_RATE_TOO_HIGH: u8 = 0x96;
+pub const REASON_QUOTA_EXCEEDED: u8 = 0x97;
+pub const REASON_ADMINISTRATIVE_ACTION: u8 = 0x98;
+pub const REASON_PAYLOAD_FORMAT_INVALID: u8 = 0x99;
+pub const REASON_RETAIN_NOT_SUPPORTED: u8 = 0x9a;
+pub const REASON_QOS_NOT_SUPPORTED: u8 = 0x9b;
+pub const REASON_USE_ANOTHER_SERVER: u8 = 0x9c;
+pub const REASON_SERVER_MOVED: u8 = 0x9d;
+pub const REASON_SHARED_SUBSCRIPTIONS_NOT_SUPPORTED: u8 = 0x9e;
+pub const REASON_CONNECTION_RATE_EXCEEDED: u8 = 0x9f;
+pub const REASON_MAXIMUM_CONNECT_TIME: u8 = 0xa0;
+pub const REASON_SUBSCRIPTION_IDENTIFIERS_NOT_SUPPORTED: u8 = 0xa1;
+pub const REASON_WILDCARD_SUBSCRIPTIONS_NOT_SUPPORTED: u8 = 0xa2;
+
+pub const PROPERTY_PAYLOAD_FORMAT_INDICATOR: u8 = 0x01;
+pub const PROPERTY_MESSAGE_EXPIRY_INTERVAL: u8 = 0x02;
+pub const PROPERTY_CONTENT_TYPE: u8 = 0x03;
+pub const PROPERTY_RESPONSE_TOPIC: u8 = 0x08;
+pub const PROPERTY_CORRELATION_DATA: u8 = 0x09;
+pub const PROPERTY_SUBSCRIPTION_IDENTIFIER: u8 = 0x0b;
+pub const PROPERTY_SESSION_EXPIRY_INTERVAL: u8 = 0x11;
+pub const PROPERTY_ASSIGNED_CLIENT_IDENTIFIER: u8 = 0x12;
+pub const PROPERTY_SERVER_KEEP_ALIVE: u8 = 0x13;
+pub const PROPERTY_AUTHENTICATION_METHOD: u8 = 0x15;
+pub const PROPERTY_AUTHENTICATION_DATA: u8 = 0x16;
+pub const PROPERTY_REQUEST_PROBLEM_INFORMATION: u8 = 0x17;
+pub const PROPERTY_WILL_DELAY_INTERVAL: u8 = 0x18;
+pub const PROPERTY_REQUEST_RESPONSE_INFORMATION: u8 = 0x19;
+pub const PROPERTY_RESPONSE_INFORMATION: u8 = 0x1a;
+pub const PROPERTY_SERVER_REFERENCE: u8 = 0x1c;
+pub const PROPERTY_REASON_STRING: u8 = 0x1f;
+pub const PROPERTY_RECEIVE_MAXIMUM: u8 = 0x21;
+pub const PROPERTY_TOPIC_ALIAS_MAXIMUM: u8 = 0x22;
+pub const PROPERTY_TOPIC_ALIAS: u8 = 0x23;
+pub const PROPERTY_MAXIMUM_QOS: u8 = 0x24;
+pub const PROPERTY_RETAIN_AVAILABLE: u8 = 0x25;
+pub const PROPERTY_USER_PROPERTY: u8 = 0x26;
+pub const PROPERTY_MAXIMUM_PACKET_SIZE: u8 = 0x27;
+pub const PROPERTY_WILDCARD_SUBSCRIPTION_AVAILABLE: u8 = 0x28;
+pub const PROPERTY_SUBSCRIPTION_IDENTIFIER_AVAILABLE: u8 = 0x29;
+pub const PROPERTY_SHARED_SUBSCRIPTION_AVAILABLE: u8 = 0x2a;
+
+/// Fixed Header
+#[derive(Clone, Copy, Debug)]
+pub struct FixedHeader {
+    /// Packet type (`TYPE_*`).
+    pub packet_type: u8,
+
+    /// Type-specific flags in the lower 4 bits.
+    pub flags: u8,
+
+    /// Number of bytes following the fixed header.
+    pub remaining_length: usize,
+
+    /// Size of the fixed header in bytes.
+    pub size: usize,
+}
+
+/// CONNECT Variable Header Reference
+#[derive(Clone, Copy, Debug)]
+pub struct Connect<'data> {
+    /// Protocol name, usually `PROTOCOL_NAME`.
+    pub protocol_name: &'data [u8],
+
+    /// Protocol level (`LEVEL_*`).
+    pub level: u8,
+
+    /// Connect flags (`CONNECT_FLAG_*`).
+    pub flags: u8,
+
+    /// Keep-alive interval in seconds, or 0 if disabled.
+    pub keep_alive: u16,
+
+    /// Encoded properties, if the protocol level has properties.
+    pub properties: Option<&'data [u8]>,
+
+    /// Payload, starting with the client identifier.
+    pub payload: &'data [u8],
+}
+
+/// CONNACK Variable Header Reference
+#[derive(Clone, Copy, Debug)]
+pub struct ConnAck<'data> {
+    /// Acknowledge flags (`CONNACK_FLAG_*`).
+    pub flags: u8,
+
+    /// Return code (`CONNACK_*`), or reason code (`REASON_*`) for
+    /// `LEVEL_5`.
+    pub code: u8,
+
+    /// Encoded properties, if the protocol level has properties.
+    pub properties: Option<&'data [u8]>,
+}
+
+/// PUBLISH Variable Header Reference
+#[derive(Clone, Copy, Debug)]
+pub struct Publish<'data> {
+    /// Topic name, which can be empty if a topic alias is used.
+    pub topic: &'data [u8],
+
+    /// Packet identifier, present only for QoS levels above 0.
+    pub packet_id: Option<u16>,
+
+    /// Encoded properties, if the protocol level has properties.
+    pub properties: Option<&'data [u8]>,
+
+    /// Application message.
+    pub payload: &'data [u8],
+}
+
+/// SUBSCRIBE Variable Header Reference
+#[derive(Clone, Copy, Debug)]
+pub struct Subscribe<'data> {
+    /// Packet identifier.
+    pub packet_id: u16,
+
+    /// Encoded properties, if the protocol level has properties.
+    pub properties: Option<&'data [u8]>,
+
+    /// Topic filters and their options (see `Subscriptions`).
+    pub payload: &'data [u8],
+}
+
+/// Property Iterator
+///
+/// An iterator over encoded properties, yielding the property identifier and
+/// its encoded value. Values with length prefixes, including both strings of
+/// `PROPERTY_USER_PROPERTY`, are returned with their prefixes. Iteration
+/// stops at the end of the data, or at the first truncated or unknown
+/// property, since the size of unknown values cannot be determined.
+#[derive(Clone, Debug)]
+pub struct Properties<'data> {
+    data: &'data [u8],
+}
+
+/// Subscription Iterator
+///
+/// An iterator over the payload of a SUBSCRIBE packet, yielding the topic
+/// filter and the subscription options (`SUBSCRIBE_*`, only the QoS for
+/// `LEVEL_3_1_1`). Iteration stops at the end of the data, or at the first
+/// truncated subscription.
+#[derive(Clone, Debug)]
+pub struct Subscriptions<'data> {
+    data: &'data [u8],
+}
+
+impl FixedHeader {
+    /// Parse a fixed header
+    ///
+    /// Parse the fixed header at the start of `data`. Return `None` if the
+    /// data is truncated, or the remaining length is invalid. The remaining
+    /// data itself need not be present.
+    pub fn parse(data: &[u8]) -> Option<Self> {
+        let first = *data.first()?;
+        let (remaining_length, n) = decode_length(&data[1..])?;
+
+        Some(Self {
+            packet_type: first >> TYPE_SHIFT,
+            flags: first & FLAGS_MASK,
+            remaining_length,
+            size: 1 + n,
+        })
+    }
+
+    /// Return QoS level of PUBLISH packets
+    pub fn qos(&self) -> u8 {
+        (self.flags & PUBLISH_QOS_MASK) >> PUBLISH_QOS_SHIFT
+    }
+}
+
+impl<'data> Connect<'data> {
+    /// Parse a CONNECT variable header
+    ///
+    /// Parse the remaining data of a CONNECT packet. Properties are
+    /// expected if the embedded protocol level is `LEVEL_5` or above.
+    /// Return `None` if the data is truncated.
+    pub fn parse(data: &'data [u8]) -> Option<Self> {
+        let mut o = 0;
+        let protocol_name = string_at(data, &mut o)?;
+        let level = *data.get(o)?;
+        let flags = *data.get(o + 1)?;
+        let keep_alive = u16::from_be_bytes([*data.get(o + 2)?, *data.get(o + 3)?]);
+        o += 4;
+        let properties = properties_at(data, &mut o, level)?;
+
+        Some(Self {
+            protocol_name,
+            level,
+            flags,
+            keep_alive,
+            properties,
+            payload: &data[o..],
+        })
+    }
+
+    /// Return QoS level of the will message
+    pub fn will_qos(&self) -> u8 {
+        (self.flags & CONNECT_WILL_QOS_MASK) >> CONNECT_WILL_QOS_SHIFT
+    }
+}
+
+impl<'data> ConnAck<'data> {
+    /// Parse a CONNACK variable header
+    ///
+    /// Parse the remaining data of a CONNACK packet of the given protocol
+    /// level. Return `None` if the data is truncated.
+    pub fn parse(data: &'data [u8], level: u8) -> Option<Self> {
+        let mut o = 2;
+        let flags = *data.first()?;
+        let code = *data.get(1)?;
+        let properties = properties_at(data, &mut o, level)?;
+
+        Some(Self {
+            flags,
+            code,
+            properties,
+        })
+    }
+}
+
+impl<'data> Publish<'data> {
+    /// Parse a PUBLISH variable header
+    ///
+    /// Parse the remaining data of a PUBLISH packet with the given fixed
+    /// header flags and protocol level. Return `None` if the data is
+    /// truncated, or the QoS level is invalid.
+    pub fn parse(data: &'data [u8], flags: u8, level: u8) -> Option<Self> {
+        let mut o = 0;
+        let topic = string_at(data, &mut o)?;
+        let packet_id = match (flags & PUBLISH_QOS_MASK) >> PUBLISH_QOS_SHIFT {
+            0 => None,
+            1 | 2 => {
+                let v = u16::from_be_bytes([*data.get(o)?, *data.get(o + 1)?]);
+                o += 2;
+                Some(v)
+            },
+            _ => return None,
+        };
+        let properties = properties_at(data, &mut o, level)?;
+
+        Some(Self {
+            topic,
+            packet_id,
+            properties,
+            payload: &data[o..],
+        })
+    }
+}
+
+impl<'data> Subscribe<'data> {
+    /// Parse a SUBSCRIBE variable header
+    ///
+    /// Parse the remaining data of a SUBSCRIBE packet of the given protocol
+    /// level. Return `None` if the data is truncated.
+    pub fn parse(data: &'data [u8], level: u8) -> Option<Self> {
+        let mut o = 2;
+        let packet_id = u16::from_be_bytes([*data.first()?, *data.get(1)?]);
+        let properties = properties_at(data, &mut o, level)?;
+
+        Some(Self {
+            packet_id,
+            properties,
+            payload: &data[o..],
+        })
+    }
+}
+
+impl<'data> Properties<'data> {
+    /// Create property iterator
+    ///
+    /// Create a new iterator over the encoded properties in `data`, without
+    /// the property length prefix.
+    pub fn new(data: &'data [u8]) -> Self {
+        Self {
+            data,
+        }
+    }
+
+    /// Return remaining data
+    ///
+    /// Return the data that has not been consumed by the iterator.
+    pub fn remainder(&self) -> &'data [u8] {
+        self.data
+    }
+}
+
+impl<'data> Iterator for Properties<'data> {
+    type Item = (u8, &'data [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let id = *self.data.first()?;
+        let v = &self.data[1..];
+        let n = match id {
+            PROPERTY_PAYLOAD_FORMAT_INDICATOR
+            | PROPERTY_REQUEST_PROBLEM_INFORMATION
+            | PROPERTY_REQUEST_RESPONSE_INFORMATION
+            | PROPERTY_MAXIMUM_QOS
+            | PROPERTY_RETAIN_AVAILABLE
+            | PROPERTY_WILDCARD_SUBSCRIPTION_AVAILABLE
+            | PROPERTY_SUBSCRIPTION_IDENTIFIER_AVAILABLE
+            | PROPERTY_SHARED_SUBSCRIPTION_AVAILABLE => 1,
+            PROPERTY_SERVER_KEEP_ALIVE
+            | PROPERTY_RECEIVE_MAXIMUM
+            | PROPERTY_TOPIC_ALIAS_MAXIMUM
+            | PROPERTY_TOPIC_ALIAS => 2,
+            PROPERTY_MESSAGE_EXPIRY_INTERVAL
+            | PROPERTY_SESSION_EXPIRY_INTERVAL
+            | PROPERTY_WILL_DELAY_INTERVAL
+            | PROPERTY_MAXIMUM_PACKET_SIZE => 4,
+            PROPERTY_SUBSCRIPTION_IDENTIFIER => decode_length(v)?.1,
+            PROPERTY_USER_PROPERTY => {
+                let mut o = 0;
+                string_at(v, &mut o)?;
+                string_at(v, &mut o)?;
+                o
+            },
+            PROPERTY_CONTENT_TYPE
+            | PROPERTY_RESPONSE_TOPIC
+            | PROPERTY_CORRELATION_DATA
+            | PROPERTY_ASSIGNED_CLIENT_IDENTIFIER
+            | PROPERTY_AUTHENTICATION_METHOD
+            | PROPERTY_AUTHENTICATION_DATA
+            | PROPERTY_RESPONSE_INFORMATION
+            | PROPERTY_SERVER_REFERENCE
+            | PROPERTY_REASON_STRING => {
+                let mut o = 0;
+                string_at(v, &mut o)?;
+                o
+            },
+            _ => return None,
+        };
+
+        let value = v.get(..n)?;
+        self.data = &v[n..];
+        Some((id, value))
+    }
+}
+
+impl<'data> Subscriptions<'data> {
+    /// Create subscription iterator
+    ///
+    /// Create a new iterator over the subscriptions in the payload of a
+    /// SUBSCRIBE packet.
+    pub fn new(data: &'data [u8]) -> Self {
+        Self {
+            data,
+        }
+    }
+
+    /// Return remaining data
+    ///
+    /// Return the data that has not been consumed by the iterator.
+    pub fn remainder(&self) -> &'data [u8] {
+        self.data
+    }
+}
+
+impl<'data> Iterator for Subscriptions<'data> {
+    type Item = (&'data [u8], u8);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut o = 0;
+        let filter = string_at(self.data, &mut o)?;
+        let options = *self.data.get(o)?;
+        self.data = &self.data[o + 1..];
+        Some((filter, options))
+    }
+}
+
+// Return the string or binary data at `o` and advance `o` past it.
+fn string_at<'data>(data: &'data [u8], o: &mut usize) -> Option<&'data [u8]> {
+    let n = u16::from_be_bytes([*data.get(*o)?, *data.get(*o + 1)?]) as usize;
+    let v = data.get(*o + 2..*o + 2 + n)?;
+    *o += 2 + n;
+    Some(v)
+}
+
+// Return the properties at `o` and advance `o` past them, if the protocol
+// level has properties. The outer `None` signals truncation.
+fn properties_at<'data>(data: &'data [u8], o: &mut usize, level: u8) -> Option<Option<&'data [u8]>> {
+    if level < LEVEL_5 {
+        return Some(None);
+    }
+
+    let (n, size) = decode_length(data.get(*o..)?)?;
+    let v = data.get(*o + size..*o + size + n)?;
+    *o += size + n;
+    Some(Some(v))
+}
+
+/// Decode remaining length
+///
+/// Decode the variable-length integer at the start of `data`, as used for
+/// remaining lengths, property lengths, and subscription identifiers. It is
+/// encoded as LEB128 of at most `LENGTH_SIZE_MAX` bytes. Return the value
+/// and the number of bytes consumed, or `None` if the data is truncated or
+/// the integer is too long.
+pub fn decode_length(data: &[u8]) -> Option<(usize, usize)> {
+    let (v, n) = varint::leb128_decode(&data[..data.len().min(LENGTH_SIZE_MAX)])?;
+    Some((v as usize, n))
+}
+
+/// Encode remaining length
+///
+/// Encode `v` as variable-length integer (see `decode_length()`). Return the
+/// number of bytes written, or `None` if `v` exceeds `LENGTH_MAX` or the
+/// buffer is too small.
+pub fn encode_length(v: usize, out: &mut [u8]) -> Option<usize> {
+    if v > LENGTH_MAX {
+        return None;
+    }
+
+    varint::leb128_encode(v as u64, out)
+}
+
+/// Find property
+///
+/// Return the encoded value of the first property with the given identifier
+/// in `data`.
+pub fn find_property(data: &[u8], id: u8) -> Option<&[u8]> {
+    Properties::new(data).find(|v| v.0 == id).map(|v| v.1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A version 5.0 CONNECT packet with a session expiry interval and an
+    // empty client identifier.
+    const CONNECT: [u8; 20] = [
+        0x10, 0x12, 0x00, 0x04, 0x4d, 0x51, 0x54, 0x54,
+        0x05, 0x02, 0x00, 0x3c, 0x05, 0x11, 0x00, 0x00,
+        0x00, 0x78, 0x00, 0x00,
+    ];
+
+    // Decode fixed headers and remaining lengths of all sizes.
+    #[test]
+    fn verify_length() {
+        let h = FixedHeader::parse(&[0x32, 0xc1, 0x02]).unwrap();
+        assert_eq!(h.packet_type, TYPE_PUBLISH);
+        assert_eq!(h.qos(), 1);
+        assert_eq!(h.remaining_length, 321);
+        assert_eq!(h.size, 3);
+
+        assert_eq!(decode_length(&[0x00]), Some((0, 1)));
+        assert_eq!(decode_length(&[0xff, 0xff, 0xff, 0x7f]), Some((LENGTH_MAX, 4)));
+        assert_eq!(decode_length(&[0xff, 0xff, 0xff, 0xff, 0x01]), None);
+        assert!(FixedHeader::parse(&[0x30, 0x80]).is_none());
+
+        let mut buf = [0; 4];
+        assert_eq!(encode_length(321, &mut buf), Some(2));
+        assert_eq!(buf[..2], [0xc1, 0x02]);
+        assert_eq!(encode_length(LENGTH_MAX + 1, &mut buf), None);
+    }
+
+    // Parse CONNECT packets of both protocol levels.
+    #[test]
+    fn verify_connect() {
+        let h = FixedHeader::parse(&CONNECT).unwrap();
+        assert_eq!(h.packet_type, TYPE_CONNECT);
+        assert_eq!(h.size + h.remaining_length, CONNECT.len());
+
+        let c = Connect::parse(&CONNECT[h.size..]).unwrap();
+        assert_eq!(c.protocol_name, PROTOCOL_NAME);
+        assert_eq!(c.level, LEVEL_5);
+        assert_eq!(c.flags, CONNECT_FLAG_CLEAN_START);
+        assert_eq!(c.keep_alive, 60);
+        assert_eq!(c.will_qos(), 0);
+        let p = c.properties.unwrap();
+        assert_eq!(find_property(p, PROPERTY_SESSION_EXPIRY_INTERVAL), Some(&[0, 0, 0, 0x78][..]));
+        assert_eq!(c.payload, &[0x00, 0x00]);
+
+        let data = [
+            0x00, 0x04, 0x4d, 0x51, 0x54, 0x54, 0x04, 0x02,
+            0x00, 0x3c, 0x00, 0x03, 0x61, 0x62, 0x63,
+        ];
+        let c = Connect::parse(&data).unwrap();
+        assert_eq!(c.level, LEVEL_3_1_1);
+        assert!(c.properties.is_none());
+        assert_eq!(c.payload, &[0x00, 0x03, 0x61, 0x62, 0x63]);
+        assert!(Connect::parse(&CONNECT[2..16]).is_none());
+
+        let a = ConnAck::parse(&[0x01, 0x00, 0x03, 0x24, 0x01, 0x25], LEVEL_5).unwrap();
+        assert_eq!(a.flags, CONNACK_FLAG_SESSION_PRESENT);
+        assert_eq!(a.code, REASON_SUCCESS);
+        let mut props = Properties::new(a.properties.unwrap());
+        assert_eq!(props.next(), Some((PROPERTY_MAXIMUM_QOS, &[0x01][..])));
+        assert!(props.next().is_none());
+        assert_eq!(props.remainder(), &[0x25]);
+    }
+
+    // Parse PUBLISH and SUBSCRIBE packets.
+    #[test]
+    fn verify_publish_subscribe() {
+        let data = [
+            0x00, 0x03, 0x61, 0x2f, 0x62, 0x00, 0x0a, 0x0a,
+            0x26, 0x00, 0x01, 0x6b, 0x00, 0x01, 0x76, 0x23,
+            0x00, 0x07, 0x68, 0x69,
+        ];
+        let p = Publish::parse(&data, 0x02, LEVEL_5).unwrap();
+        assert_eq!(p.topic, b"a/b");
+        assert_eq!(p.packet_id, Some(10));
+        let mut props = Properties::new(p.properties.unwrap());
+        assert_eq!(props.next(), Some((PROPERTY_USER_PROPERTY, &data[9..15])));
+        assert_eq!(props.next(), Some((PROPERTY_TOPIC_ALIAS, &[0x00, 0x07][..])));
+        assert!(props.next().is_none());
+        assert_eq!(p.payload, b"hi");
+
+        let p = Publish::parse(&data[..5], 0x00, LEVEL_3_1_1).unwrap();
+        assert_eq!(p.packet_id, None);
+        assert!(p.payload.is_empty());
+        assert!(Publish::parse(&data, 0x06, LEVEL_5).is_none());
+
+        let data = [0x00, 0x01, 0x00, 0x03, 0x61, 0x2f, 0x23, 0x01, 0x00, 0x01, 0x62, 0x02];
+        let s = Subscribe::parse(&data, LEVEL_3_1_1).unwrap();
+        assert_eq!(s.packet_id, 1);
+        let mut subs = Subscriptions::new(s.payload);
+        assert_eq!(subs.next(), Some((&b"a/#"[..], 1)));
+        assert_eq!(subs.next(), Some((&b"b"[..], 2)));
+        assert!(subs.next().is_none());
+    }
+}
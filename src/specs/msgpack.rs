@@ -0,0 +1,297 @@
+//! MessagePack
+//!
+//! MessagePack serializes each value as a format byte (`FORMAT_*`),
+//! optionally followed by a length, an extension type, and the payload. The
+//! fix formats embed small integers, or the length of short strings, arrays,
+//! and maps in the format byte. Array and map headers are followed by their
+//! elements, and key-value pairs, respectively.
+//!
+//! Extensions carry an application-defined type, where negative types are
+//! reserved for predefined extensions. The timestamp extension
+//! (`EXT_TIMESTAMP`) stores seconds and nanoseconds since the Unix epoch in
+//! 4, 8, or 12 bytes (see `timestamp()`).
+//!
+//! All multi-byte integers are encoded as big-endian.
+
+pub const FORMAT_POSITIVE_FIXINT: u8 = 0x00;
+pub const FORMAT_FIXMAP: u8 = 0x80;
+pub const FORMAT_FIXARRAY: u8 = 0x90;
+pub const FORMAT_FIXSTR: u8 = 0xa0;
+pub const FORMAT_NIL: u8 = 0xc0;
+/* reserved: 0xc1 */
+pub const FORMAT_FALSE: u8 = 0xc2;
+pub const FORMAT_TRUE: u8 = 0xc3;
+pub const FORMAT_BIN8: u8 = 0xc4;
+pub const FORMAT_BIN16: u8 = 0xc5;
+pub const FORMAT_BIN32: u8 = 0xc6;
+pub const FORMAT_EXT8: u8 = 0xc7;
+pub const FORMAT_EXT16: u8 = 0xc8;
+pub const FORMAT_EXT32: u8 = 0xc9;
+pub const FORMAT_FLOAT32: u8 = 0xca;
+pub const FORMAT_FLOAT64: u8 = 0xcb;
+pub const FORMAT_UINT8: u8 = 0xcc;
+pub const FORMAT_UINT16: u8 = 0xcd;
+pub const FORMAT_UINT32: u8 = 0xce;
+pub const FORMAT_UINT64: u8 = 0xcf;
+pub const FORMAT_INT8: u8 = 0xd0;
+pub const FORMAT_INT16: u8 = 0xd1;
+pub const FORMAT_INT32: u8 = 0xd2;
+pub const FORMAT_INT64: u8 = 0xd3;
+pub const FORMAT_FIXEXT1: u8 = 0xd4;
+pub const FORMAT_FIXEXT2: u8 = 0xd5;
+pub const FORMAT_FIXEXT4: u8 = 0xd6;
+pub const FORMAT_FIXEXT8: u8 = 0xd7;
+pub const FORMAT_FIXEXT16: u8 = 0xd8;
+pub const FORMAT_STR8: u8 = 0xd9;
+pub const FORMAT_STR16: u8 = 0xda;
+pub const FORMAT_STR32: u8 = 0xdb;
+pub const FORMAT_ARRAY16: u8 = 0xdc;
+pub const FORMAT_ARRAY32: u8 = 0xdd;
+pub const FORMAT_MAP16: u8 = 0xde;
+pub const FORMAT_MAP32: u8 = 0xdf;
+pub const FORMAT_NEGATIVE_FIXINT: u8 = 0xe0;
+
+pub const FIXMAP_MASK: u8 = 0x0f;
+pub const FIXARRAY_MASK: u8 = 0x0f;
+pub const FIXSTR_MASK: u8 = 0x1f;
+
+pub const EXT_TIMESTAMP: i8 = -1;
+
+pub const TIMESTAMP32_SIZE: usize = 4;
+pub const TIMESTAMP64_SIZE: usize = 8;
+pub const TIMESTAMP96_SIZE: usize = 12;
+pub const TIMESTAMP64_SECONDS_BITS: u32 = 34;
+
+/// Format Family
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Family {
+    Nil,
+    Boolean,
+    Integer,
+    Float,
+    String,
+    Binary,
+    Array,
+    Map,
+    Extension(i8),
+}
+
+/// Value Header
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Header {
+    /// Format byte (`FORMAT_*`).
+    pub format: u8,
+
+    /// Family of the format.
+    pub family: Family,
+
+    /// Number of elements of arrays, number of key-value pairs of maps, or
+    /// size of the payload in bytes for all other families. Fixints, nil,
+    /// and booleans are fully encoded in the format byte, and have no
+    /// payload.
+    pub length: usize,
+
+    /// Size of the header in bytes, including the format byte, the length,
+    /// and the extension type.
+    pub size: usize,
+}
+
+impl Header {
+    /// Parse a value header
+    ///
+    /// Parse the header of the value at the start of `data`. Return `None` if
+    /// the data is truncated, or uses the reserved format byte. The payload
+    /// need not be present.
+    pub fn parse(data: &[u8]) -> Option<Self> {
+        let format = *data.first()?;
+        let be = |n: usize| -> Option<usize> {
+            let v = data.get(1..1 + n)?;
+            Some(v.iter().fold(0, |acc, b| (acc << 8) | *b as usize))
+        };
+        let ext = |at: usize| -> Option<Family> {
+            Some(Family::Extension(*data.get(at)? as i8))
+        };
+
+        let (family, length, size) = match format {
+            FORMAT_POSITIVE_FIXINT..=0x7f => (Family::Integer, 0, 1),
+            FORMAT_FIXMAP..=0x8f => (Family::Map, (format & FIXMAP_MASK) as usize, 1),
+            FORMAT_FIXARRAY..=0x9f => (Family::Array, (format & FIXARRAY_MASK) as usize, 1),
+            FORMAT_FIXSTR..=0xbf => (Family::String, (format & FIXSTR_MASK) as usize, 1),
+            FORMAT_NIL => (Family::Nil, 0, 1),
+            FORMAT_FALSE | FORMAT_TRUE => (Family::Boolean, 0, 1),
+            FORMAT_BIN8 => (Family::Binary, be(1)?, 2),
+            FORMAT_BIN16 => (Family::Binary, be(2)?, 3),
+            FORMAT_BIN32 => (Family::Binary, be(4)?, 5),
+            FORMAT_EXT8 => (ext(2)?, be(1)?, 3),
+            FORMAT_EXT16 => (ext(3)?, be(2)?, 4),
+            FORMAT_EXT32 => (ext(5)?, be(4)?, 6),
+            FORMAT_FLOAT32 => (Family::Float, 4, 1),
+            FORMAT_FLOAT64 => (Family::Float, 8, 1),
+            FORMAT_UINT8..=FORMAT_INT64 => (Family::Integer, 1 << (format & 0x03), 1),
+            FORMAT_FIXEXT1..=FORMAT_FIXEXT16 => (ext(1)?, 1 << (format - FORMAT_FIXEXT1), 2),
+            FORMAT_STR8 => (Family::String, be(1)?, 2),
+            FORMAT_STR16 => (Family::String, be(2)?, 3),
+            FORMAT_STR32 => (Family::String, be(4)?, 5),
+            FORMAT_ARRAY16 => (Family::Array, be(2)?, 3),
+            FORMAT_ARRAY32 => (Family::Array, be(4)?, 5),
+            FORMAT_MAP16 => (Family::Map, be(2)?, 3),
+            FORMAT_MAP32 => (Family::Map, be(4)?, 5),
+            FORMAT_NEGATIVE_FIXINT..=0xff => (Family::Integer, 0, 1),
+            _ => return None,
+        };
+
+        Some(Self { format, family, length, size })
+    }
+
+    /// Return payload size
+    ///
+    /// Return the number of bytes following the header that belong to this
+    /// value itself. This is 0 for arrays and maps, whose elements follow as
+    /// separate values.
+    pub fn payload_size(&self) -> usize {
+        match self.family {
+            Family::Array | Family::Map => 0,
+            _ => self.length,
+        }
+    }
+}
+
+/// Return value of fixints
+///
+/// Return the value of a positive or negative fixint format byte, or `None`
+/// if the format byte is not a fixint.
+pub fn fixint(format: u8) -> Option<i8> {
+    match format {
+        FORMAT_POSITIVE_FIXINT..=0x7f | FORMAT_NEGATIVE_FIXINT..=0xff => Some(format as i8),
+        _ => None,
+    }
+}
+
+/// Return value size
+///
+/// Return the total size in bytes of the value at the start of `data`,
+/// including all nested elements of arrays and maps. Only headers are
+/// decoded, and payloads are skipped. Return `None` if the data is truncated
+/// or invalid.
+pub fn value_size(data: &[u8]) -> Option<usize> {
+    let mut pending: usize = 1;
+    let mut offset = 0;
+
+    while pending > 0 {
+        let h = Header::parse(&data[offset..])?;
+        pending -= 1;
+        offset += h.size;
+
+        match h.family {
+            Family::Array => pending = pending.checked_add(h.length)?,
+            Family::Map => pending = pending.checked_add(h.length.checked_mul(2)?)?,
+            _ => offset = offset.checked_add(h.length)?,
+        }
+
+        // Each value needs at least 1 byte, so bail out early on bogus lengths.
+        if offset > data.len() || pending > data.len() - offset {
+            return None;
+        }
+    }
+
+    Some(offset)
+}
+
+/// Decode timestamp
+///
+/// Decode the payload of a timestamp extension (`EXT_TIMESTAMP`). Return the
+/// seconds and nanoseconds since the Unix epoch, or `None` if the payload
+/// size is invalid.
+pub fn timestamp(payload: &[u8]) -> Option<(i64, u32)> {
+    match payload.len() {
+        TIMESTAMP32_SIZE => {
+            let v = u32::from_be_bytes(payload.try_into().ok()?);
+            Some((v as i64, 0))
+        },
+        TIMESTAMP64_SIZE => {
+            let v = u64::from_be_bytes(payload.try_into().ok()?);
+            let seconds = v & ((1 << TIMESTAMP64_SECONDS_BITS) - 1);
+            Some((seconds as i64, (v >> TIMESTAMP64_SECONDS_BITS) as u32))
+        },
+        TIMESTAMP96_SIZE => {
+            let nanoseconds = u32::from_be_bytes(payload[..4].try_into().ok()?);
+            let seconds = i64::from_be_bytes(payload[4..].try_into().ok()?);
+            Some((seconds, nanoseconds))
+        },
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Map `{"compact": true, "schema": 0}` from the project homepage.
+    const MAP: [u8; 18] = [
+        0x82, 0xa7, 0x63, 0x6f, 0x6d, 0x70, 0x61, 0x63,
+        0x74, 0xc3, 0xa6, 0x73, 0x63, 0x68, 0x65, 0x6d,
+        0x61, 0x00,
+    ];
+
+    // Timestamp 1700000000.123456789 as timestamp 64.
+    const TIMESTAMP: [u8; 10] = [
+        0xd7, 0xff, 0x1d, 0x6f, 0x34, 0x54, 0x65, 0x53,
+        0xf1, 0x00,
+    ];
+
+    // Decode headers of all format families.
+    #[test]
+    fn verify_headers() {
+        let h = Header::parse(&MAP).unwrap();
+        assert_eq!(h.family, Family::Map);
+        assert_eq!((h.length, h.size, h.payload_size()), (2, 1, 0));
+
+        let h = Header::parse(&MAP[1..]).unwrap();
+        assert_eq!(h.family, Family::String);
+        assert_eq!((h.length, h.size), (7, 1));
+        assert_eq!(Header::parse(&MAP[9..]).unwrap().family, Family::Boolean);
+
+        let h = Header::parse(&[FORMAT_STR16, 0x01, 0x00]).unwrap();
+        assert_eq!((h.family, h.length, h.size), (Family::String, 256, 3));
+        let h = Header::parse(&[FORMAT_BIN32, 0x00, 0x01, 0x00, 0x00]).unwrap();
+        assert_eq!((h.family, h.length, h.size), (Family::Binary, 65536, 5));
+        let h = Header::parse(&[FORMAT_EXT16, 0x00, 0x20, 0x05]).unwrap();
+        assert_eq!((h.family, h.length, h.size), (Family::Extension(5), 32, 4));
+        let h = Header::parse(&[FORMAT_FIXEXT16, 0x80]).unwrap();
+        assert_eq!((h.family, h.length, h.size), (Family::Extension(-128), 16, 2));
+        let h = Header::parse(&[FORMAT_INT32]).unwrap();
+        assert_eq!((h.family, h.length, h.size), (Family::Integer, 4, 1));
+        let h = Header::parse(&[FORMAT_UINT64]).unwrap();
+        assert_eq!((h.family, h.length, h.size), (Family::Integer, 8, 1));
+        let h = Header::parse(&[FORMAT_ARRAY32, 0x00, 0x00, 0x00, 0x03]).unwrap();
+        assert_eq!((h.family, h.length, h.size), (Family::Array, 3, 5));
+
+        assert_eq!(Header::parse(&[0xc1]), None);
+        assert_eq!(Header::parse(&[FORMAT_MAP16, 0x00]), None);
+        assert_eq!(Header::parse(&[FORMAT_EXT8, 0x01]), None);
+
+        assert_eq!(fixint(0x7f), Some(127));
+        assert_eq!(fixint(0xe0), Some(-32));
+        assert_eq!(fixint(FORMAT_NIL), None);
+    }
+
+    // Skip nested values and decode timestamps.
+    #[test]
+    fn verify_values() {
+        assert_eq!(value_size(&MAP), Some(18));
+        assert_eq!(value_size(&MAP[..17]), None);
+        assert_eq!(value_size(&[0x92, 0x91, 0xc0, 0xa1, 0x61, 0xff]), Some(5));
+        assert_eq!(value_size(&[FORMAT_MAP32, 0xff, 0xff, 0xff, 0xff, 0x00]), None);
+
+        let h = Header::parse(&TIMESTAMP).unwrap();
+        assert_eq!(h.family, Family::Extension(EXT_TIMESTAMP));
+        let payload = &TIMESTAMP[h.size..h.size + h.payload_size()];
+        assert_eq!(timestamp(payload), Some((1_700_000_000, 123_456_789)));
+        assert_eq!(timestamp(&[0x65, 0x53, 0xf1, 0x00]), Some((1_700_000_000, 0)));
+
+        let mut v = [0xff; 12];
+        v[..4].copy_from_slice(&123_456_789u32.to_be_bytes());
+        assert_eq!(timestamp(&v), Some((-1, 123_456_789)));
+        assert_eq!(timestamp(&v[..5]), None);
+    }
+}
@@ -0,0 +1,713 @@
+//! Netlink
+//!
+//! Netlink is the socket-based interface between the Linux kernel and user
+//! space. Each datagram carries one or more messages (see `Messages`). Each
+//! message starts with a 16-byte `MessageHeader`, followed by a
+//! family-specific payload, padded to a multiple of 4 bytes (see `align()`).
+//! Messages of a multi-part dump carry `FLAG_MULTI` and are terminated by a
+//! `MSG_DONE` message. Errors and acknowledgments are reported in
+//! `MSG_ERROR` messages (see `ErrorMessage`).
+//!
+//! Most payloads consist of a fixed family header, followed by a sequence
+//! of attributes (see `Attributes`), encoded as TLVs with 2-byte length and
+//! 2-byte type, where the length includes the 4-byte attribute header but
+//! excludes the padding to a multiple of 4 bytes. Nested attributes carry a
+//! sequence of attributes as data.
+//!
+//! The routing family (`PROTOCOL_ROUTE`) uses `InterfaceInfo`,
+//! `InterfaceAddress`, and `RouteMessage` as family headers. The generic
+//! family (`PROTOCOL_GENERIC`) multiplexes dynamically registered families,
+//! and uses `GenericHeader`.
+//!
+//! Unlike most protocols, all multi-byte integers are encoded in native
+//! byte order, unless an attribute carries `ATTRIBUTE_FLAG_NET_BYTEORDER`.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+pub const ALIGNMENT: usize = 4;
+pub const MESSAGE_HEADER_SIZE: usize = 16;
+pub const ATTRIBUTE_HEADER_SIZE: usize = 4;
+pub const ERROR_MESSAGE_SIZE: usize = 20;
+pub const GENERIC_HEADER_SIZE: usize = 4;
+pub const INTERFACE_INFO_SIZE: usize = 16;
+pub const INTERFACE_ADDRESS_SIZE: usize = 8;
+pub const ROUTE_MESSAGE_SIZE: usize = 12;
+
+pub const PROTOCOL_ROUTE: u32 = 0;
+pub const PROTOCOL_USERSOCK: u32 = 2;
+pub const PROTOCOL_FIREWALL: u32 = 3;
+pub const PROTOCOL_SOCK_DIAG: u32 = 4;
+pub const PROTOCOL_NFLOG: u32 = 5;
+pub const PROTOCOL_XFRM: u32 = 6;
+pub const PROTOCOL_SELINUX: u32 = 7;
+pub const PROTOCOL_ISCSI: u32 = 8;
+pub const PROTOCOL_AUDIT: u32 = 9;
+pub const PROTOCOL_FIB_LOOKUP: u32 = 10;
+pub const PROTOCOL_CONNECTOR: u32 = 11;
+pub const PROTOCOL_NETFILTER: u32 = 12;
+pub const PROTOCOL_KOBJECT_UEVENT: u32 = 15;
+pub const PROTOCOL_GENERIC: u32 = 16;
+pub const PROTOCOL_SCSITRANSPORT: u32 = 18;
+pub const PROTOCOL_ECRYPTFS: u32 = 19;
+pub const PROTOCOL_RDMA: u32 = 20;
+pub const PROTOCOL_CRYPTO: u32 = 21;
+pub const PROTOCOL_SMC: u32 = 22;
+
+pub const MSG_NOOP: u16 = 1;
+pub const MSG_ERROR: u16 = 2;
+pub const MSG_DONE: u16 = 3;
+pub const MSG_OVERRUN: u16 = 4;
+
+/// Minimum Family-specific Message Type
+pub const MSG_MIN_TYPE: u16 = 0x10;
+
+pub const FLAG_REQUEST: u16 = 0x0001;
+pub const FLAG_MULTI: u16 = 0x0002;
+pub const FLAG_ACK: u16 = 0x0004;
+pub const FLAG_ECHO: u16 = 0x0008;
+pub const FLAG_DUMP_INTR: u16 = 0x0010;
+pub const FLAG_DUMP_FILTERED: u16 = 0x0020;
+
+pub const FLAG_ROOT: u16 = 0x0100;
+pub const FLAG_MATCH: u16 = 0x0200;
+pub const FLAG_ATOMIC: u16 = 0x0400;
+pub const FLAG_DUMP: u16 = FLAG_ROOT | FLAG_MATCH;
+
+pub const FLAG_REPLACE: u16 = 0x0100;
+pub const FLAG_EXCL: u16 = 0x0200;
+pub const FLAG_CREATE: u16 = 0x0400;
+pub const FLAG_APPEND: u16 = 0x0800;
+
+pub const FLAG_NONREC: u16 = 0x0100;
+pub const FLAG_BULK: u16 = 0x0200;
+
+pub const FLAG_CAPPED: u16 = 0x0100;
+pub const FLAG_ACK_TLVS: u16 = 0x0200;
+
+pub const ATTRIBUTE_FLAG_NESTED: u16 = 0x8000;
+pub const ATTRIBUTE_FLAG_NET_BYTEORDER: u16 = 0x4000;
+pub const ATTRIBUTE_TYPE_MASK: u16 = 0x3fff;
+
+pub const AF_UNSPEC: u8 = 0;
+pub const AF_INET: u8 = 2;
+pub const AF_BRIDGE: u8 = 7;
+pub const AF_INET6: u8 = 10;
+pub const AF_PACKET: u8 = 17;
+
+pub const RTM_NEWLINK: u16 = 16;
+pub const RTM_DELLINK: u16 = 17;
+pub const RTM_GETLINK: u16 = 18;
+pub const RTM_SETLINK: u16 = 19;
+pub const RTM_NEWADDR: u16 = 20;
+pub const RTM_DELADDR: u16 = 21;
+pub const RTM_GETADDR: u16 = 22;
+pub const RTM_NEWROUTE: u16 = 24;
+pub const RTM_DELROUTE: u16 = 25;
+pub const RTM_GETROUTE: u16 = 26;
+pub const RTM_NEWNEIGH: u16 = 28;
+pub const RTM_DELNEIGH: u16 = 29;
+pub const RTM_GETNEIGH: u16 = 30;
+pub const RTM_NEWRULE: u16 = 32;
+pub const RTM_DELRULE: u16 = 33;
+pub const RTM_GETRULE: u16 = 34;
+
+pub const IFF_UP: u32 = 0x00001;
+pub const IFF_BROADCAST: u32 = 0x00002;
+pub const IFF_DEBUG: u32 = 0x00004;
+pub const IFF_LOOPBACK: u32 = 0x00008;
+pub const IFF_POINTOPOINT: u32 = 0x00010;
+pub const IFF_RUNNING: u32 = 0x00040;
+pub const IFF_NOARP: u32 = 0x00080;
+pub const IFF_PROMISC: u32 = 0x00100;
+pub const IFF_ALLMULTI: u32 = 0x00200;
+pub const IFF_MULTICAST: u32 = 0x01000;
+pub const IFF_LOWER_UP: u32 = 0x10000;
+pub const IFF_DORMANT: u32 = 0x20000;
+
+pub const IFLA_ADDRESS: u16 = 1;
+pub const IFLA_BROADCAST: u16 = 2;
+pub const IFLA_IFNAME: u16 = 3;
+pub const IFLA_MTU: u16 = 4;
+pub const IFLA_LINK: u16 = 5;
+pub const IFLA_QDISC: u16 = 6;
+pub const IFLA_STATS: u16 = 7;
+pub const IFLA_MASTER: u16 = 10;
+pub const IFLA_TXQLEN: u16 = 13;
+pub const IFLA_OPERSTATE: u16 = 16;
+pub const IFLA_LINKMODE: u16 = 17;
+pub const IFLA_LINKINFO: u16 = 18;
+pub const IFLA_NET_NS_PID: u16 = 19;
+pub const IFLA_IFALIAS: u16 = 20;
+pub const IFLA_STATS64: u16 = 23;
+pub const IFLA_AF_SPEC: u16 = 26;
+pub const IFLA_GROUP: u16 = 27;
+pub const IFLA_PROMISCUITY: u16 = 30;
+pub const IFLA_CARRIER: u16 = 33;
+pub const IFLA_MIN_MTU: u16 = 50;
+pub const IFLA_MAX_MTU: u16 = 51;
+
+pub const IFLA_INFO_KIND: u16 = 1;
+pub const IFLA_INFO_DATA: u16 = 2;
+
+pub const IFA_ADDRESS: u16 = 1;
+pub const IFA_LOCAL: u16 = 2;
+pub const IFA_LABEL: u16 = 3;
+pub const IFA_BROADCAST: u16 = 4;
+pub const IFA_ANYCAST: u16 = 5;
+pub const IFA_CACHEINFO: u16 = 6;
+pub const IFA_MULTICAST: u16 = 7;
+pub const IFA_FLAGS: u16 = 8;
+
+pub const IFA_F_SECONDARY: u8 = 0x01;
+pub const IFA_F_NODAD: u8 = 0x02;
+pub const IFA_F_OPTIMISTIC: u8 = 0x04;
+pub const IFA_F_DADFAILED: u8 = 0x08;
+pub const IFA_F_HOMEADDRESS: u8 = 0x10;
+pub const IFA_F_DEPRECATED: u8 = 0x20;
+pub const IFA_F_TENTATIVE: u8 = 0x40;
+pub const IFA_F_PERMANENT: u8 = 0x80;
+
+pub const RTA_DST: u16 = 1;
+pub const RTA_SRC: u16 = 2;
+pub const RTA_IIF: u16 = 3;
+pub const RTA_OIF: u16 = 4;
+pub const RTA_GATEWAY: u16 = 5;
+pub const RTA_PRIORITY: u16 = 6;
+pub const RTA_PREFSRC: u16 = 7;
+pub const RTA_METRICS: u16 = 8;
+pub const RTA_MULTIPATH: u16 = 9;
+pub const RTA_FLOW: u16 = 11;
+pub const RTA_CACHEINFO: u16 = 12;
+pub const RTA_TABLE: u16 = 15;
+pub const RTA_MARK: u16 = 16;
+pub const RTA_VIA: u16 = 18;
+pub const RTA_PREF: u16 = 20;
+
+pub const RT_TABLE_UNSPEC: u8 = 0;
+pub const RT_TABLE_DEFAULT: u8 = 253;
+pub const RT_TABLE_MAIN: u8 = 254;
+pub const RT_TABLE_LOCAL: u8 = 255;
+
+pub const RTPROT_UNSPEC: u8 = 0;
+pub const RTPROT_REDIRECT: u8 = 1;
+pub const RTPROT_KERNEL: u8 = 2;
+pub const RTPROT_BOOT: u8 = 3;
+pub const RTPROT_STATIC: u8 = 4;
+pub const RTPROT_DHCP: u8 = 16;
+
+pub const RT_SCOPE_UNIVERSE: u8 = 0;
+pub const RT_SCOPE_SITE: u8 = 200;
+pub const RT_SCOPE_LINK: u8 = 253;
+pub const RT_SCOPE_HOST: u8 = 254;
+pub const RT_SCOPE_NOWHERE: u8 = 255;
+
+pub const RTN_UNSPEC: u8 = 0;
+pub const RTN_UNICAST: u8 = 1;
+pub const RTN_LOCAL: u8 = 2;
+pub const RTN_BROADCAST: u8 = 3;
+pub const RTN_ANYCAST: u8 = 4;
+pub const RTN_MULTICAST: u8 = 5;
+pub const RTN_BLACKHOLE: u8 = 6;
+pub const RTN_UNREACHABLE: u8 = 7;
+pub const RTN_PROHIBIT: u8 = 8;
+
+/// Message Type of the Generic Netlink Controller
+pub const GENL_ID_CTRL: u16 = 0x10;
+
+pub const CTRL_CMD_NEWFAMILY: u8 = 1;
+pub const CTRL_CMD_DELFAMILY: u8 = 2;
+pub const CTRL_CMD_GETFAMILY: u8 = 3;
+
+pub const CTRL_ATTR_FAMILY_ID: u16 = 1;
+pub const CTRL_ATTR_FAMILY_NAME: u16 = 2;
+pub const CTRL_ATTR_VERSION: u16 = 3;
+pub const CTRL_ATTR_HDRSIZE: u16 = 4;
+pub const CTRL_ATTR_MAXATTR: u16 = 5;
+pub const CTRL_ATTR_OPS: u16 = 6;
+pub const CTRL_ATTR_MCAST_GROUPS: u16 = 7;
+
+/// Message Header
+#[repr(C)]
+pub struct MessageHeader {
+    /// Size of the message in bytes, including the header but excluding
+    /// padding.
+    pub length: u32,
+
+    /// Message type (`MSG_*`, or family-specific).
+    pub message_type: u16,
+
+    /// Message flags (`FLAG_*`).
+    pub flags: u16,
+
+    /// Sequence number, echoed in replies.
+    pub sequence: u32,
+
+    /// Port identifier of the sender, or 0 for the kernel.
+    pub pid: u32,
+}
+
+/// Error Message Payload
+#[repr(C)]
+pub struct ErrorMessage {
+    /// Negative errno value, or 0 for acknowledgments.
+    pub error: i32,
+
+    /// Header of the message that caused the error. Unless `FLAG_CAPPED` is
+    /// set, it is followed by the payload of that message.
+    pub message: MessageHeader,
+}
+
+/// Attribute Header
+#[repr(C)]
+pub struct AttributeHeader {
+    /// Size of the attribute in bytes, including the header but excluding
+    /// padding.
+    pub length: u16,
+
+    /// Attribute type, combined with `ATTRIBUTE_FLAG_*`.
+    pub attribute_type: u16,
+}
+
+/// Generic Netlink Header
+#[repr(C)]
+pub struct GenericHeader {
+    /// Family-specific command.
+    pub cmd: u8,
+
+    /// Family-specific version.
+    pub version: u8,
+
+    /// Reserved, must be 0.
+    pub reserved: u16,
+}
+
+/// Interface Info Message Header
+#[repr(C)]
+pub struct InterfaceInfo {
+    /// Address family, usually `AF_UNSPEC`.
+    pub family: u8,
+
+    /// Reserved, must be 0.
+    pub pad: u8,
+
+    /// ARP hardware type of the device (`arp::HARDWARE_*`).
+    pub device_type: u16,
+
+    /// Interface index.
+    pub index: i32,
+
+    /// Device flags (`IFF_*`).
+    pub flags: u32,
+
+    /// Mask of the device flags to change.
+    pub change: u32,
+}
+
+/// Interface Address Message Header
+#[repr(C)]
+pub struct InterfaceAddress {
+    /// Address family (`AF_*`).
+    pub family: u8,
+
+    /// Length of the network prefix in bits.
+    pub prefix_length: u8,
+
+    /// Address flags (`IFA_F_*`).
+    pub flags: u8,
+
+    /// Address scope (`RT_SCOPE_*`).
+    pub scope: u8,
+
+    /// Interface index.
+    pub index: u32,
+}
+
+/// Route Message Header
+#[repr(C)]
+pub struct RouteMessage {
+    /// Address family (`AF_*`).
+    pub family: u8,
+
+    /// Length of the destination prefix in bits.
+    pub dst_length: u8,
+
+    /// Length of the source prefix in bits.
+    pub src_length: u8,
+
+    /// Type-of-service filter.
+    pub tos: u8,
+
+    /// Routing table (`RT_TABLE_*`), or `RT_TABLE_UNSPEC` if given by
+    /// `RTA_TABLE`.
+    pub table: u8,
+
+    /// Origin of the route (`RTPROT_*`).
+    pub protocol: u8,
+
+    /// Distance to the destination (`RT_SCOPE_*`).
+    pub scope: u8,
+
+    /// Route type (`RTN_*`).
+    pub route_type: u8,
+
+    /// Route flags.
+    pub flags: u32,
+}
+
+/// Message Iterator
+///
+/// An iterator over the messages of a datagram, yielding the message header
+/// and payload. Iteration stops at the end of the data, or at the first
+/// truncated or malformed message.
+#[derive(Clone, Debug)]
+pub struct Messages<'data> {
+    data: &'data [u8],
+}
+
+/// Attribute Iterator
+///
+/// An iterator over the attributes of a payload, yielding the attribute type
+/// with its flags and the data without the attribute header or padding.
+/// Iteration stops at the end of the data, or at the first truncated or
+/// malformed attribute.
+#[derive(Clone, Debug)]
+pub struct Attributes<'data> {
+    data: &'data [u8],
+}
+
+impl MessageHeader {
+    /// Import a message header from a byte slice
+    ///
+    /// Create a new message header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 16]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 16], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Check for message flags
+    pub fn has_flags(&self, flags: u16) -> bool {
+        self.flags & flags == flags
+    }
+}
+
+impl ErrorMessage {
+    /// Import an error message from a byte slice
+    ///
+    /// Create a new error message from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 20]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 20], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Check for acknowledgments
+    pub fn is_ack(&self) -> bool {
+        self.error == 0
+    }
+}
+
+impl AttributeHeader {
+    /// Import an attribute header from a byte slice
+    ///
+    /// Create a new attribute header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 4]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 4], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl GenericHeader {
+    /// Import a generic header from a byte slice
+    ///
+    /// Create a new generic header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 4]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 4], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl InterfaceInfo {
+    /// Import an interface info header from a byte slice
+    ///
+    /// Create a new interface info header from data copied from a byte
+    /// slice. No byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 16]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 16], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl InterfaceAddress {
+    /// Import an interface address header from a byte slice
+    ///
+    /// Create a new interface address header from data copied from a byte
+    /// slice. No byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 8]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 8], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl RouteMessage {
+    /// Import a route message header from a byte slice
+    ///
+    /// Create a new route message header from data copied from a byte slice.
+    /// No byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 12]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 12], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl<'data> Messages<'data> {
+    /// Create message iterator
+    ///
+    /// Create a new iterator over the messages in `data`, which must start
+    /// with a message header.
+    pub fn new(data: &'data [u8]) -> Self {
+        Self {
+            data,
+        }
+    }
+
+    /// Return remaining data
+    ///
+    /// Return the data that has not been consumed by the iterator.
+    pub fn remainder(&self) -> &'data [u8] {
+        self.data
+    }
+}
+
+impl<'data> Iterator for Messages<'data> {
+    type Item = (MessageHeader, &'data [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let h = MessageHeader::from_bytes(self.data.get(..MESSAGE_HEADER_SIZE)?.try_into().unwrap());
+        let n = h.length as usize;
+        if n < MESSAGE_HEADER_SIZE {
+            return None;
+        }
+
+        let v = self.data.get(MESSAGE_HEADER_SIZE..n)?;
+        self.data = self.data.get(align(n)..).unwrap_or(&[]);
+        Some((h, v))
+    }
+}
+
+impl<'data> Attributes<'data> {
+    /// Create attribute iterator
+    ///
+    /// Create a new iterator over the attributes in `data`, which must start
+    /// after the family header, or be the data of a nested attribute.
+    pub fn new(data: &'data [u8]) -> Self {
+        Self {
+            data,
+        }
+    }
+
+    /// Return remaining data
+    ///
+    /// Return the data that has not been consumed by the iterator.
+    pub fn remainder(&self) -> &'data [u8] {
+        self.data
+    }
+}
+
+impl<'data> Iterator for Attributes<'data> {
+    type Item = (u16, &'data [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let h = AttributeHeader::from_bytes(self.data.get(..ATTRIBUTE_HEADER_SIZE)?.try_into().unwrap());
+        let n = h.length as usize;
+        if n < ATTRIBUTE_HEADER_SIZE {
+            return None;
+        }
+
+        let v = self.data.get(ATTRIBUTE_HEADER_SIZE..n)?;
+        self.data = self.data.get(align(n)..).unwrap_or(&[]);
+        Some((h.attribute_type, v))
+    }
+}
+
+/// Align size
+///
+/// Return the size rounded up to the netlink alignment of 4 bytes, as used
+/// for messages, family headers, and attributes.
+pub fn align(size: usize) -> usize {
+    (size + ALIGNMENT - 1) & !(ALIGNMENT - 1)
+}
+
+/// Find attribute
+///
+/// Return the data of the first attribute with the given type in `data`,
+/// ignoring the attribute flags.
+pub fn find_attribute(data: &[u8], attribute_type: u16) -> Option<&[u8]> {
+    Attributes::new(data)
+        .find(|v| v.0 & ATTRIBUTE_TYPE_MASK == attribute_type)
+        .map(|v| v.1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Append a message header in native byte order.
+    fn push_header(data: &mut std::vec::Vec<u8>, length: u32, message_type: u16, flags: u16, sequence: u32) {
+        data.extend_from_slice(&length.to_ne_bytes());
+        data.extend_from_slice(&message_type.to_ne_bytes());
+        data.extend_from_slice(&flags.to_ne_bytes());
+        data.extend_from_slice(&sequence.to_ne_bytes());
+        data.extend_from_slice(&0u32.to_ne_bytes());
+    }
+
+    // Append an attribute in native byte order, including padding.
+    fn push_attribute(data: &mut std::vec::Vec<u8>, attribute_type: u16, value: &[u8]) {
+        data.extend_from_slice(&((ATTRIBUTE_HEADER_SIZE + value.len()) as u16).to_ne_bytes());
+        data.extend_from_slice(&attribute_type.to_ne_bytes());
+        data.extend_from_slice(value);
+        data.resize(align(data.len()), 0);
+    }
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<MessageHeader>(), MESSAGE_HEADER_SIZE);
+        assert_eq!(align_of::<MessageHeader>(), 4);
+        assert_eq!(size_of::<ErrorMessage>(), ERROR_MESSAGE_SIZE);
+        assert_eq!(align_of::<ErrorMessage>(), 4);
+        assert_eq!(size_of::<AttributeHeader>(), ATTRIBUTE_HEADER_SIZE);
+        assert_eq!(align_of::<AttributeHeader>(), 2);
+        assert_eq!(size_of::<GenericHeader>(), GENERIC_HEADER_SIZE);
+        assert_eq!(align_of::<GenericHeader>(), 2);
+        assert_eq!(size_of::<InterfaceInfo>(), INTERFACE_INFO_SIZE);
+        assert_eq!(align_of::<InterfaceInfo>(), 4);
+        assert_eq!(size_of::<InterfaceAddress>(), INTERFACE_ADDRESS_SIZE);
+        assert_eq!(align_of::<InterfaceAddress>(), 4);
+        assert_eq!(size_of::<RouteMessage>(), ROUTE_MESSAGE_SIZE);
+        assert_eq!(align_of::<RouteMessage>(), 4);
+    }
+
+    // Parse an address dump with padded attributes and its terminator.
+    #[test]
+    fn verify_dump() {
+        let mut data = std::vec::Vec::new();
+        push_header(&mut data, 40, RTM_NEWADDR, FLAG_MULTI, 7);
+        data.extend_from_slice(&[AF_INET, 8, IFA_F_PERMANENT, RT_SCOPE_HOST]);
+        data.extend_from_slice(&1u32.to_ne_bytes());
+        push_attribute(&mut data, IFA_ADDRESS, &[127, 0, 0, 1]);
+        push_attribute(&mut data, IFA_LABEL, b"lo\0");
+        push_header(&mut data, 20, MSG_DONE, FLAG_MULTI, 7);
+        data.extend_from_slice(&0u32.to_ne_bytes());
+
+        let mut msgs = Messages::new(&data);
+        let (h, v) = msgs.next().unwrap();
+        assert_eq!(h.message_type, RTM_NEWADDR);
+        assert!(h.has_flags(FLAG_MULTI));
+        assert!(!h.has_flags(FLAG_MULTI | FLAG_ACK));
+        assert_eq!(h.sequence, 7);
+        let a = InterfaceAddress::from_bytes(v[..8].try_into().unwrap());
+        assert_eq!(a.family, AF_INET);
+        assert_eq!(a.prefix_length, 8);
+        assert_eq!(a.scope, RT_SCOPE_HOST);
+        assert_eq!(a.index, 1);
+
+        let mut attrs = Attributes::new(&v[align(INTERFACE_ADDRESS_SIZE)..]);
+        assert_eq!(attrs.next(), Some((IFA_ADDRESS, &[127, 0, 0, 1][..])));
+        assert_eq!(attrs.next(), Some((IFA_LABEL, &b"lo\0"[..])));
+        assert!(attrs.next().is_none());
+
+        let (h, _) = msgs.next().unwrap();
+        assert_eq!(h.message_type, MSG_DONE);
+        assert!(msgs.next().is_none());
+        assert!(msgs.remainder().is_empty());
+
+        // A length below the header size stops iteration.
+        data[..4].copy_from_slice(&15u32.to_ne_bytes());
+        assert!(Messages::new(&data).next().is_none());
+    }
+
+    // Parse an acknowledgment and nested attributes.
+    #[test]
+    fn verify_nested() {
+        let mut data = std::vec::Vec::new();
+        push_header(&mut data, 36, MSG_ERROR, FLAG_CAPPED, 9);
+        data.extend_from_slice(&0i32.to_ne_bytes());
+        push_header(&mut data, 20, GENL_ID_CTRL, FLAG_REQUEST | FLAG_ACK, 9);
+        let (h, v) = Messages::new(&data).next().unwrap();
+        assert_eq!(h.message_type, MSG_ERROR);
+        let e = ErrorMessage::from_bytes(v[..20].try_into().unwrap());
+        assert!(e.is_ack());
+        assert_eq!(e.message.message_type, GENL_ID_CTRL);
+
+        let mut inner = std::vec::Vec::new();
+        push_attribute(&mut inner, IFLA_INFO_KIND, b"veth\0");
+        let mut attrs = std::vec::Vec::new();
+        push_attribute(&mut attrs, IFLA_MTU, &1500u32.to_ne_bytes());
+        push_attribute(&mut attrs, IFLA_LINKINFO | ATTRIBUTE_FLAG_NESTED, &inner);
+
+        let v = find_attribute(&attrs, IFLA_LINKINFO).unwrap();
+        assert_eq!(find_attribute(v, IFLA_INFO_KIND), Some(&b"veth\0"[..]));
+        assert_eq!(find_attribute(&attrs, IFLA_MTU), Some(&1500u32.to_ne_bytes()[..]));
+        assert!(find_attribute(&attrs, IFLA_IFNAME).is_none());
+
+        assert_eq!(align(0), 0);
+        assert_eq!(align(5), 8);
+        assert_eq!(align(8), 8);
+    }
+}
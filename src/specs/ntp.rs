@@ -0,0 +1,364 @@
+//! Network Time Protocol
+//!
+//! NTP packets start with a 48-byte `Header`, optionally followed by
+//! extension fields (see `Extensions`) and a message authentication code.
+//! The same layout is used by SNTP. Packets are exchanged over UDP on
+//! `udp::PORT_NTP`.
+//!
+//! Time is encoded in two fixed-point formats relative to the prime epoch
+//! (1900-01-01 00:00 UTC). The short format (`int::u16f16be`) carries
+//! durations with 16 integer and 16 fractional bits of seconds. The
+//! timestamp format (`int::u32f32be`) carries points in time with 32
+//! integer and 32 fractional bits of seconds, wrapping every 136 years. Era
+//! 0 ends in 2036. The conversion helpers assume timestamps fall into the
+//! range from 1968 to 2104, as recommended for SNTP clients (see
+//! `timestamp_to_unix()`). A timestamp of zero means the time is unknown.
+//!
+//! All multi-byte integers are encoded as big-endian.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::int;
+
+pub const HEADER_SIZE: usize = 48;
+pub const EXTENSION_HEADER_SIZE: usize = 4;
+pub const EXTENSION_MIN: usize = 16;
+pub const VERSION: u8 = 4;
+
+/// Maximum Size of Message Authentication Codes
+///
+/// The key identifier followed by a SHA-1 digest. Trailing data of this
+/// size or less is a MAC rather than an extension field.
+pub const MAC_MAX: usize = 24;
+
+/// Offset of the Unix Epoch
+///
+/// The number of seconds from the prime epoch to the Unix epoch
+/// (1970-01-01 00:00 UTC).
+pub const UNIX_OFFSET: u64 = 2_208_988_800;
+
+pub const LI_NONE: u8 = 0;
+pub const LI_INSERT: u8 = 1;
+pub const LI_DELETE: u8 = 2;
+pub const LI_UNSYNCHRONIZED: u8 = 3;
+
+pub const MODE_SYMMETRIC_ACTIVE: u8 = 1;
+pub const MODE_SYMMETRIC_PASSIVE: u8 = 2;
+pub const MODE_CLIENT: u8 = 3;
+pub const MODE_SERVER: u8 = 4;
+pub const MODE_BROADCAST: u8 = 5;
+pub const MODE_CONTROL: u8 = 6;
+pub const MODE_PRIVATE: u8 = 7;
+
+pub const STRATUM_UNSPECIFIED: u8 = 0;
+pub const STRATUM_PRIMARY: u8 = 1;
+pub const STRATUM_MAX: u8 = 15;
+pub const STRATUM_UNSYNCHRONIZED: u8 = 16;
+
+pub const KISS_DENY: [u8; 4] = *b"DENY";
+pub const KISS_RATE: [u8; 4] = *b"RATE";
+pub const KISS_RSTR: [u8; 4] = *b"RSTR";
+
+pub const EXTENSION_NO_OPERATION: u16 = 0x0002;
+pub const EXTENSION_UNIQUE_IDENTIFIER: u16 = 0x0104;
+pub const EXTENSION_NTS_COOKIE: u16 = 0x0204;
+pub const EXTENSION_NTS_COOKIE_PLACEHOLDER: u16 = 0x0304;
+pub const EXTENSION_NTS_AUTHENTICATOR: u16 = 0x0404;
+
+/// Packet Header
+#[repr(C)]
+pub struct Header {
+    /// Leap indicator (2 bits), version (3 bits), and mode (3 bits).
+    pub li_vn_mode: u8,
+
+    /// Stratum (`STRATUM_*`).
+    pub stratum: u8,
+
+    /// Maximum interval between messages, as log2 seconds.
+    pub poll: i8,
+
+    /// Precision of the system clock, as log2 seconds.
+    pub precision: i8,
+
+    /// Round-trip delay to the reference clock.
+    pub root_delay: int::u16f16be,
+
+    /// Dispersion relative to the reference clock.
+    pub root_dispersion: int::u16f16be,
+
+    /// Reference identifier. For stratum 1, a code for the reference clock
+    /// (e.g., `GPS`). For higher strata, the IPv4 address of the upstream
+    /// server, or the first 4 bytes of the MD5 of its IPv6 address. For
+    /// stratum 0, a kiss code (`KISS_*`).
+    pub reference_id: [u8; 4],
+
+    /// Time the system clock was last set or corrected.
+    pub reference_timestamp: int::u32f32be,
+
+    /// Time the request departed the client, copied from the request.
+    pub origin_timestamp: int::u32f32be,
+
+    /// Time the request arrived at the server.
+    pub receive_timestamp: int::u32f32be,
+
+    /// Time the packet departed the sender.
+    pub transmit_timestamp: int::u32f32be,
+}
+
+/// Extension Field Header
+#[repr(C)]
+pub struct ExtensionHeader {
+    /// Field type (`EXTENSION_*`).
+    pub field_type: int::u16be,
+
+    /// Size of the field in bytes, including the header and padding.
+    pub length: int::u16be,
+}
+
+/// Extension Field Iterator
+///
+/// An iterator over the extension fields following the header, yielding the
+/// field type and the field value, including padding. Iteration stops at the
+/// first malformed field, or once `MAC_MAX` bytes or less remain, which are
+/// then left as the MAC.
+#[derive(Clone, Debug)]
+pub struct Extensions<'data> {
+    data: &'data [u8],
+}
+
+impl Header {
+    /// Import a header from a byte slice
+    ///
+    /// Create a new header from data copied from a byte slice. No byte-order
+    /// conversions are applied.
+    pub fn from_bytes(data: &[u8; 48]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 48], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Return leap indicator
+    pub fn leap(&self) -> u8 {
+        self.li_vn_mode >> 6
+    }
+
+    /// Return version
+    pub fn version(&self) -> u8 {
+        (self.li_vn_mode >> 3) & 0x7
+    }
+
+    /// Return mode
+    pub fn mode(&self) -> u8 {
+        self.li_vn_mode & 0x7
+    }
+
+    /// Check for kiss-o'-death packets
+    pub fn is_kiss(&self) -> bool {
+        self.stratum == STRATUM_UNSPECIFIED && self.mode() == MODE_SERVER
+    }
+}
+
+impl ExtensionHeader {
+    /// Import an extension field header from a byte slice
+    ///
+    /// Create a new extension field header from data copied from a byte
+    /// slice. No byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 4]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 4], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl<'data> Extensions<'data> {
+    /// Create extension field iterator
+    ///
+    /// Create a new iterator over the extension fields in `data`, which must
+    /// start directly after the header.
+    pub fn new(data: &'data [u8]) -> Self {
+        Self {
+            data,
+        }
+    }
+
+    /// Return remaining data
+    ///
+    /// Return the data that has not been consumed by the iterator.
+    pub fn remainder(&self) -> &'data [u8] {
+        self.data
+    }
+}
+
+impl<'data> Iterator for Extensions<'data> {
+    type Item = (u16, &'data [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.data.len() <= MAC_MAX {
+            return None;
+        }
+
+        let field_type = u16::from_be_bytes([self.data[0], self.data[1]]);
+        let n = u16::from_be_bytes([self.data[2], self.data[3]]) as usize;
+        if n < EXTENSION_MIN || n & 3 != 0 || n > self.data.len() {
+            return None;
+        }
+
+        let v = &self.data[4..n];
+        self.data = &self.data[n..];
+        Some((field_type, v))
+    }
+}
+
+/// Encode leap indicator, version, and mode
+pub fn li_vn_mode(leap: u8, version: u8, mode: u8) -> u8 {
+    ((leap & 0x3) << 6) | ((version & 0x7) << 3) | (mode & 0x7)
+}
+
+/// Convert Unix time to timestamp
+///
+/// Return the timestamp of the given seconds and nanoseconds since the Unix
+/// epoch. Seconds beyond the range of the timestamp wrap into the next era.
+/// The fraction is truncated. `None` is returned if `nanoseconds` is not
+/// less than 1_000_000_000.
+pub fn timestamp_from_unix(seconds: i64, nanoseconds: u32) -> Option<int::u32f32be> {
+    if nanoseconds >= 1_000_000_000 {
+        return None;
+    }
+
+    let s = (seconds as u64).wrapping_add(UNIX_OFFSET) & 0xffff_ffff;
+    let f = ((nanoseconds as u64) << 32) / 1_000_000_000;
+    Some(int::u32f32be::from_bits((s << 32) | f))
+}
+
+/// Convert timestamp to Unix time
+///
+/// Return the seconds and nanoseconds since the Unix epoch of a timestamp.
+/// Timestamps with the most significant bit set are assumed to lie in era
+/// 0, and those without in era 1, covering the range from 1968 to 2104.
+/// Seconds before the Unix epoch are negative. The fraction is truncated.
+pub fn timestamp_to_unix(timestamp: int::u32f32be) -> (i64, u32) {
+    let v = timestamp.to_bits();
+    let mut s = v >> 32;
+    if s & 0x8000_0000 == 0 {
+        s += 1 << 32;
+    }
+    let ns = ((v & 0xffff_ffff) * 1_000_000_000) >> 32;
+    (s as i64 - UNIX_OFFSET as i64, ns as u32)
+}
+
+/// Convert short format to nanoseconds
+pub fn short_to_nanoseconds(v: int::u16f16be) -> u64 {
+    (v.to_bits() as u64 * 1_000_000_000) >> 16
+}
+
+/// Convert nanoseconds to short format
+///
+/// Return the short format of a duration in nanoseconds. Durations beyond
+/// the range of the format saturate. The fraction is truncated.
+pub fn short_from_nanoseconds(v: u64) -> int::u16f16be {
+    let bits = ((v as u128) << 16) / 1_000_000_000;
+    int::u16f16be::from_bits(core::cmp::min(bits, 0xffff_ffff) as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A server response with timestamps around Unix time 1700000000.
+    const RESPONSE: [u8; 48] = [
+        0x24, 0x02, 0x06, 0xe9, 0x00, 0x00, 0x08, 0x00,
+        0x00, 0x00, 0x10, 0x00, 0xc0, 0xa8, 0x00, 0x01,
+        0xe8, 0xfe, 0x6f, 0x80, 0x00, 0x00, 0x00, 0x00,
+        0xe8, 0xfe, 0x6f, 0x8a, 0x40, 0x00, 0x00, 0x00,
+        0xe8, 0xfe, 0x6f, 0x94, 0x80, 0x00, 0x00, 0x00,
+        0xe8, 0xfe, 0x6f, 0x94, 0xc0, 0x00, 0x00, 0x00,
+    ];
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<Header>(), HEADER_SIZE);
+        assert_eq!(align_of::<Header>(), 8);
+        assert_eq!(size_of::<ExtensionHeader>(), EXTENSION_HEADER_SIZE);
+        assert_eq!(align_of::<ExtensionHeader>(), 2);
+    }
+
+    // Parse a server response and convert its timestamps.
+    #[test]
+    fn verify_response() {
+        let h = Header::from_bytes(&RESPONSE);
+        assert_eq!(h.leap(), LI_NONE);
+        assert_eq!(h.version(), VERSION);
+        assert_eq!(h.mode(), MODE_SERVER);
+        assert_eq!(li_vn_mode(LI_NONE, VERSION, MODE_SERVER), RESPONSE[0]);
+        assert_eq!(h.stratum, 2);
+        assert_eq!(h.precision, -23);
+        assert!(!h.is_kiss());
+        assert_eq!(h.root_delay.to_f64(), 0.03125);
+        assert_eq!(short_to_nanoseconds(h.root_dispersion), 62_500_000);
+        assert_eq!(h.reference_id, [192, 168, 0, 1]);
+
+        assert_eq!(timestamp_to_unix(h.reference_timestamp), (1_700_000_000, 0));
+        assert_eq!(timestamp_to_unix(h.origin_timestamp), (1_700_000_010, 250_000_000));
+        assert_eq!(timestamp_to_unix(h.receive_timestamp), (1_700_000_020, 500_000_000));
+        assert_eq!(timestamp_to_unix(h.transmit_timestamp), (1_700_000_020, 750_000_000));
+        assert_eq!(h.transmit_timestamp.to_f64(), 3_908_988_820.75);
+    }
+
+    // Verify era handling of the conversions, and extension fields.
+    #[test]
+    fn verify_conversions() {
+        assert_eq!(timestamp_from_unix(0, 0).unwrap().to_bits(), UNIX_OFFSET << 32);
+        let v = u64::from_be_bytes(RESPONSE[40..].try_into().unwrap());
+        assert_eq!(timestamp_from_unix(1_700_000_020, 750_000_000).unwrap().to_bits(), v);
+        assert_eq!(timestamp_from_unix(0, 999_999_999).unwrap().to_bits() >> 32, UNIX_OFFSET);
+        assert!(timestamp_from_unix(0, 1_000_000_000).is_none());
+
+        // Era 1 starts on 2036-02-07 06:28:16 UTC.
+        let era1 = (1i64 << 32) - UNIX_OFFSET as i64;
+        let f = |s, ns| timestamp_to_unix(timestamp_from_unix(s, ns).unwrap());
+        assert_eq!(timestamp_from_unix(era1, 0).unwrap().to_bits(), 0);
+        assert_eq!(f(era1 + 1, 0), (era1 + 1, 0));
+        assert_eq!(f(era1 - 1, 0), (era1 - 1, 0));
+
+        // The assumed range starts on 1968-01-20 03:14:08 UTC, and reaches
+        // the Unix epoch at `UNIX_OFFSET` seconds.
+        let start = (1i64 << 31) - UNIX_OFFSET as i64;
+        assert_eq!(start, -61_505_152);
+        assert_eq!(timestamp_to_unix(int::u32f32be::from_bits(0x8000_0000 << 32)), (start, 0));
+        assert_eq!(timestamp_to_unix(int::u32f32be::from_bits(0x83aa_7e7f << 32)), (-1, 0));
+        assert_eq!(timestamp_to_unix(int::u32f32be::from_bits(0x83aa_7e80 << 32)), (0, 0));
+        assert_eq!(f(-1, 500_000_000), (-1, 500_000_000));
+        assert_eq!(f(start, 0), (start, 0));
+
+        assert_eq!(short_from_nanoseconds(1_500_000_000).to_bits(), 0x0001_8000);
+        assert_eq!(short_from_nanoseconds(u64::MAX).to_bits(), 0xffff_ffff);
+
+        let mut data = [0; 44];
+        data[..4].copy_from_slice(&[0x01, 0x04, 0x00, 0x14]);
+        data[20..24].copy_from_slice(&[0x00, 0x02, 0x00, 0x10]);
+        let mut ext = Extensions::new(&data);
+        assert_eq!(ext.next(), Some((EXTENSION_UNIQUE_IDENTIFIER, &[0; 16][..])));
+        assert!(ext.next().is_none());
+        assert_eq!(ext.remainder().len(), MAC_MAX);
+
+        data[2..4].copy_from_slice(&[0x00, 0x13]);
+        assert!(Extensions::new(&data).next().is_none());
+    }
+}
@@ -0,0 +1,302 @@
+//! Ogg Encapsulation Format
+//!
+//! Ogg (RFC 3533) multiplexes one or more logical bitstreams into a sequence
+//! of pages. Every page starts with a 27-byte `PageHeader`, identified by
+//! the capture pattern `MAGIC`, followed by the segment table and the page
+//! body.
+//!
+//! Packets of a logical bitstream are split into segments of 255 bytes,
+//! with a final segment of less than 255 bytes (possibly empty). The segment
+//! table holds one lacing value per segment with the segment size. Hence, a
+//! lacing value below 255 terminates a packet. If the last lacing value of a
+//! page is 255, the packet continues on the next page, which then has
+//! `HEADER_CONTINUED` set.
+//!
+//! The granule position is codec-specific, usually the sample position at
+//! the end of the last packet completed on the page. A value of
+//! `GRANULE_NONE` means no packet is completed on the page.
+//!
+//! The page checksum is a CRC-32 over the entire page, with the checksum
+//! field set to 0. It uses the polynomial `0x04c11db7` in non-reflected
+//! form, an initial value of 0, and no final inversion, unlike the CRC-32 of
+//! most other formats (see `checksum::crc32_msb_update()`).
+//!
+//! All multi-byte integers are encoded as little-endian.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::{checksum, int};
+
+/// Capture Pattern
+///
+/// The initial 4 bytes of every page ("OggS").
+pub const MAGIC: [u8; 4] = [0x4f, 0x67, 0x67, 0x53];
+
+/// Stream Structure Version
+pub const VERSION: u8 = 0;
+
+pub const HEADER_CONTINUED: u8 = 0x01;
+pub const HEADER_BOS: u8 = 0x02;
+pub const HEADER_EOS: u8 = 0x04;
+
+/// Size of the Page Header
+pub const HEADER_SIZE: usize = 27;
+
+/// Offset of the Checksum
+///
+/// The checksum field is located at this offset of the page header.
+pub const CHECKSUM_OFFSET: usize = 22;
+
+/// Maximum Lacing Value
+///
+/// A segment of this size continues the packet in the next segment.
+pub const LACING_MAX: u8 = 255;
+
+/// Maximum Page Size
+///
+/// The size of a page with the maximum of 255 segments of maximum size.
+pub const PAGE_SIZE_MAX: usize = HEADER_SIZE + 255 + 255 * 255;
+
+/// Unset Granule Position
+pub const GRANULE_NONE: i64 = -1;
+
+/// Page Header
+///
+/// Since the granule position is not naturally aligned, this structure is
+/// packed.
+#[repr(C, packed)]
+pub struct PageHeader {
+    /// Capture pattern, must match `MAGIC`.
+    pub magic: [u8; 4],
+
+    /// Stream structure version, must be `VERSION`.
+    pub version: u8,
+
+    /// Header type flags (`HEADER_*`).
+    pub header_type: u8,
+
+    /// Codec-specific granule position, or `GRANULE_NONE`.
+    pub granule_position: int::i64le,
+
+    /// Serial number of the logical bitstream.
+    pub serial: int::u32le,
+
+    /// Page sequence number within the logical bitstream.
+    pub sequence: int::u32le,
+
+    /// Page checksum.
+    pub checksum: int::u32le,
+
+    /// Number of entries in the segment table.
+    pub segments: u8,
+}
+
+/// Page Reference
+///
+/// A reference to a page in a byte slice, as returned by `Page::parse()`.
+#[derive(Clone, Copy, Debug)]
+pub struct Page<'data> {
+    /// The entire page, including the header.
+    pub data: &'data [u8],
+
+    /// The segment table.
+    pub lacing: &'data [u8],
+
+    /// The page body.
+    pub body: &'data [u8],
+}
+
+/// Packet Iterator
+///
+/// An iterator over the packet data of a page. It yields each packet, or
+/// part of a packet, together with a flag telling whether the packet is
+/// completed on this page.
+#[derive(Clone, Debug)]
+pub struct Packets<'data> {
+    lacing: &'data [u8],
+    body: &'data [u8],
+}
+
+impl PageHeader {
+    /// Import a page header from a byte slice
+    ///
+    /// Create a new page header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 27]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 27], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl<'data> Page<'data> {
+    /// Parse a page
+    ///
+    /// Parse the page at the start of `data`. `None` is returned if the
+    /// capture pattern or version do not match, or the page is truncated.
+    /// The checksum is not verified.
+    pub fn parse(data: &'data [u8]) -> Option<Self> {
+        let h = PageHeader::from_bytes(data.get(..HEADER_SIZE)?.try_into().unwrap());
+        if h.magic != MAGIC || h.version != VERSION {
+            return None;
+        }
+
+        let body_at = HEADER_SIZE + h.segments as usize;
+        let lacing = data.get(HEADER_SIZE..body_at)?;
+        let size = lacing.iter().map(|v| *v as usize).sum::<usize>();
+        let body = data.get(body_at..body_at + size)?;
+
+        Some(Self {
+            data: &data[..body_at + size],
+            lacing,
+            body,
+        })
+    }
+
+    /// Return page header
+    pub fn header(&self) -> PageHeader {
+        PageHeader::from_bytes(self.data[..HEADER_SIZE].try_into().unwrap())
+    }
+
+    /// Verify page checksum
+    ///
+    /// Calculate the checksum of this page and compare it to the stored
+    /// checksum.
+    pub fn verify(&self) -> bool {
+        crc(self.data) == int::ForeignEndian::to_native(self.header().checksum)
+    }
+
+    /// Iterate packets
+    pub fn packets(&self) -> Packets<'data> {
+        Packets {
+            lacing: self.lacing,
+            body: self.body,
+        }
+    }
+}
+
+impl<'data> Iterator for Packets<'data> {
+    type Item = (&'data [u8], bool);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.lacing.is_empty() {
+            return None;
+        }
+
+        let mut size = 0;
+        let mut complete = false;
+        while let Some((v, rest)) = self.lacing.split_first() {
+            self.lacing = rest;
+            size += *v as usize;
+            if *v < LACING_MAX {
+                complete = true;
+                break;
+            }
+        }
+
+        let (packet, rest) = self.body.split_at(core::cmp::min(size, self.body.len()));
+        self.body = rest;
+        Some((packet, complete))
+    }
+}
+
+/// Calculate Page Checksum
+///
+/// Calculate the checksum of a complete page, treating the checksum field
+/// as 0. The page must be at least `HEADER_SIZE` bytes long.
+pub fn crc(page: &[u8]) -> u32 {
+    let crc = checksum::crc32_msb_update(0, &page[..CHECKSUM_OFFSET]);
+    let crc = checksum::crc32_msb_update(crc, &[0; 4]);
+    checksum::crc32_msb_update(crc, &page[CHECKSUM_OFFSET + 4..])
+}
+
+/// Calculate Segment Count
+///
+/// Return the number of segments needed for a packet of `size` bytes,
+/// including the terminating segment.
+pub fn segments(size: usize) -> usize {
+    size / LACING_MAX as usize + 1
+}
+
+/// Find Capture Pattern
+///
+/// Return the offset of the next capture pattern in `data`, for example to
+/// resynchronize after corrupted data.
+pub fn find_page(data: &[u8]) -> Option<usize> {
+    data.windows(4).position(|v| v == MAGIC)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The first page of an Opus stream, carrying the identification header.
+    const PAGE: [u8; 47] = [
+        0x4f, 0x67, 0x67, 0x53, 0x00, 0x02, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x78, 0x56,
+        0x34, 0x12, 0x00, 0x00, 0x00, 0x00, 0x23, 0xec,
+        0xb0, 0x3e, 0x01, 0x13, 0x4f, 0x70, 0x75, 0x73,
+        0x48, 0x65, 0x61, 0x64, 0x01, 0x02, 0x38, 0x01,
+        0x80, 0xbb, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<PageHeader>(), HEADER_SIZE);
+        assert_eq!(align_of::<PageHeader>(), 1);
+    }
+
+    // Parse a page and verify its checksum.
+    #[test]
+    fn verify_page() {
+        let p = Page::parse(&PAGE).unwrap();
+        let h = p.header();
+        assert_eq!(h.header_type, HEADER_BOS);
+        assert_eq!(int::ForeignEndian::to_native(h.granule_position), 0);
+        assert_eq!(int::ForeignEndian::to_native(h.serial), 0x12345678);
+        assert_eq!(int::ForeignEndian::to_native(h.sequence), 0);
+        assert!(p.verify());
+
+        let mut packets = p.packets();
+        let (v, complete) = packets.next().unwrap();
+        assert!(complete);
+        assert_eq!(&v[..8], b"OpusHead");
+        assert!(packets.next().is_none());
+
+        let mut corrupt = PAGE;
+        corrupt[40] ^= 0x01;
+        assert!(!Page::parse(&corrupt).unwrap().verify());
+        assert!(Page::parse(&PAGE[..46]).is_none());
+        assert_eq!(find_page(&[0x00, 0x4f, 0x67, 0x67, 0x53]), Some(1));
+    }
+
+    // Verify packet framing across lacing values of 255.
+    #[test]
+    fn verify_packets() {
+        let body = [0u8; 520];
+        let page = Page {
+            data: &[],
+            lacing: &[255, 10, 0, 255],
+            body: &body,
+        };
+
+        let v: std::vec::Vec<_> = page.packets().map(|(v, c)| (v.len(), c)).collect();
+        assert_eq!(v, [(265, true), (0, true), (255, false)]);
+
+        assert_eq!(segments(0), 1);
+        assert_eq!(segments(255), 2);
+        assert_eq!(segments(300), 2);
+        assert_eq!(PAGE_SIZE_MAX, 65307);
+    }
+}
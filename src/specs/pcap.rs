@@ -0,0 +1,307 @@
+//! Packet Capture File Format
+//!
+//! Classic pcap files start with a 24-byte `Header`, followed by a sequence
+//! of packet records (see `Records`). Each record consists of a 16-byte
+//! `RecordHeader` with the capture timestamp and lengths, followed by the
+//! captured packet data. Records are not padded.
+//!
+//! The byte order of a file is that of the machine that wrote it, and is
+//! detected from the magic number at the start of the header. The magic
+//! number also selects whether the sub-second part of timestamps is given
+//! in microseconds (`MAGIC`) or nanoseconds (`MAGIC_NANOSECONDS`). Hence,
+//! all structures use `int::RuntimeEndian` and require the byte order on
+//! access.
+//!
+//! The link-layer types (`LINKTYPE_*`) define the format of the captured
+//! packet data, and are shared with `pcapng`.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::int;
+
+pub const HEADER_SIZE: usize = 24;
+pub const RECORD_HEADER_SIZE: usize = 16;
+
+/// Magic Number of Files with Microsecond Timestamps
+pub const MAGIC: u32 = 0xa1b2c3d4;
+
+/// Magic Number of Files with Nanosecond Timestamps
+pub const MAGIC_NANOSECONDS: u32 = 0xa1b23c4d;
+
+pub const VERSION_MAJOR: u16 = 2;
+pub const VERSION_MINOR: u16 = 4;
+
+pub const LINKTYPE_MASK: u32 = 0x0000ffff;
+pub const LINKTYPE_FLAG_FCS: u32 = 0x10000000;
+pub const LINKTYPE_FCS_LENGTH_SHIFT: u32 = 29;
+
+pub const LINKTYPE_NULL: u16 = 0;
+pub const LINKTYPE_ETHERNET: u16 = 1;
+pub const LINKTYPE_PPP: u16 = 9;
+pub const LINKTYPE_RAW: u16 = 101;
+pub const LINKTYPE_IEEE802_11: u16 = 105;
+pub const LINKTYPE_LOOP: u16 = 108;
+pub const LINKTYPE_LINUX_SLL: u16 = 113;
+pub const LINKTYPE_IEEE802_11_RADIOTAP: u16 = 127;
+pub const LINKTYPE_BLUETOOTH_HCI_H4: u16 = 187;
+pub const LINKTYPE_USB_LINUX: u16 = 189;
+pub const LINKTYPE_BLUETOOTH_HCI_H4_WITH_PHDR: u16 = 201;
+pub const LINKTYPE_USB_LINUX_MMAPPED: u16 = 220;
+pub const LINKTYPE_CAN_SOCKETCAN: u16 = 227;
+pub const LINKTYPE_IPV4: u16 = 228;
+pub const LINKTYPE_IPV6: u16 = 229;
+pub const LINKTYPE_USBPCAP: u16 = 249;
+pub const LINKTYPE_NETLINK: u16 = 253;
+pub const LINKTYPE_BLUETOOTH_LINUX_MONITOR: u16 = 254;
+pub const LINKTYPE_LINUX_SLL2: u16 = 276;
+
+/// Global Header
+#[repr(C)]
+pub struct Header {
+    /// Magic number, either `MAGIC` or `MAGIC_NANOSECONDS`.
+    pub magic: int::u32re,
+
+    /// Major version, must be `VERSION_MAJOR`.
+    pub version_major: int::u16re,
+
+    /// Minor version, must be `VERSION_MINOR`.
+    pub version_minor: int::u16re,
+
+    /// Offset of local time from UTC in seconds, usually 0.
+    pub thiszone: int::i32re,
+
+    /// Accuracy of timestamps, usually 0.
+    pub sigfigs: int::u32re,
+
+    /// Maximum number of bytes captured per packet.
+    pub snaplen: int::u32re,
+
+    /// Link-layer type (`LINKTYPE_*`) in the lower 16 bits, and FCS
+    /// information in the upper bits.
+    pub network: int::u32re,
+}
+
+/// Record Header
+#[repr(C)]
+pub struct RecordHeader {
+    /// Timestamp in seconds since the epoch.
+    pub ts_sec: int::u32re,
+
+    /// Sub-second part of the timestamp, in microseconds or nanoseconds.
+    pub ts_subsec: int::u32re,
+
+    /// Number of bytes of captured packet data following this header.
+    pub captured_length: int::u32re,
+
+    /// Size of the packet on the wire, which can exceed the captured length.
+    pub original_length: int::u32re,
+}
+
+/// Record Iterator
+///
+/// An iterator over the records of a file, yielding the record header and
+/// the captured packet data. Iteration stops at the end of the data, or at
+/// the first truncated record.
+#[derive(Clone, Debug)]
+pub struct Records<'data> {
+    data: &'data [u8],
+    endian: int::Endianness,
+}
+
+impl Header {
+    /// Import a header from a byte slice
+    ///
+    /// Create a new header from data copied from a byte slice. No byte-order
+    /// conversions are applied.
+    pub fn from_bytes(data: &[u8; 24]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 24], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Return byte order
+    ///
+    /// Return the byte order of the file, or `None` if the header carries an
+    /// invalid magic number.
+    pub fn endianness(&self) -> Option<int::Endianness> {
+        detect(self.magic.to_raw().to_ne_bytes()).map(|v| v.0)
+    }
+
+    /// Check for nanosecond timestamps
+    ///
+    /// Return whether the sub-second part of timestamps is given in
+    /// nanoseconds, rather than microseconds.
+    pub fn is_nanoseconds(&self, endian: int::Endianness) -> bool {
+        self.magic.to_native(endian) == MAGIC_NANOSECONDS
+    }
+
+    /// Return link-layer type
+    pub fn link_type(&self, endian: int::Endianness) -> u16 {
+        (self.network.to_native(endian) & LINKTYPE_MASK) as u16
+    }
+
+    /// Return FCS length
+    ///
+    /// Return the number of bytes of frame check sequence at the end of
+    /// each packet, if given by the header.
+    pub fn fcs_length(&self, endian: int::Endianness) -> Option<usize> {
+        let v = self.network.to_native(endian);
+        if v & LINKTYPE_FLAG_FCS != 0 {
+            Some((v >> LINKTYPE_FCS_LENGTH_SHIFT) as usize * 2)
+        } else {
+            None
+        }
+    }
+}
+
+impl RecordHeader {
+    /// Import a record header from a byte slice
+    ///
+    /// Create a new record header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 16]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 16], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl<'data> Records<'data> {
+    /// Create record iterator
+    ///
+    /// Create a new iterator over the records in `data`, which must start
+    /// after the global header. `endian` is the byte order of the file.
+    pub fn new(data: &'data [u8], endian: int::Endianness) -> Self {
+        Self {
+            data,
+            endian,
+        }
+    }
+
+    /// Return remaining data
+    ///
+    /// Return the data that has not been consumed by the iterator.
+    pub fn remainder(&self) -> &'data [u8] {
+        self.data
+    }
+}
+
+impl<'data> Iterator for Records<'data> {
+    type Item = (RecordHeader, &'data [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let h = RecordHeader::from_bytes(self.data.get(..RECORD_HEADER_SIZE)?.try_into().unwrap());
+        let n = RECORD_HEADER_SIZE + h.captured_length.to_native(self.endian) as usize;
+        let v = self.data.get(RECORD_HEADER_SIZE..n)?;
+        self.data = &self.data[n..];
+        Some((h, v))
+    }
+}
+
+/// Detect byte order
+///
+/// Return the byte order selected by the raw magic number at the start of a
+/// file, and whether timestamps are given in nanoseconds. Return `None` if
+/// the magic number is invalid.
+pub fn detect(magic: [u8; 4]) -> Option<(int::Endianness, bool)> {
+    match u32::from_be_bytes(magic) {
+        MAGIC => Some((int::Endianness::Big, false)),
+        MAGIC_NANOSECONDS => Some((int::Endianness::Big, true)),
+        _ => match u32::from_le_bytes(magic) {
+            MAGIC => Some((int::Endianness::Little, false)),
+            MAGIC_NANOSECONDS => Some((int::Endianness::Little, true)),
+            _ => None,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A little-endian file with microsecond timestamps and two records.
+    const FILE: [u8; 62] = [
+        0xd4, 0xc3, 0xb2, 0xa1, 0x02, 0x00, 0x04, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0xff, 0xff, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00,
+        0x00, 0xe1, 0xf5, 0x05, 0x40, 0xe2, 0x01, 0x00,
+        0x04, 0x00, 0x00, 0x00, 0x40, 0x00, 0x00, 0x00,
+        0xde, 0xad, 0xbe, 0xef, 0x01, 0xe1, 0xf5, 0x05,
+        0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00,
+        0x02, 0x00, 0x00, 0x00, 0x01, 0x02,
+    ];
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<Header>(), HEADER_SIZE);
+        assert_eq!(align_of::<Header>(), 4);
+        assert_eq!(size_of::<RecordHeader>(), RECORD_HEADER_SIZE);
+        assert_eq!(align_of::<RecordHeader>(), 4);
+    }
+
+    // Parse a little-endian file and iterate its records.
+    #[test]
+    fn verify_file() {
+        let h = Header::from_bytes(FILE[..24].try_into().unwrap());
+        let e = h.endianness().unwrap();
+        assert_eq!(e, int::Endianness::Little);
+        assert!(!h.is_nanoseconds(e));
+        assert_eq!(h.version_major.to_native(e), VERSION_MAJOR);
+        assert_eq!(h.version_minor.to_native(e), VERSION_MINOR);
+        assert_eq!(h.snaplen.to_native(e), 65535);
+        assert_eq!(h.link_type(e), LINKTYPE_ETHERNET);
+        assert_eq!(h.fcs_length(e), None);
+
+        let mut records = Records::new(&FILE[HEADER_SIZE..], e);
+        let (r, v) = records.next().unwrap();
+        assert_eq!(r.ts_sec.to_native(e), 100000000);
+        assert_eq!(r.ts_subsec.to_native(e), 123456);
+        assert_eq!(r.original_length.to_native(e), 64);
+        assert_eq!(v, &[0xde, 0xad, 0xbe, 0xef]);
+        let (r, v) = records.next().unwrap();
+        assert_eq!(r.ts_sec.to_native(e), 100000001);
+        assert_eq!(v, &[0x01, 0x02]);
+        assert!(records.next().is_none());
+        assert!(records.remainder().is_empty());
+
+        // A truncated record stops iteration.
+        assert!(Records::new(&FILE[HEADER_SIZE..43], e).next().is_none());
+    }
+
+    // Detect all magic numbers and decode FCS information.
+    #[test]
+    fn verify_detect() {
+        assert_eq!(detect([0xa1, 0xb2, 0xc3, 0xd4]), Some((int::Endianness::Big, false)));
+        assert_eq!(detect([0xd4, 0xc3, 0xb2, 0xa1]), Some((int::Endianness::Little, false)));
+        assert_eq!(detect([0xa1, 0xb2, 0x3c, 0x4d]), Some((int::Endianness::Big, true)));
+        assert_eq!(detect([0x4d, 0x3c, 0xb2, 0xa1]), Some((int::Endianness::Little, true)));
+        assert_eq!(detect([0x0a, 0x0d, 0x0d, 0x0a]), None);
+
+        let mut data: [u8; 24] = FILE[..24].try_into().unwrap();
+        data[..4].copy_from_slice(&[0xa1, 0xb2, 0x3c, 0x4d]);
+        data[20..24].copy_from_slice(&[0x90, 0x00, 0x00, 0x7f]);
+        let h = Header::from_bytes(&data);
+        let e = h.endianness().unwrap();
+        assert_eq!(e, int::Endianness::Big);
+        assert!(h.is_nanoseconds(e));
+        assert_eq!(h.link_type(e), LINKTYPE_IEEE802_11_RADIOTAP);
+        assert_eq!(h.fcs_length(e), Some(8));
+    }
+}
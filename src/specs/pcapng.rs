@@ -0,0 +1,608 @@
+//! PCAP Next Generation Capture File Format
+//!
+//! PCAPNG files are a sequence of blocks (see `Blocks`). Each block starts
+//! with a `BlockHeader` carrying the block type and the total size of the
+//! block, followed by the block body, padded to a multiple of 4 bytes, and a
+//! trailing copy of the total size. Most block bodies consist of a fixed
+//! part, followed by a sequence of options (see `Options`), encoded as TLVs
+//! with 2-byte code and 2-byte length, where the length excludes the 4-byte
+//! option header and the padding to a multiple of 4 bytes.
+//!
+//! A file consists of one or more sections, each starting with a Section
+//! Header Block (`BLOCK_TYPE_SECTION_HEADER`). The byte order of a section
+//! is that of the machine that wrote it, and is detected from the byte-order
+//! magic in the section header. Hence, all structures use
+//! `int::RuntimeEndian` and require the byte order on access. Interface
+//! Description Blocks assign interface identifiers in order of appearance
+//! within a section, which packet and statistics blocks refer to.
+//!
+//! Link-layer types are shared with classic pcap files (see
+//! `pcap::LINKTYPE_*`).
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::int;
+
+pub const BLOCK_HEADER_SIZE: usize = 8;
+pub const BLOCK_TRAILER_SIZE: usize = 4;
+pub const SECTION_HEADER_SIZE: usize = 16;
+pub const INTERFACE_DESCRIPTION_SIZE: usize = 8;
+pub const ENHANCED_PACKET_SIZE: usize = 20;
+pub const INTERFACE_STATISTICS_SIZE: usize = 12;
+pub const OPTION_HEADER_SIZE: usize = 4;
+
+/// Byte-order Magic of Section Headers
+pub const BYTE_ORDER_MAGIC: u32 = 0x1a2b3c4d;
+
+pub const VERSION_MAJOR: u16 = 1;
+pub const VERSION_MINOR: u16 = 0;
+
+/// Section Length of Sections of Unspecified Size
+pub const SECTION_LENGTH_UNSPECIFIED: u64 = u64::MAX;
+
+pub const BLOCK_TYPE_INTERFACE_DESCRIPTION: u32 = 0x00000001;
+/* reserved: 0x00000002 (obsolete packet block) */
+pub const BLOCK_TYPE_SIMPLE_PACKET: u32 = 0x00000003;
+pub const BLOCK_TYPE_NAME_RESOLUTION: u32 = 0x00000004;
+pub const BLOCK_TYPE_INTERFACE_STATISTICS: u32 = 0x00000005;
+pub const BLOCK_TYPE_ENHANCED_PACKET: u32 = 0x00000006;
+pub const BLOCK_TYPE_DECRYPTION_SECRETS: u32 = 0x0000000a;
+pub const BLOCK_TYPE_CUSTOM: u32 = 0x00000bad;
+pub const BLOCK_TYPE_CUSTOM_NO_COPY: u32 = 0x40000bad;
+pub const BLOCK_TYPE_SECTION_HEADER: u32 = 0x0a0d0d0a;
+
+pub const OPTION_END: u16 = 0;
+pub const OPTION_COMMENT: u16 = 1;
+pub const OPTION_CUSTOM_UTF8: u16 = 2988;
+pub const OPTION_CUSTOM_BINARY: u16 = 2989;
+pub const OPTION_CUSTOM_UTF8_NO_COPY: u16 = 19372;
+pub const OPTION_CUSTOM_BINARY_NO_COPY: u16 = 19373;
+
+pub const SHB_OPTION_HARDWARE: u16 = 2;
+pub const SHB_OPTION_OS: u16 = 3;
+pub const SHB_OPTION_USER_APPLICATION: u16 = 4;
+
+pub const IF_OPTION_NAME: u16 = 2;
+pub const IF_OPTION_DESCRIPTION: u16 = 3;
+pub const IF_OPTION_IPV4_ADDRESS: u16 = 4;
+pub const IF_OPTION_IPV6_ADDRESS: u16 = 5;
+pub const IF_OPTION_MAC_ADDRESS: u16 = 6;
+pub const IF_OPTION_EUI_ADDRESS: u16 = 7;
+pub const IF_OPTION_SPEED: u16 = 8;
+pub const IF_OPTION_TSRESOL: u16 = 9;
+pub const IF_OPTION_TZONE: u16 = 10;
+pub const IF_OPTION_FILTER: u16 = 11;
+pub const IF_OPTION_OS: u16 = 12;
+pub const IF_OPTION_FCS_LENGTH: u16 = 13;
+pub const IF_OPTION_TSOFFSET: u16 = 14;
+pub const IF_OPTION_HARDWARE: u16 = 15;
+pub const IF_OPTION_TX_SPEED: u16 = 16;
+pub const IF_OPTION_RX_SPEED: u16 = 17;
+
+pub const EPB_OPTION_FLAGS: u16 = 2;
+pub const EPB_OPTION_HASH: u16 = 3;
+pub const EPB_OPTION_DROP_COUNT: u16 = 4;
+pub const EPB_OPTION_PACKET_ID: u16 = 5;
+pub const EPB_OPTION_QUEUE: u16 = 6;
+pub const EPB_OPTION_VERDICT: u16 = 7;
+
+pub const ISB_OPTION_START_TIME: u16 = 2;
+pub const ISB_OPTION_END_TIME: u16 = 3;
+pub const ISB_OPTION_IF_RECV: u16 = 4;
+pub const ISB_OPTION_IF_DROP: u16 = 5;
+pub const ISB_OPTION_FILTER_ACCEPT: u16 = 6;
+pub const ISB_OPTION_OS_DROP: u16 = 7;
+pub const ISB_OPTION_USER_DELIVERED: u16 = 8;
+
+/// Default Timestamp Resolution
+///
+/// The number of timestamp units per second of interfaces without
+/// `IF_OPTION_TSRESOL`.
+pub const TSRESOL_DEFAULT: u64 = 1_000_000;
+
+/// Block Header
+#[repr(C)]
+pub struct BlockHeader {
+    /// Block type (`BLOCK_TYPE_*`).
+    pub block_type: int::u32re,
+
+    /// Size of the block in bytes, including header, padding and trailer.
+    pub total_length: int::u32re,
+}
+
+/// Section Header Block Body
+#[repr(C)]
+pub struct SectionHeader {
+    /// Byte-order magic, must be `BYTE_ORDER_MAGIC`.
+    pub byte_order_magic: int::u32re,
+
+    /// Major version, must be `VERSION_MAJOR`.
+    pub version_major: int::u16re,
+
+    /// Minor version, must be `VERSION_MINOR`.
+    pub version_minor: int::u16re,
+
+    /// Size of the section in bytes, excluding this block, or
+    /// `SECTION_LENGTH_UNSPECIFIED`.
+    pub section_length: int::u64re,
+}
+
+/// Interface Description Block Body
+#[repr(C)]
+pub struct InterfaceDescription {
+    /// Link-layer type (`pcap::LINKTYPE_*`).
+    pub link_type: int::u16re,
+
+    /// Reserved, must be 0.
+    pub reserved: int::u16re,
+
+    /// Maximum number of bytes captured per packet, or 0 if unlimited.
+    pub snaplen: int::u32re,
+}
+
+/// Enhanced Packet Block Body
+#[repr(C)]
+pub struct EnhancedPacket {
+    /// Identifier of the interface the packet was captured on.
+    pub interface_id: int::u32re,
+
+    /// Upper 32 bits of the timestamp.
+    pub timestamp_high: int::u32re,
+
+    /// Lower 32 bits of the timestamp.
+    pub timestamp_low: int::u32re,
+
+    /// Number of bytes of captured packet data following this structure,
+    /// excluding padding.
+    pub captured_length: int::u32re,
+
+    /// Size of the packet on the wire, which can exceed the captured length.
+    pub original_length: int::u32re,
+}
+
+/// Interface Statistics Block Body
+#[repr(C)]
+pub struct InterfaceStatistics {
+    /// Identifier of the interface the statistics refer to.
+    pub interface_id: int::u32re,
+
+    /// Upper 32 bits of the timestamp.
+    pub timestamp_high: int::u32re,
+
+    /// Lower 32 bits of the timestamp.
+    pub timestamp_low: int::u32re,
+}
+
+/// Block Reference
+///
+/// A block with its framing validated and the byte order of its section
+/// resolved.
+#[derive(Clone, Copy, Debug)]
+pub struct Block<'data> {
+    /// Block type (`BLOCK_TYPE_*`).
+    pub block_type: u32,
+
+    /// Byte order of the section the block belongs to.
+    pub endian: int::Endianness,
+
+    /// Block body, including padding but excluding header and trailer.
+    pub body: &'data [u8],
+}
+
+/// Block Iterator
+///
+/// An iterator over the blocks of a file. The byte order is detected from
+/// each section header, so the data must start with a section header.
+/// Iteration stops at the end of the data, or at the first truncated or
+/// malformed block.
+#[derive(Clone, Debug)]
+pub struct Blocks<'data> {
+    data: &'data [u8],
+    endian: Option<int::Endianness>,
+}
+
+/// Option Iterator
+///
+/// An iterator over the options of a block body, yielding the option code
+/// and data without the option header or padding. Iteration stops at
+/// `OPTION_END`, at the end of the data, or at the first truncated option.
+#[derive(Clone, Debug)]
+pub struct Options<'data> {
+    data: &'data [u8],
+    endian: int::Endianness,
+}
+
+impl BlockHeader {
+    /// Import a block header from a byte slice
+    ///
+    /// Create a new block header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 8]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 8], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl SectionHeader {
+    /// Import a section header from a byte slice
+    ///
+    /// Create a new section header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 16]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 16], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Return byte order
+    ///
+    /// Return the byte order of the section, or `None` if the section header
+    /// carries an invalid byte-order magic.
+    pub fn endianness(&self) -> Option<int::Endianness> {
+        endianness(self.byte_order_magic.to_raw().to_ne_bytes())
+    }
+}
+
+impl InterfaceDescription {
+    /// Import an interface description from a byte slice
+    ///
+    /// Create a new interface description from data copied from a byte
+    /// slice. No byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 8]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 8], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl EnhancedPacket {
+    /// Import an enhanced packet from a byte slice
+    ///
+    /// Create a new enhanced packet from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 20]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 20], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Return timestamp
+    ///
+    /// Return the timestamp in units of the timestamp resolution of the
+    /// interface (see `tsresol()`).
+    pub fn timestamp(&self, endian: int::Endianness) -> u64 {
+        ((self.timestamp_high.to_native(endian) as u64) << 32)
+            | self.timestamp_low.to_native(endian) as u64
+    }
+}
+
+impl InterfaceStatistics {
+    /// Import interface statistics from a byte slice
+    ///
+    /// Create new interface statistics from data copied from a byte slice.
+    /// No byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 12]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 12], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Return timestamp
+    ///
+    /// Return the timestamp in units of the timestamp resolution of the
+    /// interface (see `tsresol()`).
+    pub fn timestamp(&self, endian: int::Endianness) -> u64 {
+        ((self.timestamp_high.to_native(endian) as u64) << 32)
+            | self.timestamp_low.to_native(endian) as u64
+    }
+}
+
+impl<'data> Block<'data> {
+    /// Return enhanced packet
+    ///
+    /// Return the fixed part and the captured packet data of an enhanced
+    /// packet block, or `None` if this is no enhanced packet block or it is
+    /// truncated.
+    pub fn enhanced_packet(&self) -> Option<(EnhancedPacket, &'data [u8])> {
+        if self.block_type != BLOCK_TYPE_ENHANCED_PACKET {
+            return None;
+        }
+
+        let p = EnhancedPacket::from_bytes(self.body.get(..ENHANCED_PACKET_SIZE)?.try_into().unwrap());
+        let n = p.captured_length.to_native(self.endian) as usize;
+        let v = self.body.get(ENHANCED_PACKET_SIZE..ENHANCED_PACKET_SIZE.checked_add(n)?)?;
+        Some((p, v))
+    }
+
+    /// Return block options
+    ///
+    /// Return an iterator over the options of the block, or `None` if the
+    /// block type carries no options or is unknown, or the block is
+    /// truncated.
+    pub fn options(&self) -> Option<Options<'data>> {
+        let o = match self.block_type {
+            BLOCK_TYPE_SECTION_HEADER => SECTION_HEADER_SIZE,
+            BLOCK_TYPE_INTERFACE_DESCRIPTION => INTERFACE_DESCRIPTION_SIZE,
+            BLOCK_TYPE_INTERFACE_STATISTICS => INTERFACE_STATISTICS_SIZE,
+            BLOCK_TYPE_ENHANCED_PACKET => {
+                let (_, v) = self.enhanced_packet()?;
+                ENHANCED_PACKET_SIZE + padded(v.len())
+            },
+            _ => return None,
+        };
+
+        Some(Options::new(self.body.get(o..)?, self.endian))
+    }
+}
+
+impl<'data> Blocks<'data> {
+    /// Create block iterator
+    ///
+    /// Create a new iterator over the blocks in `data`, which must start with
+    /// a section header block.
+    pub fn new(data: &'data [u8]) -> Self {
+        Self {
+            data,
+            endian: None,
+        }
+    }
+
+    /// Return remaining data
+    ///
+    /// Return the data that has not been consumed by the iterator.
+    pub fn remainder(&self) -> &'data [u8] {
+        self.data
+    }
+}
+
+impl<'data> Iterator for Blocks<'data> {
+    type Item = Block<'data>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let h = BlockHeader::from_bytes(self.data.get(..BLOCK_HEADER_SIZE)?.try_into().unwrap());
+
+        // The section header type reads the same in both byte orders, and
+        // its body starts with the byte-order magic.
+        if h.block_type.to_raw() == BLOCK_TYPE_SECTION_HEADER {
+            let magic = self.data.get(BLOCK_HEADER_SIZE..BLOCK_HEADER_SIZE + 4)?;
+            self.endian = Some(endianness(magic.try_into().unwrap())?);
+        }
+
+        let endian = self.endian?;
+        let n = h.total_length.to_native(endian) as usize;
+        if n < BLOCK_HEADER_SIZE + BLOCK_TRAILER_SIZE || n & 3 != 0 {
+            return None;
+        }
+
+        let trailer = self.data.get(n - BLOCK_TRAILER_SIZE..n)?;
+        let trailer = int::u32re::from_raw(u32::from_ne_bytes(trailer.try_into().unwrap()));
+        if trailer.to_native(endian) as usize != n {
+            return None;
+        }
+
+        let body = &self.data[BLOCK_HEADER_SIZE..n - BLOCK_TRAILER_SIZE];
+        self.data = &self.data[n..];
+        Some(Block {
+            block_type: h.block_type.to_native(endian),
+            endian,
+            body,
+        })
+    }
+}
+
+impl<'data> Options<'data> {
+    /// Create option iterator
+    ///
+    /// Create a new iterator over the options in `data`, which must start
+    /// after the fixed part of a block body. `endian` is the byte order of
+    /// the section.
+    pub fn new(data: &'data [u8], endian: int::Endianness) -> Self {
+        Self {
+            data,
+            endian,
+        }
+    }
+
+    /// Return remaining data
+    ///
+    /// Return the data that has not been consumed by the iterator.
+    pub fn remainder(&self) -> &'data [u8] {
+        self.data
+    }
+}
+
+impl<'data> Iterator for Options<'data> {
+    type Item = (u16, &'data [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let code = int::u16re::from_raw(u16::from_ne_bytes(self.data.get(..2)?.try_into().unwrap()));
+        let n = int::u16re::from_raw(u16::from_ne_bytes(self.data.get(2..4)?.try_into().unwrap()));
+        let code = code.to_native(self.endian);
+        let n = n.to_native(self.endian) as usize;
+        if code == OPTION_END {
+            return None;
+        }
+
+        let v = self.data.get(OPTION_HEADER_SIZE..OPTION_HEADER_SIZE + n)?;
+        self.data = self.data.get(OPTION_HEADER_SIZE + padded(n)..).unwrap_or(&[]);
+        Some((code, v))
+    }
+}
+
+/// Detect byte order
+///
+/// Return the byte order selected by the raw byte-order magic of a section
+/// header, or `None` if it is invalid.
+pub fn endianness(magic: [u8; 4]) -> Option<int::Endianness> {
+    if u32::from_be_bytes(magic) == BYTE_ORDER_MAGIC {
+        Some(int::Endianness::Big)
+    } else if u32::from_le_bytes(magic) == BYTE_ORDER_MAGIC {
+        Some(int::Endianness::Little)
+    } else {
+        None
+    }
+}
+
+/// Round up to padding
+///
+/// Return the size rounded up to the next multiple of 4 bytes, as used for
+/// packet data and option values.
+pub fn padded(size: usize) -> usize {
+    (size + 3) & !3
+}
+
+/// Decode timestamp resolution
+///
+/// Return the number of timestamp units per second encoded in the value of
+/// an `IF_OPTION_TSRESOL` option, or `None` if it overflows. If the most
+/// significant bit is set, the remaining bits are a power of 2, otherwise a
+/// power of 10.
+pub fn tsresol(value: u8) -> Option<u64> {
+    if value & 0x80 != 0 {
+        1u64.checked_shl((value & 0x7f) as u32)
+    } else {
+        10u64.checked_pow(value as u32)
+    }
+}
+
+/// Find option
+///
+/// Return the data of the first option with the given code in `data`.
+pub fn find_option(data: &[u8], endian: int::Endianness, code: u16) -> Option<&[u8]> {
+    Options::new(data, endian).find(|v| v.0 == code).map(|v| v.1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A little-endian section with a comment, an Ethernet interface with
+    // nanosecond timestamps, and an enhanced packet with flags.
+    const FILE: [u8; 116] = [
+        0x0a, 0x0d, 0x0d, 0x0a, 0x24, 0x00, 0x00, 0x00,
+        0x4d, 0x3c, 0x2b, 0x1a, 0x01, 0x00, 0x00, 0x00,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0x01, 0x00, 0x02, 0x00, 0x68, 0x69, 0x00, 0x00,
+        0x24, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00,
+        0x20, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x04, 0x00, 0x09, 0x00, 0x01, 0x00,
+        0x09, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x20, 0x00, 0x00, 0x00, 0x06, 0x00, 0x00, 0x00,
+        0x30, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x01, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00,
+        0x03, 0x00, 0x00, 0x00, 0x05, 0x00, 0x00, 0x00,
+        0xaa, 0xbb, 0xcc, 0x00, 0x02, 0x00, 0x04, 0x00,
+        0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,        0x30, 0x00, 0x00, 0x00,
+    ];
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<BlockHeader>(), BLOCK_HEADER_SIZE);
+        assert_eq!(align_of::<BlockHeader>(), 4);
+        assert_eq!(size_of::<SectionHeader>(), SECTION_HEADER_SIZE);
+        assert_eq!(align_of::<SectionHeader>(), 8);
+        assert_eq!(size_of::<InterfaceDescription>(), INTERFACE_DESCRIPTION_SIZE);
+        assert_eq!(align_of::<InterfaceDescription>(), 4);
+        assert_eq!(size_of::<EnhancedPacket>(), ENHANCED_PACKET_SIZE);
+        assert_eq!(align_of::<EnhancedPacket>(), 4);
+        assert_eq!(size_of::<InterfaceStatistics>(), INTERFACE_STATISTICS_SIZE);
+        assert_eq!(align_of::<InterfaceStatistics>(), 4);
+    }
+
+    // Iterate the blocks of a section and decode their bodies and options.
+    #[test]
+    fn verify_blocks() {
+        let mut blocks = Blocks::new(&FILE);
+
+        let b = blocks.next().unwrap();
+        let e = b.endian;
+        assert_eq!(b.block_type, BLOCK_TYPE_SECTION_HEADER);
+        assert_eq!(e, int::Endianness::Little);
+        let s = SectionHeader::from_bytes(b.body[..16].try_into().unwrap());
+        assert_eq!(s.endianness(), Some(e));
+        assert_eq!(s.version_major.to_native(e), VERSION_MAJOR);
+        assert_eq!(s.section_length.to_native(e), SECTION_LENGTH_UNSPECIFIED);
+        let mut opts = b.options().unwrap();
+        assert_eq!(opts.next(), Some((OPTION_COMMENT, &b"hi"[..])));
+        assert!(opts.next().is_none());
+
+        let b = blocks.next().unwrap();
+        assert_eq!(b.block_type, BLOCK_TYPE_INTERFACE_DESCRIPTION);
+        let i = InterfaceDescription::from_bytes(b.body[..8].try_into().unwrap());
+        assert_eq!(i.link_type.to_native(e), 1);
+        assert_eq!(i.snaplen.to_native(e), 0x40000);
+        let v = find_option(&b.body[8..], e, IF_OPTION_TSRESOL).unwrap();
+        assert_eq!(tsresol(v[0]), Some(1_000_000_000));
+
+        let b = blocks.next().unwrap();
+        let (p, v) = b.enhanced_packet().unwrap();
+        assert_eq!(p.interface_id.to_native(e), 0);
+        assert_eq!(p.timestamp(e), 0x0000_0001_0000_0002);
+        assert_eq!(p.original_length.to_native(e), 5);
+        assert_eq!(v, &[0xaa, 0xbb, 0xcc]);
+        let mut opts = b.options().unwrap();
+        assert_eq!(opts.next(), Some((EPB_OPTION_FLAGS, &[0x01, 0x00, 0x00, 0x00][..])));
+        assert!(opts.next().is_none());
+
+        assert!(blocks.next().is_none());
+        assert!(blocks.remainder().is_empty());
+    }
+
+    // Reject malformed framing and decode timestamp resolutions.
+    #[test]
+    fn verify_framing() {
+        // Blocks before the first section header cannot be decoded.
+        assert!(Blocks::new(&FILE[36..]).next().is_none());
+
+        // The trailing length must match.
+        let mut data = FILE;
+        data[32] = 0x20;
+        assert!(Blocks::new(&data).next().is_none());
+        assert!(Blocks::new(&FILE[..35]).next().is_none());
+
+        assert_eq!(endianness([0x1a, 0x2b, 0x3c, 0x4d]), Some(int::Endianness::Big));
+        assert_eq!(endianness([0xa1, 0xb2, 0xc3, 0xd4]), None);
+
+        assert_eq!(padded(0), 0);
+        assert_eq!(padded(5), 8);
+        assert_eq!(tsresol(6), Some(TSRESOL_DEFAULT));
+        assert_eq!(tsresol(0x8a), Some(1024));
+        assert_eq!(tsresol(20), None);
+    }
+}
@@ -0,0 +1,568 @@
+//! PCI Expansion ROM
+//!
+//! PCI devices can carry an expansion ROM with initialization code or
+//! drivers run by the platform firmware, mapped via the expansion ROM BAR
+//! (see `pci::ROM_*`). The ROM contains a chain of images, each starting
+//! with a ROM header (`RomHeader`) with the static `SIGNATURE`. The header
+//! points to the PCI data structure (`DataStructure`), describing the
+//! device the image supports, its code type, and its length.
+//!
+//! Images are placed consecutively, aligned to `IMAGE_UNIT` bytes. The
+//! image length of the PCI data structure locates the next image, and
+//! `INDICATOR_LAST` marks the final image of the chain. The remainder of the
+//! ROM header is specific to the code type of the image: legacy x86 images
+//! (`LegacyRomHeader`) must sum up to 0 modulo 256, and EFI images
+//! (`EfiRomHeader`) wrap a PE/COFF driver.
+//!
+//! All multi-byte integers are encoded as little-endian. Since the
+//! structures are not naturally aligned within an image, they are packed.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::checksum;
+use crate::specs::int;
+
+pub const SIGNATURE: [u8; 2] = [0x55, 0xaa];
+pub const PCIR_SIGNATURE: [u8; 4] = *b"PCIR";
+pub const PNP_SIGNATURE: [u8; 4] = *b"$PnP";
+
+/// Image Unit
+///
+/// The unit of image sizes and the alignment of images, in bytes.
+pub const IMAGE_UNIT: usize = 512;
+
+pub const PCIR_REVISION_2: u8 = 0x00;
+pub const PCIR_REVISION_3: u8 = 0x03;
+pub const PCIR_SIZE_V2: usize = 0x18;
+pub const PCIR_SIZE_V3: usize = 0x1c;
+
+pub const CODE_TYPE_X86: u8 = 0x00;
+pub const CODE_TYPE_OPEN_FIRMWARE: u8 = 0x01;
+pub const CODE_TYPE_HP_PA_RISC: u8 = 0x02;
+pub const CODE_TYPE_EFI: u8 = 0x03;
+pub const CODE_TYPE_NONE: u8 = 0xff;
+
+pub const INDICATOR_LAST: u8 = 0x80;
+
+/// EFI Image Signature
+///
+/// The signature of `EfiRomHeader`, following the size of the image.
+pub const EFI_SIGNATURE: u32 = 0x0ef1;
+
+pub const EFI_SUBSYSTEM_APPLICATION: u16 = 10;
+pub const EFI_SUBSYSTEM_BOOT_SERVICE_DRIVER: u16 = 11;
+pub const EFI_SUBSYSTEM_RUNTIME_DRIVER: u16 = 12;
+
+pub const EFI_COMPRESSION_NONE: u16 = 0x0000;
+pub const EFI_COMPRESSION_EFI: u16 = 0x0001;
+
+/// Expansion ROM Header
+///
+/// The header common to all images of an expansion ROM.
+#[repr(C, packed)]
+pub struct RomHeader {
+    /// The static signature. This must match `SIGNATURE`.
+    pub signature: [u8; 2],
+
+    /// Code-type specific data.
+    pub reserved: [u8; 22],
+
+    /// Offset of the PCI data structure from the start of the image.
+    pub pcir_offset: int::u16le,
+}
+
+/// Legacy Expansion ROM Header
+///
+/// The header of images of `CODE_TYPE_X86`, as executed by PC-compatible
+/// legacy BIOS.
+#[repr(C, packed)]
+pub struct LegacyRomHeader {
+    /// The static signature. This must match `SIGNATURE`.
+    pub signature: [u8; 2],
+
+    /// Size of the image in units of `IMAGE_UNIT`.
+    pub size: u8,
+
+    /// Instruction jumping to the initialization code.
+    pub init_entry_point: [u8; 3],
+
+    /// Reserved.
+    pub reserved: [u8; 18],
+
+    /// Offset of the PCI data structure from the start of the image.
+    pub pcir_offset: int::u16le,
+}
+
+/// EFI Expansion ROM Header
+///
+/// The header of images of `CODE_TYPE_EFI`, wrapping a PE/COFF image.
+#[repr(C, packed)]
+pub struct EfiRomHeader {
+    /// The static signature. This must match `SIGNATURE`.
+    pub signature: [u8; 2],
+
+    /// Size of the image in units of `IMAGE_UNIT`.
+    pub initialization_size: int::u16le,
+
+    /// The static EFI signature. This must match `EFI_SIGNATURE`.
+    pub efi_signature: int::u32le,
+
+    /// PE/COFF subsystem of the image (`EFI_SUBSYSTEM_*`).
+    pub efi_subsystem: int::u16le,
+
+    /// PE/COFF machine type of the image (`pecoff::MACHINE_*`).
+    pub efi_machine_type: int::u16le,
+
+    /// Compression of the image (`EFI_COMPRESSION_*`).
+    pub compression_type: int::u16le,
+
+    /// Reserved.
+    pub reserved: [u8; 8],
+
+    /// Offset of the PE/COFF image from the start of the image.
+    pub efi_image_header_offset: int::u16le,
+
+    /// Offset of the PCI data structure from the start of the image.
+    pub pcir_offset: int::u16le,
+}
+
+/// PCI Data Structure
+///
+/// The PCI data structure (PCIR) of an image, in its revision 3.0 layout.
+/// Earlier revisions are 24 bytes long, with the fields following
+/// `indicator` reserved.
+#[repr(C, packed)]
+pub struct DataStructure {
+    /// The static signature. This must match `PCIR_SIGNATURE`.
+    pub signature: [u8; 4],
+
+    /// Vendor ID of the supported device.
+    pub vendor_id: int::u16le,
+
+    /// Device ID of the supported device.
+    pub device_id: int::u16le,
+
+    /// Offset of the zero-terminated list of further device IDs from the
+    /// start of the structure, or 0.
+    pub device_list_offset: int::u16le,
+
+    /// Length of the structure in bytes.
+    pub length: int::u16le,
+
+    /// Revision of the structure (`PCIR_REVISION_*`).
+    pub revision: u8,
+
+    /// Class code of the supported device, as programming interface,
+    /// subclass, and base class.
+    pub class_code: [u8; 3],
+
+    /// Size of the image in units of `IMAGE_UNIT`.
+    pub image_length: int::u16le,
+
+    /// Revision of the code or data of the image.
+    pub code_revision: int::u16le,
+
+    /// Type of the code of the image (`CODE_TYPE_*`).
+    pub code_type: u8,
+
+    /// Image flags (`INDICATOR_*`).
+    pub indicator: u8,
+
+    /// Maximum size of the image at runtime in units of `IMAGE_UNIT`.
+    pub max_runtime_image_length: int::u16le,
+
+    /// Offset of the configuration utility code header, or 0.
+    pub config_utility_code_header: int::u16le,
+
+    /// Offset of the DMTF CLP entry point, or 0.
+    pub dmtf_clp_entry_point: int::u16le,
+}
+
+/// Image Reference
+///
+/// A reference to an image of an expansion ROM, with the decoded fields of
+/// its PCI data structure.
+#[derive(Clone, Copy, Debug)]
+pub struct Image<'data> {
+    /// Offset of the image from the start of the ROM.
+    pub offset: usize,
+
+    /// Vendor ID of the supported device.
+    pub vendor_id: u16,
+
+    /// Device ID of the supported device.
+    pub device_id: u16,
+
+    /// Revision of the PCI data structure (`PCIR_REVISION_*`).
+    pub revision: u8,
+
+    /// Type of the code of the image (`CODE_TYPE_*`).
+    pub code_type: u8,
+
+    /// Image flags (`INDICATOR_*`).
+    pub indicator: u8,
+
+    /// Offset of the PCI data structure from the start of the image.
+    pub pcir_offset: usize,
+
+    /// Offset of the device list from the start of the image, or 0.
+    pub device_list_offset: usize,
+
+    /// Image data, including the ROM header.
+    pub data: &'data [u8],
+}
+
+/// Image Iterator
+///
+/// An iterator over the chain of images of an expansion ROM, yielding an
+/// `Image` for every image up to, and including, the image marked with
+/// `INDICATOR_LAST`. Iteration stops at the first malformed image.
+#[derive(Clone, Debug)]
+pub struct Images<'data> {
+    data: &'data [u8],
+    offset: usize,
+    done: bool,
+}
+
+/// Device ID Iterator
+///
+/// An iterator over the zero-terminated device list of an image.
+#[derive(Clone, Debug)]
+pub struct DeviceIds<'data> {
+    data: &'data [u8],
+}
+
+impl RomHeader {
+    /// Import an ROM header from a byte slice
+    ///
+    /// Create a new ROM header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 26]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 26], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl LegacyRomHeader {
+    /// Import a legacy ROM header from a byte slice
+    ///
+    /// Create a new legacy ROM header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 26]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 26], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl EfiRomHeader {
+    /// Import an EFI ROM header from a byte slice
+    ///
+    /// Create a new EFI ROM header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 26]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 26], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl DataStructure {
+    /// Import a PCI data structure from a byte slice
+    ///
+    /// Create a new PCI data structure from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 28]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 28], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Import a PCI data structure from an image
+    ///
+    /// Create a new PCI data structure from the data at the given offset of
+    /// an image. Structures of earlier revisions are zero-extended. `None`
+    /// is returned if the signature does not match or the data is
+    /// truncated.
+    pub fn from_image(data: &[u8], offset: usize) -> Option<Self> {
+        let data = data.get(offset..)?;
+        if data.len() < PCIR_SIZE_V2 || data[..4] != PCIR_SIGNATURE {
+            return None;
+        }
+
+        let mut v = [0u8; PCIR_SIZE_V3];
+        let n = data.len().min(PCIR_SIZE_V3);
+        v[..n].copy_from_slice(&data[..n]);
+        if u16::from_le_bytes([v[10], v[11]]) < PCIR_SIZE_V3 as u16 {
+            v[PCIR_SIZE_V2..].fill(0);
+        }
+
+        Some(Self::from_bytes(&v))
+    }
+}
+
+impl<'data> Image<'data> {
+    /// Parse an image
+    ///
+    /// Parse the image at the given offset of an expansion ROM. `None` is
+    /// returned if the signatures do not match, or if the image is
+    /// truncated.
+    pub fn parse(rom: &'data [u8], offset: usize) -> Option<Self> {
+        let data = rom.get(offset..)?;
+        let header = RomHeader::from_bytes(data.get(..26)?.try_into().ok()?);
+        if header.signature != SIGNATURE {
+            return None;
+        }
+
+        let pcir_offset = int::ForeignEndian::to_native(header.pcir_offset) as usize;
+        let pcir = DataStructure::from_image(data, pcir_offset)?;
+        let length = int::ForeignEndian::to_native(pcir.image_length) as usize * IMAGE_UNIT;
+        if length == 0 {
+            return None;
+        }
+
+        let device_list = int::ForeignEndian::to_native(pcir.device_list_offset) as usize;
+        let device_list_offset = if pcir.revision >= PCIR_REVISION_3 && device_list > 0 {
+            pcir_offset + device_list
+        } else {
+            0
+        };
+
+        Some(Self {
+            offset,
+            vendor_id: int::ForeignEndian::to_native(pcir.vendor_id),
+            device_id: int::ForeignEndian::to_native(pcir.device_id),
+            revision: pcir.revision,
+            code_type: pcir.code_type,
+            indicator: pcir.indicator,
+            pcir_offset,
+            device_list_offset,
+            data: data.get(..length)?,
+        })
+    }
+
+    /// Check whether this is the last image
+    pub fn is_last(&self) -> bool {
+        (self.indicator & INDICATOR_LAST) != 0
+    }
+
+    /// Verify the image checksum
+    ///
+    /// Check that all bytes of the image sum up to 0 modulo 256, as required
+    /// for images of `CODE_TYPE_X86`.
+    pub fn checksum_valid(&self) -> bool {
+        checksum::sum8(self.data) == 0
+    }
+
+    /// Return the EFI header
+    ///
+    /// Import the ROM header of an image of `CODE_TYPE_EFI`, or return
+    /// `None` for other code types or if the EFI signature does not match.
+    pub fn efi_header(&self) -> Option<EfiRomHeader> {
+        if self.code_type != CODE_TYPE_EFI {
+            return None;
+        }
+
+        let v = EfiRomHeader::from_bytes(self.data.get(..26)?.try_into().ok()?);
+        if int::ForeignEndian::to_native(v.efi_signature) != EFI_SIGNATURE {
+            return None;
+        }
+
+        Some(v)
+    }
+
+    /// Return the device list
+    ///
+    /// Return an iterator over the further device IDs supported by the
+    /// image, as listed by revision 3.0 PCI data structures.
+    pub fn device_ids(&self) -> DeviceIds<'data> {
+        let data = if self.device_list_offset > 0 {
+            self.data.get(self.device_list_offset..).unwrap_or(&[])
+        } else {
+            &[]
+        };
+
+        DeviceIds { data }
+    }
+}
+
+impl<'data> Images<'data> {
+    /// Create image iterator
+    ///
+    /// Create a new iterator over the image chain of the expansion ROM
+    /// `data`.
+    pub fn new(data: &'data [u8]) -> Self {
+        Self {
+            data,
+            offset: 0,
+            done: false,
+        }
+    }
+
+    /// Return remaining data
+    ///
+    /// Return the data that has not been consumed by the iterator.
+    pub fn remainder(&self) -> &'data [u8] {
+        self.data.get(self.offset..).unwrap_or(&[])
+    }
+}
+
+impl<'data> Iterator for Images<'data> {
+    type Item = Image<'data>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let Some(image) = Image::parse(self.data, self.offset) else {
+            self.done = true;
+            return None;
+        };
+
+        self.offset += image.data.len();
+        self.done = image.is_last();
+        Some(image)
+    }
+}
+
+impl<'data> Iterator for DeviceIds<'data> {
+    type Item = u16;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let v = u16::from_le_bytes(self.data.get(..2)?.try_into().ok()?);
+        if v == 0 {
+            self.data = &[];
+            return None;
+        }
+
+        self.data = &self.data[2..];
+        Some(v)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::specs::int::ForeignEndian;
+
+    // Build a ROM with a legacy x86 image using a revision 2.x PCI data
+    // structure, and a final EFI image with a device list.
+    fn rom() -> std::vec::Vec<u8> {
+        let mut v = std::vec![0u8; 3 * IMAGE_UNIT + 16];
+
+        v[0x000..0x006].copy_from_slice(&[0x55, 0xaa, 0x01, 0xeb, 0x4b, 0x90]);
+        v[0x018..0x01a].copy_from_slice(&[0x1c, 0x00]);
+        v[0x01c..0x02c].copy_from_slice(&[
+            0x50, 0x43, 0x49, 0x52, 0x86, 0x80, 0x33, 0x15,
+            0x00, 0x00, 0x18, 0x00, 0x00, 0x00, 0x00, 0x02,
+        ]);
+        v[0x02c..0x034].copy_from_slice(&[0x01, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00]);
+        v[0x1ff] = 0u8.wrapping_sub(checksum::sum8(&v[..0x1ff]));
+
+        v[0x200..0x210].copy_from_slice(&[
+            0x55, 0xaa, 0x02, 0x00, 0xf1, 0x0e, 0x00, 0x00,
+            0x0b, 0x00, 0x64, 0x86, 0x00, 0x00, 0x00, 0x00,
+        ]);
+        v[0x216..0x21a].copy_from_slice(&[0x40, 0x00, 0x1c, 0x00]);
+        v[0x21c..0x22c].copy_from_slice(&[
+            0x50, 0x43, 0x49, 0x52, 0x86, 0x80, 0x33, 0x15,
+            0x1c, 0x00, 0x1c, 0x00, 0x03, 0x00, 0x00, 0x02,
+        ]);
+        v[0x22c..0x238].copy_from_slice(&[
+            0x02, 0x00, 0x00, 0x01, 0x03, 0x80, 0x02, 0x00,
+            0x00, 0x00, 0x00, 0x00,
+        ]);
+        v[0x238..0x23e].copy_from_slice(&[0x36, 0x15, 0x39, 0x15, 0x00, 0x00]);
+        v[0x240..0x242].copy_from_slice(b"MZ");
+        v
+    }
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<RomHeader>(), 26);
+        assert_eq!(size_of::<LegacyRomHeader>(), 26);
+        assert_eq!(size_of::<EfiRomHeader>(), 26);
+        assert_eq!(size_of::<DataStructure>(), 28);
+    }
+
+    // Walk the image chain and decode both images.
+    #[test]
+    fn verify_images() {
+        let data = rom();
+        let mut images = Images::new(&data);
+
+        let i = images.next().unwrap();
+        assert_eq!((i.offset, i.data.len()), (0, IMAGE_UNIT));
+        assert_eq!((i.vendor_id, i.device_id), (0x8086, 0x1533));
+        assert_eq!((i.revision, i.code_type), (PCIR_REVISION_2, CODE_TYPE_X86));
+        assert!(!i.is_last());
+        assert!(i.checksum_valid());
+        assert!(i.efi_header().is_none());
+        assert_eq!(i.device_ids().count(), 0);
+
+        let l = LegacyRomHeader::from_bytes(i.data[..26].try_into().unwrap());
+        assert_eq!(l.size as usize * IMAGE_UNIT, i.data.len());
+        assert_eq!(l.pcir_offset.to_native(), 0x1c);
+        let p = DataStructure::from_image(i.data, i.pcir_offset).unwrap();
+        assert_eq!(p.length.to_native() as usize, PCIR_SIZE_V2);
+        assert_eq!(p.class_code, [0x00, 0x00, 0x02]);
+        assert_eq!(p.max_runtime_image_length.to_native(), 0);
+
+        let i = images.next().unwrap();
+        assert_eq!((i.offset, i.data.len()), (IMAGE_UNIT, 2 * IMAGE_UNIT));
+        assert_eq!((i.revision, i.code_type), (PCIR_REVISION_3, CODE_TYPE_EFI));
+        assert!(i.is_last());
+        let ids: std::vec::Vec<u16> = i.device_ids().collect();
+        assert_eq!(ids, [0x1536, 0x1539]);
+
+        let e = i.efi_header().unwrap();
+        assert_eq!(e.initialization_size.to_native(), 2);
+        assert_eq!(e.efi_subsystem.to_native(), EFI_SUBSYSTEM_BOOT_SERVICE_DRIVER);
+        assert_eq!(e.efi_machine_type.to_native(), 0x8664);
+        assert_eq!(e.compression_type.to_native(), EFI_COMPRESSION_NONE);
+        let pe = e.efi_image_header_offset.to_native() as usize;
+        assert_eq!(&i.data[pe..pe + 2], b"MZ");
+
+        assert!(images.next().is_none());
+        assert_eq!(images.remainder().len(), 16);
+
+        // A broken checksum is detected, and a missing PCIR signature stops
+        // the chain.
+        let mut v = data.clone();
+        v[0x100] ^= 0x01;
+        assert!(!Images::new(&v).next().unwrap().checksum_valid());
+        v[0x21c] = 0;
+        assert_eq!(Images::new(&v).count(), 1);
+    }
+}
@@ -0,0 +1,435 @@
+//! Portable Network Graphics
+//!
+//! The Portable Network Graphics format (PNG) is a lossless raster image
+//! format. A PNG file consists of an 8-byte signature followed by a sequence
+//! of chunks. Each chunk is framed by its data length, a 4-byte chunk type,
+//! and a trailing CRC. The image starts with an `IHDR` chunk and ends with an
+//! `IEND` chunk. The compressed image data is stored in one or more
+//! consecutive `IDAT` chunks, which must be concatenated before
+//! decompression.
+//!
+//! Chunk types are 4 ASCII letters. The case of each letter (bit 5) carries
+//! semantics, so decoders can handle unknown chunks:
+//!
+//!  * 1st letter: ancillary bit. Lowercase means the chunk is not critical for
+//!    displaying the image and can be ignored if unknown.
+//!  * 2nd letter: private bit. Lowercase means the chunk is not registered
+//!    publicly.
+//!  * 3rd letter: reserved bit. Must be uppercase.
+//!  * 4th letter: safe-to-copy bit. Lowercase means the chunk can be copied by
+//!    editors that modify the image, even if they do not understand it.
+//!
+//! The CRC of a chunk is a CRC-32 (see `checksum::crc32()`) covering the
+//! chunk type and chunk data, but not the length field.
+//!
+//! All multi-byte integers are encoded as big-endian ("network byte order").
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::{checksum, int};
+
+/// Magic Signature
+///
+/// The initial 8 bytes of every PNG file. The signature contains a non-ASCII
+/// byte as well as both CR-LF and LF line endings to detect common file
+/// transfer corruptions.
+pub const MAGIC: [u8; 8] = [0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a];
+
+/// Maximum Chunk Length
+///
+/// Chunk lengths are limited to 2^31-1.
+pub const CHUNK_LENGTH_MAX: u32 = 0x7fff_ffff;
+
+/// Chunk Type Property Bit
+///
+/// Chunk type property bits are encoded as bit 5 of each chunk type byte.
+pub const CHUNK_PROPERTY_BIT: u8 = 0x20;
+
+pub const CHUNK_IHDR: [u8; 4] = *b"IHDR";
+pub const CHUNK_PLTE: [u8; 4] = *b"PLTE";
+pub const CHUNK_IDAT: [u8; 4] = *b"IDAT";
+pub const CHUNK_IEND: [u8; 4] = *b"IEND";
+pub const CHUNK_TRNS: [u8; 4] = *b"tRNS";
+pub const CHUNK_CHRM: [u8; 4] = *b"cHRM";
+pub const CHUNK_GAMA: [u8; 4] = *b"gAMA";
+pub const CHUNK_ICCP: [u8; 4] = *b"iCCP";
+pub const CHUNK_SBIT: [u8; 4] = *b"sBIT";
+pub const CHUNK_SRGB: [u8; 4] = *b"sRGB";
+pub const CHUNK_TEXT: [u8; 4] = *b"tEXt";
+pub const CHUNK_ZTXT: [u8; 4] = *b"zTXt";
+pub const CHUNK_ITXT: [u8; 4] = *b"iTXt";
+pub const CHUNK_BKGD: [u8; 4] = *b"bKGD";
+pub const CHUNK_HIST: [u8; 4] = *b"hIST";
+pub const CHUNK_PHYS: [u8; 4] = *b"pHYs";
+pub const CHUNK_SPLT: [u8; 4] = *b"sPLT";
+pub const CHUNK_TIME: [u8; 4] = *b"tIME";
+pub const CHUNK_EXIF: [u8; 4] = *b"eXIf";
+
+pub const COLOR_TYPE_GRAYSCALE: u8 = 0;
+pub const COLOR_TYPE_TRUECOLOR: u8 = 2;
+pub const COLOR_TYPE_INDEXED: u8 = 3;
+pub const COLOR_TYPE_GRAYSCALE_ALPHA: u8 = 4;
+pub const COLOR_TYPE_TRUECOLOR_ALPHA: u8 = 6;
+
+pub const COMPRESSION_DEFLATE: u8 = 0;
+pub const FILTER_METHOD_ADAPTIVE: u8 = 0;
+pub const INTERLACE_NONE: u8 = 0;
+pub const INTERLACE_ADAM7: u8 = 1;
+
+pub const FILTER_NONE: u8 = 0;
+pub const FILTER_SUB: u8 = 1;
+pub const FILTER_UP: u8 = 2;
+pub const FILTER_AVERAGE: u8 = 3;
+pub const FILTER_PAETH: u8 = 4;
+
+/// CRC of the IEND Chunk
+///
+/// Since `IEND` carries no data, its CRC is constant.
+pub const IEND_CRC: u32 = 0xae42_6082;
+
+/// Chunk Header
+///
+/// The header in front of every chunk. It is followed by `length` bytes of
+/// chunk data and the 4-byte CRC.
+#[repr(C)]
+pub struct ChunkHeader {
+    /// Length of the chunk data, excluding header and CRC.
+    pub length: int::u32be,
+
+    /// Chunk type code.
+    pub chunk_type: [u8; 4],
+}
+
+/// Image Header
+///
+/// The data of the `IHDR` chunk, which must be the first chunk of the file.
+/// Since the chunk data is 13 bytes, the structure is packed.
+#[repr(C, packed)]
+pub struct Ihdr {
+    /// Width of the image in pixels, must be non-zero.
+    pub width: int::u32be,
+
+    /// Height of the image in pixels, must be non-zero.
+    pub height: int::u32be,
+
+    /// Number of bits per sample (or per palette index).
+    pub bit_depth: u8,
+
+    /// Color type (`COLOR_TYPE_*`).
+    pub color_type: u8,
+
+    /// Compression method, must be `COMPRESSION_DEFLATE`.
+    pub compression_method: u8,
+
+    /// Filter method, must be `FILTER_METHOD_ADAPTIVE`.
+    pub filter_method: u8,
+
+    /// Interlace method (`INTERLACE_*`).
+    pub interlace_method: u8,
+}
+
+/// Palette Entry
+///
+/// A single entry of the `PLTE` chunk, which consists of 1 to 256 entries.
+#[repr(C)]
+pub struct PaletteEntry {
+    pub red: u8,
+    pub green: u8,
+    pub blue: u8,
+}
+
+/// Grayscale Transparency
+///
+/// The data of the `tRNS` chunk for grayscale images. It specifies the gray
+/// level that is to be treated as transparent. For indexed images, `tRNS`
+/// instead contains one alpha byte per palette entry.
+#[repr(C)]
+pub struct TrnsGray {
+    pub gray: int::u16be,
+}
+
+/// Truecolor Transparency
+///
+/// The data of the `tRNS` chunk for truecolor images. It specifies the color
+/// that is to be treated as transparent.
+#[repr(C)]
+pub struct TrnsRgb {
+    pub red: int::u16be,
+    pub green: int::u16be,
+    pub blue: int::u16be,
+}
+
+/// Chunk Reference
+///
+/// A reference to a chunk in a byte slice, as yielded by `Chunks`.
+#[derive(Clone, Copy, Debug)]
+pub struct Chunk<'data> {
+    /// Chunk type code.
+    pub chunk_type: [u8; 4],
+
+    /// Chunk data.
+    pub data: &'data [u8],
+
+    /// CRC as stored in the file.
+    pub crc: u32,
+}
+
+/// Chunk Iterator
+///
+/// An iterator over the chunks of a PNG file. It must be created on the data
+/// following the signature. Iteration stops at the end of the data, or at the
+/// first chunk that is truncated or exceeds `CHUNK_LENGTH_MAX`. CRCs are not
+/// verified.
+#[derive(Clone, Debug)]
+pub struct Chunks<'data> {
+    data: &'data [u8],
+}
+
+impl ChunkHeader {
+    /// Import a chunk header from a byte slice
+    ///
+    /// Create a new chunk header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 8]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 8], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl Ihdr {
+    /// Import an image header from a byte slice
+    ///
+    /// Create a new image header from the data of an `IHDR` chunk. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 13]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 13], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Convert to byte slice
+    ///
+    /// Return a byte-slice reference to the image header. No byte-order
+    /// conversions are applied.
+    pub fn as_bytes(&self) -> &[u8; 13] {
+        assert!(align_of::<[u8; 13]>() <= align_of::<Self>());
+        assert!(size_of::<[u8; 13]>() == size_of::<Self>());
+
+        unsafe {
+            core::mem::transmute::<&Self, &[u8; 13]>(self)
+        }
+    }
+}
+
+impl<'data> Chunk<'data> {
+    /// Verify chunk CRC
+    ///
+    /// Calculate the CRC of this chunk and compare it to the stored CRC.
+    pub fn verify(&self) -> bool {
+        crc(&self.chunk_type, self.data) == self.crc
+    }
+}
+
+impl<'data> Chunks<'data> {
+    /// Create chunk iterator
+    ///
+    /// Create a new iterator over the chunks in `data`, which must start
+    /// directly after the PNG signature.
+    pub fn new(data: &'data [u8]) -> Self {
+        Self {
+            data,
+        }
+    }
+}
+
+impl<'data> Iterator for Chunks<'data> {
+    type Item = Chunk<'data>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.data.len() < 12 {
+            return None;
+        }
+
+        let length = u32::from_be_bytes(self.data[..4].try_into().unwrap());
+        if length > CHUNK_LENGTH_MAX || self.data.len() - 12 < length as usize {
+            return None;
+        }
+
+        let end = 8 + length as usize;
+        let chunk = Chunk {
+            chunk_type: self.data[4..8].try_into().unwrap(),
+            data: &self.data[8..end],
+            crc: u32::from_be_bytes(self.data[end..end + 4].try_into().unwrap()),
+        };
+
+        self.data = &self.data[end + 4..];
+        Some(chunk)
+    }
+}
+
+/// Check Ancillary Bit
+///
+/// Return whether the chunk type denotes an ancillary chunk.
+pub fn is_ancillary(chunk_type: &[u8; 4]) -> bool {
+    chunk_type[0] & CHUNK_PROPERTY_BIT != 0
+}
+
+/// Check Private Bit
+///
+/// Return whether the chunk type denotes a private chunk.
+pub fn is_private(chunk_type: &[u8; 4]) -> bool {
+    chunk_type[1] & CHUNK_PROPERTY_BIT != 0
+}
+
+/// Check Reserved Bit
+///
+/// Return whether the reserved bit of the chunk type is set. Such chunks do
+/// not conform to the current specification.
+pub fn is_reserved(chunk_type: &[u8; 4]) -> bool {
+    chunk_type[2] & CHUNK_PROPERTY_BIT != 0
+}
+
+/// Check Safe-to-copy Bit
+///
+/// Return whether the chunk type denotes a chunk that is safe to copy.
+pub fn is_safe_to_copy(chunk_type: &[u8; 4]) -> bool {
+    chunk_type[3] & CHUNK_PROPERTY_BIT != 0
+}
+
+/// Calculate Chunk CRC
+///
+/// Calculate the CRC of a chunk with the given type and data.
+pub fn crc(chunk_type: &[u8; 4], data: &[u8]) -> u32 {
+    !checksum::crc32_update(checksum::crc32_update(0xffff_ffff, chunk_type), data)
+}
+
+/// Return Number of Channels
+///
+/// Return the number of samples per pixel for the given color type, or
+/// `None` if the color type is invalid.
+pub fn channels(color_type: u8) -> Option<u8> {
+    match color_type {
+        COLOR_TYPE_GRAYSCALE => Some(1),
+        COLOR_TYPE_TRUECOLOR => Some(3),
+        COLOR_TYPE_INDEXED => Some(1),
+        COLOR_TYPE_GRAYSCALE_ALPHA => Some(2),
+        COLOR_TYPE_TRUECOLOR_ALPHA => Some(4),
+        _ => None,
+    }
+}
+
+/// Validate Bit Depth
+///
+/// Return whether the bit depth is allowed for the given color type.
+pub fn is_valid_bit_depth(color_type: u8, bit_depth: u8) -> bool {
+    match color_type {
+        COLOR_TYPE_GRAYSCALE => matches!(bit_depth, 1 | 2 | 4 | 8 | 16),
+        COLOR_TYPE_INDEXED => matches!(bit_depth, 1 | 2 | 4 | 8),
+        COLOR_TYPE_TRUECOLOR
+        | COLOR_TYPE_GRAYSCALE_ALPHA
+        | COLOR_TYPE_TRUECOLOR_ALPHA => matches!(bit_depth, 8 | 16),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A 1x1 RGBA image with a single red pixel.
+    const RED: [u8; 70] = [
+        0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a,
+        0x00, 0x00, 0x00, 0x0d, 0x49, 0x48, 0x44, 0x52,
+        0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01,
+        0x08, 0x06, 0x00, 0x00, 0x00, 0x1f, 0x15, 0xc4,
+        0x89, 0x00, 0x00, 0x00, 0x0d, 0x49, 0x44, 0x41,
+        0x54, 0x78, 0x9c, 0x63, 0xf8, 0xcf, 0xc0, 0xf0,
+        0x1f, 0x00, 0x05, 0x00, 0x01, 0xff, 0x89, 0x99,
+        0x3d, 0x1d, 0x00, 0x00, 0x00, 0x00, 0x49, 0x45,
+        0x4e, 0x44, 0xae, 0x42, 0x60, 0x82,
+    ];
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<ChunkHeader>(), 8);
+        assert_eq!(align_of::<ChunkHeader>(), 4);
+        assert_eq!(size_of::<Ihdr>(), 13);
+        assert_eq!(align_of::<Ihdr>(), 1);
+        assert_eq!(size_of::<PaletteEntry>(), 3);
+        assert_eq!(size_of::<TrnsGray>(), 2);
+        assert_eq!(size_of::<TrnsRgb>(), 6);
+    }
+
+    // Walk all chunks of a real image and verify their CRCs.
+    #[test]
+    fn verify_chunks() {
+        assert_eq!(RED[..8], MAGIC);
+
+        let h = ChunkHeader::from_bytes(RED[8..16].try_into().unwrap());
+        assert_eq!(int::ForeignEndian::to_native(h.length), 13);
+        assert_eq!(h.chunk_type, CHUNK_IHDR);
+
+        let mut chunks = Chunks::new(&RED[8..]);
+
+        let c = chunks.next().unwrap();
+        assert_eq!(c.chunk_type, CHUNK_IHDR);
+        assert!(c.verify());
+        let ihdr = Ihdr::from_bytes(c.data.try_into().unwrap());
+        assert_eq!(int::ForeignEndian::to_native(ihdr.width), 1);
+        assert_eq!(int::ForeignEndian::to_native(ihdr.height), 1);
+        assert_eq!(ihdr.bit_depth, 8);
+        assert_eq!(ihdr.color_type, COLOR_TYPE_TRUECOLOR_ALPHA);
+        assert_eq!(ihdr.interlace_method, INTERLACE_NONE);
+        assert_eq!(ihdr.as_bytes(), c.data);
+
+        let c = chunks.next().unwrap();
+        assert_eq!(c.chunk_type, CHUNK_IDAT);
+        assert!(c.verify());
+
+        let c = chunks.next().unwrap();
+        assert_eq!(c.chunk_type, CHUNK_IEND);
+        assert_eq!(c.crc, IEND_CRC);
+        assert!(c.verify());
+
+        assert!(chunks.next().is_none());
+
+        // Truncated chunks end the iteration.
+        assert!(Chunks::new(&RED[8..30]).next().is_none());
+    }
+
+    // Verify the chunk type property bits and color helpers.
+    #[test]
+    fn verify_helpers() {
+        assert!(!is_ancillary(&CHUNK_IHDR));
+        assert!(is_ancillary(&CHUNK_TEXT));
+        assert!(!is_private(&CHUNK_TEXT));
+        assert!(is_private(b"prVt"));
+        assert!(!is_reserved(&CHUNK_IDAT));
+        assert!(!is_safe_to_copy(&CHUNK_GAMA));
+        assert!(is_safe_to_copy(&CHUNK_TEXT));
+
+        assert_eq!(channels(COLOR_TYPE_TRUECOLOR_ALPHA), Some(4));
+        assert_eq!(channels(1), None);
+        assert!(is_valid_bit_depth(COLOR_TYPE_GRAYSCALE, 1));
+        assert!(!is_valid_bit_depth(COLOR_TYPE_INDEXED, 16));
+        assert!(!is_valid_bit_depth(COLOR_TYPE_TRUECOLOR, 4));
+    }
+}
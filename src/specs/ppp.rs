@@ -0,0 +1,273 @@
+//! Point-to-Point Protocol
+//!
+//! PPP frames carry a protocol field (`PROTOCOL_*`) followed by the
+//! payload. In HDLC-like framing, the protocol field is preceded by the
+//! address and control fields (`ADDRESS_ALL`, `CONTROL_UI`), which can be
+//! omitted if address-and-control-field compression was negotiated.
+//! Likewise, protocol fields below 0x100 can be compressed to a single byte.
+//! See `decode_frame()` to handle both.
+//!
+//! The link control protocol (LCP) and the network control protocols (e.g.,
+//! IPCP and IPV6CP) share the same packet format: a 4-byte `ControlHeader`
+//! with code, identifier and length, followed by data. Configuration
+//! packets carry a sequence of options (see `Options`), encoded as TLVs with
+//! 1-byte type and 1-byte length, where the length includes the 2-byte
+//! option header.
+//!
+//! All multi-byte integers are encoded as big-endian.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::int;
+
+pub const CONTROL_HEADER_SIZE: usize = 4;
+pub const OPTION_HEADER_SIZE: usize = 2;
+
+pub const ADDRESS_ALL: u8 = 0xff;
+pub const CONTROL_UI: u8 = 0x03;
+
+/// Default Maximum Receive Unit
+pub const MRU_DEFAULT: u16 = 1500;
+
+pub const PROTOCOL_IPV4: u16 = 0x0021;
+pub const PROTOCOL_APPLETALK: u16 = 0x0029;
+pub const PROTOCOL_VJ_COMPRESSED: u16 = 0x002d;
+pub const PROTOCOL_VJ_UNCOMPRESSED: u16 = 0x002f;
+pub const PROTOCOL_BRIDGING: u16 = 0x0031;
+pub const PROTOCOL_MULTILINK: u16 = 0x003d;
+pub const PROTOCOL_IPV6: u16 = 0x0057;
+pub const PROTOCOL_MPLS: u16 = 0x0281;
+pub const PROTOCOL_IPCP: u16 = 0x8021;
+pub const PROTOCOL_BCP: u16 = 0x8031;
+pub const PROTOCOL_IPV6CP: u16 = 0x8057;
+pub const PROTOCOL_CCP: u16 = 0x80fd;
+pub const PROTOCOL_MPLSCP: u16 = 0x8281;
+pub const PROTOCOL_LCP: u16 = 0xc021;
+pub const PROTOCOL_PAP: u16 = 0xc023;
+pub const PROTOCOL_LQR: u16 = 0xc025;
+pub const PROTOCOL_CHAP: u16 = 0xc223;
+pub const PROTOCOL_EAP: u16 = 0xc227;
+
+pub const CODE_CONFIGURE_REQUEST: u8 = 1;
+pub const CODE_CONFIGURE_ACK: u8 = 2;
+pub const CODE_CONFIGURE_NAK: u8 = 3;
+pub const CODE_CONFIGURE_REJECT: u8 = 4;
+pub const CODE_TERMINATE_REQUEST: u8 = 5;
+pub const CODE_TERMINATE_ACK: u8 = 6;
+pub const CODE_CODE_REJECT: u8 = 7;
+pub const CODE_PROTOCOL_REJECT: u8 = 8;
+pub const CODE_ECHO_REQUEST: u8 = 9;
+pub const CODE_ECHO_REPLY: u8 = 10;
+pub const CODE_DISCARD_REQUEST: u8 = 11;
+
+pub const LCP_OPTION_MRU: u8 = 1;
+pub const LCP_OPTION_ACCM: u8 = 2;
+pub const LCP_OPTION_AUTH_PROTOCOL: u8 = 3;
+pub const LCP_OPTION_QUALITY_PROTOCOL: u8 = 4;
+pub const LCP_OPTION_MAGIC_NUMBER: u8 = 5;
+pub const LCP_OPTION_PFC: u8 = 7;
+pub const LCP_OPTION_ACFC: u8 = 8;
+pub const LCP_OPTION_MRRU: u8 = 17;
+pub const LCP_OPTION_ENDPOINT_DISCRIMINATOR: u8 = 19;
+
+pub const CHAP_ALGORITHM_MD5: u8 = 5;
+pub const CHAP_ALGORITHM_MSCHAP_V2: u8 = 0x81;
+
+pub const IPCP_OPTION_IP_ADDRESSES: u8 = 1;
+pub const IPCP_OPTION_IP_COMPRESSION: u8 = 2;
+pub const IPCP_OPTION_IP_ADDRESS: u8 = 3;
+pub const IPCP_OPTION_PRIMARY_DNS: u8 = 129;
+pub const IPCP_OPTION_PRIMARY_NBNS: u8 = 130;
+pub const IPCP_OPTION_SECONDARY_DNS: u8 = 131;
+pub const IPCP_OPTION_SECONDARY_NBNS: u8 = 132;
+
+pub const IPV6CP_OPTION_INTERFACE_ID: u8 = 1;
+
+/// Control Protocol Packet Header
+///
+/// The header shared by LCP and all network control protocols.
+#[repr(C)]
+pub struct ControlHeader {
+    /// Packet code (`CODE_*`).
+    pub code: u8,
+
+    /// Identifier to match requests and replies.
+    pub identifier: u8,
+
+    /// Size of the packet in bytes, including the header.
+    pub length: int::u16be,
+}
+
+/// Option Iterator
+///
+/// An iterator over the options of a configuration packet, yielding the
+/// option type and data without the option header. Iteration stops at the
+/// end of the data, or at the first truncated or malformed option.
+#[derive(Clone, Debug)]
+pub struct Options<'data> {
+    data: &'data [u8],
+}
+
+impl ControlHeader {
+    /// Import a control header from a byte slice
+    ///
+    /// Create a new control header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 4]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 4], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Check for configuration packets
+    ///
+    /// Return whether the packet carries options (see `Options`).
+    pub fn is_configure(&self) -> bool {
+        (CODE_CONFIGURE_REQUEST..=CODE_CONFIGURE_REJECT).contains(&self.code)
+    }
+}
+
+impl<'data> Options<'data> {
+    /// Create option iterator
+    ///
+    /// Create a new iterator over the options in `data`, which must start
+    /// after the control header and be limited to the packet length.
+    pub fn new(data: &'data [u8]) -> Self {
+        Self {
+            data,
+        }
+    }
+
+    /// Return remaining data
+    ///
+    /// Return the data that has not been consumed by the iterator.
+    pub fn remainder(&self) -> &'data [u8] {
+        self.data
+    }
+}
+
+impl<'data> Iterator for Options<'data> {
+    type Item = (u8, &'data [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let code = *self.data.first()?;
+        let n = *self.data.get(1)? as usize;
+        if n < OPTION_HEADER_SIZE {
+            return None;
+        }
+
+        let v = self.data.get(OPTION_HEADER_SIZE..n)?;
+        self.data = &self.data[n..];
+        Some((code, v))
+    }
+}
+
+/// Decode frame
+///
+/// Skip the address and control fields at the start of `data`, if present,
+/// and decode the protocol field, which may be compressed to a single byte.
+/// Return the protocol and the payload, or `None` if the data is truncated.
+pub fn decode_frame(data: &[u8]) -> Option<(u16, &[u8])> {
+    let data = match data {
+        [ADDRESS_ALL, CONTROL_UI, rest @ ..] => rest,
+        _ => data,
+    };
+
+    let first = *data.first()?;
+    if first & 0x01 != 0 {
+        Some((first as u16, &data[1..]))
+    } else {
+        Some((u16::from_be_bytes([first, *data.get(1)?]), &data[2..]))
+    }
+}
+
+/// Check for control protocols
+///
+/// Return whether the protocol is a link or network control protocol,
+/// rather than a network-layer protocol.
+pub fn is_control(protocol: u16) -> bool {
+    protocol >= 0x8000
+}
+
+/// Find option
+///
+/// Return the data of the first option with the given type in `data`.
+pub fn find_option(data: &[u8], code: u8) -> Option<&[u8]> {
+    Options::new(data).find(|v| v.0 == code).map(|v| v.1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // An LCP configure request with MRU, authentication and magic number.
+    const FRAME: [u8; 25] = [
+        0xff, 0x03, 0xc0, 0x21, 0x01, 0x01, 0x00, 0x15,
+        0x01, 0x04, 0x05, 0xd4, 0x03, 0x05, 0xc2, 0x23,
+        0x05, 0x05, 0x06, 0x12, 0x34, 0x56, 0x78, 0x07,
+        0x02,
+    ];
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<ControlHeader>(), CONTROL_HEADER_SIZE);
+        assert_eq!(align_of::<ControlHeader>(), 2);
+    }
+
+    // Parse an LCP configure request and its options.
+    #[test]
+    fn verify_lcp() {
+        let (protocol, packet) = decode_frame(&FRAME).unwrap();
+        assert_eq!(protocol, PROTOCOL_LCP);
+        assert!(is_control(protocol));
+
+        let h = ControlHeader::from_bytes(packet[..4].try_into().unwrap());
+        assert_eq!(h.code, CODE_CONFIGURE_REQUEST);
+        assert!(h.is_configure());
+        assert_eq!(h.identifier, 1);
+        assert_eq!(int::ForeignEndian::to_native(h.length) as usize, packet.len());
+
+        let mut opts = Options::new(&packet[4..]);
+        assert_eq!(opts.next(), Some((LCP_OPTION_MRU, &[0x05, 0xd4][..])));
+        let (code, auth) = opts.next().unwrap();
+        assert_eq!(code, LCP_OPTION_AUTH_PROTOCOL);
+        assert_eq!(u16::from_be_bytes([auth[0], auth[1]]), PROTOCOL_CHAP);
+        assert_eq!(auth[2], CHAP_ALGORITHM_MD5);
+        assert_eq!(opts.next(), Some((LCP_OPTION_MAGIC_NUMBER, &[0x12, 0x34, 0x56, 0x78][..])));
+        assert_eq!(opts.next(), Some((LCP_OPTION_PFC, &[][..])));
+        assert!(opts.next().is_none());
+        assert!(opts.remainder().is_empty());
+
+        assert_eq!(find_option(&packet[4..], LCP_OPTION_MRU), Some(&[0x05, 0xd4][..]));
+        assert!(find_option(&packet[4..], LCP_OPTION_ACFC).is_none());
+        assert!(Options::new(&[0x01, 0x00, 0x05, 0xd4]).next().is_none());
+    }
+
+    // Decode compressed frames and IPCP options.
+    #[test]
+    fn verify_compressed() {
+        let (protocol, payload) = decode_frame(&[0x21, 0x45, 0x00]).unwrap();
+        assert_eq!(protocol, PROTOCOL_IPV4);
+        assert!(!is_control(protocol));
+        assert_eq!(payload, &[0x45, 0x00]);
+
+        assert_eq!(decode_frame(&[0x00, 0x57]), Some((PROTOCOL_IPV6, &[][..])));
+        assert_eq!(decode_frame(&[0xff, 0x03, 0x80]), None);
+        assert_eq!(decode_frame(&[]), None);
+
+        let ipcp = [0x03, 0x06, 0xc0, 0xa8, 0x00, 0x01, 0x81, 0x06, 0x08, 0x08, 0x08, 0x08];
+        assert_eq!(find_option(&ipcp, IPCP_OPTION_IP_ADDRESS), Some(&[192, 168, 0, 1][..]));
+        assert_eq!(find_option(&ipcp, IPCP_OPTION_PRIMARY_DNS), Some(&[8, 8, 8, 8][..]));
+    }
+}
@@ -0,0 +1,213 @@
+//! PPP over Ethernet
+//!
+//! PPPoE carries PPP sessions over Ethernet. Hosts first run the discovery
+//! stage (`ethernet::ETHERTYPE_PPPOE_DISCOVERY`) to find an access
+//! concentrator and obtain a session identifier: the host broadcasts a PADI,
+//! concentrators answer with PADOs, the host sends a PADR to one of them,
+//! which confirms the session with a PADS. Either side ends the session with
+//! a PADT. Discovery packets carry a sequence of tags (see `Tags`), encoded
+//! as TLVs with 2-byte type and 2-byte length, without padding.
+//!
+//! Session packets (`ethernet::ETHERTYPE_PPPOE_SESSION`) carry a PPP frame
+//! without address and control fields, starting with the protocol field
+//! (see `ppp::decode_frame()`).
+//!
+//! Both stages use the same 6-byte `Header`.
+//!
+//! All multi-byte integers are encoded as big-endian.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::int;
+
+pub const HEADER_SIZE: usize = 6;
+pub const TAG_HEADER_SIZE: usize = 4;
+
+/// Version and Type of RFC 2516
+pub const VERSION_TYPE: u8 = 0x11;
+
+/// Maximum PPP Payload in Standard Ethernet Frames
+pub const PPP_MTU: usize = 1492;
+
+pub const CODE_SESSION: u8 = 0x00;
+pub const CODE_PADO: u8 = 0x07;
+pub const CODE_PADI: u8 = 0x09;
+pub const CODE_PADR: u8 = 0x19;
+pub const CODE_PADS: u8 = 0x65;
+pub const CODE_PADT: u8 = 0xa7;
+
+pub const TAG_END_OF_LIST: u16 = 0x0000;
+pub const TAG_SERVICE_NAME: u16 = 0x0101;
+pub const TAG_AC_NAME: u16 = 0x0102;
+pub const TAG_HOST_UNIQ: u16 = 0x0103;
+pub const TAG_AC_COOKIE: u16 = 0x0104;
+pub const TAG_VENDOR_SPECIFIC: u16 = 0x0105;
+pub const TAG_CREDITS: u16 = 0x0106;
+pub const TAG_METRICS: u16 = 0x0107;
+pub const TAG_SEQUENCE_NUMBER: u16 = 0x0108;
+pub const TAG_RELAY_SESSION_ID: u16 = 0x0110;
+pub const TAG_PPP_MAX_PAYLOAD: u16 = 0x0120;
+pub const TAG_SERVICE_NAME_ERROR: u16 = 0x0201;
+pub const TAG_AC_SYSTEM_ERROR: u16 = 0x0202;
+pub const TAG_GENERIC_ERROR: u16 = 0x0203;
+
+/// PPPoE Header
+#[repr(C)]
+pub struct Header {
+    /// Version (4 bits) and type (4 bits), must be `VERSION_TYPE`.
+    pub version_type: u8,
+
+    /// Packet code (`CODE_*`).
+    pub code: u8,
+
+    /// Session identifier, 0 before the session is established.
+    pub session_id: int::u16be,
+
+    /// Size of the payload in bytes, excluding the header.
+    pub length: int::u16be,
+}
+
+/// Tag Iterator
+///
+/// An iterator over the tags of a discovery packet, yielding the tag type
+/// and value. Iteration stops at the end of the data, at the first truncated
+/// tag, or at `TAG_END_OF_LIST`.
+#[derive(Clone, Debug)]
+pub struct Tags<'data> {
+    data: &'data [u8],
+}
+
+impl Header {
+    /// Import a header from a byte slice
+    ///
+    /// Create a new header from data copied from a byte slice. No byte-order
+    /// conversions are applied.
+    pub fn from_bytes(data: &[u8; 6]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 6], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Return version
+    pub fn version(&self) -> u8 {
+        self.version_type >> 4
+    }
+
+    /// Check for discovery packets
+    pub fn is_discovery(&self) -> bool {
+        self.code != CODE_SESSION
+    }
+}
+
+impl<'data> Tags<'data> {
+    /// Create tag iterator
+    ///
+    /// Create a new iterator over the tags in `data`, which must start after
+    /// the header and be limited to the payload length.
+    pub fn new(data: &'data [u8]) -> Self {
+        Self {
+            data,
+        }
+    }
+
+    /// Return remaining data
+    ///
+    /// Return the data that has not been consumed by the iterator.
+    pub fn remainder(&self) -> &'data [u8] {
+        self.data
+    }
+}
+
+impl<'data> Iterator for Tags<'data> {
+    type Item = (u16, &'data [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.data.len() < TAG_HEADER_SIZE {
+            return None;
+        }
+
+        let tag = u16::from_be_bytes([self.data[0], self.data[1]]);
+        if tag == TAG_END_OF_LIST {
+            return None;
+        }
+
+        let n = u16::from_be_bytes([self.data[2], self.data[3]]) as usize;
+        let v = self.data.get(4..4 + n)?;
+        self.data = &self.data[4 + n..];
+        Some((tag, v))
+    }
+}
+
+/// Find tag
+///
+/// Return the value of the first tag with the given type in `data`.
+pub fn find_tag(data: &[u8], tag: u16) -> Option<&[u8]> {
+    Tags::new(data).find(|v| v.0 == tag).map(|v| v.1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::specs::ppp;
+
+    // A PADO with an empty service name, a concentrator name and a cookie.
+    const PADO: [u8; 28] = [
+        0x11, 0x07, 0x00, 0x00, 0x00, 0x16, 0x01, 0x01,
+        0x00, 0x00, 0x01, 0x02, 0x00, 0x04, 0x42, 0x52,
+        0x41, 0x53, 0x01, 0x04, 0x00, 0x04, 0xde, 0xad,
+        0xbe, 0xef, 0x00, 0x00,
+    ];
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<Header>(), HEADER_SIZE);
+        assert_eq!(align_of::<Header>(), 2);
+    }
+
+    // Parse a discovery offer and its tags.
+    #[test]
+    fn verify_discovery() {
+        let h = Header::from_bytes(PADO[..6].try_into().unwrap());
+        assert_eq!(h.version_type, VERSION_TYPE);
+        assert_eq!(h.version(), 1);
+        assert_eq!(h.code, CODE_PADO);
+        assert!(h.is_discovery());
+        assert_eq!(int::ForeignEndian::to_native(h.session_id), 0);
+        assert_eq!(int::ForeignEndian::to_native(h.length) as usize, PADO.len() - HEADER_SIZE);
+
+        let mut tags = Tags::new(&PADO[6..]);
+        assert_eq!(tags.next(), Some((TAG_SERVICE_NAME, &[][..])));
+        assert_eq!(tags.next(), Some((TAG_AC_NAME, &b"BRAS"[..])));
+        assert_eq!(tags.next(), Some((TAG_AC_COOKIE, &[0xde, 0xad, 0xbe, 0xef][..])));
+        assert!(tags.next().is_none());
+        assert_eq!(tags.remainder(), &[0x00, 0x00]);
+
+        assert_eq!(find_tag(&PADO[6..], TAG_AC_NAME), Some(&b"BRAS"[..]));
+        assert!(find_tag(&PADO[6..], TAG_HOST_UNIQ).is_none());
+        assert!(Tags::new(&PADO[6..21]).nth(2).is_none());
+    }
+
+    // Parse a session packet carrying IPv4.
+    #[test]
+    fn verify_session() {
+        let data = [0x11, 0x00, 0x12, 0x34, 0x00, 0x04, 0x00, 0x21, 0x45, 0x00];
+        let h = Header::from_bytes(data[..6].try_into().unwrap());
+        assert!(!h.is_discovery());
+        assert_eq!(int::ForeignEndian::to_native(h.session_id), 0x1234);
+
+        let (protocol, payload) = ppp::decode_frame(&data[6..]).unwrap();
+        assert_eq!(protocol, ppp::PROTOCOL_IPV4);
+        assert_eq!(payload, &[0x45, 0x00]);
+    }
+}
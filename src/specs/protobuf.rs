@@ -0,0 +1,322 @@
+//! Protocol Buffers Wire Format
+//!
+//! A serialized protobuf message is a sequence of fields, without any
+//! framing of the message itself. Each field starts with a key, which is
+//! the field number and wire type (`WIRE_*`) packed into a varint (see
+//! `key()`), followed by the value. The wire type determines the size of the
+//! value, but not its interpretation, which is given by the schema:
+//!
+//!  * `WIRE_VARINT` values are varints (see `varint::leb128_decode()`), and
+//!    carry integers, booleans, and enums. Signed `sint32` and `sint64`
+//!    fields use zigzag encoding (see `varint::zigzag_decode()`).
+//!  * `WIRE_I64` and `WIRE_I32` values are 8 and 4 bytes, respectively, and
+//!    carry `fixed64`, `sfixed64`, and `double`, or `fixed32`, `sfixed32`,
+//!    and `float`.
+//!  * `WIRE_LEN` values are prefixed with their size as varint, and carry
+//!    strings, bytes, embedded messages, and packed repeated fields.
+//!  * `WIRE_SGROUP` and `WIRE_EGROUP` delimit deprecated groups, which embed
+//!    the fields of a message between a start and end key with the same
+//!    field number.
+//!
+//! The same field can occur multiple times, in which case the last value
+//! wins for scalars, and values are concatenated for repeated fields.
+//!
+//! Unlike most network protocols, all multi-byte integers are encoded as
+//! little-endian.
+
+use crate::specs::varint;
+
+pub const WIRE_VARINT: u8 = 0;
+pub const WIRE_I64: u8 = 1;
+pub const WIRE_LEN: u8 = 2;
+pub const WIRE_SGROUP: u8 = 3;
+pub const WIRE_EGROUP: u8 = 4;
+pub const WIRE_I32: u8 = 5;
+
+pub const WIRE_TYPE_BITS: u32 = 3;
+pub const WIRE_TYPE_MASK: u64 = 0x7;
+
+pub const FIXED32_SIZE: usize = 4;
+pub const FIXED64_SIZE: usize = 8;
+
+pub const FIELD_NUMBER_MIN: u32 = 1;
+pub const FIELD_NUMBER_MAX: u32 = (1 << 29) - 1;
+pub const FIELD_NUMBER_RESERVED_MIN: u32 = 19000;
+pub const FIELD_NUMBER_RESERVED_MAX: u32 = 19999;
+
+/// Maximum Size of Lengths
+///
+/// Lengths of `WIRE_LEN` values are limited to 2 GiB, making their varints
+/// at most 5 bytes long.
+pub const LENGTH_MAX: u64 = (1 << 31) - 1;
+
+/// Field Reference
+#[derive(Clone, Copy, Debug)]
+pub struct Field<'data> {
+    /// Field number.
+    pub number: u32,
+
+    /// Wire type (`WIRE_*`).
+    pub wire_type: u8,
+
+    /// Encoded value. For `WIRE_LEN`, this excludes the length, and for
+    /// `WIRE_SGROUP`, this covers the embedded fields, excluding the end key.
+    pub value: &'data [u8],
+}
+
+/// Field Iterator
+///
+/// Iterate over the fields of a serialized message. Iteration stops at the
+/// first truncated or invalid field, as well as at unbalanced end keys.
+#[derive(Clone, Debug)]
+pub struct Fields<'data> {
+    data: &'data [u8],
+}
+
+impl<'data> Field<'data> {
+    /// Decode varint value
+    pub fn varint(&self) -> Option<u64> {
+        match self.wire_type {
+            WIRE_VARINT => Some(varint::leb128_decode(self.value)?.0),
+            _ => None,
+        }
+    }
+
+    /// Decode zigzag-encoded varint value
+    pub fn sint(&self) -> Option<i64> {
+        self.varint().map(varint::zigzag_decode)
+    }
+
+    /// Decode fixed32 value
+    pub fn fixed32(&self) -> Option<u32> {
+        match self.wire_type {
+            WIRE_I32 => Some(u32::from_le_bytes(self.value.try_into().ok()?)),
+            _ => None,
+        }
+    }
+
+    /// Decode fixed64 value
+    pub fn fixed64(&self) -> Option<u64> {
+        match self.wire_type {
+            WIRE_I64 => Some(u64::from_le_bytes(self.value.try_into().ok()?)),
+            _ => None,
+        }
+    }
+
+    /// Return fields of embedded messages
+    ///
+    /// Return an iterator over the fields of a group, or a length-delimited
+    /// value interpreted as embedded message.
+    pub fn fields(&self) -> Option<Fields<'data>> {
+        match self.wire_type {
+            WIRE_LEN | WIRE_SGROUP => Some(Fields::new(self.value)),
+            _ => None,
+        }
+    }
+}
+
+impl<'data> Fields<'data> {
+    /// Create field iterator
+    ///
+    /// Create a new iterator over the fields of the serialized message
+    /// `data`.
+    pub fn new(data: &'data [u8]) -> Self {
+        Self { data }
+    }
+
+    /// Return remaining data
+    ///
+    /// Return the data that has not been consumed by the iterator.
+    pub fn remainder(&self) -> &'data [u8] {
+        self.data
+    }
+}
+
+impl<'data> Iterator for Fields<'data> {
+    type Item = Field<'data>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (number, wire_type, n) = decode_key(self.data)?;
+        if wire_type == WIRE_EGROUP {
+            return None;
+        }
+
+        let rest = &self.data[n..];
+
+        let (value, size) = match wire_type {
+            WIRE_LEN => {
+                let (length, m) = varint::leb128_decode(rest)?;
+                if length > LENGTH_MAX {
+                    return None;
+                }
+                let end = m.checked_add(length as usize)?;
+                (rest.get(m..end)?, end)
+            },
+            WIRE_SGROUP => {
+                // Skip nested fields until the matching end key, which is
+                // consumed as part of the group. Nested groups are tracked by
+                // depth only, to avoid recursion.
+                let mut depth = 0;
+                let mut end = 0;
+                loop {
+                    let (v, t, m) = decode_key(&rest[end..])?;
+                    match t {
+                        WIRE_SGROUP => depth += 1,
+                        WIRE_EGROUP if depth == 0 && v == number => break (&rest[..end], end + m),
+                        WIRE_EGROUP if depth == 0 => return None,
+                        WIRE_EGROUP => depth -= 1,
+                        _ => {},
+                    }
+                    end += m + value_size(t, &rest[end + m..])?;
+                }
+            },
+            _ => {
+                let size = value_size(wire_type, rest)?;
+                (&rest[..size], size)
+            },
+        };
+
+        self.data = &rest[size..];
+        Some(Field { number, wire_type, value })
+    }
+}
+
+/// Pack field key
+///
+/// Return the key for the given field number and wire type.
+pub fn key(number: u32, wire_type: u8) -> u64 {
+    ((number as u64) << WIRE_TYPE_BITS) | wire_type as u64
+}
+
+/// Unpack field key
+///
+/// Return the field number and wire type of a key, or `None` if the field
+/// number is out of range.
+pub fn split_key(key: u64) -> Option<(u32, u8)> {
+    let number = u32::try_from(key >> WIRE_TYPE_BITS).ok()?;
+    if !(FIELD_NUMBER_MIN..=FIELD_NUMBER_MAX).contains(&number) {
+        return None;
+    }
+    Some((number, (key & WIRE_TYPE_MASK) as u8))
+}
+
+/// Decode field key
+///
+/// Decode the key at the start of `data`. Return the field number, wire
+/// type, and the number of bytes consumed, or `None` if the key is
+/// truncated, or its field number or wire type is invalid.
+pub fn decode_key(data: &[u8]) -> Option<(u32, u8, usize)> {
+    let (v, n) = varint::leb128_decode(data)?;
+    let (number, wire_type) = split_key(v)?;
+    if wire_type > WIRE_I32 {
+        return None;
+    }
+    Some((number, wire_type, n))
+}
+
+/// Encode field key
+///
+/// Encode the key for the given field number and wire type. Return the
+/// number of bytes written, or `None` if the buffer is too small.
+pub fn encode_key(number: u32, wire_type: u8, out: &mut [u8]) -> Option<usize> {
+    varint::leb128_encode(key(number, wire_type), out)
+}
+
+/// Return value size
+///
+/// Return the size of the value of the given wire type at the start of
+/// `data`, including the length of `WIRE_LEN` values. Group keys have no
+/// value. Return `None` if the value is truncated or the wire type is
+/// invalid.
+pub fn value_size(wire_type: u8, data: &[u8]) -> Option<usize> {
+    let n = match wire_type {
+        WIRE_VARINT => varint::leb128_decode(data)?.1,
+        WIRE_I64 => FIXED64_SIZE,
+        WIRE_I32 => FIXED32_SIZE,
+        WIRE_LEN => {
+            let (length, m) = varint::leb128_decode(data)?;
+            if length > LENGTH_MAX {
+                return None;
+            }
+            m + length as usize
+        },
+        WIRE_SGROUP | WIRE_EGROUP => 0,
+        _ => return None,
+    };
+
+    if n > data.len() {
+        return None;
+    }
+    Some(n)
+}
+
+/// Find field
+///
+/// Return the last occurrence of the field with the given number in the
+/// serialized message `data`, as required for scalar fields.
+pub fn find_field(data: &[u8], number: u32) -> Option<Field<'_>> {
+    Fields::new(data).filter(|v| v.number == number).last()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Message from the encoding guide: `a = 150`, `b = "testing"`, and
+    // `c = { a: 150 }`, followed by `sfixed32 d = -2`, `sint64 e = -3`, and
+    // a group `f` with `a = 1`.
+    const MESSAGE: [u8; 28] = [
+        0x08, 0x96, 0x01, 0x12, 0x07, 0x74, 0x65, 0x73,
+        0x74, 0x69, 0x6e, 0x67, 0x1a, 0x03, 0x08, 0x96,
+        0x01, 0x25, 0xfe, 0xff, 0xff, 0xff, 0x28, 0x05,
+        0x33, 0x08, 0x01, 0x34,
+    ];
+
+    // Verify field keys.
+    #[test]
+    fn verify_keys() {
+        assert_eq!(key(1, WIRE_VARINT), 0x08);
+        assert_eq!(key(2, WIRE_LEN), 0x12);
+        assert_eq!(split_key(0x1a), Some((3, WIRE_LEN)));
+        assert_eq!(split_key(0x07), None);
+        assert_eq!(split_key((FIELD_NUMBER_MAX as u64 + 1) << 3), None);
+
+        let mut buf = [0; 5];
+        assert_eq!(encode_key(FIELD_NUMBER_MAX, WIRE_I32, &mut buf), Some(5));
+        assert_eq!(decode_key(&buf), Some((FIELD_NUMBER_MAX, WIRE_I32, 5)));
+        assert_eq!(encode_key(16, WIRE_VARINT, &mut buf[..1]), None);
+        assert_eq!(decode_key(&[0x0e]), None);
+        assert_eq!(decode_key(&[0x88]), None);
+    }
+
+    // Iterate fields of a message.
+    #[test]
+    fn verify_fields() {
+        let mut f = Fields::new(&MESSAGE);
+        let v = f.next().unwrap();
+        assert_eq!((v.number, v.varint()), (1, Some(150)));
+        let v = f.next().unwrap();
+        assert_eq!((v.number, v.value), (2, &b"testing"[..]));
+        let v = f.next().unwrap();
+        let mut c = v.fields().unwrap();
+        assert_eq!(c.next().unwrap().varint(), Some(150));
+        assert!(c.next().is_none());
+        let v = f.next().unwrap();
+        assert_eq!((v.number, v.fixed32().map(|v| v as i32)), (4, Some(-2)));
+        let v = f.next().unwrap();
+        assert_eq!((v.number, v.sint()), (5, Some(-3)));
+        let v = f.next().unwrap();
+        assert_eq!((v.number, v.wire_type, v.value), (6, WIRE_SGROUP, &[0x08, 0x01][..]));
+        assert!(f.next().is_none());
+        assert!(f.remainder().is_empty());
+
+        assert_eq!(find_field(&MESSAGE, 2).unwrap().value, b"testing");
+        assert!(find_field(&MESSAGE, 7).is_none());
+        assert_eq!(Fields::new(&MESSAGE[..11]).count(), 1);
+        assert_eq!(Fields::new(&MESSAGE[..27]).count(), 5);
+        assert_eq!(Fields::new(&MESSAGE[27..]).count(), 0);
+        assert_eq!(find_field(&[0x33, 0x3b, 0x3c, 0x34], 6).unwrap().value, [0x3b, 0x3c]);
+        assert_eq!(Fields::new(&[0x33, 0x3b, 0x34, 0x3c]).count(), 0);
+        assert_eq!(value_size(WIRE_I64, &[0; 7]), None);
+    }
+}
@@ -0,0 +1,293 @@
+//! Punycode
+//!
+//! Punycode (RFC 3492) is the instance of the Bootstring algorithm used by
+//! IDNA to represent Unicode domain name labels in ASCII. The encoding
+//! starts with all basic (ASCII) code points of the input in their original
+//! order, followed by `DELIMITER` if there are any, and then encodes the
+//! insertions of all non-basic code points as generalized variable-length
+//! integers of base-36 digits (`a`-`z` and `0`-`9`), with a bias that adapts
+//! to the data (see `BASE` and following).
+//!
+//! IDNA labels carrying Punycode are prefixed with `ACE_PREFIX` (see
+//! `encode_label()` and `decode_label()`), and must fit the label size limit
+//! of DNS (see `dns::LABEL_MAX`). Mapping and validation of the Unicode form
+//! (IDNA2008, UTS #46) is not part of Punycode.
+
+use crate::specs::dns;
+
+pub const BASE: u32 = 36;
+pub const TMIN: u32 = 1;
+pub const TMAX: u32 = 26;
+pub const SKEW: u32 = 38;
+pub const DAMP: u32 = 700;
+pub const INITIAL_BIAS: u32 = 72;
+pub const INITIAL_N: u32 = 0x80;
+pub const DELIMITER: u8 = b'-';
+
+/// ASCII-compatible Encoding Prefix
+pub const ACE_PREFIX: &[u8; 4] = b"xn--";
+
+// Adapt the bias after each delta (RFC 3492, section 6.1).
+fn adapt(mut delta: u32, points: u32, first: bool) -> u32 {
+    delta /= if first { DAMP } else { 2 };
+    delta += delta / points;
+
+    let mut k = 0;
+    while delta > ((BASE - TMIN) * TMAX) / 2 {
+        delta /= BASE - TMIN;
+        k += BASE;
+    }
+
+    k + (BASE - TMIN + 1) * delta / (delta + SKEW)
+}
+
+// Return the threshold of the digit at position `k` for the given bias.
+fn threshold(k: u32, bias: u32) -> u32 {
+    k.saturating_sub(bias).clamp(TMIN, TMAX)
+}
+
+// Return the value of a digit, accepting either case.
+fn digit_decode(c: u8) -> Option<u32> {
+    match c {
+        b'a'..=b'z' => Some((c - b'a') as u32),
+        b'A'..=b'Z' => Some((c - b'A') as u32),
+        b'0'..=b'9' => Some((c - b'0') as u32 + 26),
+        _ => None,
+    }
+}
+
+// Return the lowercase symbol of a digit.
+fn digit_encode(d: u32) -> u8 {
+    match d {
+        0..=25 => b'a' + d as u8,
+        _ => b'0' + (d - 26) as u8,
+    }
+}
+
+/// Decode Punycode
+///
+/// Decode the Punycode string `data` into code points. Return the number of
+/// code points written, or `None` if the input is invalid, overflows, or
+/// the buffer is too small.
+pub fn decode(data: &[u8], out: &mut [char]) -> Option<usize> {
+    let (basic, extended) = match data.iter().rposition(|v| *v == DELIMITER) {
+        Some(i) => (&data[..i], &data[i + 1..]),
+        None => (&data[..0], data),
+    };
+
+    if !basic.is_ascii() {
+        return None;
+    }
+
+    let mut o = basic.len();
+    for (dst, src) in out.get_mut(..o)?.iter_mut().zip(basic) {
+        *dst = *src as char;
+    }
+
+    let mut n = INITIAL_N;
+    let mut i: u32 = 0;
+    let mut bias = INITIAL_BIAS;
+    let mut rest = extended;
+
+    while !rest.is_empty() {
+        let old = i;
+        let mut w: u32 = 1;
+        let mut k = BASE;
+
+        loop {
+            let (c, r) = rest.split_first()?;
+            rest = r;
+
+            let d = digit_decode(*c)?;
+            i = i.checked_add(d.checked_mul(w)?)?;
+            let t = threshold(k, bias);
+            if d < t {
+                break;
+            }
+            w = w.checked_mul(BASE - t)?;
+            k += BASE;
+        }
+
+        let points = o as u32 + 1;
+        bias = adapt(i - old, points, old == 0);
+        n = n.checked_add(i / points)?;
+        i %= points;
+
+        let c = char::from_u32(n)?;
+        out.get(o)?;
+        out.copy_within(i as usize..o, i as usize + 1);
+        out[i as usize] = c;
+        o += 1;
+        i += 1;
+    }
+
+    Some(o)
+}
+
+/// Encode Punycode
+///
+/// Encode the code points `data` as Punycode. Return the number of bytes
+/// written, or `None` if the encoding overflows or the buffer is too
+/// small.
+pub fn encode(data: &[char], out: &mut [u8]) -> Option<usize> {
+    let mut o = 0;
+    for c in data.iter().filter(|v| v.is_ascii()) {
+        *out.get_mut(o)? = *c as u8;
+        o += 1;
+    }
+
+    let basic = o as u32;
+    let mut h = basic;
+    if basic > 0 {
+        *out.get_mut(o)? = DELIMITER;
+        o += 1;
+    }
+
+    let mut n = INITIAL_N;
+    let mut delta: u32 = 0;
+    let mut bias = INITIAL_BIAS;
+    let total = u32::try_from(data.len()).ok()?;
+
+    while h < total {
+        let m = data.iter().map(|v| *v as u32).filter(|v| *v >= n).min()?;
+        delta = delta.checked_add((m - n).checked_mul(h + 1)?)?;
+        n = m;
+
+        for c in data.iter().map(|v| *v as u32) {
+            if c < n {
+                delta = delta.checked_add(1)?;
+            } else if c == n {
+                let mut q = delta;
+                let mut k = BASE;
+                loop {
+                    let t = threshold(k, bias);
+                    if q < t {
+                        break;
+                    }
+                    *out.get_mut(o)? = digit_encode(t + (q - t) % (BASE - t));
+                    o += 1;
+                    q = (q - t) / (BASE - t);
+                    k += BASE;
+                }
+                *out.get_mut(o)? = digit_encode(q);
+                o += 1;
+
+                bias = adapt(delta, h + 1, h == basic);
+                delta = 0;
+                h += 1;
+            }
+        }
+
+        delta = delta.checked_add(1)?;
+        n = n.checked_add(1)?;
+    }
+
+    Some(o)
+}
+
+/// Decode IDNA label
+///
+/// Decode a label of a domain name into code points. Labels with the
+/// `ACE_PREFIX`, in any case, are decoded as Punycode, all others must be
+/// ASCII and are copied. Return the number of code points written, or
+/// `None` if the label exceeds `dns::LABEL_MAX`, is invalid, or the buffer
+/// is too small.
+pub fn decode_label(label: &[u8], out: &mut [char]) -> Option<usize> {
+    if label.len() > dns::LABEL_MAX {
+        return None;
+    }
+
+    match label.get(..ACE_PREFIX.len()) {
+        Some(v) if v.eq_ignore_ascii_case(ACE_PREFIX) => {
+            decode(&label[ACE_PREFIX.len()..], out)
+        },
+        _ if label.is_ascii() => {
+            for (dst, src) in out.get_mut(..label.len())?.iter_mut().zip(label) {
+                *dst = *src as char;
+            }
+            Some(label.len())
+        },
+        _ => None,
+    }
+}
+
+/// Encode IDNA label
+///
+/// Encode a label of a domain name. Labels with non-ASCII code points are
+/// encoded as Punycode with the `ACE_PREFIX`, all others are copied. Return
+/// the number of bytes written, or `None` if the encoded label exceeds
+/// `dns::LABEL_MAX`, or the buffer is too small.
+pub fn encode_label(label: &[char], out: &mut [u8]) -> Option<usize> {
+    let n = out.len().min(dns::LABEL_MAX);
+    let out = &mut out[..n];
+
+    if label.iter().all(|v| v.is_ascii()) {
+        for (dst, src) in out.get_mut(..label.len())?.iter_mut().zip(label) {
+            *dst = *src as u8;
+        }
+        return Some(label.len());
+    }
+
+    out.get_mut(..ACE_PREFIX.len())?.copy_from_slice(ACE_PREFIX);
+    Some(ACE_PREFIX.len() + encode(label, &mut out[ACE_PREFIX.len()..])?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Sample strings of RFC 3492, section 7.1, and common examples.
+    const SAMPLES: [(&str, &str); 5] = [
+        ("他们为什么不说中文", "ihqwcrb4cv8a8dqg056pqjye"),
+        ("3年B組金八先生", "3B-ww4c5e180e575a65lsy2b"),
+        ("Pročprostěnemluvíčesky", "Proprostnemluvesky-uyb24dma41a"),
+        ("bücher", "bcher-kva"),
+        ("-> $1.00 <-", "-> $1.00 <--"),
+    ];
+
+    // Encode and decode the sample strings.
+    #[test]
+    fn verify_samples() {
+        let mut chars = ['\0'; 32];
+        let mut buf = [0; 64];
+
+        for (unicode, puny) in SAMPLES {
+            let mut n = 0;
+            for (dst, src) in chars.iter_mut().zip(unicode.chars()) {
+                *dst = src;
+                n += 1;
+            }
+
+            let m = encode(&chars[..n], &mut buf).unwrap();
+            assert_eq!(&buf[..m], puny.as_bytes());
+
+            let mut out = ['\0'; 32];
+            assert_eq!(decode(puny.as_bytes(), &mut out), Some(n));
+            assert_eq!(out[..n], chars[..n]);
+        }
+
+        assert_eq!(encode(&['b', 'ü', 'c', 'h', 'e', 'r'], &mut buf[..8]), None);
+    }
+
+    // Encode and decode IDNA labels, and reject invalid input.
+    #[test]
+    fn verify_labels() {
+        let mut chars = ['\0'; 16];
+        let mut buf = [0; 16];
+        let label = ['m', 'ü', 'n', 'c', 'h', 'e', 'n'];
+
+        assert_eq!(encode_label(&label, &mut buf), Some(14));
+        assert_eq!(&buf[..14], b"xn--mnchen-3ya");
+        assert_eq!(decode_label(b"XN--mnchen-3YA", &mut chars), Some(7));
+        assert_eq!(chars[..7], label);
+        assert_eq!(encode_label(&['a', 'b'], &mut buf), Some(2));
+        assert_eq!(decode_label(b"ab", &mut chars), Some(2));
+        assert_eq!(decode_label(&[b'a'; 64], &mut chars), None);
+
+        assert_eq!(decode(b"bcher-kv", &mut chars), None);
+        assert_eq!(decode(b"bcher-k!a", &mut chars), None);
+        assert_eq!(decode(b"b\xfccher-kva", &mut chars), None);
+        assert_eq!(decode(b"99999999999", &mut chars), None);
+        assert_eq!(decode(b"bcher-kva", &mut chars[..5]), None);
+    }
+}
@@ -0,0 +1,299 @@
+//! Quite OK Image Format
+//!
+//! The Quite OK Image Format (QOI) is a simple lossless image format. A file
+//! consists of a 14-byte header, a stream of operations encoding the pixels,
+//! and an 8-byte end marker.
+//!
+//! The decoder tracks the previous pixel, starting with opaque black, and an
+//! array of 64 recently seen pixels, indexed by `hash()`. Each operation
+//! produces one or more pixels based on this state:
+//!
+//!  * `OP_RGB`, `OP_RGBA`: An explicit pixel, with 3 or 4 channel bytes
+//!    following the tag byte.
+//!  * `OP_INDEX`: A pixel from the array of recently seen pixels.
+//!  * `OP_DIFF`: Small differences to the previous pixel, 2 bits per RGB
+//!    channel with a bias of 2.
+//!  * `OP_LUMA`: A 6-bit green difference with a bias of 32, and red and
+//!    blue differences relative to the green difference, 4 bits each with a
+//!    bias of 8.
+//!  * `OP_RUN`: A repetition of the previous pixel, 1 to 62 times with a
+//!    bias of -1.
+//!
+//! The 8-bit tags `OP_RGB` and `OP_RGBA` take precedence over the 2-bit tags,
+//! which overlap the run lengths 63 and 64. Hence, these run lengths are
+//! invalid.
+//!
+//! All multi-byte integers are encoded as big-endian.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::int;
+
+/// Magic Signature
+///
+/// The initial 4 bytes of every QOI file ("qoif").
+pub const MAGIC: [u8; 4] = [0x71, 0x6f, 0x69, 0x66];
+
+/// End Marker
+///
+/// The 8 bytes following the last operation of a file.
+pub const END_MARKER: [u8; 8] = [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01];
+
+pub const CHANNELS_RGB: u8 = 3;
+pub const CHANNELS_RGBA: u8 = 4;
+
+pub const COLORSPACE_SRGB: u8 = 0;
+pub const COLORSPACE_LINEAR: u8 = 1;
+
+pub const OP_INDEX: u8 = 0x00;
+pub const OP_DIFF: u8 = 0x40;
+pub const OP_LUMA: u8 = 0x80;
+pub const OP_RUN: u8 = 0xc0;
+pub const OP_RGB: u8 = 0xfe;
+pub const OP_RGBA: u8 = 0xff;
+
+/// Mask of 2-bit Tags
+pub const OP_MASK: u8 = 0xc0;
+
+/// Maximum Run Length
+pub const RUN_MAX: u8 = 62;
+
+/// Size of the Pixel Index
+pub const INDEX_SIZE: usize = 64;
+
+/// File Header
+///
+/// Since the header is not padded to its natural alignment, this structure
+/// is packed.
+#[repr(C, packed)]
+pub struct Header {
+    /// The static signature identifying the format. This must match `MAGIC`.
+    pub magic: [u8; 4],
+
+    /// Image width in pixels.
+    pub width: int::u32be,
+
+    /// Image height in pixels.
+    pub height: int::u32be,
+
+    /// Number of channels (`CHANNELS_*`). This is informative only and does
+    /// not change the encoding.
+    pub channels: u8,
+
+    /// Colorspace (`COLORSPACE_*`). This is informative only.
+    pub colorspace: u8,
+}
+
+/// Pixel Operation
+///
+/// The decoded representation of a single operation. Differences are stored
+/// without bias.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Op {
+    Rgb([u8; 3]),
+    Rgba([u8; 4]),
+    Index(u8),
+    Diff { dr: i8, dg: i8, db: i8 },
+    Luma { dg: i8, dr_dg: i8, db_dg: i8 },
+    Run(u8),
+}
+
+impl Header {
+    /// Import a header from a byte slice
+    ///
+    /// Create a new header from data copied from a byte slice. No byte-order
+    /// conversions are applied.
+    pub fn from_bytes(data: &[u8; 14]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 14], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Convert to byte slice
+    ///
+    /// Return a byte-slice reference to the header. No byte-order conversions
+    /// are applied.
+    pub fn as_bytes(&self) -> &[u8; 14] {
+        assert!(align_of::<[u8; 14]>() <= align_of::<Self>());
+        assert!(size_of::<[u8; 14]>() == size_of::<Self>());
+
+        unsafe {
+            core::mem::transmute::<&Self, &[u8; 14]>(self)
+        }
+    }
+}
+
+impl Op {
+    /// Decode an operation
+    ///
+    /// Decode the operation at the start of `data`. Return the operation and
+    /// the number of bytes consumed, or `None` if the data is truncated.
+    pub fn decode(data: &[u8]) -> Option<(Self, usize)> {
+        let tag = *data.first()?;
+
+        match tag {
+            OP_RGB => {
+                let v = data.get(1..4)?;
+                Some((Op::Rgb([v[0], v[1], v[2]]), 4))
+            },
+            OP_RGBA => {
+                let v = data.get(1..5)?;
+                Some((Op::Rgba([v[0], v[1], v[2], v[3]]), 5))
+            },
+            _ => match tag & OP_MASK {
+                OP_INDEX => Some((Op::Index(tag & 0x3f), 1)),
+                OP_DIFF => Some((
+                    Op::Diff {
+                        dr: ((tag >> 4) & 0x03) as i8 - 2,
+                        dg: ((tag >> 2) & 0x03) as i8 - 2,
+                        db: (tag & 0x03) as i8 - 2,
+                    },
+                    1,
+                )),
+                OP_LUMA => {
+                    let v = *data.get(1)?;
+                    Some((
+                        Op::Luma {
+                            dg: (tag & 0x3f) as i8 - 32,
+                            dr_dg: (v >> 4) as i8 - 8,
+                            db_dg: (v & 0x0f) as i8 - 8,
+                        },
+                        2,
+                    ))
+                },
+                _ => Some((Op::Run((tag & 0x3f) + 1), 1)),
+            },
+        }
+    }
+
+    /// Encode an operation
+    ///
+    /// Encode the operation into the start of `data`. Return the number of
+    /// bytes written, or `None` if `data` is too small or a field is out of
+    /// range.
+    pub fn encode(&self, data: &mut [u8]) -> Option<usize> {
+        let (buf, n): ([u8; 5], usize) = match *self {
+            Op::Rgb(v) => ([OP_RGB, v[0], v[1], v[2], 0], 4),
+            Op::Rgba(v) => ([OP_RGBA, v[0], v[1], v[2], v[3]], 5),
+            Op::Index(i) => {
+                if i as usize >= INDEX_SIZE {
+                    return None;
+                }
+                ([OP_INDEX | i, 0, 0, 0, 0], 1)
+            },
+            Op::Diff { dr, dg, db } => {
+                if !(-2..=1).contains(&dr) || !(-2..=1).contains(&dg) || !(-2..=1).contains(&db) {
+                    return None;
+                }
+                let v = ((dr + 2) as u8) << 4 | ((dg + 2) as u8) << 2 | (db + 2) as u8;
+                ([OP_DIFF | v, 0, 0, 0, 0], 1)
+            },
+            Op::Luma { dg, dr_dg, db_dg } => {
+                if !(-32..=31).contains(&dg)
+                    || !(-8..=7).contains(&dr_dg)
+                    || !(-8..=7).contains(&db_dg)
+                {
+                    return None;
+                }
+                let v = ((dr_dg + 8) as u8) << 4 | (db_dg + 8) as u8;
+                ([OP_LUMA | (dg + 32) as u8, v, 0, 0, 0], 2)
+            },
+            Op::Run(r) => {
+                if r == 0 || r > RUN_MAX {
+                    return None;
+                }
+                ([OP_RUN | (r - 1), 0, 0, 0, 0], 1)
+            },
+        };
+
+        data.get_mut(..n)?.copy_from_slice(&buf[..n]);
+        Some(n)
+    }
+}
+
+/// Calculate Pixel Index Position
+///
+/// Return the position of an RGBA pixel in the array of recently seen
+/// pixels.
+pub fn hash(rgba: [u8; 4]) -> u8 {
+    let [r, g, b, a] = rgba;
+    let v = (r as u32) * 3 + (g as u32) * 5 + (b as u32) * 7 + (a as u32) * 11;
+    (v % INDEX_SIZE as u32) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A 2x2 RGB image: a red pixel, its repetition, and two pixels encoded
+    // as difference and luma operations.
+    const IMAGE: [u8; 30] = [
+        0x71, 0x6f, 0x69, 0x66, 0x00, 0x00, 0x00, 0x02,
+        0x00, 0x00, 0x00, 0x02, 0x03, 0x00, 0xfe, 0xff,
+        0x00, 0x00, 0xc0, 0x7b, 0xa8, 0x98, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x01,
+    ];
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<Header>(), 14);
+        assert_eq!(align_of::<Header>(), 1);
+    }
+
+    // Decode a small image and re-encode all operations.
+    #[test]
+    fn verify_image() {
+        let h = Header::from_bytes(IMAGE[..14].try_into().unwrap());
+        assert_eq!(h.magic, MAGIC);
+        assert_eq!(int::ForeignEndian::to_native(h.width), 2);
+        assert_eq!(int::ForeignEndian::to_native(h.height), 2);
+        assert_eq!(h.channels, CHANNELS_RGB);
+        assert_eq!(h.colorspace, COLORSPACE_SRGB);
+        assert_eq!(h.as_bytes(), &IMAGE[..14]);
+
+        let ops = [
+            Op::Rgb([0xff, 0x00, 0x00]),
+            Op::Run(1),
+            Op::Diff { dr: 1, dg: 0, db: 1 },
+            Op::Luma { dg: 8, dr_dg: 1, db_dg: 0 },
+        ];
+
+        let mut data = &IMAGE[14..];
+        let mut buf = [0u8; 5];
+        for op in ops.iter() {
+            let (v, n) = Op::decode(data).unwrap();
+            assert_eq!(v, *op);
+            assert_eq!(op.encode(&mut buf), Some(n));
+            assert_eq!(buf[..n], data[..n]);
+            data = &data[n..];
+        }
+        assert_eq!(data, END_MARKER);
+    }
+
+    // Verify the 8-bit tags take precedence and ranges are checked.
+    #[test]
+    fn verify_ops() {
+        assert_eq!(Op::decode(&[0xfd]), Some((Op::Run(62), 1)));
+        assert_eq!(Op::decode(&[0x05]), Some((Op::Index(5), 1)));
+        assert_eq!(Op::decode(&[OP_RGBA, 1, 2, 3]), None);
+
+        let mut buf = [0u8; 5];
+        assert_eq!(Op::Run(63).encode(&mut buf), None);
+        assert_eq!(Op::Diff { dr: 2, dg: 0, db: 0 }.encode(&mut buf), None);
+        assert_eq!(Op::Rgba([0; 4]).encode(&mut buf[..4]), None);
+
+        assert_eq!(hash([0, 0, 0, 255]), 53);
+        assert_eq!(hash([0xff, 0x00, 0x00, 0xff]), 50);
+    }
+}
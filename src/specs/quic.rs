@@ -0,0 +1,555 @@
+//! QUIC
+//!
+//! QUIC packets are carried in UDP datagrams and start with either a long
+//! or a short header, told apart by the most significant bit of the first
+//! byte (`FORM_LONG`). Long headers (see `LongPacket`) carry the version and
+//! both connection IDs with explicit lengths, and are used during connection
+//! establishment. Multiple long-header packets can be coalesced into a
+//! single datagram. Short headers (see `ShortPacket`) carry only the
+//! destination connection ID, whose length is not encoded but known to the
+//! receiver from the connection state.
+//!
+//! The first byte (except for the header form and fixed bit) and the packet
+//! number are protected by header protection, and must be unprotected
+//! before the reserved bits, key phase, and packet number length can be
+//! read. Packet numbers are truncated on the wire (see
+//! `decode_packet_number()`).
+//!
+//! The payload is a sequence of frames, each starting with its frame type
+//! (`FRAME_*`) encoded as variable-length integer (see
+//! `varint::quic_decode()`), as are most frame fields. `Ack` and `Stream`
+//! decode the fixed parts of the most common frames.
+//!
+//! All multi-byte integers are encoded as big-endian.
+
+use crate::specs::varint;
+
+pub const CONNECTION_ID_MAX: usize = 20;
+pub const RETRY_INTEGRITY_TAG_SIZE: usize = 16;
+pub const STATELESS_RESET_TOKEN_SIZE: usize = 16;
+
+/// Minimum Size of UDP Payloads Carrying Initial Packets
+pub const INITIAL_DATAGRAM_MIN: usize = 1200;
+
+pub const VERSION_NEGOTIATION: u32 = 0x0000_0000;
+pub const VERSION_1: u32 = 0x0000_0001;
+pub const VERSION_2: u32 = 0x6b33_43cf;
+
+pub const FORM_LONG: u8 = 0x80;
+pub const FIXED_BIT: u8 = 0x40;
+pub const LONG_TYPE_MASK: u8 = 0x30;
+pub const LONG_TYPE_SHIFT: u32 = 4;
+pub const LONG_RESERVED_MASK: u8 = 0x0c;
+pub const SHORT_SPIN_BIT: u8 = 0x20;
+pub const SHORT_RESERVED_MASK: u8 = 0x18;
+pub const SHORT_KEY_PHASE: u8 = 0x04;
+pub const PACKET_NUMBER_LENGTH_MASK: u8 = 0x03;
+
+pub const PACKET_TYPE_INITIAL: u8 = 0;
+pub const PACKET_TYPE_ZERO_RTT: u8 = 1;
+pub const PACKET_TYPE_HANDSHAKE: u8 = 2;
+pub const PACKET_TYPE_RETRY: u8 = 3;
+
+pub const FRAME_PADDING: u64 = 0x00;
+pub const FRAME_PING: u64 = 0x01;
+pub const FRAME_ACK: u64 = 0x02;
+pub const FRAME_ACK_ECN: u64 = 0x03;
+pub const FRAME_RESET_STREAM: u64 = 0x04;
+pub const FRAME_STOP_SENDING: u64 = 0x05;
+pub const FRAME_CRYPTO: u64 = 0x06;
+pub const FRAME_NEW_TOKEN: u64 = 0x07;
+pub const FRAME_STREAM: u64 = 0x08;
+pub const FRAME_STREAM_MAX: u64 = 0x0f;
+pub const FRAME_MAX_DATA: u64 = 0x10;
+pub const FRAME_MAX_STREAM_DATA: u64 = 0x11;
+pub const FRAME_MAX_STREAMS_BIDI: u64 = 0x12;
+pub const FRAME_MAX_STREAMS_UNI: u64 = 0x13;
+pub const FRAME_DATA_BLOCKED: u64 = 0x14;
+pub const FRAME_STREAM_DATA_BLOCKED: u64 = 0x15;
+pub const FRAME_STREAMS_BLOCKED_BIDI: u64 = 0x16;
+pub const FRAME_STREAMS_BLOCKED_UNI: u64 = 0x17;
+pub const FRAME_NEW_CONNECTION_ID: u64 = 0x18;
+pub const FRAME_RETIRE_CONNECTION_ID: u64 = 0x19;
+pub const FRAME_PATH_CHALLENGE: u64 = 0x1a;
+pub const FRAME_PATH_RESPONSE: u64 = 0x1b;
+pub const FRAME_CONNECTION_CLOSE: u64 = 0x1c;
+pub const FRAME_CONNECTION_CLOSE_APPLICATION: u64 = 0x1d;
+pub const FRAME_HANDSHAKE_DONE: u64 = 0x1e;
+pub const FRAME_DATAGRAM: u64 = 0x30;
+pub const FRAME_DATAGRAM_LEN: u64 = 0x31;
+
+pub const STREAM_FLAG_FIN: u64 = 0x01;
+pub const STREAM_FLAG_LEN: u64 = 0x02;
+pub const STREAM_FLAG_OFF: u64 = 0x04;
+
+pub const STREAM_ID_SERVER: u64 = 0x01;
+pub const STREAM_ID_UNIDIRECTIONAL: u64 = 0x02;
+
+pub const ERROR_NO_ERROR: u64 = 0x00;
+pub const ERROR_INTERNAL_ERROR: u64 = 0x01;
+pub const ERROR_CONNECTION_REFUSED: u64 = 0x02;
+pub const ERROR_FLOW_CONTROL_ERROR: u64 = 0x03;
+pub const ERROR_STREAM_LIMIT_ERROR: u64 = 0x04;
+pub const ERROR_STREAM_STATE_ERROR: u64 = 0x05;
+pub const ERROR_FINAL_SIZE_ERROR: u64 = 0x06;
+pub const ERROR_FRAME_ENCODING_ERROR: u64 = 0x07;
+pub const ERROR_TRANSPORT_PARAMETER_ERROR: u64 = 0x08;
+pub const ERROR_CONNECTION_ID_LIMIT_ERROR: u64 = 0x09;
+pub const ERROR_PROTOCOL_VIOLATION: u64 = 0x0a;
+pub const ERROR_INVALID_TOKEN: u64 = 0x0b;
+pub const ERROR_APPLICATION_ERROR: u64 = 0x0c;
+pub const ERROR_CRYPTO_BUFFER_EXCEEDED: u64 = 0x0d;
+pub const ERROR_KEY_UPDATE_ERROR: u64 = 0x0e;
+pub const ERROR_AEAD_LIMIT_REACHED: u64 = 0x0f;
+pub const ERROR_NO_VIABLE_PATH: u64 = 0x10;
+pub const ERROR_CRYPTO_ERROR: u64 = 0x0100;
+
+/// Long Header Packet Reference
+///
+/// A long-header packet with its connection IDs and type-specific fields
+/// located. For packets of unknown versions, as well as version negotiation
+/// and retry packets, the payload extends to the end of the datagram.
+#[derive(Clone, Copy, Debug)]
+pub struct LongPacket<'data> {
+    /// First byte, possibly still protected.
+    pub first: u8,
+
+    /// Version (`VERSION_*`).
+    pub version: u32,
+
+    /// Destination connection ID.
+    pub dcid: &'data [u8],
+
+    /// Source connection ID.
+    pub scid: &'data [u8],
+
+    /// Packet type (`PACKET_TYPE_*`), if the version is known.
+    pub packet_type: Option<u8>,
+
+    /// Address validation token of initial packets, empty otherwise.
+    pub token: &'data [u8],
+
+    /// Payload, starting with the packet number if present. For retry
+    /// packets, this is the retry token followed by the integrity tag. For
+    /// version negotiation packets, this is the list of supported versions.
+    pub payload: &'data [u8],
+
+    /// Size of the packet in bytes, after which coalesced packets follow.
+    pub size: usize,
+}
+
+/// Short Header Packet Reference
+#[derive(Clone, Copy, Debug)]
+pub struct ShortPacket<'data> {
+    /// First byte, possibly still protected.
+    pub first: u8,
+
+    /// Destination connection ID.
+    pub dcid: &'data [u8],
+
+    /// Payload, starting with the packet number.
+    pub payload: &'data [u8],
+}
+
+/// ACK Frame
+///
+/// The fixed fields of an ACK frame, following the frame type. They are
+/// followed by `range_count` pairs of gap and range length (see
+/// `AckRanges`), and by three ECN counts for `FRAME_ACK_ECN`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Ack {
+    /// Largest packet number acknowledged.
+    pub largest: u64,
+
+    /// Acknowledgment delay, scaled by the ACK delay exponent.
+    pub delay: u64,
+
+    /// Number of additional ranges.
+    pub range_count: u64,
+
+    /// Number of packets below `largest` acknowledged by the first range.
+    pub first_range: u64,
+}
+
+/// ACK Range Iterator
+///
+/// An iterator over the additional ranges of an ACK frame, yielding pairs
+/// of gap and range length. Afterwards, `remainder()` starts at the ECN
+/// counts, if any, or the next frame.
+#[derive(Clone, Debug)]
+pub struct AckRanges<'data> {
+    data: &'data [u8],
+    count: u64,
+}
+
+/// STREAM Frame Reference
+#[derive(Clone, Copy, Debug)]
+pub struct Stream<'data> {
+    /// Stream identifier.
+    pub stream_id: u64,
+
+    /// Offset of the stream data, 0 if not present.
+    pub offset: u64,
+
+    /// Whether this frame marks the end of the stream.
+    pub fin: bool,
+
+    /// Stream data.
+    pub data: &'data [u8],
+}
+
+// Decode the variable-length integer at `*o` of `data`, and advance `*o`.
+fn varint_at(data: &[u8], o: &mut usize) -> Option<u64> {
+    let (v, n) = varint::quic_decode(data.get(*o..)?)?;
+    *o += n;
+    Some(v)
+}
+
+// Return the `n` bytes at `*o` of `data`, and advance `*o`.
+fn bytes_at<'data>(data: &'data [u8], o: &mut usize, n: usize) -> Option<&'data [u8]> {
+    let v = data.get(*o..(*o).checked_add(n)?)?;
+    *o += n;
+    Some(v)
+}
+
+impl<'data> LongPacket<'data> {
+    /// Parse a long-header packet
+    ///
+    /// Parse the long-header packet at the start of `data`. Return `None` if
+    /// the packet uses a short header or is truncated.
+    pub fn parse(data: &'data [u8]) -> Option<Self> {
+        let first = *data.first()?;
+        if !is_long(first) {
+            return None;
+        }
+
+        let version = u32::from_be_bytes(data.get(1..5)?.try_into().unwrap());
+        let mut o = 5;
+        let n = *data.get(o)? as usize;
+        o += 1;
+        let dcid = bytes_at(data, &mut o, n)?;
+        let n = *data.get(o)? as usize;
+        o += 1;
+        let scid = bytes_at(data, &mut o, n)?;
+
+        let packet_type = long_packet_type(first, version);
+        let mut token: &[u8] = &[];
+        let (payload, size) = match packet_type {
+            None | Some(PACKET_TYPE_RETRY) => (&data[o..], data.len()),
+            Some(t) => {
+                if t == PACKET_TYPE_INITIAL {
+                    let n = varint_at(data, &mut o)?;
+                    token = bytes_at(data, &mut o, usize::try_from(n).ok()?)?;
+                }
+                let n = varint_at(data, &mut o)?;
+                let payload = bytes_at(data, &mut o, usize::try_from(n).ok()?)?;
+                (payload, o)
+            },
+        };
+
+        Some(Self {
+            first,
+            version,
+            dcid,
+            scid,
+            packet_type,
+            token,
+            payload,
+            size,
+        })
+    }
+}
+
+impl<'data> ShortPacket<'data> {
+    /// Parse a short-header packet
+    ///
+    /// Parse a short-header packet, given the length of the destination
+    /// connection ID. Return `None` if the packet uses a long header or is
+    /// truncated.
+    pub fn parse(data: &'data [u8], dcid_length: usize) -> Option<Self> {
+        let first = *data.first()?;
+        if is_long(first) {
+            return None;
+        }
+
+        let dcid = data.get(1..1 + dcid_length)?;
+        Some(Self {
+            first,
+            dcid,
+            payload: &data[1 + dcid_length..],
+        })
+    }
+
+    /// Return spin bit
+    pub fn spin(&self) -> bool {
+        self.first & SHORT_SPIN_BIT != 0
+    }
+
+    /// Return key phase
+    ///
+    /// Return the key phase bit, which is only valid once header protection
+    /// was removed.
+    pub fn key_phase(&self) -> bool {
+        self.first & SHORT_KEY_PHASE != 0
+    }
+}
+
+impl Ack {
+    /// Parse an ACK frame
+    ///
+    /// Parse the fixed fields of an ACK frame at the start of `data`, which
+    /// must follow the frame type. Return the frame and the number of bytes
+    /// consumed.
+    pub fn parse(data: &[u8]) -> Option<(Self, usize)> {
+        let mut o = 0;
+        let largest = varint_at(data, &mut o)?;
+        let delay = varint_at(data, &mut o)?;
+        let range_count = varint_at(data, &mut o)?;
+        let first_range = varint_at(data, &mut o)?;
+
+        Some((
+            Self {
+                largest,
+                delay,
+                range_count,
+                first_range,
+            },
+            o,
+        ))
+    }
+}
+
+impl<'data> AckRanges<'data> {
+    /// Create ACK range iterator
+    ///
+    /// Create a new iterator over `count` ranges in `data`, which must start
+    /// after the fixed fields of the ACK frame.
+    pub fn new(data: &'data [u8], count: u64) -> Self {
+        Self {
+            data,
+            count,
+        }
+    }
+
+    /// Return remaining data
+    ///
+    /// Return the data that has not been consumed by the iterator.
+    pub fn remainder(&self) -> &'data [u8] {
+        self.data
+    }
+}
+
+impl<'data> Iterator for AckRanges<'data> {
+    type Item = (u64, u64);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.count == 0 {
+            return None;
+        }
+
+        let mut o = 0;
+        let gap = varint_at(self.data, &mut o)?;
+        let length = varint_at(self.data, &mut o)?;
+        self.data = &self.data[o..];
+        self.count -= 1;
+        Some((gap, length))
+    }
+}
+
+impl<'data> Stream<'data> {
+    /// Parse a STREAM frame
+    ///
+    /// Parse a STREAM frame at the start of `data`, which must follow the
+    /// frame type. Without `STREAM_FLAG_LEN`, the stream data extends to the
+    /// end of `data`. Return the frame and the number of bytes consumed.
+    pub fn parse(frame_type: u64, data: &'data [u8]) -> Option<(Self, usize)> {
+        let mut o = 0;
+        let stream_id = varint_at(data, &mut o)?;
+        let offset = if frame_type & STREAM_FLAG_OFF != 0 {
+            varint_at(data, &mut o)?
+        } else {
+            0
+        };
+        let n = if frame_type & STREAM_FLAG_LEN != 0 {
+            usize::try_from(varint_at(data, &mut o)?).ok()?
+        } else {
+            data.len() - o
+        };
+        let v = bytes_at(data, &mut o, n)?;
+
+        Some((
+            Self {
+                stream_id,
+                offset,
+                fin: frame_type & STREAM_FLAG_FIN != 0,
+                data: v,
+            },
+            o,
+        ))
+    }
+}
+
+/// Check for long headers
+pub fn is_long(first: u8) -> bool {
+    first & FORM_LONG != 0
+}
+
+/// Return long-header packet type
+///
+/// Return the packet type (`PACKET_TYPE_*`) of a long header with the given
+/// first byte and version, or `None` if the version is not known or is
+/// `VERSION_NEGOTIATION`. QUIC version 2 uses different type values, which
+/// are mapped to their version 1 equivalents.
+pub fn long_packet_type(first: u8, version: u32) -> Option<u8> {
+    let t = (first & LONG_TYPE_MASK) >> LONG_TYPE_SHIFT;
+    match version {
+        VERSION_1 => Some(t),
+        VERSION_2 => Some(match t {
+            0 => PACKET_TYPE_RETRY,
+            v => v - 1,
+        }),
+        _ => None,
+    }
+}
+
+/// Return packet number length
+///
+/// Return the size of the packet number in bytes, given the first byte
+/// with header protection removed.
+pub fn packet_number_length(first: u8) -> usize {
+    (first & PACKET_NUMBER_LENGTH_MASK) as usize + 1
+}
+
+/// Decode packet number
+///
+/// Reconstruct the full packet number from the truncated packet number of
+/// the given size in bits, and the largest packet number processed so far,
+/// following RFC 9000 appendix A.3.
+pub fn decode_packet_number(largest: u64, truncated: u64, bits: u32) -> u64 {
+    let expected = largest + 1;
+    let window = 1u64 << bits;
+    let half = window / 2;
+    let candidate = (expected & !(window - 1)) | truncated;
+
+    if candidate + half <= expected && candidate < (1 << 62) - window {
+        candidate + window
+    } else if candidate > expected + half && candidate >= window {
+        candidate - window
+    } else {
+        candidate
+    }
+}
+
+/// Check for STREAM frames
+pub fn is_stream(frame_type: u64) -> bool {
+    (FRAME_STREAM..=FRAME_STREAM_MAX).contains(&frame_type)
+}
+
+/// Check for server-initiated streams
+pub fn is_server_stream(stream_id: u64) -> bool {
+    stream_id & STREAM_ID_SERVER != 0
+}
+
+/// Check for unidirectional streams
+pub fn is_unidirectional_stream(stream_id: u64) -> bool {
+    stream_id & STREAM_ID_UNIDIRECTIONAL != 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // An initial packet with a token, coalesced with a handshake packet.
+    const DATAGRAM: [u8; 38] = [
+        0xc1, 0x00, 0x00, 0x00, 0x01, 0x04, 0x01, 0x02,
+        0x03, 0x04, 0x02, 0x0a, 0x0b, 0x02, 0xaa, 0xbb,
+        0x04, 0x00, 0x01, 0x01, 0x00, 0xe0, 0x00, 0x00,
+        0x00, 0x01, 0x04, 0x01, 0x02, 0x03, 0x04, 0x00,
+        0x03, 0x00, 0x02, 0x01, 0xff, 0xff,
+    ];
+
+    // Parse coalesced long-header packets, and short-header packets.
+    #[test]
+    fn verify_packets() {
+        let p = LongPacket::parse(&DATAGRAM).unwrap();
+        assert_eq!(p.version, VERSION_1);
+        assert_eq!(p.packet_type, Some(PACKET_TYPE_INITIAL));
+        assert_eq!(p.dcid, &[0x01, 0x02, 0x03, 0x04]);
+        assert_eq!(p.scid, &[0x0a, 0x0b]);
+        assert_eq!(p.token, &[0xaa, 0xbb]);
+        assert_eq!(packet_number_length(p.first), 2);
+        assert_eq!(p.payload, &[0x00, 0x01, 0x01, 0x00]);
+        assert_eq!(p.size, 21);
+
+        let p = LongPacket::parse(&DATAGRAM[21..]).unwrap();
+        assert_eq!(p.packet_type, Some(PACKET_TYPE_HANDSHAKE));
+        assert!(p.scid.is_empty());
+        assert!(p.token.is_empty());
+        assert_eq!(p.payload, &[0x00, 0x02, 0x01]);
+        assert_eq!(&DATAGRAM[21 + p.size..], &[0xff, 0xff]);
+        assert!(LongPacket::parse(&DATAGRAM[..20]).is_none());
+
+        let p = LongPacket::parse(&[0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01]).unwrap();
+        assert_eq!(p.version, VERSION_NEGOTIATION);
+        assert_eq!(p.packet_type, None);
+        assert_eq!(p.payload, &VERSION_1.to_be_bytes());
+
+        assert_eq!(long_packet_type(0xd0, VERSION_2), Some(PACKET_TYPE_INITIAL));
+        assert_eq!(long_packet_type(0xc0, VERSION_2), Some(PACKET_TYPE_RETRY));
+
+        let p = ShortPacket::parse(&[0x64, 0x01, 0x02, 0x03, 0x04, 0x9b, 0x32], 4).unwrap();
+        assert!(p.spin());
+        assert!(p.key_phase());
+        assert_eq!(p.dcid, &[0x01, 0x02, 0x03, 0x04]);
+        assert_eq!(p.payload, &[0x9b, 0x32]);
+        assert!(ShortPacket::parse(&DATAGRAM, 4).is_none());
+        assert!(LongPacket::parse(&[0x40]).is_none());
+
+        assert_eq!(decode_packet_number(0xa82f30ea, 0x9b32, 16), 0xa82f9b32);
+        assert_eq!(decode_packet_number(0xffff, 0x01, 8), 0x10001);
+    }
+
+    // Parse ACK and STREAM frames.
+    #[test]
+    fn verify_frames() {
+        let data = [0x02, 0x40, 0x64, 0x0a, 0x02, 0x03, 0x01, 0x02, 0x03, 0x04, 0x01];
+        let (t, n) = varint::quic_decode(&data).unwrap();
+        assert_eq!(t, FRAME_ACK);
+
+        let (ack, m) = Ack::parse(&data[n..]).unwrap();
+        assert_eq!(
+            ack,
+            Ack {
+                largest: 100,
+                delay: 10,
+                range_count: 2,
+                first_range: 3,
+            },
+        );
+        let mut ranges = AckRanges::new(&data[n + m..], ack.range_count);
+        assert_eq!(ranges.next(), Some((1, 2)));
+        assert_eq!(ranges.next(), Some((3, 4)));
+        assert_eq!(ranges.next(), None);
+        assert_eq!(ranges.remainder(), &[FRAME_PING as u8]);
+        assert!(Ack::parse(&data[1..4]).is_none());
+
+        let data = [0x0f, 0x04, 0x40, 0x80, 0x02, 0x68, 0x69, 0x01];
+        let (t, n) = varint::quic_decode(&data).unwrap();
+        assert!(is_stream(t));
+        let (s, m) = Stream::parse(t, &data[n..]).unwrap();
+        assert_eq!(s.stream_id, 4);
+        assert_eq!(s.offset, 128);
+        assert!(s.fin);
+        assert_eq!(s.data, b"hi");
+        assert_eq!(&data[n + m..], &[0x01]);
+        assert!(!is_server_stream(s.stream_id));
+        assert!(!is_unidirectional_stream(s.stream_id));
+
+        let (s, _) = Stream::parse(FRAME_STREAM, &[0x03, 0x68, 0x69]).unwrap();
+        assert_eq!((s.stream_id, s.offset, s.fin), (3, 0, false));
+        assert_eq!(s.data, b"hi");
+        assert!(is_server_stream(s.stream_id));
+        assert!(is_unidirectional_stream(s.stream_id));
+        assert!(Stream::parse(0x0a, &[0x00, 0x05, 0x68]).is_none());
+    }
+}
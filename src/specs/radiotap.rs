@@ -0,0 +1,392 @@
+//! Radiotap Capture Headers
+//!
+//! Radiotap headers precede captured IEEE 802.11 frames (see `ieee80211`)
+//! with link type `pcap::LINKTYPE_RADIOTAP`, and carry meta-data about the
+//! reception or transmission of the frame. The `Header` gives the total size
+//! of the Radiotap data, and starts a sequence of 32-bit presence bitmaps.
+//! Each bitmap with `PRESENT_EXT` set is followed by another bitmap. The
+//! fields follow the last bitmap, in the order of their bits, each aligned
+//! to its natural alignment relative to the start of the header (see
+//! `field_layout()`).
+//!
+//! Bitmaps are grouped into namespaces. The Radiotap namespace defines the
+//! fields listed as `PRESENT_*`. `PRESENT_RADIOTAP_NAMESPACE` starts a new
+//! Radiotap namespace with the next bitmap, which allows repeating fields
+//! (e.g., per antenna). `PRESENT_VENDOR_NAMESPACE` presents a 6-byte field
+//! identifying a vendor namespace, which starts with the next bitmap and
+//! carries vendor data of the given size. `Fields` iterates over the fields
+//! of a Radiotap header accordingly.
+//!
+//! Unlike most network protocols, all multi-byte integers are encoded as
+//! little-endian.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::int;
+
+pub const HEADER_SIZE: usize = 8;
+pub const VERSION: u8 = 0;
+
+pub const PRESENT_TSFT: u32 = 0;
+pub const PRESENT_FLAGS: u32 = 1;
+pub const PRESENT_RATE: u32 = 2;
+pub const PRESENT_CHANNEL: u32 = 3;
+pub const PRESENT_FHSS: u32 = 4;
+pub const PRESENT_DBM_ANTENNA_SIGNAL: u32 = 5;
+pub const PRESENT_DBM_ANTENNA_NOISE: u32 = 6;
+pub const PRESENT_LOCK_QUALITY: u32 = 7;
+pub const PRESENT_TX_ATTENUATION: u32 = 8;
+pub const PRESENT_DB_TX_ATTENUATION: u32 = 9;
+pub const PRESENT_DBM_TX_POWER: u32 = 10;
+pub const PRESENT_ANTENNA: u32 = 11;
+pub const PRESENT_DB_ANTENNA_SIGNAL: u32 = 12;
+pub const PRESENT_DB_ANTENNA_NOISE: u32 = 13;
+pub const PRESENT_RX_FLAGS: u32 = 14;
+pub const PRESENT_TX_FLAGS: u32 = 15;
+pub const PRESENT_RTS_RETRIES: u32 = 16;
+pub const PRESENT_DATA_RETRIES: u32 = 17;
+pub const PRESENT_XCHANNEL: u32 = 18;
+pub const PRESENT_MCS: u32 = 19;
+pub const PRESENT_AMPDU_STATUS: u32 = 20;
+pub const PRESENT_VHT: u32 = 21;
+pub const PRESENT_TIMESTAMP: u32 = 22;
+pub const PRESENT_HE: u32 = 23;
+pub const PRESENT_HE_MU: u32 = 24;
+pub const PRESENT_HE_MU_OTHER_USER: u32 = 25;
+pub const PRESENT_ZERO_LENGTH_PSDU: u32 = 26;
+pub const PRESENT_L_SIG: u32 = 27;
+pub const PRESENT_TLV: u32 = 28;
+pub const PRESENT_RADIOTAP_NAMESPACE: u32 = 29;
+pub const PRESENT_VENDOR_NAMESPACE: u32 = 30;
+pub const PRESENT_EXT: u32 = 31;
+
+pub const FLAGS_CFP: u8 = 0x01;
+pub const FLAGS_SHORT_PREAMBLE: u8 = 0x02;
+pub const FLAGS_WEP: u8 = 0x04;
+pub const FLAGS_FRAGMENT: u8 = 0x08;
+pub const FLAGS_FCS: u8 = 0x10;
+pub const FLAGS_DATA_PAD: u8 = 0x20;
+pub const FLAGS_BAD_FCS: u8 = 0x40;
+pub const FLAGS_SHORT_GI: u8 = 0x80;
+
+pub const CHANNEL_TURBO: u16 = 0x0010;
+pub const CHANNEL_CCK: u16 = 0x0020;
+pub const CHANNEL_OFDM: u16 = 0x0040;
+pub const CHANNEL_2GHZ: u16 = 0x0080;
+pub const CHANNEL_5GHZ: u16 = 0x0100;
+pub const CHANNEL_PASSIVE: u16 = 0x0200;
+pub const CHANNEL_DYNAMIC: u16 = 0x0400;
+pub const CHANNEL_GFSK: u16 = 0x0800;
+
+pub const RX_FLAGS_BAD_PLCP: u16 = 0x0002;
+
+pub const TX_FLAGS_FAIL: u16 = 0x0001;
+pub const TX_FLAGS_CTS: u16 = 0x0002;
+pub const TX_FLAGS_RTS: u16 = 0x0004;
+pub const TX_FLAGS_NO_ACK: u16 = 0x0008;
+pub const TX_FLAGS_NO_SEQUENCE: u16 = 0x0010;
+
+/// Radiotap Header
+#[repr(C)]
+pub struct Header {
+    /// Version of the header format, `VERSION`.
+    pub version: u8,
+
+    /// Padding, must be 0.
+    pub pad: u8,
+
+    /// Size of the Radiotap data in bytes, including this header.
+    pub length: int::u16le,
+
+    /// First presence bitmap, with one bit per `PRESENT_*` field.
+    pub present: int::u32le,
+}
+
+/// Field Iterator
+///
+/// Iterate over the fields of a Radiotap header, yielding the `PRESENT_*`
+/// bit and the data of each field of the Radiotap namespace. Fields repeated
+/// in further Radiotap namespaces are yielded again with the same bit.
+/// Vendor namespaces are yielded as a single `PRESENT_VENDOR_NAMESPACE`
+/// field, covering both the 6-byte namespace field and the vendor data.
+/// `PRESENT_TLV` covers all remaining data.
+///
+/// Iteration stops at the first field of unknown size, since following
+/// fields cannot be located, and at the first field that exceeds the data.
+#[derive(Clone, Debug)]
+pub struct Fields<'data> {
+    data: &'data [u8],
+    bitmap: usize,
+    present: u32,
+    index: u32,
+    offset: usize,
+    vendor: bool,
+    next: Option<bool>,
+}
+
+impl Header {
+    /// Import a header from a byte slice
+    ///
+    /// Create a new header from data copied from a byte slice. No byte-order
+    /// conversions are applied.
+    pub fn from_bytes(data: &[u8; 8]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 8], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+// Read a little-endian 32-bit integer at the given offset.
+fn u32_at(data: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(data.get(offset..offset + 4)?.try_into().ok()?))
+}
+
+impl<'data> Fields<'data> {
+    /// Create field iterator
+    ///
+    /// Create a new iterator over the fields of the Radiotap data in `data`,
+    /// which must start with the `Header`, and should be limited to the
+    /// length given in it (see `split()`).
+    pub fn new(data: &'data [u8]) -> Self {
+        let mut offset = 4;
+        let mut present = 0;
+        while let Some(v) = u32_at(data, offset) {
+            offset += 4;
+            if v & (1 << PRESENT_EXT) == 0 {
+                present = u32_at(data, 4).unwrap_or(0);
+                break;
+            }
+        }
+
+        Self {
+            data,
+            bitmap: 4,
+            present,
+            index: 0,
+            offset,
+            vendor: false,
+            next: None,
+        }
+    }
+
+    /// Return remaining data
+    ///
+    /// Return the data that has not been consumed by the iterator.
+    pub fn remainder(&self) -> &'data [u8] {
+        self.data.get(self.offset..).unwrap_or(&[])
+    }
+
+    // Consume the next field with the given alignment and size.
+    fn field(&mut self, align: usize, size: usize) -> Option<&'data [u8]> {
+        let start = self.offset.next_multiple_of(align);
+        let v = self.data.get(start..start + size)?;
+        self.offset = start + size;
+        Some(v)
+    }
+}
+
+impl<'data> Iterator for Fields<'data> {
+    type Item = (u32, &'data [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.present & !(1 << PRESENT_EXT) == 0 {
+                if self.present == 0 {
+                    return None;
+                }
+
+                self.bitmap += 4;
+                self.present = u32_at(self.data, self.bitmap)?;
+                match self.next.take() {
+                    Some(vendor) => {
+                        self.vendor = vendor;
+                        self.index = 0;
+                    },
+                    None => self.index += 32,
+                }
+                continue;
+            }
+
+            let bit = self.present.trailing_zeros();
+            self.present &= !(1 << bit);
+
+            let r = match bit {
+                PRESENT_RADIOTAP_NAMESPACE => {
+                    self.next = Some(false);
+                    continue;
+                },
+                PRESENT_VENDOR_NAMESPACE => {
+                    let start = self.offset.next_multiple_of(2);
+                    let skip = self.data.get(start + 4..start + 6)?;
+                    let size = 6 + u16::from_le_bytes([skip[0], skip[1]]) as usize;
+                    self.next = Some(true);
+                    self.field(2, size).map(|v| (bit, v))
+                },
+                _ if self.vendor => continue,
+                PRESENT_TLV if self.index == 0 => {
+                    self.present = 0;
+                    let start = self.offset.next_multiple_of(4);
+                    self.offset = self.data.len().max(start);
+                    self.data.get(start..).map(|v| (bit, v))
+                },
+                _ => field_layout(self.index + bit)
+                    .and_then(|(align, size)| self.field(align, size))
+                    .map(|v| (bit, v)),
+            };
+
+            if r.is_none() {
+                self.present = 0;
+            }
+            return r;
+        }
+    }
+}
+
+/// Return field layout
+///
+/// Return the alignment and size of the field of the Radiotap namespace with
+/// the given `PRESENT_*` bit, or `None` if unknown or of variable size.
+pub fn field_layout(bit: u32) -> Option<(usize, usize)> {
+    match bit {
+        PRESENT_TSFT => Some((8, 8)),
+        PRESENT_FLAGS | PRESENT_RATE => Some((1, 1)),
+        PRESENT_CHANNEL => Some((2, 4)),
+        PRESENT_FHSS => Some((1, 2)),
+        PRESENT_DBM_ANTENNA_SIGNAL | PRESENT_DBM_ANTENNA_NOISE => Some((1, 1)),
+        PRESENT_LOCK_QUALITY => Some((2, 2)),
+        PRESENT_TX_ATTENUATION | PRESENT_DB_TX_ATTENUATION => Some((2, 2)),
+        PRESENT_DBM_TX_POWER | PRESENT_ANTENNA => Some((1, 1)),
+        PRESENT_DB_ANTENNA_SIGNAL | PRESENT_DB_ANTENNA_NOISE => Some((1, 1)),
+        PRESENT_RX_FLAGS | PRESENT_TX_FLAGS => Some((2, 2)),
+        PRESENT_RTS_RETRIES | PRESENT_DATA_RETRIES => Some((1, 1)),
+        PRESENT_XCHANNEL => Some((4, 8)),
+        PRESENT_MCS => Some((1, 3)),
+        PRESENT_AMPDU_STATUS => Some((4, 8)),
+        PRESENT_VHT => Some((2, 12)),
+        PRESENT_TIMESTAMP => Some((8, 12)),
+        PRESENT_HE => Some((2, 12)),
+        PRESENT_HE_MU => Some((2, 12)),
+        PRESENT_HE_MU_OTHER_USER => Some((2, 6)),
+        PRESENT_ZERO_LENGTH_PSDU => Some((1, 1)),
+        PRESENT_L_SIG => Some((2, 4)),
+        _ => None,
+    }
+}
+
+/// Split captured data
+///
+/// Split data of link type `pcap::LINKTYPE_RADIOTAP` into the Radiotap data,
+/// including its header, and the following frame. Return `None` if the data
+/// is too short for the length given in the header, or the version is
+/// unknown.
+pub fn split(data: &[u8]) -> Option<(&[u8], &[u8])> {
+    if data.len() < HEADER_SIZE || data[0] != VERSION {
+        return None;
+    }
+
+    let length = u16::from_le_bytes([data[2], data[3]]) as usize;
+    if length < HEADER_SIZE || length > data.len() {
+        return None;
+    }
+
+    Some(data.split_at(length))
+}
+
+/// Find field
+///
+/// Return the data of the first field of the Radiotap namespace with the
+/// given `PRESENT_*` bit.
+pub fn find_field(data: &[u8], bit: u32) -> Option<&[u8]> {
+    Fields::new(data).find(|v| v.0 == bit).map(|v| v.1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::specs::int::ForeignEndian;
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<Header>(), HEADER_SIZE);
+        assert_eq!(align_of::<Header>(), 4);
+    }
+
+    // Radiotap header with TSFT, flags, rate, channel, and signal, a second
+    // Radiotap namespace with signal and antenna, and a vendor namespace,
+    // followed by the start of an ACK frame.
+    const CAPTURE: [u8; 44] = [
+        0x00, 0x00, 0x2a, 0x00, 0x2f, 0x00, 0x00, 0xa0,
+        0x20, 0x08, 0x00, 0xc0, 0x01, 0x00, 0x00, 0x00,
+        0xef, 0xcd, 0xab, 0x89, 0x67, 0x45, 0x23, 0x01,
+        0x10, 0x02, 0x85, 0x09, 0xa0, 0x00, 0xd8, 0xd6,
+        0x01, 0x00, 0x00, 0x11, 0x22, 0x00, 0x02, 0x00,
+        0xaa, 0xbb, 0xd4, 0x00,
+    ];
+
+    // Iterate all fields, including their alignment and namespaces.
+    #[test]
+    fn verify_fields() {
+        let (radiotap, frame) = split(&CAPTURE).unwrap();
+        assert_eq!(frame, &[0xd4, 0x00]);
+
+        let h = Header::from_bytes(radiotap[..8].try_into().unwrap());
+        assert_eq!(h.length.to_native(), 42);
+        assert_ne!(h.present.to_native() & (1 << PRESENT_EXT), 0);
+
+        let mut f = Fields::new(radiotap);
+        assert_eq!(
+            f.next(),
+            Some((PRESENT_TSFT, &[0xef, 0xcd, 0xab, 0x89, 0x67, 0x45, 0x23, 0x01][..])),
+        );
+        assert_eq!(f.next(), Some((PRESENT_FLAGS, &[FLAGS_FCS][..])));
+        assert_eq!(f.next(), Some((PRESENT_RATE, &[0x02][..])));
+        assert_eq!(f.next(), Some((PRESENT_CHANNEL, &[0x85, 0x09, 0xa0, 0x00][..])));
+        assert_eq!(f.next(), Some((PRESENT_DBM_ANTENNA_SIGNAL, &[0xd8][..])));
+        assert_eq!(f.next(), Some((PRESENT_DBM_ANTENNA_SIGNAL, &[0xd6][..])));
+        assert_eq!(f.next(), Some((PRESENT_ANTENNA, &[0x01][..])));
+        assert_eq!(
+            f.next(),
+            Some((PRESENT_VENDOR_NAMESPACE, &[0x00, 0x11, 0x22, 0x00, 0x02, 0x00, 0xaa, 0xbb][..])),
+        );
+        assert_eq!(f.next(), None);
+        assert!(f.remainder().is_empty());
+
+        assert_eq!(find_field(radiotap, PRESENT_ANTENNA), Some(&[0x01][..]));
+        assert_eq!(find_field(radiotap, PRESENT_MCS), None);
+    }
+
+    // Verify iteration stops at unknown fields, and invalid headers.
+    #[test]
+    fn verify_invalid() {
+        let mut data = [0u8; 16];
+        data[2] = 16;
+        data[4..8].copy_from_slice(&((1u32 << PRESENT_FLAGS) | (1 << PRESENT_EXT)).to_le_bytes());
+        data[8..12].copy_from_slice(&1u32.to_le_bytes());
+        data[12] = FLAGS_SHORT_GI;
+        let mut f = Fields::new(&data);
+        assert_eq!(f.next(), Some((PRESENT_FLAGS, &[FLAGS_SHORT_GI][..])));
+        assert_eq!(f.next(), None);
+
+        data[4..8].copy_from_slice(&((1u32 << PRESENT_TLV) | (1 << PRESENT_FLAGS)).to_le_bytes());
+        data[8] = FLAGS_SHORT_GI;
+        let mut f = Fields::new(&data);
+        assert_eq!(f.next(), Some((PRESENT_FLAGS, &[FLAGS_SHORT_GI][..])));
+        assert_eq!(f.next(), Some((PRESENT_TLV, &data[12..])));
+        assert_eq!(f.next(), None);
+
+        assert_eq!(split(&data[..15]), None);
+        data[0] = 1;
+        assert_eq!(split(&data), None);
+    }
+}
@@ -0,0 +1,299 @@
+//! Remote Authentication Dial In User Service
+//!
+//! RADIUS packets are exchanged over UDP (see `udp::PORT_RADIUS`). Each
+//! packet starts with a 20-byte `Header` carrying the packet code, an
+//! identifier to match requests and responses, the packet length, and a
+//! 16-byte authenticator. The header is followed by a sequence of attributes
+//! (see `Attributes`), encoded as TLVs with 1-byte type and 1-byte length,
+//! where the length includes the 2-byte attribute header.
+//!
+//! Vendor-specific attributes (`ATTRIBUTE_VENDOR_SPECIFIC`) carry a 4-byte
+//! vendor identifier, followed by vendor data. Most vendors encode their
+//! data as a sequence of sub-attributes in the same TLV format (see
+//! `vendor_specific()`).
+//!
+//! All multi-byte integers are encoded as big-endian.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::int;
+
+pub const HEADER_SIZE: usize = 20;
+pub const AUTHENTICATOR_SIZE: usize = 16;
+pub const ATTRIBUTE_HEADER_SIZE: usize = 2;
+pub const VENDOR_ID_SIZE: usize = 4;
+
+/// Maximum Packet Size
+pub const PACKET_MAX: usize = 4096;
+
+/// Maximum Attribute Data Size
+pub const ATTRIBUTE_VALUE_MAX: usize = 253;
+
+pub const CODE_ACCESS_REQUEST: u8 = 1;
+pub const CODE_ACCESS_ACCEPT: u8 = 2;
+pub const CODE_ACCESS_REJECT: u8 = 3;
+pub const CODE_ACCOUNTING_REQUEST: u8 = 4;
+pub const CODE_ACCOUNTING_RESPONSE: u8 = 5;
+pub const CODE_ACCESS_CHALLENGE: u8 = 11;
+pub const CODE_STATUS_SERVER: u8 = 12;
+pub const CODE_STATUS_CLIENT: u8 = 13;
+pub const CODE_DISCONNECT_REQUEST: u8 = 40;
+pub const CODE_DISCONNECT_ACK: u8 = 41;
+pub const CODE_DISCONNECT_NAK: u8 = 42;
+pub const CODE_COA_REQUEST: u8 = 43;
+pub const CODE_COA_ACK: u8 = 44;
+pub const CODE_COA_NAK: u8 = 45;
+
+pub const ATTRIBUTE_USER_NAME: u8 = 1;
+pub const ATTRIBUTE_USER_PASSWORD: u8 = 2;
+pub const ATTRIBUTE_CHAP_PASSWORD: u8 = 3;
+pub const ATTRIBUTE_NAS_IP_ADDRESS: u8 = 4;
+pub const ATTRIBUTE_NAS_PORT: u8 = 5;
+pub const ATTRIBUTE_SERVICE_TYPE: u8 = 6;
+pub const ATTRIBUTE_FRAMED_PROTOCOL: u8 = 7;
+pub const ATTRIBUTE_FRAMED_IP_ADDRESS: u8 = 8;
+pub const ATTRIBUTE_FRAMED_IP_NETMASK: u8 = 9;
+pub const ATTRIBUTE_FRAMED_ROUTING: u8 = 10;
+pub const ATTRIBUTE_FILTER_ID: u8 = 11;
+pub const ATTRIBUTE_FRAMED_MTU: u8 = 12;
+pub const ATTRIBUTE_FRAMED_COMPRESSION: u8 = 13;
+pub const ATTRIBUTE_LOGIN_IP_HOST: u8 = 14;
+pub const ATTRIBUTE_LOGIN_SERVICE: u8 = 15;
+pub const ATTRIBUTE_LOGIN_TCP_PORT: u8 = 16;
+pub const ATTRIBUTE_REPLY_MESSAGE: u8 = 18;
+pub const ATTRIBUTE_CALLBACK_NUMBER: u8 = 19;
+pub const ATTRIBUTE_CALLBACK_ID: u8 = 20;
+pub const ATTRIBUTE_FRAMED_ROUTE: u8 = 22;
+pub const ATTRIBUTE_FRAMED_IPX_NETWORK: u8 = 23;
+pub const ATTRIBUTE_STATE: u8 = 24;
+pub const ATTRIBUTE_CLASS: u8 = 25;
+pub const ATTRIBUTE_VENDOR_SPECIFIC: u8 = 26;
+pub const ATTRIBUTE_SESSION_TIMEOUT: u8 = 27;
+pub const ATTRIBUTE_IDLE_TIMEOUT: u8 = 28;
+pub const ATTRIBUTE_TERMINATION_ACTION: u8 = 29;
+pub const ATTRIBUTE_CALLED_STATION_ID: u8 = 30;
+pub const ATTRIBUTE_CALLING_STATION_ID: u8 = 31;
+pub const ATTRIBUTE_NAS_IDENTIFIER: u8 = 32;
+pub const ATTRIBUTE_PROXY_STATE: u8 = 33;
+pub const ATTRIBUTE_LOGIN_LAT_SERVICE: u8 = 34;
+pub const ATTRIBUTE_LOGIN_LAT_NODE: u8 = 35;
+pub const ATTRIBUTE_LOGIN_LAT_GROUP: u8 = 36;
+pub const ATTRIBUTE_FRAMED_APPLETALK_LINK: u8 = 37;
+pub const ATTRIBUTE_FRAMED_APPLETALK_NETWORK: u8 = 38;
+pub const ATTRIBUTE_FRAMED_APPLETALK_ZONE: u8 = 39;
+pub const ATTRIBUTE_ACCT_STATUS_TYPE: u8 = 40;
+pub const ATTRIBUTE_ACCT_DELAY_TIME: u8 = 41;
+pub const ATTRIBUTE_ACCT_INPUT_OCTETS: u8 = 42;
+pub const ATTRIBUTE_ACCT_OUTPUT_OCTETS: u8 = 43;
+pub const ATTRIBUTE_ACCT_SESSION_ID: u8 = 44;
+pub const ATTRIBUTE_ACCT_AUTHENTIC: u8 = 45;
+pub const ATTRIBUTE_ACCT_SESSION_TIME: u8 = 46;
+pub const ATTRIBUTE_ACCT_INPUT_PACKETS: u8 = 47;
+pub const ATTRIBUTE_ACCT_OUTPUT_PACKETS: u8 = 48;
+pub const ATTRIBUTE_ACCT_TERMINATE_CAUSE: u8 = 49;
+pub const ATTRIBUTE_ACCT_MULTI_SESSION_ID: u8 = 50;
+pub const ATTRIBUTE_ACCT_LINK_COUNT: u8 = 51;
+pub const ATTRIBUTE_ACCT_INPUT_GIGAWORDS: u8 = 52;
+pub const ATTRIBUTE_ACCT_OUTPUT_GIGAWORDS: u8 = 53;
+pub const ATTRIBUTE_EVENT_TIMESTAMP: u8 = 55;
+pub const ATTRIBUTE_CHAP_CHALLENGE: u8 = 60;
+pub const ATTRIBUTE_NAS_PORT_TYPE: u8 = 61;
+pub const ATTRIBUTE_PORT_LIMIT: u8 = 62;
+pub const ATTRIBUTE_LOGIN_LAT_PORT: u8 = 63;
+pub const ATTRIBUTE_TUNNEL_TYPE: u8 = 64;
+pub const ATTRIBUTE_TUNNEL_MEDIUM_TYPE: u8 = 65;
+pub const ATTRIBUTE_CONNECT_INFO: u8 = 77;
+pub const ATTRIBUTE_EAP_MESSAGE: u8 = 79;
+pub const ATTRIBUTE_MESSAGE_AUTHENTICATOR: u8 = 80;
+pub const ATTRIBUTE_ACCT_INTERIM_INTERVAL: u8 = 85;
+pub const ATTRIBUTE_NAS_PORT_ID: u8 = 87;
+pub const ATTRIBUTE_FRAMED_POOL: u8 = 88;
+pub const ATTRIBUTE_NAS_IPV6_ADDRESS: u8 = 95;
+pub const ATTRIBUTE_FRAMED_INTERFACE_ID: u8 = 96;
+pub const ATTRIBUTE_FRAMED_IPV6_PREFIX: u8 = 97;
+pub const ATTRIBUTE_ERROR_CAUSE: u8 = 101;
+
+pub const VENDOR_CISCO: u32 = 9;
+pub const VENDOR_MICROSOFT: u32 = 311;
+pub const VENDOR_JUNIPER: u32 = 2636;
+
+/// RADIUS Header
+#[repr(C)]
+pub struct Header {
+    /// Packet code (`CODE_*`).
+    pub code: u8,
+
+    /// Identifier to match responses to requests.
+    pub identifier: u8,
+
+    /// Size of the packet in bytes, including the header.
+    pub length: int::u16be,
+
+    /// Request authenticator, or response authenticator calculated from
+    /// the request authenticator and the shared secret.
+    pub authenticator: [u8; 16],
+}
+
+/// Attribute Iterator
+///
+/// An iterator over the attributes of a packet, yielding the attribute type
+/// and data without the attribute header. Iteration stops at the end of the
+/// data, or at the first truncated or malformed attribute.
+#[derive(Clone, Debug)]
+pub struct Attributes<'data> {
+    data: &'data [u8],
+}
+
+impl Header {
+    /// Import a header from a byte slice
+    ///
+    /// Create a new header from data copied from a byte slice. No byte-order
+    /// conversions are applied.
+    pub fn from_bytes(data: &[u8; 20]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 20], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Check for requests
+    ///
+    /// Return whether the packet is a request rather than a response.
+    pub fn is_request(&self) -> bool {
+        matches!(
+            self.code,
+            CODE_ACCESS_REQUEST
+                | CODE_ACCOUNTING_REQUEST
+                | CODE_STATUS_SERVER
+                | CODE_STATUS_CLIENT
+                | CODE_DISCONNECT_REQUEST
+                | CODE_COA_REQUEST
+        )
+    }
+}
+
+impl<'data> Attributes<'data> {
+    /// Create attribute iterator
+    ///
+    /// Create a new iterator over the attributes in `data`, which must start
+    /// after the header and be limited to the packet length. This can also
+    /// iterate the sub-attributes of vendor-specific attributes.
+    pub fn new(data: &'data [u8]) -> Self {
+        Self {
+            data,
+        }
+    }
+
+    /// Return remaining data
+    ///
+    /// Return the data that has not been consumed by the iterator.
+    pub fn remainder(&self) -> &'data [u8] {
+        self.data
+    }
+}
+
+impl<'data> Iterator for Attributes<'data> {
+    type Item = (u8, &'data [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let code = *self.data.first()?;
+        let n = *self.data.get(1)? as usize;
+        if n < ATTRIBUTE_HEADER_SIZE {
+            return None;
+        }
+
+        let v = self.data.get(ATTRIBUTE_HEADER_SIZE..n)?;
+        self.data = &self.data[n..];
+        Some((code, v))
+    }
+}
+
+/// Split vendor-specific attribute
+///
+/// Split the data of a vendor-specific attribute into the vendor identifier
+/// and an iterator over its sub-attributes. Return `None` if the data is
+/// truncated. Vendors not following the recommended format must parse
+/// `Attributes::remainder()` themselves.
+pub fn vendor_specific(data: &[u8]) -> Option<(u32, Attributes<'_>)> {
+    let v = data.get(..VENDOR_ID_SIZE)?;
+    Some((
+        u32::from_be_bytes(v.try_into().unwrap()),
+        Attributes::new(&data[VENDOR_ID_SIZE..]),
+    ))
+}
+
+/// Find attribute
+///
+/// Return the data of the first attribute with the given type in `data`.
+pub fn find_attribute(data: &[u8], code: u8) -> Option<&[u8]> {
+    Attributes::new(data).find(|v| v.0 == code).map(|v| v.1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // An Access-Request with a user name, NAS address, a Microsoft VSA, and
+    // a NAS port type.
+    const PACKET: [u8; 49] = [
+        0x01, 0x2a, 0x00, 0x31, 0x10, 0x11, 0x12, 0x13,
+        0x14, 0x15, 0x16, 0x17, 0x18, 0x19, 0x1a, 0x1b,
+        0x1c, 0x1d, 0x1e, 0x1f, 0x01, 0x05, 0x62, 0x6f,
+        0x62, 0x04, 0x06, 0xc0, 0xa8, 0x01, 0x01, 0x1a,
+        0x0c, 0x00, 0x00, 0x01, 0x37, 0x01, 0x06, 0x00,
+        0x00, 0x00, 0x07, 0x3d, 0x06, 0x00, 0x00, 0x00,
+        0x0f,
+    ];
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<Header>(), HEADER_SIZE);
+        assert_eq!(align_of::<Header>(), 2);
+    }
+
+    // Parse the header and iterate the attributes.
+    #[test]
+    fn verify_packet() {
+        let h = Header::from_bytes(PACKET[..20].try_into().unwrap());
+        assert_eq!(h.code, CODE_ACCESS_REQUEST);
+        assert!(h.is_request());
+        assert_eq!(h.identifier, 0x2a);
+        assert_eq!(int::ForeignEndian::to_native(h.length) as usize, PACKET.len());
+        assert_eq!(h.authenticator[0], 0x10);
+
+        let mut attrs = Attributes::new(&PACKET[HEADER_SIZE..]);
+        assert_eq!(attrs.next(), Some((ATTRIBUTE_USER_NAME, &b"bob"[..])));
+        assert_eq!(attrs.next(), Some((ATTRIBUTE_NAS_IP_ADDRESS, &[192, 168, 1, 1][..])));
+        assert_eq!(attrs.next().unwrap().0, ATTRIBUTE_VENDOR_SPECIFIC);
+        assert_eq!(attrs.next(), Some((ATTRIBUTE_NAS_PORT_TYPE, &[0, 0, 0, 15][..])));
+        assert!(attrs.next().is_none());
+        assert!(attrs.remainder().is_empty());
+
+        // A length below the attribute header stops iteration.
+        assert!(Attributes::new(&[0x01, 0x01, 0x62]).next().is_none());
+        assert!(Attributes::new(&PACKET[HEADER_SIZE..24]).next().is_none());
+    }
+
+    // Find and split a vendor-specific attribute.
+    #[test]
+    fn verify_vendor_specific() {
+        let v = find_attribute(&PACKET[HEADER_SIZE..], ATTRIBUTE_VENDOR_SPECIFIC).unwrap();
+        let (vendor, mut sub) = vendor_specific(v).unwrap();
+        assert_eq!(vendor, VENDOR_MICROSOFT);
+        assert_eq!(sub.next(), Some((1, &[0, 0, 0, 7][..])));
+        assert!(sub.next().is_none());
+
+        assert!(vendor_specific(&v[..3]).is_none());
+        assert!(find_attribute(&PACKET[HEADER_SIZE..], ATTRIBUTE_EAP_MESSAGE).is_none());
+    }
+}
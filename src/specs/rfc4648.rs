@@ -0,0 +1,259 @@
+//! Base16, Base32, and Base64 Data Encodings
+//!
+//! RFC 4648 encodes binary data as text, using alphabets of 16, 32, or 64
+//! symbols, where each symbol encodes 4, 5, or 6 bits, respectively. The
+//! input is processed in groups of 1, 5, or 3 bytes, each encoded as 2, 8,
+//! or 4 symbols. A final partial group is padded with `PAD` symbols to a
+//! full group, unless padding is omitted by the referring specification
+//! (see `Encoding::unpadded()`).
+//!
+//! The supported encodings are `BASE64`, `BASE64_URL` (the URL and filename
+//! safe alphabet), `BASE32`, `BASE32_HEX` (the extended hex alphabet, which
+//! preserves sort order), and `BASE16`. Decoding of Base32 and Base16 is
+//! case-insensitive, as commonly done. Decoding rejects non-alphabet
+//! characters, including line breaks, and non-zero trailing bits, so each
+//! binary input has exactly one accepted encoding.
+//!
+//! Encoders and decoders write to the start of an output buffer and return
+//! the number of bytes written, or `None` if the buffer is too small or the
+//! input is invalid.
+
+/// Padding Symbol
+pub const PAD: u8 = b'=';
+
+pub const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+pub const BASE64_URL_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+pub const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+pub const BASE32_HEX_ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHIJKLMNOPQRSTUV";
+pub const BASE16_ALPHABET: &[u8; 16] = b"0123456789ABCDEF";
+
+pub const BASE64: Encoding = Encoding::new(BASE64_ALPHABET, false);
+pub const BASE64_URL: Encoding = Encoding::new(BASE64_URL_ALPHABET, false);
+pub const BASE32: Encoding = Encoding::new(BASE32_ALPHABET, true);
+pub const BASE32_HEX: Encoding = Encoding::new(BASE32_HEX_ALPHABET, true);
+pub const BASE16: Encoding = Encoding::new(BASE16_ALPHABET, true);
+
+// Marker of invalid symbols in decode tables.
+const INVALID: u8 = 0xff;
+
+/// Data Encoding
+///
+/// An alphabet with its decode table, and whether padding is used. The
+/// number of bits per symbol is derived from the size of the alphabet.
+#[derive(Clone, Copy, Debug)]
+pub struct Encoding {
+    alphabet: &'static [u8],
+    decode: [u8; 256],
+    bits: u32,
+    padding: bool,
+}
+
+impl Encoding {
+    /// Create encoding
+    ///
+    /// Create an encoding with padding from an alphabet of 16, 32, or 64
+    /// symbols, and build its decode table. If `fold` is set, lowercase
+    /// letters are decoded like their uppercase counterparts.
+    pub const fn new(alphabet: &'static [u8], fold: bool) -> Self {
+        let bits = alphabet.len().trailing_zeros();
+        assert!(alphabet.len() == 1 << bits && bits >= 4 && bits <= 6);
+
+        let mut decode = [INVALID; 256];
+        let mut i = 0;
+        while i < alphabet.len() {
+            decode[alphabet[i] as usize] = i as u8;
+            if fold {
+                decode[alphabet[i].to_ascii_lowercase() as usize] = i as u8;
+            }
+            i += 1;
+        }
+
+        Self { alphabet, decode, bits, padding: true }
+    }
+
+    /// Omit padding
+    ///
+    /// Return a copy of the encoding which neither produces nor accepts
+    /// padding.
+    pub const fn unpadded(mut self) -> Self {
+        self.padding = false;
+        self
+    }
+
+    // Number of input bytes and symbols of full groups.
+    fn group(&self) -> (usize, usize) {
+        match self.bits {
+            4 => (1, 2),
+            5 => (5, 8),
+            _ => (3, 4),
+        }
+    }
+
+    /// Return encoded size
+    ///
+    /// Return the number of symbols, including padding, needed to encode
+    /// `n` bytes.
+    pub fn encoded_size(&self, n: usize) -> usize {
+        let (bytes, symbols) = self.group();
+        match self.padding {
+            true => n.div_ceil(bytes) * symbols,
+            false => (n * 8).div_ceil(self.bits as usize),
+        }
+    }
+
+    /// Return maximum decoded size
+    ///
+    /// Return the maximum number of bytes encoded by `n` symbols, including
+    /// padding.
+    pub fn decoded_size(&self, n: usize) -> usize {
+        n * self.bits as usize / 8
+    }
+
+    /// Encode data
+    ///
+    /// Encode `data`, including padding if used by the encoding.
+    pub fn encode(&self, data: &[u8], out: &mut [u8]) -> Option<usize> {
+        let n = self.encoded_size(data.len());
+        let out = out.get_mut(..n)?;
+        let mask = (1u32 << self.bits) - 1;
+        let mut acc: u32 = 0;
+        let mut pending = 0;
+        let mut o = 0;
+
+        for b in data {
+            acc = (acc << 8) | *b as u32;
+            pending += 8;
+            while pending >= self.bits {
+                pending -= self.bits;
+                out[o] = self.alphabet[((acc >> pending) & mask) as usize];
+                o += 1;
+            }
+        }
+
+        if pending > 0 {
+            out[o] = self.alphabet[((acc << (self.bits - pending)) & mask) as usize];
+            o += 1;
+        }
+
+        out[o..].fill(PAD);
+        Some(n)
+    }
+
+    /// Decode data
+    ///
+    /// Decode the symbols in `data`. If the encoding uses padding, the input
+    /// must be padded to full groups. Return `None` if the input contains
+    /// invalid symbols or padding, or has invalid size or trailing bits.
+    pub fn decode(&self, data: &[u8], out: &mut [u8]) -> Option<usize> {
+        let (_, symbols) = self.group();
+
+        let data = match self.padding {
+            true => {
+                if !data.len().is_multiple_of(symbols) {
+                    return None;
+                }
+                let n = data.iter().rev().take(symbols - 1).take_while(|v| **v == PAD).count();
+                &data[..data.len() - n]
+            },
+            false => data,
+        };
+
+        // Partial groups must encode at least one full byte, without a
+        // superfluous symbol.
+        let rem = data.len() % symbols;
+        let bits = rem * self.bits as usize;
+        if rem > 0 && bits / 8 == (bits - self.bits as usize) / 8 {
+            return None;
+        }
+
+        let n = self.decoded_size(data.len());
+        let out = out.get_mut(..n)?;
+        let mut acc: u32 = 0;
+        let mut pending = 0;
+        let mut o = 0;
+
+        for c in data {
+            let v = self.decode[*c as usize];
+            if v == INVALID {
+                return None;
+            }
+            acc = (acc << self.bits) | v as u32;
+            pending += self.bits;
+            if pending >= 8 {
+                pending -= 8;
+                out[o] = (acc >> pending) as u8;
+                o += 1;
+            }
+        }
+
+        if acc & ((1 << pending) - 1) != 0 {
+            return None;
+        }
+
+        Some(o)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test vectors of RFC 4648 for the prefixes of "foobar".
+    const VECTORS: [[&[u8]; 5]; 7] = [
+        [b"", b"", b"", b"", b""],
+        [b"f", b"Zg==", b"MY======", b"CO======", b"66"],
+        [b"fo", b"Zm8=", b"MZXQ====", b"CPNG====", b"666F"],
+        [b"foo", b"Zm9v", b"MZXW6===", b"CPNMU===", b"666F6F"],
+        [b"foob", b"Zm9vYg==", b"MZXW6YQ=", b"CPNMUOG=", b"666F6F62"],
+        [b"fooba", b"Zm9vYmE=", b"MZXW6YTB", b"CPNMUOJ1", b"666F6F6261"],
+        [b"foobar", b"Zm9vYmFy", b"MZXW6YTBOI======", b"CPNMUOJ1E8======", b"666F6F626172"],
+    ];
+
+    // Verify the test vectors of the specification.
+    #[test]
+    fn verify_vectors() {
+        let mut buf = [0; 16];
+
+        for [data, b64, b32, b32hex, b16] in VECTORS {
+            for (e, v) in [(BASE64, b64), (BASE32, b32), (BASE32_HEX, b32hex), (BASE16, b16)] {
+                let n = e.encode(data, &mut buf).unwrap();
+                assert_eq!(&buf[..n], v);
+                assert_eq!(e.encoded_size(data.len()), v.len());
+                let n = e.decode(v, &mut buf).unwrap();
+                assert_eq!(&buf[..n], data);
+            }
+        }
+    }
+
+    // Verify unpadded encodings and rejection of invalid input.
+    #[test]
+    fn verify_errors() {
+        let mut buf = [0; 16];
+        let e = BASE64_URL.unpadded();
+        assert_eq!(e.encode(&[0xfb, 0xff], &mut buf), Some(3));
+        assert_eq!(&buf[..3], b"-_8");
+        assert_eq!(e.decode(b"-_8", &mut buf), Some(2));
+        assert_eq!(buf[..2], [0xfb, 0xff]);
+        assert_eq!(e.decode(b"-_8=", &mut buf), None);
+        assert_eq!(BASE64.decode(b"-_8=", &mut buf), None);
+        assert_eq!(BASE32.unpadded().decode(b"MZXW6", &mut buf), Some(3));
+
+        assert_eq!(BASE16.decode(b"666f6F", &mut buf), Some(3));
+        assert_eq!(BASE32.decode(b"mzxw6===", &mut buf), Some(3));
+        assert_eq!(BASE64.decode(b"zm9v", &mut buf), Some(3));
+        assert_eq!(&buf[..3], [0xce, 0x6f, 0x6f]);
+
+        assert_eq!(BASE64.decode(b"Zm9", &mut buf), None);
+        assert_eq!(BASE64.decode(b"Zh==", &mut buf), None);
+        assert_eq!(BASE64.decode(b"Z===", &mut buf), None);
+        assert_eq!(BASE64.decode(b"Zg=a", &mut buf), None);
+        assert_eq!(BASE64.decode(b"Zg==Zg==", &mut buf), None);
+        assert_eq!(BASE64.decode(b"Zm9v\n", &mut buf), None);
+        assert_eq!(BASE32.decode(b"MZX=====", &mut buf), None);
+        assert_eq!(BASE16.decode(b"666", &mut buf), None);
+        assert_eq!(BASE64.encode(b"foo", &mut buf[..3]), None);
+        assert_eq!(BASE64.decode(b"Zm9vYmFy", &mut buf[..5]), None);
+    }
+}
@@ -0,0 +1,280 @@
+//! Resource Interchange File Format
+//!
+//! The Resource Interchange File Format (RIFF) is a generic container format
+//! made of tagged chunks. It is the base of WAV, AVI, WebP, and many other
+//! formats.
+//!
+//! Every chunk starts with a 4-byte identifier (FourCC) and the size of its
+//! payload, excluding the header and excluding the padding. Chunks are padded
+//! to an even size with a single byte, if required.
+//!
+//! A file is a single `RIFF` chunk. Its payload starts with a FourCC
+//! identifying the form type, e.g., `WAVE` or `WEBP`, followed by a sequence
+//! of chunks. `LIST` chunks have the same layout and nest further chunks
+//! under a list type. The `INFO` list type carries metadata as a sequence of
+//! sub-chunks with zero-terminated strings, identified by the `INFO_*`
+//! constants.
+//!
+//! All multi-byte integers are encoded as little-endian.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::int;
+
+pub const ID_RIFF: [u8; 4] = *b"RIFF";
+pub const ID_LIST: [u8; 4] = *b"LIST";
+
+pub const LIST_INFO: [u8; 4] = *b"INFO";
+
+pub const INFO_ARCHIVAL_LOCATION: [u8; 4] = *b"IARL";
+pub const INFO_ARTIST: [u8; 4] = *b"IART";
+pub const INFO_COMMISSIONED: [u8; 4] = *b"ICMS";
+pub const INFO_COMMENT: [u8; 4] = *b"ICMT";
+pub const INFO_COPYRIGHT: [u8; 4] = *b"ICOP";
+pub const INFO_CREATION_DATE: [u8; 4] = *b"ICRD";
+pub const INFO_ENGINEER: [u8; 4] = *b"IENG";
+pub const INFO_GENRE: [u8; 4] = *b"IGNR";
+pub const INFO_KEYWORDS: [u8; 4] = *b"IKEY";
+pub const INFO_MEDIUM: [u8; 4] = *b"IMED";
+pub const INFO_NAME: [u8; 4] = *b"INAM";
+pub const INFO_PRODUCT: [u8; 4] = *b"IPRD";
+pub const INFO_SUBJECT: [u8; 4] = *b"ISBJ";
+pub const INFO_SOFTWARE: [u8; 4] = *b"ISFT";
+pub const INFO_SOURCE: [u8; 4] = *b"ISRC";
+pub const INFO_TECHNICIAN: [u8; 4] = *b"ITCH";
+pub const INFO_TRACK: [u8; 4] = *b"ITRK";
+
+/// Chunk Header
+#[repr(C)]
+pub struct ChunkHeader {
+    /// Chunk identifier.
+    pub id: [u8; 4],
+
+    /// Size of the payload in bytes, excluding the padding.
+    pub size: int::u32le,
+}
+
+/// File Header
+///
+/// The header of the top-level `RIFF` chunk, including the form type. The
+/// same layout is used for `LIST` chunks.
+#[repr(C)]
+pub struct Header {
+    /// Chunk identifier, `ID_RIFF` or `ID_LIST`.
+    pub id: [u8; 4],
+
+    /// Size of the payload in bytes, including the form type.
+    pub size: int::u32le,
+
+    /// Form or list type.
+    pub form: [u8; 4],
+}
+
+/// Chunk Reference
+///
+/// A reference to a chunk in a byte slice, as yielded by `Chunks`.
+#[derive(Clone, Copy, Debug)]
+pub struct Chunk<'data> {
+    /// Chunk identifier.
+    pub id: [u8; 4],
+
+    /// Chunk payload, excluding the padding.
+    pub data: &'data [u8],
+}
+
+/// Chunk Iterator
+///
+/// An iterator over a sequence of chunks, as found in the payload of `RIFF`
+/// and `LIST` chunks after the form type. Iteration stops at the end of the
+/// data, or at the first truncated chunk. A missing pad byte of the final
+/// chunk is tolerated.
+#[derive(Clone, Debug)]
+pub struct Chunks<'data> {
+    data: &'data [u8],
+}
+
+impl ChunkHeader {
+    /// Import a chunk header from a byte slice
+    ///
+    /// Create a new chunk header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 8]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 8], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl Header {
+    /// Import a file header from a byte slice
+    ///
+    /// Create a new file header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 12]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 12], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl<'data> Chunk<'data> {
+    /// Iterate sub-chunks
+    ///
+    /// Return the list type and an iterator over the sub-chunks of a `LIST`
+    /// chunk, or `None` if this is not a `LIST` chunk or it is truncated.
+    pub fn list(&self) -> Option<([u8; 4], Chunks<'data>)> {
+        if self.id != ID_LIST || self.data.len() < 4 {
+            return None;
+        }
+
+        Some((self.data[..4].try_into().unwrap(), Chunks::new(&self.data[4..])))
+    }
+}
+
+impl<'data> Chunks<'data> {
+    /// Create chunk iterator
+    ///
+    /// Create a new iterator over the chunks in `data`.
+    pub fn new(data: &'data [u8]) -> Self {
+        Self {
+            data,
+        }
+    }
+}
+
+impl<'data> Iterator for Chunks<'data> {
+    type Item = Chunk<'data>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.data.len() < 8 {
+            return None;
+        }
+
+        let size = u32::from_le_bytes(self.data[4..8].try_into().unwrap()) as usize;
+        if self.data.len() - 8 < size {
+            return None;
+        }
+
+        let chunk = Chunk {
+            id: self.data[..4].try_into().unwrap(),
+            data: &self.data[8..8 + size],
+        };
+
+        let end = core::cmp::min(8 + padded_size(size), self.data.len());
+        self.data = &self.data[end..];
+        Some(chunk)
+    }
+}
+
+/// Calculate Padded Size
+///
+/// Return the size of a chunk payload including the padding.
+pub fn padded_size(size: usize) -> usize {
+    size + (size & 1)
+}
+
+/// Decode INFO String
+///
+/// Return the string of an `INFO` sub-chunk, with the zero-terminator and
+/// anything following it stripped. Writers frequently omit the terminator,
+/// so its absence is tolerated.
+pub fn info_string(data: &[u8]) -> &[u8] {
+    match data.iter().position(|v| *v == 0) {
+        Some(i) => &data[..i],
+        None => data,
+    }
+}
+
+/// Parse File Header
+///
+/// Parse the file header at the start of `data`, verify it is a `RIFF`
+/// chunk, and return the form type and an iterator over its chunks. The
+/// chunk data is bounded by the size in the header, if it is smaller than
+/// the available data.
+pub fn parse(data: &[u8]) -> Option<([u8; 4], Chunks<'_>)> {
+    let h = Header::from_bytes(data.get(..12)?.try_into().unwrap());
+    if h.id != ID_RIFF {
+        return None;
+    }
+
+    let size = int::ForeignEndian::to_native(h.size) as usize;
+    let end = core::cmp::min(8_usize.checked_add(size)?, data.len());
+    if end < 12 {
+        return None;
+    }
+
+    Some((h.form, Chunks::new(&data[12..end])))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A file with an odd-sized chunk, followed by a list with one chunk.
+    const FILE: [u8; 44] = [
+        0x52, 0x49, 0x46, 0x46, 0x24, 0x00, 0x00, 0x00,
+        0x54, 0x45, 0x53, 0x54, 0x61, 0x62, 0x63, 0x20,
+        0x03, 0x00, 0x00, 0x00, 0x01, 0x02, 0x03, 0x00,
+        0x4c, 0x49, 0x53, 0x54, 0x0c, 0x00, 0x00, 0x00,
+        0x6c, 0x69, 0x73, 0x74, 0x78, 0x79, 0x7a, 0x20,
+        0x00, 0x00, 0x00, 0x00,
+    ];
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<ChunkHeader>(), 8);
+        assert_eq!(align_of::<ChunkHeader>(), 4);
+        assert_eq!(size_of::<Header>(), 12);
+    }
+
+    // Walk a file including a nested list.
+    #[test]
+    fn verify_chunks() {
+        let (form, mut chunks) = parse(&FILE).unwrap();
+        assert_eq!(&form, b"TEST");
+
+        let c = chunks.next().unwrap();
+        assert_eq!(&c.id, b"abc ");
+        assert_eq!(c.data, &[1, 2, 3]);
+        assert!(c.list().is_none());
+
+        let c = chunks.next().unwrap();
+        let (list, mut sub) = c.list().unwrap();
+        assert_eq!(&list, b"list");
+
+        let s = sub.next().unwrap();
+        assert_eq!(&s.id, b"xyz ");
+        assert!(s.data.is_empty());
+        assert!(sub.next().is_none());
+        assert!(chunks.next().is_none());
+
+        assert!(parse(b"RIFX\x04\x00\x00\x00TEST").is_none());
+        assert!(Chunks::new(&FILE[12..22]).next().is_none());
+        assert_eq!(padded_size(3), 4);
+    }
+
+    // Verify decoding of INFO strings.
+    #[test]
+    fn verify_info() {
+        assert_eq!(info_string(b"test\0"), b"test");
+        assert_eq!(info_string(b"test"), b"test");
+        assert_eq!(info_string(b"a\0b"), b"a");
+    }
+}
@@ -0,0 +1,517 @@
+//! RTP Control Protocol
+//!
+//! RTCP packets accompany RTP streams and carry reception statistics,
+//! source descriptions, and control messages. Multiple packets are stacked
+//! into a compound packet (see `Packets`), which usually starts with a
+//! sender or receiver report. Each packet starts with a 4-byte `Header`
+//! carrying the version, a padding flag, a 5-bit count whose meaning depends
+//! on the packet type (`PT_*`), and the length in 4-byte units minus one.
+//!
+//! Sender reports carry the SSRC of the sender and a 20-byte `SenderInfo`,
+//! followed by report blocks (see `ReportBlocks`). Receiver reports carry
+//! the SSRC of the sender followed by report blocks. Source descriptions
+//! carry chunks of an SSRC and items (see `SdesChunks`). Goodbye packets
+//! carry SSRCs and an optional reason (see `bye_reason()`).
+//!
+//! Timestamps are in NTP format (see `ntp`).
+//!
+//! All multi-byte integers are encoded as big-endian.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::int;
+
+pub const HEADER_SIZE: usize = 4;
+pub const SENDER_INFO_SIZE: usize = 20;
+pub const REPORT_BLOCK_SIZE: usize = 24;
+pub const VERSION: u8 = 2;
+
+pub const VERSION_MASK: u8 = 0xc0;
+pub const VERSION_SHIFT: u32 = 6;
+pub const FLAG_PADDING: u8 = 0x20;
+pub const COUNT_MASK: u8 = 0x1f;
+
+pub const PT_SR: u8 = 200;
+pub const PT_RR: u8 = 201;
+pub const PT_SDES: u8 = 202;
+pub const PT_BYE: u8 = 203;
+pub const PT_APP: u8 = 204;
+pub const PT_RTPFB: u8 = 205;
+pub const PT_PSFB: u8 = 206;
+pub const PT_XR: u8 = 207;
+
+pub const SDES_END: u8 = 0;
+pub const SDES_CNAME: u8 = 1;
+pub const SDES_NAME: u8 = 2;
+pub const SDES_EMAIL: u8 = 3;
+pub const SDES_PHONE: u8 = 4;
+pub const SDES_LOC: u8 = 5;
+pub const SDES_TOOL: u8 = 6;
+pub const SDES_NOTE: u8 = 7;
+pub const SDES_PRIV: u8 = 8;
+
+/// RTCP Header
+#[repr(C)]
+pub struct Header {
+    /// Version (2 bits), padding flag, and count (5 bits).
+    pub flags: u8,
+
+    /// Packet type (`PT_*`).
+    pub packet_type: u8,
+
+    /// Size of the packet in 4-byte units minus one, including the header.
+    pub length: int::u16be,
+}
+
+/// Sender Information
+///
+/// The sender information of a sender report, following the SSRC of the
+/// sender.
+#[repr(C)]
+pub struct SenderInfo {
+    /// Integer part of the NTP timestamp of the report.
+    pub ntp_seconds: int::u32be,
+
+    /// Fractional part of the NTP timestamp of the report.
+    pub ntp_fraction: int::u32be,
+
+    /// RTP timestamp corresponding to the NTP timestamp.
+    pub rtp_timestamp: int::u32be,
+
+    /// Number of RTP packets sent.
+    pub packet_count: int::u32be,
+
+    /// Number of payload bytes sent.
+    pub octet_count: int::u32be,
+}
+
+/// Report Block
+#[repr(C)]
+pub struct ReportBlock {
+    /// Source this block reports on.
+    pub ssrc: int::u32be,
+
+    /// Fraction of packets lost since the last report, in units of 1/256.
+    pub fraction_lost: u8,
+
+    /// Cumulative number of packets lost, signed (see `cumulative_lost()`).
+    pub cumulative_lost: int::u24be,
+
+    /// Extended highest sequence number received.
+    pub highest_sequence: int::u32be,
+
+    /// Interarrival jitter, in RTP timestamp units.
+    pub jitter: int::u32be,
+
+    /// Middle 32 bits of the NTP timestamp of the last sender report (LSR).
+    pub last_sr: int::u32be,
+
+    /// Delay since the last sender report, in units of 1/65536 seconds
+    /// (DLSR).
+    pub delay_since_last_sr: int::u32be,
+}
+
+/// Packet Reference
+#[derive(Clone, Copy, Debug)]
+pub struct Packet<'data> {
+    /// Count field of the header.
+    pub count: u8,
+
+    /// Packet type.
+    pub packet_type: u8,
+
+    /// Packet body following the header, excluding padding.
+    pub body: &'data [u8],
+}
+
+/// Compound Packet Iterator
+///
+/// An iterator over the packets of a compound packet. Iteration stops at
+/// the end of the data, or at the first truncated packet.
+#[derive(Clone, Debug)]
+pub struct Packets<'data> {
+    data: &'data [u8],
+}
+
+/// Report Block Iterator
+#[derive(Clone, Debug)]
+pub struct ReportBlocks<'data> {
+    data: &'data [u8],
+    count: usize,
+}
+
+/// SDES Chunk Iterator
+///
+/// An iterator over the chunks of a source description, yielding the SSRC
+/// and the items (see `SdesItems`) of each chunk.
+#[derive(Clone, Debug)]
+pub struct SdesChunks<'data> {
+    data: &'data [u8],
+    count: usize,
+}
+
+/// SDES Item Iterator
+///
+/// An iterator over the items of a chunk, yielding the item type and value.
+/// Iteration stops at `SDES_END`, or at the first truncated item.
+#[derive(Clone, Debug)]
+pub struct SdesItems<'data> {
+    data: &'data [u8],
+}
+
+impl Header {
+    /// Import a header from a byte slice
+    ///
+    /// Create a new header from data copied from a byte slice. No byte-order
+    /// conversions are applied.
+    pub fn from_bytes(data: &[u8; 4]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 4], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Return version
+    pub fn version(&self) -> u8 {
+        (self.flags & VERSION_MASK) >> VERSION_SHIFT
+    }
+
+    /// Return count
+    pub fn count(&self) -> u8 {
+        self.flags & COUNT_MASK
+    }
+
+    /// Return packet size
+    ///
+    /// Return the size of the packet in bytes, including the header.
+    pub fn packet_size(&self) -> usize {
+        (int::ForeignEndian::to_native(self.length) as usize + 1) * 4
+    }
+}
+
+impl SenderInfo {
+    /// Import sender information from a byte slice
+    ///
+    /// Create new sender information from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 20]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 20], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Return NTP timestamp
+    pub fn ntp_timestamp(&self) -> int::u32f32be {
+        let s = int::ForeignEndian::to_native(self.ntp_seconds) as u64;
+        let f = int::ForeignEndian::to_native(self.ntp_fraction) as u64;
+        int::u32f32be::from_bits((s << 32) | f)
+    }
+
+    /// Return compact NTP timestamp
+    ///
+    /// Return the middle 32 bits of the NTP timestamp, as echoed in the
+    /// `last_sr` field of report blocks.
+    pub fn compact_ntp_timestamp(&self) -> u32 {
+        (self.ntp_timestamp().to_bits() >> 16) as u32
+    }
+}
+
+impl ReportBlock {
+    /// Import a report block from a byte slice
+    ///
+    /// Create a new report block from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 24]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 24], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Return cumulative number of packets lost
+    ///
+    /// Return the sign-extended 24-bit count, which is negative if
+    /// duplicates were received.
+    pub fn cumulative_lost(&self) -> i32 {
+        ((self.cumulative_lost.to_native() << 8) as i32) >> 8
+    }
+}
+
+impl<'data> Packets<'data> {
+    /// Create compound packet iterator
+    pub fn new(data: &'data [u8]) -> Self {
+        Self {
+            data,
+        }
+    }
+
+    /// Return remaining data
+    ///
+    /// Return the data that has not been consumed by the iterator.
+    pub fn remainder(&self) -> &'data [u8] {
+        self.data
+    }
+}
+
+impl<'data> Iterator for Packets<'data> {
+    type Item = Packet<'data>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let h = Header::from_bytes(self.data.get(..HEADER_SIZE)?.try_into().unwrap());
+        let n = h.packet_size();
+        let packet = self.data.get(..n)?;
+
+        let end = if h.flags & FLAG_PADDING != 0 {
+            n.checked_sub(packet[n - 1] as usize).filter(|v| *v >= HEADER_SIZE)?
+        } else {
+            n
+        };
+
+        self.data = &self.data[n..];
+        Some(Packet {
+            count: h.count(),
+            packet_type: h.packet_type,
+            body: &packet[HEADER_SIZE..end],
+        })
+    }
+}
+
+impl<'data> ReportBlocks<'data> {
+    /// Create report block iterator
+    ///
+    /// Create a new iterator over `count` report blocks in `data`, which
+    /// must start after the sender information of a sender report, or after
+    /// the SSRC of a receiver report.
+    pub fn new(data: &'data [u8], count: usize) -> Self {
+        Self {
+            data,
+            count,
+        }
+    }
+
+    /// Return remaining data
+    ///
+    /// Return the data that has not been consumed by the iterator, which
+    /// holds profile-specific extensions once all blocks were yielded.
+    pub fn remainder(&self) -> &'data [u8] {
+        self.data
+    }
+}
+
+impl<'data> Iterator for ReportBlocks<'data> {
+    type Item = ReportBlock;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.count == 0 {
+            return None;
+        }
+
+        let v = ReportBlock::from_bytes(self.data.get(..REPORT_BLOCK_SIZE)?.try_into().unwrap());
+        self.data = &self.data[REPORT_BLOCK_SIZE..];
+        self.count -= 1;
+        Some(v)
+    }
+}
+
+impl<'data> SdesChunks<'data> {
+    /// Create SDES chunk iterator
+    ///
+    /// Create a new iterator over `count` chunks in the body of a source
+    /// description.
+    pub fn new(data: &'data [u8], count: usize) -> Self {
+        Self {
+            data,
+            count,
+        }
+    }
+
+    /// Return remaining data
+    ///
+    /// Return the data that has not been consumed by the iterator.
+    pub fn remainder(&self) -> &'data [u8] {
+        self.data
+    }
+}
+
+impl<'data> Iterator for SdesChunks<'data> {
+    type Item = (u32, SdesItems<'data>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.count == 0 || self.data.len() < 4 {
+            return None;
+        }
+
+        let ssrc = u32::from_be_bytes(self.data[..4].try_into().unwrap());
+        let items = &self.data[4..];
+
+        // Skip items up to the terminating null, then pad to 4 bytes.
+        let mut o = 0;
+        loop {
+            match *items.get(o)? {
+                SDES_END => break,
+                _ => o += 2 + *items.get(o + 1)? as usize,
+            }
+        }
+        let n = ((4 + o + 1 + 3) & !3).min(self.data.len());
+
+        self.data = &self.data[n..];
+        self.count -= 1;
+        Some((ssrc, SdesItems { data: &items[..o] }))
+    }
+}
+
+impl<'data> SdesItems<'data> {
+    /// Create SDES item iterator
+    pub fn new(data: &'data [u8]) -> Self {
+        Self {
+            data,
+        }
+    }
+
+    /// Return remaining data
+    ///
+    /// Return the data that has not been consumed by the iterator.
+    pub fn remainder(&self) -> &'data [u8] {
+        self.data
+    }
+}
+
+impl<'data> Iterator for SdesItems<'data> {
+    type Item = (u8, &'data [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item_type = *self.data.first()?;
+        if item_type == SDES_END {
+            return None;
+        }
+
+        let n = *self.data.get(1)? as usize;
+        let v = self.data.get(2..2 + n)?;
+        self.data = &self.data[2 + n..];
+        Some((item_type, v))
+    }
+}
+
+/// Return goodbye reason
+///
+/// Return the reason of a goodbye packet with `count` SSRCs, or `None` if
+/// no reason is given or it is truncated.
+pub fn bye_reason(body: &[u8], count: usize) -> Option<&[u8]> {
+    let o = count * 4;
+    let n = *body.get(o)? as usize;
+    body.get(o + 1..o + 1 + n)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A compound packet of a sender report with one report block, and a
+    // source description with a CNAME.
+    const COMPOUND: [u8; 68] = [
+        0x81, 0xc8, 0x00, 0x0c, 0x00, 0x00, 0x00, 0x01,
+        0xe8, 0x00, 0x00, 0x00, 0x80, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x03, 0xe8, 0x00, 0x00, 0x00, 0x0a,
+        0x00, 0x00, 0x06, 0x40, 0x00, 0x00, 0x00, 0x02,
+        0x40, 0xff, 0xff, 0xfe, 0x00, 0x01, 0x00, 0x20,
+        0x00, 0x00, 0x00, 0x10, 0x12, 0x34, 0x56, 0x78,
+        0x00, 0x01, 0x00, 0x00, 0x81, 0xca, 0x00, 0x03,
+        0x00, 0x00, 0x00, 0x01, 0x01, 0x03, 0x61, 0x40,
+        0x62, 0x00, 0x00, 0x00,
+    ];
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<Header>(), HEADER_SIZE);
+        assert_eq!(align_of::<Header>(), 2);
+        assert_eq!(size_of::<SenderInfo>(), SENDER_INFO_SIZE);
+        assert_eq!(align_of::<SenderInfo>(), 4);
+        assert_eq!(size_of::<ReportBlock>(), REPORT_BLOCK_SIZE);
+        assert_eq!(align_of::<ReportBlock>(), 4);
+    }
+
+    // Parse a sender report and its report block.
+    #[test]
+    fn verify_sender_report() {
+        let h = Header::from_bytes(COMPOUND[..4].try_into().unwrap());
+        assert_eq!(h.version(), VERSION);
+        assert_eq!(h.count(), 1);
+        assert_eq!(h.packet_size(), 52);
+
+        let mut packets = Packets::new(&COMPOUND);
+        let p = packets.next().unwrap();
+        assert_eq!(p.packet_type, PT_SR);
+        assert_eq!(u32::from_be_bytes(p.body[..4].try_into().unwrap()), 1);
+
+        let s = SenderInfo::from_bytes(p.body[4..24].try_into().unwrap());
+        assert_eq!(s.ntp_timestamp().to_bits(), 0xe800_0000_8000_0000);
+        assert_eq!(s.compact_ntp_timestamp(), 0x0000_8000);
+        assert_eq!(int::ForeignEndian::to_native(s.rtp_timestamp), 1000);
+        assert_eq!(int::ForeignEndian::to_native(s.packet_count), 10);
+        assert_eq!(int::ForeignEndian::to_native(s.octet_count), 1600);
+
+        let mut blocks = ReportBlocks::new(&p.body[24..], p.count as usize);
+        let b = blocks.next().unwrap();
+        assert_eq!(int::ForeignEndian::to_native(b.ssrc), 2);
+        assert_eq!(b.fraction_lost, 0x40);
+        assert_eq!(b.cumulative_lost(), -2);
+        assert_eq!(int::ForeignEndian::to_native(b.highest_sequence), 0x10020);
+        assert_eq!(int::ForeignEndian::to_native(b.jitter), 16);
+        assert_eq!(int::ForeignEndian::to_native(b.last_sr), 0x12345678);
+        assert_eq!(int::ForeignEndian::to_native(b.delay_since_last_sr), 0x10000);
+        assert!(blocks.next().is_none());
+        assert!(blocks.remainder().is_empty());
+
+        let p = packets.next().unwrap();
+        assert_eq!(p.packet_type, PT_SDES);
+        assert!(packets.next().is_none());
+        assert!(packets.remainder().is_empty());
+        assert_eq!(Packets::new(&COMPOUND[..51]).count(), 0);
+    }
+
+    // Parse source descriptions and goodbye packets.
+    #[test]
+    fn verify_sdes_bye() {
+        let p = Packets::new(&COMPOUND[52..]).next().unwrap();
+        let mut chunks = SdesChunks::new(p.body, p.count as usize);
+        let (ssrc, mut items) = chunks.next().unwrap();
+        assert_eq!(ssrc, 1);
+        assert_eq!(items.next(), Some((SDES_CNAME, &b"a@b"[..])));
+        assert_eq!(items.next(), None);
+        assert!(chunks.next().is_none());
+        assert!(chunks.remainder().is_empty());
+
+        let data = [
+            0xa1, 0xcb, 0x00, 0x03, 0x00, 0x00, 0x00, 0x01,
+            0x02, 0x6f, 0x6b, 0x00, 0x00, 0x00, 0x00, 0x04,
+        ];
+        let p = Packets::new(&data).next().unwrap();
+        assert_eq!(p.packet_type, PT_BYE);
+        assert_eq!(p.count, 1);
+        assert_eq!(p.body.len(), 8);
+        assert_eq!(bye_reason(p.body, p.count as usize), Some(&b"ok"[..]));
+        assert_eq!(bye_reason(&p.body[..4], 1), None);
+        assert!(Packets::new(&data[..12]).next().is_none());
+    }
+}
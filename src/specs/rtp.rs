@@ -0,0 +1,297 @@
+//! Real-time Transport Protocol
+//!
+//! RTP packets carry media over UDP. Each packet starts with a 12-byte
+//! `Header` carrying the version, padding and extension flags, the number
+//! of contributing sources (CSRCs), the marker bit, the payload type, a
+//! sequence number, a media timestamp, and the synchronization source
+//! (SSRC). The header is followed by the CSRC list, an optional header
+//! extension (`FLAG_EXTENSION`), the payload, and optional padding
+//! (`FLAG_PADDING`), whose size is given by the last byte of the packet. See
+//! `Packet` to locate them.
+//!
+//! Payload types below `PAYLOAD_TYPE_DYNAMIC_MIN` are statically assigned
+//! by the audio/video profile (`PAYLOAD_TYPE_*`), while dynamic ones are
+//! bound by signaling. The control protocol is described in `rtcp`.
+//!
+//! All multi-byte integers are encoded as big-endian.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::int;
+
+pub const HEADER_SIZE: usize = 12;
+pub const EXTENSION_HEADER_SIZE: usize = 4;
+pub const CSRC_SIZE: usize = 4;
+pub const CSRC_MAX: usize = 15;
+pub const VERSION: u8 = 2;
+
+pub const VERSION_MASK: u8 = 0xc0;
+pub const VERSION_SHIFT: u32 = 6;
+pub const FLAG_PADDING: u8 = 0x20;
+pub const FLAG_EXTENSION: u8 = 0x10;
+pub const CSRC_COUNT_MASK: u8 = 0x0f;
+pub const FLAG_MARKER: u8 = 0x80;
+pub const PAYLOAD_TYPE_MASK: u8 = 0x7f;
+
+pub const PAYLOAD_TYPE_PCMU: u8 = 0;
+pub const PAYLOAD_TYPE_GSM: u8 = 3;
+pub const PAYLOAD_TYPE_G723: u8 = 4;
+pub const PAYLOAD_TYPE_PCMA: u8 = 8;
+pub const PAYLOAD_TYPE_G722: u8 = 9;
+pub const PAYLOAD_TYPE_L16_STEREO: u8 = 10;
+pub const PAYLOAD_TYPE_L16_MONO: u8 = 11;
+pub const PAYLOAD_TYPE_QCELP: u8 = 12;
+pub const PAYLOAD_TYPE_CN: u8 = 13;
+pub const PAYLOAD_TYPE_MPA: u8 = 14;
+pub const PAYLOAD_TYPE_G728: u8 = 15;
+pub const PAYLOAD_TYPE_G729: u8 = 18;
+pub const PAYLOAD_TYPE_JPEG: u8 = 26;
+pub const PAYLOAD_TYPE_H261: u8 = 31;
+pub const PAYLOAD_TYPE_MPV: u8 = 32;
+pub const PAYLOAD_TYPE_MP2T: u8 = 33;
+pub const PAYLOAD_TYPE_H263: u8 = 34;
+pub const PAYLOAD_TYPE_DYNAMIC_MIN: u8 = 96;
+pub const PAYLOAD_TYPE_DYNAMIC_MAX: u8 = 127;
+
+pub const EXTENSION_PROFILE_ONE_BYTE: u16 = 0xbede;
+pub const EXTENSION_PROFILE_TWO_BYTE: u16 = 0x1000;
+pub const EXTENSION_PROFILE_TWO_BYTE_MASK: u16 = 0xfff0;
+
+/// RTP Header
+#[repr(C)]
+pub struct Header {
+    /// Version (2 bits), padding and extension flags, and CSRC count (4
+    /// bits).
+    pub flags: u8,
+
+    /// Marker bit and payload type (7 bits).
+    pub marker_payload_type: u8,
+
+    /// Sequence number.
+    pub sequence: int::u16be,
+
+    /// Media timestamp, in units of the payload clock rate.
+    pub timestamp: int::u32be,
+
+    /// Synchronization source identifier.
+    pub ssrc: int::u32be,
+}
+
+/// Header Extension Header
+#[repr(C)]
+pub struct ExtensionHeader {
+    /// Profile-defined identifier (`EXTENSION_PROFILE_*`).
+    pub profile: int::u16be,
+
+    /// Size of the extension data in 4-byte units, excluding this header.
+    pub length: int::u16be,
+}
+
+/// Packet Reference
+///
+/// An RTP packet with its CSRC list, header extension, and payload located.
+#[derive(Clone, Copy, Debug)]
+pub struct Packet<'data> {
+    /// Entire packet, including the header.
+    pub data: &'data [u8],
+
+    /// CSRC list, in 4-byte entries (see `csrc()`).
+    pub csrcs: &'data [u8],
+
+    /// Profile identifier and data of the header extension, if present.
+    pub extension: Option<(u16, &'data [u8])>,
+
+    /// Payload, excluding padding.
+    pub payload: &'data [u8],
+}
+
+impl Header {
+    /// Import a header from a byte slice
+    ///
+    /// Create a new header from data copied from a byte slice. No byte-order
+    /// conversions are applied.
+    pub fn from_bytes(data: &[u8; 12]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 12], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Return version
+    pub fn version(&self) -> u8 {
+        (self.flags & VERSION_MASK) >> VERSION_SHIFT
+    }
+
+    /// Return CSRC count
+    pub fn csrc_count(&self) -> usize {
+        (self.flags & CSRC_COUNT_MASK) as usize
+    }
+
+    /// Return marker bit
+    pub fn marker(&self) -> bool {
+        self.marker_payload_type & FLAG_MARKER != 0
+    }
+
+    /// Return payload type
+    pub fn payload_type(&self) -> u8 {
+        self.marker_payload_type & PAYLOAD_TYPE_MASK
+    }
+}
+
+impl ExtensionHeader {
+    /// Import an extension header from a byte slice
+    ///
+    /// Create a new extension header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 4]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 4], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl<'data> Packet<'data> {
+    /// Parse a packet
+    ///
+    /// Parse an RTP packet and locate its parts. Return `None` if the
+    /// packet is truncated, or if the padding exceeds the packet.
+    pub fn parse(data: &'data [u8]) -> Option<Self> {
+        let flags = *data.first()?;
+        let h = Header::from_bytes(data.get(..HEADER_SIZE)?.try_into().unwrap());
+        let mut o = HEADER_SIZE + h.csrc_count() * CSRC_SIZE;
+        let csrcs = data.get(HEADER_SIZE..o)?;
+
+        let extension = if flags & FLAG_EXTENSION != 0 {
+            let e = ExtensionHeader::from_bytes(data.get(o..o + EXTENSION_HEADER_SIZE)?.try_into().unwrap());
+            let n = int::ForeignEndian::to_native(e.length) as usize * 4;
+            let v = data.get(o + EXTENSION_HEADER_SIZE..o + EXTENSION_HEADER_SIZE + n)?;
+            o += EXTENSION_HEADER_SIZE + n;
+            Some((int::ForeignEndian::to_native(e.profile), v))
+        } else {
+            None
+        };
+
+        let end = if flags & FLAG_PADDING != 0 {
+            let n = *data.last()? as usize;
+            if n == 0 || o + n > data.len() {
+                return None;
+            }
+            data.len() - n
+        } else {
+            data.len()
+        };
+
+        Some(Self {
+            data,
+            csrcs,
+            extension,
+            payload: &data[o..end],
+        })
+    }
+
+    /// Return the packet header
+    pub fn header(&self) -> Header {
+        Header::from_bytes(self.data[..HEADER_SIZE].try_into().unwrap())
+    }
+
+    /// Return CSRC
+    ///
+    /// Return the CSRC at the given index of the CSRC list, or `None` if out
+    /// of bounds.
+    pub fn csrc(&self, index: usize) -> Option<u32> {
+        let v = self.csrcs.get(index * CSRC_SIZE..(index + 1) * CSRC_SIZE)?;
+        Some(u32::from_be_bytes(v.try_into().unwrap()))
+    }
+}
+
+/// Check for dynamic payload types
+pub fn is_dynamic(payload_type: u8) -> bool {
+    (PAYLOAD_TYPE_DYNAMIC_MIN..=PAYLOAD_TYPE_DYNAMIC_MAX).contains(&payload_type)
+}
+
+/// Compare sequence numbers
+///
+/// Return the signed distance from sequence number `a` to `b`, taking
+/// wrap-around into account.
+pub fn sequence_delta(a: u16, b: u16) -> i16 {
+    b.wrapping_sub(a) as i16
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A packet with one CSRC, a one-byte header extension, and padding.
+    const PACKET: [u8; 32] = [
+        0xb1, 0xe0, 0x12, 0x34, 0x00, 0x00, 0x03, 0xe8,
+        0xde, 0xad, 0xbe, 0xef, 0x00, 0x00, 0x00, 0x2a,
+        0xbe, 0xde, 0x00, 0x01, 0x10, 0x7f, 0x00, 0x00,
+        0x68, 0x69, 0x00, 0x00, 0x00, 0x00, 0x00, 0x06,
+    ];
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<Header>(), HEADER_SIZE);
+        assert_eq!(align_of::<Header>(), 4);
+        assert_eq!(size_of::<ExtensionHeader>(), EXTENSION_HEADER_SIZE);
+        assert_eq!(align_of::<ExtensionHeader>(), 2);
+    }
+
+    // Parse a packet with all optional parts.
+    #[test]
+    fn verify_packet() {
+        let p = Packet::parse(&PACKET).unwrap();
+        let h = p.header();
+        assert_eq!(h.version(), VERSION);
+        assert_eq!(h.csrc_count(), 1);
+        assert!(h.marker());
+        assert_eq!(h.payload_type(), 96);
+        assert!(is_dynamic(h.payload_type()));
+        assert_eq!(int::ForeignEndian::to_native(h.sequence), 0x1234);
+        assert_eq!(int::ForeignEndian::to_native(h.timestamp), 1000);
+        assert_eq!(int::ForeignEndian::to_native(h.ssrc), 0xdeadbeef);
+
+        assert_eq!(p.csrc(0), Some(42));
+        assert_eq!(p.csrc(1), None);
+        assert_eq!(p.extension, Some((EXTENSION_PROFILE_ONE_BYTE, &[0x10, 0x7f, 0x00, 0x00][..])));
+        assert_eq!(p.payload, b"hi");
+
+        assert!(Packet::parse(&PACKET[..23]).is_none());
+        let mut data = PACKET;
+        data[31] = 0x0a;
+        assert!(Packet::parse(&data).is_none());
+    }
+
+    // Parse a minimal packet and compare sequence numbers.
+    #[test]
+    fn verify_minimal() {
+        let data = [0x80, 0x00, 0xff, 0xff, 0x00, 0x00, 0x00, 0xa0, 0x00, 0x00, 0x00, 0x01, 0xd5];
+        let p = Packet::parse(&data).unwrap();
+        assert!(!p.header().marker());
+        assert_eq!(p.header().payload_type(), PAYLOAD_TYPE_PCMU);
+        assert!(p.csrcs.is_empty());
+        assert!(p.extension.is_none());
+        assert_eq!(p.payload, &[0xd5]);
+        assert!(Packet::parse(&data[..11]).is_none());
+
+        assert_eq!(sequence_delta(0xffff, 0x0001), 2);
+        assert_eq!(sequence_delta(0x0001, 0xffff), -2);
+        assert!(!is_dynamic(PAYLOAD_TYPE_H263));
+    }
+}
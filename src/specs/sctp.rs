@@ -0,0 +1,602 @@
+//! Stream Control Transmission Protocol
+//!
+//! SCTP packets start with a 12-byte `CommonHeader`, followed by one or more
+//! chunks (see `Chunks`). Each chunk starts with a `ChunkHeader` whose length
+//! covers the header and the chunk value, but not the padding to the next
+//! multiple of 4 bytes. The padding is present even after the last chunk,
+//! but receivers must tolerate its absence. Several chunks carry a sequence
+//! of parameters (see `Parameters`), which use the same rules with 16-bit
+//! types.
+//!
+//! The upper 2 bits of chunk and parameter types tell receivers how to treat
+//! unknown types (see `ACTION_*`).
+//!
+//! The checksum is the CRC-32C (see `checksum::crc32c()`) of the entire packet,
+//! calculated with the checksum field set to zero. It does not cover a
+//! pseudo-header.
+//!
+//! All multi-byte integers are encoded as big-endian, except for the
+//! checksum, which is transmitted least significant byte first.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::{checksum, int};
+
+pub const COMMON_HEADER_SIZE: usize = 12;
+pub const CHUNK_HEADER_SIZE: usize = 4;
+pub const CHECKSUM_OFFSET: usize = 8;
+
+pub const CHUNK_DATA: u8 = 0;
+pub const CHUNK_INIT: u8 = 1;
+pub const CHUNK_INIT_ACK: u8 = 2;
+pub const CHUNK_SACK: u8 = 3;
+pub const CHUNK_HEARTBEAT: u8 = 4;
+pub const CHUNK_HEARTBEAT_ACK: u8 = 5;
+pub const CHUNK_ABORT: u8 = 6;
+pub const CHUNK_SHUTDOWN: u8 = 7;
+pub const CHUNK_SHUTDOWN_ACK: u8 = 8;
+pub const CHUNK_ERROR: u8 = 9;
+pub const CHUNK_COOKIE_ECHO: u8 = 10;
+pub const CHUNK_COOKIE_ACK: u8 = 11;
+pub const CHUNK_ECNE: u8 = 12;
+pub const CHUNK_CWR: u8 = 13;
+pub const CHUNK_SHUTDOWN_COMPLETE: u8 = 14;
+pub const CHUNK_AUTH: u8 = 15;
+pub const CHUNK_I_DATA: u8 = 64;
+pub const CHUNK_ASCONF_ACK: u8 = 128;
+pub const CHUNK_RE_CONFIG: u8 = 130;
+pub const CHUNK_PAD: u8 = 132;
+pub const CHUNK_FORWARD_TSN: u8 = 192;
+pub const CHUNK_ASCONF: u8 = 193;
+pub const CHUNK_I_FORWARD_TSN: u8 = 194;
+
+pub const ACTION_MASK: u8 = 0xc0;
+pub const ACTION_STOP: u8 = 0x00;
+pub const ACTION_STOP_REPORT: u8 = 0x40;
+pub const ACTION_SKIP: u8 = 0x80;
+pub const ACTION_SKIP_REPORT: u8 = 0xc0;
+
+pub const DATA_END: u8 = 0x01;
+pub const DATA_BEGIN: u8 = 0x02;
+pub const DATA_UNORDERED: u8 = 0x04;
+pub const DATA_IMMEDIATE: u8 = 0x08;
+
+/// Abort and Shutdown-complete Flag
+///
+/// Set if the verification tag is the one of the sender instead of the
+/// receiver.
+pub const FLAG_TAG_REFLECTED: u8 = 0x01;
+
+pub const PARAM_HEARTBEAT_INFO: u16 = 1;
+pub const PARAM_IPV4_ADDRESS: u16 = 5;
+pub const PARAM_IPV6_ADDRESS: u16 = 6;
+pub const PARAM_STATE_COOKIE: u16 = 7;
+pub const PARAM_UNRECOGNIZED: u16 = 8;
+pub const PARAM_COOKIE_PRESERVATIVE: u16 = 9;
+pub const PARAM_HOST_NAME: u16 = 11;
+pub const PARAM_SUPPORTED_ADDRESS_TYPES: u16 = 12;
+pub const PARAM_ECN_CAPABLE: u16 = 0x8000;
+pub const PARAM_RANDOM: u16 = 0x8002;
+pub const PARAM_CHUNK_LIST: u16 = 0x8003;
+pub const PARAM_HMAC_ALGORITHM: u16 = 0x8004;
+pub const PARAM_SUPPORTED_EXTENSIONS: u16 = 0x8008;
+pub const PARAM_FORWARD_TSN_SUPPORTED: u16 = 0xc000;
+
+pub const PPID_NONE: u32 = 0;
+pub const PPID_M3UA: u32 = 3;
+pub const PPID_S1AP: u32 = 18;
+pub const PPID_DIAMETER: u32 = 46;
+pub const PPID_WEBRTC_STRING: u32 = 51;
+pub const PPID_WEBRTC_BINARY: u32 = 53;
+pub const PPID_NGAP: u32 = 60;
+
+/// Common Header
+#[repr(C)]
+pub struct CommonHeader {
+    /// Source port.
+    pub source_port: int::u16be,
+
+    /// Destination port.
+    pub destination_port: int::u16be,
+
+    /// Verification tag of the receiver, or 0 for INIT.
+    pub verification_tag: int::u32be,
+
+    /// CRC-32C of the packet.
+    pub checksum: int::u32le,
+}
+
+/// Chunk Header
+#[repr(C)]
+pub struct ChunkHeader {
+    /// Chunk type (`CHUNK_*`).
+    pub chunk_type: u8,
+
+    /// Chunk flags, depending on the type (e.g., `DATA_*`).
+    pub flags: u8,
+
+    /// Size of the chunk in bytes, including the header, excluding padding.
+    pub length: int::u16be,
+}
+
+/// Parameter Header
+#[repr(C)]
+pub struct ParameterHeader {
+    /// Parameter type (`PARAM_*`).
+    pub parameter_type: int::u16be,
+
+    /// Size of the parameter in bytes, including the header, excluding
+    /// padding.
+    pub length: int::u16be,
+}
+
+/// DATA Chunk
+///
+/// The value of `CHUNK_DATA` chunks preceding the user data.
+#[repr(C)]
+pub struct Data {
+    /// Transmission sequence number.
+    pub tsn: int::u32be,
+
+    /// Stream identifier.
+    pub stream_id: int::u16be,
+
+    /// Stream sequence number, ignored for `DATA_UNORDERED`.
+    pub stream_sequence: int::u16be,
+
+    /// Payload protocol identifier (`PPID_*`).
+    pub ppid: int::u32be,
+}
+
+/// INIT and INIT ACK Chunk
+///
+/// The value of `CHUNK_INIT` and `CHUNK_INIT_ACK` chunks preceding their
+/// parameters.
+#[repr(C)]
+pub struct Init {
+    /// Verification tag the peer must use, never 0.
+    pub initiate_tag: int::u32be,
+
+    /// Advertised receiver window credit in bytes.
+    pub a_rwnd: int::u32be,
+
+    /// Number of requested outbound streams.
+    pub outbound_streams: int::u16be,
+
+    /// Maximum number of inbound streams.
+    pub inbound_streams: int::u16be,
+
+    /// Initial transmission sequence number.
+    pub initial_tsn: int::u32be,
+}
+
+/// SACK Chunk
+///
+/// The value of `CHUNK_SACK` chunks, followed by the gap blocks (see
+/// `GapBlock`) and the duplicate TSNs (each a 32-bit integer).
+#[repr(C)]
+pub struct Sack {
+    /// Cumulative TSN acknowledgment.
+    pub cumulative_tsn_ack: int::u32be,
+
+    /// Advertised receiver window credit in bytes.
+    pub a_rwnd: int::u32be,
+
+    /// Number of gap blocks.
+    pub gap_blocks: int::u16be,
+
+    /// Number of duplicate TSNs.
+    pub duplicate_tsns: int::u16be,
+}
+
+/// Gap Acknowledgment Block
+#[repr(C)]
+pub struct GapBlock {
+    /// Start of the block, as offset from the cumulative TSN.
+    pub start: int::u16be,
+
+    /// End of the block, as offset from the cumulative TSN.
+    pub end: int::u16be,
+}
+
+/// Chunk Reference
+#[derive(Clone, Copy, Debug)]
+pub struct Chunk<'data> {
+    /// Chunk type (`CHUNK_*`).
+    pub chunk_type: u8,
+
+    /// Chunk flags.
+    pub flags: u8,
+
+    /// Chunk value, excluding the header and padding.
+    pub data: &'data [u8],
+}
+
+/// Chunk Iterator
+///
+/// An iterator over the chunks of an SCTP packet. It must be created on the
+/// data following the common header. Iteration stops at the end of the data,
+/// or at the first chunk that is truncated or claims to be smaller than its
+/// header. Missing padding after the last chunk is tolerated.
+#[derive(Clone, Debug)]
+pub struct Chunks<'data> {
+    data: &'data [u8],
+}
+
+/// Parameter Iterator
+///
+/// An iterator over the parameters of a chunk, yielding the parameter type
+/// and the parameter value, excluding the header and padding. Iteration
+/// stops like for `Chunks`. Heartbeat chunks carry a single
+/// `PARAM_HEARTBEAT_INFO` parameter, and error causes use the same framing.
+#[derive(Clone, Debug)]
+pub struct Parameters<'data> {
+    data: &'data [u8],
+}
+
+impl CommonHeader {
+    /// Import a common header from a byte slice
+    ///
+    /// Create a new common header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 12]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 12], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl ChunkHeader {
+    /// Import a chunk header from a byte slice
+    ///
+    /// Create a new chunk header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 4]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 4], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl ParameterHeader {
+    /// Import a parameter header from a byte slice
+    ///
+    /// Create a new parameter header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 4]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 4], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl Data {
+    /// Import a DATA chunk from a byte slice
+    ///
+    /// Create a new DATA chunk from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 12]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 12], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl Init {
+    /// Import an INIT chunk from a byte slice
+    ///
+    /// Create a new INIT chunk from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 16]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 16], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl Sack {
+    /// Import a SACK chunk from a byte slice
+    ///
+    /// Create a new SACK chunk from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 12]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 12], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl GapBlock {
+    /// Import a gap block from a byte slice
+    ///
+    /// Create a new gap block from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 4]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 4], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+// Split the TLV at the start of `data` into its header and value, and return
+// the data following its padding.
+fn split_tlv(data: &[u8]) -> Option<([u8; 4], &[u8], &[u8])> {
+    if data.len() < 4 {
+        return None;
+    }
+
+    let length = u16::from_be_bytes([data[2], data[3]]) as usize;
+    if length < 4 || data.len() < length {
+        return None;
+    }
+
+    let end = core::cmp::min(padded(length), data.len());
+    Some((data[..4].try_into().unwrap(), &data[4..length], &data[end..]))
+}
+
+impl<'data> Chunks<'data> {
+    /// Create chunk iterator
+    ///
+    /// Create a new iterator over the chunks in `data`, which must start
+    /// directly after the common header.
+    pub fn new(data: &'data [u8]) -> Self {
+        Self {
+            data,
+        }
+    }
+
+    /// Return remaining data
+    ///
+    /// Return the data that has not been consumed by the iterator.
+    pub fn remainder(&self) -> &'data [u8] {
+        self.data
+    }
+}
+
+impl<'data> Iterator for Chunks<'data> {
+    type Item = Chunk<'data>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (header, data, rest) = split_tlv(self.data)?;
+        self.data = rest;
+        Some(Chunk {
+            chunk_type: header[0],
+            flags: header[1],
+            data,
+        })
+    }
+}
+
+impl<'data> Parameters<'data> {
+    /// Create parameter iterator
+    ///
+    /// Create a new iterator over the parameters in `data`, which must start
+    /// at the first parameter header.
+    pub fn new(data: &'data [u8]) -> Self {
+        Self {
+            data,
+        }
+    }
+
+    /// Return remaining data
+    ///
+    /// Return the data that has not been consumed by the iterator.
+    pub fn remainder(&self) -> &'data [u8] {
+        self.data
+    }
+}
+
+impl<'data> Iterator for Parameters<'data> {
+    type Item = (u16, &'data [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (header, data, rest) = split_tlv(self.data)?;
+        self.data = rest;
+        Some((u16::from_be_bytes([header[0], header[1]]), data))
+    }
+}
+
+/// Return padded length
+///
+/// Return `length` rounded up to the next multiple of 4, which is the space
+/// a chunk or parameter of that length occupies.
+pub fn padded(length: usize) -> usize {
+    (length + 3) & !3
+}
+
+/// Calculate Packet Checksum
+///
+/// Calculate the CRC-32C of an entire packet, treating the checksum field as
+/// zero. The result must be stored least significant byte first. `None` is
+/// returned if `packet` is shorter than `COMMON_HEADER_SIZE`.
+pub fn packet_checksum(packet: &[u8]) -> Option<u32> {
+    if packet.len() < COMMON_HEADER_SIZE {
+        return None;
+    }
+
+    let crc = checksum::crc32c_update(0xffff_ffff, &packet[..CHECKSUM_OFFSET]);
+    let crc = checksum::crc32c_update(crc, &[0; 4]);
+    Some(!checksum::crc32c_update(crc, &packet[CHECKSUM_OFFSET + 4..]))
+}
+
+/// Verify Packet Checksum
+///
+/// Verify the checksum of an entire packet. Packets shorter than
+/// `COMMON_HEADER_SIZE` always fail verification.
+pub fn verify_checksum(packet: &[u8]) -> bool {
+    let Some(v) = packet.get(CHECKSUM_OFFSET..CHECKSUM_OFFSET + 4) else {
+        return false;
+    };
+    packet_checksum(packet) == Some(u32::from_le_bytes(v.try_into().unwrap()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // An INIT with the forward-TSN and supported-address-types parameters.
+    const INIT: [u8; 44] = [
+        0x13, 0x88, 0x0b, 0x59, 0x00, 0x00, 0x00, 0x00,
+        0x8b, 0xb0, 0xf5, 0xc4, 0x01, 0x00, 0x00, 0x1e,
+        0x11, 0x22, 0x33, 0x44, 0x00, 0x01, 0x00, 0x00,
+        0x00, 0x0a, 0x00, 0x0a, 0x01, 0x00, 0x00, 0x00,
+        0xc0, 0x00, 0x00, 0x04, 0x00, 0x0c, 0x00, 0x06,
+        0x00, 0x05, 0x00, 0x00,
+    ];
+
+    // A padded DATA chunk, followed by a HEARTBEAT chunk.
+    const DATA: [u8; 40] = [
+        0x13, 0x88, 0x0b, 0x59, 0xde, 0xad, 0xbe, 0xef,
+        0xc2, 0xd8, 0x7e, 0x68, 0x00, 0x03, 0x00, 0x12,
+        0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x2e, 0x68, 0x69, 0x00, 0x00,
+        0x04, 0x00, 0x00, 0x08, 0x00, 0x01, 0x00, 0x04,
+    ];
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<CommonHeader>(), COMMON_HEADER_SIZE);
+        assert_eq!(align_of::<CommonHeader>(), 4);
+        assert_eq!(size_of::<ChunkHeader>(), CHUNK_HEADER_SIZE);
+        assert_eq!(align_of::<ChunkHeader>(), 2);
+        assert_eq!(size_of::<ParameterHeader>(), 4);
+        assert_eq!(align_of::<ParameterHeader>(), 2);
+        assert_eq!(size_of::<Data>(), 12);
+        assert_eq!(align_of::<Data>(), 4);
+        assert_eq!(size_of::<Init>(), 16);
+        assert_eq!(align_of::<Init>(), 4);
+        assert_eq!(size_of::<Sack>(), 12);
+        assert_eq!(align_of::<Sack>(), 4);
+        assert_eq!(size_of::<GapBlock>(), 4);
+        assert_eq!(align_of::<GapBlock>(), 2);
+    }
+
+    // Parse an INIT packet, its parameters, and verify its checksum.
+    #[test]
+    fn verify_init() {
+        let h = CommonHeader::from_bytes(INIT[..12].try_into().unwrap());
+        assert_eq!(int::ForeignEndian::to_native(h.source_port), 5000);
+        assert_eq!(int::ForeignEndian::to_native(h.destination_port), 2905);
+        assert_eq!(int::ForeignEndian::to_native(h.verification_tag), 0);
+        assert_eq!(int::ForeignEndian::to_native(h.checksum), 0xc4f5b08b);
+        assert_eq!(packet_checksum(&INIT), Some(0xc4f5b08b));
+        assert!(verify_checksum(&INIT));
+
+        let c = ChunkHeader::from_bytes(INIT[12..16].try_into().unwrap());
+        assert_eq!(int::ForeignEndian::to_native(c.length), 30);
+
+        let mut chunks = Chunks::new(&INIT[12..]);
+        let c = chunks.next().unwrap();
+        assert_eq!(c.chunk_type, CHUNK_INIT);
+        assert_eq!(c.data.len(), 26);
+        assert!(chunks.next().is_none());
+        assert!(chunks.remainder().is_empty());
+
+        let i = Init::from_bytes(c.data[..16].try_into().unwrap());
+        assert_eq!(int::ForeignEndian::to_native(i.initiate_tag), 0x11223344);
+        assert_eq!(int::ForeignEndian::to_native(i.a_rwnd), 65536);
+        assert_eq!(int::ForeignEndian::to_native(i.outbound_streams), 10);
+        assert_eq!(int::ForeignEndian::to_native(i.initial_tsn), 0x01000000);
+
+        let mut params = Parameters::new(&c.data[16..]);
+        assert_eq!(params.next(), Some((PARAM_FORWARD_TSN_SUPPORTED, &[][..])));
+        assert_eq!(params.next(), Some((PARAM_SUPPORTED_ADDRESS_TYPES, &[0x00, 0x05][..])));
+        assert!(params.next().is_none());
+        assert_eq!((PARAM_FORWARD_TSN_SUPPORTED >> 8) as u8 & ACTION_MASK, ACTION_SKIP_REPORT);
+
+        let mut data = INIT;
+        data[20] ^= 0x01;
+        assert!(!verify_checksum(&data));
+
+        // Truncated packets fail verification.
+        assert!(packet_checksum(&INIT[..11]).is_none());
+        assert!(!verify_checksum(&INIT[..10]));
+        assert!(!verify_checksum(&[]));
+        assert!(packet_checksum(&INIT[..12]).is_some());
+    }
+
+    // Walk padded chunks and parse DATA, HEARTBEAT, and SACK values.
+    #[test]
+    fn verify_chunks() {
+        assert!(verify_checksum(&DATA));
+
+        let mut chunks = Chunks::new(&DATA[12..]);
+        let c = chunks.next().unwrap();
+        assert_eq!(c.chunk_type, CHUNK_DATA);
+        assert_eq!(c.flags, DATA_BEGIN | DATA_END);
+        let d = Data::from_bytes(c.data[..12].try_into().unwrap());
+        assert_eq!(int::ForeignEndian::to_native(d.tsn), 1);
+        assert_eq!(int::ForeignEndian::to_native(d.ppid), PPID_DIAMETER);
+        assert_eq!(&c.data[12..], b"hi");
+
+        let c = chunks.next().unwrap();
+        assert_eq!(c.chunk_type, CHUNK_HEARTBEAT);
+        let mut params = Parameters::new(c.data);
+        assert_eq!(params.next(), Some((PARAM_HEARTBEAT_INFO, &[][..])));
+        assert!(chunks.next().is_none());
+
+        // Unpadded trailing chunks are tolerated, truncated ones are not.
+        assert_eq!(Chunks::new(&DATA[12..30]).count(), 1);
+        assert_eq!(Chunks::new(&DATA[12..29]).count(), 0);
+        assert_eq!(Chunks::new(&[0x00, 0x00, 0x00, 0x02]).count(), 0);
+        assert_eq!(padded(18), 20);
+        assert_eq!(padded(20), 20);
+
+        let s = Sack::from_bytes(&[
+            0x00, 0x00, 0x00, 0x05, 0x00, 0x01, 0x00, 0x00,
+            0x00, 0x01, 0x00, 0x00,
+        ]);
+        assert_eq!(int::ForeignEndian::to_native(s.cumulative_tsn_ack), 5);
+        assert_eq!(int::ForeignEndian::to_native(s.gap_blocks), 1);
+        let g = GapBlock::from_bytes(&[0x00, 0x02, 0x00, 0x03]);
+        assert_eq!(int::ForeignEndian::to_native(g.start), 2);
+        assert_eq!(int::ForeignEndian::to_native(g.end), 3);
+    }
+}
@@ -0,0 +1,271 @@
+//! 7z Archive Format
+//!
+//! The 7z format is the native archive format of the 7-Zip archiver. It
+//! supports solid compression with a wide range of codecs (LZMA and LZMA2
+//! being the most common), encryption, and arbitrary filter chains.
+//!
+//! A 7z archive starts with a fixed 32-byte signature header. It contains the
+//! format signature, the format version, and the location of the actual
+//! archive header (the "next header"), which is placed at the end of the
+//! archive after all packed streams. The signature header is protected by its
+//! own CRC ("start header CRC"), and it contains the CRC of the next header.
+//!
+//! The next header is a tree of properties, each introduced by a property ID
+//! and terminated by `ID_END`. The header can itself be compressed, in which
+//! case it starts with `ID_ENCODED_HEADER` and describes the streams that
+//! contain the real header.
+//!
+//! Almost all integers in the next header use a variable-length encoding (see
+//! `read_number()`). Integers in the signature header are fixed-size and
+//! little-endian. All CRCs are CRC-32 (see `checksum::crc32()`).
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::{checksum, int};
+
+/// Magic Signature
+///
+/// The initial 6 bytes of every 7z archive.
+pub const MAGIC: [u8; 6] = [0x37, 0x7a, 0xbc, 0xaf, 0x27, 0x1c];
+
+/// Major Format Version
+///
+/// The major version of the format. Decoders must reject any other major
+/// version.
+pub const VERSION_MAJOR: u8 = 0;
+
+/// Size of the Signature Header
+pub const SIGNATURE_HEADER_SIZE: usize = 32;
+
+pub const ID_END: u8 = 0x00;
+pub const ID_HEADER: u8 = 0x01;
+pub const ID_ARCHIVE_PROPERTIES: u8 = 0x02;
+pub const ID_ADDITIONAL_STREAMS_INFO: u8 = 0x03;
+pub const ID_MAIN_STREAMS_INFO: u8 = 0x04;
+pub const ID_FILES_INFO: u8 = 0x05;
+pub const ID_PACK_INFO: u8 = 0x06;
+pub const ID_UNPACK_INFO: u8 = 0x07;
+pub const ID_SUBSTREAMS_INFO: u8 = 0x08;
+pub const ID_SIZE: u8 = 0x09;
+pub const ID_CRC: u8 = 0x0a;
+pub const ID_FOLDER: u8 = 0x0b;
+pub const ID_CODERS_UNPACK_SIZE: u8 = 0x0c;
+pub const ID_NUM_UNPACK_STREAM: u8 = 0x0d;
+pub const ID_EMPTY_STREAM: u8 = 0x0e;
+pub const ID_EMPTY_FILE: u8 = 0x0f;
+pub const ID_ANTI: u8 = 0x10;
+pub const ID_NAME: u8 = 0x11;
+pub const ID_CTIME: u8 = 0x12;
+pub const ID_ATIME: u8 = 0x13;
+pub const ID_MTIME: u8 = 0x14;
+pub const ID_WIN_ATTRIBUTES: u8 = 0x15;
+pub const ID_COMMENT: u8 = 0x16;
+pub const ID_ENCODED_HEADER: u8 = 0x17;
+pub const ID_START_POS: u8 = 0x18;
+pub const ID_DUMMY: u8 = 0x19;
+
+/// Signature Header
+///
+/// The static header at offset 0 of every 7z archive. Note that the 64-bit
+/// fields are not naturally aligned, hence this structure is packed.
+#[repr(C, packed)]
+pub struct SignatureHeader {
+    /// The static signature identifying the format. This must match `MAGIC`.
+    pub signature: [u8; 6],
+
+    /// Major format version, must match `VERSION_MAJOR`.
+    pub major_version: u8,
+
+    /// Minor format version. Newer minor versions are backwards compatible.
+    pub minor_version: u8,
+
+    /// CRC-32 of the following 20 bytes (the start header).
+    pub start_header_crc: int::u32le,
+
+    /// Offset of the next header relative to the end of this signature
+    /// header.
+    pub next_header_offset: int::u64le,
+
+    /// Size of the next header in bytes.
+    pub next_header_size: int::u64le,
+
+    /// CRC-32 of the next header.
+    pub next_header_crc: int::u32le,
+}
+
+impl SignatureHeader {
+    /// Import a signature header from a byte slice
+    ///
+    /// Create a new signature header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 32]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 32], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Convert to byte slice
+    ///
+    /// Return a byte-slice reference to the signature header. No byte-order
+    /// conversions are applied.
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        assert!(align_of::<[u8; 32]>() <= align_of::<Self>());
+        assert!(size_of::<[u8; 32]>() == size_of::<Self>());
+
+        unsafe {
+            core::mem::transmute::<&Self, &[u8; 32]>(self)
+        }
+    }
+
+    /// Calculate start header CRC
+    ///
+    /// Calculate the CRC-32 of the start header (i.e., the next-header
+    /// offset, size, and CRC), as it is to be stored in `start_header_crc`.
+    pub fn calculate_start_header_crc(&self) -> u32 {
+        checksum::crc32(&self.as_bytes()[12..])
+    }
+
+    /// Return absolute offset of the next header
+    ///
+    /// Return the offset of the next header relative to the start of the
+    /// archive, or `None` on overflow.
+    pub fn next_header_position(&self) -> Option<u64> {
+        int::ForeignEndian::to_native(self.next_header_offset)
+            .checked_add(SIGNATURE_HEADER_SIZE as u64)
+    }
+}
+
+/// Read Variable-length Number
+///
+/// Decode a variable-length number as used throughout the 7z header. The
+/// number of leading 1-bits in the first byte specifies how many further
+/// bytes follow (0 to 8). The remaining low-order bits of the first byte form
+/// the most-significant part of the value, and the following bytes are a
+/// little-endian integer forming the least-significant part.
+///
+/// On success, the value and the number of bytes consumed are returned.
+/// `None` is returned if the data is truncated.
+pub fn read_number(data: &[u8]) -> Option<(u64, usize)> {
+    let first = *data.first()?;
+    let n = first.leading_ones() as usize;
+
+    if data.len() < 1 + n {
+        return None;
+    }
+
+    let mut v: u64 = 0;
+    for (i, b) in data[1..1 + n].iter().enumerate() {
+        v |= (*b as u64) << (8 * i);
+    }
+
+    if n < 7 {
+        let high = (first & (0x7f >> n)) as u64;
+        v |= high << (8 * n);
+    }
+
+    Some((v, 1 + n))
+}
+
+/// Write Variable-length Number
+///
+/// Encode a value in the variable-length number format of the 7z header,
+/// using the shortest possible form. See `read_number()` for details.
+///
+/// On success, the number of bytes written is returned. `None` is returned if
+/// the buffer is too small. At most 9 bytes are needed.
+pub fn write_number(value: u64, buf: &mut [u8]) -> Option<usize> {
+    let mut n = 0;
+    while n < 8 && (value >> (8 * n)) >= (0x80 >> n) {
+        n += 1;
+    }
+
+    if buf.len() < 1 + n {
+        return None;
+    }
+
+    let (prefix, high) = if n < 8 {
+        (!(0xffu8 >> n), (value >> (8 * n)) as u8)
+    } else {
+        (0xff, 0)
+    };
+
+    buf[0] = prefix | high;
+    for i in 0..n {
+        buf[1 + i] = (value >> (8 * i)) as u8;
+    }
+
+    Some(1 + n)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // An empty archive as produced by 7-Zip.
+    const EMPTY: [u8; 32] = [
+        0x37, 0x7a, 0xbc, 0xaf, 0x27, 0x1c, 0x00, 0x04,
+        0x8d, 0x9b, 0xd5, 0x0f, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<SignatureHeader>(), SIGNATURE_HEADER_SIZE);
+        assert_eq!(align_of::<SignatureHeader>(), 1);
+    }
+
+    // Verify the signature header accessors and CRC calculation.
+    #[test]
+    fn verify_signature_header() {
+        let h = SignatureHeader::from_bytes(&EMPTY);
+
+        assert_eq!(h.signature, MAGIC);
+        assert_eq!(h.major_version, VERSION_MAJOR);
+        assert_eq!(h.minor_version, 4);
+        assert_eq!(
+            int::ForeignEndian::to_native(h.start_header_crc),
+            h.calculate_start_header_crc(),
+        );
+        assert_eq!(h.next_header_position(), Some(32));
+        assert_eq!(h.as_bytes(), &EMPTY);
+    }
+
+    // Verify variable-length numbers at all size boundaries.
+    #[test]
+    fn verify_number() {
+        let vectors: &[(u64, &[u8])] = &[
+            (0x00, &[0x00]),
+            (0x7f, &[0x7f]),
+            (0x80, &[0x80, 0x80]),
+            (0x3fff, &[0xbf, 0xff]),
+            (0x4000, &[0xc0, 0x00, 0x40]),
+            (0x12_3456, &[0xd2, 0x56, 0x34]),
+            ((1 << 56) - 1, &[0xfe, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff]),
+            (1 << 56, &[0xff, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01]),
+            (u64::MAX, &[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff]),
+        ];
+
+        for (v, enc) in vectors {
+            let mut buf = [0u8; 9];
+            let n = write_number(*v, &mut buf).unwrap();
+            assert_eq!(&buf[..n], *enc);
+            assert_eq!(read_number(enc), Some((*v, enc.len())));
+        }
+
+        assert_eq!(read_number(&[]), None);
+        assert_eq!(read_number(&[0xc0, 0x00]), None);
+        assert_eq!(write_number(0x80, &mut [0u8; 1]), None);
+    }
+}
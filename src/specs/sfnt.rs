@@ -0,0 +1,901 @@
+//! TrueType and OpenType Fonts
+//!
+//! TrueType and OpenType fonts share the sfnt container. A font starts with
+//! an `OffsetTable`, followed by a `TableRecord` for each table, sorted by
+//! tag. Each record locates a table by offset and length relative to the
+//! start of the file, and carries a table checksum (see `checksum()`).
+//! Tables are 4-byte aligned and zero-padded.
+//!
+//! This module provides the layouts of the tables needed to identify and
+//! subset fonts: `head`, `hhea`, `maxp`, the headers of `cmap` and its
+//! subtables, `name`, and `OS/2`. Many tables have multiple versions, which
+//! are usually extensions of the previous version.
+//!
+//! Fixed-point values use the `Fixed` (16.16) and `F2DOT14` (2.14) formats,
+//! and dates (`LONGDATETIME`) are signed 64-bit seconds since 1904-01-01
+//! 00:00 UTC.
+//!
+//! All multi-byte integers are encoded as big-endian.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::int;
+
+pub const VERSION_TRUETYPE: u32 = 0x0001_0000;
+pub const VERSION_CFF: u32 = 0x4f54_544f;
+pub const VERSION_APPLE: u32 = 0x7472_7565;
+
+/// Font Collection Tag
+///
+/// The initial 4 bytes of TrueType collections ("ttcf"), which bundle
+/// multiple offset tables in one file.
+pub const TAG_COLLECTION: [u8; 4] = *b"ttcf";
+
+pub const TAG_CFF: [u8; 4] = *b"CFF ";
+pub const TAG_CFF2: [u8; 4] = *b"CFF2";
+pub const TAG_CMAP: [u8; 4] = *b"cmap";
+pub const TAG_GLYF: [u8; 4] = *b"glyf";
+pub const TAG_GPOS: [u8; 4] = *b"GPOS";
+pub const TAG_GSUB: [u8; 4] = *b"GSUB";
+pub const TAG_HEAD: [u8; 4] = *b"head";
+pub const TAG_HHEA: [u8; 4] = *b"hhea";
+pub const TAG_HMTX: [u8; 4] = *b"hmtx";
+pub const TAG_KERN: [u8; 4] = *b"kern";
+pub const TAG_LOCA: [u8; 4] = *b"loca";
+pub const TAG_MAXP: [u8; 4] = *b"maxp";
+pub const TAG_NAME: [u8; 4] = *b"name";
+pub const TAG_OS2: [u8; 4] = *b"OS/2";
+pub const TAG_POST: [u8; 4] = *b"post";
+
+/// Magic Number of `head`
+pub const HEAD_MAGIC: u32 = 0x5f0f_3cf5;
+
+/// Checksum Adjustment Base
+///
+/// The checksum adjustment in `head` is this value minus the checksum of the
+/// entire font, calculated with the adjustment set to 0.
+pub const CHECKSUM_MAGIC: u32 = 0xb1b0_afba;
+
+/// Epoch Offset
+///
+/// The number of seconds between 1904-01-01 and 1970-01-01, to convert
+/// dates to Unix time.
+pub const EPOCH_OFFSET: i64 = 2_082_844_800;
+
+pub const MAC_STYLE_BOLD: u16 = 0x0001;
+pub const MAC_STYLE_ITALIC: u16 = 0x0002;
+pub const MAC_STYLE_UNDERLINE: u16 = 0x0004;
+pub const MAC_STYLE_OUTLINE: u16 = 0x0008;
+pub const MAC_STYLE_SHADOW: u16 = 0x0010;
+pub const MAC_STYLE_CONDENSED: u16 = 0x0020;
+pub const MAC_STYLE_EXTENDED: u16 = 0x0040;
+
+pub const LOCA_SHORT: i16 = 0;
+pub const LOCA_LONG: i16 = 1;
+
+pub const MAXP_VERSION_0_5: u32 = 0x0000_5000;
+pub const MAXP_VERSION_1_0: u32 = 0x0001_0000;
+
+pub const PLATFORM_UNICODE: u16 = 0;
+pub const PLATFORM_MACINTOSH: u16 = 1;
+pub const PLATFORM_WINDOWS: u16 = 3;
+
+pub const WINDOWS_SYMBOL: u16 = 0;
+pub const WINDOWS_UNICODE_BMP: u16 = 1;
+pub const WINDOWS_UNICODE_FULL: u16 = 10;
+
+pub const NAME_COPYRIGHT: u16 = 0;
+pub const NAME_FAMILY: u16 = 1;
+pub const NAME_SUBFAMILY: u16 = 2;
+pub const NAME_UNIQUE_ID: u16 = 3;
+pub const NAME_FULL_NAME: u16 = 4;
+pub const NAME_VERSION: u16 = 5;
+pub const NAME_POSTSCRIPT_NAME: u16 = 6;
+pub const NAME_TRADEMARK: u16 = 7;
+pub const NAME_MANUFACTURER: u16 = 8;
+pub const NAME_DESIGNER: u16 = 9;
+pub const NAME_DESCRIPTION: u16 = 10;
+pub const NAME_VENDOR_URL: u16 = 11;
+pub const NAME_DESIGNER_URL: u16 = 12;
+pub const NAME_LICENSE: u16 = 13;
+pub const NAME_LICENSE_URL: u16 = 14;
+pub const NAME_TYPOGRAPHIC_FAMILY: u16 = 16;
+pub const NAME_TYPOGRAPHIC_SUBFAMILY: u16 = 17;
+
+pub const FS_TYPE_RESTRICTED: u16 = 0x0002;
+pub const FS_TYPE_PREVIEW_PRINT: u16 = 0x0004;
+pub const FS_TYPE_EDITABLE: u16 = 0x0008;
+pub const FS_TYPE_NO_SUBSETTING: u16 = 0x0100;
+pub const FS_TYPE_BITMAP_ONLY: u16 = 0x0200;
+
+pub const FS_SELECTION_ITALIC: u16 = 0x0001;
+pub const FS_SELECTION_UNDERSCORE: u16 = 0x0002;
+pub const FS_SELECTION_NEGATIVE: u16 = 0x0004;
+pub const FS_SELECTION_OUTLINED: u16 = 0x0008;
+pub const FS_SELECTION_STRIKEOUT: u16 = 0x0010;
+pub const FS_SELECTION_BOLD: u16 = 0x0020;
+pub const FS_SELECTION_REGULAR: u16 = 0x0040;
+pub const FS_SELECTION_USE_TYPO_METRICS: u16 = 0x0080;
+pub const FS_SELECTION_WWS: u16 = 0x0100;
+pub const FS_SELECTION_OBLIQUE: u16 = 0x0200;
+
+/// Sizes of the OS/2 Table Versions
+///
+/// Indexed by the version of the table. Each version extends the previous
+/// one, except for versions 2 to 4, which share their layout.
+pub const OS2_SIZES: [usize; 6] = [78, 86, 96, 96, 96, 100];
+
+/// Offset Table
+///
+/// The header of a font, followed by `num_tables` table records.
+#[repr(C)]
+pub struct OffsetTable {
+    /// Font version (`VERSION_*`).
+    pub sfnt_version: int::u32be,
+
+    /// Number of tables.
+    pub num_tables: int::u16be,
+
+    /// Binary search parameters (see `search_params()`).
+    pub search_range: int::u16be,
+    pub entry_selector: int::u16be,
+    pub range_shift: int::u16be,
+}
+
+/// Table Record
+#[repr(C)]
+pub struct TableRecord {
+    /// Table tag (`TAG_*`).
+    pub tag: [u8; 4],
+
+    /// Checksum of the table.
+    pub checksum: int::u32be,
+
+    /// Offset of the table from the start of the file.
+    pub offset: int::u32be,
+
+    /// Length of the table, excluding the padding.
+    pub length: int::u32be,
+}
+
+/// Font Header Table
+///
+/// The `head` table. Since the dates are not naturally aligned, this
+/// structure is packed.
+#[repr(C, packed)]
+pub struct Head {
+    pub major_version: int::u16be,
+    pub minor_version: int::u16be,
+
+    /// Font revision set by the manufacturer.
+    pub font_revision: int::s15f16be,
+
+    /// Checksum adjustment (see `CHECKSUM_MAGIC`).
+    pub checksum_adjustment: int::u32be,
+
+    /// Magic number, must match `HEAD_MAGIC`.
+    pub magic: int::u32be,
+
+    /// Font flags.
+    pub flags: int::u16be,
+
+    /// Units per em, from 16 to 16384.
+    pub units_per_em: int::u16be,
+
+    /// Creation date in seconds since 1904.
+    pub created: int::i64be,
+
+    /// Modification date in seconds since 1904.
+    pub modified: int::i64be,
+
+    /// Bounding box of all glyphs.
+    pub x_min: int::i16be,
+    pub y_min: int::i16be,
+    pub x_max: int::i16be,
+    pub y_max: int::i16be,
+
+    /// Style flags (`MAC_STYLE_*`).
+    pub mac_style: int::u16be,
+
+    /// Smallest readable size in pixels.
+    pub lowest_rec_ppem: int::u16be,
+
+    /// Deprecated, set to 2.
+    pub font_direction_hint: int::i16be,
+
+    /// Format of `loca` (`LOCA_*`).
+    pub index_to_loc_format: int::i16be,
+
+    /// Format of `glyf`, must be 0.
+    pub glyph_data_format: int::i16be,
+}
+
+/// Horizontal Header Table
+///
+/// The `hhea` table.
+#[repr(C)]
+pub struct Hhea {
+    pub major_version: int::u16be,
+    pub minor_version: int::u16be,
+
+    /// Typographic ascender, descender, and line gap.
+    pub ascender: int::i16be,
+    pub descender: int::i16be,
+    pub line_gap: int::i16be,
+
+    /// Maximum advance width in `hmtx`.
+    pub advance_width_max: int::u16be,
+
+    /// Minimum left and right side bearings, and maximum extent.
+    pub min_left_side_bearing: int::i16be,
+    pub min_right_side_bearing: int::i16be,
+    pub x_max_extent: int::i16be,
+
+    /// Slope of the caret, and its offset for slanted fonts.
+    pub caret_slope_rise: int::i16be,
+    pub caret_slope_run: int::i16be,
+    pub caret_offset: int::i16be,
+
+    pub reserved: [int::i16be; 4],
+
+    /// Format of the metric data, must be 0.
+    pub metric_data_format: int::i16be,
+
+    /// Number of advance widths in `hmtx`.
+    pub number_of_h_metrics: int::u16be,
+}
+
+/// Maximum Profile Table (Version 0.5)
+///
+/// The `maxp` table of fonts with CFF outlines. Since the size is not a
+/// multiple of the alignment of the version, this structure is packed.
+#[repr(C, packed)]
+pub struct MaxpV05 {
+    /// Table version (`MAXP_VERSION_*`).
+    pub version: int::u32be,
+
+    /// Number of glyphs.
+    pub num_glyphs: int::u16be,
+}
+
+/// Maximum Profile Table (Version 1.0)
+///
+/// The `maxp` table of fonts with TrueType outlines.
+#[repr(C)]
+pub struct MaxpV10 {
+    /// Table version (`MAXP_VERSION_*`).
+    pub version: int::u32be,
+
+    /// Number of glyphs.
+    pub num_glyphs: int::u16be,
+
+    pub max_points: int::u16be,
+    pub max_contours: int::u16be,
+    pub max_composite_points: int::u16be,
+    pub max_composite_contours: int::u16be,
+    pub max_zones: int::u16be,
+    pub max_twilight_points: int::u16be,
+    pub max_storage: int::u16be,
+    pub max_function_defs: int::u16be,
+    pub max_instruction_defs: int::u16be,
+    pub max_stack_elements: int::u16be,
+    pub max_size_of_instructions: int::u16be,
+    pub max_component_elements: int::u16be,
+    pub max_component_depth: int::u16be,
+}
+
+/// Character Map Header
+///
+/// The header of the `cmap` table, followed by `num_tables` encoding
+/// records.
+#[repr(C)]
+pub struct CmapHeader {
+    /// Table version, must be 0.
+    pub version: int::u16be,
+
+    /// Number of encoding records.
+    pub num_tables: int::u16be,
+}
+
+/// Character Map Encoding Record
+#[repr(C)]
+pub struct EncodingRecord {
+    /// Platform ID (`PLATFORM_*`).
+    pub platform_id: int::u16be,
+
+    /// Platform-specific encoding ID (e.g., `WINDOWS_*`).
+    pub encoding_id: int::u16be,
+
+    /// Offset of the subtable from the start of the `cmap` table.
+    pub subtable_offset: int::u32be,
+}
+
+/// Character Map Subtable Header
+///
+/// The common header of subtable formats 0, 2, 4, and 6. Formats 8 and
+/// above use `CmapSubtableHeader32`.
+#[repr(C)]
+pub struct CmapSubtableHeader {
+    /// Subtable format.
+    pub format: int::u16be,
+
+    /// Length of the subtable in bytes.
+    pub length: int::u16be,
+
+    /// Macintosh language code, or 0.
+    pub language: int::u16be,
+}
+
+/// Character Map Subtable Format 4
+///
+/// The header of format 4 subtables, followed by the segment arrays.
+#[repr(C)]
+pub struct CmapFormat4 {
+    /// Common header, with a format of 4.
+    pub header: CmapSubtableHeader,
+
+    /// Twice the number of segments.
+    pub seg_count_x2: int::u16be,
+
+    /// Binary search parameters, in units of 2 bytes.
+    pub search_range: int::u16be,
+    pub entry_selector: int::u16be,
+    pub range_shift: int::u16be,
+}
+
+/// Character Map Subtable Header (32-bit)
+///
+/// The common header of subtable formats 8, 10, 12, and 13. Format 12 and
+/// 13 subtables are followed by `num_groups` groups.
+#[repr(C)]
+pub struct CmapSubtableHeader32 {
+    /// Subtable format.
+    pub format: int::u16be,
+
+    pub reserved: int::u16be,
+
+    /// Length of the subtable in bytes.
+    pub length: int::u32be,
+
+    /// Macintosh language code, or 0.
+    pub language: int::u32be,
+
+    /// Number of groups.
+    pub num_groups: int::u32be,
+}
+
+/// Character Map Group
+///
+/// A group of format 12 and 13 subtables, mapping a range of code points
+/// to consecutive glyphs (format 12) or a single glyph (format 13).
+#[repr(C)]
+pub struct SequentialMapGroup {
+    pub start_char_code: int::u32be,
+    pub end_char_code: int::u32be,
+    pub start_glyph_id: int::u32be,
+}
+
+/// Naming Table Header
+///
+/// The header of the `name` table, followed by `count` name records.
+#[repr(C)]
+pub struct NameHeader {
+    /// Table version, 0 or 1.
+    pub version: int::u16be,
+
+    /// Number of name records.
+    pub count: int::u16be,
+
+    /// Offset of the string storage from the start of the table.
+    pub storage_offset: int::u16be,
+}
+
+/// Name Record
+#[repr(C)]
+pub struct NameRecord {
+    /// Platform ID (`PLATFORM_*`).
+    pub platform_id: int::u16be,
+
+    /// Platform-specific encoding ID.
+    pub encoding_id: int::u16be,
+
+    /// Platform-specific language ID.
+    pub language_id: int::u16be,
+
+    /// Name ID (`NAME_*`).
+    pub name_id: int::u16be,
+
+    /// Length of the string in bytes.
+    pub length: int::u16be,
+
+    /// Offset of the string from the start of the string storage.
+    pub string_offset: int::u16be,
+}
+
+/// OS/2 and Windows Metrics Table
+///
+/// The `OS/2` table in its version 5 layout. Earlier versions are prefixes
+/// of this layout, with sizes given by `OS2_SIZES`, so shorter tables must be
+/// zero-padded before import. Since the Unicode ranges are not naturally
+/// aligned, this structure is packed.
+#[repr(C, packed)]
+pub struct Os2 {
+    /// Table version.
+    pub version: int::u16be,
+
+    pub x_avg_char_width: int::i16be,
+    pub us_weight_class: int::u16be,
+    pub us_width_class: int::u16be,
+
+    /// Embedding permissions (`FS_TYPE_*`).
+    pub fs_type: int::u16be,
+
+    pub y_subscript_x_size: int::i16be,
+    pub y_subscript_y_size: int::i16be,
+    pub y_subscript_x_offset: int::i16be,
+    pub y_subscript_y_offset: int::i16be,
+    pub y_superscript_x_size: int::i16be,
+    pub y_superscript_y_size: int::i16be,
+    pub y_superscript_x_offset: int::i16be,
+    pub y_superscript_y_offset: int::i16be,
+    pub y_strikeout_size: int::i16be,
+    pub y_strikeout_position: int::i16be,
+    pub s_family_class: int::i16be,
+
+    /// PANOSE classification.
+    pub panose: [u8; 10],
+
+    /// Supported Unicode ranges.
+    pub ul_unicode_range: [int::u32be; 4],
+
+    /// Font vendor identifier.
+    pub ach_vend_id: [u8; 4],
+
+    /// Style flags (`FS_SELECTION_*`).
+    pub fs_selection: int::u16be,
+
+    pub us_first_char_index: int::u16be,
+    pub us_last_char_index: int::u16be,
+    pub s_typo_ascender: int::i16be,
+    pub s_typo_descender: int::i16be,
+    pub s_typo_line_gap: int::i16be,
+    pub us_win_ascent: int::u16be,
+    pub us_win_descent: int::u16be,
+
+    /// Supported code pages, since version 1.
+    pub ul_code_page_range: [int::u32be; 2],
+
+    /// Since version 2.
+    pub sx_height: int::i16be,
+    pub s_cap_height: int::i16be,
+    pub us_default_char: int::u16be,
+    pub us_break_char: int::u16be,
+    pub us_max_context: int::u16be,
+
+    /// Since version 5.
+    pub us_lower_optical_point_size: int::u16be,
+    pub us_upper_optical_point_size: int::u16be,
+}
+
+impl OffsetTable {
+    /// Import a offset table from a byte slice
+    ///
+    /// Create a new offset table from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 12]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 12], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl TableRecord {
+    /// Import a table record from a byte slice
+    ///
+    /// Create a new table record from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 16]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 16], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl Head {
+    /// Import a font header from a byte slice
+    ///
+    /// Create a new font header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 54]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 54], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl Hhea {
+    /// Import a horizontal header from a byte slice
+    ///
+    /// Create a new horizontal header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 36]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 36], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl MaxpV05 {
+    /// Import a maximum profile from a byte slice
+    ///
+    /// Create a new maximum profile from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 6]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 6], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl MaxpV10 {
+    /// Import a maximum profile from a byte slice
+    ///
+    /// Create a new maximum profile from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 32]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 32], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl CmapHeader {
+    /// Import a character map header from a byte slice
+    ///
+    /// Create a new character map header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 4]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 4], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl EncodingRecord {
+    /// Import a encoding record from a byte slice
+    ///
+    /// Create a new encoding record from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 8]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 8], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl CmapSubtableHeader {
+    /// Import a subtable header from a byte slice
+    ///
+    /// Create a new subtable header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 6]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 6], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl CmapFormat4 {
+    /// Import a format 4 header from a byte slice
+    ///
+    /// Create a new format 4 header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 14]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 14], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl CmapSubtableHeader32 {
+    /// Import a subtable header from a byte slice
+    ///
+    /// Create a new subtable header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 16]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 16], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl SequentialMapGroup {
+    /// Import a map group from a byte slice
+    ///
+    /// Create a new map group from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 12]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 12], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl NameHeader {
+    /// Import a naming table header from a byte slice
+    ///
+    /// Create a new naming table header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 6]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 6], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl NameRecord {
+    /// Import a name record from a byte slice
+    ///
+    /// Create a new name record from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 12]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 12], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl Os2 {
+    /// Import a OS/2 table from a byte slice
+    ///
+    /// Create a new OS/2 table from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 100]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 100], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+/// Calculate Table Checksum
+///
+/// Calculate the checksum of a table, the wrapping sum of its big-endian
+/// 32-bit words. A trailing partial word is zero-padded.
+pub fn checksum(data: &[u8]) -> u32 {
+    data.chunks(4).fold(0u32, |acc, v| {
+        let mut word = [0; 4];
+        word[..v.len()].copy_from_slice(v);
+        acc.wrapping_add(u32::from_be_bytes(word))
+    })
+}
+
+/// Calculate Binary Search Parameters
+///
+/// Return the search range, entry selector, and range shift for `count`
+/// entries of `size` bytes each, as stored in the offset table (with a
+/// size of 16) and in format 4 `cmap` subtables (with a size of 2). `None`
+/// is returned if the search range or range shift do not fit in 16 bits.
+pub fn search_params(count: u16, size: u16) -> Option<(u16, u16, u16)> {
+    if count == 0 {
+        return Some((0, 0, 0));
+    }
+
+    let selector = 15 - count.leading_zeros() as u16;
+    let range = (1u32 << selector) * size as u32;
+    let shift = count as u32 * size as u32 - range;
+    Some((u16::try_from(range).ok()?, selector, u16::try_from(shift).ok()?))
+}
+
+/// Find Table
+///
+/// Look up the table with the given tag in a font with an offset table at
+/// the start of `data`, and return its data. `None` is returned if the
+/// table does not exist, or the font is truncated.
+pub fn find_table<'data>(data: &'data [u8], tag: &[u8; 4]) -> Option<&'data [u8]> {
+    let h = OffsetTable::from_bytes(data.get(..12)?.try_into().unwrap());
+    let n = int::ForeignEndian::to_native(h.num_tables) as usize;
+    let records = data.get(12..12 + n * 16)?;
+
+    let r = records
+        .chunks_exact(16)
+        .map(|v| TableRecord::from_bytes(v.try_into().unwrap()))
+        .find(|v| v.tag == *tag)?;
+
+    let offset = int::ForeignEndian::to_native(r.offset) as usize;
+    let length = int::ForeignEndian::to_native(r.length) as usize;
+    data.get(offset..offset.checked_add(length)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A font with a `head` and a version 0.5 `maxp` table, with valid
+    // checksums.
+    const FONT: [u8; 108] = [
+        0x00, 0x01, 0x00, 0x00, 0x00, 0x02, 0x00, 0x20,
+        0x00, 0x01, 0x00, 0x00, 0x68, 0x65, 0x61, 0x64,
+        0x63, 0x07, 0x5f, 0x5d, 0x00, 0x00, 0x00, 0x2c,
+        0x00, 0x00, 0x00, 0x36, 0x6d, 0x61, 0x78, 0x70,
+        0x00, 0x03, 0x50, 0x00, 0x00, 0x00, 0x00, 0x64,
+        0x00, 0x00, 0x00, 0x06, 0x00, 0x01, 0x00, 0x00,
+        0x00, 0x01, 0x00, 0x00, 0x15, 0xd0, 0x76, 0x40,
+        0x5f, 0x0f, 0x3c, 0xf5, 0x00, 0x0b, 0x03, 0xe8,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0e, 0x10,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0e, 0x10,
+        0x00, 0x00, 0xff, 0x38, 0x03, 0xe8, 0x03, 0x20,
+        0x00, 0x00, 0x00, 0x08, 0x00, 0x02, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x50, 0x00,
+        0x00, 0x03, 0x00, 0x00,
+    ];
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<OffsetTable>(), 12);
+        assert_eq!(align_of::<OffsetTable>(), 4);
+        assert_eq!(size_of::<TableRecord>(), 16);
+        assert_eq!(size_of::<Head>(), 54);
+        assert_eq!(size_of::<Hhea>(), 36);
+        assert_eq!(size_of::<MaxpV05>(), 6);
+        assert_eq!(size_of::<MaxpV10>(), 32);
+        assert_eq!(size_of::<CmapHeader>(), 4);
+        assert_eq!(size_of::<EncodingRecord>(), 8);
+        assert_eq!(size_of::<CmapSubtableHeader>(), 6);
+        assert_eq!(size_of::<CmapFormat4>(), 14);
+        assert_eq!(size_of::<CmapSubtableHeader32>(), 16);
+        assert_eq!(size_of::<SequentialMapGroup>(), 12);
+        assert_eq!(size_of::<NameHeader>(), 6);
+        assert_eq!(size_of::<NameRecord>(), 12);
+        assert_eq!(size_of::<Os2>(), OS2_SIZES[5]);
+    }
+
+    // Look up tables, and verify the table checksums and the checksum
+    // adjustment.
+    #[test]
+    fn verify_font() {
+        let h = OffsetTable::from_bytes(FONT[..12].try_into().unwrap());
+        assert_eq!(int::ForeignEndian::to_native(h.sfnt_version), VERSION_TRUETYPE);
+        assert_eq!(int::ForeignEndian::to_native(h.num_tables), 2);
+        assert_eq!(search_params(2, 16), Some((
+            int::ForeignEndian::to_native(h.search_range),
+            int::ForeignEndian::to_native(h.entry_selector),
+            int::ForeignEndian::to_native(h.range_shift),
+        )));
+
+        let data = find_table(&FONT, &TAG_HEAD).unwrap();
+        let head = Head::from_bytes(data.try_into().unwrap());
+        assert_eq!(int::ForeignEndian::to_native(head.magic), HEAD_MAGIC);
+        assert_eq!(int::ForeignEndian::to_native(head.units_per_em), 1000);
+        assert_eq!(head.font_revision.to_f64(), 1.0);
+        assert_eq!(int::ForeignEndian::to_native(head.created), 3600);
+        assert_eq!(int::ForeignEndian::to_native(head.y_min), -200);
+        assert_eq!(int::ForeignEndian::to_native(head.index_to_loc_format), LOCA_SHORT);
+
+        let r = TableRecord::from_bytes(FONT[12..28].try_into().unwrap());
+        let mut zeroed = [0; 54];
+        zeroed.copy_from_slice(data);
+        zeroed[8..12].copy_from_slice(&[0; 4]);
+        assert_eq!(checksum(&zeroed), int::ForeignEndian::to_native(r.checksum));
+        assert_eq!(checksum(&FONT), CHECKSUM_MAGIC);
+
+        let maxp = MaxpV05::from_bytes(find_table(&FONT, &TAG_MAXP).unwrap().try_into().unwrap());
+        assert_eq!(int::ForeignEndian::to_native(maxp.version), MAXP_VERSION_0_5);
+        assert_eq!(int::ForeignEndian::to_native(maxp.num_glyphs), 3);
+
+        assert!(find_table(&FONT, &TAG_CMAP).is_none());
+        assert!(find_table(&FONT[..100], &TAG_MAXP).is_none());
+    }
+
+    // Verify search parameters, checksum padding, and fixed-point values.
+    #[test]
+    fn verify_helpers() {
+        assert_eq!(search_params(0, 16), Some((0, 0, 0)));
+        assert_eq!(search_params(1, 16), Some((16, 0, 0)));
+        assert_eq!(search_params(11, 16), Some((128, 3, 48)));
+        assert_eq!(search_params(39, 2), Some((64, 5, 14)));
+        assert_eq!(search_params(4095, 16), Some((32768, 11, 32752)));
+        assert!(search_params(4096, 16).is_none());
+        assert!(search_params(u16::MAX, 2).is_none());
+
+        assert_eq!(checksum(&[0x01, 0x02, 0x03, 0x04, 0x05]), 0x06020304);
+        assert_eq!(checksum(&[0xff; 8]), 0xfffffffe);
+
+        assert_eq!(int::s1f14be::from_bits(0x7000).to_f64(), 1.75);
+        assert_eq!(int::s1f14be::from_bits(-0x4000).to_f64(), -1.0);
+    }
+}
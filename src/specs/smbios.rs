@@ -0,0 +1,1329 @@
+//! System Management BIOS
+//!
+//! SMBIOS describes the hardware inventory of a system (firmware, board,
+//! chassis, processors, memory, and more) as a table of structures provided
+//! by firmware. The table is located via an entry point: the 32-bit
+//! `EntryPoint` (anchor `_SM_`) of SMBIOS 2.1, or the 64-bit `EntryPoint3`
+//! (anchor `_SM3_`) of SMBIOS 3.0. Both are protected by checksums where all
+//! bytes sum to zero modulo 256.
+//!
+//! Every structure starts with a `Header`, giving its type and the length of
+//! its formatted area. The formatted area is followed by the string-set: a
+//! sequence of NUL-terminated strings, terminated by an additional NUL byte.
+//! A structure without strings is followed by two NUL bytes. Fields of the
+//! formatted area refer to strings by their 1-based index in the string-set,
+//! with 0 meaning no string. The table ends with a structure of type
+//! `TYPE_END_OF_TABLE`.
+//!
+//! Structures are extended with new fields in newer specification versions,
+//! so the formatted area can be shorter or longer than the layouts defined
+//! here. Use the `from_structure()` constructors to read structures of any
+//! version.
+//!
+//! All multi-byte integers are encoded as little-endian. Since the
+//! structures are not naturally aligned in memory, they are packed.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::checksum;
+use crate::specs::int;
+
+/// SMBIOS 2.1 Anchor
+///
+/// The initial 4 bytes of the 32-bit entry point ("_SM_").
+pub const ANCHOR: [u8; 4] = *b"_SM_";
+
+/// Intermediate Anchor
+///
+/// The anchor of the intermediate entry point embedded in the 32-bit entry
+/// point ("_DMI_").
+pub const ANCHOR_DMI: [u8; 5] = *b"_DMI_";
+
+/// SMBIOS 3.0 Anchor
+///
+/// The initial 5 bytes of the 64-bit entry point ("_SM3_").
+pub const ANCHOR3: [u8; 5] = *b"_SM3_";
+
+pub const TYPE_BIOS_INFORMATION: u8 = 0;
+pub const TYPE_SYSTEM_INFORMATION: u8 = 1;
+pub const TYPE_BASEBOARD_INFORMATION: u8 = 2;
+pub const TYPE_SYSTEM_ENCLOSURE: u8 = 3;
+pub const TYPE_PROCESSOR_INFORMATION: u8 = 4;
+pub const TYPE_CACHE_INFORMATION: u8 = 7;
+pub const TYPE_SYSTEM_SLOTS: u8 = 9;
+pub const TYPE_OEM_STRINGS: u8 = 11;
+pub const TYPE_PHYSICAL_MEMORY_ARRAY: u8 = 16;
+pub const TYPE_MEMORY_DEVICE: u8 = 17;
+pub const TYPE_MEMORY_ARRAY_MAPPED_ADDRESS: u8 = 19;
+pub const TYPE_SYSTEM_BOOT_INFORMATION: u8 = 32;
+pub const TYPE_INACTIVE: u8 = 126;
+pub const TYPE_END_OF_TABLE: u8 = 127;
+/* OEM: 128..255 */
+
+pub const HANDLE_NONE: u16 = 0xffff;
+pub const HANDLE_NOT_PROVIDED: u16 = 0xfffe;
+
+pub const BIOS_CHARACTERISTICS_NOT_SUPPORTED: u64 = 0x0000_0000_0000_0008;
+pub const BIOS_ISA: u64 = 0x0000_0000_0000_0010;
+pub const BIOS_PCI: u64 = 0x0000_0000_0000_0080;
+pub const BIOS_PNP: u64 = 0x0000_0000_0000_0200;
+pub const BIOS_APM: u64 = 0x0000_0000_0000_0400;
+pub const BIOS_UPGRADEABLE: u64 = 0x0000_0000_0000_0800;
+pub const BIOS_SHADOWING: u64 = 0x0000_0000_0000_1000;
+pub const BIOS_BOOT_FROM_CD: u64 = 0x0000_0000_0000_8000;
+pub const BIOS_SELECTABLE_BOOT: u64 = 0x0000_0000_0001_0000;
+pub const BIOS_EDD: u64 = 0x0000_0000_0008_0000;
+
+pub const BIOS_EXT1_ACPI: u8 = 0x01;
+pub const BIOS_EXT1_USB_LEGACY: u8 = 0x02;
+pub const BIOS_EXT2_BOOT_SPECIFICATION: u8 = 0x01;
+pub const BIOS_EXT2_NETWORK_BOOT: u8 = 0x02;
+pub const BIOS_EXT2_TARGETED_CONTENT_DISTRIBUTION: u8 = 0x04;
+pub const BIOS_EXT2_UEFI: u8 = 0x08;
+pub const BIOS_EXT2_VIRTUAL_MACHINE: u8 = 0x10;
+
+pub const WAKE_UP_OTHER: u8 = 0x01;
+pub const WAKE_UP_UNKNOWN: u8 = 0x02;
+pub const WAKE_UP_APM_TIMER: u8 = 0x03;
+pub const WAKE_UP_MODEM_RING: u8 = 0x04;
+pub const WAKE_UP_LAN_REMOTE: u8 = 0x05;
+pub const WAKE_UP_POWER_SWITCH: u8 = 0x06;
+pub const WAKE_UP_PCI_PME: u8 = 0x07;
+pub const WAKE_UP_AC_POWER_RESTORED: u8 = 0x08;
+
+pub const BASEBOARD_HOSTING: u8 = 0x01;
+pub const BASEBOARD_REQUIRES_DAUGHTER_BOARD: u8 = 0x02;
+pub const BASEBOARD_REMOVABLE: u8 = 0x04;
+pub const BASEBOARD_REPLACEABLE: u8 = 0x08;
+pub const BASEBOARD_HOT_SWAPPABLE: u8 = 0x10;
+
+pub const BOARD_TYPE_UNKNOWN: u8 = 0x01;
+pub const BOARD_TYPE_OTHER: u8 = 0x02;
+pub const BOARD_TYPE_SERVER_BLADE: u8 = 0x03;
+pub const BOARD_TYPE_CONNECTIVITY_SWITCH: u8 = 0x04;
+pub const BOARD_TYPE_SYSTEM_MANAGEMENT_MODULE: u8 = 0x05;
+pub const BOARD_TYPE_PROCESSOR_MODULE: u8 = 0x06;
+pub const BOARD_TYPE_IO_MODULE: u8 = 0x07;
+pub const BOARD_TYPE_MEMORY_MODULE: u8 = 0x08;
+pub const BOARD_TYPE_DAUGHTER_BOARD: u8 = 0x09;
+pub const BOARD_TYPE_MOTHERBOARD: u8 = 0x0a;
+pub const BOARD_TYPE_PROCESSOR_MEMORY_MODULE: u8 = 0x0b;
+pub const BOARD_TYPE_PROCESSOR_IO_MODULE: u8 = 0x0c;
+pub const BOARD_TYPE_INTERCONNECT_BOARD: u8 = 0x0d;
+
+pub const CHASSIS_LOCK: u8 = 0x80;
+pub const CHASSIS_TYPE_MASK: u8 = 0x7f;
+pub const CHASSIS_OTHER: u8 = 0x01;
+pub const CHASSIS_UNKNOWN: u8 = 0x02;
+pub const CHASSIS_DESKTOP: u8 = 0x03;
+pub const CHASSIS_LOW_PROFILE_DESKTOP: u8 = 0x04;
+pub const CHASSIS_PIZZA_BOX: u8 = 0x05;
+pub const CHASSIS_MINI_TOWER: u8 = 0x06;
+pub const CHASSIS_TOWER: u8 = 0x07;
+pub const CHASSIS_PORTABLE: u8 = 0x08;
+pub const CHASSIS_LAPTOP: u8 = 0x09;
+pub const CHASSIS_NOTEBOOK: u8 = 0x0a;
+pub const CHASSIS_HAND_HELD: u8 = 0x0b;
+pub const CHASSIS_DOCKING_STATION: u8 = 0x0c;
+pub const CHASSIS_ALL_IN_ONE: u8 = 0x0d;
+pub const CHASSIS_SUB_NOTEBOOK: u8 = 0x0e;
+pub const CHASSIS_SPACE_SAVING: u8 = 0x0f;
+pub const CHASSIS_LUNCH_BOX: u8 = 0x10;
+pub const CHASSIS_MAIN_SERVER: u8 = 0x11;
+pub const CHASSIS_EXPANSION: u8 = 0x12;
+pub const CHASSIS_SUB: u8 = 0x13;
+pub const CHASSIS_BUS_EXPANSION: u8 = 0x14;
+pub const CHASSIS_PERIPHERAL: u8 = 0x15;
+pub const CHASSIS_RAID: u8 = 0x16;
+pub const CHASSIS_RACK_MOUNT: u8 = 0x17;
+pub const CHASSIS_SEALED_CASE_PC: u8 = 0x18;
+pub const CHASSIS_MULTI_SYSTEM: u8 = 0x19;
+pub const CHASSIS_COMPACT_PCI: u8 = 0x1a;
+pub const CHASSIS_ADVANCED_TCA: u8 = 0x1b;
+pub const CHASSIS_BLADE: u8 = 0x1c;
+pub const CHASSIS_BLADE_ENCLOSURE: u8 = 0x1d;
+pub const CHASSIS_TABLET: u8 = 0x1e;
+pub const CHASSIS_CONVERTIBLE: u8 = 0x1f;
+pub const CHASSIS_DETACHABLE: u8 = 0x20;
+pub const CHASSIS_IOT_GATEWAY: u8 = 0x21;
+pub const CHASSIS_EMBEDDED_PC: u8 = 0x22;
+pub const CHASSIS_MINI_PC: u8 = 0x23;
+pub const CHASSIS_STICK_PC: u8 = 0x24;
+
+pub const STATE_OTHER: u8 = 0x01;
+pub const STATE_UNKNOWN: u8 = 0x02;
+pub const STATE_SAFE: u8 = 0x03;
+pub const STATE_WARNING: u8 = 0x04;
+pub const STATE_CRITICAL: u8 = 0x05;
+pub const STATE_NON_RECOVERABLE: u8 = 0x06;
+
+pub const PROCESSOR_TYPE_OTHER: u8 = 0x01;
+pub const PROCESSOR_TYPE_UNKNOWN: u8 = 0x02;
+pub const PROCESSOR_TYPE_CENTRAL: u8 = 0x03;
+pub const PROCESSOR_TYPE_MATH: u8 = 0x04;
+pub const PROCESSOR_TYPE_DSP: u8 = 0x05;
+pub const PROCESSOR_TYPE_VIDEO: u8 = 0x06;
+
+pub const PROCESSOR_STATUS_POPULATED: u8 = 0x40;
+pub const PROCESSOR_STATUS_MASK: u8 = 0x07;
+pub const PROCESSOR_STATUS_UNKNOWN: u8 = 0x00;
+pub const PROCESSOR_STATUS_ENABLED: u8 = 0x01;
+pub const PROCESSOR_STATUS_DISABLED_BY_USER: u8 = 0x02;
+pub const PROCESSOR_STATUS_DISABLED_BY_FIRMWARE: u8 = 0x03;
+pub const PROCESSOR_STATUS_IDLE: u8 = 0x04;
+pub const PROCESSOR_STATUS_OTHER: u8 = 0x07;
+
+pub const PROCESSOR_64BIT: u16 = 0x0004;
+pub const PROCESSOR_MULTI_CORE: u16 = 0x0008;
+pub const PROCESSOR_HARDWARE_THREAD: u16 = 0x0010;
+pub const PROCESSOR_EXECUTE_PROTECTION: u16 = 0x0020;
+pub const PROCESSOR_ENHANCED_VIRTUALIZATION: u16 = 0x0040;
+pub const PROCESSOR_POWER_CONTROL: u16 = 0x0080;
+pub const PROCESSOR_128BIT: u16 = 0x0100;
+pub const PROCESSOR_ARM64_SOC_ID: u16 = 0x0200;
+
+pub const ARRAY_LOCATION_OTHER: u8 = 0x01;
+pub const ARRAY_LOCATION_UNKNOWN: u8 = 0x02;
+pub const ARRAY_LOCATION_SYSTEM_BOARD: u8 = 0x03;
+pub const ARRAY_LOCATION_ISA_ADDON: u8 = 0x04;
+pub const ARRAY_LOCATION_EISA_ADDON: u8 = 0x05;
+pub const ARRAY_LOCATION_PCI_ADDON: u8 = 0x06;
+pub const ARRAY_LOCATION_MCA_ADDON: u8 = 0x07;
+pub const ARRAY_LOCATION_PCMCIA_ADDON: u8 = 0x08;
+pub const ARRAY_LOCATION_PROPRIETARY_ADDON: u8 = 0x09;
+pub const ARRAY_LOCATION_NUBUS: u8 = 0x0a;
+pub const ARRAY_LOCATION_CXL_ADDON: u8 = 0xa3;
+
+pub const ARRAY_USE_OTHER: u8 = 0x01;
+pub const ARRAY_USE_UNKNOWN: u8 = 0x02;
+pub const ARRAY_USE_SYSTEM_MEMORY: u8 = 0x03;
+pub const ARRAY_USE_VIDEO_MEMORY: u8 = 0x04;
+pub const ARRAY_USE_FLASH_MEMORY: u8 = 0x05;
+pub const ARRAY_USE_NON_VOLATILE_RAM: u8 = 0x06;
+pub const ARRAY_USE_CACHE_MEMORY: u8 = 0x07;
+
+pub const ERROR_CORRECTION_OTHER: u8 = 0x01;
+pub const ERROR_CORRECTION_UNKNOWN: u8 = 0x02;
+pub const ERROR_CORRECTION_NONE: u8 = 0x03;
+pub const ERROR_CORRECTION_PARITY: u8 = 0x04;
+pub const ERROR_CORRECTION_SINGLE_BIT_ECC: u8 = 0x05;
+pub const ERROR_CORRECTION_MULTI_BIT_ECC: u8 = 0x06;
+pub const ERROR_CORRECTION_CRC: u8 = 0x07;
+
+pub const MAXIMUM_CAPACITY_EXTENDED: u32 = 0x8000_0000;
+
+pub const FORM_FACTOR_OTHER: u8 = 0x01;
+pub const FORM_FACTOR_UNKNOWN: u8 = 0x02;
+pub const FORM_FACTOR_SIMM: u8 = 0x03;
+pub const FORM_FACTOR_SIP: u8 = 0x04;
+pub const FORM_FACTOR_CHIP: u8 = 0x05;
+pub const FORM_FACTOR_DIP: u8 = 0x06;
+pub const FORM_FACTOR_ZIP: u8 = 0x07;
+pub const FORM_FACTOR_PROPRIETARY_CARD: u8 = 0x08;
+pub const FORM_FACTOR_DIMM: u8 = 0x09;
+pub const FORM_FACTOR_TSOP: u8 = 0x0a;
+pub const FORM_FACTOR_ROW_OF_CHIPS: u8 = 0x0b;
+pub const FORM_FACTOR_RIMM: u8 = 0x0c;
+pub const FORM_FACTOR_SODIMM: u8 = 0x0d;
+pub const FORM_FACTOR_SRIMM: u8 = 0x0e;
+pub const FORM_FACTOR_FB_DIMM: u8 = 0x0f;
+pub const FORM_FACTOR_DIE: u8 = 0x10;
+pub const FORM_FACTOR_CAMM: u8 = 0x11;
+
+pub const MEMORY_TYPE_OTHER: u8 = 0x01;
+pub const MEMORY_TYPE_UNKNOWN: u8 = 0x02;
+pub const MEMORY_TYPE_DRAM: u8 = 0x03;
+pub const MEMORY_TYPE_SDRAM: u8 = 0x0f;
+pub const MEMORY_TYPE_ROM: u8 = 0x11;
+pub const MEMORY_TYPE_DDR: u8 = 0x12;
+pub const MEMORY_TYPE_DDR2: u8 = 0x13;
+pub const MEMORY_TYPE_DDR2_FB_DIMM: u8 = 0x14;
+pub const MEMORY_TYPE_DDR3: u8 = 0x18;
+pub const MEMORY_TYPE_FBD2: u8 = 0x19;
+pub const MEMORY_TYPE_DDR4: u8 = 0x1a;
+pub const MEMORY_TYPE_LPDDR: u8 = 0x1b;
+pub const MEMORY_TYPE_LPDDR2: u8 = 0x1c;
+pub const MEMORY_TYPE_LPDDR3: u8 = 0x1d;
+pub const MEMORY_TYPE_LPDDR4: u8 = 0x1e;
+pub const MEMORY_TYPE_LOGICAL_NON_VOLATILE: u8 = 0x1f;
+pub const MEMORY_TYPE_HBM: u8 = 0x20;
+pub const MEMORY_TYPE_HBM2: u8 = 0x21;
+pub const MEMORY_TYPE_DDR5: u8 = 0x22;
+pub const MEMORY_TYPE_LPDDR5: u8 = 0x23;
+pub const MEMORY_TYPE_HBM3: u8 = 0x24;
+
+/// SMBIOS 2.1 Entry Point
+///
+/// The 32-bit entry point, located via the EFI configuration table or by a
+/// scan of the BIOS area at 16-byte boundaries.
+#[repr(C, packed)]
+pub struct EntryPoint {
+    /// The static anchor. This must match `ANCHOR`.
+    pub anchor: [u8; 4],
+
+    /// Checksum of the entire entry point (see `checksum::sum8()`).
+    pub checksum: u8,
+
+    /// Size of the entry point in bytes.
+    pub length: u8,
+
+    /// Major version of the specification.
+    pub major_version: u8,
+
+    /// Minor version of the specification.
+    pub minor_version: u8,
+
+    /// Size of the largest structure in bytes, including its strings.
+    pub max_structure_size: int::u16le,
+
+    /// Revision of the entry point format, 0 for 2.1.
+    pub entry_point_revision: u8,
+
+    /// Revision specific data, zero for revision 0.
+    pub formatted_area: [u8; 5],
+
+    /// The static intermediate anchor. This must match `ANCHOR_DMI`.
+    pub intermediate_anchor: [u8; 5],
+
+    /// Checksum of the intermediate entry point, starting at `intermediate_anchor`.
+    pub intermediate_checksum: u8,
+
+    /// Size of the structure table in bytes.
+    pub table_length: int::u16le,
+
+    /// Physical address of the structure table.
+    pub table_address: int::u32le,
+
+    /// Number of structures in the structure table.
+    pub structure_count: int::u16le,
+
+    /// Specification version in BCD, or 0 if not given.
+    pub bcd_revision: u8,
+}
+
+/// SMBIOS 3.0 Entry Point
+///
+/// The 64-bit entry point. Unlike the 2.1 entry point, it does not give the
+/// number of structures, but only the maximum size of the structure table.
+#[repr(C, packed)]
+pub struct EntryPoint3 {
+    /// The static anchor. This must match `ANCHOR3`.
+    pub anchor: [u8; 5],
+
+    /// Checksum of the entire entry point (see `checksum::sum8()`).
+    pub checksum: u8,
+
+    /// Size of the entry point in bytes.
+    pub length: u8,
+
+    /// Major version of the specification.
+    pub major_version: u8,
+
+    /// Minor version of the specification.
+    pub minor_version: u8,
+
+    /// Revision of the specification document.
+    pub docrev: u8,
+
+    /// Revision of the entry point format, 1 for 3.0.
+    pub entry_point_revision: u8,
+
+    /// Reserved.
+    pub reserved: u8,
+
+    /// Maximum size of the structure table in bytes.
+    pub table_max_size: int::u32le,
+
+    /// Physical address of the structure table.
+    pub table_address: int::u64le,
+}
+
+/// Structure Header
+///
+/// The header of every structure. The formatted area of `length` bytes,
+/// including the header, is followed by the string-set.
+#[repr(C, packed)]
+pub struct Header {
+    /// Structure type (`TYPE_*`).
+    pub structure_type: u8,
+
+    /// Size of the formatted area in bytes, including the header.
+    pub length: u8,
+
+    /// Handle of the structure, used to refer to it from other structures.
+    pub handle: int::u16le,
+}
+
+/// BIOS Information (Type 0)
+///
+/// The layout of SMBIOS 3.1. Earlier versions are shorter.
+#[repr(C, packed)]
+pub struct BiosInformation {
+    /// Structure header.
+    pub header: Header,
+
+    /// Index of the vendor string, or 0 if none.
+    pub vendor: u8,
+
+    /// Index of the BIOS version string, or 0 if none.
+    pub bios_version: u8,
+
+    /// Segment of the start of the BIOS image, or 0 for UEFI systems.
+    pub bios_starting_segment: int::u16le,
+
+    /// Index of the release date string, or 0 if none.
+    pub bios_release_date: u8,
+
+    /// Size of the BIOS image as `(n + 1) * 64 KiB`, or `0xff` for extended size.
+    pub bios_rom_size: u8,
+
+    /// BIOS characteristics (`BIOS_*`).
+    pub bios_characteristics: int::u64le,
+
+    /// BIOS characteristics extension bytes.
+    pub bios_characteristics_extension: [u8; 2],
+
+    /// Major release of the system BIOS.
+    pub system_bios_major_release: u8,
+
+    /// Minor release of the system BIOS.
+    pub system_bios_minor_release: u8,
+
+    /// Major release of the embedded controller firmware.
+    pub ec_firmware_major_release: u8,
+
+    /// Minor release of the embedded controller firmware.
+    pub ec_firmware_minor_release: u8,
+
+    /// Extended size of the BIOS image. Bits 14-15 give the unit (MiB, GiB).
+    pub extended_bios_rom_size: int::u16le,
+}
+
+/// System Information (Type 1)
+///
+/// The layout of SMBIOS 2.4 and later.
+#[repr(C, packed)]
+pub struct SystemInformation {
+    /// Structure header.
+    pub header: Header,
+
+    /// Index of the manufacturer string, or 0 if none.
+    pub manufacturer: u8,
+
+    /// Index of the product name string, or 0 if none.
+    pub product_name: u8,
+
+    /// Index of the version string, or 0 if none.
+    pub version: u8,
+
+    /// Index of the serial number string, or 0 if none.
+    pub serial_number: u8,
+
+    /// System UUID. The first three fields are little-endian since 2.6.
+    pub uuid: [u8; 16],
+
+    /// Event that caused the system to power up (`WAKE_UP_*`).
+    pub wake_up_type: u8,
+
+    /// Index of the SKU number string, or 0 if none.
+    pub sku_number: u8,
+
+    /// Index of the family string, or 0 if none.
+    pub family: u8,
+}
+
+/// Baseboard Information (Type 2)
+///
+/// Followed by the handles of contained objects.
+#[repr(C, packed)]
+pub struct BaseboardInformation {
+    /// Structure header.
+    pub header: Header,
+
+    /// Index of the manufacturer string, or 0 if none.
+    pub manufacturer: u8,
+
+    /// Index of the product string, or 0 if none.
+    pub product: u8,
+
+    /// Index of the version string, or 0 if none.
+    pub version: u8,
+
+    /// Index of the serial number string, or 0 if none.
+    pub serial_number: u8,
+
+    /// Index of the asset tag string, or 0 if none.
+    pub asset_tag: u8,
+
+    /// Feature flags (`BASEBOARD_*`).
+    pub feature_flags: u8,
+
+    /// Index of the location in the chassis string, or 0 if none.
+    pub location_in_chassis: u8,
+
+    /// Handle of the chassis containing the board.
+    pub chassis_handle: int::u16le,
+
+    /// Board type (`BOARD_TYPE_*`).
+    pub board_type: u8,
+
+    /// Number of contained object handles following.
+    pub contained_object_handle_count: u8,
+}
+
+/// System Enclosure or Chassis (Type 3)
+///
+/// Followed by the contained element records, and the SKU number string
+/// index.
+#[repr(C, packed)]
+pub struct SystemEnclosure {
+    /// Structure header.
+    pub header: Header,
+
+    /// Index of the manufacturer string, or 0 if none.
+    pub manufacturer: u8,
+
+    /// Chassis type (`CHASSIS_*`), with bit 7 indicating a chassis lock.
+    pub chassis_type: u8,
+
+    /// Index of the version string, or 0 if none.
+    pub version: u8,
+
+    /// Index of the serial number string, or 0 if none.
+    pub serial_number: u8,
+
+    /// Index of the asset tag string, or 0 if none.
+    pub asset_tag: u8,
+
+    /// State of the enclosure at last boot (`STATE_*`).
+    pub bootup_state: u8,
+
+    /// State of the power supply at last boot (`STATE_*`).
+    pub power_supply_state: u8,
+
+    /// Thermal state at last boot (`STATE_*`).
+    pub thermal_state: u8,
+
+    /// Physical security status at last boot.
+    pub security_status: u8,
+
+    /// OEM defined data.
+    pub oem_defined: int::u32le,
+
+    /// Height of the enclosure in rack units, or 0 if unspecified.
+    pub height: u8,
+
+    /// Number of power cords, or 0 if unspecified.
+    pub power_cord_count: u8,
+
+    /// Number of contained element records.
+    pub contained_element_count: u8,
+
+    /// Size of each contained element record in bytes.
+    pub contained_element_record_length: u8,
+}
+
+/// Processor Information (Type 4)
+///
+/// The layout of SMBIOS 3.6. Earlier versions are shorter.
+#[repr(C, packed)]
+pub struct ProcessorInformation {
+    /// Structure header.
+    pub header: Header,
+
+    /// Index of the socket designation string, or 0 if none.
+    pub socket_designation: u8,
+
+    /// Processor type (`PROCESSOR_TYPE_*`).
+    pub processor_type: u8,
+
+    /// Processor family, or `0xfe` to use `processor_family_2`.
+    pub processor_family: u8,
+
+    /// Index of the manufacturer string, or 0 if none.
+    pub processor_manufacturer: u8,
+
+    /// Raw processor identification data (e.g., CPUID on x86).
+    pub processor_id: int::u64le,
+
+    /// Index of the version string, or 0 if none.
+    pub processor_version: u8,
+
+    /// Voltage, either in 0.1 V units (bit 7 set) or as legacy bits.
+    pub voltage: u8,
+
+    /// External clock in MHz, or 0 if unknown.
+    pub external_clock: int::u16le,
+
+    /// Maximum supported speed in MHz, or 0 if unknown.
+    pub max_speed: int::u16le,
+
+    /// Speed at boot in MHz, or 0 if unknown.
+    pub current_speed: int::u16le,
+
+    /// Socket and CPU status (`PROCESSOR_STATUS_*`).
+    pub status: u8,
+
+    /// Socket type.
+    pub processor_upgrade: u8,
+
+    /// Handle of the L1 cache information, or `0xffff`.
+    pub l1_cache_handle: int::u16le,
+
+    /// Handle of the L2 cache information, or `0xffff`.
+    pub l2_cache_handle: int::u16le,
+
+    /// Handle of the L3 cache information, or `0xffff`.
+    pub l3_cache_handle: int::u16le,
+
+    /// Index of the serial number string, or 0 if none.
+    pub serial_number: u8,
+
+    /// Index of the asset tag string, or 0 if none.
+    pub asset_tag: u8,
+
+    /// Index of the part number string, or 0 if none.
+    pub part_number: u8,
+
+    /// Number of cores, or `0xff` to use `core_count_2`.
+    pub core_count: u8,
+
+    /// Number of enabled cores, or `0xff` to use `core_enabled_2`.
+    pub core_enabled: u8,
+
+    /// Number of threads, or `0xff` to use `thread_count_2`.
+    pub thread_count: u8,
+
+    /// Processor characteristics (`PROCESSOR_*`).
+    pub processor_characteristics: int::u16le,
+
+    /// Extended processor family.
+    pub processor_family_2: int::u16le,
+
+    /// Extended number of cores.
+    pub core_count_2: int::u16le,
+
+    /// Extended number of enabled cores.
+    pub core_enabled_2: int::u16le,
+
+    /// Extended number of threads.
+    pub thread_count_2: int::u16le,
+
+    /// Number of enabled threads.
+    pub thread_enabled: int::u16le,
+}
+
+/// Physical Memory Array (Type 16)
+#[repr(C, packed)]
+pub struct PhysicalMemoryArray {
+    /// Structure header.
+    pub header: Header,
+
+    /// Location of the array (`ARRAY_LOCATION_*`).
+    pub location: u8,
+
+    /// Function of the array (`ARRAY_USE_*`).
+    pub array_use: u8,
+
+    /// Error correction type (`ERROR_CORRECTION_*`).
+    pub memory_error_correction: u8,
+
+    /// Maximum capacity in KiB, or `0x80000000` to use `extended_maximum_capacity`.
+    pub maximum_capacity: int::u32le,
+
+    /// Handle of the error information, or `0xfffe` if not provided.
+    pub memory_error_information_handle: int::u16le,
+
+    /// Number of memory device slots.
+    pub memory_device_count: int::u16le,
+
+    /// Extended maximum capacity in bytes.
+    pub extended_maximum_capacity: int::u64le,
+}
+
+/// Memory Device (Type 17)
+///
+/// The layout of SMBIOS 3.3. Earlier versions are shorter.
+#[repr(C, packed)]
+pub struct MemoryDevice {
+    /// Structure header.
+    pub header: Header,
+
+    /// Handle of the physical memory array of the device.
+    pub physical_memory_array_handle: int::u16le,
+
+    /// Handle of the error information, or `0xfffe` if not provided.
+    pub memory_error_information_handle: int::u16le,
+
+    /// Total width in bits, including ECC bits, or `0xffff` if unknown.
+    pub total_width: int::u16le,
+
+    /// Data width in bits, or `0xffff` if unknown.
+    pub data_width: int::u16le,
+
+    /// Size of the device (see `MemoryDevice::size_bytes()`).
+    pub size: int::u16le,
+
+    /// Form factor (`FORM_FACTOR_*`).
+    pub form_factor: u8,
+
+    /// Set of devices that must be populated together, or 0 if none.
+    pub device_set: u8,
+
+    /// Index of the device locator string, or 0 if none.
+    pub device_locator: u8,
+
+    /// Index of the bank locator string, or 0 if none.
+    pub bank_locator: u8,
+
+    /// Memory type (`MEMORY_TYPE_*`).
+    pub memory_type: u8,
+
+    /// Memory type details.
+    pub type_detail: int::u16le,
+
+    /// Maximum speed in MT/s, or `0xffff` to use `extended_speed`.
+    pub speed: int::u16le,
+
+    /// Index of the manufacturer string, or 0 if none.
+    pub manufacturer: u8,
+
+    /// Index of the serial number string, or 0 if none.
+    pub serial_number: u8,
+
+    /// Index of the asset tag string, or 0 if none.
+    pub asset_tag: u8,
+
+    /// Index of the part number string, or 0 if none.
+    pub part_number: u8,
+
+    /// Rank in bits 0-3, or 0 if unknown.
+    pub attributes: u8,
+
+    /// Extended size in MiB, if `size` is `0x7fff`.
+    pub extended_size: int::u32le,
+
+    /// Configured speed in MT/s, or `0xffff` to use the extended field.
+    pub configured_memory_speed: int::u16le,
+
+    /// Minimum voltage in mV, or 0 if unknown.
+    pub minimum_voltage: int::u16le,
+
+    /// Maximum voltage in mV, or 0 if unknown.
+    pub maximum_voltage: int::u16le,
+
+    /// Configured voltage in mV, or 0 if unknown.
+    pub configured_voltage: int::u16le,
+
+    /// Memory technology.
+    pub memory_technology: u8,
+
+    /// Supported operating modes.
+    pub memory_operating_mode_capability: int::u16le,
+
+    /// Index of the firmware version string, or 0 if none.
+    pub firmware_version: u8,
+
+    /// JEDEC id of the module manufacturer.
+    pub module_manufacturer_id: int::u16le,
+
+    /// Product id of the module.
+    pub module_product_id: int::u16le,
+
+    /// JEDEC id of the memory controller manufacturer.
+    pub memory_subsystem_controller_manufacturer_id: int::u16le,
+
+    /// Product id of the memory controller.
+    pub memory_subsystem_controller_product_id: int::u16le,
+
+    /// Size of the non-volatile portion in bytes.
+    pub non_volatile_size: int::u64le,
+
+    /// Size of the volatile portion in bytes.
+    pub volatile_size: int::u64le,
+
+    /// Size of the cache portion in bytes.
+    pub cache_size: int::u64le,
+
+    /// Size of the logical portion in bytes.
+    pub logical_size: int::u64le,
+
+    /// Extended maximum speed in MT/s.
+    pub extended_speed: int::u32le,
+
+    /// Extended configured speed in MT/s.
+    pub extended_configured_memory_speed: int::u32le,
+}
+
+/// Structure Reference
+///
+/// A reference to a single structure of the structure table, split into its
+/// formatted area and string-set.
+#[derive(Clone, Copy, Debug)]
+pub struct Structure<'data> {
+    /// Structure type (`TYPE_*`).
+    pub structure_type: u8,
+
+    /// Handle of the structure.
+    pub handle: u16,
+
+    /// Formatted area, including the header.
+    pub formatted: &'data [u8],
+
+    /// String-set, excluding the terminating NUL byte.
+    pub strings: &'data [u8],
+
+    /// Total size of the structure in bytes, including the string-set.
+    pub size: usize,
+}
+
+/// String Iterator
+///
+/// Iterate the strings of a string-set, without their NUL terminators. The
+/// first string yielded has index 1.
+#[derive(Clone, Debug)]
+pub struct Strings<'data> {
+    data: &'data [u8],
+}
+
+/// Structure Iterator
+///
+/// Iterate the structures of a structure table. The iterator stops after the
+/// end-of-table structure, or at the first malformed structure. The remaining
+/// data can be retrieved via `Structures::remainder()`.
+#[derive(Clone, Debug)]
+pub struct Structures<'data> {
+    data: &'data [u8],
+    done: bool,
+}
+
+// Copy a formatted area into a zero-extended array of the given size.
+fn extend<const N: usize>(data: &[u8]) -> [u8; N] {
+    let mut v = [0u8; N];
+    let n = core::cmp::min(data.len(), N);
+
+    v[..n].copy_from_slice(&data[..n]);
+    v
+}
+
+impl EntryPoint {
+    /// Import an entry point from a byte slice
+    ///
+    /// Create a new entry point from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 31]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 31], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Check whether the entry point is valid
+    ///
+    /// Verify the anchors and both checksums of an entry point. The data can
+    /// exceed the entry point.
+    pub fn is_valid(data: &[u8]) -> bool {
+        let Some(v) = data.get(..31) else {
+            return false;
+        };
+        let ep = Self::from_bytes(v.try_into().unwrap());
+        let Some(v) = data.get(..ep.length as usize) else {
+            return false;
+        };
+
+        ep.anchor == ANCHOR
+            && ep.intermediate_anchor == ANCHOR_DMI
+            && v.len() >= 31
+            && checksum::sum8(v) == 0
+            && checksum::sum8(&v[16..31]) == 0
+    }
+}
+
+impl EntryPoint3 {
+    /// Import an entry point from a byte slice
+    ///
+    /// Create a new entry point from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 24]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 24], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Check whether the entry point is valid
+    ///
+    /// Verify the anchor and checksum of an entry point. The data can exceed
+    /// the entry point.
+    pub fn is_valid(data: &[u8]) -> bool {
+        let Some(v) = data.get(..24) else {
+            return false;
+        };
+        let ep = Self::from_bytes(v.try_into().unwrap());
+        match data.get(..ep.length as usize) {
+            Some(v) if v.len() >= 24 => ep.anchor == ANCHOR3 && checksum::sum8(v) == 0,
+            _ => false,
+        }
+    }
+}
+
+impl Header {
+    /// Import a structure header from a byte slice
+    ///
+    /// Create a new structure header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 4]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 4], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl BiosInformation {
+    /// Import a structure from a byte slice
+    ///
+    /// Create a new structure from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 26]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 26], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Import BIOS information from a structure of any version
+    ///
+    /// Create a new structure from the formatted area of a structure of any
+    /// specification version. Shorter structures are extended with zeroes,
+    /// and trailing data is ignored. No byte-order conversions are applied.
+    pub fn from_structure(data: &[u8]) -> Self {
+        Self::from_bytes(&extend(data))
+    }
+}
+
+impl SystemInformation {
+    /// Import a structure from a byte slice
+    ///
+    /// Create a new structure from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 27]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 27], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Import system information from a structure of any version
+    ///
+    /// Create a new structure from the formatted area of a structure of any
+    /// specification version. Shorter structures are extended with zeroes,
+    /// and trailing data is ignored. No byte-order conversions are applied.
+    pub fn from_structure(data: &[u8]) -> Self {
+        Self::from_bytes(&extend(data))
+    }
+}
+
+impl BaseboardInformation {
+    /// Import a structure from a byte slice
+    ///
+    /// Create a new structure from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 15]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 15], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Import baseboard information from a structure of any version
+    ///
+    /// Create a new structure from the formatted area of a structure of any
+    /// specification version. Shorter structures are extended with zeroes,
+    /// and trailing data is ignored. No byte-order conversions are applied.
+    pub fn from_structure(data: &[u8]) -> Self {
+        Self::from_bytes(&extend(data))
+    }
+}
+
+impl SystemEnclosure {
+    /// Import a structure from a byte slice
+    ///
+    /// Create a new structure from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 21]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 21], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Import a system enclosure from a structure of any version
+    ///
+    /// Create a new structure from the formatted area of a structure of any
+    /// specification version. Shorter structures are extended with zeroes,
+    /// and trailing data is ignored. No byte-order conversions are applied.
+    pub fn from_structure(data: &[u8]) -> Self {
+        Self::from_bytes(&extend(data))
+    }
+}
+
+impl ProcessorInformation {
+    /// Import a structure from a byte slice
+    ///
+    /// Create a new structure from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 50]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 50], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Import processor information from a structure of any version
+    ///
+    /// Create a new structure from the formatted area of a structure of any
+    /// specification version. Shorter structures are extended with zeroes,
+    /// and trailing data is ignored. No byte-order conversions are applied.
+    pub fn from_structure(data: &[u8]) -> Self {
+        Self::from_bytes(&extend(data))
+    }
+}
+
+impl PhysicalMemoryArray {
+    /// Import a structure from a byte slice
+    ///
+    /// Create a new structure from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 23]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 23], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Import a physical memory array from a structure of any version
+    ///
+    /// Create a new structure from the formatted area of a structure of any
+    /// specification version. Shorter structures are extended with zeroes,
+    /// and trailing data is ignored. No byte-order conversions are applied.
+    pub fn from_structure(data: &[u8]) -> Self {
+        Self::from_bytes(&extend(data))
+    }
+}
+
+impl MemoryDevice {
+    /// Import a structure from a byte slice
+    ///
+    /// Create a new structure from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 92]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 92], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Import a memory device from a structure of any version
+    ///
+    /// Create a new structure from the formatted area of a structure of any
+    /// specification version. Shorter structures are extended with zeroes,
+    /// and trailing data is ignored. No byte-order conversions are applied.
+    pub fn from_structure(data: &[u8]) -> Self {
+        Self::from_bytes(&extend(data))
+    }
+
+    /// Return size of the device
+    ///
+    /// Return the size of the device in bytes, decoding the granularity of
+    /// `size` and falling back to `extended_size` if required. `None` is
+    /// returned if no device is installed or its size is unknown.
+    pub fn size_bytes(&self) -> Option<u64> {
+        let size = int::ForeignEndian::to_native(self.size);
+        match size {
+            0 | 0xffff => None,
+            0x7fff => {
+                let ext = int::ForeignEndian::to_native(self.extended_size) & 0x7fff_ffff;
+                Some((ext as u64) << 20)
+            },
+            _ if size & 0x8000 != 0 => Some(((size & 0x7fff) as u64) << 10),
+            _ => Some((size as u64) << 20),
+        }
+    }
+}
+
+impl<'data> Structure<'data> {
+    /// Parse a structure
+    ///
+    /// Parse the structure at the start of `data`, including its string-set.
+    /// `None` is returned if the data is truncated, or the length of the
+    /// formatted area is smaller than the header.
+    pub fn parse(data: &'data [u8]) -> Option<Self> {
+        let header = Header::from_bytes(data.get(..4)?.try_into().ok()?);
+        let length = header.length as usize;
+        if length < size_of::<Header>() {
+            return None;
+        }
+
+        let formatted = data.get(..length)?;
+        let rest = &data[length..];
+        let end = if rest.get(..2)? == [0, 0] {
+            0
+        } else {
+            rest.windows(2).position(|v| v == [0, 0])? + 1
+        };
+
+        Some(Self {
+            structure_type: header.structure_type,
+            handle: int::ForeignEndian::to_native(header.handle),
+            formatted,
+            strings: &rest[..end],
+            size: length + end + if end == 0 { 2 } else { 1 },
+        })
+    }
+
+    /// Iterate strings
+    ///
+    /// Create an iterator over the strings of the string-set.
+    pub fn strings(&self) -> Strings<'data> {
+        Strings::new(self.strings)
+    }
+
+    /// Look up a string
+    ///
+    /// Return the string with the given 1-based index, as stored in the
+    /// formatted area. `None` is returned for index 0, or if the string-set
+    /// has fewer strings.
+    pub fn string(&self, index: u8) -> Option<&'data [u8]> {
+        self.strings().nth((index as usize).checked_sub(1)?)
+    }
+}
+
+impl<'data> Strings<'data> {
+    /// Create string iterator
+    ///
+    /// Create a new iterator over the strings of a string-set. The data must
+    /// not include the terminating NUL byte of the string-set.
+    pub fn new(data: &'data [u8]) -> Self {
+        Self {
+            data,
+        }
+    }
+}
+
+impl<'data> Iterator for Strings<'data> {
+    type Item = &'data [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.data.is_empty() {
+            return None;
+        }
+
+        let end = self.data.iter().position(|v| *v == 0).unwrap_or(self.data.len());
+        let v = &self.data[..end];
+        self.data = self.data.get(end + 1..).unwrap_or(&[]);
+        Some(v)
+    }
+}
+
+impl<'data> Structures<'data> {
+    /// Create structure iterator
+    ///
+    /// Create a new iterator over the structures of a structure table.
+    pub fn new(data: &'data [u8]) -> Self {
+        Self {
+            data,
+            done: false,
+        }
+    }
+
+    /// Return remaining data
+    ///
+    /// Return the data that has not been consumed by the iterator.
+    pub fn remainder(&self) -> &'data [u8] {
+        self.data
+    }
+}
+
+impl<'data> Iterator for Structures<'data> {
+    type Item = Structure<'data>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let Some(v) = Structure::parse(self.data) else {
+            self.done = true;
+            return None;
+        };
+
+        self.data = &self.data[v.size..];
+        self.done = v.structure_type == TYPE_END_OF_TABLE;
+        Some(v)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::specs::int::ForeignEndian;
+
+    // SMBIOS 3.0 entry point, with valid checksum.
+    const ENTRY_POINT: [u8; 24] = [
+        0x5f, 0x53, 0x4d, 0x33, 0x5f, 0x01, 0x18, 0x03,
+        0x06, 0x00, 0x01, 0x00, 0x00, 0x02, 0x00, 0x00,
+        0x00, 0x60, 0x6b, 0x7f, 0x00, 0x00, 0x00, 0x00,
+    ];
+
+    // Structure table with BIOS information, a 32 GiB memory device using
+    // the extended size (in a short, pre-3.3 layout), and the end-of-table marker.
+    const TABLE: [u8; 102] = [
+        0x00, 0x1a, 0x00, 0x00, 0x01, 0x02, 0x00, 0xe8,
+        0x03, 0xff, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x03, 0x0d, 0x01, 0x02, 0xff, 0xff,
+        0x10, 0x00, 0x56, 0x65, 0x6e, 0x64, 0x6f, 0x72,
+        0x00, 0x31, 0x2e, 0x32, 0x00, 0x30, 0x31, 0x2f,
+        0x30, 0x32, 0x2f, 0x32, 0x30, 0x32, 0x34, 0x00,
+        0x00, 0x11, 0x20, 0x00, 0x11, 0x00, 0x10, 0xfe,
+        0xff, 0x40, 0x00, 0x40, 0x00, 0xff, 0x7f, 0x09,
+        0x00, 0x01, 0x02, 0x1a, 0x80, 0x00, 0x80, 0x0c,
+        0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x80, 0x00,
+        0x00, 0x44, 0x49, 0x4d, 0x4d, 0x20, 0x30, 0x00,
+        0x42, 0x41, 0x4e, 0x4b, 0x20, 0x30, 0x00, 0x00,
+        0x7f, 0x04, 0xff, 0xfe, 0x00, 0x00,
+    ];
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<EntryPoint>(), 31);
+        assert_eq!(size_of::<EntryPoint3>(), 24);
+        assert_eq!(size_of::<Header>(), 4);
+        assert_eq!(size_of::<BiosInformation>(), 26);
+        assert_eq!(size_of::<SystemInformation>(), 27);
+        assert_eq!(size_of::<BaseboardInformation>(), 15);
+        assert_eq!(size_of::<SystemEnclosure>(), 21);
+        assert_eq!(size_of::<ProcessorInformation>(), 50);
+        assert_eq!(size_of::<PhysicalMemoryArray>(), 23);
+        assert_eq!(size_of::<MemoryDevice>(), 92);
+    }
+
+    // Verify the 64-bit entry point and its checksum.
+    #[test]
+    fn verify_entry_point() {
+        assert!(EntryPoint3::is_valid(&ENTRY_POINT));
+        assert!(!EntryPoint3::is_valid(&ENTRY_POINT[..23]));
+        assert!(!EntryPoint::is_valid(&ENTRY_POINT));
+
+        let ep = EntryPoint3::from_bytes(&ENTRY_POINT);
+        assert_eq!(ep.major_version, 3);
+        assert_eq!(ep.minor_version, 6);
+        assert_eq!(ep.table_max_size.to_native(), 0x200);
+        assert_eq!(ep.table_address.to_native(), 0x7f6b6000);
+
+        let mut v = ENTRY_POINT;
+        v[12] ^= 0x01;
+        assert!(!EntryPoint3::is_valid(&v));
+    }
+
+    // Walk the structure table, decode short structures, and look up their
+    // strings.
+    #[test]
+    fn verify_structures() {
+        let mut it = Structures::new(&TABLE);
+
+        let s = it.next().unwrap();
+        assert_eq!(s.structure_type, TYPE_BIOS_INFORMATION);
+        assert_eq!(s.handle, 0);
+        assert_eq!(s.string(0), None);
+        assert_eq!(s.string(1), Some(&b"Vendor"[..]));
+        assert_eq!(s.string(3), Some(&b"01/02/2024"[..]));
+        assert_eq!(s.string(4), None);
+        let v = BiosInformation::from_structure(s.formatted);
+        assert_eq!(v.bios_starting_segment.to_native(), 0xe800);
+        assert_eq!(v.bios_characteristics.to_native(), BIOS_CHARACTERISTICS_NOT_SUPPORTED);
+        assert_eq!(v.bios_characteristics_extension[1] & BIOS_EXT2_UEFI, BIOS_EXT2_UEFI);
+        assert_eq!(v.extended_bios_rom_size.to_native(), 16);
+
+        let s = it.next().unwrap();
+        assert_eq!(s.structure_type, TYPE_MEMORY_DEVICE);
+        assert_eq!(s.handle, 0x1100);
+        assert_eq!(s.strings().count(), 2);
+        let v = MemoryDevice::from_structure(s.formatted);
+        assert_eq!(s.string(v.device_locator), Some(&b"DIMM 0"[..]));
+        assert_eq!(s.string(v.bank_locator), Some(&b"BANK 0"[..]));
+        assert_eq!(s.string(v.manufacturer), None);
+        assert_eq!(v.memory_type, MEMORY_TYPE_DDR4);
+        assert_eq!(v.speed.to_native(), 3200);
+        assert_eq!(v.size_bytes(), Some(32 << 30));
+        assert_eq!(v.configured_memory_speed.to_native(), 0);
+
+        let s = it.next().unwrap();
+        assert_eq!(s.structure_type, TYPE_END_OF_TABLE);
+        assert_eq!(s.strings().count(), 0);
+        assert_eq!(s.size, 6);
+
+        assert!(it.next().is_none());
+        assert!(it.remainder().is_empty());
+
+        // A missing string-set terminator stops the iteration.
+        let mut it = Structures::new(&TABLE[..40]);
+        assert!(it.next().is_none());
+        assert_eq!(it.remainder().len(), 40);
+    }
+}
@@ -0,0 +1,686 @@
+//! SQLite Database File Format
+//!
+//! An SQLite database file is a sequence of fixed-size pages, numbered
+//! from 1. The first page starts with the 100-byte database header, which
+//! gives the page size and other global parameters (see `Header`). Tables
+//! and indices are stored as b-trees, where each page starts with a b-tree
+//! page header (see `PageHeader`), directly followed by an array of 2-byte
+//! offsets of the cells on the page. Cells are stored at the end of the
+//! page, growing towards the cell pointer array. On the first page, the
+//! b-tree page header follows the database header.
+//!
+//! Table b-trees store rows in their leaf cells, keyed by a 64-bit rowid.
+//! Index b-trees store keys only. Payloads that do not fit into a cell
+//! continue on a linked list of overflow pages (see `local_payload_size()`).
+//! Row data and index keys are encoded as records, which start with a
+//! header of serial types (`SERIAL_*`), followed by the column values (see
+//! `Record`).
+//!
+//! SQLite uses its own variable-length integer format, with big-endian
+//! groups of 7 bits, except for the 9th byte which carries 8 bits (see
+//! `varint_decode()`). All multi-byte integers are encoded as big-endian.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::int;
+
+/// Magic Signature
+///
+/// The initial 16 bytes of every database file ("SQLite format 3\0").
+pub const MAGIC: [u8; 16] = [
+    0x53, 0x51, 0x4c, 0x69, 0x74, 0x65, 0x20, 0x66,
+    0x6f, 0x72, 0x6d, 0x61, 0x74, 0x20, 0x33, 0x00,
+];
+
+pub const HEADER_SIZE: usize = 100;
+
+pub const PAGE_SIZE_MIN: u32 = 512;
+pub const PAGE_SIZE_MAX: u32 = 65536;
+pub const USABLE_SIZE_MIN: u32 = 480;
+
+/// Encoding of `PAGE_SIZE_MAX` in the Header
+pub const PAGE_SIZE_65536: u16 = 1;
+
+pub const VERSION_LEGACY: u8 = 1;
+pub const VERSION_WAL: u8 = 2;
+
+pub const PAYLOAD_FRACTION_MAX: u8 = 64;
+pub const PAYLOAD_FRACTION_MIN: u8 = 32;
+pub const PAYLOAD_FRACTION_LEAF: u8 = 32;
+
+pub const TEXT_ENCODING_UTF8: u32 = 1;
+pub const TEXT_ENCODING_UTF16LE: u32 = 2;
+pub const TEXT_ENCODING_UTF16BE: u32 = 3;
+
+pub const SCHEMA_FORMAT_1: u32 = 1;
+pub const SCHEMA_FORMAT_2: u32 = 2;
+pub const SCHEMA_FORMAT_3: u32 = 3;
+pub const SCHEMA_FORMAT_4: u32 = 4;
+
+pub const PAGE_INTERIOR_INDEX: u8 = 0x02;
+pub const PAGE_INTERIOR_TABLE: u8 = 0x05;
+pub const PAGE_LEAF_INDEX: u8 = 0x0a;
+pub const PAGE_LEAF_TABLE: u8 = 0x0d;
+
+pub const PAGE_HEADER_LEAF_SIZE: usize = 8;
+pub const PAGE_HEADER_INTERIOR_SIZE: usize = 12;
+
+pub const SERIAL_NULL: u64 = 0;
+pub const SERIAL_I8: u64 = 1;
+pub const SERIAL_I16: u64 = 2;
+pub const SERIAL_I24: u64 = 3;
+pub const SERIAL_I32: u64 = 4;
+pub const SERIAL_I48: u64 = 5;
+pub const SERIAL_I64: u64 = 6;
+pub const SERIAL_F64: u64 = 7;
+pub const SERIAL_ZERO: u64 = 8;
+pub const SERIAL_ONE: u64 = 9;
+/* reserved: 10, 11 */
+pub const SERIAL_BLOB_MIN: u64 = 12;
+pub const SERIAL_TEXT_MIN: u64 = 13;
+
+/// Maximum Size of Varints
+pub const VARINT_MAX: usize = 9;
+
+/// Database Header
+#[repr(C)]
+pub struct Header {
+    /// The static signature identifying the format. This must match `MAGIC`.
+    pub magic: [u8; 16],
+
+    /// Page size in bytes, a power of two between `PAGE_SIZE_MIN` and
+    /// `PAGE_SIZE_MAX`, with the latter encoded as `PAGE_SIZE_65536`.
+    pub page_size: int::u16be,
+
+    /// File format version for writing (`VERSION_*`).
+    pub write_version: u8,
+
+    /// File format version for reading (`VERSION_*`).
+    pub read_version: u8,
+
+    /// Number of bytes reserved at the end of each page, usually 0.
+    pub reserved_space: u8,
+
+    /// Maximum embedded payload fraction. This must be
+    /// `PAYLOAD_FRACTION_MAX`.
+    pub max_payload_fraction: u8,
+
+    /// Minimum embedded payload fraction. This must be
+    /// `PAYLOAD_FRACTION_MIN`.
+    pub min_payload_fraction: u8,
+
+    /// Leaf payload fraction. This must be `PAYLOAD_FRACTION_LEAF`.
+    pub leaf_payload_fraction: u8,
+
+    /// Counter incremented on every change of the file.
+    pub file_change_counter: int::u32be,
+
+    /// Size of the database in pages. This is only valid if
+    /// `version_valid_for` matches `file_change_counter`.
+    pub database_size: int::u32be,
+
+    /// Page number of the first freelist trunk page, or 0.
+    pub first_freelist_trunk: int::u32be,
+
+    /// Total number of freelist pages.
+    pub freelist_count: int::u32be,
+
+    /// Counter incremented on every change of the schema.
+    pub schema_cookie: int::u32be,
+
+    /// Schema format number (`SCHEMA_FORMAT_*`).
+    pub schema_format: int::u32be,
+
+    /// Suggested page cache size.
+    pub default_cache_size: int::u32be,
+
+    /// Page number of the largest root b-tree page in auto-vacuum mode, or
+    /// 0 if auto-vacuum is disabled.
+    pub largest_root_page: int::u32be,
+
+    /// Encoding of all text (`TEXT_ENCODING_*`).
+    pub text_encoding: int::u32be,
+
+    /// Version number chosen by the user.
+    pub user_version: int::u32be,
+
+    /// Non-zero for incremental-vacuum mode.
+    pub incremental_vacuum: int::u32be,
+
+    /// Application id chosen by the user.
+    pub application_id: int::u32be,
+
+    /// Reserved for expansion. This must be zero.
+    pub reserved: [u8; 20],
+
+    /// Value of `file_change_counter` when `sqlite_version` was stored.
+    pub version_valid_for: int::u32be,
+
+    /// Version number of the library that most recently modified the file.
+    pub sqlite_version: int::u32be,
+}
+
+/// B-tree Page Header
+///
+/// Interior pages extend the header with the page number of the right-most
+/// child (see `Page`). Since the header is not padded to its natural
+/// alignment, this structure is packed.
+#[repr(C, packed)]
+pub struct PageHeader {
+    /// Page type (`PAGE_*`).
+    pub page_type: u8,
+
+    /// Offset of the first freeblock on the page, or 0.
+    pub first_freeblock: int::u16be,
+
+    /// Number of cells on the page.
+    pub cell_count: int::u16be,
+
+    /// Offset of the cell content area, with 0 meaning 65536.
+    pub cell_content_start: int::u16be,
+
+    /// Number of fragmented free bytes in the cell content area.
+    pub fragmented_bytes: u8,
+}
+
+/// B-tree Page Reference
+#[derive(Clone, Copy, Debug)]
+pub struct Page<'data> {
+    /// Page data, including the database header on the first page.
+    pub data: &'data [u8],
+
+    /// Offset of the page header.
+    pub offset: usize,
+
+    /// Page type (`PAGE_*`).
+    pub page_type: u8,
+
+    /// Number of cells on the page.
+    pub cell_count: u16,
+
+    /// Page number of the right-most child of interior pages.
+    pub right_pointer: Option<u32>,
+}
+
+/// Cell Reference
+#[derive(Clone, Copy, Debug)]
+pub struct Cell<'data> {
+    /// Page number of the left child of interior pages.
+    pub left_child: Option<u32>,
+
+    /// Rowid of table pages.
+    pub rowid: Option<i64>,
+
+    /// Total size of the payload, or 0 on interior table pages.
+    pub payload_size: u64,
+
+    /// Part of the payload stored in the cell.
+    pub payload: &'data [u8],
+
+    /// Page number of the first overflow page, if the payload overflows.
+    pub overflow: Option<u32>,
+}
+
+/// Record Reference
+#[derive(Clone, Copy, Debug)]
+pub struct Record<'data> {
+    /// Serial types of the columns, as varints.
+    pub header: &'data [u8],
+
+    /// Column values.
+    pub body: &'data [u8],
+}
+
+/// Column Value
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Value<'data> {
+    Null,
+    Integer(i64),
+    Float(f64),
+    Blob(&'data [u8]),
+    Text(&'data [u8]),
+}
+
+/// Column Iterator
+///
+/// Iterate over the values of a record. Iteration stops at the first
+/// truncated or invalid value.
+#[derive(Clone, Debug)]
+pub struct Columns<'data> {
+    header: &'data [u8],
+    body: &'data [u8],
+}
+
+/// Decode varint
+///
+/// Decode the varint at the start of `data`. Return the value and the
+/// number of bytes consumed, or `None` if the data is truncated.
+pub fn varint_decode(data: &[u8]) -> Option<(u64, usize)> {
+    let mut v: u64 = 0;
+
+    for i in 0..VARINT_MAX - 1 {
+        let b = *data.get(i)?;
+        v = (v << 7) | (b & 0x7f) as u64;
+        if b & 0x80 == 0 {
+            return Some((v, i + 1));
+        }
+    }
+
+    Some(((v << 8) | *data.get(VARINT_MAX - 1)? as u64, VARINT_MAX))
+}
+
+/// Encode varint
+///
+/// Encode `v` as varint. Return the number of bytes written, or `None` if
+/// the buffer is too small.
+pub fn varint_encode(v: u64, out: &mut [u8]) -> Option<usize> {
+    if v >> 56 != 0 {
+        let out = out.get_mut(..VARINT_MAX)?;
+        out[VARINT_MAX - 1] = v as u8;
+        let mut w = v >> 8;
+        for b in out[..VARINT_MAX - 1].iter_mut().rev() {
+            *b = 0x80 | (w & 0x7f) as u8;
+            w >>= 7;
+        }
+        return Some(VARINT_MAX);
+    }
+
+    let mut buf = [0; VARINT_MAX];
+    let mut i = buf.len() - 1;
+    let mut w = v >> 7;
+
+    buf[i] = (v & 0x7f) as u8;
+    while w > 0 {
+        i -= 1;
+        buf[i] = 0x80 | (w & 0x7f) as u8;
+        w >>= 7;
+    }
+
+    let n = buf.len() - i;
+    out.get_mut(..n)?.copy_from_slice(&buf[i..]);
+    Some(n)
+}
+
+/// Return size of serial type
+///
+/// Return the size of a value of the given serial type in the body of a
+/// record, or `None` for reserved serial types.
+pub fn serial_type_size(serial_type: u64) -> Option<u64> {
+    match serial_type {
+        SERIAL_NULL | SERIAL_ZERO | SERIAL_ONE => Some(0),
+        SERIAL_I8..=SERIAL_I32 => Some(serial_type),
+        SERIAL_I48 => Some(6),
+        SERIAL_I64 | SERIAL_F64 => Some(8),
+        10 | 11 => None,
+        _ => Some((serial_type - SERIAL_BLOB_MIN) / 2),
+    }
+}
+
+/// Return local payload size
+///
+/// Return the number of payload bytes stored in a cell on a page of the
+/// given type, for a payload of `payload_size` bytes and pages with
+/// `usable_size` bytes (the page size minus the reserved space). Any
+/// remaining bytes are stored on overflow pages. Usable sizes below
+/// `USABLE_SIZE_MIN` are treated as `USABLE_SIZE_MIN`.
+pub fn local_payload_size(page_type: u8, usable_size: u32, payload_size: u64) -> usize {
+    let u = usable_size.max(USABLE_SIZE_MIN) as u64;
+    let x = match page_type {
+        PAGE_LEAF_TABLE => u - 35,
+        _ => ((u - 12) * 64 / 255) - 23,
+    };
+    if payload_size <= x {
+        return payload_size as usize;
+    }
+
+    let m = ((u - 12) * 32 / 255) - 23;
+    let k = m + ((payload_size - m) % (u - 4));
+    if k <= x { k as usize } else { m as usize }
+}
+
+impl Header {
+    /// Import a header from a byte slice
+    ///
+    /// Create a new header from data copied from a byte slice. No byte-order
+    /// conversions are applied.
+    pub fn from_bytes(data: &[u8; 100]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 100], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Return page size
+    ///
+    /// Return the page size in bytes, or `None` if it is invalid.
+    pub fn page_size(&self) -> Option<u32> {
+        let v = match int::ForeignEndian::to_native(self.page_size) {
+            PAGE_SIZE_65536 => PAGE_SIZE_MAX,
+            v => v as u32,
+        };
+
+        match v.is_power_of_two() && (PAGE_SIZE_MIN..=PAGE_SIZE_MAX).contains(&v) {
+            true => Some(v),
+            false => None,
+        }
+    }
+
+    /// Return usable page size
+    ///
+    /// Return the number of usable bytes of each page, excluding the
+    /// reserved space, or `None` if the page size is invalid.
+    pub fn usable_size(&self) -> Option<u32> {
+        self.page_size()?.checked_sub(self.reserved_space as u32)
+    }
+}
+
+impl PageHeader {
+    /// Import a header from a byte slice
+    ///
+    /// Create a new header from data copied from a byte slice. No byte-order
+    /// conversions are applied.
+    pub fn from_bytes(data: &[u8; 8]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 8], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl<'data> Page<'data> {
+    /// Parse b-tree page
+    ///
+    /// Parse the page `data` with the page header at `offset`, which is
+    /// `HEADER_SIZE` on the first page, and 0 otherwise. Return `None` if
+    /// the page type is invalid, or the header or cell pointer array is
+    /// truncated.
+    pub fn parse(data: &'data [u8], offset: usize) -> Option<Self> {
+        let h = PageHeader::from_bytes(data.get(offset..offset + 8)?.try_into().ok()?);
+
+        let right_pointer = match h.page_type {
+            PAGE_INTERIOR_INDEX | PAGE_INTERIOR_TABLE => {
+                let v = data.get(offset + 8..offset + PAGE_HEADER_INTERIOR_SIZE)?;
+                Some(u32::from_be_bytes(v.try_into().ok()?))
+            },
+            PAGE_LEAF_INDEX | PAGE_LEAF_TABLE => None,
+            _ => return None,
+        };
+
+        let page = Self {
+            data,
+            offset,
+            page_type: h.page_type,
+            cell_count: int::ForeignEndian::to_native(h.cell_count),
+            right_pointer,
+        };
+
+        let end = page.header_size() + page.cell_count as usize * 2;
+        data.get(..offset + end)?;
+        Some(page)
+    }
+
+    /// Return size of the page header
+    pub fn header_size(&self) -> usize {
+        match self.right_pointer {
+            Some(_) => PAGE_HEADER_INTERIOR_SIZE,
+            None => PAGE_HEADER_LEAF_SIZE,
+        }
+    }
+
+    /// Return cell offset
+    ///
+    /// Return the offset in the page of the cell at index `i` of the cell
+    /// pointer array.
+    pub fn cell_offset(&self, i: u16) -> Option<usize> {
+        if i >= self.cell_count {
+            return None;
+        }
+
+        let p = self.offset + self.header_size() + i as usize * 2;
+        Some(u16::from_be_bytes([self.data[p], self.data[p + 1]]) as usize)
+    }
+
+    /// Parse cell
+    ///
+    /// Parse the cell at index `i` of the cell pointer array, on a page
+    /// with `usable_size` usable bytes. See `Cell::parse()` for details.
+    pub fn cell(&self, i: u16, usable_size: u32) -> Option<Cell<'data>> {
+        let data = self.data.get(self.cell_offset(i)?..)?;
+        Cell::parse(self.page_type, data, usable_size)
+    }
+}
+
+impl<'data> Cell<'data> {
+    /// Parse cell
+    ///
+    /// Parse the cell at the start of `data`, on a page of the given type
+    /// with `usable_size` usable bytes. Return `None` if the cell is
+    /// truncated or the page type is invalid.
+    pub fn parse(page_type: u8, data: &'data [u8], usable_size: u32) -> Option<Self> {
+        let mut o = 0;
+
+        let left_child = match page_type {
+            PAGE_INTERIOR_INDEX | PAGE_INTERIOR_TABLE => {
+                o = 4;
+                Some(u32::from_be_bytes(data.get(..4)?.try_into().ok()?))
+            },
+            PAGE_LEAF_INDEX | PAGE_LEAF_TABLE => None,
+            _ => return None,
+        };
+
+        let payload_size = match page_type {
+            PAGE_INTERIOR_TABLE => 0,
+            _ => {
+                let (v, n) = varint_decode(&data[o..])?;
+                o += n;
+                v
+            },
+        };
+
+        let rowid = match page_type {
+            PAGE_INTERIOR_TABLE | PAGE_LEAF_TABLE => {
+                let (v, n) = varint_decode(&data[o..])?;
+                o += n;
+                Some(v as i64)
+            },
+            _ => None,
+        };
+
+        let local = local_payload_size(page_type, usable_size, payload_size);
+        let payload = data.get(o..o.checked_add(local)?)?;
+        let overflow = match local as u64 == payload_size {
+            true => None,
+            false => {
+                let v = data.get(o + local..o + local + 4)?;
+                Some(u32::from_be_bytes(v.try_into().ok()?))
+            },
+        };
+
+        Some(Self { left_child, rowid, payload_size, payload, overflow })
+    }
+}
+
+impl<'data> Record<'data> {
+    /// Parse record
+    ///
+    /// Parse the record `data`, which starts with the size of the header as
+    /// varint, including the size itself. Return `None` if the header is
+    /// truncated.
+    pub fn parse(data: &'data [u8]) -> Option<Self> {
+        let (size, n) = varint_decode(data)?;
+        let size = usize::try_from(size).ok()?;
+        if size < n {
+            return None;
+        }
+
+        Some(Self { header: data.get(n..size)?, body: &data[size..] })
+    }
+
+    /// Return column iterator
+    pub fn columns(&self) -> Columns<'data> {
+        Columns::new(self.header, self.body)
+    }
+}
+
+impl<'data> Value<'data> {
+    /// Decode value
+    ///
+    /// Decode the value of the given serial type at the start of `data`.
+    /// Return `None` if the value is truncated or the serial type is
+    /// reserved.
+    pub fn decode(serial_type: u64, data: &'data [u8]) -> Option<Self> {
+        let n = usize::try_from(serial_type_size(serial_type)?).ok()?;
+        let v = data.get(..n)?;
+
+        let value = match serial_type {
+            SERIAL_NULL => Value::Null,
+            SERIAL_ZERO => Value::Integer(0),
+            SERIAL_ONE => Value::Integer(1),
+            SERIAL_I8..=SERIAL_I64 => {
+                let mut i: i64 = if v[0] & 0x80 != 0 { -1 } else { 0 };
+                for b in v {
+                    i = (i << 8) | *b as i64;
+                }
+                Value::Integer(i)
+            },
+            SERIAL_F64 => Value::Float(f64::from_be_bytes(v.try_into().ok()?)),
+            _ if serial_type.is_multiple_of(2) => Value::Blob(v),
+            _ => Value::Text(v),
+        };
+
+        Some(value)
+    }
+}
+
+impl<'data> Columns<'data> {
+    /// Create column iterator
+    ///
+    /// Create a new iterator over the values in `body`, with the serial types
+    /// in `header`.
+    pub fn new(header: &'data [u8], body: &'data [u8]) -> Self {
+        Self { header, body }
+    }
+
+    /// Return remaining data
+    ///
+    /// Return the header and body data that has not been consumed by the
+    /// iterator.
+    pub fn remainder(&self) -> (&'data [u8], &'data [u8]) {
+        (self.header, self.body)
+    }
+}
+
+impl<'data> Iterator for Columns<'data> {
+    type Item = Value<'data>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (t, n) = varint_decode(self.header)?;
+        let v = Value::decode(t, self.body)?;
+        let m = serial_type_size(t)? as usize;
+
+        self.header = &self.header[n..];
+        self.body = &self.body[m..];
+        Some(v)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::specs::int::ForeignEndian;
+
+    // Header of a database with 512-byte pages and a single table.
+    const HEADER: [u8; 100] = [
+        0x53, 0x51, 0x4c, 0x69, 0x74, 0x65, 0x20, 0x66,
+        0x6f, 0x72, 0x6d, 0x61, 0x74, 0x20, 0x33, 0x00,
+        0x02, 0x00, 0x01, 0x01, 0x00, 0x40, 0x20, 0x20,
+        0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x02,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x04,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02,
+        0x00, 0x2e, 0x63, 0x01,
+    ];
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<Header>(), 100);
+        assert_eq!(align_of::<Header>(), 4);
+        assert_eq!(size_of::<PageHeader>(), 8);
+        assert_eq!(align_of::<PageHeader>(), 1);
+    }
+
+    // Parse the database header and the leaf page of the table, with the
+    // row `(1, 'hi')`.
+    #[test]
+    fn verify_database() {
+        let h = Header::from_bytes(&HEADER);
+        assert_eq!(h.magic, MAGIC);
+        assert_eq!(h.page_size(), Some(512));
+        assert_eq!(h.usable_size(), Some(512));
+        assert_eq!(h.write_version, VERSION_LEGACY);
+        assert_eq!(h.database_size.to_native(), 2);
+        assert_eq!(h.schema_format.to_native(), SCHEMA_FORMAT_4);
+        assert_eq!(h.text_encoding.to_native(), TEXT_ENCODING_UTF8);
+        assert_eq!(h.sqlite_version.to_native(), 3040001);
+
+        let mut data = [0; 512];
+        data[..10].copy_from_slice(&[0x0d, 0x00, 0x00, 0x00, 0x01, 0x01, 0xf9, 0x00, 0x01, 0xf9]);
+        data[505..].copy_from_slice(&[0x05, 0x01, 0x03, 0x09, 0x11, 0x68, 0x69]);
+
+        let p = Page::parse(&data, 0).unwrap();
+        assert_eq!((p.page_type, p.cell_count, p.right_pointer), (PAGE_LEAF_TABLE, 1, None));
+        assert_eq!(p.cell_offset(0), Some(505));
+        assert!(p.cell_offset(1).is_none());
+
+        let c = p.cell(0, 512).unwrap();
+        assert_eq!((c.rowid, c.payload_size, c.overflow), (Some(1), 5, None));
+        let mut v = Record::parse(c.payload).unwrap().columns();
+        assert_eq!(v.next(), Some(Value::Integer(1)));
+        assert_eq!(v.next(), Some(Value::Text(b"hi")));
+        assert_eq!(v.next(), None);
+
+        assert_eq!(local_payload_size(PAGE_LEAF_TABLE, 512, 477), 477);
+        assert_eq!(local_payload_size(PAGE_LEAF_TABLE, 512, 478), 39);
+        assert_eq!(local_payload_size(PAGE_LEAF_INDEX, 512, 1000), 39);
+    }
+
+    // Encode and decode varints and values.
+    #[test]
+    fn verify_encodings() {
+        let mut buf = [0; VARINT_MAX];
+
+        for (v, n) in [(0, 1), (0x7f, 1), (0x80, 2), (0x3fff, 2), (1 << 56, 9), (u64::MAX, 9)] {
+            assert_eq!(varint_encode(v, &mut buf), Some(n));
+            assert_eq!(varint_decode(&buf), Some((v, n)));
+        }
+        assert_eq!(buf, [0xff; 9]);
+        assert_eq!(varint_decode(&[0x81, 0x00]), Some((0x80, 2)));
+        assert_eq!(varint_decode(&[0x81]), None);
+
+        assert_eq!(Value::decode(SERIAL_I24, &[0xff, 0xff, 0xfe]), Some(Value::Integer(-2)));
+        assert_eq!(Value::decode(SERIAL_F64, &1.5f64.to_be_bytes()), Some(Value::Float(1.5)));
+        assert_eq!(Value::decode(14, &[0xab]), Some(Value::Blob(&[0xab])));
+        assert_eq!(Value::decode(10, &[]), None);
+        assert_eq!(Value::decode(SERIAL_I32, &[0; 3]), None);
+    }
+}
@@ -0,0 +1,406 @@
+//! Secure Shell Transport Layer Protocol
+//!
+//! SSH connections start with an identification string from both sides
+//! (`IDENTIFICATION_PREFIX`), terminated by CR LF. Afterwards, all data is
+//! sent as binary packets, each starting with a 5-byte `PacketHeader`
+//! carrying the packet length and the padding length, followed by the
+//! payload, random padding, and a MAC. Once keys are negotiated, everything
+//! but the MAC is encrypted (or, with some ciphers, everything but the
+//! packet length). The payload starts with a message number (`MSG_*`).
+//!
+//! Messages are built from a few wire types: unsigned 32-bit integers,
+//! booleans as single bytes, strings with a 32-bit length prefix (see
+//! `decode_string()`), multiple-precision integers encoded as strings in
+//! two's complement (see `decode_mpint()`), and comma-separated name-lists
+//! encoded as strings (see `NameList`). Key exchange starts with a KEXINIT
+//! message from both sides (see `KexInit`).
+//!
+//! All multi-byte integers are encoded as big-endian.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::int;
+
+pub const PACKET_HEADER_SIZE: usize = 5;
+pub const COOKIE_SIZE: usize = 16;
+pub const PADDING_MIN: usize = 4;
+pub const BLOCK_SIZE_MIN: usize = 8;
+
+/// Maximum Packet Size All Implementations Must Accept
+pub const PACKET_SIZE_MAX: usize = 35000;
+
+pub const IDENTIFICATION_PREFIX: &[u8; 8] = b"SSH-2.0-";
+pub const IDENTIFICATION_MAX: usize = 255;
+
+pub const MSG_DISCONNECT: u8 = 1;
+pub const MSG_IGNORE: u8 = 2;
+pub const MSG_UNIMPLEMENTED: u8 = 3;
+pub const MSG_DEBUG: u8 = 4;
+pub const MSG_SERVICE_REQUEST: u8 = 5;
+pub const MSG_SERVICE_ACCEPT: u8 = 6;
+pub const MSG_EXT_INFO: u8 = 7;
+pub const MSG_KEXINIT: u8 = 20;
+pub const MSG_NEWKEYS: u8 = 21;
+pub const MSG_KEX_ECDH_INIT: u8 = 30;
+pub const MSG_KEX_ECDH_REPLY: u8 = 31;
+pub const MSG_USERAUTH_REQUEST: u8 = 50;
+pub const MSG_USERAUTH_FAILURE: u8 = 51;
+pub const MSG_USERAUTH_SUCCESS: u8 = 52;
+pub const MSG_USERAUTH_BANNER: u8 = 53;
+pub const MSG_USERAUTH_PK_OK: u8 = 60;
+pub const MSG_GLOBAL_REQUEST: u8 = 80;
+pub const MSG_REQUEST_SUCCESS: u8 = 81;
+pub const MSG_REQUEST_FAILURE: u8 = 82;
+pub const MSG_CHANNEL_OPEN: u8 = 90;
+pub const MSG_CHANNEL_OPEN_CONFIRMATION: u8 = 91;
+pub const MSG_CHANNEL_OPEN_FAILURE: u8 = 92;
+pub const MSG_CHANNEL_WINDOW_ADJUST: u8 = 93;
+pub const MSG_CHANNEL_DATA: u8 = 94;
+pub const MSG_CHANNEL_EXTENDED_DATA: u8 = 95;
+pub const MSG_CHANNEL_EOF: u8 = 96;
+pub const MSG_CHANNEL_CLOSE: u8 = 97;
+pub const MSG_CHANNEL_REQUEST: u8 = 98;
+pub const MSG_CHANNEL_SUCCESS: u8 = 99;
+pub const MSG_CHANNEL_FAILURE: u8 = 100;
+
+pub const DISCONNECT_HOST_NOT_ALLOWED_TO_CONNECT: u32 = 1;
+pub const DISCONNECT_PROTOCOL_ERROR: u32 = 2;
+pub const DISCONNECT_KEY_EXCHANGE_FAILED: u32 = 3;
+pub const DISCONNECT_RESERVED: u32 = 4;
+pub const DISCONNECT_MAC_ERROR: u32 = 5;
+pub const DISCONNECT_COMPRESSION_ERROR: u32 = 6;
+pub const DISCONNECT_SERVICE_NOT_AVAILABLE: u32 = 7;
+pub const DISCONNECT_PROTOCOL_VERSION_NOT_SUPPORTED: u32 = 8;
+pub const DISCONNECT_HOST_KEY_NOT_VERIFIABLE: u32 = 9;
+pub const DISCONNECT_CONNECTION_LOST: u32 = 10;
+pub const DISCONNECT_BY_APPLICATION: u32 = 11;
+pub const DISCONNECT_TOO_MANY_CONNECTIONS: u32 = 12;
+pub const DISCONNECT_AUTH_CANCELLED_BY_USER: u32 = 13;
+pub const DISCONNECT_NO_MORE_AUTH_METHODS_AVAILABLE: u32 = 14;
+pub const DISCONNECT_ILLEGAL_USER_NAME: u32 = 15;
+
+/// Binary Packet Header
+///
+/// Since the size of the header is not a multiple of the alignment of
+/// `packet_length`, this structure is packed.
+#[repr(C, packed)]
+pub struct PacketHeader {
+    /// Size of the packet in bytes, excluding this field and the MAC.
+    pub packet_length: int::u32be,
+
+    /// Size of the random padding in bytes.
+    pub padding_length: u8,
+}
+
+/// Binary Packet Reference
+///
+/// A decrypted binary packet with its payload and padding located.
+#[derive(Clone, Copy, Debug)]
+pub struct Packet<'data> {
+    /// Payload, starting with the message number.
+    pub payload: &'data [u8],
+
+    /// Random padding.
+    pub padding: &'data [u8],
+
+    /// Size of the packet in bytes, after which the MAC follows.
+    pub size: usize,
+}
+
+/// KEXINIT Reference
+///
+/// A KEXINIT message with its name-lists located (see `NameList`). Lists
+/// of algorithms are given in order of preference.
+#[derive(Clone, Copy, Debug)]
+pub struct KexInit<'data> {
+    /// Random cookie.
+    pub cookie: &'data [u8; 16],
+
+    /// Key exchange algorithms.
+    pub kex_algorithms: &'data [u8],
+
+    /// Host key algorithms.
+    pub server_host_key_algorithms: &'data [u8],
+
+    /// Encryption algorithms from client to server.
+    pub encryption_client_to_server: &'data [u8],
+
+    /// Encryption algorithms from server to client.
+    pub encryption_server_to_client: &'data [u8],
+
+    /// MAC algorithms from client to server.
+    pub mac_client_to_server: &'data [u8],
+
+    /// MAC algorithms from server to client.
+    pub mac_server_to_client: &'data [u8],
+
+    /// Compression algorithms from client to server.
+    pub compression_client_to_server: &'data [u8],
+
+    /// Compression algorithms from server to client.
+    pub compression_server_to_client: &'data [u8],
+
+    /// Languages from client to server.
+    pub languages_client_to_server: &'data [u8],
+
+    /// Languages from server to client.
+    pub languages_server_to_client: &'data [u8],
+
+    /// Whether a guessed key exchange packet follows.
+    pub first_kex_packet_follows: bool,
+
+    /// Reserved, must be 0.
+    pub reserved: u32,
+}
+
+/// Name-list Iterator
+///
+/// An iterator over the names of a name-list, without the length prefix.
+/// An empty list yields no names.
+#[derive(Clone, Debug)]
+pub struct NameList<'data> {
+    data: &'data [u8],
+    done: bool,
+}
+
+impl PacketHeader {
+    /// Import a packet header from a byte slice
+    ///
+    /// Create a new packet header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 5]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 5], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl<'data> Packet<'data> {
+    /// Parse a binary packet
+    ///
+    /// Parse the decrypted binary packet at the start of `data`. Return
+    /// `None` if the packet is truncated, or if the padding exceeds the
+    /// packet.
+    pub fn parse(data: &'data [u8]) -> Option<Self> {
+        let h = PacketHeader::from_bytes(data.get(..PACKET_HEADER_SIZE)?.try_into().unwrap());
+        let n = int::ForeignEndian::to_native(h.packet_length) as usize;
+        let padding = h.padding_length as usize;
+        let end = n.checked_add(4)?;
+        if n < 1 + padding || data.len() < end {
+            return None;
+        }
+
+        Some(Self {
+            payload: &data[PACKET_HEADER_SIZE..end - padding],
+            padding: &data[end - padding..end],
+            size: end,
+        })
+    }
+}
+
+impl<'data> KexInit<'data> {
+    /// Parse a KEXINIT message
+    ///
+    /// Parse the payload of a KEXINIT message, starting with the message
+    /// number. Return `None` if the message is truncated or has another
+    /// message number.
+    pub fn parse(payload: &'data [u8]) -> Option<Self> {
+        if *payload.first()? != MSG_KEXINIT {
+            return None;
+        }
+
+        let cookie = payload.get(1..1 + COOKIE_SIZE)?.try_into().unwrap();
+        let mut data = &payload[1 + COOKIE_SIZE..];
+        let mut lists = [&data[..0]; 10];
+        for list in lists.iter_mut() {
+            let (v, n) = decode_string(data)?;
+            *list = v;
+            data = &data[n..];
+        }
+        let trailer = data.get(..5)?;
+
+        Some(Self {
+            cookie,
+            kex_algorithms: lists[0],
+            server_host_key_algorithms: lists[1],
+            encryption_client_to_server: lists[2],
+            encryption_server_to_client: lists[3],
+            mac_client_to_server: lists[4],
+            mac_server_to_client: lists[5],
+            compression_client_to_server: lists[6],
+            compression_server_to_client: lists[7],
+            languages_client_to_server: lists[8],
+            languages_server_to_client: lists[9],
+            first_kex_packet_follows: trailer[0] != 0,
+            reserved: u32::from_be_bytes(trailer[1..].try_into().unwrap()),
+        })
+    }
+}
+
+impl<'data> NameList<'data> {
+    /// Create name-list iterator
+    pub fn new(data: &'data [u8]) -> Self {
+        Self {
+            data,
+            done: data.is_empty(),
+        }
+    }
+}
+
+impl<'data> Iterator for NameList<'data> {
+    type Item = &'data [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        match self.data.iter().position(|v| *v == b',') {
+            Some(i) => {
+                let v = &self.data[..i];
+                self.data = &self.data[i + 1..];
+                Some(v)
+            },
+            None => {
+                self.done = true;
+                Some(self.data)
+            },
+        }
+    }
+}
+
+/// Decode string
+///
+/// Decode the string at the start of `data`. Return the string and the
+/// number of bytes consumed, or `None` if it is truncated.
+pub fn decode_string(data: &[u8]) -> Option<(&[u8], usize)> {
+    let n = u32::from_be_bytes(data.get(..4)?.try_into().unwrap()) as usize;
+    let v = data.get(4..4usize.checked_add(n)?)?;
+    Some((v, 4 + n))
+}
+
+/// Decode multiple-precision integer
+///
+/// Decode the mpint at the start of `data`. Return the two's complement
+/// big-endian value and the number of bytes consumed, or `None` if it is
+/// truncated or not minimally encoded. Zero is encoded as empty value.
+pub fn decode_mpint(data: &[u8]) -> Option<(&[u8], usize)> {
+    let (v, n) = decode_string(data)?;
+    match v {
+        [0x00, b, ..] if b & 0x80 == 0 => None,
+        [0xff, b, ..] if b & 0x80 != 0 => None,
+        [0x00] => None,
+        _ => Some((v, n)),
+    }
+}
+
+/// Check for negative mpints
+///
+/// Return whether a value returned by `decode_mpint()` is negative.
+pub fn is_negative_mpint(value: &[u8]) -> bool {
+    matches!(value.first(), Some(v) if v & 0x80 != 0)
+}
+
+/// Return padding length
+///
+/// Return the size of the padding for a packet with a payload of the given
+/// size, such that the packet (excluding the MAC) is a multiple of the
+/// block size and carries at least `PADDING_MIN` bytes of padding.
+pub fn padding_length(payload: usize, block_size: usize) -> usize {
+    let block_size = block_size.max(BLOCK_SIZE_MIN);
+    let n = PACKET_HEADER_SIZE + payload + PADDING_MIN;
+    PADDING_MIN + (block_size - n % block_size) % block_size
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A packet carrying a KEXINIT message, with 10 bytes of padding.
+    const PACKET: [u8; 112] = [
+        0x00, 0x00, 0x00, 0x6c, 0x0a, 0x14, 0x00, 0x01,
+        0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09,
+        0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f, 0x00, 0x00,
+        0x00, 0x0a, 0x63, 0x75, 0x72, 0x76, 0x65, 0x32,
+        0x35, 0x35, 0x31, 0x39, 0x00, 0x00, 0x00, 0x0b,
+        0x73, 0x73, 0x68, 0x2d, 0x65, 0x64, 0x32, 0x35,
+        0x35, 0x31, 0x39, 0x00, 0x00, 0x00, 0x03, 0x61,
+        0x2c, 0x62, 0x00, 0x00, 0x00, 0x01, 0x61, 0x00,
+        0x00, 0x00, 0x01, 0x6d, 0x00, 0x00, 0x00, 0x01,
+        0x6d, 0x00, 0x00, 0x00, 0x04, 0x6e, 0x6f, 0x6e,
+        0x65, 0x00, 0x00, 0x00, 0x04, 0x6e, 0x6f, 0x6e,
+        0x65, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<PacketHeader>(), PACKET_HEADER_SIZE);
+        assert_eq!(align_of::<PacketHeader>(), 1);
+    }
+
+    // Parse a packet and its KEXINIT payload.
+    #[test]
+    fn verify_kexinit() {
+        let h = PacketHeader::from_bytes(PACKET[..5].try_into().unwrap());
+        assert_eq!(int::ForeignEndian::to_native(h.packet_length) as usize, PACKET.len() - 4);
+        assert_eq!(h.padding_length, 10);
+
+        let p = Packet::parse(&PACKET).unwrap();
+        assert_eq!(p.payload[0], MSG_KEXINIT);
+        assert_eq!(p.padding, &[0; 10]);
+        assert_eq!(p.size, PACKET.len());
+        assert_eq!(padding_length(p.payload.len(), 8), 10);
+        assert!(Packet::parse(&PACKET[..111]).is_none());
+
+        let k = KexInit::parse(p.payload).unwrap();
+        assert_eq!(k.cookie[15], 0x0f);
+        assert_eq!(k.kex_algorithms, b"curve25519");
+        assert_eq!(k.server_host_key_algorithms, b"ssh-ed25519");
+        let mut names = NameList::new(k.encryption_client_to_server);
+        assert_eq!(names.next(), Some(&b"a"[..]));
+        assert_eq!(names.next(), Some(&b"b"[..]));
+        assert_eq!(names.next(), None);
+        assert_eq!(k.compression_server_to_client, b"none");
+        assert_eq!(NameList::new(k.languages_client_to_server).count(), 0);
+        assert!(k.first_kex_packet_follows);
+        assert_eq!(k.reserved, 0);
+        assert!(KexInit::parse(&p.payload[..p.payload.len() - 1]).is_none());
+    }
+
+    // Decode strings and mpints, and compute padding.
+    #[test]
+    fn verify_wire_types() {
+        assert_eq!(decode_string(&[0x00, 0x00, 0x00, 0x02, 0x68, 0x69, 0x21]), Some((&b"hi"[..], 6)));
+        assert!(decode_string(&[0x00, 0x00, 0x00, 0x02, 0x68]).is_none());
+        assert!(decode_string(&[0xff, 0xff, 0xff, 0xff]).is_none());
+
+        // Examples of RFC 4251 section 5.
+        assert_eq!(decode_mpint(&[0x00, 0x00, 0x00, 0x00]), Some((&[][..], 4)));
+        let (v, _) = decode_mpint(&[0x00, 0x00, 0x00, 0x02, 0x00, 0x80]).unwrap();
+        assert!(!is_negative_mpint(v));
+        let (v, _) = decode_mpint(&[0x00, 0x00, 0x00, 0x02, 0xed, 0xcc]).unwrap();
+        assert!(is_negative_mpint(v));
+        assert!(!is_negative_mpint(&[]));
+        assert!(decode_mpint(&[0x00, 0x00, 0x00, 0x02, 0x00, 0x7f]).is_none());
+        assert!(decode_mpint(&[0x00, 0x00, 0x00, 0x02, 0xff, 0x80]).is_none());
+        assert!(decode_mpint(&[0x00, 0x00, 0x00, 0x01, 0x00]).is_none());
+
+        assert_eq!(padding_length(0, 8), 11);
+        assert_eq!(padding_length(7, 16), 4);
+        assert_eq!(padding_length(8, 16), 19);
+        assert_eq!(padding_length(3, 0), 8);
+    }
+}
@@ -0,0 +1,333 @@
+//! Spanning Tree Protocol
+//!
+//! Bridges exchange bridge protocol data units (BPDUs) to compute a
+//! loop-free topology. BPDUs are sent to `GROUP_ADDRESS` in IEEE 802.2 LLC
+//! frames with both service access points set to `ethernet::SAP_STP`, and
+//! start with a 4-byte `Header`. IEEE 802.1D defines configuration BPDUs
+//! (see `Config`) and topology change notification BPDUs, which consist of
+//! the header only. IEEE 802.1w (RSTP) replaces configuration BPDUs with
+//! rapid spanning tree BPDUs (see `Rst`), which use the previously unused
+//! flag bits to carry the port role and state. MSTP extends them with
+//! further data.
+//!
+//! Bridges are identified by an 8-byte `BridgeId`, and lower values take
+//! precedence. Times are given in units of 1/256 seconds.
+//!
+//! All multi-byte integers are encoded as big-endian.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::{ethernet, int};
+
+pub const HEADER_SIZE: usize = 4;
+pub const CONFIG_SIZE: usize = 35;
+pub const RST_SIZE: usize = 36;
+
+pub const GROUP_ADDRESS: [u8; 6] = [0x01, 0x80, 0xc2, 0x00, 0x00, 0x00];
+
+pub const PROTOCOL_ID: u16 = 0;
+
+pub const VERSION_STP: u8 = 0;
+pub const VERSION_RSTP: u8 = 2;
+pub const VERSION_MSTP: u8 = 3;
+
+pub const TYPE_CONFIG: u8 = 0x00;
+pub const TYPE_RST: u8 = 0x02;
+pub const TYPE_TCN: u8 = 0x80;
+
+pub const FLAG_TC: u8 = 0x01;
+pub const FLAG_PROPOSAL: u8 = 0x02;
+pub const FLAG_ROLE_MASK: u8 = 0x0c;
+pub const FLAG_ROLE_SHIFT: u32 = 2;
+pub const FLAG_LEARNING: u8 = 0x10;
+pub const FLAG_FORWARDING: u8 = 0x20;
+pub const FLAG_AGREEMENT: u8 = 0x40;
+pub const FLAG_TCA: u8 = 0x80;
+
+pub const ROLE_UNKNOWN: u8 = 0;
+pub const ROLE_ALTERNATE_BACKUP: u8 = 1;
+pub const ROLE_ROOT: u8 = 2;
+pub const ROLE_DESIGNATED: u8 = 3;
+
+pub const PRIORITY_DEFAULT: u16 = 0x8000;
+pub const PRIORITY_STEP: u16 = 0x1000;
+pub const SYSTEM_ID_MASK: u16 = 0x0fff;
+
+pub const PORT_PRIORITY_DEFAULT: u8 = 0x80;
+pub const PORT_NUMBER_MASK: u16 = 0x0fff;
+
+/// Bridge Identifier
+///
+/// The 4-bit bridge priority, the 12-bit system identifier extension (e.g.,
+/// the VLAN of a per-VLAN spanning tree), and the MAC address of the
+/// bridge. Comparing the raw bytes yields the precedence of bridges.
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+#[repr(transparent)]
+pub struct BridgeId {
+    raw: [u8; 8],
+}
+
+/// BPDU Header
+#[repr(C)]
+pub struct Header {
+    /// Protocol identifier, must be `PROTOCOL_ID`.
+    pub protocol_id: int::u16be,
+
+    /// Protocol version (`VERSION_*`).
+    pub version: u8,
+
+    /// BPDU type (`TYPE_*`).
+    pub bpdu_type: u8,
+}
+
+/// Configuration BPDU
+///
+/// Since the root path cost is not naturally aligned, this structure is
+/// packed.
+#[repr(C, packed)]
+pub struct Config {
+    /// Protocol identifier, must be `PROTOCOL_ID`.
+    pub protocol_id: int::u16be,
+
+    /// Protocol version (`VERSION_*`).
+    pub version: u8,
+
+    /// BPDU type, must be `TYPE_CONFIG`.
+    pub bpdu_type: u8,
+
+    /// Flags (`FLAG_TC` and `FLAG_TCA`).
+    pub flags: u8,
+
+    /// Identifier of the root bridge.
+    pub root_id: BridgeId,
+
+    /// Cost of the path to the root bridge.
+    pub root_path_cost: int::u32be,
+
+    /// Identifier of the sending bridge.
+    pub bridge_id: BridgeId,
+
+    /// Priority and number of the sending port (see `port_id()`).
+    pub port_id: int::u16be,
+
+    /// Age of the information since it was sent by the root bridge.
+    pub message_age: int::u16be,
+
+    /// Age after which the information is discarded.
+    pub max_age: int::u16be,
+
+    /// Interval between configuration BPDUs of the root bridge.
+    pub hello_time: int::u16be,
+
+    /// Time spent in the listening and learning states.
+    pub forward_delay: int::u16be,
+}
+
+/// Rapid Spanning Tree BPDU
+///
+/// The same layout as `Config`, followed by the length of version 1 data,
+/// with all flags in use. Since the root path cost is not naturally aligned,
+/// this structure is packed.
+#[repr(C, packed)]
+pub struct Rst {
+    /// Configuration BPDU fields, with `TYPE_RST` as BPDU type.
+    pub config: Config,
+
+    /// Size of version 1 data in bytes, must be 0.
+    pub version1_length: u8,
+}
+
+impl BridgeId {
+    /// Create a bridge identifier from its raw value
+    pub fn from_raw(raw: [u8; 8]) -> Self {
+        Self { raw }
+    }
+
+    /// Return the raw value of the bridge identifier
+    pub fn to_raw(self) -> [u8; 8] {
+        self.raw
+    }
+
+    /// Create a bridge identifier from its components
+    ///
+    /// Combine the bridge priority (a multiple of `PRIORITY_STEP`), the
+    /// system identifier extension, and the MAC address. Excess bits of
+    /// `priority` and `system_id` are discarded.
+    pub fn new(priority: u16, system_id: u16, mac: ethernet::Mac) -> Self {
+        let v = (priority & !SYSTEM_ID_MASK) | (system_id & SYSTEM_ID_MASK);
+        let mut raw = [0; 8];
+        raw[..2].copy_from_slice(&v.to_be_bytes());
+        raw[2..].copy_from_slice(&mac.to_raw());
+        Self { raw }
+    }
+
+    /// Return native value
+    pub fn to_native(self) -> u64 {
+        u64::from_be_bytes(self.raw)
+    }
+
+    /// Return bridge priority
+    pub fn priority(self) -> u16 {
+        u16::from_be_bytes([self.raw[0], self.raw[1]]) & !SYSTEM_ID_MASK
+    }
+
+    /// Return system identifier extension
+    pub fn system_id(self) -> u16 {
+        u16::from_be_bytes([self.raw[0], self.raw[1]]) & SYSTEM_ID_MASK
+    }
+
+    /// Return MAC address
+    pub fn mac(self) -> ethernet::Mac {
+        ethernet::Mac::from_raw(self.raw[2..].try_into().unwrap())
+    }
+}
+
+impl Header {
+    /// Import a header from a byte slice
+    ///
+    /// Create a new header from data copied from a byte slice. No byte-order
+    /// conversions are applied.
+    pub fn from_bytes(data: &[u8; 4]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 4], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl Config {
+    /// Import a configuration BPDU from a byte slice
+    ///
+    /// Create a new configuration BPDU from data copied from a byte slice.
+    /// No byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 35]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 35], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Return port role
+    ///
+    /// Return the port role (`ROLE_*`) of a rapid spanning tree BPDU.
+    pub fn role(&self) -> u8 {
+        (self.flags & FLAG_ROLE_MASK) >> FLAG_ROLE_SHIFT
+    }
+}
+
+impl Rst {
+    /// Import a rapid spanning tree BPDU from a byte slice
+    ///
+    /// Create a new rapid spanning tree BPDU from data copied from a byte
+    /// slice. No byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 36]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 36], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+/// Encode port identifier
+///
+/// Combine the port priority and the 12-bit port number. Excess bits of
+/// `number` are discarded.
+pub fn port_id(priority: u8, number: u16) -> u16 {
+    ((priority as u16 & 0xf0) << 8) | (number & PORT_NUMBER_MASK)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // An RST BPDU of a designated, forwarding port on the root bridge.
+    const RST: [u8; 36] = [
+        0x00, 0x00, 0x02, 0x02, 0x3c, 0x80, 0x01, 0x00,
+        0x11, 0x22, 0x33, 0x44, 0x55, 0x00, 0x00, 0x00,
+        0x00, 0x80, 0x01, 0x00, 0x11, 0x22, 0x33, 0x44,
+        0x55, 0x80, 0x02, 0x00, 0x00, 0x14, 0x00, 0x02,
+        0x00, 0x0f, 0x00, 0x00,
+    ];
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<BridgeId>(), 8);
+        assert_eq!(align_of::<BridgeId>(), 1);
+        assert_eq!(size_of::<Header>(), HEADER_SIZE);
+        assert_eq!(align_of::<Header>(), 2);
+        assert_eq!(size_of::<Config>(), CONFIG_SIZE);
+        assert_eq!(align_of::<Config>(), 1);
+        assert_eq!(size_of::<Rst>(), RST_SIZE);
+        assert_eq!(align_of::<Rst>(), 1);
+    }
+
+    // Parse an RST BPDU.
+    #[test]
+    fn verify_rst() {
+        let h = Header::from_bytes(RST[..4].try_into().unwrap());
+        assert_eq!(int::ForeignEndian::to_native(h.protocol_id), PROTOCOL_ID);
+        assert_eq!(h.version, VERSION_RSTP);
+        assert_eq!(h.bpdu_type, TYPE_RST);
+
+        let b = Rst::from_bytes(&RST);
+        let c = &b.config;
+        assert_eq!(c.role(), ROLE_DESIGNATED);
+        assert_eq!(c.flags & (FLAG_LEARNING | FLAG_FORWARDING), FLAG_LEARNING | FLAG_FORWARDING);
+        assert_eq!(c.flags & (FLAG_TC | FLAG_PROPOSAL | FLAG_AGREEMENT | FLAG_TCA), 0);
+        assert_eq!(c.root_id, c.bridge_id);
+        assert_eq!(c.root_id.priority(), PRIORITY_DEFAULT);
+        assert_eq!(c.root_id.system_id(), 1);
+        assert_eq!(c.root_id.mac().to_raw(), [0x00, 0x11, 0x22, 0x33, 0x44, 0x55]);
+        assert_eq!(int::ForeignEndian::to_native(c.root_path_cost), 0);
+        assert_eq!(int::ForeignEndian::to_native(c.port_id), port_id(PORT_PRIORITY_DEFAULT, 2));
+        assert_eq!(int::ForeignEndian::to_native(c.max_age), 20 << 8);
+        assert_eq!(int::ForeignEndian::to_native(c.hello_time), 2 << 8);
+        assert_eq!(int::ForeignEndian::to_native(c.forward_delay), 15 << 8);
+        assert_eq!(b.version1_length, 0);
+    }
+
+    // Parse a configuration BPDU and compare bridge identifiers.
+    #[test]
+    fn verify_config() {
+        let mut data = [0; 35];
+        data[..35].copy_from_slice(&RST[..35]);
+        data[2] = VERSION_STP;
+        data[3] = TYPE_CONFIG;
+        data[4] = FLAG_TC;
+        let c = Config::from_bytes(&data);
+        assert_eq!(c.version, VERSION_STP);
+        assert_eq!(c.flags & FLAG_TC, FLAG_TC);
+
+        let mac = ethernet::Mac::from_raw([0x00, 0x11, 0x22, 0x33, 0x44, 0x55]);
+        let id = BridgeId::new(PRIORITY_DEFAULT, 1, mac);
+        assert_eq!(id, c.root_id);
+        assert_eq!(id.to_raw(), RST[5..13]);
+        assert_eq!(id.to_native(), 0x8001_0011_2233_4455);
+
+        let other = BridgeId::new(PRIORITY_DEFAULT - PRIORITY_STEP, 1, mac);
+        assert!(other.to_native() < id.to_native());
+        assert!(other.to_raw() < id.to_raw());
+        assert_eq!(other.priority(), 0x7000);
+        assert_eq!(port_id(0x1f, 0xffff), 0x1fff);
+    }
+}
@@ -0,0 +1,363 @@
+//! Transmission Control Protocol
+//!
+//! TCP segments start with a `Header` of at least `HEADER_SIZE` bytes. Its
+//! actual size is given by the data offset in units of 4 bytes, and any space
+//! beyond the fixed header is filled with options. Options use the framing of
+//! IPv4 options (see `ipv4::Options`), with `OPTION_END` and `OPTION_NOP` as
+//! the only single-byte options. The layouts of common options include their
+//! kind and length bytes, and several of them are not naturally aligned.
+//!
+//! The checksum is the Internet checksum (see `checksum::internet()`) of the
+//! IPv4 or IPv6 pseudo-header (see `ipv4::PseudoHeader` and
+//! `ipv6::PseudoHeader`) followed by the entire segment, calculated with the
+//! checksum field set to zero.
+//!
+//! All multi-byte integers are encoded as big-endian.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::{checksum, int, ipv4, ipv6};
+
+pub const HEADER_SIZE: usize = 20;
+pub const HEADER_SIZE_MAX: usize = 60;
+pub const CHECKSUM_OFFSET: usize = 16;
+
+pub const FLAG_FIN: u16 = 0x001;
+pub const FLAG_SYN: u16 = 0x002;
+pub const FLAG_RST: u16 = 0x004;
+pub const FLAG_PSH: u16 = 0x008;
+pub const FLAG_ACK: u16 = 0x010;
+pub const FLAG_URG: u16 = 0x020;
+pub const FLAG_ECE: u16 = 0x040;
+pub const FLAG_CWR: u16 = 0x080;
+pub const FLAG_AE: u16 = 0x100;
+pub const FLAG_MASK: u16 = 0x1ff;
+
+pub const OPTION_END: u8 = 0;
+pub const OPTION_NOP: u8 = 1;
+pub const OPTION_MSS: u8 = 2;
+pub const OPTION_WINDOW_SCALE: u8 = 3;
+pub const OPTION_SACK_PERMITTED: u8 = 4;
+pub const OPTION_SACK: u8 = 5;
+pub const OPTION_TIMESTAMPS: u8 = 8;
+pub const OPTION_MD5: u8 = 19;
+pub const OPTION_USER_TIMEOUT: u8 = 28;
+pub const OPTION_AUTH: u8 = 29;
+pub const OPTION_MPTCP: u8 = 30;
+pub const OPTION_FAST_OPEN: u8 = 34;
+pub const OPTION_EXPERIMENT1: u8 = 253;
+pub const OPTION_EXPERIMENT2: u8 = 254;
+
+/// Maximum Window Scale Shift
+pub const WINDOW_SCALE_MAX: u8 = 14;
+
+/// TCP Header
+#[repr(C)]
+pub struct Header {
+    /// Source port.
+    pub source_port: int::u16be,
+
+    /// Destination port.
+    pub destination_port: int::u16be,
+
+    /// Sequence number of the first data byte, or the initial sequence number
+    /// with `FLAG_SYN`.
+    pub sequence: int::u32be,
+
+    /// Next sequence number expected by the sender, if `FLAG_ACK` is set.
+    pub acknowledgment: int::u32be,
+
+    /// Data offset in units of 4 bytes in the upper 4 bits, and flags
+    /// (`FLAG_*`) in the lower 9 bits (see `data_offset()` and `flags()`).
+    pub offset_flags: int::u16be,
+
+    /// Receive window, scaled by the negotiated window scale.
+    pub window: int::u16be,
+
+    /// Checksum of the segment and pseudo-header.
+    pub checksum: int::u16be,
+
+    /// Offset of the byte following urgent data, if `FLAG_URG` is set.
+    pub urgent_pointer: int::u16be,
+}
+
+/// Maximum Segment Size Option
+#[repr(C)]
+pub struct Mss {
+    /// Option kind, must be `OPTION_MSS`.
+    pub kind: u8,
+
+    /// Option length, must be 4.
+    pub length: u8,
+
+    /// Maximum segment size the sender can receive.
+    pub mss: int::u16be,
+}
+
+/// Window Scale Option
+#[repr(C)]
+pub struct WindowScale {
+    /// Option kind, must be `OPTION_WINDOW_SCALE`.
+    pub kind: u8,
+
+    /// Option length, must be 3.
+    pub length: u8,
+
+    /// Shift count, at most `WINDOW_SCALE_MAX`.
+    pub shift: u8,
+}
+
+/// Timestamps Option
+///
+/// Since the timestamps are not naturally aligned, this structure is packed.
+#[repr(C, packed)]
+pub struct Timestamps {
+    /// Option kind, must be `OPTION_TIMESTAMPS`.
+    pub kind: u8,
+
+    /// Option length, must be 10.
+    pub length: u8,
+
+    /// Timestamp of the sender.
+    pub value: int::u32be,
+
+    /// Most recent timestamp received from the peer, if `FLAG_ACK` is set.
+    pub echo_reply: int::u32be,
+}
+
+/// SACK Block
+///
+/// The data of `OPTION_SACK` is a sequence of up to 4 of these blocks.
+#[repr(C)]
+pub struct SackBlock {
+    /// Sequence number of the first received byte.
+    pub left_edge: int::u32be,
+
+    /// Sequence number following the last received byte.
+    pub right_edge: int::u32be,
+}
+
+impl Header {
+    /// Import a header from a byte slice
+    ///
+    /// Create a new header from data copied from a byte slice. No byte-order
+    /// conversions are applied.
+    pub fn from_bytes(data: &[u8; 20]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 20], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Return data offset
+    ///
+    /// Return the header length in units of 4 bytes. Valid headers have a
+    /// length of at least 5.
+    pub fn data_offset(&self) -> u8 {
+        (int::ForeignEndian::to_native(self.offset_flags) >> 12) as u8
+    }
+
+    /// Return header size
+    ///
+    /// Return the size of the header including options, in bytes.
+    pub fn header_size(&self) -> usize {
+        self.data_offset() as usize * 4
+    }
+
+    /// Return flags (`FLAG_*`)
+    pub fn flags(&self) -> u16 {
+        int::ForeignEndian::to_native(self.offset_flags) & FLAG_MASK
+    }
+
+    /// Check whether all of the given flags are set
+    pub fn has_flags(&self, flags: u16) -> bool {
+        self.flags() & flags == flags
+    }
+}
+
+impl Mss {
+    /// Import an MSS option from a byte slice
+    ///
+    /// Create a new MSS option from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 4]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 4], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl WindowScale {
+    /// Import a window scale option from a byte slice
+    ///
+    /// Create a new window scale option from data copied from a byte slice.
+    /// No byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 3]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 3], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl Timestamps {
+    /// Import a timestamps option from a byte slice
+    ///
+    /// Create a new timestamps option from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 10]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 10], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl SackBlock {
+    /// Import a SACK block from a byte slice
+    ///
+    /// Create a new SACK block from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 8]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 8], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+// Finish the checksum of `segment` on top of the sum of a pseudo-header,
+// treating the checksum field as zero.
+fn segment_checksum(sum: u32, segment: &[u8]) -> u16 {
+    let sum = checksum::internet_update(sum, &segment[..CHECKSUM_OFFSET]);
+    let sum = checksum::internet_update(sum, &segment[CHECKSUM_OFFSET + 2..]);
+    checksum::internet_finish(sum)
+}
+
+/// Calculate Segment Checksum over IPv4
+///
+/// Calculate the checksum of an entire segment sent from `source` to
+/// `destination`, treating the checksum field as zero. `segment` must be at
+/// least `HEADER_SIZE` bytes.
+pub fn checksum_v4(source: &[u8; 4], destination: &[u8; 4], segment: &[u8]) -> u16 {
+    let ph = ipv4::PseudoHeader::new(*source, *destination, ipv4::PROTOCOL_TCP, segment.len() as u16);
+    segment_checksum(checksum::internet_update(0, ph.as_bytes()), segment)
+}
+
+/// Calculate Segment Checksum over IPv6
+///
+/// Calculate the checksum of an entire segment sent from `source` to
+/// `destination`, treating the checksum field as zero. `segment` must be at
+/// least `HEADER_SIZE` bytes.
+pub fn checksum_v6(source: &[u8; 16], destination: &[u8; 16], segment: &[u8]) -> u16 {
+    let ph = ipv6::PseudoHeader::new(*source, *destination, segment.len() as u32, ipv4::PROTOCOL_TCP);
+    segment_checksum(checksum::internet_update(0, ph.as_bytes()), segment)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A SYN from 10.0.0.1:49152 to 10.0.0.2:80 with the usual options.
+    const SYN: [u8; 40] = [
+        0xc0, 0x00, 0x00, 0x50, 0x11, 0x22, 0x33, 0x44,
+        0x00, 0x00, 0x00, 0x00, 0xa0, 0x02, 0xfa, 0xf0,
+        0x30, 0x50, 0x00, 0x00, 0x02, 0x04, 0x05, 0xb4,
+        0x04, 0x02, 0x08, 0x0a, 0x01, 0x02, 0x03, 0x04,
+        0x00, 0x00, 0x00, 0x00, 0x01, 0x03, 0x03, 0x07,
+    ];
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<Header>(), HEADER_SIZE);
+        assert_eq!(align_of::<Header>(), 4);
+        assert_eq!(size_of::<Mss>(), 4);
+        assert_eq!(align_of::<Mss>(), 2);
+        assert_eq!(size_of::<WindowScale>(), 3);
+        assert_eq!(align_of::<WindowScale>(), 1);
+        assert_eq!(size_of::<Timestamps>(), 10);
+        assert_eq!(align_of::<Timestamps>(), 1);
+        assert_eq!(size_of::<SackBlock>(), 8);
+        assert_eq!(align_of::<SackBlock>(), 4);
+    }
+
+    // Parse a SYN and walk its options.
+    #[test]
+    fn verify_syn() {
+        let h = Header::from_bytes(SYN[..20].try_into().unwrap());
+        assert_eq!(int::ForeignEndian::to_native(h.source_port), 49152);
+        assert_eq!(int::ForeignEndian::to_native(h.destination_port), 80);
+        assert_eq!(int::ForeignEndian::to_native(h.sequence), 0x11223344);
+        assert_eq!(h.header_size(), 40);
+        assert_eq!(h.flags(), FLAG_SYN);
+        assert!(!h.has_flags(FLAG_SYN | FLAG_ACK));
+        assert_eq!(int::ForeignEndian::to_native(h.window), 64240);
+
+        let mut opts = ipv4::Options::new(&SYN[20..40]);
+        assert_eq!(opts.next(), Some((OPTION_MSS, &[0x05, 0xb4][..])));
+        assert_eq!(opts.next(), Some((OPTION_SACK_PERMITTED, &[][..])));
+        let (kind, _) = opts.next().unwrap();
+        assert_eq!(kind, OPTION_TIMESTAMPS);
+        assert_eq!(opts.next(), Some((OPTION_NOP, &[][..])));
+        assert_eq!(opts.next(), Some((OPTION_WINDOW_SCALE, &[7][..])));
+        assert!(opts.next().is_none());
+
+        let m = Mss::from_bytes(SYN[20..24].try_into().unwrap());
+        assert_eq!(int::ForeignEndian::to_native(m.mss), 1460);
+        let t = Timestamps::from_bytes(SYN[26..36].try_into().unwrap());
+        assert_eq!(int::ForeignEndian::to_native(t.value), 0x01020304);
+        assert_eq!(int::ForeignEndian::to_native(t.echo_reply), 0);
+        let w = WindowScale::from_bytes(SYN[37..40].try_into().unwrap());
+        assert_eq!(w.shift, 7);
+
+        let s = SackBlock::from_bytes(&[0, 0, 0x10, 0, 0, 0, 0x20, 0]);
+        assert_eq!(int::ForeignEndian::to_native(s.left_edge), 0x1000);
+        assert_eq!(int::ForeignEndian::to_native(s.right_edge), 0x2000);
+    }
+
+    // Verify checksums over both pseudo-headers.
+    #[test]
+    fn verify_checksum() {
+        assert_eq!(checksum_v4(&[10, 0, 0, 1], &[10, 0, 0, 2], &SYN), 0x3050);
+        assert_ne!(checksum_v4(&[10, 0, 0, 1], &[10, 0, 0, 3], &SYN), 0x3050);
+
+        let mut src = [0; 16];
+        let mut dst = [0; 16];
+        src[..4].copy_from_slice(&[0x20, 0x01, 0x0d, 0xb8]);
+        dst[..4].copy_from_slice(&[0x20, 0x01, 0x0d, 0xb8]);
+        src[15] = 1;
+        dst[15] = 2;
+        assert_eq!(checksum_v6(&src, &dst, &SYN), 0xe8dd);
+    }
+}
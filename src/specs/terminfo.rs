@@ -0,0 +1,441 @@
+//! Compiled Terminfo Format
+//!
+//! Terminfo describes the capabilities of terminals, identified by the
+//! terminal names. The compiled format stores one terminal per file, which
+//! starts with a 12-byte header (see `Header`) giving the sizes of the
+//! following sections:
+//!
+//!  * Names: The terminal names, separated by `NAME_SEPARATOR`, with the
+//!    last one being a description, terminated by NUL.
+//!  * Booleans: One byte per boolean capability, 0 or 1. If the sizes of
+//!    names and booleans sum to an odd number, a padding byte follows,
+//!    so the numbers start at an even offset.
+//!  * Numbers: One 16-bit integer per numeric capability, or one 32-bit
+//!    integer if the header carries `MAGIC_NUMBER32`.
+//!  * Strings: One 16-bit offset into the string table per string
+//!    capability.
+//!  * String Table: The NUL-terminated values of the string capabilities.
+//!
+//! Capabilities are identified by their index in the respective section,
+//! in the order defined by the terminfo specification (e.g., `NUMBER_*`).
+//! Negative values mark capabilities as absent (`ABSENT`), or as cancelled
+//! (`CANCELLED`) when inheriting from another entry.
+//!
+//! An extended section with user-defined capabilities can follow the string
+//! table, aligned to an even offset (see `ExtendedHeader`). It has the same
+//! layout, but the string table additionally carries the capability names,
+//! referenced by an offset array following the string offsets.
+//!
+//! All multi-byte integers are encoded as little-endian.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::int;
+
+pub const MAGIC_LEGACY: u16 = 0o432;
+pub const MAGIC_NUMBER32: u16 = 0o1036;
+
+pub const SIZE_MAX_LEGACY: usize = 4096;
+pub const SIZE_MAX_NUMBER32: usize = 32768;
+
+pub const NAME_SEPARATOR: u8 = b'|';
+
+pub const ABSENT: i32 = -1;
+pub const CANCELLED: i32 = -2;
+
+pub const BOOLEAN_AUTO_LEFT_MARGIN: usize = 0;
+pub const BOOLEAN_AUTO_RIGHT_MARGIN: usize = 1;
+pub const BOOLEAN_HAS_META_KEY: usize = 8;
+pub const BOOLEAN_XON_XOFF: usize = 20;
+pub const BOOLEAN_CAN_CHANGE: usize = 27;
+pub const BOOLEAN_BACK_COLOR_ERASE: usize = 28;
+
+pub const NUMBER_COLUMNS: usize = 0;
+pub const NUMBER_INIT_TABS: usize = 1;
+pub const NUMBER_LINES: usize = 2;
+pub const NUMBER_MAX_COLORS: usize = 13;
+pub const NUMBER_MAX_PAIRS: usize = 14;
+
+pub const STRING_BELL: usize = 1;
+pub const STRING_CARRIAGE_RETURN: usize = 2;
+pub const STRING_CLEAR_SCREEN: usize = 5;
+pub const STRING_CLR_EOL: usize = 6;
+pub const STRING_CURSOR_ADDRESS: usize = 10;
+pub const STRING_CURSOR_HOME: usize = 12;
+pub const STRING_CURSOR_INVISIBLE: usize = 13;
+pub const STRING_CURSOR_NORMAL: usize = 16;
+pub const STRING_ENTER_BOLD_MODE: usize = 27;
+pub const STRING_ENTER_CA_MODE: usize = 28;
+pub const STRING_EXIT_ATTRIBUTE_MODE: usize = 39;
+pub const STRING_EXIT_CA_MODE: usize = 40;
+
+/// File Header
+#[repr(C)]
+pub struct Header {
+    /// Magic number identifying the format (`MAGIC_*`).
+    pub magic: int::u16le,
+
+    /// Size of the names section in bytes, including the terminating NUL.
+    pub names_size: int::i16le,
+
+    /// Number of boolean capabilities.
+    pub boolean_count: int::i16le,
+
+    /// Number of numeric capabilities.
+    pub number_count: int::i16le,
+
+    /// Number of string capabilities.
+    pub string_count: int::i16le,
+
+    /// Size of the string table in bytes.
+    pub string_table_size: int::i16le,
+}
+
+/// Extended Section Header
+#[repr(C)]
+pub struct ExtendedHeader {
+    /// Number of extended boolean capabilities.
+    pub boolean_count: int::i16le,
+
+    /// Number of extended numeric capabilities.
+    pub number_count: int::i16le,
+
+    /// Number of extended string capabilities.
+    pub string_count: int::i16le,
+
+    /// Number of entries of the string table, which are the string values
+    /// and the names of all extended capabilities.
+    pub item_count: int::i16le,
+
+    /// Size of the string table in bytes.
+    pub string_table_size: int::i16le,
+}
+
+/// Capability Sections
+///
+/// References the sections of the standard or extended capabilities of a
+/// compiled entry.
+#[derive(Clone, Copy, Debug)]
+pub struct Capabilities<'data> {
+    /// Boolean capabilities, one byte each.
+    pub booleans: &'data [u8],
+
+    /// Numeric capabilities, `number_size` bytes each.
+    pub numbers: &'data [u8],
+
+    /// Size of numeric capabilities in bytes, 2 or 4.
+    pub number_size: usize,
+
+    /// Offsets of string capabilities into `string_table`.
+    pub strings: &'data [u8],
+
+    /// Offsets of capability names into the name part of `string_table`.
+    /// This is empty for standard capabilities.
+    pub names: &'data [u8],
+
+    /// String table.
+    pub string_table: &'data [u8],
+}
+
+/// Compiled Entry Reference
+#[derive(Clone, Copy, Debug)]
+pub struct Entry<'data> {
+    /// Terminal names, separated by `NAME_SEPARATOR`, excluding the
+    /// terminating NUL.
+    pub names: &'data [u8],
+
+    /// Standard capabilities.
+    pub standard: Capabilities<'data>,
+
+    /// Extended capabilities, if present.
+    pub extended: Option<Capabilities<'data>>,
+}
+
+// Read a little-endian 16-bit offset at the given index.
+fn offset(data: &[u8], i: usize) -> Option<i16> {
+    Some(i16::from_le_bytes(data.get(i * 2..i * 2 + 2)?.try_into().ok()?))
+}
+
+// Return the NUL-terminated string at a non-negative offset.
+fn string_at(table: &[u8], offset: i16) -> Option<&[u8]> {
+    let v = table.get(usize::try_from(offset).ok()?..)?;
+    Some(&v[..v.iter().position(|c| *c == 0)?])
+}
+
+// Return a section size, rejecting negative values.
+fn count(v: int::i16le) -> Option<usize> {
+    usize::try_from(int::ForeignEndian::to_native(v)).ok()
+}
+
+impl Header {
+    /// Import a header from a byte slice
+    ///
+    /// Create a new header from data copied from a byte slice. No byte-order
+    /// conversions are applied.
+    pub fn from_bytes(data: &[u8; 12]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 12], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl ExtendedHeader {
+    /// Import a header from a byte slice
+    ///
+    /// Create a new header from data copied from a byte slice. No byte-order
+    /// conversions are applied.
+    pub fn from_bytes(data: &[u8; 10]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 10], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl<'data> Capabilities<'data> {
+    // Split the sections following a header, returning the consumed size.
+    fn split(
+        data: &'data [u8],
+        head: usize,
+        counts: [usize; 5],
+        number_size: usize,
+    ) -> Option<(Self, usize)> {
+        let [booleans, numbers, strings, names, table] = counts;
+        let mut o = head;
+
+        let b = data.get(o..o + booleans)?;
+        o += booleans;
+        o += o % 2;
+        let n = data.get(o..o + numbers * number_size)?;
+        o += n.len();
+        let s = data.get(o..o + strings * 2)?;
+        o += s.len();
+        let m = data.get(o..o + names * 2)?;
+        o += m.len();
+        let t = data.get(o..o + table)?;
+        o += table;
+
+        let c = Self {
+            booleans: b,
+            numbers: n,
+            number_size,
+            strings: s,
+            names: m,
+            string_table: t,
+        };
+        Some((c, o))
+    }
+
+    /// Return boolean capability
+    ///
+    /// Return whether the boolean capability at index `i` is set.
+    pub fn boolean(&self, i: usize) -> bool {
+        self.booleans.get(i).is_some_and(|v| *v == 1)
+    }
+
+    /// Return numeric capability
+    ///
+    /// Return the value of the numeric capability at index `i`, or `None`
+    /// if it is absent or cancelled.
+    pub fn number(&self, i: usize) -> Option<i32> {
+        let n = self.number_size;
+        let v = self.numbers.get(i * n..(i + 1) * n)?;
+        let v = match n {
+            4 => i32::from_le_bytes(v.try_into().ok()?),
+            _ => i16::from_le_bytes(v.try_into().ok()?) as i32,
+        };
+        if v < 0 { None } else { Some(v) }
+    }
+
+    /// Return string capability
+    ///
+    /// Return the value of the string capability at index `i`, excluding
+    /// the terminating NUL, or `None` if it is absent, cancelled, or
+    /// invalid.
+    pub fn string(&self, i: usize) -> Option<&'data [u8]> {
+        string_at(self.string_table, offset(self.strings, i)?)
+    }
+
+    /// Return capability name
+    ///
+    /// Return the name of the extended capability at index `i`, counting
+    /// booleans, numbers, and strings in this order. The names follow the
+    /// last string value in the string table.
+    pub fn name(&self, i: usize) -> Option<&'data [u8]> {
+        let base = (0..self.strings.len() / 2)
+            .filter_map(|v| {
+                let o = offset(self.strings, v)?;
+                Some(o as usize + string_at(self.string_table, o)?.len() + 1)
+            })
+            .max()
+            .unwrap_or(0);
+
+        string_at(self.string_table.get(base..)?, offset(self.names, i)?)
+    }
+}
+
+impl<'data> Entry<'data> {
+    /// Parse compiled entry
+    ///
+    /// Parse the compiled terminfo entry `data`, including the extended
+    /// section if present. Return `None` if the magic number is unknown, a
+    /// section is truncated, or a size is negative.
+    pub fn parse(data: &'data [u8]) -> Option<Self> {
+        let h = Header::from_bytes(data.get(..12)?.try_into().ok()?);
+        let number_size = match int::ForeignEndian::to_native(h.magic) {
+            MAGIC_LEGACY => 2,
+            MAGIC_NUMBER32 => 4,
+            _ => return None,
+        };
+
+        let names_size = count(h.names_size)?;
+        let names = data.get(12..12 + names_size)?;
+        let names = &names[..names.iter().position(|v| *v == 0)?];
+
+        let counts = [
+            count(h.boolean_count)?,
+            count(h.number_count)?,
+            count(h.string_count)?,
+            0,
+            count(h.string_table_size)?,
+        ];
+        let (standard, mut o) = Capabilities::split(data, 12 + names_size, counts, number_size)?;
+
+        o += o % 2;
+        let extended = match data.get(o..o + 10) {
+            Some(v) => {
+                let h = ExtendedHeader::from_bytes(v.try_into().ok()?);
+                let booleans = count(h.boolean_count)?;
+                let numbers = count(h.number_count)?;
+                let strings = count(h.string_count)?;
+                let counts = [
+                    booleans,
+                    numbers,
+                    strings,
+                    booleans + numbers + strings,
+                    count(h.string_table_size)?,
+                ];
+                Some(Capabilities::split(&data[o..], 10, counts, number_size)?.0)
+            },
+            None => None,
+        };
+
+        Some(Self { names, standard, extended })
+    }
+
+    /// Iterate terminal names
+    ///
+    /// Return an iterator over the terminal names. The last name is a
+    /// description of the terminal.
+    pub fn terminal_names(&self) -> impl Iterator<Item = &'data [u8]> {
+        self.names.split(|v| *v == NAME_SEPARATOR)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Entry compiled by `tic -x` from "tst|test terminal, am, cols#80,
+    // colors#8, bel=^G, clear=\E[H, Tc, U8#1,".
+    const ENTRY: [u8; 102] = [
+        0x1a, 0x01, 0x12, 0x00, 0x02, 0x00, 0x0e, 0x00,
+        0x06, 0x00, 0x06, 0x00, 0x74, 0x73, 0x74, 0x7c,
+        0x74, 0x65, 0x73, 0x74, 0x20, 0x74, 0x65, 0x72,
+        0x6d, 0x69, 0x6e, 0x61, 0x6c, 0x00, 0x00, 0x01,
+        0x50, 0x00, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0xff, 0xff, 0x08, 0x00, 0xff, 0xff, 0x00, 0x00,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x02, 0x00,
+        0x07, 0x00, 0x1b, 0x5b, 0x48, 0x00, 0x01, 0x00,
+        0x01, 0x00, 0x00, 0x00, 0x02, 0x00, 0x06, 0x00,
+        0x01, 0x00, 0x01, 0x00, 0x00, 0x00, 0x03, 0x00,
+        0x54, 0x63, 0x00, 0x55, 0x38, 0x00,
+    ];
+
+    // Entry compiled by `tic` from "big|big colors, colors#0x10000, bel=^G,".
+    const ENTRY32: [u8; 90] = [
+        0x1e, 0x02, 0x0f, 0x00, 0x00, 0x00, 0x0e, 0x00,
+        0x02, 0x00, 0x02, 0x00, 0x62, 0x69, 0x67, 0x7c,
+        0x62, 0x69, 0x67, 0x20, 0x63, 0x6f, 0x6c, 0x6f,
+        0x72, 0x73, 0x00, 0x00, 0xff, 0xff, 0xff, 0xff,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0x00, 0x00, 0x01, 0x00, 0xff, 0xff, 0x00, 0x00,
+        0x07, 0x00,
+    ];
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<Header>(), 12);
+        assert_eq!(align_of::<Header>(), 2);
+        assert_eq!(size_of::<ExtendedHeader>(), 10);
+        assert_eq!(align_of::<ExtendedHeader>(), 2);
+    }
+
+    // Parse an entry with extended capabilities.
+    #[test]
+    fn verify_entry() {
+        let e = Entry::parse(&ENTRY).unwrap();
+        let mut n = e.terminal_names();
+        assert_eq!(n.next(), Some(&b"tst"[..]));
+        assert_eq!(n.next(), Some(&b"test terminal"[..]));
+        assert!(n.next().is_none());
+
+        let s = e.standard;
+        assert!(s.boolean(BOOLEAN_AUTO_RIGHT_MARGIN));
+        assert!(!s.boolean(BOOLEAN_AUTO_LEFT_MARGIN));
+        assert_eq!(s.number(NUMBER_COLUMNS), Some(80));
+        assert_eq!(s.number(NUMBER_MAX_COLORS), Some(8));
+        assert_eq!(s.number(NUMBER_LINES), None);
+        assert_eq!(s.string(STRING_BELL), Some(&b"\x07"[..]));
+        assert_eq!(s.string(STRING_CLEAR_SCREEN), Some(&b"\x1b[H"[..]));
+        assert_eq!(s.string(STRING_CARRIAGE_RETURN), None);
+
+        let x = e.extended.unwrap();
+        assert!(x.boolean(0));
+        assert_eq!(x.number(0), Some(1));
+        assert_eq!(x.name(0), Some(&b"Tc"[..]));
+        assert_eq!(x.name(1), Some(&b"U8"[..]));
+        assert_eq!(x.name(2), None);
+
+        assert!(Entry::parse(&ENTRY[..77]).is_none());
+        assert!(Entry::parse(&ENTRY[..90]).is_none());
+        assert!(Entry::parse(&ENTRY[..78]).unwrap().extended.is_none());
+    }
+
+    // Parse an entry with 32-bit numbers.
+    #[test]
+    fn verify_number32() {
+        let h = Header::from_bytes(ENTRY32[..12].try_into().unwrap());
+        assert_eq!(int::ForeignEndian::to_native(h.magic), MAGIC_NUMBER32);
+
+        let e = Entry::parse(&ENTRY32).unwrap();
+        assert_eq!(e.names, b"big|big colors");
+        assert_eq!(e.standard.number_size, 4);
+        assert_eq!(e.standard.number(NUMBER_MAX_COLORS), Some(0x10000));
+        assert_eq!(e.standard.string(STRING_BELL), Some(&b"\x07"[..]));
+        assert!(e.extended.is_none());
+    }
+}
@@ -0,0 +1,304 @@
+//! Trivial File Transfer Protocol
+//!
+//! TFTP packets start with a 2-byte opcode (`OPCODE_*`). Read and write
+//! requests are sent to `udp::PORT_TFTP` and carry the zero-terminated file
+//! name and transfer mode (see `Request`), optionally followed by pairs of
+//! zero-terminated option names and values. The server answers from a new
+//! port, either with the first DATA packet (or an ACK of block 0 for write
+//! requests), or with an OACK acknowledging the accepted options.
+//!
+//! File data is transferred in DATA packets (see `BlockHeader`) carrying up
+//! to `BLOCK_SIZE` bytes, unless a different size was negotiated with
+//! `OPTION_BLKSIZE`. Each DATA packet is answered with an ACK of the same
+//! block number. A DATA packet shorter than the block size ends the
+//! transfer. ERROR packets (see `ErrorHeader`) are followed by a
+//! zero-terminated message and terminate the transfer.
+//!
+//! Mode and option names are case-insensitive ASCII. In `MODE_NETASCII`,
+//! line endings are transferred as CR LF, and a literal CR as CR NUL.
+//!
+//! All multi-byte integers are encoded as big-endian.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::int;
+
+pub const HEADER_SIZE: usize = 2;
+pub const BLOCK_HEADER_SIZE: usize = 4;
+pub const BLOCK_SIZE: usize = 512;
+pub const BLKSIZE_MIN: usize = 8;
+pub const BLKSIZE_MAX: usize = 65464;
+
+pub const OPCODE_RRQ: u16 = 1;
+pub const OPCODE_WRQ: u16 = 2;
+pub const OPCODE_DATA: u16 = 3;
+pub const OPCODE_ACK: u16 = 4;
+pub const OPCODE_ERROR: u16 = 5;
+pub const OPCODE_OACK: u16 = 6;
+
+pub const MODE_NETASCII: &[u8] = b"netascii";
+pub const MODE_OCTET: &[u8] = b"octet";
+pub const MODE_MAIL: &[u8] = b"mail";
+
+pub const OPTION_BLKSIZE: &[u8] = b"blksize";
+pub const OPTION_TIMEOUT: &[u8] = b"timeout";
+pub const OPTION_TSIZE: &[u8] = b"tsize";
+pub const OPTION_WINDOWSIZE: &[u8] = b"windowsize";
+
+pub const ERROR_UNDEFINED: u16 = 0;
+pub const ERROR_NOT_FOUND: u16 = 1;
+pub const ERROR_ACCESS_VIOLATION: u16 = 2;
+pub const ERROR_DISK_FULL: u16 = 3;
+pub const ERROR_ILLEGAL_OPERATION: u16 = 4;
+pub const ERROR_UNKNOWN_TID: u16 = 5;
+pub const ERROR_FILE_EXISTS: u16 = 6;
+pub const ERROR_NO_SUCH_USER: u16 = 7;
+pub const ERROR_OPTION_REFUSED: u16 = 8;
+
+/// Packet Header
+#[repr(C)]
+pub struct Header {
+    /// Opcode (`OPCODE_*`).
+    pub opcode: int::u16be,
+}
+
+/// DATA and ACK Header
+///
+/// The header of `OPCODE_DATA` packets, followed by the data, and the
+/// entire `OPCODE_ACK` packet.
+#[repr(C)]
+pub struct BlockHeader {
+    /// Opcode (`OPCODE_DATA` or `OPCODE_ACK`).
+    pub opcode: int::u16be,
+
+    /// Block number, starting at 1 and wrapping around.
+    pub block: int::u16be,
+}
+
+/// ERROR Header
+///
+/// The header of `OPCODE_ERROR` packets, followed by a zero-terminated
+/// message.
+#[repr(C)]
+pub struct ErrorHeader {
+    /// Opcode (`OPCODE_ERROR`).
+    pub opcode: int::u16be,
+
+    /// Error code (`ERROR_*`).
+    pub error_code: int::u16be,
+}
+
+/// Read and Write Request Reference
+#[derive(Clone, Copy, Debug)]
+pub struct Request<'data> {
+    /// Opcode (`OPCODE_RRQ` or `OPCODE_WRQ`).
+    pub opcode: u16,
+
+    /// File name, excluding the terminating zero.
+    pub filename: &'data [u8],
+
+    /// Transfer mode (`MODE_*`), excluding the terminating zero.
+    pub mode: &'data [u8],
+
+    /// Options following the mode (see `Options`).
+    pub options: &'data [u8],
+}
+
+/// Option Iterator
+///
+/// An iterator over the options of a request or OACK, yielding the option
+/// name and value, both excluding the terminating zero. Iteration stops at
+/// the end of the data, or at the first unterminated option.
+#[derive(Clone, Debug)]
+pub struct Options<'data> {
+    data: &'data [u8],
+}
+
+impl Header {
+    /// Import a header from a byte slice
+    ///
+    /// Create a new header from data copied from a byte slice. No byte-order
+    /// conversions are applied.
+    pub fn from_bytes(data: &[u8; 2]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 2], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl BlockHeader {
+    /// Import a block header from a byte slice
+    ///
+    /// Create a new block header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 4]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 4], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl ErrorHeader {
+    /// Import an error header from a byte slice
+    ///
+    /// Create a new error header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 4]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 4], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+// Split the zero-terminated string at the start of `data` from the data
+// following it.
+fn split_string(data: &[u8]) -> Option<(&[u8], &[u8])> {
+    let n = data.iter().position(|v| *v == 0)?;
+    Some((&data[..n], &data[n + 1..]))
+}
+
+impl<'data> Request<'data> {
+    /// Parse a request
+    ///
+    /// Parse a read or write request, including its opcode. Return `None` if
+    /// the packet is not a request, or the file name or mode is not
+    /// terminated.
+    pub fn parse(data: &'data [u8]) -> Option<Self> {
+        let opcode = u16::from_be_bytes([*data.first()?, *data.get(1)?]);
+        if opcode != OPCODE_RRQ && opcode != OPCODE_WRQ {
+            return None;
+        }
+
+        let (filename, rest) = split_string(&data[2..])?;
+        let (mode, options) = split_string(rest)?;
+        Some(Self {
+            opcode,
+            filename,
+            mode,
+            options,
+        })
+    }
+
+    /// Check for mode
+    ///
+    /// Return whether the transfer mode matches `mode` (`MODE_*`), ignoring
+    /// ASCII case.
+    pub fn has_mode(&self, mode: &[u8]) -> bool {
+        self.mode.eq_ignore_ascii_case(mode)
+    }
+}
+
+impl<'data> Options<'data> {
+    /// Create option iterator
+    ///
+    /// Create a new iterator over the options in `data`, which must start
+    /// after the mode of a request, or after the opcode of an OACK.
+    pub fn new(data: &'data [u8]) -> Self {
+        Self {
+            data,
+        }
+    }
+
+    /// Return remaining data
+    ///
+    /// Return the data that has not been consumed by the iterator.
+    pub fn remainder(&self) -> &'data [u8] {
+        self.data
+    }
+}
+
+impl<'data> Iterator for Options<'data> {
+    type Item = (&'data [u8], &'data [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (name, rest) = split_string(self.data)?;
+        let (value, rest) = split_string(rest)?;
+        self.data = rest;
+        Some((name, value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A read request for a PXE boot loader with block size and size options.
+    const RRQ: [u8; 43] = [
+        0x00, 0x01, 0x70, 0x78, 0x65, 0x6c, 0x69, 0x6e,
+        0x75, 0x78, 0x2e, 0x30, 0x00, 0x6f, 0x63, 0x74,
+        0x65, 0x74, 0x00, 0x62, 0x6c, 0x6b, 0x73, 0x69,
+        0x7a, 0x65, 0x00, 0x31, 0x34, 0x36, 0x38, 0x00,
+        0x74, 0x73, 0x69, 0x7a, 0x65, 0x00, 0x30, 0x00,
+        0x00, 0x00, 0x00,
+    ];
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<Header>(), HEADER_SIZE);
+        assert_eq!(align_of::<Header>(), 2);
+        assert_eq!(size_of::<BlockHeader>(), BLOCK_HEADER_SIZE);
+        assert_eq!(align_of::<BlockHeader>(), 2);
+        assert_eq!(size_of::<ErrorHeader>(), 4);
+        assert_eq!(align_of::<ErrorHeader>(), 2);
+    }
+
+    // Parse a read request and its options.
+    #[test]
+    fn verify_request() {
+        let r = Request::parse(&RRQ).unwrap();
+        assert_eq!(r.opcode, OPCODE_RRQ);
+        assert_eq!(r.filename, b"pxelinux.0");
+        assert!(r.has_mode(b"OCTET"));
+        assert!(!r.has_mode(MODE_NETASCII));
+
+        let mut opts = Options::new(r.options);
+        assert_eq!(opts.next(), Some((OPTION_BLKSIZE, &b"1468"[..])));
+        assert_eq!(opts.next(), Some((OPTION_TSIZE, &b"0"[..])));
+        assert_eq!(opts.next(), Some((&b""[..], &b""[..])));
+        assert!(opts.next().is_none());
+        assert_eq!(opts.remainder(), &[0]);
+
+        assert!(Request::parse(&RRQ[..18]).is_none());
+        assert!(Request::parse(&[0x00, 0x03, 0x00, 0x00]).is_none());
+    }
+
+    // Parse data, ack, and error packets.
+    #[test]
+    fn verify_blocks() {
+        let h = BlockHeader::from_bytes(&[0x00, 0x03, 0xff, 0xff]);
+        assert_eq!(int::ForeignEndian::to_native(h.opcode), OPCODE_DATA);
+        assert_eq!(int::ForeignEndian::to_native(h.block), 0xffff);
+
+        let h = Header::from_bytes(&[0x00, 0x04]);
+        assert_eq!(int::ForeignEndian::to_native(h.opcode), OPCODE_ACK);
+
+        let data = b"\x00\x05\x00\x01not found\x00";
+        let h = ErrorHeader::from_bytes(data[..4].try_into().unwrap());
+        assert_eq!(int::ForeignEndian::to_native(h.opcode), OPCODE_ERROR);
+        assert_eq!(int::ForeignEndian::to_native(h.error_code), ERROR_NOT_FOUND);
+        assert_eq!(split_string(&data[4..]), Some((&b"not found"[..], &b""[..])));
+    }
+}
@@ -0,0 +1,546 @@
+//! Thrift Compact Protocol
+//!
+//! The compact protocol is the space-efficient wire format of Apache Thrift,
+//! also used by Parquet for its metadata. Like Protocol Buffers, it is not
+//! self-describing beyond the type of each value, so field names and the
+//! meaning of values are given by the schema.
+//!
+//! A struct is a sequence of fields terminated by `TYPE_STOP`. Each field
+//! starts with a field header, which carries the type (`TYPE_*`) in its low
+//! nibble and, if possible, the field id as delta to the previous field id
+//! in its high nibble. Otherwise, the delta is 0 and the field id follows as
+//! zigzag varint (see `FieldHeader`). Boolean fields carry their value in
+//! the type and have no value bytes.
+//!
+//! Values are encoded as follows:
+//!
+//!  * `TYPE_I16`, `TYPE_I32`, and `TYPE_I64` are zigzag varints (see
+//!    `varint::zigzag_decode()`), and `TYPE_BYTE` is a single byte.
+//!  * `TYPE_DOUBLE` is 8 bytes, and `TYPE_UUID` is 16 bytes.
+//!  * `TYPE_BINARY` is prefixed with its size as varint, and carries strings
+//!    and binary data.
+//!  * `TYPE_LIST` and `TYPE_SET` start with a collection header (see
+//!    `ListHeader`), followed by the elements. Boolean elements are a single
+//!    byte each.
+//!  * `TYPE_MAP` starts with a map header (see `MapHeader`), followed by
+//!    alternating keys and values.
+//!  * `TYPE_STRUCT` is an embedded struct, terminated by `TYPE_STOP`.
+//!
+//! RPC messages start with a message header (see `MessageHeader`), followed
+//! by a struct with the arguments or result.
+//!
+//! Unlike most network protocols, fixed-size numbers are encoded as
+//! little-endian.
+
+use crate::specs::varint;
+
+pub const TYPE_STOP: u8 = 0x0;
+pub const TYPE_BOOLEAN_TRUE: u8 = 0x1;
+pub const TYPE_BOOLEAN_FALSE: u8 = 0x2;
+pub const TYPE_BYTE: u8 = 0x3;
+pub const TYPE_I16: u8 = 0x4;
+pub const TYPE_I32: u8 = 0x5;
+pub const TYPE_I64: u8 = 0x6;
+pub const TYPE_DOUBLE: u8 = 0x7;
+pub const TYPE_BINARY: u8 = 0x8;
+pub const TYPE_LIST: u8 = 0x9;
+pub const TYPE_SET: u8 = 0xa;
+pub const TYPE_MAP: u8 = 0xb;
+pub const TYPE_STRUCT: u8 = 0xc;
+pub const TYPE_UUID: u8 = 0xd;
+
+pub const TYPE_MASK: u8 = 0x0f;
+pub const DELTA_SHIFT: u32 = 4;
+pub const DELTA_MAX: i16 = 15;
+
+pub const LIST_SIZE_SHIFT: u32 = 4;
+pub const LIST_SIZE_LONG: u8 = 0xf;
+
+pub const DOUBLE_SIZE: usize = 8;
+pub const UUID_SIZE: usize = 16;
+
+pub const PROTOCOL_ID: u8 = 0x82;
+pub const VERSION: u8 = 1;
+pub const VERSION_MASK: u8 = 0x1f;
+pub const MESSAGE_TYPE_SHIFT: u32 = 5;
+
+pub const MESSAGE_CALL: u8 = 1;
+pub const MESSAGE_REPLY: u8 = 2;
+pub const MESSAGE_EXCEPTION: u8 = 3;
+pub const MESSAGE_ONEWAY: u8 = 4;
+
+/// Maximum Nesting Depth
+///
+/// Nesting depth of structs and collections supported when skipping values,
+/// which matches the default recursion limit of the reference
+/// implementation.
+pub const DEPTH_MAX: usize = 64;
+
+/// Field Header
+#[derive(Clone, Copy, Debug)]
+pub struct FieldHeader {
+    /// Field id.
+    pub id: i16,
+
+    /// Field type (`TYPE_*`).
+    pub field_type: u8,
+
+    /// Size of the header in bytes.
+    pub size: usize,
+}
+
+/// List and Set Header
+#[derive(Clone, Copy, Debug)]
+pub struct ListHeader {
+    /// Element type (`TYPE_*`).
+    pub element_type: u8,
+
+    /// Number of elements.
+    pub count: u32,
+
+    /// Size of the header in bytes.
+    pub size: usize,
+}
+
+/// Map Header
+#[derive(Clone, Copy, Debug)]
+pub struct MapHeader {
+    /// Key type (`TYPE_*`), or `TYPE_STOP` for empty maps.
+    pub key_type: u8,
+
+    /// Value type (`TYPE_*`), or `TYPE_STOP` for empty maps.
+    pub value_type: u8,
+
+    /// Number of entries.
+    pub count: u32,
+
+    /// Size of the header in bytes.
+    pub size: usize,
+}
+
+/// Message Header
+#[derive(Clone, Copy, Debug)]
+pub struct MessageHeader<'data> {
+    /// Message type (`MESSAGE_*`).
+    pub message_type: u8,
+
+    /// Sequence id to match replies with calls.
+    pub seqid: i32,
+
+    /// Method name.
+    pub name: &'data [u8],
+
+    /// Size of the header in bytes.
+    pub size: usize,
+}
+
+/// Field Reference
+#[derive(Clone, Copy, Debug)]
+pub struct Field<'data> {
+    /// Field id.
+    pub id: i16,
+
+    /// Field type (`TYPE_*`).
+    pub field_type: u8,
+
+    /// Encoded value. For `TYPE_BINARY`, this excludes the size, and for
+    /// `TYPE_STRUCT`, this includes the terminating `TYPE_STOP`.
+    pub value: &'data [u8],
+}
+
+/// Field Iterator
+///
+/// Iterate over the fields of an encoded struct. Iteration stops at
+/// `TYPE_STOP`, as well as at the first truncated or invalid field.
+#[derive(Clone, Debug)]
+pub struct Fields<'data> {
+    data: &'data [u8],
+    last: i16,
+}
+
+// Decode a varint that must fit into 32 bits.
+fn varint32(data: &[u8]) -> Option<(u32, usize)> {
+    let (v, n) = varint::leb128_decode(data)?;
+    Some((u32::try_from(v).ok()?, n))
+}
+
+// Decode a zigzag varint that must fit into 16 bits.
+fn zigzag16(data: &[u8]) -> Option<(i16, usize)> {
+    let (v, n) = varint::leb128_decode(data)?;
+    Some((i16::try_from(varint::zigzag_decode(v)).ok()?, n))
+}
+
+impl FieldHeader {
+    /// Parse field header
+    ///
+    /// Parse the field header at the start of `data`, with `last` being the
+    /// id of the previous field of the struct, or 0 for the first field.
+    /// Return `None` for `TYPE_STOP`, if the header is truncated, or the type
+    /// is invalid.
+    pub fn parse(data: &[u8], last: i16) -> Option<Self> {
+        let first = *data.first()?;
+        let field_type = first & TYPE_MASK;
+        if field_type == TYPE_STOP || field_type > TYPE_UUID {
+            return None;
+        }
+
+        let delta = (first >> DELTA_SHIFT) as i16;
+        let (id, size) = match delta {
+            0 => {
+                let (id, n) = zigzag16(&data[1..])?;
+                (id, 1 + n)
+            },
+            _ => (last.checked_add(delta)?, 1),
+        };
+
+        Some(Self { id, field_type, size })
+    }
+
+    /// Encode field header
+    ///
+    /// Encode the header of a field, with `last` being the id of the
+    /// previous field of the struct, or 0 for the first field. The short
+    /// form is used if possible. Return the number of bytes written, or
+    /// `None` if the buffer is too small.
+    pub fn encode(id: i16, field_type: u8, last: i16, out: &mut [u8]) -> Option<usize> {
+        let delta = id as i32 - last as i32;
+        if delta > 0 && delta <= DELTA_MAX as i32 {
+            *out.first_mut()? = ((delta as u8) << DELTA_SHIFT) | field_type;
+            return Some(1);
+        }
+
+        *out.first_mut()? = field_type;
+        let v = varint::zigzag_encode(id as i64);
+        Some(1 + varint::leb128_encode(v, &mut out[1..])?)
+    }
+}
+
+impl ListHeader {
+    /// Parse list or set header
+    ///
+    /// Parse the collection header at the start of `data`. Return `None` if
+    /// the header is truncated, or the element type is invalid.
+    pub fn parse(data: &[u8]) -> Option<Self> {
+        let first = *data.first()?;
+        let element_type = first & TYPE_MASK;
+        if element_type == TYPE_STOP || element_type > TYPE_UUID {
+            return None;
+        }
+
+        let (count, size) = match first >> LIST_SIZE_SHIFT {
+            LIST_SIZE_LONG => {
+                let (count, n) = varint32(&data[1..])?;
+                (count, 1 + n)
+            },
+            v => (v as u32, 1),
+        };
+
+        Some(Self { element_type, count, size })
+    }
+
+    /// Encode list or set header
+    ///
+    /// Encode the collection header. Return the number of bytes written, or
+    /// `None` if the buffer is too small.
+    pub fn encode(element_type: u8, count: u32, out: &mut [u8]) -> Option<usize> {
+        if count < LIST_SIZE_LONG as u32 {
+            *out.first_mut()? = ((count as u8) << LIST_SIZE_SHIFT) | element_type;
+            return Some(1);
+        }
+
+        *out.first_mut()? = (LIST_SIZE_LONG << LIST_SIZE_SHIFT) | element_type;
+        Some(1 + varint::leb128_encode(count as u64, &mut out[1..])?)
+    }
+}
+
+impl MapHeader {
+    /// Parse map header
+    ///
+    /// Parse the map header at the start of `data`. Empty maps are encoded
+    /// as a single zero byte, without key and value type. Return `None` if
+    /// the header is truncated, or a type is invalid.
+    pub fn parse(data: &[u8]) -> Option<Self> {
+        let (count, n) = varint32(data)?;
+        if count == 0 {
+            return Some(Self { key_type: TYPE_STOP, value_type: TYPE_STOP, count, size: n });
+        }
+
+        let types = *data.get(n)?;
+        let key_type = types >> 4;
+        let value_type = types & TYPE_MASK;
+        for t in [key_type, value_type] {
+            if t == TYPE_STOP || t > TYPE_UUID {
+                return None;
+            }
+        }
+
+        Some(Self { key_type, value_type, count, size: n + 1 })
+    }
+
+    /// Encode map header
+    ///
+    /// Encode the map header. Return the number of bytes written, or `None`
+    /// if the buffer is too small.
+    pub fn encode(key_type: u8, value_type: u8, count: u32, out: &mut [u8]) -> Option<usize> {
+        let n = varint::leb128_encode(count as u64, out)?;
+        if count == 0 {
+            return Some(n);
+        }
+
+        *out.get_mut(n)? = (key_type << 4) | value_type;
+        Some(n + 1)
+    }
+}
+
+impl<'data> MessageHeader<'data> {
+    /// Parse message header
+    ///
+    /// Parse the message header at the start of `data`. Return `None` if
+    /// the header is truncated, or the protocol id or version do not match.
+    pub fn parse(data: &'data [u8]) -> Option<Self> {
+        if *data.first()? != PROTOCOL_ID {
+            return None;
+        }
+
+        let v = *data.get(1)?;
+        if v & VERSION_MASK != VERSION {
+            return None;
+        }
+
+        let (seqid, n) = varint32(&data[2..])?;
+        let (length, m) = varint32(&data[2 + n..])?;
+        let start = 2 + n + m;
+        let end = start.checked_add(length as usize)?;
+
+        Some(Self {
+            message_type: v >> MESSAGE_TYPE_SHIFT,
+            seqid: seqid as i32,
+            name: data.get(start..end)?,
+            size: end,
+        })
+    }
+}
+
+impl<'data> Field<'data> {
+    /// Return boolean value
+    pub fn boolean(&self) -> Option<bool> {
+        match self.field_type {
+            TYPE_BOOLEAN_TRUE => Some(true),
+            TYPE_BOOLEAN_FALSE => Some(false),
+            _ => None,
+        }
+    }
+
+    /// Decode integer value
+    ///
+    /// Decode the value of `TYPE_BYTE`, `TYPE_I16`, `TYPE_I32`, and
+    /// `TYPE_I64` fields.
+    pub fn int(&self) -> Option<i64> {
+        match self.field_type {
+            TYPE_BYTE => Some(*self.value.first()? as i8 as i64),
+            TYPE_I16 | TYPE_I32 | TYPE_I64 => {
+                Some(varint::zigzag_decode(varint::leb128_decode(self.value)?.0))
+            },
+            _ => None,
+        }
+    }
+
+    /// Decode double value
+    pub fn double(&self) -> Option<f64> {
+        match self.field_type {
+            TYPE_DOUBLE => Some(f64::from_le_bytes(self.value.try_into().ok()?)),
+            _ => None,
+        }
+    }
+
+    /// Return fields of embedded structs
+    pub fn fields(&self) -> Option<Fields<'data>> {
+        match self.field_type {
+            TYPE_STRUCT => Some(Fields::new(self.value)),
+            _ => None,
+        }
+    }
+}
+
+impl<'data> Fields<'data> {
+    /// Create field iterator
+    ///
+    /// Create a new iterator over the fields of the encoded struct `data`.
+    pub fn new(data: &'data [u8]) -> Self {
+        Self { data, last: 0 }
+    }
+
+    /// Return remaining data
+    ///
+    /// Return the data that has not been consumed by the iterator.
+    pub fn remainder(&self) -> &'data [u8] {
+        self.data
+    }
+}
+
+impl<'data> Iterator for Fields<'data> {
+    type Item = Field<'data>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let h = FieldHeader::parse(self.data, self.last)?;
+        let rest = &self.data[h.size..];
+
+        let (value, size) = match h.field_type {
+            TYPE_BOOLEAN_TRUE | TYPE_BOOLEAN_FALSE => (&rest[..0], 0),
+            TYPE_BINARY => {
+                let (length, m) = varint32(rest)?;
+                let end = m.checked_add(length as usize)?;
+                (rest.get(m..end)?, end)
+            },
+            t => {
+                let size = value_size(t, rest)?;
+                (&rest[..size], size)
+            },
+        };
+
+        self.data = &rest[size..];
+        self.last = h.id;
+        Some(Field { id: h.id, field_type: h.field_type, value })
+    }
+}
+
+// Return the size of a value, limiting the nesting depth of containers.
+fn skip(value_type: u8, data: &[u8], depth: usize) -> Option<usize> {
+    let n = match value_type {
+        TYPE_BOOLEAN_TRUE | TYPE_BOOLEAN_FALSE | TYPE_BYTE => 1,
+        TYPE_I16 | TYPE_I32 | TYPE_I64 => varint::leb128_decode(data)?.1,
+        TYPE_DOUBLE => DOUBLE_SIZE,
+        TYPE_UUID => UUID_SIZE,
+        TYPE_BINARY => {
+            let (length, m) = varint32(data)?;
+            m.checked_add(length as usize)?
+        },
+        TYPE_LIST | TYPE_SET => {
+            let h = ListHeader::parse(data)?;
+            let mut end = h.size;
+            for _ in 0..h.count {
+                end += skip(h.element_type, data.get(end..)?, depth.checked_sub(1)?)?;
+            }
+            end
+        },
+        TYPE_MAP => {
+            let h = MapHeader::parse(data)?;
+            let mut end = h.size;
+            for _ in 0..h.count {
+                end += skip(h.key_type, data.get(end..)?, depth.checked_sub(1)?)?;
+                end += skip(h.value_type, data.get(end..)?, depth.checked_sub(1)?)?;
+            }
+            end
+        },
+        TYPE_STRUCT => {
+            let mut last = 0;
+            let mut end = 0;
+            loop {
+                if *data.get(end)? == TYPE_STOP {
+                    break end + 1;
+                }
+                let h = FieldHeader::parse(&data[end..], last)?;
+                end += h.size;
+                end += match h.field_type {
+                    TYPE_BOOLEAN_TRUE | TYPE_BOOLEAN_FALSE => 0,
+                    t => skip(t, &data[end..], depth.checked_sub(1)?)?,
+                };
+                last = h.id;
+            }
+        },
+        _ => return None,
+    };
+
+    if n > data.len() {
+        return None;
+    }
+    Some(n)
+}
+
+/// Return value size
+///
+/// Return the size of the value of the given type at the start of `data`,
+/// as encoded in a collection, including headers and sizes. Boolean values
+/// are encoded as a single byte in collections, `TYPE_BOOLEAN_TRUE` for
+/// true, and any other value for false. Return `None` if the value is
+/// truncated, invalid, or nested deeper than `DEPTH_MAX`.
+pub fn value_size(value_type: u8, data: &[u8]) -> Option<usize> {
+    skip(value_type, data, DEPTH_MAX)
+}
+
+/// Find field
+///
+/// Return the field with the given id in the encoded struct `data`.
+pub fn find_field(data: &[u8], id: i16) -> Option<Field<'_>> {
+    Fields::new(data).find(|v| v.id == id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Struct with `1: i32 = 150`, `2: string = "hi"`, `3: bool = true`,
+    // `20: list<i16> = [1, -1]`, `21: struct = {}`, and `22: map = {}`.
+    const STRUCT: [u8; 18] = [
+        0x15, 0xac, 0x02, 0x18, 0x02, 0x68, 0x69, 0x11,
+        0x09, 0x28, 0x24, 0x02, 0x01, 0x1c, 0x00, 0x1b,
+        0x00, 0x00,
+    ];
+
+    // Iterate fields of a struct.
+    #[test]
+    fn verify_fields() {
+        let mut f = Fields::new(&STRUCT);
+        let v = f.next().unwrap();
+        assert_eq!((v.id, v.int()), (1, Some(150)));
+        let v = f.next().unwrap();
+        assert_eq!((v.id, v.value), (2, &b"hi"[..]));
+        let v = f.next().unwrap();
+        assert_eq!((v.id, v.boolean(), v.value.len()), (3, Some(true), 0));
+        let v = f.next().unwrap();
+        assert_eq!((v.id, v.field_type, v.value), (20, TYPE_LIST, &[0x24, 0x02, 0x01][..]));
+        let l = ListHeader::parse(v.value).unwrap();
+        assert_eq!((l.element_type, l.count, l.size), (TYPE_I16, 2, 1));
+        let v = f.next().unwrap();
+        assert_eq!((v.id, v.fields().unwrap().count()), (21, 0));
+        let v = f.next().unwrap();
+        let m = MapHeader::parse(v.value).unwrap();
+        assert_eq!((v.id, m.count, m.size), (22, 0, 1));
+        assert!(f.next().is_none());
+        assert_eq!(f.remainder(), [0x00]);
+
+        assert_eq!(value_size(TYPE_STRUCT, &STRUCT), Some(STRUCT.len()));
+        assert_eq!(value_size(TYPE_STRUCT, &STRUCT[..17]), None);
+        assert_eq!(find_field(&STRUCT, 2).unwrap().value, b"hi");
+        assert!(find_field(&STRUCT, 4).is_none());
+        assert_eq!(value_size(TYPE_LIST, &[0x19; DEPTH_MAX + 1]), None);
+    }
+
+    // Encode and decode headers.
+    #[test]
+    fn verify_headers() {
+        let mut buf = [0; 8];
+
+        assert_eq!(FieldHeader::encode(5, TYPE_I32, 1, &mut buf), Some(1));
+        assert_eq!(buf[0], 0x45);
+        assert_eq!(FieldHeader::encode(-1, TYPE_BINARY, 1, &mut buf), Some(2));
+        assert_eq!(buf[..2], [0x08, 0x01]);
+        let h = FieldHeader::parse(&buf, 1).unwrap();
+        assert_eq!((h.id, h.field_type, h.size), (-1, TYPE_BINARY, 2));
+        assert!(FieldHeader::parse(&[0x00], 0).is_none());
+        assert!(FieldHeader::parse(&[0x1e], 0).is_none());
+
+        assert_eq!(ListHeader::encode(TYPE_BYTE, 300, &mut buf), Some(3));
+        assert_eq!(buf[..3], [0xf3, 0xac, 0x02]);
+        let h = ListHeader::parse(&buf).unwrap();
+        assert_eq!((h.element_type, h.count, h.size), (TYPE_BYTE, 300, 3));
+        assert_eq!(MapHeader::encode(TYPE_BINARY, TYPE_I64, 2, &mut buf), Some(2));
+        assert_eq!(buf[..2], [0x02, 0x86]);
+        assert_eq!(MapHeader::parse(&buf).unwrap().value_type, TYPE_I64);
+
+        let m = MessageHeader::parse(b"\x82\x21\x01\x04ping\x00").unwrap();
+        assert_eq!((m.message_type, m.seqid, m.name, m.size), (MESSAGE_CALL, 1, &b"ping"[..], 8));
+        assert!(MessageHeader::parse(b"\x82\x22\x01\x04ping").is_none());
+        assert!(MessageHeader::parse(b"\x82\x21\x01\x04pin").is_none());
+    }
+}
@@ -0,0 +1,464 @@
+//! Tagged Image File Format
+//!
+//! The Tagged Image File Format (TIFF) stores images and their metadata as a
+//! linked list of Image File Directories (IFDs). Each IFD is a list of tagged
+//! entries, followed by the offset of the next IFD. Entries carry a field type
+//! and a count, and either store their value inline, if it fits, or the
+//! offset of the value. All offsets are relative to the start of the header.
+//!
+//! The byte order of a file is selected by the first two bytes of the header:
+//! `II` for little-endian, and `MM` for big-endian. It applies to all
+//! multi-byte integers of the file, hence all structures use
+//! `int::RuntimeEndian` and require the byte order on access.
+//!
+//! A classic TIFF file uses 32-bit offsets and counts. BigTIFF uses a
+//! different magic number and extends all offsets and counts to 64 bits,
+//! with the IFD entry count extended to 64 bits as well. Inline values can
+//! then be up to 8 bytes in size.
+//!
+//! The classic layout of an IFD is a 16-bit entry count, the `IfdEntry`
+//! array, and a 32-bit offset of the next IFD, or 0. BigTIFF uses a 64-bit
+//! entry count, the `BigIfdEntry` array, and a 64-bit next offset. Entries
+//! must be sorted by ascending tag.
+//!
+//! The same structures are used by Exif and several camera raw formats.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::int;
+
+pub const BYTE_ORDER_LITTLE: [u8; 2] = *b"II";
+pub const BYTE_ORDER_BIG: [u8; 2] = *b"MM";
+
+/// Magic Number of Classic TIFF
+pub const MAGIC: u16 = 42;
+
+/// Magic Number of BigTIFF
+pub const MAGIC_BIG: u16 = 43;
+
+/// Offset Size of BigTIFF
+///
+/// The size of offsets in bytes, as stored in the BigTIFF header. No other
+/// value is defined.
+pub const BIG_OFFSET_SIZE: u16 = 8;
+
+pub const TYPE_BYTE: u16 = 1;
+pub const TYPE_ASCII: u16 = 2;
+pub const TYPE_SHORT: u16 = 3;
+pub const TYPE_LONG: u16 = 4;
+pub const TYPE_RATIONAL: u16 = 5;
+pub const TYPE_SBYTE: u16 = 6;
+pub const TYPE_UNDEFINED: u16 = 7;
+pub const TYPE_SSHORT: u16 = 8;
+pub const TYPE_SLONG: u16 = 9;
+pub const TYPE_SRATIONAL: u16 = 10;
+pub const TYPE_FLOAT: u16 = 11;
+pub const TYPE_DOUBLE: u16 = 12;
+pub const TYPE_IFD: u16 = 13;
+pub const TYPE_LONG8: u16 = 16;
+pub const TYPE_SLONG8: u16 = 17;
+pub const TYPE_IFD8: u16 = 18;
+
+pub const TAG_NEW_SUBFILE_TYPE: u16 = 0x00fe;
+pub const TAG_SUBFILE_TYPE: u16 = 0x00ff;
+pub const TAG_IMAGE_WIDTH: u16 = 0x0100;
+pub const TAG_IMAGE_LENGTH: u16 = 0x0101;
+pub const TAG_BITS_PER_SAMPLE: u16 = 0x0102;
+pub const TAG_COMPRESSION: u16 = 0x0103;
+pub const TAG_PHOTOMETRIC_INTERPRETATION: u16 = 0x0106;
+pub const TAG_FILL_ORDER: u16 = 0x010a;
+pub const TAG_DOCUMENT_NAME: u16 = 0x010d;
+pub const TAG_IMAGE_DESCRIPTION: u16 = 0x010e;
+pub const TAG_MAKE: u16 = 0x010f;
+pub const TAG_MODEL: u16 = 0x0110;
+pub const TAG_STRIP_OFFSETS: u16 = 0x0111;
+pub const TAG_ORIENTATION: u16 = 0x0112;
+pub const TAG_SAMPLES_PER_PIXEL: u16 = 0x0115;
+pub const TAG_ROWS_PER_STRIP: u16 = 0x0116;
+pub const TAG_STRIP_BYTE_COUNTS: u16 = 0x0117;
+pub const TAG_X_RESOLUTION: u16 = 0x011a;
+pub const TAG_Y_RESOLUTION: u16 = 0x011b;
+pub const TAG_PLANAR_CONFIGURATION: u16 = 0x011c;
+pub const TAG_RESOLUTION_UNIT: u16 = 0x0128;
+pub const TAG_PAGE_NUMBER: u16 = 0x0129;
+pub const TAG_SOFTWARE: u16 = 0x0131;
+pub const TAG_DATE_TIME: u16 = 0x0132;
+pub const TAG_ARTIST: u16 = 0x013b;
+pub const TAG_HOST_COMPUTER: u16 = 0x013c;
+pub const TAG_PREDICTOR: u16 = 0x013d;
+pub const TAG_COLOR_MAP: u16 = 0x0140;
+pub const TAG_TILE_WIDTH: u16 = 0x0142;
+pub const TAG_TILE_LENGTH: u16 = 0x0143;
+pub const TAG_TILE_OFFSETS: u16 = 0x0144;
+pub const TAG_TILE_BYTE_COUNTS: u16 = 0x0145;
+pub const TAG_SUB_IFDS: u16 = 0x014a;
+pub const TAG_EXTRA_SAMPLES: u16 = 0x0152;
+pub const TAG_SAMPLE_FORMAT: u16 = 0x0153;
+pub const TAG_JPEG_TABLES: u16 = 0x015b;
+pub const TAG_YCBCR_SUBSAMPLING: u16 = 0x0212;
+pub const TAG_REFERENCE_BLACK_WHITE: u16 = 0x0214;
+pub const TAG_XMP: u16 = 0x02bc;
+pub const TAG_COPYRIGHT: u16 = 0x8298;
+pub const TAG_IPTC: u16 = 0x83bb;
+pub const TAG_PHOTOSHOP: u16 = 0x8649;
+pub const TAG_EXIF_IFD: u16 = 0x8769;
+pub const TAG_ICC_PROFILE: u16 = 0x8773;
+pub const TAG_GPS_IFD: u16 = 0x8825;
+
+pub const COMPRESSION_NONE: u16 = 1;
+pub const COMPRESSION_CCITT_RLE: u16 = 2;
+pub const COMPRESSION_CCITT_T4: u16 = 3;
+pub const COMPRESSION_CCITT_T6: u16 = 4;
+pub const COMPRESSION_LZW: u16 = 5;
+pub const COMPRESSION_OJPEG: u16 = 6;
+pub const COMPRESSION_JPEG: u16 = 7;
+pub const COMPRESSION_DEFLATE: u16 = 8;
+pub const COMPRESSION_PACKBITS: u16 = 32773;
+
+pub const PHOTOMETRIC_MIN_IS_WHITE: u16 = 0;
+pub const PHOTOMETRIC_MIN_IS_BLACK: u16 = 1;
+pub const PHOTOMETRIC_RGB: u16 = 2;
+pub const PHOTOMETRIC_PALETTE: u16 = 3;
+pub const PHOTOMETRIC_MASK: u16 = 4;
+pub const PHOTOMETRIC_SEPARATED: u16 = 5;
+pub const PHOTOMETRIC_YCBCR: u16 = 6;
+pub const PHOTOMETRIC_CIELAB: u16 = 8;
+
+pub const RESOLUTION_UNIT_NONE: u16 = 1;
+pub const RESOLUTION_UNIT_INCH: u16 = 2;
+pub const RESOLUTION_UNIT_CENTIMETER: u16 = 3;
+
+/// Classic Header
+#[repr(C)]
+pub struct Header {
+    /// Byte order, either `BYTE_ORDER_LITTLE` or `BYTE_ORDER_BIG`.
+    pub byte_order: [u8; 2],
+
+    /// Magic number, must be `MAGIC`.
+    pub magic: int::u16re,
+
+    /// Offset of the first IFD.
+    pub ifd_offset: int::u32re,
+}
+
+/// BigTIFF Header
+#[repr(C)]
+pub struct BigHeader {
+    /// Byte order, either `BYTE_ORDER_LITTLE` or `BYTE_ORDER_BIG`.
+    pub byte_order: [u8; 2],
+
+    /// Magic number, must be `MAGIC_BIG`.
+    pub magic: int::u16re,
+
+    /// Size of offsets in bytes, must be `BIG_OFFSET_SIZE`.
+    pub offset_size: int::u16re,
+
+    /// Reserved, must be 0.
+    pub reserved: int::u16re,
+
+    /// Offset of the first IFD.
+    pub ifd_offset: int::u64re,
+}
+
+/// Classic IFD Entry
+#[repr(C)]
+pub struct IfdEntry {
+    /// Tag identifying the field (`TAG_*`).
+    pub tag: int::u16re,
+
+    /// Field type (`TYPE_*`).
+    pub field_type: int::u16re,
+
+    /// Number of values of the field type.
+    pub count: int::u32re,
+
+    /// The value if it fits into 4 bytes, left-aligned. Otherwise, the offset
+    /// of the value.
+    pub value: [u8; 4],
+}
+
+/// BigTIFF IFD Entry
+///
+/// Since the count is not naturally aligned, this structure is packed.
+#[repr(C, packed)]
+pub struct BigIfdEntry {
+    /// Tag identifying the field (`TAG_*`).
+    pub tag: int::u16re,
+
+    /// Field type (`TYPE_*`).
+    pub field_type: int::u16re,
+
+    /// Number of values of the field type.
+    pub count: int::u64re,
+
+    /// The value if it fits into 8 bytes, left-aligned. Otherwise, the offset
+    /// of the value.
+    pub value: [u8; 8],
+}
+
+impl Header {
+    /// Import a header from a byte slice
+    ///
+    /// Create a new header from data copied from a byte slice. No byte-order
+    /// conversions are applied.
+    pub fn from_bytes(data: &[u8; 8]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 8], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Return byte order
+    ///
+    /// Return the byte order of the file, or `None` if the header carries an
+    /// invalid byte-order mark.
+    pub fn endianness(&self) -> Option<int::Endianness> {
+        endianness(&self.byte_order)
+    }
+}
+
+impl BigHeader {
+    /// Import a header from a byte slice
+    ///
+    /// Create a new header from data copied from a byte slice. No byte-order
+    /// conversions are applied.
+    pub fn from_bytes(data: &[u8; 16]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 16], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Return byte order
+    ///
+    /// Return the byte order of the file, or `None` if the header carries an
+    /// invalid byte-order mark.
+    pub fn endianness(&self) -> Option<int::Endianness> {
+        endianness(&self.byte_order)
+    }
+}
+
+impl IfdEntry {
+    /// Import an IFD entry from a byte slice
+    ///
+    /// Create a new IFD entry from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 12]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 12], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Return value size
+    ///
+    /// Return the size of the value in bytes, or `None` if the field type is
+    /// unknown or the size overflows.
+    pub fn value_size(&self, endian: int::Endianness) -> Option<u64> {
+        let size = type_size(self.field_type.to_native(endian))?;
+        (size as u64).checked_mul(self.count.to_native(endian) as u64)
+    }
+
+    /// Return value offset
+    ///
+    /// Return the offset of the value, or `None` if the value is stored
+    /// inline (or its size is unknown).
+    pub fn value_offset(&self, endian: int::Endianness) -> Option<u32> {
+        if self.value_size(endian)? <= 4 {
+            None
+        } else {
+            Some(int::u32re::from_raw(u32::from_ne_bytes(self.value)).to_native(endian))
+        }
+    }
+}
+
+impl BigIfdEntry {
+    /// Import an IFD entry from a byte slice
+    ///
+    /// Create a new IFD entry from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 20]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 20], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Return value size
+    ///
+    /// Return the size of the value in bytes, or `None` if the field type is
+    /// unknown or the size overflows.
+    pub fn value_size(&self, endian: int::Endianness) -> Option<u64> {
+        let size = type_size(self.field_type.to_native(endian))?;
+        (size as u64).checked_mul(self.count.to_native(endian))
+    }
+
+    /// Return value offset
+    ///
+    /// Return the offset of the value, or `None` if the value is stored
+    /// inline (or its size is unknown).
+    pub fn value_offset(&self, endian: int::Endianness) -> Option<u64> {
+        if self.value_size(endian)? <= 8 {
+            None
+        } else {
+            Some(int::u64re::from_raw(u64::from_ne_bytes(self.value)).to_native(endian))
+        }
+    }
+}
+
+/// Decode Byte-order Mark
+///
+/// Return the byte order selected by the first two bytes of a header, or
+/// `None` if they are invalid.
+pub fn endianness(byte_order: &[u8; 2]) -> Option<int::Endianness> {
+    match *byte_order {
+        BYTE_ORDER_LITTLE => Some(int::Endianness::Little),
+        BYTE_ORDER_BIG => Some(int::Endianness::Big),
+        _ => None,
+    }
+}
+
+/// Return Field Type Size
+///
+/// Return the size in bytes of a single value of the given field type, or
+/// `None` if the type is unknown. Rational types consist of two 32-bit
+/// integers, numerator and denominator.
+pub fn type_size(field_type: u16) -> Option<usize> {
+    match field_type {
+        TYPE_BYTE | TYPE_ASCII | TYPE_SBYTE | TYPE_UNDEFINED => Some(1),
+        TYPE_SHORT | TYPE_SSHORT => Some(2),
+        TYPE_LONG | TYPE_SLONG | TYPE_FLOAT | TYPE_IFD => Some(4),
+        TYPE_RATIONAL | TYPE_SRATIONAL | TYPE_DOUBLE => Some(8),
+        TYPE_LONG8 | TYPE_SLONG8 | TYPE_IFD8 => Some(8),
+        _ => None,
+    }
+}
+
+/// Calculate IFD Size
+///
+/// Return the size in bytes of a classic IFD with `entries` entries,
+/// including the entry count and the next-IFD offset.
+pub fn ifd_size(entries: u16) -> usize {
+    2 + entries as usize * size_of::<IfdEntry>() + 4
+}
+
+/// Calculate BigTIFF IFD Size
+///
+/// Return the size in bytes of a BigTIFF IFD with `entries` entries,
+/// including the entry count and the next-IFD offset. `None` is returned on
+/// overflow.
+pub fn big_ifd_size(entries: u64) -> Option<u64> {
+    entries
+        .checked_mul(size_of::<BigIfdEntry>() as u64)?
+        .checked_add(16)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A classic big-endian header with an IFD of two entries, one inline and
+    // one with an out-of-line rational value.
+    const BE: [u8; 46] = [
+        0x4d, 0x4d, 0x00, 0x2a, 0x00, 0x00, 0x00, 0x08,
+        0x00, 0x02, 0x01, 0x00, 0x00, 0x03, 0x00, 0x00,
+        0x00, 0x01, 0x01, 0x40, 0x00, 0x00, 0x01, 0x1a,
+        0x00, 0x05, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00,
+        0x00, 0x26, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x48, 0x00, 0x00, 0x00, 0x01,
+    ];
+
+    // A little-endian BigTIFF header.
+    const BIG: [u8; 16] = [
+        0x49, 0x49, 0x2b, 0x00, 0x08, 0x00, 0x00, 0x00,
+        0x10, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+    ];
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<Header>(), 8);
+        assert_eq!(align_of::<Header>(), 4);
+        assert_eq!(size_of::<BigHeader>(), 16);
+        assert_eq!(align_of::<BigHeader>(), 8);
+        assert_eq!(size_of::<IfdEntry>(), 12);
+        assert_eq!(align_of::<IfdEntry>(), 4);
+        assert_eq!(size_of::<BigIfdEntry>(), 20);
+        assert_eq!(align_of::<BigIfdEntry>(), 1);
+    }
+
+    // Parse a classic big-endian file.
+    #[test]
+    fn verify_classic() {
+        let h = Header::from_bytes(BE[..8].try_into().unwrap());
+        let e = h.endianness().unwrap();
+        assert_eq!(e, int::Endianness::Big);
+        assert_eq!(h.magic.to_native(e), MAGIC);
+        assert_eq!(h.ifd_offset.to_native(e), 8);
+
+        let n = u16::from_be_bytes(BE[8..10].try_into().unwrap());
+        assert_eq!(n, 2);
+        assert_eq!(ifd_size(n), 30);
+
+        let a = IfdEntry::from_bytes(BE[10..22].try_into().unwrap());
+        assert_eq!(a.tag.to_native(e), TAG_IMAGE_WIDTH);
+        assert_eq!(a.field_type.to_native(e), TYPE_SHORT);
+        assert_eq!(a.value_size(e), Some(2));
+        assert_eq!(a.value_offset(e), None);
+        assert_eq!(
+            int::u16re::from_raw(u16::from_ne_bytes([a.value[0], a.value[1]])).to_native(e),
+            320,
+        );
+
+        let b = IfdEntry::from_bytes(BE[22..34].try_into().unwrap());
+        assert_eq!(b.tag.to_native(e), TAG_X_RESOLUTION);
+        assert_eq!(b.value_size(e), Some(8));
+        assert_eq!(b.value_offset(e), Some(38));
+        assert_eq!(u32::from_be_bytes(BE[34..38].try_into().unwrap()), 0);
+        assert_eq!(u32::from_be_bytes(BE[38..42].try_into().unwrap()), 72);
+    }
+
+    // Parse a BigTIFF header and verify runtime-endian round-trips.
+    #[test]
+    fn verify_big() {
+        let h = BigHeader::from_bytes(&BIG);
+        let e = h.endianness().unwrap();
+        assert_eq!(e, int::Endianness::Little);
+        assert_eq!(h.magic.to_native(e), MAGIC_BIG);
+        assert_eq!(h.offset_size.to_native(e), BIG_OFFSET_SIZE);
+        assert_eq!(h.ifd_offset.to_native(e), 16);
+        assert_eq!(big_ifd_size(1), Some(36));
+
+        let v = int::u32re::from_native(0x01020304, int::Endianness::Big);
+        assert_eq!(v.to_raw().to_ne_bytes(), [1, 2, 3, 4]);
+        assert_eq!(v.to_native(int::Endianness::Big), 0x01020304);
+        assert_eq!(v.to_native(int::Endianness::Little), 0x04030201);
+
+        assert_eq!(endianness(b"IM"), None);
+        assert_eq!(type_size(14), None);
+    }
+}
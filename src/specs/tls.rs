@@ -0,0 +1,556 @@
+//! Transport Layer Security
+//!
+//! TLS splits its data into records, each starting with a 5-byte
+//! `RecordHeader` carrying the content type (`CONTENT_*`), the legacy
+//! protocol version (`VERSION_*`), and the size of the fragment. Handshake
+//! records carry handshake messages, which start with a 4-byte
+//! `HandshakeHeader` carrying the message type (`HANDSHAKE_*`) and a 24-bit
+//! length. Handshake messages can span multiple records, and multiple
+//! messages can share a record.
+//!
+//! The hello messages are sent in plaintext (see `ClientHello` and
+//! `ServerHello`). Both end with a list of extensions (see `Extensions`),
+//! encoded as TLVs with 2-byte type (`EXTENSION_*`) and 2-byte length. TLS
+//! 1.3 negotiates its version via `EXTENSION_SUPPORTED_VERSIONS`, keeping
+//! `VERSION_TLS_1_2` in the legacy fields. Variable-length vectors carry a
+//! length prefix of 1, 2, or 3 bytes, depending on their maximum size.
+//!
+//! Clients may insert reserved GREASE values into lists of versions, cipher
+//! suites, extensions, and groups (see `is_grease()`), which must be ignored
+//! by peers and fingerprinting alike.
+//!
+//! All multi-byte integers are encoded as big-endian.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::int;
+
+pub const RECORD_HEADER_SIZE: usize = 5;
+pub const HANDSHAKE_HEADER_SIZE: usize = 4;
+pub const EXTENSION_HEADER_SIZE: usize = 4;
+pub const RANDOM_SIZE: usize = 32;
+pub const SESSION_ID_MAX: usize = 32;
+
+/// Maximum Size of Plaintext Record Fragments
+pub const RECORD_SIZE_MAX: usize = 16384;
+
+/// Random of HelloRetryRequest Messages
+pub const HELLO_RETRY_REQUEST_RANDOM: [u8; 32] = [
+    0xcf, 0x21, 0xad, 0x74, 0xe5, 0x9a, 0x61, 0x11,
+    0xbe, 0x1d, 0x8c, 0x02, 0x1e, 0x65, 0xb8, 0x91,
+    0xc2, 0xa2, 0x11, 0x16, 0x7a, 0xbb, 0x8c, 0x5e,
+    0x07, 0x9e, 0x09, 0xe2, 0xc8, 0xa8, 0x33, 0x9c,
+];
+
+pub const CONTENT_CHANGE_CIPHER_SPEC: u8 = 20;
+pub const CONTENT_ALERT: u8 = 21;
+pub const CONTENT_HANDSHAKE: u8 = 22;
+pub const CONTENT_APPLICATION_DATA: u8 = 23;
+pub const CONTENT_HEARTBEAT: u8 = 24;
+
+pub const VERSION_SSL_3_0: u16 = 0x0300;
+pub const VERSION_TLS_1_0: u16 = 0x0301;
+pub const VERSION_TLS_1_1: u16 = 0x0302;
+pub const VERSION_TLS_1_2: u16 = 0x0303;
+pub const VERSION_TLS_1_3: u16 = 0x0304;
+
+pub const HANDSHAKE_HELLO_REQUEST: u8 = 0;
+pub const HANDSHAKE_CLIENT_HELLO: u8 = 1;
+pub const HANDSHAKE_SERVER_HELLO: u8 = 2;
+pub const HANDSHAKE_NEW_SESSION_TICKET: u8 = 4;
+pub const HANDSHAKE_END_OF_EARLY_DATA: u8 = 5;
+pub const HANDSHAKE_ENCRYPTED_EXTENSIONS: u8 = 8;
+pub const HANDSHAKE_CERTIFICATE: u8 = 11;
+pub const HANDSHAKE_SERVER_KEY_EXCHANGE: u8 = 12;
+pub const HANDSHAKE_CERTIFICATE_REQUEST: u8 = 13;
+pub const HANDSHAKE_SERVER_HELLO_DONE: u8 = 14;
+pub const HANDSHAKE_CERTIFICATE_VERIFY: u8 = 15;
+pub const HANDSHAKE_CLIENT_KEY_EXCHANGE: u8 = 16;
+pub const HANDSHAKE_FINISHED: u8 = 20;
+pub const HANDSHAKE_CERTIFICATE_STATUS: u8 = 22;
+pub const HANDSHAKE_KEY_UPDATE: u8 = 24;
+pub const HANDSHAKE_MESSAGE_HASH: u8 = 254;
+
+pub const EXTENSION_SERVER_NAME: u16 = 0;
+pub const EXTENSION_MAX_FRAGMENT_LENGTH: u16 = 1;
+pub const EXTENSION_STATUS_REQUEST: u16 = 5;
+pub const EXTENSION_SUPPORTED_GROUPS: u16 = 10;
+pub const EXTENSION_EC_POINT_FORMATS: u16 = 11;
+pub const EXTENSION_SIGNATURE_ALGORITHMS: u16 = 13;
+pub const EXTENSION_USE_SRTP: u16 = 14;
+pub const EXTENSION_HEARTBEAT: u16 = 15;
+pub const EXTENSION_ALPN: u16 = 16;
+pub const EXTENSION_SIGNED_CERTIFICATE_TIMESTAMP: u16 = 18;
+pub const EXTENSION_PADDING: u16 = 21;
+pub const EXTENSION_ENCRYPT_THEN_MAC: u16 = 22;
+pub const EXTENSION_EXTENDED_MASTER_SECRET: u16 = 23;
+pub const EXTENSION_COMPRESS_CERTIFICATE: u16 = 27;
+pub const EXTENSION_RECORD_SIZE_LIMIT: u16 = 28;
+pub const EXTENSION_SESSION_TICKET: u16 = 35;
+pub const EXTENSION_PRE_SHARED_KEY: u16 = 41;
+pub const EXTENSION_EARLY_DATA: u16 = 42;
+pub const EXTENSION_SUPPORTED_VERSIONS: u16 = 43;
+pub const EXTENSION_COOKIE: u16 = 44;
+pub const EXTENSION_PSK_KEY_EXCHANGE_MODES: u16 = 45;
+pub const EXTENSION_CERTIFICATE_AUTHORITIES: u16 = 47;
+pub const EXTENSION_POST_HANDSHAKE_AUTH: u16 = 49;
+pub const EXTENSION_SIGNATURE_ALGORITHMS_CERT: u16 = 50;
+pub const EXTENSION_KEY_SHARE: u16 = 51;
+pub const EXTENSION_QUIC_TRANSPORT_PARAMETERS: u16 = 57;
+pub const EXTENSION_ENCRYPTED_CLIENT_HELLO: u16 = 0xfe0d;
+pub const EXTENSION_RENEGOTIATION_INFO: u16 = 0xff01;
+
+pub const SERVER_NAME_HOST_NAME: u8 = 0;
+
+pub const ALERT_LEVEL_WARNING: u8 = 1;
+pub const ALERT_LEVEL_FATAL: u8 = 2;
+
+/// Record Header
+///
+/// Since the version is not naturally aligned, this structure is packed.
+#[repr(C, packed)]
+pub struct RecordHeader {
+    /// Content type (`CONTENT_*`).
+    pub content_type: u8,
+
+    /// Legacy protocol version (`VERSION_*`).
+    pub version: int::u16be,
+
+    /// Size of the fragment in bytes, excluding the header.
+    pub length: int::u16be,
+}
+
+/// Handshake Message Header
+#[repr(C)]
+pub struct HandshakeHeader {
+    /// Message type (`HANDSHAKE_*`).
+    pub msg_type: u8,
+
+    /// Size of the message body in bytes, excluding the header.
+    pub length: int::u24be,
+}
+
+/// Extension Header
+#[repr(C)]
+pub struct ExtensionHeader {
+    /// Extension type (`EXTENSION_*`).
+    pub extension_type: int::u16be,
+
+    /// Size of the extension data in bytes, excluding the header.
+    pub length: int::u16be,
+}
+
+/// ClientHello Reference
+///
+/// A ClientHello message body with its vectors located.
+#[derive(Clone, Copy, Debug)]
+pub struct ClientHello<'data> {
+    /// Legacy protocol version.
+    pub version: u16,
+
+    /// Client random.
+    pub random: &'data [u8; 32],
+
+    /// Legacy session identifier.
+    pub session_id: &'data [u8],
+
+    /// Cipher suites, as list of 16-bit values (see `List16`).
+    pub cipher_suites: &'data [u8],
+
+    /// Legacy compression methods.
+    pub compression_methods: &'data [u8],
+
+    /// Extensions (see `Extensions`), empty if absent.
+    pub extensions: &'data [u8],
+}
+
+/// ServerHello Reference
+///
+/// A ServerHello message body with its vectors located. HelloRetryRequest
+/// messages share this format.
+#[derive(Clone, Copy, Debug)]
+pub struct ServerHello<'data> {
+    /// Legacy protocol version.
+    pub version: u16,
+
+    /// Server random, or `HELLO_RETRY_REQUEST_RANDOM`.
+    pub random: &'data [u8; 32],
+
+    /// Legacy session identifier, echoed from the client.
+    pub session_id: &'data [u8],
+
+    /// Selected cipher suite.
+    pub cipher_suite: u16,
+
+    /// Legacy compression method.
+    pub compression_method: u8,
+
+    /// Extensions (see `Extensions`), empty if absent.
+    pub extensions: &'data [u8],
+}
+
+/// Extension Iterator
+///
+/// An iterator over a list of extensions, yielding the extension type and
+/// data. Iteration stops at the end of the data, or at the first truncated
+/// extension.
+#[derive(Clone, Debug)]
+pub struct Extensions<'data> {
+    data: &'data [u8],
+}
+
+/// 16-bit List Iterator
+///
+/// An iterator over a list of 16-bit values, as used for cipher suites,
+/// supported groups, signature algorithms, and supported versions. Trailing
+/// odd bytes are ignored.
+#[derive(Clone, Debug)]
+pub struct List16<'data> {
+    data: &'data [u8],
+}
+
+// Return the vector with a `width`-byte length prefix at `*o` of `data`,
+// and advance `*o` past it.
+fn vector<'data>(data: &'data [u8], o: &mut usize, width: usize) -> Option<&'data [u8]> {
+    let prefix = data.get(*o..*o + width)?;
+    let n = prefix.iter().fold(0, |acc, b| (acc << 8) | *b as usize);
+    let v = data.get(*o + width..*o + width + n)?;
+    *o += width + n;
+    Some(v)
+}
+
+// Return the version and random at the start of a hello message body.
+fn hello_prefix(data: &[u8]) -> Option<(u16, &[u8; 32])> {
+    let version = u16::from_be_bytes([*data.first()?, *data.get(1)?]);
+    let random = data.get(2..2 + RANDOM_SIZE)?.try_into().unwrap();
+    Some((version, random))
+}
+
+// Return the extensions starting at `o` of a hello message body, which are
+// optional in TLS 1.2 and earlier.
+fn hello_extensions(data: &[u8], mut o: usize) -> Option<&[u8]> {
+    if o == data.len() {
+        Some(&[])
+    } else {
+        vector(data, &mut o, 2)
+    }
+}
+
+impl RecordHeader {
+    /// Import a record header from a byte slice
+    ///
+    /// Create a new record header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 5]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 5], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl HandshakeHeader {
+    /// Import a handshake header from a byte slice
+    ///
+    /// Create a new handshake header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 4]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 4], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl ExtensionHeader {
+    /// Import an extension header from a byte slice
+    ///
+    /// Create a new extension header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 4]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 4], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl<'data> ClientHello<'data> {
+    /// Parse a ClientHello
+    ///
+    /// Parse the body of a ClientHello message, following the handshake
+    /// header. Return `None` if any vector is truncated.
+    pub fn parse(data: &'data [u8]) -> Option<Self> {
+        let (version, random) = hello_prefix(data)?;
+        let mut o = 2 + RANDOM_SIZE;
+        let session_id = vector(data, &mut o, 1)?;
+        let cipher_suites = vector(data, &mut o, 2)?;
+        let compression_methods = vector(data, &mut o, 1)?;
+        let extensions = hello_extensions(data, o)?;
+
+        Some(Self {
+            version,
+            random,
+            session_id,
+            cipher_suites,
+            compression_methods,
+            extensions,
+        })
+    }
+}
+
+impl<'data> ServerHello<'data> {
+    /// Parse a ServerHello
+    ///
+    /// Parse the body of a ServerHello message, following the handshake
+    /// header. Return `None` if any field is truncated.
+    pub fn parse(data: &'data [u8]) -> Option<Self> {
+        let (version, random) = hello_prefix(data)?;
+        let mut o = 2 + RANDOM_SIZE;
+        let session_id = vector(data, &mut o, 1)?;
+        let fixed = data.get(o..o + 3)?;
+        let extensions = hello_extensions(data, o + 3)?;
+
+        Some(Self {
+            version,
+            random,
+            session_id,
+            cipher_suite: u16::from_be_bytes([fixed[0], fixed[1]]),
+            compression_method: fixed[2],
+            extensions,
+        })
+    }
+
+    /// Check for HelloRetryRequest
+    pub fn is_hello_retry_request(&self) -> bool {
+        *self.random == HELLO_RETRY_REQUEST_RANDOM
+    }
+}
+
+impl<'data> Extensions<'data> {
+    /// Create extension iterator
+    ///
+    /// Create a new iterator over the extensions in `data`, which must not
+    /// include the length prefix of the list.
+    pub fn new(data: &'data [u8]) -> Self {
+        Self {
+            data,
+        }
+    }
+
+    /// Return remaining data
+    ///
+    /// Return the data that has not been consumed by the iterator.
+    pub fn remainder(&self) -> &'data [u8] {
+        self.data
+    }
+}
+
+impl<'data> Iterator for Extensions<'data> {
+    type Item = (u16, &'data [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.data.len() < EXTENSION_HEADER_SIZE {
+            return None;
+        }
+
+        let extension_type = u16::from_be_bytes([self.data[0], self.data[1]]);
+        let mut o = 2;
+        let v = vector(self.data, &mut o, 2)?;
+        self.data = &self.data[o..];
+        Some((extension_type, v))
+    }
+}
+
+impl<'data> List16<'data> {
+    /// Create 16-bit list iterator
+    ///
+    /// Create a new iterator over the values in `data`, which must not
+    /// include the length prefix of the list.
+    pub fn new(data: &'data [u8]) -> Self {
+        Self {
+            data,
+        }
+    }
+
+    /// Return remaining data
+    ///
+    /// Return the data that has not been consumed by the iterator.
+    pub fn remainder(&self) -> &'data [u8] {
+        self.data
+    }
+}
+
+impl<'data> Iterator for List16<'data> {
+    type Item = u16;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let v = self.data.get(..2)?;
+        self.data = &self.data[2..];
+        Some(u16::from_be_bytes([v[0], v[1]]))
+    }
+}
+
+/// Return 24-bit length
+///
+/// Decode the 24-bit length prefix at the start of `data`, as used by
+/// handshake messages and certificate lists.
+pub fn length24(data: &[u8; 3]) -> usize {
+    int::u24be::from_raw(*data).to_native() as usize
+}
+
+/// Check for GREASE values
+///
+/// Return whether `v` is one of the reserved GREASE values of RFC 8701
+/// (`0x0a0a`, `0x1a1a`, ..., `0xfafa`).
+pub fn is_grease(v: u16) -> bool {
+    v & 0x0f0f == 0x0a0a && v >> 8 == v & 0xff
+}
+
+/// Find extension
+///
+/// Return the data of the first extension with the given type in
+/// `extensions`.
+pub fn find_extension(extensions: &[u8], extension_type: u16) -> Option<&[u8]> {
+    Extensions::new(extensions).find(|v| v.0 == extension_type).map(|v| v.1)
+}
+
+/// Return server name
+///
+/// Return the first host name of the data of an `EXTENSION_SERVER_NAME`
+/// extension sent by a client.
+pub fn server_name(data: &[u8]) -> Option<&[u8]> {
+    let mut o = 0;
+    let list = vector(data, &mut o, 2)?;
+
+    let mut o = 0;
+    while o < list.len() {
+        let name_type = list[o];
+        o += 1;
+        let name = vector(list, &mut o, 2)?;
+        if name_type == SERVER_NAME_HOST_NAME {
+            return Some(name);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A ClientHello record with SNI, supported groups and supported versions,
+    // carrying GREASE values in the cipher suites and groups.
+    const CLIENT_HELLO: [u8; 93] = [
+        0x16, 0x03, 0x01, 0x00, 0x58, 0x01, 0x00, 0x00,
+        0x54, 0x03, 0x03, 0x00, 0x01, 0x02, 0x03, 0x04,
+        0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c,
+        0x0d, 0x0e, 0x0f, 0x10, 0x11, 0x12, 0x13, 0x14,
+        0x15, 0x16, 0x17, 0x18, 0x19, 0x1a, 0x1b, 0x1c,
+        0x1d, 0x1e, 0x1f, 0x00, 0x00, 0x04, 0x13, 0x01,
+        0x0a, 0x0a, 0x01, 0x00, 0x00, 0x27, 0x00, 0x00,
+        0x00, 0x10, 0x00, 0x0e, 0x00, 0x00, 0x0b, 0x65,
+        0x78, 0x61, 0x6d, 0x70, 0x6c, 0x65, 0x2e, 0x63,
+        0x6f, 0x6d, 0x00, 0x0a, 0x00, 0x06, 0x00, 0x04,
+        0x1a, 0x1a, 0x00, 0x1d, 0x00, 0x2b, 0x00, 0x05,
+        0x04, 0x03, 0x04, 0x03, 0x03,
+    ];
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<RecordHeader>(), RECORD_HEADER_SIZE);
+        assert_eq!(align_of::<RecordHeader>(), 1);
+        assert_eq!(size_of::<HandshakeHeader>(), HANDSHAKE_HEADER_SIZE);
+        assert_eq!(align_of::<HandshakeHeader>(), 1);
+        assert_eq!(size_of::<ExtensionHeader>(), EXTENSION_HEADER_SIZE);
+        assert_eq!(align_of::<ExtensionHeader>(), 2);
+    }
+
+    // Parse a ClientHello record and its extensions.
+    #[test]
+    fn verify_client_hello() {
+        let r = RecordHeader::from_bytes(CLIENT_HELLO[..5].try_into().unwrap());
+        assert_eq!(r.content_type, CONTENT_HANDSHAKE);
+        assert_eq!(int::ForeignEndian::to_native(r.version), VERSION_TLS_1_0);
+        assert_eq!(int::ForeignEndian::to_native(r.length) as usize, CLIENT_HELLO.len() - 5);
+
+        let h = HandshakeHeader::from_bytes(CLIENT_HELLO[5..9].try_into().unwrap());
+        assert_eq!(h.msg_type, HANDSHAKE_CLIENT_HELLO);
+        assert_eq!(h.length.to_native() as usize, CLIENT_HELLO.len() - 9);
+        assert_eq!(length24(CLIENT_HELLO[6..9].try_into().unwrap()), 0x54);
+
+        let c = ClientHello::parse(&CLIENT_HELLO[9..]).unwrap();
+        assert_eq!(c.version, VERSION_TLS_1_2);
+        assert_eq!(c.random[31], 0x1f);
+        assert!(c.session_id.is_empty());
+        assert_eq!(c.compression_methods, &[0x00]);
+        let mut suites = List16::new(c.cipher_suites);
+        assert_eq!(suites.next(), Some(0x1301));
+        assert!(is_grease(suites.next().unwrap()));
+        assert_eq!(suites.next(), None);
+
+        let mut exts = Extensions::new(c.extensions);
+        let (t, sni) = exts.next().unwrap();
+        assert_eq!(t, EXTENSION_SERVER_NAME);
+        assert_eq!(server_name(sni), Some(&b"example.com"[..]));
+        let (t, groups) = exts.next().unwrap();
+        assert_eq!(t, EXTENSION_SUPPORTED_GROUPS);
+        assert_eq!(List16::new(&groups[2..]).filter(|v| !is_grease(*v)).count(), 1);
+        assert!(exts.next().is_some());
+        assert!(exts.next().is_none());
+        assert!(exts.remainder().is_empty());
+
+        let versions = find_extension(c.extensions, EXTENSION_SUPPORTED_VERSIONS).unwrap();
+        assert_eq!(List16::new(&versions[1..]).next(), Some(VERSION_TLS_1_3));
+        assert!(ClientHello::parse(&CLIENT_HELLO[9..90]).is_none());
+    }
+
+    // Parse a HelloRetryRequest and check GREASE values.
+    #[test]
+    fn verify_server_hello() {
+        let data = [
+            0x03, 0x03, 0xcf, 0x21, 0xad, 0x74, 0xe5, 0x9a,
+            0x61, 0x11, 0xbe, 0x1d, 0x8c, 0x02, 0x1e, 0x65,
+            0xb8, 0x91, 0xc2, 0xa2, 0x11, 0x16, 0x7a, 0xbb,
+            0x8c, 0x5e, 0x07, 0x9e, 0x09, 0xe2, 0xc8, 0xa8,
+            0x33, 0x9c, 0x00, 0x13, 0x01, 0x00, 0x00, 0x06,
+            0x00, 0x2b, 0x00, 0x02, 0x03, 0x04,
+        ];
+        let s = ServerHello::parse(&data).unwrap();
+        assert_eq!(s.version, VERSION_TLS_1_2);
+        assert!(s.is_hello_retry_request());
+        assert!(s.session_id.is_empty());
+        assert_eq!(s.cipher_suite, 0x1301);
+        assert_eq!(s.compression_method, 0);
+        assert_eq!(find_extension(s.extensions, EXTENSION_SUPPORTED_VERSIONS), Some(&[0x03, 0x04][..]));
+
+        let s = ServerHello::parse(&data[..38]).unwrap();
+        assert!(s.extensions.is_empty());
+        assert!(ServerHello::parse(&data[..37]).is_none());
+
+        assert!(is_grease(0x0a0a));
+        assert!(is_grease(0xfafa));
+        assert!(!is_grease(0x0a1a));
+        assert!(!is_grease(VERSION_TLS_1_3));
+    }
+}
@@ -0,0 +1,1174 @@
+//! Trusted Platform Module 2.0 Commands
+//!
+//! Software talks to a TPM 2.0 by sending commands and receiving responses
+//! as byte streams of marshalled structures. Every command starts with a
+//! `CommandHeader`, followed by the handle area of command-specific length
+//! (a number of 32-bit handles), then the authorization area (sessions) if
+//! the tag is `ST_SESSIONS`, and finally the parameter area (see
+//! `Command`). Responses start with a `ResponseHeader`, followed by the
+//! handle area, the parameter area, and the authorization area (see
+//! `Response`).
+//!
+//! The authorization area of commands is prefixed by its 32-bit size, and
+//! carries one or more sessions, each made up of the session handle, a
+//! nonce, the session attributes, and an HMAC or password. The parameter
+//! area of responses is prefixed by its 32-bit size if sessions follow.
+//! Variable sized buffers (TPM2B) are marshalled as 16-bit size followed
+//! by the data (see `tpm2b()`).
+//!
+//! All multi-byte integers are encoded as big-endian. Since marshalled
+//! structures are not padded, they are packed.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::int;
+
+pub const HEADER_SIZE: usize = 10;
+pub const HANDLE_SIZE: usize = 4;
+
+pub const ST_RSP_COMMAND: u16 = 0x00c4;
+pub const ST_NULL: u16 = 0x8000;
+pub const ST_NO_SESSIONS: u16 = 0x8001;
+pub const ST_SESSIONS: u16 = 0x8002;
+pub const ST_ATTEST_NV: u16 = 0x8014;
+pub const ST_ATTEST_COMMAND_AUDIT: u16 = 0x8015;
+pub const ST_ATTEST_SESSION_AUDIT: u16 = 0x8016;
+pub const ST_ATTEST_CERTIFY: u16 = 0x8017;
+pub const ST_ATTEST_QUOTE: u16 = 0x8018;
+pub const ST_ATTEST_TIME: u16 = 0x8019;
+pub const ST_ATTEST_CREATION: u16 = 0x801a;
+pub const ST_CREATION: u16 = 0x8021;
+pub const ST_VERIFIED: u16 = 0x8022;
+pub const ST_AUTH_SECRET: u16 = 0x8023;
+pub const ST_HASHCHECK: u16 = 0x8024;
+pub const ST_AUTH_SIGNED: u16 = 0x8025;
+
+pub const CC_NV_UNDEFINE_SPACE_SPECIAL: u32 = 0x0000011f;
+pub const CC_EVICT_CONTROL: u32 = 0x00000120;
+pub const CC_HIERARCHY_CONTROL: u32 = 0x00000121;
+pub const CC_NV_UNDEFINE_SPACE: u32 = 0x00000122;
+pub const CC_CHANGE_EPS: u32 = 0x00000124;
+pub const CC_CHANGE_PPS: u32 = 0x00000125;
+pub const CC_CLEAR: u32 = 0x00000126;
+pub const CC_CLEAR_CONTROL: u32 = 0x00000127;
+pub const CC_CLOCK_SET: u32 = 0x00000128;
+pub const CC_HIERARCHY_CHANGE_AUTH: u32 = 0x00000129;
+pub const CC_NV_DEFINE_SPACE: u32 = 0x0000012a;
+pub const CC_PCR_ALLOCATE: u32 = 0x0000012b;
+pub const CC_PCR_SET_AUTH_POLICY: u32 = 0x0000012c;
+pub const CC_PP_COMMANDS: u32 = 0x0000012d;
+pub const CC_SET_PRIMARY_POLICY: u32 = 0x0000012e;
+pub const CC_FIELD_UPGRADE_START: u32 = 0x0000012f;
+pub const CC_CLOCK_RATE_ADJUST: u32 = 0x00000130;
+pub const CC_CREATE_PRIMARY: u32 = 0x00000131;
+pub const CC_NV_GLOBAL_WRITE_LOCK: u32 = 0x00000132;
+pub const CC_GET_COMMAND_AUDIT_DIGEST: u32 = 0x00000133;
+pub const CC_NV_INCREMENT: u32 = 0x00000134;
+pub const CC_NV_SET_BITS: u32 = 0x00000135;
+pub const CC_NV_EXTEND: u32 = 0x00000136;
+pub const CC_NV_WRITE: u32 = 0x00000137;
+pub const CC_NV_WRITE_LOCK: u32 = 0x00000138;
+pub const CC_DICTIONARY_ATTACK_LOCK_RESET: u32 = 0x00000139;
+pub const CC_DICTIONARY_ATTACK_PARAMETERS: u32 = 0x0000013a;
+pub const CC_NV_CHANGE_AUTH: u32 = 0x0000013b;
+pub const CC_PCR_EVENT: u32 = 0x0000013c;
+pub const CC_PCR_RESET: u32 = 0x0000013d;
+pub const CC_SEQUENCE_COMPLETE: u32 = 0x0000013e;
+pub const CC_SET_ALGORITHM_SET: u32 = 0x0000013f;
+pub const CC_SET_COMMAND_CODE_AUDIT_STATUS: u32 = 0x00000140;
+pub const CC_FIELD_UPGRADE_DATA: u32 = 0x00000141;
+pub const CC_INCREMENTAL_SELF_TEST: u32 = 0x00000142;
+pub const CC_SELF_TEST: u32 = 0x00000143;
+pub const CC_STARTUP: u32 = 0x00000144;
+pub const CC_SHUTDOWN: u32 = 0x00000145;
+pub const CC_STIR_RANDOM: u32 = 0x00000146;
+pub const CC_ACTIVATE_CREDENTIAL: u32 = 0x00000147;
+pub const CC_CERTIFY: u32 = 0x00000148;
+pub const CC_POLICY_NV: u32 = 0x00000149;
+pub const CC_CERTIFY_CREATION: u32 = 0x0000014a;
+pub const CC_DUPLICATE: u32 = 0x0000014b;
+pub const CC_GET_TIME: u32 = 0x0000014c;
+pub const CC_GET_SESSION_AUDIT_DIGEST: u32 = 0x0000014d;
+pub const CC_NV_READ: u32 = 0x0000014e;
+pub const CC_NV_READ_LOCK: u32 = 0x0000014f;
+pub const CC_OBJECT_CHANGE_AUTH: u32 = 0x00000150;
+pub const CC_POLICY_SECRET: u32 = 0x00000151;
+pub const CC_REWRAP: u32 = 0x00000152;
+pub const CC_CREATE: u32 = 0x00000153;
+pub const CC_ECDH_ZGEN: u32 = 0x00000154;
+pub const CC_HMAC: u32 = 0x00000155;
+pub const CC_IMPORT: u32 = 0x00000156;
+pub const CC_LOAD: u32 = 0x00000157;
+pub const CC_QUOTE: u32 = 0x00000158;
+pub const CC_RSA_DECRYPT: u32 = 0x00000159;
+pub const CC_HMAC_START: u32 = 0x0000015b;
+pub const CC_SEQUENCE_UPDATE: u32 = 0x0000015c;
+pub const CC_SIGN: u32 = 0x0000015d;
+pub const CC_UNSEAL: u32 = 0x0000015e;
+pub const CC_POLICY_SIGNED: u32 = 0x00000160;
+pub const CC_CONTEXT_LOAD: u32 = 0x00000161;
+pub const CC_CONTEXT_SAVE: u32 = 0x00000162;
+pub const CC_ECDH_KEYGEN: u32 = 0x00000163;
+pub const CC_ENCRYPT_DECRYPT: u32 = 0x00000164;
+pub const CC_FLUSH_CONTEXT: u32 = 0x00000165;
+pub const CC_LOAD_EXTERNAL: u32 = 0x00000167;
+pub const CC_MAKE_CREDENTIAL: u32 = 0x00000168;
+pub const CC_NV_READ_PUBLIC: u32 = 0x00000169;
+pub const CC_POLICY_AUTHORIZE: u32 = 0x0000016a;
+pub const CC_POLICY_AUTH_VALUE: u32 = 0x0000016b;
+pub const CC_POLICY_COMMAND_CODE: u32 = 0x0000016c;
+pub const CC_POLICY_COUNTER_TIMER: u32 = 0x0000016d;
+pub const CC_POLICY_CP_HASH: u32 = 0x0000016e;
+pub const CC_POLICY_LOCALITY: u32 = 0x0000016f;
+pub const CC_POLICY_NAME_HASH: u32 = 0x00000170;
+pub const CC_POLICY_OR: u32 = 0x00000171;
+pub const CC_POLICY_TICKET: u32 = 0x00000172;
+pub const CC_READ_PUBLIC: u32 = 0x00000173;
+pub const CC_RSA_ENCRYPT: u32 = 0x00000174;
+pub const CC_START_AUTH_SESSION: u32 = 0x00000176;
+pub const CC_VERIFY_SIGNATURE: u32 = 0x00000177;
+pub const CC_ECC_PARAMETERS: u32 = 0x00000178;
+pub const CC_FIRMWARE_READ: u32 = 0x00000179;
+pub const CC_GET_CAPABILITY: u32 = 0x0000017a;
+pub const CC_GET_RANDOM: u32 = 0x0000017b;
+pub const CC_GET_TEST_RESULT: u32 = 0x0000017c;
+pub const CC_HASH: u32 = 0x0000017d;
+pub const CC_PCR_READ: u32 = 0x0000017e;
+pub const CC_POLICY_PCR: u32 = 0x0000017f;
+pub const CC_POLICY_RESTART: u32 = 0x00000180;
+pub const CC_READ_CLOCK: u32 = 0x00000181;
+pub const CC_PCR_EXTEND: u32 = 0x00000182;
+pub const CC_PCR_SET_AUTH_VALUE: u32 = 0x00000183;
+pub const CC_NV_CERTIFY: u32 = 0x00000184;
+pub const CC_EVENT_SEQUENCE_COMPLETE: u32 = 0x00000185;
+pub const CC_HASH_SEQUENCE_START: u32 = 0x00000186;
+pub const CC_POLICY_PHYSICAL_PRESENCE: u32 = 0x00000187;
+pub const CC_POLICY_DUPLICATION_SELECT: u32 = 0x00000188;
+pub const CC_POLICY_GET_DIGEST: u32 = 0x00000189;
+pub const CC_TEST_PARMS: u32 = 0x0000018a;
+pub const CC_COMMIT: u32 = 0x0000018b;
+pub const CC_POLICY_PASSWORD: u32 = 0x0000018c;
+pub const CC_ZGEN_2PHASE: u32 = 0x0000018d;
+pub const CC_EC_EPHEMERAL: u32 = 0x0000018e;
+pub const CC_POLICY_NV_WRITTEN: u32 = 0x0000018f;
+pub const CC_POLICY_TEMPLATE: u32 = 0x00000190;
+pub const CC_CREATE_LOADED: u32 = 0x00000191;
+pub const CC_POLICY_AUTHORIZE_NV: u32 = 0x00000192;
+pub const CC_ENCRYPT_DECRYPT2: u32 = 0x00000193;
+
+pub const RC_VER1: u32 = 0x100;
+pub const RC_FMT1: u32 = 0x080;
+pub const RC_WARN: u32 = 0x900;
+pub const RC_P: u32 = 0x040;
+pub const RC_S: u32 = 0x800;
+pub const RC_N_MASK: u32 = 0xf00;
+pub const RC_N_SHIFT: u32 = 8;
+
+pub const RC_SUCCESS: u32 = 0x000;
+pub const RC_BAD_TAG: u32 = 0x01e;
+pub const RC_INITIALIZE: u32 = 0x100;
+pub const RC_FAILURE: u32 = 0x101;
+pub const RC_SEQUENCE: u32 = 0x103;
+pub const RC_PRIVATE: u32 = 0x10b;
+pub const RC_HMAC: u32 = 0x119;
+pub const RC_DISABLED: u32 = 0x120;
+pub const RC_EXCLUSIVE: u32 = 0x121;
+pub const RC_AUTH_TYPE: u32 = 0x124;
+pub const RC_AUTH_MISSING: u32 = 0x125;
+pub const RC_POLICY: u32 = 0x126;
+pub const RC_PCR: u32 = 0x127;
+pub const RC_PCR_CHANGED: u32 = 0x128;
+pub const RC_UPGRADE: u32 = 0x12d;
+pub const RC_TOO_MANY_CONTEXTS: u32 = 0x12e;
+pub const RC_AUTH_UNAVAILABLE: u32 = 0x12f;
+pub const RC_REBOOT: u32 = 0x130;
+pub const RC_UNBALANCED: u32 = 0x131;
+pub const RC_COMMAND_SIZE: u32 = 0x142;
+pub const RC_COMMAND_CODE: u32 = 0x143;
+pub const RC_AUTHSIZE: u32 = 0x144;
+pub const RC_AUTH_CONTEXT: u32 = 0x145;
+pub const RC_NV_RANGE: u32 = 0x146;
+pub const RC_NV_SIZE: u32 = 0x147;
+pub const RC_NV_LOCKED: u32 = 0x148;
+pub const RC_NV_AUTHORIZATION: u32 = 0x149;
+pub const RC_NV_UNINITIALIZED: u32 = 0x14a;
+pub const RC_NV_SPACE: u32 = 0x14b;
+pub const RC_NV_DEFINED: u32 = 0x14c;
+pub const RC_BAD_CONTEXT: u32 = 0x150;
+pub const RC_CPHASH: u32 = 0x151;
+pub const RC_PARENT: u32 = 0x152;
+pub const RC_NEEDS_TEST: u32 = 0x153;
+pub const RC_NO_RESULT: u32 = 0x154;
+pub const RC_SENSITIVE: u32 = 0x155;
+
+pub const RC_ASYMMETRIC: u32 = 0x081;
+pub const RC_ATTRIBUTES: u32 = 0x082;
+pub const RC_HASH: u32 = 0x083;
+pub const RC_VALUE: u32 = 0x084;
+pub const RC_HIERARCHY: u32 = 0x085;
+pub const RC_KEY_SIZE: u32 = 0x087;
+pub const RC_MGF: u32 = 0x088;
+pub const RC_MODE: u32 = 0x089;
+pub const RC_TYPE: u32 = 0x08a;
+pub const RC_HANDLE: u32 = 0x08b;
+pub const RC_KDF: u32 = 0x08c;
+pub const RC_RANGE: u32 = 0x08d;
+pub const RC_AUTH_FAIL: u32 = 0x08e;
+pub const RC_NONCE: u32 = 0x08f;
+pub const RC_PP: u32 = 0x090;
+pub const RC_SCHEME: u32 = 0x092;
+pub const RC_SIZE: u32 = 0x095;
+pub const RC_SYMMETRIC: u32 = 0x096;
+pub const RC_TAG: u32 = 0x097;
+pub const RC_SELECTOR: u32 = 0x098;
+pub const RC_INSUFFICIENT: u32 = 0x09a;
+pub const RC_SIGNATURE: u32 = 0x09b;
+pub const RC_KEY: u32 = 0x09c;
+pub const RC_POLICY_FAIL: u32 = 0x09d;
+pub const RC_INTEGRITY: u32 = 0x09f;
+pub const RC_TICKET: u32 = 0x0a0;
+pub const RC_RESERVED_BITS: u32 = 0x0a1;
+pub const RC_BAD_AUTH: u32 = 0x0a2;
+pub const RC_EXPIRED: u32 = 0x0a3;
+pub const RC_POLICY_CC: u32 = 0x0a4;
+pub const RC_BINDING: u32 = 0x0a5;
+pub const RC_CURVE: u32 = 0x0a6;
+pub const RC_ECC_POINT: u32 = 0x0a7;
+
+pub const RC_CONTEXT_GAP: u32 = 0x901;
+pub const RC_OBJECT_MEMORY: u32 = 0x902;
+pub const RC_SESSION_MEMORY: u32 = 0x903;
+pub const RC_MEMORY: u32 = 0x904;
+pub const RC_SESSION_HANDLES: u32 = 0x905;
+pub const RC_OBJECT_HANDLES: u32 = 0x906;
+pub const RC_LOCALITY: u32 = 0x907;
+pub const RC_YIELDED: u32 = 0x908;
+pub const RC_CANCELED: u32 = 0x909;
+pub const RC_TESTING: u32 = 0x90a;
+pub const RC_NV_RATE: u32 = 0x920;
+pub const RC_LOCKOUT: u32 = 0x921;
+pub const RC_RETRY: u32 = 0x922;
+pub const RC_NV_UNAVAILABLE: u32 = 0x923;
+
+pub const ALG_ERROR: u16 = 0x0000;
+pub const ALG_RSA: u16 = 0x0001;
+pub const ALG_TDES: u16 = 0x0003;
+pub const ALG_SHA1: u16 = 0x0004;
+pub const ALG_HMAC: u16 = 0x0005;
+pub const ALG_AES: u16 = 0x0006;
+pub const ALG_MGF1: u16 = 0x0007;
+pub const ALG_KEYEDHASH: u16 = 0x0008;
+pub const ALG_XOR: u16 = 0x000a;
+pub const ALG_SHA256: u16 = 0x000b;
+pub const ALG_SHA384: u16 = 0x000c;
+pub const ALG_SHA512: u16 = 0x000d;
+pub const ALG_NULL: u16 = 0x0010;
+pub const ALG_SM3_256: u16 = 0x0012;
+pub const ALG_SM4: u16 = 0x0013;
+pub const ALG_RSASSA: u16 = 0x0014;
+pub const ALG_RSAES: u16 = 0x0015;
+pub const ALG_RSAPSS: u16 = 0x0016;
+pub const ALG_OAEP: u16 = 0x0017;
+pub const ALG_ECDSA: u16 = 0x0018;
+pub const ALG_ECDH: u16 = 0x0019;
+pub const ALG_ECDAA: u16 = 0x001a;
+pub const ALG_SM2: u16 = 0x001b;
+pub const ALG_ECSCHNORR: u16 = 0x001c;
+pub const ALG_ECMQV: u16 = 0x001d;
+pub const ALG_KDF1_SP800_56A: u16 = 0x0020;
+pub const ALG_KDF2: u16 = 0x0021;
+pub const ALG_KDF1_SP800_108: u16 = 0x0022;
+pub const ALG_ECC: u16 = 0x0023;
+pub const ALG_SYMCIPHER: u16 = 0x0025;
+pub const ALG_CAMELLIA: u16 = 0x0026;
+pub const ALG_SHA3_256: u16 = 0x0027;
+pub const ALG_SHA3_384: u16 = 0x0028;
+pub const ALG_SHA3_512: u16 = 0x0029;
+pub const ALG_CTR: u16 = 0x0040;
+pub const ALG_OFB: u16 = 0x0041;
+pub const ALG_CBC: u16 = 0x0042;
+pub const ALG_CFB: u16 = 0x0043;
+pub const ALG_ECB: u16 = 0x0044;
+
+pub const ECC_NIST_P192: u16 = 0x0001;
+pub const ECC_NIST_P224: u16 = 0x0002;
+pub const ECC_NIST_P256: u16 = 0x0003;
+pub const ECC_NIST_P384: u16 = 0x0004;
+pub const ECC_NIST_P521: u16 = 0x0005;
+pub const ECC_BN_P256: u16 = 0x0010;
+pub const ECC_BN_P638: u16 = 0x0011;
+pub const ECC_SM2_P256: u16 = 0x0020;
+
+pub const HT_MASK: u32 = 0xff00_0000;
+pub const HT_SHIFT: u32 = 24;
+pub const HT_PCR: u8 = 0x00;
+pub const HT_NV_INDEX: u8 = 0x01;
+pub const HT_HMAC_SESSION: u8 = 0x02;
+pub const HT_POLICY_SESSION: u8 = 0x03;
+pub const HT_PERMANENT: u8 = 0x40;
+pub const HT_TRANSIENT: u8 = 0x80;
+pub const HT_PERSISTENT: u8 = 0x81;
+
+pub const RH_OWNER: u32 = 0x4000_0001;
+pub const RH_NULL: u32 = 0x4000_0007;
+pub const RS_PW: u32 = 0x4000_0009;
+pub const RH_LOCKOUT: u32 = 0x4000_000a;
+pub const RH_ENDORSEMENT: u32 = 0x4000_000b;
+pub const RH_PLATFORM: u32 = 0x4000_000c;
+pub const RH_PLATFORM_NV: u32 = 0x4000_000d;
+
+pub const SESSION_CONTINUE_SESSION: u8 = 0x01;
+pub const SESSION_AUDIT_EXCLUSIVE: u8 = 0x02;
+pub const SESSION_AUDIT_RESET: u8 = 0x04;
+pub const SESSION_DECRYPT: u8 = 0x20;
+pub const SESSION_ENCRYPT: u8 = 0x40;
+pub const SESSION_AUDIT: u8 = 0x80;
+
+pub const SU_CLEAR: u16 = 0x0000;
+pub const SU_STATE: u16 = 0x0001;
+
+pub const OBJECT_FIXED_TPM: u32 = 0x0000_0002;
+pub const OBJECT_ST_CLEAR: u32 = 0x0000_0004;
+pub const OBJECT_FIXED_PARENT: u32 = 0x0000_0010;
+pub const OBJECT_SENSITIVE_DATA_ORIGIN: u32 = 0x0000_0020;
+pub const OBJECT_USER_WITH_AUTH: u32 = 0x0000_0040;
+pub const OBJECT_ADMIN_WITH_POLICY: u32 = 0x0000_0080;
+pub const OBJECT_NO_DA: u32 = 0x0000_0400;
+pub const OBJECT_ENCRYPTED_DUPLICATION: u32 = 0x0000_0800;
+pub const OBJECT_RESTRICTED: u32 = 0x0001_0000;
+pub const OBJECT_DECRYPT: u32 = 0x0002_0000;
+pub const OBJECT_SIGN_ENCRYPT: u32 = 0x0004_0000;
+
+pub const CAP_ALGS: u32 = 0x0000_0000;
+pub const CAP_HANDLES: u32 = 0x0000_0001;
+pub const CAP_COMMANDS: u32 = 0x0000_0002;
+pub const CAP_PP_COMMANDS: u32 = 0x0000_0003;
+pub const CAP_AUDIT_COMMANDS: u32 = 0x0000_0004;
+pub const CAP_PCRS: u32 = 0x0000_0005;
+pub const CAP_TPM_PROPERTIES: u32 = 0x0000_0006;
+pub const CAP_PCR_PROPERTIES: u32 = 0x0000_0007;
+pub const CAP_ECC_CURVES: u32 = 0x0000_0008;
+pub const CAP_AUTH_POLICIES: u32 = 0x0000_0009;
+
+/// PCR Select Size
+///
+/// The usual size of the PCR bit map of a PCR selection, covering PCRs 0
+/// to 23.
+pub const PCR_SELECT_SIZE: usize = 3;
+
+/// Command Header
+///
+/// The header of every command, followed by the handle area, the
+/// authorization area if `tag` is `ST_SESSIONS`, and the parameter area.
+#[repr(C, packed)]
+pub struct CommandHeader {
+    /// Command tag (`ST_NO_SESSIONS` or `ST_SESSIONS`).
+    pub tag: int::u16be,
+
+    /// Size of the command in bytes, including the header.
+    pub command_size: int::u32be,
+
+    /// Command code (`CC_*`).
+    pub command_code: int::u32be,
+}
+
+/// Response Header
+///
+/// The header of every response. Successful responses are followed by the
+/// handle area, the parameter area, and the authorization area if `tag`
+/// is `ST_SESSIONS`. Responses with errors carry only the header.
+#[repr(C, packed)]
+pub struct ResponseHeader {
+    /// Response tag (`ST_NO_SESSIONS` or `ST_SESSIONS`).
+    pub tag: int::u16be,
+
+    /// Size of the response in bytes, including the header.
+    pub response_size: int::u32be,
+
+    /// Response code (`RC_*`).
+    pub response_code: int::u32be,
+}
+
+/// Public Area Header
+///
+/// The fixed start of a `TPMT_PUBLIC`, followed by the authorization policy
+/// as TPM2B, the type-specific parameters, and the type-specific unique
+/// identifier (see `Public`).
+#[repr(C, packed)]
+pub struct PublicHeader {
+    /// Algorithm of the object (`ALG_RSA`, `ALG_ECC`, `ALG_KEYEDHASH`, `ALG_SYMCIPHER`).
+    pub object_type: int::u16be,
+
+    /// Hash algorithm used to compute the name of the object (`ALG_*`).
+    pub name_alg: int::u16be,
+
+    /// Object attributes (`OBJECT_*`).
+    pub object_attributes: int::u32be,
+}
+
+/// Response Code Location
+///
+/// The handle, parameter, or session that a format-one response code refers
+/// to, by its 1-based index.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RcLocation {
+    /// The error is associated with a handle.
+    Handle(u8),
+    /// The error is associated with a parameter.
+    Parameter(u8),
+    /// The error is associated with a session.
+    Session(u8),
+}
+
+/// Command Reference
+///
+/// A reference to a command, split into its areas.
+#[derive(Clone, Copy, Debug)]
+pub struct Command<'data> {
+    /// Command tag (`ST_NO_SESSIONS` or `ST_SESSIONS`).
+    pub tag: u16,
+
+    /// Command code (`CC_*`).
+    pub code: u32,
+
+    /// Handle area.
+    pub handles: &'data [u8],
+
+    /// Sessions of the authorization area, without the size prefix (see
+    /// `Sessions`).
+    pub sessions: &'data [u8],
+
+    /// Parameter area.
+    pub parameters: &'data [u8],
+}
+
+/// Response Reference
+///
+/// A reference to a response, split into its areas. All areas are empty
+/// for error responses.
+#[derive(Clone, Copy, Debug)]
+pub struct Response<'data> {
+    /// Response tag (`ST_NO_SESSIONS` or `ST_SESSIONS`).
+    pub tag: u16,
+
+    /// Response code (`RC_*`).
+    pub code: u32,
+
+    /// Handle area.
+    pub handles: &'data [u8],
+
+    /// Parameter area, without the size prefix.
+    pub parameters: &'data [u8],
+
+    /// Sessions of the authorization area (see `Sessions`).
+    pub sessions: &'data [u8],
+}
+
+/// Session Reference
+///
+/// A reference to a session of an authorization area.
+#[derive(Clone, Copy, Debug)]
+pub struct Session<'data> {
+    /// Session handle, or 0 for sessions of responses.
+    pub handle: u32,
+
+    /// Nonce of the caller or the TPM.
+    pub nonce: &'data [u8],
+
+    /// Session attributes (`SESSION_*`).
+    pub attributes: u8,
+
+    /// HMAC or password.
+    pub hmac: &'data [u8],
+}
+
+/// Session Iterator
+///
+/// An iterator over the sessions of an authorization area, yielding a
+/// `Session` for every session. Iteration stops at the first truncated
+/// session.
+#[derive(Clone, Debug)]
+pub struct Sessions<'data> {
+    data: &'data [u8],
+    response: bool,
+}
+
+/// PCR Selection Reference
+///
+/// A reference to a `TPMS_PCR_SELECTION`.
+#[derive(Clone, Copy, Debug)]
+pub struct PcrSelection<'data> {
+    /// Hash algorithm of the PCR bank (`ALG_*`).
+    pub hash: u16,
+
+    /// Bit map of the selected PCRs, with PCR 0 in bit 0 of byte 0.
+    pub select: &'data [u8],
+}
+
+/// PCR Selection Iterator
+///
+/// An iterator over the entries of a `TPML_PCR_SELECTION`, yielding a
+/// `PcrSelection` for every entry.
+#[derive(Clone, Debug)]
+pub struct PcrSelections<'data> {
+    data: &'data [u8],
+    count: u32,
+}
+
+/// Public Area Reference
+///
+/// A reference to a `TPMT_PUBLIC`, split into its type-specific parts.
+#[derive(Clone, Copy, Debug)]
+pub struct Public<'data> {
+    /// Algorithm of the object (`ALG_RSA`, `ALG_ECC`, `ALG_KEYEDHASH`,
+    /// `ALG_SYMCIPHER`).
+    pub object_type: u16,
+
+    /// Hash algorithm used to compute the name of the object (`ALG_*`).
+    pub name_alg: u16,
+
+    /// Object attributes (`OBJECT_*`).
+    pub object_attributes: u32,
+
+    /// Authorization policy digest.
+    pub auth_policy: &'data [u8],
+
+    /// Marshalled type-specific parameters (`TPMU_PUBLIC_PARMS`).
+    pub parameters: &'data [u8],
+
+    /// Marshalled type-specific unique identifier (`TPMU_PUBLIC_ID`).
+    pub unique: &'data [u8],
+
+    /// Size of the public area in bytes.
+    pub size: usize,
+}
+
+impl CommandHeader {
+    /// Create a command header
+    ///
+    /// Create a new command header with the given tag, total size, and
+    /// command code.
+    pub fn new(tag: u16, command_size: u32, command_code: u32) -> Self {
+        Self {
+            tag: int::ForeignEndian::from_native(tag),
+            command_size: int::ForeignEndian::from_native(command_size),
+            command_code: int::ForeignEndian::from_native(command_code),
+        }
+    }
+
+    /// Import a command header from a byte slice
+    ///
+    /// Create a new command header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 10]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 10], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Convert to byte slice
+    ///
+    /// Return a byte-slice reference to the command header. No byte-order
+    /// conversions are applied.
+    pub fn as_bytes(&self) -> &[u8; 10] {
+        assert!(align_of::<[u8; 10]>() <= align_of::<Self>());
+        assert!(size_of::<[u8; 10]>() == size_of::<Self>());
+
+        unsafe {
+            core::mem::transmute::<&Self, &[u8; 10]>(self)
+        }
+    }
+}
+
+impl ResponseHeader {
+    /// Import a response header from a byte slice
+    ///
+    /// Create a new response header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 10]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 10], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Convert to byte slice
+    ///
+    /// Return a byte-slice reference to the response header. No byte-order
+    /// conversions are applied.
+    pub fn as_bytes(&self) -> &[u8; 10] {
+        assert!(align_of::<[u8; 10]>() <= align_of::<Self>());
+        assert!(size_of::<[u8; 10]>() == size_of::<Self>());
+
+        unsafe {
+            core::mem::transmute::<&Self, &[u8; 10]>(self)
+        }
+    }
+}
+
+impl PublicHeader {
+    /// Import a public area header from a byte slice
+    ///
+    /// Create a new public area header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 8]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 8], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Convert to byte slice
+    ///
+    /// Return a byte-slice reference to the public area header. No byte-order
+    /// conversions are applied.
+    pub fn as_bytes(&self) -> &[u8; 8] {
+        assert!(align_of::<[u8; 8]>() <= align_of::<Self>());
+        assert!(size_of::<[u8; 8]>() == size_of::<Self>());
+
+        unsafe {
+            core::mem::transmute::<&Self, &[u8; 8]>(self)
+        }
+    }
+}
+
+// Read an 8-bit value at the start of `data` and advance it.
+fn read_u8(data: &mut &[u8]) -> Option<u8> {
+    let (v, rest) = data.split_first()?;
+    *data = rest;
+    Some(*v)
+}
+
+// Read a big-endian 16-bit value at the start of `data` and advance it.
+fn read_u16(data: &mut &[u8]) -> Option<u16> {
+    let v = u16::from_be_bytes(data.get(..2)?.try_into().unwrap());
+    *data = &data[2..];
+    Some(v)
+}
+
+// Read a big-endian 32-bit value at the start of `data` and advance it.
+fn read_u32(data: &mut &[u8]) -> Option<u32> {
+    let v = u32::from_be_bytes(data.get(..4)?.try_into().unwrap());
+    *data = &data[4..];
+    Some(v)
+}
+
+// Read a slice of `n` bytes at the start of `data` and advance it.
+fn read_slice<'data>(data: &mut &'data [u8], n: usize) -> Option<&'data [u8]> {
+    let v = data.get(..n)?;
+    *data = &data[n..];
+    Some(v)
+}
+
+// Read a TPM2B at the start of `data` and advance it.
+fn read_tpm2b<'data>(data: &mut &'data [u8]) -> Option<&'data [u8]> {
+    let n = read_u16(data)?;
+    read_slice(data, n as usize)
+}
+
+// Skip a scheme with an optional hash algorithm, like `TPMT_RSA_SCHEME`,
+// `TPMT_ECC_SCHEME`, or `TPMT_KDF_SCHEME`, at the start of `data`.
+fn skip_scheme(data: &mut &[u8]) -> Option<()> {
+    match read_u16(data)? {
+        ALG_NULL => {},
+        ALG_ECDAA => {
+            read_slice(data, 4)?;
+        },
+        ALG_XOR => {
+            read_slice(data, 4)?;
+        },
+        _ => {
+            read_u16(data)?;
+        },
+    }
+    Some(())
+}
+
+// Skip a `TPMT_SYM_DEF_OBJECT` at the start of `data`.
+fn skip_symmetric(data: &mut &[u8]) -> Option<()> {
+    if read_u16(data)? != ALG_NULL {
+        read_slice(data, 4)?;
+    }
+    Some(())
+}
+
+impl<'data> Command<'data> {
+    /// Parse a command
+    ///
+    /// Parse the command `data` with `handle_count` handles, as defined by
+    /// its command code. Trailing data beyond the command size is ignored.
+    /// `None` is returned if the tag is invalid, or the command is
+    /// truncated.
+    pub fn parse(data: &'data [u8], handle_count: usize) -> Option<Self> {
+        let header = CommandHeader::from_bytes(data.get(..HEADER_SIZE)?.try_into().ok()?);
+        let tag = int::ForeignEndian::to_native(header.tag);
+        let size = int::ForeignEndian::to_native(header.command_size) as usize;
+        let mut body = data.get(HEADER_SIZE..size)?;
+
+        let handles = read_slice(&mut body, handle_count * HANDLE_SIZE)?;
+        let sessions = match tag {
+            ST_NO_SESSIONS => &[],
+            ST_SESSIONS => {
+                let n = read_u32(&mut body)?;
+                read_slice(&mut body, n as usize)?
+            },
+            _ => return None,
+        };
+
+        Some(Self {
+            tag,
+            code: int::ForeignEndian::to_native(header.command_code),
+            handles,
+            sessions,
+            parameters: body,
+        })
+    }
+
+    /// Return a handle
+    ///
+    /// Return the handle with the given index of the handle area.
+    pub fn handle(&self, index: usize) -> Option<u32> {
+        let v = self.handles.get(index * HANDLE_SIZE..(index + 1) * HANDLE_SIZE)?;
+        Some(u32::from_be_bytes(v.try_into().ok()?))
+    }
+}
+
+impl<'data> Response<'data> {
+    /// Parse a response
+    ///
+    /// Parse the response `data` with `handle_count` handles, as defined by
+    /// the command code of the command. Trailing data beyond the response
+    /// size is ignored. `None` is returned if the tag is invalid, or the
+    /// response is truncated.
+    pub fn parse(data: &'data [u8], handle_count: usize) -> Option<Self> {
+        let header = ResponseHeader::from_bytes(data.get(..HEADER_SIZE)?.try_into().ok()?);
+        let tag = int::ForeignEndian::to_native(header.tag);
+        let code = int::ForeignEndian::to_native(header.response_code);
+        let size = int::ForeignEndian::to_native(header.response_size) as usize;
+        let mut body = data.get(HEADER_SIZE..size)?;
+
+        if code != RC_SUCCESS {
+            return Some(Self {
+                tag,
+                code,
+                handles: &[],
+                parameters: &[],
+                sessions: &[],
+            });
+        }
+
+        let handles = read_slice(&mut body, handle_count * HANDLE_SIZE)?;
+        let (parameters, sessions) = match tag {
+            ST_NO_SESSIONS => (body, &[][..]),
+            ST_SESSIONS => {
+                let n = read_u32(&mut body)?;
+                (read_slice(&mut body, n as usize)?, body)
+            },
+            _ => return None,
+        };
+
+        Some(Self {
+            tag,
+            code,
+            handles,
+            parameters,
+            sessions,
+        })
+    }
+}
+
+impl<'data> Sessions<'data> {
+    /// Create session iterator
+    ///
+    /// Create a new iterator over the sessions of the authorization area of
+    /// a command, as found in `Command::sessions`.
+    pub fn new(data: &'data [u8]) -> Self {
+        Self {
+            data,
+            response: false,
+        }
+    }
+
+    /// Create response session iterator
+    ///
+    /// Create a new iterator over the sessions of the authorization area of
+    /// a response, which carry no session handles.
+    pub fn new_response(data: &'data [u8]) -> Self {
+        Self {
+            data,
+            response: true,
+        }
+    }
+
+    /// Return remaining data
+    ///
+    /// Return the data that has not been consumed by the iterator.
+    pub fn remainder(&self) -> &'data [u8] {
+        self.data
+    }
+}
+
+impl<'data> Iterator for Sessions<'data> {
+    type Item = Session<'data>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut data = self.data;
+        let handle = if self.response { 0 } else { read_u32(&mut data)? };
+        let nonce = read_tpm2b(&mut data)?;
+        let attributes = read_u8(&mut data)?;
+        let hmac = read_tpm2b(&mut data)?;
+
+        self.data = data;
+        Some(Session {
+            handle,
+            nonce,
+            attributes,
+            hmac,
+        })
+    }
+}
+
+impl<'data> PcrSelection<'data> {
+    /// Check whether a PCR is selected
+    pub fn is_selected(&self, pcr: u32) -> bool {
+        self.select
+            .get((pcr / 8) as usize)
+            .is_some_and(|v| (v & (1 << (pcr % 8))) != 0)
+    }
+}
+
+impl<'data> PcrSelections<'data> {
+    /// Create PCR selection iterator
+    ///
+    /// Create a new iterator over the `TPML_PCR_SELECTION` at the start of
+    /// `data`. `None` is returned if the count is truncated.
+    pub fn new(data: &'data [u8]) -> Option<Self> {
+        let mut data = data;
+        let count = read_u32(&mut data)?;
+        Some(Self { data, count })
+    }
+
+    /// Return remaining data
+    ///
+    /// Return the data that has not been consumed by the iterator.
+    pub fn remainder(&self) -> &'data [u8] {
+        self.data
+    }
+}
+
+impl<'data> Iterator for PcrSelections<'data> {
+    type Item = PcrSelection<'data>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.count == 0 {
+            return None;
+        }
+
+        let mut data = self.data;
+        let hash = read_u16(&mut data)?;
+        let n = read_u8(&mut data)?;
+        let select = read_slice(&mut data, n as usize)?;
+
+        self.data = data;
+        self.count -= 1;
+        Some(PcrSelection { hash, select })
+    }
+}
+
+impl<'data> Public<'data> {
+    /// Parse a public area
+    ///
+    /// Parse the `TPMT_PUBLIC` at the start of `data`, splitting its
+    /// parameters and unique identifier based on the object type. `None` is
+    /// returned for unknown object types, or if the data is truncated.
+    pub fn parse(data: &'data [u8]) -> Option<Self> {
+        let header = PublicHeader::from_bytes(data.get(..8)?.try_into().ok()?);
+        let object_type = int::ForeignEndian::to_native(header.object_type);
+        let mut rest = &data[8..];
+        let auth_policy = read_tpm2b(&mut rest)?;
+
+        let mut v = rest;
+        match object_type {
+            ALG_RSA => {
+                skip_symmetric(&mut v)?;
+                skip_scheme(&mut v)?;
+                read_slice(&mut v, 6)?;
+            },
+            ALG_ECC => {
+                skip_symmetric(&mut v)?;
+                skip_scheme(&mut v)?;
+                read_u16(&mut v)?;
+                skip_scheme(&mut v)?;
+            },
+            ALG_KEYEDHASH => skip_scheme(&mut v)?,
+            ALG_SYMCIPHER => skip_symmetric(&mut v)?,
+            _ => return None,
+        }
+        let parameters = &rest[..rest.len() - v.len()];
+
+        let u = v;
+        read_tpm2b(&mut v)?;
+        if object_type == ALG_ECC {
+            read_tpm2b(&mut v)?;
+        }
+        let unique = &u[..u.len() - v.len()];
+
+        Some(Self {
+            object_type,
+            name_alg: int::ForeignEndian::to_native(header.name_alg),
+            object_attributes: int::ForeignEndian::to_native(header.object_attributes),
+            auth_policy,
+            parameters,
+            unique,
+            size: data.len() - v.len(),
+        })
+    }
+}
+
+/// Split a TPM2B
+///
+/// Split the TPM2B at the start of `data` into its contents and the
+/// remaining data. `None` is returned if it is truncated.
+pub fn tpm2b(data: &[u8]) -> Option<(&[u8], &[u8])> {
+    let mut data = data;
+    let v = read_tpm2b(&mut data)?;
+    Some((v, data))
+}
+
+/// Encode a TPM2B
+///
+/// Encode `data` as TPM2B into `out`. Return the number of bytes written,
+/// or `None` if the data exceeds 16 bits of size, or the buffer is too
+/// small.
+pub fn encode_tpm2b(data: &[u8], out: &mut [u8]) -> Option<usize> {
+    let n = u16::try_from(data.len()).ok()?;
+    out.get_mut(..2)?.copy_from_slice(&n.to_be_bytes());
+    out.get_mut(2..2 + data.len())?.copy_from_slice(data);
+    Some(2 + data.len())
+}
+
+/// Encode a command session
+///
+/// Encode a session of the authorization area of a command into `out`.
+/// Password sessions use `RS_PW` with an empty nonce, and the password as
+/// HMAC. Return the number of bytes written, or `None` if the buffer is
+/// too small.
+pub fn encode_session(
+    handle: u32,
+    nonce: &[u8],
+    attributes: u8,
+    hmac: &[u8],
+    out: &mut [u8],
+) -> Option<usize> {
+    out.get_mut(..4)?.copy_from_slice(&handle.to_be_bytes());
+    let mut o = 4;
+    o += encode_tpm2b(nonce, out.get_mut(o..)?)?;
+    *out.get_mut(o)? = attributes;
+    o += 1;
+    o += encode_tpm2b(hmac, out.get_mut(o..)?)?;
+    Some(o)
+}
+
+/// Return base response code
+///
+/// Return the response code without the handle, parameter, or session
+/// number of format-one codes, to be compared with `RC_*`.
+pub fn rc_base(rc: u32) -> u32 {
+    if (rc & RC_FMT1) != 0 {
+        rc & (RC_FMT1 | 0x3f)
+    } else {
+        rc
+    }
+}
+
+/// Return response code location
+///
+/// Return the handle, parameter, or session that a format-one response
+/// code refers to, or `None` for format-zero codes and unspecified
+/// locations.
+pub fn rc_location(rc: u32) -> Option<RcLocation> {
+    if (rc & RC_FMT1) == 0 {
+        return None;
+    }
+
+    let n = ((rc & RC_N_MASK) >> RC_N_SHIFT) as u8;
+    if (rc & RC_P) != 0 {
+        Some(RcLocation::Parameter(n))
+    } else if n == 0 {
+        None
+    } else if (rc & RC_S) != 0 {
+        Some(RcLocation::Session(n & 0x7))
+    } else {
+        Some(RcLocation::Handle(n & 0x7))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::specs::int::ForeignEndian;
+
+    // TPM2_PCR_Read of PCR 0 and 7 of the SHA-256 bank.
+    const PCR_READ: [u8; 20] = [
+        0x80, 0x01, 0x00, 0x00, 0x00, 0x14, 0x00, 0x00,
+        0x01, 0x7e, 0x00, 0x00, 0x00, 0x01, 0x00, 0x0b,
+        0x03, 0x81, 0x00, 0x00,
+    ];
+
+    // TPM2_Unseal of a persistent object with an empty password session.
+    const UNSEAL: [u8; 27] = [
+        0x80, 0x02, 0x00, 0x00, 0x00, 0x1b, 0x00, 0x00,
+        0x01, 0x5e, 0x81, 0x00, 0x00, 0x01, 0x00, 0x00,
+        0x00, 0x09, 0x40, 0x00, 0x00, 0x09, 0x00, 0x00,
+        0x01, 0x00, 0x00,
+    ];
+
+    // Response to the unseal with a 4-byte secret.
+    const UNSEAL_RESPONSE: [u8; 25] = [
+        0x80, 0x02, 0x00, 0x00, 0x00, 0x19, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x06, 0x00, 0x04,
+        0xde, 0xad, 0xbe, 0xef, 0x00, 0x00, 0x01, 0x00,
+        0x00,
+    ];
+
+    // Public area of an RSA-2048 storage key with AES-128-CFB, and a 4-byte
+    // modulus for brevity, followed by trailing data.
+    const PUBLIC_RSA: [u8; 30] = [
+        0x00, 0x01, 0x00, 0x0b, 0x00, 0x03, 0x00, 0x72,
+        0x00, 0x00, 0x00, 0x06, 0x00, 0x80, 0x00, 0x43,
+        0x00, 0x10, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x04, 0x01, 0x02, 0x03, 0x04,
+    ];
+
+    // Public area of an ECDSA P-256 signing key with 2-byte coordinates.
+    const PUBLIC_ECC: [u8; 28] = [
+        0x00, 0x23, 0x00, 0x0b, 0x00, 0x04, 0x00, 0x72,
+        0x00, 0x00, 0x00, 0x10, 0x00, 0x18, 0x00, 0x0b,
+        0x00, 0x03, 0x00, 0x10, 0x00, 0x02, 0xaa, 0xbb,
+        0x00, 0x02, 0xcc, 0xdd,
+    ];
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<CommandHeader>(), HEADER_SIZE);
+        assert_eq!(size_of::<ResponseHeader>(), HEADER_SIZE);
+        assert_eq!(size_of::<PublicHeader>(), 8);
+    }
+
+    // Parse commands with and without sessions, and their PCR selection.
+    #[test]
+    fn verify_commands() {
+        let h = CommandHeader::new(ST_NO_SESSIONS, 20, CC_PCR_READ);
+        assert_eq!(h.as_bytes(), &PCR_READ[..HEADER_SIZE]);
+
+        let c = Command::parse(&PCR_READ, 0).unwrap();
+        assert_eq!((c.tag, c.code), (ST_NO_SESSIONS, CC_PCR_READ));
+        assert!(c.handles.is_empty() && c.sessions.is_empty());
+        let mut s = PcrSelections::new(c.parameters).unwrap();
+        let p = s.next().unwrap();
+        assert_eq!((p.hash, p.select.len()), (ALG_SHA256, PCR_SELECT_SIZE));
+        assert!(p.is_selected(0) && p.is_selected(7));
+        assert!(!p.is_selected(1) && !p.is_selected(24));
+        assert!(s.next().is_none());
+        assert!(s.remainder().is_empty());
+
+        let c = Command::parse(&UNSEAL, 1).unwrap();
+        assert_eq!((c.tag, c.code), (ST_SESSIONS, CC_UNSEAL));
+        assert_eq!(c.handle(0), Some(0x8100_0001));
+        assert_eq!(c.handle(0).unwrap() >> HT_SHIFT, HT_PERSISTENT as u32);
+        assert!(c.parameters.is_empty());
+        let mut s = Sessions::new(c.sessions);
+        let v = s.next().unwrap();
+        assert_eq!((v.handle, v.attributes), (RS_PW, SESSION_CONTINUE_SESSION));
+        assert!(v.nonce.is_empty() && v.hmac.is_empty());
+        assert!(s.next().is_none());
+
+        let mut buf = [0u8; 16];
+        let n = encode_session(RS_PW, &[], SESSION_CONTINUE_SESSION, &[], &mut buf).unwrap();
+        assert_eq!(&buf[..n], &UNSEAL[18..]);
+
+        assert!(Command::parse(&UNSEAL[..26], 1).is_none());
+        assert!(Command::parse(&UNSEAL, 5).is_none());
+    }
+
+    // Parse successful and error responses.
+    #[test]
+    fn verify_responses() {
+        let r = Response::parse(&UNSEAL_RESPONSE, 0).unwrap();
+        assert_eq!((r.tag, r.code), (ST_SESSIONS, RC_SUCCESS));
+        let (secret, rest) = tpm2b(r.parameters).unwrap();
+        assert_eq!(secret, &[0xde, 0xad, 0xbe, 0xef]);
+        assert!(rest.is_empty());
+        let v = Sessions::new_response(r.sessions).next().unwrap();
+        assert_eq!((v.handle, v.attributes), (0, SESSION_CONTINUE_SESSION));
+
+        let mut buf = [0u8; 8];
+        assert_eq!(encode_tpm2b(secret, &mut buf), Some(6));
+        assert_eq!(&buf[..6], &UNSEAL_RESPONSE[14..20]);
+
+        // TPM_RC_AUTH_FAIL of session 1, and TPM_RC_VALUE of parameter 2.
+        let e = [0x80, 0x01, 0x00, 0x00, 0x00, 0x0a, 0x00, 0x00, 0x09, 0x8e];
+        let r = Response::parse(&e, 0).unwrap();
+        let h = ResponseHeader::from_bytes(&e);
+        assert_eq!(h.response_code.to_native(), r.code);
+        assert!(r.parameters.is_empty());
+        assert_eq!(rc_base(r.code), RC_AUTH_FAIL);
+        assert_eq!(rc_location(r.code), Some(RcLocation::Session(1)));
+        assert_eq!(rc_base(0x2c4), RC_VALUE);
+        assert_eq!(rc_location(0x2c4), Some(RcLocation::Parameter(2)));
+        assert_eq!(rc_location(0x18b), Some(RcLocation::Handle(1)));
+        assert_eq!(rc_location(RC_HANDLE), None);
+        assert_eq!((rc_base(RC_RETRY), rc_location(RC_RETRY)), (RC_RETRY, None));
+    }
+
+    // Split the public areas of RSA and ECC keys.
+    #[test]
+    fn verify_public() {
+        let p = Public::parse(&PUBLIC_RSA).unwrap();
+        assert_eq!((p.object_type, p.name_alg), (ALG_RSA, ALG_SHA256));
+        let attributes = OBJECT_FIXED_TPM
+            | OBJECT_FIXED_PARENT
+            | OBJECT_SENSITIVE_DATA_ORIGIN
+            | OBJECT_USER_WITH_AUTH
+            | OBJECT_RESTRICTED
+            | OBJECT_DECRYPT;
+        assert_eq!(p.object_attributes, attributes);
+        assert!(p.auth_policy.is_empty());
+        assert_eq!(p.parameters, &PUBLIC_RSA[10..24]);
+        assert_eq!(p.unique, &PUBLIC_RSA[24..30]);
+        assert_eq!(p.size, 30);
+
+        let p = Public::parse(&PUBLIC_ECC).unwrap();
+        assert_eq!(p.object_type, ALG_ECC);
+        assert_eq!(p.object_attributes & OBJECT_SIGN_ENCRYPT, OBJECT_SIGN_ENCRYPT);
+        assert_eq!(p.parameters, &PUBLIC_ECC[10..20]);
+        assert_eq!(p.unique, &PUBLIC_ECC[20..]);
+        assert_eq!(&p.parameters[6..8], &ECC_NIST_P256.to_be_bytes());
+
+        let h = PublicHeader::from_bytes(PUBLIC_ECC[..8].try_into().unwrap());
+        assert_eq!(h.object_type.to_native(), ALG_ECC);
+        assert!(Public::parse(&PUBLIC_ECC[..26]).is_none());
+        assert!(Public::parse(&[0x00, 0x25, 0x00, 0x0b, 0, 0, 0, 0, 0, 0, 0x00, 0x10]).is_none());
+    }
+}
@@ -0,0 +1,230 @@
+//! User Datagram Protocol
+//!
+//! UDP datagrams start with an 8-byte `Header`, directly followed by the
+//! payload. The checksum is the Internet checksum (see `checksum::internet()`)
+//! of the IPv4 or IPv6 pseudo-header (see `ipv4::PseudoHeader` and
+//! `ipv6::PseudoHeader`) followed by the entire datagram, calculated with the
+//! checksum field set to zero. A calculated checksum of zero is transmitted
+//! as `0xffff`, since a checksum field of zero means that no checksum was
+//! calculated. This is only allowed over IPv4.
+//!
+//! UDP-Lite (`ipv4::PROTOCOL_UDPLITE`) uses the same header, but replaces the
+//! length with the checksum coverage (see `lite_coverage()`). The length is
+//! taken from the IP layer instead, and the checksum is mandatory.
+//!
+//! All multi-byte integers are encoded as big-endian.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::{checksum, int, ipv4, ipv6};
+
+pub const HEADER_SIZE: usize = 8;
+pub const CHECKSUM_OFFSET: usize = 6;
+
+/// Checksum Field of Datagrams without Checksum
+pub const CHECKSUM_NONE: u16 = 0;
+
+pub const PORT_DNS: u16 = 53;
+pub const PORT_DHCP_SERVER: u16 = 67;
+pub const PORT_DHCP_CLIENT: u16 = 68;
+pub const PORT_TFTP: u16 = 69;
+pub const PORT_NTP: u16 = 123;
+pub const PORT_SNMP: u16 = 161;
+pub const PORT_QUIC: u16 = 443;
+pub const PORT_SYSLOG: u16 = 514;
+pub const PORT_DHCPV6_CLIENT: u16 = 546;
+pub const PORT_DHCPV6_SERVER: u16 = 547;
+pub const PORT_RADIUS: u16 = 1812;
+pub const PORT_RADIUS_ACCOUNTING: u16 = 1813;
+pub const PORT_RADIUS_DYNAMIC_AUTHORIZATION: u16 = 3799;
+pub const PORT_VXLAN: u16 = 4789;
+pub const PORT_VXLAN_GPE: u16 = 4790;
+pub const PORT_MDNS: u16 = 5353;
+pub const PORT_GENEVE: u16 = 6081;
+
+/// UDP Header
+///
+/// This is also the layout of UDP-Lite headers, which carry the checksum
+/// coverage in place of the length.
+#[repr(C)]
+pub struct Header {
+    /// Source port, or 0 if unused.
+    pub source_port: int::u16be,
+
+    /// Destination port.
+    pub destination_port: int::u16be,
+
+    /// Size of the datagram in bytes, including the header. For UDP-Lite,
+    /// the checksum coverage.
+    pub length: int::u16be,
+
+    /// Checksum of the datagram and pseudo-header, or `CHECKSUM_NONE`.
+    pub checksum: int::u16be,
+}
+
+impl Header {
+    /// Import a header from a byte slice
+    ///
+    /// Create a new header from data copied from a byte slice. No byte-order
+    /// conversions are applied.
+    pub fn from_bytes(data: &[u8; 8]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 8], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+// Finish the checksum of `data` on top of the sum of a pseudo-header,
+// treating the checksum field as zero, and map a result of zero to its
+// alternative representation.
+fn datagram_checksum(sum: u32, data: &[u8]) -> u16 {
+    let sum = checksum::internet_update(sum, &data[..CHECKSUM_OFFSET]);
+    let sum = checksum::internet_update(sum, &data[CHECKSUM_OFFSET + 2..]);
+    match checksum::internet_finish(sum) {
+        CHECKSUM_NONE => 0xffff,
+        v => v,
+    }
+}
+
+/// Calculate Datagram Checksum over IPv4
+///
+/// Calculate the checksum of an entire datagram sent from `source` to
+/// `destination`, treating the checksum field as zero. `datagram` must be at
+/// least `HEADER_SIZE` bytes.
+pub fn checksum_v4(source: &[u8; 4], destination: &[u8; 4], datagram: &[u8]) -> u16 {
+    let ph = ipv4::PseudoHeader::new(*source, *destination, ipv4::PROTOCOL_UDP, datagram.len() as u16);
+    datagram_checksum(checksum::internet_update(0, ph.as_bytes()), datagram)
+}
+
+/// Calculate Datagram Checksum over IPv6
+///
+/// Calculate the checksum of an entire datagram sent from `source` to
+/// `destination`, treating the checksum field as zero. `datagram` must be at
+/// least `HEADER_SIZE` bytes.
+pub fn checksum_v6(source: &[u8; 16], destination: &[u8; 16], datagram: &[u8]) -> u16 {
+    let ph = ipv6::PseudoHeader::new(*source, *destination, datagram.len() as u32, ipv4::PROTOCOL_UDP);
+    datagram_checksum(checksum::internet_update(0, ph.as_bytes()), datagram)
+}
+
+/// Return UDP-Lite checksum coverage
+///
+/// Return the number of bytes of a UDP-Lite datagram covered by its checksum,
+/// or `None` if the coverage field is invalid. A coverage of 0 covers the
+/// entire datagram, otherwise it must include at least the header. `None`
+/// is also returned if `datagram` is shorter than `HEADER_SIZE`.
+pub fn lite_coverage(datagram: &[u8]) -> Option<usize> {
+    if datagram.len() < HEADER_SIZE {
+        return None;
+    }
+
+    match u16::from_be_bytes([datagram[4], datagram[5]]) as usize {
+        0 => Some(datagram.len()),
+        v if v < HEADER_SIZE || v > datagram.len() => None,
+        v => Some(v),
+    }
+}
+
+/// Calculate UDP-Lite Checksum over IPv4
+///
+/// Calculate the checksum of a UDP-Lite datagram sent from `source` to
+/// `destination`, covering as much of the datagram as given by its coverage
+/// field. Return `None` if the coverage field is invalid, or the datagram
+/// is shorter than `HEADER_SIZE`.
+pub fn lite_checksum_v4(source: &[u8; 4], destination: &[u8; 4], datagram: &[u8]) -> Option<u16> {
+    let n = lite_coverage(datagram)?;
+    let ph = ipv4::PseudoHeader::new(*source, *destination, ipv4::PROTOCOL_UDPLITE, datagram.len() as u16);
+    Some(datagram_checksum(checksum::internet_update(0, ph.as_bytes()), &datagram[..n]))
+}
+
+/// Calculate UDP-Lite Checksum over IPv6
+///
+/// Calculate the checksum of a UDP-Lite datagram sent from `source` to
+/// `destination`, covering as much of the datagram as given by its coverage
+/// field. Return `None` if the coverage field is invalid, or the datagram
+/// is shorter than `HEADER_SIZE`.
+pub fn lite_checksum_v6(source: &[u8; 16], destination: &[u8; 16], datagram: &[u8]) -> Option<u16> {
+    let n = lite_coverage(datagram)?;
+    let ph = ipv6::PseudoHeader::new(*source, *destination, datagram.len() as u32, ipv4::PROTOCOL_UDPLITE);
+    Some(datagram_checksum(checksum::internet_update(0, ph.as_bytes()), &datagram[..n]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A datagram from 10.0.0.1:40000 to 10.0.0.2:53.
+    const DATAGRAM: [u8; 20] = [
+        0x9c, 0x40, 0x00, 0x35, 0x00, 0x14, 0x0f, 0x02,
+        0x68, 0x65, 0x6c, 0x6c, 0x6f, 0x2c, 0x20, 0x77,
+        0x6f, 0x72, 0x6c, 0x64,
+    ];
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<Header>(), HEADER_SIZE);
+        assert_eq!(align_of::<Header>(), 2);
+    }
+
+    // Parse a datagram and verify its checksum over both pseudo-headers.
+    #[test]
+    fn verify_datagram() {
+        let h = Header::from_bytes(DATAGRAM[..8].try_into().unwrap());
+        assert_eq!(int::ForeignEndian::to_native(h.source_port), 40000);
+        assert_eq!(int::ForeignEndian::to_native(h.destination_port), PORT_DNS);
+        assert_eq!(int::ForeignEndian::to_native(h.length) as usize, DATAGRAM.len());
+        assert_eq!(int::ForeignEndian::to_native(h.checksum), 0x0f02);
+
+        assert_eq!(checksum_v4(&[10, 0, 0, 1], &[10, 0, 0, 2], &DATAGRAM), 0x0f02);
+
+        let mut src = [0; 16];
+        let mut dst = [0; 16];
+        src[..4].copy_from_slice(&[0x20, 0x01, 0x0d, 0xb8]);
+        dst[..4].copy_from_slice(&[0x20, 0x01, 0x0d, 0xb8]);
+        src[15] = 1;
+        dst[15] = 2;
+        assert_eq!(checksum_v6(&src, &dst, &DATAGRAM), 0xc78f);
+
+        // A sum of zero is transmitted as all ones. Adjust the payload so the
+        // checksum would come out as zero.
+        let mut data = DATAGRAM;
+        data[18..20].copy_from_slice(&(0x6c64_u16 + 0x0f02).to_be_bytes());
+        assert_eq!(checksum_v4(&[10, 0, 0, 1], &[10, 0, 0, 2], &data), 0xffff);
+    }
+
+    // Verify the checksum coverage of UDP-Lite.
+    #[test]
+    fn verify_lite() {
+        let mut data = DATAGRAM;
+        data[4..6].copy_from_slice(&[0x00, 0x08]);
+        assert_eq!(lite_coverage(&data), Some(8));
+        assert_eq!(lite_checksum_v4(&[10, 0, 0, 1], &[10, 0, 0, 2], &data), Some(0x4ee3));
+
+        // The payload beyond the coverage is not covered.
+        data[19] ^= 0xff;
+        assert_eq!(lite_checksum_v4(&[10, 0, 0, 1], &[10, 0, 0, 2], &data), Some(0x4ee3));
+
+        data[4..6].copy_from_slice(&[0x00, 0x00]);
+        assert_eq!(lite_coverage(&data), Some(20));
+        data[4..6].copy_from_slice(&[0x00, 0x07]);
+        assert!(lite_coverage(&data).is_none());
+        data[4..6].copy_from_slice(&[0x00, 0x15]);
+        assert!(lite_checksum_v6(&[0; 16], &[0; 16], &data).is_none());
+
+        // Truncated datagrams are rejected.
+        data[4..6].copy_from_slice(&[0x00, 0x00]);
+        assert!(lite_coverage(&data[..5]).is_none());
+        assert!(lite_checksum_v4(&[10, 0, 0, 1], &[10, 0, 0, 2], &data[..6]).is_none());
+        assert!(lite_checksum_v6(&[0; 16], &[0; 16], &data[..7]).is_none());
+    }
+}
@@ -0,0 +1,892 @@
+//! UEFI Firmware Volumes
+//!
+//! The UEFI Platform Initialization (PI) specification stores firmware in
+//! firmware volumes on flash. A volume starts with a `FirmwareVolumeHeader`
+//! and its block map, followed by a flat sequence of files in the Firmware
+//! File System (FFS). Each file starts with a `FileHeader`, and files are
+//! aligned to 8 bytes relative to the start of the volume. Unused space is
+//! erased flash, with all bits set to the erase polarity of the volume.
+//!
+//! The data of most file types is a sequence of sections, each starting with
+//! a `SectionHeader` and aligned to 4 bytes relative to the start of the file
+//! data. Encapsulation sections (compression and GUID-defined sections)
+//! contain further sections.
+//!
+//! File and section sizes are stored as 24-bit little-endian integers,
+//! including the header. Larger files set `FFS_ATTRIB_LARGE_FILE` and use
+//! the 64-bit size of `FileHeader2`, larger sections set the 24-bit size to
+//! `0xffffff` and use the 32-bit size of `SectionHeader2`.
+//!
+//! The volume header is protected by a 16-bit checksum, the file header by an
+//! 8-bit checksum where `state` and `file_checksum` are taken as zero. The
+//! file data is protected by an 8-bit checksum if `FFS_ATTRIB_CHECKSUM` is
+//! set, otherwise `file_checksum` must be `FFS_FIXED_CHECKSUM`.
+//!
+//! All multi-byte integers are encoded as little-endian.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::checksum;
+use crate::specs::int;
+use crate::specs::uuid;
+
+/// Firmware Volume Signature
+///
+/// The signature of every firmware volume header ("_FVH").
+pub const SIGNATURE: [u8; 4] = *b"_FVH";
+
+/// Firmware File System 2
+///
+/// The file system GUID of volumes using the FFS of PI 1.0 and later.
+pub const FILE_SYSTEM2_GUID: uuid::Guid =
+    uuid::Guid::from_u128(0x8c8ce578_8a3d_4f1c_9935_896185c32dd3);
+
+/// Firmware File System 3
+///
+/// The file system GUID of volumes using the FFS of PI 1.2 and later, which
+/// can hold files of `FFS_ATTRIB_LARGE_FILE`.
+pub const FILE_SYSTEM3_GUID: uuid::Guid =
+    uuid::Guid::from_u128(0x5473c07a_3dcb_4dca_bd6f_1e9689e7349a);
+
+pub const FVH_REVISION: u8 = 2;
+
+pub const FVB2_READ_DISABLED_CAP: u32 = 0x0000_0001;
+pub const FVB2_READ_ENABLED_CAP: u32 = 0x0000_0002;
+pub const FVB2_READ_STATUS: u32 = 0x0000_0004;
+pub const FVB2_WRITE_DISABLED_CAP: u32 = 0x0000_0008;
+pub const FVB2_WRITE_ENABLED_CAP: u32 = 0x0000_0010;
+pub const FVB2_WRITE_STATUS: u32 = 0x0000_0020;
+pub const FVB2_LOCK_CAP: u32 = 0x0000_0040;
+pub const FVB2_LOCK_STATUS: u32 = 0x0000_0080;
+/* reserved: 0x0000_0100 */
+pub const FVB2_STICKY_WRITE: u32 = 0x0000_0200;
+pub const FVB2_MEMORY_MAPPED: u32 = 0x0000_0400;
+pub const FVB2_ERASE_POLARITY: u32 = 0x0000_0800;
+pub const FVB2_READ_LOCK_CAP: u32 = 0x0000_1000;
+pub const FVB2_READ_LOCK_STATUS: u32 = 0x0000_2000;
+pub const FVB2_WRITE_LOCK_CAP: u32 = 0x0000_4000;
+pub const FVB2_WRITE_LOCK_STATUS: u32 = 0x0000_8000;
+pub const FVB2_ALIGNMENT_MASK: u32 = 0x001f_0000;
+pub const FVB2_ALIGNMENT_SHIFT: u32 = 16;
+pub const FVB2_WEAK_ALIGNMENT: u32 = 0x8000_0000;
+
+pub const FV_EXT_TYPE_OEM_TYPE: u16 = 0x0001;
+pub const FV_EXT_TYPE_GUID_TYPE: u16 = 0x0002;
+pub const FV_EXT_TYPE_USED_SIZE_TYPE: u16 = 0x0003;
+
+pub const FV_FILETYPE_ALL: u8 = 0x00;
+pub const FV_FILETYPE_RAW: u8 = 0x01;
+pub const FV_FILETYPE_FREEFORM: u8 = 0x02;
+pub const FV_FILETYPE_SECURITY_CORE: u8 = 0x03;
+pub const FV_FILETYPE_PEI_CORE: u8 = 0x04;
+pub const FV_FILETYPE_DXE_CORE: u8 = 0x05;
+pub const FV_FILETYPE_PEIM: u8 = 0x06;
+pub const FV_FILETYPE_DRIVER: u8 = 0x07;
+pub const FV_FILETYPE_COMBINED_PEIM_DRIVER: u8 = 0x08;
+pub const FV_FILETYPE_APPLICATION: u8 = 0x09;
+pub const FV_FILETYPE_MM: u8 = 0x0a;
+pub const FV_FILETYPE_FIRMWARE_VOLUME_IMAGE: u8 = 0x0b;
+pub const FV_FILETYPE_COMBINED_MM_DXE: u8 = 0x0c;
+pub const FV_FILETYPE_MM_CORE: u8 = 0x0d;
+pub const FV_FILETYPE_MM_STANDALONE: u8 = 0x0e;
+pub const FV_FILETYPE_MM_CORE_STANDALONE: u8 = 0x0f;
+pub const FV_FILETYPE_OEM_MIN: u8 = 0xc0;
+pub const FV_FILETYPE_OEM_MAX: u8 = 0xdf;
+pub const FV_FILETYPE_DEBUG_MIN: u8 = 0xe0;
+pub const FV_FILETYPE_DEBUG_MAX: u8 = 0xef;
+pub const FV_FILETYPE_FFS_MIN: u8 = 0xf0;
+pub const FV_FILETYPE_FFS_PAD: u8 = 0xf0;
+pub const FV_FILETYPE_FFS_MAX: u8 = 0xff;
+
+pub const FFS_ATTRIB_LARGE_FILE: u8 = 0x01;
+pub const FFS_ATTRIB_DATA_ALIGNMENT_2: u8 = 0x02;
+pub const FFS_ATTRIB_FIXED: u8 = 0x04;
+pub const FFS_ATTRIB_DATA_ALIGNMENT_MASK: u8 = 0x38;
+pub const FFS_ATTRIB_DATA_ALIGNMENT_SHIFT: u32 = 3;
+pub const FFS_ATTRIB_CHECKSUM: u8 = 0x40;
+
+/// Fixed File Checksum
+///
+/// The value of `file_checksum` of files without `FFS_ATTRIB_CHECKSUM`.
+pub const FFS_FIXED_CHECKSUM: u8 = 0xaa;
+
+pub const EFI_FILE_HEADER_CONSTRUCTION: u8 = 0x01;
+pub const EFI_FILE_HEADER_VALID: u8 = 0x02;
+pub const EFI_FILE_DATA_VALID: u8 = 0x04;
+pub const EFI_FILE_MARKED_FOR_UPDATE: u8 = 0x08;
+pub const EFI_FILE_DELETED: u8 = 0x10;
+pub const EFI_FILE_HEADER_INVALID: u8 = 0x20;
+
+pub const SECTION_COMPRESSION: u8 = 0x01;
+pub const SECTION_GUID_DEFINED: u8 = 0x02;
+pub const SECTION_DISPOSABLE: u8 = 0x03;
+pub const SECTION_PE32: u8 = 0x10;
+pub const SECTION_PIC: u8 = 0x11;
+pub const SECTION_TE: u8 = 0x12;
+pub const SECTION_DXE_DEPEX: u8 = 0x13;
+pub const SECTION_VERSION: u8 = 0x14;
+pub const SECTION_USER_INTERFACE: u8 = 0x15;
+pub const SECTION_COMPATIBILITY16: u8 = 0x16;
+pub const SECTION_FIRMWARE_VOLUME_IMAGE: u8 = 0x17;
+pub const SECTION_FREEFORM_SUBTYPE_GUID: u8 = 0x18;
+pub const SECTION_RAW: u8 = 0x19;
+/* reserved: 0x1a */
+pub const SECTION_PEI_DEPEX: u8 = 0x1b;
+pub const SECTION_MM_DEPEX: u8 = 0x1c;
+
+pub const COMPRESSION_NOT_COMPRESSED: u8 = 0x00;
+pub const COMPRESSION_STANDARD: u8 = 0x01;
+
+pub const GUIDED_SECTION_PROCESSING_REQUIRED: u16 = 0x0001;
+pub const GUIDED_SECTION_AUTH_STATUS_VALID: u16 = 0x0002;
+
+pub const SIZE24_MAX: u32 = 0x00ff_ffff;
+pub const FILE_ALIGNMENT: usize = 8;
+pub const SECTION_ALIGNMENT: usize = 4;
+
+/// Firmware Volume Header
+///
+/// The header at the start of every firmware volume. It is followed by the
+/// block map, terminated by a zeroed `BlockMapEntry`. The header length
+/// covers both, and the checksum covers the entire header length.
+#[repr(C)]
+pub struct FirmwareVolumeHeader {
+    /// Reserved for the reset vector of the processor, usually zero.
+    pub zero_vector: [u8; 16],
+
+    /// File system of the volume (e.g., `FILE_SYSTEM2_GUID`).
+    pub file_system_guid: uuid::Guid,
+
+    /// Size of the volume in bytes, including the header.
+    pub fv_length: int::u64le,
+
+    /// The static signature. This must match `SIGNATURE`.
+    pub signature: [u8; 4],
+
+    /// Volume attributes (`FVB2_*`).
+    pub attributes: int::u32le,
+
+    /// Size of the header in bytes, including the block map.
+    pub header_length: int::u16le,
+
+    /// Checksum of the header (see `FirmwareVolumeHeader::checksum_valid()`).
+    pub checksum: int::u16le,
+
+    /// Offset of the extended header from the start of the volume, or 0.
+    pub ext_header_offset: int::u16le,
+
+    /// Reserved.
+    pub reserved: u8,
+
+    /// Revision of the header format (`FVH_REVISION`).
+    pub revision: u8,
+}
+
+/// Block Map Entry
+///
+/// Describes a run of blocks of equal size. The block map is terminated by
+/// an entry with both fields set to zero.
+#[repr(C)]
+pub struct BlockMapEntry {
+    /// Number of blocks in the run.
+    pub num_blocks: int::u32le,
+
+    /// Size of each block in bytes.
+    pub length: int::u32le,
+}
+
+/// Firmware Volume Extended Header
+///
+/// Followed by extension entries, each starting with an `ExtEntryHeader`.
+#[repr(C)]
+pub struct ExtHeader {
+    /// Name of the volume.
+    pub fv_name: uuid::Guid,
+
+    /// Size of the extended header in bytes, including its entries.
+    pub ext_header_size: int::u32le,
+}
+
+/// Firmware Volume Extension Entry Header
+#[repr(C)]
+pub struct ExtEntryHeader {
+    /// Size of the entry in bytes, including this header.
+    pub ext_entry_size: int::u16le,
+
+    /// Type of the entry (`FV_EXT_TYPE_*`).
+    pub ext_entry_type: int::u16le,
+}
+
+/// FFS File Header
+///
+/// The header of every file of a firmware volume. The 24-bit size includes
+/// the header. Files of `FFS_ATTRIB_LARGE_FILE` use `FileHeader2` instead,
+/// with the 24-bit size set to zero.
+#[repr(C)]
+pub struct FileHeader {
+    /// Name of the file.
+    pub name: uuid::Guid,
+
+    /// Checksum of the header (see `File::checksum_valid()`).
+    pub header_checksum: u8,
+
+    /// Checksum of the file data, or `FFS_FIXED_CHECKSUM`.
+    pub file_checksum: u8,
+
+    /// File type (`FV_FILETYPE_*`).
+    pub file_type: u8,
+
+    /// File attributes (`FFS_ATTRIB_*`).
+    pub attributes: u8,
+
+    /// Size of the file in bytes, including the header.
+    pub size: int::u24le,
+
+    /// File state (`EFI_FILE_*`).
+    pub state: u8,
+}
+
+/// Large FFS File Header
+///
+/// The header of files with `FFS_ATTRIB_LARGE_FILE`, which extends the
+/// header with a 64-bit size.
+#[repr(C)]
+pub struct FileHeader2 {
+    /// Common file header.
+    pub header: FileHeader,
+
+    /// Size of the file in bytes, including the header.
+    pub extended_size: int::u64le,
+}
+
+/// Common Section Header
+///
+/// The header of every section of a file. The 24-bit size includes the
+/// header. Sections of `0xffffff` bytes or more use `SectionHeader2`
+/// instead, with the 24-bit size set to `0xffffff`.
+#[repr(C)]
+pub struct SectionHeader {
+    /// Size of the section in bytes, including the header.
+    pub size: int::u24le,
+
+    /// Section type (`SECTION_*`).
+    pub section_type: u8,
+}
+
+/// Large Section Header
+#[repr(C)]
+pub struct SectionHeader2 {
+    /// Common section header.
+    pub header: SectionHeader,
+
+    /// Size of the section in bytes, including the header.
+    pub extended_size: int::u32le,
+}
+
+/// Compression Section Header
+///
+/// Followed by the compressed data. Since the header is not padded to its
+/// natural alignment, this structure is packed.
+#[repr(C, packed)]
+pub struct CompressionSection {
+    /// Common section header.
+    pub header: SectionHeader,
+
+    /// Size of the section data after decompression.
+    pub uncompressed_length: int::u32le,
+
+    /// Compression algorithm (`COMPRESSION_*`).
+    pub compression_type: u8,
+}
+
+/// GUID-defined Section Header
+///
+/// Followed by GUID-specific header data, and the section data at
+/// `data_offset`.
+#[repr(C)]
+pub struct GuidDefinedSection {
+    /// Common section header.
+    pub header: SectionHeader,
+
+    /// GUID of the encapsulation format.
+    pub section_definition_guid: uuid::Guid,
+
+    /// Offset of the section data from the start of the section.
+    pub data_offset: int::u16le,
+
+    /// Section attributes (`GUIDED_SECTION_*`).
+    pub attributes: int::u16le,
+}
+
+/// File Reference
+///
+/// A reference to a single file of a firmware volume, as yielded by `Files`.
+#[derive(Clone, Copy, Debug)]
+pub struct File<'data> {
+    /// Name of the file.
+    pub name: uuid::Guid,
+
+    /// File type (`FV_FILETYPE_*`).
+    pub file_type: u8,
+
+    /// File attributes (`FFS_ATTRIB_*`).
+    pub attributes: u8,
+
+    /// Raw file state, as stored (see `File::state()`).
+    pub raw_state: u8,
+
+    /// File header, including the extended size of large files.
+    pub header: &'data [u8],
+
+    /// File data, following the header.
+    pub data: &'data [u8],
+}
+
+/// File Iterator
+///
+/// Iterate the files of a firmware volume. The iterator stops at free space
+/// (a header that is erased entirely), or at the first malformed file. The
+/// remaining data can be retrieved via `Files::remainder()`.
+#[derive(Clone, Debug)]
+pub struct Files<'data> {
+    data: &'data [u8],
+    erase_polarity: bool,
+}
+
+/// Section Reference
+///
+/// A reference to a single section of a file, as yielded by `Sections`.
+#[derive(Clone, Copy, Debug)]
+pub struct Section<'data> {
+    /// Section type (`SECTION_*`).
+    pub section_type: u8,
+
+    /// Section header, including the extended size of large sections.
+    pub header: &'data [u8],
+
+    /// Section data, following the header.
+    pub data: &'data [u8],
+}
+
+/// Section Iterator
+///
+/// Iterate a sequence of sections. The iterator stops at the first malformed
+/// section, and the remaining data can be retrieved via
+/// `Sections::remainder()`.
+#[derive(Clone, Debug)]
+pub struct Sections<'data> {
+    data: &'data [u8],
+}
+
+// Sum all 16-bit little-endian words of the data, the length must be even.
+fn sum16(data: &[u8]) -> u16 {
+    data.chunks_exact(2)
+        .fold(0u16, |acc, v| acc.wrapping_add(u16::from_le_bytes([v[0], v[1]])))
+}
+
+impl FirmwareVolumeHeader {
+    /// Import a firmware volume header from a byte slice
+    ///
+    /// Create a new firmware volume header from data copied from a byte
+    /// slice. No byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 56]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 56], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Check whether the checksum is valid
+    ///
+    /// Verify the checksum of the header at the start of `data`. All 16-bit
+    /// words of the header, including the block map, must sum to zero modulo
+    /// 2^16. `false` is returned if the header is truncated or has an odd
+    /// length.
+    pub fn checksum_valid(data: &[u8]) -> bool {
+        let Some(v) = data.get(..56) else {
+            return false;
+        };
+        let header = Self::from_bytes(v.try_into().unwrap());
+        let length = int::ForeignEndian::to_native(header.header_length) as usize;
+        if length < 56 || !length.is_multiple_of(2) {
+            return false;
+        }
+
+        match data.get(..length) {
+            Some(v) => sum16(v) == 0,
+            None => false,
+        }
+    }
+
+    /// Return the erase polarity
+    ///
+    /// Return whether erased flash reads as ones (`FVB2_ERASE_POLARITY`). This
+    /// inverts the meaning of the file state bits.
+    pub fn erase_polarity(&self) -> bool {
+        int::ForeignEndian::to_native(self.attributes) & FVB2_ERASE_POLARITY != 0
+    }
+}
+
+impl BlockMapEntry {
+    /// Import a block map entry from a byte slice
+    ///
+    /// Create a new block map entry from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 8]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 8], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl ExtHeader {
+    /// Import an extended header from a byte slice
+    ///
+    /// Create a new extended header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 20]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 20], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl ExtEntryHeader {
+    /// Import an extension entry header from a byte slice
+    ///
+    /// Create a new extension entry header from data copied from a byte
+    /// slice. No byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 4]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 4], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl FileHeader {
+    /// Import a file header from a byte slice
+    ///
+    /// Create a new file header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 24]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 24], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl FileHeader2 {
+    /// Import a file header from a byte slice
+    ///
+    /// Create a new file header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 32]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 32], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl SectionHeader {
+    /// Import a section header from a byte slice
+    ///
+    /// Create a new section header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 4]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 4], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl SectionHeader2 {
+    /// Import a section header from a byte slice
+    ///
+    /// Create a new section header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 8]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 8], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl CompressionSection {
+    /// Import a compression section header from a byte slice
+    ///
+    /// Create a new compression section header from data copied from a byte
+    /// slice. No byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 9]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 9], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl GuidDefinedSection {
+    /// Import a GUID-defined section header from a byte slice
+    ///
+    /// Create a new GUID-defined section header from data copied from a byte
+    /// slice. No byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 24]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 24], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl<'data> File<'data> {
+    /// Parse a file
+    ///
+    /// Parse the file at the start of `data`. Any trailing data is ignored.
+    /// `None` is returned if the data is truncated, or the size of the file
+    /// is smaller than its header. The checksums are not verified.
+    pub fn parse(data: &'data [u8]) -> Option<Self> {
+        let h = FileHeader::from_bytes(data.get(..24)?.try_into().ok()?);
+        let (header_size, size) = if h.attributes & FFS_ATTRIB_LARGE_FILE != 0 {
+            let h2 = FileHeader2::from_bytes(data.get(..32)?.try_into().ok()?);
+            let size = int::ForeignEndian::to_native(h2.extended_size);
+            (32, usize::try_from(size).ok()?)
+        } else {
+            (24, h.size.to_native() as usize)
+        };
+
+        if size < header_size {
+            return None;
+        }
+
+        let v = data.get(..size)?;
+        Some(Self {
+            name: h.name,
+            file_type: h.file_type,
+            attributes: h.attributes,
+            raw_state: h.state,
+            header: &v[..header_size],
+            data: &v[header_size..],
+        })
+    }
+
+    /// Return total size
+    ///
+    /// Return the size of the file in bytes, including its header.
+    pub fn size(&self) -> usize {
+        self.header.len() + self.data.len()
+    }
+
+    /// Return the file state
+    ///
+    /// Return the effective state of the file, which is the most significant
+    /// state bit set (`EFI_FILE_*`), or 0 if none is set. The raw state is
+    /// inverted first, if the volume has an erase polarity of 1.
+    pub fn state(&self, erase_polarity: bool) -> u8 {
+        let v = if erase_polarity { !self.raw_state } else { self.raw_state };
+        match v {
+            0 => 0,
+            _ => 0x80 >> v.leading_zeros(),
+        }
+    }
+
+    /// Check whether the checksums are valid
+    ///
+    /// Verify the header checksum, and the data checksum if
+    /// `FFS_ATTRIB_CHECKSUM` is set. Otherwise, verify the file checksum is
+    /// `FFS_FIXED_CHECKSUM`.
+    pub fn checksum_valid(&self) -> bool {
+        let h = FileHeader::from_bytes(self.header[..24].try_into().unwrap());
+        let sum = checksum::sum8(self.header)
+            .wrapping_sub(h.state)
+            .wrapping_sub(h.file_checksum);
+        if sum != 0 {
+            return false;
+        }
+
+        if self.attributes & FFS_ATTRIB_CHECKSUM != 0 {
+            checksum::sum8_update(h.file_checksum, self.data) == 0
+        } else {
+            h.file_checksum == FFS_FIXED_CHECKSUM
+        }
+    }
+
+    /// Iterate sections
+    ///
+    /// Create an iterator over the sections of the file data. This is only
+    /// meaningful for file types that contain sections.
+    pub fn sections(&self) -> Sections<'data> {
+        Sections::new(self.data)
+    }
+}
+
+impl<'data> Files<'data> {
+    /// Create file iterator
+    ///
+    /// Create a new iterator over the files of a firmware volume. The data
+    /// must start at the first file (usually following the header and block
+    /// map), aligned to 8 bytes relative to the start of the volume.
+    pub fn new(data: &'data [u8], erase_polarity: bool) -> Self {
+        Self {
+            data,
+            erase_polarity,
+        }
+    }
+
+    /// Return remaining data
+    ///
+    /// Return the data that has not been consumed by the iterator.
+    pub fn remainder(&self) -> &'data [u8] {
+        self.data
+    }
+}
+
+impl<'data> Iterator for Files<'data> {
+    type Item = File<'data>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let erased = if self.erase_polarity { 0xff } else { 0x00 };
+        if self.data.get(..24)?.iter().all(|v| *v == erased) {
+            return None;
+        }
+
+        let v = File::parse(self.data)?;
+        let next = v.size().next_multiple_of(FILE_ALIGNMENT);
+        self.data = self.data.get(next..).unwrap_or(&[]);
+        Some(v)
+    }
+}
+
+impl<'data> Section<'data> {
+    /// Parse a section
+    ///
+    /// Parse the section at the start of `data`. Any trailing data is
+    /// ignored. `None` is returned if the data is truncated, or the size of
+    /// the section is smaller than its header.
+    pub fn parse(data: &'data [u8]) -> Option<Self> {
+        let h = SectionHeader::from_bytes(data.get(..4)?.try_into().ok()?);
+        let (header_size, size) = match h.size.to_native() {
+            SIZE24_MAX => {
+                let h2 = SectionHeader2::from_bytes(data.get(..8)?.try_into().ok()?);
+                (8, int::ForeignEndian::to_native(h2.extended_size) as usize)
+            },
+            v => (4, v as usize),
+        };
+
+        if size < header_size {
+            return None;
+        }
+
+        let v = data.get(..size)?;
+        Some(Self {
+            section_type: h.section_type,
+            header: &v[..header_size],
+            data: &v[header_size..],
+        })
+    }
+
+    /// Return total size
+    ///
+    /// Return the size of the section in bytes, including its header.
+    pub fn size(&self) -> usize {
+        self.header.len() + self.data.len()
+    }
+}
+
+impl<'data> Sections<'data> {
+    /// Create section iterator
+    ///
+    /// Create a new iterator over a sequence of sections. The data must start
+    /// at the first section, and alignment is relative to its start.
+    pub fn new(data: &'data [u8]) -> Self {
+        Self {
+            data,
+        }
+    }
+
+    /// Return remaining data
+    ///
+    /// Return the data that has not been consumed by the iterator.
+    pub fn remainder(&self) -> &'data [u8] {
+        self.data
+    }
+}
+
+impl<'data> Iterator for Sections<'data> {
+    type Item = Section<'data>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let v = Section::parse(self.data)?;
+        let next = v.size().next_multiple_of(SECTION_ALIGNMENT);
+        self.data = self.data.get(next..).unwrap_or(&[]);
+        Some(v)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::specs::int::ForeignEndian;
+
+    // Firmware volume of 128 bytes with an erase polarity of 1, holding a
+    // single freeform file with a raw and a user interface section.
+    const VOLUME: [u8; 128] = [
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x78, 0xe5, 0x8c, 0x8c, 0x3d, 0x8a, 0x1c, 0x4f,
+        0x99, 0x35, 0x89, 0x61, 0x85, 0xc3, 0x2d, 0xd3,
+        0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x5f, 0x46, 0x56, 0x48, 0xff, 0xfe, 0x04, 0x00,
+        0x48, 0x00, 0x0d, 0xf6, 0x00, 0x00, 0x00, 0x02,
+        0x02, 0x00, 0x00, 0x00, 0x40, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x67, 0x45, 0x23, 0x01, 0xab, 0x89, 0xef, 0xcd,
+        0x01, 0x23, 0x45, 0x67, 0x89, 0xab, 0xcd, 0xef,
+        0x12, 0x6c, 0x02, 0x40, 0x2c, 0x00, 0x00, 0xf8,
+        0x09, 0x00, 0x00, 0x19, 0x68, 0x65, 0x6c, 0x6c,
+        0x6f, 0x00, 0x00, 0x00, 0x08, 0x00, 0x00, 0x15,
+        0x41, 0x00, 0x00, 0x00, 0xff, 0xff, 0xff, 0xff,
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    ];
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<FirmwareVolumeHeader>(), 56);
+        assert_eq!(size_of::<BlockMapEntry>(), 8);
+        assert_eq!(size_of::<ExtHeader>(), 20);
+        assert_eq!(size_of::<ExtEntryHeader>(), 4);
+        assert_eq!(size_of::<FileHeader>(), 24);
+        assert_eq!(align_of::<FileHeader>(), 1);
+        assert_eq!(size_of::<FileHeader2>(), 32);
+        assert_eq!(size_of::<SectionHeader>(), 4);
+        assert_eq!(align_of::<SectionHeader>(), 1);
+        assert_eq!(size_of::<SectionHeader2>(), 8);
+        assert_eq!(size_of::<CompressionSection>(), 9);
+        assert_eq!(align_of::<CompressionSection>(), 1);
+        assert_eq!(size_of::<GuidDefinedSection>(), 24);
+    }
+
+    // Verify the volume header, walk its files and sections, and verify the
+    // file checksums.
+    #[test]
+    fn verify_volume() {
+        assert!(FirmwareVolumeHeader::checksum_valid(&VOLUME));
+        let fvh = FirmwareVolumeHeader::from_bytes(VOLUME[..56].try_into().unwrap());
+        assert_eq!(fvh.signature, SIGNATURE);
+        assert_eq!(fvh.file_system_guid, FILE_SYSTEM2_GUID);
+        assert_eq!(fvh.fv_length.to_native(), 128);
+        assert_eq!(fvh.revision, FVH_REVISION);
+        assert!(fvh.erase_polarity());
+
+        let bm = BlockMapEntry::from_bytes(VOLUME[56..64].try_into().unwrap());
+        assert_eq!(bm.num_blocks.to_native(), 2);
+        assert_eq!(bm.length.to_native(), 64);
+
+        let length = fvh.header_length.to_native() as usize;
+        let mut files = Files::new(&VOLUME[length..], fvh.erase_polarity());
+
+        let f = files.next().unwrap();
+        assert_eq!(f.file_type, FV_FILETYPE_FREEFORM);
+        assert_eq!(f.size(), 44);
+        assert_eq!(f.state(true), EFI_FILE_DATA_VALID);
+        assert!(f.checksum_valid());
+
+        let mut sections = f.sections();
+        let s = sections.next().unwrap();
+        assert_eq!(s.section_type, SECTION_RAW);
+        assert_eq!(s.data, b"hello");
+        let s = sections.next().unwrap();
+        assert_eq!(s.section_type, SECTION_USER_INTERFACE);
+        assert_eq!(s.data, [b'A', 0, 0, 0]);
+        assert!(sections.next().is_none());
+        assert!(sections.remainder().is_empty());
+
+        assert!(files.next().is_none());
+        assert_eq!(files.remainder().len(), 8);
+
+        // Any corruption of the header fails its checksum, but the state can
+        // be changed freely.
+        let mut v = VOLUME;
+        v[72 + 24] ^= 0x01;
+        let f = File::parse(&v[72..]).unwrap();
+        assert!(!f.checksum_valid());
+        let mut v = VOLUME;
+        v[72 + 23] &= !EFI_FILE_DELETED;
+        let f = File::parse(&v[72..]).unwrap();
+        assert!(f.checksum_valid());
+        assert_eq!(f.state(true), EFI_FILE_DELETED);
+
+        let mut v = VOLUME;
+        v[40] ^= 0x01;
+        assert!(!FirmwareVolumeHeader::checksum_valid(&v));
+    }
+
+    // Verify the 24-bit size of section headers.
+    #[test]
+    fn verify_size24() {
+        let h = SectionHeader::from_bytes(&[0x34, 0x12, 0xab, SECTION_RAW]);
+        assert_eq!(h.size.to_native(), 0xab1234);
+        assert_eq!(int::u24le::from_native(0xab1234).to_raw(), [0x34, 0x12, 0xab]);
+        assert_eq!(int::u24le::from_raw([0xff; 3]).to_native(), SIZE24_MAX);
+    }
+}
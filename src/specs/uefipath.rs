@@ -0,0 +1,1334 @@
+//! UEFI Device Paths
+//!
+//! A UEFI device path describes the location of a device, or of a file on a
+//! device, as a sequence of nodes from the root of the system to the target.
+//! It is used by boot options, the device path protocol, and various
+//! variables. Each node starts with a `NodeHeader` giving its type, subtype,
+//! and length. A device path can contain multiple instances, separated by
+//! nodes of `END_INSTANCE`, and is terminated by a node of `END_ENTIRE`.
+//!
+//! Nodes have no alignment guarantees, and their layout depends on the type
+//! and subtype. The layouts of the common fixed-size nodes are provided
+//! here. File path nodes (`MEDIA_FILE_PATH`) carry a NUL-terminated UTF-16
+//! string instead.
+//!
+//! All multi-byte integers are encoded as little-endian. Since the nodes are
+//! not naturally aligned, the structures are packed.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::int;
+use crate::specs::utf16;
+use crate::specs::uuid;
+
+pub const TYPE_HARDWARE: u8 = 0x01;
+pub const TYPE_ACPI: u8 = 0x02;
+pub const TYPE_MESSAGING: u8 = 0x03;
+pub const TYPE_MEDIA: u8 = 0x04;
+pub const TYPE_BBS: u8 = 0x05;
+pub const TYPE_END: u8 = 0x7f;
+
+pub const HW_PCI: u8 = 0x01;
+pub const HW_PCCARD: u8 = 0x02;
+pub const HW_MEMORY_MAPPED: u8 = 0x03;
+pub const HW_VENDOR: u8 = 0x04;
+pub const HW_CONTROLLER: u8 = 0x05;
+pub const HW_BMC: u8 = 0x06;
+
+pub const ACPI_ACPI: u8 = 0x01;
+pub const ACPI_EXTENDED: u8 = 0x02;
+pub const ACPI_ADR: u8 = 0x03;
+pub const ACPI_NVDIMM: u8 = 0x04;
+
+pub const MSG_ATAPI: u8 = 0x01;
+pub const MSG_SCSI: u8 = 0x02;
+pub const MSG_FIBRE_CHANNEL: u8 = 0x03;
+pub const MSG_1394: u8 = 0x04;
+pub const MSG_USB: u8 = 0x05;
+pub const MSG_I2O: u8 = 0x06;
+pub const MSG_INFINIBAND: u8 = 0x09;
+pub const MSG_VENDOR: u8 = 0x0a;
+pub const MSG_MAC: u8 = 0x0b;
+pub const MSG_IPV4: u8 = 0x0c;
+pub const MSG_IPV6: u8 = 0x0d;
+pub const MSG_UART: u8 = 0x0e;
+pub const MSG_USB_CLASS: u8 = 0x0f;
+pub const MSG_USB_WWID: u8 = 0x10;
+pub const MSG_LUN: u8 = 0x11;
+pub const MSG_SATA: u8 = 0x12;
+pub const MSG_ISCSI: u8 = 0x13;
+pub const MSG_VLAN: u8 = 0x14;
+pub const MSG_FIBRE_CHANNEL_EX: u8 = 0x15;
+pub const MSG_SAS_EX: u8 = 0x16;
+pub const MSG_NVME: u8 = 0x17;
+pub const MSG_URI: u8 = 0x18;
+pub const MSG_UFS: u8 = 0x19;
+pub const MSG_SD: u8 = 0x1a;
+pub const MSG_BLUETOOTH: u8 = 0x1b;
+pub const MSG_WIFI: u8 = 0x1c;
+pub const MSG_EMMC: u8 = 0x1d;
+pub const MSG_BLUETOOTH_LE: u8 = 0x1e;
+pub const MSG_DNS: u8 = 0x1f;
+pub const MSG_NVDIMM: u8 = 0x20;
+pub const MSG_REST_SERVICE: u8 = 0x21;
+pub const MSG_NVME_OF: u8 = 0x22;
+
+pub const MEDIA_HARD_DRIVE: u8 = 0x01;
+pub const MEDIA_CDROM: u8 = 0x02;
+pub const MEDIA_VENDOR: u8 = 0x03;
+pub const MEDIA_FILE_PATH: u8 = 0x04;
+pub const MEDIA_PROTOCOL: u8 = 0x05;
+pub const MEDIA_PIWG_FW_FILE: u8 = 0x06;
+pub const MEDIA_PIWG_FW_VOLUME: u8 = 0x07;
+pub const MEDIA_RELATIVE_OFFSET_RANGE: u8 = 0x08;
+pub const MEDIA_RAM_DISK: u8 = 0x09;
+
+pub const BBS_BBS101: u8 = 0x01;
+
+pub const END_INSTANCE: u8 = 0x01;
+pub const END_ENTIRE: u8 = 0xff;
+
+pub const PARTITION_FORMAT_MBR: u8 = 0x01;
+pub const PARTITION_FORMAT_GPT: u8 = 0x02;
+
+pub const SIGNATURE_TYPE_NONE: u8 = 0x00;
+pub const SIGNATURE_TYPE_MBR: u8 = 0x01;
+pub const SIGNATURE_TYPE_GUID: u8 = 0x02;
+
+/// PCI Root Bridge (PNP0A03)
+pub const HID_PCI_ROOT: u32 = eisa_id(*b"PNP", 0x0a03);
+
+/// PCI Express Root Bridge (PNP0A08)
+pub const HID_PCIE_ROOT: u32 = eisa_id(*b"PNP", 0x0a08);
+
+/// Device Path Node Header
+///
+/// The header of every device path node. The length includes the header.
+#[repr(C, packed)]
+pub struct NodeHeader {
+    /// Node type (`TYPE_*`).
+    pub node_type: u8,
+
+    /// Node subtype, specific to the node type.
+    pub subtype: u8,
+
+    /// Size of the node in bytes, including the header.
+    pub length: int::u16le,
+}
+
+/// PCI Device Path Node (`TYPE_HARDWARE`, `HW_PCI`)
+///
+/// Identifies a PCI function relative to its parent bus.
+#[repr(C, packed)]
+pub struct PciNode {
+    /// Node header.
+    pub header: NodeHeader,
+
+    /// PCI function number.
+    pub function: u8,
+
+    /// PCI device number.
+    pub device: u8,
+}
+
+/// Controller Device Path Node (`TYPE_HARDWARE`, `HW_CONTROLLER`)
+#[repr(C, packed)]
+pub struct ControllerNode {
+    /// Node header.
+    pub header: NodeHeader,
+
+    /// Controller number.
+    pub controller_number: int::u32le,
+}
+
+/// Vendor-defined Device Path Node
+///
+/// The layout of `HW_VENDOR`, `MSG_VENDOR`, and `MEDIA_VENDOR` nodes. It is
+/// followed by vendor-defined data.
+#[repr(C, packed)]
+pub struct VendorNode {
+    /// Node header.
+    pub header: NodeHeader,
+
+    /// Vendor GUID defining the data.
+    pub vendor_guid: uuid::Guid,
+}
+
+/// ACPI Device Path Node (`TYPE_ACPI`, `ACPI_ACPI`)
+///
+/// Identifies a device in the ACPI namespace by its compressed EISA ids (see
+/// `eisa_id()`).
+#[repr(C, packed)]
+pub struct AcpiNode {
+    /// Node header.
+    pub header: NodeHeader,
+
+    /// Compressed EISA id of the `_HID` of the device.
+    pub hid: int::u32le,
+
+    /// Value of the `_UID` of the device.
+    pub uid: int::u32le,
+}
+
+/// ACPI _ADR Device Path Node (`TYPE_ACPI`, `ACPI_ADR`)
+///
+/// Followed by further `_ADR` values, for devices with multiple outputs.
+#[repr(C, packed)]
+pub struct AcpiAdrNode {
+    /// Node header.
+    pub header: NodeHeader,
+
+    /// Value of the `_ADR` of the device.
+    pub adr: int::u32le,
+}
+
+/// SCSI Device Path Node (`TYPE_MESSAGING`, `MSG_SCSI`)
+#[repr(C, packed)]
+pub struct ScsiNode {
+    /// Node header.
+    pub header: NodeHeader,
+
+    /// Target id on the SCSI bus.
+    pub target_id: int::u16le,
+
+    /// Logical unit number.
+    pub lun: int::u16le,
+}
+
+/// USB Device Path Node (`TYPE_MESSAGING`, `MSG_USB`)
+#[repr(C, packed)]
+pub struct UsbNode {
+    /// Node header.
+    pub header: NodeHeader,
+
+    /// Port number of the USB parent.
+    pub parent_port_number: u8,
+
+    /// Interface number of the USB device.
+    pub interface_number: u8,
+}
+
+/// USB Class Device Path Node (`TYPE_MESSAGING`, `MSG_USB_CLASS`)
+///
+/// Matches USB devices by class. Fields of `0xffff` or `0xff` match any
+/// value.
+#[repr(C, packed)]
+pub struct UsbClassNode {
+    /// Node header.
+    pub header: NodeHeader,
+
+    /// USB vendor id.
+    pub vendor_id: int::u16le,
+
+    /// USB product id.
+    pub product_id: int::u16le,
+
+    /// USB class code.
+    pub device_class: u8,
+
+    /// USB subclass code.
+    pub device_subclass: u8,
+
+    /// USB protocol code.
+    pub device_protocol: u8,
+}
+
+/// MAC Address Device Path Node (`TYPE_MESSAGING`, `MSG_MAC`)
+#[repr(C, packed)]
+pub struct MacAddressNode {
+    /// Node header.
+    pub header: NodeHeader,
+
+    /// MAC address, padded with zeroes.
+    pub mac_address: [u8; 32],
+
+    /// Network interface type (e.g., 1 for Ethernet).
+    pub if_type: u8,
+}
+
+/// IPv4 Device Path Node (`TYPE_MESSAGING`, `MSG_IPV4`)
+///
+/// The layout of UEFI 2.0 and later.
+#[repr(C, packed)]
+pub struct Ipv4Node {
+    /// Node header.
+    pub header: NodeHeader,
+
+    /// Local IPv4 address.
+    pub local_ip_address: [u8; 4],
+
+    /// Remote IPv4 address.
+    pub remote_ip_address: [u8; 4],
+
+    /// Local port number.
+    pub local_port: int::u16le,
+
+    /// Remote port number.
+    pub remote_port: int::u16le,
+
+    /// IP protocol number (e.g., 6 for TCP).
+    pub protocol: int::u16le,
+
+    /// 1 if the local address is static, 0 if assigned via DHCP.
+    pub static_ip_address: u8,
+
+    /// Gateway IPv4 address.
+    pub gateway_ip_address: [u8; 4],
+
+    /// Subnet mask.
+    pub subnet_mask: [u8; 4],
+}
+
+/// SATA Device Path Node (`TYPE_MESSAGING`, `MSG_SATA`)
+#[repr(C, packed)]
+pub struct SataNode {
+    /// Node header.
+    pub header: NodeHeader,
+
+    /// Port number of the host bus adapter.
+    pub hba_port_number: int::u16le,
+
+    /// Port multiplier port, or `0xffff` if directly connected.
+    pub port_multiplier_port_number: int::u16le,
+
+    /// Logical unit number.
+    pub lun: int::u16le,
+}
+
+/// NVMe Namespace Device Path Node (`TYPE_MESSAGING`, `MSG_NVME`)
+#[repr(C, packed)]
+pub struct NvmeNode {
+    /// Node header.
+    pub header: NodeHeader,
+
+    /// Namespace id, 0 and `0xffffffff` are invalid.
+    pub namespace_id: int::u32le,
+
+    /// IEEE extended unique identifier of the namespace, or zero.
+    pub ieee_eui64: [u8; 8],
+}
+
+/// Hard Drive Media Device Path Node (`TYPE_MEDIA`, `MEDIA_HARD_DRIVE`)
+///
+/// Identifies a partition of a disk.
+#[repr(C, packed)]
+pub struct HardDriveNode {
+    /// Node header.
+    pub header: NodeHeader,
+
+    /// Partition number (1-based), or 0 for the entire disk.
+    pub partition_number: int::u32le,
+
+    /// Start of the partition in logical blocks.
+    pub partition_start: int::u64le,
+
+    /// Size of the partition in logical blocks.
+    pub partition_size: int::u64le,
+
+    /// Partition signature: the GPT partition GUID, or the 32-bit MBR
+    /// disk signature padded with zeroes.
+    pub partition_signature: [u8; 16],
+
+    /// Partition format (`PARTITION_FORMAT_*`).
+    pub partition_format: u8,
+
+    /// Type of the partition signature (`SIGNATURE_TYPE_*`).
+    pub signature_type: u8,
+}
+
+/// CD-ROM Media Device Path Node (`TYPE_MEDIA`, `MEDIA_CDROM`)
+///
+/// Identifies an El Torito boot entry of a CD-ROM.
+#[repr(C, packed)]
+pub struct CdromNode {
+    /// Node header.
+    pub header: NodeHeader,
+
+    /// Boot entry number of the boot catalog, 0 for the initial entry.
+    pub boot_entry: int::u32le,
+
+    /// Start of the partition in logical blocks.
+    pub partition_start: int::u64le,
+
+    /// Size of the partition in logical blocks.
+    pub partition_size: int::u64le,
+}
+
+/// PI Firmware Device Path Node
+///
+/// The layout of `MEDIA_PIWG_FW_FILE` and `MEDIA_PIWG_FW_VOLUME` nodes,
+/// naming a firmware file or volume.
+#[repr(C, packed)]
+pub struct PiwgFirmwareNode {
+    /// Node header.
+    pub header: NodeHeader,
+
+    /// Name of the firmware file or volume.
+    pub name: uuid::Guid,
+}
+
+/// Relative Offset Range Device Path Node (`TYPE_MEDIA`, `MEDIA_RELATIVE_OFFSET_RANGE`)
+#[repr(C, packed)]
+pub struct RelativeOffsetRangeNode {
+    /// Node header.
+    pub header: NodeHeader,
+
+    /// Reserved.
+    pub reserved: int::u32le,
+
+    /// Offset of the first byte of the range.
+    pub starting_offset: int::u64le,
+
+    /// Offset of the last byte of the range.
+    pub ending_offset: int::u64le,
+}
+
+/// Node Reference
+///
+/// A reference to a single node of a device path, as yielded by `Nodes`.
+/// The data covers the entire node, including its header.
+#[derive(Clone, Copy, Debug)]
+pub struct Node<'data> {
+    /// Node type (`TYPE_*`).
+    pub node_type: u8,
+
+    /// Node subtype.
+    pub subtype: u8,
+
+    /// Data of the node, including the header.
+    pub data: &'data [u8],
+}
+
+/// Node Iterator
+///
+/// Iterate the nodes of a device path, including `END_INSTANCE` nodes. The
+/// iterator stops at the terminating `END_ENTIRE` node, which is not yielded,
+/// or at the first malformed node. Use `Nodes::is_terminated()` to check
+/// whether the terminator was found, and `Nodes::remainder()` to get the
+/// data following it.
+#[derive(Clone, Debug)]
+pub struct Nodes<'data> {
+    data: &'data [u8],
+    terminated: bool,
+}
+
+impl NodeHeader {
+    /// Import a node header from a byte slice
+    ///
+    /// Create a new node header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 4]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 4], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Convert to byte slice
+    ///
+    /// Return a byte-slice reference to the node header. No byte-order
+    /// conversions are applied.
+    pub fn as_bytes(&self) -> &[u8; 4] {
+        assert!(align_of::<[u8; 4]>() <= align_of::<Self>());
+        assert!(size_of::<[u8; 4]>() == size_of::<Self>());
+
+        unsafe {
+            core::mem::transmute::<&Self, &[u8; 4]>(self)
+        }
+    }
+
+    /// Create node header
+    ///
+    /// Create a new node header with the given type, subtype, and total
+    /// length of the node.
+    pub fn new(node_type: u8, subtype: u8, length: u16) -> Self {
+        Self {
+            node_type,
+            subtype,
+            length: int::ForeignEndian::from_native(length),
+        }
+    }
+}
+
+impl PciNode {
+    /// Import a node from a byte slice
+    ///
+    /// Create a new node from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 6]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 6], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Convert to byte slice
+    ///
+    /// Return a byte-slice reference to the node. No byte-order
+    /// conversions are applied.
+    pub fn as_bytes(&self) -> &[u8; 6] {
+        assert!(align_of::<[u8; 6]>() <= align_of::<Self>());
+        assert!(size_of::<[u8; 6]>() == size_of::<Self>());
+
+        unsafe {
+            core::mem::transmute::<&Self, &[u8; 6]>(self)
+        }
+    }
+
+    /// Create PCI node
+    ///
+    /// 
+    /// Create a new PCI node for the given device and function.
+    pub fn new(device: u8, function: u8) -> Self {
+        Self {
+            header: NodeHeader::new(TYPE_HARDWARE, HW_PCI, size_of::<Self>() as u16),
+            function,
+            device,
+        }
+    }
+}
+
+impl ControllerNode {
+    /// Import a node from a byte slice
+    ///
+    /// Create a new node from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 8]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 8], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Convert to byte slice
+    ///
+    /// Return a byte-slice reference to the node. No byte-order
+    /// conversions are applied.
+    pub fn as_bytes(&self) -> &[u8; 8] {
+        assert!(align_of::<[u8; 8]>() <= align_of::<Self>());
+        assert!(size_of::<[u8; 8]>() == size_of::<Self>());
+
+        unsafe {
+            core::mem::transmute::<&Self, &[u8; 8]>(self)
+        }
+    }
+}
+
+impl VendorNode {
+    /// Import a node from a byte slice
+    ///
+    /// Create a new node from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 20]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 20], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Convert to byte slice
+    ///
+    /// Return a byte-slice reference to the node. No byte-order
+    /// conversions are applied.
+    pub fn as_bytes(&self) -> &[u8; 20] {
+        assert!(align_of::<[u8; 20]>() <= align_of::<Self>());
+        assert!(size_of::<[u8; 20]>() == size_of::<Self>());
+
+        unsafe {
+            core::mem::transmute::<&Self, &[u8; 20]>(self)
+        }
+    }
+}
+
+impl AcpiNode {
+    /// Import a node from a byte slice
+    ///
+    /// Create a new node from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 12]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 12], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Convert to byte slice
+    ///
+    /// Return a byte-slice reference to the node. No byte-order
+    /// conversions are applied.
+    pub fn as_bytes(&self) -> &[u8; 12] {
+        assert!(align_of::<[u8; 12]>() <= align_of::<Self>());
+        assert!(size_of::<[u8; 12]>() == size_of::<Self>());
+
+        unsafe {
+            core::mem::transmute::<&Self, &[u8; 12]>(self)
+        }
+    }
+
+    /// Create ACPI node
+    ///
+    /// 
+    /// Create a new ACPI node for the given compressed ids.
+    pub fn new(hid: u32, uid: u32) -> Self {
+        Self {
+            header: NodeHeader::new(TYPE_ACPI, ACPI_ACPI, size_of::<Self>() as u16),
+            hid: int::ForeignEndian::from_native(hid),
+            uid: int::ForeignEndian::from_native(uid),
+        }
+    }
+}
+
+impl AcpiAdrNode {
+    /// Import a node from a byte slice
+    ///
+    /// Create a new node from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 8]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 8], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Convert to byte slice
+    ///
+    /// Return a byte-slice reference to the node. No byte-order
+    /// conversions are applied.
+    pub fn as_bytes(&self) -> &[u8; 8] {
+        assert!(align_of::<[u8; 8]>() <= align_of::<Self>());
+        assert!(size_of::<[u8; 8]>() == size_of::<Self>());
+
+        unsafe {
+            core::mem::transmute::<&Self, &[u8; 8]>(self)
+        }
+    }
+}
+
+impl ScsiNode {
+    /// Import a node from a byte slice
+    ///
+    /// Create a new node from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 8]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 8], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Convert to byte slice
+    ///
+    /// Return a byte-slice reference to the node. No byte-order
+    /// conversions are applied.
+    pub fn as_bytes(&self) -> &[u8; 8] {
+        assert!(align_of::<[u8; 8]>() <= align_of::<Self>());
+        assert!(size_of::<[u8; 8]>() == size_of::<Self>());
+
+        unsafe {
+            core::mem::transmute::<&Self, &[u8; 8]>(self)
+        }
+    }
+
+    /// Create SCSI node
+    ///
+    /// 
+    /// Create a new SCSI node for the given target and logical unit.
+    pub fn new(target_id: u16, lun: u16) -> Self {
+        Self {
+            header: NodeHeader::new(TYPE_MESSAGING, MSG_SCSI, size_of::<Self>() as u16),
+            target_id: int::ForeignEndian::from_native(target_id),
+            lun: int::ForeignEndian::from_native(lun),
+        }
+    }
+}
+
+impl UsbNode {
+    /// Import a node from a byte slice
+    ///
+    /// Create a new node from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 6]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 6], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Convert to byte slice
+    ///
+    /// Return a byte-slice reference to the node. No byte-order
+    /// conversions are applied.
+    pub fn as_bytes(&self) -> &[u8; 6] {
+        assert!(align_of::<[u8; 6]>() <= align_of::<Self>());
+        assert!(size_of::<[u8; 6]>() == size_of::<Self>());
+
+        unsafe {
+            core::mem::transmute::<&Self, &[u8; 6]>(self)
+        }
+    }
+
+    /// Create USB node
+    ///
+    /// 
+    /// Create a new USB node for the given parent port and interface.
+    pub fn new(parent_port_number: u8, interface_number: u8) -> Self {
+        Self {
+            header: NodeHeader::new(TYPE_MESSAGING, MSG_USB, size_of::<Self>() as u16),
+            parent_port_number,
+            interface_number,
+        }
+    }
+}
+
+impl UsbClassNode {
+    /// Import a node from a byte slice
+    ///
+    /// Create a new node from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 11]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 11], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Convert to byte slice
+    ///
+    /// Return a byte-slice reference to the node. No byte-order
+    /// conversions are applied.
+    pub fn as_bytes(&self) -> &[u8; 11] {
+        assert!(align_of::<[u8; 11]>() <= align_of::<Self>());
+        assert!(size_of::<[u8; 11]>() == size_of::<Self>());
+
+        unsafe {
+            core::mem::transmute::<&Self, &[u8; 11]>(self)
+        }
+    }
+}
+
+impl MacAddressNode {
+    /// Import a node from a byte slice
+    ///
+    /// Create a new node from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 37]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 37], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Convert to byte slice
+    ///
+    /// Return a byte-slice reference to the node. No byte-order
+    /// conversions are applied.
+    pub fn as_bytes(&self) -> &[u8; 37] {
+        assert!(align_of::<[u8; 37]>() <= align_of::<Self>());
+        assert!(size_of::<[u8; 37]>() == size_of::<Self>());
+
+        unsafe {
+            core::mem::transmute::<&Self, &[u8; 37]>(self)
+        }
+    }
+}
+
+impl Ipv4Node {
+    /// Import a node from a byte slice
+    ///
+    /// Create a new node from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 27]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 27], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Convert to byte slice
+    ///
+    /// Return a byte-slice reference to the node. No byte-order
+    /// conversions are applied.
+    pub fn as_bytes(&self) -> &[u8; 27] {
+        assert!(align_of::<[u8; 27]>() <= align_of::<Self>());
+        assert!(size_of::<[u8; 27]>() == size_of::<Self>());
+
+        unsafe {
+            core::mem::transmute::<&Self, &[u8; 27]>(self)
+        }
+    }
+}
+
+impl SataNode {
+    /// Import a node from a byte slice
+    ///
+    /// Create a new node from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 10]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 10], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Convert to byte slice
+    ///
+    /// Return a byte-slice reference to the node. No byte-order
+    /// conversions are applied.
+    pub fn as_bytes(&self) -> &[u8; 10] {
+        assert!(align_of::<[u8; 10]>() <= align_of::<Self>());
+        assert!(size_of::<[u8; 10]>() == size_of::<Self>());
+
+        unsafe {
+            core::mem::transmute::<&Self, &[u8; 10]>(self)
+        }
+    }
+
+    /// Create SATA node
+    ///
+    /// 
+    /// Create a new SATA node for the given ports and logical unit.
+    pub fn new(hba_port_number: u16, port_multiplier_port_number: u16, lun: u16) -> Self {
+        Self {
+            header: NodeHeader::new(TYPE_MESSAGING, MSG_SATA, size_of::<Self>() as u16),
+            hba_port_number: int::ForeignEndian::from_native(hba_port_number),
+            port_multiplier_port_number: int::ForeignEndian::from_native(
+                port_multiplier_port_number,
+            ),
+            lun: int::ForeignEndian::from_native(lun),
+        }
+    }
+}
+
+impl NvmeNode {
+    /// Import a node from a byte slice
+    ///
+    /// Create a new node from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 16]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 16], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Convert to byte slice
+    ///
+    /// Return a byte-slice reference to the node. No byte-order
+    /// conversions are applied.
+    pub fn as_bytes(&self) -> &[u8; 16] {
+        assert!(align_of::<[u8; 16]>() <= align_of::<Self>());
+        assert!(size_of::<[u8; 16]>() == size_of::<Self>());
+
+        unsafe {
+            core::mem::transmute::<&Self, &[u8; 16]>(self)
+        }
+    }
+
+    /// Create NVMe node
+    ///
+    /// 
+    /// Create a new NVMe node for the given namespace.
+    pub fn new(namespace_id: u32, ieee_eui64: [u8; 8]) -> Self {
+        Self {
+            header: NodeHeader::new(TYPE_MESSAGING, MSG_NVME, size_of::<Self>() as u16),
+            namespace_id: int::ForeignEndian::from_native(namespace_id),
+            ieee_eui64,
+        }
+    }
+}
+
+impl HardDriveNode {
+    /// Import a node from a byte slice
+    ///
+    /// Create a new node from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 42]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 42], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Convert to byte slice
+    ///
+    /// Return a byte-slice reference to the node. No byte-order
+    /// conversions are applied.
+    pub fn as_bytes(&self) -> &[u8; 42] {
+        assert!(align_of::<[u8; 42]>() <= align_of::<Self>());
+        assert!(size_of::<[u8; 42]>() == size_of::<Self>());
+
+        unsafe {
+            core::mem::transmute::<&Self, &[u8; 42]>(self)
+        }
+    }
+
+    /// Create GPT hard drive node
+    ///
+    /// Create a new hard drive node for a GPT partition, identified by its
+    /// unique partition GUID.
+    pub fn new_gpt(
+        partition_number: u32,
+        partition_start: u64,
+        partition_size: u64,
+        partition_guid: uuid::Guid,
+    ) -> Self {
+        Self {
+            header: NodeHeader::new(TYPE_MEDIA, MEDIA_HARD_DRIVE, size_of::<Self>() as u16),
+            partition_number: int::ForeignEndian::from_native(partition_number),
+            partition_start: int::ForeignEndian::from_native(partition_start),
+            partition_size: int::ForeignEndian::from_native(partition_size),
+            partition_signature: partition_guid.to_raw(),
+            partition_format: PARTITION_FORMAT_GPT,
+            signature_type: SIGNATURE_TYPE_GUID,
+        }
+    }
+}
+
+impl CdromNode {
+    /// Import a node from a byte slice
+    ///
+    /// Create a new node from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 24]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 24], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Convert to byte slice
+    ///
+    /// Return a byte-slice reference to the node. No byte-order
+    /// conversions are applied.
+    pub fn as_bytes(&self) -> &[u8; 24] {
+        assert!(align_of::<[u8; 24]>() <= align_of::<Self>());
+        assert!(size_of::<[u8; 24]>() == size_of::<Self>());
+
+        unsafe {
+            core::mem::transmute::<&Self, &[u8; 24]>(self)
+        }
+    }
+}
+
+impl PiwgFirmwareNode {
+    /// Import a node from a byte slice
+    ///
+    /// Create a new node from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 20]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 20], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Convert to byte slice
+    ///
+    /// Return a byte-slice reference to the node. No byte-order
+    /// conversions are applied.
+    pub fn as_bytes(&self) -> &[u8; 20] {
+        assert!(align_of::<[u8; 20]>() <= align_of::<Self>());
+        assert!(size_of::<[u8; 20]>() == size_of::<Self>());
+
+        unsafe {
+            core::mem::transmute::<&Self, &[u8; 20]>(self)
+        }
+    }
+}
+
+impl RelativeOffsetRangeNode {
+    /// Import a node from a byte slice
+    ///
+    /// Create a new node from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 24]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 24], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Convert to byte slice
+    ///
+    /// Return a byte-slice reference to the node. No byte-order
+    /// conversions are applied.
+    pub fn as_bytes(&self) -> &[u8; 24] {
+        assert!(align_of::<[u8; 24]>() <= align_of::<Self>());
+        assert!(size_of::<[u8; 24]>() == size_of::<Self>());
+
+        unsafe {
+            core::mem::transmute::<&Self, &[u8; 24]>(self)
+        }
+    }
+}
+
+impl<'data> Node<'data> {
+    /// Parse a node
+    ///
+    /// Parse the node at the start of `data`. Any trailing data is ignored.
+    /// `None` is returned if the data is truncated, or the length of the node
+    /// is smaller than its header.
+    pub fn parse(data: &'data [u8]) -> Option<Self> {
+        let h = NodeHeader::from_bytes(data.get(..4)?.try_into().ok()?);
+        let length = int::ForeignEndian::to_native(h.length) as usize;
+        if length < size_of::<NodeHeader>() {
+            return None;
+        }
+
+        Some(Self {
+            node_type: h.node_type,
+            subtype: h.subtype,
+            data: data.get(..length)?,
+        })
+    }
+
+    /// Return node payload
+    ///
+    /// Return the data of the node following its header.
+    pub fn payload(&self) -> &'data [u8] {
+        &self.data[size_of::<NodeHeader>()..]
+    }
+
+    /// Check whether this is an end node
+    pub fn is_end(&self) -> bool {
+        self.node_type == TYPE_END
+    }
+
+    /// Decode file path
+    ///
+    /// Decode the path of a `MEDIA_FILE_PATH` node to UTF-8, dropping the NUL
+    /// terminator. Return the number of bytes written, or `None` if this is
+    /// not a file path node, the path is malformed, or the buffer is too
+    /// small.
+    pub fn file_path(&self, policy: utf16::Policy, out: &mut [u8]) -> Option<usize> {
+        if self.node_type != TYPE_MEDIA || self.subtype != MEDIA_FILE_PATH {
+            return None;
+        }
+
+        let v = self.payload();
+        let v = v.strip_suffix(&[0, 0]).unwrap_or(v);
+        utf16::decode_le(v, policy, out)
+    }
+}
+
+impl<'data> Nodes<'data> {
+    /// Create node iterator
+    ///
+    /// Create a new iterator over the nodes of a device path.
+    pub fn new(data: &'data [u8]) -> Self {
+        Self {
+            data,
+            terminated: false,
+        }
+    }
+
+    /// Return remaining data
+    ///
+    /// Return the data that has not been consumed by the iterator. Once the
+    /// iterator is terminated, this is the data following the terminator.
+    pub fn remainder(&self) -> &'data [u8] {
+        self.data
+    }
+
+    /// Check whether the terminator was found
+    pub fn is_terminated(&self) -> bool {
+        self.terminated
+    }
+}
+
+impl<'data> Iterator for Nodes<'data> {
+    type Item = Node<'data>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.terminated {
+            return None;
+        }
+
+        let v = Node::parse(self.data)?;
+        self.data = &self.data[v.data.len()..];
+        if v.node_type == TYPE_END && v.subtype == END_ENTIRE {
+            self.terminated = true;
+            return None;
+        }
+
+        Some(v)
+    }
+}
+
+/// Compress EISA id
+///
+/// Compress a 3-letter vendor id and 16-bit product id into the 32-bit form
+/// used by ACPI nodes. For example, "PNP0A03" is `eisa_id(*b"PNP", 0x0a03)`.
+/// The vendor letters must be upper-case ASCII.
+pub const fn eisa_id(vendor: [u8; 3], product: u16) -> u32 {
+    let v = (((vendor[0] & 0x1f) as u32) << 10)
+        | (((vendor[1] & 0x1f) as u32) << 5)
+        | ((vendor[2] & 0x1f) as u32);
+    ((product as u32) << 16) | v
+}
+
+/// Return device path size
+///
+/// Return the size of the device path at the start of `data` in bytes,
+/// including its terminator, or `None` if it is malformed or not terminated.
+pub fn size(data: &[u8]) -> Option<usize> {
+    let mut nodes = Nodes::new(data);
+
+    for _ in nodes.by_ref() {}
+    if nodes.is_terminated() {
+        Some(data.len() - nodes.remainder().len())
+    } else {
+        None
+    }
+}
+
+/// Encode node
+///
+/// Write a node of the given type and subtype with the given payload to
+/// `out`. Return the number of bytes written, or `None` if the buffer is
+/// too small, or the node exceeds the maximum length.
+pub fn encode_node(node_type: u8, subtype: u8, payload: &[u8], out: &mut [u8]) -> Option<usize> {
+    let length = size_of::<NodeHeader>() + payload.len();
+    let h = NodeHeader::new(node_type, subtype, u16::try_from(length).ok()?);
+
+    out.get_mut(..4)?.copy_from_slice(h.as_bytes());
+    out.get_mut(4..length)?.copy_from_slice(payload);
+    Some(length)
+}
+
+/// Encode end node
+///
+/// Write an `END_ENTIRE` node to `out`. Return the number of bytes written,
+/// or `None` if the buffer is too small.
+pub fn encode_end(out: &mut [u8]) -> Option<usize> {
+    encode_node(TYPE_END, END_ENTIRE, &[], out)
+}
+
+/// Encode file path node
+///
+/// Write a `MEDIA_FILE_PATH` node with the given path, encoded as
+/// NUL-terminated UTF-16, to `out`. UEFI uses backslashes as path separator.
+/// Return the number of bytes written, or `None` if the buffer is too
+/// small, or the node exceeds the maximum length.
+pub fn encode_file_path(path: &str, out: &mut [u8]) -> Option<usize> {
+    let n = utf16::encode_le(path, out.get_mut(4..)?)?;
+    out.get_mut(4 + n..4 + n + 2)?.copy_from_slice(&[0, 0]);
+
+    let length = u16::try_from(4 + n + 2).ok()?;
+    let h = NodeHeader::new(TYPE_MEDIA, MEDIA_FILE_PATH, length);
+    out[..4].copy_from_slice(h.as_bytes());
+    Some(length as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::specs::int::ForeignEndian;
+
+    // Device path of a boot loader on a GPT partition of a SATA disk:
+    // PciRoot(0x0)/Pci(0x1f,0x2)/Sata(0x0,0xffff,0x0)/HD(1,GPT,...)/\EFI\BOOT\BOOTX64.EFI
+    const PATH: [u8; 122] = [
+        0x02, 0x01, 0x0c, 0x00, 0xd0, 0x41, 0x03, 0x0a,
+        0x00, 0x00, 0x00, 0x00, 0x01, 0x01, 0x06, 0x00,
+        0x02, 0x1f, 0x03, 0x12, 0x0a, 0x00, 0x00, 0x00,
+        0xff, 0xff, 0x00, 0x00, 0x04, 0x01, 0x2a, 0x00,
+        0x01, 0x00, 0x00, 0x00, 0x00, 0x08, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x10, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0xc5, 0xb0, 0xe5, 0x5d,
+        0x1c, 0x9b, 0x9b, 0x4e, 0xa5, 0xd5, 0x6a, 0x3a,
+        0x9c, 0x2b, 0x7e, 0x41, 0x02, 0x02, 0x04, 0x04,
+        0x30, 0x00, 0x5c, 0x00, 0x45, 0x00, 0x46, 0x00,
+        0x49, 0x00, 0x5c, 0x00, 0x42, 0x00, 0x4f, 0x00,
+        0x4f, 0x00, 0x54, 0x00, 0x5c, 0x00, 0x42, 0x00,
+        0x4f, 0x00, 0x4f, 0x00, 0x54, 0x00, 0x58, 0x00,
+        0x36, 0x00, 0x34, 0x00, 0x2e, 0x00, 0x45, 0x00,
+        0x46, 0x00, 0x49, 0x00, 0x00, 0x00, 0x7f, 0xff,
+        0x04, 0x00,
+    ];
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<NodeHeader>(), 4);
+        assert_eq!(size_of::<PciNode>(), 6);
+        assert_eq!(size_of::<ControllerNode>(), 8);
+        assert_eq!(size_of::<VendorNode>(), 20);
+        assert_eq!(size_of::<AcpiNode>(), 12);
+        assert_eq!(size_of::<AcpiAdrNode>(), 8);
+        assert_eq!(size_of::<ScsiNode>(), 8);
+        assert_eq!(size_of::<UsbNode>(), 6);
+        assert_eq!(size_of::<UsbClassNode>(), 11);
+        assert_eq!(size_of::<MacAddressNode>(), 37);
+        assert_eq!(size_of::<Ipv4Node>(), 27);
+        assert_eq!(size_of::<SataNode>(), 10);
+        assert_eq!(size_of::<NvmeNode>(), 16);
+        assert_eq!(size_of::<HardDriveNode>(), 42);
+        assert_eq!(align_of::<HardDriveNode>(), 1);
+        assert_eq!(size_of::<CdromNode>(), 24);
+        assert_eq!(size_of::<PiwgFirmwareNode>(), 20);
+        assert_eq!(size_of::<RelativeOffsetRangeNode>(), 24);
+    }
+
+    // Walk all nodes of a real-world shaped boot path and decode them.
+    #[test]
+    fn verify_parse() {
+        assert_eq!(size(&PATH), Some(PATH.len()));
+        assert_eq!(size(&PATH[..PATH.len() - 1]), None);
+
+        let mut nodes = Nodes::new(&PATH);
+
+        let n = nodes.next().unwrap();
+        assert_eq!((n.node_type, n.subtype), (TYPE_ACPI, ACPI_ACPI));
+        let v = AcpiNode::from_bytes(n.data.try_into().unwrap());
+        assert_eq!(v.hid.to_native(), HID_PCI_ROOT);
+        assert_eq!(v.uid.to_native(), 0);
+
+        let n = nodes.next().unwrap();
+        let v = PciNode::from_bytes(n.data.try_into().unwrap());
+        assert_eq!((v.device, v.function), (0x1f, 0x02));
+
+        let n = nodes.next().unwrap();
+        let v = SataNode::from_bytes(n.data.try_into().unwrap());
+        assert_eq!(v.port_multiplier_port_number.to_native(), 0xffff);
+
+        let n = nodes.next().unwrap();
+        assert_eq!((n.node_type, n.subtype), (TYPE_MEDIA, MEDIA_HARD_DRIVE));
+        let v = HardDriveNode::from_bytes(n.data.try_into().unwrap());
+        assert_eq!(v.partition_number.to_native(), 1);
+        assert_eq!(v.partition_start.to_native(), 0x800);
+        assert_eq!(v.partition_size.to_native(), 0x100000);
+        assert_eq!(v.partition_format, PARTITION_FORMAT_GPT);
+        assert_eq!(v.signature_type, SIGNATURE_TYPE_GUID);
+        assert_eq!(
+            uuid::Guid::from_raw(v.partition_signature),
+            uuid::Guid::from_u128(0x5de5b0c5_9b1c_4e9b_a5d5_6a3a9c2b7e41),
+        );
+
+        let n = nodes.next().unwrap();
+        let mut buf = [0u8; 32];
+        let len = n.file_path(utf16::Policy::Reject, &mut buf).unwrap();
+        assert_eq!(&buf[..len], b"\\EFI\\BOOT\\BOOTX64.EFI");
+
+        assert!(nodes.next().is_none());
+        assert!(nodes.is_terminated());
+        assert!(nodes.remainder().is_empty());
+    }
+
+    // Construct the same boot path from its parts.
+    #[test]
+    fn verify_encode() {
+        let mut buf = [0u8; 128];
+        let mut o = 0;
+
+        let hd = HardDriveNode::new_gpt(
+            1,
+            0x800,
+            0x100000,
+            uuid::Guid::from_u128(0x5de5b0c5_9b1c_4e9b_a5d5_6a3a9c2b7e41),
+        );
+        for v in [
+            &AcpiNode::new(HID_PCI_ROOT, 0).as_bytes()[..],
+            &PciNode::new(0x1f, 0x02).as_bytes()[..],
+            &SataNode::new(0, 0xffff, 0).as_bytes()[..],
+            &hd.as_bytes()[..],
+        ] {
+            buf[o..o + v.len()].copy_from_slice(v);
+            o += v.len();
+        }
+        o += encode_file_path("\\EFI\\BOOT\\BOOTX64.EFI", &mut buf[o..]).unwrap();
+        o += encode_end(&mut buf[o..]).unwrap();
+
+        assert_eq!(&buf[..o], &PATH[..]);
+        assert_eq!(encode_end(&mut buf[..3]), None);
+        assert_eq!(eisa_id(*b"PNP", 0x0a08), 0x0a0841d0);
+    }
+}
@@ -0,0 +1,603 @@
+//! UEFI Variables, Boot Options, and Capsules
+//!
+//! UEFI firmware stores configuration in variables, each identified by a
+//! vendor GUID and a UTF-16 name, and carrying attributes (`VARIABLE_*`)
+//! and binary data. The variables of the boot manager use
+//! `GLOBAL_VARIABLE_GUID`. Boot options are stored in variables named
+//! `Boot####` (with `####` being 4 upper-case hexadecimal digits), and
+//! ordered by the `BootOrder` variable, an array of 16-bit option numbers.
+//!
+//! The data of boot options is an `EFI_LOAD_OPTION`: a `LoadOptionHeader`,
+//! followed by a NUL-terminated UTF-16 description, a list of device paths
+//! (see `uefipath`), and optional data passed to the loaded image.
+//!
+//! Writes to variables with time-based authentication are prefixed with a
+//! `VariableAuthentication2` descriptor, carrying a timestamp and a PKCS#7
+//! signature. Firmware updates are passed to firmware as capsules, each
+//! starting with a `CapsuleHeader`.
+//!
+//! All multi-byte integers are encoded as little-endian.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::int;
+use crate::specs::utf16;
+use crate::specs::uuid;
+
+/// Global Variable GUID
+///
+/// The vendor GUID of the architecturally defined variables, including all
+/// boot manager variables (`EFI_GLOBAL_VARIABLE`).
+pub const GLOBAL_VARIABLE_GUID: uuid::Guid =
+    uuid::Guid::from_u128(0x8be4df61_93ca_11d2_aa0d_00e098032b8c);
+
+/// Image Security Database GUID
+///
+/// The vendor GUID of the signature databases `db`, `dbx`, `dbt`, and `dbr`.
+pub const IMAGE_SECURITY_DATABASE_GUID: uuid::Guid =
+    uuid::Guid::from_u128(0xd719b2cb_3d3a_4596_a3bc_dad00e67656f);
+
+pub const CERT_TYPE_PKCS7_GUID: uuid::Guid =
+    uuid::Guid::from_u128(0x4aafd29d_68df_49ee_8aa9_347d375665a7);
+pub const CERT_TYPE_RSA2048_SHA256_GUID: uuid::Guid =
+    uuid::Guid::from_u128(0xa7717414_c616_4977_9420_844712a735bf);
+
+/// FMP Capsule GUID
+///
+/// The capsule GUID of capsules carrying a `FmpCapsuleHeader`, as consumed by
+/// the firmware management protocol.
+pub const FMP_CAPSULE_GUID: uuid::Guid =
+    uuid::Guid::from_u128(0x6dcbd5ed_e82d_4c44_bda1_7194199ad92a);
+
+pub const VARIABLE_NON_VOLATILE: u32 = 0x0000_0001;
+pub const VARIABLE_BOOTSERVICE_ACCESS: u32 = 0x0000_0002;
+pub const VARIABLE_RUNTIME_ACCESS: u32 = 0x0000_0004;
+pub const VARIABLE_HARDWARE_ERROR_RECORD: u32 = 0x0000_0008;
+pub const VARIABLE_AUTHENTICATED_WRITE_ACCESS: u32 = 0x0000_0010;
+pub const VARIABLE_TIME_BASED_AUTHENTICATED_WRITE_ACCESS: u32 = 0x0000_0020;
+pub const VARIABLE_APPEND_WRITE: u32 = 0x0000_0040;
+pub const VARIABLE_ENHANCED_AUTHENTICATED_ACCESS: u32 = 0x0000_0080;
+
+pub const NAME_BOOT_ORDER: &str = "BootOrder";
+pub const NAME_BOOT_NEXT: &str = "BootNext";
+pub const NAME_BOOT_CURRENT: &str = "BootCurrent";
+pub const NAME_TIMEOUT: &str = "Timeout";
+pub const NAME_OS_INDICATIONS: &str = "OsIndications";
+pub const NAME_OS_INDICATIONS_SUPPORTED: &str = "OsIndicationsSupported";
+pub const NAME_SECURE_BOOT: &str = "SecureBoot";
+pub const NAME_SETUP_MODE: &str = "SetupMode";
+pub const NAME_PK: &str = "PK";
+pub const NAME_KEK: &str = "KEK";
+pub const NAME_DB: &str = "db";
+pub const NAME_DBX: &str = "dbx";
+
+pub const PREFIX_BOOT: &str = "Boot";
+pub const PREFIX_DRIVER: &str = "Driver";
+pub const PREFIX_SYSPREP: &str = "SysPrep";
+pub const PREFIX_PLATFORM_RECOVERY: &str = "PlatformRecovery";
+
+pub const LOAD_OPTION_ACTIVE: u32 = 0x0000_0001;
+pub const LOAD_OPTION_FORCE_RECONNECT: u32 = 0x0000_0002;
+pub const LOAD_OPTION_HIDDEN: u32 = 0x0000_0008;
+pub const LOAD_OPTION_CATEGORY_MASK: u32 = 0x0000_1f00;
+pub const LOAD_OPTION_CATEGORY_BOOT: u32 = 0x0000_0000;
+pub const LOAD_OPTION_CATEGORY_APP: u32 = 0x0000_0100;
+
+pub const OS_INDICATIONS_BOOT_TO_FW_UI: u64 = 0x0000_0000_0000_0001;
+pub const OS_INDICATIONS_TIMESTAMP_REVOCATION: u64 = 0x0000_0000_0000_0002;
+pub const OS_INDICATIONS_FILE_CAPSULE_DELIVERY_SUPPORTED: u64 = 0x0000_0000_0000_0004;
+pub const OS_INDICATIONS_FMP_CAPSULE_SUPPORTED: u64 = 0x0000_0000_0000_0008;
+pub const OS_INDICATIONS_CAPSULE_RESULT_VAR_SUPPORTED: u64 = 0x0000_0000_0000_0010;
+pub const OS_INDICATIONS_START_OS_RECOVERY: u64 = 0x0000_0000_0000_0020;
+pub const OS_INDICATIONS_START_PLATFORM_RECOVERY: u64 = 0x0000_0000_0000_0040;
+pub const OS_INDICATIONS_JSON_CONFIG_DATA_REFRESH: u64 = 0x0000_0000_0000_0080;
+
+pub const UNSPECIFIED_TIMEZONE: i16 = 0x07ff;
+pub const TIME_ADJUST_DAYLIGHT: u8 = 0x01;
+pub const TIME_IN_DAYLIGHT: u8 = 0x02;
+
+pub const WIN_CERT_REVISION: u16 = 0x0200;
+pub const WIN_CERT_TYPE_PKCS_SIGNED_DATA: u16 = 0x0002;
+pub const WIN_CERT_TYPE_EFI_PKCS115: u16 = 0x0ef0;
+pub const WIN_CERT_TYPE_EFI_GUID: u16 = 0x0ef1;
+
+pub const CAPSULE_FLAGS_PERSIST_ACROSS_RESET: u32 = 0x0001_0000;
+pub const CAPSULE_FLAGS_POPULATE_SYSTEM_TABLE: u32 = 0x0002_0000;
+pub const CAPSULE_FLAGS_INITIATE_RESET: u32 = 0x0004_0000;
+
+pub const FMP_CAPSULE_HEADER_VERSION: u32 = 0x0000_0001;
+
+/// Load Option Header
+///
+/// The fixed part of `EFI_LOAD_OPTION`, followed by the NUL-terminated UTF-16
+/// description, the device path list, and the optional data (see
+/// `LoadOption`). Since the header is not padded to its natural alignment,
+/// this structure is packed.
+#[repr(C, packed)]
+pub struct LoadOptionHeader {
+    /// Load option attributes (`LOAD_OPTION_*`).
+    pub attributes: int::u32le,
+
+    /// Size of the device path list in bytes.
+    pub file_path_list_length: int::u16le,
+}
+
+/// Time
+///
+/// The `EFI_TIME` structure. The time zone is the offset from UTC in
+/// minutes, or `UNSPECIFIED_TIMEZONE`.
+#[repr(C)]
+pub struct Time {
+    /// Year, 1900 to 9999.
+    pub year: int::u16le,
+
+    /// Month, 1 to 12.
+    pub month: u8,
+
+    /// Day of the month, 1 to 31.
+    pub day: u8,
+
+    /// Hour, 0 to 23.
+    pub hour: u8,
+
+    /// Minute, 0 to 59.
+    pub minute: u8,
+
+    /// Second, 0 to 59.
+    pub second: u8,
+
+    /// Padding.
+    pub pad1: u8,
+
+    /// Nanosecond, 0 to 999999999.
+    pub nanosecond: int::u32le,
+
+    /// Offset from UTC in minutes, or `UNSPECIFIED_TIMEZONE`.
+    pub time_zone: int::i16le,
+
+    /// Daylight saving time flags (`TIME_*`).
+    pub daylight: u8,
+
+    /// Padding.
+    pub pad2: u8,
+}
+
+/// Certificate Header
+///
+/// The `WIN_CERTIFICATE` header, followed by certificate data of the given
+/// type. The length includes the header.
+#[repr(C)]
+pub struct WinCertificate {
+    /// Size of the certificate in bytes, including the header.
+    pub length: int::u32le,
+
+    /// Revision of the structure (`WIN_CERT_REVISION`).
+    pub revision: int::u16le,
+
+    /// Certificate type (`WIN_CERT_TYPE_*`).
+    pub certificate_type: int::u16le,
+}
+
+/// GUID Certificate Header
+///
+/// The `WIN_CERTIFICATE_UEFI_GUID` header of `WIN_CERT_TYPE_EFI_GUID`,
+/// followed by certificate data of the format given by the GUID.
+#[repr(C)]
+pub struct WinCertificateUefiGuid {
+    /// Common certificate header.
+    pub header: WinCertificate,
+
+    /// Format of the certificate data (e.g., `CERT_TYPE_PKCS7_GUID`).
+    pub cert_type: uuid::Guid,
+}
+
+/// Time-based Authentication Descriptor
+///
+/// The `EFI_VARIABLE_AUTHENTICATION_2` descriptor prepended to the data of
+/// writes to variables with `VARIABLE_TIME_BASED_AUTHENTICATED_WRITE_ACCESS`.
+/// The certificate data follows, as given by the certificate length, and
+/// then the variable data.
+#[repr(C)]
+pub struct VariableAuthentication2 {
+    /// Time of the signature, with all fields but the date and time zeroed.
+    pub timestamp: Time,
+
+    /// Certificate header, usually of `CERT_TYPE_PKCS7_GUID`.
+    pub auth_info: WinCertificateUefiGuid,
+}
+
+/// Capsule Header
+///
+/// The header of every capsule passed to `UpdateCapsule()`, followed by the
+/// capsule body at `header_size`.
+#[repr(C)]
+pub struct CapsuleHeader {
+    /// Format of the capsule (e.g., `FMP_CAPSULE_GUID`).
+    pub capsule_guid: uuid::Guid,
+
+    /// Size of the header in bytes.
+    pub header_size: int::u32le,
+
+    /// Capsule flags (`CAPSULE_FLAGS_*`).
+    pub flags: int::u32le,
+
+    /// Size of the capsule in bytes, including the header.
+    pub capsule_image_size: int::u32le,
+}
+
+/// Firmware Management Capsule Header
+///
+/// The header of the body of `FMP_CAPSULE_GUID` capsules, followed by the
+/// 64-bit offsets of the embedded drivers and then the payload items.
+#[repr(C)]
+pub struct FmpCapsuleHeader {
+    /// Version of the structure (`FMP_CAPSULE_HEADER_VERSION`).
+    pub version: int::u32le,
+
+    /// Number of embedded drivers.
+    pub embedded_driver_count: int::u16le,
+
+    /// Number of payload items.
+    pub payload_item_count: int::u16le,
+}
+
+/// Load Option Reference
+///
+/// A parsed `EFI_LOAD_OPTION`, referencing the variable data.
+#[derive(Clone, Copy, Debug)]
+pub struct LoadOption<'data> {
+    /// Load option attributes (`LOAD_OPTION_*`).
+    pub attributes: u32,
+
+    /// Description as UTF-16LE bytes, without the NUL terminator.
+    pub description: &'data [u8],
+
+    /// List of device paths, the first one locating the image to load.
+    pub file_path_list: &'data [u8],
+
+    /// Optional data passed to the loaded image.
+    pub optional_data: &'data [u8],
+}
+
+impl LoadOptionHeader {
+    /// Import a load option header from a byte slice
+    ///
+    /// Create a new load option header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 6]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 6], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl Time {
+    /// Import a time from a byte slice
+    ///
+    /// Create a new time from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 16]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 16], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl WinCertificate {
+    /// Import a certificate header from a byte slice
+    ///
+    /// Create a new certificate header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 8]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 8], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl WinCertificateUefiGuid {
+    /// Import a certificate header from a byte slice
+    ///
+    /// Create a new certificate header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 24]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 24], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl VariableAuthentication2 {
+    /// Import an authentication descriptor from a byte slice
+    ///
+    /// Create a new authentication descriptor from data copied from a byte
+    /// slice. No byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 40]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 40], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl CapsuleHeader {
+    /// Import a capsule header from a byte slice
+    ///
+    /// Create a new capsule header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 28]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 28], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl FmpCapsuleHeader {
+    /// Import an FMP capsule header from a byte slice
+    ///
+    /// Create a new FMP capsule header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 8]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 8], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl<'data> LoadOption<'data> {
+    /// Parse a load option
+    ///
+    /// Parse the load option stored in `data`. All data following the device
+    /// path list is taken as optional data. `None` is returned if the data
+    /// is truncated, or the description is not terminated.
+    pub fn parse(data: &'data [u8]) -> Option<Self> {
+        let h = LoadOptionHeader::from_bytes(data.get(..6)?.try_into().ok()?);
+        let rest = &data[6..];
+
+        let end = rest.chunks_exact(2).position(|v| v == [0, 0])? * 2;
+        let description = &rest[..end];
+        let rest = &rest[end + 2..];
+
+        let length = int::ForeignEndian::to_native(h.file_path_list_length) as usize;
+        let file_path_list = rest.get(..length)?;
+
+        Some(Self {
+            attributes: int::ForeignEndian::to_native(h.attributes),
+            description,
+            file_path_list,
+            optional_data: &rest[length..],
+        })
+    }
+
+    /// Decode description
+    ///
+    /// Decode the description to UTF-8. Return the number of bytes written,
+    /// or `None` if the description is malformed, or the buffer is too
+    /// small.
+    pub fn decode_description(&self, policy: utf16::Policy, out: &mut [u8]) -> Option<usize> {
+        utf16::decode_le(self.description, policy, out)
+    }
+
+    /// Check whether the option is active
+    pub fn is_active(&self) -> bool {
+        self.attributes & LOAD_OPTION_ACTIVE != 0
+    }
+
+    /// Encode a load option
+    ///
+    /// Write a load option with the given attributes, description, device
+    /// path list, and optional data to `out`. Return the number of bytes
+    /// written, or `None` if the buffer is too small, or the device path list
+    /// exceeds the maximum length.
+    pub fn encode(
+        attributes: u32,
+        description: &str,
+        file_path_list: &[u8],
+        optional_data: &[u8],
+        out: &mut [u8],
+    ) -> Option<usize> {
+        let length = u16::try_from(file_path_list.len()).ok()?;
+        out.get_mut(..4)?.copy_from_slice(&attributes.to_le_bytes());
+        out.get_mut(4..6)?.copy_from_slice(&length.to_le_bytes());
+
+        let mut o = 6;
+        o += utf16::encode_le(description, out.get_mut(o..)?)?;
+        for v in [&[0, 0][..], file_path_list, optional_data] {
+            out.get_mut(o..o + v.len())?.copy_from_slice(v);
+            o += v.len();
+        }
+
+        Some(o)
+    }
+}
+
+/// Format option variable name
+///
+/// Write the name of the load option variable with the given prefix (e.g.,
+/// `PREFIX_BOOT`) and number to `out`, such as "Boot0001". Return the number
+/// of bytes written, or `None` if the buffer is too small.
+pub fn option_name(prefix: &str, number: u16, out: &mut [u8]) -> Option<usize> {
+    const HEX: &[u8; 16] = b"0123456789ABCDEF";
+    let n = prefix.len();
+
+    out.get_mut(..n)?.copy_from_slice(prefix.as_bytes());
+    for (i, v) in out.get_mut(n..n + 4)?.iter_mut().enumerate() {
+        *v = HEX[((number >> (12 - i * 4)) & 0xf) as usize];
+    }
+
+    Some(n + 4)
+}
+
+/// Parse option variable name
+///
+/// Parse the number of a load option variable name with the given prefix,
+/// such as "Boot0001". The number must be given as exactly 4 upper-case
+/// hexadecimal digits. `None` is returned if the name does not match.
+pub fn parse_option_name(prefix: &str, name: &str) -> Option<u16> {
+    let v = name.strip_prefix(prefix)?.as_bytes();
+    if v.len() != 4 {
+        return None;
+    }
+
+    v.iter().try_fold(0u16, |acc, c| {
+        let d = match c {
+            b'0'..=b'9' => c - b'0',
+            b'A'..=b'F' => c - b'A' + 10,
+            _ => return None,
+        };
+        Some((acc << 4) | d as u16)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::specs::int::ForeignEndian;
+    use crate::specs::uefipath;
+
+    // Boot option "Linux Boot Manager" with a file path, and two bytes of
+    // optional data.
+    const BOOT_OPTION: [u8; 120] = [
+        0x01, 0x00, 0x00, 0x00, 0x4a, 0x00, 0x4c, 0x00,
+        0x69, 0x00, 0x6e, 0x00, 0x75, 0x00, 0x78, 0x00,
+        0x20, 0x00, 0x42, 0x00, 0x6f, 0x00, 0x6f, 0x00,
+        0x74, 0x00, 0x20, 0x00, 0x4d, 0x00, 0x61, 0x00,
+        0x6e, 0x00, 0x61, 0x00, 0x67, 0x00, 0x65, 0x00,
+        0x72, 0x00, 0x00, 0x00, 0x04, 0x04, 0x46, 0x00,
+        0x5c, 0x00, 0x45, 0x00, 0x46, 0x00, 0x49, 0x00,
+        0x5c, 0x00, 0x73, 0x00, 0x79, 0x00, 0x73, 0x00,
+        0x74, 0x00, 0x65, 0x00, 0x6d, 0x00, 0x64, 0x00,
+        0x5c, 0x00, 0x73, 0x00, 0x79, 0x00, 0x73, 0x00,
+        0x74, 0x00, 0x65, 0x00, 0x6d, 0x00, 0x64, 0x00,
+        0x2d, 0x00, 0x62, 0x00, 0x6f, 0x00, 0x6f, 0x00,
+        0x74, 0x00, 0x78, 0x00, 0x36, 0x00, 0x34, 0x00,
+        0x2e, 0x00, 0x65, 0x00, 0x66, 0x00, 0x69, 0x00,
+        0x00, 0x00, 0x7f, 0xff, 0x04, 0x00, 0x01, 0x02,
+    ];
+
+    // Authentication descriptor with an empty PKCS#7 signature.
+    const AUTH2: [u8; 40] = [
+        0xe8, 0x07, 0x01, 0x02, 0x03, 0x04, 0x05, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x18, 0x00, 0x00, 0x00, 0x00, 0x02, 0xf1, 0x0e,
+        0x9d, 0xd2, 0xaf, 0x4a, 0xdf, 0x68, 0xee, 0x49,
+        0x8a, 0xa9, 0x34, 0x7d, 0x37, 0x56, 0x65, 0xa7,
+    ];
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<LoadOptionHeader>(), 6);
+        assert_eq!(align_of::<LoadOptionHeader>(), 1);
+        assert_eq!(size_of::<Time>(), 16);
+        assert_eq!(size_of::<WinCertificate>(), 8);
+        assert_eq!(size_of::<WinCertificateUefiGuid>(), 24);
+        assert_eq!(size_of::<VariableAuthentication2>(), 40);
+        assert_eq!(size_of::<CapsuleHeader>(), 28);
+        assert_eq!(size_of::<FmpCapsuleHeader>(), 8);
+    }
+
+    // Parse a boot option, decode its parts, and encode it again.
+    #[test]
+    fn verify_load_option() {
+        let v = LoadOption::parse(&BOOT_OPTION).unwrap();
+        assert!(v.is_active());
+        assert_eq!(v.attributes & LOAD_OPTION_CATEGORY_MASK, LOAD_OPTION_CATEGORY_BOOT);
+        assert_eq!(v.optional_data, [0x01, 0x02]);
+
+        let mut buf = [0u8; 128];
+        let n = v.decode_description(utf16::Policy::Reject, &mut buf).unwrap();
+        assert_eq!(&buf[..n], b"Linux Boot Manager");
+
+        assert_eq!(uefipath::size(v.file_path_list), Some(v.file_path_list.len()));
+        let node = uefipath::Node::parse(v.file_path_list).unwrap();
+        let n = node.file_path(utf16::Policy::Reject, &mut buf).unwrap();
+        assert_eq!(&buf[..n], b"\\EFI\\systemd\\systemd-bootx64.efi");
+
+        let n = LoadOption::encode(
+            LOAD_OPTION_ACTIVE,
+            "Linux Boot Manager",
+            v.file_path_list,
+            &[0x01, 0x02],
+            &mut buf,
+        ).unwrap();
+        assert_eq!(&buf[..n], &BOOT_OPTION[..]);
+
+        assert!(LoadOption::parse(&BOOT_OPTION[..40]).is_none());
+        assert!(LoadOption::parse(&BOOT_OPTION[..60]).is_none());
+    }
+
+    // Decode the timestamp and certificate header of an authentication
+    // descriptor.
+    #[test]
+    fn verify_authentication() {
+        let v = VariableAuthentication2::from_bytes(&AUTH2);
+        assert_eq!(v.timestamp.year.to_native(), 2024);
+        assert_eq!((v.timestamp.month, v.timestamp.day), (1, 2));
+        assert_eq!(v.timestamp.nanosecond.to_native(), 0);
+        assert_eq!(v.auth_info.header.length.to_native(), 24);
+        assert_eq!(v.auth_info.header.revision.to_native(), WIN_CERT_REVISION);
+        assert_eq!(v.auth_info.header.certificate_type.to_native(), WIN_CERT_TYPE_EFI_GUID);
+        assert_eq!(v.auth_info.cert_type, CERT_TYPE_PKCS7_GUID);
+    }
+
+    // Format and parse load option variable names.
+    #[test]
+    fn verify_option_name() {
+        let mut buf = [0u8; 16];
+        let n = option_name(PREFIX_BOOT, 0x00af, &mut buf).unwrap();
+        assert_eq!(&buf[..n], b"Boot00AF");
+        assert_eq!(option_name(PREFIX_DRIVER, 1, &mut buf[..9]), None);
+
+        assert_eq!(parse_option_name(PREFIX_BOOT, "Boot00AF"), Some(0x00af));
+        assert_eq!(parse_option_name(PREFIX_BOOT, "Boot00af"), None);
+        assert_eq!(parse_option_name(PREFIX_BOOT, "Boot001"), None);
+        assert_eq!(parse_option_name(PREFIX_BOOT, NAME_BOOT_ORDER), None);
+        assert_eq!(parse_option_name(PREFIX_SYSPREP, "SysPrepFFFF"), Some(0xffff));
+    }
+}
@@ -0,0 +1,1108 @@
+//! Universal Serial Bus Descriptors
+//!
+//! USB devices describe themselves via descriptors, fetched by the host
+//! with `REQ_GET_DESCRIPTOR` control requests (see `SetupPacket`). Every
+//! descriptor starts with its length and type (`DescriptorHeader`). The
+//! device descriptor identifies the device and the number of its
+//! configurations. Each configuration is fetched as a single blob starting
+//! with the configuration descriptor, followed by interface association,
+//! interface, endpoint, and class-specific descriptors in order (see
+//! `Descriptors`).
+//!
+//! Strings are referenced by index and fetched as string descriptors,
+//! carrying UTF-16LE text without a terminator. String descriptor 0 instead
+//! carries the list of supported 16-bit language IDs. USB 2.1 and later
+//! devices further provide the binary device object store (BOS), a blob of
+//! device capability descriptors.
+//!
+//! All multi-byte integers are encoded as little-endian. Since descriptors
+//! are placed consecutively without padding, the structures are packed.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::int;
+use crate::specs::utf16;
+
+pub const DT_DEVICE: u8 = 0x01;
+pub const DT_CONFIG: u8 = 0x02;
+pub const DT_STRING: u8 = 0x03;
+pub const DT_INTERFACE: u8 = 0x04;
+pub const DT_ENDPOINT: u8 = 0x05;
+pub const DT_DEVICE_QUALIFIER: u8 = 0x06;
+pub const DT_OTHER_SPEED_CONFIG: u8 = 0x07;
+pub const DT_INTERFACE_POWER: u8 = 0x08;
+pub const DT_OTG: u8 = 0x09;
+pub const DT_DEBUG: u8 = 0x0a;
+pub const DT_INTERFACE_ASSOCIATION: u8 = 0x0b;
+pub const DT_BOS: u8 = 0x0f;
+pub const DT_DEVICE_CAPABILITY: u8 = 0x10;
+pub const DT_HID: u8 = 0x21;
+pub const DT_REPORT: u8 = 0x22;
+pub const DT_PHYSICAL: u8 = 0x23;
+pub const DT_CS_INTERFACE: u8 = 0x24;
+pub const DT_CS_ENDPOINT: u8 = 0x25;
+pub const DT_HUB: u8 = 0x29;
+pub const DT_SS_HUB: u8 = 0x2a;
+pub const DT_SS_ENDPOINT_COMP: u8 = 0x30;
+pub const DT_SSP_ISOC_ENDPOINT_COMP: u8 = 0x31;
+
+pub const DT_DEVICE_SIZE: usize = 18;
+pub const DT_CONFIG_SIZE: usize = 9;
+pub const DT_INTERFACE_SIZE: usize = 9;
+pub const DT_ENDPOINT_SIZE: usize = 7;
+pub const DT_ENDPOINT_AUDIO_SIZE: usize = 9;
+pub const DT_INTERFACE_ASSOCIATION_SIZE: usize = 8;
+pub const DT_BOS_SIZE: usize = 5;
+
+/// Maximum Descriptor Size
+///
+/// The maximum size of a single descriptor, limited by its 8-bit length.
+pub const DT_MAX_SIZE: usize = 255;
+
+pub const BCD_USB_1_1: u16 = 0x0110;
+pub const BCD_USB_2_0: u16 = 0x0200;
+pub const BCD_USB_2_1: u16 = 0x0210;
+pub const BCD_USB_3_0: u16 = 0x0300;
+pub const BCD_USB_3_1: u16 = 0x0310;
+pub const BCD_USB_3_2: u16 = 0x0320;
+
+pub const CLASS_PER_INTERFACE: u8 = 0x00;
+pub const CLASS_AUDIO: u8 = 0x01;
+pub const CLASS_COMM: u8 = 0x02;
+pub const CLASS_HID: u8 = 0x03;
+pub const CLASS_PHYSICAL: u8 = 0x05;
+pub const CLASS_STILL_IMAGE: u8 = 0x06;
+pub const CLASS_PRINTER: u8 = 0x07;
+pub const CLASS_MASS_STORAGE: u8 = 0x08;
+pub const CLASS_HUB: u8 = 0x09;
+pub const CLASS_CDC_DATA: u8 = 0x0a;
+pub const CLASS_CSCID: u8 = 0x0b;
+pub const CLASS_CONTENT_SEC: u8 = 0x0d;
+pub const CLASS_VIDEO: u8 = 0x0e;
+pub const CLASS_PERSONAL_HEALTHCARE: u8 = 0x0f;
+pub const CLASS_AUDIO_VIDEO: u8 = 0x10;
+pub const CLASS_BILLBOARD: u8 = 0x11;
+pub const CLASS_TYPE_C_BRIDGE: u8 = 0x12;
+pub const CLASS_DIAGNOSTIC: u8 = 0xdc;
+pub const CLASS_WIRELESS_CONTROLLER: u8 = 0xe0;
+pub const CLASS_MISC: u8 = 0xef;
+pub const CLASS_APPLICATION_SPEC: u8 = 0xfe;
+pub const CLASS_VENDOR_SPEC: u8 = 0xff;
+
+pub const MISC_SUBCLASS_COMMON: u8 = 0x02;
+pub const MISC_PROTOCOL_IAD: u8 = 0x01;
+
+pub const HID_SUBCLASS_NONE: u8 = 0x00;
+pub const HID_SUBCLASS_BOOT: u8 = 0x01;
+pub const HID_PROTOCOL_NONE: u8 = 0x00;
+pub const HID_PROTOCOL_KEYBOARD: u8 = 0x01;
+pub const HID_PROTOCOL_MOUSE: u8 = 0x02;
+
+pub const MSC_SUBCLASS_RBC: u8 = 0x01;
+pub const MSC_SUBCLASS_MMC5: u8 = 0x02;
+pub const MSC_SUBCLASS_UFI: u8 = 0x04;
+pub const MSC_SUBCLASS_SCSI: u8 = 0x06;
+pub const MSC_PROTOCOL_CBI: u8 = 0x00;
+pub const MSC_PROTOCOL_BULK_ONLY: u8 = 0x50;
+pub const MSC_PROTOCOL_UAS: u8 = 0x62;
+
+pub const CDC_SUBCLASS_ACM: u8 = 0x02;
+pub const CDC_SUBCLASS_ETHERNET: u8 = 0x06;
+pub const CDC_SUBCLASS_NCM: u8 = 0x0d;
+pub const CDC_SUBCLASS_MBIM: u8 = 0x0e;
+
+pub const HUB_PROTOCOL_FULL_SPEED: u8 = 0x00;
+pub const HUB_PROTOCOL_SINGLE_TT: u8 = 0x01;
+pub const HUB_PROTOCOL_MULTI_TT: u8 = 0x02;
+pub const HUB_PROTOCOL_SUPER_SPEED: u8 = 0x03;
+
+pub const CONFIG_ATT_ONE: u8 = 0x80;
+pub const CONFIG_ATT_SELFPOWER: u8 = 0x40;
+pub const CONFIG_ATT_WAKEUP: u8 = 0x20;
+pub const CONFIG_ATT_BATTERY: u8 = 0x10;
+
+/// Maximum Power Unit
+///
+/// The unit of the maximum power of configurations, in mA, at up to high
+/// speed. SuperSpeed devices use units of `MAX_POWER_UNIT_SS` mA.
+pub const MAX_POWER_UNIT: u32 = 2;
+pub const MAX_POWER_UNIT_SS: u32 = 8;
+
+pub const ENDPOINT_NUMBER_MASK: u8 = 0x0f;
+pub const ENDPOINT_DIR_MASK: u8 = 0x80;
+pub const ENDPOINT_DIR_OUT: u8 = 0x00;
+pub const ENDPOINT_DIR_IN: u8 = 0x80;
+
+pub const ENDPOINT_XFER_MASK: u8 = 0x03;
+pub const ENDPOINT_XFER_CONTROL: u8 = 0x00;
+pub const ENDPOINT_XFER_ISOC: u8 = 0x01;
+pub const ENDPOINT_XFER_BULK: u8 = 0x02;
+pub const ENDPOINT_XFER_INT: u8 = 0x03;
+pub const ENDPOINT_SYNC_MASK: u8 = 0x0c;
+pub const ENDPOINT_SYNC_NONE: u8 = 0x00;
+pub const ENDPOINT_SYNC_ASYNC: u8 = 0x04;
+pub const ENDPOINT_SYNC_ADAPTIVE: u8 = 0x08;
+pub const ENDPOINT_SYNC_SYNC: u8 = 0x0c;
+pub const ENDPOINT_USAGE_MASK: u8 = 0x30;
+pub const ENDPOINT_USAGE_DATA: u8 = 0x00;
+pub const ENDPOINT_USAGE_FEEDBACK: u8 = 0x10;
+pub const ENDPOINT_USAGE_IMPLICIT_FB: u8 = 0x20;
+
+pub const MAXP_SIZE_MASK: u16 = 0x07ff;
+pub const MAXP_MULT_MASK: u16 = 0x1800;
+pub const MAXP_MULT_SHIFT: u16 = 11;
+
+pub const REQTYPE_DIR_MASK: u8 = 0x80;
+pub const REQTYPE_DIR_OUT: u8 = 0x00;
+pub const REQTYPE_DIR_IN: u8 = 0x80;
+pub const REQTYPE_TYPE_MASK: u8 = 0x60;
+pub const REQTYPE_TYPE_STANDARD: u8 = 0x00;
+pub const REQTYPE_TYPE_CLASS: u8 = 0x20;
+pub const REQTYPE_TYPE_VENDOR: u8 = 0x40;
+pub const REQTYPE_RECIP_MASK: u8 = 0x1f;
+pub const REQTYPE_RECIP_DEVICE: u8 = 0x00;
+pub const REQTYPE_RECIP_INTERFACE: u8 = 0x01;
+pub const REQTYPE_RECIP_ENDPOINT: u8 = 0x02;
+pub const REQTYPE_RECIP_OTHER: u8 = 0x03;
+
+pub const REQ_GET_STATUS: u8 = 0x00;
+pub const REQ_CLEAR_FEATURE: u8 = 0x01;
+pub const REQ_SET_FEATURE: u8 = 0x03;
+pub const REQ_SET_ADDRESS: u8 = 0x05;
+pub const REQ_GET_DESCRIPTOR: u8 = 0x06;
+pub const REQ_SET_DESCRIPTOR: u8 = 0x07;
+pub const REQ_GET_CONFIGURATION: u8 = 0x08;
+pub const REQ_SET_CONFIGURATION: u8 = 0x09;
+pub const REQ_GET_INTERFACE: u8 = 0x0a;
+pub const REQ_SET_INTERFACE: u8 = 0x0b;
+pub const REQ_SYNCH_FRAME: u8 = 0x0c;
+pub const REQ_SET_SEL: u8 = 0x30;
+pub const REQ_SET_ISOCH_DELAY: u8 = 0x31;
+
+pub const FEATURE_ENDPOINT_HALT: u16 = 0x00;
+pub const FEATURE_DEVICE_REMOTE_WAKEUP: u16 = 0x01;
+pub const FEATURE_TEST_MODE: u16 = 0x02;
+
+/// US English Language ID
+///
+/// The language ID of US English, as listed by string descriptor 0 of most
+/// devices.
+pub const LANGID_EN_US: u16 = 0x0409;
+
+pub const CAP_WIRELESS_USB: u8 = 0x01;
+pub const CAP_USB2_EXTENSION: u8 = 0x02;
+pub const CAP_SUPERSPEED: u8 = 0x03;
+pub const CAP_CONTAINER_ID: u8 = 0x04;
+pub const CAP_PLATFORM: u8 = 0x05;
+pub const CAP_POWER_DELIVERY: u8 = 0x06;
+pub const CAP_BATTERY_INFO: u8 = 0x07;
+pub const CAP_SUPERSPEED_PLUS: u8 = 0x0a;
+pub const CAP_BILLBOARD: u8 = 0x0d;
+
+pub const USB2_EXT_LPM: u32 = 0x0000_0002;
+pub const USB2_EXT_BESL: u32 = 0x0000_0004;
+
+pub const SS_LTM: u8 = 0x02;
+pub const SS_SPEED_LOW: u16 = 0x0001;
+pub const SS_SPEED_FULL: u16 = 0x0002;
+pub const SS_SPEED_HIGH: u16 = 0x0004;
+pub const SS_SPEED_SUPER: u16 = 0x0008;
+
+/// Setup Packet
+///
+/// The request of a control transfer, sent in the setup stage.
+#[repr(C, packed)]
+pub struct SetupPacket {
+    /// Direction, type, and recipient of the request (`REQTYPE_*`).
+    pub request_type: u8,
+
+    /// Request code (`REQ_*` for standard requests).
+    pub request: u8,
+
+    /// Request-specific value.
+    pub value: int::u16le,
+
+    /// Request-specific index, usually an interface or endpoint.
+    pub index: int::u16le,
+
+    /// Number of bytes to transfer in the data stage.
+    pub length: int::u16le,
+}
+
+/// Descriptor Header
+///
+/// The header shared by all descriptors.
+#[repr(C, packed)]
+pub struct DescriptorHeader {
+    /// Size of the descriptor in bytes.
+    pub length: u8,
+
+    /// Descriptor type (`DT_*`).
+    pub descriptor_type: u8,
+}
+
+/// Device Descriptor (`DT_DEVICE`)
+#[repr(C, packed)]
+pub struct DeviceDescriptor {
+    /// Size of the descriptor in bytes.
+    pub length: u8,
+
+    /// Descriptor type (`DT_*`).
+    pub descriptor_type: u8,
+
+    /// USB specification release in binary-coded decimal (`BCD_USB_*`).
+    pub bcd_usb: int::u16le,
+
+    /// Class code (`CLASS_*`), or `CLASS_PER_INTERFACE`.
+    pub device_class: u8,
+
+    /// Subclass code, qualified by the class code.
+    pub device_subclass: u8,
+
+    /// Protocol code, qualified by the class and subclass codes.
+    pub device_protocol: u8,
+
+    /// Maximum packet size of endpoint 0, or its exponent for SuperSpeed.
+    pub max_packet_size0: u8,
+
+    /// Vendor ID.
+    pub vendor_id: int::u16le,
+
+    /// Product ID.
+    pub product_id: int::u16le,
+
+    /// Device release number in binary-coded decimal.
+    pub bcd_device: int::u16le,
+
+    /// Index of the manufacturer string, or 0.
+    pub manufacturer: u8,
+
+    /// Index of the product string, or 0.
+    pub product: u8,
+
+    /// Index of the serial number string, or 0.
+    pub serial_number: u8,
+
+    /// Number of configurations.
+    pub num_configurations: u8,
+}
+
+/// Device Qualifier Descriptor (`DT_DEVICE_QUALIFIER`)
+///
+/// The device information of a high-speed capable device that would change
+/// when operating at the other speed.
+#[repr(C, packed)]
+pub struct DeviceQualifierDescriptor {
+    /// Size of the descriptor in bytes.
+    pub length: u8,
+
+    /// Descriptor type (`DT_*`).
+    pub descriptor_type: u8,
+
+    /// USB specification release in binary-coded decimal (`BCD_USB_*`).
+    pub bcd_usb: int::u16le,
+
+    /// Class code (`CLASS_*`).
+    pub device_class: u8,
+
+    /// Subclass code.
+    pub device_subclass: u8,
+
+    /// Protocol code.
+    pub device_protocol: u8,
+
+    /// Maximum packet size of endpoint 0 at the other speed.
+    pub max_packet_size0: u8,
+
+    /// Number of configurations at the other speed.
+    pub num_configurations: u8,
+
+    /// Reserved.
+    pub reserved: u8,
+}
+
+/// Configuration Descriptor (`DT_CONFIG`)
+///
+/// The first descriptor of a configuration, followed by the interface,
+/// endpoint, and class-specific descriptors of the configuration, up to
+/// `total_length` bytes in total (see `Descriptors`).
+#[repr(C, packed)]
+pub struct ConfigurationDescriptor {
+    /// Size of the descriptor in bytes.
+    pub length: u8,
+
+    /// Descriptor type (`DT_*`).
+    pub descriptor_type: u8,
+
+    /// Size of all descriptors of the configuration in bytes.
+    pub total_length: int::u16le,
+
+    /// Number of interfaces.
+    pub num_interfaces: u8,
+
+    /// Value selecting the configuration via `REQ_SET_CONFIGURATION`.
+    pub configuration_value: u8,
+
+    /// Index of the configuration string, or 0.
+    pub configuration: u8,
+
+    /// Configuration attributes (`CONFIG_ATT_*`).
+    pub attributes: u8,
+
+    /// Maximum power consumption in units of `MAX_POWER_UNIT` mA.
+    pub max_power: u8,
+}
+
+/// Interface Association Descriptor (`DT_INTERFACE_ASSOCIATION`)
+///
+/// Groups consecutive interfaces into a single function.
+#[repr(C, packed)]
+pub struct InterfaceAssociationDescriptor {
+    /// Size of the descriptor in bytes.
+    pub length: u8,
+
+    /// Descriptor type (`DT_*`).
+    pub descriptor_type: u8,
+
+    /// Number of the first interface of the function.
+    pub first_interface: u8,
+
+    /// Number of consecutive interfaces of the function.
+    pub interface_count: u8,
+
+    /// Class code of the function (`CLASS_*`).
+    pub function_class: u8,
+
+    /// Subclass code of the function.
+    pub function_subclass: u8,
+
+    /// Protocol code of the function.
+    pub function_protocol: u8,
+
+    /// Index of the function string, or 0.
+    pub function: u8,
+}
+
+/// Interface Descriptor (`DT_INTERFACE`)
+///
+/// Describes an alternate setting of an interface, followed by the
+/// class-specific and endpoint descriptors of the setting.
+#[repr(C, packed)]
+pub struct InterfaceDescriptor {
+    /// Size of the descriptor in bytes.
+    pub length: u8,
+
+    /// Descriptor type (`DT_*`).
+    pub descriptor_type: u8,
+
+    /// Number of the interface.
+    pub interface_number: u8,
+
+    /// Value selecting the setting via `REQ_SET_INTERFACE`.
+    pub alternate_setting: u8,
+
+    /// Number of endpoints, excluding endpoint 0.
+    pub num_endpoints: u8,
+
+    /// Class code (`CLASS_*`).
+    pub interface_class: u8,
+
+    /// Subclass code, qualified by the class code.
+    pub interface_subclass: u8,
+
+    /// Protocol code, qualified by the class and subclass codes.
+    pub interface_protocol: u8,
+
+    /// Index of the interface string, or 0.
+    pub interface: u8,
+}
+
+/// Endpoint Descriptor (`DT_ENDPOINT`)
+///
+/// Audio class endpoints extend this descriptor by 2 bytes (see
+/// `DT_ENDPOINT_AUDIO_SIZE`).
+#[repr(C, packed)]
+pub struct EndpointDescriptor {
+    /// Size of the descriptor in bytes.
+    pub length: u8,
+
+    /// Descriptor type (`DT_*`).
+    pub descriptor_type: u8,
+
+    /// Endpoint number and direction (`ENDPOINT_*`).
+    pub endpoint_address: u8,
+
+    /// Transfer type, synchronization, and usage (`ENDPOINT_XFER_*`,
+    /// `ENDPOINT_SYNC_*`, `ENDPOINT_USAGE_*`).
+    pub attributes: u8,
+
+    /// Maximum packet size and additional transactions (`MAXP_*`).
+    pub max_packet_size: int::u16le,
+
+    /// Polling interval of interrupt and isochronous endpoints.
+    pub interval: u8,
+}
+
+/// SuperSpeed Endpoint Companion Descriptor (`DT_SS_ENDPOINT_COMP`)
+#[repr(C, packed)]
+pub struct SsEndpointCompanionDescriptor {
+    /// Size of the descriptor in bytes.
+    pub length: u8,
+
+    /// Descriptor type (`DT_*`).
+    pub descriptor_type: u8,
+
+    /// Maximum number of additional packets per burst.
+    pub max_burst: u8,
+
+    /// Maximum streams of bulk endpoints, or the multiplier of isochronous
+    /// endpoints.
+    pub attributes: u8,
+
+    /// Bytes transferred per service interval of periodic endpoints.
+    pub bytes_per_interval: int::u16le,
+}
+
+/// Binary Device Object Store Descriptor (`DT_BOS`)
+///
+/// The root of the BOS, followed by `num_device_caps` device capability
+/// descriptors, up to `total_length` bytes in total.
+#[repr(C, packed)]
+pub struct BosDescriptor {
+    /// Size of the descriptor in bytes.
+    pub length: u8,
+
+    /// Descriptor type (`DT_*`).
+    pub descriptor_type: u8,
+
+    /// Size of the BOS in bytes.
+    pub total_length: int::u16le,
+
+    /// Number of device capability descriptors.
+    pub num_device_caps: u8,
+}
+
+/// Device Capability Header (`DT_DEVICE_CAPABILITY`)
+///
+/// The header of all device capability descriptors.
+#[repr(C, packed)]
+pub struct DeviceCapabilityHeader {
+    /// Size of the descriptor in bytes.
+    pub length: u8,
+
+    /// Descriptor type (`DT_*`).
+    pub descriptor_type: u8,
+
+    /// Device capability type (`CAP_*`).
+    pub dev_capability_type: u8,
+}
+
+/// USB 2.0 Extension Capability (`CAP_USB2_EXTENSION`)
+#[repr(C, packed)]
+pub struct Usb2ExtensionCapability {
+    /// Size of the descriptor in bytes.
+    pub length: u8,
+
+    /// Descriptor type (`DT_*`).
+    pub descriptor_type: u8,
+
+    /// Device capability type (`CAP_*`).
+    pub dev_capability_type: u8,
+
+    /// Supported features (`USB2_EXT_*`).
+    pub attributes: int::u32le,
+}
+
+/// SuperSpeed Capability (`CAP_SUPERSPEED`)
+#[repr(C, packed)]
+pub struct SuperSpeedCapability {
+    /// Size of the descriptor in bytes.
+    pub length: u8,
+
+    /// Descriptor type (`DT_*`).
+    pub descriptor_type: u8,
+
+    /// Device capability type (`CAP_*`).
+    pub dev_capability_type: u8,
+
+    /// Supported features (`SS_LTM`).
+    pub attributes: u8,
+
+    /// Supported speeds (`SS_SPEED_*`).
+    pub speeds_supported: int::u16le,
+
+    /// Lowest speed with full functionality (`SS_SPEED_*` bit index).
+    pub functionality_support: u8,
+
+    /// U1 device exit latency in microseconds.
+    pub u1_dev_exit_lat: u8,
+
+    /// U2 device exit latency in microseconds.
+    pub u2_dev_exit_lat: int::u16le,
+}
+
+/// Container ID Capability (`CAP_CONTAINER_ID`)
+#[repr(C, packed)]
+pub struct ContainerIdCapability {
+    /// Size of the descriptor in bytes.
+    pub length: u8,
+
+    /// Descriptor type (`DT_*`).
+    pub descriptor_type: u8,
+
+    /// Device capability type (`CAP_*`).
+    pub dev_capability_type: u8,
+
+    /// Reserved.
+    pub reserved: u8,
+
+    /// UUID identifying the device across all its bus instances.
+    pub container_id: [u8; 16],
+}
+
+/// Platform Capability (`CAP_PLATFORM`)
+///
+/// A platform-specific capability identified by a UUID, followed by
+/// capability data.
+#[repr(C, packed)]
+pub struct PlatformCapability {
+    /// Size of the descriptor in bytes.
+    pub length: u8,
+
+    /// Descriptor type (`DT_*`).
+    pub descriptor_type: u8,
+
+    /// Device capability type (`CAP_*`).
+    pub dev_capability_type: u8,
+
+    /// Reserved.
+    pub reserved: u8,
+
+    /// UUID identifying the capability.
+    pub platform_capability_uuid: [u8; 16],
+}
+
+/// Descriptor Reference
+///
+/// A reference to a descriptor in a blob of consecutive descriptors.
+#[derive(Clone, Copy, Debug)]
+pub struct Descriptor<'data> {
+    /// Descriptor type (`DT_*`).
+    pub descriptor_type: u8,
+
+    /// Descriptor data, including the header.
+    pub data: &'data [u8],
+}
+
+/// Descriptor Iterator
+///
+/// An iterator over a blob of consecutive descriptors, like a configuration
+/// or the BOS, yielding a `Descriptor` for every descriptor. Iteration
+/// stops at the first descriptor with an invalid length.
+#[derive(Clone, Debug)]
+pub struct Descriptors<'data> {
+    data: &'data [u8],
+}
+
+impl SetupPacket {
+    /// Import a setup packet from a byte slice
+    ///
+    /// Create a new setup packet from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 8]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 8], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl DescriptorHeader {
+    /// Import a descriptor header from a byte slice
+    ///
+    /// Create a new descriptor header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 2]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 2], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl DeviceDescriptor {
+    /// Import a device descriptor from a byte slice
+    ///
+    /// Create a new device descriptor from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 18]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 18], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl DeviceQualifierDescriptor {
+    /// Import a device qualifier descriptor from a byte slice
+    ///
+    /// Create a new device qualifier descriptor from data copied from a byte
+    /// slice. No byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 10]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 10], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl ConfigurationDescriptor {
+    /// Import a configuration descriptor from a byte slice
+    ///
+    /// Create a new configuration descriptor from data copied from a byte
+    /// slice. No byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 9]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 9], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl InterfaceAssociationDescriptor {
+    /// Import an interface association descriptor from a byte slice
+    ///
+    /// Create a new interface association descriptor from data copied from a byte
+    /// slice. No byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 8]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 8], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl InterfaceDescriptor {
+    /// Import an interface descriptor from a byte slice
+    ///
+    /// Create a new interface descriptor from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 9]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 9], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl EndpointDescriptor {
+    /// Import an endpoint descriptor from a byte slice
+    ///
+    /// Create a new endpoint descriptor from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 7]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 7], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Return endpoint number
+    pub fn number(&self) -> u8 {
+        self.endpoint_address & ENDPOINT_NUMBER_MASK
+    }
+
+    /// Check for an IN endpoint
+    pub fn is_in(&self) -> bool {
+        (self.endpoint_address & ENDPOINT_DIR_MASK) == ENDPOINT_DIR_IN
+    }
+
+    /// Return transfer type
+    ///
+    /// Return the transfer type of the endpoint (`ENDPOINT_XFER_*`).
+    pub fn transfer_type(&self) -> u8 {
+        self.attributes & ENDPOINT_XFER_MASK
+    }
+
+    /// Return maximum packet size
+    ///
+    /// Return the maximum packet size, without the number of additional
+    /// transactions per microframe.
+    pub fn max_packet(&self) -> u16 {
+        int::ForeignEndian::to_native(self.max_packet_size) & MAXP_SIZE_MASK
+    }
+
+    /// Return transactions per microframe
+    ///
+    /// Return the number of transactions per microframe of high-speed
+    /// periodic endpoints, which is in the range 1 to 3.
+    pub fn transactions(&self) -> u8 {
+        let v = int::ForeignEndian::to_native(self.max_packet_size);
+        (((v & MAXP_MULT_MASK) >> MAXP_MULT_SHIFT) + 1) as u8
+    }
+}
+
+impl SsEndpointCompanionDescriptor {
+    /// Import an endpoint companion descriptor from a byte slice
+    ///
+    /// Create a new endpoint companion descriptor from data copied from a byte
+    /// slice. No byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 6]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 6], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl BosDescriptor {
+    /// Import a BOS descriptor from a byte slice
+    ///
+    /// Create a new BOS descriptor from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 5]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 5], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl DeviceCapabilityHeader {
+    /// Import a device capability header from a byte slice
+    ///
+    /// Create a new device capability header from data copied from a byte
+    /// slice. No byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 3]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 3], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl Usb2ExtensionCapability {
+    /// Import a USB 2.0 extension capability from a byte slice
+    ///
+    /// Create a new USB 2.0 extension capability from data copied from a byte
+    /// slice. No byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 7]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 7], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl SuperSpeedCapability {
+    /// Import an SuperSpeed capability from a byte slice
+    ///
+    /// Create a new SuperSpeed capability from data copied from a byte
+    /// slice. No byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 10]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 10], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl ContainerIdCapability {
+    /// Import a container ID capability from a byte slice
+    ///
+    /// Create a new container ID capability from data copied from a byte
+    /// slice. No byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 20]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 20], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl PlatformCapability {
+    /// Import a platform capability from a byte slice
+    ///
+    /// Create a new platform capability from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 20]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 20], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl<'data> Descriptors<'data> {
+    /// Create descriptor iterator
+    ///
+    /// Create a new iterator over the descriptors in `data`.
+    pub fn new(data: &'data [u8]) -> Self {
+        Self { data }
+    }
+
+    /// Return remaining data
+    ///
+    /// Return the data that has not been consumed by the iterator.
+    pub fn remainder(&self) -> &'data [u8] {
+        self.data
+    }
+}
+
+impl<'data> Iterator for Descriptors<'data> {
+    type Item = Descriptor<'data>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let length = *self.data.first()? as usize;
+        if length < size_of::<DescriptorHeader>() || length > self.data.len() {
+            return None;
+        }
+
+        let (data, rest) = self.data.split_at(length);
+        self.data = rest;
+        Some(Descriptor {
+            descriptor_type: data[1],
+            data,
+        })
+    }
+}
+
+/// Decode a string descriptor
+///
+/// Decode the UTF-16LE text of the string descriptor `data` to UTF-8.
+/// Return the number of bytes written, or `None` if `data` is not a valid
+/// string descriptor, the text is rejected, or the buffer is too small.
+pub fn decode_string(data: &[u8], policy: utf16::Policy, out: &mut [u8]) -> Option<usize> {
+    let length = *data.first()? as usize;
+    if length < 2 || *data.get(1)? != DT_STRING {
+        return None;
+    }
+
+    utf16::decode_le(data.get(2..length)?, policy, out)
+}
+
+/// Encode a string descriptor
+///
+/// Encode `text` as string descriptor into `out`. Return the number of bytes
+/// written, or `None` if the text exceeds `DT_MAX_SIZE`, or the buffer is
+/// too small.
+pub fn encode_string(text: &str, out: &mut [u8]) -> Option<usize> {
+    let n = utf16::encode_le(text, out.get_mut(2..DT_MAX_SIZE.min(out.len()))?)?;
+    out[0] = (n + 2) as u8;
+    out[1] = DT_STRING;
+    Some(n + 2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::specs::int::ForeignEndian;
+
+    // Device descriptor of a full-speed HID keyboard.
+    const DEVICE: [u8; 18] = [
+        0x12, 0x01, 0x00, 0x02, 0x00, 0x00, 0x00, 0x08,
+        0x6d, 0x04, 0x1c, 0xc3, 0x00, 0x49, 0x01, 0x02,
+        0x00, 0x01,
+    ];
+
+    // Configuration of the keyboard with a single boot interface, a HID
+    // class descriptor, and an interrupt IN endpoint.
+    const CONFIG: [u8; 34] = [
+        0x09, 0x02, 0x22, 0x00, 0x01, 0x01, 0x00, 0xa0,
+        0x32, 0x09, 0x04, 0x00, 0x00, 0x01, 0x03, 0x01,
+        0x01, 0x00, 0x09, 0x21, 0x11, 0x01, 0x00, 0x01,
+        0x22, 0x41, 0x00, 0x07, 0x05, 0x81, 0x03, 0x08,
+        0x00, 0x0a,
+    ];
+
+    // BOS with a USB 2.0 extension and a SuperSpeed capability.
+    const BOS: [u8; 22] = [
+        0x05, 0x0f, 0x16, 0x00, 0x02, 0x07, 0x10, 0x02,
+        0x06, 0x00, 0x00, 0x00, 0x0a, 0x10, 0x03, 0x00,
+        0x0e, 0x00, 0x01, 0x0a, 0xff, 0x07,
+    ];
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<SetupPacket>(), 8);
+        assert_eq!(size_of::<DescriptorHeader>(), 2);
+        assert_eq!(size_of::<DeviceDescriptor>(), DT_DEVICE_SIZE);
+        assert_eq!(size_of::<DeviceQualifierDescriptor>(), 10);
+        assert_eq!(size_of::<ConfigurationDescriptor>(), DT_CONFIG_SIZE);
+        assert_eq!(size_of::<InterfaceAssociationDescriptor>(), DT_INTERFACE_ASSOCIATION_SIZE);
+        assert_eq!(size_of::<InterfaceDescriptor>(), DT_INTERFACE_SIZE);
+        assert_eq!(size_of::<EndpointDescriptor>(), DT_ENDPOINT_SIZE);
+        assert_eq!(size_of::<SsEndpointCompanionDescriptor>(), 6);
+        assert_eq!(size_of::<BosDescriptor>(), DT_BOS_SIZE);
+        assert_eq!(size_of::<DeviceCapabilityHeader>(), 3);
+        assert_eq!(size_of::<Usb2ExtensionCapability>(), 7);
+        assert_eq!(size_of::<SuperSpeedCapability>(), 10);
+        assert_eq!(size_of::<ContainerIdCapability>(), 20);
+        assert_eq!(size_of::<PlatformCapability>(), 20);
+    }
+
+    // Import the device descriptor and the setup packet fetching it.
+    #[test]
+    fn verify_device() {
+        let d = DeviceDescriptor::from_bytes(&DEVICE);
+        assert_eq!(d.descriptor_type, DT_DEVICE);
+        assert_eq!(d.bcd_usb.to_native(), BCD_USB_2_0);
+        assert_eq!(d.device_class, CLASS_PER_INTERFACE);
+        assert_eq!(d.max_packet_size0, 8);
+        assert_eq!(d.vendor_id.to_native(), 0x046d);
+        assert_eq!(d.product_id.to_native(), 0xc31c);
+        assert_eq!((d.manufacturer, d.product, d.serial_number), (1, 2, 0));
+        assert_eq!(d.num_configurations, 1);
+
+        let s = SetupPacket::from_bytes(&[0x80, 0x06, 0x00, 0x01, 0x00, 0x00, 0x12, 0x00]);
+        assert_eq!(s.request_type, REQTYPE_DIR_IN | REQTYPE_TYPE_STANDARD);
+        assert_eq!(s.request, REQ_GET_DESCRIPTOR);
+        assert_eq!(s.value.to_native() >> 8, DT_DEVICE as u16);
+        assert_eq!(s.length.to_native() as usize, DT_DEVICE_SIZE);
+    }
+
+    // Walk the configuration and the BOS.
+    #[test]
+    fn verify_descriptors() {
+        let mut d = Descriptors::new(&CONFIG);
+
+        let v = d.next().unwrap();
+        assert_eq!(v.descriptor_type, DT_CONFIG);
+        let c = ConfigurationDescriptor::from_bytes(v.data.try_into().unwrap());
+        assert_eq!(c.total_length.to_native() as usize, CONFIG.len());
+        assert_eq!(c.attributes, CONFIG_ATT_ONE | CONFIG_ATT_WAKEUP);
+        assert_eq!(c.max_power as u32 * MAX_POWER_UNIT, 100);
+
+        let v = d.next().unwrap();
+        let i = InterfaceDescriptor::from_bytes(v.data.try_into().unwrap());
+        assert_eq!(i.interface_class, CLASS_HID);
+        assert_eq!(i.interface_subclass, HID_SUBCLASS_BOOT);
+        assert_eq!(i.interface_protocol, HID_PROTOCOL_KEYBOARD);
+        assert_eq!(i.num_endpoints, 1);
+
+        assert_eq!(d.next().unwrap().descriptor_type, DT_HID);
+
+        let v = d.next().unwrap();
+        let e = EndpointDescriptor::from_bytes(v.data.try_into().unwrap());
+        assert_eq!(e.number(), 1);
+        assert!(e.is_in());
+        assert_eq!(e.transfer_type(), ENDPOINT_XFER_INT);
+        assert_eq!((e.max_packet(), e.transactions()), (8, 1));
+        assert_eq!(e.interval, 10);
+
+        assert!(d.next().is_none());
+        assert!(d.remainder().is_empty());
+
+        let b = BosDescriptor::from_bytes(BOS[..5].try_into().unwrap());
+        assert_eq!(b.total_length.to_native() as usize, BOS.len());
+        let caps: std::vec::Vec<u8> = Descriptors::new(&BOS[5..]).map(|v| v.data[2]).collect();
+        assert_eq!(caps, [CAP_USB2_EXTENSION, CAP_SUPERSPEED]);
+        let s = SuperSpeedCapability::from_bytes(BOS[12..].try_into().unwrap());
+        assert_eq!(s.speeds_supported.to_native(), 0x000e);
+        assert_eq!(s.u2_dev_exit_lat.to_native(), 0x07ff);
+
+        // A descriptor exceeding the data stops the iteration.
+        assert_eq!(Descriptors::new(&CONFIG[..30]).count(), 3);
+        assert_eq!(Descriptors::new(&[0x00, 0x04]).count(), 0);
+    }
+
+    // Encode and decode string descriptors.
+    #[test]
+    fn verify_strings() {
+        let mut buf = [0u8; 64];
+        let n = encode_string("Keyboard", &mut buf).unwrap();
+        assert_eq!(n, 18);
+        assert_eq!(&buf[..6], &[0x12, 0x03, 0x4b, 0x00, 0x65, 0x00]);
+
+        let mut out = [0u8; 16];
+        let m = decode_string(&buf[..n], utf16::Policy::Reject, &mut out).unwrap();
+        assert_eq!(&out[..m], b"Keyboard");
+        assert!(decode_string(&CONFIG[..9], utf16::Policy::Reject, &mut out).is_none());
+        assert!(encode_string("Keyboard", &mut buf[..8]).is_none());
+
+        let mut big = [0u8; 512];
+        let long = "x".repeat(127);
+        assert!(encode_string(&long[..126], &mut big).is_some());
+        assert!(encode_string(&long, &mut big).is_none());
+    }
+}
@@ -0,0 +1,627 @@
+//! USB/IP
+//!
+//! USB/IP exports USB devices over TCP (see `TCP_PORT`). A connection starts
+//! with operations, each starting with an `OpHeader`. Clients list the
+//! exported devices with `OP_REQ_DEVLIST`, answered by `OP_REP_DEVLIST` with
+//! a 4-byte device count, followed by a `Device` and its `Interface`
+//! descriptions for each device. Clients attach a device with
+//! `OP_REQ_IMPORT` carrying the 32-byte bus ID, answered by `OP_REP_IMPORT`
+//! with the `Device`, if successful.
+//!
+//! Once imported, the connection carries URB commands and replies, each
+//! starting with a common `Header` that selects one of `CmdSubmit`,
+//! `RetSubmit`, `CmdUnlink`, and `RetUnlink`, all of the same size. Submits
+//! are followed by the transfer data of OUT transfers, and replies by the
+//! transfer data of IN transfers, followed by `IsoPacket` descriptors for
+//! isochronous transfers (see `CmdSubmit::payload_size()`).
+//!
+//! All multi-byte integers are encoded as big-endian, except for the setup
+//! packet of control transfers, which is passed unchanged from USB, and
+//! thus little-endian.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::int;
+
+pub const TCP_PORT: u16 = 3240;
+
+pub const OP_HEADER_SIZE: usize = 8;
+pub const HEADER_SIZE: usize = 48;
+pub const DEVICE_SIZE: usize = 312;
+pub const INTERFACE_SIZE: usize = 4;
+pub const ISO_PACKET_SIZE: usize = 16;
+pub const PATH_SIZE: usize = 256;
+pub const BUSID_SIZE: usize = 32;
+
+pub const VERSION: u16 = 0x0111;
+
+pub const OP_REQUEST: u16 = 0x8000;
+pub const OP_REQ_IMPORT: u16 = 0x8003;
+pub const OP_REP_IMPORT: u16 = 0x0003;
+pub const OP_REQ_DEVLIST: u16 = 0x8005;
+pub const OP_REP_DEVLIST: u16 = 0x0005;
+
+pub const ST_OK: u32 = 0x00;
+pub const ST_NA: u32 = 0x01;
+pub const ST_DEV_BUSY: u32 = 0x02;
+pub const ST_DEV_ERR: u32 = 0x03;
+pub const ST_NODEV: u32 = 0x04;
+pub const ST_ERROR: u32 = 0x05;
+
+pub const CMD_SUBMIT: u32 = 0x0001;
+pub const CMD_UNLINK: u32 = 0x0002;
+pub const RET_SUBMIT: u32 = 0x0003;
+pub const RET_UNLINK: u32 = 0x0004;
+
+pub const DIR_OUT: u32 = 0;
+pub const DIR_IN: u32 = 1;
+
+pub const SPEED_UNKNOWN: u32 = 0;
+pub const SPEED_LOW: u32 = 1;
+pub const SPEED_FULL: u32 = 2;
+pub const SPEED_HIGH: u32 = 3;
+pub const SPEED_WIRELESS: u32 = 4;
+pub const SPEED_SUPER: u32 = 5;
+pub const SPEED_SUPER_PLUS: u32 = 6;
+
+pub const URB_SHORT_NOT_OK: u32 = 0x0001;
+pub const URB_ISO_ASAP: u32 = 0x0002;
+pub const URB_NO_TRANSFER_DMA_MAP: u32 = 0x0004;
+pub const URB_ZERO_PACKET: u32 = 0x0040;
+pub const URB_NO_INTERRUPT: u32 = 0x0080;
+pub const URB_FREE_BUFFER: u32 = 0x0100;
+pub const URB_DIR_IN: u32 = 0x0200;
+
+/// Number of Packets of Non-Isochronous Transfers
+pub const NON_ISO_PACKETS: u32 = 0xffffffff;
+
+/// Operation Header
+#[repr(C)]
+pub struct OpHeader {
+    /// Protocol version, `VERSION`.
+    pub version: int::u16be,
+
+    /// Operation code (`OP_*`).
+    pub code: int::u16be,
+
+    /// Status (`ST_*`), 0 in requests.
+    pub status: int::u32be,
+}
+
+/// Exported Device Description
+#[repr(C)]
+pub struct Device {
+    /// Path of the device in sysfs, 0-terminated.
+    pub path: [u8; 256],
+
+    /// Bus ID of the device, 0-terminated.
+    pub busid: [u8; 32],
+
+    /// Bus number.
+    pub busnum: int::u32be,
+
+    /// Device number on the bus.
+    pub devnum: int::u32be,
+
+    /// Speed of the device (`SPEED_*`).
+    pub speed: int::u32be,
+
+    /// Vendor ID.
+    pub id_vendor: int::u16be,
+
+    /// Product ID.
+    pub id_product: int::u16be,
+
+    /// Device release number in binary-coded decimal.
+    pub bcd_device: int::u16be,
+
+    /// Device class.
+    pub device_class: u8,
+
+    /// Device subclass.
+    pub device_subclass: u8,
+
+    /// Device protocol.
+    pub device_protocol: u8,
+
+    /// Current configuration.
+    pub configuration_value: u8,
+
+    /// Number of configurations.
+    pub num_configurations: u8,
+
+    /// Number of interfaces, each described in `OP_REP_DEVLIST`.
+    pub num_interfaces: u8,
+}
+
+/// Interface Description
+#[repr(C)]
+pub struct Interface {
+    /// Interface class.
+    pub interface_class: u8,
+
+    /// Interface subclass.
+    pub interface_subclass: u8,
+
+    /// Interface protocol.
+    pub interface_protocol: u8,
+
+    /// Padding, must be 0.
+    pub padding: u8,
+}
+
+/// Common URB Header
+#[repr(C)]
+pub struct Header {
+    /// Command (`CMD_*` or `RET_*`).
+    pub command: int::u32be,
+
+    /// Sequence number, copied from the command to its reply.
+    pub seqnum: int::u32be,
+
+    /// Device ID (see `devid()`), 0 in replies.
+    pub devid: int::u32be,
+
+    /// Direction (`DIR_*`), 0 in replies.
+    pub direction: int::u32be,
+
+    /// Endpoint number, 0 in replies.
+    pub ep: int::u32be,
+}
+
+/// URB Submit Command
+#[repr(C)]
+pub struct CmdSubmit {
+    /// Common header.
+    pub header: Header,
+
+    /// Transfer flags (`URB_*`).
+    pub transfer_flags: int::u32be,
+
+    /// Size of the transfer buffer in bytes.
+    pub transfer_buffer_length: int::u32be,
+
+    /// Start frame of isochronous transfers.
+    pub start_frame: int::i32be,
+
+    /// Number of isochronous packets, or `NON_ISO_PACKETS`.
+    pub number_of_packets: int::u32be,
+
+    /// Polling interval of interrupt and isochronous transfers.
+    pub interval: int::u32be,
+
+    /// Setup packet of control transfers, or all 0.
+    pub setup: [u8; 8],
+}
+
+/// URB Submit Reply
+#[repr(C)]
+pub struct RetSubmit {
+    /// Common header.
+    pub header: Header,
+
+    /// Status, 0 or a negative errno.
+    pub status: int::i32be,
+
+    /// Number of bytes transferred.
+    pub actual_length: int::u32be,
+
+    /// Start frame of isochronous transfers.
+    pub start_frame: int::i32be,
+
+    /// Number of isochronous packets, or `NON_ISO_PACKETS`.
+    pub number_of_packets: int::u32be,
+
+    /// Number of isochronous packets with errors.
+    pub error_count: int::u32be,
+
+    /// Padding, must be 0.
+    pub padding: [u8; 8],
+}
+
+/// URB Unlink Command
+#[repr(C)]
+pub struct CmdUnlink {
+    /// Common header.
+    pub header: Header,
+
+    /// Sequence number of the submit command to unlink.
+    pub unlink_seqnum: int::u32be,
+
+    /// Padding, must be 0.
+    pub padding: [u8; 24],
+}
+
+/// URB Unlink Reply
+#[repr(C)]
+pub struct RetUnlink {
+    /// Common header.
+    pub header: Header,
+
+    /// Status, `-ECONNRESET` if unlinked, or 0 if already completed.
+    pub status: int::i32be,
+
+    /// Padding, must be 0.
+    pub padding: [u8; 24],
+}
+
+/// Isochronous Packet Descriptor
+#[repr(C)]
+pub struct IsoPacket {
+    /// Offset of the packet in the transfer buffer.
+    pub offset: int::u32be,
+
+    /// Expected size of the packet in bytes.
+    pub length: int::u32be,
+
+    /// Number of bytes transferred.
+    pub actual_length: int::u32be,
+
+    /// Status of the packet, 0 or a negative errno.
+    pub status: int::i32be,
+}
+
+impl OpHeader {
+    /// Import a header from a byte slice
+    ///
+    /// Create a new header from data copied from a byte slice. No byte-order
+    /// conversions are applied.
+    pub fn from_bytes(data: &[u8; 8]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 8], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl Device {
+    /// Import a device from a byte slice
+    ///
+    /// Create a new device from data copied from a byte slice. No byte-order
+    /// conversions are applied.
+    pub fn from_bytes(data: &[u8; 312]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 312], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Return sysfs path
+    ///
+    /// Return the path without its 0-terminator and padding.
+    pub fn path(&self) -> &[u8] {
+        string(&self.path)
+    }
+
+    /// Return bus ID
+    ///
+    /// Return the bus ID without its 0-terminator and padding.
+    pub fn busid(&self) -> &[u8] {
+        string(&self.busid)
+    }
+}
+
+impl Interface {
+    /// Import an interface from a byte slice
+    ///
+    /// Create a new interface from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 4]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 4], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl Header {
+    /// Import a header from a byte slice
+    ///
+    /// Create a new header from data copied from a byte slice. No byte-order
+    /// conversions are applied.
+    pub fn from_bytes(data: &[u8; 20]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 20], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl CmdSubmit {
+    /// Import a command from a byte slice
+    ///
+    /// Create a new command from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 48]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 48], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Return payload size
+    ///
+    /// Return the size of the data following the command, which is the
+    /// transfer buffer of OUT transfers, followed by the isochronous packet
+    /// descriptors, if any.
+    pub fn payload_size(&self) -> usize {
+        let data = match int::ForeignEndian::to_native(self.header.direction) {
+            DIR_OUT => int::ForeignEndian::to_native(self.transfer_buffer_length),
+            _ => 0,
+        };
+        data as usize + iso_size(int::ForeignEndian::to_native(self.number_of_packets))
+    }
+}
+
+impl RetSubmit {
+    /// Import a reply from a byte slice
+    ///
+    /// Create a new reply from data copied from a byte slice. No byte-order
+    /// conversions are applied.
+    pub fn from_bytes(data: &[u8; 48]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 48], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Return payload size
+    ///
+    /// Return the size of the data following the reply, which is the
+    /// transfer buffer of IN transfers, followed by the isochronous packet
+    /// descriptors, if any. Replies do not carry the direction, so it must be
+    /// taken from the matching `CmdSubmit`.
+    pub fn payload_size(&self, direction: u32) -> usize {
+        let data = match direction {
+            DIR_IN => int::ForeignEndian::to_native(self.actual_length),
+            _ => 0,
+        };
+        data as usize + iso_size(int::ForeignEndian::to_native(self.number_of_packets))
+    }
+}
+
+impl CmdUnlink {
+    /// Import a command from a byte slice
+    ///
+    /// Create a new command from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 48]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 48], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl RetUnlink {
+    /// Import a reply from a byte slice
+    ///
+    /// Create a new reply from data copied from a byte slice. No byte-order
+    /// conversions are applied.
+    pub fn from_bytes(data: &[u8; 48]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 48], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl IsoPacket {
+    /// Import a descriptor from a byte slice
+    ///
+    /// Create a new descriptor from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 16]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 16], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+// Return the size of the isochronous packet descriptors of a transfer.
+fn iso_size(number_of_packets: u32) -> usize {
+    match number_of_packets {
+        NON_ISO_PACKETS => 0,
+        v => v as usize * ISO_PACKET_SIZE,
+    }
+}
+
+// Return a 0-terminated string field without the terminator.
+fn string(field: &[u8]) -> &[u8] {
+    let n = field.iter().position(|v| *v == 0).unwrap_or(field.len());
+    &field[..n]
+}
+
+/// Combine device ID
+///
+/// Return the device ID used in URB headers for the device with the given
+/// bus and device number.
+pub fn devid(busnum: u32, devnum: u32) -> u32 {
+    (busnum << 16) | (devnum & 0xffff)
+}
+
+/// Return size of device list entry
+///
+/// Return the size of the entry of an `OP_REP_DEVLIST` reply describing a
+/// device with the given number of interfaces.
+pub fn device_entry_size(num_interfaces: u8) -> usize {
+    DEVICE_SIZE + num_interfaces as usize * INTERFACE_SIZE
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::specs::int::ForeignEndian;
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<OpHeader>(), OP_HEADER_SIZE);
+        assert_eq!(align_of::<OpHeader>(), 4);
+        assert_eq!(size_of::<Device>(), DEVICE_SIZE);
+        assert_eq!(align_of::<Device>(), 4);
+        assert_eq!(size_of::<Interface>(), INTERFACE_SIZE);
+        assert_eq!(align_of::<Interface>(), 1);
+        assert_eq!(size_of::<Header>(), 20);
+        assert_eq!(align_of::<Header>(), 4);
+        assert_eq!(size_of::<CmdSubmit>(), HEADER_SIZE);
+        assert_eq!(align_of::<CmdSubmit>(), 4);
+        assert_eq!(size_of::<RetSubmit>(), HEADER_SIZE);
+        assert_eq!(align_of::<RetSubmit>(), 4);
+        assert_eq!(size_of::<CmdUnlink>(), HEADER_SIZE);
+        assert_eq!(align_of::<CmdUnlink>(), 4);
+        assert_eq!(size_of::<RetUnlink>(), HEADER_SIZE);
+        assert_eq!(align_of::<RetUnlink>(), 4);
+        assert_eq!(size_of::<IsoPacket>(), ISO_PACKET_SIZE);
+        assert_eq!(align_of::<IsoPacket>(), 4);
+    }
+
+    // Decode a device list reply with a single device and interface.
+    #[test]
+    fn verify_devlist() {
+        let h = OpHeader::from_bytes(&[0x01, 0x11, 0x00, 0x05, 0x00, 0x00, 0x00, 0x00]);
+        assert_eq!(h.version.to_native(), VERSION);
+        assert_eq!(h.code.to_native(), OP_REP_DEVLIST);
+        assert_eq!(h.code.to_native() | OP_REQUEST, OP_REQ_DEVLIST);
+        assert_eq!(h.status.to_native(), ST_OK);
+
+        let mut data = [0u8; 312 + 4];
+        data[..27].copy_from_slice(b"/sys/devices/pci0000:00/usb");
+        data[256..261].copy_from_slice(b"1-1.2");
+        data[288..300].copy_from_slice(&[0, 0, 0, 1, 0, 0, 0, 3, 0, 0, 0, 3]);
+        data[300..306].copy_from_slice(&[0x1d, 0x6b, 0x01, 0x04, 0x01, 0x00]);
+        data[306..312].copy_from_slice(&[0x09, 0x00, 0x01, 0x01, 0x01, 0x01]);
+        data[312..316].copy_from_slice(&[0x09, 0x00, 0x00, 0x00]);
+
+        let d = Device::from_bytes(data[..312].try_into().unwrap());
+        assert_eq!(d.path(), b"/sys/devices/pci0000:00/usb");
+        assert_eq!(d.busid(), b"1-1.2");
+        assert_eq!(devid(d.busnum.to_native(), d.devnum.to_native()), 0x0001_0003);
+        assert_eq!(d.speed.to_native(), SPEED_HIGH);
+        assert_eq!(d.id_vendor.to_native(), 0x1d6b);
+        assert_eq!(d.id_product.to_native(), 0x0104);
+        assert_eq!(d.device_class, 0x09);
+        assert_eq!(device_entry_size(d.num_interfaces), data.len());
+
+        let i = Interface::from_bytes(data[312..].try_into().unwrap());
+        assert_eq!(i.interface_class, 0x09);
+    }
+
+    // Submit of a GET_DESCRIPTOR control transfer.
+    const CMD_SUBMIT_DATA: [u8; 48] = [
+        0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x05,
+        0x00, 0x01, 0x00, 0x02, 0x00, 0x00, 0x00, 0x01,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00,
+        0x00, 0x00, 0x00, 0x12, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x80, 0x06, 0x00, 0x01, 0x00, 0x00, 0x12, 0x00,
+    ];
+
+    // Reply to the submit, with the start of the device descriptor.
+    const RET_SUBMIT_DATA: [u8; 52] = [
+        0x00, 0x00, 0x00, 0x03, 0x00, 0x00, 0x00, 0x05,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x12, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x12, 0x01, 0x00, 0x02,
+    ];
+
+    // Decode URB submit and unlink commands and replies.
+    #[test]
+    fn verify_urb() {
+        let c = CmdSubmit::from_bytes(&CMD_SUBMIT_DATA);
+        assert_eq!(c.header.command.to_native(), CMD_SUBMIT);
+        assert_eq!(c.header.seqnum.to_native(), 5);
+        assert_eq!(c.header.devid.to_native(), devid(1, 2));
+        assert_eq!(c.header.direction.to_native(), DIR_IN);
+        assert_eq!(c.header.ep.to_native(), 0);
+        assert_eq!(c.transfer_flags.to_native(), URB_DIR_IN);
+        assert_eq!(c.transfer_buffer_length.to_native(), 18);
+        assert_eq!(c.setup, [0x80, 0x06, 0x00, 0x01, 0x00, 0x00, 0x12, 0x00]);
+        assert_eq!(c.payload_size(), 0);
+
+        let r = RetSubmit::from_bytes(RET_SUBMIT_DATA[..48].try_into().unwrap());
+        assert_eq!(r.header.command.to_native(), RET_SUBMIT);
+        assert_eq!(r.header.seqnum.to_native(), 5);
+        assert_eq!(r.status.to_native(), 0);
+        assert_eq!(r.actual_length.to_native(), 18);
+        assert_eq!(r.payload_size(DIR_IN), 18);
+        assert_eq!(r.payload_size(DIR_OUT), 0);
+
+        let mut data = [0u8; 48];
+        data[3] = CMD_UNLINK as u8;
+        data[7] = 6;
+        data[23] = 5;
+        let u = CmdUnlink::from_bytes(&data);
+        assert_eq!(u.header.command.to_native(), CMD_UNLINK);
+        assert_eq!(u.unlink_seqnum.to_native(), 5);
+
+        data[3] = RET_UNLINK as u8;
+        data[20..24].copy_from_slice(&(-104i32).to_be_bytes());
+        let u = RetUnlink::from_bytes(&data);
+        assert_eq!(u.header.command.to_native(), RET_UNLINK);
+        assert_eq!(u.status.to_native(), -104);
+
+        let mut data = CMD_SUBMIT_DATA;
+        data[15] = DIR_OUT as u8;
+        data[32..36].copy_from_slice(&2u32.to_be_bytes());
+        let c = CmdSubmit::from_bytes(&data);
+        assert_eq!(c.payload_size(), 18 + 2 * ISO_PACKET_SIZE);
+
+        let p = IsoPacket::from_bytes(&[0, 0, 0, 0, 0, 0, 0, 9, 0, 0, 0, 9, 0, 0, 0, 0]);
+        assert_eq!(p.length.to_native(), 9);
+        assert_eq!(p.actual_length.to_native(), 9);
+    }
+}
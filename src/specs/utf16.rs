@@ -0,0 +1,405 @@
+//! UTF-16 Text Encoding
+//!
+//! UTF-16 encodes Unicode scalar values as one or two 16-bit code units.
+//! Values beyond the basic multilingual plane are encoded as a surrogate
+//! pair, a high surrogate followed by a low surrogate. Many formats store
+//! UTF-16 strings without validation, so unpaired surrogates are common in
+//! practice and must be handled according to a `Policy`.
+//!
+//! Strings are usually stored as arrays of little-endian code units (e.g.,
+//! PE resources, NTFS, GPT partition names, and CFBF), which can be viewed
+//! as `WideStr`. Byte streams of unknown byte order can start with a byte
+//! order mark (see `detect_bom()`).
+
+use crate::specs::int;
+
+/// Byte Order Mark
+pub const BOM: u16 = 0xfeff;
+
+/// Replacement Character
+pub const REPLACEMENT: char = '\u{fffd}';
+
+pub const SURROGATE_HIGH_MIN: u16 = 0xd800;
+pub const SURROGATE_HIGH_MAX: u16 = 0xdbff;
+pub const SURROGATE_LOW_MIN: u16 = 0xdc00;
+pub const SURROGATE_LOW_MAX: u16 = 0xdfff;
+
+pub const BOM_UTF8: [u8; 3] = [0xef, 0xbb, 0xbf];
+pub const BOM_UTF16LE: [u8; 2] = [0xff, 0xfe];
+pub const BOM_UTF16BE: [u8; 2] = [0xfe, 0xff];
+pub const BOM_UTF32LE: [u8; 4] = [0xff, 0xfe, 0x00, 0x00];
+pub const BOM_UTF32BE: [u8; 4] = [0x00, 0x00, 0xfe, 0xff];
+
+/// Unicode Encoding
+///
+/// Identifies the encoding signaled by a byte order mark.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum Encoding {
+    Utf8,
+    Utf16(int::Endianness),
+    Utf32(int::Endianness),
+}
+
+/// Unpaired Surrogate Policy
+///
+/// Selects how decoders handle surrogates that are not part of a valid
+/// surrogate pair.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum Policy {
+    /// Fail decoding
+    Reject,
+    /// Decode as `REPLACEMENT`
+    Replace,
+}
+
+/// UTF-16 Code Unit
+///
+/// Abstracts over the representations of UTF-16 code units, so strings can
+/// be used in place, regardless of their byte order.
+pub trait Utf16Char: Copy {
+    /// Create code unit from native value
+    fn from_unit(unit: u16) -> Self;
+
+    /// Return native value of the code unit
+    fn to_unit(self) -> u16;
+}
+
+impl Utf16Char for u16 {
+    fn from_unit(unit: u16) -> Self {
+        unit
+    }
+
+    fn to_unit(self) -> u16 {
+        self
+    }
+}
+
+impl Utf16Char for int::u16le {
+    fn from_unit(unit: u16) -> Self {
+        int::ForeignEndian::from_native(unit)
+    }
+
+    fn to_unit(self) -> u16 {
+        int::ForeignEndian::to_native(self)
+    }
+}
+
+impl Utf16Char for int::u16be {
+    fn from_unit(unit: u16) -> Self {
+        int::ForeignEndian::from_native(unit)
+    }
+
+    fn to_unit(self) -> u16 {
+        int::ForeignEndian::to_native(self)
+    }
+}
+
+/// UTF-16 String
+///
+/// A view of a string stored as UTF-16 code units, which are not required
+/// to be valid UTF-16.
+#[derive(Clone, Copy, Debug)]
+pub struct WideStr<'data, C> {
+    pub units: &'data [C],
+}
+
+/// UTF-16 Decoder
+///
+/// Iterates the code points of UTF-16 code units. Unpaired surrogates are
+/// yielded as `Err`.
+#[derive(Clone, Debug)]
+pub struct Chars<'data, C> {
+    data: &'data [C],
+}
+
+/// Check for high surrogate
+pub fn is_high_surrogate(unit: u16) -> bool {
+    (SURROGATE_HIGH_MIN..=SURROGATE_HIGH_MAX).contains(&unit)
+}
+
+/// Check for low surrogate
+pub fn is_low_surrogate(unit: u16) -> bool {
+    (SURROGATE_LOW_MIN..=SURROGATE_LOW_MAX).contains(&unit)
+}
+
+/// Detect byte order mark
+///
+/// Detect the byte order mark at the start of `data`. Return the signaled
+/// encoding and the size of the mark, or `None` if there is none. A UTF-32LE
+/// mark takes precedence over the UTF-16LE mark it starts with.
+pub fn detect_bom(data: &[u8]) -> Option<(Encoding, usize)> {
+    if data.starts_with(&BOM_UTF32LE) {
+        Some((Encoding::Utf32(int::Endianness::Little), BOM_UTF32LE.len()))
+    } else if data.starts_with(&BOM_UTF32BE) {
+        Some((Encoding::Utf32(int::Endianness::Big), BOM_UTF32BE.len()))
+    } else if data.starts_with(&BOM_UTF8) {
+        Some((Encoding::Utf8, BOM_UTF8.len()))
+    } else if data.starts_with(&BOM_UTF16LE) {
+        Some((Encoding::Utf16(int::Endianness::Little), BOM_UTF16LE.len()))
+    } else if data.starts_with(&BOM_UTF16BE) {
+        Some((Encoding::Utf16(int::Endianness::Big), BOM_UTF16BE.len()))
+    } else {
+        None
+    }
+}
+
+// Decode code points to UTF-8, applying the surrogate policy.
+fn decode_iter<I>(units: I, policy: Policy, out: &mut [u8]) -> Option<usize>
+where
+    I: Iterator<Item = u16>,
+{
+    let mut o = 0;
+
+    for r in core::char::decode_utf16(units) {
+        let c = match (r, policy) {
+            (Ok(c), _) => c,
+            (Err(_), Policy::Replace) => REPLACEMENT,
+            (Err(_), Policy::Reject) => return None,
+        };
+        let n = c.len_utf8();
+        c.encode_utf8(out.get_mut(o..o + n)?);
+        o += n;
+    }
+
+    Some(o)
+}
+
+// Split bytes into code units of the given byte order.
+fn units(data: &[u8], endian: int::Endianness) -> Option<impl Iterator<Item = u16> + '_> {
+    if !data.len().is_multiple_of(2) {
+        return None;
+    }
+
+    Some(data.chunks_exact(2).map(move |v| match endian {
+        int::Endianness::Big => u16::from_be_bytes([v[0], v[1]]),
+        int::Endianness::Little => u16::from_le_bytes([v[0], v[1]]),
+    }))
+}
+
+/// Decode UTF-16 code units
+///
+/// Decode the code units `data` to UTF-8. Return the number of bytes
+/// written, or `None` if an unpaired surrogate is rejected, or the buffer
+/// is too small.
+pub fn decode<C: Utf16Char>(data: &[C], policy: Policy, out: &mut [u8]) -> Option<usize> {
+    decode_iter(data.iter().map(|v| v.to_unit()), policy, out)
+}
+
+/// Decode UTF-16 bytes
+///
+/// Decode the UTF-16 byte stream `data` of the given byte order to UTF-8.
+/// Return the number of bytes written, or `None` if `data` has an odd size,
+/// an unpaired surrogate is rejected, or the buffer is too small.
+pub fn decode_bytes(
+    data: &[u8],
+    endian: int::Endianness,
+    policy: Policy,
+    out: &mut [u8],
+) -> Option<usize> {
+    decode_iter(units(data, endian)?, policy, out)
+}
+
+/// Decode UTF-16LE bytes
+pub fn decode_le(data: &[u8], policy: Policy, out: &mut [u8]) -> Option<usize> {
+    decode_bytes(data, int::Endianness::Little, policy, out)
+}
+
+/// Decode UTF-16BE bytes
+pub fn decode_be(data: &[u8], policy: Policy, out: &mut [u8]) -> Option<usize> {
+    decode_bytes(data, int::Endianness::Big, policy, out)
+}
+
+/// Encode UTF-16 code units
+///
+/// Encode the string `data` as UTF-16 code units. Return the number of
+/// code units written, or `None` if the buffer is too small.
+pub fn encode<C: Utf16Char>(data: &str, out: &mut [C]) -> Option<usize> {
+    let mut o = 0;
+
+    for u in data.encode_utf16() {
+        *out.get_mut(o)? = C::from_unit(u);
+        o += 1;
+    }
+
+    Some(o)
+}
+
+/// Encode UTF-16 bytes
+///
+/// Encode the string `data` as UTF-16 byte stream of the given byte order.
+/// Return the number of bytes written, or `None` if the buffer is too small.
+pub fn encode_bytes(data: &str, endian: int::Endianness, out: &mut [u8]) -> Option<usize> {
+    let mut o = 0;
+
+    for u in data.encode_utf16() {
+        let v = match endian {
+            int::Endianness::Big => u.to_be_bytes(),
+            int::Endianness::Little => u.to_le_bytes(),
+        };
+        out.get_mut(o..o + 2)?.copy_from_slice(&v);
+        o += 2;
+    }
+
+    Some(o)
+}
+
+/// Encode UTF-16LE bytes
+pub fn encode_le(data: &str, out: &mut [u8]) -> Option<usize> {
+    encode_bytes(data, int::Endianness::Little, out)
+}
+
+/// Encode UTF-16BE bytes
+pub fn encode_be(data: &str, out: &mut [u8]) -> Option<usize> {
+    encode_bytes(data, int::Endianness::Big, out)
+}
+
+impl<'data, C: Utf16Char> WideStr<'data, C> {
+    /// Create string view
+    ///
+    /// Create a new view of all code units in `units`.
+    pub fn new(units: &'data [C]) -> Self {
+        Self { units }
+    }
+
+    /// Create string view of NUL-terminated string
+    ///
+    /// Create a new view of the code units in `units` up to, but excluding,
+    /// the first NUL. All units are used if there is no NUL.
+    pub fn from_nul_terminated(units: &'data [C]) -> Self {
+        let n = units.iter().position(|v| v.to_unit() == 0).unwrap_or(units.len());
+        Self { units: &units[..n] }
+    }
+
+    /// Return number of code units
+    pub fn len(&self) -> usize {
+        self.units.len()
+    }
+
+    /// Check for empty string
+    pub fn is_empty(&self) -> bool {
+        self.units.is_empty()
+    }
+
+    /// Iterate code points
+    ///
+    /// Return an iterator over the code points of the string.
+    pub fn chars(&self) -> Chars<'data, C> {
+        Chars::new(self.units)
+    }
+
+    /// Decode string
+    ///
+    /// Decode the string to UTF-8. See `decode()` for details.
+    pub fn decode(&self, policy: Policy, out: &mut [u8]) -> Option<usize> {
+        decode(self.units, policy, out)
+    }
+
+    /// Compare with string
+    ///
+    /// Check whether the code units are exactly the UTF-16 encoding of `s`.
+    pub fn eq_str(&self, s: &str) -> bool {
+        self.units.iter().map(|v| v.to_unit()).eq(s.encode_utf16())
+    }
+}
+
+impl<'data, C: Utf16Char> Chars<'data, C> {
+    /// Create UTF-16 decoder
+    ///
+    /// Create a new iterator over the code points of `data`.
+    pub fn new(data: &'data [C]) -> Self {
+        Self { data }
+    }
+
+    /// Return remaining data
+    ///
+    /// Return the data that has not been consumed by the iterator.
+    pub fn remainder(&self) -> &'data [C] {
+        self.data
+    }
+}
+
+impl<'data, C: Utf16Char> Iterator for Chars<'data, C> {
+    type Item = Result<char, u16>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (first, rest) = self.data.split_first()?;
+        let high = first.to_unit();
+        self.data = rest;
+
+        if !is_high_surrogate(high) {
+            return Some(char::from_u32(high as u32).ok_or(high));
+        }
+
+        match rest.first().map(|v| v.to_unit()) {
+            Some(low) if is_low_surrogate(low) => {
+                self.data = &rest[1..];
+                let v = 0x10000
+                    + (((high - SURROGATE_HIGH_MIN) as u32) << 10)
+                    + (low - SURROGATE_LOW_MIN) as u32;
+                Some(char::from_u32(v).ok_or(high))
+            },
+            _ => Some(Err(high)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::specs::int::ForeignEndian;
+
+    // Encode and decode strings in both byte orders.
+    #[test]
+    fn verify_codec() {
+        let mut buf = [0; 16];
+        let mut text = [0; 16];
+
+        assert_eq!(encode_le("a€𝄞", &mut buf), Some(8));
+        assert_eq!(buf[..8], [0x61, 0x00, 0xac, 0x20, 0x34, 0xd8, 0x1e, 0xdd]);
+        assert_eq!(decode_le(&buf[..8], Policy::Reject, &mut text), Some(8));
+        assert_eq!(&text[..8], "a€𝄞".as_bytes());
+
+        assert_eq!(encode_be("a€", &mut buf), Some(4));
+        assert_eq!(buf[..4], [0x00, 0x61, 0x20, 0xac]);
+        assert_eq!(decode_be(&buf[..4], Policy::Reject, &mut text), Some(4));
+        assert_eq!(decode_be(&buf[..3], Policy::Reject, &mut text), None);
+        assert_eq!(encode_be("a€", &mut buf[..3]), None);
+
+        assert_eq!(decode_le(&[0x34, 0xd8, 0x61, 0x00], Policy::Reject, &mut text), None);
+        assert_eq!(decode_le(&[0x34, 0xd8, 0x61, 0x00], Policy::Replace, &mut text), Some(4));
+        assert_eq!(&text[..4], "\u{fffd}a".as_bytes());
+    }
+
+    // Use wide strings in place and iterate their code points.
+    #[test]
+    fn verify_wide_str() {
+        let mut units = [int::u16le::from_native(0); 8];
+        assert_eq!(encode("Root𝄞", &mut units), Some(6));
+        units[6] = int::u16le::from_native(SURROGATE_LOW_MIN);
+
+        let s = WideStr::from_nul_terminated(&units[..6]);
+        assert!(s.eq_str("Root𝄞"));
+        assert_eq!(WideStr::from_nul_terminated(&units).len(), 7);
+
+        let mut c = WideStr::new(&units[4..]).chars();
+        assert_eq!(c.next(), Some(Ok('𝄞')));
+        assert_eq!(c.next(), Some(Err(SURROGATE_LOW_MIN)));
+        assert_eq!(c.next(), Some(Ok('\0')));
+        assert_eq!(c.remainder().len(), 0);
+        assert_eq!(c.next(), None);
+    }
+
+    // Detect byte order marks.
+    #[test]
+    fn verify_bom() {
+        let le = int::Endianness::Little;
+        let be = int::Endianness::Big;
+
+        assert_eq!(detect_bom(&[0xff, 0xfe, 0x61, 0x00]), Some((Encoding::Utf16(le), 2)));
+        assert_eq!(detect_bom(&[0xfe, 0xff]), Some((Encoding::Utf16(be), 2)));
+        assert_eq!(detect_bom(&[0xff, 0xfe, 0x00, 0x00]), Some((Encoding::Utf32(le), 4)));
+        assert_eq!(detect_bom(&[0x00, 0x00, 0xfe, 0xff]), Some((Encoding::Utf32(be), 4)));
+        assert_eq!(detect_bom(b"\xef\xbb\xbfa"), Some((Encoding::Utf8, 3)));
+        assert_eq!(detect_bom(b"a"), None);
+    }
+}
@@ -0,0 +1,324 @@
+//! Universally Unique Identifiers
+//!
+//! UUIDs (RFC 4122, RFC 9562) are 128-bit identifiers, divided into the
+//! fields `time_low` (32 bits), `time_mid` (16 bits), `time_hi_and_version`
+//! (16 bits), and `clock_seq_and_node` (64 bits). The upper bits of the
+//! clock sequence carry the variant (`VARIANT_*`), and for the RFC 4122
+//! variant, the upper 4 bits of `time_hi_and_version` carry the version
+//! (`VERSION_*`).
+//!
+//! The network form (see `Uuid`) encodes all fields as big-endian, and
+//! matches the order of the textual representation. Microsoft GUIDs, as used
+//! by GPT, PE/COFF, and UEFI, encode the first three fields as little-endian
+//! instead (see `Guid`). Both use the same textual representation of 32
+//! hexadecimal digits in groups of 8, 4, 4, 4, and 12, separated by hyphens.
+
+/// Size of UUIDs
+pub const SIZE: usize = 16;
+
+/// Size of the Textual Representation
+pub const STRING_SIZE: usize = 36;
+
+pub const VARIANT_NCS: u8 = 0;
+pub const VARIANT_RFC4122: u8 = 2;
+pub const VARIANT_MICROSOFT: u8 = 6;
+pub const VARIANT_FUTURE: u8 = 7;
+
+pub const VERSION_TIME: u8 = 1;
+pub const VERSION_DCE: u8 = 2;
+pub const VERSION_MD5: u8 = 3;
+pub const VERSION_RANDOM: u8 = 4;
+pub const VERSION_SHA1: u8 = 5;
+pub const VERSION_TIME_REORDERED: u8 = 6;
+pub const VERSION_UNIX_TIME: u8 = 7;
+pub const VERSION_CUSTOM: u8 = 8;
+
+/// UUID in Network Form
+///
+/// A UUID stored as 16 bytes with all fields encoded as big-endian. The
+/// structure has an alignment of 1, so it can be embedded in packed
+/// structures.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+pub struct Uuid {
+    raw: [u8; 16],
+}
+
+/// UUID in Microsoft GUID Form
+///
+/// A UUID stored as 16 bytes with `time_low`, `time_mid`, and
+/// `time_hi_and_version` encoded as little-endian. The structure has an
+/// alignment of 1, so it can be embedded in packed structures.
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+pub struct Guid {
+    raw: [u8; 16],
+}
+
+// Swap the byte order of the first three fields, converting between the
+// network form and the GUID form in either direction.
+const fn swap_fields(v: [u8; 16]) -> [u8; 16] {
+    [
+        v[3], v[2], v[1], v[0], v[5], v[4], v[7], v[6],
+        v[8], v[9], v[10], v[11], v[12], v[13], v[14], v[15],
+    ]
+}
+
+impl Uuid {
+    /// Nil UUID
+    pub const NIL: Self = Self::from_raw([0x00; 16]);
+
+    /// Max UUID
+    pub const MAX: Self = Self::from_raw([0xff; 16]);
+
+    /// Create from raw value
+    pub const fn from_raw(raw: [u8; 16]) -> Self {
+        Self { raw }
+    }
+
+    /// Return raw value
+    pub const fn to_raw(self) -> [u8; 16] {
+        self.raw
+    }
+
+    /// Create from integer
+    ///
+    /// Create a UUID from its 128-bit integer value, which matches the
+    /// textual representation when written as hexadecimal.
+    pub const fn from_u128(v: u128) -> Self {
+        Self::from_raw(v.to_be_bytes())
+    }
+
+    /// Return integer value
+    pub const fn to_u128(self) -> u128 {
+        u128::from_be_bytes(self.raw)
+    }
+
+    /// Create from fields
+    pub const fn from_fields(
+        time_low: u32,
+        time_mid: u16,
+        time_hi_and_version: u16,
+        clock_seq_and_node: [u8; 8],
+    ) -> Self {
+        let v = ((time_low as u128) << 96)
+            | ((time_mid as u128) << 80)
+            | ((time_hi_and_version as u128) << 64)
+            | u64::from_be_bytes(clock_seq_and_node) as u128;
+        Self::from_u128(v)
+    }
+
+    /// Return fields
+    ///
+    /// Return `time_low`, `time_mid`, `time_hi_and_version`, and
+    /// `clock_seq_and_node` of the UUID.
+    pub fn fields(&self) -> (u32, u16, u16, [u8; 8]) {
+        let r = &self.raw;
+        (
+            u32::from_be_bytes([r[0], r[1], r[2], r[3]]),
+            u16::from_be_bytes([r[4], r[5]]),
+            u16::from_be_bytes([r[6], r[7]]),
+            [r[8], r[9], r[10], r[11], r[12], r[13], r[14], r[15]],
+        )
+    }
+
+    /// Return variant
+    ///
+    /// Return the variant (`VARIANT_*`) of the UUID. Bits not used by the
+    /// variant are masked.
+    pub fn variant(&self) -> u8 {
+        match self.raw[8] >> 5 {
+            0..=3 => VARIANT_NCS,
+            4 | 5 => VARIANT_RFC4122,
+            6 => VARIANT_MICROSOFT,
+            _ => VARIANT_FUTURE,
+        }
+    }
+
+    /// Return version
+    ///
+    /// Return the version (`VERSION_*`) of UUIDs of the RFC 4122 variant,
+    /// or `None` for other variants.
+    pub fn version(&self) -> Option<u8> {
+        match self.variant() {
+            VARIANT_RFC4122 => Some(self.raw[6] >> 4),
+            _ => None,
+        }
+    }
+
+    /// Convert to GUID form
+    pub const fn to_guid(self) -> Guid {
+        Guid::from_raw(swap_fields(self.raw))
+    }
+
+    /// Format as string
+    ///
+    /// Return the textual representation of the UUID, using lowercase
+    /// hexadecimal digits.
+    pub fn format(&self) -> [u8; STRING_SIZE] {
+        const DIGITS: &[u8; 16] = b"0123456789abcdef";
+        let mut out = [b'-'; STRING_SIZE];
+        let mut o = 0;
+
+        for (i, b) in self.raw.iter().enumerate() {
+            if matches!(i, 4 | 6 | 8 | 10) {
+                o += 1;
+            }
+            out[o] = DIGITS[(b >> 4) as usize];
+            out[o + 1] = DIGITS[(b & 0xf) as usize];
+            o += 2;
+        }
+
+        out
+    }
+
+    /// Parse string
+    ///
+    /// Parse the textual representation of a UUID. Hexadecimal digits are
+    /// accepted in either case, and the representation can optionally be
+    /// enclosed in braces, or prefixed with `urn:uuid:`.
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.as_bytes();
+        let s = match s {
+            [b'{', v @ .., b'}'] => v,
+            _ => s.strip_prefix(b"urn:uuid:").unwrap_or(s),
+        };
+        if s.len() != STRING_SIZE {
+            return None;
+        }
+
+        let mut v: u128 = 0;
+        for (i, c) in s.iter().enumerate() {
+            if matches!(i, 8 | 13 | 18 | 23) {
+                if *c != b'-' {
+                    return None;
+                }
+                continue;
+            }
+            v = (v << 4) | (*c as char).to_digit(16)? as u128;
+        }
+
+        Some(Self::from_u128(v))
+    }
+}
+
+impl Guid {
+    /// Create from raw value
+    pub const fn from_raw(raw: [u8; 16]) -> Self {
+        Self { raw }
+    }
+
+    /// Return raw value
+    pub const fn to_raw(self) -> [u8; 16] {
+        self.raw
+    }
+
+    /// Create from integer
+    ///
+    /// Create a GUID from the 128-bit integer value of its textual
+    /// representation (see `Uuid::from_u128()`).
+    pub const fn from_u128(v: u128) -> Self {
+        Uuid::from_u128(v).to_guid()
+    }
+
+    /// Convert to network form
+    pub const fn to_uuid(self) -> Uuid {
+        Uuid::from_raw(swap_fields(self.raw))
+    }
+
+    /// Parse string
+    ///
+    /// Parse the textual representation of a GUID (see `Uuid::parse()`).
+    pub fn parse(s: &str) -> Option<Self> {
+        Uuid::parse(s).map(Uuid::to_guid)
+    }
+}
+
+impl From<Guid> for Uuid {
+    fn from(v: Guid) -> Self {
+        v.to_uuid()
+    }
+}
+
+impl From<Uuid> for Guid {
+    fn from(v: Uuid) -> Self {
+        v.to_guid()
+    }
+}
+
+impl core::fmt::Display for Uuid {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter<'_>) -> Result<(), core::fmt::Error> {
+        let v = self.format();
+        // The formatted representation is always ASCII.
+        fmt.write_str(core::str::from_utf8(&v).map_err(|_| core::fmt::Error)?)
+    }
+}
+
+impl core::fmt::Display for Guid {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter<'_>) -> Result<(), core::fmt::Error> {
+        <Uuid as core::fmt::Display>::fmt(&self.to_uuid(), fmt)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // EFI System Partition type GUID as stored in GPT partition entries.
+    const ESP: [u8; 16] = [
+        0x28, 0x73, 0x2a, 0xc1, 0x1f, 0xf8, 0xd2, 0x11,
+        0xba, 0x4b, 0x00, 0xa0, 0xc9, 0x3e, 0xc9, 0x3b,
+    ];
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(core::mem::size_of::<Uuid>(), SIZE);
+        assert_eq!(core::mem::align_of::<Uuid>(), 1);
+        assert_eq!(core::mem::size_of::<Guid>(), SIZE);
+        assert_eq!(core::mem::align_of::<Guid>(), 1);
+    }
+
+    // Verify fields, variants, and versions, and conversion to GUIDs.
+    #[test]
+    fn verify_fields() {
+        // DNS namespace UUID from RFC 4122.
+        let dns = Uuid::from_u128(0x6ba7b810_9dad_11d1_80b4_00c04fd430c8);
+        let fields = (0x6ba7b810, 0x9dad, 0x11d1, [0x80, 0xb4, 0x00, 0xc0, 0x4f, 0xd4, 0x30, 0xc8]);
+        assert_eq!(dns.fields(), fields);
+        assert_eq!(Uuid::from_fields(fields.0, fields.1, fields.2, fields.3), dns);
+        assert_eq!(dns.variant(), VARIANT_RFC4122);
+        assert_eq!(dns.version(), Some(VERSION_TIME));
+        assert_eq!(dns.to_raw()[..4], [0x6b, 0xa7, 0xb8, 0x10]);
+
+        assert_eq!(Uuid::NIL.variant(), VARIANT_NCS);
+        assert_eq!(Uuid::NIL.version(), None);
+        assert_eq!(Uuid::MAX.variant(), VARIANT_FUTURE);
+        assert_eq!(Uuid::MAX.to_u128(), u128::MAX);
+
+        let esp = Guid::from_raw(ESP);
+        let uuid = esp.to_uuid();
+        assert_eq!(uuid.to_u128(), 0xc12a7328_f81f_11d2_ba4b_00a0c93ec93b);
+        assert_eq!(uuid.to_guid(), esp);
+        assert_eq!(Guid::from_u128(uuid.to_u128()), esp);
+        assert_eq!(Uuid::from(esp), uuid);
+    }
+
+    // Format and parse textual representations.
+    #[test]
+    fn verify_strings() {
+        let esp = Guid::from_raw(ESP);
+        let s = b"c12a7328-f81f-11d2-ba4b-00a0c93ec93b";
+        assert_eq!(&esp.to_uuid().format(), s);
+        assert_eq!(std::format!("{}", esp), "c12a7328-f81f-11d2-ba4b-00a0c93ec93b");
+
+        assert_eq!(Guid::parse("C12A7328-F81F-11D2-BA4B-00A0C93EC93B"), Some(esp));
+        assert_eq!(Guid::parse("{c12a7328-f81f-11d2-ba4b-00a0c93ec93b}"), Some(esp));
+        let uuid = Uuid::parse("urn:uuid:c12a7328-f81f-11d2-ba4b-00a0c93ec93b");
+        assert_eq!(uuid, Some(esp.to_uuid()));
+
+        assert_eq!(Uuid::parse("c12a7328-f81f-11d2-ba4b-00a0c93ec93"), None);
+        assert_eq!(Uuid::parse("c12a7328f-81f-11d2-ba4b-00a0c93ec93b"), None);
+        assert_eq!(Uuid::parse("c12a7328-f81f-11d2-ba4b-00a0c93ec9zb"), None);
+        assert_eq!(Uuid::parse("{c12a7328-f81f-11d2-ba4b-00a0c93ec93b"), None);
+    }
+}
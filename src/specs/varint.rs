@@ -0,0 +1,426 @@
+//! Variable-length Integers
+//!
+//! This module provides encoders and decoders for variable-length integer
+//! encodings that are shared by multiple specifications. They encode small
+//! values in fewer bytes, at the cost of a variable size.
+//!
+//!  * LEB128: Little-endian groups of 7 bits, with the most significant bit
+//!    of each byte set if more bytes follow. Used by DWARF, WebAssembly,
+//!    Protocol Buffers (as "varint"), Android DEX, and others. The signed
+//!    variant sign-extends from the last group. Zigzag encoding is commonly
+//!    used to map signed integers to unsigned integers with small magnitude
+//!    first.
+//!  * VLQ: Big-endian groups of 7 bits, with the most significant bit of
+//!    each byte set if more bytes follow. Used by Standard MIDI Files, and
+//!    others (also known as base-128 varint in ASN.1 and Git).
+//!  * VINT: The EBML variable-size integer. The number of leading zero bits
+//!    of the first byte plus one gives the total width in bytes, followed by
+//!    a marker bit and the big-endian value. A value with all value bits set
+//!    is reserved.
+//!  * QUIC: The two most significant bits of the first byte give the width
+//!    as 1, 2, 4, or 8 bytes, followed by the big-endian value in the
+//!    remaining bits. Used by QUIC and HTTP/3.
+//!
+//! Decoders return the decoded value and the number of bytes consumed, or
+//! `None` if the data is truncated or the value overflows. Encoders write to
+//! the start of an output buffer and return the number of bytes written, or
+//! `None` if the buffer is too small.
+
+/// Maximum Size of LEB128 Encoded 64-bit Integers
+pub const LEB128_MAX: usize = 10;
+
+/// Maximum Size of VLQ Encoded 64-bit Integers
+pub const VLQ_MAX: usize = 10;
+
+/// Maximum Width of VINTs
+pub const VINT_MAX: usize = 8;
+
+/// Maximum Width of QUIC Variable-length Integers
+pub const QUIC_MAX: usize = 8;
+
+/// Maximum Value of QUIC Variable-length Integers
+pub const QUIC_VALUE_MAX: u64 = (1 << 62) - 1;
+
+/// Decode LEB128
+///
+/// Decode an unsigned LEB128 integer at the start of `data`. Padding with
+/// redundant `0x80` bytes is accepted, as long as the value fits in 64 bits
+/// and `LEB128_MAX` bytes.
+pub fn leb128_decode(data: &[u8]) -> Option<(u64, usize)> {
+    let mut v: u64 = 0;
+
+    for (i, b) in data.iter().enumerate().take(LEB128_MAX) {
+        let bits = (*b & 0x7f) as u64;
+        if i == LEB128_MAX - 1 && bits > 1 {
+            return None;
+        }
+
+        v |= bits << (7 * i);
+        if *b & 0x80 == 0 {
+            return Some((v, i + 1));
+        }
+    }
+
+    None
+}
+
+/// Encode LEB128
+///
+/// Encode `v` as unsigned LEB128 integer, using the minimum number of bytes.
+pub fn leb128_encode(mut v: u64, out: &mut [u8]) -> Option<usize> {
+    let mut n = 0;
+
+    loop {
+        let b = (v & 0x7f) as u8;
+        v >>= 7;
+
+        if v == 0 {
+            *out.get_mut(n)? = b;
+            return Some(n + 1);
+        }
+
+        *out.get_mut(n)? = b | 0x80;
+        n += 1;
+    }
+}
+
+/// Decode Signed LEB128
+///
+/// Decode a signed LEB128 integer at the start of `data`. The value is
+/// sign-extended from bit 6 of the last byte. Padding is accepted, as long
+/// as the value fits in 64 bits and `LEB128_MAX` bytes.
+pub fn sleb128_decode(data: &[u8]) -> Option<(i64, usize)> {
+    let mut v: i64 = 0;
+
+    for (i, b) in data.iter().enumerate().take(LEB128_MAX) {
+        let shift = 7 * i as u32;
+        if i == LEB128_MAX - 1 && *b & 0x7f != 0 && *b & 0x7f != 0x7f {
+            return None;
+        }
+
+        v |= ((*b & 0x7f) as i64) << shift;
+
+        if *b & 0x80 == 0 {
+            if shift + 7 < 64 && *b & 0x40 != 0 {
+                v |= -1i64 << (shift + 7);
+            }
+            return Some((v, i + 1));
+        }
+    }
+
+    None
+}
+
+/// Encode Signed LEB128
+///
+/// Encode `v` as signed LEB128 integer, using the minimum number of bytes.
+pub fn sleb128_encode(mut v: i64, out: &mut [u8]) -> Option<usize> {
+    let mut n = 0;
+
+    loop {
+        let b = (v & 0x7f) as u8;
+        v >>= 7;
+
+        if (v == 0 && b & 0x40 == 0) || (v == -1 && b & 0x40 != 0) {
+            *out.get_mut(n)? = b;
+            return Some(n + 1);
+        }
+
+        *out.get_mut(n)? = b | 0x80;
+        n += 1;
+    }
+}
+
+/// Zigzag Encode
+///
+/// Map a signed integer to an unsigned integer, such that values of small
+/// magnitude map to small values (0, -1, 1, -2, ... map to 0, 1, 2, 3, ...).
+pub fn zigzag_encode(v: i64) -> u64 {
+    ((v << 1) ^ (v >> 63)) as u64
+}
+
+/// Zigzag Decode
+///
+/// Reverse `zigzag_encode()`.
+pub fn zigzag_decode(v: u64) -> i64 {
+    ((v >> 1) as i64) ^ -((v & 1) as i64)
+}
+
+/// Decode VLQ
+///
+/// Decode a VLQ integer at the start of `data`. Leading `0x80` bytes are
+/// accepted, as long as the value fits in 64 bits and `VLQ_MAX` bytes.
+pub fn vlq_decode(data: &[u8]) -> Option<(u64, usize)> {
+    let mut v: u64 = 0;
+
+    for (i, b) in data.iter().enumerate().take(VLQ_MAX) {
+        if v >> 57 != 0 {
+            return None;
+        }
+
+        v = (v << 7) | (*b & 0x7f) as u64;
+        if *b & 0x80 == 0 {
+            return Some((v, i + 1));
+        }
+    }
+
+    None
+}
+
+/// Encode VLQ
+///
+/// Encode `v` as VLQ integer, using the minimum number of bytes.
+pub fn vlq_encode(v: u64, out: &mut [u8]) -> Option<usize> {
+    let n = (1..VLQ_MAX).find(|n| v >> (7 * n) == 0).unwrap_or(VLQ_MAX);
+    let bytes = out.get_mut(..n)?;
+
+    for (i, b) in bytes.iter_mut().enumerate() {
+        *b = ((v >> (7 * (n - 1 - i))) & 0x7f) as u8 | 0x80;
+    }
+    bytes[n - 1] &= 0x7f;
+
+    Some(n)
+}
+
+/// Return VINT Width
+///
+/// Return the total width in bytes of a VINT given its first byte, or `None`
+/// if the first byte is 0.
+pub fn vint_width(first: u8) -> Option<usize> {
+    match first.leading_zeros() as usize {
+        v if v < VINT_MAX => Some(v + 1),
+        _ => None,
+    }
+}
+
+/// Return Reserved VINT Value
+///
+/// Return the value with all value bits set for a VINT of the given width,
+/// which is reserved (e.g., to encode an unknown size in EBML). `width` must
+/// be within 1 and `VINT_MAX`, otherwise this panics.
+pub fn vint_reserved(width: usize) -> u64 {
+    assert!((1..=VINT_MAX).contains(&width));
+    (1u64 << (7 * width)) - 1
+}
+
+/// Decode VINT
+///
+/// Decode the VINT at the start of `data`, with the width marker removed.
+/// The caller must check for reserved values via `vint_reserved()`.
+pub fn vint_decode(data: &[u8]) -> Option<(u64, usize)> {
+    let width = vint_width(*data.first()?)?;
+    let bytes = data.get(..width)?;
+
+    let first = (bytes[0] as u64) & (0xff >> width);
+    let v = bytes[1..].iter().fold(first, |acc, b| (acc << 8) | *b as u64);
+    Some((v, width))
+}
+
+/// Encode VINT with Fixed Width
+///
+/// Encode `v` as VINT of the given width. `None` is returned if the width is
+/// invalid or `v` does not fit. Reserved values are not rejected, so this
+/// can be used to encode them.
+pub fn vint_encode_width(v: u64, width: usize, out: &mut [u8]) -> Option<usize> {
+    if width == 0 || width > VINT_MAX || v > vint_reserved(width) {
+        return None;
+    }
+
+    let bytes = out.get_mut(..width)?;
+    for (i, b) in bytes.iter_mut().enumerate() {
+        *b = (v >> (8 * (width - 1 - i))) as u8;
+    }
+    bytes[0] |= 0x80 >> (width - 1);
+
+    Some(width)
+}
+
+/// Encode VINT
+///
+/// Encode `v` as VINT, using the minimum width that does not yield a
+/// reserved value. `None` is returned if `v` does not fit in `VINT_MAX`
+/// bytes.
+pub fn vint_encode(v: u64, out: &mut [u8]) -> Option<usize> {
+    let width = (1..=VINT_MAX).find(|w| v < vint_reserved(*w))?;
+    vint_encode_width(v, width, out)
+}
+
+/// Return QUIC Variable-length Integer Width
+///
+/// Return the total width in bytes of a QUIC variable-length integer given
+/// its first byte.
+pub fn quic_width(first: u8) -> usize {
+    1 << (first >> 6)
+}
+
+/// Decode QUIC Variable-length Integer
+///
+/// Decode the QUIC variable-length integer at the start of `data`, with the
+/// width prefix removed. Non-minimal encodings are accepted.
+pub fn quic_decode(data: &[u8]) -> Option<(u64, usize)> {
+    let width = quic_width(*data.first()?);
+    let bytes = data.get(..width)?;
+
+    let first = (bytes[0] & 0x3f) as u64;
+    let v = bytes[1..].iter().fold(first, |acc, b| (acc << 8) | *b as u64);
+    Some((v, width))
+}
+
+/// Encode QUIC Variable-length Integer
+///
+/// Encode `v` as QUIC variable-length integer, using the minimum width.
+/// `None` is returned if `v` exceeds `QUIC_VALUE_MAX`.
+pub fn quic_encode(v: u64, out: &mut [u8]) -> Option<usize> {
+    let (width, prefix) = match v {
+        0..=0x3f => (1, 0x00),
+        0x40..=0x3fff => (2, 0x40),
+        0x4000..=0x3fff_ffff => (4, 0x80),
+        0x4000_0000..=QUIC_VALUE_MAX => (8, 0xc0),
+        _ => return None,
+    };
+
+    let bytes = out.get_mut(..width)?;
+    for (i, b) in bytes.iter_mut().enumerate() {
+        *b = (v >> (8 * (width - 1 - i))) as u8;
+    }
+    bytes[0] |= prefix;
+
+    Some(width)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Verify LEB128 against the examples of the DWARF specification, and
+    // verify overflow handling.
+    #[test]
+    fn verify_leb128() {
+        let mut buf = [0; LEB128_MAX];
+
+        assert_eq!(leb128_decode(&[0xe5, 0x8e, 0x26]), Some((624485, 3)));
+        assert_eq!(leb128_encode(624485, &mut buf), Some(3));
+        assert_eq!(buf[..3], [0xe5, 0x8e, 0x26]);
+        assert_eq!(leb128_decode(&[0x80, 0x00]), Some((0, 2)));
+
+        assert_eq!(leb128_encode(u64::MAX, &mut buf), Some(10));
+        assert_eq!(leb128_decode(&buf), Some((u64::MAX, 10)));
+        buf[9] = 0x02;
+        assert!(leb128_decode(&buf).is_none());
+        assert!(leb128_decode(&[0x80]).is_none());
+        assert!(leb128_encode(128, &mut buf[..1]).is_none());
+
+        assert_eq!(sleb128_decode(&[0xc0, 0xbb, 0x78]), Some((-123456, 3)));
+        assert_eq!(sleb128_encode(-123456, &mut buf), Some(3));
+        assert_eq!(buf[..3], [0xc0, 0xbb, 0x78]);
+        assert_eq!(sleb128_encode(63, &mut buf), Some(1));
+        assert_eq!(sleb128_encode(64, &mut buf), Some(2));
+        assert_eq!(sleb128_decode(&[0x7f]), Some((-1, 1)));
+
+        for v in [i64::MIN, -1, 0, 1, i64::MAX] {
+            let n = sleb128_encode(v, &mut buf).unwrap();
+            assert_eq!(sleb128_decode(&buf), Some((v, n)));
+        }
+
+        // The 10th byte must be a sign extension of bit 63.
+        let mut v = [0xff; 11];
+        v[9] = 0x7f;
+        assert_eq!(sleb128_decode(&v[..10]), Some((-1, 10)));
+        v[9] = 0x01;
+        assert!(sleb128_decode(&v[..10]).is_none());
+        v[9] = 0x3f;
+        assert!(sleb128_decode(&v[..10]).is_none());
+        v[9] = 0xff;
+        v[10] = 0x7f;
+        assert!(sleb128_decode(&v).is_none());
+    }
+
+    // Verify zigzag mapping at its boundaries.
+    #[test]
+    fn verify_zigzag() {
+        assert_eq!(zigzag_encode(0), 0);
+        assert_eq!(zigzag_encode(-1), 1);
+        assert_eq!(zigzag_encode(1), 2);
+        assert_eq!(zigzag_encode(i64::MAX), u64::MAX - 1);
+        assert_eq!(zigzag_encode(i64::MIN), u64::MAX);
+
+        for v in [i64::MIN, -2, -1, 0, 1, i64::MAX] {
+            assert_eq!(zigzag_decode(zigzag_encode(v)), v);
+        }
+    }
+
+    // Verify VLQ against the examples of the Standard MIDI File
+    // specification, and verify overflow handling.
+    #[test]
+    fn verify_vlq() {
+        let mut buf = [0; VLQ_MAX];
+
+        for (v, e) in [
+            (0x00, &[0x00][..]),
+            (0x7f, &[0x7f][..]),
+            (0x80, &[0x81, 0x00][..]),
+            (0x2000, &[0xc0, 0x00][..]),
+            (0x3fff, &[0xff, 0x7f][..]),
+            (0x4000, &[0x81, 0x80, 0x00][..]),
+            (0x0fff_ffff, &[0xff, 0xff, 0xff, 0x7f][..]),
+        ] {
+            assert_eq!(vlq_encode(v, &mut buf), Some(e.len()));
+            assert_eq!(&buf[..e.len()], e);
+            assert_eq!(vlq_decode(e), Some((v, e.len())));
+        }
+
+        assert_eq!(vlq_encode(u64::MAX, &mut buf), Some(10));
+        assert_eq!(buf[0], 0x81);
+        assert_eq!(vlq_decode(&buf), Some((u64::MAX, 10)));
+        buf[0] = 0x82;
+        assert!(vlq_decode(&buf).is_none());
+        assert!(vlq_decode(&[0x81]).is_none());
+        assert!(vlq_encode(0x80, &mut buf[..1]).is_none());
+    }
+
+    // Verify VINT widths and reserved values.
+    #[test]
+    fn verify_vint() {
+        let mut buf = [0; VINT_MAX];
+
+        assert_eq!(vint_decode(&[0x81]), Some((1, 1)));
+        assert_eq!(vint_decode(&[0x40, 0x02]), Some((2, 2)));
+        assert_eq!(vint_decode(&[0x1a, 0x45, 0xdf, 0xa3]), Some((0x0a45dfa3, 4)));
+        assert!(vint_decode(&[0x00]).is_none());
+        assert!(vint_decode(&[0x40]).is_none());
+
+        assert_eq!(vint_encode(126, &mut buf), Some(1));
+        assert_eq!(buf[0], 0xfe);
+        assert_eq!(vint_encode(127, &mut buf), Some(2));
+        assert_eq!(buf[..2], [0x40, 0x7f]);
+        assert!(vint_encode(vint_reserved(VINT_MAX), &mut buf).is_none());
+
+        assert_eq!(vint_encode_width(vint_reserved(8), 8, &mut buf), Some(8));
+        assert_eq!(buf, [0x01, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff]);
+        assert_eq!(vint_decode(&buf), Some((vint_reserved(8), 8)));
+        assert!(vint_encode_width(128, 1, &mut buf).is_none());
+    }
+
+    // Verify QUIC variable-length integers against the examples of RFC 9000.
+    #[test]
+    fn verify_quic() {
+        let mut buf = [0; QUIC_MAX];
+
+        for (v, e) in [
+            (151_288_809_941_952_652, &[0xc2, 0x19, 0x7c, 0x5e, 0xff, 0x14, 0xe8, 0x8c][..]),
+            (494_878_333, &[0x9d, 0x7f, 0x3e, 0x7d][..]),
+            (15_293, &[0x7b, 0xbd][..]),
+            (37, &[0x25][..]),
+        ] {
+            assert_eq!(quic_encode(v, &mut buf), Some(e.len()));
+            assert_eq!(&buf[..e.len()], e);
+            assert_eq!(quic_decode(e), Some((v, e.len())));
+        }
+
+        assert_eq!(quic_decode(&[0x40, 0x25]), Some((37, 2)));
+        assert_eq!(quic_width(0xc0), QUIC_MAX);
+        assert!(quic_decode(&[0x80, 0x00]).is_none());
+        assert!(quic_encode(QUIC_VALUE_MAX + 1, &mut buf).is_none());
+        assert!(quic_encode(64, &mut buf[..1]).is_none());
+    }
+}
@@ -0,0 +1,226 @@
+//! IEEE 802.1Q VLAN Tags
+//!
+//! VLAN tags are inserted into Ethernet frames between the source address
+//! and the EtherType. Each tag consists of a tag protocol identifier (TPID),
+//! which takes the place of the EtherType, and the tag control information
+//! (TCI). The TCI carries the priority code point (PCP), the drop eligible
+//! indicator (DEI), and the VLAN identifier (VID).
+//!
+//! IEEE 802.1ad allows stacking tags ("QinQ"), with an outer service tag using
+//! `TPID_8021AD`, followed by an inner customer tag using `TPID_8021Q`. Some
+//! equipment uses the non-standard `TPID_QINQ_*` values for outer tags. The
+//! actual EtherType of the frame follows the last tag (see `Tags`).
+//!
+//! All multi-byte integers are encoded as big-endian.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::int;
+
+pub const TAG_SIZE: usize = 4;
+
+pub const TPID_8021Q: u16 = 0x8100;
+pub const TPID_8021AD: u16 = 0x88a8;
+pub const TPID_QINQ_9100: u16 = 0x9100;
+pub const TPID_QINQ_9200: u16 = 0x9200;
+pub const TPID_QINQ_9300: u16 = 0x9300;
+
+pub const PCP_BEST_EFFORT: u8 = 0;
+pub const PCP_BACKGROUND: u8 = 1;
+pub const PCP_EXCELLENT_EFFORT: u8 = 2;
+pub const PCP_CRITICAL_APPLICATIONS: u8 = 3;
+pub const PCP_VIDEO: u8 = 4;
+pub const PCP_VOICE: u8 = 5;
+pub const PCP_INTERNETWORK_CONTROL: u8 = 6;
+pub const PCP_NETWORK_CONTROL: u8 = 7;
+
+/// Null VLAN Identifier
+///
+/// The frame carries no VLAN membership, only a priority ("priority tag").
+pub const VID_NULL: u16 = 0;
+
+pub const VID_DEFAULT: u16 = 1;
+pub const VID_MAX: u16 = 4094;
+/* reserved: 4095 */
+
+pub const TCI_PCP_SHIFT: u16 = 13;
+pub const TCI_DEI: u16 = 0x1000;
+pub const TCI_VID_MASK: u16 = 0x0fff;
+
+/// VLAN Tag
+#[repr(C)]
+pub struct Tag {
+    /// Tag protocol identifier (`TPID_*`).
+    pub tpid: int::u16be,
+
+    /// Tag control information (see `pcp()`, `dei()`, and `vid()`).
+    pub tci: int::u16be,
+}
+
+/// Tag Iterator
+///
+/// An iterator over stacked VLAN tags. It must be created on the data
+/// following the source address of an Ethernet frame, and yields tags as
+/// long as their TPID is one of `TPID_*`. Afterwards, `remainder()` starts
+/// at the EtherType of the frame.
+#[derive(Clone, Debug)]
+pub struct Tags<'data> {
+    data: &'data [u8],
+}
+
+impl Tag {
+    /// Import a tag from a byte slice
+    ///
+    /// Create a new tag from data copied from a byte slice. No byte-order
+    /// conversions are applied.
+    pub fn from_bytes(data: &[u8; 4]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 4], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Create a tag from native values
+    pub fn from_native(tpid: u16, pcp: u8, dei: bool, vid: u16) -> Self {
+        Self {
+            tpid: int::ForeignEndian::from_native(tpid),
+            tci: int::ForeignEndian::from_native(tci(pcp, dei, vid)),
+        }
+    }
+
+    /// Return priority code point (`PCP_*`)
+    pub fn pcp(&self) -> u8 {
+        (int::ForeignEndian::to_native(self.tci) >> TCI_PCP_SHIFT) as u8
+    }
+
+    /// Return drop eligible indicator
+    pub fn dei(&self) -> bool {
+        int::ForeignEndian::to_native(self.tci) & TCI_DEI != 0
+    }
+
+    /// Return VLAN identifier
+    pub fn vid(&self) -> u16 {
+        int::ForeignEndian::to_native(self.tci) & TCI_VID_MASK
+    }
+}
+
+impl<'data> Tags<'data> {
+    /// Create tag iterator
+    ///
+    /// Create a new iterator over the tags in `data`, which must start at
+    /// the first TPID.
+    pub fn new(data: &'data [u8]) -> Self {
+        Self {
+            data,
+        }
+    }
+
+    /// Return remaining data
+    ///
+    /// Return the data that has not been consumed by the iterator.
+    pub fn remainder(&self) -> &'data [u8] {
+        self.data
+    }
+}
+
+impl<'data> Iterator for Tags<'data> {
+    type Item = Tag;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.data.len() < TAG_SIZE || !is_tpid(u16::from_be_bytes([self.data[0], self.data[1]])) {
+            return None;
+        }
+
+        let tag = Tag::from_bytes(self.data[..TAG_SIZE].try_into().unwrap());
+        self.data = &self.data[TAG_SIZE..];
+        Some(tag)
+    }
+}
+
+/// Check for VLAN TPIDs
+///
+/// Return whether `v` is one of the `TPID_*` values, and thus introduces a
+/// VLAN tag rather than the EtherType of the frame.
+pub fn is_tpid(v: u16) -> bool {
+    matches!(v, TPID_8021Q | TPID_8021AD | TPID_QINQ_9100 | TPID_QINQ_9200 | TPID_QINQ_9300)
+}
+
+/// Encode Tag Control Information
+///
+/// Combine the priority code point, the drop eligible indicator, and the
+/// VLAN identifier. Excess bits of `pcp` and `vid` are discarded.
+pub fn tci(pcp: u8, dei: bool, vid: u16) -> u16 {
+    ((pcp as u16 & 0x7) << TCI_PCP_SHIFT)
+        | if dei { TCI_DEI } else { 0 }
+        | (vid & TCI_VID_MASK)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A double-tagged frame after the MAC addresses, carrying IPv4.
+    const QINQ: [u8; 10] = [
+        0x88, 0xa8, 0xa0, 0x64, 0x81, 0x00, 0x30, 0xc8,
+        0x08, 0x00,
+    ];
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<Tag>(), TAG_SIZE);
+        assert_eq!(align_of::<Tag>(), 2);
+    }
+
+    // Walk stacked tags up to the EtherType.
+    #[test]
+    fn verify_stacked() {
+        let mut tags = Tags::new(&QINQ);
+
+        let t = tags.next().unwrap();
+        assert_eq!(int::ForeignEndian::to_native(t.tpid), TPID_8021AD);
+        assert_eq!(t.pcp(), PCP_VOICE);
+        assert!(!t.dei());
+        assert_eq!(t.vid(), 100);
+
+        let t = tags.next().unwrap();
+        assert_eq!(int::ForeignEndian::to_native(t.tpid), TPID_8021Q);
+        assert_eq!(t.pcp(), PCP_BACKGROUND);
+        assert!(t.dei());
+        assert_eq!(t.vid(), 200);
+
+        assert!(tags.next().is_none());
+        assert_eq!(tags.remainder(), &[0x08, 0x00]);
+
+        // Truncated tags are not yielded.
+        let mut tags = Tags::new(&QINQ[..7]);
+        assert!(tags.next().is_some());
+        assert!(tags.next().is_none());
+        assert_eq!(tags.remainder().len(), 3);
+    }
+
+    // Verify TCI encoding and TPID detection.
+    #[test]
+    fn verify_tci() {
+        assert_eq!(tci(PCP_VOICE, false, 100), 0xa064);
+        assert_eq!(tci(PCP_BACKGROUND, true, 200), 0x30c8);
+        assert_eq!(tci(0xff, true, 0xffff), 0xffff);
+
+        let t = Tag::from_native(TPID_8021Q, PCP_NETWORK_CONTROL, false, VID_MAX);
+        assert_eq!(t.pcp(), PCP_NETWORK_CONTROL);
+        assert_eq!(t.vid(), VID_MAX);
+        assert_eq!(int::ForeignEndian::to_native(t.tci), 0xeffe);
+
+        assert!(is_tpid(TPID_QINQ_9100));
+        assert!(!is_tpid(0x0800));
+    }
+}
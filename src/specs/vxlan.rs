@@ -0,0 +1,223 @@
+//! Virtual eXtensible Local Area Network
+//!
+//! VXLAN tunnels Ethernet frames over UDP (`udp::PORT_VXLAN`). Each datagram
+//! carries an 8-byte `Header` with a 24-bit VXLAN network identifier (VNI),
+//! directly followed by the inner Ethernet frame without FCS.
+//!
+//! The Generic Protocol Extension (VXLAN-GPE) uses its own port
+//! (`udp::PORT_VXLAN_GPE`) and replaces some reserved bits with a version,
+//! additional flags, and a next-protocol field, allowing payloads other than
+//! Ethernet (see `GpeHeader`).
+//!
+//! All multi-byte integers are encoded as big-endian.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::int;
+
+pub const HEADER_SIZE: usize = 8;
+
+/// Maximum VNI
+pub const VNI_MAX: u32 = 0x00ff_ffff;
+
+pub const FLAG_I: u8 = 0x08;
+
+pub const GPE_FLAG_I: u8 = 0x08;
+pub const GPE_FLAG_P: u8 = 0x04;
+pub const GPE_FLAG_B: u8 = 0x02;
+pub const GPE_FLAG_O: u8 = 0x01;
+pub const GPE_VERSION_MASK: u8 = 0x30;
+pub const GPE_VERSION_SHIFT: u32 = 4;
+
+pub const GPE_PROTOCOL_IPV4: u8 = 1;
+pub const GPE_PROTOCOL_IPV6: u8 = 2;
+pub const GPE_PROTOCOL_ETHERNET: u8 = 3;
+pub const GPE_PROTOCOL_NSH: u8 = 4;
+pub const GPE_PROTOCOL_MPLS: u8 = 5;
+
+/// VXLAN Header
+#[repr(C)]
+pub struct Header {
+    /// Flags (`FLAG_I` must be set).
+    pub flags: u8,
+
+    /// Reserved, must be 0.
+    pub reserved0: int::u24be,
+
+    /// VXLAN network identifier.
+    pub vni: int::u24be,
+
+    /// Reserved, must be 0.
+    pub reserved1: u8,
+}
+
+/// VXLAN-GPE Header
+#[repr(C)]
+pub struct GpeHeader {
+    /// Version and flags (`GPE_FLAG_*`).
+    pub flags: u8,
+
+    /// Reserved, must be 0.
+    pub reserved0: [u8; 2],
+
+    /// Protocol of the payload (`GPE_PROTOCOL_*`), if `GPE_FLAG_P` is set.
+    pub next_protocol: u8,
+
+    /// VXLAN network identifier.
+    pub vni: int::u24be,
+
+    /// Reserved, must be 0.
+    pub reserved1: u8,
+}
+
+impl Header {
+    /// Import a header from a byte slice
+    ///
+    /// Create a new header from data copied from a byte slice. No byte-order
+    /// conversions are applied.
+    pub fn from_bytes(data: &[u8; 8]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 8], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Create header
+    ///
+    /// Create a new header with `FLAG_I` set and the given VNI, which must
+    /// not exceed `VNI_MAX`.
+    pub fn new(vni: u32) -> Self {
+        assert!(vni <= VNI_MAX);
+
+        Self {
+            flags: FLAG_I,
+            reserved0: int::u24be::from_native(0),
+            vni: int::u24be::from_native(vni),
+            reserved1: 0,
+        }
+    }
+
+    /// Convert to byte slice
+    ///
+    /// Return a byte-slice reference to the header. No byte-order
+    /// conversions are applied.
+    pub fn as_bytes(&self) -> &[u8; 8] {
+        assert!(align_of::<[u8; 8]>() <= align_of::<Self>());
+        assert!(size_of::<[u8; 8]>() == size_of::<Self>());
+
+        unsafe {
+            core::mem::transmute::<&Self, &[u8; 8]>(self)
+        }
+    }
+
+    /// Return VNI
+    ///
+    /// Return the VXLAN network identifier, or `None` if `FLAG_I` is not
+    /// set.
+    pub fn vni(&self) -> Option<u32> {
+        if self.flags & FLAG_I != 0 {
+            Some(self.vni.to_native())
+        } else {
+            None
+        }
+    }
+}
+
+impl GpeHeader {
+    /// Import a GPE header from a byte slice
+    ///
+    /// Create a new GPE header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 8]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 8], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Return version
+    pub fn version(&self) -> u8 {
+        (self.flags & GPE_VERSION_MASK) >> GPE_VERSION_SHIFT
+    }
+
+    /// Return VNI
+    ///
+    /// Return the VXLAN network identifier, or `None` if `GPE_FLAG_I` is not
+    /// set.
+    pub fn vni(&self) -> Option<u32> {
+        if self.flags & GPE_FLAG_I != 0 {
+            Some(self.vni.to_native())
+        } else {
+            None
+        }
+    }
+
+    /// Return next protocol
+    ///
+    /// Return the protocol of the payload, or `None` if `GPE_FLAG_P` is not
+    /// set, in which case the payload is Ethernet.
+    pub fn next_protocol(&self) -> Option<u8> {
+        if self.flags & GPE_FLAG_P != 0 {
+            Some(self.next_protocol)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<Header>(), HEADER_SIZE);
+        assert_eq!(align_of::<Header>(), 1);
+        assert_eq!(size_of::<GpeHeader>(), HEADER_SIZE);
+        assert_eq!(align_of::<GpeHeader>(), 1);
+    }
+
+    // Parse and create VXLAN headers.
+    #[test]
+    fn verify_header() {
+        let data = [0x08, 0x00, 0x00, 0x00, 0x12, 0x34, 0x56, 0x00];
+        let h = Header::from_bytes(&data);
+        assert_eq!(h.vni(), Some(0x123456));
+        assert_eq!(Header::new(0x123456).as_bytes(), &data);
+        assert_eq!(Header::new(VNI_MAX).vni(), Some(VNI_MAX));
+
+        let h = Header::from_bytes(&[0x00, 0x00, 0x00, 0x00, 0x12, 0x34, 0x56, 0x00]);
+        assert_eq!(h.vni(), None);
+    }
+
+    // Parse VXLAN-GPE headers.
+    #[test]
+    fn verify_gpe() {
+        let h = GpeHeader::from_bytes(&[0x0c, 0x00, 0x00, 0x04, 0x00, 0x00, 0x2a, 0x00]);
+        assert_eq!(h.version(), 0);
+        assert_eq!(h.vni(), Some(42));
+        assert_eq!(h.next_protocol(), Some(GPE_PROTOCOL_NSH));
+        assert_eq!(h.flags & GPE_FLAG_O, 0);
+
+        let h = GpeHeader::from_bytes(&[0x19, 0x00, 0x00, 0x01, 0x00, 0x00, 0x01, 0x00]);
+        assert_eq!(h.version(), 1);
+        assert_eq!(h.vni(), Some(1));
+        assert_eq!(h.next_protocol(), None);
+        assert_ne!(h.flags & GPE_FLAG_O, 0);
+    }
+}
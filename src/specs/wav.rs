@@ -0,0 +1,318 @@
+//! Waveform Audio File Format
+//!
+//! WAV files are RIFF containers (see `riff`) of form type `WAVE`. The `fmt `
+//! chunk describes the sample format and must precede the `data` chunk,
+//! which carries the interleaved samples. Non-PCM formats additionally carry
+//! a `fact` chunk with the number of samples per channel. Metadata is
+//! commonly stored in a `LIST` chunk of type `INFO`.
+//!
+//! The `fmt ` chunk comes in several sizes. The basic 16-byte layout is
+//! `Format` (`PCMWAVEFORMAT`). Non-PCM formats append a 16-bit extension size
+//! and format-specific data (`WAVEFORMATEX`). The extension of
+//! `FORMAT_EXTENSIBLE` is 22 bytes, for a total of 40 bytes, and is covered
+//! by `FormatExtensible` (`WAVEFORMATEXTENSIBLE`). It carries a channel mask
+//! and the actual format as GUID, which embeds the classic format tag for
+//! the common formats.
+//!
+//! All multi-byte integers are encoded as little-endian.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::int;
+
+/// Form Type
+///
+/// The form type of the RIFF header of a WAV file.
+pub const FORM: [u8; 4] = *b"WAVE";
+
+pub const CHUNK_FMT: [u8; 4] = *b"fmt ";
+pub const CHUNK_DATA: [u8; 4] = *b"data";
+pub const CHUNK_FACT: [u8; 4] = *b"fact";
+pub const CHUNK_CUE: [u8; 4] = *b"cue ";
+pub const CHUNK_SMPL: [u8; 4] = *b"smpl";
+pub const CHUNK_BEXT: [u8; 4] = *b"bext";
+
+pub const FORMAT_PCM: u16 = 0x0001;
+pub const FORMAT_ADPCM: u16 = 0x0002;
+pub const FORMAT_IEEE_FLOAT: u16 = 0x0003;
+pub const FORMAT_ALAW: u16 = 0x0006;
+pub const FORMAT_MULAW: u16 = 0x0007;
+pub const FORMAT_IMA_ADPCM: u16 = 0x0011;
+pub const FORMAT_GSM610: u16 = 0x0031;
+pub const FORMAT_MPEG: u16 = 0x0050;
+pub const FORMAT_MPEGLAYER3: u16 = 0x0055;
+pub const FORMAT_EXTENSIBLE: u16 = 0xfffe;
+
+pub const SPEAKER_FRONT_LEFT: u32 = 0x0000_0001;
+pub const SPEAKER_FRONT_RIGHT: u32 = 0x0000_0002;
+pub const SPEAKER_FRONT_CENTER: u32 = 0x0000_0004;
+pub const SPEAKER_LOW_FREQUENCY: u32 = 0x0000_0008;
+pub const SPEAKER_BACK_LEFT: u32 = 0x0000_0010;
+pub const SPEAKER_BACK_RIGHT: u32 = 0x0000_0020;
+pub const SPEAKER_FRONT_LEFT_OF_CENTER: u32 = 0x0000_0040;
+pub const SPEAKER_FRONT_RIGHT_OF_CENTER: u32 = 0x0000_0080;
+pub const SPEAKER_BACK_CENTER: u32 = 0x0000_0100;
+pub const SPEAKER_SIDE_LEFT: u32 = 0x0000_0200;
+pub const SPEAKER_SIDE_RIGHT: u32 = 0x0000_0400;
+pub const SPEAKER_TOP_CENTER: u32 = 0x0000_0800;
+pub const SPEAKER_TOP_FRONT_LEFT: u32 = 0x0000_1000;
+pub const SPEAKER_TOP_FRONT_CENTER: u32 = 0x0000_2000;
+pub const SPEAKER_TOP_FRONT_RIGHT: u32 = 0x0000_4000;
+pub const SPEAKER_TOP_BACK_LEFT: u32 = 0x0000_8000;
+pub const SPEAKER_TOP_BACK_CENTER: u32 = 0x0001_0000;
+pub const SPEAKER_TOP_BACK_RIGHT: u32 = 0x0002_0000;
+
+/// Size of the basic Format
+pub const FORMAT_SIZE: usize = 16;
+
+/// Size of the Extensible Format
+pub const FORMAT_EXTENSIBLE_SIZE: usize = 40;
+
+/// Sub-format GUID Suffix
+///
+/// The trailing 14 bytes of the sub-format GUIDs that embed a classic format
+/// tag (`xxxxxxxx-0000-0010-8000-00aa00389b71`), in their on-disk form. The
+/// format tag is stored little-endian in the first 2 bytes, followed by 2
+/// zero bytes.
+pub const SUBFORMAT_SUFFIX: [u8; 14] = [
+    0x00, 0x00, 0x00, 0x00, 0x10, 0x00, 0x80, 0x00,
+    0x00, 0xaa, 0x00, 0x38, 0x9b, 0x71,
+];
+
+/// Basic Format
+///
+/// The payload of a `fmt ` chunk, or its start if it is larger.
+#[repr(C)]
+pub struct Format {
+    /// Format tag (`FORMAT_*`).
+    pub format_tag: int::u16le,
+
+    /// Number of interleaved channels.
+    pub channels: int::u16le,
+
+    /// Sample frames per second.
+    pub samples_per_sec: int::u32le,
+
+    /// Average data rate in bytes per second.
+    pub avg_bytes_per_sec: int::u32le,
+
+    /// Size of a sample frame of all channels in bytes.
+    pub block_align: int::u16le,
+
+    /// Bits per sample of a single channel.
+    pub bits_per_sample: int::u16le,
+}
+
+/// Extensible Format
+///
+/// The complete payload of a `fmt ` chunk with `FORMAT_EXTENSIBLE`.
+#[repr(C)]
+pub struct FormatExtensible {
+    /// Basic format, with `FORMAT_EXTENSIBLE` as format tag.
+    pub format: Format,
+
+    /// Size of the extension in bytes, must be at least 22.
+    pub cb_size: int::u16le,
+
+    /// Number of valid bits per sample, or the number of samples per block
+    /// for compressed formats.
+    pub valid_bits_per_sample: int::u16le,
+
+    /// Assignment of channels to speaker positions (`SPEAKER_*`).
+    pub channel_mask: int::u32le,
+
+    /// Sub-format GUID in its on-disk form.
+    pub sub_format: [u8; 16],
+}
+
+/// Fact
+///
+/// The payload of a `fact` chunk, or its start if it is larger.
+#[repr(C)]
+pub struct Fact {
+    /// Number of samples per channel.
+    pub sample_length: int::u32le,
+}
+
+impl Format {
+    /// Import a format from a byte slice
+    ///
+    /// Create a new format from data copied from a byte slice. No byte-order
+    /// conversions are applied.
+    pub fn from_bytes(data: &[u8; 16]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 16], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl FormatExtensible {
+    /// Import an extensible format from a byte slice
+    ///
+    /// Create a new extensible format from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 40]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 40], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Return embedded format tag
+    ///
+    /// Return the classic format tag embedded in the sub-format GUID, or
+    /// `None` if the GUID does not embed a format tag.
+    pub fn sub_format_tag(&self) -> Option<u16> {
+        if self.sub_format[2..] == SUBFORMAT_SUFFIX {
+            Some(u16::from_le_bytes([self.sub_format[0], self.sub_format[1]]))
+        } else {
+            None
+        }
+    }
+}
+
+impl Fact {
+    /// Import a fact from a byte slice
+    ///
+    /// Create a new fact from data copied from a byte slice. No byte-order
+    /// conversions are applied.
+    pub fn from_bytes(data: &[u8; 4]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 4], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+/// Create Sub-format GUID
+///
+/// Return the sub-format GUID that embeds the given classic format tag, in
+/// its on-disk form.
+pub fn sub_format(format_tag: u16) -> [u8; 16] {
+    let mut v = [0; 16];
+    v[..2].copy_from_slice(&format_tag.to_le_bytes());
+    v[2..].copy_from_slice(&SUBFORMAT_SUFFIX);
+    v
+}
+
+/// Determine Effective Format Tag
+///
+/// Return the format tag of the payload of a `fmt ` chunk, resolving
+/// `FORMAT_EXTENSIBLE` to the embedded format tag, if any. `None` is
+/// returned if the payload is truncated.
+pub fn format_tag(data: &[u8]) -> Option<u16> {
+    let f = Format::from_bytes(data.get(..FORMAT_SIZE)?.try_into().unwrap());
+
+    match int::ForeignEndian::to_native(f.format_tag) {
+        FORMAT_EXTENSIBLE => {
+            let e = FormatExtensible::from_bytes(
+                data.get(..FORMAT_EXTENSIBLE_SIZE)?.try_into().unwrap(),
+            );
+            Some(e.sub_format_tag().unwrap_or(FORMAT_EXTENSIBLE))
+        },
+        v => Some(v),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::specs::riff;
+
+    // A 16-bit stereo PCM file at 44.1kHz with 2 sample frames, written by
+    // the python `wave` module, with an `INFO` list appended.
+    const FILE: [u8; 78] = [
+        0x52, 0x49, 0x46, 0x46, 0x46, 0x00, 0x00, 0x00,
+        0x57, 0x41, 0x56, 0x45, 0x66, 0x6d, 0x74, 0x20,
+        0x10, 0x00, 0x00, 0x00, 0x01, 0x00, 0x02, 0x00,
+        0x44, 0xac, 0x00, 0x00, 0x10, 0xb1, 0x02, 0x00,
+        0x04, 0x00, 0x10, 0x00, 0x64, 0x61, 0x74, 0x61,
+        0x08, 0x00, 0x00, 0x00, 0x01, 0x00, 0x02, 0x00,
+        0x03, 0x00, 0x04, 0x00, 0x4c, 0x49, 0x53, 0x54,
+        0x12, 0x00, 0x00, 0x00, 0x49, 0x4e, 0x46, 0x4f,
+        0x49, 0x53, 0x46, 0x54, 0x05, 0x00, 0x00, 0x00,
+        0x74, 0x65, 0x73, 0x74, 0x00, 0x00,
+    ];
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<Format>(), FORMAT_SIZE);
+        assert_eq!(align_of::<Format>(), 4);
+        assert_eq!(size_of::<FormatExtensible>(), FORMAT_EXTENSIBLE_SIZE);
+        assert_eq!(size_of::<Fact>(), 4);
+    }
+
+    // Walk the chunks of a real file.
+    #[test]
+    fn verify_file() {
+        let (form, mut chunks) = riff::parse(&FILE).unwrap();
+        assert_eq!(form, FORM);
+
+        let c = chunks.next().unwrap();
+        assert_eq!(c.id, CHUNK_FMT);
+        let f = Format::from_bytes(c.data.try_into().unwrap());
+        assert_eq!(int::ForeignEndian::to_native(f.format_tag), FORMAT_PCM);
+        assert_eq!(int::ForeignEndian::to_native(f.channels), 2);
+        assert_eq!(int::ForeignEndian::to_native(f.samples_per_sec), 44100);
+        assert_eq!(int::ForeignEndian::to_native(f.avg_bytes_per_sec), 176400);
+        assert_eq!(int::ForeignEndian::to_native(f.block_align), 4);
+        assert_eq!(int::ForeignEndian::to_native(f.bits_per_sample), 16);
+        assert_eq!(format_tag(c.data), Some(FORMAT_PCM));
+
+        let c = chunks.next().unwrap();
+        assert_eq!(c.id, CHUNK_DATA);
+        assert_eq!(c.data.len(), 8);
+
+        let c = chunks.next().unwrap();
+        let (list, mut info) = c.list().unwrap();
+        assert_eq!(list, riff::LIST_INFO);
+        let i = info.next().unwrap();
+        assert_eq!(i.id, riff::INFO_SOFTWARE);
+        assert_eq!(riff::info_string(i.data), b"test");
+
+        assert!(chunks.next().is_none());
+    }
+
+    // Resolve the format tag of an extensible float format.
+    #[test]
+    fn verify_extensible() {
+        let mut data = [0u8; 40];
+        data[..2].copy_from_slice(&FORMAT_EXTENSIBLE.to_le_bytes());
+        data[16..18].copy_from_slice(&22u16.to_le_bytes());
+        data[20..24].copy_from_slice(&(SPEAKER_FRONT_LEFT | SPEAKER_FRONT_RIGHT).to_le_bytes());
+        data[24..].copy_from_slice(&sub_format(FORMAT_IEEE_FLOAT));
+
+        let e = FormatExtensible::from_bytes(&data);
+        assert_eq!(e.sub_format_tag(), Some(FORMAT_IEEE_FLOAT));
+        assert_eq!(int::ForeignEndian::to_native(e.channel_mask), 0x3);
+        assert_eq!(format_tag(&data), Some(FORMAT_IEEE_FLOAT));
+        assert_eq!(format_tag(&data[..16]), None);
+
+        data[30] = 0xff;
+        assert_eq!(format_tag(&data), Some(FORMAT_EXTENSIBLE));
+
+        let f = Fact::from_bytes(&[0x40, 0x1f, 0x00, 0x00]);
+        assert_eq!(int::ForeignEndian::to_native(f.sample_length), 8000);
+    }
+}
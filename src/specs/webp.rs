@@ -0,0 +1,380 @@
+//! WebP Container
+//!
+//! WebP images are stored in a RIFF container of form type `WEBP` (see
+//! `riff`). The simple formats consist of a single `VP8 ` chunk with a lossy
+//! VP8 key frame, or a single `VP8L` chunk with a lossless bitstream. The
+//! extended format starts with a `VP8X` chunk, which carries the canvas size
+//! and feature flags, followed by optional `ICCP`, `ANIM`/`ANMF`, `ALPH`,
+//! `EXIF`, and `XMP ` chunks around the image data.
+//!
+//! Dimensions are stored as odd-width integers. `VP8X` and `ANMF` store the
+//! canvas and frame sizes minus one in 24-bit fields. `VP8L` packs 14-bit
+//! dimensions minus one into a 32-bit bitfield, and VP8 stores 14-bit
+//! dimensions along with a 2-bit scaling code in 16-bit fields.
+//!
+//! All multi-byte integers are encoded as little-endian.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::{int, riff};
+
+/// Form Type
+///
+/// The form type of the RIFF header of a WebP file.
+pub const FORM: [u8; 4] = *b"WEBP";
+
+pub const CHUNK_VP8: [u8; 4] = *b"VP8 ";
+pub const CHUNK_VP8L: [u8; 4] = *b"VP8L";
+pub const CHUNK_VP8X: [u8; 4] = *b"VP8X";
+pub const CHUNK_ALPH: [u8; 4] = *b"ALPH";
+pub const CHUNK_ANIM: [u8; 4] = *b"ANIM";
+pub const CHUNK_ANMF: [u8; 4] = *b"ANMF";
+pub const CHUNK_ICCP: [u8; 4] = *b"ICCP";
+pub const CHUNK_EXIF: [u8; 4] = *b"EXIF";
+pub const CHUNK_XMP: [u8; 4] = *b"XMP ";
+
+/* reserved: 0x80, 0x40 */
+pub const VP8X_ICC: u8 = 0x20;
+pub const VP8X_ALPHA: u8 = 0x10;
+pub const VP8X_EXIF: u8 = 0x08;
+pub const VP8X_XMP: u8 = 0x04;
+pub const VP8X_ANIMATION: u8 = 0x02;
+/* reserved: 0x01 */
+
+/* reserved: 0xfc */
+pub const ANMF_NO_BLEND: u8 = 0x02;
+pub const ANMF_DISPOSE_BACKGROUND: u8 = 0x01;
+
+/// VP8L Signature
+///
+/// The first byte of a `VP8L` chunk payload.
+pub const VP8L_SIGNATURE: u8 = 0x2f;
+
+/// VP8 Start Code
+///
+/// The start code following the frame tag of a VP8 key frame.
+pub const VP8_START_CODE: [u8; 3] = [0x9d, 0x01, 0x2a];
+
+/// Extended Format Header
+///
+/// The payload of a `VP8X` chunk.
+#[repr(C)]
+pub struct Vp8x {
+    /// Feature flags (`VP8X_*`).
+    pub flags: u8,
+
+    /// Reserved, must be 0.
+    pub reserved: [u8; 3],
+
+    /// Canvas width minus one.
+    pub canvas_width_minus_one: int::u24le,
+
+    /// Canvas height minus one.
+    pub canvas_height_minus_one: int::u24le,
+}
+
+/// Lossless Header
+///
+/// The start of a `VP8L` chunk payload. Since the bitfield is not naturally
+/// aligned, this structure is packed.
+#[repr(C, packed)]
+pub struct Vp8l {
+    /// Signature, must be `VP8L_SIGNATURE`.
+    pub signature: u8,
+
+    /// Image width minus one (14 bits), image height minus one (14 bits),
+    /// alpha hint (1 bit), and version (3 bits, must be 0), starting at the
+    /// least significant bit.
+    pub bits: int::u32le,
+}
+
+/// Lossy Key Frame Header
+///
+/// The start of a `VP8 ` chunk payload.
+#[repr(C)]
+pub struct Vp8 {
+    /// Frame tag: key frame flag (1 bit, 0 for key frames), version (3
+    /// bits), show frame flag (1 bit), and size of the first partition (19
+    /// bits), starting at the least significant bit.
+    pub frame_tag: int::u24le,
+
+    /// Start code, must be `VP8_START_CODE`.
+    pub start_code: [u8; 3],
+
+    /// Width (14 bits) and horizontal scale (2 bits).
+    pub width: int::u16le,
+
+    /// Height (14 bits) and vertical scale (2 bits).
+    pub height: int::u16le,
+}
+
+/// Animation Parameters
+///
+/// The payload of an `ANIM` chunk.
+#[repr(C)]
+pub struct Anim {
+    /// Background color in blue, green, red, alpha byte order.
+    pub background_color: [u8; 4],
+
+    /// Number of loops, 0 means infinite.
+    pub loop_count: int::u16le,
+}
+
+/// Animation Frame Header
+///
+/// The start of an `ANMF` chunk payload. It is followed by the chunks of the
+/// frame image.
+#[repr(C)]
+pub struct Anmf {
+    /// Horizontal offset of the frame divided by 2.
+    pub x_div2: int::u24le,
+
+    /// Vertical offset of the frame divided by 2.
+    pub y_div2: int::u24le,
+
+    /// Frame width minus one.
+    pub width_minus_one: int::u24le,
+
+    /// Frame height minus one.
+    pub height_minus_one: int::u24le,
+
+    /// Frame duration in milliseconds.
+    pub duration: int::u24le,
+
+    /// Flags (`ANMF_*`).
+    pub flags: u8,
+}
+
+impl Vp8x {
+    /// Import a header from a byte slice
+    ///
+    /// Create a new header from data copied from a byte slice. No byte-order
+    /// conversions are applied.
+    pub fn from_bytes(data: &[u8; 10]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 10], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Return canvas size
+    ///
+    /// Return the canvas size as `(width, height)` in pixels.
+    pub fn canvas_size(&self) -> (u32, u32) {
+        (
+            self.canvas_width_minus_one.to_native() + 1,
+            self.canvas_height_minus_one.to_native() + 1,
+        )
+    }
+}
+
+impl Vp8l {
+    /// Import a header from a byte slice
+    ///
+    /// Create a new header from data copied from a byte slice. No byte-order
+    /// conversions are applied.
+    pub fn from_bytes(data: &[u8; 5]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 5], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Return image size
+    ///
+    /// Return the image size as `(width, height)` in pixels.
+    pub fn size(&self) -> (u32, u32) {
+        let v = int::ForeignEndian::to_native(self.bits);
+        ((v & 0x3fff) + 1, ((v >> 14) & 0x3fff) + 1)
+    }
+
+    /// Return alpha hint
+    pub fn has_alpha(&self) -> bool {
+        (int::ForeignEndian::to_native(self.bits) >> 28) & 1 != 0
+    }
+
+    /// Return version
+    pub fn version(&self) -> u8 {
+        (int::ForeignEndian::to_native(self.bits) >> 29) as u8
+    }
+}
+
+impl Vp8 {
+    /// Import a header from a byte slice
+    ///
+    /// Create a new header from data copied from a byte slice. No byte-order
+    /// conversions are applied.
+    pub fn from_bytes(data: &[u8; 10]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 10], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Check for key frames
+    pub fn is_key_frame(&self) -> bool {
+        self.frame_tag.to_native() & 1 == 0
+    }
+
+    /// Return size of the first partition
+    pub fn first_partition_size(&self) -> u32 {
+        self.frame_tag.to_native() >> 5
+    }
+
+    /// Return image size
+    ///
+    /// Return the image size as `(width, height)` in pixels, ignoring the
+    /// scaling codes.
+    pub fn size(&self) -> (u16, u16) {
+        (
+            int::ForeignEndian::to_native(self.width) & 0x3fff,
+            int::ForeignEndian::to_native(self.height) & 0x3fff,
+        )
+    }
+}
+
+impl Anmf {
+    /// Import a frame header from a byte slice
+    ///
+    /// Create a new frame header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 16]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 16], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+/// Determine Image Size
+///
+/// Walk the chunks of a WebP file and return the canvas size as `(width,
+/// height)` in pixels, taken from the first `VP8X`, `VP8L`, or `VP8 ` chunk.
+/// `None` is returned if the file is not a WebP file, or no such chunk is
+/// found.
+pub fn size(data: &[u8]) -> Option<(u32, u32)> {
+    let (form, chunks) = riff::parse(data)?;
+    if form != FORM {
+        return None;
+    }
+
+    for c in chunks {
+        match c.id {
+            CHUNK_VP8X => {
+                let h = Vp8x::from_bytes(c.data.get(..10)?.try_into().unwrap());
+                return Some(h.canvas_size());
+            },
+            CHUNK_VP8L => {
+                let h = Vp8l::from_bytes(c.data.get(..5)?.try_into().unwrap());
+                if h.signature != VP8L_SIGNATURE {
+                    return None;
+                }
+                return Some(h.size());
+            },
+            CHUNK_VP8 => {
+                let h = Vp8::from_bytes(c.data.get(..10)?.try_into().unwrap());
+                if h.start_code != VP8_START_CODE {
+                    return None;
+                }
+                let (w, h) = h.size();
+                return Some((w as u32, h as u32));
+            },
+            _ => {},
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // An extended file with alpha, and a lossless 400x300 image.
+    const EXTENDED: [u8; 44] = [
+        0x52, 0x49, 0x46, 0x46, 0x24, 0x00, 0x00, 0x00,
+        0x57, 0x45, 0x42, 0x50, 0x56, 0x50, 0x38, 0x58,
+        0x0a, 0x00, 0x00, 0x00, 0x10, 0x00, 0x00, 0x00,
+        0x8f, 0x01, 0x00, 0x2b, 0x01, 0x00, 0x56, 0x50,
+        0x38, 0x4c, 0x05, 0x00, 0x00, 0x00, 0x2f, 0x8f,
+        0xc1, 0x4a, 0x10, 0x00,
+    ];
+
+    // A lossy key frame header of a 640x480 image.
+    const VP8: [u8; 10] = [
+        0x90, 0x46, 0x02, 0x9d, 0x01, 0x2a, 0x80, 0x02,
+        0xe0, 0x01,
+    ];
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<Vp8x>(), 10);
+        assert_eq!(align_of::<Vp8x>(), 1);
+        assert_eq!(size_of::<Vp8l>(), 5);
+        assert_eq!(size_of::<Vp8>(), 10);
+        assert_eq!(align_of::<Vp8>(), 2);
+        assert_eq!(size_of::<Anim>(), 6);
+        assert_eq!(size_of::<Anmf>(), 16);
+        assert_eq!(align_of::<Anmf>(), 1);
+    }
+
+    // Parse the headers of an extended file.
+    #[test]
+    fn verify_extended() {
+        let (_, mut chunks) = riff::parse(&EXTENDED).unwrap();
+
+        let c = chunks.next().unwrap();
+        assert_eq!(c.id, CHUNK_VP8X);
+        let x = Vp8x::from_bytes(c.data.try_into().unwrap());
+        assert_eq!(x.flags, VP8X_ALPHA);
+        assert_eq!(x.canvas_size(), (400, 300));
+
+        let c = chunks.next().unwrap();
+        assert_eq!(c.id, CHUNK_VP8L);
+        let l = Vp8l::from_bytes(c.data.try_into().unwrap());
+        assert_eq!(l.size(), (400, 300));
+        assert!(l.has_alpha());
+        assert_eq!(l.version(), 0);
+
+        assert_eq!(size(&EXTENDED), Some((400, 300)));
+    }
+
+    // Parse a lossy key frame and verify 24-bit round-trips.
+    #[test]
+    fn verify_vp8() {
+        let h = Vp8::from_bytes(&VP8);
+        assert!(h.is_key_frame());
+        assert_eq!(h.first_partition_size(), 0x1234);
+        assert_eq!(h.size(), (640, 480));
+
+        let v = int::u24le::from_native(0x0102_0304);
+        assert_eq!(v.to_raw(), [0x04, 0x03, 0x02]);
+        assert_eq!(v.to_native(), 0x020304);
+        assert_eq!(int::u24be::from_native(0x020304).to_raw(), [0x02, 0x03, 0x04]);
+    }
+}
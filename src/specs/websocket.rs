@@ -0,0 +1,224 @@
+//! WebSocket Protocol
+//!
+//! After the opening handshake over HTTP, WebSocket endpoints exchange
+//! frames. Each frame starts with a 2-byte header carrying the FIN bit,
+//! three reserved bits for extensions, the opcode (`OPCODE_*`), the mask bit,
+//! and a 7-bit payload length. Lengths of 126 and 127 announce a 16-bit or a
+//! 64-bit extended length to follow, respectively. Masked frames then carry
+//! a 4-byte masking key, which is XORed onto the payload (see
+//! `apply_mask()`). Clients must mask all frames, servers must not. See
+//! `FrameHeader` to decode the variable parts.
+//!
+//! Control frames (close, ping, pong) must not be fragmented and carry at
+//! most `CONTROL_PAYLOAD_MAX` bytes. Close frames optionally start with a
+//! 2-byte status code (`CLOSE_*`), followed by a UTF-8 reason.
+//!
+//! All multi-byte integers are encoded as big-endian.
+
+pub const HEADER_SIZE_MIN: usize = 2;
+pub const HEADER_SIZE_MAX: usize = 14;
+pub const MASK_SIZE: usize = 4;
+pub const CONTROL_PAYLOAD_MAX: usize = 125;
+
+/// GUID Appended to the Key in the Opening Handshake
+pub const ACCEPT_GUID: &[u8; 36] = b"258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+pub const FLAG_FIN: u8 = 0x80;
+pub const FLAG_RSV1: u8 = 0x40;
+pub const FLAG_RSV2: u8 = 0x20;
+pub const FLAG_RSV3: u8 = 0x10;
+pub const OPCODE_MASK: u8 = 0x0f;
+pub const FLAG_MASK: u8 = 0x80;
+pub const LENGTH_MASK: u8 = 0x7f;
+
+pub const LENGTH_16: u8 = 126;
+pub const LENGTH_64: u8 = 127;
+
+pub const OPCODE_CONTINUATION: u8 = 0x0;
+pub const OPCODE_TEXT: u8 = 0x1;
+pub const OPCODE_BINARY: u8 = 0x2;
+pub const OPCODE_CLOSE: u8 = 0x8;
+pub const OPCODE_PING: u8 = 0x9;
+pub const OPCODE_PONG: u8 = 0xa;
+
+pub const CLOSE_NORMAL: u16 = 1000;
+pub const CLOSE_GOING_AWAY: u16 = 1001;
+pub const CLOSE_PROTOCOL_ERROR: u16 = 1002;
+pub const CLOSE_UNSUPPORTED_DATA: u16 = 1003;
+/* reserved: 1004 */
+pub const CLOSE_NO_STATUS: u16 = 1005;
+pub const CLOSE_ABNORMAL: u16 = 1006;
+pub const CLOSE_INVALID_PAYLOAD: u16 = 1007;
+pub const CLOSE_POLICY_VIOLATION: u16 = 1008;
+pub const CLOSE_MESSAGE_TOO_BIG: u16 = 1009;
+pub const CLOSE_MANDATORY_EXTENSION: u16 = 1010;
+pub const CLOSE_INTERNAL_ERROR: u16 = 1011;
+pub const CLOSE_TLS_HANDSHAKE: u16 = 1015;
+
+/// Frame Header
+///
+/// The decoded header of a frame, with the extended length and masking
+/// key located.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FrameHeader {
+    /// FIN and reserved bits (`FLAG_FIN`, `FLAG_RSV*`).
+    pub flags: u8,
+
+    /// Opcode (`OPCODE_*`).
+    pub opcode: u8,
+
+    /// Masking key, if the frame is masked.
+    pub mask: Option<[u8; 4]>,
+
+    /// Size of the payload in bytes.
+    pub length: u64,
+
+    /// Size of the header in bytes, after which the payload follows.
+    pub size: usize,
+}
+
+impl FrameHeader {
+    /// Parse a frame header
+    ///
+    /// Parse the frame header at the start of `data`. Return `None` if the
+    /// header is truncated, or if a 64-bit length has its most significant
+    /// bit set. Non-minimal length encodings are accepted.
+    pub fn parse(data: &[u8]) -> Option<Self> {
+        let b0 = *data.first()?;
+        let b1 = *data.get(1)?;
+
+        let (length, mut o) = match b1 & LENGTH_MASK {
+            LENGTH_16 => (u16::from_be_bytes(data.get(2..4)?.try_into().unwrap()) as u64, 4),
+            LENGTH_64 => {
+                let v = u64::from_be_bytes(data.get(2..10)?.try_into().unwrap());
+                if v >> 63 != 0 {
+                    return None;
+                }
+                (v, 10)
+            },
+            v => (v as u64, 2),
+        };
+
+        let mask = if b1 & FLAG_MASK != 0 {
+            o += MASK_SIZE;
+            Some(data.get(o - MASK_SIZE..o)?.try_into().unwrap())
+        } else {
+            None
+        };
+
+        Some(Self {
+            flags: b0 & !OPCODE_MASK,
+            opcode: b0 & OPCODE_MASK,
+            mask,
+            length,
+            size: o,
+        })
+    }
+
+    /// Encode a frame header
+    ///
+    /// Write the header to the start of `out`, using the shortest length
+    /// encoding, and return the number of bytes written, or `None` if the
+    /// buffer is too small. `size` is ignored.
+    pub fn encode(&self, out: &mut [u8]) -> Option<usize> {
+        let (b1, n) = match self.length {
+            0..=125 => (self.length as u8, 0),
+            126..=0xffff => (LENGTH_16, 2),
+            _ => (LENGTH_64, 8),
+        };
+        let m = if self.mask.is_some() { MASK_SIZE } else { 0 };
+        let bytes = out.get_mut(..2 + n + m)?;
+
+        bytes[0] = self.flags | (self.opcode & OPCODE_MASK);
+        bytes[1] = b1 | if self.mask.is_some() { FLAG_MASK } else { 0 };
+        bytes[2..2 + n].copy_from_slice(&self.length.to_be_bytes()[8 - n..]);
+        if let Some(mask) = self.mask {
+            bytes[2 + n..].copy_from_slice(&mask);
+        }
+
+        Some(2 + n + m)
+    }
+
+    /// Check for the final fragment
+    pub fn is_fin(&self) -> bool {
+        self.flags & FLAG_FIN != 0
+    }
+}
+
+/// Check for control frames
+pub fn is_control(opcode: u8) -> bool {
+    opcode & 0x8 != 0
+}
+
+/// Apply masking key
+///
+/// XOR the masking key onto `data` in place, which masks and unmasks alike.
+/// `offset` is the position of `data` within the payload, allowing the
+/// payload to be processed in pieces.
+pub fn apply_mask(mask: [u8; 4], offset: u64, data: &mut [u8]) {
+    for (i, b) in data.iter_mut().enumerate() {
+        *b ^= mask[((offset + i as u64) % 4) as usize];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A masked text frame from a client, carrying "Hello" (RFC 6455).
+    const FRAME: [u8; 11] = [
+        0x81, 0x85, 0x37, 0xfa, 0x21, 0x3d, 0x7f, 0x9f,
+        0x4d, 0x51, 0x58,
+    ];
+
+    // Parse and unmask a client frame, and encode it again.
+    #[test]
+    fn verify_masked() {
+        let h = FrameHeader::parse(&FRAME).unwrap();
+        assert!(h.is_fin());
+        assert_eq!(h.flags & (FLAG_RSV1 | FLAG_RSV2 | FLAG_RSV3), 0);
+        assert_eq!(h.opcode, OPCODE_TEXT);
+        assert_eq!(h.mask, Some([0x37, 0xfa, 0x21, 0x3d]));
+        assert_eq!(h.length, 5);
+        assert_eq!(h.size, 6);
+
+        let mut payload = [0; 5];
+        payload.copy_from_slice(&FRAME[6..]);
+        apply_mask(h.mask.unwrap(), 0, &mut payload[..2]);
+        apply_mask(h.mask.unwrap(), 2, &mut payload[2..]);
+        assert_eq!(&payload, b"Hello");
+
+        let mut buf = [0; HEADER_SIZE_MAX];
+        assert_eq!(h.encode(&mut buf), Some(6));
+        assert_eq!(buf[..6], FRAME[..6]);
+        assert!(FrameHeader::parse(&FRAME[..5]).is_none());
+    }
+
+    // Parse and encode extended lengths and control frames.
+    #[test]
+    fn verify_lengths() {
+        let h = FrameHeader::parse(&[0x82, 0x7e, 0x01, 0x00]).unwrap();
+        assert_eq!(h.opcode, OPCODE_BINARY);
+        assert_eq!(h.mask, None);
+        assert_eq!((h.length, h.size), (256, 4));
+
+        let data = [0x02, 0x7f, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00];
+        let h = FrameHeader::parse(&data).unwrap();
+        assert!(!h.is_fin());
+        assert_eq!((h.length, h.size), (65536, 10));
+
+        let mut buf = [0; HEADER_SIZE_MAX];
+        assert_eq!(h.encode(&mut buf), Some(10));
+        assert_eq!(buf[..10], data);
+        assert!(h.encode(&mut buf[..9]).is_none());
+
+        let mut data = data;
+        data[2] = 0x80;
+        assert!(FrameHeader::parse(&data).is_none());
+
+        let h = FrameHeader::parse(&[0x88, 0x02, 0x03, 0xe8]).unwrap();
+        assert!(is_control(h.opcode));
+        assert!(!is_control(OPCODE_CONTINUATION));
+        assert_eq!(u16::from_be_bytes([0x03, 0xe8]), CLOSE_NORMAL);
+    }
+}
@@ -0,0 +1,336 @@
+//! Windows Imaging Format
+//!
+//! The Windows Imaging Format (WIM) is a file-based disk image format used
+//! by Microsoft to deploy Windows. A single WIM file can contain multiple
+//! images, which share a single-instance store of file contents (resources).
+//! File contents are identified by their SHA-1 hash and stored only once.
+//!
+//! A WIM file starts with a fixed 208-byte `Header`. It references the other
+//! top-level resources of the file: the lookup table, the XML data, the boot
+//! metadata, and the integrity table. Each reference is a `ResourceHeader`,
+//! which specifies the location, size, and flags of a resource.
+//!
+//! The lookup table is an array of `LookupEntry` structures, one for each
+//! resource stored in the file. Each entry maps a SHA-1 hash to its resource.
+//! Metadata resources (one per image) describe the directory tree of an image
+//! and are flagged with `RESOURCE_METADATA`.
+//!
+//! The integrity table is an optional array of SHA-1 hashes over consecutive
+//! chunks of the file, starting after the header and covering everything up
+//! to the end of the lookup table.
+//!
+//! Compressed resources are split into chunks (usually 32KiB) that are
+//! compressed individually, preceded by a chunk table. Note that resource
+//! headers are packed into 24 bytes by storing the flags in the most
+//! significant byte of the 64-bit size field.
+//!
+//! Resource headers are not naturally aligned in the header or the lookup
+//! table, hence all structures in this module are packed. All multi-byte
+//! integers are encoded as little-endian.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::int;
+
+/// Magic Signature
+///
+/// The initial 8 bytes of every WIM file ("MSWIM" padded with zeroes).
+pub const MAGIC: [u8; 8] = [0x4d, 0x53, 0x57, 0x49, 0x4d, 0x00, 0x00, 0x00];
+
+/// Size of the Header
+pub const HEADER_SIZE: usize = 208;
+
+/// Format Version
+///
+/// The version used by all WIM files since Windows Vista. Files with solid
+/// resources (introduced with Windows 8) use `VERSION_SOLID` instead.
+pub const VERSION: u32 = 0x0001_0d00;
+pub const VERSION_SOLID: u32 = 0x0000_0e00;
+
+/// Default Chunk Size
+///
+/// The default uncompressed size of the chunks of a compressed resource.
+pub const CHUNK_SIZE_DEFAULT: u32 = 32 * 1024;
+
+/// Default Integrity Chunk Size
+///
+/// The default size of the chunks hashed by the integrity table.
+pub const INTEGRITY_CHUNK_SIZE_DEFAULT: u32 = 10 * 1024 * 1024;
+
+/// Size of SHA-1 Hashes
+pub const HASH_SIZE: usize = 20;
+
+pub const FLAG_RESERVED: u32 = 0x0000_0001;
+pub const FLAG_COMPRESSION: u32 = 0x0000_0002;
+pub const FLAG_READ_ONLY: u32 = 0x0000_0004;
+pub const FLAG_SPANNED: u32 = 0x0000_0008;
+pub const FLAG_RESOURCE_ONLY: u32 = 0x0000_0010;
+pub const FLAG_METADATA_ONLY: u32 = 0x0000_0020;
+pub const FLAG_WRITE_IN_PROGRESS: u32 = 0x0000_0040;
+pub const FLAG_RP_FIX: u32 = 0x0000_0080;
+pub const FLAG_COMPRESS_RESERVED: u32 = 0x0001_0000;
+pub const FLAG_COMPRESS_XPRESS: u32 = 0x0002_0000;
+pub const FLAG_COMPRESS_LZX: u32 = 0x0004_0000;
+pub const FLAG_COMPRESS_LZMS: u32 = 0x0008_0000;
+
+pub const RESOURCE_FREE: u8 = 0x01;
+pub const RESOURCE_METADATA: u8 = 0x02;
+pub const RESOURCE_COMPRESSED: u8 = 0x04;
+pub const RESOURCE_SPANNED: u8 = 0x08;
+pub const RESOURCE_SOLID: u8 = 0x10;
+
+/// Maximum Resource Size
+///
+/// Resource sizes are stored in 56 bits, since the upper 8 bits of the size
+/// field carry the resource flags.
+pub const RESOURCE_SIZE_MAX: u64 = 0x00ff_ffff_ffff_ffff;
+
+/// Resource Header
+///
+/// Describes location and size of a resource (RESHDR_DISK_SHORT). The size of
+/// the resource as stored in the file and the resource flags share a single
+/// 64-bit field, see `size()` and `flags()`.
+#[repr(C, packed)]
+pub struct ResourceHeader {
+    /// Stored size of the resource in the lower 56 bits, resource flags
+    /// (`RESOURCE_*`) in the upper 8 bits.
+    pub size_and_flags: int::u64le,
+
+    /// Absolute file offset of the resource.
+    pub offset: int::u64le,
+
+    /// Uncompressed size of the resource.
+    pub original_size: int::u64le,
+}
+
+/// File Header
+///
+/// The static header at offset 0 of every WIM file (WIMHEADER_V1_PACKED).
+#[repr(C, packed)]
+pub struct Header {
+    /// The static signature identifying the format. This must match `MAGIC`.
+    pub image_tag: [u8; 8],
+
+    /// Size of this header in bytes, must be `HEADER_SIZE`.
+    pub cb_size: int::u32le,
+
+    /// Format version, usually `VERSION`.
+    pub version: int::u32le,
+
+    /// Header flags (`FLAG_*`), including the compression type.
+    pub flags: int::u32le,
+
+    /// Uncompressed chunk size of compressed resources.
+    pub compression_size: int::u32le,
+
+    /// Unique identifier of this WIM file. All parts of a split WIM share the
+    /// same GUID.
+    pub guid: [u8; 16],
+
+    /// Part number of this file in a split WIM, starting at 1.
+    pub part_number: int::u16le,
+
+    /// Total number of parts of a split WIM.
+    pub total_parts: int::u16le,
+
+    /// Number of images stored in this WIM.
+    pub image_count: int::u32le,
+
+    /// Location of the lookup table.
+    pub offset_table: ResourceHeader,
+
+    /// Location of the XML data, encoded in UTF-16LE.
+    pub xml_data: ResourceHeader,
+
+    /// Location of the metadata resource of the bootable image.
+    pub boot_metadata: ResourceHeader,
+
+    /// Index of the bootable image, starting at 1, or 0 if none.
+    pub boot_index: int::u32le,
+
+    /// Location of the integrity table, or all zero if not present.
+    pub integrity: ResourceHeader,
+
+    /// Unused, must be zero.
+    pub unused: [u8; 60],
+}
+
+/// Lookup Table Entry
+///
+/// Describes a single resource stored in the file and maps it to the SHA-1
+/// hash of its uncompressed content.
+#[repr(C, packed)]
+pub struct LookupEntry {
+    /// Location of the resource.
+    pub resource: ResourceHeader,
+
+    /// Part number of the split WIM that contains this resource.
+    pub part_number: int::u16le,
+
+    /// Number of references to this resource from all images.
+    pub ref_count: int::u32le,
+
+    /// SHA-1 hash of the uncompressed content.
+    pub hash: [u8; HASH_SIZE],
+}
+
+/// Integrity Table Header
+///
+/// The header of the integrity table. It is followed by `num_elements` SHA-1
+/// hashes, each covering `chunk_size` bytes of the file (the last chunk can
+/// be shorter).
+#[repr(C, packed)]
+pub struct IntegrityHeader {
+    /// Total size of the integrity table in bytes, including this header.
+    pub cb_size: int::u32le,
+
+    /// Number of hashes following this header.
+    pub num_elements: int::u32le,
+
+    /// Number of bytes covered by each hash.
+    pub chunk_size: int::u32le,
+}
+
+impl ResourceHeader {
+    /// Create resource header
+    ///
+    /// Create a new resource header from its native values. The size must not
+    /// exceed `RESOURCE_SIZE_MAX`.
+    pub fn new(size: u64, flags: u8, offset: u64, original_size: u64) -> Self {
+        assert!(size <= RESOURCE_SIZE_MAX);
+
+        Self {
+            size_and_flags: (size | ((flags as u64) << 56)).into(),
+            offset: offset.into(),
+            original_size: original_size.into(),
+        }
+    }
+
+    /// Return stored size
+    ///
+    /// Return the size of the resource as stored in the file.
+    pub fn size(&self) -> u64 {
+        int::ForeignEndian::to_native(self.size_and_flags) & RESOURCE_SIZE_MAX
+    }
+
+    /// Return resource flags
+    ///
+    /// Return the resource flags (`RESOURCE_*`).
+    pub fn flags(&self) -> u8 {
+        (int::ForeignEndian::to_native(self.size_and_flags) >> 56) as u8
+    }
+}
+
+impl Header {
+    /// Import a header from a byte slice
+    ///
+    /// Create a new header structure from a byte slice, copying the data over.
+    /// The data is copied verbatim without any conversion.
+    pub fn from_bytes(data: &[u8; 208]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 208], *data);
+            uninit.assume_init()
+        }
+    }
+
+    /// Convert to byte slice
+    ///
+    /// Return a byte-slice reference to the header. No byte-order conversions
+    /// are applied.
+    pub fn as_bytes(&self) -> &[u8; 208] {
+        assert!(align_of::<[u8; 208]>() <= align_of::<Self>());
+        assert!(size_of::<[u8; 208]>() == size_of::<Self>());
+
+        unsafe {
+            core::mem::transmute::<&Self, &[u8; 208]>(self)
+        }
+    }
+}
+
+impl LookupEntry {
+    /// Import a lookup table entry from a byte slice
+    ///
+    /// Create a new lookup table entry from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 50]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 50], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<ResourceHeader>(), 24);
+        assert_eq!(align_of::<ResourceHeader>(), 1);
+        assert_eq!(size_of::<Header>(), HEADER_SIZE);
+        assert_eq!(align_of::<Header>(), 1);
+        assert_eq!(size_of::<LookupEntry>(), 50);
+        assert_eq!(align_of::<LookupEntry>(), 1);
+        assert_eq!(size_of::<IntegrityHeader>(), 12);
+        assert_eq!(align_of::<IntegrityHeader>(), 1);
+    }
+
+    // Verify the packed size and flags field of resource headers.
+    #[test]
+    fn verify_resource_header() {
+        let r = ResourceHeader::new(0x1234, RESOURCE_METADATA | RESOURCE_COMPRESSED, 0xd0, 0x8000);
+
+        assert_eq!(r.size(), 0x1234);
+        assert_eq!(r.flags(), 0x06);
+        assert_eq!(
+            int::ForeignEndian::to_native(r.size_and_flags),
+            0x0600_0000_0000_1234,
+        );
+
+        let r = ResourceHeader::new(RESOURCE_SIZE_MAX, 0xff, 0, 0);
+        assert_eq!(r.size(), RESOURCE_SIZE_MAX);
+        assert_eq!(r.flags(), 0xff);
+    }
+
+    // Verify field offsets of the header by decoding a synthetic header.
+    #[test]
+    fn verify_header() {
+        let mut data = [0u8; 208];
+        data[..8].copy_from_slice(&MAGIC);
+        data[8] = 0xd0;
+        data[12..16].copy_from_slice(&VERSION.to_le_bytes());
+        data[44] = 0x02;
+        data[48] = 0x32;
+        data[55] = RESOURCE_COMPRESSED;
+        data[120] = 0x01;
+        data[124] = 0x44;
+
+        let h = Header::from_bytes(&data);
+
+        assert_eq!(h.image_tag, MAGIC);
+        assert_eq!(int::ForeignEndian::to_native(h.cb_size), HEADER_SIZE as u32);
+        assert_eq!(int::ForeignEndian::to_native(h.version), VERSION);
+        assert_eq!(int::ForeignEndian::to_native(h.image_count), 2);
+        assert_eq!(h.offset_table.size(), 0x32);
+        assert_eq!(h.offset_table.flags(), RESOURCE_COMPRESSED);
+        assert_eq!(int::ForeignEndian::to_native(h.boot_index), 1);
+        assert_eq!(h.integrity.size(), 0x44);
+        assert_eq!(h.as_bytes(), &data);
+    }
+}
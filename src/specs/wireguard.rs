@@ -0,0 +1,319 @@
+//! WireGuard
+//!
+//! WireGuard exchanges messages in UDP datagrams, with one message per
+//! datagram. Each message starts with a 1-byte message type followed by 3
+//! reserved bytes, which are combined into a single 32-bit `message_type`
+//! field here (see `MESSAGE_*`). The handshake messages (`Initiation`,
+//! `Response`, and `CookieReply`) have a fixed size each, while transport
+//! data messages (`Transport`) carry a 16-byte header followed by the
+//! encrypted packet, padded to a multiple of 16 bytes before encryption and
+//! followed by the authentication tag.
+//!
+//! All fields but the message type, sender and receiver indices, and counter
+//! are opaque keys, encrypted data, or MACs. The MACs of handshake messages
+//! are calculated over all preceding bytes of the message (see
+//! `INITIATION_MAC1_OFFSET` and `RESPONSE_MAC1_OFFSET`), so they can be
+//! validated passively if the public key of the responder is known.
+//! `classify()` returns the message type of a datagram if its size is valid
+//! for that type.
+//!
+//! Unlike most network protocols, all multi-byte integers are encoded as
+//! little-endian.
+
+use core::mem::{
+    align_of,
+    align_of_val,
+    size_of,
+    size_of_val,
+};
+
+use crate::specs::int;
+
+pub const KEY_SIZE: usize = 32;
+pub const TAG_SIZE: usize = 16;
+pub const MAC_SIZE: usize = 16;
+pub const TIMESTAMP_SIZE: usize = 12;
+pub const COOKIE_SIZE: usize = 16;
+pub const COOKIE_NONCE_SIZE: usize = 24;
+
+pub const INITIATION_SIZE: usize = 148;
+pub const RESPONSE_SIZE: usize = 92;
+pub const COOKIE_REPLY_SIZE: usize = 64;
+pub const TRANSPORT_HEADER_SIZE: usize = 16;
+
+/// Size of Keepalive Messages
+///
+/// Keepalives are transport data messages with an empty packet, consisting
+/// only of the header and the authentication tag.
+pub const KEEPALIVE_SIZE: usize = 32;
+
+/// Padding of Encrypted Packets
+pub const PADDING: usize = 16;
+
+pub const INITIATION_MAC1_OFFSET: usize = 116;
+pub const INITIATION_MAC2_OFFSET: usize = 132;
+pub const RESPONSE_MAC1_OFFSET: usize = 60;
+pub const RESPONSE_MAC2_OFFSET: usize = 76;
+
+pub const MESSAGE_INITIATION: u32 = 1;
+pub const MESSAGE_RESPONSE: u32 = 2;
+pub const MESSAGE_COOKIE_REPLY: u32 = 3;
+pub const MESSAGE_TRANSPORT: u32 = 4;
+
+/// Noise Protocol Construction
+pub const CONSTRUCTION: &[u8] = b"Noise_IKpsk2_25519_ChaChaPoly_BLAKE2s";
+
+/// Protocol Identifier
+pub const IDENTIFIER: &[u8] = b"WireGuard v1 zx2c4 Jason@zx2c4.com";
+
+pub const LABEL_MAC1: &[u8] = b"mac1----";
+pub const LABEL_COOKIE: &[u8] = b"cookie--";
+
+/// Handshake Initiation Message
+#[repr(C)]
+pub struct Initiation {
+    /// Message type (`MESSAGE_INITIATION`), including the reserved bytes.
+    pub message_type: int::u32le,
+
+    /// Index chosen by the initiator to identify this session.
+    pub sender: int::u32le,
+
+    /// Unencrypted ephemeral public key of the initiator.
+    pub ephemeral: [u8; 32],
+
+    /// Encrypted static public key of the initiator, followed by the tag.
+    pub encrypted_static: [u8; 48],
+
+    /// Encrypted TAI64N timestamp, followed by the tag.
+    pub encrypted_timestamp: [u8; 28],
+
+    /// MAC over all preceding bytes, keyed by the responder's public key.
+    pub mac1: [u8; 16],
+
+    /// MAC over all preceding bytes, keyed by a cookie, or all 0.
+    pub mac2: [u8; 16],
+}
+
+/// Handshake Response Message
+#[repr(C)]
+pub struct Response {
+    /// Message type (`MESSAGE_RESPONSE`), including the reserved bytes.
+    pub message_type: int::u32le,
+
+    /// Index chosen by the responder to identify this session.
+    pub sender: int::u32le,
+
+    /// Index of the initiator, copied from the `Initiation`.
+    pub receiver: int::u32le,
+
+    /// Unencrypted ephemeral public key of the responder.
+    pub ephemeral: [u8; 32],
+
+    /// Tag of the encrypted empty payload.
+    pub encrypted_nothing: [u8; 16],
+
+    /// MAC over all preceding bytes, keyed by the initiator's public key.
+    pub mac1: [u8; 16],
+
+    /// MAC over all preceding bytes, keyed by a cookie, or all 0.
+    pub mac2: [u8; 16],
+}
+
+/// Cookie Reply Message
+#[repr(C)]
+pub struct CookieReply {
+    /// Message type (`MESSAGE_COOKIE_REPLY`), including the reserved bytes.
+    pub message_type: int::u32le,
+
+    /// Sender index of the handshake message this replies to.
+    pub receiver: int::u32le,
+
+    /// Random nonce of the encrypted cookie.
+    pub nonce: [u8; 24],
+
+    /// Encrypted cookie, followed by the tag.
+    pub encrypted_cookie: [u8; 32],
+}
+
+/// Transport Data Message Header
+#[repr(C)]
+pub struct Transport {
+    /// Message type (`MESSAGE_TRANSPORT`), including the reserved bytes.
+    pub message_type: int::u32le,
+
+    /// Sender index of the peer this message is sent to.
+    pub receiver: int::u32le,
+
+    /// Nonce counter of the encrypted packet.
+    pub counter: int::u64le,
+}
+
+impl Initiation {
+    /// Import a message from a byte slice
+    ///
+    /// Create a new message from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 148]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 148], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl Response {
+    /// Import a message from a byte slice
+    ///
+    /// Create a new message from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 92]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 92], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl CookieReply {
+    /// Import a message from a byte slice
+    ///
+    /// Create a new message from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 64]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 64], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+impl Transport {
+    /// Import a header from a byte slice
+    ///
+    /// Create a new header from data copied from a byte slice. No
+    /// byte-order conversions are applied.
+    pub fn from_bytes(data: &[u8; 16]) -> Self {
+        let mut uninit: core::mem::MaybeUninit<Self> = core::mem::MaybeUninit::uninit();
+
+        assert!(align_of_val(data) <= align_of::<Self>());
+        assert!(size_of_val(data) == size_of::<Self>());
+
+        unsafe {
+            core::ptr::write(uninit.as_mut_ptr() as *mut [u8; 16], *data);
+            uninit.assume_init()
+        }
+    }
+}
+
+/// Classify a message
+///
+/// Return the message type (`MESSAGE_*`) of a datagram, or `None` if the
+/// type is unknown, the reserved bytes are not 0, or the size of the datagram
+/// is not valid for its type. Transport data messages must be at least
+/// `KEEPALIVE_SIZE` bytes long, and a multiple of `PADDING` bytes.
+pub fn classify(data: &[u8]) -> Option<u32> {
+    let v = u32::from_le_bytes(data.get(..4)?.try_into().ok()?);
+    let n = data.len();
+    match v {
+        MESSAGE_INITIATION if n == INITIATION_SIZE => Some(v),
+        MESSAGE_RESPONSE if n == RESPONSE_SIZE => Some(v),
+        MESSAGE_COOKIE_REPLY if n == COOKIE_REPLY_SIZE => Some(v),
+        MESSAGE_TRANSPORT if n >= KEEPALIVE_SIZE && n & (PADDING - 1) == 0 => Some(v),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::specs::int::ForeignEndian;
+
+    #[test]
+    fn verify_types() {
+        assert_eq!(size_of::<Initiation>(), INITIATION_SIZE);
+        assert_eq!(align_of::<Initiation>(), 4);
+        assert_eq!(size_of::<Response>(), RESPONSE_SIZE);
+        assert_eq!(align_of::<Response>(), 4);
+        assert_eq!(size_of::<CookieReply>(), COOKIE_REPLY_SIZE);
+        assert_eq!(align_of::<CookieReply>(), 4);
+        assert_eq!(size_of::<Transport>(), TRANSPORT_HEADER_SIZE);
+        assert_eq!(align_of::<Transport>(), 8);
+
+        assert_eq!(core::mem::offset_of!(Initiation, mac1), INITIATION_MAC1_OFFSET);
+        assert_eq!(core::mem::offset_of!(Initiation, mac2), INITIATION_MAC2_OFFSET);
+        assert_eq!(core::mem::offset_of!(Response, mac1), RESPONSE_MAC1_OFFSET);
+        assert_eq!(core::mem::offset_of!(Response, mac2), RESPONSE_MAC2_OFFSET);
+    }
+
+    // Decode handshake messages with little-endian indices.
+    #[test]
+    fn verify_handshake() {
+        let mut data = [0u8; 148];
+        data[..8].copy_from_slice(&[0x01, 0x00, 0x00, 0x00, 0x78, 0x56, 0x34, 0x12]);
+        data[8] = 0xaa;
+        data[116] = 0xbb;
+        let m = Initiation::from_bytes(&data);
+        assert_eq!(m.message_type.to_native(), MESSAGE_INITIATION);
+        assert_eq!(m.sender.to_native(), 0x12345678);
+        assert_eq!(m.ephemeral[0], 0xaa);
+        assert_eq!(m.mac1[0], 0xbb);
+        assert_eq!(m.mac2, [0; 16]);
+        assert_eq!(classify(&data), Some(MESSAGE_INITIATION));
+        assert_eq!(classify(&data[..147]), None);
+
+        data[1] = 0x01;
+        assert_eq!(classify(&data), None);
+
+        let mut data = [0u8; 92];
+        data[..12].copy_from_slice(&[
+            0x02, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00,
+            0x78, 0x56, 0x34, 0x12,
+        ]);
+        let m = Response::from_bytes(&data);
+        assert_eq!(m.message_type.to_native(), MESSAGE_RESPONSE);
+        assert_eq!(m.sender.to_native(), 1);
+        assert_eq!(m.receiver.to_native(), 0x12345678);
+        assert_eq!(classify(&data), Some(MESSAGE_RESPONSE));
+
+        let mut data = [0u8; 64];
+        data[0] = 0x03;
+        let m = CookieReply::from_bytes(&data);
+        assert_eq!(m.message_type.to_native(), MESSAGE_COOKIE_REPLY);
+        assert_eq!(classify(&data), Some(MESSAGE_COOKIE_REPLY));
+        assert_eq!(classify(&data[..3]), None);
+    }
+
+    // Decode transport data headers and verify size constraints.
+    #[test]
+    fn verify_transport() {
+        let mut data = [0u8; 48];
+        data[..16].copy_from_slice(&[
+            0x04, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00,
+            0x02, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x80,
+        ]);
+        let m = Transport::from_bytes(data[..16].try_into().unwrap());
+        assert_eq!(m.message_type.to_native(), MESSAGE_TRANSPORT);
+        assert_eq!(m.receiver.to_native(), 1);
+        assert_eq!(m.counter.to_native(), 0x8000_0000_0000_0102);
+
+        assert_eq!(classify(&data), Some(MESSAGE_TRANSPORT));
+        assert_eq!(classify(&data[..KEEPALIVE_SIZE]), Some(MESSAGE_TRANSPORT));
+        assert_eq!(classify(&data[..40]), None);
+        assert_eq!(classify(&data[..16]), None);
+    }
+}
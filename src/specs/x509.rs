@@ -0,0 +1,442 @@
+//! X.509 Public Key Infrastructure Certificates
+//!
+//! X.509 certificates (RFC 5280) are DER encodings (see `asn1`) of a
+//! SEQUENCE of the to-be-signed certificate (see `TbsCertificate`), the
+//! signature algorithm, and the signature as BIT STRING (see
+//! `Certificate`). The to-be-signed certificate is a SEQUENCE of the
+//! version, serial number, signature algorithm, issuer, validity, subject,
+//! and public key, optionally followed by the unique identifiers of issuer
+//! and subject, and the extensions (see `Extensions`), which are tagged with
+//! context-specific tags (`TAG_*`).
+//!
+//! Algorithms, extensions, extended key usages, and name attributes are
+//! identified by object identifiers. The `OID_*` constants hold the contents
+//! octets of their DER encodings, without identifier and length, so they can
+//! be compared directly against the contents of parsed OBJECT IDENTIFIER
+//! encodings.
+//!
+//! All multi-byte integers are encoded as big-endian.
+
+use crate::specs::asn1;
+
+pub const VERSION_1: u8 = 0;
+pub const VERSION_2: u8 = 1;
+pub const VERSION_3: u8 = 2;
+
+pub const TAG_VERSION: u32 = 0;
+pub const TAG_ISSUER_UNIQUE_ID: u32 = 1;
+pub const TAG_SUBJECT_UNIQUE_ID: u32 = 2;
+pub const TAG_EXTENSIONS: u32 = 3;
+
+pub const GENERAL_NAME_OTHER: u32 = 0;
+pub const GENERAL_NAME_RFC822: u32 = 1;
+pub const GENERAL_NAME_DNS: u32 = 2;
+pub const GENERAL_NAME_X400: u32 = 3;
+pub const GENERAL_NAME_DIRECTORY: u32 = 4;
+pub const GENERAL_NAME_EDI_PARTY: u32 = 5;
+pub const GENERAL_NAME_URI: u32 = 6;
+pub const GENERAL_NAME_IP_ADDRESS: u32 = 7;
+pub const GENERAL_NAME_REGISTERED_ID: u32 = 8;
+
+pub const KEY_USAGE_DIGITAL_SIGNATURE: u16 = 0x0001;
+pub const KEY_USAGE_NON_REPUDIATION: u16 = 0x0002;
+pub const KEY_USAGE_KEY_ENCIPHERMENT: u16 = 0x0004;
+pub const KEY_USAGE_DATA_ENCIPHERMENT: u16 = 0x0008;
+pub const KEY_USAGE_KEY_AGREEMENT: u16 = 0x0010;
+pub const KEY_USAGE_KEY_CERT_SIGN: u16 = 0x0020;
+pub const KEY_USAGE_CRL_SIGN: u16 = 0x0040;
+pub const KEY_USAGE_ENCIPHER_ONLY: u16 = 0x0080;
+pub const KEY_USAGE_DECIPHER_ONLY: u16 = 0x0100;
+
+pub const OID_RSA_ENCRYPTION: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x01];
+pub const OID_MD5_WITH_RSA: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x04];
+pub const OID_SHA1_WITH_RSA: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x05];
+pub const OID_RSASSA_PSS: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x0a];
+pub const OID_SHA256_WITH_RSA: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x0b];
+pub const OID_SHA384_WITH_RSA: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x0c];
+pub const OID_SHA512_WITH_RSA: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x0d];
+pub const OID_DSA: &[u8] = &[0x2a, 0x86, 0x48, 0xce, 0x38, 0x04, 0x01];
+pub const OID_DSA_WITH_SHA1: &[u8] = &[0x2a, 0x86, 0x48, 0xce, 0x38, 0x04, 0x03];
+pub const OID_EC_PUBLIC_KEY: &[u8] = &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x02, 0x01];
+pub const OID_ECDSA_WITH_SHA1: &[u8] = &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x04, 0x01];
+pub const OID_ECDSA_WITH_SHA256: &[u8] = &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x04, 0x03, 0x02];
+pub const OID_ECDSA_WITH_SHA384: &[u8] = &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x04, 0x03, 0x03];
+pub const OID_ECDSA_WITH_SHA512: &[u8] = &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x04, 0x03, 0x04];
+pub const OID_X25519: &[u8] = &[0x2b, 0x65, 0x6e];
+pub const OID_X448: &[u8] = &[0x2b, 0x65, 0x6f];
+pub const OID_ED25519: &[u8] = &[0x2b, 0x65, 0x70];
+pub const OID_ED448: &[u8] = &[0x2b, 0x65, 0x71];
+
+pub const OID_SECP256R1: &[u8] = &[0x2a, 0x86, 0x48, 0xce, 0x3d, 0x03, 0x01, 0x07];
+pub const OID_SECP384R1: &[u8] = &[0x2b, 0x81, 0x04, 0x00, 0x22];
+pub const OID_SECP521R1: &[u8] = &[0x2b, 0x81, 0x04, 0x00, 0x23];
+
+pub const OID_SUBJECT_KEY_IDENTIFIER: &[u8] = &[0x55, 0x1d, 0x0e];
+pub const OID_KEY_USAGE: &[u8] = &[0x55, 0x1d, 0x0f];
+pub const OID_SUBJECT_ALT_NAME: &[u8] = &[0x55, 0x1d, 0x11];
+pub const OID_ISSUER_ALT_NAME: &[u8] = &[0x55, 0x1d, 0x12];
+pub const OID_BASIC_CONSTRAINTS: &[u8] = &[0x55, 0x1d, 0x13];
+pub const OID_CRL_NUMBER: &[u8] = &[0x55, 0x1d, 0x14];
+pub const OID_NAME_CONSTRAINTS: &[u8] = &[0x55, 0x1d, 0x1e];
+pub const OID_CRL_DISTRIBUTION_POINTS: &[u8] = &[0x55, 0x1d, 0x1f];
+pub const OID_CERTIFICATE_POLICIES: &[u8] = &[0x55, 0x1d, 0x20];
+pub const OID_POLICY_MAPPINGS: &[u8] = &[0x55, 0x1d, 0x21];
+pub const OID_AUTHORITY_KEY_IDENTIFIER: &[u8] = &[0x55, 0x1d, 0x23];
+pub const OID_POLICY_CONSTRAINTS: &[u8] = &[0x55, 0x1d, 0x24];
+pub const OID_EXT_KEY_USAGE: &[u8] = &[0x55, 0x1d, 0x25];
+pub const OID_INHIBIT_ANY_POLICY: &[u8] = &[0x55, 0x1d, 0x36];
+pub const OID_AUTHORITY_INFO_ACCESS: &[u8] = &[0x2b, 0x06, 0x01, 0x05, 0x05, 0x07, 0x01, 0x01];
+pub const OID_SCT_LIST: &[u8] = &[0x2b, 0x06, 0x01, 0x04, 0x01, 0xd6, 0x79, 0x02, 0x04, 0x02];
+
+pub const OID_ANY_EXTENDED_KEY_USAGE: &[u8] = &[0x55, 0x1d, 0x25, 0x00];
+pub const OID_SERVER_AUTH: &[u8] = &[0x2b, 0x06, 0x01, 0x05, 0x05, 0x07, 0x03, 0x01];
+pub const OID_CLIENT_AUTH: &[u8] = &[0x2b, 0x06, 0x01, 0x05, 0x05, 0x07, 0x03, 0x02];
+pub const OID_CODE_SIGNING: &[u8] = &[0x2b, 0x06, 0x01, 0x05, 0x05, 0x07, 0x03, 0x03];
+pub const OID_EMAIL_PROTECTION: &[u8] = &[0x2b, 0x06, 0x01, 0x05, 0x05, 0x07, 0x03, 0x04];
+pub const OID_TIME_STAMPING: &[u8] = &[0x2b, 0x06, 0x01, 0x05, 0x05, 0x07, 0x03, 0x08];
+pub const OID_OCSP_SIGNING: &[u8] = &[0x2b, 0x06, 0x01, 0x05, 0x05, 0x07, 0x03, 0x09];
+
+pub const OID_OCSP: &[u8] = &[0x2b, 0x06, 0x01, 0x05, 0x05, 0x07, 0x30, 0x01];
+pub const OID_CA_ISSUERS: &[u8] = &[0x2b, 0x06, 0x01, 0x05, 0x05, 0x07, 0x30, 0x02];
+
+pub const OID_COMMON_NAME: &[u8] = &[0x55, 0x04, 0x03];
+pub const OID_SERIAL_NUMBER: &[u8] = &[0x55, 0x04, 0x05];
+pub const OID_COUNTRY_NAME: &[u8] = &[0x55, 0x04, 0x06];
+pub const OID_LOCALITY_NAME: &[u8] = &[0x55, 0x04, 0x07];
+pub const OID_STATE_OR_PROVINCE_NAME: &[u8] = &[0x55, 0x04, 0x08];
+pub const OID_ORGANIZATION_NAME: &[u8] = &[0x55, 0x04, 0x0a];
+pub const OID_ORGANIZATIONAL_UNIT_NAME: &[u8] = &[0x55, 0x04, 0x0b];
+pub const OID_EMAIL_ADDRESS: &[u8] = &[0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x09, 0x01];
+
+/// Certificate Reference
+#[derive(Clone, Copy, Debug)]
+pub struct Certificate<'data> {
+    /// To-be-signed certificate, as SEQUENCE (see `TbsCertificate`).
+    pub tbs_certificate: asn1::Tlv<'data>,
+
+    /// Signature algorithm, as AlgorithmIdentifier (see `algorithm()`).
+    pub signature_algorithm: asn1::Tlv<'data>,
+
+    /// Contents of the signature BIT STRING, including the leading number
+    /// of unused bits.
+    pub signature_value: &'data [u8],
+}
+
+/// To-be-signed Certificate Reference
+#[derive(Clone, Copy, Debug)]
+pub struct TbsCertificate<'data> {
+    /// Version (`VERSION_*`), `VERSION_1` if omitted.
+    pub version: u8,
+
+    /// Contents of the serial number INTEGER.
+    pub serial_number: &'data [u8],
+
+    /// Signature algorithm, as AlgorithmIdentifier (see `algorithm()`).
+    pub signature: asn1::Tlv<'data>,
+
+    /// Issuer name, as SEQUENCE of relative distinguished names.
+    pub issuer: asn1::Tlv<'data>,
+
+    /// Validity, as SEQUENCE of the start and end time.
+    pub validity: asn1::Tlv<'data>,
+
+    /// Subject name, as SEQUENCE of relative distinguished names.
+    pub subject: asn1::Tlv<'data>,
+
+    /// Subject public key, as SEQUENCE of the algorithm and key.
+    pub subject_public_key_info: asn1::Tlv<'data>,
+
+    /// Contents of the issuer unique identifier BIT STRING.
+    pub issuer_unique_id: Option<&'data [u8]>,
+
+    /// Contents of the subject unique identifier BIT STRING.
+    pub subject_unique_id: Option<&'data [u8]>,
+
+    /// Contents of the extensions SEQUENCE (see `Extensions`).
+    pub extensions: Option<&'data [u8]>,
+}
+
+/// Extension Reference
+#[derive(Clone, Copy, Debug)]
+pub struct Extension<'data> {
+    /// Contents of the extension identifier (`OID_*`).
+    pub id: &'data [u8],
+
+    /// Whether the extension is critical.
+    pub critical: bool,
+
+    /// Contents of the OCTET STRING wrapping the DER encoded value.
+    pub value: &'data [u8],
+}
+
+/// Extension Iterator
+///
+/// Iterate over the extensions of a certificate, given the contents of the
+/// extensions SEQUENCE. Iteration stops at the first invalid extension.
+#[derive(Clone, Debug)]
+pub struct Extensions<'data> {
+    data: asn1::Tlvs<'data>,
+}
+
+// Return the next encoding of `v` if it has the given universal tag.
+fn universal<'data>(v: &mut asn1::Tlvs<'data>, tag: u32) -> Option<asn1::Tlv<'data>> {
+    let mut peek = v.clone();
+    match peek.next() {
+        Some(t) if t.identifier.is_universal(tag) => {
+            *v = peek;
+            Some(t)
+        },
+        _ => None,
+    }
+}
+
+// Return the next encoding of `v` if it has the given context-specific tag.
+fn context<'data>(v: &mut asn1::Tlvs<'data>, tag: u32) -> Option<asn1::Tlv<'data>> {
+    let mut peek = v.clone();
+    match peek.next() {
+        Some(t) if t.identifier.class == asn1::CLASS_CONTEXT && t.identifier.tag == tag => {
+            *v = peek;
+            Some(t)
+        },
+        _ => None,
+    }
+}
+
+impl<'data> Certificate<'data> {
+    /// Parse a certificate
+    ///
+    /// Parse the DER encoded certificate at the start of `data`. Return
+    /// `None` if the outer structure is invalid. The to-be-signed
+    /// certificate is not parsed.
+    pub fn parse(data: &'data [u8]) -> Option<Self> {
+        let v = asn1::Tlv::parse_der(data)?;
+        if !v.identifier.is_universal(asn1::TAG_SEQUENCE) {
+            return None;
+        }
+
+        let mut c = v.children()?;
+        let tbs_certificate = universal(&mut c, asn1::TAG_SEQUENCE)?;
+        let signature_algorithm = universal(&mut c, asn1::TAG_SEQUENCE)?;
+        let signature_value = universal(&mut c, asn1::TAG_BIT_STRING)?.contents;
+
+        Some(Self { tbs_certificate, signature_algorithm, signature_value })
+    }
+
+    /// Parse the to-be-signed certificate
+    pub fn tbs(&self) -> Option<TbsCertificate<'data>> {
+        TbsCertificate::parse(self.tbs_certificate.contents)
+    }
+}
+
+impl<'data> TbsCertificate<'data> {
+    /// Parse a to-be-signed certificate
+    ///
+    /// Parse the contents of the to-be-signed certificate SEQUENCE. Return
+    /// `None` if a mandatory field is missing, or has an unexpected tag. The
+    /// fields themselves are not parsed.
+    pub fn parse(data: &'data [u8]) -> Option<Self> {
+        let mut c = asn1::Tlvs::new(data);
+
+        let version = match context(&mut c, TAG_VERSION) {
+            Some(v) => match asn1::Tlvs::new(v.contents).next()? {
+                t if t.identifier.is_universal(asn1::TAG_INTEGER) => match t.contents {
+                    [v] => *v,
+                    _ => return None,
+                },
+                _ => return None,
+            },
+            None => VERSION_1,
+        };
+
+        Some(Self {
+            version,
+            serial_number: universal(&mut c, asn1::TAG_INTEGER)?.contents,
+            signature: universal(&mut c, asn1::TAG_SEQUENCE)?,
+            issuer: universal(&mut c, asn1::TAG_SEQUENCE)?,
+            validity: universal(&mut c, asn1::TAG_SEQUENCE)?,
+            subject: universal(&mut c, asn1::TAG_SEQUENCE)?,
+            subject_public_key_info: universal(&mut c, asn1::TAG_SEQUENCE)?,
+            issuer_unique_id: context(&mut c, TAG_ISSUER_UNIQUE_ID).map(|v| v.contents),
+            subject_unique_id: context(&mut c, TAG_SUBJECT_UNIQUE_ID).map(|v| v.contents),
+            extensions: match context(&mut c, TAG_EXTENSIONS) {
+                Some(v) => {
+                    let mut e = asn1::Tlvs::new(v.contents);
+                    Some(universal(&mut e, asn1::TAG_SEQUENCE)?.contents)
+                },
+                None => None,
+            },
+        })
+    }
+}
+
+impl<'data> Extensions<'data> {
+    /// Create extension iterator
+    ///
+    /// Create a new iterator over the contents of the extensions SEQUENCE
+    /// `data`.
+    pub fn new(data: &'data [u8]) -> Self {
+        Self { data: asn1::Tlvs::new(data) }
+    }
+
+    /// Return remaining data
+    ///
+    /// Return the data that has not been consumed by the iterator.
+    pub fn remainder(&self) -> &'data [u8] {
+        self.data.remainder()
+    }
+}
+
+impl<'data> Iterator for Extensions<'data> {
+    type Item = Extension<'data>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut peek = self.data.clone();
+        let v = universal(&mut peek, asn1::TAG_SEQUENCE)?;
+
+        let mut c = asn1::Tlvs::new(v.contents);
+        let id = universal(&mut c, asn1::TAG_OBJECT_IDENTIFIER)?.contents;
+        let critical = match universal(&mut c, asn1::TAG_BOOLEAN) {
+            Some(v) => v.contents != [asn1::BOOLEAN_FALSE],
+            None => false,
+        };
+        let value = universal(&mut c, asn1::TAG_OCTET_STRING)?.contents;
+
+        self.data = peek;
+        Some(Extension { id, critical, value })
+    }
+}
+
+/// Decode algorithm identifier
+///
+/// Return the contents of the algorithm identifier (`OID_*`) and the
+/// parameters, if any, of an AlgorithmIdentifier SEQUENCE.
+pub fn algorithm<'data>(v: &asn1::Tlv<'data>) -> Option<(&'data [u8], Option<asn1::Tlv<'data>>)> {
+    let mut c = v.children()?;
+    let id = universal(&mut c, asn1::TAG_OBJECT_IDENTIFIER)?.contents;
+    Some((id, c.next()))
+}
+
+/// Decode key usage
+///
+/// Decode the value of a key usage extension (`OID_KEY_USAGE`) into a mask
+/// of `KEY_USAGE_*` flags.
+pub fn key_usage(value: &[u8]) -> Option<u16> {
+    let v = asn1::Tlv::parse_der(value)?;
+    if !v.identifier.is_universal(asn1::TAG_BIT_STRING) {
+        return None;
+    }
+
+    let (unused, bits) = v.contents.split_first()?;
+    if *unused > 7 || (bits.is_empty() && *unused != 0) {
+        return None;
+    }
+
+    let n = (bits.len() * 8 - *unused as usize).min(16);
+    Some((0..n).filter(|i| bits[i / 8] & (0x80 >> (i % 8)) != 0).fold(0, |acc, i| acc | 1 << i))
+}
+
+/// Find extension
+///
+/// Return the extension with the given identifier in the contents of the
+/// extensions SEQUENCE `data`.
+pub fn find_extension<'data>(data: &'data [u8], id: &[u8]) -> Option<Extension<'data>> {
+    Extensions::new(data).find(|v| v.id == id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Self-signed Ed25519 certificate for `CN=a` with critical key usage.
+    const CERTIFICATE: [u8; 320] = [
+        0x30, 0x82, 0x01, 0x3c, 0x30, 0x81, 0xef, 0xa0,
+        0x03, 0x02, 0x01, 0x02, 0x02, 0x14, 0x25, 0x80,
+        0xbe, 0x40, 0xd1, 0x2a, 0x87, 0x21, 0x3b, 0x3f,
+        0x21, 0x77, 0xd1, 0xe9, 0x94, 0x1b, 0xc0, 0xd2,
+        0xfc, 0x1e, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65,
+        0x70, 0x30, 0x0c, 0x31, 0x0a, 0x30, 0x08, 0x06,
+        0x03, 0x55, 0x04, 0x03, 0x0c, 0x01, 0x61, 0x30,
+        0x1e, 0x17, 0x0d, 0x32, 0x36, 0x31, 0x30, 0x31,
+        0x34, 0x30, 0x38, 0x33, 0x35, 0x32, 0x39, 0x5a,
+        0x17, 0x0d, 0x32, 0x36, 0x31, 0x30, 0x31, 0x35,
+        0x30, 0x38, 0x33, 0x35, 0x32, 0x39, 0x5a, 0x30,
+        0x0c, 0x31, 0x0a, 0x30, 0x08, 0x06, 0x03, 0x55,
+        0x04, 0x03, 0x0c, 0x01, 0x61, 0x30, 0x2a, 0x30,
+        0x05, 0x06, 0x03, 0x2b, 0x65, 0x70, 0x03, 0x21,
+        0x00, 0x1f, 0xa4, 0x64, 0xf2, 0xea, 0xc3, 0x6b,
+        0x48, 0xfa, 0x0b, 0xe2, 0xbe, 0x28, 0x5c, 0xe0,
+        0x93, 0xa1, 0x74, 0xbd, 0x9f, 0x81, 0xe6, 0x92,
+        0xf3, 0x29, 0xf1, 0x88, 0x7e, 0xe9, 0xac, 0x3a,
+        0x67, 0xa3, 0x63, 0x30, 0x61, 0x30, 0x1d, 0x06,
+        0x03, 0x55, 0x1d, 0x0e, 0x04, 0x16, 0x04, 0x14,
+        0x20, 0xd8, 0x77, 0xd5, 0x5e, 0xaf, 0x69, 0xf2,
+        0x3d, 0xdb, 0x40, 0xa9, 0xa9, 0x8c, 0x0a, 0x4a,
+        0xe2, 0x1f, 0xe0, 0x33, 0x30, 0x1f, 0x06, 0x03,
+        0x55, 0x1d, 0x23, 0x04, 0x18, 0x30, 0x16, 0x80,
+        0x14, 0x20, 0xd8, 0x77, 0xd5, 0x5e, 0xaf, 0x69,
+        0xf2, 0x3d, 0xdb, 0x40, 0xa9, 0xa9, 0x8c, 0x0a,
+        0x4a, 0xe2, 0x1f, 0xe0, 0x33, 0x30, 0x0f, 0x06,
+        0x03, 0x55, 0x1d, 0x13, 0x01, 0x01, 0xff, 0x04,
+        0x05, 0x30, 0x03, 0x01, 0x01, 0xff, 0x30, 0x0e,
+        0x06, 0x03, 0x55, 0x1d, 0x0f, 0x01, 0x01, 0xff,
+        0x04, 0x04, 0x03, 0x02, 0x07, 0x80, 0x30, 0x05,
+        0x06, 0x03, 0x2b, 0x65, 0x70, 0x03, 0x41, 0x00,
+        0xd7, 0xa6, 0x48, 0xc3, 0x87, 0xf1, 0x60, 0xc1,
+        0x1c, 0x42, 0x4c, 0x87, 0xb2, 0xa4, 0xd2, 0xd9,
+        0x37, 0x5b, 0x7e, 0x20, 0xec, 0xd5, 0x50, 0x1a,
+        0x32, 0xa2, 0x00, 0x89, 0x5c, 0xdb, 0xe0, 0xfe,
+        0xa9, 0x81, 0xd7, 0xad, 0x48, 0x7d, 0x36, 0xf7,
+        0x4d, 0x27, 0x9f, 0x1c, 0x72, 0x03, 0xa5, 0x7f,
+        0x9e, 0x7f, 0x8b, 0xe7, 0x38, 0x9f, 0x9e, 0xd0,
+        0x7f, 0x25, 0x5e, 0x8c, 0x5c, 0x22, 0x65, 0x0c,
+    ];
+
+    // Verify encoded object identifiers against their dotted notation.
+    #[test]
+    fn verify_oids() {
+        assert_eq!(OID_COMMON_NAME, [0x55, 0x04, 0x03]);
+        assert_eq!(OID_ED25519, [0x2b, 0x65, 0x70]);
+        assert_eq!(OID_RSA_ENCRYPTION[..3], [0x2a, 0x86, 0x48]);
+        assert_eq!(OID_SERVER_AUTH[..6], OID_AUTHORITY_INFO_ACCESS[..6]);
+        assert_eq!(OID_EXT_KEY_USAGE, &OID_ANY_EXTENDED_KEY_USAGE[..3]);
+    }
+
+    // Parse a certificate and its extensions.
+    #[test]
+    fn verify_certificate() {
+        let c = Certificate::parse(&CERTIFICATE).unwrap();
+        assert_eq!(algorithm(&c.signature_algorithm).unwrap().0, OID_ED25519);
+        assert_eq!((c.signature_value.len(), c.signature_value[0]), (65, 0));
+
+        let t = c.tbs().unwrap();
+        assert_eq!(t.version, VERSION_3);
+        assert_eq!(t.serial_number, &CERTIFICATE[14..34]);
+        let (id, parameters) = algorithm(&t.signature).unwrap();
+        assert!(id == OID_ED25519 && parameters.is_none());
+        assert_eq!(t.issuer.size, 14);
+        assert_eq!(t.subject.contents, &CERTIFICATE[89..101]);
+        assert_eq!(t.validity.children().unwrap().count(), 2);
+        assert!(t.issuer_unique_id.is_none() && t.subject_unique_id.is_none());
+
+        let extensions = t.extensions.unwrap();
+        let mut e = Extensions::new(extensions);
+        let v = e.next().unwrap();
+        assert_eq!((v.id, v.critical), (OID_SUBJECT_KEY_IDENTIFIER, false));
+        assert_eq!(e.count(), 3);
+
+        let v = find_extension(extensions, OID_BASIC_CONSTRAINTS).unwrap();
+        assert_eq!((v.critical, v.value), (true, &[0x30, 0x03, 0x01, 0x01, 0xff][..]));
+        let v = find_extension(extensions, OID_KEY_USAGE).unwrap();
+        assert_eq!(key_usage(v.value), Some(KEY_USAGE_DIGITAL_SIGNATURE));
+        assert!(find_extension(extensions, OID_SUBJECT_ALT_NAME).is_none());
+
+        let v = [0x03, 0x03, 0x07, 0x86, 0x80];
+        let mask = KEY_USAGE_DIGITAL_SIGNATURE
+            | KEY_USAGE_KEY_CERT_SIGN
+            | KEY_USAGE_CRL_SIGN
+            | KEY_USAGE_DECIPHER_ONLY;
+        assert_eq!(key_usage(&v), Some(mask));
+        assert_eq!(key_usage(&[0x03, 0x01, 0x01]), None);
+        assert!(Certificate::parse(&CERTIFICATE[..319]).is_none());
+    }
+}